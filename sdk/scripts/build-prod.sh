@@ -65,4 +65,7 @@ mkdir -p "$BUILD_WORKERS"
 if cp "$SOURCE_WASM_VRF/pkg/wasm_vrf_worker_bg.wasm" "$BUILD_WORKERS/" 2>/dev/null; then print_success "VRF WASM copied"; else print_warning "VRF WASM not found"; fi
 if cp "$SOURCE_WASM_SIGNER/pkg/wasm_signer_worker_bg.wasm" "$BUILD_WORKERS/" 2>/dev/null; then print_success "Signer WASM copied"; else print_warning "Signer WASM not found"; fi
 
+print_step "Building slim WASM artifacts (local-signer only)..."
+if ./scripts/build-wasm-slim.sh --release; then print_success "Slim WASM artifacts built"; else print_error "Slim WASM build failed"; exit 1; fi
+
 print_success "Production build completed successfully!"
\ No newline at end of file
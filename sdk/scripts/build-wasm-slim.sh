@@ -0,0 +1,44 @@
+#!/bin/bash
+
+# Slim WASM build for @tatchi-xyz/sdk
+# - Builds both workers with `--no-default-features` into `pkg-slim/`
+#   (signer: no `threshold`/FROST stack; VRF: no `shamir-server` lock helpers)
+# - Local-signer-only embedders bundle the workers against `pkg-slim/` instead of `pkg/`
+# - Pass `--release` for release-mode wasm-pack builds
+
+set -e
+
+source ./build-paths.sh
+
+RED='\033[0;31m'
+GREEN='\033[0;32m'
+BLUE='\033[0;34m'
+NC='\033[0m'
+
+print_step() { echo -e "${BLUE}📦 $1${NC}"; }
+print_success() { echo -e "${GREEN}✅ $1${NC}"; }
+print_error() { echo -e "${RED}❌ $1${NC}"; }
+
+PROFILE_FLAG=""
+if [ "$1" = "--release" ]; then PROFILE_FLAG="--release"; fi
+
+print_step "Building slim WASM signer worker (no threshold)..."
+cd "$SOURCE_WASM_SIGNER"
+if wasm-pack build --target web --out-dir pkg-slim $PROFILE_FLAG -- --no-default-features; then print_success "Slim WASM signer worker built"; else print_error "Slim WASM signer build failed"; exit 1; fi
+cd ../..
+
+print_step "Building slim WASM VRF worker (no shamir-server)..."
+cd "$SOURCE_WASM_VRF"
+if wasm-pack build --target web --out-dir pkg-slim $PROFILE_FLAG -- --no-default-features; then print_success "Slim WASM VRF worker built"; else print_error "Slim WASM VRF build failed"; exit 1; fi
+cd ../..
+
+if node ./scripts/fix-wasm-pack-sideeffects.mjs "$SOURCE_WASM_SIGNER/pkg-slim" "$SOURCE_WASM_VRF/pkg-slim"; then
+  print_success "Slim WASM package metadata optimized"
+fi
+
+if [ -d "$BUILD_WORKERS" ]; then
+  mkdir -p "$BUILD_WORKERS/slim"
+  cp "$SOURCE_WASM_SIGNER/pkg-slim/wasm_signer_worker_bg.wasm" "$BUILD_WORKERS/slim/"
+  cp "$SOURCE_WASM_VRF/pkg-slim/wasm_vrf_worker_bg.wasm" "$BUILD_WORKERS/slim/"
+  print_success "Slim WASM binaries copied to $BUILD_WORKERS/slim"
+fi
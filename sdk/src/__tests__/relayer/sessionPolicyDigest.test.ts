@@ -0,0 +1,46 @@
+import { test, expect } from '@playwright/test';
+import { base64UrlEncode } from '../../utils/encoders';
+import { computeSessionPolicyDigest32 } from '../../server/core/shamirWorker';
+
+// Pinned vector shared with the VRF worker Rust tests (`session_policy_digest_matches_pinned_vector`).
+const PINNED_POLICY = {
+  version: 'threshold_session_v1',
+  nearAccountId: 'alice.testnet',
+  rpId: 'example.localhost',
+  relayerKeyId: 'relayer-key-1',
+  sessionId: 'tsess-fixed',
+  participantIds: [1, 2],
+  ttlMs: 300000,
+  remainingUses: 5,
+};
+const PINNED_POLICY_DIGEST_B64U = 'pzR1TlQUUoWeSUM38w1ulPPHi_neDTP0C4U2n7G4I_c';
+
+test.describe('session policy digest (relayer)', () => {
+  test('matches the pinned vector', async () => {
+    const digest = await computeSessionPolicyDigest32(PINNED_POLICY);
+    expect(digest.length).toBe(32);
+    expect(base64UrlEncode(digest)).toBe(PINNED_POLICY_DIGEST_B64U);
+  });
+
+  test('is independent of key insertion order', async () => {
+    const reordered = {
+      remainingUses: 5,
+      ttlMs: 300000,
+      participantIds: [1, 2],
+      sessionId: 'tsess-fixed',
+      relayerKeyId: 'relayer-key-1',
+      rpId: 'example.localhost',
+      nearAccountId: 'alice.testnet',
+      version: 'threshold_session_v1',
+    };
+    expect(base64UrlEncode(await computeSessionPolicyDigest32(reordered))).toBe(PINNED_POLICY_DIGEST_B64U);
+  });
+
+  test('rejects non-integer numbers', async () => {
+    const err = await computeSessionPolicyDigest32({ ...PINNED_POLICY, ttlMs: 1.5 }).then(
+      () => null,
+      (e: unknown) => e,
+    );
+    expect(String(err)).toContain('INVALID_SESSION_POLICY: $.ttlMs must be an integer');
+  });
+});
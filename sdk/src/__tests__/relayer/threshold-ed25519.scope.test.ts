@@ -7,6 +7,7 @@ import { ActionType, type ActionArgsWasm } from '../../core/types/actions';
 import { AuthService } from '../../server/core/AuthService';
 import { createThresholdSigningService } from '../../server/core/ThresholdService';
 import type { VerifyAuthenticationRequest, VerifyAuthenticationResponse } from '../../server/core/types';
+import { computeSessionPolicyDigest32 } from '../../server/core/shamirWorker';
 import { createRelayRouter } from '../../server/router/express-adaptor';
 import { createCloudflareRouter } from '../../server/router/cloudflare-adaptor';
 import { threshold_ed25519_compute_near_tx_signing_digests } from '../../wasm_signer_worker/pkg/wasm_signer_worker.js';
@@ -116,7 +117,7 @@ async function buildThresholdSessionBody(input: {
     ttlMs: input.ttlMs,
     remainingUses: input.remainingUses,
   };
-  const sessionPolicyDigest32 = Array.from(await sha256BytesUtf8(alphabetizeStringify(policy)));
+  const sessionPolicyDigest32 = Array.from(await computeSessionPolicyDigest32(policy));
   const intentDigest32 = Array.from(await sha256BytesUtf8('threshold_session_mint_v1'));
 
   return {
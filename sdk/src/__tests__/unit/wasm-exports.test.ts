@@ -22,6 +22,23 @@ test.describe('WASM Module Exports', () => {
 
     // Other critical exports
     expect(typeof wasmModule.handle_signer_message).toBe('function');
+    expect(typeof wasmModule.cancel_operation).toBe('function');
+    expect(typeof wasmModule.active_signing_sessions).toBe('function');
+    expect(typeof wasmModule.clear_signing_session).toBe('function');
+    expect(typeof wasmModule.validate_attestation_object).toBe('function');
+    expect(typeof wasmModule.extract_cose_public_key_sync).toBe('function');
+    expect(typeof wasmModule.extract_ec2_coordinates_sync).toBe('function');
+    expect(typeof wasmModule.get_threshold_session_metrics).toBe('function');
+    expect(typeof wasmModule.clear_threshold_client_share_cache).toBe('function');
+    expect(typeof wasmModule.verify_verification_attestation).toBe('function');
+    expect(typeof wasmModule.export_sign_counter_snapshot).toBe('function');
+    expect(typeof wasmModule.import_sign_counter_snapshot).toBe('function');
+    expect(typeof wasmModule.acknowledge_credential_restore).toBe('function');
+    expect(typeof wasmModule.get_session_binding_nonce).toBe('function');
+    expect(typeof wasmModule.validate_transaction_payload).toBe('function');
+    expect(typeof wasmModule.summarize_transactions).toBe('function');
+    expect(typeof wasmModule.check_encrypted_key_envelope_structure).toBe('function');
+    expect(typeof wasmModule.verify_threshold_relayer_request_signature).toBe('function');
     expect(typeof wasmModule.WorkerRequestType).toBe('object');
     expect(typeof wasmModule.WorkerResponseType).toBe('object');
   });
@@ -45,6 +62,7 @@ test.describe('WASM Module Exports', () => {
     // Core entrypoints used by the VRF web worker bridge
     expect(typeof vrfModule.handle_message).toBe('function');
     expect(typeof vrfModule.attach_wrap_key_seed_port).toBe('function');
+    expect(typeof vrfModule.compute_session_policy_digest_32).toBe('function');
 
     // Confirm enums are present for VRF worker messaging
     expect(typeof vrfModule.WorkerRequestType).toBe('object');
@@ -53,5 +71,6 @@ test.describe('WASM Module Exports', () => {
     // Shamir helpers should also be exported for configuration
     expect(typeof vrfModule.configure_shamir_p).toBe('function');
     expect(typeof vrfModule.configure_shamir_server_urls).toBe('function');
+    expect(typeof vrfModule.set_shamir_primality_rounds).toBe('function');
   });
 });
@@ -0,0 +1,77 @@
+/**
+ * Size regression tests for the slim WASM builds (`--no-default-features`).
+ *
+ * The slim signer drops the `threshold` feature (FROST coordinator, relayer transport,
+ * threshold signer backend) and the slim VRF worker drops `shamir-server`. If a threshold
+ * or server-lock symbol shows up in a slim build, the feature gating has leaked and
+ * frost-ed25519 / curve25519-dalek are being linked in again.
+ *
+ * Build the artifacts with `npm run build:wasm:slim` (or run `npm run test:wasm:slim`).
+ */
+
+import { test, expect } from '@playwright/test';
+import fs from 'node:fs';
+import path from 'node:path';
+
+const SIGNER_DIR = path.join(process.cwd(), 'src/wasm_signer_worker');
+const VRF_DIR = path.join(process.cwd(), 'src/wasm_vrf_worker');
+
+const slimBuilt =
+  fs.existsSync(path.join(SIGNER_DIR, 'pkg-slim/wasm_signer_worker.js')) &&
+  fs.existsSync(path.join(VRF_DIR, 'pkg-slim/wasm_vrf_worker.js'));
+
+test.describe('Slim WASM builds', () => {
+  test.skip(!slimBuilt, 'slim WASM not built; run `npm run build:wasm:slim`');
+
+  test('slim wasm_signer_worker does not export threshold symbols', async () => {
+    const wasmModule: Record<string, unknown> = await import(
+      '../../wasm_signer_worker/pkg-slim/wasm_signer_worker.js'
+    );
+
+    // Local signing surface is unchanged
+    expect(typeof wasmModule.init_worker).toBe('function');
+    expect(typeof wasmModule.init_wasm_signer_worker).toBe('function');
+    expect(typeof wasmModule.handle_signer_message).toBe('function');
+    expect(typeof wasmModule.verify_verification_attestation).toBe('function');
+    expect(typeof wasmModule.clear_signing_session).toBe('function');
+    expect(typeof wasmModule.WorkerRequestType).toBe('object');
+
+    for (const name of [
+      'get_threshold_session_metrics',
+      'clear_threshold_client_share_cache',
+      'verify_threshold_signature_proof',
+      'DeriveThresholdEd25519ClientVerifyingShareRequest',
+      'SignAddKeyThresholdPublicKeyNoPromptRequest',
+      'WarmThresholdEd25519ClientShareRequest',
+      'WarmThresholdEd25519ClientShareResult',
+    ]) {
+      expect(wasmModule, `slim signer exports ${name}`).not.toHaveProperty(name);
+    }
+  });
+
+  test('slim wasm_vrf_worker does not export Shamir server-lock symbols', async () => {
+    const vrfModule: Record<string, unknown> = await import(
+      '../../wasm_vrf_worker/pkg-slim/wasm_vrf_worker.js'
+    );
+
+    expect(typeof vrfModule.handle_message).toBe('function');
+    expect(typeof vrfModule.configure_shamir_p).toBe('function');
+    expect(typeof vrfModule.compute_session_policy_digest_32).toBe('function');
+
+    for (const name of [
+      'Shamir3PassGenerateServerKeypairRequest',
+      'Shamir3PassApplyServerLockRequest',
+      'Shamir3PassRemoveServerLockRequest',
+    ]) {
+      expect(vrfModule, `slim VRF worker exports ${name}`).not.toHaveProperty(name);
+    }
+  });
+
+  test('slim signer wasm is smaller than the default build', async () => {
+    const full = path.join(SIGNER_DIR, 'pkg/wasm_signer_worker_bg.wasm');
+    const slim = path.join(SIGNER_DIR, 'pkg-slim/wasm_signer_worker_bg.wasm');
+    test.skip(!fs.existsSync(full), 'default signer WASM not built');
+
+    expect(fs.statSync(slim).size).toBeLessThan(fs.statSync(full).size);
+  });
+});
@@ -37,6 +37,32 @@ export interface LocalNearSkV3Material extends BasePasskeyNearKeyMaterial {
    * Base64url-encoded AEAD nonce (ChaCha20-Poly1305) for `encryptedSk`.
    */
   chacha20NonceB64u: string;
+  /**
+   * WebAuthn credential the envelope is bound to (derivation version 3).
+   * Unset for version 2 envelopes, which use the account-wide KEK.
+   */
+  credentialId?: string;
+  /**
+   * Envelope format from `deriveNearKeypairAndEncrypt` (4 binds the account id as AEAD AAD).
+   * Unset means 3 with a `credentialId`, 2 without.
+   */
+  envelopeVersion?: number;
+  /** App context bound into the AAD of a version 4 envelope. */
+  aadContext?: string;
+  /** Envelopes of other passkeys registered for this account on this device. */
+  credentialEnvelopes?: LocalCredentialKeyEnvelope[];
+}
+
+/** A version 3 envelope for one additional passkey on the same device. */
+export interface LocalCredentialKeyEnvelope {
+  credentialId: string;
+  /** May differ from the record's key when the passkeys derive different NEAR keys. */
+  publicKey: string;
+  encryptedSk: string;
+  chacha20NonceB64u: string;
+  wrapKeySalt: string;
+  envelopeVersion?: number;
+  aadContext?: string;
 }
 
 export interface ThresholdEd25519_2p_V1Material extends BasePasskeyNearKeyMaterial {
@@ -54,6 +80,66 @@ export type PasskeyNearKeyMaterial =
   | LocalNearSkV3Material
   | ThresholdEd25519_2p_V1Material;
 
+function sanitizeCredentialScope(
+  rec: any,
+): Pick<LocalNearSkV3Material, 'credentialId' | 'envelopeVersion' | 'aadContext' | 'credentialEnvelopes'> {
+  const out: Pick<LocalNearSkV3Material, 'credentialId' | 'envelopeVersion' | 'aadContext' | 'credentialEnvelopes'> = {};
+  if (typeof rec?.credentialId === 'string' && rec.credentialId) {
+    out.credentialId = rec.credentialId;
+  }
+  if (typeof rec?.envelopeVersion === 'number') {
+    out.envelopeVersion = rec.envelopeVersion;
+  }
+  if (typeof rec?.aadContext === 'string') {
+    out.aadContext = rec.aadContext;
+  }
+  if (Array.isArray(rec?.credentialEnvelopes)) {
+    const envelopes = (rec.credentialEnvelopes as any[]).filter((env): env is LocalCredentialKeyEnvelope =>
+      !!env?.credentialId && !!env?.publicKey && !!env?.encryptedSk
+      && !!env?.chacha20NonceB64u && !!env?.wrapKeySalt
+    );
+    if (envelopes.length) out.credentialEnvelopes = envelopes;
+  }
+  return out;
+}
+
+function toCredentialEnvelope(material: LocalNearSkV3Material): LocalCredentialKeyEnvelope | null {
+  if (!material.credentialId) return null;
+  return {
+    credentialId: material.credentialId,
+    publicKey: material.publicKey,
+    encryptedSk: material.encryptedSk,
+    chacha20NonceB64u: material.chacha20NonceB64u,
+    wrapKeySalt: material.wrapKeySalt,
+    ...(typeof material.envelopeVersion === 'number' ? { envelopeVersion: material.envelopeVersion } : {}),
+    ...(typeof material.aadContext === 'string' ? { aadContext: material.aadContext } : {}),
+  };
+}
+
+/**
+ * Fold a newly derived local key record into the existing one for the same device so that
+ * registering another passkey keeps the earlier passkeys' envelopes instead of overwriting them.
+ * A version 2 record (no `credentialId`) stays the top-level envelope and the new passkey is
+ * added alongside it; the signer falls back to it for credentials without their own envelope.
+ */
+export function mergeLocalCredentialEnvelopes(
+  existing: LocalNearSkV3Material | null,
+  next: LocalNearSkV3Material,
+): LocalNearSkV3Material {
+  const nextEnvelope = toCredentialEnvelope(next);
+  if (!existing || !nextEnvelope) return next;
+
+  if (!existing.credentialId) {
+    const others = (existing.credentialEnvelopes || [])
+      .filter((env) => env.credentialId !== nextEnvelope.credentialId);
+    return { ...existing, credentialEnvelopes: [...others, nextEnvelope], timestamp: next.timestamp };
+  }
+
+  const previous = [toCredentialEnvelope(existing)!, ...(existing.credentialEnvelopes || [])];
+  const credentialEnvelopes = previous.filter((env) => env.credentialId !== nextEnvelope.credentialId);
+  return credentialEnvelopes.length ? { ...next, credentialEnvelopes } : next;
+}
+
 interface PasskeyNearKeysDBConfig {
   dbName: string;
   dbVersion: number;
@@ -196,6 +282,7 @@ export class PasskeyNearKeysDBManager {
           wrapKeySalt: rec.wrapKeySalt,
           encryptedSk: rec.encryptedSk,
           chacha20NonceB64u: rec.chacha20NonceB64u,
+          ...sanitizeCredentialScope(rec),
           timestamp: rec.timestamp,
         };
       }
@@ -311,6 +398,7 @@ export class PasskeyNearKeysDBManager {
             wrapKeySalt: rec.wrapKeySalt,
             encryptedSk: rec.encryptedSk,
             chacha20NonceB64u: rec.chacha20NonceB64u,
+            ...sanitizeCredentialScope(rec),
             timestamp: rec.timestamp,
           } as LocalNearSkV3Material;
         }
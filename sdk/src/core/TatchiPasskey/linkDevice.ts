@@ -440,6 +440,7 @@ export class LinkDeviceFlow {
     }
 
     const realAccountId = this.session.accountId;
+    const requestsBefore = this.context.webAuthnManager.getWorkerRequestCounts();
 
     this.safeOnEvent({
       step: 6,
@@ -499,11 +500,16 @@ export class LinkDeviceFlow {
 
     this.session.phase = DeviceLinkingPhase.STEP_7_LINKING_COMPLETE;
     this.registrationRetryCount = 0; // Reset retry counter on success
+    const requestsAfter = this.context.webAuthnManager.getWorkerRequestCounts();
     this.safeOnEvent({
       step: 7,
       phase: DeviceLinkingPhase.STEP_7_LINKING_COMPLETE,
       status: DeviceLinkingStatus.SUCCESS,
-      message: 'Device linking completed successfully'
+      message: 'Device linking completed successfully',
+      workerRequests: {
+        vrf: requestsAfter.vrf - requestsBefore.vrf,
+        signer: requestsAfter.signer - requestsBefore.signer,
+      },
     });
 
     // Auto-login for Device2 after successful device linking
@@ -903,34 +909,17 @@ export class LinkDeviceFlow {
     this.session.credential = confirm.credential;
     this.session.vrfChallenge = confirm.vrfChallenge || null;
 
-    // Derive deterministic VRF keypair from PRF output embedded in the credential.
-    // This also loads the VRF keypair into the worker's memory (saveInMemory=true by default)
-    // and automatically tracks the account ID at the TypeScript level.
-    const vrfDerivationResult = await this.context.webAuthnManager.deriveVrfKeypair({
-      credential: confirm.credential,
+    // === STEP 1: Derive deterministic VRF + NEAR keys (no transaction signing) ===
+    // One VRF request derives, stores and encrypts the VRF keypair (also loading it into worker
+    // memory) and hands WrapKeySeed to the signer, which derives and stores the NEAR keypair.
+    // We need the deterministic NEAR public key to get the nonce for the key replacement
+    // transaction first; the registration transaction is signed with it after the swap.
+    const derived = await this.context.webAuthnManager.completeDevice2Registration({
       nearAccountId: realAccountId,
-    });
-
-    if (!vrfDerivationResult.success || !vrfDerivationResult.encryptedVrfKeypair) {
-      throw new Error('Failed to derive VRF keypair from PRF for real account');
-    }
-
-    // === STEP 1: Generate NEAR keypair (deterministic, no transaction signing) ===
-    // Use base account ID for consistent keypair derivation across devices
-    const nearKeyResultStep1 = await this.context.webAuthnManager.deriveNearKeypairAndEncryptFromSerialized({
-      nearAccountId: realAccountId, // Use base account ID for consistency
       credential: confirm.credential,
-      options: { deviceNumber: this.session.deviceNumber },
-      // No options - just generate the keypair, don't sign registration tx yet.
-      // We need the deterministic NEAR public key to get the nonce for the key replacement transaction first
-      // Then once the key replacement transaction is executed, we use the deterministic key
-      // to sign the registration transaction
+      deviceNumber: this.session.deviceNumber!,
     });
 
-    if (!nearKeyResultStep1.success || !nearKeyResultStep1.publicKey) {
-      throw new Error('Failed to derive NEAR keypair in step 1');
-    }
-
     // === STEP 2: Execute Key Replacement Transaction ===
     this.context.webAuthnManager.getNonceManager().initializeUser(realAccountId, this.session!.nearPublicKey);
     // Initialize NonceManager with current (temporary) on-chain key
@@ -941,7 +930,7 @@ export class LinkDeviceFlow {
       .getNonceBlockHashAndHeight(this.context.nearClient);
 
     await this.executeKeySwapTransaction(
-      nearKeyResultStep1.publicKey,
+      derived.nearPublicKey,
       nextNonce,
       txBlockHash
     );
@@ -950,7 +939,7 @@ export class LinkDeviceFlow {
     // so future transactions and VRF flows use the correct on-chain key.
     this.context.webAuthnManager.getNonceManager().initializeUser(
       realAccountId,
-      nearKeyResultStep1.publicKey
+      derived.nearPublicKey
     );
 
     // Clean up any temp account VRF data.
@@ -968,10 +957,10 @@ export class LinkDeviceFlow {
 
     // Return all derived values.
     const result = {
-      encryptedVrfKeypair: vrfDerivationResult.encryptedVrfKeypair,
-      serverEncryptedVrfKeypair: vrfDerivationResult.serverEncryptedVrfKeypair,
-      vrfPublicKey: vrfDerivationResult.vrfPublicKey,
-      nearPublicKey: nearKeyResultStep1.publicKey,
+      encryptedVrfKeypair: derived.encryptedVrfKeypair,
+      serverEncryptedVrfKeypair: derived.serverEncryptedVrfKeypair,
+      vrfPublicKey: derived.vrfPublicKey,
+      nearPublicKey: derived.nearPublicKey,
       credential: this.session.credential,
       vrfChallenge: this.session.vrfChallenge!
     };
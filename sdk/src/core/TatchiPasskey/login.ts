@@ -39,7 +39,7 @@ type ThresholdSessionPlan = {
   relayerUrl: string;
   relayerKeyId: string;
   cacheKey: string;
-  policy: Awaited<ReturnType<typeof buildThresholdSessionPolicy>>;
+  policy: ReturnType<typeof buildThresholdSessionPolicy>;
   deviceNumber: number;
 };
 
@@ -342,7 +342,7 @@ async function prepareThresholdSessionPlan(args: {
       return null;
     }
 
-    const policy = await buildThresholdSessionPolicy({
+    const policy = buildThresholdSessionPolicy({
       nearAccountId,
       rpId,
       relayerKeyId,
@@ -433,7 +433,7 @@ async function mintThresholdSessionBestEffort(args: {
       sessionKind: plan.sessionKind,
       policy: plan.policy.policy,
       policyJson: plan.policy.policyJson,
-      sessionPolicyDigest32: plan.policy.sessionPolicyDigest32,
+      sessionPolicyDigest32: vrfChallenge.sessionPolicyDigest32 || '',
       jwt: minted.jwt,
       ...(minted.expiresAtMs ? { expiresAtMs: minted.expiresAtMs } : {}),
     });
@@ -499,7 +499,7 @@ async function runRelayerSessionFlow(args: {
       blockHash: txBlockHash,
       blockHeight: txBlockHeight,
       intentDigest,
-      ...(thresholdPlan ? { sessionPolicyDigest32: thresholdPlan.policy.sessionPolicyDigest32 } : {}),
+      ...(thresholdPlan ? { sessionPolicyJson: thresholdPlan.policy.policyJson } : {}),
     });
 
     const authenticators = await webAuthnManager.getAuthenticatorsByUser(nearAccountId);
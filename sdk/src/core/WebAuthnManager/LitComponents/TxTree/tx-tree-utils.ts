@@ -1,5 +1,5 @@
 import { TxTreeStyles } from './tx-tree-themes';
-import type { ActionArgs, TransactionInput } from '../../../types/actions';
+import { getActionRisk, type ActionArgs, type ActionRisk, type ActionType, type TransactionInput } from '../../../types/actions';
 import { formatArgs, formatDeposit, shortenPubkey, formatCodeSize } from '../common/formatters';
 import { isString } from '@/utils/validation';
 
@@ -61,6 +61,8 @@ export interface TreeNode {
    * The index of this action within its transaction, for display purposes.
    */
   actionIndex?: number;
+  /** Risk annotation for action folder nodes (`getActionRisk`) */
+  risk?: ActionRisk;
   /** Transaction data for transaction-level folder nodes */
   transaction?: TransactionInput;
   /** Index of this transaction in the list */
@@ -242,6 +244,16 @@ function buildActionNode(action: ActionArgs, idx: number): TreeNode {
       break;
   }
 
+  const risk = getActionRisk(action.type as ActionType);
+  if (risk === 'High') {
+    actionNodes.unshift({
+      id: `a${idx}-risk`,
+      label: 'risk: High',
+      type: 'file',
+      open: false,
+    });
+  }
+
   return {
     id: `action-${idx}`,
     // Label is now computed at render time from action data
@@ -252,6 +264,7 @@ function buildActionNode(action: ActionArgs, idx: number): TreeNode {
     // Attach action data for the renderer
     action,
     actionIndex: idx,
+    risk,
     children: actionNodes
   } as TreeNode;
 }
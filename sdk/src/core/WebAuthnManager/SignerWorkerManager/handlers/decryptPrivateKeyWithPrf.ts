@@ -39,6 +39,9 @@ export async function decryptPrivateKeyWithPrf({
           nearAccountId: nearAccountId,
           encryptedPrivateKeyData: keyMaterial.encryptedSk,
           encryptedPrivateKeyChacha20NonceB64u: keyMaterial.chacha20NonceB64u,
+          credentialId: keyMaterial.credentialId,
+          envelopeVersion: keyMaterial.envelopeVersion,
+          aadContext: keyMaterial.aadContext,
         })
       },
     });
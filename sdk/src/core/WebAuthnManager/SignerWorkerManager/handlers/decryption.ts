@@ -0,0 +1,50 @@
+import type { LocalNearSkV3Material } from '../../../IndexedDBManager/passkeyNearKeysDB';
+import type { SignerDecryptionPayload } from '../../../types/signer-worker';
+
+function envelopeFormat(env: { envelopeVersion?: number; aadContext?: string }): {
+  version?: number;
+  aadContext?: string;
+} {
+  return {
+    ...(typeof env.envelopeVersion === 'number' ? { version: env.envelopeVersion } : {}),
+    ...(typeof env.aadContext === 'string' ? { aadContext: env.aadContext } : {}),
+  };
+}
+
+/**
+ * Build the signer worker `decryption` payload for a local key record.
+ *
+ * Version 2 envelopes travel in the top-level fields; every credential-scoped (version 3)
+ * envelope goes into `credentialEnvelopes` so the worker can pick the one matching
+ * `credentialId` or the presented WebAuthn assertion.
+ */
+export function localDecryptionPayload(material: LocalNearSkV3Material): SignerDecryptionPayload {
+  const credentialEnvelopes = (material.credentialEnvelopes || []).map((env) => ({
+    credentialId: env.credentialId,
+    encryptedPrivateKeyData: env.encryptedSk,
+    encryptedPrivateKeyChacha20NonceB64u: env.chacha20NonceB64u,
+    ...envelopeFormat(env),
+  }));
+
+  if (!material.credentialId) {
+    return {
+      encryptedPrivateKeyData: material.encryptedSk,
+      encryptedPrivateKeyChacha20NonceB64u: material.chacha20NonceB64u,
+      ...(credentialEnvelopes.length ? { credentialEnvelopes } : {}),
+    };
+  }
+
+  return {
+    encryptedPrivateKeyData: '',
+    encryptedPrivateKeyChacha20NonceB64u: '',
+    credentialEnvelopes: [
+      {
+        credentialId: material.credentialId,
+        encryptedPrivateKeyData: material.encryptedSk,
+        encryptedPrivateKeyChacha20NonceB64u: material.chacha20NonceB64u,
+        ...envelopeFormat(material),
+      },
+      ...credentialEnvelopes,
+    ],
+  };
+}
@@ -1,5 +1,8 @@
 
-import type { LocalNearSkV3Material } from '../../../IndexedDBManager/passkeyNearKeysDB';
+import {
+  mergeLocalCredentialEnvelopes,
+  type LocalNearSkV3Material,
+} from '../../../IndexedDBManager/passkeyNearKeysDB';
 import type { AuthenticatorOptions } from '../../../types/authenticatorOptions';
 import {
   WorkerRequestType,
@@ -29,6 +32,12 @@ export async function deriveNearKeypairAndEncryptFromSerialized({
   options?: {
     authenticatorOptions?: AuthenticatorOptions;
     deviceNumber?: number;
+    /** Also return the implicit account ID (hex of the derived public key). */
+    deriveImplicitAccountId?: boolean;
+    /** App context bound (with the account id) into the key envelope's AAD. */
+    aadContext?: string;
+    /** Replace the worker's device record for `deviceNumber` if another credential holds it. */
+    force?: boolean;
   };
   sessionId: string;
 }): Promise<{
@@ -40,6 +49,9 @@ export async function deriveNearKeypairAndEncryptFromSerialized({
    */
   chacha20NonceB64u?: string;
   wrapKeySalt?: string;
+  /** Credential the stored envelope is bound to (derivation version 3). */
+  credentialId?: string;
+  implicitAccountId?: string;
   error?: string;
 }> {
   try {
@@ -47,6 +59,11 @@ export async function deriveNearKeypairAndEncryptFromSerialized({
     // No need to extract or send them through main thread
     if (!sessionId) throw new Error('Missing sessionId for registration WrapKeySeed delivery');
 
+    // Prefer explicitly provided deviceNumber, else derive from IndexedDB state
+    const deviceNumber = (typeof options?.deviceNumber === 'number')
+      ? options!.deviceNumber!
+      : await getLastLoggedInDeviceNumber(nearAccountId, ctx.indexedDB.clientDB);
+
     const response = await ctx.sendMessage<WorkerRequestType.DeriveNearKeypairAndEncrypt>({
       sessionId,
       message: {
@@ -58,6 +75,10 @@ export async function deriveNearKeypairAndEncryptFromSerialized({
             userVerification: toEnumUserVerificationPolicy(options.authenticatorOptions.userVerification),
             originPolicy: options.authenticatorOptions.originPolicy,
           } : undefined,
+          deriveImplicitAccountId: options?.deriveImplicitAccountId,
+          aadContext: options?.aadContext,
+          deviceNumber,
+          force: options?.force,
         })
       },
     });
@@ -71,10 +92,6 @@ export async function deriveNearKeypairAndEncryptFromSerialized({
     if (!wrapKeySaltPersisted) {
       throw new Error('Missing wrapKeySalt in deriveNearKeypairAndEncrypt result');
     }
-    // Prefer explicitly provided deviceNumber, else derive from IndexedDB state
-    const deviceNumber = (typeof options?.deviceNumber === 'number')
-      ? options!.deviceNumber!
-      : await getLastLoggedInDeviceNumber(nearAccountId, ctx.indexedDB.clientDB);
     const chacha20NonceB64u = wasmResult.chacha20NonceB64u;
     if (!chacha20NonceB64u) {
       throw new Error('Missing chacha20NonceB64u in deriveNearKeypairAndEncrypt result');
@@ -87,9 +104,15 @@ export async function deriveNearKeypairAndEncryptFromSerialized({
       encryptedSk: wasmResult.encryptedData,
       chacha20NonceB64u,
       wrapKeySalt: wrapKeySaltPersisted,
+      ...(wasmResult.credentialId ? { credentialId: wasmResult.credentialId } : {}),
+      envelopeVersion: wasmResult.version,
+      ...(typeof wasmResult.aadContext === 'string' ? { aadContext: wasmResult.aadContext } : {}),
       timestamp: Date.now(),
     };
-    await ctx.indexedDB.nearKeysDB.storeKeyMaterial(keyMaterial);
+    // Another passkey on this device keeps its own envelope; both NEAR keys (if they differ)
+    // must be on-chain access keys for either passkey to sign.
+    const existing = await ctx.indexedDB.nearKeysDB.getLocalKeyMaterial(nearAccountId, deviceNumber);
+    await ctx.indexedDB.nearKeysDB.storeKeyMaterial(mergeLocalCredentialEnvelopes(existing, keyMaterial));
 
     return {
       success: true,
@@ -97,6 +120,8 @@ export async function deriveNearKeypairAndEncryptFromSerialized({
       publicKey: wasmResult.publicKey,
       chacha20NonceB64u,
       wrapKeySalt: wrapKeySaltPersisted,
+      ...(wasmResult.credentialId ? { credentialId: wasmResult.credentialId } : {}),
+      ...(wasmResult.implicitAccountId ? { implicitAccountId: wasmResult.implicitAccountId } : {}),
     };
   } catch (error: unknown) {
     console.error('WebAuthnManager: deriveNearKeypairAndEncryptFromSerialized error:', error);
@@ -50,6 +50,9 @@ export async function exportNearKeypairUi({
         nearAccountId: accountId,
         encryptedPrivateKeyData: keyData.encryptedSk,
         encryptedPrivateKeyChacha20NonceB64u: keyData.chacha20NonceB64u,
+        credentialId: keyData.credentialId,
+        envelopeVersion: keyData.envelopeVersion,
+        aadContext: keyData.aadContext,
       },
     },
   });
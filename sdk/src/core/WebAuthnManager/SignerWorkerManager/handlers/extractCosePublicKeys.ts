@@ -0,0 +1,31 @@
+
+import {
+  WorkerRequestType,
+  isExtractCosePublicKeysSuccess,
+  type WasmCoseExtractionItem,
+} from '../../../types/signer-worker';
+import { SignerWorkerManagerContext } from '..';
+
+
+/**
+ * Extract COSE public keys from several WebAuthn attestation objects in one worker round trip.
+ * Each entry succeeds or fails independently; results are returned in request order.
+ */
+export async function extractCosePublicKeys({ ctx, attestationObjectsB64u }: {
+  ctx: SignerWorkerManagerContext;
+  attestationObjectsB64u: string[];
+}): Promise<WasmCoseExtractionItem[]> {
+  const response = await ctx.sendMessage<WorkerRequestType.ExtractCosePublicKeys>({
+    message: {
+      type: WorkerRequestType.ExtractCosePublicKeys,
+      payload: {
+        attestationObjectsB64u
+      }
+    }
+  });
+
+  if (isExtractCosePublicKeysSuccess(response)) {
+    return response.payload.results;
+  }
+  throw new Error('Batched COSE public key extraction failed in WASM worker');
+}
@@ -0,0 +1,32 @@
+
+import {
+  WorkerRequestType,
+  isGetDeviceRecordsSuccess,
+  type WasmDeviceRecord,
+} from '../../../types/signer-worker';
+import type { AccountId } from '../../../types/accountIds';
+import { SignerWorkerManagerContext } from '..';
+
+
+/**
+ * List the devices (device number, NEAR public key, credential id) the signer worker has
+ * recorded for an account, ordered by device number.
+ */
+export async function getDeviceRecords({ ctx, nearAccountId }: {
+  ctx: SignerWorkerManagerContext;
+  nearAccountId: AccountId;
+}): Promise<WasmDeviceRecord[]> {
+  const response = await ctx.sendMessage<WorkerRequestType.GetDeviceRecords>({
+    message: {
+      type: WorkerRequestType.GetDeviceRecords,
+      payload: {
+        nearAccountId
+      }
+    }
+  });
+
+  if (isGetDeviceRecordsSuccess(response)) {
+    return response.payload.records;
+  }
+  throw new Error('Device record lookup failed in WASM worker');
+}
@@ -1,12 +1,17 @@
 export * from './checkCanRegisterUser';
 export * from './deriveNearKeypairAndEncryptFromSerialized';
 export * from './decryptPrivateKeyWithPrf';
+export * from './migrateLegacyEncryptedKey';
 export * from './signTransactionsWithActions';
 export * from './signDelegateAction';
 export * from './recoverKeypairFromPasskey';
 export * from './extractCosePublicKey';
+export * from './extractCosePublicKeys';
+export * from './getDeviceRecords';
 export * from './signTransactionWithKeyPair';
 export * from './signNep413Message';
 export * from './registerDevice2WithDerivedKey';
 export * from './exportNearKeypairUi';
 export * from './deriveThresholdEd25519ClientVerifyingShare';
+export * from './warmThresholdEd25519ClientShare';
+export * from './provisionRelayerRequestAuthSecret';
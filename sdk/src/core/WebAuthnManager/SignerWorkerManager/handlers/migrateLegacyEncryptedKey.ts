@@ -0,0 +1,64 @@
+import {
+  WorkerRequestType,
+  isMigrateLegacyEncryptedKeySuccess,
+} from '../../../types/signer-worker';
+import { AccountId, toAccountId } from '../../../types/accountIds';
+
+import { SignerWorkerManagerContext } from '..';
+import { isObject } from '@/utils/validation';
+import { withSessionId } from './session';
+
+/**
+ * Re-encrypt a legacy `packages/passkey` (dual-PRF) key blob under the current WrapKey envelope.
+ *
+ * `prfFirstOutputB64u` comes from a normal WebAuthn ceremony; WrapKey material for the new
+ * envelope is delivered to the signer over the session's WrapKeySeed MessagePort.
+ * Callers must check `publicKey` against the on-chain access key before deleting the legacy blob.
+ */
+export async function migrateLegacyEncryptedKey({
+  ctx,
+  sessionId,
+  nearAccountId,
+  legacyEncryptedPrivateKeyData,
+  legacyEncryptedPrivateKeyIv,
+  prfFirstOutputB64u,
+}: {
+  ctx: SignerWorkerManagerContext,
+  sessionId: string,
+  nearAccountId: AccountId,
+  legacyEncryptedPrivateKeyData: string,
+  legacyEncryptedPrivateKeyIv: string,
+  prfFirstOutputB64u: string,
+}): Promise<{
+  nearAccountId: AccountId;
+  publicKey: string;
+  encryptedPrivateKeyData: string;
+  encryptedPrivateKeyChacha20NonceB64u: string;
+  wrapKeySalt: string;
+}> {
+  const response = await ctx.sendMessage({
+    sessionId,
+    message: {
+      type: WorkerRequestType.MigrateLegacyEncryptedKey,
+      payload: withSessionId(sessionId, {
+        nearAccountId,
+        legacyEncryptedPrivateKeyData,
+        legacyEncryptedPrivateKeyIv,
+        prfFirstOutputB64u,
+      }),
+    },
+  });
+
+  if (!isMigrateLegacyEncryptedKeySuccess(response)) {
+    console.error('WebAuthnManager: legacy key migration failed:', response);
+    const payloadError = isObject(response?.payload) && (response as any)?.payload?.error;
+    throw new Error(payloadError || 'Legacy key migration failed');
+  }
+  return {
+    nearAccountId: toAccountId(response.payload.nearAccountId),
+    publicKey: response.payload.publicKey,
+    encryptedPrivateKeyData: response.payload.encryptedPrivateKeyData,
+    encryptedPrivateKeyChacha20NonceB64u: response.payload.encryptedPrivateKeyChacha20NonceB64u,
+    wrapKeySalt: response.payload.wrapKeySalt,
+  };
+}
@@ -0,0 +1,35 @@
+import type { SignerWorkerManagerContext } from '..';
+import {
+  WorkerRequestType,
+  WorkerResponseType,
+  type WasmProvisionRelayerRequestAuthSecretResult,
+} from '../../../types/signer-worker';
+
+/**
+ * Store the relayer `requestAuth` HMAC secret in the signer worker reserved for `sessionId`,
+ * under `keyId`. Threshold signer configs then refer to it by `keyId` only.
+ */
+export async function provisionRelayerRequestAuthSecret(args: {
+  ctx: SignerWorkerManagerContext;
+  sessionId: string;
+  keyId: string;
+  secretB64u: string;
+}): Promise<{ keyId: string }> {
+  const { ctx, sessionId, keyId, secretB64u } = args;
+  if (!sessionId) throw new Error('Missing sessionId');
+  if (!keyId) throw new Error('Missing keyId');
+
+  const response = await ctx.sendMessage<WorkerRequestType.ProvisionRelayerRequestAuthSecret>({
+    sessionId,
+    message: {
+      type: WorkerRequestType.ProvisionRelayerRequestAuthSecret,
+      payload: { keyId, secretB64u },
+    },
+  });
+
+  if (response.type !== WorkerResponseType.ProvisionRelayerRequestAuthSecretSuccess) {
+    throw new Error('ProvisionRelayerRequestAuthSecret failed');
+  }
+  const wasmResult = response.payload as WasmProvisionRelayerRequestAuthSecretResult;
+  return { keyId: wasmResult?.keyId || keyId };
+}
@@ -15,11 +15,17 @@ export async function recoverKeypairFromPasskey({
   credential,
   accountIdHint,
   sessionId,
+  rpId,
+  allowedAppIds,
 }: {
   ctx: SignerWorkerManagerContext;
   credential: WebAuthnAuthenticationCredential;
   accountIdHint?: string;
   sessionId: string;
+  /** When set, the assertion's rpIdHash must match this rp id (or an `allowedAppIds` entry). */
+  rpId?: string;
+  /** Legacy U2F appids accepted for imported security keys; empty accepts `rpId` only. */
+  allowedAppIds?: string[];
 }): Promise<{
   publicKey: string;
   encryptedPrivateKey: string;
@@ -29,6 +35,10 @@ export async function recoverKeypairFromPasskey({
   chacha20NonceB64u: string;
   accountIdHint?: string;
   wrapKeySalt: string;
+  /** True when the assertion matched an allowed appid; prompt the user to re-register. */
+  usedLegacyAppId?: boolean;
+  /** `clientExtensionResults.appid` as reported by the browser. */
+  appidExtension?: boolean;
 }> {
   try {
     console.info('SignerWorkerManager: Starting dual PRF-based keypair recovery from authentication credential');
@@ -52,6 +62,8 @@ export async function recoverKeypairFromPasskey({
         payload: withSessionId(sessionId, {
           credential,
           accountIdHint,
+          rpId,
+          allowedAppIds,
         })
       },
     });
@@ -71,6 +83,8 @@ export async function recoverKeypairFromPasskey({
       chacha20NonceB64u,
       accountIdHint: response.payload.accountIdHint,
       wrapKeySalt: response.payload.wrapKeySalt,
+      usedLegacyAppId: response.payload.usedLegacyAppId,
+      appidExtension: response.payload.appidExtension,
     };
 
   } catch (error: unknown) {
@@ -25,6 +25,9 @@ import { UserVerificationPolicy, toEnumUserVerificationPolicy } from '../../../t
  * 5. Signing the transaction with the derived NEAR keypair
  * 6. Storing encrypted key data in IndexedDB
  *
+ * With `signRegistrationTransaction: false` steps 4-5 are skipped, e.g. to learn the NEAR
+ * public key before a key swap; `vrfChallenge` and `transactionContext` are then not needed.
+ *
  * Security: PRF.second and WrapKeySeed never traverse the main thread - they're delivered
  * directly from VRF worker to Signer worker via MessagePort.
  */
@@ -39,22 +42,29 @@ export async function registerDevice2WithDerivedKey({
   wrapKeySalt,
   deviceNumber,
   deterministicVrfPublicKey,
+  nearRpcUrl,
+  signRegistrationTransaction = true,
 }: {
   ctx: SignerWorkerManagerContext;
   sessionId: string;
   nearAccountId: AccountId;
   credential: WebAuthnRegistrationCredential;
-  vrfChallenge: VRFChallenge;
-  transactionContext: TransactionContext;
+  vrfChallenge?: VRFChallenge;
+  transactionContext?: TransactionContext;
   contractId: string;
   wrapKeySalt: string;
   deviceNumber?: number;
   deterministicVrfPublicKey?: string;
+  /** When set, the worker picks the device number from the contract's authenticator list. */
+  nearRpcUrl?: string;
+  /** Sign `link_device_register_user` in the same request (default: true). */
+  signRegistrationTransaction?: boolean;
 }): Promise<
   | {
     success: true;
     publicKey: string;
-    signedTransaction: WasmSignedTransaction;
+    /** Null when `signRegistrationTransaction` was false. */
+    signedTransaction: WasmSignedTransaction | null;
     wrapKeySalt: string;
     encryptedData: string;
     /**
@@ -86,36 +96,53 @@ export async function registerDevice2WithDerivedKey({
       if (!s) return [];
       return Array.from(base64UrlDecode(s));
     };
-    const intentDigest32 = b64ToBytes(vrfChallenge.intentDigest);
-    if (intentDigest32.length !== 32) {
-      throw new Error('Missing or invalid vrfChallenge.intentDigest (expected base64url-encoded 32 bytes)');
-    }
-    // Construct contractArgs in TypeScript and use JSON.stringify() here.
-    // This is native to JS, extremely fast, and means the Rust worker just receives a "dumb" string that it can blindly convert to bytes
-    const finalContractArgs = {
-      vrf_data: {
-        vrf_input_data: b64ToBytes(vrfChallenge.vrfInput),
-        vrf_output: b64ToBytes(vrfChallenge.vrfOutput),
-        vrf_proof: b64ToBytes(vrfChallenge.vrfProof),
-        public_key: b64ToBytes(vrfChallenge.vrfPublicKey),
-        user_id: vrfChallenge.userId,
-        rp_id: vrfChallenge.rpId,
-        block_height: Number(vrfChallenge.blockHeight),
-        block_hash: b64ToBytes(vrfChallenge.blockHash),
-        intent_digest_32: intentDigest32,
-      },
-      webauthn_registration: credential,
-      deterministic_vrf_public_key: b64ToBytes(deterministicVrfPublicKey),
-      authenticator_options: {
-        userVerification: toEnumUserVerificationPolicy(UserVerificationPolicy.Preferred),
-        originPolicy: {
-          single: undefined,
-          all_subdomains: true,
-          multiple: undefined,
+    const buildContractArgs = (vrfChallenge: VRFChallenge) => {
+      const intentDigest32 = b64ToBytes(vrfChallenge.intentDigest);
+      if (intentDigest32.length !== 32) {
+        throw new Error('Missing or invalid vrfChallenge.intentDigest (expected base64url-encoded 32 bytes)');
+      }
+      // Construct contractArgs in TypeScript and use JSON.stringify() here.
+      // This is native to JS, extremely fast, and means the Rust worker just receives a "dumb" string that it can blindly convert to bytes
+      return {
+        vrf_data: {
+          vrf_input_data: b64ToBytes(vrfChallenge.vrfInput),
+          vrf_output: b64ToBytes(vrfChallenge.vrfOutput),
+          vrf_proof: b64ToBytes(vrfChallenge.vrfProof),
+          public_key: b64ToBytes(vrfChallenge.vrfPublicKey),
+          user_id: vrfChallenge.userId,
+          rp_id: vrfChallenge.rpId,
+          block_height: Number(vrfChallenge.blockHeight),
+          block_hash: b64ToBytes(vrfChallenge.blockHash),
+          intent_digest_32: intentDigest32,
         },
-      },
+        webauthn_registration: credential,
+        deterministic_vrf_public_key: b64ToBytes(deterministicVrfPublicKey),
+        authenticator_options: {
+          userVerification: toEnumUserVerificationPolicy(UserVerificationPolicy.Preferred),
+          originPolicy: {
+            single: undefined,
+            all_subdomains: true,
+            multiple: undefined,
+          },
+        },
+      };
     };
 
+    let signingFields = {};
+    if (signRegistrationTransaction) {
+      if (!vrfChallenge || !transactionContext) {
+        throw new Error('vrfChallenge and transactionContext are required to sign the Device2 registration transaction');
+      }
+      signingFields = {
+        transactionContext: {
+          txBlockHash: transactionContext.txBlockHash,
+          txBlockHeight: transactionContext.txBlockHeight,
+          baseNonce: transactionContext.nextNonce,
+        },
+        contractArgsJson: JSON.stringify(buildContractArgs(vrfChallenge)),
+      };
+    }
+
     // Build request payload for combined Device2 registration
     const response = await ctx.sendMessage<WorkerRequestType.RegisterDevice2WithDerivedKey>({
       sessionId,
@@ -124,13 +151,10 @@ export async function registerDevice2WithDerivedKey({
         payload: withSessionId(sessionId, {
           credential,
           nearAccountId,
-          transactionContext: {
-            txBlockHash: transactionContext.txBlockHash,
-            txBlockHeight: transactionContext.txBlockHeight,
-            baseNonce: transactionContext.nextNonce,
-          },
+          ...signingFields,
           contractId,
-          contractArgsJson: JSON.stringify(finalContractArgs),
+          signRegistrationTransaction,
+          rpcUrl: nearRpcUrl,
         }),
       },
     });
@@ -140,6 +164,9 @@ export async function registerDevice2WithDerivedKey({
     }
 
     const wasmResult = response.payload;
+    if (signRegistrationTransaction && !wasmResult.signedTransaction) {
+      throw new Error('Missing signedTransaction in Device2 registration result');
+    }
 
     console.debug('[SignerWorkerManager] Device2 registration complete, storing encrypted key');
 
@@ -156,7 +183,8 @@ export async function registerDevice2WithDerivedKey({
     const keyMaterial: LocalNearSkV3Material = {
       kind: 'local_near_sk_v3',
       nearAccountId,
-      deviceNumber: deviceNumber ?? 2, // Default to device 2
+      // Default to the number the worker assigned (next free device number)
+      deviceNumber: deviceNumber ?? wasmResult.deviceNumber ?? 2,
       publicKey: wasmResult.publicKey,
       encryptedSk: wasmResult.encryptedData,
       chacha20NonceB64u,
@@ -170,7 +198,7 @@ export async function registerDevice2WithDerivedKey({
     return {
       success: true,
       publicKey: wasmResult.publicKey,
-      signedTransaction: wasmResult.signedTransaction,
+      signedTransaction: wasmResult.signedTransaction ?? null,
       wrapKeySalt: wrapKeySaltPersisted,
       encryptedData: wasmResult.encryptedData,
       chacha20NonceB64u,
@@ -34,6 +34,7 @@ import { SignerWorkerManagerContext } from '..';
 import { getLastLoggedInDeviceNumber } from '../getDeviceNumber';
 import { generateSessionId } from '../sessionHandshake.js';
 import { ensureEd25519Prefix, toPublicKeyString } from './validation';
+import { localDecryptionPayload } from './decryption';
 
 export async function signDelegateAction({
   ctx,
@@ -162,10 +163,7 @@ export async function signDelegateAction({
           signerMode: signingContext.resolvedSignerMode,
           rpcCall: resolvedRpcCall,
           createdAt: Date.now(),
-          decryption: {
-            encryptedPrivateKeyData: localKeyMaterial.encryptedSk,
-            encryptedPrivateKeyChacha20NonceB64u: localKeyMaterial.chacha20NonceB64u,
-          },
+          decryption: localDecryptionPayload(localKeyMaterial),
           delegate: delegatePayload,
           intentDigest,
           transactionContext,
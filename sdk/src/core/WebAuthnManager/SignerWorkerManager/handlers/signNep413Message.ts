@@ -4,6 +4,7 @@ import {
   isWorkerError,
   type ConfirmationConfig,
   type Nep413SigningResponse,
+  type SignerDecryptionPayload,
   type SignerMode,
   type WorkerSuccessResponse,
 } from '../../../types/signer-worker';
@@ -25,6 +26,8 @@ import { normalizeThresholdEd25519ParticipantIds } from '../../../../threshold/p
 import { getLastLoggedInDeviceNumber } from '../getDeviceNumber';
 import { generateSessionId } from '../sessionHandshake.js';
 import { SignerWorkerManagerContext } from '..';
+import { localDecryptionPayload } from './decryption';
+import { computeNep413MessageSummary } from '../../../digests/intentDigest';
 
 /**
  * Sign a NEP-413 message using the user's passkey-derived private key
@@ -60,6 +63,7 @@ export async function signNep413Message({ ctx, payload }: {
     const sessionId = payload.sessionId ?? generateSessionId();
     const relayerUrl = ctx.relayerUrl;
     const nearAccountId = payload.accountId;
+    const messageSummary = await computeNep413MessageSummary(payload.message);
 
     const deviceNumber = await getLastLoggedInDeviceNumber(nearAccountId, ctx.indexedDB.clientDB);
     const [localKeyMaterial, thresholdKeyMaterial] = await Promise.all([
@@ -139,7 +143,7 @@ export async function signNep413Message({ ctx, payload }: {
         sessionId,
         message: { type: WorkerRequestType.SignNep413Message, payload: requestPayload },
       });
-      const okResponse = requireOkSignNep413MessageResponse(response);
+      const okResponse = requireOkSignNep413MessageResponse(response, messageSummary);
 
       return {
         success: true,
@@ -156,7 +160,7 @@ export async function signNep413Message({ ctx, payload }: {
         sessionId,
         message: { type: WorkerRequestType.SignNep413Message, payload: requestPayload },
       });
-      okResponse = requireOkSignNep413MessageResponse(response);
+      okResponse = requireOkSignNep413MessageResponse(response, messageSummary);
     } catch (e: unknown) {
       const err = e instanceof Error ? e : new Error(String(e));
       if (!isThresholdSessionAuthUnavailableError(err)) throw err;
@@ -191,7 +195,7 @@ export async function signNep413Message({ ctx, payload }: {
         sessionId,
         message: { type: WorkerRequestType.SignNep413Message, payload: requestPayload },
       });
-      okResponse = requireOkSignNep413MessageResponse(response);
+      okResponse = requireOkSignNep413MessageResponse(response, messageSummary);
     }
 
     return {
@@ -219,7 +223,7 @@ export async function signNep413Message({ ctx, payload }: {
 type ThresholdNep413SigningContext = {
   resolvedSignerMode: 'threshold-signer';
   nearPublicKey: string;
-  decryption: { encryptedPrivateKeyData: string; encryptedPrivateKeyChacha20NonceB64u: string };
+  decryption: SignerDecryptionPayload;
   threshold: {
     relayerUrl: string;
     thresholdKeyMaterial: ThresholdEd25519_2p_V1Material;
@@ -231,7 +235,7 @@ type ThresholdNep413SigningContext = {
 type LocalNep413SigningContext = {
   resolvedSignerMode: 'local-signer';
   nearPublicKey: string;
-  decryption: { encryptedPrivateKeyData: string; encryptedPrivateKeyChacha20NonceB64u: string };
+  decryption: SignerDecryptionPayload;
   threshold: null;
 };
 
@@ -254,10 +258,7 @@ function validateAndPrepareNep413SigningContext(args: {
     return {
       resolvedSignerMode: 'local-signer',
       nearPublicKey: localPublicKey,
-      decryption: {
-        encryptedPrivateKeyData: args.localKeyMaterial.encryptedSk,
-        encryptedPrivateKeyChacha20NonceB64u: args.localKeyMaterial.chacha20NonceB64u,
-      },
+      decryption: localDecryptionPayload(args.localKeyMaterial),
       threshold: null,
     };
   }
@@ -332,6 +333,7 @@ function extractSigningEvidenceFromConfirmation(confirmation: {
 
 function requireOkSignNep413MessageResponse(
   response: Nep413SigningResponse,
+  confirmedSummary: { messageByteLength: number; messageSha256Hex: string },
 ): WorkerSuccessResponse<typeof WorkerRequestType.SignNep413Message> {
   if (!isSignNep413MessageSuccess(response)) {
     if (isWorkerError(response)) {
@@ -339,5 +341,12 @@ function requireOkSignNep413MessageResponse(
     }
     throw new Error('NEP-413 signing failed');
   }
+  // The digest shown in the confirmation summary must be the one the signature covers.
+  if (
+    response.payload.messageSha256Hex !== confirmedSummary.messageSha256Hex
+    || response.payload.messageByteLength !== confirmedSummary.messageByteLength
+  ) {
+    throw new Error('NEP-413 signature does not cover the confirmed message');
+  }
   return response;
 }
@@ -1,6 +1,10 @@
 
 import { SignedTransaction } from '../../../NearClient';
-import { TransactionInputWasm, validateActionArgsWasm } from '../../../types/actions';
+import {
+  TransactionInputWasm,
+  detachDeployContractCode,
+  validateActionArgsWasm,
+} from '../../../types/actions';
 import { type onProgressEvents } from '../../../types/sdkSentEvents';
 import {
   WorkerRequestType,
@@ -39,6 +43,7 @@ import {
   isThresholdSignerMissingKeyError,
 } from '../../../threshold/thresholdSessionPolicy';
 import { normalizeThresholdEd25519ParticipantIds } from '../../../../threshold/participants';
+import { localDecryptionPayload } from './decryption';
 
 /**
  * Sign multiple transactions with shared VRF challenge and credential
@@ -312,19 +317,20 @@ async function signTransactionsWithActionsLocally(args: {
   nearAccountId: AccountId;
   logs?: string[];
 }>> {
+  // Contract code travels as transferred bytes; the worker resolves `codeAttachment` indices
+  // before verifying the intent digest.
+  const { txs: txSigningRequests, binaryAttachments } = detachDeployContractCode(args.txSigningRequests);
   const response = await args.ctx.sendMessage<WorkerRequestType.SignTransactionsWithActions>({
     sessionId: args.sessionId,
     message: {
       type: WorkerRequestType.SignTransactionsWithActions,
+      binaryAttachments,
       payload: {
         signerMode: 'local-signer',
         rpcCall: args.resolvedRpcCall,
         createdAt: Date.now(),
-        decryption: {
-          encryptedPrivateKeyData: args.localKeyMaterial.encryptedSk,
-          encryptedPrivateKeyChacha20NonceB64u: args.localKeyMaterial.chacha20NonceB64u,
-        },
-        txSigningRequests: args.txSigningRequests,
+        decryption: localDecryptionPayload(args.localKeyMaterial),
+        txSigningRequests,
         intentDigest: args.intentDigest,
         transactionContext: args.transactionContext,
         credential: args.credential,
@@ -0,0 +1,61 @@
+import type { SignerWorkerManagerContext } from '..';
+import {
+  WorkerRequestType,
+  WorkerResponseType,
+  type WasmWarmThresholdEd25519ClientShareResult,
+} from '../../../types/signer-worker';
+
+/**
+ * Derive the client threshold signing share once for `sessionId` and cache it in the signer
+ * worker, so later threshold signs on the same session skip the HKDF derivation.
+ * The cache is zeroized when the signing session is released.
+ */
+export async function warmThresholdEd25519ClientShare(args: {
+  ctx: SignerWorkerManagerContext;
+  sessionId: string;
+  nearAccountId: string;
+}): Promise<{
+  success: boolean;
+  nearAccountId: string;
+  clientVerifyingShareB64u: string;
+  error?: string;
+}> {
+  const { ctx } = args;
+  const sessionId = args.sessionId;
+  const nearAccountId = args.nearAccountId;
+
+  try {
+    if (!sessionId) throw new Error('Missing sessionId');
+    if (!nearAccountId) throw new Error('Missing nearAccountId');
+
+    const response = await ctx.sendMessage<WorkerRequestType.WarmThresholdEd25519ClientShare>({
+      sessionId,
+      message: {
+        type: WorkerRequestType.WarmThresholdEd25519ClientShare,
+        payload: { nearAccountId },
+      },
+    });
+
+    if (response.type !== WorkerResponseType.WarmThresholdEd25519ClientShareSuccess) {
+      throw new Error('WarmThresholdEd25519ClientShare failed');
+    }
+
+    const wasmResult = response.payload as WasmWarmThresholdEd25519ClientShareResult;
+    const clientVerifyingShareB64u = wasmResult?.clientVerifyingShareB64u;
+    if (!clientVerifyingShareB64u) throw new Error('Missing clientVerifyingShareB64u in worker response');
+
+    return {
+      success: true,
+      nearAccountId,
+      clientVerifyingShareB64u,
+    };
+  } catch (error: unknown) {
+    const message = String((error as { message?: unknown })?.message ?? error);
+    return {
+      success: false,
+      nearAccountId,
+      clientVerifyingShareB64u: '',
+      error: message
+    };
+  }
+}
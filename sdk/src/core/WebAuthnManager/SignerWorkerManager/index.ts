@@ -27,17 +27,20 @@ import {
   type SignerMode,
   WasmSignedDelegate,
 } from '../../types/signer-worker';
-import type { ThresholdBehavior } from '../../types/signer-worker';
+import type { ThresholdBehavior, WasmCoseExtractionItem, WasmDeviceRecord } from '../../types/signer-worker';
 import { TouchIdPrompt } from "../touchIdPrompt";
 import { isSignerWorkerControlMessage } from './sessionMessages';
 import { WorkerControlMessage } from '../../workerControlMessages';
 
 import {
   decryptPrivateKeyWithPrf,
+  migrateLegacyEncryptedKey,
   checkCanRegisterUser,
   signTransactionsWithActions,
   recoverKeypairFromPasskey,
   extractCosePublicKey,
+  extractCosePublicKeys,
+  getDeviceRecords,
   signTransactionWithKeyPair,
   signNep413Message,
   deriveNearKeypairAndEncryptFromSerialized,
@@ -45,6 +48,8 @@ import {
   registerDevice2WithDerivedKey,
   exportNearKeypairUi,
   deriveThresholdEd25519ClientVerifyingShare,
+  warmThresholdEd25519ClientShare,
+  provisionRelayerRequestAuthSecret,
 } from './handlers';
 import { RpcCallPayload } from '../../types/signer-worker';
 import { UserPreferencesManager } from '../userPreferences';
@@ -80,6 +85,8 @@ export interface SignerWorkerManagerContext {
     message: {
       type: T;
       payload: WithOptionalSessionId<WorkerRequestTypeMap[T]['request']>;
+      binaryAttachments?: Uint8Array[];
+      deadlineMsEpoch?: number;
     };
     onEvent?: (update: onProgressEvents) => void;
     timeoutMs?: number;
@@ -186,10 +193,21 @@ export class SignerWorkerManager {
   private readonly MAX_WORKER_POOL_SIZE = 3; // Increased for security model
   // Map of active signing sessions to reserved workers and optional WrapKeySeed ports
   private signingSessions: Map<string, SigningSessionEntry> = new Map();
+  // Relayer `requestAuth` HMAC secrets by keyId. Session workers are terminated on release, so
+  // this is the copy each newly reserved worker is provisioned from; it is never put in a
+  // signing payload and never leaves this manager otherwise.
+  private relayerRequestAuthSecrets: Map<string, string> = new Map();
   private readonly SIGNING_SESSION_TIMEOUT_MS = 5 * 60 * 1000; // 5 minutes
   // Queue operations per Worker instance to avoid clobbering `worker.onmessage`
   // when multiple sendMessage() calls target the same session worker concurrently.
   private sendQueueByWorker: WeakMap<Worker, Promise<unknown>> = new WeakMap();
+  // Requests posted to signer workers since construction (telemetry for composed flows).
+  private requestsPosted = 0;
+
+  /** Number of requests posted to signer workers so far. */
+  getRequestsPosted(): number {
+    return this.requestsPosted;
+  }
 
   private enqueueOnWorker<T>(worker: Worker, task: () => Promise<T>): Promise<T> {
     const prev = this.sendQueueByWorker.get(worker) ?? Promise.resolve();
@@ -225,6 +243,7 @@ export class SignerWorkerManager {
       try { worker.terminate(); } catch {}
     }
     this.workerPool = [];
+    this.relayerRequestAuthSecrets.clear();
     // Drop any queued message chains tied to terminated worker instances.
     this.sendQueueByWorker = new WeakMap();
   }
@@ -260,9 +279,15 @@ export class SignerWorkerManager {
    * @param opts.signerPort - Optional signer-facing `MessagePort` created/owned by the caller (VRF-created channel).
    *                         If omitted, this method creates a fresh `MessageChannel` and returns `vrfPort` so the
    *                         caller can transfer it to the VRF worker.
-   * @returns `{ worker, signerPort, vrfPort }` where `vrfPort` is only present when we created the channel here.
+   * @param opts.bindSession - Bind the session to a signer-issued `sessionBindingNonce`, which the
+   *                           caller must forward to the VRF worker with the WrapKeySeed request.
+   * @returns `{ worker, signerPort, vrfPort, sessionBindingNonce }` where `vrfPort` is only present when we
+   *          created the channel here and `sessionBindingNonce` only when `bindSession` was set.
    */
-  async reserveSignerWorkerSession(sessionId: string, opts?: { signerPort?: MessagePort }): Promise<{ worker: Worker; signerPort?: MessagePort; vrfPort?: MessagePort }> {
+  async reserveSignerWorkerSession(
+    sessionId: string,
+    opts?: { signerPort?: MessagePort; bindSession?: boolean }
+  ): Promise<{ worker: Worker; signerPort?: MessagePort; vrfPort?: MessagePort; sessionBindingNonce?: string }> {
     if (this.signingSessions.has(sessionId)) {
       throw new Error(`Signing session already exists for id: ${sessionId}`);
     }
@@ -270,6 +295,7 @@ export class SignerWorkerManager {
     const worker = this.getWorkerFromPool();
     let signerPort = opts?.signerPort;
     let vrfPort: MessagePort | undefined;
+    let sessionBindingNonce: string | undefined;
     if (!signerPort) {
       // If caller did not provide a signer-facing port, create a channel.
       // - port1 => signer worker (receiver)
@@ -286,7 +312,13 @@ export class SignerWorkerManager {
       }
 
       // Use centralized handshake logic (registers listener, sends message, waits for ACK)
-      await attachSessionPort(worker, sessionId, signerPort);
+      sessionBindingNonce = await attachSessionPort(
+        worker,
+        sessionId,
+        signerPort,
+        undefined,
+        opts?.bindSession ?? false,
+      );
 
       // Only add to signingSessions after successful attachment
       // (prevents callers from observing a session that can't receive WrapKeySeed yet).
@@ -295,6 +327,9 @@ export class SignerWorkerManager {
         wrapKeySeedPort: signerPort,
         createdAt: Date.now(),
       });
+      for (const [keyId, secretB64u] of this.relayerRequestAuthSecrets) {
+        await provisionRelayerRequestAuthSecret({ ctx: this.getContext(), sessionId, keyId, secretB64u });
+      }
 
     } catch (err) {
       console.error('[SignerWorkerManager]: Failed to attach WrapKeySeed port to signer worker', err);
@@ -305,7 +340,28 @@ export class SignerWorkerManager {
       this.signingSessions.delete(sessionId);
       throw err;
     }
-    return { worker, signerPort, vrfPort };
+    return { worker, signerPort, vrfPort, sessionBindingNonce };
+  }
+
+  /**
+   * Register the HMAC secret for threshold `requestAuth` under `keyId`. It is provisioned into
+   * every signer session worker (including ones already reserved), and threshold signer configs
+   * name it by `keyId` only. Re-registering a keyId replaces the secret.
+   */
+  async registerRelayerRequestAuthSecret(args: { keyId: string; secretB64u: string }): Promise<void> {
+    const keyId = String(args.keyId || '').trim();
+    if (!keyId) throw new Error('Missing keyId');
+    if (!args.secretB64u) throw new Error('Missing secretB64u');
+    const sessionIds = Array.from(this.signingSessions.keys());
+    for (const sessionId of sessionIds) {
+      await provisionRelayerRequestAuthSecret({
+        ctx: this.getContext(),
+        sessionId,
+        keyId,
+        secretB64u: args.secretB64u,
+      });
+    }
+    this.relayerRequestAuthSecrets.set(keyId, args.secretB64u);
   }
 
   /**
@@ -314,11 +370,23 @@ export class SignerWorkerManager {
   releaseSigningSession(sessionId: string): void {
     const entry = this.signingSessions.get(sessionId);
     if (!entry) return;
+    // Zeroize session-cached key material first; queued ahead of termination on a best-effort basis.
+    try { entry.worker.postMessage({ type: WorkerControlMessage.RELEASE_SESSION, sessionId }) } catch {}
     try { entry.wrapKeySeedPort?.close() } catch {}
     try { this.terminateAndReplaceWorker(entry.worker) } catch {}
     this.signingSessions.delete(sessionId);
   }
 
+  /**
+   * Cancel an in-flight request that is still waiting for WrapKeySeed on this session,
+   * so it fails as cancelled instead of timing out.
+   */
+  cancelOperation(sessionId: string): void {
+    const entry = this.signingSessions.get(sessionId);
+    if (!entry) return;
+    entry.worker.postMessage({ type: WorkerControlMessage.CANCEL_OPERATION, sessionId });
+  }
+
   /**
    * Sweep expired signing sessions based on createdAt and timeout.
    */
@@ -428,7 +496,12 @@ export class SignerWorkerManager {
     timeoutMs = SIGNER_WORKER_MANAGER_CONFIG.TIMEOUTS.DEFAULT, // 60s
   }: {
     sessionId?: string;
-    message: { type: T; payload: WithOptionalSessionId<WorkerRequestTypeMap[T]['request']> };
+    message: {
+      type: T;
+      payload: WithOptionalSessionId<WorkerRequestTypeMap[T]['request']>;
+      binaryAttachments?: Uint8Array[];
+      deadlineMsEpoch?: number;
+    };
     onEvent?: (update: onProgressEvents) => void;
     timeoutMs?: number;
   }): Promise<WorkerResponseForRequest<T>> {
@@ -558,12 +631,19 @@ export class SignerWorkerManager {
       };
 
       // Format message for Rust SignerWorkerMessage structure using WASM types
+      const binaryAttachments = message.binaryAttachments ?? [];
       const formattedMessage = {
         type: message.type, // Numeric enum value from WorkerRequestType
         payload: finalPayload,
+        // Embedder page origin for the worker's optional origin gate; the worker cannot see it.
+        callerOrigin: globalThis.location?.origin,
+        ...(binaryAttachments.length ? { binaryAttachments } : {}),
+        ...(message.deadlineMsEpoch !== undefined ? { deadlineMsEpoch: message.deadlineMsEpoch } : {}),
       };
 
-      worker.postMessage(formattedMessage);
+      // Attachments are transferred, not cloned: the caller's buffers are detached afterwards
+      this.requestsPosted++;
+      worker.postMessage(formattedMessage, binaryAttachments.map(bytes => bytes.buffer));
     }));
   }
 
@@ -608,6 +688,26 @@ export class SignerWorkerManager {
     });
   }
 
+  /**
+   * Opt-in: derive and cache the client threshold signing share for this signing session,
+   * so subsequent threshold signs skip re-deriving it. Cleared on `releaseSigningSession`.
+   */
+  async warmThresholdEd25519ClientShare(args: {
+    sessionId: string;
+    nearAccountId: AccountId;
+  }): Promise<{
+    success: boolean;
+    nearAccountId: string;
+    clientVerifyingShareB64u: string;
+    error?: string;
+  }> {
+    return warmThresholdEd25519ClientShare({
+      ctx: this.getContext(),
+      sessionId: args.sessionId,
+      nearAccountId: String(args.nearAccountId),
+    });
+  }
+
   /**
    * Secure private key decryption with dual PRF
    */
@@ -622,6 +722,22 @@ export class SignerWorkerManager {
     return decryptPrivateKeyWithPrf({ ctx: this.getContext(), ...args });
   }
 
+  async migrateLegacyEncryptedKey(args: {
+    sessionId: string,
+    nearAccountId: AccountId,
+    legacyEncryptedPrivateKeyData: string,
+    legacyEncryptedPrivateKeyIv: string,
+    prfFirstOutputB64u: string,
+  }): Promise<{
+    nearAccountId: AccountId;
+    publicKey: string;
+    encryptedPrivateKeyData: string;
+    encryptedPrivateKeyChacha20NonceB64u: string;
+    wrapKeySalt: string;
+  }> {
+    return migrateLegacyEncryptedKey({ ctx: this.getContext(), ...args });
+  }
+
   async checkCanRegisterUser(args: {
     vrfChallenge: VRFChallenge,
     credential: WebAuthnRegistrationCredential,
@@ -646,17 +762,20 @@ export class SignerWorkerManager {
    *
    * This replaces the old two-step flow (register → authenticate → sign).
    * PRF.second and WrapKeySeed are already in the signer worker via MessagePort.
+   * Pass `signRegistrationTransaction: false` to only derive and store the key.
    */
   async registerDevice2WithDerivedKey(args: {
     sessionId: string;
     nearAccountId: AccountId;
     credential: WebAuthnRegistrationCredential;
-    vrfChallenge: VRFChallenge;
-    transactionContext: TransactionContext;
+    vrfChallenge?: VRFChallenge;
+    transactionContext?: TransactionContext;
     contractId: string;
     wrapKeySalt: string;
     deviceNumber?: number;
-    deterministicVrfPublicKey: string;
+    deterministicVrfPublicKey?: string;
+    nearRpcUrl?: string;
+    signRegistrationTransaction?: boolean;
   }): Promise<{
     success: boolean;
     publicKey: string;
@@ -743,6 +862,21 @@ export class SignerWorkerManager {
     return extractCosePublicKey({ ctx: this.getContext(), attestationObjectBase64url });
   }
 
+  /**
+   * Batched COSE extraction (one worker message for several attestation objects).
+   * Per-item failures are reported in the results rather than thrown.
+   */
+  async extractCosePublicKeys(attestationObjectsB64u: string[]): Promise<WasmCoseExtractionItem[]> {
+    return extractCosePublicKeys({ ctx: this.getContext(), attestationObjectsB64u });
+  }
+
+  /**
+   * Devices the signer worker has derived or registered for an account, ordered by device number.
+   */
+  async getDeviceRecords(nearAccountId: AccountId): Promise<WasmDeviceRecord[]> {
+    return getDeviceRecords({ ctx: this.getContext(), nearAccountId });
+  }
+
   /**
    * Sign transaction with raw private key (for key replacement in Option D device linking)
    * No TouchID/PRF required - uses provided private key directly
@@ -32,25 +32,33 @@ type VrfSessionKeyDispenser = {
  * @param sessionId - The signing session ID
  * @param signerPort - The MessagePort for receiving WrapKeySeed material
  * @param timeoutMs - How long to wait for ACK (default: 2000ms)
+ * @param bindSession - Ask the signer for a `sessionBindingNonce`; WrapKeySeed for this session
+ *                      is then only accepted when the VRF worker echoes it
+ * @returns The session binding nonce when `bindSession` is set
  * @throws Error if attachment fails or times out
  */
 export async function attachSessionPort(
   worker: Worker,
   sessionId: string,
   signerPort: MessagePort,
-  timeoutMs: number = 2000
-): Promise<void> {
+  timeoutMs: number = 2000,
+  bindSession: boolean = false
+): Promise<string | undefined> {
   // Register the ACK listener BEFORE sending the message to avoid race condition
   const waitPromise = waitForWrapKeyPortAttach(worker, sessionId, timeoutMs);
 
   // Send the attach command (transfer the port)
   worker.postMessage(
-    { type: WorkerControlMessage.ATTACH_WRAP_KEY_SEED_PORT, sessionId },
+    {
+      type: WorkerControlMessage.ATTACH_WRAP_KEY_SEED_PORT,
+      sessionId,
+      ...(bindSession ? { bindSession } : {}),
+    },
     [signerPort]
   );
 
   // Wait for the worker to acknowledge successful attachment
-  await waitPromise;
+  return await waitPromise;
 }
 
 export const generateSessionId = (): string => {
@@ -17,6 +17,8 @@ import { WorkerControlMessage } from '../../workerControlMessages';
 export interface AttachWrapKeySeedPortOkMessage {
   type: typeof WorkerControlMessage.ATTACH_WRAP_KEY_SEED_PORT_OK;
   sessionId: string;
+  /** Present when the attach asked for session binding (`bindSession: true`). */
+  sessionBindingNonce?: string;
 }
 
 /**
@@ -182,17 +184,26 @@ export function waitForSessionMessage(
  * @param worker - The worker that should send the ACK
  * @param sessionId - The session ID to match
  * @param timeoutMs - How long to wait before rejecting (default: 2000ms)
- * @returns Promise that resolves on success ACK, rejects on error or timeout
+ * @returns Promise that resolves with the ACK's `sessionBindingNonce` (if any) on success,
+ *          rejects on error or timeout
  */
-export function waitForWrapKeyPortAttach(
+export async function waitForWrapKeyPortAttach(
   worker: Worker,
   sessionId: string,
   timeoutMs: number = 2000
-): Promise<void> {
-  return waitForSessionMessage(worker, {
+): Promise<string | undefined> {
+  let sessionBindingNonce: string | undefined;
+  await waitForSessionMessage(worker, {
     successType: WorkerControlMessage.ATTACH_WRAP_KEY_SEED_PORT_OK,
     errorType: WorkerControlMessage.ATTACH_WRAP_KEY_SEED_PORT_ERROR,
     sessionId,
     timeoutMs,
+    validator: (msg) => {
+      if (typeof msg.sessionBindingNonce === 'string') {
+        sessionBindingNonce = msg.sessionBindingNonce;
+      }
+      return true;
+    },
   });
+  return sessionBindingNonce;
 }
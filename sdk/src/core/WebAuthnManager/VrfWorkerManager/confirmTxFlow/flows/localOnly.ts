@@ -156,6 +156,7 @@ export async function handleLocalOnlyFlow(
     } catch (err: unknown) {
       const cancelled = isUserCancelledSecureConfirm(err);
       if (cancelled) {
+        ctx.onUserCancelled?.(request.requestId);
         window.parent?.postMessage({ type: 'WALLET_UI_CLOSED' }, '*');
       }
       return session.confirmAndCloseModal({
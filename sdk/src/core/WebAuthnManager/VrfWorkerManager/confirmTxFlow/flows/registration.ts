@@ -182,6 +182,7 @@ export async function handleRegistrationFlow(
     const cancelled = isUserCancelledSecureConfirm(err);
     const msg = String((toError(err))?.message || err || '');
     if (cancelled) {
+      ctx.onUserCancelled?.(request.requestId);
       window.parent?.postMessage({ type: 'WALLET_UI_CLOSED' }, '*');
     }
 
@@ -1,5 +1,5 @@
 import type { VrfWorkerManagerContext } from '../../';
-import type { ConfirmationConfig } from '../../../../types/signer-worker';
+import { keyReusePolicyFromConfirmationConfig, type ConfirmationConfig } from '../../../../types/signer-worker';
 import {
   SecureConfirmationType,
   TransactionSummary,
@@ -113,6 +113,7 @@ export async function handleTransactionSigningFlow(
     // 3) UI confirm
     const { confirmed, error: uiError } = await session.promptUser({ vrfChallenge: uiVrfChallengeForUi });
     if (!confirmed) {
+      ctx.onUserCancelled?.(request.requestId);
       return session.confirmAndCloseModal({
         requestId: request.requestId,
         intentDigest: getIntentDigest(request),
@@ -205,6 +206,9 @@ export async function handleTransactionSigningFlow(
         wrapKeySalt,
         contractId,
         nearRpcUrl,
+        keyReusePolicy: keyReusePolicyFromConfirmationConfig(confirmationConfig),
+        // The signer checks this against the intentDigest of the request it signs.
+        intentDigest: getIntentDigest(request),
         credential: serializedCredential,
       });
 	    } catch (err) {
@@ -228,6 +232,7 @@ export async function handleTransactionSigningFlow(
     const cancelled = isUserCancelledSecureConfirm(err);
     const msg = String((toError(err))?.message || err || '');
     if (cancelled) {
+      ctx.onUserCancelled?.(request.requestId);
       window.parent?.postMessage({ type: 'WALLET_UI_CLOSED' }, '*');
     }
     const isWrongPasskeyError = /multiple passkeys \(devicenumbers\) for account/i.test(msg);
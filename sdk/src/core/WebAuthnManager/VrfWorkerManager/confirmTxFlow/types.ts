@@ -39,6 +39,15 @@ export interface SecureConfirmDecision extends ForbiddenMainThreadSecrets {
   error?: string;
 }
 
+export interface SummaryField {
+  /** Canonical field id (`receiver`, `amount`) */
+  id: string;
+  /** Raw critical value (receiver account id, formatted NEAR amount) */
+  value: string;
+  /** Text to display: localized when accepted, canonical English otherwise */
+  text: string;
+}
+
 export interface TransactionSummary {
   totalAmount?: string;
   title?: string;
@@ -47,6 +56,12 @@ export interface TransactionSummary {
   intentDigest?: string;
   receiverId?: string;
   type?: string;
+  /** Set by the VRF worker from `receiverId`/`totalAmount` and `confirmationConfig.localizedStrings` */
+  fields?: SummaryField[];
+  /** SHA-256 (base64url) over the canonical field values; identical across locales */
+  fieldsDigest?: string;
+  /** Locale of `fields[*].text` when localized strings were accepted */
+  locale?: string;
   delegate?: {
     senderId?: string;
     receiverId?: string;
@@ -58,6 +73,11 @@ export interface TransactionSummary {
     deviceNumber: number;
     deterministicVrfPublicKey?: string;
   };
+  /** NEP-413 only: exact size and hex SHA-256 of the message being signed. */
+  nep413?: {
+    messageByteLength: number;
+    messageSha256Hex: string;
+  };
   vrfChallenge?: VRFChallenge;
   summary?: unknown;
 }
@@ -131,6 +151,9 @@ export interface SecureConfirmRequest<TPayload = SecureConfirmPayload, TSummary
   // Optional intent digest to echo back in responses for flows that
   // do not have a tx-centric payload (e.g., registration/link flows)
   intentDigest?: string;
+  // Set by the VRF worker when `confirmationConfig.localizedStrings` failed validation and
+  // the summary fell back to canonical English text.
+  localizationRejected?: boolean;
 }
 
 // V2 payloads
@@ -16,6 +16,12 @@ export async function checkSessionStatus(
   remainingUses?: number;
   expiresAtMs?: number;
   createdAtMs?: number;
+  renewableUntilMs?: number;
+  renewable?: boolean;
+  /** VRF worker state version at the time of the check (see `getStateChangesSince`). */
+  stateVersion?: number;
+  /** Epoch of the VRF worker's last completed logout. */
+  logoutEpoch?: number;
 }> {
   await ctx.ensureWorkerReady(true);
   const message: VRFWorkerMessage<WasmCheckSessionStatusRequest> = {
@@ -1,4 +1,9 @@
-import type { VRFWorkerMessage, VRFWorkerStatus, WasmVrfWorkerRequestType } from '../../../types/vrf-worker';
+import type {
+  VRFWorkerDiagnostics,
+  VRFWorkerMessage,
+  VRFWorkerStatus,
+  WasmVrfWorkerRequestType,
+} from '../../../types/vrf-worker';
 import { toAccountId } from '../../../types/accountIds';
 import type { VrfWorkerManagerHandlerContext } from './types';
 
@@ -26,13 +31,19 @@ export async function checkVrfStatus(ctx: VrfWorkerManagerHandlerContext): Promi
     const response = await ctx.sendMessage(message);
 
     if (response.success && response.data) {
-      const data = response.data as { active: boolean; sessionDuration?: number; vrfPublicKey?: string };
+      const data = response.data as {
+        active: boolean;
+        sessionDuration?: number;
+        vrfPublicKey?: string;
+        logoutEpoch?: number;
+      };
       const current = ctx.getCurrentVrfAccountId();
       return {
         active: data.active,
         nearAccountId: current ? toAccountId(current) : null,
         sessionDuration: data.sessionDuration,
         vrfPublicKey: data.vrfPublicKey ?? null,
+        logoutEpoch: data.logoutEpoch,
       };
     }
 
@@ -42,3 +53,21 @@ export async function checkVrfStatus(ctx: VrfWorkerManagerHandlerContext): Promi
     return { active: false, nearAccountId: null, vrfPublicKey: null };
   }
 }
+
+/**
+ * Non-secret VRF manager state for diagnosing "why is my VRF locked": session/keypair flags,
+ * the bound account and whether Shamir/relay config is present. Never includes key material.
+ */
+export async function getVrfDiagnostics(ctx: VrfWorkerManagerHandlerContext): Promise<VRFWorkerDiagnostics> {
+  await ctx.ensureWorkerReady();
+  const message: VRFWorkerMessage<WasmVrfWorkerRequestType> = {
+    type: 'VRF_DIAGNOSTICS',
+    id: ctx.generateMessageId(),
+    payload: {} as WasmVrfWorkerRequestType,
+  };
+  const response = await ctx.sendMessage(message);
+  if (!response.success || !response.data) {
+    throw new Error(`getVrfDiagnostics failed: ${response.error}`);
+  }
+  return response.data as VRFWorkerDiagnostics;
+}
@@ -1,4 +1,4 @@
-import type { VRFWorkerMessage, WasmVrfWorkerRequestType } from '../../../types/vrf-worker';
+import type { VRFWorkerMessage, WasmClearVrfRequest, WasmVrfWorkerRequestType } from '../../../types/vrf-worker';
 import type { VrfWorkerManagerHandlerContext } from './types';
 
 /**
@@ -6,8 +6,14 @@ import type { VrfWorkerManagerHandlerContext } from './types';
  *
  * This differs from `clearSession`, which only clears a single signing session (`sessionId`).
  * Use this when the user explicitly logs out / locks the VRF keypair.
+ *
+ * Pass `logoutEpoch` when the signer worker's CLEAR_ALL_SESSIONS gets the same value; resolves
+ * with the epoch the VRF worker completed, or `null` if clearing failed.
  */
-export async function clearVrfSession(ctx: VrfWorkerManagerHandlerContext): Promise<void> {
+export async function clearVrfSession(
+  ctx: VrfWorkerManagerHandlerContext,
+  args: WasmClearVrfRequest = {}
+): Promise<number | null> {
   console.debug('VRF Manager: Clearing VRF session...');
 
   await ctx.ensureWorkerReady();
@@ -16,7 +22,7 @@ export async function clearVrfSession(ctx: VrfWorkerManagerHandlerContext): Prom
     const message: VRFWorkerMessage<WasmVrfWorkerRequestType> = {
       type: 'CLEAR_VRF',
       id: ctx.generateMessageId(),
-      payload: {} as WasmVrfWorkerRequestType
+      payload: { logoutEpoch: args.logoutEpoch } as WasmVrfWorkerRequestType
     };
 
     const response = await ctx.sendMessage(message);
@@ -25,10 +31,36 @@ export async function clearVrfSession(ctx: VrfWorkerManagerHandlerContext): Prom
       // Clear the TypeScript-tracked account ID
       ctx.setCurrentVrfAccountId(null);
       console.debug('VRF Manager: VRF session cleared (key material zeroized)');
-    } else {
-      console.warn('️VRF Manager: Clear VRF failed:', response.error);
+      return Number((response.data as { logoutEpoch?: number } | undefined)?.logoutEpoch ?? 0);
     }
+    console.warn('️VRF Manager: Clear VRF failed:', response.error);
   } catch (error) {
     console.warn('VRF Manager: Clear VRF error:', error);
   }
+  return null;
+}
+
+/**
+ * Full sign-out across every VRF session (LOGOUT_ALL): like `clearVrfSession`, but reports what
+ * was wiped and throws on failure. Pair with the signer worker's LogoutAll, which drops its
+ * cached WrapKeySeeds and relayer threshold auth sessions.
+ */
+export async function logoutAll(ctx: VrfWorkerManagerHandlerContext): Promise<{
+  clearedSessions: number;
+  clearedChallenges: number;
+  clearedActiveKeypair: boolean;
+  clearedUnlockedKeypairs: number;
+}> {
+  await ctx.ensureWorkerReady();
+  const message: VRFWorkerMessage<WasmVrfWorkerRequestType> = {
+    type: 'LOGOUT_ALL',
+    id: ctx.generateMessageId(),
+    payload: {} as WasmVrfWorkerRequestType,
+  };
+  const response = await ctx.sendMessage(message);
+  if (!response.success) {
+    throw new Error(`logoutAll failed: ${response.error}`);
+  }
+  ctx.setCurrentVrfAccountId(null);
+  return response.data as any;
 }
@@ -2,7 +2,11 @@ import type { TransactionInputWasm } from '../../../types/actions';
 import { ActionType } from '../../../types/actions';
 import type { RpcCallPayload, ConfirmationConfig } from '../../../types/signer-worker';
 import type { TransactionContext } from '../../../types/rpc';
-import { computeUiIntentDigestFromTxs, orderActionForDigest } from '../../../digests/intentDigest';
+import {
+  computeNep413MessageSummary,
+  computeUiIntentDigestFromTxs,
+  orderActionForDigest,
+} from '../../../digests/intentDigest';
 import {
   SecureConfirmationType,
   type SecureConfirmRequest,
@@ -183,6 +187,7 @@ export async function confirmAndPrepareSigningSession(
         intentDigest,
         method: 'NEP-413',
         receiverId: params.recipient,
+        nep413: await computeNep413MessageSummary(params.message),
         ...(params.title != null ? { title: params.title } : {}),
         ...(params.body != null ? { body: params.body } : {}),
       };
@@ -50,6 +50,8 @@ export async function deriveVrfKeypairFromPrf(
 	        blockHash: vrfInputData.blockHash,
 	        intentDigest: vrfInputData.intentDigest,
           sessionPolicyDigest32: vrfInputData.sessionPolicyDigest32,
+          sessionPolicyJson: vrfInputData.sessionPolicyJson,
+          extraContextDigest32: vrfInputData.extraContextDigest32,
 	      } : undefined,
 	    }
 	  };
@@ -86,6 +88,7 @@ export async function deriveVrfKeypairFromPrf(
       blockHash: data.vrfChallengeData.blockHash,
       ...(data.vrfChallengeData.intentDigest ? { intentDigest: data.vrfChallengeData.intentDigest } : {}),
       ...(data.vrfChallengeData.sessionPolicyDigest32 ? { sessionPolicyDigest32: data.vrfChallengeData.sessionPolicyDigest32 } : {}),
+      ...(data.vrfChallengeData.extraContextDigest32 ? { extraContextDigest32: data.vrfChallengeData.extraContextDigest32 } : {}),
     })
     : null;
 
@@ -0,0 +1,82 @@
+import type { AccountId } from '../../../types/accountIds';
+import type {
+  EncryptedVRFKeypair,
+  ServerEncryptedVrfKeypair,
+  VRFInputData,
+  VRFWorkerMessage,
+  WasmDevice2CompleteRegistrationRequest,
+} from '../../../types/vrf-worker';
+import { validateVRFChallenge, type VRFChallenge } from '../../../types/vrf-worker';
+import type { WebAuthnRegistrationCredential } from '../../../types/webauthn';
+import type { VrfWorkerManagerHandlerContext } from './types';
+
+/**
+ * Device2 linking in a single VRF request: derive + store the deterministic VRF keypair,
+ * encrypt it (locally and via Shamir 3-pass when configured), and deliver
+ * WrapKeySeed + PRF.second to the signer session so that a single
+ * REGISTER_DEVICE2_WITH_DERIVED_KEY request can finish the registration.
+ */
+export async function device2CompleteRegistration(
+  ctx: VrfWorkerManagerHandlerContext,
+  args: {
+    sessionId: string;
+    credential: WebAuthnRegistrationCredential;
+    nearAccountId: AccountId;
+    vrfInputData?: VRFInputData;
+    wrapKeySalt?: string;
+  }
+): Promise<{
+  vrfPublicKey: string;
+  vrfChallenge: VRFChallenge | null;
+  encryptedVrfKeypair: EncryptedVRFKeypair;
+  serverEncryptedVrfKeypair: ServerEncryptedVrfKeypair | null;
+  wrapKeySalt: string;
+}> {
+  await ctx.ensureWorkerReady();
+
+  const message: VRFWorkerMessage<WasmDevice2CompleteRegistrationRequest> = {
+    type: 'DEVICE2_COMPLETE_REGISTRATION',
+    id: ctx.generateMessageId(),
+    payload: {
+      sessionId: args.sessionId,
+      nearAccountId: args.nearAccountId,
+      credential: args.credential,
+      vrfInputData: args.vrfInputData ? {
+        userId: args.vrfInputData.userId,
+        rpId: args.vrfInputData.rpId,
+        blockHeight: String(args.vrfInputData.blockHeight),
+        blockHash: args.vrfInputData.blockHash,
+        intentDigest: args.vrfInputData.intentDigest,
+        sessionPolicyDigest32: args.vrfInputData.sessionPolicyDigest32,
+        sessionPolicyJson: args.vrfInputData.sessionPolicyJson,
+        extraContextDigest32: args.vrfInputData.extraContextDigest32,
+      } : undefined,
+      wrapKeySalt: args.wrapKeySalt,
+    },
+  };
+
+  const response = await ctx.sendMessage(message);
+  if (!response.success || !response.data) {
+    throw new Error(`Device2 registration failed: ${response.error}`);
+  }
+  const data = response.data as {
+    vrfPublicKey: string;
+    vrfChallengeData?: VRFChallenge | null;
+    encryptedVrfKeypair?: EncryptedVRFKeypair;
+    serverEncryptedVrfKeypair?: ServerEncryptedVrfKeypair | null;
+    wrapKeySalt: string;
+  };
+  if (!data.encryptedVrfKeypair) {
+    throw new Error('Encrypted VRF keypair not found in response');
+  }
+
+  ctx.setCurrentVrfAccountId(args.nearAccountId);
+
+  return {
+    vrfPublicKey: data.vrfPublicKey,
+    vrfChallenge: data.vrfChallengeData ? validateVRFChallenge(data.vrfChallengeData) : null,
+    encryptedVrfKeypair: data.encryptedVrfKeypair,
+    serverEncryptedVrfKeypair: data.serverEncryptedVrfKeypair || null,
+    wrapKeySalt: data.wrapKeySalt,
+  };
+}
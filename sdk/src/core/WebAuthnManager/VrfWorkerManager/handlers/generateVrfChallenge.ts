@@ -1,9 +1,11 @@
 import type {
+  VRFChallengeBatch,
   VRFInputData,
   VRFWorkerMessage,
   WasmGenerateVrfChallengeRequest,
+  WasmGenerateVrfChallengesRequest,
 } from '../../../types/vrf-worker';
-import { validateVRFChallenge, type VRFChallenge } from '../../../types/vrf-worker';
+import { validateVRFChallenge, VrfWorkerError, type VRFChallenge } from '../../../types/vrf-worker';
 import { toAccountId } from '../../../types/accountIds';
 import type { VrfWorkerManagerHandlerContext } from './types';
 import { checkVrfStatus } from './checkVrfStatus';
@@ -36,6 +38,55 @@ export async function generateVrfChallengeOnce(
   return generateVrfChallengeInternal(ctx, inputData);
 }
 
+/**
+ * Mint one VRF challenge per intent digest in a single worker round trip.
+ *
+ * All challenges share the block context of `base`. Entries fail individually (e.g. a
+ * duplicate digest or an exhausted session budget) and carry `errorCode` instead of a challenge.
+ */
+export async function generateVrfChallenges(
+  ctx: VrfWorkerManagerHandlerContext,
+  args: WasmGenerateVrfChallengesRequest
+): Promise<VRFChallengeBatch> {
+  await ctx.ensureWorkerReady(true);
+  await ensureVrfKeypairBoundToLastUser(ctx, args.base.userId);
+
+  const message: VRFWorkerMessage<WasmGenerateVrfChallengesRequest> = {
+    type: 'GENERATE_VRF_CHALLENGES',
+    id: ctx.generateMessageId(),
+    payload: {
+      sessionId: args.sessionId,
+      base: {
+        userId: args.base.userId,
+        rpId: args.base.rpId,
+        blockHeight: args.base.blockHeight,
+        blockHash: args.base.blockHash,
+        sessionPolicyDigest32: args.base.sessionPolicyDigest32,
+        sessionPolicyJson: args.base.sessionPolicyJson,
+        extraContextDigest32: args.base.extraContextDigest32,
+        rpIdNormalization: args.base.rpIdNormalization,
+      },
+      intentDigests: args.intentDigests,
+      maxCount: args.maxCount,
+    },
+  };
+
+  const response = await ctx.sendMessage(message);
+
+  if (!response.success || !response.data) {
+    throw new VrfWorkerError(`VRF challenge batch failed: ${response.error}`, response.errorCode);
+  }
+
+  const batch = response.data as unknown as VRFChallengeBatch;
+  return {
+    ...batch,
+    remainingUses: batch.remainingUses ?? null,
+    challenges: batch.challenges.map(item => item.challenge
+      ? { ...item, challenge: validateVRFChallenge(item.challenge) }
+      : item),
+  };
+}
+
 async function generateVrfChallengeInternal(
   ctx: VrfWorkerManagerHandlerContext,
   inputData: VRFInputData,
@@ -62,6 +113,8 @@ async function generateVrfChallengeInternal(
 	        blockHash: inputData.blockHash,
 	        intentDigest: inputData.intentDigest,
           sessionPolicyDigest32: inputData.sessionPolicyDigest32,
+          sessionPolicyJson: inputData.sessionPolicyJson,
+          extraContextDigest32: inputData.extraContextDigest32,
 	      },
 	    },
 	  };
@@ -69,7 +122,7 @@ async function generateVrfChallengeInternal(
   const response = await ctx.sendMessage(message);
 
   if (!response.success || !response.data) {
-    throw new Error(`VRF challenge generation failed: ${response.error}`);
+    throw new VrfWorkerError(`VRF challenge generation failed: ${response.error}`, response.errorCode);
   }
 
   const data = response.data as unknown as VRFChallenge;
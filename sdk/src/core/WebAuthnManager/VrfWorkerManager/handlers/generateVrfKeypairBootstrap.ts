@@ -38,6 +38,8 @@ export async function generateVrfKeypairBootstrap(
 	              blockHash: args.vrfInputData.blockHash,
 	              intentDigest: args.vrfInputData.intentDigest,
                 sessionPolicyDigest32: args.vrfInputData.sessionPolicyDigest32,
+                sessionPolicyJson: args.vrfInputData.sessionPolicyJson,
+                extraContextDigest32: args.vrfInputData.extraContextDigest32,
 	            }
 	          : undefined,
 	      },
@@ -76,6 +78,7 @@ export async function generateVrfKeypairBootstrap(
         blockHash: challengeData.blockHash,
         ...(challengeData.intentDigest ? { intentDigest: challengeData.intentDigest } : {}),
         ...(challengeData.sessionPolicyDigest32 ? { sessionPolicyDigest32: challengeData.sessionPolicyDigest32 } : {}),
+        ...(challengeData.extraContextDigest32 ? { extraContextDigest32: challengeData.extraContextDigest32 } : {}),
       })
     }
 
@@ -0,0 +1,50 @@
+import type {
+  StateChangesSince,
+  VRFWorkerMessage,
+  WasmGetStateChangesSinceRequest,
+  WasmVrfWorkerRequestType,
+} from '../../../types/vrf-worker';
+import type { VrfWorkerManagerHandlerContext } from './types';
+
+/**
+ * Current VRF worker state version. Bumped on every unlock, logout and session mutation;
+ * cheap enough to poll so the UI only re-reads status when it moved.
+ */
+export async function getStateVersion(ctx: VrfWorkerManagerHandlerContext): Promise<number> {
+  await ctx.ensureWorkerReady(true);
+  const message: VRFWorkerMessage<WasmVrfWorkerRequestType> = {
+    type: 'GET_STATE_VERSION',
+    id: ctx.generateMessageId(),
+    payload: {} as WasmVrfWorkerRequestType,
+  };
+  const response = await ctx.sendMessage(message);
+  if (!response.success) {
+    throw new Error(`getStateVersion failed: ${response.error}`);
+  }
+  return Number((response.data as { stateVersion?: number } | undefined)?.stateVersion ?? 0);
+}
+
+/**
+ * State changes the caller missed since `version`, oldest first. When `resyncRequired` is set
+ * the ring no longer covers the gap: re-read full VRF/session status instead.
+ */
+export async function getStateChangesSince(
+  ctx: VrfWorkerManagerHandlerContext,
+  args: { version: number }
+): Promise<StateChangesSince> {
+  await ctx.ensureWorkerReady(true);
+  const message: VRFWorkerMessage<WasmGetStateChangesSinceRequest> = {
+    type: 'GET_STATE_CHANGES_SINCE',
+    id: ctx.generateMessageId(),
+    payload: { version: args.version },
+  };
+  const response = await ctx.sendMessage<WasmGetStateChangesSinceRequest>(message);
+  if (!response.success) {
+    throw new Error(`getStateChangesSince failed: ${response.error}`);
+  }
+  return (response.data as StateChangesSince | undefined) || {
+    version: args.version,
+    events: [],
+    resyncRequired: true,
+  };
+}
@@ -5,12 +5,15 @@ export * from './confirmAndDeriveDevice2RegistrationSession';
 export * from './confirmAndPrepareSigningSession';
 export * from './createSigningSessionChannel';
 export * from './deriveVrfKeypairFromPrf';
+export * from './device2CompleteRegistration';
 export * from './mintSessionKeysAndSendToSigner';
 export * from './dispenseSessionKey';
 export * from './generateVrfChallenge';
 export * from './generateVrfKeypairBootstrap';
+export * from './getStateChanges';
 export * from './checkSessionStatus';
 export * from './prepareDecryptSession';
+export * from './renewSession';
 export * from './requestRegistrationCredentialConfirmation';
 export * from './shamir3PassDecryptVrfKeypair';
 export * from './shamir3PassEncryptCurrentVrfKeypair';
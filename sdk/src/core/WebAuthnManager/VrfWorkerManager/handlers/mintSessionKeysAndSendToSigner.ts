@@ -2,6 +2,7 @@ import type {
   VRFWorkerMessage,
   WasmMintSessionKeysAndSendToSignerRequest,
 } from '../../../types/vrf-worker';
+import type { KeyReusePolicy } from '../../../types/signer-worker';
 import type { WebAuthnAuthenticationCredential, WebAuthnRegistrationCredential } from '../../../types/webauthn';
 import type { VrfWorkerManagerHandlerContext } from './types';
 
@@ -13,7 +14,8 @@ import type { VrfWorkerManagerHandlerContext } from './types';
  * - derive WrapKeySeed from PRF.first_auth + the in-memory VRF secret key,
  * - choose/generate `wrapKeySalt` (when omitted/empty),
  * - upsert session metadata (TTL + remaining uses),
- * - and send `{ wrap_key_seed, wrapKeySalt, prfSecond? }` to the signer worker over the attached MessagePort.
+ * - and send `{ wrap_key_seed, wrapKeySalt, prfSecond?, keyReusePolicy?, sessionBindingNonce?, intentDigest? }`
+ *   to the signer worker over the attached MessagePort.
  *
  * The main thread never receives WrapKeySeed; it only receives `wrapKeySalt` metadata.
  * This expects `createSigningSessionChannel` + signer port attachment to have happened for `sessionId`.
@@ -27,6 +29,9 @@ export async function mintSessionKeysAndSendToSigner(
     nearRpcUrl?: string;
     ttlMs?: number;
     remainingUses?: number;
+    keyReusePolicy?: KeyReusePolicy;
+    sessionBindingNonce?: string;
+    intentDigest?: string;
     credential: WebAuthnRegistrationCredential | WebAuthnAuthenticationCredential;
   }
 ): Promise<{ sessionId: string; wrapKeySalt: string }> {
@@ -43,6 +48,9 @@ export async function mintSessionKeysAndSendToSigner(
       nearRpcUrl: args.nearRpcUrl,
       ttlMs: args.ttlMs,
       remainingUses: args.remainingUses,
+      keyReusePolicy: args.keyReusePolicy,
+      sessionBindingNonce: args.sessionBindingNonce,
+      intentDigest: args.intentDigest,
       credential: args.credential,
     }
   };
@@ -0,0 +1,34 @@
+import type { VRFWorkerMessage, WasmRenewSessionRequest } from '../../../types/vrf-worker';
+import type { VrfWorkerManagerHandlerContext } from './types';
+
+/**
+ * Extend a live signing session's expiry without a WebAuthn prompt.
+ *
+ * The new expiry never exceeds `renewableUntilMs` (the session's max total lifetime).
+ */
+export async function renewSession(
+  ctx: VrfWorkerManagerHandlerContext,
+  args: { sessionId: string; ttlMs?: number; sessionPolicyDigest?: string; sessionPolicyJson?: string }
+): Promise<{
+  sessionId: string;
+  expiresAtMs: number;
+  remainingMs: number;
+  renewableUntilMs?: number;
+}> {
+  await ctx.ensureWorkerReady(true);
+  const message: VRFWorkerMessage<WasmRenewSessionRequest> = {
+    type: 'RENEW_SESSION',
+    id: ctx.generateMessageId(),
+    payload: {
+      sessionId: args.sessionId,
+      ttlMs: args.ttlMs,
+      sessionPolicyDigest: args.sessionPolicyDigest,
+      sessionPolicyJson: args.sessionPolicyJson,
+    },
+  };
+  const response = await ctx.sendMessage<WasmRenewSessionRequest>(message);
+  if (!response.success) {
+    throw new Error(`renewSession failed: ${response.error}`);
+  }
+  return response.data as any;
+}
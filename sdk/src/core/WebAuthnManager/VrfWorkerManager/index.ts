@@ -5,6 +5,7 @@
 
 import type {
   VRFWorkerStatus,
+  VRFWorkerDiagnostics,
   VrfWorkerManagerConfig,
   EncryptedVRFKeypair,
   VRFInputData,
@@ -14,8 +15,15 @@ import type {
   WasmVrfWorkerRequestType,
   WasmShamir3PassConfigPRequest,
   WasmShamir3PassConfigServerUrlsRequest,
+  VrfProgressEvent,
+} from '../../types/vrf-worker';
+import { isVrfProgressMessage } from '../../types/vrf-worker';
+import type {
+  StateChangesSince,
+  VRFChallenge,
+  VRFChallengeBatch,
+  WasmGenerateVrfChallengesRequest,
 } from '../../types/vrf-worker';
-import type { VRFChallenge } from '../../types/vrf-worker';
 import { BUILD_PATHS } from '../../../../build-paths.js';
 import { resolveWorkerUrl } from '../../sdkPaths';
 import type { AccountId } from '../../types/accountIds';
@@ -31,7 +39,7 @@ import {
   type SigningAuthMode,
 } from './confirmTxFlow/types';
 import type { TransactionInputWasm } from '../../types/actions';
-import type { RpcCallPayload, ConfirmationConfig } from '../../types/signer-worker';
+import type { RpcCallPayload, ConfirmationConfig, KeyReusePolicy } from '../../types/signer-worker';
 import type { TransactionContext } from '../../types/rpc';
 import type { ThemeName } from '../../types/tatchi';
 import type { RegistrationCredentialConfirmationPayload } from '../SignerWorkerManager/handlers/validation';
@@ -41,18 +49,25 @@ import type { VrfWorkerManagerHandlerContext } from './handlers/types';
 import { WorkerControlMessage } from '../../workerControlMessages';
 import {
   checkVrfStatus,
+  getVrfDiagnostics,
   clearSession,
   clearVrfSession,
+  logoutAll,
   confirmAndDeriveDevice2RegistrationSession,
   confirmAndPrepareSigningSession,
   deriveVrfKeypairFromPrf,
+  device2CompleteRegistration,
   mintSessionKeysAndSendToSigner,
   dispenseSessionKey,
   generateVrfChallengeForSession,
   generateVrfChallengeOnce,
+  generateVrfChallenges,
   generateVrfKeypairBootstrap,
+  getStateChangesSince,
+  getStateVersion,
   checkSessionStatus,
   prepareDecryptSession,
+  renewSession,
   requestRegistrationCredentialConfirmation,
   shamir3PassDecryptVrfKeypair,
   shamir3PassEncryptCurrentVrfKeypair,
@@ -72,6 +87,8 @@ export interface VrfWorkerManagerContext {
   rpIdOverride?: string;
   nearExplorerUrl?: string;
   vrfWorkerManager?: SessionVrfWorkerManager;
+  /** Called with the signing session id when the user cancels a confirm flow. */
+  onUserCancelled?: (sessionId: string) => void;
 }
 
 /**
@@ -98,6 +115,9 @@ export interface SessionVrfWorkerManager {
     nearRpcUrl?: string;
     ttlMs?: number;
     remainingUses?: number;
+    keyReusePolicy?: KeyReusePolicy;
+    sessionBindingNonce?: string;
+    intentDigest?: string;
     credential: WebAuthnRegistrationCredential | WebAuthnAuthenticationCredential;
   }): Promise<{ sessionId: string; wrapKeySalt: string }>;
 
@@ -165,10 +185,13 @@ export class VrfWorkerManager {
   private vrfWorker: Worker | null = null;
   private initializationPromise: Promise<void> | null = null;
   private messageId = 0;
+  // Requests posted to the VRF worker since construction (telemetry for composed flows).
+  private requestsPosted = 0;
   private config: VrfWorkerManagerConfig;
   private currentVrfAccountId: string | null = null;
   private workerBaseOrigin: string | undefined;
   private context: VrfWorkerManagerContext;
+  private progressListeners = new Set<(event: VrfProgressEvent) => void>();
 
   constructor(config: VrfWorkerManagerConfig, context: VrfWorkerManagerContext) {
     this.config = {
@@ -192,6 +215,11 @@ export class VrfWorkerManager {
     return this.context;
   }
 
+  /** Number of requests posted to the VRF worker so far. */
+  getRequestsPosted(): number {
+    return this.requestsPosted;
+  }
+
   /**
    * Force-terminate the underlying VRF worker and reset initialization state.
    *
@@ -205,6 +233,24 @@ export class VrfWorkerManager {
     this.currentVrfAccountId = null;
   }
 
+  /**
+   * Subscribe to VRF unlock/derivation phases (e.g. to tell a slow Shamir relay
+   * round trip apart from local decryption). Returns an unsubscribe function.
+   */
+  onVrfProgress(listener: (event: VrfProgressEvent) => void): () => void {
+    this.progressListeners.add(listener);
+    return () => { this.progressListeners.delete(listener); };
+  }
+
+  private dispatchVrfProgress(event: MessageEvent): void {
+    if (!isVrfProgressMessage(event.data)) return;
+    for (const listener of this.progressListeners) {
+      try { listener(event.data.payload); } catch (err) {
+        console.warn('VRF Manager: progress listener failed:', err);
+      }
+    }
+  }
+
   private getHandlerContext(): VrfWorkerManagerHandlerContext {
     return {
       ensureWorkerReady: this.ensureWorkerReady.bind(this),
@@ -293,6 +339,12 @@ export class VrfWorkerManager {
     // Optional signing-session config. When omitted, VRF worker uses defaults.
     ttlMs?: number;
     remainingUses?: number;
+    // Optional opt-in reuse of one decrypted signing key; forwarded to the signer worker.
+    keyReusePolicy?: KeyReusePolicy;
+    // Optional signer-issued session binding nonce and confirmed intent digest; echoed to the
+    // signer worker so it can refuse WrapKeySeed that arrives on the wrong session's port.
+    sessionBindingNonce?: string;
+    intentDigest?: string;
     // Optional credential for PRF.second extraction (registration or authentication)
     credential: WebAuthnRegistrationCredential | WebAuthnAuthenticationCredential;
   }): Promise<{ sessionId: string; wrapKeySalt: string }> {
@@ -328,10 +380,34 @@ export class VrfWorkerManager {
     remainingUses?: number;
     expiresAtMs?: number;
     createdAtMs?: number;
+    renewableUntilMs?: number;
+    renewable?: boolean;
+    stateVersion?: number;
+    logoutEpoch?: number;
   }> {
     return checkSessionStatus(this.getHandlerContext(), args);
   }
 
+  /**
+   * Extend a live VRF-owned signing session without a WebAuthn prompt.
+   * Fails with SESSION_RENEWAL_WINDOW_CLOSED past `renewableUntilMs` and
+   * SESSION_POLICY_MISMATCH when `sessionPolicyDigest` (or the canonical digest of
+   * `sessionPolicyJson`) differs from the minted one.
+   */
+  async renewSession(args: {
+    sessionId: string;
+    ttlMs?: number;
+    sessionPolicyDigest?: string;
+    sessionPolicyJson?: string;
+  }): Promise<{
+    sessionId: string;
+    expiresAtMs: number;
+    remainingMs: number;
+    renewableUntilMs?: number;
+  }> {
+    return renewSession(this.getHandlerContext(), args);
+  }
+
   /**
    * Clear VRF-owned signing session material for a given `sessionId`.
    * Intended for explicit "Lock" actions or lifecycle cleanup.
@@ -347,6 +423,20 @@ export class VrfWorkerManager {
     return clearSession(this.getHandlerContext(), args);
   }
 
+  /**
+   * Monotonic VRF worker state version; poll it and call `getStateChangesSince` when it moves.
+   */
+  async getStateVersion(): Promise<number> {
+    return getStateVersion(this.getHandlerContext());
+  }
+
+  /**
+   * VRF worker state changes after `version` (unlock, logout, session lifecycle).
+   */
+  async getStateChangesSince(args: { version: number }): Promise<StateChangesSince> {
+    return getStateChangesSince(this.getHandlerContext(), args);
+  }
+
   /**
    * VRF-driven decrypt session for export flows.
    * Kicks off a LocalOnly DECRYPT_PRIVATE_KEY_WITH_PRF confirm via VRF Rust and derives
@@ -556,6 +646,8 @@ export class VrfWorkerManager {
       this.vrfWorker.onerror = (error) => {
         console.error('VRF Manager: Web Worker error:', error);
       };
+      // Progress envelopes carry no request id; fan them out to onVrfProgress listeners
+      this.vrfWorker.addEventListener('message', (event) => this.dispatchVrfProgress(event));
       // Test communication with the Web Worker
       await this.testWebWorkerCommunication();
 
@@ -653,6 +745,7 @@ export class VrfWorkerManager {
       }, timeoutMs);
 
       worker.addEventListener('message', handleMessage);
+      this.requestsPosted++;
       worker.postMessage(message);
     });
   }
@@ -685,6 +778,10 @@ export class VrfWorkerManager {
     return generateVrfChallengeOnce(this.getHandlerContext(), inputData);
   }
 
+  async generateVrfChallenges(args: WasmGenerateVrfChallengesRequest): Promise<VRFChallengeBatch> {
+    return generateVrfChallenges(this.getHandlerContext(), args);
+  }
+
   /**
    * Get current VRF session status
    */
@@ -693,10 +790,30 @@ export class VrfWorkerManager {
   }
 
   /**
-   * Logout and clear VRF session
+   * Non-secret VRF manager state (session/keypair flags, bound account, Shamir/relay config)
+   */
+  async getVrfDiagnostics(): Promise<VRFWorkerDiagnostics> {
+    return getVrfDiagnostics(this.getHandlerContext());
+  }
+
+  /**
+   * Logout and clear VRF session. Resolves with the completed logout epoch (null on failure);
+   * pass `logoutEpoch` to match the signer worker's CLEAR_ALL_SESSIONS.
    */
-  async clearVrfSession(): Promise<void> {
-    return clearVrfSession(this.getHandlerContext());
+  async clearVrfSession(args?: { logoutEpoch?: number }): Promise<number | null> {
+    return clearVrfSession(this.getHandlerContext(), args);
+  }
+
+  /**
+   * Full sign-out: clear every VRF session and unlocked keypair, reporting what was wiped.
+   */
+  async logoutAll(): Promise<{
+    clearedSessions: number;
+    clearedChallenges: number;
+    clearedActiveKeypair: boolean;
+    clearedUnlockedKeypairs: number;
+  }> {
+    return logoutAll(this.getHandlerContext());
   }
 
   /**
@@ -753,6 +870,30 @@ export class VrfWorkerManager {
     return deriveVrfKeypairFromPrf(this.getHandlerContext(), args);
   }
 
+  /**
+   * Device2 linking: derive the deterministic VRF keypair, encrypt it, and deliver
+   * WrapKeySeed + PRF.second to the signer session in one request.
+   * Replaces deriveVrfKeypairFromPrf + mintSessionKeysAndSendToSigner for this flow.
+   *
+   * @param sessionId - Signer session created via createSigningSessionChannel
+   * @returns VRF key material for storage
+   */
+  async device2CompleteRegistration(args: {
+    sessionId: string;
+    credential: WebAuthnRegistrationCredential;
+    nearAccountId: AccountId;
+    vrfInputData?: VRFInputData;
+    wrapKeySalt?: string;
+  }): Promise<{
+    vrfPublicKey: string;
+    vrfChallenge: VRFChallenge | null;
+    encryptedVrfKeypair: EncryptedVRFKeypair;
+    serverEncryptedVrfKeypair: ServerEncryptedVrfKeypair | null;
+    wrapKeySalt: string;
+  }> {
+    return device2CompleteRegistration(this.getHandlerContext(), args);
+  }
+
   /**
    * This securely decrypts the shamir3Pass encrypted VRF keypair and loads it into memory
    * It performs Shamir-3-Pass commutative decryption within WASM worker with the relay-server
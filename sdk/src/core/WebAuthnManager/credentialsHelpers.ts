@@ -56,6 +56,7 @@ export function serializeAuthenticationCredential(
   credential: PublicKeyCredential,
 ): WebAuthnAuthenticationCredential {
   const response = credential.response as AuthenticatorAssertionResponse;
+  const appid = readAppidExtension(credential);
 
   return {
     id: credential.id,
@@ -74,11 +75,22 @@ export function serializeAuthenticationCredential(
           first: undefined,
           second: undefined
         }
-      }
+      },
+      ...(appid !== undefined ? { appid } : {}),
     },
   };
 }
 
+/** Legacy U2F `appid` extension output, when the browser reports one. */
+function readAppidExtension(credential: PublicKeyCredential): boolean | undefined {
+  try {
+    const appid = credential.getClientExtensionResults?.()?.appid;
+    return typeof appid === 'boolean' ? appid : undefined;
+  } catch {
+    return undefined;
+  }
+}
+
 /**
  * Serialize PublicKeyCredential for both authentication and registration for WASM worker
  * @returns SerializableCredential - The serialized credential
@@ -130,6 +142,7 @@ export function serializeAuthenticationCredentialWithPRF({
   return {
     ...base,
     clientExtensionResults: {
+      ...base.clientExtensionResults,
       prf: {
         results: {
           first: chacha20PrfOutput,
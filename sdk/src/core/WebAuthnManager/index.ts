@@ -35,6 +35,7 @@ import {
   type RpcCallPayload,
   type SignerMode,
   type ThresholdBehavior,
+  type WasmCoseExtractionItem,
   type WasmSignedDelegate,
 } from '../types/signer-worker';
 import { WebAuthnRegistrationCredential, WebAuthnAuthenticationCredential } from '../types';
@@ -48,6 +49,7 @@ import { ensureEd25519Prefix } from '../../utils/validation';
 import { enrollThresholdEd25519KeyHandler } from './threshold/enrollThresholdEd25519Key';
 import { rotateThresholdEd25519KeyPostRegistrationHandler } from './threshold/rotateThresholdEd25519KeyPostRegistration';
 import { collectAuthenticationCredentialForVrfChallenge as collectAuthenticationCredentialForVrfChallengeImpl } from './collectAuthenticationCredentialForVrfChallenge';
+import { localDecryptionPayload } from './SignerWorkerManager/handlers/decryption';
 
 type SigningSessionOptions = {
   /** PRF-bearing credential; VRF worker extracts PRF outputs internally */
@@ -112,6 +114,8 @@ export class WebAuthnManager {
         rpIdOverride: this.touchIdPrompt.getRpId(),
         nearExplorerUrl: tatchiPasskeyConfigs.nearExplorerUrl,
         getTheme: () => this.theme,
+        // Fail a signer request still waiting on WrapKeySeed as cancelled, not as a timeout.
+        onUserCancelled: (sessionId) => this.signerWorkerManager?.cancelOperation(sessionId),
       }
     );
     this.signerWorkerManager = new SignerWorkerManager(
@@ -264,7 +268,10 @@ export class WebAuthnManager {
     handler: (sessionId: string) => Promise<T>;
   }): Promise<T> {
     const signerPort = await this.vrfWorkerManager.createSigningSessionChannel(args.sessionId);
-    await this.signerWorkerManager.reserveSignerWorkerSession(args.sessionId, { signerPort });
+    const { sessionBindingNonce } = await this.signerWorkerManager.reserveSignerWorkerSession(
+      args.sessionId,
+      { signerPort, bindSession: !!args.options },
+    );
     try {
       // If PRF is provided, derive WrapKeySeed in VRF worker and deliver it
       // (along with PRF.second if credential is provided) to the signer worker
@@ -273,6 +280,7 @@ export class WebAuthnManager {
         await this.vrfWorkerManager.mintSessionKeysAndSendToSigner({
           sessionId: args.sessionId,
           wrapKeySalt: args.options.wrapKeySalt,
+          sessionBindingNonce,
           credential: args.options.credential,
         });
       }
@@ -508,6 +516,68 @@ export class WebAuthnManager {
     });
   }
 
+  /**
+   * Derive and store Device2's VRF and NEAR keys from one registration credential, before the
+   * key swap. DEVICE2_COMPLETE_REGISTRATION derives the VRF keypair and delivers WrapKeySeed to
+   * the signer session; REGISTER_DEVICE2_WITH_DERIVED_KEY then derives and stores the NEAR key
+   * without signing (the registration transaction is signed after the swap).
+   */
+  async completeDevice2Registration(args: {
+    nearAccountId: AccountId;
+    credential: WebAuthnRegistrationCredential;
+    deviceNumber: number;
+  }): Promise<{
+    vrfPublicKey: string;
+    encryptedVrfKeypair: EncryptedVRFKeypair;
+    serverEncryptedVrfKeypair: ServerEncryptedVrfKeypair | null;
+    nearPublicKey: string;
+  }> {
+    const { nearAccountId, credential, deviceNumber } = args;
+    const sessionId = this.generateSessionId('device2-link');
+    const signerPort = await this.vrfWorkerManager.createSigningSessionChannel(sessionId);
+    await this.signerWorkerManager.reserveSignerWorkerSession(
+      sessionId,
+      { signerPort, bindSession: false },
+    );
+    try {
+      const vrf = await this.vrfWorkerManager.device2CompleteRegistration({
+        sessionId,
+        credential,
+        nearAccountId,
+      });
+      const near = await this.signerWorkerManager.registerDevice2WithDerivedKey({
+        sessionId,
+        nearAccountId,
+        credential,
+        contractId: this.tatchiPasskeyConfigs.contractId,
+        wrapKeySalt: vrf.wrapKeySalt,
+        deviceNumber,
+        signRegistrationTransaction: false,
+      });
+      if (!near.success || !near.publicKey) {
+        throw new Error(near.error || 'Failed to derive Device2 NEAR keypair');
+      }
+      return {
+        vrfPublicKey: vrf.vrfPublicKey,
+        encryptedVrfKeypair: vrf.encryptedVrfKeypair,
+        serverEncryptedVrfKeypair: vrf.serverEncryptedVrfKeypair,
+        nearPublicKey: near.publicKey,
+      };
+    } finally {
+      this.signerWorkerManager.releaseSigningSession(sessionId);
+    }
+  }
+
+  /**
+   * Number of requests posted to each worker so far; diff two snapshots to count a flow's messages.
+   */
+  getWorkerRequestCounts(): { vrf: number; signer: number } {
+    return {
+      vrf: this.vrfWorkerManager.getRequestsPosted(),
+      signer: this.signerWorkerManager.getRequestsPosted(),
+    };
+  }
+
   /**
    * **Sign Device2 registration transaction with already-stored key (no prompt)**
    *
@@ -562,7 +632,10 @@ export class WebAuthnManager {
 
       // === STEP 1: Create MessagePort session for WrapKeySeed delivery ===
       const signerPort = await this.vrfWorkerManager.createSigningSessionChannel(sessionId);
-      await this.signerWorkerManager.reserveSignerWorkerSession(sessionId, { signerPort });
+      const { sessionBindingNonce } = await this.signerWorkerManager.reserveSignerWorkerSession(
+        sessionId,
+        { signerPort, bindSession: true },
+      );
 
       // === STEP 2: VRF worker re-derives WrapKeySeed and sends to signer ===
       // This extracts PRF.second from the credential and delivers both WrapKeySeed + PRF.second
@@ -570,6 +643,7 @@ export class WebAuthnManager {
       await this.vrfWorkerManager.mintSessionKeysAndSendToSigner({
         sessionId,
         wrapKeySalt,
+        sessionBindingNonce,
         credential, // VRF will extract PRF.second from this
       });
 
@@ -771,7 +845,7 @@ export class WebAuthnManager {
     if (typeof window !== 'undefined' && this.workerBaseOrigin !== window.location.origin) {
       return;
     }
-    return await this.vrfWorkerManager.clearVrfSession();
+    await this.vrfWorkerManager.clearVrfSession();
   }
 
   /**
@@ -1288,10 +1362,7 @@ export class WebAuthnManager {
             type: INTERNAL_WORKER_REQUEST_TYPE_SIGN_ADD_KEY_THRESHOLD_PUBLIC_KEY_NO_PROMPT,
             payload: {
               createdAt: Date.now(),
-              decryption: {
-                encryptedPrivateKeyData: localKeyMaterial.encryptedSk,
-                encryptedPrivateKeyChacha20NonceB64u: localKeyMaterial.chacha20NonceB64u,
-              },
+              decryption: localDecryptionPayload(localKeyMaterial),
               transactionContext: args.transactionContext,
               nearAccountId,
               thresholdPublicKey,
@@ -1440,6 +1511,13 @@ export class WebAuthnManager {
     return await this.signerWorkerManager.extractCosePublicKey(attestationObjectBase64url);
   }
 
+  /**
+   * Extract COSE public keys from several attestation objects in one WASM worker round trip
+   */
+  async extractCosePublicKeys(attestationObjectsB64u: string[]): Promise<WasmCoseExtractionItem[]> {
+    return await this.signerWorkerManager.extractCosePublicKeys(attestationObjectsB64u);
+  }
+
   ///////////////////////////////////////
   // PRIVATE KEY EXPORT (Drawer/Modal in sandboxed iframe)
   ///////////////////////////////////////
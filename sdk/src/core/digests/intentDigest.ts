@@ -21,6 +21,22 @@ export async function computeUiIntentDigestFromNep413(args: {
   return sha256Base64UrlUtf8(json);
 }
 
+/**
+ * Byte length and hex SHA-256 of a NEP-413 message. Confirmation UIs show these rather than
+ * truncated message text; the signer worker returns the same values for what it signed.
+ */
+export async function computeNep413MessageSummary(message: string): Promise<{
+  messageByteLength: number;
+  messageSha256Hex: string;
+}> {
+  const bytes = new TextEncoder().encode(message);
+  const digest = new Uint8Array(await crypto.subtle.digest('SHA-256', bytes));
+  return {
+    messageByteLength: bytes.length,
+    messageSha256Hex: Array.from(digest, (b) => b.toString(16).padStart(2, '0')).join(''),
+  };
+}
+
 export async function computeThresholdEd25519KeygenIntentDigest(args: {
   nearAccountId: string;
   rpId: string;
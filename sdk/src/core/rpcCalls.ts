@@ -914,6 +914,12 @@ export async function checkCanRegisterUserContractCall({
     if (session_policy_digest_32.length !== 0 && session_policy_digest_32.length !== 32) {
       throw new Error('Invalid vrfChallenge.sessionPolicyDigest32 (expected base64url-encoded 32 bytes)');
     }
+    const extra_context_digest_32 = vrfChallenge.extraContextDigest32
+      ? Array.from(base64UrlDecode(vrfChallenge.extraContextDigest32))
+      : [];
+    if (extra_context_digest_32.length !== 0 && extra_context_digest_32.length !== 32) {
+      throw new Error('Invalid vrfChallenge.extraContextDigest32 (expected base64url-encoded 32 bytes)');
+    }
     const vrfData = {
       vrf_input_data: Array.from(base64UrlDecode(vrfChallenge.vrfInput)),
       vrf_output: Array.from(base64UrlDecode(vrfChallenge.vrfOutput)),
@@ -925,6 +931,7 @@ export async function checkCanRegisterUserContractCall({
       block_hash: Array.from(base64UrlDecode(vrfChallenge.blockHash)),
       intent_digest_32,
       ...(session_policy_digest_32.length ? { session_policy_digest_32 } : {}),
+      ...(extra_context_digest_32.length ? { extra_context_digest_32 } : {}),
     };
 
     const args = {
@@ -956,6 +963,32 @@ export async function checkCanRegisterUserContractCall({
   }
 }
 
+/**
+ * Whether `credentialId` is already registered on-chain for `accountId`.
+ *
+ * Used before adding another passkey on the same device: a second credential that derives a
+ * different NEAR key still needs its own AddKey. Unlike `getCredentialIdsContractCall`, RPC
+ * failures are thrown rather than read as "not registered".
+ */
+export async function checkCredentialRegisteredContractCall({
+  nearClient,
+  contractId,
+  accountId,
+  credentialId,
+}: {
+  nearClient: NearClient;
+  contractId: string;
+  accountId: AccountId;
+  credentialId: string;
+}): Promise<boolean> {
+  const credentialIds = await nearClient.callFunction<{ account_id: AccountId }, string[]>(
+    contractId,
+    'get_credential_ids_by_account',
+    { account_id: accountId }
+  );
+  return Array.isArray(credentialIds) && credentialIds.includes(credentialId);
+}
+
 
 /**
  * Verify authentication response through relay server
@@ -990,6 +1023,10 @@ export async function verifyAuthenticationResponse(
     if (session_policy_digest_32.length !== 0 && session_policy_digest_32.length !== 32) {
       throw new Error('Invalid vrfChallenge.sessionPolicyDigest32 (expected base64url-encoded 32 bytes)');
     }
+    const extra_context_digest_32 = toBytes(vrfChallenge.extraContextDigest32);
+    if (extra_context_digest_32.length !== 0 && extra_context_digest_32.length !== 32) {
+      throw new Error('Invalid vrfChallenge.extraContextDigest32 (expected base64url-encoded 32 bytes)');
+    }
     const vrf_data = {
       vrf_input_data: toBytes(vrfChallenge.vrfInput),
       vrf_output: toBytes(vrfChallenge.vrfOutput),
@@ -1001,6 +1038,7 @@ export async function verifyAuthenticationResponse(
       block_hash: toBytes(vrfChallenge.blockHash),
       intent_digest_32,
       ...(session_policy_digest_32.length ? { session_policy_digest_32 } : {}),
+      ...(extra_context_digest_32.length ? { extra_context_digest_32 } : {}),
     };
 
     // Normalize authenticatorAttachment and userHandle to null for server schema
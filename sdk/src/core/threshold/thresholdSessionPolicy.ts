@@ -1,5 +1,3 @@
-import { alphabetizeStringify, sha256BytesUtf8 } from '@/utils/digests';
-import { base64UrlEncode } from '@/utils/encoders';
 import { normalizeThresholdEd25519ParticipantIds } from '../../threshold/participants';
 
 export const THRESHOLD_SESSION_POLICY_VERSION = 'threshold_session_v1' as const;
@@ -45,13 +43,15 @@ export function generateThresholdSessionId(): string {
   return `tsess-${id}`;
 }
 
-export async function computeThresholdSessionPolicyDigest32(policy: ThresholdEd25519SessionPolicy): Promise<string> {
-  const json = alphabetizeStringify(policy);
-  const bytes = await sha256BytesUtf8(json);
-  return base64UrlEncode(bytes);
-}
-
-export async function buildThresholdSessionPolicy(params: {
+/**
+ * Build a threshold session policy and its JSON.
+ *
+ * `sessionPolicyDigest32` is not computed here: pass `policyJson` to the VRF worker as
+ * `sessionPolicyJson` and it binds the canonical digest into the VRF challenge
+ * (read it back from `vrfChallenge.sessionPolicyDigest32`). The relayer hashes the same
+ * canonical form via the VRF wasm.
+ */
+export function buildThresholdSessionPolicy(params: {
   nearAccountId: string;
   rpId: string;
   relayerKeyId: string;
@@ -59,11 +59,10 @@ export async function buildThresholdSessionPolicy(params: {
   sessionId?: string;
   ttlMs?: number;
   remainingUses?: number;
-}): Promise<{
+}): {
   policy: ThresholdEd25519SessionPolicy;
   policyJson: string;
-  sessionPolicyDigest32: string;
-}> {
+} {
   const sessionId = params.sessionId || generateThresholdSessionId();
   const { ttlMs, remainingUses } = clampThresholdSessionPolicy({
     ttlMs: params.ttlMs ?? DEFAULT_THRESHOLD_SESSION_POLICY.ttlMs,
@@ -86,8 +85,7 @@ export async function buildThresholdSessionPolicy(params: {
     ttlMs,
     remainingUses,
   };
-  const sessionPolicyDigest32 = await computeThresholdSessionPolicyDigest32(policy);
-  return { policy, policyJson: JSON.stringify(policy), sessionPolicyDigest32 };
+  return { policy, policyJson: JSON.stringify(policy) };
 }
 
 export function isThresholdSessionAuthUnavailableError(err: unknown): boolean {
@@ -30,6 +30,36 @@ export enum ActionType {
   UseGlobalContract = "UseGlobalContract",
 }
 
+export type ActionRisk = 'Low' | 'Medium' | 'High';
+
+/**
+ * Risk annotation for confirmation summaries; mirrors `ActionParams::risk` in the signer worker.
+ * Anything that replaces the account's code, keys or existence is `High`.
+ */
+export function getActionRisk(type: ActionType): ActionRisk {
+  switch (type) {
+    case ActionType.CreateAccount:
+    case ActionType.Transfer:
+      return 'Low';
+    case ActionType.FunctionCall:
+    case ActionType.Stake:
+    case ActionType.DeleteKey:
+    case ActionType.SignedDelegate:
+      return 'Medium';
+    case ActionType.DeployContract:
+    case ActionType.DeployGlobalContract:
+    case ActionType.UseGlobalContract:
+    case ActionType.AddKey:
+    case ActionType.DeleteAccount:
+    default:
+      return 'High';
+  }
+}
+
+export function isKnownActionType(type: unknown): type is ActionType {
+  return Object.values(ActionType).includes(type as ActionType);
+}
+
 export enum TxExecutionStatus {
   NONE = 'NONE',
   INCLUDED = 'INCLUDED',
@@ -150,7 +180,12 @@ export type ActionArgs =
 // snake_case for wasm
 export type ActionArgsWasm =
   | { action_type: ActionType.CreateAccount }
-  | { action_type: ActionType.DeployContract; code: number[] }
+  | {
+    action_type: ActionType.DeployContract;
+    code: number[];
+    /** Index into the message's `binaryAttachments`; `code` is then left empty */
+    codeAttachment?: number;
+  }
   | {
     action_type: ActionType.FunctionCall;
     method_name: string;
@@ -303,6 +338,9 @@ export function validateActionArgsWasm(actionArgsWasm: ActionArgsWasm): void {
       // No additional validation needed
       break;
     case ActionType.DeployContract:
+      if (actionArgsWasm.codeAttachment !== undefined) {
+        break;
+      }
       if (!actionArgsWasm.code || actionArgsWasm.code.length === 0) {
         throw new Error('code required for DeployContract');
       }
@@ -511,4 +549,26 @@ export function fromTransactionInputWasm(tx: TransactionInputWasm): TransactionI
 export function fromTransactionInputsWasm(txs: TransactionInputWasm[]): TransactionInput[] {
   return (txs || []).map(fromTransactionInputWasm);
 }
+
+/**
+ * Move DeployContract code out of the JSON payload into transferable byte buffers.
+ * Each moved action references its buffer via `codeAttachment`, so the signer worker
+ * receives the contract once as a Uint8Array instead of a JSON number array.
+ */
+export function detachDeployContractCode<T extends { actions: ActionArgsWasm[] }>(
+  txs: T[],
+): { txs: T[]; binaryAttachments: Uint8Array[] } {
+  const binaryAttachments: Uint8Array[] = [];
+  const detached = txs.map(tx => ({
+    ...tx,
+    actions: tx.actions.map(action => {
+      if (action.action_type !== ActionType.DeployContract || !action.code?.length) {
+        return action;
+      }
+      binaryAttachments.push(Uint8Array.from(action.code));
+      return { ...action, code: [], codeAttachment: binaryAttachments.length - 1 };
+    }),
+  }));
+  return { txs: detached, binaryAttachments };
+}
 import { isObject } from '@/utils/validation';
@@ -443,6 +443,8 @@ export interface DeviceLinkingEventStep6 extends BaseDeviceLinkingSSEEvent {
 export interface DeviceLinkingEventStep7 extends BaseDeviceLinkingSSEEvent {
   step: 7;
   phase: DeviceLinkingPhase.STEP_7_LINKING_COMPLETE;
+  /** Requests posted to the VRF and signer workers during registration (steps 6-7). */
+  workerRequests?: { vrf: number; signer: number };
 }
 
 export interface DeviceLinkingEventStep8 extends BaseDeviceLinkingSSEEvent {
@@ -9,8 +9,8 @@ export { WorkerRequestType, WorkerResponseType }; // Export the WASM enums direc
 import { StripFree } from "./index.js";
 import type { onProgressEvents } from "./sdkSentEvents.js";
 import type { TransactionContext } from './rpc.js';
-import type { VRFChallenge } from './vrf-worker.js';
-import type { ActionArgsWasm } from './actions.js';
+import type { StateChangesSince, VRFChallenge, WasmGetStateChangesSinceRequest } from './vrf-worker.js';
+import type { ActionArgsWasm, ActionType } from './actions.js';
 
 export type WasmTransaction = wasmModule.WasmTransaction;
 export type WasmSignature = wasmModule.WasmSignature;
@@ -156,14 +156,62 @@ export interface ThresholdSignerConfig {
    * When present, the signer worker uses it to authenticate `/threshold-ed25519/authorize` requests.
    */
   thresholdSessionJwt?: string;
+  /**
+   * Treat cached relayer sessions as expired this many ms before their skew-corrected expiry.
+   * Defaults to 30000.
+   */
+  thresholdSessionRefreshMarginMs?: number;
+  /**
+   * Clock-skew tolerance subtracted from a cached relayer session's expiry, on top of the
+   * measured skew bound. Defaults to 5000.
+   */
+  thresholdSessionMaxClockSkewMs?: number;
+  /**
+   * Discard any cached relayer session (and ignore `thresholdSessionJwt`) and mint/authorize a
+   * fresh one, e.g. after a relayer key rotation or policy change.
+   */
+  forceSessionRefresh?: boolean;
   /**
    * Preferred session token delivery mechanism for `/threshold-ed25519/session`.
    * - `jwt` (default): return token in JSON and use Authorization: Bearer on subsequent requests.
    * - `cookie`: set HttpOnly cookie (same-site only).
    */
   thresholdSessionKind?: 'jwt' | 'cookie';
+  /**
+   * Per-request authentication for relayers that require signed requests. With `hmac-sha256`
+   * every relayer request carries a timestamp header and
+   * `keyId=<keyId>,signature=<b64u HMAC-SHA256(secret, timestamp\nMETHOD\npath\nb64u(sha256(body)))>`.
+   * Defaults to `{ kind: 'none' }`.
+   */
+  requestAuth?: ThresholdRelayerRequestAuth;
+  /**
+   * Nonce source for threshold transactions.
+   * - `accessKey` (default): `nextNonce` from the transaction context, incremented per transaction.
+   * - `relayerReserved`: reserve a contiguous range from `/threshold-ed25519/nonce/reserve` per
+   *   batch, when the relayer advertises `nonceReservation`; otherwise falls back to `accessKey`.
+   */
+  nonceMode?: 'accessKey' | 'relayerReserved';
+  /** `nonceReservation` capability flag from the relayer's keygen response. */
+  relayerNonceReservation?: boolean;
+  /** Most relayer HTTP requests in flight at once, across all signing requests. Defaults to 4. */
+  maxConcurrentRelayerRequests?: number;
 }
 
+export type ThresholdRelayerRequestAuth =
+  | { kind: 'none' }
+  | {
+      kind: 'hmac-sha256';
+      /**
+       * Secret provisioned with `SignerWorkerManager.registerRelayerRequestAuthSecret`. The
+       * secret itself is never part of the config; a config carrying `secretB64u` is rejected.
+       */
+      keyId: string;
+      /** Defaults to `X-Relayer-Signature`. */
+      headerName?: string;
+      /** Defaults to `X-Relayer-Timestamp`. */
+      timestampHeaderName?: string;
+    };
+
 export interface TransactionPayload {
   nearAccountId: string;
   receiverId: string;
@@ -184,24 +232,108 @@ export type WasmDeriveNearKeypairAndEncryptRequest = StripFree<wasmModule.Derive
 export type WasmRecoverKeypairRequest = StripFree<wasmModule.RecoverKeypairRequest>;
 export type WasmDeriveThresholdEd25519ClientVerifyingShareRequest =
   StripFree<wasmModule.DeriveThresholdEd25519ClientVerifyingShareRequest>;
+export type WasmWarmThresholdEd25519ClientShareRequest =
+  StripFree<wasmModule.WarmThresholdEd25519ClientShareRequest>;
+/** Credential-scoped (version 3) NEAR key envelope sent to the signer worker. */
+export interface SignerCredentialKeyEnvelope {
+  credentialId: string;
+  encryptedPrivateKeyData: string;
+  encryptedPrivateKeyChacha20NonceB64u: string;
+  /** 3 (default) or 4, which authenticates the account id and `aadContext` as AAD. */
+  version?: number;
+  aadContext?: string;
+}
+
+export type SignerDecryptionPayload = StripFree<wasmModule.DecryptionPayload> & {
+  /** One envelope per passkey registered for the account on this device. */
+  credentialEnvelopes?: SignerCredentialKeyEnvelope[];
+};
+
 export interface WasmSignTransactionsWithActionsRequest {
   signerMode: SignerMode['mode'];
   rpcCall: RpcCallPayload;
   sessionId: string;
   createdAt?: number;
-  decryption: StripFree<wasmModule.DecryptionPayload>;
+  decryption: SignerDecryptionPayload;
   threshold?: ThresholdSignerConfig;
   txSigningRequests: TransactionPayload[];
   intentDigest?: string;
   transactionContext?: TransactionContext;
   vrfChallenge?: VRFChallenge;
   credential?: string;
+  /** Passkey whose key envelope to decrypt; defaults to the credential behind `credential`. */
+  credentialId?: string;
+  /** Opt-in: broadcast each signed transaction from the worker, in nonce order. */
+  broadcast?: SignerWorkerBroadcastConfig;
+  /** Which handler log lines are returned in `logs`. Defaults to `all`. */
+  logVerbosity?: SignerWorkerLogVerbosity;
+  /**
+   * Opt-in (local signer only): sign the session's contract verification so a relayer can skip
+   * its own `verify_authentication_response` call. Requires a session minted with contract gating.
+   */
+  produceVerificationAttestation?: boolean;
+  /**
+   * Opt-in: fail with `InsufficientBalance` when the batch's deposits, transfers and estimated
+   * gas exceed the signer's spendable balance (fetched from `rpcCall.nearRpcUrl`).
+   */
+  checkBalance?: boolean;
+  /** Opt-in: return the exact bytes each signature covers in `signaturePreimages`. */
+  includeSignaturePreimage?: boolean;
+}
+
+export type SignerWorkerLogVerbosity = 'none' | 'errors' | 'all';
+
+/**
+ * Signed statement that `verify_authentication_response` succeeded for this credential + VRF output.
+ * Verify with the wasm `verify_verification_attestation(attestation, maxAgeMs?)` export, then check
+ * `publicKey` is an access key of the account.
+ */
+export interface VerificationAttestation {
+  version: number;
+  vrfOutput: string;
+  credentialId: string;
+  contractId: string;
+  /** When the VRF worker's RPC verification succeeded (ms since epoch, whole ms). */
+  verifiedAtMs: number;
+  blockHeight?: number;
+  /** `ed25519:<base58>` */
+  publicKey: string;
+  /** Standard base64 Ed25519 signature */
+  signature: string;
+}
+
+/**
+ * Bytes covered by one signature, returned when `includeSignaturePreimage` is set. Check with the
+ * wasm `verify_signature_over_preimage(publicKey, signaturePreimageB64u, kind, signatureB64)`
+ * export, passing `'digest'` as `kind` when `preimageFormat` is `'digest'` (threshold signatures).
+ */
+export interface SignaturePreimage {
+  kind: 'transaction' | 'delegateAction' | 'nep413';
+  /** `message`: pre-hash bytes; `digest`: the 32-byte digest the threshold signers co-signed. */
+  preimageFormat: 'message' | 'digest';
+  signaturePreimageB64u: string;
+  /** sha256 fed to the signer; absent for `digest` preimages. */
+  signatureDigestB64u?: string;
+}
+
+export interface SignerWorkerBroadcastConfig {
+  rpcUrl: string;
+  /** Defaults to `none` (return once the node accepts the transaction). */
+  waitUntil?: 'none' | 'included' | 'executed';
+}
+
+export interface SignerWorkerBroadcastResult {
+  transactionHash: string;
+  finalStatus: 'submitted' | 'included' | 'executed' | 'failed' | 'broadcastError' | 'notBroadcast';
+  errorDetail?: string;
 }
 
 export interface WasmSignAddKeyThresholdPublicKeyNoPromptRequest {
   sessionId: string;
   createdAt?: number;
-  decryption: StripFree<wasmModule.DecryptionPayload>;
+  decryption: SignerDecryptionPayload;
+  /** Passkey whose key envelope to decrypt; defaults to the session's credential. */
+  credentialId?: string;
   nearAccountId: string;
   thresholdPublicKey: string;
   relayerVerifyingShareB64u: string;
@@ -215,13 +347,19 @@ export interface WasmSignDelegateActionRequest {
   rpcCall: RpcCallPayload;
   sessionId: string;
   createdAt?: number;
-  decryption: StripFree<wasmModule.DecryptionPayload>;
+  decryption: SignerDecryptionPayload;
   threshold?: ThresholdSignerConfig;
   delegate: DelegatePayload;
   intentDigest?: string;
   transactionContext?: TransactionContext;
   vrfChallenge?: VRFChallenge;
   credential?: string;
+  /** Passkey whose key envelope to decrypt; defaults to the credential behind `credential`. */
+  credentialId?: string;
+  /** Blocks above the current height `maxBlockHeight` may reach (worker default: 100000). */
+  maxBlockHeightWindow?: number;
+  /** Opt-in: return the exact bytes the signature covers in `signaturePreimage`. */
+  includeSignaturePreimage?: boolean;
 }
 export interface DelegatePayload {
   senderId: string;
@@ -231,21 +369,164 @@ export interface DelegatePayload {
   maxBlockHeight: string;
   publicKey: string;
 }
+export interface IntentConstraints {
+  /** Upper bound on the summed deposits of all steps, in yoctoNEAR. */
+  maxTotalDepositYocto: string;
+  /** Last block height at which the intent may still be signed. */
+  deadlineBlockHeight: string;
+}
+/** Sequential multi-contract operation confirmed once and signed as one unit. */
+export interface Intent {
+  intentId: string;
+  description: string;
+  steps: TransactionPayload[];
+  constraints: IntentConstraints;
+}
+export interface WasmSignIntentRequest {
+  signerMode: SignerMode['mode'];
+  rpcCall: RpcCallPayload;
+  sessionId: string;
+  createdAt?: number;
+  decryption: SignerDecryptionPayload;
+  intent: Intent;
+  /** Must equal `compute_intent_digest_sync(intent)`. */
+  intentDigest?: string;
+  transactionContext?: TransactionContext;
+  credential?: string;
+  /** Passkey whose key envelope to decrypt; defaults to the credential behind `credential`. */
+  credentialId?: string;
+  allowedMethods?: string[];
+  /** Action-kind allowlist (`ActionType` names, e.g. `UseGlobalContract`); empty = any kind. */
+  allowedActionKinds?: ActionType[];
+  /** Keep signing later steps after a step fails; a failed step does not consume a nonce. */
+  continueOnError?: boolean;
+}
+export interface WasmSignedIntentStep {
+  index: number;
+  intentId: string;
+  success: boolean;
+  transactionHash?: string;
+  signedTransaction?: WasmSignedTransaction;
+  error?: string;
+}
+export interface WasmSignIntentResult {
+  success: boolean;
+  intentId: string;
+  intentDigest: string;
+  steps: WasmSignedIntentStep[];
+  logs: string[];
+  error?: string;
+}
+/** Signed device-linking card shown in Device1's QR code (valid for 10 minutes). */
+export interface AccountCard {
+  nearAccountId: string;
+  nearPublicKey: string;
+  vrfPublicKeyRawB64u: string;
+  /** Device number assigned to the device that scans the card. */
+  deviceNumber: number;
+  issuedAtMs: number;
+  expiresAtMs: number;
+}
+export interface WasmCreateAccountCardRequest {
+  sessionId: string;
+  nearAccountId: string;
+  vrfPublicKeyRawB64u: string;
+  deviceNumber: number;
+  decryption: SignerDecryptionPayload;
+  credential?: string;
+  /** Passkey whose key envelope to decrypt; defaults to the credential behind `credential`. */
+  credentialId?: string;
+  expectedPublicKey?: string;
+}
+export interface WasmCreateAccountCardResult {
+  /** Compact base64url blob for QR embedding. */
+  cardB64u: string;
+  card: AccountCard;
+}
+export interface WasmVerifyAccountCardRequest {
+  cardB64u: string;
+}
+export interface WasmStateVersionResult {
+  stateVersion: number;
+}
+export interface WasmLogoutAllResult {
+  clearedSigningSessions: number;
+  /** Always 0 when the worker is built without threshold support. */
+  clearedThresholdAuthSessions: number;
+}
+export interface WasmClearAllSessionsRequest {
+  /** Epoch for this logout; send the same value to the VRF worker's CLEAR_VRF. */
+  logoutEpoch?: number;
+}
+export interface WasmClearAllSessionsResult extends WasmLogoutAllResult {
+  /** Logout epoch now current in the signer worker; compare with the VRF worker's. */
+  logoutEpoch: number;
+  clearedInFlightRequests: number;
+  /** WrapKeySeed / PRF.second waits failed with `LoggedOut`. */
+  rejectedWaiters: number;
+}
+export interface WasmSignBorshTransactionRequest {
+  sessionId: string;
+  /** Unsigned borsh `Transaction` (base64url); its `publicKey` must be the session's key. */
+  transactionBorshB64u: string;
+  decryption: SignerDecryptionPayload;
+  credential?: string;
+  /** Passkey whose key envelope to decrypt; defaults to the credential behind `credential`. */
+  credentialId?: string;
+}
+/** Stored key envelope plus the integrity metadata written at encryption time. */
+export interface WasmEncryptedKeyEnvelope {
+  encryptedPrivateKeyData: string;
+  encryptedPrivateKeyChacha20NonceB64u: string;
+  version?: number;
+  credentialId?: string;
+  /** Absent (with `integrityMacB64u`) on envelopes written before integrity tags. */
+  publicKey?: string;
+  integrityMacB64u?: string;
+}
+export interface WasmVerifyEncryptedKeyEnvelopeRequest {
+  sessionId: string;
+  nearAccountId: string;
+  envelope: WasmEncryptedKeyEnvelope;
+}
+export interface WasmVerifyEncryptedKeyEnvelopeResult {
+  intact: boolean;
+  publicKey?: string;
+  /** Legacy envelope without an integrity tag; `intact` carries no information. */
+  integrityUnavailable: boolean;
+}
+export interface WasmProvisionRelayerRequestAuthSecretRequest {
+  keyId: string;
+  /** Shared secret (base64url, at least 16 bytes). Kept in the worker; never returned. */
+  secretB64u: string;
+}
+export interface WasmProvisionRelayerRequestAuthSecretResult {
+  keyId: string;
+}
 export type WasmDecryptPrivateKeyRequest = StripFree<wasmModule.DecryptPrivateKeyRequest>;
 export type WasmExtractCosePublicKeyRequest = StripFree<wasmModule.ExtractCoseRequest>;
+export type WasmExtractCosePublicKeysRequest = StripFree<wasmModule.ExtractCosePublicKeysRequest>;
+export type WasmGetDeviceRecordsRequest = StripFree<wasmModule.GetDeviceRecordsRequest>;
+export type WasmMigrateLegacyEncryptedKeyRequest = StripFree<wasmModule.MigrateLegacyEncryptedKeyRequest>;
 export interface WasmSignNep413MessageRequest {
   signerMode: SignerMode['mode'];
   sessionId: string;
   accountId: string;
   nearPublicKey: string;
-  decryption: StripFree<wasmModule.DecryptionPayload>;
+  decryption: SignerDecryptionPayload;
   threshold?: ThresholdSignerConfig;
   message: string;
   recipient: string;
   nonce: string;
   state?: string;
+  /** Message size cap in UTF-8 bytes (worker default: 16 KiB). */
+  maxMessageBytes?: number;
   vrfChallenge?: VRFChallenge;
   credential?: string;
+  /** Passkey whose key envelope to decrypt; defaults to the credential behind `credential`. */
+  credentialId?: string;
+  /** Opt-in: return the exact bytes the signature covers in `signaturePreimage`. */
+  includeSignaturePreimage?: boolean;
 }
 export interface WasmSignTransactionWithKeyPairRequest {
   nearPrivateKey: string;
@@ -261,11 +542,23 @@ export type WasmRegisterDevice2WithDerivedKeyRequest = StripFree<wasmModule.Regi
 export type WasmRequestPayload = WasmDeriveNearKeypairAndEncryptRequest
   | WasmRecoverKeypairRequest
   | WasmDeriveThresholdEd25519ClientVerifyingShareRequest
+  | WasmWarmThresholdEd25519ClientShareRequest
   | WasmSignTransactionsWithActionsRequest
   | WasmSignAddKeyThresholdPublicKeyNoPromptRequest
   | WasmSignDelegateActionRequest
+  | WasmSignIntentRequest
+  | WasmCreateAccountCardRequest
+  | WasmVerifyAccountCardRequest
+  | WasmGetStateChangesSinceRequest
+  | WasmClearAllSessionsRequest
+  | WasmSignBorshTransactionRequest
+  | WasmVerifyEncryptedKeyEnvelopeRequest
+  | WasmProvisionRelayerRequestAuthSecretRequest
   | WasmDecryptPrivateKeyRequest
   | WasmExtractCosePublicKeyRequest
+  | WasmExtractCosePublicKeysRequest
+  | WasmGetDeviceRecordsRequest
+  | WasmMigrateLegacyEncryptedKeyRequest
   | WasmSignNep413MessageRequest
   | WasmSignTransactionWithKeyPairRequest
   | WasmRegisterDevice2WithDerivedKeyRequest;
@@ -275,9 +568,28 @@ export type WasmRecoverKeypairResult = InstanceType<typeof wasmModule.RecoverKey
 export type WasmSignedTransaction = InstanceType<typeof wasmModule.WasmSignedTransaction>;
 export type WasmSignedDelegate = wasmModule.WasmSignedDelegate;
 export type WasmDelegateAction = wasmModule.WasmDelegateAction;
-export type WasmTransactionSignResult = InstanceType<typeof wasmModule.TransactionSignResult>;
-export type WasmDelegateSignResult = wasmModule.DelegateSignResult;
+export type WasmTransactionSignResult = InstanceType<typeof wasmModule.TransactionSignResult> & {
+  /** Present only when the request set `broadcast`; one entry per signed transaction. */
+  broadcastResults?: SignerWorkerBroadcastResult[];
+  /** Present only when the request set `produceVerificationAttestation`. */
+  verificationAttestation?: VerificationAttestation;
+  /** Digest of the intent the user confirmed for this batch. */
+  intentDigest?: string;
+  /** True when `checkBalance` was requested but the balance could not be fetched. */
+  balanceUnchecked?: boolean;
+  /** Present only when the request set `includeSignaturePreimage`; one entry per signed transaction. */
+  signaturePreimages?: SignaturePreimage[];
+};
+export type WasmDelegateSignResult = wasmModule.DelegateSignResult & {
+  /** Present only when the request set `includeSignaturePreimage`. */
+  signaturePreimage?: SignaturePreimage;
+};
+export type WasmSignNep413Result = wasmModule.SignNep413Result & {
+  /** Present only when the request set `includeSignaturePreimage`. */
+  signaturePreimage?: SignaturePreimage;
+};
 export type WasmDecryptPrivateKeyResult = InstanceType<typeof wasmModule.DecryptPrivateKeyResult>;
+export type WasmMigrateLegacyEncryptedKeyResult = wasmModule.MigrateLegacyEncryptedKeyResult;
 export type WasmDeriveNearKeypairAndEncryptResult = InstanceType<typeof wasmModule.DeriveNearKeypairAndEncryptResult>;
 // wasm-bindgen generates some classes with private constructors, which breaks
 // `InstanceType<typeof Class>`. Use the class name directly for the instance type.
@@ -286,6 +598,29 @@ export type WasmRegisterDevice2WithDerivedKeyResult = InstanceType<typeof wasmMo
 // `InstanceType<typeof Class>`. Use the class name directly for the instance type.
 export type WasmDeriveThresholdEd25519ClientVerifyingShareResult =
   wasmModule.DeriveThresholdEd25519ClientVerifyingShareResult;
+export type WasmWarmThresholdEd25519ClientShareResult =
+  wasmModule.WarmThresholdEd25519ClientShareResult;
+/** Per-attestation outcome of `ExtractCosePublicKeys`; exactly one of `cosePublicKeyB64u` / `error` is set. */
+export interface WasmCoseExtractionItem {
+  index: number;
+  ok: boolean;
+  cosePublicKeyB64u?: string;
+  error?: string;
+}
+export interface WasmCoseExtractionBatchResult {
+  results: WasmCoseExtractionItem[];
+}
+/** A device (passkey + derived NEAR key) the signer worker has set up for an account. */
+export interface WasmDeviceRecord {
+  deviceNumber: number;
+  publicKey: string;
+  credentialId: string;
+  registeredAtMs: number;
+}
+export interface WasmGetDeviceRecordsResult {
+  nearAccountId: string;
+  records: WasmDeviceRecord[];
+}
 
 // === WORKER REQUEST TYPE MAPPING ===
 // Define the complete type mapping for each worker request
@@ -320,6 +655,56 @@ export interface WorkerRequestTypeMap {
     request: WasmSignDelegateActionRequest;
     result: WasmDelegateSignResult;
   };
+  [WorkerRequestType.SignIntent]: {
+    type: WorkerRequestType.SignIntent;
+    request: WasmSignIntentRequest;
+    result: WasmSignIntentResult;
+  };
+  [WorkerRequestType.CreateAccountCard]: {
+    type: WorkerRequestType.CreateAccountCard;
+    request: WasmCreateAccountCardRequest;
+    result: WasmCreateAccountCardResult;
+  };
+  [WorkerRequestType.VerifyAccountCard]: {
+    type: WorkerRequestType.VerifyAccountCard;
+    request: WasmVerifyAccountCardRequest;
+    result: AccountCard;
+  };
+  [WorkerRequestType.GetStateVersion]: {
+    type: WorkerRequestType.GetStateVersion;
+    request: Record<string, never>;
+    result: WasmStateVersionResult;
+  };
+  [WorkerRequestType.GetStateChangesSince]: {
+    type: WorkerRequestType.GetStateChangesSince;
+    request: WasmGetStateChangesSinceRequest;
+    result: StateChangesSince;
+  };
+  [WorkerRequestType.LogoutAll]: {
+    type: WorkerRequestType.LogoutAll;
+    request: Record<string, never>;
+    result: WasmLogoutAllResult;
+  };
+  [WorkerRequestType.ClearAllSessions]: {
+    type: WorkerRequestType.ClearAllSessions;
+    request: WasmClearAllSessionsRequest;
+    result: WasmClearAllSessionsResult;
+  };
+  [WorkerRequestType.SignBorshTransaction]: {
+    type: WorkerRequestType.SignBorshTransaction;
+    request: WasmSignBorshTransactionRequest;
+    result: WasmTransactionSignResult;
+  };
+  [WorkerRequestType.VerifyEncryptedKeyEnvelope]: {
+    type: WorkerRequestType.VerifyEncryptedKeyEnvelope;
+    request: WasmVerifyEncryptedKeyEnvelopeRequest;
+    result: WasmVerifyEncryptedKeyEnvelopeResult;
+  };
+  [WorkerRequestType.ProvisionRelayerRequestAuthSecret]: {
+    type: WorkerRequestType.ProvisionRelayerRequestAuthSecret;
+    request: WasmProvisionRelayerRequestAuthSecretRequest;
+    result: WasmProvisionRelayerRequestAuthSecretResult;
+  };
   [WorkerRequestType.DecryptPrivateKeyWithPrf]: {
     type: WorkerRequestType.DecryptPrivateKeyWithPrf;
     request: WasmDecryptPrivateKeyRequest;
@@ -338,13 +723,33 @@ export interface WorkerRequestTypeMap {
   [WorkerRequestType.SignNep413Message]: {
     type: WorkerRequestType.SignNep413Message;
     request: WasmSignNep413MessageRequest;
-    result: wasmModule.SignNep413Result;
+    result: WasmSignNep413Result;
   };
   [WorkerRequestType.RegisterDevice2WithDerivedKey]: {
     type: WorkerRequestType.RegisterDevice2WithDerivedKey;
     request: WasmRegisterDevice2WithDerivedKeyRequest;
     result: WasmRegisterDevice2WithDerivedKeyResult;
   };
+  [WorkerRequestType.MigrateLegacyEncryptedKey]: {
+    type: WorkerRequestType.MigrateLegacyEncryptedKey;
+    request: WasmMigrateLegacyEncryptedKeyRequest;
+    result: WasmMigrateLegacyEncryptedKeyResult;
+  };
+  [WorkerRequestType.WarmThresholdEd25519ClientShare]: {
+    type: WorkerRequestType.WarmThresholdEd25519ClientShare;
+    request: WasmWarmThresholdEd25519ClientShareRequest;
+    result: WasmWarmThresholdEd25519ClientShareResult;
+  };
+  [WorkerRequestType.ExtractCosePublicKeys]: {
+    type: WorkerRequestType.ExtractCosePublicKeys;
+    request: WasmExtractCosePublicKeysRequest;
+    result: WasmCoseExtractionBatchResult;
+  };
+  [WorkerRequestType.GetDeviceRecords]: {
+    type: WorkerRequestType.GetDeviceRecords;
+    request: WasmGetDeviceRecordsRequest;
+    result: WasmGetDeviceRecordsResult;
+  };
 }
 
 /**
@@ -372,6 +777,52 @@ export interface ConfirmationConfig {
   behavior: ConfirmationBehavior;
   /** Delay in milliseconds before proceeding without a click (only used with skipClick) */
   autoProceedDelay?: number;
+  /** Bind signing to this origin; credentials whose clientDataJSON origin differs are rejected */
+  expectedOrigin?: string;
+  /** Locale of `localizedStrings` (display-only; never part of the intent digest) */
+  locale?: string;
+  /**
+   * Pre-rendered summary strings keyed by summary field id (`receiver`, `amount`).
+   * Each must contain the field's raw value, otherwise the canonical English summary is shown.
+   */
+  localizedStrings?: Record<string, string>;
+  /**
+   * Let the signer worker decrypt the NEAR key once and reuse it for the signing operations of
+   * this session (off by default). Reuse also requires non-zero `keyReuseMaxOperations` and
+   * `keyReuseTtlMs`; the signer clamps both to its own limits.
+   */
+  allowKeyReuseWithinSession?: boolean;
+  /** Signing operations one decryption may serve, including the first */
+  keyReuseMaxOperations?: number;
+  /** Lifetime in milliseconds of the reused key, counted from its decryption */
+  keyReuseTtlMs?: number;
+  /** What happens once the budget or TTL is spent: decrypt again (default) or fail the operation */
+  keyReuseOnExhausted?: KeyReuseExhaustion;
+}
+
+export type KeyReuseExhaustion = 'redecrypt' | 'fail';
+
+/** `keyReusePolicy` forwarded by the VRF worker to the signer worker with the WrapKeySeed */
+export interface KeyReusePolicy {
+  allowKeyReuseWithinSession: boolean;
+  maxOperations: number;
+  ttlMs: number;
+  onExhausted: KeyReuseExhaustion;
+}
+
+export function keyReusePolicyFromConfirmationConfig(
+  config: Partial<ConfirmationConfig> | undefined,
+): KeyReusePolicy | undefined {
+  if (!config?.allowKeyReuseWithinSession) return undefined;
+  const maxOperations = Math.floor(Number(config.keyReuseMaxOperations));
+  const ttlMs = Math.floor(Number(config.keyReuseTtlMs));
+  if (!(maxOperations > 0) || !(ttlMs > 0)) return undefined;
+  return {
+    allowKeyReuseWithinSession: true,
+    maxOperations,
+    ttlMs,
+    onExhausted: config.keyReuseOnExhausted === 'fail' ? 'fail' : 'redecrypt',
+  };
 }
 
 export const DEFAULT_CONFIRMATION_CONFIG: ConfirmationConfig = {
@@ -447,6 +898,28 @@ export type WasmRequestResult = WasmRecoverKeypairResult
 export interface SignerWorkerMessage<T extends SignerWorkerRequestType, R extends WasmRequestPayload> {
   type: T;
   payload: R;
+  /**
+   * What to do when an identical request (same sessionId, type and intent/payload) is
+   * already in flight: 'share' (default) awaits its result, 'reject' fails fast with
+   * DuplicateRequestInFlight.
+   */
+  onDuplicate?: 'share' | 'reject';
+  /**
+   * Large byte payloads (e.g. contract code) referenced by index from `payload`;
+   * transferred to the worker rather than copied.
+   */
+  binaryAttachments?: Uint8Array[];
+  /**
+   * Absolute deadline (ms since the Unix epoch, e.g. `Date.now() + 30_000`). The worker fails
+   * the request with "request deadline exceeded" once it passes, checked before work starts
+   * and between batch items.
+   */
+  deadlineMsEpoch?: number;
+  /**
+   * Origin of the embedding page, set by `SignerWorkerManager`. Checked only once the worker's
+   * origin gate is initialized, and then required.
+   */
+  callerOrigin?: string;
 }
 
 /**
@@ -531,10 +1004,24 @@ export interface RequestResponseMap {
   [WorkerRequestType.SignTransactionsWithActions]: WasmTransactionSignResult;
   [INTERNAL_WORKER_REQUEST_TYPE_SIGN_ADD_KEY_THRESHOLD_PUBLIC_KEY_NO_PROMPT]: WasmTransactionSignResult;
   [WorkerRequestType.SignDelegateAction]: WasmDelegateSignResult;
+  [WorkerRequestType.SignIntent]: WasmSignIntentResult;
+  [WorkerRequestType.CreateAccountCard]: WasmCreateAccountCardResult;
+  [WorkerRequestType.VerifyAccountCard]: AccountCard;
+  [WorkerRequestType.GetStateVersion]: WasmStateVersionResult;
+  [WorkerRequestType.GetStateChangesSince]: StateChangesSince;
+  [WorkerRequestType.LogoutAll]: WasmLogoutAllResult;
+  [WorkerRequestType.ClearAllSessions]: WasmClearAllSessionsResult;
+  [WorkerRequestType.SignBorshTransaction]: WasmTransactionSignResult;
+  [WorkerRequestType.VerifyEncryptedKeyEnvelope]: WasmVerifyEncryptedKeyEnvelopeResult;
+  [WorkerRequestType.ProvisionRelayerRequestAuthSecret]: WasmProvisionRelayerRequestAuthSecretResult;
   [WorkerRequestType.ExtractCosePublicKey]: wasmModule.CoseExtractionResult;
   [WorkerRequestType.SignTransactionWithKeyPair]: WasmTransactionSignResult;
-  [WorkerRequestType.SignNep413Message]: wasmModule.SignNep413Result;
+  [WorkerRequestType.SignNep413Message]: WasmSignNep413Result;
   [WorkerRequestType.RegisterDevice2WithDerivedKey]: WasmRegisterDevice2WithDerivedKeyResult;
+  [WorkerRequestType.MigrateLegacyEncryptedKey]: WasmMigrateLegacyEncryptedKeyResult;
+  [WorkerRequestType.WarmThresholdEd25519ClientShare]: WasmWarmThresholdEd25519ClientShareResult;
+  [WorkerRequestType.ExtractCosePublicKeys]: WasmCoseExtractionBatchResult;
+  [WorkerRequestType.GetDeviceRecords]: WasmGetDeviceRecordsResult;
 }
 
 export type RequestTypeKey = keyof RequestResponseMap;
@@ -582,9 +1069,21 @@ export type EncryptionResponse = WorkerResponseForRequest<typeof WorkerRequestTy
 export type RecoveryResponse = WorkerResponseForRequest<typeof WorkerRequestType.RecoverKeypairFromPasskey>;
 export type TransactionResponse = WorkerResponseForRequest<typeof WorkerRequestType.SignTransactionsWithActions>;
 export type DelegateSignResponse = WorkerResponseForRequest<typeof WorkerRequestType.SignDelegateAction>;
+export type IntentSignResponse = WorkerResponseForRequest<typeof WorkerRequestType.SignIntent>;
+export type AccountCardCreateResponse = WorkerResponseForRequest<typeof WorkerRequestType.CreateAccountCard>;
+export type AccountCardVerifyResponse = WorkerResponseForRequest<typeof WorkerRequestType.VerifyAccountCard>;
+export type StateVersionResponse = WorkerResponseForRequest<typeof WorkerRequestType.GetStateVersion>;
+export type StateChangesSinceResponse = WorkerResponseForRequest<typeof WorkerRequestType.GetStateChangesSince>;
+export type LogoutAllResponse = WorkerResponseForRequest<typeof WorkerRequestType.LogoutAll>;
+export type ClearAllSessionsResponse = WorkerResponseForRequest<typeof WorkerRequestType.ClearAllSessions>;
+export type BorshTransactionSignResponse = WorkerResponseForRequest<typeof WorkerRequestType.SignBorshTransaction>;
+export type VerifyEncryptedKeyEnvelopeResponse = WorkerResponseForRequest<typeof WorkerRequestType.VerifyEncryptedKeyEnvelope>;
 export type DecryptionResponse = WorkerResponseForRequest<typeof WorkerRequestType.DecryptPrivateKeyWithPrf>;
 export type CoseExtractionResponse = WorkerResponseForRequest<typeof WorkerRequestType.ExtractCosePublicKey>;
+export type CoseBatchExtractionResponse = WorkerResponseForRequest<typeof WorkerRequestType.ExtractCosePublicKeys>;
+export type DeviceRecordsResponse = WorkerResponseForRequest<typeof WorkerRequestType.GetDeviceRecords>;
 export type Nep413SigningResponse = WorkerResponseForRequest<typeof WorkerRequestType.SignNep413Message>;
+export type LegacyKeyMigrationResponse = WorkerResponseForRequest<typeof WorkerRequestType.MigrateLegacyEncryptedKey>;
 
 // === TYPE GUARDS FOR GENERIC RESPONSES ===
 
@@ -608,11 +1107,25 @@ export function isWorkerSuccess<T extends RequestTypeKey>(
     response.type === WorkerResponseType.DecryptPrivateKeyWithPrfSuccess ||
     response.type === WorkerResponseType.SignTransactionsWithActionsSuccess ||
     response.type === WorkerResponseType.SignDelegateActionSuccess ||
+    response.type === WorkerResponseType.SignIntentSuccess ||
+    response.type === WorkerResponseType.CreateAccountCardSuccess ||
+    response.type === WorkerResponseType.VerifyAccountCardSuccess ||
+    response.type === WorkerResponseType.GetStateVersionSuccess ||
+    response.type === WorkerResponseType.GetStateChangesSinceSuccess ||
+    response.type === WorkerResponseType.LogoutAllSuccess ||
+    response.type === WorkerResponseType.ClearAllSessionsSuccess ||
+    response.type === WorkerResponseType.SignBorshTransactionSuccess ||
+    response.type === WorkerResponseType.VerifyEncryptedKeyEnvelopeSuccess ||
+    response.type === WorkerResponseType.ProvisionRelayerRequestAuthSecretSuccess ||
     response.type === WorkerResponseType.ExtractCosePublicKeySuccess ||
     response.type === WorkerResponseType.SignTransactionWithKeyPairSuccess ||
     response.type === WorkerResponseType.SignNep413MessageSuccess ||
     response.type === WorkerResponseType.RegisterDevice2WithDerivedKeySuccess ||
     response.type === WorkerResponseType.DeriveThresholdEd25519ClientVerifyingShareSuccess ||
+    response.type === WorkerResponseType.MigrateLegacyEncryptedKeySuccess ||
+    response.type === WorkerResponseType.WarmThresholdEd25519ClientShareSuccess ||
+    response.type === WorkerResponseType.ExtractCosePublicKeysSuccess ||
+    response.type === WorkerResponseType.GetDeviceRecordsSuccess ||
     response.type === INTERNAL_WORKER_RESPONSE_TYPE_SIGN_ADD_KEY_THRESHOLD_PUBLIC_KEY_NO_PROMPT_SUCCESS
   );
 }
@@ -626,11 +1139,25 @@ export function isWorkerError<T extends RequestTypeKey>(
     response.type === WorkerResponseType.DecryptPrivateKeyWithPrfFailure ||
     response.type === WorkerResponseType.SignTransactionsWithActionsFailure ||
     response.type === WorkerResponseType.SignDelegateActionFailure ||
+    response.type === WorkerResponseType.SignIntentFailure ||
+    response.type === WorkerResponseType.CreateAccountCardFailure ||
+    response.type === WorkerResponseType.VerifyAccountCardFailure ||
+    response.type === WorkerResponseType.GetStateVersionFailure ||
+    response.type === WorkerResponseType.GetStateChangesSinceFailure ||
+    response.type === WorkerResponseType.LogoutAllFailure ||
+    response.type === WorkerResponseType.ClearAllSessionsFailure ||
+    response.type === WorkerResponseType.SignBorshTransactionFailure ||
+    response.type === WorkerResponseType.VerifyEncryptedKeyEnvelopeFailure ||
+    response.type === WorkerResponseType.ProvisionRelayerRequestAuthSecretFailure ||
     response.type === WorkerResponseType.ExtractCosePublicKeyFailure ||
     response.type === WorkerResponseType.SignTransactionWithKeyPairFailure ||
     response.type === WorkerResponseType.SignNep413MessageFailure ||
     response.type === WorkerResponseType.RegisterDevice2WithDerivedKeyFailure ||
     response.type === WorkerResponseType.DeriveThresholdEd25519ClientVerifyingShareFailure ||
+    response.type === WorkerResponseType.MigrateLegacyEncryptedKeyFailure ||
+    response.type === WorkerResponseType.WarmThresholdEd25519ClientShareFailure ||
+    response.type === WorkerResponseType.ExtractCosePublicKeysFailure ||
+    response.type === WorkerResponseType.GetDeviceRecordsFailure ||
     response.type === INTERNAL_WORKER_RESPONSE_TYPE_SIGN_ADD_KEY_THRESHOLD_PUBLIC_KEY_NO_PROMPT_FAILURE
   );
 }
@@ -659,14 +1186,62 @@ export function isSignDelegateActionSuccess(response: DelegateSignResponse): res
   return response.type === WorkerResponseType.SignDelegateActionSuccess;
 }
 
+export function isSignIntentSuccess(response: IntentSignResponse): response is WorkerSuccessResponse<typeof WorkerRequestType.SignIntent> {
+  return response.type === WorkerResponseType.SignIntentSuccess;
+}
+
+export function isCreateAccountCardSuccess(response: AccountCardCreateResponse): response is WorkerSuccessResponse<typeof WorkerRequestType.CreateAccountCard> {
+  return response.type === WorkerResponseType.CreateAccountCardSuccess;
+}
+
+export function isVerifyAccountCardSuccess(response: AccountCardVerifyResponse): response is WorkerSuccessResponse<typeof WorkerRequestType.VerifyAccountCard> {
+  return response.type === WorkerResponseType.VerifyAccountCardSuccess;
+}
+
+export function isGetStateVersionSuccess(response: StateVersionResponse): response is WorkerSuccessResponse<typeof WorkerRequestType.GetStateVersion> {
+  return response.type === WorkerResponseType.GetStateVersionSuccess;
+}
+
+export function isGetStateChangesSinceSuccess(response: StateChangesSinceResponse): response is WorkerSuccessResponse<typeof WorkerRequestType.GetStateChangesSince> {
+  return response.type === WorkerResponseType.GetStateChangesSinceSuccess;
+}
+
+export function isLogoutAllSuccess(response: LogoutAllResponse): response is WorkerSuccessResponse<typeof WorkerRequestType.LogoutAll> {
+  return response.type === WorkerResponseType.LogoutAllSuccess;
+}
+
+export function isClearAllSessionsSuccess(response: ClearAllSessionsResponse): response is WorkerSuccessResponse<typeof WorkerRequestType.ClearAllSessions> {
+  return response.type === WorkerResponseType.ClearAllSessionsSuccess;
+}
+
+export function isSignBorshTransactionSuccess(response: BorshTransactionSignResponse): response is WorkerSuccessResponse<typeof WorkerRequestType.SignBorshTransaction> {
+  return response.type === WorkerResponseType.SignBorshTransactionSuccess;
+}
+
+export function isVerifyEncryptedKeyEnvelopeSuccess(response: VerifyEncryptedKeyEnvelopeResponse): response is WorkerSuccessResponse<typeof WorkerRequestType.VerifyEncryptedKeyEnvelope> {
+  return response.type === WorkerResponseType.VerifyEncryptedKeyEnvelopeSuccess;
+}
+
 export function isDecryptPrivateKeyWithPrfSuccess(response: DecryptionResponse): response is WorkerSuccessResponse<typeof WorkerRequestType.DecryptPrivateKeyWithPrf> {
   return response.type === WorkerResponseType.DecryptPrivateKeyWithPrfSuccess;
 }
 
+export function isMigrateLegacyEncryptedKeySuccess(response: LegacyKeyMigrationResponse): response is WorkerSuccessResponse<typeof WorkerRequestType.MigrateLegacyEncryptedKey> {
+  return response.type === WorkerResponseType.MigrateLegacyEncryptedKeySuccess;
+}
+
 export function isExtractCosePublicKeySuccess(response: CoseExtractionResponse): response is WorkerSuccessResponse<typeof WorkerRequestType.ExtractCosePublicKey> {
   return response.type === WorkerResponseType.ExtractCosePublicKeySuccess;
 }
 
+export function isExtractCosePublicKeysSuccess(response: CoseBatchExtractionResponse): response is WorkerSuccessResponse<typeof WorkerRequestType.ExtractCosePublicKeys> {
+  return response.type === WorkerResponseType.ExtractCosePublicKeysSuccess;
+}
+
+export function isGetDeviceRecordsSuccess(response: DeviceRecordsResponse): response is WorkerSuccessResponse<typeof WorkerRequestType.GetDeviceRecords> {
+  return response.type === WorkerResponseType.GetDeviceRecordsSuccess;
+}
+
 export function isSignNep413MessageSuccess(response: Nep413SigningResponse): response is WorkerSuccessResponse<typeof WorkerRequestType.SignNep413Message> {
   return response.type === WorkerResponseType.SignNep413MessageSuccess;
 }
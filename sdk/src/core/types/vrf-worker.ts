@@ -5,18 +5,31 @@ import * as wasmModule from '../../wasm_vrf_worker/pkg/wasm_vrf_worker.js';
 import { StripFree } from "./index.js";
 
 import { WebAuthnAuthenticationCredential, WebAuthnRegistrationCredential } from "./webauthn";
-import { ConfirmationConfig } from './signer-worker';
+import { AccountCard, ConfirmationConfig, KeyReusePolicy } from './signer-worker';
 import { AccountId } from "./accountIds.js";
 import { base64UrlDecode, base64UrlEncode } from "../../utils/encoders.js";
 import type { SecureConfirmRequest } from "../WebAuthnManager/VrfWorkerManager/confirmTxFlow/types";
 
 export type WasmGenerateVrfKeypairBootstrapRequest = StripFree<wasmModule.GenerateVrfKeypairBootstrapRequest>;
 export type WasmGenerateVrfChallengeRequest = StripFree<wasmModule.GenerateVrfChallengeRequest>;
+/** GENERATE_VRF_CHALLENGES payload: one challenge per digest, all on the same block context. */
+export interface WasmGenerateVrfChallengesRequest {
+  /** Session whose use budget the minted challenges are charged to. */
+  sessionId?: string;
+  base: Omit<VRFInputData, 'blockHeight' | 'blockHash' | 'intentDigest'> & {
+    blockHeight?: string;
+    blockHash?: string;
+  };
+  intentDigests: string[];
+  /** Lowers the worker's batch cap (20). */
+  maxCount?: number;
+}
 export type WasmUnlockVrfKeypairRequest = Omit<StripFree<wasmModule.UnlockVrfKeypairRequest>, 'prfKey'> & {
   // Prefer forwarding the full serialized WebAuthn credential so PRF outputs do not need
   // to be extracted into separate main-thread strings.
   credential: WebAuthnRegistrationCredential | WebAuthnAuthenticationCredential;
 };
+export type WasmUnlockVrfKeypairsRequest = StripFree<wasmModule.UnlockVrfKeypairsRequest>;
 export type WasmDeriveVrfKeypairFromPrfRequest = Omit<
   StripFree<wasmModule.DeriveVrfKeypairFromPrfRequest>,
   'credential' | 'prfOutput'
@@ -27,19 +40,62 @@ export type WasmDeriveVrfKeypairFromPrfRequest = Omit<
 export type WasmMintSessionKeysAndSendToSignerRequest =
   Omit<
     StripFree<wasmModule.MintSessionKeysAndSendToSignerRequest>,
-    'contractId' | 'nearRpcUrl' | 'ttlMs' | 'remainingUses'
+    | 'contractId' | 'nearRpcUrl' | 'ttlMs' | 'remainingUses' | 'maxLifetimeMs' | 'sessionPolicyDigest'
+    | 'sessionPolicyJson' | 'sessionBindingNonce' | 'intentDigest'
   > & {
     contractId?: string;
     nearRpcUrl?: string;
     // Optional signing-session config. When omitted, VRF worker uses defaults.
     ttlMs?: number;
     remainingUses?: number;
+    // Optional renewal bounds: total-lifetime ceiling and the policy RENEW_SESSION must match.
+    maxLifetimeMs?: number;
+    sessionPolicyDigest?: string;
+    // Policy JSON the worker hashes canonically instead of trusting a caller-computed digest.
+    sessionPolicyJson?: string;
+    // Opt-in reuse of one decrypted signing key within the session; forwarded to the signer worker.
+    keyReusePolicy?: KeyReusePolicy;
+    // Echoed to the signer worker with the WrapKeySeed (session binding).
+    sessionBindingNonce?: string;
+    intentDigest?: string;
     // Forward the WebAuthn credential so PRF outputs do not need to be extracted in main-thread JS.
     credential: WebAuthnRegistrationCredential | WebAuthnAuthenticationCredential;
   };
 export type WasmDispenseSessionKeyRequest = StripFree<wasmModule.DispenseSessionKeyRequest>;
 export type WasmCheckSessionStatusRequest = StripFree<wasmModule.CheckSessionStatusRequest>;
+export type WasmRenewSessionRequest = Omit<
+  StripFree<wasmModule.RenewSessionRequest>,
+  'ttlMs' | 'sessionPolicyDigest' | 'sessionPolicyJson'
+> & {
+  ttlMs?: number;
+  sessionPolicyDigest?: string;
+  sessionPolicyJson?: string;
+};
 export type WasmClearSessionRequest = StripFree<wasmModule.ClearSessionRequest>;
+export interface WasmGetStateChangesSinceRequest {
+  /** Last state version the caller has seen. */
+  version: number;
+}
+
+export interface WasmClearVrfRequest {
+  /** Epoch for this logout; send the same value to the signer worker's CLEAR_ALL_SESSIONS. */
+  logoutEpoch?: number;
+}
+
+/** One entry of a worker's state change log (VRF and signer workers share the shape and kinds). */
+export interface StateChangeEvent {
+  /** State version right after this change. */
+  version: number;
+  kind: wasmModule.StateChangeKind;
+  accountId?: string;
+  atMs: number;
+}
+/** GET_STATE_CHANGES_SINCE result. When `resyncRequired`, re-read full status instead of replaying `events`. */
+export interface StateChangesSince {
+  version: number;
+  events: StateChangeEvent[];
+  resyncRequired: boolean;
+}
 export type WasmConfirmAndPrepareSigningSessionRequest = {
   request: SecureConfirmRequest;
 };
@@ -49,8 +105,18 @@ export type WasmRegistrationCredentialConfirmationRequest = StripFree<wasmModule
 };
 export type WasmDevice2RegistrationSessionRequest = StripFree<wasmModule.Device2RegistrationSessionRequest> & {
   confirmationConfig?: ConfirmationConfig;
+  /** Device1 account card returned by the signer worker's VERIFY_ACCOUNT_CARD; cross-checked. */
+  accountCard?: AccountCard;
 };
-
+export type WasmDevice2CompleteRegistrationRequest = Omit<
+  StripFree<wasmModule.Device2CompleteRegistrationRequest>,
+  'vrfInputData' | 'wrapKeySalt'
+> & {
+  credential: WebAuthnRegistrationCredential;
+  vrfInputData?: VRFInputData;
+  wrapKeySalt?: string;
+};
+/** Worker message counts reported by DEVICE2_COMPLETE_REGISTRATION for telemetry. */
 export type WasmShamir3PassConfigPRequest = StripFree<wasmModule.Shamir3PassConfigPRequest>;
 export type WasmShamir3PassConfigServerUrlsRequest = StripFree<wasmModule.Shamir3PassConfigServerUrlsRequest>;
 export type WasmShamir3PassClientEncryptCurrentVrfKeypairRequest = StripFree<wasmModule.Shamir3PassClientEncryptCurrentVrfKeypairRequest>;
@@ -58,16 +124,22 @@ export type WasmShamir3PassClientDecryptVrfKeypairRequest = StripFree<wasmModule
 
 export type WasmVrfWorkerRequestType = WasmGenerateVrfKeypairBootstrapRequest
   | WasmGenerateVrfChallengeRequest
+  | WasmGenerateVrfChallengesRequest
   | WasmUnlockVrfKeypairRequest
+  | WasmUnlockVrfKeypairsRequest
   | WasmDeriveVrfKeypairFromPrfRequest
   | WasmMintSessionKeysAndSendToSignerRequest
   | WasmDispenseSessionKeyRequest
   | WasmCheckSessionStatusRequest
+  | WasmRenewSessionRequest
   | WasmClearSessionRequest
+  | WasmGetStateChangesSinceRequest
+  | WasmClearVrfRequest
   | WasmConfirmAndPrepareSigningSessionRequest
   | WasmDecryptSessionRequest
   | WasmRegistrationCredentialConfirmationRequest
   | WasmDevice2RegistrationSessionRequest
+  | WasmDevice2CompleteRegistrationRequest
   | WasmShamir3PassConfigPRequest
   | WasmShamir3PassConfigServerUrlsRequest
   | WasmShamir3PassClientEncryptCurrentVrfKeypairRequest
@@ -92,6 +164,11 @@ export interface VRFChallenge {
    * relayer session policy binding (v4+ only).
    */
   sessionPolicyDigest32?: string;
+  /**
+   * Optional base64url-encoded 32-byte app context that was bound into the VRF input hash
+   * (after its own domain separator).
+   */
+  extraContextDigest32?: string;
 }
 
 /**
@@ -120,6 +197,7 @@ export function validateVRFChallenge(vrfChallengeData: {
   blockHash: string;
   intentDigest?: string;
   sessionPolicyDigest32?: string;
+  extraContextDigest32?: string;
 }): VRFChallenge {
   if (!vrfChallengeData.vrfInput || typeof vrfChallengeData.vrfInput !== 'string') {
     throw new Error('vrfInput must be a non-empty string');
@@ -157,6 +235,7 @@ export function validateVRFChallenge(vrfChallengeData: {
     blockHash: vrfChallengeData.blockHash,
     ...(vrfChallengeData.intentDigest ? { intentDigest: vrfChallengeData.intentDigest } : {}),
     ...(vrfChallengeData.sessionPolicyDigest32 ? { sessionPolicyDigest32: vrfChallengeData.sessionPolicyDigest32 } : {}),
+    ...(vrfChallengeData.extraContextDigest32 ? { extraContextDigest32: vrfChallengeData.extraContextDigest32 } : {}),
   };
 }
 
@@ -201,6 +280,37 @@ export interface VRFWorkerStatus {
    * Used to detect device/passkey mismatches for multi-device accounts.
    */
   vrfPublicKey?: string | null;
+  /** Epoch of the last completed logout (CLEAR_VRF / LOGOUT_ALL); 0 before any. */
+  logoutEpoch?: number;
+}
+
+/** One GENERATE_VRF_CHALLENGES entry, in request order; a failed entry does not affect the others. */
+export interface VRFChallengeBatchItem {
+  intentDigest: string;
+  challenge?: VRFChallenge;
+  errorCode?: string;
+  error?: string;
+}
+
+export interface VRFChallengeBatch {
+  challenges: VRFChallengeBatchItem[];
+  /** Challenges minted (and, with a `sessionId`, charged against its use budget). */
+  minted: number;
+  /** The session's uses left after this batch; null without a session or use limit. */
+  remainingUses: number | null;
+}
+
+/** Non-secret VRF manager state (VRF_DIAGNOSTICS), for support tooling. */
+export interface VRFWorkerDiagnostics {
+  sessionActive: boolean;
+  hasKeypair: boolean;
+  /** Account the active keypair was unlocked for; null when locked or not yet bound. */
+  accountId: string | null;
+  /** A Shamir prime other than the built-in default is configured. */
+  shamirConfigured: boolean;
+  /** Relay server URL and both server-lock routes are set. */
+  relayUrlsSet: boolean;
+  primeBits: number;
 }
 
 export interface EncryptedVRFKeypair {
@@ -208,6 +318,14 @@ export interface EncryptedVRFKeypair {
   chacha20NonceB64u: string;
 }
 
+/**
+ * RP-ID normalization policy shared by the VRF input and threshold relayer share derivation.
+ * - `lowercaseAscii` (default): lowercase ASCII letters, leave other characters untouched
+ * - `rejectNonAscii`: lowercase ASCII letters, reject non-ASCII rpIds
+ * - `punycodeEncode`: lowercase each label, Punycode-encode non-ASCII labels (`xn--…`)
+ */
+export type RpIdNormalization = 'lowercaseAscii' | 'rejectNonAscii' | 'punycodeEncode';
+
 export interface VRFInputData {
   userId: string;
   rpId: string;
@@ -222,25 +340,48 @@ export interface VRFInputData {
    * Optional base64url-encoded 32-byte digest to bind a relayer session policy into the VRF input hash (v4+ only).
    */
   sessionPolicyDigest32?: string;
+  /**
+   * Optional session policy JSON. The VRF worker computes the canonical `sessionPolicyDigest32`
+   * from it (a `sessionPolicyDigest32` sent alongside must match).
+   */
+  sessionPolicyJson?: string;
+  /**
+   * Optional base64url-encoded 32-byte app context (e.g. a hash of an off-chain order) to bind
+   * into the VRF input hash. Omitting it leaves the VRF input unchanged.
+   */
+  extraContextDigest32?: string;
+  /**
+   * How `rpId` is normalized before it is hashed into the VRF input. Defaults to
+   * `lowercaseAscii`; must match the normalization the verifying contract applies.
+   */
+  rpIdNormalization?: RpIdNormalization;
 }
 
 export interface VRFWorkerMessage<T extends WasmVrfWorkerRequestType> {
   // type: wasmModule.WorkerRequestType
   type: 'PING'
   | 'GENERATE_VRF_CHALLENGE'
+  | 'GENERATE_VRF_CHALLENGES'
   | 'GENERATE_VRF_KEYPAIR_BOOTSTRAP'
   | 'UNLOCK_VRF_KEYPAIR'
+  | 'UNLOCK_VRF_KEYPAIRS'
   | 'CHECK_VRF_STATUS'
   | 'CLEAR_VRF'
   | 'DERIVE_VRF_KEYPAIR_FROM_PRF'
   | 'MINT_SESSION_KEYS_AND_SEND_TO_SIGNER'
   | 'DISPENSE_SESSION_KEY'
   | 'CHECK_SESSION_STATUS'
+  | 'RENEW_SESSION'
   | 'CLEAR_SESSION'
+  | 'GET_STATE_VERSION'
+  | 'GET_STATE_CHANGES_SINCE'
+  | 'LOGOUT_ALL'
+  | 'VRF_DIAGNOSTICS'
   | 'CONFIRM_AND_PREPARE_SIGNING_SESSION'
   | 'DECRYPT_SESSION'
   | 'REGISTRATION_CREDENTIAL_CONFIRMATION'
   | 'DEVICE2_REGISTRATION_SESSION'
+  | 'DEVICE2_COMPLETE_REGISTRATION'
   | 'SHAMIR3PASS_CLIENT_ENCRYPT_CURRENT_VRF_KEYPAIR' // client only
   | 'SHAMIR3PASS_CLIENT_DECRYPT_VRF_KEYPAIR' // client only
   | 'SHAMIR3PASS_APPLY_SERVER_LOCK_KEK' // server only
@@ -256,6 +397,76 @@ export interface VRFWorkerResponse<TData = Record<string, unknown>> {
   success: boolean;
   data?: TData;
   error?: string;
+  /** Machine-readable failure class, e.g. `VRF_LOCKED` when no VRF keypair is unlocked */
+  errorCode?: VrfWorkerErrorCode;
+}
+
+export type VrfWorkerErrorCode = 'VRF_LOCKED' | 'INVALID_INPUT' | 'BLOCK_CONTEXT_UNAVAILABLE' | 'VRF_ERROR';
+
+/** VRF worker failure with its `errorCode`; `VRF_LOCKED` means the UI should prompt an unlock */
+export class VrfWorkerError extends Error {
+  public readonly code?: VrfWorkerErrorCode;
+
+  constructor(message: string, code?: VrfWorkerErrorCode) {
+    super(message);
+    this.name = 'VrfWorkerError';
+    this.code = code;
+  }
+}
+
+// === PROGRESS MESSAGES ===
+
+/**
+ * Phases reported while unlocking or deriving a VRF keypair.
+ * Matches `vrf_progress_phase_name()` in wasm_vrf_worker/src/types/progress.rs;
+ * numeric values come from the generated `wasmModule.VrfProgressPhase`.
+ */
+export enum VrfProgressPhase {
+  CLIENT_LOCK_APPLIED = 'client-lock-applied',             // Rust: ClientLockApplied
+  SERVER_ROUNDTRIP_START = 'server-roundtrip-start',       // Rust: ServerRoundtripStart
+  SERVER_ROUNDTRIP_COMPLETE = 'server-roundtrip-complete', // Rust: ServerRoundtripComplete
+  AEAD_DECRYPT_START = 'aead-decrypt-start',               // Rust: AeadDecryptStart
+  KEYPAIR_LOADED = 'keypair-loaded',                       // Rust: KeypairLoaded
+  DERIVATION = 'derivation',                               // Rust: Derivation
+  ENCRYPTION = 'encryption',                               // Rust: Encryption
+  SERVER_ENCRYPTION = 'server-encryption',                 // Rust: ServerEncryption
+}
+
+export interface VrfProgressPhaseMap {
+  [wasmModule.VrfProgressPhase.ClientLockApplied]: VrfProgressPhase.CLIENT_LOCK_APPLIED;
+  [wasmModule.VrfProgressPhase.ServerRoundtripStart]: VrfProgressPhase.SERVER_ROUNDTRIP_START;
+  [wasmModule.VrfProgressPhase.ServerRoundtripComplete]: VrfProgressPhase.SERVER_ROUNDTRIP_COMPLETE;
+  [wasmModule.VrfProgressPhase.AeadDecryptStart]: VrfProgressPhase.AEAD_DECRYPT_START;
+  [wasmModule.VrfProgressPhase.KeypairLoaded]: VrfProgressPhase.KEYPAIR_LOADED;
+  [wasmModule.VrfProgressPhase.Derivation]: VrfProgressPhase.DERIVATION;
+  [wasmModule.VrfProgressPhase.Encryption]: VrfProgressPhase.ENCRYPTION;
+  [wasmModule.VrfProgressPhase.ServerEncryption]: VrfProgressPhase.SERVER_ENCRYPTION;
+}
+
+/** `type` of the progress envelopes the VRF worker posts alongside request responses */
+export const VRF_PROGRESS_MESSAGE_TYPE = 'VRF_PROGRESS' as const;
+
+export interface VrfProgressEvent {
+  /** Numeric `wasmModule.VrfProgressPhase` value */
+  phase: number;
+  phaseName: VrfProgressPhase;
+  /** Milliseconds since the reporting handler started */
+  elapsedMs: number;
+  detail?: string;
+}
+
+export interface VrfProgressMessage {
+  type: typeof VRF_PROGRESS_MESSAGE_TYPE;
+  payload: VrfProgressEvent;
+}
+
+export function isVrfProgressMessage(obj: unknown): obj is VrfProgressMessage {
+  return (
+    typeof obj === 'object' &&
+    obj !== null &&
+    (obj as { type?: unknown }).type === VRF_PROGRESS_MESSAGE_TYPE &&
+    typeof (obj as { payload?: { phaseName?: unknown } }).payload?.phaseName === 'string'
+  );
 }
 
 export interface VRFKeypairBootstrapResponse {
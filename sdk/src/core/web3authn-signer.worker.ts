@@ -46,7 +46,11 @@ import {
 // Import WASM binary directly
 import init, {
   attach_wrap_key_seed_port,
+  cancel_operation,
+  clear_signing_session,
+  get_session_binding_nonce,
   handle_signer_message,
+  initialize_worker_security,
 } from '../wasm_signer_worker/pkg/wasm_signer_worker.js';
 import { resolveWasmUrl } from './sdkPaths/wasm-loader';
 import { errorMessage } from '../utils/errors';
@@ -186,6 +190,10 @@ function getFailureResponseType(requestType: SignerWorkerRequestType): SignerWor
       return WorkerResponseType.SignTransactionsWithActionsFailure;
     case WorkerRequestType.ExtractCosePublicKey:
       return WorkerResponseType.ExtractCosePublicKeyFailure;
+    case WorkerRequestType.ExtractCosePublicKeys:
+      return WorkerResponseType.ExtractCosePublicKeysFailure;
+    case WorkerRequestType.GetDeviceRecords:
+      return WorkerResponseType.GetDeviceRecordsFailure;
     case WorkerRequestType.SignTransactionWithKeyPair:
       return WorkerResponseType.SignTransactionWithKeyPairFailure;
     case WorkerRequestType.SignNep413Message:
@@ -194,6 +202,26 @@ function getFailureResponseType(requestType: SignerWorkerRequestType): SignerWor
       return WorkerResponseType.RegisterDevice2WithDerivedKeyFailure;
     case WorkerRequestType.SignDelegateAction:
       return WorkerResponseType.SignDelegateActionFailure;
+    case WorkerRequestType.SignIntent:
+      return WorkerResponseType.SignIntentFailure;
+    case WorkerRequestType.CreateAccountCard:
+      return WorkerResponseType.CreateAccountCardFailure;
+    case WorkerRequestType.VerifyAccountCard:
+      return WorkerResponseType.VerifyAccountCardFailure;
+    case WorkerRequestType.GetStateVersion:
+      return WorkerResponseType.GetStateVersionFailure;
+    case WorkerRequestType.GetStateChangesSince:
+      return WorkerResponseType.GetStateChangesSinceFailure;
+    case WorkerRequestType.LogoutAll:
+      return WorkerResponseType.LogoutAllFailure;
+    case WorkerRequestType.ClearAllSessions:
+      return WorkerResponseType.ClearAllSessionsFailure;
+    case WorkerRequestType.SignBorshTransaction:
+      return WorkerResponseType.SignBorshTransactionFailure;
+    case WorkerRequestType.VerifyEncryptedKeyEnvelope:
+      return WorkerResponseType.VerifyEncryptedKeyEnvelopeFailure;
+    case WorkerRequestType.ProvisionRelayerRequestAuthSecret:
+      return WorkerResponseType.ProvisionRelayerRequestAuthSecretFailure;
     case WorkerRequestType.DeriveThresholdEd25519ClientVerifyingShare:
       return WorkerResponseType.DeriveThresholdEd25519ClientVerifyingShareFailure;
     case INTERNAL_WORKER_REQUEST_TYPE_SIGN_ADD_KEY_THRESHOLD_PUBLIC_KEY_NO_PROMPT:
@@ -214,7 +242,11 @@ async function processWorkerMessage(event: MessageEvent): Promise<void> {
     // Initialize WASM
     await initializeWasm();
     // Pass message object directly to Rust WASM (Zero-Copy)
-    // SignerWorkerMessage in Rust now supports JsValue payload via serde_wasm_bindgen
+    // SignerWorkerMessage in Rust now supports JsValue payload via serde_wasm_bindgen.
+    // `callerOrigin` (set by SignerWorkerManager on the main thread) feeds the optional origin
+    // gate (see INITIALIZE_WORKER_SECURITY). It is passed through untouched: `event.origin` is
+    // empty for dedicated workers and `self.location.origin` is the worker's own origin, so
+    // neither says anything about the embedder, and a missing value must fail the gate.
     const response = await handle_signer_message(event.data);
     // Response is already a JS object, send back to main thread
     self.postMessage(response);
@@ -252,6 +284,22 @@ self.onmessage = async (event: MessageEvent<SignerWorkerMessage<WorkerRequestTyp
     return;
   }
 
+  if (eventType === WorkerControlMessage.INITIALIZE_WORKER_SECURITY) {
+    await handleInitializeWorkerSecurity(event);
+    return;
+  }
+
+  // Must bypass `messageQueue`: the request it cancels is the one holding the queue.
+  if (eventType === WorkerControlMessage.CANCEL_OPERATION) {
+    await handleCancelOperation(event);
+    return;
+  }
+
+  if (eventType === WorkerControlMessage.RELEASE_SESSION) {
+    await handleReleaseSession(event);
+    return;
+  }
+
   if (typeof eventType !== 'number') {
     console.warn('[signer-worker]: Ignoring message with invalid non-numeric type:', eventType);
     return;
@@ -285,11 +333,16 @@ async function handleAttachWrapKeySeedPort(
   try {
     await initializeWasm();
     attach_wrap_key_seed_port(sessionId, port);
+    // Bound sessions only accept WrapKeySeed that echoes this nonce (see get_session_binding_nonce).
+    const sessionBindingNonce = (event.data as any)?.bindSession === true
+      ? get_session_binding_nonce(sessionId)
+      : undefined;
 
     // Emit success ACK to main thread
     self.postMessage({
       type: WorkerControlMessage.ATTACH_WRAP_KEY_SEED_PORT_OK,
       sessionId,
+      ...(sessionBindingNonce ? { sessionBindingNonce } : {}),
     });
   } catch (err) {
     console.error('[signer-worker]: Failed to attach WrapKeySeed port in WASM', err);
@@ -309,6 +362,60 @@ async function handleAttachWrapKeySeedPort(
   }
 }
 
+/**
+ * Pin the embedder origin and allowed request types. Rust enforces set-once semantics,
+ * so a later call (e.g. from an injected script) fails instead of loosening the policy.
+ */
+async function handleInitializeWorkerSecurity(event: MessageEvent<any>): Promise<void> {
+  try {
+    await initializeWasm();
+    initialize_worker_security((event.data as any)?.config);
+    self.postMessage({ type: WorkerControlMessage.INITIALIZE_WORKER_SECURITY_OK });
+  } catch (err) {
+    console.error('[signer-worker]: Failed to initialize worker security', err);
+    self.postMessage({
+      type: WorkerControlMessage.INITIALIZE_WORKER_SECURITY_ERROR,
+      error: errorMessage(err),
+    });
+  }
+}
+
+/**
+ * Interrupt a WrapKeySeed wait for `sessionId`; the pending request fails with
+ * "operation cancelled while awaiting key material" rather than a timeout.
+ */
+async function handleCancelOperation(event: MessageEvent<any>): Promise<void> {
+  const sessionId = (event.data as any)?.sessionId as string | undefined;
+  if (!sessionId) {
+    console.warn('[signer-worker]: CANCEL_OPERATION missing sessionId');
+    return;
+  }
+  try {
+    await initializeWasm();
+    cancel_operation(sessionId);
+  } catch (err) {
+    console.error('[signer-worker]: Failed to cancel operation', err);
+  }
+}
+
+/**
+ * Zeroize session-cached key material (WrapKeySeed, PRF.second, warmed threshold client
+ * shares) for `sessionId`.
+ */
+async function handleReleaseSession(event: MessageEvent<any>): Promise<void> {
+  const sessionId = (event.data as any)?.sessionId as string | undefined;
+  if (!sessionId) {
+    console.warn('[signer-worker]: RELEASE_SESSION missing sessionId');
+    return;
+  }
+  try {
+    await initializeWasm();
+    clear_signing_session(sessionId);
+  } catch (err) {
+    console.error('[signer-worker]: Failed to release session', err);
+  }
+}
+
 function assertNoPrfOrVrfSecrets(data: any): void {
   const payload = data?.payload;
   if (!payload || typeof payload !== 'object') return;
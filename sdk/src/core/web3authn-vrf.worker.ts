@@ -10,7 +10,9 @@ import { resolveWasmUrl } from './sdkPaths/wasm-loader';
 import type {
   VRFWorkerMessage,
   WasmVrfWorkerRequestType,
-  VRFWorkerResponse
+  VRFWorkerResponse,
+  VrfProgressMessage,
+  VrfProgressPhase,
 } from './types/vrf-worker';
 import {
   awaitSecureConfirmationV2,
@@ -37,6 +39,40 @@ console.debug(`[vrf-worker] WASM URL resolved to: ${wasmUrl.href}`);
 // awaitSecureConfirmationV2 expects a SecureConfirmRequest object.
 (globalThis as any).awaitSecureConfirmationV2 = awaitSecureConfirmationV2;
 
+/**
+ * Called by WASM (`send_vrf_progress_message`) at each VRF unlock/derivation phase.
+ * Progress is best-effort and never affects the pending request's response.
+ *
+ * @param phase - Numeric VrfProgressPhase enum value
+ * @param phaseName - String name of the phase (e.g. 'server-roundtrip-start')
+ * @param elapsedMs - Milliseconds since the handler started
+ * @param detail - Optional context (relay URL, account id)
+ */
+function sendVrfProgressMessage(
+  phase: number,
+  phaseName: string,
+  elapsedMs: number,
+  detail?: string | null,
+): void {
+  try {
+    const message: VrfProgressMessage = {
+      type: 'VRF_PROGRESS',
+      payload: {
+        phase,
+        phaseName: phaseName as VrfProgressPhase,
+        elapsedMs,
+        ...(detail ? { detail } : {}),
+      },
+    };
+    self.postMessage(message);
+  } catch (error: unknown) {
+    console.warn('[vrf-worker]: Failed to send progress message:', errorMessage(error));
+  }
+}
+
+// Make sendVrfProgressMessage available globally for WASM to call
+(globalThis as any).sendVrfProgressMessage = sendVrfProgressMessage;
+
 let wasmReady = false;
 let messageQueue: MessageEvent[] = [];
 
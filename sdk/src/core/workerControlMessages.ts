@@ -4,12 +4,20 @@
  * These messages are JS-only and do NOT go through the Rust WASM JSON request/response pipeline.
  * They are used for:
  * - MessagePort attachment handshakes (WrapKeySeed delivery)
+ * - One-shot signer worker security initialization (origin/capability gate)
+ * - Cancelling an in-flight WrapKeySeed wait (user cancelled the operation)
+ * - Releasing a signing session (zeroizing session-cached key material)
  * - Readiness signals (worker pool health checks)
  */
 export const WorkerControlMessage = {
   ATTACH_WRAP_KEY_SEED_PORT: 'ATTACH_WRAP_KEY_SEED_PORT',
   ATTACH_WRAP_KEY_SEED_PORT_OK: 'ATTACH_WRAP_KEY_SEED_PORT_OK',
   ATTACH_WRAP_KEY_SEED_PORT_ERROR: 'ATTACH_WRAP_KEY_SEED_PORT_ERROR',
+  INITIALIZE_WORKER_SECURITY: 'INITIALIZE_WORKER_SECURITY',
+  INITIALIZE_WORKER_SECURITY_OK: 'INITIALIZE_WORKER_SECURITY_OK',
+  INITIALIZE_WORKER_SECURITY_ERROR: 'INITIALIZE_WORKER_SECURITY_ERROR',
+  CANCEL_OPERATION: 'CANCEL_OPERATION',
+  RELEASE_SESSION: 'RELEASE_SESSION',
   WORKER_READY: 'WORKER_READY',
 } as const;
 
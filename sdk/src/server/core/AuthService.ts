@@ -102,6 +102,13 @@ function normalizeContractVrfDataForContract(input: unknown): Record<string, unk
     }
   }
 
+  if (Object.prototype.hasOwnProperty.call(input, 'extra_context_digest_32')) {
+    const v = input.extra_context_digest_32;
+    if (v != null) {
+      base.extra_context_digest_32 = normalizeU8ArrayLike(v, 'vrf_data.extra_context_digest_32');
+    }
+  }
+
   return base;
 }
 
@@ -629,6 +636,17 @@ export class AuthService {
           };
         }
       }
+      const extraContextDigest32 = (request?.vrf_data as { extra_context_digest_32?: unknown })?.extra_context_digest_32;
+      if (extraContextDigest32 !== undefined) {
+        if (!Array.isArray(extraContextDigest32) || extraContextDigest32.length !== 32) {
+          return {
+            success: false,
+            verified: false,
+            code: 'invalid_extra_context_digest',
+            message: 'Invalid vrf_data.extra_context_digest_32 (expected 32 bytes when present)',
+          };
+        }
+      }
 
       const args = {
         vrf_data: request.vrf_data,
@@ -49,6 +49,7 @@ import {
   verifyThresholdEd25519AuthorizeSigningPayload,
 } from './validation';
 import { alphabetizeStringify, sha256BytesUtf8 } from '../../../utils/digests';
+import { computeSessionPolicyDigest32 } from '../shamirWorker';
 import {
   normalizeThresholdEd25519ParticipantIds,
   normalizeThresholdEd25519ParticipantId,
@@ -59,6 +60,7 @@ import {
   coerceThresholdNodeRole,
   parseThresholdCoordinatorSharedSecretBytes,
   parseThresholdEd25519ParticipantIds2p,
+  parseThresholdEd25519RpIdNormalization,
   parseThresholdRelayerCosignerThreshold,
   parseThresholdRelayerCosigners,
   validateThresholdEd25519MasterSecretB64u,
@@ -396,6 +398,8 @@ export class ThresholdSigningService {
       relayerMasterSecretB64u: this.relayerMasterSecretB64u,
       clientParticipantId: this.clientParticipantId,
       relayerParticipantId: this.relayerParticipantId,
+      rpIdNormalization: parseThresholdEd25519RpIdNormalization(cfg.THRESHOLD_ED25519_RP_ID_NORMALIZATION),
+      serviceId: toOptionalTrimmedString(cfg.THRESHOLD_ED25519_SERVICE_ID) || null,
       ensureSignerWasm: this.ensureSignerWasm,
     });
     this.signingHandlers = new ThresholdEd25519SigningHandlers({
@@ -441,11 +445,6 @@ export class ThresholdSigningService {
     };
   }
 
-  private async computeSessionPolicyDigest32(policy: unknown): Promise<Uint8Array> {
-    const json = alphabetizeStringify(policy);
-    return await sha256BytesUtf8(json);
-  }
-
   private async putAuthSessionRecord(input: {
     sessionId: string;
     record: ThresholdEd25519AuthSessionRecord;
@@ -885,7 +884,7 @@ export class ThresholdSigningService {
         ttlMs,
         remainingUses,
       };
-      const expectedPolicyDigest32 = await this.computeSessionPolicyDigest32(normalizedPolicy);
+      const expectedPolicyDigest32 = await computeSessionPolicyDigest32(normalizedPolicy);
       if (!bytesEqual32(expectedPolicyDigest32, sessionPolicyDigest32)) {
         return { ok: false, code: 'session_policy_digest_mismatch', message: 'sessionPolicy does not match vrf_data.session_policy_digest_32' };
       }
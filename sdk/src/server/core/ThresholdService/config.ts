@@ -18,6 +18,19 @@ export function coerceThresholdEd25519ShareMode(input: unknown): ThresholdEd2551
   return 'auto';
 }
 
+/** Mirrors the signer worker's `RpIdNormalization`; part of relayer share derivation. */
+export type ThresholdEd25519RpIdNormalization = 'lowercaseAscii' | 'rejectNonAscii' | 'punycodeEncode';
+
+export function parseThresholdEd25519RpIdNormalization(input: unknown): ThresholdEd25519RpIdNormalization {
+  const policy = toOptionalTrimmedString(input);
+  if (!policy) return 'lowercaseAscii';
+  if (policy === 'lowercaseAscii' || policy === 'rejectNonAscii' || policy === 'punycodeEncode') return policy;
+  // Unknown values throw rather than fall back: the policy changes derived relayer shares.
+  throw new Error(
+    'THRESHOLD_ED25519_RP_ID_NORMALIZATION must be one of lowercaseAscii, rejectNonAscii, punycodeEncode',
+  );
+}
+
 export type ThresholdNodeRole = 'cosigner' | 'coordinator';
 
 export function coerceThresholdNodeRole(input: unknown): ThresholdNodeRole {
@@ -41,6 +41,8 @@ export function createThresholdSigningService(input: {
       THRESHOLD_ED25519_RELAYER_PARTICIPANT_ID: env.THRESHOLD_ED25519_RELAYER_PARTICIPANT_ID,
       THRESHOLD_ED25519_MASTER_SECRET_B64U: env.THRESHOLD_ED25519_MASTER_SECRET_B64U,
       THRESHOLD_ED25519_SHARE_MODE: env.THRESHOLD_ED25519_SHARE_MODE,
+      THRESHOLD_ED25519_RP_ID_NORMALIZATION: env.THRESHOLD_ED25519_RP_ID_NORMALIZATION,
+      THRESHOLD_ED25519_SERVICE_ID: env.THRESHOLD_ED25519_SERVICE_ID,
       THRESHOLD_NODE_ROLE: env.THRESHOLD_NODE_ROLE,
       THRESHOLD_COORDINATOR_SHARED_SECRET_B64U: env.THRESHOLD_COORDINATOR_SHARED_SECRET_B64U,
       THRESHOLD_ED25519_RELAYER_COSIGNERS: env.THRESHOLD_ED25519_RELAYER_COSIGNERS,
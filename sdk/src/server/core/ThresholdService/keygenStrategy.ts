@@ -1,4 +1,5 @@
 import { ensureEd25519Prefix, toOptionalTrimmedString } from '../../../utils/validation';
+import type { ThresholdEd25519RpIdNormalization } from './config';
 import {
   threshold_ed25519_keygen_from_client_verifying_share,
   threshold_ed25519_keygen_from_master_secret_and_client_verifying_share,
@@ -54,6 +55,8 @@ export class ThresholdEd25519KeygenStrategyV1 implements ThresholdEd25519KeygenS
   private readonly relayerMasterSecretB64u: string | null;
   private readonly clientParticipantId: number;
   private readonly relayerParticipantId: number;
+  private readonly rpIdNormalization: ThresholdEd25519RpIdNormalization;
+  private readonly serviceId: string | null;
   private readonly ensureSignerWasm: () => Promise<void>;
 
   constructor(input: {
@@ -61,12 +64,17 @@ export class ThresholdEd25519KeygenStrategyV1 implements ThresholdEd25519KeygenS
     relayerMasterSecretB64u: string | null;
     clientParticipantId: number;
     relayerParticipantId: number;
+    rpIdNormalization?: ThresholdEd25519RpIdNormalization;
+    /** Relayer service id folded into the derived-share HKDF salt (domain separation). */
+    serviceId?: string | null;
     ensureSignerWasm: () => Promise<void>;
   }) {
     this.useDerivedShares = input.useDerivedShares;
     this.relayerMasterSecretB64u = input.relayerMasterSecretB64u;
     this.clientParticipantId = input.clientParticipantId;
     this.relayerParticipantId = input.relayerParticipantId;
+    this.rpIdNormalization = input.rpIdNormalization ?? 'lowercaseAscii';
+    this.serviceId = input.serviceId ?? null;
     this.ensureSignerWasm = input.ensureSignerWasm;
   }
 
@@ -107,7 +115,9 @@ export class ThresholdEd25519KeygenStrategyV1 implements ThresholdEd25519KeygenS
           masterSecretB64u,
           nearAccountId,
           rpId,
+          rpIdNormalization: this.rpIdNormalization,
           clientVerifyingShareB64u,
+          ...(this.serviceId ? { serviceId: this.serviceId } : {}),
           clientParticipantId: this.clientParticipantId,
           relayerParticipantId: this.relayerParticipantId,
         }),
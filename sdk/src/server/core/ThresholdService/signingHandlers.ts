@@ -965,6 +965,8 @@ export class ThresholdEd25519SigningHandlers {
       signingDigestB64u: sess.signingDigestB64u,
       clientCommitments,
       relayerCommitments,
+      clientVerifyingShareB64u: sess.clientVerifyingShareB64u,
+      expectedRelayerVerifyingShareB64u: key.relayerVerifyingShareB64u,
     }));
 
     return { ok: true, relayerSignatureShareB64u: out.relayerSignatureShareB64u };
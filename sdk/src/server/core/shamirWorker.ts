@@ -6,6 +6,7 @@
 import initWasm, {
   handle_message as wasmHandleMessage,
   configure_shamir_p,
+  compute_session_policy_digest_32,
   get_shamir_p_b64u,
   SHAMIR_P_B64U,
   type InitInput,
@@ -192,6 +193,16 @@ async function ensureWasmInitialized(): Promise<void> {
   }
 }
 
+/**
+ * Canonical `sessionPolicyDigest32` for a session policy, computed by the VRF wasm so the
+ * relayer hashes exactly what the client's VRF worker bound into the VRF challenge
+ * (sorted keys, no whitespace, integers only, domain-prefixed SHA-256).
+ */
+export async function computeSessionPolicyDigest32(policy: unknown): Promise<Uint8Array> {
+  await ensureWasmInitialized();
+  return compute_session_policy_digest_32(JSON.stringify(policy));
+}
+
 export class Shamir3PassUtils {
   private p_b64u: string;
   private e_s_b64u: string;
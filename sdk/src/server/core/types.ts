@@ -192,6 +192,21 @@ export type ThresholdEd25519KeyStoreEnvInput = {
    * - "auto": prefer derived when master secret is configured, otherwise kv
    */
   THRESHOLD_ED25519_SHARE_MODE?: string;
+  /**
+   * rpId normalization applied before deriving relayer shares:
+   * "lowercaseAscii" (default), "rejectNonAscii" or "punycodeEncode".
+   *
+   * The normalized rpId is an input to share derivation: changing this on a deployment with
+   * existing accounts changes the derived relayer share (and group public key) of every account
+   * whose rpId normalizes differently, e.g. any non-ASCII rpId.
+   */
+  THRESHOLD_ED25519_RP_ID_NORMALIZATION?: string;
+  /**
+   * Optional relayer service id folded into the derived-share HKDF salt, for running several
+   * logically separate relayer services from one master secret. Unset keeps existing
+   * derivations; changing it changes every derived relayer share (and group public key).
+   */
+  THRESHOLD_ED25519_SERVICE_ID?: string;
   /**
    * Threshold node role.
    * - "coordinator" (default): exposes `/threshold-ed25519/sign/*` and can fan out to cosigners when configured.
@@ -347,6 +362,11 @@ export interface ContractVrfData {
    * When present, must be exactly 32 bytes.
    */
   session_policy_digest_32?: number[];
+  /**
+   * Optional 32-byte app context bound into VRF input derivation (after its domain separator).
+   * When present, must be exactly 32 bytes.
+   */
+  extra_context_digest_32?: number[];
 }
 
 // WebAuthn registration credential structure
@@ -511,6 +531,8 @@ export interface ThresholdEd25519SessionResponse {
   expiresAt?: string;
   remainingUses?: number;
   jwt?: string;
+  /** Relayer `Date.now()` at mint time; lets clients estimate clock skew against `expiresAt`. */
+  serverTimeMs?: number;
 }
 
 export interface ThresholdEd25519AuthorizeWithSessionRequest {
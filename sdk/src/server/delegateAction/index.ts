@@ -1,6 +1,6 @@
 import type { FinalExecutionOutcome } from '@near-js/types';
 import type { NearClient, SignedTransaction } from '../../core/NearClient';
-import { ActionType, type ActionArgsWasm, validateActionArgsWasm } from '../../core/types/actions';
+import { ActionType, isKnownActionType, type ActionArgsWasm, validateActionArgsWasm } from '../../core/types/actions';
 import type { SignedDelegate as CoreSignedDelegate } from '../../core/types/delegate';
 import { isObject } from '@/utils/validation';
 
@@ -9,6 +9,11 @@ export interface DelegateActionPolicy {
   allowedReceivers?: string[];
   /** Optional allowlist of function call method names. Empty = any method. */
   allowedMethods?: string[];
+  /**
+   * Optional allowlist of action types (e.g. `FunctionCall`, `UseGlobalContract`).
+   * Empty/omitted = any action type this relay understands.
+   */
+  allowedActionTypes?: ActionType[];
   /**
    * Optional maximum total attached deposit (yoctoNEAR) across all actions.
    * Represented as decimal string to avoid BigInt JSON issues.
@@ -101,6 +106,7 @@ export async function validateDelegateExpiryAndNonce(params: {
 /**
  * Enforce a simple policy over the delegate action:
  * - Receiver allowlist
+ * - Action type allowlist (unknown action types are always rejected)
  * - FunctionCall method allowlist
  * - Total attached deposit limit
  */
@@ -132,6 +138,19 @@ export async function enforceDelegatePolicy(input: {
     if (!isObject(action) || !('type' in action)) continue;
     const kind = (action as any).type;
 
+    // An action type added after this relay was built cannot be summarized or checked here;
+    // refuse it rather than letting it pass the policy unexamined.
+    if (!isKnownActionType(kind)) {
+      throw Object.assign(new Error(`unsupported_action_type: ${String(kind)}`), {
+        code: 'unsupported_action_type',
+      });
+    }
+    if (policy.allowedActionTypes && policy.allowedActionTypes.length > 0) {
+      if (!policy.allowedActionTypes.includes(kind)) {
+        throw Object.assign(new Error('action_type_not_allowed'), { code: 'action_type_not_allowed' });
+      }
+    }
+
     if (kind === ActionType.FunctionCall) {
       const methodName = String((action as any).methodName || '');
       if (policy.allowedMethods && policy.allowedMethods.length > 0) {
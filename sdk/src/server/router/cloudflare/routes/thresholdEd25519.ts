@@ -114,7 +114,8 @@ export async function handleThresholdEd25519(ctx: CloudflareRelayContext): Promi
         return Number.isFinite(ms) && ms > 0 ? ms : undefined;
       })();
       const exp = thresholdExpiresAtMs ? Math.floor(thresholdExpiresAtMs / 1000) : undefined;
-      const iat = Math.floor(Date.now() / 1000);
+      const serverTimeMs = Date.now();
+      const iat = Math.floor(serverTimeMs / 1000);
       const participantIds =
         normalizeThresholdEd25519ParticipantIds(b.sessionPolicy?.participantIds)
         || [...THRESHOLD_ED25519_2P_PARTICIPANT_IDS];
@@ -130,7 +131,12 @@ export async function handleThresholdEd25519(ctx: CloudflareRelayContext): Promi
       });
       const sessionKind = parseSessionKind(b);
 
-      const res = json(sessionKind === 'cookie' ? { ...result, jwt: undefined } : { ...result, jwt: token }, { status: 200 });
+      const res = json(
+        sessionKind === 'cookie'
+          ? { ...result, jwt: undefined, serverTimeMs }
+          : { ...result, jwt: token, serverTimeMs },
+        { status: 200 },
+      );
       if (sessionKind === 'cookie') {
         res.headers.set('Set-Cookie', session.buildSetCookie(token));
       }
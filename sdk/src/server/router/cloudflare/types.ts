@@ -37,6 +37,8 @@ export interface RelayCloudflareWorkerEnv {
   // The SDK enables `/threshold-ed25519/*` endpoints when `thresholdEd25519KeyStore` is configured.
   THRESHOLD_ED25519_SHARE_MODE?: string;
   THRESHOLD_ED25519_MASTER_SECRET_B64U?: string;
+  THRESHOLD_ED25519_RP_ID_NORMALIZATION?: string;
+  THRESHOLD_ED25519_SERVICE_ID?: string;
 }
 
 export interface CfExecutionContext {
@@ -22,6 +22,7 @@ export function summarizeVrfData(input: unknown): Record<string, unknown> | unde
   const intent_digest_32_len = Array.isArray(input.intent_digest_32) ? input.intent_digest_32.length : undefined;
   const has_session_policy_digest_32 = Array.isArray(input.session_policy_digest_32) ? true : undefined;
   const session_policy_digest_32_len = Array.isArray(input.session_policy_digest_32) ? input.session_policy_digest_32.length : undefined;
+  const has_extra_context_digest_32 = Array.isArray(input.extra_context_digest_32) ? true : undefined;
   return {
     ...(user_id ? { user_id } : {}),
     ...(rp_id ? { rp_id } : {}),
@@ -30,6 +31,7 @@ export function summarizeVrfData(input: unknown): Record<string, unknown> | unde
     ...(intent_digest_32_len != null ? { intent_digest_32_len } : {}),
     ...(has_session_policy_digest_32 != null ? { has_session_policy_digest_32 } : {}),
     ...(session_policy_digest_32_len != null ? { session_policy_digest_32_len } : {}),
+    ...(has_extra_context_digest_32 != null ? { has_extra_context_digest_32 } : {}),
   };
 }
 
@@ -128,7 +128,8 @@ export function registerThresholdEd25519Routes(router: ExpressRouter, ctx: Expre
         return Number.isFinite(ms) && ms > 0 ? ms : undefined;
       })();
       const exp = thresholdExpiresAtMs ? Math.floor(thresholdExpiresAtMs / 1000) : undefined;
-      const iat = Math.floor(Date.now() / 1000);
+      const serverTimeMs = Date.now();
+      const iat = Math.floor(serverTimeMs / 1000);
       const participantIds =
         normalizeThresholdEd25519ParticipantIds(body.sessionPolicy?.participantIds)
         || [...THRESHOLD_ED25519_2P_PARTICIPANT_IDS];
@@ -147,10 +148,10 @@ export function registerThresholdEd25519Routes(router: ExpressRouter, ctx: Expre
       if (sessionKind === 'cookie') {
         res.set('Set-Cookie', session.buildSetCookie(token));
         const { jwt: _omit, ...rest } = result;
-        return { ...rest, ok: true };
+        return { ...rest, ok: true, serverTimeMs };
       }
 
-      return { ...result, jwt: token };
+      return { ...result, jwt: token, serverTimeMs };
     });
   });
 
@@ -0,0 +1,211 @@
+//! Signed account cards for device linking.
+//!
+//! Device1 embeds a card in its linking QR code so Device2 can check, before doing any
+//! registration work, that the QR was produced by a holder of the account's NEAR key. The card
+//! binds the account id, that key, the account's VRF public key and the device number being
+//! handed out, and is valid for `ACCOUNT_CARD_TTL_MS` only.
+//!
+//! Wire format: `base64url(borsh(payload) || signature)`, where the 64-byte Ed25519 signature
+//! covers `sha256(ACCOUNT_CARD_DISCRIMINANT || borsh(payload))`, so a card signature can never
+//! be replayed as any other signable message kind.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use ed25519_dalek::Verifier;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ACCOUNT_CARD_MAX_CLOCK_SKEW_MS, ACCOUNT_CARD_TTL_MS};
+use crate::encoders::{base64_url_decode, base64_url_encode, B64UrlBytes};
+use crate::error::AccountCardError;
+use crate::signable_message::{compute_signable_digest, SignableMessageKind};
+
+/// Current card format version; bumped whenever the signed payload layout changes.
+pub const ACCOUNT_CARD_VERSION: u8 = 1;
+
+const SIGNATURE_LEN: usize = 64;
+
+/// Parsed account card fields, as returned to JS.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountCard {
+    pub near_account_id: String,
+    /// Signer public key (`ed25519:<base58>`)
+    pub near_public_key: String,
+    pub vrf_public_key_raw_b64u: B64UrlBytes,
+    /// Device number the issuing device assigns to the device being linked.
+    pub device_number: u32,
+    pub issued_at_ms: u64,
+    pub expires_at_ms: u64,
+}
+
+/// Borsh layout of the signed payload. Field order is part of the format.
+#[derive(BorshSerialize, BorshDeserialize)]
+struct AccountCardPayloadV1 {
+    version: u8,
+    near_account_id: String,
+    near_public_key: [u8; 32],
+    vrf_public_key_raw: Vec<u8>,
+    device_number: u32,
+    issued_at_ms: u64,
+    expires_at_ms: u64,
+}
+
+fn invalid(field: &'static str, reason: impl Into<String>) -> AccountCardError {
+    AccountCardError::InvalidField {
+        field,
+        reason: reason.into(),
+    }
+}
+
+fn near_public_key_bytes(near_public_key: &str) -> Result<[u8; 32], AccountCardError> {
+    let b58 = near_public_key
+        .strip_prefix("ed25519:")
+        .ok_or_else(|| invalid("nearPublicKey", "must be an ed25519:<base58> key"))?;
+    bs58::decode(b58)
+        .into_vec()
+        .map_err(|e| invalid("nearPublicKey", e.to_string()))?
+        .try_into()
+        .map_err(|_| invalid("nearPublicKey", "must be 32 bytes"))
+}
+
+impl AccountCard {
+    /// A card for `near_public_key_bytes` issued at `now_ms`, expiring `ACCOUNT_CARD_TTL_MS` later.
+    pub fn new(
+        near_account_id: &str,
+        near_public_key_bytes: &[u8; 32],
+        vrf_public_key_raw: &[u8],
+        device_number: u32,
+        now_ms: u64,
+    ) -> Result<Self, AccountCardError> {
+        let card = Self {
+            near_account_id: near_account_id.to_string(),
+            near_public_key: format!(
+                "ed25519:{}",
+                bs58::encode(near_public_key_bytes).into_string()
+            ),
+            vrf_public_key_raw_b64u: B64UrlBytes(vrf_public_key_raw.to_vec()),
+            device_number,
+            issued_at_ms: now_ms,
+            expires_at_ms: now_ms.saturating_add(ACCOUNT_CARD_TTL_MS),
+        };
+        card.to_payload()?;
+        Ok(card)
+    }
+
+    fn to_payload(&self) -> Result<AccountCardPayloadV1, AccountCardError> {
+        crate::types::near::validate_account_id_syntax(&self.near_account_id)
+            .map_err(|e| invalid("nearAccountId", e))?;
+        let vrf_public_key_raw = self.vrf_public_key_raw_b64u.0.clone();
+        if vrf_public_key_raw.is_empty() {
+            return Err(invalid("vrfPublicKeyRawB64u", "empty"));
+        }
+        Ok(AccountCardPayloadV1 {
+            version: ACCOUNT_CARD_VERSION,
+            near_account_id: self.near_account_id.clone(),
+            near_public_key: near_public_key_bytes(&self.near_public_key)?,
+            vrf_public_key_raw,
+            device_number: self.device_number,
+            issued_at_ms: self.issued_at_ms,
+            expires_at_ms: self.expires_at_ms,
+        })
+    }
+
+    fn from_payload(payload: AccountCardPayloadV1) -> Self {
+        Self {
+            near_account_id: payload.near_account_id,
+            near_public_key: format!(
+                "ed25519:{}",
+                bs58::encode(payload.near_public_key).into_string()
+            ),
+            vrf_public_key_raw_b64u: B64UrlBytes(payload.vrf_public_key_raw),
+            device_number: payload.device_number,
+            issued_at_ms: payload.issued_at_ms,
+            expires_at_ms: payload.expires_at_ms,
+        }
+    }
+}
+
+fn payload_borsh(card: &AccountCard) -> Result<Vec<u8>, AccountCardError> {
+    borsh::to_vec(&card.to_payload()?)
+        .map_err(|e| AccountCardError::Malformed(format!("Borsh serialization failed: {}", e)))
+}
+
+/// `sha256(ACCOUNT_CARD_DISCRIMINANT || borsh(payload))`
+pub fn account_card_digest(card: &AccountCard) -> Result<[u8; 32], AccountCardError> {
+    Ok(compute_signable_digest(
+        SignableMessageKind::AccountCard,
+        &payload_borsh(card)?,
+    ))
+}
+
+/// The compact QR blob: `base64url(borsh(payload) || signature)`.
+pub fn encode_account_card(
+    card: &AccountCard,
+    signature: &[u8; 64],
+) -> Result<String, AccountCardError> {
+    let mut bytes = payload_borsh(card)?;
+    bytes.extend_from_slice(signature);
+    Ok(base64_url_encode(&bytes))
+}
+
+/// Decode a blob, check its signature against the embedded `nearPublicKey`, and check that
+/// `now_ms` falls inside its validity window. Callers must still confirm `nearPublicKey` is
+/// an access key of `nearAccountId`.
+pub fn verify_account_card_inner(
+    blob_b64u: &str,
+    now_ms: u64,
+) -> Result<AccountCard, AccountCardError> {
+    let bytes = base64_url_decode(blob_b64u.trim()).map_err(AccountCardError::Malformed)?;
+    if bytes.len() <= SIGNATURE_LEN {
+        return Err(AccountCardError::Malformed(format!(
+            "{} bytes is too short",
+            bytes.len()
+        )));
+    }
+    let (payload_bytes, signature_bytes) = bytes.split_at(bytes.len() - SIGNATURE_LEN);
+    if payload_bytes[0] != ACCOUNT_CARD_VERSION {
+        return Err(AccountCardError::UnsupportedVersion(payload_bytes[0]));
+    }
+    let payload: AccountCardPayloadV1 =
+        borsh::from_slice(payload_bytes).map_err(|e| AccountCardError::Malformed(e.to_string()))?;
+
+    let verifying_key = ed25519_dalek::VerifyingKey::from_bytes(&payload.near_public_key)
+        .map_err(|e| invalid("nearPublicKey", e.to_string()))?;
+    let signature_bytes: [u8; 64] = signature_bytes
+        .try_into()
+        .map_err(|_| AccountCardError::BadSignature)?;
+    let digest = compute_signable_digest(SignableMessageKind::AccountCard, payload_bytes);
+    verifying_key
+        .verify(
+            &digest,
+            &ed25519_dalek::Signature::from_bytes(&signature_bytes),
+        )
+        .map_err(|_| AccountCardError::BadSignature)?;
+
+    let lifetime_ms = payload
+        .expires_at_ms
+        .checked_sub(payload.issued_at_ms)
+        .ok_or_else(|| invalid("expiresAtMs", "before issuedAtMs"))?;
+    if lifetime_ms > ACCOUNT_CARD_TTL_MS {
+        return Err(AccountCardError::LifetimeTooLong {
+            lifetime_ms,
+            max_ms: ACCOUNT_CARD_TTL_MS,
+        });
+    }
+    if payload.issued_at_ms > now_ms.saturating_add(ACCOUNT_CARD_MAX_CLOCK_SKEW_MS) {
+        return Err(AccountCardError::NotYetValid {
+            issued_at_ms: payload.issued_at_ms,
+            now_ms,
+        });
+    }
+    if now_ms >= payload.expires_at_ms {
+        return Err(AccountCardError::Expired {
+            expires_at_ms: payload.expires_at_ms,
+            now_ms,
+        });
+    }
+
+    let card = AccountCard::from_payload(payload);
+    // Re-validates the account id and VRF key the signer committed to.
+    card.to_payload()?;
+    Ok(card)
+}
@@ -1,3 +1,5 @@
+use crate::error::BinaryAttachmentError;
+use crate::types::worker_messages::BinaryAttachments;
 use crate::types::*;
 use bs58;
 use serde::{Deserialize, Serialize};
@@ -9,7 +11,16 @@ use serde::{Deserialize, Serialize};
 pub enum ActionParams {
     CreateAccount,
     DeployContract {
+        #[serde(default)]
         code: Vec<u8>,
+        /// Index into the envelope's `binaryAttachments` carrying the code instead of `code`;
+        /// moved into `code` by `resolve_code_attachments` before the action is built.
+        #[serde(
+            default,
+            rename = "codeAttachment",
+            skip_serializing_if = "Option::is_none"
+        )]
+        code_attachment: Option<u32>,
     },
     FunctionCall {
         method_name: String,
@@ -58,12 +69,10 @@ impl ActionParams {
         match self {
             ActionParams::CreateAccount => Ok(NearAction::CreateAccount),
 
-            ActionParams::DeployContract { code } => {
-                if code.is_empty() {
-                    return Err("Contract code cannot be empty".to_string());
-                }
-                Ok(NearAction::DeployContract { code: code.clone() })
-            }
+            ActionParams::DeployContract {
+                code,
+                code_attachment,
+            } => deploy_contract_action(code.clone(), *code_attachment),
 
             ActionParams::FunctionCall {
                 method_name,
@@ -313,11 +322,219 @@ impl ActionParams {
         }
     }
 
+    /// `to_action` that moves DeployContract code into the action instead of cloning it.
+    pub fn into_action(self) -> Result<NearAction, String> {
+        match self {
+            ActionParams::DeployContract {
+                code,
+                code_attachment,
+            } => deploy_contract_action(code, code_attachment),
+            other => other.to_action(),
+        }
+    }
+
     /// Lightweight validator used by tests and callers that only care about
     /// parameter validity, not the constructed action.
     pub fn validate(&self) -> Result<(), String> {
         self.to_action().map(|_| ())
     }
+
+    /// The `action_type` tag of this action, as matched by `allowedActionKinds`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ActionParams::CreateAccount => "CreateAccount",
+            ActionParams::DeployContract { .. } => "DeployContract",
+            ActionParams::FunctionCall { .. } => "FunctionCall",
+            ActionParams::Transfer { .. } => "Transfer",
+            ActionParams::Stake { .. } => "Stake",
+            ActionParams::AddKey { .. } => "AddKey",
+            ActionParams::DeleteKey { .. } => "DeleteKey",
+            ActionParams::DeleteAccount { .. } => "DeleteAccount",
+            ActionParams::SignedDelegate { .. } => "SignedDelegate",
+            ActionParams::DeployGlobalContract { .. } => "DeployGlobalContract",
+            ActionParams::UseGlobalContract { .. } => "UseGlobalContract",
+        }
+    }
+
+    /// Risk annotation shown with the action in confirmation summaries. Anything that replaces
+    /// the account's code, keys or existence is `High`.
+    pub fn risk(&self) -> ActionRisk {
+        match self {
+            ActionParams::CreateAccount | ActionParams::Transfer { .. } => ActionRisk::Low,
+            ActionParams::FunctionCall { .. }
+            | ActionParams::Stake { .. }
+            | ActionParams::DeleteKey { .. }
+            | ActionParams::SignedDelegate { .. } => ActionRisk::Medium,
+            ActionParams::DeployContract { .. }
+            | ActionParams::DeployGlobalContract { .. }
+            | ActionParams::UseGlobalContract { .. }
+            | ActionParams::AddKey { .. }
+            | ActionParams::DeleteAccount { .. } => ActionRisk::High,
+        }
+    }
+}
+
+fn deploy_contract_action(
+    code: Vec<u8>,
+    code_attachment: Option<u32>,
+) -> Result<NearAction, String> {
+    if let Some(index) = code_attachment {
+        return Err(format!(
+            "Contract code attachment {} was not resolved",
+            index
+        ));
+    }
+    if code.is_empty() {
+        return Err("Contract code cannot be empty".to_string());
+    }
+    Ok(NearAction::DeployContract { code })
+}
+
+/// `deserialize_with` for `actions` fields: a list of actions, or (legacy callers) a JSON string
+/// holding that list.
+pub fn deserialize_actions_flexible<'de, D>(deserializer: D) -> Result<Vec<ActionParams>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct ActionsVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for ActionsVisitor {
+        type Value = Vec<ActionParams>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a list of actions or a JSON string containing them")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+        {
+            let mut vec = Vec::new();
+            while let Some(elem) = seq.next_element()? {
+                vec.push(elem);
+            }
+            Ok(vec)
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            #[cfg(target_arch = "wasm32")]
+            {
+                let js_val = js_sys::JSON::parse(v).map_err(|e| {
+                    E::custom(format!("Failed to parse actions JSON string: {:?}", e))
+                })?;
+                serde_wasm_bindgen::from_value(js_val).map_err(E::custom)
+            }
+            // `serde_json` is only a dev-dependency; native tests use it to exercise this path.
+            #[cfg(all(test, not(target_arch = "wasm32")))]
+            {
+                serde_json::from_str(v)
+                    .map_err(|e| E::custom(format!("Failed to parse actions JSON string: {}", e)))
+            }
+            #[cfg(all(not(test), not(target_arch = "wasm32")))]
+            {
+                let _ = v;
+                Err(E::custom(
+                    "Parsing actions from JSON string is not supported on native targets",
+                ))
+            }
+        }
+    }
+
+    deserializer.deserialize_any(ActionsVisitor)
+}
+
+/// Move each referenced `binaryAttachments` entry into its DeployContract's `code`, so the
+/// contract bytes never travel as a JSON number array nor get copied on the way.
+pub fn resolve_code_attachments(
+    actions: &mut [ActionParams],
+    attachments: &mut BinaryAttachments,
+) -> Result<(), BinaryAttachmentError> {
+    for action in actions {
+        if let ActionParams::DeployContract {
+            code,
+            code_attachment,
+        } = action
+        {
+            let Some(index) = *code_attachment else {
+                continue;
+            };
+            if !code.is_empty() {
+                return Err(BinaryAttachmentError::InlineAndAttached { index });
+            }
+            *code = attachments.take(index)?;
+            *code_attachment = None;
+        }
+    }
+    Ok(())
+}
+
+/// Warnings for DeployContract code larger than `max_code_bytes`; such transactions are
+/// still signed, but the chain will reject code above its contract size limit.
+pub fn contract_code_size_warnings(actions: &[ActionParams], max_code_bytes: usize) -> Vec<String> {
+    actions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, action)| match action {
+            ActionParams::DeployContract { code, .. } if code.len() > max_code_bytes => {
+                Some(format!(
+                    "Action {}: contract code is {} bytes, above the {} byte limit",
+                    index,
+                    code.len(),
+                    max_code_bytes
+                ))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ActionRisk {
+    Low,
+    Medium,
+    High,
+}
+
+/// Enforce an optional FunctionCall method-name allowlist.
+/// `None` or an empty list leaves signing unrestricted.
+pub fn enforce_allowed_methods(
+    actions: &[ActionParams],
+    allowed_methods: Option<&[String]>,
+) -> Result<(), String> {
+    let allowed = match allowed_methods {
+        Some(allowed) if !allowed.is_empty() => allowed,
+        _ => return Ok(()),
+    };
+    for action in actions {
+        if let ActionParams::FunctionCall { method_name, .. } = action {
+            if !allowed.iter().any(|m| m == method_name) {
+                return Err(format!("method '{}' not permitted by policy", method_name));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Enforce an optional action-kind allowlist (`ActionParams::kind` names).
+/// `None` or an empty list leaves signing unrestricted.
+pub fn enforce_allowed_action_kinds(
+    actions: &[ActionParams],
+    allowed_kinds: Option<&[String]>,
+) -> Result<(), String> {
+    let allowed = match allowed_kinds {
+        Some(allowed) if !allowed.is_empty() => allowed,
+        _ => return Ok(()),
+    };
+    for action in actions {
+        let kind = action.kind();
+        if !allowed.iter().any(|k| k == kind) {
+            return Err(format!("action '{}' not permitted by policy", kind));
+        }
+    }
+    Ok(())
 }
 
 // Helper for parsing JSON string to AccessKey without serde_json dependency on WASM
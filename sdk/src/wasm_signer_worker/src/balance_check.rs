@@ -0,0 +1,83 @@
+//! Opt-in pre-sign balance check for `SignTransactionsWithActions` (`checkBalance`).
+//!
+//! Transfers that exceed the signer's spendable balance (often because storage staking eats
+//! into it) only fail on-chain, minutes after the user confirmed. With `checkBalance` the worker
+//! fetches the account via `view_account` and refuses to sign when the batch's deposits,
+//! transfers and a conservative gas estimate exceed what the account can spend. An RPC outage
+//! never blocks signing; the result is flagged `balanceUnchecked` instead.
+
+use crate::actions::ActionParams;
+use crate::config::{
+    BALANCE_CHECK_GAS_PER_ACTION, BALANCE_CHECK_GAS_PRICE_YOCTO, STORAGE_AMOUNT_PER_BYTE_YOCTO,
+};
+use crate::error::BalanceCheckError;
+use crate::rpc_calls::{view_account, AccountView};
+use crate::transaction::build_actions_from_params;
+use crate::types::near::NearAction;
+
+impl AccountView {
+    /// What the account can spend: `amount` minus the storage staking `locked` does not cover.
+    pub fn available_balance(&self) -> u128 {
+        let storage_cost =
+            u128::from(self.storage_usage).saturating_mul(STORAGE_AMOUNT_PER_BYTE_YOCTO);
+        self.amount
+            .saturating_sub(storage_cost.saturating_sub(self.locked))
+    }
+}
+
+/// Attached deposits + Transfer amounts + gas (attached FunctionCall gas plus a flat per-action
+/// fee, priced at `BALANCE_CHECK_GAS_PRICE_YOCTO`) for one transaction, in yoctoNEAR.
+pub fn transaction_cost(actions: &[NearAction]) -> Result<u128, BalanceCheckError> {
+    actions.iter().try_fold(0u128, |total, action| {
+        let (deposit, attached_gas) = match action {
+            NearAction::FunctionCall(call) => (call.deposit, call.gas),
+            NearAction::Transfer { deposit } => (*deposit, 0),
+            _ => (0, 0),
+        };
+        let gas = u128::from(attached_gas) + u128::from(BALANCE_CHECK_GAS_PER_ACTION);
+        gas.checked_mul(BALANCE_CHECK_GAS_PRICE_YOCTO)
+            .and_then(|gas_cost| gas_cost.checked_add(deposit))
+            .and_then(|cost| total.checked_add(cost))
+            .ok_or(BalanceCheckError::AmountOverflow)
+    })
+}
+
+/// Total cost of a batch, one `ActionParams` list per transaction.
+pub fn batch_cost<'a>(
+    transactions: impl IntoIterator<Item = &'a [ActionParams]>,
+) -> Result<u128, BalanceCheckError> {
+    transactions
+        .into_iter()
+        .enumerate()
+        .try_fold(0u128, |total, (index, params)| {
+            let actions = build_actions_from_params(params.to_vec())
+                .map_err(|reason| BalanceCheckError::InvalidActions { index, reason })?;
+            total
+                .checked_add(transaction_cost(&actions)?)
+                .ok_or(BalanceCheckError::AmountOverflow)
+        })
+}
+
+/// `Ok(available)` when `account` can pay `required`.
+pub fn ensure_affordable(account: &AccountView, required: u128) -> Result<u128, BalanceCheckError> {
+    let available = account.available_balance();
+    if required > available {
+        return Err(BalanceCheckError::InsufficientBalance {
+            required,
+            available,
+        });
+    }
+    Ok(available)
+}
+
+/// Price the batch, then fetch the signer's account and compare. Returns `(required, available)`.
+pub async fn check_batch_balance<'a>(
+    rpc_url: &str,
+    account_id: &str,
+    transactions: impl IntoIterator<Item = &'a [ActionParams]>,
+) -> Result<(u128, u128), BalanceCheckError> {
+    let required = batch_cost(transactions)?;
+    let account = view_account(rpc_url, account_id).await?;
+    let available = ensure_affordable(&account, required)?;
+    Ok((required, available))
+}
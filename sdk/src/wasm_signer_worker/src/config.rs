@@ -22,17 +22,230 @@ pub const ED25519_HKDF_KEY_INFO: &str = "ed25519-signing-key-dual-prf-v1";
 /// Constant used for HKDF info when deriving KEK from WrapKeySeed
 pub const NEAR_KEK_INFO: &[u8] = b"near-kek";
 
+/// HKDF info prefix for credential-scoped KEKs; the WebAuthn credential id is appended
+pub const NEAR_KEK_CREDENTIAL_INFO_PREFIX: &[u8] = b"near-kek-credential-v3:";
+
+/// Envelope version for NEAR keys encrypted under the account-wide KEK (`NEAR_KEK_INFO`)
+pub const NEAR_KEY_ENVELOPE_VERSION_ACCOUNT: u8 = 2;
+
+/// Envelope version for NEAR keys encrypted under a credential-scoped KEK
+pub const NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL: u8 = 3;
+
+/// Envelope version for credential-scoped NEAR keys that also bind the account (and optional
+/// app context) as AEAD associated data; earlier versions decrypt without AAD
+pub const NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL_AAD: u8 = 4;
+
+/// Domain tag prefixed to the AEAD associated data of version 4 envelopes
+pub const NEAR_KEY_AAD_DOMAIN: &[u8] = b"near-sk-aad-v1";
+
+/// HKDF info for the key that MACs NEAR key envelopes (ciphertext, nonce, account id and
+/// public key), so a stored envelope can be checked without decrypting it
+pub const NEAR_KEY_ENVELOPE_INTEGRITY_INFO: &[u8] = b"near-key-envelope-integrity-v1";
+
+/// Size of the HMAC-SHA256 integrity tag stored with NEAR key envelopes
+pub const NEAR_KEY_ENVELOPE_INTEGRITY_MAC_SIZE: usize = 32;
+
+/// Version of the portable JSON key envelope (`key_envelope::EncryptedKeyEnvelope`). Decoders
+/// reject any other version instead of guessing at its layout
+pub const PORTABLE_KEY_ENVELOPE_VERSION: u8 = 1;
+
+/// `aead` identifier written into portable key envelopes
+pub const PORTABLE_KEY_ENVELOPE_AEAD: &str = "chacha20-poly1305";
+
+/// `kdf` identifier written into portable key envelopes: HKDF-SHA256 over WrapKeySeed with
+/// `NEAR_KEK_INFO`, the account-wide KEK
+pub const PORTABLE_KEY_ENVELOPE_KDF: &str = "hkdf-sha256:near-kek";
+
+/// HKDF info for deterministic ChaCha20 nonces, keyed by the KEK over
+/// `account_id || counter || plaintext`
+pub const DETERMINISTIC_NONCE_HKDF_INFO: &[u8] = b"chacha20-deterministic-nonce-v2";
+
 /// Maximum session duration in milliseconds (30 minutes)
 pub const SESSION_MAX_DURATION_MS: f64 = 30.0 * 60.0 * 1000.0;
 
+/// How long a signing request waits for the VRF worker to deliver WrapKeySeed
+/// (and PRF.second) over the attached MessagePort before failing.
+pub const WRAP_KEY_SEED_TIMEOUT_MS: u32 = 2000;
+
+/// Extra polls of the session maps after that timeout, for material whose port message was
+/// still queued when the wait gave up (slow devices).
+#[cfg(any(test, target_arch = "wasm32"))]
+pub const WRAP_KEY_SEED_RECHECK_ATTEMPTS: u32 = 3;
+
+/// Delay between those polls (ms).
+#[cfg(any(test, target_arch = "wasm32"))]
+pub const WRAP_KEY_SEED_RECHECK_INTERVAL_MS: u32 = 50;
+
+/// Grace period after the VRF side closes the WrapKeySeed port before waiters are failed, so a
+/// message posted just before the close is still delivered first (ms).
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub const WRAP_KEY_SEED_CLOSE_GRACE_MS: u32 = 20;
+
+// === NEAR RPC BROADCAST ===
+
+/// Maximum `tx` status polls in `executed` broadcast mode after `send_tx` times out.
+#[cfg(target_arch = "wasm32")]
+pub const BROADCAST_STATUS_POLL_MAX_ATTEMPTS: u32 = 10;
+
+/// Delay between `tx` status polls (ms).
+#[cfg(target_arch = "wasm32")]
+pub const BROADCAST_STATUS_POLL_INTERVAL_MS: u32 = 1500;
+
+// === NEP-413 MESSAGES ===
+
+/// Default cap on a NEP-413 message (UTF-8 bytes). Override per request with `maxMessageBytes`.
+pub const NEP413_DEFAULT_MAX_MESSAGE_BYTES: usize = 16 * 1024;
+
+/// Longest accepted NEP-413 recipient (account id or URL-like string).
+pub const NEP413_MAX_RECIPIENT_LEN: usize = 255;
+
+// === ACCOUNT CARDS ===
+
+/// Lifetime of a device-linking account card (10 minutes). Verification also rejects any
+/// card whose signed lifetime is longer than this.
+pub const ACCOUNT_CARD_TTL_MS: u64 = 10 * 60 * 1000;
+
+/// How far a card's `issuedAtMs` may run ahead of the verifier's clock.
+pub const ACCOUNT_CARD_MAX_CLOCK_SKEW_MS: u64 = 60 * 1000;
+
+/// Most signing operations a session-cached signing key may serve (`keyReusePolicy.maxOperations`
+/// is clamped to this).
+pub const SESSION_KEY_REUSE_MAX_OPERATIONS: u32 = 32;
+
+/// Longest a session-cached signing key may live (`keyReusePolicy.ttlMs` is clamped to this).
+pub const SESSION_KEY_REUSE_MAX_TTL_MS: u64 = 5 * 60 * 1000;
+
+// === AUTHENTICATOR SIGN COUNTER ===
+
+/// HKDF info for the key sealing exported `signCount` snapshots, derived from WrapKeySeed
+pub const SIGN_COUNTER_SNAPSHOT_INFO: &[u8] = b"sign-counter-snapshot-v1";
+
+/// Format version of sealed `signCount` snapshots
+pub const SIGN_COUNTER_SNAPSHOT_VERSION: u8 = 1;
+
+// === DELEGATE ACTIONS ===
+
+/// Blocks past the current height used when a delegate arrives with `maxBlockHeight == 0`.
+pub const DELEGATE_DEFAULT_EXPIRY_BLOCKS: u64 = 10_000;
+
+/// Default cap on how far above the current height a delegate may expire (~1 day of blocks).
+/// Override per request with `maxBlockHeightWindow`.
+pub const DELEGATE_DEFAULT_MAX_BLOCK_HEIGHT_WINDOW: u64 = 100_000;
+
+// === CONTRACT DEPLOYMENT ===
+
+/// Default size above which DeployContract code draws a warning (the protocol's 4 MiB contract
+/// size limit). Override per request with `contractCodeWarnBytes`.
+pub const CONTRACT_CODE_WARN_BYTES: usize = 4 * 1024 * 1024;
+
+// === TRANSACTION SIZE ===
+
+/// Largest borsh-serialized action list signed in one transaction (the protocol's 4 MiB
+/// `max_transaction_size`). Larger transactions are rejected before signing instead of at broadcast.
+pub const MAX_TX_SIZE_BYTES: usize = 4 * 1024 * 1024;
+
+// === INTENTS ===
+
+/// Domain tag prefixed to the borsh encoding of an `Intent` before hashing its digest
+pub const INTENT_DIGEST_DOMAIN: &[u8] = b"tatchi:intent:v1";
+
+// === THRESHOLD SESSION CLOCK ===
+
+/// Default early-refresh margin: a cached relayer session is treated as expired this long
+/// before its skew-corrected `expiresAt`. Override with `thresholdSessionRefreshMarginMs`.
+#[cfg(all(feature = "threshold", target_arch = "wasm32"))]
+pub const THRESHOLD_SESSION_REFRESH_MARGIN_MS: f64 = 30_000.0;
+
+/// Default clock-skew tolerance: the minimum error assumed on the relayer clock estimate,
+/// even right after a fast mint. Override with `thresholdSessionMaxClockSkewMs`.
+#[cfg(all(feature = "threshold", any(test, target_arch = "wasm32")))]
+pub const THRESHOLD_SESSION_MAX_CLOCK_SKEW_MS: f64 = 5_000.0;
+
+// === THRESHOLD RELAYER REQUEST AUTH ===
+
+/// Signature header for `requestAuth: { kind: "hmac-sha256" }` unless `headerName` is set.
+pub const THRESHOLD_REQUEST_AUTH_DEFAULT_HEADER: &str = "X-Relayer-Signature";
+/// Timestamp header for HMAC request auth unless `timestampHeaderName` is set.
+pub const THRESHOLD_REQUEST_AUTH_DEFAULT_TIMESTAMP_HEADER: &str = "X-Relayer-Timestamp";
+/// Shortest accepted HMAC request-auth secret (128 bits).
+pub const THRESHOLD_REQUEST_AUTH_MIN_SECRET_BYTES: usize = 16;
+/// Default window a relayer accepts signed request timestamps in, either side of its clock.
+pub const THRESHOLD_REQUEST_AUTH_MAX_SKEW_MS: f64 = 300_000.0;
+/// Client clocks before 2020-01-01 are treated as unset rather than signed with.
+pub const THRESHOLD_REQUEST_AUTH_MIN_TIMESTAMP_MS: u64 = 1_577_836_800_000;
+
+// === THRESHOLD RELAYER CONCURRENCY ===
+
+/// Default cap on relayer HTTP requests in flight at once, across all signing requests.
+/// Override with `maxConcurrentRelayerRequests`.
+pub const THRESHOLD_MAX_CONCURRENT_RELAYER_REQUESTS: u32 = 4;
+
+// === STATE CHANGE LOG ===
+
+/// State-change events kept for GET_STATE_CHANGES_SINCE; callers that fall further behind
+/// are told to resync.
+pub const STATE_CHANGE_LOG_CAPACITY: usize = 64;
+
+// === BALANCE CHECK ===
+
+/// yoctoNEAR staked per byte of account storage (nearcore `storage_amount_per_byte`).
+pub const STORAGE_AMOUNT_PER_BYTE_YOCTO: u128 = 10_000_000_000_000_000_000;
+/// Gas price the pre-sign balance check assumes: twice the protocol minimum of 100M yocto/gas,
+/// so a moderate price rise does not turn a passing check into an on-chain failure.
+pub const BALANCE_CHECK_GAS_PRICE_YOCTO: u128 = 200_000_000;
+/// Gas charged per action on top of any attached FunctionCall gas (send + exec fees).
+pub const BALANCE_CHECK_GAS_PER_ACTION: u64 = 5_000_000_000_000;
+
+// === TRANSACTION SUMMARY ===
+
+/// A caller-supplied gas price quoted more than this many blocks before the transaction's
+/// reference block is flagged `gasPriceStale` (the price moves by at most ~1% per block).
+pub const GAS_PRICE_STALE_AFTER_BLOCKS: u64 = 60;
+
+// === LEGACY KEY MIGRATION ===
+
+/// HKDF info the legacy `packages/passkey` worker used to derive its ChaCha20 key
+/// directly from PRF.first (no WrapKeySeed/KEK envelope).
+pub const LEGACY_CHACHA20_HKDF_INFO: &str = "chacha20-encryption-key-v1";
+
 // === ERROR MESSAGES ===
 
 /// Error message for invalid key size
 pub const ERROR_INVALID_KEY_SIZE: &str = "Invalid key size for ChaCha20Poly1305";
 
+/// Error for a delegate `maxBlockHeight` that is zero, already passed, or too far ahead
+pub const ERROR_MAX_BLOCK_HEIGHT_OUT_OF_RANGE: &str = "max_block_height out of range";
+
+/// Error returned when a `CANCEL_OPERATION` interrupts a WrapKeySeed / PRF.second wait
+#[cfg(target_arch = "wasm32")]
+pub const ERROR_OPERATION_CANCELLED: &str = "operation cancelled while awaiting key material";
+
+/// Error pending WrapKeySeed / PRF.second waiters and duplicate requests settle with when
+/// `CLEAR_ALL_SESSIONS` tears the worker's sessions down
+#[cfg(any(test, target_arch = "wasm32"))]
+pub const ERROR_LOGGED_OUT: &str = "LoggedOut: signing sessions were cleared by CLEAR_ALL_SESSIONS";
+
 // === UTILITY FUNCTIONS ===
 
 /// Generate account-specific NEAR key derivation salt
 pub fn near_key_salt_for_account(account_id: &str) -> String {
     format!("near-key-derivation:{}", account_id)
 }
+
+/// AEAD associated data binding a NEAR key envelope to its account and optional app context.
+/// Fields are NUL-separated; NEAR account ids never contain NUL.
+pub fn near_key_aad_for_account(account_id: &str, app_context: Option<&str>) -> Vec<u8> {
+    let mut aad = NEAR_KEY_AAD_DOMAIN.to_vec();
+    aad.push(0);
+    aad.extend_from_slice(account_id.as_bytes());
+    if let Some(app_context) = app_context {
+        aad.push(0);
+        aad.extend_from_slice(app_context.as_bytes());
+    }
+    aad
+}
+
+/// Account-specific HKDF salt of the legacy `packages/passkey` ChaCha20 key
+pub fn legacy_chacha20_salt_for_account(account_id: &str) -> String {
+    format!("chacha20-salt:{}", account_id)
+}
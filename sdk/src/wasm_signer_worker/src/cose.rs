@@ -1,7 +1,10 @@
 use ciborium::Value as CborValue;
 use log::debug;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
 
-use crate::encoders::base64_url_decode;
+use crate::encoders::{base64_url_decode, base64_url_encode};
+use crate::error::{AttestationError, CoseKeyError};
 
 /// Parse WebAuthn attestation object to extract authData
 pub fn parse_attestation_object(attestation_object_bytes: &[u8]) -> Result<Vec<u8>, String> {
@@ -25,13 +28,37 @@ pub fn parse_attestation_object(attestation_object_bytes: &[u8]) -> Result<Vec<u
     }
 }
 
-/// Parse authenticator data to extract COSE public key
-pub fn parse_authenticator_data(auth_data_bytes: &[u8]) -> Result<Vec<u8>, String> {
+/// Fixed authenticator data header: rpIdHash(32) + flags(1) + signCount(4, big-endian).
+struct AuthenticatorDataHeader {
+    flags: u8,
+    sign_count: u32,
+}
+
+fn parse_authenticator_data_header(
+    auth_data_bytes: &[u8],
+) -> Result<AuthenticatorDataHeader, String> {
     if auth_data_bytes.len() < 37 {
         return Err("Authenticator data too short".to_string());
     }
+    Ok(AuthenticatorDataHeader {
+        flags: auth_data_bytes[32],
+        sign_count: u32::from_be_bytes([
+            auth_data_bytes[33],
+            auth_data_bytes[34],
+            auth_data_bytes[35],
+            auth_data_bytes[36],
+        ]),
+    })
+}
 
-    let flags = auth_data_bytes[32];
+/// Parse authenticator data (registration or assertion) to extract the signature counter
+pub fn parse_authenticator_data_sign_count(auth_data_bytes: &[u8]) -> Result<u32, String> {
+    parse_authenticator_data_header(auth_data_bytes).map(|header| header.sign_count)
+}
+
+/// Parse authenticator data to extract COSE public key
+pub fn parse_authenticator_data(auth_data_bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let flags = parse_authenticator_data_header(auth_data_bytes)?.flags;
 
     // Check if attested credential data is present (AT flag = bit 6)
     if (flags & 0x40) == 0 {
@@ -87,3 +114,225 @@ pub fn extract_cose_public_key_from_attestation(
     );
     Ok(cose_public_key_bytes)
 }
+
+/// COSE key parameter labels (RFC 9053).
+const COSE_KEY_KTY: i128 = 1;
+const COSE_KTY_EC2: i128 = 2;
+const COSE_EC2_CRV: i128 = -1;
+const COSE_EC2_X: i128 = -2;
+const COSE_EC2_Y: i128 = -3;
+
+/// NIST curve of an EC2 COSE key (`crv`).
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ec2Curve {
+    #[serde(rename = "P-256")]
+    P256,
+    #[serde(rename = "P-384")]
+    P384,
+    #[serde(rename = "P-521")]
+    P521,
+}
+
+impl Ec2Curve {
+    fn from_cose(crv: i128) -> Result<Self, CoseKeyError> {
+        match crv {
+            1 => Ok(Ec2Curve::P256),
+            2 => Ok(Ec2Curve::P384),
+            3 => Ok(Ec2Curve::P521),
+            other => Err(CoseKeyError::UnsupportedCurve(other)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Ec2Curve::P256 => "P-256",
+            Ec2Curve::P384 => "P-384",
+            Ec2Curve::P521 => "P-521",
+        }
+    }
+
+    /// Byte length of each affine coordinate.
+    pub fn coordinate_len(self) -> usize {
+        match self {
+            Ec2Curve::P256 => 32,
+            Ec2Curve::P384 => 48,
+            Ec2Curve::P521 => 66,
+        }
+    }
+}
+
+/// Affine public key coordinates of an EC2 (ES256/ES384/ES512) credential.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Ec2Coordinates {
+    pub crv: Ec2Curve,
+    pub x_b64u: String,
+    pub y_b64u: String,
+}
+
+/// Read `crv`, `x` and `y` from a COSE_Key. Each coordinate must be exactly the curve's size.
+/// Bytes after the key (authenticator extensions) are ignored.
+pub fn extract_ec2_coordinates(cose_key_bytes: &[u8]) -> Result<Ec2Coordinates, CoseKeyError> {
+    let cbor_value: CborValue = ciborium::from_reader(cose_key_bytes)
+        .map_err(|e| CoseKeyError::MalformedCbor(e.to_string()))?;
+    let CborValue::Map(map) = cbor_value else {
+        return Err(CoseKeyError::NotCborMap);
+    };
+    let parameter = |label: i128| {
+        map.iter()
+            .find(|(k, _)| matches!(k, CborValue::Integer(i) if i128::from(*i) == label))
+            .map(|(_, v)| v)
+    };
+    let integer = |label: i128, name: &'static str| match parameter(label) {
+        Some(CborValue::Integer(i)) => Ok(i128::from(*i)),
+        Some(_) => Err(CoseKeyError::WrongParameterType {
+            parameter: name,
+            expected: "an integer",
+        }),
+        None => Err(CoseKeyError::MissingParameter(name)),
+    };
+
+    let kty = integer(COSE_KEY_KTY, "kty")?;
+    if kty != COSE_KTY_EC2 {
+        return Err(CoseKeyError::NotEc2 { kty });
+    }
+    let crv = Ec2Curve::from_cose(integer(COSE_EC2_CRV, "crv")?)?;
+
+    let coordinate = |label: i128, name: &'static str| match parameter(label) {
+        Some(CborValue::Bytes(bytes)) if bytes.len() == crv.coordinate_len() => {
+            Ok(base64_url_encode(bytes))
+        }
+        Some(CborValue::Bytes(bytes)) => Err(CoseKeyError::InvalidCoordinateLength {
+            coordinate: name,
+            curve: crv.name(),
+            expected: crv.coordinate_len(),
+            actual: bytes.len(),
+        }),
+        Some(_) => Err(CoseKeyError::WrongParameterType {
+            parameter: name,
+            expected: "a byte string",
+        }),
+        None => Err(CoseKeyError::MissingParameter(name)),
+    };
+    Ok(Ec2Coordinates {
+        crv,
+        x_b64u: coordinate(COSE_EC2_X, "x")?,
+        y_b64u: coordinate(COSE_EC2_Y, "y")?,
+    })
+}
+
+/// EC2 coordinates of the credential public key in a base64url attestation object.
+pub fn extract_ec2_coordinates_from_attestation(
+    attestation_object_b64u: &str,
+) -> Result<Ec2Coordinates, String> {
+    let cose_public_key_bytes = extract_cose_public_key_from_attestation(attestation_object_b64u)?;
+    extract_ec2_coordinates(&cose_public_key_bytes).map_err(String::from)
+}
+
+/// Structural summary of a well-formed attestation object.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationObjectSummary {
+    pub fmt: String,
+    pub has_auth_data: bool,
+    pub has_att_stmt: bool,
+    pub credential_id_length: u16,
+}
+
+/// Validate an attestation object's structure without extracting key material.
+/// Reports the first structural problem: CBOR framing, required fields, then authData layout.
+pub fn validate_attestation_object_bytes(
+    attestation_object_bytes: &[u8],
+) -> Result<AttestationObjectSummary, AttestationError> {
+    let initial_byte = *attestation_object_bytes
+        .first()
+        .ok_or(AttestationError::Empty)?;
+    // Major type 5 (map) occupies the top three bits.
+    if initial_byte >> 5 != 5 {
+        return Err(AttestationError::NotCborMap { initial_byte });
+    }
+
+    let mut reader = attestation_object_bytes;
+    let cbor_value: CborValue = ciborium::from_reader(&mut reader).map_err(|e| match e {
+        ciborium::de::Error::Io(io) if io.kind() == std::io::ErrorKind::UnexpectedEof => {
+            AttestationError::TruncatedCbor
+        }
+        other => AttestationError::MalformedCbor(other.to_string()),
+    })?;
+    if !reader.is_empty() {
+        return Err(AttestationError::TrailingBytes {
+            count: reader.len(),
+        });
+    }
+    let CborValue::Map(map) = cbor_value else {
+        return Err(AttestationError::NotCborMap { initial_byte });
+    };
+
+    let field = |name: &str| {
+        map.iter()
+            .find(|(k, _)| matches!(k, CborValue::Text(t) if t == name))
+            .map(|(_, v)| v)
+    };
+
+    let fmt = match field("fmt") {
+        Some(CborValue::Text(fmt)) => fmt.clone(),
+        Some(_) => {
+            return Err(AttestationError::WrongFieldType {
+                field: "fmt",
+                expected: "a text string",
+            })
+        }
+        None => return Err(AttestationError::MissingField("fmt")),
+    };
+    let has_att_stmt = match field("attStmt") {
+        Some(CborValue::Map(_)) => true,
+        Some(_) => {
+            return Err(AttestationError::WrongFieldType {
+                field: "attStmt",
+                expected: "a map",
+            })
+        }
+        None => false,
+    };
+    let auth_data = match field("authData") {
+        Some(CborValue::Bytes(bytes)) => bytes,
+        Some(_) => {
+            return Err(AttestationError::WrongFieldType {
+                field: "authData",
+                expected: "a byte string",
+            })
+        }
+        None => return Err(AttestationError::MissingField("authData")),
+    };
+
+    let cose_public_key =
+        parse_authenticator_data(auth_data).map_err(AttestationError::InvalidAuthData)?;
+    if cose_public_key.is_empty() {
+        return Err(AttestationError::InvalidAuthData(
+            "credential public key is missing".to_string(),
+        ));
+    }
+    // rpIdHash(32) + flags(1) + counter(4) + AAGUID(16), then the 2-byte length.
+    let credential_id_length = u16::from_be_bytes([auth_data[53], auth_data[54]]);
+
+    Ok(AttestationObjectSummary {
+        fmt,
+        has_auth_data: true,
+        has_att_stmt,
+        credential_id_length,
+    })
+}
+
+/// Structurally validate a base64url attestation object before COSE key extraction.
+/// Returns `{ fmt, hasAuthData, hasAttStmt, credentialIdLength }`, or `{ code, message }`
+/// naming the first structural problem.
+#[wasm_bindgen]
+pub fn validate_attestation_object(
+    attestation_object_base64url: String,
+) -> Result<JsValue, JsValue> {
+    let attestation_object_bytes = base64_url_decode(&attestation_object_base64url)
+        .map_err(AttestationError::InvalidBase64)?;
+    let summary = validate_attestation_object_bytes(&attestation_object_bytes)?;
+    serde_wasm_bindgen::to_value(&summary)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize attestation summary: {}", e)))
+}
@@ -0,0 +1,49 @@
+//! Binding of a WebAuthn assertion to the relying party it was made for.
+//!
+//! `authenticatorData` starts with `sha256(rpId)`. Security keys first registered through the
+//! legacy U2F `appid` flow (and later imported) keep hashing the appid instead, so recovery can
+//! opt in to a caller-supplied allowlist of appids. An empty allowlist accepts the rp id only.
+
+use sha2::{Digest, Sha256};
+
+use crate::error::CredentialBindingError;
+
+/// Length of the `rpIdHash` prefix of `authenticatorData`.
+const RP_ID_HASH_LEN: usize = 32;
+
+/// How an assertion's `rpIdHash` matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CredentialBinding {
+    /// The hash matched an allowlisted appid rather than the rp id; the credential should be
+    /// re-registered under the rp id.
+    pub used_legacy_app_id: bool,
+}
+
+/// Check that `authenticator_data` was produced for `rp_id`, or for one of `allowed_app_ids`.
+pub fn validate_credential_binding(
+    authenticator_data: &[u8],
+    rp_id: &str,
+    allowed_app_ids: &[String],
+) -> Result<CredentialBinding, CredentialBindingError> {
+    let rp_id_hash = authenticator_data
+        .get(..RP_ID_HASH_LEN)
+        .ok_or(CredentialBindingError::AuthenticatorDataTooShort)?;
+
+    if rp_id_hash == Sha256::digest(rp_id.as_bytes()).as_slice() {
+        return Ok(CredentialBinding {
+            used_legacy_app_id: false,
+        });
+    }
+    if allowed_app_ids
+        .iter()
+        .any(|app_id| rp_id_hash == Sha256::digest(app_id.as_bytes()).as_slice())
+    {
+        return Ok(CredentialBinding {
+            used_legacy_app_id: true,
+        });
+    }
+    Err(CredentialBindingError::RpIdHashMismatch {
+        rp_id: rp_id.to_string(),
+        allowed_app_ids: allowed_app_ids.len(),
+    })
+}
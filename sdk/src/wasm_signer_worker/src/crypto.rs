@@ -1,18 +1,21 @@
 use bs58;
-use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
 use chacha20poly1305::{ChaCha20Poly1305, Nonce};
 use getrandom::getrandom;
+use hkdf::hmac::{Hmac, Mac};
 use hkdf::Hkdf;
 use log::debug;
 use sha2::Sha256;
+use zeroize::Zeroizing;
 
 use crate::config::{
-    near_key_salt_for_account, CHACHA20_KEY_SIZE, CHACHA20_NONCE_SIZE, ED25519_HKDF_KEY_INFO,
-    ED25519_PRIVATE_KEY_SIZE, ERROR_INVALID_KEY_SIZE,
+    near_key_salt_for_account, CHACHA20_KEY_SIZE, CHACHA20_NONCE_SIZE,
+    DETERMINISTIC_NONCE_HKDF_INFO, ED25519_HKDF_KEY_INFO, ED25519_PRIVATE_KEY_SIZE,
+    ERROR_INVALID_KEY_SIZE,
 };
 use crate::encoders::{base64_url_decode, base64_url_encode};
 use crate::error::KdfError;
-use crate::types::EncryptedDataChaCha20Response;
+use crate::types::{EncryptedDataChaCha20Response, SelectedKeyEnvelope};
 
 /// Ephemeral wrap key material derived in the VRF worker and delivered to the signer.
 /// Holds the base64url-encoded WrapKeySeed and its salt, and exposes a helper to derive KEK.
@@ -29,6 +32,96 @@ impl WrapKey {
             .map_err(|e| format!("WrapKeySeed → KEK derivation failed: {}", e))
     }
 
+    /// Derive the KEK for a version 3 envelope, bound to one WebAuthn credential id.
+    pub fn derive_kek_for_credential(&self, credential_id: &str) -> Result<Vec<u8>, String> {
+        if credential_id.is_empty() {
+            return Err("WrapKeySeed → KEK derivation failed: empty credential id".to_string());
+        }
+        let mut info = crate::config::NEAR_KEK_CREDENTIAL_INFO_PREFIX.to_vec();
+        info.extend_from_slice(credential_id.as_bytes());
+        derive_kek_with_info(&self.wrap_key_seed, &self.wrap_key_salt, &info)
+            .map_err(|e| format!("WrapKeySeed → KEK derivation failed: {}", e))
+    }
+
+    /// Derive the key that seals `signCount` snapshots (`sign_counter`) for this account.
+    pub fn derive_sign_counter_snapshot_key(&self) -> Result<Vec<u8>, String> {
+        derive_kek_with_info(
+            &self.wrap_key_seed,
+            &self.wrap_key_salt,
+            crate::config::SIGN_COUNTER_SNAPSHOT_INFO,
+        )
+        .map_err(|e| format!("WrapKeySeed → snapshot key derivation failed: {}", e))
+    }
+
+    /// Derive the key that MACs NEAR key envelopes (`near_key_envelope_integrity_mac`).
+    pub fn derive_envelope_integrity_key(&self) -> Result<Vec<u8>, String> {
+        derive_kek_with_info(
+            &self.wrap_key_seed,
+            &self.wrap_key_salt,
+            crate::config::NEAR_KEY_ENVELOPE_INTEGRITY_INFO,
+        )
+        .map_err(|e| format!("WrapKeySeed → integrity key derivation failed: {}", e))
+    }
+
+    /// Integrity tag (base64url) stored next to a freshly encrypted envelope; see
+    /// `near_key_envelope_integrity_mac`.
+    pub fn near_key_envelope_integrity_mac_b64u(
+        &self,
+        encrypted_private_key_data_b64u: &str,
+        chacha20_nonce_b64u: &str,
+        near_account_id: &str,
+        public_key: &str,
+    ) -> Result<String, String> {
+        let key = self.derive_envelope_integrity_key()?;
+        let mac = near_key_envelope_integrity_mac(
+            &key,
+            encrypted_private_key_data_b64u,
+            chacha20_nonce_b64u,
+            near_account_id,
+            public_key,
+        )?;
+        Ok(base64_url_encode(&mac))
+    }
+
+    /// Encrypt a NEAR private key as a version 4 envelope: credential-scoped KEK, with the
+    /// account id (and optional app context) authenticated as AAD.
+    pub fn encrypt_near_key_for_credential(
+        &self,
+        near_private_key: &str,
+        credential_id: &str,
+        near_account_id: &str,
+        aad_context: Option<&str>,
+    ) -> Result<EncryptedDataChaCha20Response, String> {
+        let kek = self.derive_kek_for_credential(credential_id)?;
+        let aad = crate::config::near_key_aad_for_account(near_account_id, aad_context);
+        encrypt_data_chacha20_with_aad(near_private_key, &kek, &aad)
+            .map_err(|e| format!("Failed to encrypt private key: {}", e))
+    }
+
+    /// Decrypt a NEAR key envelope of any version into the private key string.
+    /// Version 4 envelopes moved to another account (or app context) fail authentication here.
+    pub fn decrypt_near_key_envelope(
+        &self,
+        envelope: &SelectedKeyEnvelope<'_>,
+        near_account_id: &str,
+    ) -> Result<String, String> {
+        let kek = match envelope.credential_id {
+            Some(credential_id) => self.derive_kek_for_credential(credential_id)?,
+            None => self.derive_kek()?,
+        };
+        let aad = if envelope.version >= crate::config::NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL_AAD {
+            crate::config::near_key_aad_for_account(near_account_id, envelope.aad_context)
+        } else {
+            Vec::new()
+        };
+        decrypt_data_chacha20_with_aad(
+            envelope.encrypted_private_key_data,
+            envelope.encrypted_private_key_chacha20_nonce_b64u,
+            &kek,
+            &aad,
+        )
+    }
+
     /// Return the base64url-encoded wrap_key_salt associated with this wrap key.
     pub fn salt_b64u(&self) -> &str {
         &self.wrap_key_salt
@@ -48,6 +141,18 @@ impl std::fmt::Debug for WrapKey {
 pub(crate) fn derive_kek_from_wrap_key_seed(
     wrap_key_seed_b64u: &str,
     wrap_key_salt_b64u: &str,
+) -> Result<Vec<u8>, KdfError> {
+    derive_kek_with_info(
+        wrap_key_seed_b64u,
+        wrap_key_salt_b64u,
+        crate::config::NEAR_KEK_INFO,
+    )
+}
+
+fn derive_kek_with_info(
+    wrap_key_seed_b64u: &str,
+    wrap_key_salt_b64u: &str,
+    info: &[u8],
 ) -> Result<Vec<u8>, KdfError> {
     let wrap_key_seed = base64_url_decode(wrap_key_seed_b64u)?;
     if wrap_key_seed.is_empty() {
@@ -56,17 +161,111 @@ pub(crate) fn derive_kek_from_wrap_key_seed(
     let wrap_key_salt = base64_url_decode(wrap_key_salt_b64u)?;
     let hk = Hkdf::<Sha256>::new(Some(&wrap_key_salt), &wrap_key_seed);
     let mut kek = vec![0u8; CHACHA20_KEY_SIZE];
-    hk.expand(crate::config::NEAR_KEK_INFO, &mut kek)
-        .map_err(|_| KdfError::HkdfError)?;
+    hk.expand(info, &mut kek).map_err(|_| KdfError::HkdfError)?;
     Ok(kek)
 }
 
+// === ENVELOPE INTEGRITY ===
+
+fn near_key_envelope_integrity_hmac(
+    key: &[u8],
+    encrypted_private_key_data_b64u: &str,
+    chacha20_nonce_b64u: &str,
+    near_account_id: &str,
+    public_key: &str,
+) -> Result<Hmac<Sha256>, String> {
+    let ciphertext = base64_url_decode(encrypted_private_key_data_b64u)
+        .map_err(|e| format!("Base64 decode error for encrypted data: {}", e))?;
+    let nonce = base64_url_decode(chacha20_nonce_b64u)
+        .map_err(|e| format!("Base64 decode error for ChaCha20 nonce: {}", e))?;
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key)
+        .map_err(|_| ERROR_INVALID_KEY_SIZE.to_string())?;
+    // Length-prefix each field so the variable-length account id and public key cannot shift
+    // bytes into one another.
+    for field in [
+        ciphertext.as_slice(),
+        nonce.as_slice(),
+        near_account_id.as_bytes(),
+        public_key.as_bytes(),
+    ] {
+        mac.update(&(field.len() as u32).to_le_bytes());
+        mac.update(field);
+    }
+    Ok(mac)
+}
+
+/// HMAC-SHA256 over (ciphertext || nonce || accountId || publicKey) of a NEAR key envelope,
+/// keyed by `WrapKey::derive_envelope_integrity_key`. Lets a session confirm that a stored
+/// envelope still belongs to `near_account_id` / `public_key` without decrypting it.
+pub(crate) fn near_key_envelope_integrity_mac(
+    key: &[u8],
+    encrypted_private_key_data_b64u: &str,
+    chacha20_nonce_b64u: &str,
+    near_account_id: &str,
+    public_key: &str,
+) -> Result<Vec<u8>, String> {
+    let mac = near_key_envelope_integrity_hmac(
+        key,
+        encrypted_private_key_data_b64u,
+        chacha20_nonce_b64u,
+        near_account_id,
+        public_key,
+    )?;
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+/// Constant-time check of a tag produced by `near_key_envelope_integrity_mac`.
+pub(crate) fn verify_near_key_envelope_integrity_mac(
+    key: &[u8],
+    encrypted_private_key_data_b64u: &str,
+    chacha20_nonce_b64u: &str,
+    near_account_id: &str,
+    public_key: &str,
+    expected_mac: &[u8],
+) -> Result<bool, String> {
+    let mac = near_key_envelope_integrity_hmac(
+        key,
+        encrypted_private_key_data_b64u,
+        chacha20_nonce_b64u,
+        near_account_id,
+        public_key,
+    )?;
+    Ok(mac.verify_slice(expected_mac).is_ok())
+}
+
 // === CHACHA20POLY1305 ENCRYPTION/DECRYPTION ===
 
 /// Encrypt data using ChaCha20Poly1305
 pub(crate) fn encrypt_data_chacha20(
     plain_text_data_str: &str,
     key_bytes: &[u8],
+) -> Result<EncryptedDataChaCha20Response, String> {
+    encrypt_data_chacha20_with_aad(plain_text_data_str, key_bytes, &[])
+}
+
+/// Encrypt data using ChaCha20Poly1305, authenticating `aad` alongside the ciphertext
+pub(crate) fn encrypt_data_chacha20_with_aad(
+    plain_text_data_str: &str,
+    key_bytes: &[u8],
+    aad: &[u8],
+) -> Result<EncryptedDataChaCha20Response, String> {
+    if key_bytes.len() != CHACHA20_KEY_SIZE {
+        return Err(ERROR_INVALID_KEY_SIZE.to_string());
+    }
+
+    crate::randomness::ensure_randomness_ok()?;
+    let mut nonce_bytes = [0u8; CHACHA20_NONCE_SIZE];
+    getrandom(&mut nonce_bytes).map_err(|e| format!("Failed to generate nonce: {}", e))?;
+    encrypt_data_chacha20_with_aad_and_nonce(plain_text_data_str, key_bytes, aad, &nonce_bytes)
+}
+
+/// Encrypt with a caller-chosen nonce (see `derive_deterministic_chacha20_nonce`). The caller
+/// is responsible for never reusing `nonce_bytes` under the same key.
+pub(crate) fn encrypt_data_chacha20_with_aad_and_nonce(
+    plain_text_data_str: &str,
+    key_bytes: &[u8],
+    aad: &[u8],
+    nonce_bytes: &[u8; CHACHA20_NONCE_SIZE],
 ) -> Result<EncryptedDataChaCha20Response, String> {
     if key_bytes.len() != CHACHA20_KEY_SIZE {
         return Err(ERROR_INVALID_KEY_SIZE.to_string());
@@ -74,27 +273,73 @@ pub(crate) fn encrypt_data_chacha20(
 
     let key = chacha20poly1305::Key::from_slice(key_bytes);
     let cipher = ChaCha20Poly1305::new(key);
-
-    let mut nonce_bytes = [0u8; 12];
-    getrandom(&mut nonce_bytes).map_err(|e| format!("Failed to generate nonce: {}", e))?;
-    let nonce = Nonce::from_slice(&nonce_bytes);
+    let nonce = Nonce::from_slice(nonce_bytes);
 
     let ciphertext = cipher
-        .encrypt(nonce, plain_text_data_str.as_bytes())
+        .encrypt(
+            nonce,
+            Payload {
+                msg: plain_text_data_str.as_bytes(),
+                aad,
+            },
+        )
         .map_err(|e| format!("Encryption error: {}", e))?;
 
     Ok(EncryptedDataChaCha20Response {
         encrypted_near_key_data_b64u: base64_url_encode(&ciphertext),
-        chacha20_nonce_b64u: base64_url_encode(&nonce_bytes),
+        chacha20_nonce_b64u: base64_url_encode(nonce_bytes),
         wrap_key_salt_b64u: None,
     })
 }
 
+/// Deterministic ChaCha20 nonce: HKDF-SHA256 keyed by the encryption key (as salt) over
+/// `len(account_id) || account_id || counter || plaintext` (big-endian lengths/counter).
+///
+/// Binding the key and plaintext means two encryptions share a (key, nonce) pair only when
+/// they also share the plaintext, so a repeated or reset counter (e.g. the first re-encryption
+/// after a key rotation) cannot leak anything beyond plaintext equality. Distinct counters
+/// still give distinct nonces for one key and plaintext.
+pub(crate) fn derive_deterministic_chacha20_nonce(
+    key_bytes: &[u8],
+    account_id: &str,
+    counter: u64,
+    plain_text: &[u8],
+) -> Result<[u8; CHACHA20_NONCE_SIZE], String> {
+    if key_bytes.len() != CHACHA20_KEY_SIZE {
+        return Err(ERROR_INVALID_KEY_SIZE.to_string());
+    }
+    if account_id.is_empty() {
+        return Err("Deterministic nonce requires an account id".to_string());
+    }
+    let mut ikm = Zeroizing::new(Vec::with_capacity(
+        8 + account_id.len() + 8 + plain_text.len(),
+    ));
+    ikm.extend_from_slice(&(account_id.len() as u64).to_be_bytes());
+    ikm.extend_from_slice(account_id.as_bytes());
+    ikm.extend_from_slice(&counter.to_be_bytes());
+    ikm.extend_from_slice(plain_text);
+    let hk = Hkdf::<Sha256>::new(Some(key_bytes), &ikm);
+    let mut nonce = [0u8; CHACHA20_NONCE_SIZE];
+    hk.expand(DETERMINISTIC_NONCE_HKDF_INFO, &mut nonce)
+        .map_err(|_| "Deterministic nonce derivation failed".to_string())?;
+    Ok(nonce)
+}
+
 /// Decrypt data using ChaCha20Poly1305
 pub(crate) fn decrypt_data_chacha20(
     encrypted_data_b64u: &str,
     chacha20_nonce_b64u: &str,
     key_bytes: &[u8],
+) -> Result<String, String> {
+    decrypt_data_chacha20_with_aad(encrypted_data_b64u, chacha20_nonce_b64u, key_bytes, &[])
+}
+
+/// Decrypt data using ChaCha20Poly1305; fails authentication unless `aad` matches encryption
+pub(crate) fn decrypt_data_chacha20_with_aad(
+    encrypted_data_b64u: &str,
+    chacha20_nonce_b64u: &str,
+    key_bytes: &[u8],
+    aad: &[u8],
 ) -> Result<String, String> {
     if key_bytes.len() != CHACHA20_KEY_SIZE {
         return Err(ERROR_INVALID_KEY_SIZE.to_string());
@@ -117,7 +362,13 @@ pub(crate) fn decrypt_data_chacha20(
         .map_err(|e| format!("Base64 decode error for encrypted data: {}", e))?;
 
     let decrypted_bytes = cipher
-        .decrypt(nonce, encrypted_data.as_slice())
+        .decrypt(
+            nonce,
+            Payload {
+                msg: encrypted_data.as_slice(),
+                aad,
+            },
+        )
         .map_err(|e| format!("Decryption error: {}", e))?;
 
     String::from_utf8(decrypted_bytes).map_err(|e| format!("UTF-8 decoding error: {}", e))
@@ -0,0 +1,242 @@
+//! Per-account registry of the devices (passkey + derived NEAR key) set up by this worker.
+//!
+//! Device numbers identify each device of an account: 1 is the original device, 2+ are
+//! linked devices. Deriving under one number and registering under another leaves the key
+//! unrecoverable, so the derive and Device2 handlers assign numbers here. When an RPC URL is
+//! available the contract's authenticator list is the source of truth for which numbers are
+//! taken; otherwise the local registry is.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::error::DeviceRegistryError;
+
+/// Contract view listing an account's authenticators as `[credentialId, authenticator][]`.
+pub const GET_AUTHENTICATORS_BY_USER_METHOD: &str = "get_authenticators_by_user";
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceRecord {
+    #[wasm_bindgen(js_name = "deviceNumber")]
+    pub device_number: u8,
+    #[wasm_bindgen(getter_with_clone, js_name = "publicKey")]
+    pub public_key: String,
+    #[wasm_bindgen(getter_with_clone, js_name = "credentialId")]
+    pub credential_id: String,
+    #[wasm_bindgen(js_name = "registeredAtMs")]
+    pub registered_at_ms: f64,
+}
+
+/// The part of a contract `get_authenticators_by_user` entry the registry needs.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ContractAuthenticatorView {
+    pub device_number: u8,
+}
+
+#[derive(Serialize)]
+struct GetAuthenticatorsByUserArgs<'a> {
+    user_id: &'a str,
+}
+
+/// Device records keyed by account, then device number.
+#[derive(Default)]
+pub struct DeviceRegistry {
+    accounts: HashMap<String, BTreeMap<u8, DeviceRecord>>,
+}
+
+impl DeviceRegistry {
+    /// Records for the account, ordered by device number.
+    pub fn records(&self, near_account_id: &str) -> Vec<DeviceRecord> {
+        self.accounts
+            .get(near_account_id)
+            .map(|devices| devices.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// One past the highest device number recorded locally (1 for a new account).
+    pub fn next_free_device_number(&self, near_account_id: &str) -> Result<u8, DeviceRegistryError> {
+        let highest = self
+            .accounts
+            .get(near_account_id)
+            .and_then(|devices| devices.keys().next_back().copied());
+        next_after(near_account_id, highest)
+    }
+
+    /// Reject an explicit device number already held by a different credential, unless `force`.
+    pub fn check_available(
+        &self,
+        near_account_id: &str,
+        device_number: u8,
+        credential_id: &str,
+        force: bool,
+    ) -> Result<(), DeviceRegistryError> {
+        if device_number == 0 {
+            return Err(DeviceRegistryError::InvalidDeviceNumber(device_number));
+        }
+        let existing = self
+            .accounts
+            .get(near_account_id)
+            .and_then(|devices| devices.get(&device_number));
+        match existing {
+            Some(existing) if existing.credential_id != credential_id && !force => {
+                Err(DeviceRegistryError::DeviceNumberConflict {
+                    near_account_id: near_account_id.to_string(),
+                    device_number,
+                    existing_credential_id: existing.credential_id.clone(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Explicit device number if it is free (or `force`), otherwise the next free one.
+    pub fn assign_device_number(
+        &self,
+        near_account_id: &str,
+        requested: Option<u8>,
+        credential_id: &str,
+        force: bool,
+    ) -> Result<u8, DeviceRegistryError> {
+        match requested {
+            Some(device_number) => {
+                self.check_available(near_account_id, device_number, credential_id, force)?;
+                Ok(device_number)
+            }
+            None => self.next_free_device_number(near_account_id),
+        }
+    }
+
+    /// Record a device, replacing whatever held its number and any earlier record for the
+    /// same credential (a credential belongs to exactly one device).
+    pub fn insert(&mut self, near_account_id: &str, record: DeviceRecord) {
+        let devices = self.accounts.entry(near_account_id.to_string()).or_default();
+        devices.retain(|_, existing| existing.credential_id != record.credential_id);
+        devices.insert(record.device_number, record);
+    }
+
+    /// Align local records with the contract's authenticator list and return the next free
+    /// device number according to the contract.
+    ///
+    /// Local records whose credential the contract lists under another number are re-keyed to
+    /// the contract's number. Records for credentials the contract does not know yet are kept
+    /// unless a contract-confirmed record takes their number.
+    pub fn reconcile_with_contract(
+        &mut self,
+        near_account_id: &str,
+        authenticators: &[(String, ContractAuthenticatorView)],
+    ) -> Result<u8, DeviceRegistryError> {
+        if let Some(devices) = self.accounts.get_mut(near_account_id) {
+            let (mut confirmed, unconfirmed): (Vec<DeviceRecord>, Vec<DeviceRecord>) =
+                std::mem::take(devices)
+                    .into_values()
+                    .partition(|record| {
+                        authenticators
+                            .iter()
+                            .any(|(credential_id, _)| *credential_id == record.credential_id)
+                    });
+            for record in confirmed.iter_mut() {
+                if let Some((_, authenticator)) = authenticators
+                    .iter()
+                    .find(|(credential_id, _)| *credential_id == record.credential_id)
+                {
+                    record.device_number = authenticator.device_number;
+                }
+            }
+            for record in unconfirmed.into_iter().chain(confirmed) {
+                devices.insert(record.device_number, record);
+            }
+        }
+        let highest = authenticators
+            .iter()
+            .map(|(_, authenticator)| authenticator.device_number)
+            .max();
+        next_after(near_account_id, highest)
+    }
+}
+
+fn next_after(near_account_id: &str, highest: Option<u8>) -> Result<u8, DeviceRegistryError> {
+    match highest {
+        None => Ok(1),
+        Some(n) => n
+            .checked_add(1)
+            .ok_or_else(|| DeviceRegistryError::DeviceNumbersExhausted {
+                near_account_id: near_account_id.to_string(),
+            }),
+    }
+}
+
+thread_local! {
+    static DEVICE_REGISTRY: RefCell<DeviceRegistry> = RefCell::new(DeviceRegistry::default());
+}
+
+pub(crate) fn device_records(near_account_id: &str) -> Vec<DeviceRecord> {
+    DEVICE_REGISTRY.with(|registry| registry.borrow().records(near_account_id))
+}
+
+pub(crate) fn assign_device_number(
+    near_account_id: &str,
+    requested: Option<u8>,
+    credential_id: &str,
+    force: bool,
+) -> Result<u8, DeviceRegistryError> {
+    DEVICE_REGISTRY.with(|registry| {
+        registry
+            .borrow()
+            .assign_device_number(near_account_id, requested, credential_id, force)
+    })
+}
+
+pub(crate) fn record_device(near_account_id: &str, record: DeviceRecord) {
+    DEVICE_REGISTRY.with(|registry| registry.borrow_mut().insert(near_account_id, record));
+}
+
+/// Next free device number for linking a new device. With an RPC URL the contract's
+/// authenticator list decides (and local records are reconciled against it); without one,
+/// the local registry does.
+pub(crate) async fn next_device_number_for_link(
+    near_account_id: &str,
+    contract_id: &str,
+    rpc_url: Option<&str>,
+) -> Result<u8, String> {
+    let Some(rpc_url) = rpc_url.map(str::trim).filter(|url| !url.is_empty()) else {
+        return DEVICE_REGISTRY
+            .with(|registry| registry.borrow().next_free_device_number(near_account_id))
+            .map_err(String::from);
+    };
+    let authenticators: Vec<(String, ContractAuthenticatorView)> =
+        crate::rpc_calls::view_function_call(
+            rpc_url,
+            contract_id,
+            GET_AUTHENTICATORS_BY_USER_METHOD,
+            &GetAuthenticatorsByUserArgs {
+                user_id: near_account_id,
+            },
+        )
+        .await?;
+    DEVICE_REGISTRY
+        .with(|registry| {
+            registry
+                .borrow_mut()
+                .reconcile_with_contract(near_account_id, &authenticators)
+        })
+        .map_err(String::from)
+}
+
+/// Milliseconds since the epoch for `DeviceRecord::registered_at_ms`.
+pub(crate) fn now_ms() -> f64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as f64)
+            .unwrap_or(0.0)
+    }
+}
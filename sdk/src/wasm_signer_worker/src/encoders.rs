@@ -1,67 +1,170 @@
 //! Encoding utilities for the signer worker.
-//! Includes base64 helpers and NEAR delegate action encoders.
+//! Includes base64 helpers and the NEP-461 delegate action hash.
 
-use base64ct::{Base64, Base64UrlUnpadded, Encoding};
-use sha2::{Digest, Sha256};
+use base64ct::{Base64, Base64UrlUnpadded, Encoding as _};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+#[cfg(any(test, feature = "threshold"))]
+use crate::signable_message::{compute_signable_digest, SignableMessageKind};
+#[cfg(any(test, feature = "threshold"))]
 use crate::types::DelegateAction;
 
-/// NEP-461 delegate action prefix (2^30 + 366)
-pub const DELEGATE_ACTION_PREFIX: u32 = 1_073_742_190;
+// === BASE64 VARIANTS ===
+
+/// Base64 variants that appear on the worker's wire formats.
+///
+/// Everything the SDK defines itself uses `B64Url`. `B64Std` is reserved for formats fixed by
+/// external specs (NEP-413 nonces/signatures, NEAR RPC `send_tx`). Padded base64url from
+/// browser APIs is accepted on input by `base64_url_decode_lenient` and never emitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// URL-safe alphabet, no padding (canonical)
+    B64Url,
+    /// Standard alphabet with `=` padding
+    B64Std,
+}
+
+impl Encoding {
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::B64Url => "base64url",
+            Encoding::B64Std => "standard base64",
+        }
+    }
+
+    pub fn encode(self, data: &[u8]) -> String {
+        match self {
+            Encoding::B64Url => Base64UrlUnpadded::encode_string(data),
+            Encoding::B64Std => Base64::encode_string(data),
+        }
+    }
+
+    /// Decode `input`; the error names the variant that was expected.
+    pub fn decode(self, input: &str) -> Result<Vec<u8>, String> {
+        let decoded = match self {
+            Encoding::B64Url => Base64UrlUnpadded::decode_vec(input),
+            Encoding::B64Std => Base64::decode_vec(input),
+        };
+        decoded.map_err(|e| format!("expected {}: {}", self.name(), e))
+    }
+}
 
 // === BASE64URL (URL-SAFE, NO PADDING) ===
 
-/// Decode a base64url string using base64ct library
-/// This function uses the `base64ct` library with `Base64UrlUnpadded` encoding,
-/// which is the standard for WebAuthn and cryptographic operations.
+/// Decode a base64url string (`Encoding::B64Url`).
+/// This is the standard for WebAuthn and cryptographic operations.
 /// Returns `String` error for consistency with HTTP operations.
 pub fn base64_url_decode(input: &str) -> Result<Vec<u8>, String> {
-    Base64UrlUnpadded::decode_vec(input).map_err(|e| format!("Base64 decode error: {}", e))
+    Encoding::B64Url.decode(input)
 }
 
-/// Encode bytes to a base64url string using base64ct library
-///
-/// This function uses the `base64ct` library with `Base64UrlUnpadded` encoding,
-/// which is the standard for WebAuthn and cryptographic operations.
+/// Decode base64url that may carry `=` padding (e.g. straight from a browser API).
+pub fn base64_url_decode_lenient(input: &str) -> Result<Vec<u8>, String> {
+    Encoding::B64Url.decode(input.trim().trim_end_matches('='))
+}
+
+/// Encode bytes to a base64url string (`Encoding::B64Url`).
+/// This is the standard for WebAuthn and cryptographic operations.
 pub fn base64_url_encode(data: &[u8]) -> String {
-    Base64UrlUnpadded::encode_string(data)
+    Encoding::B64Url.encode(data)
 }
 
 // === BASE64 STANDARD (FOR JSON/HTTP OPERATIONS) ===
 
-/// Encode bytes to a standard base64 string
+/// Encode bytes to a standard base64 string (`Encoding::B64Std`)
 /// Used for JSON payloads and HTTP operations where standard base64 is expected.
 pub fn base64_standard_encode(data: &[u8]) -> String {
-    Base64::encode_string(data)
+    Encoding::B64Std.encode(data)
 }
 
-/// Decode a standard base64 string
+/// Decode a standard base64 string (`Encoding::B64Std`)
 /// Used for JSON payloads and HTTP operations.
 pub fn base64_standard_decode(input: &str) -> Result<Vec<u8>, String> {
-    Base64::decode_vec(input).map_err(|e| format!("Base64 decode error: {}", e))
+    Encoding::B64Std.decode(input)
 }
 
-// === NEP-461 DELEGATE ACTION ENCODERS ===
+// === TYPED WIRE FIELDS ===
 
-/// Encode DelegateAction with the NEP-461 prefix, mirroring @near-js/transactions encodeDelegateAction.
-pub fn encode_delegate_action(delegate: &DelegateAction) -> Result<Vec<u8>, String> {
-    let mut encoded = borsh::to_vec(&DELEGATE_ACTION_PREFIX)
-        .map_err(|e| format!("Prefix encode error: {}", e))?;
-    let mut delegate_bytes =
-        borsh::to_vec(delegate).map_err(|e| format!("Delegate encode error: {}", e))?;
-    encoded.append(&mut delegate_bytes);
-    Ok(encoded)
+/// Bytes carried as base64url on the wire. Deserializing validates the encoding (padding from
+/// browser APIs is tolerated); serializing always emits canonical unpadded base64url.
+///
+/// Used for serde-only request/response fields the worker decodes itself. Fields of
+/// `#[wasm_bindgen]` structs stay `String` (their JS getters need a wasm-bindgen type) and are
+/// decoded at the use site with the helpers above; so do blobs whose decode failure is
+/// reported as a typed error (account cards, `VERIFY_ENCRYPTED_KEY_ENVELOPE` issues) and the
+/// relayer HTTP protocol structs under `threshold/`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct B64UrlBytes(pub Vec<u8>);
+
+/// Bytes carried as standard base64 on the wire, for spec-mandated fields only.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct B64StdBytes(pub Vec<u8>);
+
+impl B64UrlBytes {
+    pub fn decode(input: &str) -> Result<Self, String> {
+        base64_url_decode_lenient(input).map(Self)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn to_encoded(&self) -> String {
+        Encoding::B64Url.encode(&self.0)
+    }
 }
 
+impl B64StdBytes {
+    pub fn decode(input: &str) -> Result<Self, String> {
+        Encoding::B64Std.decode(input.trim()).map(Self)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn to_encoded(&self) -> String {
+        Encoding::B64Std.encode(&self.0)
+    }
+}
+
+impl Serialize for B64UrlBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_encoded())
+    }
+}
+
+impl<'de> Deserialize<'de> for B64UrlBytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        Self::decode(&encoded).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for B64StdBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_encoded())
+    }
+}
+
+impl<'de> Deserialize<'de> for B64StdBytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        Self::decode(&encoded).map_err(serde::de::Error::custom)
+    }
+}
+
+// === NEP-461 DELEGATE ACTION HASH ===
+
 /// Compute sha256 over the NEP-461-prefixed delegate action bytes.
+#[cfg(any(test, feature = "threshold"))]
 pub fn hash_delegate_action(delegate: &DelegateAction) -> Result<[u8; 32], String> {
-    let encoded = encode_delegate_action(delegate)?;
-    let mut hasher = Sha256::new();
-    hasher.update(&encoded);
-    let digest = hasher.finalize();
-    let mut hash = [0u8; 32];
-    hash.copy_from_slice(&digest);
-    Ok(hash)
+    let delegate_bytes =
+        borsh::to_vec(delegate).map_err(|e| format!("Delegate encode error: {}", e))?;
+    Ok(compute_signable_digest(
+        SignableMessageKind::DelegateAction,
+        &delegate_bytes,
+    ))
 }
 
 #[cfg(test)]
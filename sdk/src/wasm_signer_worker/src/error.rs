@@ -24,7 +24,7 @@ impl QuoteStyle {
 }
 
 const REDACTED: &str = "[REDACTED]";
-const SECRET_STRING_FIELDS: [(&str, QuoteStyle); 32] = [
+const SECRET_STRING_FIELDS: [(&str, QuoteStyle); 34] = [
     ("\"nearPrivateKey\"", QuoteStyle::Plain),
     ("\"near_private_key\"", QuoteStyle::Plain),
     ("\\\"nearPrivateKey\\\"", QuoteStyle::Escaped),
@@ -57,6 +57,9 @@ const SECRET_STRING_FIELDS: [(&str, QuoteStyle); 32] = [
     ("\"prf_second_b64u\"", QuoteStyle::Plain),
     ("\\\"prfSecondB64u\\\"", QuoteStyle::Escaped),
     ("\\\"prf_second_b64u\\\"", QuoteStyle::Escaped),
+    // Not secret, but potentially megabytes of DeployContract code.
+    ("\"signaturePreimageB64u\"", QuoteStyle::Plain),
+    ("\\\"signaturePreimageB64u\\\"", QuoteStyle::Escaped),
 ];
 
 fn scrub_json_string_fields(input: &str, patterns: &[(&str, QuoteStyle)]) -> String {
@@ -232,6 +235,1408 @@ fn format_parse_payload_error(
     scrub_error_message(&message)
 }
 
+/// Malformed WrapKeySeed delivery on the VRF -> signer MessagePort.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WrapKeySeedPortError {
+    /// Payload is neither `{ ok: false, error }` nor carries `wrap_key_seed`.
+    MissingOkAndWrapKeySeed { session_id: String },
+    /// Payload is not an `ok: false` error envelope but lacks `wrap_key_seed` or `wrapKeySalt`.
+    MissingWrapKeyMaterial {
+        session_id: String,
+        field: &'static str,
+    },
+    /// The port closed (or failed to deserialize a message) before any material arrived.
+    ChannelClosed { session_id: String },
+}
+
+impl fmt::Display for WrapKeySeedPortError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WrapKeySeedPortError::MissingOkAndWrapKeySeed { session_id } => write!(
+                f,
+                "Malformed WrapKeySeed port message for session {}: missing both ok and wrap_key_seed",
+                session_id
+            ),
+            WrapKeySeedPortError::MissingWrapKeyMaterial { session_id, field } => write!(
+                f,
+                "Malformed WrapKeySeed port message for session {}: missing {}",
+                session_id, field
+            ),
+            WrapKeySeedPortError::ChannelClosed { session_id } => write!(
+                f,
+                "VRF closed the channel before delivering WrapKeySeed for session {}",
+                session_id
+            ),
+        }
+    }
+}
+
+impl From<WrapKeySeedPortError> for JsValue {
+    fn from(err: WrapKeySeedPortError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// WrapKeySeed delivered on a session's port that was not minted for that session (e.g. two
+/// sessions' ports swapped by the JS shell).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionBindingError {
+    /// `sessionBindingNonce` differs from the one this worker issued for the session.
+    NonceMismatch { session_id: String },
+    /// A nonce was issued for the session but the payload carries none.
+    MissingNonce { session_id: String },
+    /// The payload carries a nonce but this worker never issued one for the session.
+    UnexpectedNonce { session_id: String },
+    /// The signing request's intent digest is not the one delivered with the WrapKeySeed.
+    IntentDigestMismatch { session_id: String },
+}
+
+impl fmt::Display for SessionBindingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SessionBindingError::NonceMismatch { session_id } => write!(
+                f,
+                "SessionBindingMismatch: WrapKeySeed for session {} carries another session's binding nonce",
+                session_id
+            ),
+            SessionBindingError::MissingNonce { session_id } => write!(
+                f,
+                "SessionBindingMismatch: WrapKeySeed for session {} is missing sessionBindingNonce",
+                session_id
+            ),
+            SessionBindingError::UnexpectedNonce { session_id } => write!(
+                f,
+                "SessionBindingMismatch: no binding nonce was issued for session {}",
+                session_id
+            ),
+            SessionBindingError::IntentDigestMismatch { session_id } => write!(
+                f,
+                "SessionBindingMismatch: intent digest differs from the one confirmed for session {}",
+                session_id
+            ),
+        }
+    }
+}
+
+impl From<SessionBindingError> for String {
+    fn from(err: SessionBindingError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<SessionBindingError> for JsValue {
+    fn from(err: SessionBindingError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// Account ID rejected by a flow that only supports some account kinds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountIdError {
+    /// Flow requires a named account but received an implicit (64-hex) account ID.
+    NamedAccountRequired { account_id: String },
+}
+
+impl fmt::Display for AccountIdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AccountIdError::NamedAccountRequired { account_id } => write!(
+                f,
+                "NamedAccountRequired: {} is an implicit account; this flow requires a named account",
+                account_id
+            ),
+        }
+    }
+}
+
+impl From<AccountIdError> for String {
+    fn from(err: AccountIdError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<AccountIdError> for JsValue {
+    fn from(err: AccountIdError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// Request needs a cargo feature that was left out of this (slim) worker build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeatureError {
+    FeatureNotCompiled {
+        /// Cargo feature name, e.g. `threshold`.
+        feature: &'static str,
+        /// What the caller asked for, e.g. a request type or signer mode.
+        requested: String,
+    },
+}
+
+impl FeatureError {
+    pub fn not_compiled(feature: &'static str, requested: impl Into<String>) -> Self {
+        FeatureError::FeatureNotCompiled {
+            feature,
+            requested: requested.into(),
+        }
+    }
+}
+
+impl fmt::Display for FeatureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FeatureError::FeatureNotCompiled { feature, requested } => write!(
+                f,
+                "FeatureNotCompiled: {} requires the `{}` feature, which is not compiled into this worker build",
+                requested, feature
+            ),
+        }
+    }
+}
+
+impl From<FeatureError> for String {
+    fn from(err: FeatureError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<FeatureError> for JsValue {
+    fn from(err: FeatureError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// A `ThresholdSignerConfig` that cannot be used to reach the relayer
+/// (`ThresholdSignerConfig::builder`, `build_threshold_signer_config`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum ThresholdConfigError {
+    MissingRelayerUrl,
+    /// Not an absolute http(s) URL.
+    InvalidRelayerUrl { url: String },
+    MissingRelayerKeyId,
+    /// Client/relayer participant ids and `participantIds` do not describe a 2-party signer set.
+    InvalidParticipantIds { reason: String },
+    /// `thresholdSessionKind` other than `"jwt"` or `"cookie"`.
+    InvalidSessionKind { kind: String },
+    /// `thresholdSessionPolicyJson` is not a JSON object.
+    InvalidSessionPolicyJson { reason: String },
+    /// A bearer token was supplied for a cookie session.
+    JwtWithCookieSession,
+    /// A timing field that is negative or not finite.
+    InvalidDuration { field: &'static str, value: f64 },
+    /// `requestAuth` is incomplete or malformed.
+    InvalidRequestAuth(RequestAuthError),
+    /// `maxConcurrentRelayerRequests` of 0 would stall every relayer request.
+    InvalidConcurrencyLimit { value: u32 },
+}
+
+impl fmt::Display for ThresholdConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ThresholdConfigError::MissingRelayerUrl => {
+                write!(f, "threshold-signer: missing relayerUrl")
+            }
+            ThresholdConfigError::InvalidRelayerUrl { url } => write!(
+                f,
+                "threshold-signer: relayerUrl must be an absolute http(s) URL, got {:?}",
+                url
+            ),
+            ThresholdConfigError::MissingRelayerKeyId => {
+                write!(f, "threshold-signer: missing relayerKeyId")
+            }
+            ThresholdConfigError::InvalidParticipantIds { reason } => write!(f, "{}", reason),
+            ThresholdConfigError::InvalidSessionKind { kind } => write!(
+                f,
+                "threshold-signer: thresholdSessionKind must be \"jwt\" or \"cookie\", got {:?}",
+                kind
+            ),
+            ThresholdConfigError::InvalidSessionPolicyJson { reason } => write!(
+                f,
+                "threshold-signer: thresholdSessionPolicyJson must be a JSON object: {}",
+                reason
+            ),
+            ThresholdConfigError::JwtWithCookieSession => write!(
+                f,
+                "threshold-signer: thresholdSessionJwt cannot be used with thresholdSessionKind \"cookie\""
+            ),
+            ThresholdConfigError::InvalidDuration { field, value } => write!(
+                f,
+                "threshold-signer: {} must be a non-negative number of milliseconds, got {}",
+                field, value
+            ),
+            ThresholdConfigError::InvalidRequestAuth(err) => write!(f, "{}", err),
+            ThresholdConfigError::InvalidConcurrencyLimit { value } => write!(
+                f,
+                "threshold-signer: maxConcurrentRelayerRequests must be at least 1, got {}",
+                value
+            ),
+        }
+    }
+}
+
+impl From<ThresholdConfigError> for String {
+    fn from(err: ThresholdConfigError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<ThresholdConfigError> for JsValue {
+    fn from(err: ThresholdConfigError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// Signing or verifying a relayer request under `requestAuth` (`threshold::request_auth`).
+/// No variant carries the shared secret.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RequestAuthError {
+    MissingKeyId,
+    /// Key id with characters the signature header cannot carry unquoted.
+    InvalidKeyId { key_id: String },
+    /// `secretB64u` is not base64url (`decoded_len: None`) or decodes to too few bytes.
+    InvalidSecret { decoded_len: Option<usize> },
+    /// The signer config carries `secretB64u` instead of a provisioned `keyId`.
+    InlineSecret,
+    /// No secret was provisioned in this worker for `key_id`.
+    SecretNotProvisioned { key_id: String },
+    InvalidHeaderName { name: String },
+    /// The clock to sign with is unset or implausible.
+    ClockUnavailable { timestamp_ms: String },
+    MalformedSignatureHeader,
+    MalformedTimestamp,
+    UnknownKeyId { key_id: String },
+    TimestampOutOfRange {
+        timestamp_ms: u64,
+        now_ms: f64,
+        max_skew_ms: f64,
+    },
+    SignatureMismatch,
+}
+
+impl fmt::Display for RequestAuthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RequestAuthError::MissingKeyId => {
+                write!(f, "threshold-signer: requestAuth.keyId is required")
+            }
+            RequestAuthError::InvalidKeyId { key_id } => write!(
+                f,
+                "threshold-signer: requestAuth.keyId may only contain [A-Za-z0-9-_.:], got {:?}",
+                key_id
+            ),
+            RequestAuthError::InvalidSecret { decoded_len: None } => write!(
+                f,
+                "threshold-signer: requestAuth.secretB64u is not valid base64url"
+            ),
+            RequestAuthError::InvalidSecret {
+                decoded_len: Some(len),
+            } => write!(
+                f,
+                "threshold-signer: requestAuth.secretB64u must decode to at least {} bytes, got {}",
+                crate::config::THRESHOLD_REQUEST_AUTH_MIN_SECRET_BYTES,
+                len
+            ),
+            RequestAuthError::InlineSecret => write!(
+                f,
+                "threshold-signer: requestAuth.secretB64u is not accepted in the signer config; \
+                 provision it with PROVISION_RELAYER_REQUEST_AUTH_SECRET and pass only keyId"
+            ),
+            RequestAuthError::SecretNotProvisioned { key_id } => write!(
+                f,
+                "threshold-signer: no requestAuth secret provisioned for keyId {:?}",
+                key_id
+            ),
+            RequestAuthError::InvalidHeaderName { name } => write!(
+                f,
+                "threshold-signer: requestAuth header names must be distinct HTTP tokens, got {:?}",
+                name
+            ),
+            RequestAuthError::ClockUnavailable { timestamp_ms } => write!(
+                f,
+                "threshold-signer: cannot sign relayer request, clock reads {} ms",
+                timestamp_ms
+            ),
+            RequestAuthError::MalformedSignatureHeader => write!(
+                f,
+                "unauthorized: malformed request signature header (expected keyId=...,signature=...)"
+            ),
+            RequestAuthError::MalformedTimestamp => {
+                write!(f, "unauthorized: malformed request timestamp header")
+            }
+            RequestAuthError::UnknownKeyId { key_id } => {
+                write!(f, "unauthorized: unknown request signing keyId {:?}", key_id)
+            }
+            RequestAuthError::TimestampOutOfRange {
+                timestamp_ms,
+                now_ms,
+                max_skew_ms,
+            } => write!(
+                f,
+                "unauthorized: request timestamp {} is more than {} ms from relayer time {}",
+                timestamp_ms, max_skew_ms, now_ms
+            ),
+            RequestAuthError::SignatureMismatch => {
+                write!(f, "unauthorized: request signature mismatch")
+            }
+        }
+    }
+}
+
+impl From<RequestAuthError> for String {
+    fn from(err: RequestAuthError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<RequestAuthError> for JsValue {
+    fn from(err: RequestAuthError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// No encrypted NEAR key envelope could be picked for a signing request
+/// (`DecryptionPayload::select_envelope`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyEnvelopeError {
+    /// The presented (or requested) credential has no stored envelope.
+    NoEnvelopeForCredential {
+        credential_id: String,
+        available: Vec<String>,
+    },
+    /// `credentialId` names a different passkey than the one behind the WebAuthn assertion.
+    CredentialMismatch {
+        requested: String,
+        presented: String,
+    },
+    /// Several credential envelopes and no credential id to choose between them.
+    AmbiguousEnvelope {
+        available: Vec<String>,
+    },
+    MissingEnvelope,
+}
+
+impl fmt::Display for KeyEnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeyEnvelopeError::NoEnvelopeForCredential {
+                credential_id,
+                available,
+            } => write!(
+                f,
+                "CredentialEnvelopeMismatch: no stored key envelope for credential {} (stored: {})",
+                credential_id,
+                available.join(", ")
+            ),
+            KeyEnvelopeError::CredentialMismatch {
+                requested,
+                presented,
+            } => write!(
+                f,
+                "CredentialEnvelopeMismatch: credentialId {} does not match the presented credential {}",
+                requested, presented
+            ),
+            KeyEnvelopeError::AmbiguousEnvelope { available } => write!(
+                f,
+                "Multiple credential key envelopes ({}); pass credentialId or a WebAuthn assertion to pick one",
+                available.join(", ")
+            ),
+            KeyEnvelopeError::MissingEnvelope => {
+                write!(f, "Missing encrypted private key in decryption payload")
+            }
+        }
+    }
+}
+
+impl From<KeyEnvelopeError> for String {
+    fn from(err: KeyEnvelopeError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<KeyEnvelopeError> for JsValue {
+    fn from(err: KeyEnvelopeError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// Rejections from the optional worker origin/capability gate (`worker_security`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkerSecurityError {
+    /// The gate is set once per worker lifetime.
+    AlreadyInitialized,
+    /// `initialize_worker_security` config could not be used.
+    InvalidConfig(String),
+    /// Caller origin is not the pinned origin, or the request type is not allowlisted.
+    OriginNotAllowed {
+        caller_origin: Option<String>,
+        request_type: &'static str,
+    },
+}
+
+impl fmt::Display for WorkerSecurityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WorkerSecurityError::AlreadyInitialized => {
+                write!(f, "Worker security is already initialized")
+            }
+            WorkerSecurityError::InvalidConfig(msg) => {
+                write!(f, "Invalid worker security config: {}", msg)
+            }
+            WorkerSecurityError::OriginNotAllowed {
+                caller_origin,
+                request_type,
+            } => write!(
+                f,
+                "OriginNotAllowed: {} from origin {} is not permitted",
+                request_type,
+                caller_origin.as_deref().unwrap_or("<missing>")
+            ),
+        }
+    }
+}
+
+impl From<WorkerSecurityError> for JsValue {
+    fn from(err: WorkerSecurityError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// A batched `Intent` was malformed or broke its own constraints (`handle_sign_intent`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntentError {
+    /// The intent has no steps.
+    EmptyIntent,
+    /// Steps are signed by different accounts; an intent is signed with one key.
+    MixedSigners,
+    /// A yoctoNEAR amount or block height could not be parsed.
+    InvalidNumber { field: String, value: String },
+    /// A step's actions could not be built.
+    InvalidStep { index: usize, reason: String },
+    /// Summed deposits do not fit in a u128.
+    DepositOverflow,
+    /// Deposits across all steps exceed `constraints.maxTotalDepositYocto`.
+    DepositLimitExceeded { total_yocto: u128, max_yocto: u128 },
+    /// The current block height is past `constraints.deadlineBlockHeight`.
+    DeadlinePassed {
+        current_block_height: u64,
+        deadline_block_height: u64,
+    },
+    /// The confirmed digest is not the digest of the intent being signed.
+    DigestMismatch { confirmed: String, computed: String },
+}
+
+impl fmt::Display for IntentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IntentError::EmptyIntent => write!(f, "Intent has no steps"),
+            IntentError::MixedSigners => {
+                write!(f, "All intent steps must use the same NEAR account ID")
+            }
+            IntentError::InvalidNumber { field, value } => {
+                write!(f, "Invalid {}: '{}'", field, value)
+            }
+            IntentError::InvalidStep { index, reason } => {
+                write!(f, "Intent step {}: {}", index + 1, reason)
+            }
+            IntentError::DepositOverflow => write!(f, "Intent deposits overflow u128"),
+            IntentError::DepositLimitExceeded {
+                total_yocto,
+                max_yocto,
+            } => write!(
+                f,
+                "IntentDepositLimitExceeded: steps deposit {} yoctoNEAR, limit is {}",
+                total_yocto, max_yocto
+            ),
+            IntentError::DeadlinePassed {
+                current_block_height,
+                deadline_block_height,
+            } => write!(
+                f,
+                "IntentDeadlinePassed: current block height {} is past deadline {}",
+                current_block_height, deadline_block_height
+            ),
+            IntentError::DigestMismatch {
+                confirmed,
+                computed,
+            } => write!(
+                f,
+                "IntentDigestMismatch: confirmed {} but intent hashes to {}",
+                confirmed, computed
+            ),
+        }
+    }
+}
+
+impl From<IntentError> for String {
+    fn from(err: IntentError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<IntentError> for JsValue {
+    fn from(err: IntentError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// The opt-in pre-sign balance check (`checkBalance`) refused or could not run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BalanceCheckError {
+    /// The batch needs more than the signer can spend. Amounts are yoctoNEAR.
+    InsufficientBalance { required: u128, available: u128 },
+    /// A transaction's actions could not be built, so their cost is unknown.
+    InvalidActions { index: usize, reason: String },
+    /// Summed deposits and gas do not fit in a u128.
+    AmountOverflow,
+    /// `view_account` failed; callers sign anyway and flag the result `balanceUnchecked`.
+    Rpc(NearRpcError),
+}
+
+impl BalanceCheckError {
+    /// Only RPC failures are tolerated: the check must never block signing on its own outage.
+    pub fn is_unavailable(&self) -> bool {
+        matches!(self, BalanceCheckError::Rpc(_))
+    }
+}
+
+impl fmt::Display for BalanceCheckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BalanceCheckError::InsufficientBalance {
+                required,
+                available,
+            } => write!(
+                f,
+                "InsufficientBalance: batch requires {} yoctoNEAR, available {}",
+                required, available
+            ),
+            BalanceCheckError::InvalidActions { index, reason } => {
+                write!(f, "Transaction {}: {}", index + 1, reason)
+            }
+            BalanceCheckError::AmountOverflow => {
+                write!(f, "Transaction deposits and gas overflow u128")
+            }
+            BalanceCheckError::Rpc(err) => write!(f, "Balance check failed: {}", err),
+        }
+    }
+}
+
+impl From<NearRpcError> for BalanceCheckError {
+    fn from(err: NearRpcError) -> Self {
+        BalanceCheckError::Rpc(err)
+    }
+}
+
+impl From<BalanceCheckError> for String {
+    fn from(err: BalanceCheckError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<BalanceCheckError> for JsValue {
+    fn from(err: BalanceCheckError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// Why a transaction batch could not be summarized for confirmation (`transaction_summary`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionSummaryError {
+    EmptyBatch,
+    /// A transaction's actions could not be built, so its gas and deposits are unknown.
+    InvalidActions { index: usize, reason: String },
+    /// Gas, deposits or their priced total do not fit in a u128.
+    AmountOverflow { index: usize },
+}
+
+impl fmt::Display for TransactionSummaryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TransactionSummaryError::EmptyBatch => write!(f, "No transactions to summarize"),
+            TransactionSummaryError::InvalidActions { index, reason } => {
+                write!(f, "Transaction {}: {}", index + 1, reason)
+            }
+            TransactionSummaryError::AmountOverflow { index } => write!(
+                f,
+                "Transaction {}: gas and deposits overflow u128",
+                index + 1
+            ),
+        }
+    }
+}
+
+impl From<TransactionSummaryError> for String {
+    fn from(err: TransactionSummaryError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<TransactionSummaryError> for JsValue {
+    fn from(err: TransactionSummaryError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// A JS global the worker host is expected to install is absent (`host_environment`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostBridgeError {
+    /// Name of the missing globalThis function, e.g. `sendProgressMessage`.
+    MissingHostBridge(&'static str),
+}
+
+impl fmt::Display for HostBridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HostBridgeError::MissingHostBridge(name) => write!(
+                f,
+                "MissingHostBridge(\"{}\"): the worker host did not install globalThis.{}",
+                name, name
+            ),
+        }
+    }
+}
+
+impl From<HostBridgeError> for String {
+    fn from(err: HostBridgeError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<HostBridgeError> for JsValue {
+    fn from(err: HostBridgeError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// Failures talking to a NEAR JSON-RPC endpoint from the worker (`rpc_calls`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NearRpcError {
+    /// Signed transaction bytes (or the request built from them) were unusable.
+    InvalidTransaction(String),
+    /// `fetch` failed, or the response body could not be read.
+    Transport(String),
+    /// Non-2xx HTTP response.
+    Http { status: u16, body: String },
+    /// JSON-RPC `error` object, e.g. `HANDLER_ERROR` / `INVALID_TRANSACTION`.
+    Rpc {
+        name: String,
+        cause: Option<String>,
+        message: String,
+    },
+    /// Response did not match the expected JSON-RPC / outcome shape.
+    InvalidResponse(String),
+    /// `executed` mode gave up polling `tx` before the transaction executed.
+    PollTimeout { tx_hash: String, attempts: u32 },
+}
+
+impl NearRpcError {
+    /// Node-side timeouts and not-yet-known transactions are retryable while polling.
+    pub fn is_pending(&self) -> bool {
+        matches!(
+            self,
+            NearRpcError::Rpc { cause: Some(cause), .. }
+                if cause == "TIMEOUT_ERROR" || cause == "UNKNOWN_TRANSACTION"
+        )
+    }
+}
+
+impl fmt::Display for NearRpcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NearRpcError::InvalidTransaction(msg) => {
+                write!(f, "Invalid signed transaction: {}", msg)
+            }
+            NearRpcError::Transport(msg) => write!(f, "NEAR RPC transport error: {}", msg),
+            NearRpcError::Http { status, body } => {
+                write!(f, "NEAR RPC HTTP {}: {}", status, body)
+            }
+            NearRpcError::Rpc {
+                name,
+                cause,
+                message,
+            } => match cause {
+                Some(cause) => write!(f, "NEAR RPC {} ({}): {}", name, cause, message),
+                None => write!(f, "NEAR RPC {}: {}", name, message),
+            },
+            NearRpcError::InvalidResponse(msg) => {
+                write!(f, "Invalid NEAR RPC response: {}", msg)
+            }
+            NearRpcError::PollTimeout { tx_hash, attempts } => write!(
+                f,
+                "Transaction {} not executed after {} status polls",
+                tx_hash, attempts
+            ),
+        }
+    }
+}
+
+impl From<NearRpcError> for String {
+    fn from(err: NearRpcError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<NearRpcError> for JsValue {
+    fn from(err: NearRpcError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// Device number assignment failures (`device_registry`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceRegistryError {
+    /// An explicit device number is already held by a different passkey/key for the account.
+    DeviceNumberConflict {
+        near_account_id: String,
+        device_number: u8,
+        existing_credential_id: String,
+    },
+    /// Device numbers are a single byte on the wire; the account has used them all.
+    DeviceNumbersExhausted { near_account_id: String },
+    /// Device numbers start at 1 (the original device).
+    InvalidDeviceNumber(u8),
+}
+
+impl fmt::Display for DeviceRegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeviceRegistryError::DeviceNumberConflict {
+                near_account_id,
+                device_number,
+                existing_credential_id,
+            } => write!(
+                f,
+                "DeviceNumberConflict: device {} of {} is already registered to credential {}; pass force: true to replace it",
+                device_number, near_account_id, existing_credential_id
+            ),
+            DeviceRegistryError::DeviceNumbersExhausted { near_account_id } => {
+                write!(f, "No free device number left for {}", near_account_id)
+            }
+            DeviceRegistryError::InvalidDeviceNumber(n) => {
+                write!(f, "Invalid device number {}: device numbers start at 1", n)
+            }
+        }
+    }
+}
+
+impl From<DeviceRegistryError> for String {
+    fn from(err: DeviceRegistryError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<DeviceRegistryError> for JsValue {
+    fn from(err: DeviceRegistryError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// NEP-413 message/recipient validation failures (`handle_sign_nep413_message`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Nep413Error {
+    MessageTooLarge { len: usize, max: usize },
+    /// A control character other than `\n` / `\t`, at a byte offset into the message.
+    ControlCharacter { byte_offset: usize, code_point: u32 },
+    InvalidRecipient(String),
+}
+
+impl fmt::Display for Nep413Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Nep413Error::MessageTooLarge { len, max } => write!(
+                f,
+                "MessageTooLarge: NEP-413 message is {} bytes (max {})",
+                len, max
+            ),
+            Nep413Error::ControlCharacter {
+                byte_offset,
+                code_point,
+            } => write!(
+                f,
+                "NEP-413 message contains control character U+{:04X} at byte {}",
+                code_point, byte_offset
+            ),
+            Nep413Error::InvalidRecipient(reason) => {
+                write!(f, "Invalid NEP-413 recipient: {}", reason)
+            }
+        }
+    }
+}
+
+impl From<Nep413Error> for String {
+    fn from(err: Nep413Error) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<Nep413Error> for JsValue {
+    fn from(err: Nep413Error) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// A `binaryAttachments` reference in a request payload that cannot be resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BinaryAttachmentError {
+    /// The payload references an attachment index the envelope does not carry.
+    OutOfRange { index: u32, count: usize },
+    /// Two references point at the same attachment; each one is moved into place once.
+    AlreadyUsed { index: u32 },
+    /// A DeployContract sets both inline `code` and `codeAttachment`.
+    InlineAndAttached { index: u32 },
+    /// Attachments no payload field referenced (the caller's indices are off).
+    Unreferenced { indices: Vec<u32> },
+}
+
+impl fmt::Display for BinaryAttachmentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BinaryAttachmentError::OutOfRange { index, count } => write!(
+                f,
+                "binaryAttachments[{}] is out of range ({} attachments sent)",
+                index, count
+            ),
+            BinaryAttachmentError::AlreadyUsed { index } => {
+                write!(
+                    f,
+                    "binaryAttachments[{}] is referenced more than once",
+                    index
+                )
+            }
+            BinaryAttachmentError::InlineAndAttached { index } => write!(
+                f,
+                "DeployContract sets both code and codeAttachment {}",
+                index
+            ),
+            BinaryAttachmentError::Unreferenced { indices } => {
+                write!(
+                    f,
+                    "binaryAttachments {:?} are not referenced by the payload",
+                    indices
+                )
+            }
+        }
+    }
+}
+
+impl From<BinaryAttachmentError> for String {
+    fn from(err: BinaryAttachmentError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<BinaryAttachmentError> for JsValue {
+    fn from(err: BinaryAttachmentError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// An assertion whose `authenticatorData` is not bound to the expected relying party.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CredentialBindingError {
+    /// `authenticatorData` is shorter than its 32-byte `rpIdHash`.
+    AuthenticatorDataTooShort,
+    /// `rpIdHash` matches neither the rp id nor any allowlisted appid.
+    RpIdHashMismatch { rp_id: String, allowed_app_ids: usize },
+}
+
+impl fmt::Display for CredentialBindingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CredentialBindingError::AuthenticatorDataTooShort => {
+                write!(f, "Authenticator data too short for rpIdHash")
+            }
+            CredentialBindingError::RpIdHashMismatch {
+                rp_id,
+                allowed_app_ids,
+            } => write!(
+                f,
+                "Assertion rpIdHash matches neither rpId {} nor any of {} allowed appids",
+                rp_id, allowed_app_ids
+            ),
+        }
+    }
+}
+
+impl From<CredentialBindingError> for String {
+    fn from(err: CredentialBindingError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<CredentialBindingError> for JsValue {
+    fn from(err: CredentialBindingError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// A numeric `message.type` that does not name a `WorkerRequestType` in this build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestTypeError {
+    /// The value sits in a reserved range but is not assigned yet: the client was built
+    /// against a newer worker than the one it is talking to.
+    FromNewerClient { value: u32, category: &'static str },
+    /// The value is outside every reserved range.
+    Unknown { value: u32 },
+}
+
+impl fmt::Display for RequestTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RequestTypeError::FromNewerClient { value, category } => write!(
+                f,
+                "Request type {} ({} range) is not supported by this worker; the client is newer than the worker build",
+                value, category
+            ),
+            RequestTypeError::Unknown { value } => {
+                write!(f, "Invalid WorkerRequestType value: {}", value)
+            }
+        }
+    }
+}
+
+impl From<RequestTypeError> for String {
+    fn from(err: RequestTypeError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<RequestTypeError> for JsValue {
+    fn from(err: RequestTypeError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// A caller-built unsigned transaction rejected by `handle_sign_borsh_transaction`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BorshTransactionError {
+    /// `transactionBorshB64u` is not base64url-encoded borsh of a `Transaction`.
+    InvalidTransaction(String),
+    /// Only ed25519 (`key_type` 0) keys are signed by this worker.
+    UnsupportedKeyType(u8),
+    /// The transaction names a signer key other than the one the session decrypts.
+    SignerPublicKeyMismatch {
+        transaction_public_key: String,
+        signing_public_key: String,
+    },
+}
+
+impl fmt::Display for BorshTransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BorshTransactionError::InvalidTransaction(reason) => {
+                write!(f, "Invalid borsh transaction: {}", reason)
+            }
+            BorshTransactionError::UnsupportedKeyType(key_type) => write!(
+                f,
+                "Unsupported transaction public key type {} (expected ed25519)",
+                key_type
+            ),
+            BorshTransactionError::SignerPublicKeyMismatch {
+                transaction_public_key,
+                signing_public_key,
+            } => write!(
+                f,
+                "SignerPublicKeyMismatch: transaction is for {} but the session key is {}",
+                transaction_public_key, signing_public_key
+            ),
+        }
+    }
+}
+
+impl From<BorshTransactionError> for String {
+    fn from(err: BorshTransactionError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<BorshTransactionError> for JsValue {
+    fn from(err: BorshTransactionError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// Reasons a device-linking account card is rejected (`account_card`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountCardError {
+    /// The blob is not base64url, or its payload does not borsh-decode.
+    Malformed(String),
+    UnsupportedVersion(u8),
+    InvalidField { field: &'static str, reason: String },
+    BadSignature,
+    Expired { expires_at_ms: u64, now_ms: u64 },
+    NotYetValid { issued_at_ms: u64, now_ms: u64 },
+    LifetimeTooLong { lifetime_ms: u64, max_ms: u64 },
+}
+
+impl fmt::Display for AccountCardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AccountCardError::Malformed(reason) => write!(f, "Malformed account card: {}", reason),
+            AccountCardError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported account card version: {}", version)
+            }
+            AccountCardError::InvalidField { field, reason } => {
+                write!(f, "Invalid account card field {}: {}", field, reason)
+            }
+            AccountCardError::BadSignature => {
+                write!(f, "Account card signature does not verify under nearPublicKey")
+            }
+            AccountCardError::Expired {
+                expires_at_ms,
+                now_ms,
+            } => write!(
+                f,
+                "Account card expired at {} (now {})",
+                expires_at_ms, now_ms
+            ),
+            AccountCardError::NotYetValid {
+                issued_at_ms,
+                now_ms,
+            } => write!(
+                f,
+                "Account card issued in the future at {} (now {})",
+                issued_at_ms, now_ms
+            ),
+            AccountCardError::LifetimeTooLong {
+                lifetime_ms,
+                max_ms,
+            } => write!(
+                f,
+                "Account card lifetime {}ms exceeds {}ms",
+                lifetime_ms, max_ms
+            ),
+        }
+    }
+}
+
+impl From<AccountCardError> for String {
+    fn from(err: AccountCardError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<AccountCardError> for JsValue {
+    fn from(err: AccountCardError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// A session-cached signing key can no longer be used and the session's `keyReusePolicy`
+/// says to fail rather than decrypt again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionKeyReuseError {
+    BudgetExhausted {
+        session_id: String,
+    },
+    Expired {
+        session_id: String,
+        expired_at_ms: u64,
+    },
+}
+
+impl fmt::Display for SessionKeyReuseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SessionKeyReuseError::BudgetExhausted { session_id } => write!(
+                f,
+                "Signing key reuse budget exhausted for session {}; confirm again to sign",
+                session_id
+            ),
+            SessionKeyReuseError::Expired {
+                session_id,
+                expired_at_ms,
+            } => write!(
+                f,
+                "Signing key reuse for session {} expired at {}; confirm again to sign",
+                session_id, expired_at_ms
+            ),
+        }
+    }
+}
+
+impl From<SessionKeyReuseError> for String {
+    fn from(err: SessionKeyReuseError) -> Self {
+        err.to_string()
+    }
+}
+
+/// WebAuthn signature counter check (`sign_counter`) failed for a presented assertion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignCountError {
+    /// The assertion's `signCount` is below the last one seen for this credential; the
+    /// authenticator may have been cloned.
+    AuthenticatorCounterRegression {
+        credential_id: String,
+        stored: u32,
+        presented: u32,
+    },
+}
+
+impl fmt::Display for SignCountError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SignCountError::AuthenticatorCounterRegression {
+                credential_id,
+                stored,
+                presented,
+            } => write!(
+                f,
+                "AuthenticatorCounterRegression: credential {} presented signCount {} below the stored {}; the authenticator may be cloned",
+                credential_id, presented, stored
+            ),
+        }
+    }
+}
+
+impl From<SignCountError> for String {
+    fn from(err: SignCountError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<SignCountError> for JsValue {
+    fn from(err: SignCountError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// A request identical to one already running in this worker (`inflight_requests`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InFlightRequestError {
+    /// The envelope asked for `onDuplicate: "reject"`; the first request started at `started_at_ms`.
+    DuplicateRequestInFlight {
+        session_id: String,
+        started_at_ms: u64,
+    },
+    /// The first request was dropped before producing a result.
+    FirstRequestAbandoned { session_id: String },
+}
+
+impl fmt::Display for InFlightRequestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InFlightRequestError::DuplicateRequestInFlight {
+                session_id,
+                started_at_ms,
+            } => write!(
+                f,
+                "DuplicateRequestInFlight: an identical request for session {} is already in flight (started at {})",
+                session_id, started_at_ms
+            ),
+            InFlightRequestError::FirstRequestAbandoned { session_id } => write!(
+                f,
+                "The identical request this one was waiting on for session {} ended without a result",
+                session_id
+            ),
+        }
+    }
+}
+
+impl From<InFlightRequestError> for String {
+    fn from(err: InFlightRequestError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<InFlightRequestError> for JsValue {
+    fn from(err: InFlightRequestError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// The envelope's `deadlineMsEpoch` passed before the request finished.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RequestDeadlineError {
+    Exceeded { deadline_ms_epoch: f64, now_ms: f64 },
+}
+
+impl fmt::Display for RequestDeadlineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RequestDeadlineError::Exceeded {
+                deadline_ms_epoch,
+                now_ms,
+            } => write!(
+                f,
+                "request deadline exceeded (deadline {}, now {})",
+                deadline_ms_epoch, now_ms
+            ),
+        }
+    }
+}
+
+impl From<RequestDeadlineError> for String {
+    fn from(err: RequestDeadlineError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<RequestDeadlineError> for JsValue {
+    fn from(err: RequestDeadlineError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// Why a COSE credential public key could not be read as EC2 coordinates (`cose::extract_ec2_coordinates`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoseKeyError {
+    MalformedCbor(String),
+    NotCborMap,
+    MissingParameter(&'static str),
+    WrongParameterType {
+        parameter: &'static str,
+        expected: &'static str,
+    },
+    /// `kty` is not 2 (EC2), e.g. 1 (OKP) for Ed25519 authenticators.
+    NotEc2 {
+        kty: i128,
+    },
+    /// `crv` is not P-256 (1), P-384 (2) or P-521 (3).
+    UnsupportedCurve(i128),
+    InvalidCoordinateLength {
+        coordinate: &'static str,
+        curve: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl fmt::Display for CoseKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CoseKeyError::MalformedCbor(e) => write!(f, "COSE key is not valid CBOR: {}", e),
+            CoseKeyError::NotCborMap => write!(f, "COSE key is not a CBOR map"),
+            CoseKeyError::MissingParameter(parameter) => {
+                write!(f, "COSE key is missing the {} parameter", parameter)
+            }
+            CoseKeyError::WrongParameterType {
+                parameter,
+                expected,
+            } => write!(f, "COSE key {} must be {}", parameter, expected),
+            CoseKeyError::NotEc2 { kty } => {
+                write!(f, "COSE key type {} is not EC2 (2)", kty)
+            }
+            CoseKeyError::UnsupportedCurve(crv) => write!(
+                f,
+                "Unsupported EC2 curve {} (expected P-256, P-384 or P-521)",
+                crv
+            ),
+            CoseKeyError::InvalidCoordinateLength {
+                coordinate,
+                curve,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} coordinate is {} bytes; {} coordinates are {} bytes",
+                coordinate, actual, curve, expected
+            ),
+        }
+    }
+}
+
+impl From<CoseKeyError> for String {
+    fn from(err: CoseKeyError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<CoseKeyError> for JsValue {
+    fn from(err: CoseKeyError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
+/// First structural problem found in a WebAuthn attestation object (`cose::validate_attestation_object`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttestationError {
+    InvalidBase64(String),
+    Empty,
+    /// The blob does not start with a CBOR map header (major type 5).
+    NotCborMap {
+        initial_byte: u8,
+    },
+    /// CBOR ended before the top-level map was complete.
+    TruncatedCbor,
+    MalformedCbor(String),
+    TrailingBytes {
+        count: usize,
+    },
+    MissingField(&'static str),
+    WrongFieldType {
+        field: &'static str,
+        expected: &'static str,
+    },
+    /// authData is present but its attested credential data is malformed.
+    InvalidAuthData(String),
+}
+
+impl AttestationError {
+    /// Stable machine-readable code for registration UIs.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AttestationError::InvalidBase64(_) => "INVALID_BASE64",
+            AttestationError::Empty => "EMPTY",
+            AttestationError::NotCborMap { .. } => "NOT_CBOR_MAP",
+            AttestationError::TruncatedCbor => "TRUNCATED_CBOR",
+            AttestationError::MalformedCbor(_) => "MALFORMED_CBOR",
+            AttestationError::TrailingBytes { .. } => "TRAILING_BYTES",
+            AttestationError::MissingField(_) => "MISSING_FIELD",
+            AttestationError::WrongFieldType { .. } => "WRONG_FIELD_TYPE",
+            AttestationError::InvalidAuthData(_) => "INVALID_AUTH_DATA",
+        }
+    }
+}
+
+impl fmt::Display for AttestationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AttestationError::InvalidBase64(e) => {
+                write!(f, "Attestation object is not valid base64url: {}", e)
+            }
+            AttestationError::Empty => write!(f, "Attestation object is empty"),
+            AttestationError::NotCborMap { initial_byte } => write!(
+                f,
+                "Attestation object is not a CBOR map (initial byte 0x{:02x})",
+                initial_byte
+            ),
+            AttestationError::TruncatedCbor => {
+                write!(f, "Attestation object CBOR is truncated")
+            }
+            AttestationError::MalformedCbor(e) => {
+                write!(f, "Attestation object CBOR is malformed: {}", e)
+            }
+            AttestationError::TrailingBytes { count } => write!(
+                f,
+                "Attestation object has {} trailing bytes after the CBOR map",
+                count
+            ),
+            AttestationError::MissingField(field) => {
+                write!(f, "Attestation object is missing {}", field)
+            }
+            AttestationError::WrongFieldType { field, expected } => {
+                write!(f, "Attestation object {} must be {}", field, expected)
+            }
+            AttestationError::InvalidAuthData(e) => write!(f, "Invalid authData: {}", e),
+        }
+    }
+}
+
+impl From<AttestationError> for String {
+    fn from(err: AttestationError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<AttestationError> for JsValue {
+    fn from(err: AttestationError) -> Self {
+        let obj = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&obj, &"code".into(), &err.code().into());
+        let _ = js_sys::Reflect::set(&obj, &"message".into(), &err.to_string().into());
+        obj.into()
+    }
+}
+
 // Custom error type for KDF operations
 #[derive(Debug)]
 pub enum KdfError {
@@ -274,7 +1679,36 @@ impl From<String> for KdfError {
 
 #[cfg(test)]
 mod tests {
-    use super::scrub_error_message;
+    use super::{scrub_error_message, WrapKeySeedPortError};
+
+    #[test]
+    fn wrap_key_seed_port_error_names_session_and_missing_fields() {
+        let err = WrapKeySeedPortError::MissingOkAndWrapKeySeed {
+            session_id: "sess-1".to_string(),
+        };
+        let msg = err.to_string();
+        assert!(msg.contains("sess-1"));
+        assert!(msg.contains("missing both ok and wrap_key_seed"));
+
+        let msg = WrapKeySeedPortError::MissingWrapKeyMaterial {
+            session_id: "sess-1".to_string(),
+            field: "wrapKeySalt",
+        }
+        .to_string();
+        assert!(msg.contains("sess-1"));
+        assert!(msg.ends_with("missing wrapKeySalt"));
+    }
+
+    #[test]
+    fn wrap_key_seed_channel_closed_error_is_distinct_from_timeout() {
+        let msg = WrapKeySeedPortError::ChannelClosed {
+            session_id: "sess-2".to_string(),
+        }
+        .to_string();
+        assert!(msg.contains("VRF closed the channel before delivering WrapKeySeed"));
+        assert!(msg.contains("sess-2"));
+        assert!(!msg.contains("Timed out"));
+    }
 
     #[test]
     fn scrubs_plain_json_string_fields() {
@@ -0,0 +1,64 @@
+// ******************************************************************************
+// *                                                                            *
+// *                        HANDLER: CLEAR ALL SESSIONS                         *
+// *                                                                            *
+// ******************************************************************************
+
+use serde::{Deserialize, Serialize};
+
+use crate::inflight_requests::clear_signer_requests_in_flight;
+use crate::state_changes::{advance_logout_epoch, record_state_change, StateChangeKind};
+use crate::wrap_key_handshake::clear_all_signing_sessions;
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ClearAllSessionsRequest {
+    /// Epoch the TS shell assigned to this logout; pass the same value to the VRF worker's
+    /// `CLEAR_VRF`. Defaults to the worker's current epoch + 1.
+    #[serde(default)]
+    pub logout_epoch: Option<u64>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClearAllSessionsResult {
+    pub logout_epoch: u64,
+    pub cleared_signing_sessions: usize,
+    /// Always 0 in builds without the `threshold` feature.
+    pub cleared_threshold_auth_sessions: usize,
+    pub cleared_in_flight_requests: usize,
+    /// WrapKeySeed / PRF.second waits settled with `LoggedOut`.
+    pub rejected_waiters: usize,
+}
+
+/// **Handles:** `WorkerRequestType::ClearAllSessions`
+/// Logout-everywhere teardown for this worker. Everything `LOGOUT_ALL` clears (WrapKeySeed,
+/// PRF.second, contract verifications, cached signing keys, warmed threshold shares, relayer
+/// threshold auth sessions), plus the in-flight de-duplication markers; requests still waiting
+/// on key material or on a duplicate fail with `ERROR_LOGGED_OUT`. Runs synchronously, so no
+/// other request observes a partially cleared worker.
+pub fn handle_clear_all_sessions(request: ClearAllSessionsRequest) -> ClearAllSessionsResult {
+    #[cfg(target_arch = "wasm32")]
+    let rejected_waiters =
+        crate::wrap_key_handshake::reject_all_material_waiters(crate::config::ERROR_LOGGED_OUT);
+    #[cfg(not(target_arch = "wasm32"))]
+    let rejected_waiters = 0;
+
+    let cleared_in_flight_requests = clear_signer_requests_in_flight();
+    let cleared_signing_sessions = clear_all_signing_sessions();
+    #[cfg(feature = "threshold")]
+    let cleared_threshold_auth_sessions =
+        crate::threshold::relayer_signer::clear_all_threshold_auth_sessions();
+    #[cfg(not(feature = "threshold"))]
+    let cleared_threshold_auth_sessions = 0;
+
+    let logout_epoch = advance_logout_epoch(request.logout_epoch);
+    record_state_change(StateChangeKind::Logout, None);
+    ClearAllSessionsResult {
+        logout_epoch,
+        cleared_signing_sessions,
+        cleared_threshold_auth_sessions,
+        cleared_in_flight_requests,
+        rejected_waiters,
+    }
+}
@@ -0,0 +1,84 @@
+// ******************************************************************************
+// *                                                                            *
+// *                       HANDLER: CREATE ACCOUNT CARD                         *
+// *                                                                            *
+// ******************************************************************************
+
+use serde::{Deserialize, Serialize};
+
+use crate::account_card::{account_card_digest, encode_account_card, AccountCard};
+use crate::encoders::B64UrlBytes;
+use crate::handlers::handle_sign_nep413_message::ensure_expected_public_key;
+use crate::threshold::signer_backend::Ed25519SignerBackend;
+use crate::types::{handlers::presented_credential_id, DecryptionPayload, SignerMode};
+use crate::WrapKey;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAccountCardRequest {
+    pub session_id: String,
+    pub near_account_id: String,
+    /// The account's VRF public key (`Encoding::B64Url`), as registered with the contract.
+    pub vrf_public_key_raw_b64u: B64UrlBytes,
+    /// Device number handed to the device that scans the card.
+    pub device_number: u32,
+    pub decryption: DecryptionPayload,
+    pub credential: Option<String>,
+    /// WebAuthn credential id whose key envelope to decrypt; defaults to the credential behind
+    /// the presented assertion.
+    #[serde(default)]
+    pub credential_id: Option<String>,
+    /// Expected NEAR ed25519 public key (`ed25519:<base58>`); when set, the card is not
+    /// signed unless the decrypted key matches it.
+    #[serde(default)]
+    pub expected_public_key: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAccountCardResult {
+    /// Compact base64url blob for the linking QR code.
+    pub card_b64u: String,
+    pub card: AccountCard,
+}
+
+/// **Handles:** `WorkerRequestType::CreateAccountCard`
+/// Signs an account card with the account's (local) NEAR key for Device1's linking QR code.
+/// The card expires `ACCOUNT_CARD_TTL_MS` after it is issued.
+pub async fn handle_create_account_card(
+    request: CreateAccountCardRequest,
+    wrap_key: WrapKey,
+) -> Result<CreateAccountCardResult, String> {
+    let presented_credential_id =
+        presented_credential_id(request.credential.as_deref(), &request.session_id);
+    let envelope = request.decryption.select_envelope(
+        request.credential_id.as_deref(),
+        presented_credential_id.as_deref(),
+    )?;
+    let signer = Ed25519SignerBackend::from_encrypted_near_private_key(
+        SignerMode::LocalSigner,
+        &wrap_key,
+        &envelope,
+        &request.near_account_id,
+    )?;
+    let public_key_bytes = signer.public_key_bytes()?;
+    ensure_expected_public_key(&public_key_bytes, request.expected_public_key.as_deref())?;
+
+    #[cfg(target_arch = "wasm32")]
+    let now_ms = js_sys::Date::now() as u64;
+    #[cfg(not(target_arch = "wasm32"))]
+    let now_ms = 0;
+
+    let card = AccountCard::new(
+        &request.near_account_id,
+        &public_key_bytes,
+        request.vrf_public_key_raw_b64u.as_bytes(),
+        request.device_number,
+        now_ms,
+    )?;
+    let digest = account_card_digest(&card)?;
+    let signature = signer.sign(&digest).await?;
+    let card_b64u = encode_account_card(&card, &signature)?;
+
+    Ok(CreateAccountCardResult { card_b64u, card })
+}
@@ -0,0 +1,26 @@
+// ******************************************************************************
+// *                                                                            *
+// *                            HANDLER: DEBUG PANIC                            *
+// *                                                                            *
+// ******************************************************************************
+
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugPanicRequest {
+    /// Panic message; defaults to a fixed string.
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// **Handles:** `WorkerRequestType::DebugPanic` (only with the `debug-panic` feature)
+/// Panics on purpose so the `panic_guard` secret wipe can be exercised end to end.
+pub async fn handle_debug_panic(request: DebugPanicRequest) -> Result<(), String> {
+    panic!(
+        "{}",
+        request
+            .message
+            .unwrap_or_else(|| "DEBUG_PANIC requested".to_string())
+    );
+}
@@ -7,6 +7,7 @@ use bs58;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
+use crate::types::SelectedKeyEnvelope;
 use crate::WrapKey;
 
 // Export/decrypt confirmation has been moved to the VRF bridge; signer no longer owns awaitSecureConfirmationV2.
@@ -26,6 +27,18 @@ pub struct DecryptPrivateKeyRequest {
     pub encrypted_private_key_chacha20_nonce_b64u: String,
     #[wasm_bindgen(getter_with_clone, js_name = "sessionId")]
     pub session_id: String,
+    /// Credential id for credential-scoped (version 3+) envelopes; unset for version 2.
+    #[wasm_bindgen(getter_with_clone, js_name = "credentialId")]
+    #[serde(default)]
+    pub credential_id: Option<String>,
+    /// Envelope format version; version 4 authenticates `nearAccountId` (and `aadContext`)
+    /// as AAD. Defaults to 3 with a `credentialId`, 2 without.
+    #[wasm_bindgen(js_name = "envelopeVersion")]
+    #[serde(default)]
+    pub envelope_version: Option<u8>,
+    #[wasm_bindgen(getter_with_clone, js_name = "aadContext")]
+    #[serde(default)]
+    pub aad_context: Option<String>,
 }
 
 #[wasm_bindgen]
@@ -42,6 +55,27 @@ impl DecryptPrivateKeyRequest {
             encrypted_private_key_data,
             encrypted_private_key_chacha20_nonce_b64u,
             session_id,
+            credential_id: None,
+            envelope_version: None,
+            aad_context: None,
+        }
+    }
+}
+
+impl DecryptPrivateKeyRequest {
+    pub(crate) fn key_envelope(&self) -> SelectedKeyEnvelope<'_> {
+        let credential_id = self.credential_id.as_deref().filter(|id| !id.is_empty());
+        let default_version = match credential_id {
+            Some(_) => crate::config::NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL,
+            None => crate::config::NEAR_KEY_ENVELOPE_VERSION_ACCOUNT,
+        };
+        SelectedKeyEnvelope {
+            encrypted_private_key_data: &self.encrypted_private_key_data,
+            encrypted_private_key_chacha20_nonce_b64u: &self
+                .encrypted_private_key_chacha20_nonce_b64u,
+            credential_id,
+            version: self.envelope_version.unwrap_or(default_version),
+            aad_context: self.aad_context.as_deref(),
         }
     }
 }
@@ -81,15 +115,12 @@ pub async fn handle_decrypt_private_key_with_prf(
     request: DecryptPrivateKeyRequest,
     wrap_key: WrapKey,
 ) -> Result<DecryptPrivateKeyResult, String> {
-    // Derive KEK from WrapKeySeed + wrap_key_salt and decrypt
-    let kek = wrap_key.derive_kek()?;
-
-    let decrypted_private_key_str = crate::crypto::decrypt_data_chacha20(
-        &request.encrypted_private_key_data,
-        &request.encrypted_private_key_chacha20_nonce_b64u,
-        &kek,
-    )
-    .map_err(|e| format!("Decryption failed: {}", e))?;
+    // Derive KEK from WrapKeySeed + wrap_key_salt and decrypt; a version 4 envelope moved to
+    // another account fails AEAD authentication here
+    let envelope = request.key_envelope();
+    let decrypted_private_key_str = wrap_key
+        .decrypt_near_key_envelope(&envelope, &request.near_account_id)
+        .map_err(|e| format!("Decryption failed: {}", e))?;
 
     // Convert decrypted string into SigningKey
     let decoded = bs58::decode(
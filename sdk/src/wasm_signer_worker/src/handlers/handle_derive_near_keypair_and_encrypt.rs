@@ -8,6 +8,7 @@ use log::debug;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
+use crate::device_registry::DeviceRecord;
 use crate::types::{AuthenticatorOptions, SerializedRegistrationCredential};
 use crate::WrapKey;
 
@@ -23,6 +24,23 @@ pub struct DeriveNearKeypairAndEncryptRequest {
     pub authenticator_options: Option<AuthenticatorOptions>,
     #[wasm_bindgen(getter_with_clone, js_name = "sessionId")]
     pub session_id: String,
+    /// Also return the implicit account ID (hex of the derived public key).
+    #[wasm_bindgen(js_name = "deriveImplicitAccountId")]
+    #[serde(default)]
+    pub derive_implicit_account_id: Option<bool>,
+    /// Optional app context authenticated (with the account id) as the envelope's AAD;
+    /// signing must present the same value.
+    #[wasm_bindgen(getter_with_clone, js_name = "aadContext")]
+    #[serde(default)]
+    pub aad_context: Option<String>,
+    /// Device number to derive for (1 = original device). Defaults to the next free number
+    /// in the device registry.
+    #[wasm_bindgen(js_name = "deviceNumber")]
+    #[serde(default)]
+    pub device_number: Option<u8>,
+    /// Replace an existing device record that holds `deviceNumber` for another credential.
+    #[serde(default)]
+    pub force: Option<bool>,
 }
 
 #[wasm_bindgen]
@@ -42,6 +60,27 @@ pub struct DeriveNearKeypairAndEncryptResult {
     #[wasm_bindgen(getter_with_clone, js_name = "version")]
     pub version: u8,
     pub stored: bool,
+    /// WebAuthn credential the envelope is bound to (version 3 envelopes).
+    #[wasm_bindgen(getter_with_clone, js_name = "credentialId")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_id: Option<String>,
+    /// App context bound into the envelope AAD, echoed from the request.
+    #[wasm_bindgen(getter_with_clone, js_name = "aadContext")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aad_context: Option<String>,
+    /// Set when the request asked for `deriveImplicitAccountId`.
+    #[wasm_bindgen(getter_with_clone, js_name = "implicitAccountId")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub implicit_account_id: Option<String>,
+    /// Device number the key was recorded under in the device registry.
+    #[wasm_bindgen(js_name = "deviceNumber")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_number: Option<u8>,
+    /// HMAC tying the envelope to the account and public key; check it with
+    /// `VerifyEncryptedKeyEnvelope` without decrypting.
+    #[wasm_bindgen(getter_with_clone, js_name = "integrityMacB64u")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub integrity_mac_b64u: Option<String>,
 }
 
 #[wasm_bindgen]
@@ -64,14 +103,63 @@ impl DeriveNearKeypairAndEncryptResult {
             wrap_key_salt,
             version,
             stored,
+            credential_id: None,
+            aad_context: None,
+            implicit_account_id: None,
+            device_number: None,
+            integrity_mac_b64u: None,
         }
     }
 }
 
+impl DeriveNearKeypairAndEncryptResult {
+    pub fn with_credential_id(mut self, credential_id: String) -> Self {
+        self.credential_id = Some(credential_id);
+        self
+    }
+
+    pub fn with_aad_context(mut self, aad_context: Option<String>) -> Self {
+        self.aad_context = aad_context;
+        self
+    }
+
+    pub fn with_implicit_account_id(mut self, implicit_account_id: Option<String>) -> Self {
+        self.implicit_account_id = implicit_account_id;
+        self
+    }
+
+    pub fn with_device_number(mut self, device_number: u8) -> Self {
+        self.device_number = Some(device_number);
+        self
+    }
+
+    pub fn with_integrity_mac(mut self, integrity_mac_b64u: String) -> Self {
+        self.integrity_mac_b64u = Some(integrity_mac_b64u);
+        self
+    }
+}
+
+/// Implicit account ID for a `ed25519:<base58>` public key string.
+pub(crate) fn implicit_account_id_from_public_key(public_key: &str) -> Result<String, String> {
+    let encoded = public_key.strip_prefix("ed25519:").unwrap_or(public_key);
+    let bytes: [u8; 32] = bs58::decode(encoded)
+        .into_vec()
+        .map_err(|e| format!("Failed to decode derived public key: {}", e))?
+        .try_into()
+        .map_err(|_| "Derived public key must be 32 bytes".to_string())?;
+    Ok(crate::types::AccountId::implicit_from_public_key(&bytes).0)
+}
+
 /// **Handles:** `WorkerRequestType::DeriveNearKeypairAndEncrypt`
 /// This is the primary handler for new device setup and linking. It performs the following operations:
 /// 1. Derives Ed25519 keypair from PRF.second (delivered via MessagePort) using HKDF with account-specific salt
-/// 2. Encrypts the private key using KEK derived from WrapKeySeed (delivered via MessagePort)
+/// 2. Encrypts the private key using a KEK derived from WrapKeySeed (delivered via MessagePort)
+///    and bound to the registering credential id, so each passkey registered for the account
+///    on this device gets its own envelope; the account id (plus optional `aadContext`) is
+///    authenticated as AAD (version 4 envelope)
+/// 3. Optionally derives the implicit account ID from the new public key (`deriveImplicitAccountId`)
+/// 4. Records the device in the device registry under `deviceNumber` (or the next free number);
+///    an explicit number held by another credential is rejected unless `force` is set
 ///
 /// # Security Note
 /// PRF outputs are delivered via MessagePort from VRF worker and never exposed to main thread.
@@ -90,6 +178,15 @@ pub async fn handle_derive_near_keypair_and_encrypt(
 ) -> Result<DeriveNearKeypairAndEncryptResult, String> {
     debug!("[rust wasm]: starting PRF-based keypair derivation (secure MessagePort flow)");
 
+    // Settle the device number before any key material is derived
+    let credential_id = request.credential.id.clone();
+    let device_number = crate::device_registry::assign_device_number(
+        &request.near_account_id,
+        request.device_number,
+        &credential_id,
+        request.force.unwrap_or(false),
+    )?;
+
     // Derive Ed25519 keypair from PRF.second (delivered securely via MessagePort)
     let (near_private_key, near_public_key) = crate::crypto::derive_ed25519_key_from_prf_output(
         &prf_second_b64u,
@@ -97,14 +194,39 @@ pub async fn handle_derive_near_keypair_and_encrypt(
     )
     .map_err(|e| format!("Failed to derive Ed25519 key from PRF.second: {}", e))?;
 
-    // Derive KEK from WrapKeySeed+wrapKeySalt and encrypt NEAR private key
-    let kek = wrap_key.derive_kek()?;
-
+    // Encrypt under the credential-scoped KEK (WrapKeySeed+wrapKeySalt), binding the account as AAD
     let wrap_key_salt_bytes = crate::encoders::base64_url_decode(wrap_key.salt_b64u())
         .map_err(|e| format!("Failed to decode wrapKeySalt: {}", e))?;
-    let encryption_result = crate::crypto::encrypt_data_chacha20(&near_private_key, &kek)
-        .map_err(|e| format!("Failed to encrypt private key: {}", e))?
+    let encryption_result = wrap_key
+        .encrypt_near_key_for_credential(
+            &near_private_key,
+            &credential_id,
+            &request.near_account_id,
+            request.aad_context.as_deref(),
+        )?
         .with_wrap_key_salt(&wrap_key_salt_bytes);
+    let integrity_mac_b64u = wrap_key.near_key_envelope_integrity_mac_b64u(
+        &encryption_result.encrypted_near_key_data_b64u,
+        &encryption_result.chacha20_nonce_b64u,
+        &request.near_account_id,
+        &near_public_key,
+    )?;
+
+    let implicit_account_id = if request.derive_implicit_account_id.unwrap_or(false) {
+        Some(implicit_account_id_from_public_key(&near_public_key)?)
+    } else {
+        None
+    };
+
+    crate::device_registry::record_device(
+        &request.near_account_id,
+        DeviceRecord {
+            device_number,
+            public_key: near_public_key.clone(),
+            credential_id: credential_id.clone(),
+            registered_at_ms: crate::device_registry::now_ms(),
+        },
+    );
 
     // Return structured result
     Ok(DeriveNearKeypairAndEncryptResult::new(
@@ -115,7 +237,12 @@ pub async fn handle_derive_near_keypair_and_encrypt(
         encryption_result
             .wrap_key_salt_b64u
             .unwrap_or_else(|| "".to_string()),
-        2,
+        crate::config::NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL_AAD,
         true, // stored = true since we're storing in WASM
-    ))
+    )
+    .with_credential_id(credential_id)
+    .with_aad_context(request.aad_context)
+    .with_implicit_account_id(implicit_account_id)
+    .with_device_number(device_number)
+    .with_integrity_mac(integrity_mac_b64u))
 }
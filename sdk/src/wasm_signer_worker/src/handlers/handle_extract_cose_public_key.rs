@@ -6,6 +6,8 @@
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
+use crate::encoders::base64_url_encode;
+
 #[wasm_bindgen]
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -22,6 +24,45 @@ pub struct CoseExtractionResult {
     pub cose_public_key_bytes: Vec<u8>,
 }
 
+#[wasm_bindgen]
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractCosePublicKeysRequest {
+    #[wasm_bindgen(getter_with_clone, js_name = "attestationObjectsB64u")]
+    pub attestation_objects_b64u: Vec<String>,
+}
+
+/// Per-attestation outcome of a batched extraction. Exactly one of
+/// `cose_public_key_b64u` / `error` is set, matching `ok`.
+#[wasm_bindgen]
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CoseExtractionItem {
+    pub index: u32,
+    pub ok: bool,
+    #[wasm_bindgen(getter_with_clone, js_name = "cosePublicKeyB64u")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cose_public_key_b64u: Option<String>,
+    #[wasm_bindgen(getter_with_clone)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[wasm_bindgen]
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CoseExtractionBatchResult {
+    #[wasm_bindgen(getter_with_clone)]
+    pub results: Vec<CoseExtractionItem>,
+}
+
+/// Synchronous COSE extraction shared by the single and batched handlers and the
+/// `extract_cose_public_key_sync` wasm export.
+pub fn extract_cose_public_key(attestation_object_b64u: &str) -> Result<Vec<u8>, String> {
+    crate::cose::extract_cose_public_key_from_attestation(attestation_object_b64u)
+        .map_err(|e| format!("Failed to extract COSE public key: {}", e))
+}
+
 /// **Handles:** `WorkerRequestType::ExtractCosePublicKey`
 /// This handler parses a WebAuthn attestation object and extracts the COSE-formatted public key
 /// for cryptographic operations. Used during registration to obtain the authenticator's public key
@@ -32,17 +73,59 @@ pub struct CoseExtractionResult {
 ///
 /// # Returns
 /// * `CoseExtractionResult` - Contains extracted COSE public key bytes
-pub async fn handle_extract_cose_public_key(
+pub fn handle_extract_cose_public_key(
     request: ExtractCoseRequest,
 ) -> Result<CoseExtractionResult, String> {
-    let cose_public_key_bytes = crate::cose::extract_cose_public_key_from_attestation(
-        &request.attestation_object_base64url,
-    )
-    .map_err(|e| format!("Failed to extract COSE public key: {}", e))?;
+    let cose_public_key_bytes = extract_cose_public_key(&request.attestation_object_base64url)?;
+    Ok(CoseExtractionResult {
+        cose_public_key_bytes,
+    })
+}
 
-    let result = CoseExtractionResult {
-        cose_public_key_bytes: cose_public_key_bytes,
-    };
+/// **Handles:** `WorkerRequestType::ExtractCosePublicKeys`
+/// Batched variant used during multi-device reconciliation. Each attestation object is
+/// extracted independently: a corrupt entry yields `ok: false` with its error and never
+/// fails the batch. Results are returned in request order.
+pub fn handle_extract_cose_public_keys(
+    request: ExtractCosePublicKeysRequest,
+) -> CoseExtractionBatchResult {
+    let results = request
+        .attestation_objects_b64u
+        .iter()
+        .enumerate()
+        .map(
+            |(index, attestation_b64u)| match extract_cose_public_key(attestation_b64u) {
+                Ok(bytes) => CoseExtractionItem {
+                    index: index as u32,
+                    ok: true,
+                    cose_public_key_b64u: Some(base64_url_encode(&bytes)),
+                    error: None,
+                },
+                Err(error) => CoseExtractionItem {
+                    index: index as u32,
+                    ok: false,
+                    cose_public_key_b64u: None,
+                    error: Some(error),
+                },
+            },
+        )
+        .collect();
+    CoseExtractionBatchResult { results }
+}
 
-    Ok(result)
+/// Extract the COSE public key from a base64url attestation object without going through the
+/// worker message envelope (e.g. relay-side registration validation in Node).
+#[wasm_bindgen]
+pub fn extract_cose_public_key_sync(attestation_b64u: &str) -> Result<Vec<u8>, JsValue> {
+    extract_cose_public_key(attestation_b64u).map_err(|e| JsValue::from_str(&e))
+}
+
+/// `{ crv, xB64u, yB64u }` of an ES256/ES384/ES512 credential, from a base64url attestation
+/// object. Rejects non-EC2 keys and coordinates of the wrong size for the curve.
+#[wasm_bindgen]
+pub fn extract_ec2_coordinates_sync(attestation_b64u: &str) -> Result<JsValue, JsValue> {
+    let coordinates = crate::cose::extract_ec2_coordinates_from_attestation(attestation_b64u)
+        .map_err(|e| JsValue::from_str(&format!("Failed to extract EC2 coordinates: {}", e)))?;
+    serde_wasm_bindgen::to_value(&coordinates)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize EC2 coordinates: {}", e)))
 }
@@ -0,0 +1,39 @@
+// ******************************************************************************
+// *                                                                            *
+// *                      HANDLER: GET DEVICE RECORDS                           *
+// *                                                                            *
+// ******************************************************************************
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::device_registry::DeviceRecord;
+
+#[wasm_bindgen]
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDeviceRecordsRequest {
+    #[wasm_bindgen(getter_with_clone, js_name = "nearAccountId")]
+    pub near_account_id: String,
+}
+
+#[wasm_bindgen]
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDeviceRecordsResult {
+    #[wasm_bindgen(getter_with_clone, js_name = "nearAccountId")]
+    pub near_account_id: String,
+    #[wasm_bindgen(getter_with_clone)]
+    pub records: Vec<DeviceRecord>,
+}
+
+/// **Handles:** `WorkerRequestType::GetDeviceRecords`
+/// Lists the devices this worker has derived or registered for an account, ordered by
+/// device number. Records are populated by `DeriveNearKeypairAndEncrypt` and
+/// `RegisterDevice2WithDerivedKey`.
+pub fn handle_get_device_records(request: GetDeviceRecordsRequest) -> GetDeviceRecordsResult {
+    let records = crate::device_registry::device_records(&request.near_account_id);
+    GetDeviceRecordsResult {
+        near_account_id: request.near_account_id,
+        records,
+    }
+}
@@ -0,0 +1,37 @@
+// ******************************************************************************
+// *                                                                            *
+// *                            HANDLER: LOGOUT ALL                             *
+// *                                                                            *
+// ******************************************************************************
+
+use serde::Serialize;
+
+use crate::state_changes::{record_state_change, StateChangeKind};
+use crate::wrap_key_handshake::clear_all_signing_sessions;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LogoutAllResult {
+    pub cleared_signing_sessions: usize,
+    /// Always 0 in builds without the `threshold` feature.
+    pub cleared_threshold_auth_sessions: usize,
+}
+
+/// **Handles:** `WorkerRequestType::LogoutAll`
+/// Full sign-out: zeroizes and drops every signing session's WrapKeySeed, PRF.second, cached
+/// signing key and warmed threshold share, plus every cached relayer threshold auth session.
+/// Pair with the VRF worker's `LOGOUT_ALL`, which clears its own session map.
+pub fn handle_logout_all() -> LogoutAllResult {
+    let cleared_signing_sessions = clear_all_signing_sessions();
+    #[cfg(feature = "threshold")]
+    let cleared_threshold_auth_sessions =
+        crate::threshold::relayer_signer::clear_all_threshold_auth_sessions();
+    #[cfg(not(feature = "threshold"))]
+    let cleared_threshold_auth_sessions = 0;
+
+    record_state_change(StateChangeKind::Logout, None);
+    LogoutAllResult {
+        cleared_signing_sessions,
+        cleared_threshold_auth_sessions,
+    }
+}
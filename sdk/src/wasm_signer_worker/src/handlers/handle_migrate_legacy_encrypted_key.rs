@@ -0,0 +1,121 @@
+// ******************************************************************************
+// *                                                                            *
+// *                 HANDLER: MIGRATE LEGACY ENCRYPTED KEY                      *
+// *                                                                            *
+// ******************************************************************************
+use log::debug;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::WrapKey;
+
+/// Legacy `packages/passkey` blob plus the PRF.first output needed to open it.
+/// WrapKey material for the new envelope arrives over the session's WrapKeySeed MessagePort.
+#[wasm_bindgen]
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrateLegacyEncryptedKeyRequest {
+    #[wasm_bindgen(getter_with_clone, js_name = "sessionId")]
+    pub session_id: String,
+    #[wasm_bindgen(getter_with_clone, js_name = "nearAccountId")]
+    pub near_account_id: String,
+    /// Legacy ChaCha20-Poly1305 ciphertext (base64url).
+    #[wasm_bindgen(getter_with_clone, js_name = "legacyEncryptedPrivateKeyData")]
+    pub legacy_encrypted_private_key_data: String,
+    /// Legacy ChaCha20-Poly1305 nonce (base64url).
+    #[wasm_bindgen(getter_with_clone, js_name = "legacyEncryptedPrivateKeyIv")]
+    pub legacy_encrypted_private_key_iv: String,
+    /// PRF.first output (base64url) from a normal WebAuthn ceremony.
+    #[wasm_bindgen(getter_with_clone, js_name = "prfFirstOutputB64u")]
+    pub prf_first_output_b64u: String,
+}
+
+impl std::fmt::Debug for MigrateLegacyEncryptedKeyRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MigrateLegacyEncryptedKeyRequest")
+            .field("session_id", &self.session_id)
+            .field("near_account_id", &self.near_account_id)
+            .field("prf_first_output_b64u", &"***")
+            .finish()
+    }
+}
+
+/// Re-encrypted key under the current WrapKey envelope. Callers must check `publicKey`
+/// against the on-chain access key before deleting the legacy blob.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrateLegacyEncryptedKeyResult {
+    #[wasm_bindgen(getter_with_clone, js_name = "nearAccountId")]
+    pub near_account_id: String,
+    #[wasm_bindgen(getter_with_clone, js_name = "publicKey")]
+    pub public_key: String,
+    #[wasm_bindgen(getter_with_clone, js_name = "encryptedPrivateKeyData")]
+    pub encrypted_private_key_data: String,
+    #[wasm_bindgen(getter_with_clone, js_name = "encryptedPrivateKeyChacha20NonceB64u")]
+    pub encrypted_private_key_chacha20_nonce_b64u: String,
+    #[wasm_bindgen(getter_with_clone, js_name = "wrapKeySalt")]
+    pub wrap_key_salt: String,
+    /// HMAC tying the envelope to the account and public key; check it with
+    /// `VerifyEncryptedKeyEnvelope` without decrypting.
+    #[wasm_bindgen(getter_with_clone, js_name = "integrityMacB64u")]
+    pub integrity_mac_b64u: String,
+}
+
+/// **Handles:** `WorkerRequestType::MigrateLegacyEncryptedKey`
+/// Decrypts a dual-PRF era blob with the legacy derivation, re-encrypts the same private key
+/// under the KEK derived from WrapKeySeed + wrapKeySalt, and zeroizes the plaintext and keys.
+///
+/// # Arguments
+/// * `request` - Legacy blob, nonce and PRF.first output
+/// * `wrap_key` - Session WrapKey material for the new envelope
+///
+/// # Returns
+/// * `MigrateLegacyEncryptedKeyResult` - New envelope plus the public key to verify on-chain
+pub async fn handle_migrate_legacy_encrypted_key(
+    request: MigrateLegacyEncryptedKeyRequest,
+    wrap_key: WrapKey,
+) -> Result<MigrateLegacyEncryptedKeyResult, String> {
+    migrate_legacy_encrypted_key(&request, &wrap_key)
+}
+
+pub(crate) fn migrate_legacy_encrypted_key(
+    request: &MigrateLegacyEncryptedKeyRequest,
+    wrap_key: &WrapKey,
+) -> Result<MigrateLegacyEncryptedKeyResult, String> {
+    let near_private_key = crate::legacy::decrypt_private_key_with_prf(
+        &request.near_account_id,
+        &request.legacy_encrypted_private_key_data,
+        &request.legacy_encrypted_private_key_iv,
+        &request.prf_first_output_b64u,
+    )?;
+    let public_key = crate::legacy::near_public_key_for_private_key(&near_private_key)?;
+
+    let kek = Zeroizing::new(wrap_key.derive_kek()?);
+    let wrap_key_salt_bytes = crate::encoders::base64_url_decode(wrap_key.salt_b64u())
+        .map_err(|e| format!("Failed to decode wrapKeySalt: {}", e))?;
+    let encryption_result = crate::crypto::encrypt_data_chacha20(&near_private_key, &kek)
+        .map_err(|e| format!("Failed to encrypt private key: {}", e))?
+        .with_wrap_key_salt(&wrap_key_salt_bytes);
+    let integrity_mac_b64u = wrap_key.near_key_envelope_integrity_mac_b64u(
+        &encryption_result.encrypted_near_key_data_b64u,
+        &encryption_result.chacha20_nonce_b64u,
+        &request.near_account_id,
+        &public_key,
+    )?;
+
+    debug!(
+        "[rust wasm]: Migrated legacy encrypted key for {} ({})",
+        request.near_account_id, public_key
+    );
+
+    Ok(MigrateLegacyEncryptedKeyResult {
+        near_account_id: request.near_account_id.clone(),
+        public_key,
+        encrypted_private_key_data: encryption_result.encrypted_near_key_data_b64u,
+        encrypted_private_key_chacha20_nonce_b64u: encryption_result.chacha20_nonce_b64u,
+        wrap_key_salt: wrap_key.salt_b64u().to_string(),
+        integrity_mac_b64u,
+    })
+}
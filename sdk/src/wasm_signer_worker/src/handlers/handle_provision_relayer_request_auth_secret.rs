@@ -0,0 +1,34 @@
+// ******************************************************************************
+// *                                                                            *
+// *             HANDLER: PROVISION RELAYER REQUEST-AUTH HMAC SECRET            *
+// *                                                                            *
+// ******************************************************************************
+
+use serde::{Deserialize, Serialize};
+
+use crate::threshold::request_auth::{provision_request_auth_secret, RequestAuthSecret};
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvisionRelayerRequestAuthSecretRequest {
+    pub key_id: String,
+    pub secret_b64u: RequestAuthSecret,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvisionRelayerRequestAuthSecretResult {
+    /// Trimmed key id to use as `requestAuth.keyId` in the signer config.
+    pub key_id: String,
+}
+
+/// **Handles:** `WorkerRequestType::ProvisionRelayerRequestAuthSecret`
+///
+/// Keeps the `requestAuth` HMAC secret in this worker (replacing any earlier secret for the same
+/// `keyId`). Signing requests then carry only the `keyId`; the secret is never returned.
+pub fn handle_provision_relayer_request_auth_secret(
+    request: ProvisionRelayerRequestAuthSecretRequest,
+) -> Result<ProvisionRelayerRequestAuthSecretResult, String> {
+    let key_id = provision_request_auth_secret(&request.key_id, request.secret_b64u)?;
+    Ok(ProvisionRelayerRequestAuthSecretResult { key_id })
+}
@@ -4,6 +4,7 @@
 // *                                                                            *
 // ******************************************************************************
 
+use crate::credential_binding::{validate_credential_binding, CredentialBinding};
 use crate::{types::SerializedCredential, WrapKey};
 use log::debug;
 use serde::{Deserialize, Serialize};
@@ -19,6 +20,15 @@ pub struct RecoverKeypairRequest {
     pub account_id_hint: Option<String>,
     #[wasm_bindgen(getter_with_clone, js_name = "sessionId")]
     pub session_id: String,
+    /// When set, the assertion's `rpIdHash` must match this rp id (or an `allowedAppIds` entry).
+    #[wasm_bindgen(getter_with_clone, js_name = "rpId")]
+    #[serde(default)]
+    pub rp_id: Option<String>,
+    /// Legacy U2F appids accepted in place of `rpId` for imported security keys. Empty by
+    /// default, which accepts `rpId` only.
+    #[wasm_bindgen(getter_with_clone, js_name = "allowedAppIds")]
+    #[serde(default)]
+    pub allowed_app_ids: Vec<String>,
 }
 
 #[wasm_bindgen]
@@ -35,6 +45,20 @@ pub struct RecoverKeypairResult {
     pub wrap_key_salt: String,
     #[wasm_bindgen(getter_with_clone, js_name = "accountIdHint")]
     pub account_id_hint: Option<String>,
+    /// HMAC tying the envelope to the account and public key; check it with
+    /// `VerifyEncryptedKeyEnvelope` without decrypting.
+    #[wasm_bindgen(getter_with_clone, js_name = "integrityMacB64u")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub integrity_mac_b64u: Option<String>,
+    /// The assertion matched an allowlisted appid instead of `rpId`; prompt the user to
+    /// re-register the credential.
+    #[wasm_bindgen(js_name = "usedLegacyAppId")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used_legacy_app_id: Option<bool>,
+    /// `clientExtensionResults.appid` as reported by the browser.
+    #[wasm_bindgen(js_name = "appidExtension")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appid_extension: Option<bool>,
 }
 
 #[wasm_bindgen]
@@ -53,10 +77,30 @@ impl RecoverKeypairResult {
             chacha20_nonce_b64u,
             wrap_key_salt,
             account_id_hint,
+            integrity_mac_b64u: None,
+            used_legacy_app_id: None,
+            appid_extension: None,
         }
     }
 }
 
+impl RecoverKeypairResult {
+    pub fn with_integrity_mac(mut self, integrity_mac_b64u: String) -> Self {
+        self.integrity_mac_b64u = Some(integrity_mac_b64u);
+        self
+    }
+
+    pub fn with_credential_binding(
+        mut self,
+        binding: Option<CredentialBinding>,
+        appid_extension: Option<bool>,
+    ) -> Self {
+        self.used_legacy_app_id = binding.map(|b| b.used_legacy_app_id);
+        self.appid_extension = appid_extension;
+        self
+    }
+}
+
 /// Recovers a NEAR keypair from an existing WebAuthn authentication credential with dual PRF outputs.
 /// **Handles:** `WorkerRequestType::RecoverKeypairFromPasskey`
 ///
@@ -73,6 +117,7 @@ pub async fn handle_recover_keypair_from_passkey(
     request: RecoverKeypairRequest,
     wrap_key: WrapKey,
 ) -> Result<RecoverKeypairResult, String> {
+    let binding = check_recovery_credential_binding(&request)?;
     let ed25519_prf_output = request
         .credential
         .client_extension_results
@@ -104,6 +149,12 @@ pub async fn handle_recover_keypair_from_passkey(
     let encryption_result = crate::crypto::encrypt_data_chacha20(&private_key, &kek)
         .map_err(|e| format!("Failed to encrypt private key: {}", e))?
         .with_wrap_key_salt(&wrap_key_salt_bytes);
+    let integrity_mac_b64u = wrap_key.near_key_envelope_integrity_mac_b64u(
+        &encryption_result.encrypted_near_key_data_b64u,
+        &encryption_result.chacha20_nonce_b64u,
+        account_id,
+        &public_key,
+    )?;
 
     debug!("[rust wasm]: Successfully derived NEAR keypair and encrypted with ChaCha20Poly1305");
     debug!("[rust wasm]: Key recovery from authentication credential successful");
@@ -114,5 +165,29 @@ pub async fn handle_recover_keypair_from_passkey(
         encryption_result.chacha20_nonce_b64u,
         wrap_key.salt_b64u().to_string(),
         Some(account_id.to_string()),
-    ))
+    )
+    .with_integrity_mac(integrity_mac_b64u)
+    .with_credential_binding(binding, request.credential.client_extension_results.appid))
+}
+
+/// Check the assertion's `rpIdHash` when the caller names the rp id. Without `rpId` there is
+/// nothing to bind to, so an appid allowlist on its own is refused rather than ignored.
+pub fn check_recovery_credential_binding(
+    request: &RecoverKeypairRequest,
+) -> Result<Option<CredentialBinding>, String> {
+    let Some(rp_id) = request.rp_id.as_deref() else {
+        if !request.allowed_app_ids.is_empty() {
+            return Err("allowedAppIds requires rpId".to_string());
+        }
+        return Ok(None);
+    };
+    let authenticator_data =
+        crate::encoders::base64_url_decode(&request.credential.response.authenticator_data)
+            .map_err(|e| format!("Failed to decode authenticatorData: {}", e))?;
+    let binding =
+        validate_credential_binding(&authenticator_data, rp_id, &request.allowed_app_ids)?;
+    if binding.used_legacy_app_id {
+        debug!("[rust wasm]: Recovery assertion matched a legacy appid");
+    }
+    Ok(Some(binding))
 }
@@ -8,6 +8,7 @@ use log::debug;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
+use crate::transaction::parse_nonce;
 use crate::types::wasm_to_json::WasmSignedTransaction;
 use crate::types::SerializedRegistrationCredential;
 use crate::WrapKey;
@@ -31,10 +32,10 @@ pub struct RegisterDevice2WithDerivedKeyRequest {
     #[wasm_bindgen(getter_with_clone, js_name = "nearAccountId")]
     pub near_account_id: String,
 
-    /// Transaction context from VRF worker
+    /// Transaction context from VRF worker (required when signing the registration transaction)
     #[wasm_bindgen(skip)]
-    #[serde(rename = "transactionContext")]
-    pub transaction_context: Device2TransactionContext,
+    #[serde(default, rename = "transactionContext")]
+    pub transaction_context: Option<Device2TransactionContext>,
 
     /// Contract ID (Receiver ID for the transaction)
     #[wasm_bindgen(getter_with_clone, js_name = "contractId")]
@@ -42,8 +43,24 @@ pub struct RegisterDevice2WithDerivedKeyRequest {
 
     /// Contract arguments for Device2 registration (JSON string already serialized in JS)
     #[wasm_bindgen(skip)]
-    #[serde(rename = "contractArgsJson")]
-    pub contract_args_json: String,
+    #[serde(default, rename = "contractArgsJson")]
+    pub contract_args_json: Option<String>,
+
+    /// Sign the `link_device_register_user` transaction with the derived key (default: true).
+    /// Set to false to only derive and encrypt the NEAR key, e.g. before a key swap.
+    #[wasm_bindgen(js_name = "signRegistrationTransaction")]
+    #[serde(default = "default_true")]
+    pub sign_registration_transaction: bool,
+
+    /// NEAR RPC URL. When set, the next device number is taken from the contract's
+    /// authenticator list; otherwise from the worker's local device registry.
+    #[wasm_bindgen(getter_with_clone, js_name = "rpcUrl")]
+    #[serde(default)]
+    pub rpc_url: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 /// Transaction context from NEAR RPC
@@ -80,9 +97,21 @@ pub struct RegisterDevice2WithDerivedKeyResult {
     #[wasm_bindgen(getter_with_clone, js_name = "wrapKeySalt")]
     pub wrap_key_salt: String,
 
-    /// Signed registration transaction (borsh-serialized, base64url-encoded)
+    /// Signed registration transaction; absent when `signRegistrationTransaction` was false
     #[wasm_bindgen(getter_with_clone, js_name = "signedTransaction")]
-    pub signed_transaction: WasmSignedTransaction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signed_transaction: Option<WasmSignedTransaction>,
+
+    /// Device number assigned to this device and recorded in the device registry
+    #[wasm_bindgen(js_name = "deviceNumber")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_number: Option<u8>,
+
+    /// HMAC tying the envelope to the account and public key; check it with
+    /// `VerifyEncryptedKeyEnvelope` without decrypting.
+    #[wasm_bindgen(getter_with_clone, js_name = "integrityMacB64u")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub integrity_mac_b64u: Option<String>,
 }
 
 #[wasm_bindgen]
@@ -93,7 +122,7 @@ impl RegisterDevice2WithDerivedKeyResult {
         encrypted_data: String,
         chacha20_nonce_b64u: String,
         wrap_key_salt: String,
-        signed_transaction: WasmSignedTransaction,
+        signed_transaction: Option<WasmSignedTransaction>,
     ) -> RegisterDevice2WithDerivedKeyResult {
         RegisterDevice2WithDerivedKeyResult {
             public_key,
@@ -101,10 +130,24 @@ impl RegisterDevice2WithDerivedKeyResult {
             chacha20_nonce_b64u,
             wrap_key_salt,
             signed_transaction,
+            device_number: None,
+            integrity_mac_b64u: None,
         }
     }
 }
 
+impl RegisterDevice2WithDerivedKeyResult {
+    pub fn with_device_number(mut self, device_number: u8) -> Self {
+        self.device_number = Some(device_number);
+        self
+    }
+
+    pub fn with_integrity_mac(mut self, integrity_mac_b64u: String) -> Self {
+        self.integrity_mac_b64u = Some(integrity_mac_b64u);
+        self
+    }
+}
+
 /// Handler for combined Device2 registration.
 ///
 /// This handler performs:
@@ -113,7 +156,11 @@ impl RegisterDevice2WithDerivedKeyResult {
 /// 3. Encrypt NEAR private key with KEK (derived from WrapKeySeed + wrapKeySalt)
 /// 4. Build Device2 registration transaction (`link_device_register_user`)
 /// 5. Sign transaction with the derived NEAR keypair
-/// 6. Return public key, encrypted key data, and signed transaction
+/// 6. Record the device under the next free device number (contract-backed when `rpcUrl` is set)
+/// 7. Return public key, encrypted key data, signed transaction, and device number
+///
+/// Steps 4-5 replace a separate `SignTransactionsWithActions` round trip and are skipped
+/// when `sign_registration_transaction` is false.
 ///
 /// # Arguments
 /// * `request` - Contains sessionId, account ID, transaction context, contract args JSON
@@ -121,7 +168,7 @@ impl RegisterDevice2WithDerivedKeyResult {
 /// * `prf_second_b64u` - PRF.second output retrieved from session storage
 ///
 /// # Returns
-/// * `RegisterDevice2WithDerivedKeyResult` - Public key, encrypted key data, signed tx
+/// * `RegisterDevice2WithDerivedKeyResult` - Public key, encrypted key data, optional signed tx
 pub async fn handle_register_device2_with_derived_key(
     request: RegisterDevice2WithDerivedKeyRequest,
     wrap_key: WrapKey,
@@ -132,6 +179,21 @@ pub async fn handle_register_device2_with_derived_key(
         request.near_account_id
     );
 
+    // `link_device_register_user` registers a device against a named account
+    if crate::types::near::is_implicit_account_id(&request.near_account_id) {
+        return Err(crate::error::AccountIdError::NamedAccountRequired {
+            account_id: request.near_account_id.clone(),
+        }
+        .into());
+    }
+
+    let device_number = crate::device_registry::next_device_number_for_link(
+        &request.near_account_id,
+        &request.contract_id,
+        request.rpc_url.as_deref(),
+    )
+    .await?;
+
     // === STEP 1: Derive NEAR keypair from PRF.second ===
     let (near_private_key, near_public_key) = crate::crypto::derive_ed25519_key_from_prf_output(
         &prf_second_b64u,
@@ -155,7 +217,51 @@ pub async fn handle_register_device2_with_derived_key(
     let encryption_result = crate::crypto::encrypt_data_chacha20(&near_private_key, &kek)
         .map_err(|e| format!("Failed to encrypt Device2 private key: {}", e))?
         .with_wrap_key_salt(&wrap_key_salt_bytes);
+    let integrity_mac_b64u = wrap_key.near_key_envelope_integrity_mac_b64u(
+        &encryption_result.encrypted_near_key_data_b64u,
+        &encryption_result.chacha20_nonce_b64u,
+        &request.near_account_id,
+        &near_public_key,
+    )?;
+
+    let signed_transaction = if request.sign_registration_transaction {
+        Some(sign_device2_registration_transaction(
+            &request,
+            &near_private_key,
+        )?)
+    } else {
+        debug!("[rust wasm signer]: Skipping Device2 registration transaction signing");
+        None
+    };
 
+    crate::device_registry::record_device(
+        &request.near_account_id,
+        crate::device_registry::DeviceRecord {
+            device_number,
+            public_key: near_public_key.clone(),
+            credential_id: request.credential.id.clone(),
+            registered_at_ms: crate::device_registry::now_ms(),
+        },
+    );
+
+    Ok(RegisterDevice2WithDerivedKeyResult::new(
+        near_public_key,
+        encryption_result.encrypted_near_key_data_b64u,
+        encryption_result.chacha20_nonce_b64u,
+        encryption_result
+            .wrap_key_salt_b64u
+            .unwrap_or_else(|| String::new()),
+        signed_transaction,
+    )
+    .with_device_number(device_number)
+    .with_integrity_mac(integrity_mac_b64u))
+}
+
+/// Build and sign the `link_device_register_user` transaction with the derived NEAR key.
+fn sign_device2_registration_transaction(
+    request: &RegisterDevice2WithDerivedKeyRequest,
+    near_private_key: &str,
+) -> Result<WasmSignedTransaction, String> {
     // === STEP 3: Parse private key to extract signing key ===
     // near_private_key is in format "ed25519:base58_encoded_64_bytes"
     let private_key_str = if near_private_key.starts_with("ed25519:") {
@@ -191,10 +297,14 @@ pub async fn handle_register_device2_with_derived_key(
 
     // === STEP 4: Build Device2 registration transaction ===
     // Use the JSON args provided by TS directly
-    let function_call_args = request.contract_args_json.clone().into_bytes();
+    let function_call_args = request
+        .contract_args_json
+        .clone()
+        .ok_or_else(|| "Missing contractArgsJson for Device2 registration signing".to_string())?
+        .into_bytes();
 
     let registration_tx =
-        build_device2_registration_transaction(&request, &public_key_bytes, function_call_args)?;
+        build_device2_registration_transaction(request, &public_key_bytes, function_call_args)?;
 
     debug!(
         "[rust wasm signer]: Built Device2 registration transaction for contract {}",
@@ -217,18 +327,7 @@ pub async fn handle_register_device2_with_derived_key(
     let signed_tx = crate::types::SignedTransaction::from_borsh_bytes(&signed_tx_bytes)
         .map_err(|e| format!("Failed to deserialize SignedTransaction: {}", e))?;
 
-    let signed_transaction_wasm = WasmSignedTransaction::from(&signed_tx);
-
-    // === STEP 7: Return result ===
-    Ok(RegisterDevice2WithDerivedKeyResult::new(
-        near_public_key,
-        encryption_result.encrypted_near_key_data_b64u,
-        encryption_result.chacha20_nonce_b64u,
-        encryption_result
-            .wrap_key_salt_b64u
-            .unwrap_or_else(|| String::new()),
-        signed_transaction_wasm,
-    ))
+    Ok(WasmSignedTransaction::from(&signed_tx))
 }
 
 /// Build Device2 registration transaction for `link_device_register_user` contract call.
@@ -239,15 +338,16 @@ fn build_device2_registration_transaction(
 ) -> Result<crate::types::near::Transaction, String> {
     use crate::types::near::{FunctionCallAction, NearAction};
 
-    // Parse nonce
-    let parsed_nonce = request
+    let transaction_context = request
         .transaction_context
-        .base_nonce
-        .parse::<u64>()
-        .map_err(|e| format!("Invalid nonce format: {}", e))?;
+        .as_ref()
+        .ok_or_else(|| "Missing transactionContext for Device2 registration signing".to_string())?;
+
+    // Parse nonce
+    let parsed_nonce = parse_nonce(&transaction_context.base_nonce)?;
 
     // Decode block hash (base58-encoded)
-    let block_hash_bytes = bs58::decode(&request.transaction_context.tx_block_hash)
+    let block_hash_bytes = bs58::decode(&transaction_context.tx_block_hash)
         .into_vec()
         .map_err(|e| format!("Failed to decode block hash: {}", e))?;
     let block_hash: [u8; 32] = block_hash_bytes
@@ -2,7 +2,7 @@ use crate::threshold::signer_backend::Ed25519SignerBackend;
 use crate::threshold::threshold_client_share::derive_threshold_client_verifying_share_bytes_v1;
 use crate::threshold::threshold_frost::compute_threshold_ed25519_group_public_key_2p_from_verifying_shares;
 use crate::transaction::{
-    build_transaction_with_actions, calculate_transaction_hash, sign_transaction,
+    build_transaction_with_actions, calculate_transaction_hash, parse_nonce, sign_transaction,
 };
 use crate::types::{
     AccessKey, AccessKeyPermission, DecryptionPayload, NearAction, PublicKey, SignedTransaction,
@@ -14,7 +14,7 @@ use curve25519_dalek::edwards::CompressedEdwardsY;
 use serde::Deserialize;
 
 use super::handle_sign_transactions_with_actions::TransactionSignResult;
-use crate::encoders::base64_url_decode;
+use crate::encoders::B64UrlBytes;
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -22,9 +22,13 @@ pub struct SignAddKeyThresholdPublicKeyNoPromptRequest {
     pub session_id: String,
     pub created_at: Option<f64>,
     pub decryption: DecryptionPayload,
+    /// WebAuthn credential id whose key envelope to decrypt; defaults to the session's credential.
+    #[serde(default)]
+    pub credential_id: Option<String>,
     pub near_account_id: String,
     pub threshold_public_key: String,
-    pub relayer_verifying_share_b64u: String,
+    /// Relayer's FROST verifying share (`Encoding::B64Url`).
+    pub relayer_verifying_share_b64u: B64UrlBytes,
     pub client_participant_id: Option<u16>,
     pub relayer_participant_id: Option<u16>,
     pub transaction_context: crate::types::handlers::TransactionContext,
@@ -54,29 +58,30 @@ pub async fn handle_sign_add_key_threshold_public_key_no_prompt(
         return Err("Missing thresholdPublicKey".to_string());
     }
 
-    let relayer_verifying_share_b64u = request.relayer_verifying_share_b64u.trim();
-    if relayer_verifying_share_b64u.is_empty() {
+    if request.relayer_verifying_share_b64u.as_bytes().is_empty() {
         return Err("Missing relayerVerifyingShareB64u".to_string());
     }
 
     let tx_context = request.transaction_context;
 
-    let nonce: u64 = tx_context
-        .next_nonce
-        .trim()
-        .parse()
-        .map_err(|e| format!("Invalid transactionContext.nextNonce: {e}"))?;
+    let nonce = parse_nonce(&tx_context.next_nonce)?;
 
     let block_hash_bytes = bs58::decode(tx_context.tx_block_hash.trim())
         .into_vec()
         .map_err(|e| format!("Invalid block hash base58: {e}"))?;
 
     // Local signer: decrypt and sign using the locally stored encrypted key material.
+    let presented_credential_id =
+        crate::types::handlers::presented_credential_id(None, &request.session_id);
+    let envelope = request.decryption.select_envelope(
+        request.credential_id.as_deref(),
+        presented_credential_id.as_deref(),
+    )?;
     let signer = Ed25519SignerBackend::from_encrypted_near_private_key(
         SignerMode::LocalSigner,
         &wrap_key,
-        &request.decryption.encrypted_private_key_data,
-        &request.decryption.encrypted_private_key_chacha20_nonce_b64u,
+        &envelope,
+        near_account_id,
     )?;
     let signer_public_key_bytes = signer.public_key_bytes()?;
 
@@ -100,7 +105,7 @@ pub async fn handle_sign_add_key_threshold_public_key_no_prompt(
 
     let client_verifying_share_bytes =
         derive_threshold_client_verifying_share_bytes_v1(&wrap_key, near_account_id)?;
-    let decoded_relayer_verifying_share = base64_url_decode(relayer_verifying_share_b64u)?;
+    let decoded_relayer_verifying_share = request.relayer_verifying_share_b64u.as_bytes();
     if decoded_relayer_verifying_share.len() != 32 {
         return Err(format!(
             "Invalid relayer verifying share length: expected 32 bytes, got {}",
@@ -108,7 +113,6 @@ pub async fn handle_sign_add_key_threshold_public_key_no_prompt(
         ));
     }
     let relayer_verifying_share_bytes: [u8; 32] = decoded_relayer_verifying_share
-        .as_slice()
         .try_into()
         .expect("checked length above");
 
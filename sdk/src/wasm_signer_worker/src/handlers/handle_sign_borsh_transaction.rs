@@ -0,0 +1,122 @@
+// ******************************************************************************
+// *                                                                            *
+// *                    HANDLER: SIGN BORSH TRANSACTION                         *
+// *                                                                            *
+// ******************************************************************************
+
+use serde::Deserialize;
+
+use crate::encoders::B64UrlBytes;
+use crate::error::BorshTransactionError;
+use crate::handlers::handle_sign_transactions_with_actions::TransactionSignResult;
+use crate::threshold::signer_backend::Ed25519SignerBackend;
+use crate::transaction::{calculate_transaction_hash, sign_transaction};
+use crate::types::{
+    handlers::presented_credential_id, wasm_to_json::WasmSignedTransaction, DecryptionPayload,
+    PublicKey, SignedTransaction, SignerMode, Transaction,
+};
+use crate::WrapKey;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignBorshTransactionRequest {
+    pub session_id: String,
+    /// Unsigned `Transaction` as built by the caller (e.g. a wallet adapter), borsh-serialized
+    /// and base64url-encoded (`Encoding::B64Url`).
+    pub transaction_borsh_b64u: B64UrlBytes,
+    pub decryption: DecryptionPayload,
+    pub credential: Option<String>,
+    /// WebAuthn credential id whose key envelope to decrypt; defaults to the credential behind
+    /// the presented assertion.
+    #[serde(default)]
+    pub credential_id: Option<String>,
+}
+
+/// Decode the `transactionBorshB64u` bytes. Trailing bytes after the transaction are rejected.
+pub fn decode_borsh_transaction(
+    transaction_borsh: &[u8],
+) -> Result<Transaction, BorshTransactionError> {
+    borsh::from_slice(transaction_borsh)
+        .map_err(|e| BorshTransactionError::InvalidTransaction(e.to_string()))
+}
+
+/// Reject a transaction whose embedded `public_key` is not the key about to sign it; NEAR would
+/// refuse the signature anyway, and signing it would hand out a signature over foreign bytes.
+pub fn ensure_transaction_signer_key(
+    transaction: &Transaction,
+    signing_public_key: &[u8; 32],
+) -> Result<(), BorshTransactionError> {
+    let key = &transaction.public_key;
+    if key.key_type != 0 {
+        return Err(BorshTransactionError::UnsupportedKeyType(key.key_type));
+    }
+    if key != &PublicKey::from_ed25519_bytes(signing_public_key) {
+        return Err(BorshTransactionError::SignerPublicKeyMismatch {
+            transaction_public_key: format!("ed25519:{}", bs58::encode(key.key_data).into_string()),
+            signing_public_key: format!(
+                "ed25519:{}",
+                bs58::encode(signing_public_key).into_string()
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Decrypt the session key for the transaction's `signer_id`, check it is the transaction's
+/// `public_key`, and sign. Returns the transaction hash and the signed transaction.
+pub async fn sign_borsh_transaction(
+    request: &SignBorshTransactionRequest,
+    wrap_key: &WrapKey,
+) -> Result<(String, SignedTransaction), String> {
+    let transaction = decode_borsh_transaction(request.transaction_borsh_b64u.as_bytes())?;
+    let near_account_id = transaction.signer_id.0.clone();
+    crate::sign_counter::enforce_sign_count(request.credential.as_deref(), &near_account_id)?;
+
+    let presented_credential_id =
+        presented_credential_id(request.credential.as_deref(), &request.session_id);
+    let envelope = request.decryption.select_envelope(
+        request.credential_id.as_deref(),
+        presented_credential_id.as_deref(),
+    )?;
+    let signer = Ed25519SignerBackend::from_session_near_private_key(
+        SignerMode::LocalSigner,
+        &request.session_id,
+        wrap_key,
+        &envelope,
+        &near_account_id,
+    )?;
+    ensure_transaction_signer_key(&transaction, &signer.public_key_bytes()?)?;
+
+    let (hash_to_sign, _size) = transaction.get_hash_and_size();
+    let signature = signer.sign(&hash_to_sign.0).await?;
+    let signed_tx_bytes = sign_transaction(transaction, &signature)?;
+    let transaction_hash = calculate_transaction_hash(&signed_tx_bytes);
+    let signed_tx = SignedTransaction::from_borsh_bytes(&signed_tx_bytes)
+        .map_err(|e| format!("Failed to deserialize SignedTransaction: {}", e))?;
+    Ok((transaction_hash, signed_tx))
+}
+
+/// **Handles:** `WorkerRequestType::SignBorshTransaction`
+/// Signs an unsigned transaction the caller already serialized, instead of building one from
+/// action params. The key envelope is decrypted for the transaction's `signer_id`, and the
+/// transaction must name that key as its `public_key`. Local signer only.
+pub async fn handle_sign_borsh_transaction(
+    request: SignBorshTransactionRequest,
+    wrap_key: WrapKey,
+) -> Result<TransactionSignResult, String> {
+    let (transaction_hash, signed_tx) = sign_borsh_transaction(&request, &wrap_key).await?;
+    let logs = vec![format!(
+        "Signed borsh transaction {} -> {} ({} actions)",
+        signed_tx.transaction.signer_id.0,
+        signed_tx.transaction.receiver_id.0,
+        signed_tx.transaction.actions.len()
+    )];
+
+    Ok(TransactionSignResult::new(
+        true,
+        Some(vec![transaction_hash]),
+        Some(vec![WasmSignedTransaction::from(&signed_tx)]),
+        logs,
+        None,
+    ))
+}
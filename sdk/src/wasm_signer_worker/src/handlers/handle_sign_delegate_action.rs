@@ -1,7 +1,8 @@
 use crate::actions::ActionParams;
-use crate::encoders::hash_delegate_action;
+use crate::signable_message::{compute_signable_digest, SignableMessageKind};
+use crate::signature_preimage::SignaturePreimage;
 use crate::threshold::signer_backend::Ed25519SignerBackend;
-use crate::transaction::build_actions_from_params;
+use crate::transaction::{build_actions_from_params, parse_nonce};
 use crate::types::progress::{
     send_completion_message, send_progress_message, ProgressData, ProgressMessageType, ProgressStep,
 };
@@ -21,6 +22,7 @@ use wasm_bindgen::prelude::*;
 pub struct DelegatePayload {
     pub sender_id: String,
     pub receiver_id: String,
+    #[serde(deserialize_with = "crate::actions::deserialize_actions_flexible")]
     pub actions: Vec<ActionParams>,
     pub nonce: String,
     pub max_block_height: String,
@@ -46,6 +48,17 @@ pub struct SignDelegateActionRequest {
     /// VRF challenge data required for relayer authorization in threshold mode.
     pub vrf_challenge: Option<crate::types::VrfChallenge>,
     pub credential: Option<String>,
+    /// WebAuthn credential id whose key envelope to decrypt (local signer); defaults to the
+    /// credential behind the presented assertion.
+    #[serde(default)]
+    pub credential_id: Option<String>,
+    /// Blocks above the current height a delegate may expire at
+    /// (default `DELEGATE_DEFAULT_MAX_BLOCK_HEIGHT_WINDOW`).
+    #[serde(default)]
+    pub max_block_height_window: Option<u64>,
+    /// Return the exact bytes the signature covers in `signaturePreimage` (for audit).
+    #[serde(default)]
+    pub include_signature_preimage: bool,
 }
 
 #[wasm_bindgen]
@@ -61,6 +74,10 @@ pub struct DelegateSignResult {
     pub logs: Vec<String>,
     #[wasm_bindgen(getter_with_clone)]
     pub error: Option<String>,
+    /// Present only when `includeSignaturePreimage` was requested
+    #[wasm_bindgen(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature_preimage: Option<SignaturePreimage>,
 }
 
 #[wasm_bindgen]
@@ -79,6 +96,7 @@ impl DelegateSignResult {
             signed_delegate,
             logs,
             error,
+            signature_preimage: None,
         }
     }
 
@@ -87,13 +105,60 @@ impl DelegateSignResult {
     }
 }
 
+impl DelegateSignResult {
+    pub fn with_signature_preimage(mut self, preimage: SignaturePreimage) -> Self {
+        self.signature_preimage = Some(preimage);
+        self
+    }
+}
+
+/// Resolve and bound the delegate's `maxBlockHeight` against the caller-provided current height.
+///
+/// Zero means "no explicit expiry" and becomes `current + DELEGATE_DEFAULT_EXPIRY_BLOCKS`; it is
+/// rejected when no current height is known. Otherwise the height must lie in
+/// `(current, current + window]`. Without a current height only zero is rejected.
+pub fn resolve_delegate_max_block_height(
+    max_block_height: u64,
+    current_block_height: Option<u64>,
+    window: u64,
+) -> Result<u64, String> {
+    let out_of_range = || crate::config::ERROR_MAX_BLOCK_HEIGHT_OUT_OF_RANGE.to_string();
+    let Some(current) = current_block_height else {
+        return if max_block_height == 0 {
+            Err(out_of_range())
+        } else {
+            Ok(max_block_height)
+        };
+    };
+    let resolved = if max_block_height == 0 {
+        current.saturating_add(crate::config::DELEGATE_DEFAULT_EXPIRY_BLOCKS.min(window))
+    } else {
+        max_block_height
+    };
+    if resolved <= current || resolved - current > window {
+        return Err(out_of_range());
+    }
+    Ok(resolved)
+}
+
 /// Handles session-based delegate action signing (NEP-461).
 pub async fn handle_sign_delegate_action(
     request: SignDelegateActionRequest,
     wrap_key: WrapKey,
 ) -> Result<DelegateSignResult, String> {
+    crate::host_environment::require_host_bridge(crate::host_environment::SEND_PROGRESS_MESSAGE)?;
     let mut logs: Vec<String> = Vec::new();
 
+    // Reject credentials minted for a different origin than the session is bound to
+    if let Some(confirmation_config) = request.confirmation_config.as_ref() {
+        confirmation_config.enforce_expected_origin(request.credential.as_deref())?;
+    }
+    // Reject assertions whose signCount went backwards (possible authenticator clone)
+    crate::sign_counter::enforce_sign_count(
+        request.credential.as_deref(),
+        &request.rpc_call.near_account_id,
+    )?;
+
     // Validate session expiry if created_at is present
     if let Some(created_at) = request.created_at {
         let now = js_sys::Date::now();
@@ -152,14 +217,22 @@ pub async fn handle_sign_delegate_action(
     // Derive delegate nonce from transaction context whenever possible so it
     // matches the user's on-chain access key nonce expectations. This avoids
     // DelegateActionInvalidNonce when relayers submit the signed delegate.
-    let mut nonce: u64 = match request.delegate.nonce.parse() {
-        Ok(n) => n,
-        Err(_) => 0,
+    // An empty delegate nonce means "take it from the transaction context".
+    let mut nonce = if request.delegate.nonce.trim().is_empty() {
+        0
+    } else {
+        match parse_nonce(&request.delegate.nonce) {
+            Ok(n) => n,
+            Err(error_msg) => {
+                logs.push(error_msg.clone());
+                return Ok(DelegateSignResult::failed(logs, error_msg));
+            }
+        }
     };
 
     if let Some(ctx) = &request.transaction_context {
         // Use the NonceManager-provided nextNonce as the canonical delegate nonce.
-        match ctx.next_nonce.parse::<u64>() {
+        match parse_nonce(&ctx.next_nonce) {
             Ok(chain_next) => {
                 if nonce == 0 || nonce <= chain_next {
                     let prev = nonce;
@@ -170,8 +243,7 @@ pub async fn handle_sign_delegate_action(
                     ));
                 }
             }
-            Err(e) => {
-                let error_msg = format!("Invalid transactionContext.nextNonce: {}", e);
+            Err(error_msg) => {
                 logs.push(error_msg.clone());
                 return Ok(DelegateSignResult::failed(logs, error_msg));
             }
@@ -184,7 +256,7 @@ pub async fn handle_sign_delegate_action(
         return Ok(DelegateSignResult::failed(logs, error_msg));
     }
 
-    let mut max_block_height: u64 = match request.delegate.max_block_height.parse() {
+    let requested_max_block_height: u64 = match request.delegate.max_block_height.parse() {
         Ok(h) => h,
         Err(e) => {
             let error_msg = format!("Invalid maxBlockHeight: {}", e);
@@ -193,27 +265,32 @@ pub async fn handle_sign_delegate_action(
         }
     };
 
-    // Treat maxBlockHeight == 0 as “no explicit expiry” from the caller.
-    // For NEP-461 this must still be a concrete future block height, so
-    // derive one from the transaction context when available.
-    if max_block_height == 0 {
-        if let Some(ctx) = &request.transaction_context {
-            let base: u64 = ctx.tx_block_height.parse().unwrap_or(0);
-            // Give a generous horizon in blocks to avoid accidental expiry
-            // while keeping the delegate bounded.
-            max_block_height = base.saturating_add(10_000);
+    let current_block_height = request
+        .transaction_context
+        .as_ref()
+        .and_then(|ctx| ctx.tx_block_height.parse::<u64>().ok());
+    let window = request
+        .max_block_height_window
+        .unwrap_or(crate::config::DELEGATE_DEFAULT_MAX_BLOCK_HEIGHT_WINDOW);
+    let max_block_height = match resolve_delegate_max_block_height(
+        requested_max_block_height,
+        current_block_height,
+        window,
+    ) {
+        Ok(h) => h,
+        Err(error_msg) => {
             logs.push(format!(
-                "Normalized delegate maxBlockHeight from 0 to {} based on tx_block_height {}",
-                max_block_height, ctx.tx_block_height
+                "{}: requested {}, current height {:?}, window {}",
+                error_msg, requested_max_block_height, current_block_height, window
             ));
-        } else {
-            // Fallback: choose a fixed horizon if no context is present.
-            max_block_height = 10_000;
-            logs.push(
-                "Normalized delegate maxBlockHeight from 0 to fallback 10000 (no tx context)"
-                    .to_string(),
-            );
+            return Ok(DelegateSignResult::failed(logs, error_msg));
         }
+    };
+    if max_block_height != requested_max_block_height {
+        logs.push(format!(
+            "Normalized delegate maxBlockHeight from 0 to {} based on tx_block_height {:?}",
+            max_block_height, current_block_height
+        ));
     }
 
     let sender_id: AccountId = match request.delegate.sender_id.parse() {
@@ -243,12 +320,23 @@ pub async fn handle_sign_delegate_action(
     );
 
     let signer = match request.signer_mode {
-        SignerMode::LocalSigner => Ed25519SignerBackend::from_encrypted_near_private_key(
-            SignerMode::LocalSigner,
-            &wrap_key,
-            &request.decryption.encrypted_private_key_data,
-            &request.decryption.encrypted_private_key_chacha20_nonce_b64u,
-        )?,
+        SignerMode::LocalSigner => {
+            let presented_credential_id = crate::types::handlers::presented_credential_id(
+                request.credential.as_deref(),
+                &request.session_id,
+            );
+            let envelope = request.decryption.select_envelope(
+                request.credential_id.as_deref(),
+                presented_credential_id.as_deref(),
+            )?;
+            Ed25519SignerBackend::from_session_near_private_key(
+                SignerMode::LocalSigner,
+                &request.session_id,
+                &wrap_key,
+                &envelope,
+                &request.rpc_call.near_account_id,
+            )?
+        }
         SignerMode::ThresholdSigner => {
             let cfg = request
                 .threshold
@@ -296,6 +384,7 @@ pub async fn handle_sign_delegate_action(
 
             Ed25519SignerBackend::from_threshold_signer_config(
                 &wrap_key,
+                &request.session_id,
                 &request.rpc_call.near_account_id,
                 &transaction_context.near_public_key_str,
                 "nep461_delegate",
@@ -349,7 +438,19 @@ pub async fn handle_sign_delegate_action(
         public_key,
     };
 
-    let delegate_hash_bytes = hash_delegate_action(&delegate_action)?;
+    // sha256(NEP-461 prefix || borsh(delegate)); the borsh buffer doubles as the audit preimage
+    let delegate_bytes =
+        borsh::to_vec(&delegate_action).map_err(|e| format!("Delegate encode error: {}", e))?;
+    let delegate_hash_bytes =
+        compute_signable_digest(SignableMessageKind::DelegateAction, &delegate_bytes);
+    let signature_preimage = request.include_signature_preimage.then(|| {
+        SignaturePreimage::for_signer(
+            &signer,
+            SignableMessageKind::DelegateAction,
+            &delegate_bytes,
+            &delegate_hash_bytes,
+        )
+    });
     let delegate_hash_hex: String = delegate_hash_bytes
         .iter()
         .map(|b| format!("{:02x}", b))
@@ -382,11 +483,15 @@ pub async fn handle_sign_delegate_action(
         ),
     );
 
-    Ok(DelegateSignResult::new(
+    let result = DelegateSignResult::new(
         true,
         Some(delegate_hash_hex),
         Some(wasm_signed_delegate),
         logs,
         None,
-    ))
+    );
+    Ok(match signature_preimage {
+        Some(preimage) => result.with_signature_preimage(preimage),
+        None => result,
+    })
 }
@@ -0,0 +1,505 @@
+// ******************************************************************************
+// *                                                                            *
+// *                          HANDLER: SIGN INTENT                              *
+// *                                                                            *
+// ******************************************************************************
+
+use borsh::BorshSerialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use wasm_bindgen::prelude::*;
+
+use crate::actions::{enforce_allowed_action_kinds, enforce_allowed_methods};
+use crate::encoders::base64_url_encode;
+use crate::error::IntentError;
+use crate::handlers::handle_sign_transactions_with_actions::TransactionPayload;
+use crate::threshold::signer_backend::Ed25519SignerBackend;
+use crate::transaction::{
+    build_actions_from_params, build_transaction_with_actions, calculate_transaction_hash,
+    parse_nonce, sign_transaction,
+};
+use crate::types::{
+    handlers::{presented_credential_id, ConfirmationConfig, RpcCallPayload, TransactionContext},
+    progress::{
+        send_completion_message, send_progress_message, ProgressData, ProgressMessageType,
+        ProgressStep,
+    },
+    wasm_to_json::WasmSignedTransaction,
+    DecryptionPayload, NearAction, SignedTransaction, SignerMode,
+};
+use crate::WrapKey;
+
+/// Limits the whole intent must respect, checked against the aggregated steps.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntentConstraints {
+    /// Upper bound on the summed FunctionCall/Transfer deposits of all steps.
+    pub max_total_deposit_yocto: String,
+    /// Last block height at which the intent may still be signed.
+    pub deadline_block_height: String,
+}
+
+/// Sequential multi-contract operation confirmed once and signed as one unit
+/// (e.g. approve on a token, swap on a DEX, withdraw).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Intent {
+    pub intent_id: String,
+    pub description: String,
+    pub steps: Vec<TransactionPayload>,
+    pub constraints: IntentConstraints,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignIntentRequest {
+    pub signer_mode: SignerMode,
+    pub rpc_call: RpcCallPayload,
+    pub session_id: String,
+    pub created_at: Option<f64>,
+    pub decryption: DecryptionPayload,
+    pub intent: Intent,
+    pub confirmation_config: Option<ConfirmationConfig>,
+    /// Digest the confirmation (and VRF challenge) was bound to; must equal `compute_intent_digest`.
+    pub intent_digest: Option<String>,
+    pub transaction_context: Option<TransactionContext>,
+    pub credential: Option<String>,
+    /// WebAuthn credential id whose key envelope to decrypt; defaults to the credential behind
+    /// the presented assertion.
+    #[serde(default)]
+    pub credential_id: Option<String>,
+    /// Optional FunctionCall method allowlist; `None`/empty means unrestricted.
+    #[serde(default)]
+    pub allowed_methods: Option<Vec<String>>,
+    /// Optional action-kind allowlist; `None`/empty means unrestricted.
+    #[serde(default)]
+    pub allowed_action_kinds: Option<Vec<String>>,
+    /// Keep signing later steps after a step fails. The failed step does not consume a nonce.
+    #[serde(default)]
+    pub continue_on_error: bool,
+}
+
+/// One line of the confirmation summary.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntentStepSummary {
+    pub index: u32,
+    pub receiver_id: String,
+    pub action_count: u32,
+    pub method_names: Vec<String>,
+    pub deposit_yocto: String,
+}
+
+/// What a single confirmation shows for an intent: the description, then each step in order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntentSummary {
+    pub intent_id: String,
+    pub description: String,
+    pub signer_account_id: String,
+    pub total_deposit_yocto: String,
+    pub max_total_deposit_yocto: String,
+    pub deadline_block_height: String,
+    pub steps: Vec<IntentStepSummary>,
+}
+
+/// A step's outcome. `intent_id` ties every signed transaction back to its intent for audit.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedIntentStep {
+    pub index: u32,
+    pub intent_id: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signed_transaction: Option<WasmSignedTransaction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignIntentResult {
+    /// True only when every step was signed.
+    pub success: bool,
+    pub intent_id: String,
+    pub intent_digest: String,
+    pub steps: Vec<SignedIntentStep>,
+    pub logs: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Borsh layout hashed for the intent digest. Actions are the built NEAR actions, so two
+/// payloads that sign the same transactions hash the same.
+#[derive(BorshSerialize)]
+struct IntentDigestInput<'a> {
+    intent_id: &'a str,
+    description: &'a str,
+    steps: Vec<IntentDigestStep<'a>>,
+    max_total_deposit_yocto: u128,
+    deadline_block_height: u64,
+}
+
+#[derive(BorshSerialize)]
+struct IntentDigestStep<'a> {
+    near_account_id: &'a str,
+    receiver_id: &'a str,
+    actions: Vec<NearAction>,
+}
+
+fn parse_number<T: std::str::FromStr>(field: &str, value: &str) -> Result<T, IntentError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| IntentError::InvalidNumber {
+            field: field.to_string(),
+            value: value.to_string(),
+        })
+}
+
+fn build_step_actions(steps: &[TransactionPayload]) -> Result<Vec<Vec<NearAction>>, IntentError> {
+    if steps.is_empty() {
+        return Err(IntentError::EmptyIntent);
+    }
+    if steps
+        .iter()
+        .any(|step| step.near_account_id != steps[0].near_account_id)
+    {
+        return Err(IntentError::MixedSigners);
+    }
+    steps
+        .iter()
+        .enumerate()
+        .map(|(index, step)| {
+            build_actions_from_params(step.actions.clone())
+                .map_err(|reason| IntentError::InvalidStep { index, reason })
+        })
+        .collect()
+}
+
+/// FunctionCall and Transfer deposits attached by a step's actions.
+fn step_deposit(actions: &[NearAction]) -> Result<u128, IntentError> {
+    actions.iter().try_fold(0u128, |total, action| {
+        let deposit = match action {
+            NearAction::FunctionCall(call) => call.deposit,
+            NearAction::Transfer { deposit } => *deposit,
+            _ => 0,
+        };
+        total
+            .checked_add(deposit)
+            .ok_or(IntentError::DepositOverflow)
+    })
+}
+
+/// `SHA-256(INTENT_DIGEST_DOMAIN || borsh(intent))`, base64url. This is the digest the
+/// confirmation and VRF challenge bind to.
+pub fn compute_intent_digest(intent: &Intent) -> Result<String, IntentError> {
+    let step_actions = build_step_actions(&intent.steps)?;
+    let input = IntentDigestInput {
+        intent_id: &intent.intent_id,
+        description: &intent.description,
+        steps: intent
+            .steps
+            .iter()
+            .zip(step_actions)
+            .map(|(step, actions)| IntentDigestStep {
+                near_account_id: &step.near_account_id,
+                receiver_id: &step.receiver_id,
+                actions,
+            })
+            .collect(),
+        max_total_deposit_yocto: parse_number(
+            "maxTotalDepositYocto",
+            &intent.constraints.max_total_deposit_yocto,
+        )?,
+        deadline_block_height: parse_number(
+            "deadlineBlockHeight",
+            &intent.constraints.deadline_block_height,
+        )?,
+    };
+    let encoded = borsh::to_vec(&input).map_err(|e| IntentError::InvalidNumber {
+        field: "intent".to_string(),
+        value: e.to_string(),
+    })?;
+    let mut hasher = Sha256::new();
+    hasher.update(crate::config::INTENT_DIGEST_DOMAIN);
+    hasher.update(&encoded);
+    Ok(base64_url_encode(&hasher.finalize()))
+}
+
+/// Step-by-step summary for the single confirmation, with the aggregated deposit.
+pub fn summarize_intent(intent: &Intent) -> Result<IntentSummary, IntentError> {
+    summarize_with_total(intent).map(|(summary, _)| summary)
+}
+
+fn summarize_with_total(intent: &Intent) -> Result<(IntentSummary, u128), IntentError> {
+    let step_actions = build_step_actions(&intent.steps)?;
+    let mut total_deposit = 0u128;
+    let mut steps = Vec::with_capacity(intent.steps.len());
+    for (index, (step, actions)) in intent.steps.iter().zip(&step_actions).enumerate() {
+        let deposit = step_deposit(actions)?;
+        total_deposit = total_deposit
+            .checked_add(deposit)
+            .ok_or(IntentError::DepositOverflow)?;
+        steps.push(IntentStepSummary {
+            index: index as u32,
+            receiver_id: step.receiver_id.clone(),
+            action_count: actions.len() as u32,
+            method_names: actions
+                .iter()
+                .filter_map(|action| match action {
+                    NearAction::FunctionCall(call) => Some(call.method_name.clone()),
+                    _ => None,
+                })
+                .collect(),
+            deposit_yocto: deposit.to_string(),
+        });
+    }
+    let summary = IntentSummary {
+        intent_id: intent.intent_id.clone(),
+        description: intent.description.clone(),
+        signer_account_id: intent.steps[0].near_account_id.clone(),
+        total_deposit_yocto: total_deposit.to_string(),
+        max_total_deposit_yocto: intent.constraints.max_total_deposit_yocto.clone(),
+        deadline_block_height: intent.constraints.deadline_block_height.clone(),
+        steps,
+    };
+    Ok((summary, total_deposit))
+}
+
+/// Check the aggregated steps against the intent's constraints at `current_block_height`.
+pub fn validate_intent_constraints(
+    intent: &Intent,
+    current_block_height: u64,
+) -> Result<IntentSummary, IntentError> {
+    let (summary, total_yocto) = summarize_with_total(intent)?;
+    let max_yocto: u128 = parse_number(
+        "maxTotalDepositYocto",
+        &intent.constraints.max_total_deposit_yocto,
+    )?;
+    let deadline_block_height: u64 = parse_number(
+        "deadlineBlockHeight",
+        &intent.constraints.deadline_block_height,
+    )?;
+    if total_yocto > max_yocto {
+        return Err(IntentError::DepositLimitExceeded {
+            total_yocto,
+            max_yocto,
+        });
+    }
+    if current_block_height > deadline_block_height {
+        return Err(IntentError::DeadlinePassed {
+            current_block_height,
+            deadline_block_height,
+        });
+    }
+    Ok(summary)
+}
+
+/// Sign one step at `nonce`, returning its hash and the signed transaction.
+async fn sign_intent_step(
+    step: &TransactionPayload,
+    actions: Vec<NearAction>,
+    nonce: u64,
+    block_hash: &[u8],
+    public_key_bytes: &[u8; 32],
+    signer: &Ed25519SignerBackend,
+) -> Result<(String, WasmSignedTransaction), String> {
+    let transaction = build_transaction_with_actions(
+        &step.near_account_id,
+        &step.receiver_id,
+        nonce,
+        block_hash,
+        public_key_bytes,
+        actions,
+    )?;
+    let (hash_to_sign, _size) = transaction.get_hash_and_size();
+    let signature = signer.sign(&hash_to_sign.0).await?;
+    let signed_tx_bytes = sign_transaction(transaction, &signature)?;
+    let transaction_hash = calculate_transaction_hash(&signed_tx_bytes);
+    let signed_tx: SignedTransaction = borsh::from_slice(&signed_tx_bytes)
+        .map_err(|e| format!("Failed to deserialize SignedTransaction: {}", e))?;
+    Ok((transaction_hash, WasmSignedTransaction::from(&signed_tx)))
+}
+
+/// **Handles:** `WorkerRequestType::SignIntent`
+/// Signs every step of a confirmed `Intent` with sequential nonces after one approval. The
+/// intent digest is recomputed and must match the confirmed one, and the constraints are
+/// checked against the aggregated steps before any key material is used. Local signer only:
+/// threshold relayers authorize per-transaction digests, not intents.
+pub async fn handle_sign_intent(
+    request: SignIntentRequest,
+    wrap_key: WrapKey,
+) -> Result<SignIntentResult, String> {
+    crate::host_environment::require_host_bridge(crate::host_environment::SEND_PROGRESS_MESSAGE)?;
+
+    if request.signer_mode != SignerMode::LocalSigner {
+        return Err("SignIntent is only supported with the local signer".to_string());
+    }
+    if let Some(confirmation_config) = request.confirmation_config.as_ref() {
+        confirmation_config.enforce_expected_origin(request.credential.as_deref())?;
+    }
+    crate::sign_counter::enforce_sign_count(
+        request.credential.as_deref(),
+        &request.rpc_call.near_account_id,
+    )?;
+    if let Some(created_at) = request.created_at {
+        let now = js_sys::Date::now();
+        if now - created_at > crate::config::SESSION_MAX_DURATION_MS {
+            return Err("Session expired".to_string());
+        }
+    }
+    for step in &request.intent.steps {
+        enforce_allowed_action_kinds(&step.actions, request.allowed_action_kinds.as_deref())?;
+        enforce_allowed_methods(&step.actions, request.allowed_methods.as_deref())?;
+    }
+
+    let intent = &request.intent;
+    let transaction_context = request
+        .transaction_context
+        .as_ref()
+        .ok_or_else(|| "Missing transaction context from confirmation".to_string())?;
+    let confirmed_digest = request
+        .intent_digest
+        .clone()
+        .ok_or_else(|| "Missing intent digest from pre-confirmed session".to_string())?;
+    let intent_digest = compute_intent_digest(intent)?;
+    if intent_digest != confirmed_digest {
+        return Err(IntentError::DigestMismatch {
+            confirmed: confirmed_digest,
+            computed: intent_digest,
+        }
+        .into());
+    }
+    let current_block_height: u64 =
+        parse_number("txBlockHeight", &transaction_context.tx_block_height)?;
+    let summary = validate_intent_constraints(intent, current_block_height)?;
+
+    let step_count = intent.steps.len();
+    let mut logs = vec![format!(
+        "Intent {} ({} steps, {} yoctoNEAR deposited): {}",
+        intent.intent_id, step_count, summary.total_deposit_yocto, intent.description
+    )];
+    send_progress_message(
+        ProgressMessageType::ExecuteActionsProgress,
+        ProgressStep::TransactionSigningProgress,
+        "Signing intent steps...",
+        Some(&ProgressData::new(1, step_count as u32).with_transaction_count(step_count)),
+    );
+
+    let presented_credential_id =
+        presented_credential_id(request.credential.as_deref(), &request.session_id);
+    let envelope = request.decryption.select_envelope(
+        request.credential_id.as_deref(),
+        presented_credential_id.as_deref(),
+    )?;
+    let signer = Ed25519SignerBackend::from_session_near_private_key(
+        SignerMode::LocalSigner,
+        &request.session_id,
+        &wrap_key,
+        &envelope,
+        &request.rpc_call.near_account_id,
+    )?;
+    let public_key_bytes = signer.public_key_bytes()?;
+    let block_hash = bs58::decode(&transaction_context.tx_block_hash)
+        .into_vec()
+        .map_err(|e| format!("Invalid block hash: {}", e))?;
+    let mut nonce = parse_nonce(&transaction_context.next_nonce)?;
+
+    let step_actions = build_step_actions(&intent.steps)?;
+    let mut steps = Vec::with_capacity(step_count);
+    let mut first_error: Option<String> = None;
+    for (index, (step, actions)) in intent.steps.iter().zip(step_actions).enumerate() {
+        match sign_intent_step(
+            step,
+            actions,
+            nonce,
+            &block_hash,
+            &public_key_bytes,
+            &signer,
+        )
+        .await
+        {
+            Ok((transaction_hash, signed_transaction)) => {
+                logs.push(format!(
+                    "Step {}: signed {} -> {} (nonce {})",
+                    index + 1,
+                    step.near_account_id,
+                    step.receiver_id,
+                    nonce
+                ));
+                steps.push(SignedIntentStep {
+                    index: index as u32,
+                    intent_id: intent.intent_id.clone(),
+                    success: true,
+                    transaction_hash: Some(transaction_hash),
+                    signed_transaction: Some(signed_transaction),
+                    error: None,
+                });
+                nonce = nonce.saturating_add(1);
+            }
+            Err(e) => {
+                let error_msg = format!("Step {}: {}", index + 1, e);
+                logs.push(error_msg.clone());
+                steps.push(SignedIntentStep {
+                    index: index as u32,
+                    intent_id: intent.intent_id.clone(),
+                    success: false,
+                    transaction_hash: None,
+                    signed_transaction: None,
+                    error: Some(e),
+                });
+                first_error.get_or_insert(error_msg);
+                if !request.continue_on_error {
+                    break;
+                }
+            }
+        }
+    }
+
+    let success = first_error.is_none() && steps.len() == step_count;
+    send_completion_message(
+        ProgressMessageType::ExecuteActionsProgress,
+        ProgressStep::TransactionSigningComplete,
+        &if success {
+            format!("Intent {} signed ({} steps)", intent.intent_id, step_count)
+        } else {
+            format!("Intent {} signing failed", intent.intent_id)
+        },
+        Some(
+            &ProgressData::new(step_count as u32, step_count as u32)
+                .with_success(success)
+                .with_transaction_count(step_count),
+        ),
+    );
+
+    Ok(SignIntentResult {
+        success,
+        intent_id: intent.intent_id.clone(),
+        intent_digest,
+        steps,
+        logs,
+        error: first_error,
+    })
+}
+
+/// Digest a JS `Intent` without going through the worker envelope, so hosts can bind the
+/// confirmation (and relayers can re-check) before asking the worker to sign.
+#[wasm_bindgen]
+pub fn compute_intent_digest_sync(intent: JsValue) -> Result<String, JsValue> {
+    let intent: Intent = serde_wasm_bindgen::from_value(intent)
+        .map_err(|e| JsValue::from_str(&format!("Invalid intent: {}", e)))?;
+    compute_intent_digest(&intent).map_err(JsValue::from)
+}
+
+/// Confirmation summary for a JS `Intent` (see `IntentSummary`).
+#[wasm_bindgen]
+pub fn summarize_intent_sync(intent: JsValue) -> Result<JsValue, JsValue> {
+    let intent: Intent = serde_wasm_bindgen::from_value(intent)
+        .map_err(|e| JsValue::from_str(&format!("Invalid intent: {}", e)))?;
+    let summary = summarize_intent(&intent)?;
+    serde_wasm_bindgen::to_value(&summary)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize summary: {:?}", e)))
+}
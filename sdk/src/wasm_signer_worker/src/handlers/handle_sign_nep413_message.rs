@@ -4,10 +4,17 @@
 // *                                                                            *
 // ******************************************************************************
 use crate::{
-    encoders::base64_standard_encode, threshold::signer_backend::Ed25519SignerBackend, WrapKey,
+    config::{NEP413_DEFAULT_MAX_MESSAGE_BYTES, NEP413_MAX_RECIPIENT_LEN},
+    encoders::{base64_standard_encode, B64StdBytes},
+    error::Nep413Error,
+    signable_message::SignableMessageKind,
+    signature_preimage::SignaturePreimage,
+    threshold::signer_backend::Ed25519SignerBackend,
+    WrapKey,
 };
 use log::debug;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use wasm_bindgen::prelude::*;
 
 #[derive(Deserialize, Debug, Clone)]
@@ -17,7 +24,7 @@ pub struct SignNep413Request {
     pub signer_mode: crate::types::SignerMode,
     pub message: String,         // Message to sign
     pub recipient: String,       // Recipient identifier
-    pub nonce: String,           // Base64-encoded 32-byte nonce
+    pub nonce: B64StdBytes,      // 32-byte nonce, standard base64 per NEP-413
     pub state: Option<String>,   // Optional state
     pub account_id: String,      // NEAR account ID
     pub near_public_key: String, // NEAR ed25519 public key (ed25519:<base58>)
@@ -30,6 +37,20 @@ pub struct SignNep413Request {
     pub vrf_challenge: Option<crate::types::VrfChallenge>,
     /// Serialized WebAuthn authentication credential JSON (used only for relayer authorization in threshold mode).
     pub credential: Option<String>,
+    /// WebAuthn credential id whose key envelope to decrypt (local signer); defaults to the
+    /// credential behind the presented assertion.
+    #[serde(default)]
+    pub credential_id: Option<String>,
+    /// Expected NEAR ed25519 public key (`ed25519:<base58>`); when set, signing fails
+    /// unless the decrypted key matches it.
+    #[serde(default)]
+    pub expected_public_key: Option<String>,
+    /// Message size cap in UTF-8 bytes; defaults to `NEP413_DEFAULT_MAX_MESSAGE_BYTES`.
+    #[serde(default)]
+    pub max_message_bytes: Option<u32>,
+    /// Return the exact bytes the signature covers in `signaturePreimage` (for audit).
+    #[serde(default)]
+    pub include_signature_preimage: bool,
 }
 
 #[wasm_bindgen]
@@ -41,9 +62,19 @@ pub struct SignNep413Result {
     #[wasm_bindgen(getter_with_clone, js_name = "publicKey")]
     pub public_key: String, // Base58-encoded public key
     #[wasm_bindgen(getter_with_clone)]
-    pub signature: String, // Base64-encoded signature
+    pub signature: String, // Standard base64 signature (NEP-413), never base64url
     #[wasm_bindgen(getter_with_clone)]
     pub state: Option<String>,
+    /// UTF-8 byte length of the signed message.
+    #[wasm_bindgen(js_name = "messageByteLength")]
+    pub message_byte_length: u32,
+    /// Hex SHA-256 of the signed message bytes, for comparison with the confirmation summary.
+    #[wasm_bindgen(getter_with_clone, js_name = "messageSha256Hex")]
+    pub message_sha256_hex: String,
+    /// Present only when `includeSignaturePreimage` was requested
+    #[wasm_bindgen(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature_preimage: Option<SignaturePreimage>,
 }
 
 #[wasm_bindgen]
@@ -54,36 +85,187 @@ impl SignNep413Result {
         public_key: String,
         signature: String,
         state: Option<String>,
+        message_byte_length: u32,
+        message_sha256_hex: String,
     ) -> SignNep413Result {
         SignNep413Result {
             account_id,
             public_key,
             signature,
             state,
+            message_byte_length,
+            message_sha256_hex,
+            signature_preimage: None,
         }
     }
 }
 
+impl SignNep413Result {
+    pub fn with_signature_preimage(mut self, preimage: SignaturePreimage) -> Self {
+        self.signature_preimage = Some(preimage);
+        self
+    }
+}
+
+/// What a confirmation UI must show for a NEP-413 message instead of (possibly truncated)
+/// message text: its exact byte length and SHA-256.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nep413MessageSummary {
+    pub message_byte_length: u32,
+    pub message_sha256_hex: String,
+}
+
+pub fn nep413_message_summary(message: &str) -> Nep413MessageSummary {
+    Nep413MessageSummary {
+        message_byte_length: message.len() as u32,
+        message_sha256_hex: Sha256::digest(message.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect(),
+    }
+}
+
+/// Enforce the message size cap and reject control characters other than `\n` and `\t`.
+/// UTF-8 validity is already guaranteed by deserializing into `String`.
+pub fn validate_nep413_message(message: &str, max_bytes: usize) -> Result<(), Nep413Error> {
+    if message.len() > max_bytes {
+        return Err(Nep413Error::MessageTooLarge {
+            len: message.len(),
+            max: max_bytes,
+        });
+    }
+    if let Some((byte_offset, c)) = message
+        .char_indices()
+        .find(|(_, c)| c.is_control() && *c != '\n' && *c != '\t')
+    {
+        return Err(Nep413Error::ControlCharacter {
+            byte_offset,
+            code_point: c as u32,
+        });
+    }
+    Ok(())
+}
+
+/// A recipient is a NEAR account id or a URL-like `scheme://rest` string, at most
+/// `NEP413_MAX_RECIPIENT_LEN` bytes, with no whitespace or control characters.
+pub fn validate_nep413_recipient(recipient: &str) -> Result<(), Nep413Error> {
+    if recipient.is_empty() {
+        return Err(Nep413Error::InvalidRecipient("empty".to_string()));
+    }
+    if recipient.len() > NEP413_MAX_RECIPIENT_LEN {
+        return Err(Nep413Error::InvalidRecipient(format!(
+            "{} bytes exceeds {}",
+            recipient.len(),
+            NEP413_MAX_RECIPIENT_LEN
+        )));
+    }
+    if recipient
+        .chars()
+        .any(|c| c.is_control() || c.is_whitespace())
+    {
+        return Err(Nep413Error::InvalidRecipient(
+            "contains whitespace or control characters".to_string(),
+        ));
+    }
+    if crate::types::near::validate_account_id_syntax(recipient).is_ok() {
+        return Ok(());
+    }
+    let url_like = recipient
+        .split_once("://")
+        .is_some_and(|(scheme, rest)| {
+            !rest.is_empty()
+                && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        });
+    if url_like {
+        Ok(())
+    } else {
+        Err(Nep413Error::InvalidRecipient(format!(
+            "{:?} is neither a NEAR account id nor a URL",
+            recipient
+        )))
+    }
+}
+
+/// Borsh encoding of the NEP-413 payload; the signature covers
+/// `sha256(NEP-413 prefix || nep413_payload_bytes(..))`.
+pub fn nep413_payload_bytes(
+    message: &str,
+    recipient: &str,
+    nonce: [u8; 32],
+    state: Option<&str>,
+) -> Result<Vec<u8>, String> {
+    #[derive(borsh::BorshSerialize)]
+    struct Nep413Payload<'a> {
+        message: &'a str,
+        recipient: &'a str,
+        nonce: [u8; 32],
+        state: Option<&'a str>,
+    }
+
+    borsh::to_vec(&Nep413Payload {
+        message,
+        recipient,
+        nonce,
+        state,
+    })
+    .map_err(|e| format!("Borsh serialization failed: {}", e))
+}
+
+/// Reject a decrypted signing key that does not match the caller's expected account key.
+/// Without this, a wrong PRF/account combination yields a valid signature under an
+/// unexpected key.
+pub(crate) fn ensure_expected_public_key(
+    public_key_bytes: &[u8; 32],
+    expected_public_key: Option<&str>,
+) -> Result<(), String> {
+    let Some(expected) = expected_public_key else {
+        return Ok(());
+    };
+    let expected_b58 = expected.strip_prefix("ed25519:").unwrap_or(expected);
+    let expected_bytes = bs58::decode(expected_b58)
+        .into_vec()
+        .map_err(|e| format!("Invalid expected public key: {}", e))?;
+    if expected_bytes.as_slice() != public_key_bytes.as_slice() {
+        return Err("decrypted key does not match expected public key".to_string());
+    }
+    Ok(())
+}
+
 /// **Handles:** `WorkerRequestType::SignNep413Message`
 /// This handler implements NEP-413 message signing, which allows signing arbitrary off-chain messages
 /// that cannot represent valid NEAR transactions. It follows the NEP-413 specification for message
 /// structure, serialization, hashing, and signing.
 ///
+/// The message is capped at `maxMessageBytes` (default 16 KiB) and may not contain control
+/// characters other than `\n`/`\t`; the recipient must be an account id or URL-like string.
+/// Both are checked before any key material is touched.
+///
 /// # Arguments
 /// * `request` - Contains message data, recipient, nonce, optional state, and decryption parameters
 ///
 /// # Returns
-/// * `SignNep413Result` - Contains signed message with account ID, public key, signature, and optional state
+/// * `SignNep413Result` - Contains signed message with account ID, public key, signature, optional
+///   state, and the message byte length and SHA-256 the confirmation summary must match
 pub async fn handle_sign_nep413_message(
     request: SignNep413Request,
     wrap_key: WrapKey,
 ) -> Result<SignNep413Result, String> {
     debug!("RUST: Starting NEP-413 message signing");
 
-    // Decode and validate nonce is exactly 32 bytes
-    let nonce_bytes = crate::encoders::base64_standard_decode(&request.nonce)
-        .map_err(|e| format!("Failed to decode nonce from base64: {}", e))?;
+    let max_message_bytes = request
+        .max_message_bytes
+        .map(|n| n as usize)
+        .unwrap_or(NEP413_DEFAULT_MAX_MESSAGE_BYTES);
+    validate_nep413_message(&request.message, max_message_bytes)?;
+    validate_nep413_recipient(&request.recipient)?;
+    crate::sign_counter::enforce_sign_count(request.credential.as_deref(), &request.account_id)?;
 
+    // Decode and validate nonce is exactly 32 bytes
+    // Encoding is validated on deserialize; only the length is left to check.
+    let nonce_bytes = request.nonce.as_bytes();
     if nonce_bytes.len() != 32 {
         return Err(format!(
             "Invalid nonce length: expected 32 bytes, got {}",
@@ -93,11 +275,20 @@ pub async fn handle_sign_nep413_message(
 
     let signer = match request.signer_mode {
         crate::types::SignerMode::LocalSigner => {
-            Ed25519SignerBackend::from_encrypted_near_private_key(
+            let presented_credential_id = crate::types::handlers::presented_credential_id(
+                request.credential.as_deref(),
+                &request.session_id,
+            );
+            let envelope = request.decryption.select_envelope(
+                request.credential_id.as_deref(),
+                presented_credential_id.as_deref(),
+            )?;
+            Ed25519SignerBackend::from_session_near_private_key(
                 crate::types::SignerMode::LocalSigner,
+                &request.session_id,
                 &wrap_key,
-                &request.decryption.encrypted_private_key_data,
-                &request.decryption.encrypted_private_key_chacha20_nonce_b64u,
+                &envelope,
+                &request.account_id,
             )?
         }
         crate::types::SignerMode::ThresholdSigner => {
@@ -113,7 +304,7 @@ pub async fn handle_sign_nep413_message(
                 near_account_id: &'a str,
                 message: &'a str,
                 recipient: &'a str,
-                nonce: &'a str,
+                nonce: &'a B64StdBytes,
                 #[serde(skip_serializing_if = "Option::is_none")]
                 state: Option<&'a str>,
             }
@@ -124,7 +315,7 @@ pub async fn handle_sign_nep413_message(
                     near_account_id: request.account_id.as_str(),
                     message: request.message.as_str(),
                     recipient: request.recipient.as_str(),
-                    nonce: request.nonce.as_str(),
+                    nonce: &request.nonce,
                     state: request.state.as_deref(),
                 })
                 .map_err(|e| format!("Failed to serialize signingPayload: {e}"))?;
@@ -138,6 +329,7 @@ pub async fn handle_sign_nep413_message(
 
             Ed25519SignerBackend::from_threshold_signer_config(
                 &wrap_key,
+                &request.session_id,
                 &request.account_id,
                 &request.near_public_key,
                 "nep413",
@@ -149,56 +341,38 @@ pub async fn handle_sign_nep413_message(
         }
     };
 
-    // Create NEP-413 payload structure for Borsh serialization
-    #[derive(borsh::BorshSerialize)]
-    struct Nep413Payload {
-        message: String,
-        recipient: String,
-        nonce: [u8; 32],
-        state: Option<String>,
-    }
-
     let nonce_array: [u8; 32] = nonce_bytes
         .try_into()
         .map_err(|_| "Failed to convert nonce to 32-byte array")?;
 
-    let payload = Nep413Payload {
-        message: request.message,
-        recipient: request.recipient,
-        nonce: nonce_array,
-        state: request.state.clone(),
-    };
-
     // Serialize with Borsh
-    let serialized =
-        borsh::to_vec(&payload).map_err(|e| format!("Borsh serialization failed: {}", e))?;
+    let serialized = nep413_payload_bytes(
+        &request.message,
+        &request.recipient,
+        nonce_array,
+        request.state.as_deref(),
+    )?;
+    let summary = nep413_message_summary(&request.message);
 
     debug!(
         "RUST: NEP-413 payload serialized with Borsh ({} bytes)",
         serialized.len()
     );
 
-    // Prepend NEP-413 prefix (2^31 + 413 = 2147484061 in little-endian)
-    let prefix: u32 = 2147484061;
-    let mut prefixed_data = prefix.to_le_bytes().to_vec();
-    prefixed_data.extend_from_slice(&serialized);
-
-    debug!(
-        "RUST: NEP-413 prefix added, total data size: {} bytes",
-        prefixed_data.len()
+    // sha256(NEP-413 prefix (2^31 + 413) || borsh(payload))
+    let hash = crate::signable_message::compute_signable_digest(
+        crate::signable_message::SignableMessageKind::Nep413,
+        &serialized,
     );
 
-    // Hash the prefixed data using SHA-256
-    use sha2::{Digest, Sha256};
-    let mut hasher = Sha256::new();
-    hasher.update(&prefixed_data);
-    let hash = hasher.finalize();
-
     debug!("RUST: SHA-256 hash computed");
 
+    // Validate the decrypted key before producing any signature
+    let public_key_bytes = signer.public_key_bytes()?;
+    ensure_expected_public_key(&public_key_bytes, request.expected_public_key.as_deref())?;
+
     // Sign the hash using the Ed25519 private key
     let signature_bytes = signer.sign(hash.as_slice()).await?;
-    let public_key_bytes = signer.public_key_bytes()?;
     let public_key_b58 = format!("ed25519:{}", bs58::encode(&public_key_bytes).into_string());
 
     // Encode signature as base64
@@ -206,10 +380,19 @@ pub async fn handle_sign_nep413_message(
 
     debug!("RUST: NEP-413 message signed successfully");
 
-    Ok(SignNep413Result::new(
+    let signature_preimage = request.include_signature_preimage.then(|| {
+        SignaturePreimage::for_signer(&signer, SignableMessageKind::Nep413, &serialized, &hash)
+    });
+    let result = SignNep413Result::new(
         request.account_id,
         public_key_b58,
         signature_b64,
         request.state,
-    ))
+        summary.message_byte_length,
+        summary.message_sha256_hex,
+    );
+    Ok(match signature_preimage {
+        Some(preimage) => result.with_signature_preimage(preimage),
+        None => result,
+    })
 }
@@ -5,11 +5,11 @@
 // ******************************************************************************
 use std::fmt;
 
-use crate::actions::ActionParams;
+use crate::actions::{deserialize_actions_flexible, ActionParams};
 use crate::handlers::handle_sign_transactions_with_actions::TransactionSignResult;
 use crate::transaction::{
     build_actions_from_params, build_transaction_with_actions, calculate_transaction_hash,
-    sign_transaction,
+    parse_nonce, sign_transaction,
 };
 use crate::types::wasm_to_json::WasmSignedTransaction;
 use bs58;
@@ -40,53 +40,6 @@ impl fmt::Debug for SignTransactionWithKeyPairRequest {
     }
 }
 
-fn deserialize_actions_flexible<'de, D>(deserializer: D) -> Result<Vec<ActionParams>, D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    struct ActionsVisitor;
-
-    impl<'de> serde::de::Visitor<'de> for ActionsVisitor {
-        type Value = Vec<ActionParams>;
-
-        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-            formatter.write_str("a list of actions or a JSON string containing them")
-        }
-
-        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
-        where
-            A: serde::de::SeqAccess<'de>,
-        {
-            let mut vec = Vec::new();
-            while let Some(elem) = seq.next_element()? {
-                vec.push(elem);
-            }
-            Ok(vec)
-        }
-
-        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
-        where
-            E: serde::de::Error,
-        {
-            #[cfg(target_arch = "wasm32")]
-            {
-                let js_val = js_sys::JSON::parse(v)
-                    .map_err(|_| E::custom("Failed to parse actions JSON string"))?;
-                serde_wasm_bindgen::from_value(js_val).map_err(E::custom)
-            }
-            #[cfg(not(target_arch = "wasm32"))]
-            {
-                let _ = v;
-                Err(E::custom(
-                    "Parsing actions from JSON string is not supported on native targets",
-                ))
-            }
-        }
-    }
-
-    deserializer.deserialize_any(ActionsVisitor)
-}
-
 /// Signs a transaction using a provided private key without requiring WebAuthn authentication.
 ///
 /// **Handles:** `WorkerRequestType::SignTransactionWithKeyPair`
@@ -147,10 +100,7 @@ pub async fn handle_sign_transaction_with_keypair(
     let transaction = build_transaction_with_actions(
         &request.signer_account_id,
         &request.receiver_id,
-        request
-            .nonce
-            .parse()
-            .map_err(|e| format!("Invalid nonce: {}", e))?,
+        parse_nonce(&request.nonce)?,
         &bs58::decode(&request.block_hash)
             .into_vec()
             .map_err(|e| format!("Invalid block hash: {}", e))?,
@@ -4,21 +4,42 @@
 // *                                                                            *
 // ******************************************************************************
 
+use crate::balance_check::check_batch_balance;
+use crate::request_deadline::RequestDeadline;
+use crate::rpc_calls::{
+    broadcast_signed_transaction, BroadcastConfig, BroadcastResult, TxFinalStatus,
+};
+use crate::session_key_cache::now_ms;
+use crate::signable_message::SignableMessageKind;
+use crate::signature_preimage::SignaturePreimage;
+use crate::threshold::nonce_reservation::BatchNonces;
 use crate::threshold::signer_backend::Ed25519SignerBackend;
+use crate::threshold::threshold_proof::ThresholdSignatureProof;
 use crate::transaction::{
-    build_actions_from_params, build_transaction_with_actions, calculate_transaction_hash,
-    sign_transaction,
+    append_transaction_signature, build_actions_from_params, build_transaction_with_actions,
+    calculate_transaction_hash, parse_nonce, serialize_transaction_for_signing,
+    signed_transaction_rpc_payload,
 };
 use crate::types::{
-    handlers::{ConfirmationConfig, RpcCallPayload},
+    handlers::{presented_credential_id, ConfirmationConfig, RpcCallPayload},
     progress::{
         send_completion_message, send_progress_message, ProgressData, ProgressMessageType,
         ProgressStep,
     },
     wasm_to_json::WasmSignedTransaction,
-    DecryptionPayload, SignedTransaction, SignerMode, ThresholdSignerConfig,
+    worker_messages::BinaryAttachments,
+    DecryptionPayload, HandlerLogs, LogVerbosity, SignerMode, ThresholdSignerConfig,
+};
+use crate::verification_attestation::{
+    verification_attestation_digest, VerificationAttestation, VERIFICATION_ATTESTATION_VERSION,
+};
+use crate::{
+    actions::{
+        contract_code_size_warnings, enforce_allowed_action_kinds, enforce_allowed_methods,
+        resolve_code_attachments, ActionParams,
+    },
+    WrapKey,
 };
-use crate::{actions::ActionParams, WrapKey};
 use bs58;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
@@ -42,6 +63,57 @@ pub struct SignTransactionsWithActionsRequest {
     /// VRF challenge data required for relayer authorization in threshold mode.
     pub vrf_challenge: Option<crate::types::VrfChallenge>,
     pub credential: Option<String>,
+    /// WebAuthn credential id whose key envelope to decrypt (local signer); defaults to the
+    /// credential behind the presented assertion.
+    #[serde(default)]
+    pub credential_id: Option<String>,
+    /// Optional FunctionCall method allowlist; `None`/empty means unrestricted.
+    #[serde(default)]
+    pub allowed_methods: Option<Vec<String>>,
+    /// Optional action-kind allowlist (e.g. `["FunctionCall", "Transfer"]`); `None`/empty means
+    /// unrestricted.
+    #[serde(default)]
+    pub allowed_action_kinds: Option<Vec<String>>,
+    /// Return a `ThresholdSignatureProof` per signature (threshold mode only).
+    #[serde(default)]
+    pub include_threshold_proof: bool,
+    /// Return the exact bytes each signature covers in `signaturePreimages` (for audit).
+    #[serde(default)]
+    pub include_signature_preimage: bool,
+    /// Broadcast each signed transaction from the worker (opt-in); results land in `broadcastResults`.
+    #[serde(default)]
+    pub broadcast: Option<BroadcastConfig>,
+    /// Which lines end up in `logs`: `"none"`, `"errors"` (failure branches only) or `"all"` (default).
+    #[serde(default)]
+    pub log_verbosity: Option<String>,
+    /// Sign the session's contract verification so a relayer can skip re-verifying (local signer only).
+    #[serde(default)]
+    pub produce_verification_attestation: bool,
+    /// Refuse to sign when the batch costs more than the signer can spend (fetched from
+    /// `rpcCall.nearRpcUrl`). An unreachable RPC only flags the result `balanceUnchecked`.
+    #[serde(default)]
+    pub check_balance: bool,
+    /// DeployContract code above this many bytes is logged as a warning (default 4 MiB).
+    #[serde(default)]
+    pub contract_code_warn_bytes: Option<usize>,
+    /// Envelope `deadlineMsEpoch`, checked before signing and between transactions.
+    #[serde(skip)]
+    pub deadline: RequestDeadline,
+}
+
+impl SignTransactionsWithActionsRequest {
+    /// Move the envelope's `binaryAttachments` into the DeployContract actions that reference
+    /// them (`codeAttachment`). Every attachment must be referenced exactly once.
+    pub fn resolve_binary_attachments(
+        &mut self,
+        mut attachments: BinaryAttachments,
+    ) -> Result<(), String> {
+        for tx in &mut self.tx_signing_requests {
+            resolve_code_attachments(&mut tx.actions, &mut attachments)?;
+        }
+        attachments.ensure_all_taken()?;
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -49,9 +121,80 @@ pub struct SignTransactionsWithActionsRequest {
 pub struct TransactionPayload {
     pub near_account_id: String,
     pub receiver_id: String,
+    #[serde(deserialize_with = "crate::actions::deserialize_actions_flexible")]
     pub actions: Vec<ActionParams>,
 }
 
+/// One invalid `TransactionPayload` field, e.g. `{ field: "actions[1]", message }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PayloadFieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl PayloadFieldError {
+    pub(crate) fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        PayloadFieldError {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl TransactionPayload {
+    /// Every field-level problem the signer would otherwise report only after confirmation:
+    /// account id syntax, an empty action list and invalid action parameters. Attached
+    /// DeployContract code is not resolved yet and is not checked here.
+    pub fn field_errors(&self) -> Vec<PayloadFieldError> {
+        let mut errors = Vec::new();
+        if let Err(e) = crate::types::validate_account_id_syntax(&self.near_account_id) {
+            errors.push(PayloadFieldError::new("nearAccountId", e));
+        }
+        if let Err(e) = crate::types::validate_account_id_syntax(&self.receiver_id) {
+            errors.push(PayloadFieldError::new("receiverId", e));
+        }
+        if self.actions.is_empty() {
+            errors.push(PayloadFieldError::new(
+                "actions",
+                "at least one action is required",
+            ));
+        }
+        for (index, action) in self.actions.iter().enumerate() {
+            if let ActionParams::DeployContract {
+                code_attachment: Some(_),
+                ..
+            } = action
+            {
+                continue;
+            }
+            if let Err(e) = action.validate() {
+                errors.push(PayloadFieldError::new(format!("actions[{}]", index), e));
+            }
+        }
+        errors
+    }
+}
+
+/// Validate one `TransactionPayload` without starting a signing session. Resolves to the
+/// payload in its typed form (`actions` as an array, even when given as a JSON string);
+/// rejects with an array of `{ field, message }`.
+#[wasm_bindgen]
+pub fn validate_transaction_payload(payload: JsValue) -> Result<JsValue, JsValue> {
+    let field_errors_to_js = |errors: &[PayloadFieldError]| {
+        serde_wasm_bindgen::to_value(errors).unwrap_or_else(|e| {
+            JsValue::from_str(&format!("Failed to serialize validation errors: {:?}", e))
+        })
+    };
+    let payload: TransactionPayload = serde_wasm_bindgen::from_value(payload)
+        .map_err(|e| field_errors_to_js(&[PayloadFieldError::new("payload", e.to_string())]))?;
+    let errors = payload.field_errors();
+    if !errors.is_empty() {
+        return Err(field_errors_to_js(&errors));
+    }
+    serde_wasm_bindgen::to_value(&payload)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize payload: {:?}", e)))
+}
+
 #[wasm_bindgen]
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -65,6 +208,30 @@ pub struct TransactionSignResult {
     pub logs: Vec<String>,
     #[wasm_bindgen(getter_with_clone)]
     pub error: Option<String>,
+    /// One proof per signed transaction, present only when `includeThresholdProof` was requested
+    #[wasm_bindgen(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold_proofs: Option<Vec<ThresholdSignatureProof>>,
+    /// One preimage per signed transaction, present only when `includeSignaturePreimage` was requested
+    #[wasm_bindgen(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature_preimages: Option<Vec<SignaturePreimage>>,
+    /// One entry per signed transaction, present only when `broadcast` was requested
+    #[wasm_bindgen(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub broadcast_results: Option<Vec<BroadcastResult>>,
+    /// Signed contract verification, present only when `produceVerificationAttestation` was requested
+    #[wasm_bindgen(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification_attestation: Option<VerificationAttestation>,
+    /// Digest of the intent the user confirmed, so each signature can be reconciled against it
+    #[wasm_bindgen(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intent_digest: Option<String>,
+    /// `Some(true)` when `checkBalance` was requested but the balance could not be fetched
+    #[wasm_bindgen(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance_unchecked: Option<bool>,
 }
 
 #[wasm_bindgen]
@@ -83,6 +250,12 @@ impl TransactionSignResult {
             signed_transactions,
             logs,
             error,
+            threshold_proofs: None,
+            signature_preimages: None,
+            broadcast_results: None,
+            verification_attestation: None,
+            intent_digest: None,
+            balance_unchecked: None,
         }
     }
 
@@ -98,6 +271,38 @@ impl TransactionSignResult {
     }
 }
 
+impl TransactionSignResult {
+    pub fn with_threshold_proofs(mut self, proofs: Vec<ThresholdSignatureProof>) -> Self {
+        self.threshold_proofs = Some(proofs);
+        self
+    }
+
+    pub fn with_signature_preimages(mut self, preimages: Vec<SignaturePreimage>) -> Self {
+        self.signature_preimages = Some(preimages);
+        self
+    }
+
+    pub fn with_broadcast_results(mut self, results: Vec<BroadcastResult>) -> Self {
+        self.broadcast_results = Some(results);
+        self
+    }
+
+    pub fn with_verification_attestation(mut self, attestation: VerificationAttestation) -> Self {
+        self.verification_attestation = Some(attestation);
+        self
+    }
+
+    pub fn with_intent_digest(mut self, intent_digest: String) -> Self {
+        self.intent_digest = Some(intent_digest);
+        self
+    }
+
+    pub fn with_balance_unchecked(mut self) -> Self {
+        self.balance_unchecked = Some(true);
+        self
+    }
+}
+
 #[wasm_bindgen]
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -151,17 +356,32 @@ pub async fn handle_sign_transactions_with_actions(
     tx_batch_request: SignTransactionsWithActionsRequest,
     wrap_key: WrapKey,
 ) -> Result<TransactionSignResult, String> {
+    crate::host_environment::require_host_bridge(crate::host_environment::SEND_PROGRESS_MESSAGE)?;
+
     // Validate input
     if tx_batch_request.tx_signing_requests.is_empty() {
         return Err("No transactions provided".to_string());
     }
+    // The WrapKeySeed wait may have outlived the caller's deadline
+    tx_batch_request.deadline.check()?;
 
-    let mut logs: Vec<String> = Vec::new();
+    let log_verbosity = LogVerbosity::from_request(tx_batch_request.log_verbosity.as_deref())?;
+    let mut logs = HandlerLogs::new(log_verbosity);
     logs.push(format!(
         "Processing {} transactions",
         tx_batch_request.tx_signing_requests.len()
     ));
 
+    // Reject credentials minted for a different origin than the session is bound to
+    if let Some(confirmation_config) = tx_batch_request.confirmation_config.as_ref() {
+        confirmation_config.enforce_expected_origin(tx_batch_request.credential.as_deref())?;
+    }
+    // Reject assertions whose signCount went backwards (possible authenticator clone)
+    crate::sign_counter::enforce_sign_count(
+        tx_batch_request.credential.as_deref(),
+        &tx_batch_request.rpc_call.near_account_id,
+    )?;
+
     // Validate session expiry if created_at is present
     if let Some(created_at) = tx_batch_request.created_at {
         let now = js_sys::Date::now();
@@ -170,7 +390,48 @@ pub async fn handle_sign_transactions_with_actions(
         }
     }
 
+    // Reject disallowed action kinds and FunctionCall methods before any key material is touched
+    for tx in &tx_batch_request.tx_signing_requests {
+        enforce_allowed_action_kinds(
+            &tx.actions,
+            tx_batch_request.allowed_action_kinds.as_deref(),
+        )?;
+        enforce_allowed_methods(&tx.actions, tx_batch_request.allowed_methods.as_deref())?;
+    }
+
+    // Attestations vouch for the VRF worker's contract verification; fail before signing if
+    // there is none to vouch for. Threshold signatures are already authorized by the relayer.
+    let contract_verification = if tx_batch_request.produce_verification_attestation {
+        if tx_batch_request.signer_mode != SignerMode::LocalSigner {
+            return Err(
+                "produceVerificationAttestation is only supported with the local signer"
+                    .to_string(),
+            );
+        }
+        Some(
+            crate::wrap_key_handshake::session_contract_verification(&tx_batch_request.session_id)
+                .ok_or_else(|| {
+                    format!(
+                        "No contract verification recorded for session {}; mint the session with contractId and nearRpcUrl",
+                        tx_batch_request.session_id
+                    )
+                })?,
+        )
+    } else {
+        None
+    };
+
+    // Optional: refuse a batch the signer cannot pay for, before any key material is touched
+    let balance_unchecked = if tx_batch_request.check_balance {
+        check_balance(&tx_batch_request, &mut logs).await?
+    } else {
+        false
+    };
+
     // Step 1: Validate pre-confirmed context (confirmation already ran in VRF-driven flow)
+    let max_code_bytes = tx_batch_request
+        .contract_code_warn_bytes
+        .unwrap_or(crate::config::CONTRACT_CODE_WARN_BYTES);
     for (i, tx) in tx_batch_request.tx_signing_requests.iter().enumerate() {
         logs.push(format!(
             "Transaction {}: {} -> {} ({} actions)",
@@ -179,6 +440,11 @@ pub async fn handle_sign_transactions_with_actions(
             tx.receiver_id,
             tx.actions.len()
         ));
+        for warning in contract_code_size_warnings(&tx.actions, max_code_bytes) {
+            let warning = format!("Transaction {}: {}", i + 1, warning);
+            log::warn!("{}", warning);
+            logs.push(warning);
+        }
     }
     send_progress_message(
         ProgressMessageType::ExecuteActionsProgress,
@@ -194,6 +460,10 @@ pub async fn handle_sign_transactions_with_actions(
         .intent_digest
         .clone()
         .ok_or_else(|| "Missing intent digest from pre-confirmed session".to_string())?;
+    crate::wrap_key_handshake::check_session_intent_digest(
+        &tx_batch_request.session_id,
+        &intent_digest,
+    )?;
 
     let transaction_context = tx_batch_request
         .transaction_context
@@ -235,14 +505,23 @@ pub async fn handle_sign_transactions_with_actions(
     let tx_count = tx_batch_request.tx_signing_requests.len();
 
     let signer = match tx_batch_request.signer_mode {
-        SignerMode::LocalSigner => Ed25519SignerBackend::from_encrypted_near_private_key(
-            SignerMode::LocalSigner,
-            &wrap_key,
-            &tx_batch_request.decryption.encrypted_private_key_data,
-            &tx_batch_request
-                .decryption
-                .encrypted_private_key_chacha20_nonce_b64u,
-        )?,
+        SignerMode::LocalSigner => {
+            let presented_credential_id = presented_credential_id(
+                tx_batch_request.credential.as_deref(),
+                &tx_batch_request.session_id,
+            );
+            let envelope = tx_batch_request.decryption.select_envelope(
+                tx_batch_request.credential_id.as_deref(),
+                presented_credential_id.as_deref(),
+            )?;
+            Ed25519SignerBackend::from_session_near_private_key(
+                SignerMode::LocalSigner,
+                &tx_batch_request.session_id,
+                &wrap_key,
+                &envelope,
+                &tx_batch_request.rpc_call.near_account_id,
+            )?
+        }
         SignerMode::ThresholdSigner => {
             let cfg = tx_batch_request
                 .threshold
@@ -274,6 +553,7 @@ pub async fn handle_sign_transactions_with_actions(
 
             Ed25519SignerBackend::from_threshold_signer_config(
                 &wrap_key,
+                &tx_batch_request.session_id,
                 &tx_batch_request.rpc_call.near_account_id,
                 &transaction_context.near_public_key_str,
                 "near_tx",
@@ -285,13 +565,46 @@ pub async fn handle_sign_transactions_with_actions(
         }
     };
 
-    let result = sign_near_transactions_with_actions_impl(
+    let mut result = sign_near_transactions_with_actions_impl(
         tx_batch_request.tx_signing_requests,
         &signer,
         &transaction_context,
+        tx_batch_request.include_threshold_proof,
+        tx_batch_request.include_signature_preimage,
+        tx_batch_request.deadline,
         logs,
     )
-    .await?;
+    .await?
+    .with_intent_digest(intent_digest);
+    if balance_unchecked {
+        result = result.with_balance_unchecked();
+    }
+
+    if let Some(facts) = contract_verification.as_ref() {
+        if result.success {
+            let digest = verification_attestation_digest(VERIFICATION_ATTESTATION_VERSION, facts)?;
+            let signature = signer.sign(&digest).await?;
+            let public_key = signer.public_key_bytes()?;
+            result = result.with_verification_attestation(VerificationAttestation::new(
+                facts,
+                &public_key,
+                &signature,
+            ));
+        }
+    }
+
+    // Step 4 (optional): broadcast from the worker so the caller doesn't have to submit the
+    // transactions itself. The signed bytes are still returned in `signedTransactions`.
+    if let Some(broadcast) = tx_batch_request.broadcast.as_ref() {
+        if result.success {
+            let signed = result.signed_transactions.clone().unwrap_or_default();
+            let mut broadcast_logs = HandlerLogs::new(log_verbosity);
+            let broadcast_results =
+                broadcast_signed_transactions(broadcast, &signed, &mut broadcast_logs).await;
+            result.logs.extend(broadcast_logs.into_lines());
+            result = result.with_broadcast_results(broadcast_results);
+        }
+    }
 
     // Send completion progress message
     let completion_message = if result.success {
@@ -314,6 +627,154 @@ pub async fn handle_sign_transactions_with_actions(
     Ok(result)
 }
 
+/// Opt-in `checkBalance`: fail when the batch costs more than the signer can spend. Returns
+/// `true` when the balance could not be fetched and signing proceeds unchecked.
+async fn check_balance(
+    request: &SignTransactionsWithActionsRequest,
+    logs: &mut HandlerLogs,
+) -> Result<bool, String> {
+    let rpc_url = request.rpc_call.near_rpc_url.trim();
+    if rpc_url.is_empty() {
+        logs.push_error("Balance check skipped: no nearRpcUrl".to_string());
+        return Ok(true);
+    }
+    let transactions = request
+        .tx_signing_requests
+        .iter()
+        .map(|tx| tx.actions.as_slice());
+    match check_batch_balance(rpc_url, &request.rpc_call.near_account_id, transactions).await {
+        Ok((required, available)) => {
+            logs.push(format!(
+                "Balance check passed: batch requires {} yoctoNEAR, available {}",
+                required, available
+            ));
+            Ok(false)
+        }
+        Err(e) if e.is_unavailable() => {
+            log::warn!("[rust wasm]: {}; signing without a balance check", e);
+            logs.push_error(format!("{}; signing without a balance check", e));
+            Ok(true)
+        }
+        Err(e) => {
+            logs.push_error(e.to_string());
+            Err(e.into())
+        }
+    }
+}
+
+/// Broadcast signed transactions one at a time, in signing (nonce) order.
+/// The first failure halts the batch: later transactions are reported as `notBroadcast`,
+/// since their nonces and any state they depend on assume the earlier ones landed.
+async fn broadcast_signed_transactions(
+    config: &BroadcastConfig,
+    signed_transactions: &[WasmSignedTransaction],
+    logs: &mut HandlerLogs,
+) -> Vec<BroadcastResult> {
+    let total = signed_transactions.len();
+    let mut results = Vec::with_capacity(total);
+    let mut halted_at: Option<usize> = None;
+
+    for (index, signed_tx) in signed_transactions.iter().enumerate() {
+        let payload = match signed_transaction_rpc_payload(&signed_tx.borsh_bytes) {
+            Ok(payload) => payload,
+            Err(e) => {
+                let detail = format!("Failed to prepare broadcast payload: {}", e);
+                logs.push_error(format!("Transaction {}: {}", index + 1, detail));
+                results.push(BroadcastResult::new(
+                    String::new(),
+                    TxFinalStatus::BroadcastError,
+                    Some(detail),
+                ));
+                halted_at.get_or_insert(index);
+                continue;
+            }
+        };
+
+        if let Some(failed_index) = halted_at {
+            results.push(BroadcastResult::new(
+                payload.tx_hash,
+                TxFinalStatus::NotBroadcast,
+                Some(format!(
+                    "Not broadcast: transaction {} did not succeed",
+                    failed_index + 1
+                )),
+            ));
+            continue;
+        }
+
+        send_progress_message(
+            ProgressMessageType::ExecuteActionsProgress,
+            ProgressStep::TransactionSigningProgress,
+            &format!("Broadcasting transaction {} of {}...", index + 1, total),
+            Some(
+                &ProgressData::new(index as u32 + 1, total as u32)
+                    .with_hash(payload.tx_hash.clone()),
+            ),
+        );
+
+        let result = match broadcast_signed_transaction(
+            &config.rpc_url,
+            &payload.signed_tx_base64,
+            config.wait_until,
+        )
+        .await
+        {
+            Ok(result) => result,
+            Err(e) => BroadcastResult::new(
+                payload.tx_hash,
+                TxFinalStatus::BroadcastError,
+                Some(e.to_string()),
+            ),
+        };
+
+        let succeeded = !matches!(
+            result.final_status,
+            TxFinalStatus::Failed | TxFinalStatus::BroadcastError
+        );
+        let status_line = format!(
+            "Transaction {}: broadcast {:?} ({})",
+            index + 1,
+            result.final_status,
+            result.transaction_hash
+        );
+        if succeeded {
+            logs.push(status_line);
+        } else {
+            logs.push_error(status_line);
+        }
+        send_progress_message(
+            ProgressMessageType::ExecuteActionsProgress,
+            ProgressStep::TransactionSigningProgress,
+            &format!(
+                "Transaction {} of {} broadcast: {:?}",
+                index + 1,
+                total,
+                result.final_status
+            ),
+            Some(
+                &ProgressData::new(index as u32 + 1, total as u32)
+                    .with_success(succeeded)
+                    .with_hash(result.transaction_hash.clone()),
+            ),
+        );
+        if !succeeded {
+            halted_at = Some(index);
+        }
+        results.push(result);
+    }
+
+    if let Some(failed_index) = halted_at {
+        logs.push_error(format!(
+            "Broadcast halted at transaction {} of {}: {} broadcast before it, {} signed only",
+            failed_index + 1,
+            total,
+            failed_index,
+            total - failed_index - 1
+        ));
+    }
+    results
+}
+
 /// Internal implementation for batch transaction signing after verification is complete.
 /// This function handles the actual signing logic for multiple transactions using a shared
 /// decrypted private key. It processes each transaction individually, provides detailed logging
@@ -322,7 +783,9 @@ pub async fn handle_sign_transactions_with_actions(
 /// # Arguments
 /// * `tx_requests` - Array of transaction payloads to sign
 /// * `decryption` - Shared decryption parameters for private key access
-/// * `logs` - Existing log entries to append to
+/// * `include_threshold_proof` - Collect a `ThresholdSignatureProof` per signature (threshold mode)
+/// * `include_signature_preimage` - Collect the bytes each signature covers
+/// * `logs` - Existing log entries to append to (failure branches use `push_error`)
 ///
 /// # Returns
 /// * `TransactionSignResult` - Contains batch signing results with individual transaction details
@@ -330,12 +793,15 @@ async fn sign_near_transactions_with_actions_impl(
     tx_requests: Vec<TransactionPayload>,
     signer: &Ed25519SignerBackend,
     transaction_context: &crate::types::handlers::TransactionContext,
-    mut logs: Vec<String>,
+    include_threshold_proof: bool,
+    include_signature_preimage: bool,
+    deadline: RequestDeadline,
+    mut logs: HandlerLogs,
 ) -> Result<TransactionSignResult, String> {
     if tx_requests.is_empty() {
         let error_msg = "No transactions provided".to_string();
-        logs.push(error_msg.clone());
-        return Ok(TransactionSignResult::failed(logs, error_msg));
+        logs.push_error(error_msg.clone());
+        return Ok(TransactionSignResult::failed(logs.into_lines(), error_msg));
     }
 
     // Decrypt private key using the shared decryption data (use first transaction's signer account)
@@ -345,7 +811,22 @@ async fn sign_near_transactions_with_actions_impl(
     for tx in &tx_requests {
         if first_transaction.near_account_id != tx.near_account_id {
             let error_msg = format!("All transactions must use the same NEAR account ID");
-            return Ok(TransactionSignResult::failed(logs, error_msg));
+            return Ok(TransactionSignResult::failed(logs.into_lines(), error_msg));
+        }
+    }
+
+    // Validate every receiver before signing anything so a typo in a later transaction
+    // cannot leave the batch partially signed.
+    for (index, tx) in tx_requests.iter().enumerate() {
+        if let Err(e) = crate::types::validate_account_id_syntax(&tx.receiver_id) {
+            let error_msg = format!(
+                "Transaction {}: invalid receiver_id '{}': {}",
+                index + 1,
+                tx.receiver_id,
+                e
+            );
+            logs.push_error(error_msg.clone());
+            return Ok(TransactionSignResult::failed(logs.into_lines(), error_msg));
         }
     }
 
@@ -353,33 +834,56 @@ async fn sign_near_transactions_with_actions_impl(
     let public_key_bytes = signer.public_key_bytes()?;
     logs.push("Signer backend initialized successfully".to_string());
 
-    // Prepare nonce sequencing: start from next_nonce and increment per transaction
-    let mut current_nonce: u64 = transaction_context
-        .next_nonce
-        .parse()
-        .map_err(|e| format!("Invalid nonce: {}", e))?;
+    let tx_count = tx_requests.len();
+
+    // Prepare nonce sequencing: a relayer-reserved range for threshold accounts configured with
+    // `nonceMode: "relayerReserved"`, otherwise next_nonce incremented per transaction
+    let access_key_nonce = parse_nonce(&transaction_context.next_nonce)?;
+    let reservation = match signer.reserve_nonces(tx_count as u32).await {
+        Ok(reservation) => reservation,
+        Err(e) => {
+            logs.push(format!(
+                "Nonce reservation failed, using access key nonce: {}",
+                e
+            ));
+            None
+        }
+    };
+    let mut nonces = BatchNonces::start(access_key_nonce, reservation, now_ms());
+    match nonces.lease_id() {
+        Some(lease_id) => logs.push(format!(
+            "Using relayer-reserved nonces (lease {})",
+            lease_id
+        )),
+        None => signer.release_nonce_lease(),
+    }
 
     // Process each transaction
     let mut signed_transactions_wasm = Vec::new();
     let mut transaction_hashes = Vec::new();
+    let mut threshold_proofs = Vec::new();
+    let mut signature_preimages = Vec::new();
 
-    for (index, tx_data) in tx_requests.iter().enumerate() {
+    // Consume the payloads: DeployContract code is moved into the transaction, not cloned.
+    for (index, tx_data) in tx_requests.into_iter().enumerate() {
+        deadline.check()?;
         logs.push(format!(
             "Processing transaction {} of {}",
             index + 1,
-            tx_requests.len()
+            tx_count
         ));
 
         // Parse and build actions for this transaction
-        let action_params: Vec<ActionParams> = {
-            let params = tx_data.actions.clone();
-            logs.push(format!(
-                "Transaction {}: Parsed {} actions",
-                index + 1,
-                params.len()
-            ));
-            params
-        };
+        let TransactionPayload {
+            near_account_id,
+            receiver_id,
+            actions: action_params,
+        } = tx_data;
+        logs.push(format!(
+            "Transaction {}: Parsed {} actions",
+            index + 1,
+            action_params.len()
+        ));
 
         let actions = match build_actions_from_params(action_params) {
             Ok(actions) => {
@@ -392,15 +896,24 @@ async fn sign_near_transactions_with_actions_impl(
             Err(e) => {
                 let error_msg =
                     format!("Transaction {}: Failed to build actions: {}", index + 1, e);
-                logs.push(error_msg.clone());
-                return Ok(TransactionSignResult::failed(logs, error_msg));
+                logs.push_error(error_msg.clone());
+                return Ok(TransactionSignResult::failed(logs.into_lines(), error_msg));
+            }
+        };
+
+        let current_nonce = match nonces.next_nonce(now_ms()) {
+            Ok(nonce) => nonce,
+            Err(e) => {
+                let error_msg = format!("Transaction {}: {}", index + 1, e);
+                logs.push_error(error_msg.clone());
+                return Ok(TransactionSignResult::failed(logs.into_lines(), error_msg));
             }
         };
 
         // Build and sign transaction
         let transaction = match build_transaction_with_actions(
-            &tx_data.near_account_id,
-            &tx_data.receiver_id,
+            &near_account_id,
+            &receiver_id,
             current_nonce,
             &bs58::decode(&transaction_context.tx_block_hash)
                 .into_vec()
@@ -422,40 +935,64 @@ async fn sign_near_transactions_with_actions_impl(
                     index + 1,
                     e
                 );
-                logs.push(error_msg.clone());
-                return Ok(TransactionSignResult::failed(logs, error_msg));
+                logs.push_error(error_msg.clone());
+                return Ok(TransactionSignResult::failed(logs.into_lines(), error_msg));
             }
         };
 
-        let (transaction_hash_to_sign, _size) = transaction.get_hash_and_size();
-        let signature_bytes = match signer.sign(&transaction_hash_to_sign.0).await {
-            Ok(sig) => sig,
+        // One pre-sized buffer holds the transaction and then the signature; the built
+        // transaction (and its contract code) is dropped once written.
+        let (transaction_bytes, transaction_hash_to_sign) =
+            match serialize_transaction_for_signing(transaction) {
+                Ok(serialized) => serialized,
+                Err(e) => {
+                    let error_msg = format!("Transaction {}: {}", index + 1, e);
+                    logs.push_error(error_msg.clone());
+                    return Ok(TransactionSignResult::failed(logs.into_lines(), error_msg));
+                }
+            };
+        if include_signature_preimage {
+            signature_preimages.push(SignaturePreimage::for_signer(
+                signer,
+                SignableMessageKind::Transaction,
+                &transaction_bytes,
+                &transaction_hash_to_sign.0,
+            ));
+        }
+        let signature_bytes = match signer.sign_with_proof(&transaction_hash_to_sign.0).await {
+            Ok((sig, proof)) => {
+                if include_threshold_proof {
+                    threshold_proofs.extend(proof);
+                }
+                sig
+            }
             Err(e) => {
                 let error_msg = format!(
                     "Transaction {}: Failed to sign transaction: {}",
                     index + 1,
                     e
                 );
-                logs.push(error_msg.clone());
-                return Ok(TransactionSignResult::failed(logs, error_msg));
+                logs.push_error(error_msg.clone());
+                return Ok(TransactionSignResult::failed(logs.into_lines(), error_msg));
             }
         };
 
-        let signed_tx_bytes = match sign_transaction(transaction, &signature_bytes) {
-            Ok(bytes) => {
-                logs.push(format!("Transaction {}: Signed successfully", index + 1));
-                bytes
-            }
-            Err(e) => {
-                let error_msg = format!(
-                    "Transaction {}: Failed to serialize signed transaction: {}",
-                    index + 1,
-                    e
-                );
-                logs.push(error_msg.clone());
-                return Ok(TransactionSignResult::failed(logs, error_msg));
-            }
-        };
+        let signed_tx_bytes =
+            match append_transaction_signature(transaction_bytes, &signature_bytes) {
+                Ok(bytes) => {
+                    logs.push(format!("Transaction {}: Signed successfully", index + 1));
+                    bytes
+                }
+                Err(e) => {
+                    let error_msg = format!(
+                        "Transaction {}: Failed to serialize signed transaction: {}",
+                        index + 1,
+                        e
+                    );
+                    logs.push_error(error_msg.clone());
+                    return Ok(TransactionSignResult::failed(logs.into_lines(), error_msg));
+                }
+            };
 
         // Calculate transaction hash from signed transaction bytes (before moving the bytes)
         let transaction_hash = calculate_transaction_hash(&signed_tx_bytes);
@@ -465,24 +1002,20 @@ async fn sign_near_transactions_with_actions_impl(
             transaction_hash
         ));
 
-        // Create SignedTransaction from signed bytes
-        let signed_tx: SignedTransaction = borsh::from_slice(&signed_tx_bytes).map_err(|e| {
-            let error_msg = format!(
-                "Transaction {}: Failed to deserialize SignedTransaction: {}",
-                index + 1,
-                e
-            );
-            logs.push(error_msg.clone());
-            error_msg
-        })?;
-
-        let signed_tx_wasm = WasmSignedTransaction::from(&signed_tx);
+        // The signed bytes become `borshBytes` as-is
+        let signed_tx_wasm = WasmSignedTransaction::from_signed_borsh_bytes(signed_tx_bytes)
+            .map_err(|e| {
+                let error_msg = format!(
+                    "Transaction {}: Failed to deserialize SignedTransaction: {}",
+                    index + 1,
+                    e
+                );
+                logs.push_error(error_msg.clone());
+                error_msg
+            })?;
 
         signed_transactions_wasm.push(signed_tx_wasm);
         transaction_hashes.push(transaction_hash);
-
-        // Increment nonce for the next transaction in the batch
-        current_nonce = current_nonce.saturating_add(1);
     }
 
     logs.push(format!(
@@ -490,11 +1023,18 @@ async fn sign_near_transactions_with_actions_impl(
         signed_transactions_wasm.len()
     ));
 
-    Ok(TransactionSignResult::new(
+    let mut result = TransactionSignResult::new(
         true,
         Some(transaction_hashes),
         Some(signed_transactions_wasm),
-        logs,
+        logs.into_lines(),
         None,
-    ))
+    );
+    if include_signature_preimage {
+        result = result.with_signature_preimages(signature_preimages);
+    }
+    if include_threshold_proof && !threshold_proofs.is_empty() {
+        return Ok(result.with_threshold_proofs(threshold_proofs));
+    }
+    Ok(result)
 }
@@ -0,0 +1,36 @@
+// ******************************************************************************
+// *                                                                            *
+// *                    HANDLER: STATE VERSION / STATE CHANGES                  *
+// *                                                                            *
+// ******************************************************************************
+
+use serde::{Deserialize, Serialize};
+
+use crate::state_changes::{state_changes_since, state_version, StateChangesSince};
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StateVersionResult {
+    pub state_version: u64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetStateChangesSinceRequest {
+    /// The last state version the caller has seen.
+    pub version: u64,
+}
+
+/// **Handles:** `WorkerRequestType::GetStateVersion`
+/// Current state version; cheap enough to poll.
+pub fn handle_get_state_version() -> StateVersionResult {
+    StateVersionResult {
+        state_version: state_version(),
+    }
+}
+
+/// **Handles:** `WorkerRequestType::GetStateChangesSince`
+/// Retained changes after `version`, or `resyncRequired` when some were already evicted.
+pub fn handle_get_state_changes_since(request: GetStateChangesSinceRequest) -> StateChangesSince {
+    state_changes_since(request.version)
+}
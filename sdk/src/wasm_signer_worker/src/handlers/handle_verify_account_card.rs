@@ -0,0 +1,29 @@
+// ******************************************************************************
+// *                                                                            *
+// *                       HANDLER: VERIFY ACCOUNT CARD                         *
+// *                                                                            *
+// ******************************************************************************
+
+use serde::Deserialize;
+
+use crate::account_card::{verify_account_card_inner, AccountCard};
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyAccountCardRequest {
+    pub card_b64u: String,
+}
+
+/// **Handles:** `WorkerRequestType::VerifyAccountCard`
+/// Checks a scanned account card's signature and validity window and returns its fields.
+/// Needs no key material, so Device2 can run it before starting registration.
+pub fn handle_verify_account_card(
+    request: VerifyAccountCardRequest,
+) -> Result<AccountCard, String> {
+    #[cfg(target_arch = "wasm32")]
+    let now_ms = js_sys::Date::now() as u64;
+    #[cfg(not(target_arch = "wasm32"))]
+    let now_ms = 0;
+
+    Ok(verify_account_card_inner(&request.card_b64u, now_ms)?)
+}
@@ -0,0 +1,246 @@
+// ******************************************************************************
+// *                                                                            *
+// *                HANDLER: VERIFY ENCRYPTED KEY ENVELOPE                      *
+// *                                                                            *
+// ******************************************************************************
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::config::{
+    CHACHA20_NONCE_SIZE, NEAR_KEY_ENVELOPE_INTEGRITY_MAC_SIZE, NEAR_KEY_ENVELOPE_VERSION_ACCOUNT,
+    NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL, NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL_AAD,
+};
+use crate::encoders::base64_url_decode;
+use crate::handlers::handle_sign_transactions_with_actions::PayloadFieldError;
+use crate::WrapKey;
+
+/// ChaCha20-Poly1305 authentication tag appended to every ciphertext.
+const POLY1305_TAG_SIZE: usize = 16;
+
+/// A persisted NEAR key envelope together with the integrity metadata written at encryption
+/// time (`publicKey`, `integrityMacB64u`). Envelopes stored before integrity tags existed
+/// have neither.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedKeyEnvelope {
+    pub encrypted_private_key_data: String,
+    pub encrypted_private_key_chacha20_nonce_b64u: String,
+    /// Envelope format version; defaults to 3 with a `credentialId`, 2 without.
+    #[serde(default)]
+    pub version: Option<u8>,
+    #[serde(default)]
+    pub credential_id: Option<String>,
+    #[serde(default)]
+    pub public_key: Option<String>,
+    #[serde(default)]
+    pub integrity_mac_b64u: Option<String>,
+}
+
+impl EncryptedKeyEnvelope {
+    fn effective_version(&self) -> u8 {
+        self.version.unwrap_or(match self.credential_id {
+            Some(_) => NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL,
+            None => NEAR_KEY_ENVELOPE_VERSION_ACCOUNT,
+        })
+    }
+
+    /// `publicKey` and `integrityMacB64u`, when the envelope carries both.
+    fn integrity_metadata(&self) -> Option<(&str, &str)> {
+        let public_key = self.public_key.as_deref().filter(|k| !k.is_empty())?;
+        let mac = self
+            .integrity_mac_b64u
+            .as_deref()
+            .filter(|m| !m.is_empty())?;
+        Some((public_key, mac))
+    }
+
+    /// Structural problems only (fields present, lengths, known version); needs no session
+    /// and never touches key material.
+    pub fn structure_errors(&self) -> Vec<PayloadFieldError> {
+        let mut errors = Vec::new();
+
+        match base64_url_decode(&self.encrypted_private_key_data) {
+            Ok(ciphertext) if ciphertext.len() <= POLY1305_TAG_SIZE => {
+                errors.push(PayloadFieldError::new(
+                    "encryptedPrivateKeyData",
+                    format!(
+                        "ciphertext is {} bytes; must exceed the {}-byte authentication tag",
+                        ciphertext.len(),
+                        POLY1305_TAG_SIZE
+                    ),
+                ))
+            }
+            Ok(_) => {}
+            Err(e) => errors.push(PayloadFieldError::new("encryptedPrivateKeyData", e)),
+        }
+        match base64_url_decode(&self.encrypted_private_key_chacha20_nonce_b64u) {
+            Ok(nonce) if nonce.len() != CHACHA20_NONCE_SIZE => errors.push(PayloadFieldError::new(
+                "encryptedPrivateKeyChacha20NonceB64u",
+                format!(
+                    "nonce must be {} bytes, got {}",
+                    CHACHA20_NONCE_SIZE,
+                    nonce.len()
+                ),
+            )),
+            Ok(_) => {}
+            Err(e) => errors.push(PayloadFieldError::new(
+                "encryptedPrivateKeyChacha20NonceB64u",
+                e,
+            )),
+        }
+
+        let version = self.effective_version();
+        if !(NEAR_KEY_ENVELOPE_VERSION_ACCOUNT..=NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL_AAD)
+            .contains(&version)
+        {
+            errors.push(PayloadFieldError::new(
+                "version",
+                format!("unknown envelope version {}", version),
+            ));
+        } else if version >= NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL
+            && self.credential_id.as_deref().unwrap_or("").is_empty()
+        {
+            errors.push(PayloadFieldError::new(
+                "credentialId",
+                format!("version {} envelopes are bound to a credential id", version),
+            ));
+        }
+
+        if let Some(public_key) = &self.public_key {
+            let decoded =
+                bs58::decode(public_key.strip_prefix("ed25519:").unwrap_or(public_key)).into_vec();
+            match decoded {
+                Ok(bytes) if bytes.len() == 32 => {}
+                Ok(bytes) => errors.push(PayloadFieldError::new(
+                    "publicKey",
+                    format!("public key must be 32 bytes, got {}", bytes.len()),
+                )),
+                Err(e) => errors.push(PayloadFieldError::new("publicKey", e.to_string())),
+            }
+        }
+        if let Some(mac) = &self.integrity_mac_b64u {
+            match base64_url_decode(mac) {
+                Ok(bytes) if bytes.len() == NEAR_KEY_ENVELOPE_INTEGRITY_MAC_SIZE => {}
+                Ok(bytes) => errors.push(PayloadFieldError::new(
+                    "integrityMacB64u",
+                    format!(
+                        "integrity tag must be {} bytes, got {}",
+                        NEAR_KEY_ENVELOPE_INTEGRITY_MAC_SIZE,
+                        bytes.len()
+                    ),
+                )),
+                Err(e) => errors.push(PayloadFieldError::new("integrityMacB64u", e)),
+            }
+        }
+        if self.public_key.is_some() != self.integrity_mac_b64u.is_some() {
+            errors.push(PayloadFieldError::new(
+                "integrityMacB64u",
+                "publicKey and integrityMacB64u are stored together",
+            ));
+        }
+
+        errors
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyEncryptedKeyEnvelopeRequest {
+    pub session_id: String,
+    pub near_account_id: String,
+    pub envelope: EncryptedKeyEnvelope,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyEncryptedKeyEnvelopeResult {
+    /// The integrity tag matches the ciphertext, nonce, account id and public key.
+    pub intact: bool,
+    /// Public key recorded in the envelope (unset for legacy envelopes).
+    pub public_key: Option<String>,
+    /// Legacy envelope without an integrity tag; `intact` is false but says nothing.
+    pub integrity_unavailable: bool,
+}
+
+/// Recompute the envelope's integrity tag under the session's WrapKeySeed. A tag that does not
+/// match (or fields that no longer decode) report `intact: false`; only a failed key
+/// derivation is an error.
+pub fn verify_encrypted_key_envelope(
+    request: &VerifyEncryptedKeyEnvelopeRequest,
+    wrap_key: &WrapKey,
+) -> Result<VerifyEncryptedKeyEnvelopeResult, String> {
+    let envelope = &request.envelope;
+    let Some((public_key, mac_b64u)) = envelope.integrity_metadata() else {
+        return Ok(VerifyEncryptedKeyEnvelopeResult {
+            intact: false,
+            public_key: envelope.public_key.clone(),
+            integrity_unavailable: true,
+        });
+    };
+
+    let key = wrap_key.derive_envelope_integrity_key()?;
+    let intact = base64_url_decode(mac_b64u)
+        .and_then(|expected_mac| {
+            crate::crypto::verify_near_key_envelope_integrity_mac(
+                &key,
+                &envelope.encrypted_private_key_data,
+                &envelope.encrypted_private_key_chacha20_nonce_b64u,
+                &request.near_account_id,
+                public_key,
+                &expected_mac,
+            )
+        })
+        .unwrap_or(false);
+
+    Ok(VerifyEncryptedKeyEnvelopeResult {
+        intact,
+        public_key: Some(public_key.to_string()),
+        integrity_unavailable: false,
+    })
+}
+
+/// **Handles:** `WorkerRequestType::VerifyEncryptedKeyEnvelope`
+/// Checks that a persisted envelope still belongs to `nearAccountId` and the public key it was
+/// written with (storage corruption, account mix-ups) without decrypting the private key.
+///
+/// # Arguments
+/// * `request` - Envelope with its integrity metadata and the account id it is stored under
+/// * `wrap_key` - Session WrapKey material; only the integrity key is derived from it
+///
+/// # Returns
+/// * `VerifyEncryptedKeyEnvelopeResult` - `{ intact, publicKey, integrityUnavailable }`
+pub async fn handle_verify_encrypted_key_envelope(
+    request: VerifyEncryptedKeyEnvelopeRequest,
+    wrap_key: WrapKey,
+) -> Result<VerifyEncryptedKeyEnvelopeResult, String> {
+    verify_encrypted_key_envelope(&request, &wrap_key)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvelopeStructureReport {
+    pub valid: bool,
+    /// No integrity tag stored; only a decrypting request can vouch for this envelope.
+    pub integrity_unavailable: bool,
+    pub errors: Vec<PayloadFieldError>,
+}
+
+pub fn check_envelope_structure(envelope: &EncryptedKeyEnvelope) -> EnvelopeStructureReport {
+    let errors = envelope.structure_errors();
+    EnvelopeStructureReport {
+        valid: errors.is_empty(),
+        integrity_unavailable: envelope.integrity_metadata().is_none(),
+        errors,
+    }
+}
+
+/// Offline envelope check (no session, no TouchID): fields present, lengths correct, version
+/// known. Returns `{ valid, integrityUnavailable, errors: [{ field, message }] }`.
+#[wasm_bindgen]
+pub fn check_encrypted_key_envelope_structure(envelope: JsValue) -> Result<JsValue, JsValue> {
+    let envelope: EncryptedKeyEnvelope = serde_wasm_bindgen::from_value(envelope)
+        .map_err(|e| JsValue::from_str(&format!("Invalid envelope: {}", e)))?;
+    serde_wasm_bindgen::to_value(&check_envelope_structure(&envelope))
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))
+}
@@ -0,0 +1,70 @@
+// ******************************************************************************
+// *                                                                            *
+// *          HANDLER: WARM THRESHOLD CLIENT SIGNING SHARE (SESSION CACHE)      *
+// *                                                                            *
+// ******************************************************************************
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::WrapKey;
+
+#[wasm_bindgen]
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WarmThresholdEd25519ClientShareRequest {
+    #[wasm_bindgen(getter_with_clone, js_name = "sessionId")]
+    pub session_id: String,
+    #[wasm_bindgen(getter_with_clone, js_name = "nearAccountId")]
+    pub near_account_id: String,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WarmThresholdEd25519ClientShareResult {
+    #[wasm_bindgen(getter_with_clone, js_name = "sessionId")]
+    pub session_id: String,
+    #[wasm_bindgen(getter_with_clone, js_name = "nearAccountId")]
+    pub near_account_id: String,
+    /// Base64url-encoded verifying share of the cached signing share (participant id=1).
+    #[wasm_bindgen(getter_with_clone, js_name = "clientVerifyingShareB64u")]
+    pub client_verifying_share_b64u: String,
+}
+
+/// **Handles:** `WorkerRequestType::WarmThresholdEd25519ClientShare`
+///
+/// Derives the client threshold signing share from WrapKeySeed + nearAccountId once and caches it
+/// for later threshold signs on the same session. The cache is zeroized on session release
+/// (`clear_threshold_client_share_cache`). Only the public verifying share is returned.
+pub async fn handle_warm_threshold_ed25519_client_share(
+    request: WarmThresholdEd25519ClientShareRequest,
+    wrap_key: WrapKey,
+) -> Result<WarmThresholdEd25519ClientShareResult, String> {
+    let near_account_id = request.near_account_id.trim().to_string();
+    if near_account_id.is_empty() {
+        return Err("Missing nearAccountId".to_string());
+    }
+
+    debug!(
+        "[rust wasm]: warm threshold client share for account {} (session {})",
+        near_account_id, request.session_id
+    );
+
+    let signing_share = crate::threshold::client_share_cache::warm_client_signing_share(
+        &request.session_id,
+        &near_account_id,
+        &wrap_key,
+    )?;
+    let verifying_share =
+        crate::threshold::threshold_client_share::verifying_share_bytes_from_signing_share(
+            &signing_share,
+        );
+
+    Ok(WarmThresholdEd25519ClientShareResult {
+        session_id: request.session_id,
+        near_account_id,
+        client_verifying_share_b64u: crate::encoders::base64_url_encode(&verifying_share),
+    })
+}
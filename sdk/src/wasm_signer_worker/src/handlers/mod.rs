@@ -1,41 +1,100 @@
+pub mod handle_clear_all_sessions;
+pub mod handle_create_account_card;
+#[cfg(any(test, feature = "debug-panic"))]
+pub mod handle_debug_panic;
 pub mod handle_decrypt_private_key_with_prf;
 pub mod handle_derive_near_keypair_and_encrypt;
 pub mod handle_extract_cose_public_key;
+pub mod handle_get_device_records;
+pub mod handle_logout_all;
+pub mod handle_migrate_legacy_encrypted_key;
+pub mod handle_provision_relayer_request_auth_secret;
 pub mod handle_recover_keypair_from_passkey;
 pub mod handle_register_device2_with_derived_key;
+#[cfg(feature = "threshold")]
 pub mod handle_sign_add_key_threshold_public_key_no_prompt;
+pub mod handle_sign_borsh_transaction;
 pub mod handle_sign_delegate_action;
+pub mod handle_sign_intent;
 pub mod handle_sign_nep413_message;
 pub mod handle_sign_transaction_with_keypair;
 pub mod handle_sign_transactions_with_actions;
+pub mod handle_state_changes;
+#[cfg(feature = "threshold")]
 pub mod handle_threshold_ed25519_derive_client_verifying_share;
+pub mod handle_verify_account_card;
+pub mod handle_verify_encrypted_key_envelope;
+#[cfg(feature = "threshold")]
+pub mod handle_warm_threshold_ed25519_client_share;
 
 // Handler functions
+pub use handle_clear_all_sessions::handle_clear_all_sessions;
+pub use handle_create_account_card::handle_create_account_card;
+#[cfg(any(test, feature = "debug-panic"))]
+pub use handle_debug_panic::handle_debug_panic;
 pub use handle_decrypt_private_key_with_prf::handle_decrypt_private_key_with_prf;
 pub use handle_derive_near_keypair_and_encrypt::handle_derive_near_keypair_and_encrypt;
-pub use handle_extract_cose_public_key::handle_extract_cose_public_key;
+pub use handle_extract_cose_public_key::{
+    handle_extract_cose_public_key, handle_extract_cose_public_keys,
+};
+pub use handle_get_device_records::handle_get_device_records;
+pub use handle_logout_all::handle_logout_all;
+pub use handle_migrate_legacy_encrypted_key::handle_migrate_legacy_encrypted_key;
+pub use handle_provision_relayer_request_auth_secret::handle_provision_relayer_request_auth_secret;
 pub use handle_recover_keypair_from_passkey::handle_recover_keypair_from_passkey;
 pub use handle_register_device2_with_derived_key::handle_register_device2_with_derived_key;
+#[cfg(feature = "threshold")]
 pub use handle_sign_add_key_threshold_public_key_no_prompt::handle_sign_add_key_threshold_public_key_no_prompt;
+pub use handle_sign_borsh_transaction::handle_sign_borsh_transaction;
 pub use handle_sign_delegate_action::handle_sign_delegate_action;
+pub use handle_sign_intent::handle_sign_intent;
 pub use handle_sign_nep413_message::handle_sign_nep413_message;
 pub use handle_sign_transaction_with_keypair::handle_sign_transaction_with_keypair;
 pub use handle_sign_transactions_with_actions::handle_sign_transactions_with_actions;
+pub use handle_state_changes::{handle_get_state_changes_since, handle_get_state_version};
+#[cfg(feature = "threshold")]
 pub use handle_threshold_ed25519_derive_client_verifying_share::handle_threshold_ed25519_derive_client_verifying_share;
+pub use handle_verify_account_card::handle_verify_account_card;
+pub use handle_verify_encrypted_key_envelope::handle_verify_encrypted_key_envelope;
+#[cfg(feature = "threshold")]
+pub use handle_warm_threshold_ed25519_client_share::handle_warm_threshold_ed25519_client_share;
 
 // Request/Result types
-pub use handle_extract_cose_public_key::{CoseExtractionResult, ExtractCoseRequest};
+pub use handle_clear_all_sessions::ClearAllSessionsRequest;
+pub use handle_create_account_card::CreateAccountCardRequest;
+#[cfg(any(test, feature = "debug-panic"))]
+pub use handle_debug_panic::DebugPanicRequest;
+pub use handle_extract_cose_public_key::{
+    CoseExtractionBatchResult, CoseExtractionItem, CoseExtractionResult, ExtractCosePublicKeysRequest,
+    ExtractCoseRequest,
+};
+pub use handle_get_device_records::{GetDeviceRecordsRequest, GetDeviceRecordsResult};
+pub use handle_migrate_legacy_encrypted_key::{
+    MigrateLegacyEncryptedKeyRequest, MigrateLegacyEncryptedKeyResult,
+};
+pub use handle_provision_relayer_request_auth_secret::ProvisionRelayerRequestAuthSecretRequest;
 pub use handle_recover_keypair_from_passkey::{RecoverKeypairRequest, RecoverKeypairResult};
 pub use handle_register_device2_with_derived_key::{
     RegisterDevice2WithDerivedKeyRequest, RegisterDevice2WithDerivedKeyResult,
 };
+#[cfg(feature = "threshold")]
 pub use handle_sign_add_key_threshold_public_key_no_prompt::SignAddKeyThresholdPublicKeyNoPromptRequest;
+pub use handle_sign_borsh_transaction::SignBorshTransactionRequest;
 pub use handle_sign_delegate_action::{
     DelegatePayload, DelegateSignResult, SignDelegateActionRequest,
 };
+pub use handle_sign_intent::SignIntentRequest;
 pub use handle_sign_nep413_message::{SignNep413Request, SignNep413Result};
 pub use handle_sign_transaction_with_keypair::SignTransactionWithKeyPairRequest;
 pub use handle_sign_transactions_with_actions::{
     KeyActionResult, SignTransactionsWithActionsRequest, TransactionPayload,
 };
+pub use handle_state_changes::GetStateChangesSinceRequest;
+#[cfg(feature = "threshold")]
 pub use handle_threshold_ed25519_derive_client_verifying_share::DeriveThresholdEd25519ClientVerifyingShareRequest;
+pub use handle_verify_account_card::VerifyAccountCardRequest;
+pub use handle_verify_encrypted_key_envelope::VerifyEncryptedKeyEnvelopeRequest;
+#[cfg(feature = "threshold")]
+pub use handle_warm_threshold_ed25519_client_share::{
+    WarmThresholdEd25519ClientShareRequest, WarmThresholdEd25519ClientShareResult,
+};
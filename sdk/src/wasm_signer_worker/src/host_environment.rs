@@ -0,0 +1,108 @@
+//! Probe for the JS globals the worker host (web3authn-signer.worker.ts) must install.
+//!
+//! wasm-bindgen resolves `extern "C"` imports such as `sendProgressMessage` on globalThis at
+//! call time, so a bundler that tree-shakes or renames a bridge only fails deep inside a
+//! signing flow. `check_environment` reports what is missing up front; handlers that need a
+//! bridge call `require_host_bridge` and fail with `MissingHostBridge` instead.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use log::warn;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::error::HostBridgeError;
+
+/// Progress bridge called by `send_progress_message`.
+pub const SEND_PROGRESS_MESSAGE: &str = "sendProgressMessage";
+/// Used by the WrapKeySeed handshake to time out waits (`timeout_promise`).
+pub const SET_TIMEOUT: &str = "setTimeout";
+/// Used by NEAR RPC calls (device registry reconciliation, threshold signing).
+pub const FETCH: &str = "fetch";
+
+/// Globals without which some request types cannot complete.
+pub const REQUIRED_GLOBALS: &[&str] = &[SEND_PROGRESS_MESSAGE, SET_TIMEOUT];
+/// Globals whose absence only disables optional features.
+pub const OPTIONAL_GLOBALS: &[(&str, &str)] = &[(
+    FETCH,
+    "fetch is not available; requests that call NEAR RPC will fail",
+)];
+
+static ENVIRONMENT_CHECKED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentReport {
+    pub ok: bool,
+    pub missing: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Build the report from a predicate telling whether a global function is installed.
+pub fn evaluate_environment<F>(has_global: F) -> EnvironmentReport
+where
+    F: Fn(&str) -> bool,
+{
+    let missing: Vec<String> = REQUIRED_GLOBALS
+        .iter()
+        .filter(|name| !has_global(name))
+        .map(|name| name.to_string())
+        .collect();
+    let warnings = OPTIONAL_GLOBALS
+        .iter()
+        .filter(|(name, _)| !has_global(name))
+        .map(|(_, warning)| warning.to_string())
+        .collect();
+    EnvironmentReport {
+        ok: missing.is_empty(),
+        missing,
+        warnings,
+    }
+}
+
+/// Whether `globalThis[name]` is a function. Native builds have no JS host, and the bridges
+/// have native fallbacks there, so everything counts as installed.
+pub fn has_global_function(name: &str) -> bool {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str(name))
+            .map(|value| value.is_function())
+            .unwrap_or(false)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let _ = name;
+        true
+    }
+}
+
+/// Fail with `MissingHostBridge` if the named bridge is not installed.
+pub fn require_host_bridge(name: &'static str) -> Result<(), HostBridgeError> {
+    if has_global_function(name) {
+        Ok(())
+    } else {
+        Err(HostBridgeError::MissingHostBridge(name))
+    }
+}
+
+/// Probe every global the signer worker relies on. Returns `{ ok, missing, warnings }`.
+/// Idempotent; safe to call before or after `init_worker`.
+#[wasm_bindgen]
+pub fn check_environment() -> JsValue {
+    let report = evaluate_environment(has_global_function);
+    serde_wasm_bindgen::to_value(&report).unwrap_or(JsValue::NULL)
+}
+
+/// Log a structured warning for missing globals, once per worker. Never fails startup.
+pub(crate) fn warn_if_environment_incomplete() {
+    if ENVIRONMENT_CHECKED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let report = evaluate_environment(has_global_function);
+    if !report.ok || !report.warnings.is_empty() {
+        warn!(
+            "[signer wasm] host environment incomplete: missing={:?} warnings={:?}",
+            report.missing, report.warnings
+        );
+    }
+}
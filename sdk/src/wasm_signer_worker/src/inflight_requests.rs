@@ -0,0 +1,295 @@
+//! De-duplication of identical signer requests that are in flight at the same time.
+//!
+//! A double-click can post the same request twice for one signing session. Both invocations
+//! would wait on the same WrapKeySeed and both would sign, so the host ends up broadcasting
+//! the same transfer twice (or, with auto-nonce, two different nonces for one intent).
+//!
+//! Requests carrying a `sessionId` are keyed by `(sessionId, request type, intentDigest or
+//! payload hash)`. While one is running, an identical request either awaits and returns the
+//! first one's result (`onDuplicate: "share"`, the default) or fails fast with
+//! `DuplicateRequestInFlight` (`onDuplicate: "reject"`). The marker is removed when the first
+//! request completes or fails, and when its future is dropped without completing.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+use sha2::{Digest, Sha256};
+use wasm_bindgen::JsValue;
+
+use crate::encoders::base64_url_encode;
+use crate::error::InFlightRequestError;
+
+thread_local! {
+    static SIGNER_REQUESTS_IN_FLIGHT: InFlightRegistry<Result<JsValue, JsValue>> =
+        InFlightRegistry::default();
+}
+
+/// The registry `handle_signer_message` uses.
+pub(crate) fn signer_requests_in_flight() -> InFlightRegistry<Result<JsValue, JsValue>> {
+    SIGNER_REQUESTS_IN_FLIGHT.with(|registry| registry.clone())
+}
+
+/// Drop every signer in-flight marker; duplicates still waiting fail with `ERROR_LOGGED_OUT`.
+pub(crate) fn clear_signer_requests_in_flight() -> usize {
+    let registry = signer_requests_in_flight();
+    #[cfg(target_arch = "wasm32")]
+    {
+        registry.clear_with(Some(Err(JsValue::from_str(
+            crate::config::ERROR_LOGGED_OUT,
+        ))))
+    }
+    // JS strings can't be built natively; waiters there read as abandoned.
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        registry.clear_with(None)
+    }
+}
+
+/// Key for a request payload; `None` when it carries no `sessionId` (never de-duplicated).
+pub(crate) fn request_key(request_type: u32, payload: &JsValue) -> Option<InFlightKey> {
+    let field = |name: &str| {
+        js_sys::Reflect::get(payload, &JsValue::from_str(name))
+            .ok()
+            .and_then(|v| v.as_string())
+    };
+    let session_id = field("sessionId").filter(|id| !id.is_empty())?;
+    let intent_digest = field("intentDigest");
+    let payload_json = js_sys::JSON::stringify(payload).ok()?.as_string()?;
+    Some(InFlightKey::new(
+        &session_id,
+        request_type,
+        intent_digest.as_deref(),
+        &payload_json,
+    ))
+}
+
+/// What a duplicate of an in-flight request does (`onDuplicate` on the request envelope).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Await the first request and return its result.
+    #[default]
+    Share,
+    /// Fail with `DuplicateRequestInFlight`.
+    Reject,
+}
+
+impl DuplicatePolicy {
+    /// `"reject"` rejects; anything else (including absent) shares.
+    pub fn from_envelope(value: Option<&str>) -> Self {
+        match value {
+            Some("reject") => DuplicatePolicy::Reject,
+            _ => DuplicatePolicy::Share,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InFlightKey {
+    pub session_id: String,
+    pub request_type: u32,
+    /// `intentDigest` when the payload has one, else base64url(sha256(payload JSON)).
+    pub payload_digest: String,
+}
+
+impl InFlightKey {
+    pub fn new(
+        session_id: &str,
+        request_type: u32,
+        intent_digest: Option<&str>,
+        payload_json: &str,
+    ) -> Self {
+        let payload_digest = match intent_digest.map(str::trim).filter(|d| !d.is_empty()) {
+            Some(digest) => format!("intent:{}", digest),
+            None => base64_url_encode(&Sha256::digest(payload_json.as_bytes())),
+        };
+        Self {
+            session_id: session_id.to_string(),
+            request_type,
+            payload_digest,
+        }
+    }
+}
+
+struct Slot<T> {
+    started_at_ms: u64,
+    /// `Some(None)` once the first request was dropped without a result.
+    outcome: Option<Option<T>>,
+    wakers: Vec<Waker>,
+}
+
+type Entries<T> = Rc<RefCell<HashMap<InFlightKey, Rc<RefCell<Slot<T>>>>>>;
+
+/// In-flight markers for one worker. Cloning shares the same map.
+pub struct InFlightRegistry<T> {
+    entries: Entries<T>,
+}
+
+impl<T> Clone for InFlightRegistry<T> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+impl<T> Default for InFlightRegistry<T> {
+    fn default() -> Self {
+        Self {
+            entries: Rc::new(RefCell::new(HashMap::new())),
+        }
+    }
+}
+
+pub enum InFlight<T> {
+    /// No identical request is running: run it and hand the result to `complete`.
+    Leader(InFlightGuard<T>),
+    /// An identical request is running: await its result (`None` if it was abandoned).
+    Follower(SharedOutcome<T>),
+}
+
+impl<T: Clone> InFlightRegistry<T> {
+    pub fn begin(
+        &self,
+        key: InFlightKey,
+        policy: DuplicatePolicy,
+        now_ms: u64,
+    ) -> Result<InFlight<T>, InFlightRequestError> {
+        let existing = self.entries.borrow().get(&key).cloned();
+        if let Some(slot) = existing {
+            return match policy {
+                DuplicatePolicy::Share => Ok(InFlight::Follower(SharedOutcome { slot })),
+                DuplicatePolicy::Reject => Err(InFlightRequestError::DuplicateRequestInFlight {
+                    session_id: key.session_id,
+                    started_at_ms: slot.borrow().started_at_ms,
+                }),
+            };
+        }
+        let slot = Rc::new(RefCell::new(Slot {
+            started_at_ms: now_ms,
+            outcome: None,
+            wakers: Vec::new(),
+        }));
+        self.entries.borrow_mut().insert(key.clone(), slot.clone());
+        Ok(InFlight::Leader(InFlightGuard {
+            entries: self.entries.clone(),
+            key,
+            slot,
+            finished: false,
+        }))
+    }
+
+    /// Drop every marker and settle each request still waiting on one with `outcome` (`None`
+    /// reads as abandoned) for `CLEAR_ALL_SESSIONS`. Leaders keep running; their eventual result
+    /// is discarded and does not disturb a marker a new request has taken since. Returns the
+    /// markers dropped.
+    pub fn clear_with(&self, outcome: Option<T>) -> usize {
+        let slots: Vec<_> = self
+            .entries
+            .borrow_mut()
+            .drain()
+            .map(|(_, slot)| slot)
+            .collect();
+        for slot in &slots {
+            let wakers = {
+                let mut slot = slot.borrow_mut();
+                slot.outcome = Some(outcome.clone());
+                std::mem::take(&mut slot.wakers)
+            };
+            for waker in wakers {
+                waker.wake();
+            }
+        }
+        slots.len()
+    }
+
+    /// Whether any request for `session_id` is still running.
+    #[cfg(any(test, target_arch = "wasm32"))]
+    pub fn has_session_in_flight(&self, session_id: &str) -> bool {
+        self.entries
+            .borrow()
+            .keys()
+            .any(|key| key.session_id == session_id)
+    }
+
+    #[cfg(test)]
+    pub fn is_in_flight(&self, key: &InFlightKey) -> bool {
+        self.entries.borrow().contains_key(key)
+    }
+
+    #[cfg(test)]
+    pub fn in_flight_count(&self) -> usize {
+        self.entries.borrow().len()
+    }
+}
+
+/// Held by the first request for a key; clears the marker on `complete` or drop.
+pub struct InFlightGuard<T> {
+    entries: Entries<T>,
+    key: InFlightKey,
+    slot: Rc<RefCell<Slot<T>>>,
+    finished: bool,
+}
+
+impl<T> InFlightGuard<T> {
+    /// Publish the result (success or failure) to every duplicate and clear the marker.
+    pub fn complete(mut self, outcome: T) {
+        self.finish(Some(outcome));
+    }
+
+    fn finish(&mut self, outcome: Option<T>) {
+        self.finished = true;
+        {
+            // The marker may have been cleared (and the key reused) since this request began.
+            let mut entries = self.entries.borrow_mut();
+            if entries
+                .get(&self.key)
+                .is_some_and(|slot| Rc::ptr_eq(slot, &self.slot))
+            {
+                entries.remove(&self.key);
+            }
+        }
+        let wakers = {
+            let mut slot = self.slot.borrow_mut();
+            if slot.outcome.is_some() {
+                return;
+            }
+            slot.outcome = Some(outcome);
+            std::mem::take(&mut slot.wakers)
+        };
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Drop for InFlightGuard<T> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.finish(None);
+        }
+    }
+}
+
+/// Resolves with the first request's result, or `None` if it was dropped without one.
+pub struct SharedOutcome<T> {
+    slot: Rc<RefCell<Slot<T>>>,
+}
+
+impl<T: Clone> Future for SharedOutcome<T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.slot.borrow_mut();
+        match &slot.outcome {
+            Some(outcome) => Poll::Ready(outcome.clone()),
+            None => {
+                slot.wakers.push(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+// === PORTABLE KEY ENVELOPE ===
+// A single, self-describing JSON object for an encrypted NEAR private key, so callers can
+// store or export it without reassembling `encryptedData` / `chacha20NonceB64u` / version
+// fields themselves. The split-field results returned by the derive handlers are unchanged.
+//
+// The key is sealed with ChaCha20-Poly1305 under the account-wide KEK derived from
+// WrapKeySeed, with the account id authenticated as AAD, so an envelope copied onto another
+// account fails to decrypt. `aead` and `kdf` name the algorithms; a decoder that does not
+// recognise the `version` refuses the envelope before reading anything else.
+//
+// Nonces are random by default. `EnvelopeNonceMode::Deterministic` instead derives the nonce
+// from the KEK, account id, a counter stored in the envelope (`nonceCounter`) and the private
+// key itself, so a (KEK, nonce) pair never covers two different keys even if a counter is
+// reused. Re-encrypting with `next_nonce_counter()` keeps nonces unique without relying on
+// the RNG, and decryption re-derives the nonce so a mismatched counter is caught.
+
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::config::{
+    near_key_aad_for_account, PORTABLE_KEY_ENVELOPE_AEAD, PORTABLE_KEY_ENVELOPE_KDF,
+    PORTABLE_KEY_ENVELOPE_VERSION,
+};
+use crate::crypto::{
+    decrypt_data_chacha20_with_aad, derive_deterministic_chacha20_nonce,
+    encrypt_data_chacha20_with_aad, encrypt_data_chacha20_with_aad_and_nonce, WrapKey,
+};
+use crate::encoders::base64_url_encode;
+
+/// How the nonce of a new envelope is chosen.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EnvelopeNonceMode {
+    /// Fresh random nonce.
+    #[default]
+    Random,
+    /// `HKDF(kek; account_id || counter || private_key)`; `counter` is stored in the envelope.
+    Deterministic { counter: u64 },
+}
+
+/// Portable, versioned envelope for an encrypted NEAR private key. Distinct from the stored
+/// split-field record checked by `VERIFY_ENCRYPTED_KEY_ENVELOPE`.
+///
+/// Deserializing checks `version` before the remaining fields, so a newer envelope is
+/// reported as unsupported rather than as a missing or renamed field.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", try_from = "RawEncryptedKeyEnvelope")]
+pub struct EncryptedKeyEnvelope {
+    pub version: u8,
+    pub aead: String,
+    pub kdf: String,
+    pub ciphertext_b64u: String,
+    pub nonce_b64u: String,
+    pub account_id: String,
+    /// Counter the nonce was derived from (deterministic mode only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce_counter: Option<u64>,
+}
+
+impl EncryptedKeyEnvelope {
+    /// Counter for the next deterministic re-encryption of this key: one past the stored
+    /// counter, or 0 for an envelope with a random nonce.
+    pub fn next_nonce_counter(&self) -> Result<u64, String> {
+        match self.nonce_counter {
+            None => Ok(0),
+            Some(counter) => counter
+                .checked_add(1)
+                .ok_or_else(|| "Key envelope nonce counter exhausted".to_string()),
+        }
+    }
+}
+
+/// Every field optional, so `version` can be checked before the v1 layout is required.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawEncryptedKeyEnvelope {
+    version: Option<u64>,
+    aead: Option<String>,
+    kdf: Option<String>,
+    ciphertext_b64u: Option<String>,
+    nonce_b64u: Option<String>,
+    account_id: Option<String>,
+    nonce_counter: Option<u64>,
+}
+
+impl TryFrom<RawEncryptedKeyEnvelope> for EncryptedKeyEnvelope {
+    type Error = String;
+
+    fn try_from(raw: RawEncryptedKeyEnvelope) -> Result<Self, String> {
+        let version = raw.version.ok_or("Key envelope has no version")?;
+        check_version(version)?;
+        let field = |value: Option<String>, name: &str| {
+            value.ok_or_else(|| format!("Key envelope is missing `{}`", name))
+        };
+        Ok(EncryptedKeyEnvelope {
+            version: PORTABLE_KEY_ENVELOPE_VERSION,
+            aead: field(raw.aead, "aead")?,
+            kdf: field(raw.kdf, "kdf")?,
+            ciphertext_b64u: field(raw.ciphertext_b64u, "ciphertextB64u")?,
+            nonce_b64u: field(raw.nonce_b64u, "nonceB64u")?,
+            account_id: field(raw.account_id, "accountId")?,
+            nonce_counter: raw.nonce_counter,
+        })
+    }
+}
+
+fn check_version(version: u64) -> Result<(), String> {
+    if version != u64::from(PORTABLE_KEY_ENVELOPE_VERSION) {
+        return Err(format!(
+            "Unsupported key envelope version {}: this worker only reads version {}",
+            version, PORTABLE_KEY_ENVELOPE_VERSION
+        ));
+    }
+    Ok(())
+}
+
+/// Encrypt `near_private_key` (`ed25519:...`) for `near_account_id` into a version 1 envelope.
+pub fn encrypt_private_key_to_envelope(
+    wrap_key: &WrapKey,
+    near_account_id: &str,
+    near_private_key: &str,
+) -> Result<EncryptedKeyEnvelope, String> {
+    encrypt_private_key_to_envelope_with_nonce_mode(
+        wrap_key,
+        near_account_id,
+        near_private_key,
+        EnvelopeNonceMode::Random,
+    )
+}
+
+/// `encrypt_private_key_to_envelope` with an explicit nonce mode.
+pub fn encrypt_private_key_to_envelope_with_nonce_mode(
+    wrap_key: &WrapKey,
+    near_account_id: &str,
+    near_private_key: &str,
+    nonce_mode: EnvelopeNonceMode,
+) -> Result<EncryptedKeyEnvelope, String> {
+    if near_account_id.is_empty() {
+        return Err("Key envelope requires an account id".to_string());
+    }
+    let kek = Zeroizing::new(wrap_key.derive_kek()?);
+    let aad = near_key_aad_for_account(near_account_id, None);
+    let (encrypted, nonce_counter) = match nonce_mode {
+        EnvelopeNonceMode::Random => (
+            encrypt_data_chacha20_with_aad(near_private_key, &kek, &aad),
+            None,
+        ),
+        EnvelopeNonceMode::Deterministic { counter } => {
+            let nonce = derive_deterministic_chacha20_nonce(
+                &kek[..],
+                near_account_id,
+                counter,
+                near_private_key.as_bytes(),
+            )?;
+            (
+                encrypt_data_chacha20_with_aad_and_nonce(near_private_key, &kek, &aad, &nonce),
+                Some(counter),
+            )
+        }
+    };
+    let encrypted = encrypted.map_err(|e| format!("Failed to encrypt private key: {}", e))?;
+
+    Ok(EncryptedKeyEnvelope {
+        version: PORTABLE_KEY_ENVELOPE_VERSION,
+        aead: PORTABLE_KEY_ENVELOPE_AEAD.to_string(),
+        kdf: PORTABLE_KEY_ENVELOPE_KDF.to_string(),
+        ciphertext_b64u: encrypted.encrypted_near_key_data_b64u,
+        nonce_b64u: encrypted.chacha20_nonce_b64u,
+        account_id: near_account_id.to_string(),
+        nonce_counter,
+    })
+}
+
+/// Decrypt a version 1 envelope back into the NEAR private key string. Unknown versions,
+/// algorithms, or an envelope moved to another account are errors.
+pub fn decrypt_private_key_from_envelope(
+    wrap_key: &WrapKey,
+    envelope: &EncryptedKeyEnvelope,
+) -> Result<Zeroizing<String>, String> {
+    check_version(u64::from(envelope.version))?;
+    if envelope.aead != PORTABLE_KEY_ENVELOPE_AEAD {
+        return Err(format!("Unsupported key envelope aead '{}'", envelope.aead));
+    }
+    if envelope.kdf != PORTABLE_KEY_ENVELOPE_KDF {
+        return Err(format!("Unsupported key envelope kdf '{}'", envelope.kdf));
+    }
+    if envelope.account_id.is_empty() {
+        return Err("Key envelope has no account id".to_string());
+    }
+    let kek = Zeroizing::new(wrap_key.derive_kek()?);
+    let aad = near_key_aad_for_account(&envelope.account_id, None);
+    let private_key =
+        decrypt_data_chacha20_with_aad(&envelope.ciphertext_b64u, &envelope.nonce_b64u, &kek, &aad)
+            .map(Zeroizing::new)
+            .map_err(|e| format!("Failed to decrypt key envelope: {}", e))?;
+    if let Some(counter) = envelope.nonce_counter {
+        let expected = derive_deterministic_chacha20_nonce(
+            &kek[..],
+            &envelope.account_id,
+            counter,
+            private_key.as_bytes(),
+        )?;
+        if envelope.nonce_b64u != base64_url_encode(&expected) {
+            return Err(format!(
+                "Key envelope nonce does not match nonceCounter {}",
+                counter
+            ));
+        }
+    }
+    Ok(private_key)
+}
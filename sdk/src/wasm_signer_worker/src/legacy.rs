@@ -0,0 +1,102 @@
+// === LEGACY KEY ENVELOPE (packages/passkey) ===
+// Read-only port of the dual-PRF era encryption used by the old `packages/passkey` worker.
+//
+// The legacy worker encrypted the NEAR private key string (`ed25519:<bs58 64 bytes>`) with
+// ChaCha20-Poly1305 under a key derived directly from PRF.first:
+//   key = HKDF-SHA256(salt = "chacha20-salt:<accountId>", ikm = PRF.first, info = LEGACY_CHACHA20_HKDF_INFO)
+// There is no WrapKeySeed/KEK layer. This module only decrypts; new blobs always use the
+// WrapKey envelope (see `handle_migrate_legacy_encrypted_key`).
+
+use hkdf::Hkdf;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use crate::config::{
+    legacy_chacha20_salt_for_account, CHACHA20_KEY_SIZE, LEGACY_CHACHA20_HKDF_INFO,
+};
+use crate::encoders::base64_url_decode_lenient;
+use crate::error::KdfError;
+
+/// Derive the legacy ChaCha20 key from PRF.first (base64url, padding tolerated).
+pub(crate) fn derive_legacy_chacha20_key(
+    prf_output_b64u: &str,
+    account_id: &str,
+) -> Result<Zeroizing<Vec<u8>>, KdfError> {
+    let prf_output = Zeroizing::new(base64_url_decode_lenient(prf_output_b64u)?);
+    if prf_output.is_empty() {
+        return Err(KdfError::InvalidInput("Empty PRF output".to_string()));
+    }
+
+    let salt = legacy_chacha20_salt_for_account(account_id);
+    let hk = Hkdf::<Sha256>::new(Some(salt.as_bytes()), &prf_output);
+    let mut key = Zeroizing::new(vec![0u8; CHACHA20_KEY_SIZE]);
+    hk.expand(LEGACY_CHACHA20_HKDF_INFO.as_bytes(), &mut key)
+        .map_err(|_| KdfError::HkdfError)?;
+    Ok(key)
+}
+
+/// Decrypt a legacy blob into the NEAR private key string (`ed25519:...`).
+pub(crate) fn decrypt_private_key_with_prf(
+    account_id: &str,
+    encrypted_private_key_data_b64u: &str,
+    encrypted_private_key_iv_b64u: &str,
+    prf_output_b64u: &str,
+) -> Result<Zeroizing<String>, String> {
+    let key = derive_legacy_chacha20_key(prf_output_b64u, account_id)
+        .map_err(|e| format!("Legacy key derivation failed: {}", e))?;
+    let private_key = crate::crypto::decrypt_data_chacha20(
+        encrypted_private_key_data_b64u,
+        encrypted_private_key_iv_b64u,
+        &key,
+    )
+    .map(Zeroizing::new)
+    .map_err(|e| format!("Legacy decryption failed: {}", e))?;
+
+    if !private_key.starts_with("ed25519:") {
+        return Err("Legacy blob did not decrypt to an ed25519 private key".to_string());
+    }
+    Ok(private_key)
+}
+
+/// Legacy encryption path, kept only to generate fixtures for migration tests.
+#[cfg(test)]
+pub(crate) fn encrypt_private_key_with_prf(
+    account_id: &str,
+    near_private_key: &str,
+    prf_output_b64u: &str,
+) -> Result<crate::types::EncryptedDataChaCha20Response, String> {
+    let key = derive_legacy_chacha20_key(prf_output_b64u, account_id)
+        .map_err(|e| format!("Legacy key derivation failed: {}", e))?;
+    crate::crypto::encrypt_data_chacha20(near_private_key, &key)
+}
+
+/// `ed25519:<bs58 public key>` for a NEAR private key string (`ed25519:<bs58 seed||pk>`).
+/// Rejects keys whose embedded public half does not match the seed.
+pub(crate) fn near_public_key_for_private_key(near_private_key: &str) -> Result<String, String> {
+    let decoded = Zeroizing::new(
+        bs58::decode(
+            near_private_key
+                .strip_prefix("ed25519:")
+                .unwrap_or(near_private_key),
+        )
+        .into_vec()
+        .map_err(|e| format!("Invalid private key base58: {}", e))?,
+    );
+    if decoded.len() != 64 {
+        return Err(format!(
+            "Private key must be 64 bytes (seed || public key), got {}",
+            decoded.len()
+        ));
+    }
+    let mut seed = Zeroizing::new([0u8; 32]);
+    seed.copy_from_slice(&decoded[..32]);
+    let signing_key = ed25519_dalek::SigningKey::from_bytes(&seed);
+    let public_key_bytes = signing_key.verifying_key().to_bytes();
+    if public_key_bytes[..] != decoded[32..] {
+        return Err("Private key public half does not match its seed".to_string());
+    }
+    Ok(format!(
+        "ed25519:{}",
+        bs58::encode(public_key_bytes).into_string()
+    ))
+}
@@ -1,24 +1,47 @@
+mod account_card;
 mod actions;
+mod balance_check;
 mod config;
 mod cose;
+mod credential_binding;
 mod crypto;
+mod device_registry;
 mod encoders;
 mod error;
 #[cfg(target_arch = "wasm32")]
 mod fetch;
 mod handlers;
+mod host_environment;
+mod inflight_requests;
+mod key_envelope;
+mod legacy;
 mod logger;
+mod panic_guard;
+mod randomness;
+mod request_deadline;
+mod rpc_calls;
+mod session_key_cache;
+mod sign_counter;
+mod signable_message;
+mod signature_preimage;
+mod state_changes;
 #[cfg(test)]
 mod tests;
 mod threshold;
 mod transaction;
+mod transaction_summary;
 mod types;
+mod verification_attestation;
+mod worker_security;
 mod wrap_key_handshake;
 
+use crate::config::WRAP_KEY_SEED_TIMEOUT_MS;
+use crate::inflight_requests::InFlight;
+use crate::request_deadline::RequestDeadline;
 use crate::types::worker_messages::{
     parse_typed_payload, parse_worker_request_envelope, worker_request_type_name,
-    worker_response_type_name, SignerWorkerMessage, SignerWorkerResponse, WorkerRequestType,
-    WorkerResponseType,
+    worker_response_type_name, BinaryAttachments, SignerWorkerMessage, SignerWorkerResponse,
+    WorkerRequestType, WorkerResponseType,
 };
 use crate::types::*;
 use crate::wrap_key_handshake::{get_prf_second_b64u, get_wrap_key_shards};
@@ -33,22 +56,28 @@ pub use handlers::handle_derive_near_keypair_and_encrypt::{
     DeriveNearKeypairAndEncryptResult,
 };
 pub use handlers::{
+    CoseExtractionBatchResult,
+    CoseExtractionItem,
     CoseExtractionResult,
     // Delegate Actions
     DelegatePayload,
     DelegateSignResult,
-    // Threshold Signing
-    DeriveThresholdEd25519ClientVerifyingShareRequest,
     // Extract Cose Public Key
+    ExtractCosePublicKeysRequest,
     ExtractCoseRequest,
+    // Device Registry
+    GetDeviceRecordsRequest,
+    GetDeviceRecordsResult,
     KeyActionResult,
+    // Legacy Key Migration
+    MigrateLegacyEncryptedKeyRequest,
+    MigrateLegacyEncryptedKeyResult,
     // Recover Account
     RecoverKeypairRequest,
     RecoverKeypairResult,
     // Combined Device2 Registration
     RegisterDevice2WithDerivedKeyRequest,
     RegisterDevice2WithDerivedKeyResult,
-    SignAddKeyThresholdPublicKeyNoPromptRequest,
     SignDelegateActionRequest,
     // Sign Nep413 Message
     SignNep413Request,
@@ -59,10 +88,20 @@ pub use handlers::{
     SignTransactionsWithActionsRequest,
     TransactionPayload,
 };
+#[cfg(feature = "threshold")]
+pub use handlers::{
+    // Threshold Signing
+    DeriveThresholdEd25519ClientVerifyingShareRequest,
+    SignAddKeyThresholdPublicKeyNoPromptRequest,
+    // Threshold client share warm-up
+    WarmThresholdEd25519ClientShareRequest,
+    WarmThresholdEd25519ClientShareResult,
+};
 
 // Re-export NEAR types for TypeScript usage
 pub use types::near::{
-    DelegateAction, PublicKey, Signature, SignedDelegate, SignedTransaction, Transaction,
+    is_valid_near_access_key, DelegateAction, PublicKey, Signature, SignedDelegate,
+    SignedTransaction, Transaction,
 };
 // Re-export progress types for auto-generation
 pub use types::progress::{
@@ -75,11 +114,43 @@ pub use types::wasm_to_json::{
 };
 
 pub use crate::crypto::WrapKey;
-pub use wrap_key_handshake::attach_wrap_key_seed_port;
+pub use cose::validate_attestation_object;
+pub use handlers::handle_extract_cose_public_key::{
+    extract_cose_public_key_sync, extract_ec2_coordinates_sync,
+};
+pub use handlers::handle_sign_intent::{compute_intent_digest_sync, summarize_intent_sync};
+pub use handlers::handle_sign_transactions_with_actions::validate_transaction_payload;
+pub use handlers::handle_verify_encrypted_key_envelope::check_encrypted_key_envelope_structure;
+pub use host_environment::check_environment;
+pub use key_envelope::{
+    decrypt_private_key_from_envelope, encrypt_private_key_to_envelope,
+    encrypt_private_key_to_envelope_with_nonce_mode, EncryptedKeyEnvelope, EnvelopeNonceMode,
+};
+pub use device_registry::DeviceRecord;
+pub use randomness::randomness_self_test;
+pub use signature_preimage::verify_signature_over_preimage;
+#[cfg(feature = "threshold")]
+pub use threshold::client_share_cache::clear_threshold_client_share_cache;
+#[cfg(feature = "threshold")]
+pub use threshold::relayer_signer::get_threshold_session_metrics;
+pub use threshold::request_auth::verify_threshold_relayer_request_signature;
+pub use transaction::signed_transaction_to_rpc_payload;
+pub use transaction_summary::summarize_transactions;
+pub use verification_attestation::verify_verification_attestation;
+pub use worker_security::initialize_worker_security;
+pub use wrap_key_handshake::{
+    active_signing_sessions, attach_wrap_key_seed_port, cancel_operation, clear_signing_session,
+    get_session_binding_nonce, signing_session_status,
+};
 
 #[wasm_bindgen]
 pub fn init_worker() {
     logger::init(config::CURRENT_LOG_LEVEL);
+    panic_guard::install_panic_hook();
+    // Failure is logged and latched; secret-generating paths refuse to run afterwards.
+    let _ = randomness::randomness_self_test();
+    // Missing host bridges are only logged here; handlers that need one reject lazily.
+    host_environment::warn_if_environment_incomplete();
 }
 
 /// Alias for init_worker to maintain compatibility with bundlers that auto-generate
@@ -125,6 +196,11 @@ pub fn send_progress_message(message_type: u32, step: u32, message: &str, data:
     // Only try to send message in WASM context
     #[cfg(target_arch = "wasm32")]
     {
+        // Calling an uninstalled import throws; progress is best-effort, so drop it instead.
+        if !host_environment::has_global_function(host_environment::SEND_PROGRESS_MESSAGE) {
+            debug!("Dropping progress message: sendProgressMessage is not installed");
+            return;
+        }
         let logs = JsValue::from(js_sys::Array::new());
         send_progress_message_js(
             message_type,
@@ -162,23 +238,94 @@ pub async fn handle_signer_message(message_val: JsValue) -> Result<JsValue, JsVa
     let SignerWorkerMessage {
         request_type,
         request_type_raw: msg_type_num,
+        caller_origin,
+        on_duplicate,
         payload: payload_js,
+        binary_attachments,
+        deadline,
     } = parse_worker_request_envelope(message_val)?;
 
+    // Optional origin/capability gate; rejects before any payload parsing.
+    worker_security::ensure_request_allowed(caller_origin.as_deref(), request_type)?;
+    // A request that sat in the queue past its deadline is dropped before any work starts.
+    deadline.check()?;
+
+    // Identical session-bound requests in flight at once (e.g. a double-click) run only once.
+    let Some(key) = inflight_requests::request_key(msg_type_num, &payload_js) else {
+        return dispatch_signer_request(
+            request_type,
+            msg_type_num,
+            payload_js,
+            binary_attachments,
+            deadline,
+        )
+        .await;
+    };
+    let session_id = key.session_id.clone();
+    match inflight_requests::signer_requests_in_flight().begin(
+        key,
+        on_duplicate,
+        session_key_cache::now_ms(),
+    )? {
+        InFlight::Leader(guard) => {
+            let result = dispatch_signer_request(
+                request_type,
+                msg_type_num,
+                payload_js,
+                binary_attachments,
+                deadline,
+            )
+            .await;
+            guard.complete(result.clone());
+            wrap_key_handshake::discard_pending_cancel_if_idle(&session_id);
+            result
+        }
+        InFlight::Follower(first) => {
+            debug!(
+                "WASM Worker: awaiting identical in-flight {} for session {}",
+                worker_request_type_name(request_type),
+                session_id
+            );
+            first.await.unwrap_or_else(|| {
+                Err(error::InFlightRequestError::FirstRequestAbandoned { session_id }.into())
+            })
+        }
+    }
+}
+
+/// Route one parsed request to its handler and wrap the result in a `SignerWorkerResponse`.
+async fn dispatch_signer_request(
+    request_type: WorkerRequestType,
+    msg_type_num: u32,
+    payload_js: JsValue,
+    binary_attachments: BinaryAttachments,
+    deadline: RequestDeadline,
+) -> Result<JsValue, JsValue> {
     debug!(
         "WASM Worker: Received message type: {} ({})",
         worker_request_type_name(request_type),
         msg_type_num
     );
+    if !binary_attachments.is_empty()
+        && request_type != WorkerRequestType::SignTransactionsWithActions
+    {
+        return Err(JsValue::from_str(&format!(
+            "binaryAttachments are not accepted by {}",
+            worker_request_type_name(request_type)
+        )));
+    }
 
     // Route message to appropriate handler
     let response_payload = match request_type {
         WorkerRequestType::DeriveNearKeypairAndEncrypt => {
             let request: DeriveNearKeypairAndEncryptRequest =
                 parse_typed_payload(&payload_js, request_type)?;
-            let wrap_key = get_wrap_key_shards(&request.session_id, request_type, 2000).await?;
+            let wrap_key =
+                get_wrap_key_shards(&request.session_id, request_type, WRAP_KEY_SEED_TIMEOUT_MS)
+                    .await?;
             let prf_second_b64u =
-                get_prf_second_b64u(&request.session_id, request_type, 2000).await?;
+                get_prf_second_b64u(&request.session_id, request_type, WRAP_KEY_SEED_TIMEOUT_MS)
+                    .await?;
             let result = handlers::handle_derive_near_keypair_and_encrypt(
                 request,
                 wrap_key,
@@ -190,22 +337,30 @@ pub async fn handle_signer_message(message_val: JsValue) -> Result<JsValue, JsVa
         }
         WorkerRequestType::RecoverKeypairFromPasskey => {
             let request: RecoverKeypairRequest = parse_typed_payload(&payload_js, request_type)?;
-            let wrap_key = get_wrap_key_shards(&request.session_id, request_type, 2000).await?;
+            let wrap_key =
+                get_wrap_key_shards(&request.session_id, request_type, WRAP_KEY_SEED_TIMEOUT_MS)
+                    .await?;
             let result = handlers::handle_recover_keypair_from_passkey(request, wrap_key).await?;
             serde_wasm_bindgen::to_value(&result)
                 .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
         }
         WorkerRequestType::DecryptPrivateKeyWithPrf => {
             let request: DecryptPrivateKeyRequest = parse_typed_payload(&payload_js, request_type)?;
-            let wrap_key = get_wrap_key_shards(&request.session_id, request_type, 2000).await?;
+            let wrap_key =
+                get_wrap_key_shards(&request.session_id, request_type, WRAP_KEY_SEED_TIMEOUT_MS)
+                    .await?;
             let result = handlers::handle_decrypt_private_key_with_prf(request, wrap_key).await?;
             serde_wasm_bindgen::to_value(&result)
                 .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
         }
         WorkerRequestType::SignTransactionsWithActions => {
-            let request: SignTransactionsWithActionsRequest =
+            let mut request: SignTransactionsWithActionsRequest =
                 parse_typed_payload(&payload_js, request_type)?;
-            let wrap_key = get_wrap_key_shards(&request.session_id, request_type, 2000).await?;
+            request.resolve_binary_attachments(binary_attachments)?;
+            request.deadline = deadline;
+            let wrap_key =
+                get_wrap_key_shards(&request.session_id, request_type, WRAP_KEY_SEED_TIMEOUT_MS)
+                    .await?;
             let result = handlers::handle_sign_transactions_with_actions(request, wrap_key).await?;
             serde_wasm_bindgen::to_value(&result)
                 .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
@@ -213,14 +368,122 @@ pub async fn handle_signer_message(message_val: JsValue) -> Result<JsValue, JsVa
         WorkerRequestType::SignDelegateAction => {
             let request: SignDelegateActionRequest =
                 parse_typed_payload(&payload_js, request_type)?;
-            let wrap_key = get_wrap_key_shards(&request.session_id, request_type, 2000).await?;
+            let wrap_key =
+                get_wrap_key_shards(&request.session_id, request_type, WRAP_KEY_SEED_TIMEOUT_MS)
+                    .await?;
             let result = handlers::handle_sign_delegate_action(request, wrap_key).await?;
             serde_wasm_bindgen::to_value(&result)
                 .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
         }
         WorkerRequestType::ExtractCosePublicKey => {
             let request: ExtractCoseRequest = parse_typed_payload(&payload_js, request_type)?;
-            let result = handlers::handle_extract_cose_public_key(request).await?;
+            let result = handlers::handle_extract_cose_public_key(request)?;
+            serde_wasm_bindgen::to_value(&result)
+                .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
+        }
+        WorkerRequestType::ExtractCosePublicKeys => {
+            let request: ExtractCosePublicKeysRequest =
+                parse_typed_payload(&payload_js, request_type)?;
+            let result = handlers::handle_extract_cose_public_keys(request);
+            serde_wasm_bindgen::to_value(&result)
+                .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
+        }
+        WorkerRequestType::GetDeviceRecords => {
+            let request: GetDeviceRecordsRequest = parse_typed_payload(&payload_js, request_type)?;
+            let result = handlers::handle_get_device_records(request);
+            serde_wasm_bindgen::to_value(&result)
+                .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
+        }
+        WorkerRequestType::SignIntent => {
+            let request: handlers::SignIntentRequest =
+                parse_typed_payload(&payload_js, request_type)?;
+            let wrap_key =
+                get_wrap_key_shards(&request.session_id, request_type, WRAP_KEY_SEED_TIMEOUT_MS)
+                    .await?;
+            let result = handlers::handle_sign_intent(request, wrap_key).await?;
+            serde_wasm_bindgen::to_value(&result)
+                .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
+        }
+        WorkerRequestType::CreateAccountCard => {
+            let request: handlers::CreateAccountCardRequest =
+                parse_typed_payload(&payload_js, request_type)?;
+            let wrap_key =
+                get_wrap_key_shards(&request.session_id, request_type, WRAP_KEY_SEED_TIMEOUT_MS)
+                    .await?;
+            let result = handlers::handle_create_account_card(request, wrap_key).await?;
+            serde_wasm_bindgen::to_value(&result)
+                .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
+        }
+        WorkerRequestType::VerifyAccountCard => {
+            let request: handlers::VerifyAccountCardRequest =
+                parse_typed_payload(&payload_js, request_type)?;
+            let result = handlers::handle_verify_account_card(request)?;
+            serde_wasm_bindgen::to_value(&result)
+                .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
+        }
+        WorkerRequestType::GetStateVersion => {
+            let result = handlers::handle_get_state_version();
+            serde_wasm_bindgen::to_value(&result)
+                .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
+        }
+        WorkerRequestType::GetStateChangesSince => {
+            let request: handlers::GetStateChangesSinceRequest =
+                parse_typed_payload(&payload_js, request_type)?;
+            let result = handlers::handle_get_state_changes_since(request);
+            serde_wasm_bindgen::to_value(&result)
+                .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
+        }
+        WorkerRequestType::LogoutAll => {
+            let result = handlers::handle_logout_all();
+            serde_wasm_bindgen::to_value(&result)
+                .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
+        }
+        WorkerRequestType::ClearAllSessions => {
+            let request: handlers::ClearAllSessionsRequest =
+                parse_typed_payload(&payload_js, request_type)?;
+            let result = handlers::handle_clear_all_sessions(request);
+            serde_wasm_bindgen::to_value(&result)
+                .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
+        }
+        WorkerRequestType::SignBorshTransaction => {
+            let request: handlers::SignBorshTransactionRequest =
+                parse_typed_payload(&payload_js, request_type)?;
+            let wrap_key =
+                get_wrap_key_shards(&request.session_id, request_type, WRAP_KEY_SEED_TIMEOUT_MS)
+                    .await?;
+            let result = handlers::handle_sign_borsh_transaction(request, wrap_key).await?;
+            serde_wasm_bindgen::to_value(&result)
+                .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
+        }
+        WorkerRequestType::VerifyEncryptedKeyEnvelope => {
+            let request: handlers::VerifyEncryptedKeyEnvelopeRequest =
+                parse_typed_payload(&payload_js, request_type)?;
+            let wrap_key =
+                get_wrap_key_shards(&request.session_id, request_type, WRAP_KEY_SEED_TIMEOUT_MS)
+                    .await?;
+            let result = handlers::handle_verify_encrypted_key_envelope(request, wrap_key).await?;
+            serde_wasm_bindgen::to_value(&result)
+                .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
+        }
+        #[cfg(feature = "debug-panic")]
+        WorkerRequestType::DebugPanic => {
+            let request: handlers::DebugPanicRequest =
+                parse_typed_payload(&payload_js, request_type)?;
+            handlers::handle_debug_panic(request).await?;
+            JsValue::UNDEFINED
+        }
+        #[cfg(not(feature = "debug-panic"))]
+        WorkerRequestType::DebugPanic => {
+            return Err(error::FeatureError::not_compiled(
+                "debug-panic",
+                worker_request_type_name(request_type),
+            )
+            .into());
+        }
+        WorkerRequestType::ProvisionRelayerRequestAuthSecret => {
+            let request: handlers::ProvisionRelayerRequestAuthSecretRequest =
+                parse_typed_payload(&payload_js, request_type)?;
+            let result = handlers::handle_provision_relayer_request_auth_secret(request)?;
             serde_wasm_bindgen::to_value(&result)
                 .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
         }
@@ -235,7 +498,9 @@ pub async fn handle_signer_message(message_val: JsValue) -> Result<JsValue, JsVa
         }
         WorkerRequestType::SignNep413Message => {
             let request: SignNep413Request = parse_typed_payload(&payload_js, request_type)?;
-            let wrap_key = get_wrap_key_shards(&request.session_id, request_type, 2000).await?;
+            let wrap_key =
+                get_wrap_key_shards(&request.session_id, request_type, WRAP_KEY_SEED_TIMEOUT_MS)
+                    .await?;
             let result = handlers::handle_sign_nep413_message(request, wrap_key).await?;
             serde_wasm_bindgen::to_value(&result)
                 .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
@@ -243,9 +508,12 @@ pub async fn handle_signer_message(message_val: JsValue) -> Result<JsValue, JsVa
         WorkerRequestType::RegisterDevice2WithDerivedKey => {
             let request: handlers::RegisterDevice2WithDerivedKeyRequest =
                 parse_typed_payload(&payload_js, request_type)?;
-            let wrap_key = get_wrap_key_shards(&request.session_id, request_type, 2000).await?;
+            let wrap_key =
+                get_wrap_key_shards(&request.session_id, request_type, WRAP_KEY_SEED_TIMEOUT_MS)
+                    .await?;
             let prf_second_b64u =
-                get_prf_second_b64u(&request.session_id, request_type, 2000).await?;
+                get_prf_second_b64u(&request.session_id, request_type, WRAP_KEY_SEED_TIMEOUT_MS)
+                    .await?;
             let result = handlers::handle_register_device2_with_derived_key(
                 request,
                 wrap_key,
@@ -255,26 +523,64 @@ pub async fn handle_signer_message(message_val: JsValue) -> Result<JsValue, JsVa
             serde_wasm_bindgen::to_value(&result)
                 .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
         }
+        #[cfg(feature = "threshold")]
         WorkerRequestType::DeriveThresholdEd25519ClientVerifyingShare => {
             let request: DeriveThresholdEd25519ClientVerifyingShareRequest =
                 parse_typed_payload(&payload_js, request_type)?;
-            let wrap_key = get_wrap_key_shards(&request.session_id, request_type, 2000).await?;
+            let wrap_key =
+                get_wrap_key_shards(&request.session_id, request_type, WRAP_KEY_SEED_TIMEOUT_MS)
+                    .await?;
             let result =
                 handlers::handle_threshold_ed25519_derive_client_verifying_share(request, wrap_key)
                     .await?;
             serde_wasm_bindgen::to_value(&result)
                 .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
         }
+        #[cfg(feature = "threshold")]
         WorkerRequestType::SignAddKeyThresholdPublicKeyNoPrompt => {
             let request: SignAddKeyThresholdPublicKeyNoPromptRequest =
                 parse_typed_payload(&payload_js, request_type)?;
-            let wrap_key = get_wrap_key_shards(&request.session_id, request_type, 2000).await?;
+            let wrap_key =
+                get_wrap_key_shards(&request.session_id, request_type, WRAP_KEY_SEED_TIMEOUT_MS)
+                    .await?;
             let result =
                 handlers::handle_sign_add_key_threshold_public_key_no_prompt(request, wrap_key)
                     .await?;
             serde_wasm_bindgen::to_value(&result)
                 .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
         }
+        WorkerRequestType::MigrateLegacyEncryptedKey => {
+            let request: MigrateLegacyEncryptedKeyRequest =
+                parse_typed_payload(&payload_js, request_type)?;
+            let wrap_key =
+                get_wrap_key_shards(&request.session_id, request_type, WRAP_KEY_SEED_TIMEOUT_MS)
+                    .await?;
+            let result = handlers::handle_migrate_legacy_encrypted_key(request, wrap_key).await?;
+            serde_wasm_bindgen::to_value(&result)
+                .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
+        }
+        #[cfg(feature = "threshold")]
+        WorkerRequestType::WarmThresholdEd25519ClientShare => {
+            let request: WarmThresholdEd25519ClientShareRequest =
+                parse_typed_payload(&payload_js, request_type)?;
+            let wrap_key =
+                get_wrap_key_shards(&request.session_id, request_type, WRAP_KEY_SEED_TIMEOUT_MS)
+                    .await?;
+            let result =
+                handlers::handle_warm_threshold_ed25519_client_share(request, wrap_key).await?;
+            serde_wasm_bindgen::to_value(&result)
+                .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {:?}", e)))?
+        }
+        #[cfg(not(feature = "threshold"))]
+        WorkerRequestType::DeriveThresholdEd25519ClientVerifyingShare
+        | WorkerRequestType::SignAddKeyThresholdPublicKeyNoPrompt
+        | WorkerRequestType::WarmThresholdEd25519ClientShare => {
+            return Err(error::FeatureError::not_compiled(
+                "threshold",
+                worker_request_type_name(request_type),
+            )
+            .into());
+        }
     };
 
     // At this point, response_payload is the successful JsValue result.
@@ -309,6 +615,31 @@ pub async fn handle_signer_message(message_val: JsValue) -> Result<JsValue, JsVa
         WorkerRequestType::SignAddKeyThresholdPublicKeyNoPrompt => {
             WorkerResponseType::SignAddKeyThresholdPublicKeyNoPromptSuccess
         }
+        WorkerRequestType::MigrateLegacyEncryptedKey => {
+            WorkerResponseType::MigrateLegacyEncryptedKeySuccess
+        }
+        WorkerRequestType::WarmThresholdEd25519ClientShare => {
+            WorkerResponseType::WarmThresholdEd25519ClientShareSuccess
+        }
+        WorkerRequestType::ExtractCosePublicKeys => {
+            WorkerResponseType::ExtractCosePublicKeysSuccess
+        }
+        WorkerRequestType::GetDeviceRecords => WorkerResponseType::GetDeviceRecordsSuccess,
+        WorkerRequestType::SignIntent => WorkerResponseType::SignIntentSuccess,
+        WorkerRequestType::CreateAccountCard => WorkerResponseType::CreateAccountCardSuccess,
+        WorkerRequestType::VerifyAccountCard => WorkerResponseType::VerifyAccountCardSuccess,
+        WorkerRequestType::GetStateVersion => WorkerResponseType::GetStateVersionSuccess,
+        WorkerRequestType::GetStateChangesSince => WorkerResponseType::GetStateChangesSinceSuccess,
+        WorkerRequestType::LogoutAll => WorkerResponseType::LogoutAllSuccess,
+        WorkerRequestType::ClearAllSessions => WorkerResponseType::ClearAllSessionsSuccess,
+        WorkerRequestType::SignBorshTransaction => WorkerResponseType::SignBorshTransactionSuccess,
+        WorkerRequestType::VerifyEncryptedKeyEnvelope => {
+            WorkerResponseType::VerifyEncryptedKeyEnvelopeSuccess
+        }
+        WorkerRequestType::DebugPanic => WorkerResponseType::DebugPanicSuccess,
+        WorkerRequestType::ProvisionRelayerRequestAuthSecret => {
+            WorkerResponseType::ProvisionRelayerRequestAuthSecretSuccess
+        }
     };
 
     // Debug logging for response type
@@ -0,0 +1,39 @@
+//! Secret wipe on handler panic.
+//!
+//! Release builds use `panic = "abort"`, so a panic in a handler (an `unwrap` on unexpected
+//! input, an out-of-bounds slice) traps the wasm instance: the request rejects with a JS
+//! `RuntimeError` and the worker must be recycled. The panic hook runs before the trap and
+//! zeroizes every secret store it can borrow, so a dead instance holds no WrapKeySeeds,
+//! PRF outputs, signing keys or threshold shares.
+
+use std::cell::RefCell;
+use std::panic;
+use std::sync::Once;
+use std::thread::LocalKey;
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// Install the wiping panic hook (idempotent). Chains to the previously installed hook.
+pub(crate) fn install_panic_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let _ = crate::wrap_key_handshake::try_wipe_signing_secrets();
+            previous(info);
+        }));
+    });
+}
+
+/// Clear a secret-bearing thread-local from the panic hook. Returns false (instead of
+/// panicking) when the store is mid-borrow or already destroyed.
+pub(crate) fn try_wipe<T: 'static>(
+    key: &'static LocalKey<RefCell<T>>,
+    wipe: impl FnOnce(&mut T),
+) -> bool {
+    key.try_with(|cell| {
+        cell.try_borrow_mut()
+            .map(|mut value| wipe(&mut value))
+            .is_ok()
+    })
+    .unwrap_or(false)
+}
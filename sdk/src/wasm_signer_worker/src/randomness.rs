@@ -0,0 +1,68 @@
+//! Startup CSPRNG self-test.
+//!
+//! Some constrained runtimes mis-stub `crypto.getRandomValues`. Before any ChaCha20
+//! nonce or FROST signing nonce is drawn from `getrandom`/`OsRng`, we check that it yields
+//! non-zero, non-repeating output and remember the verdict for the lifetime of the worker.
+
+use getrandom::getrandom;
+use log::error;
+use std::sync::atomic::{AtomicU8, Ordering};
+use wasm_bindgen::prelude::*;
+
+const RANDOMNESS_UNTESTED: u8 = 0;
+const RANDOMNESS_OK: u8 = 1;
+const RANDOMNESS_FAILED: u8 = 2;
+
+static RANDOMNESS_STATUS: AtomicU8 = AtomicU8::new(RANDOMNESS_UNTESTED);
+
+/// Draw two 32-byte buffers from `fill` and reject all-zero or identical output.
+pub fn randomness_self_test_with<F>(mut fill: F) -> Result<(), String>
+where
+    F: FnMut(&mut [u8]) -> Result<(), String>,
+{
+    let mut first = [0u8; 32];
+    let mut second = [0u8; 32];
+    fill(&mut first).map_err(|e| format!("CSPRNG self-test failed: {}", e))?;
+    fill(&mut second).map_err(|e| format!("CSPRNG self-test failed: {}", e))?;
+
+    if first.iter().all(|b| *b == 0) || second.iter().all(|b| *b == 0) {
+        return Err("CSPRNG self-test failed: RNG returned an all-zero buffer".to_string());
+    }
+    if first == second {
+        return Err("CSPRNG self-test failed: RNG returned identical buffers".to_string());
+    }
+    Ok(())
+}
+
+fn run_os_randomness_self_test() -> Result<(), String> {
+    let result = randomness_self_test_with(|buf| getrandom(buf).map_err(|e| e.to_string()));
+    let status = if result.is_ok() {
+        RANDOMNESS_OK
+    } else {
+        RANDOMNESS_FAILED
+    };
+    RANDOMNESS_STATUS.store(status, Ordering::SeqCst);
+    if let Err(e) = &result {
+        error!("[signer wasm] {} - secret generation is disabled", e);
+    }
+    result
+}
+
+/// Run the CSPRNG self-test against the host's `getrandom` and record the verdict.
+/// Called from worker startup; exported so hosts can surface the failure explicitly.
+#[wasm_bindgen]
+pub fn randomness_self_test() -> Result<(), JsValue> {
+    run_os_randomness_self_test().map_err(|e| JsValue::from_str(&e))
+}
+
+/// Guard for every code path that generates secrets. Runs the self-test lazily if
+/// startup did not, and refuses once it has failed.
+pub fn ensure_randomness_ok() -> Result<(), String> {
+    match RANDOMNESS_STATUS.load(Ordering::SeqCst) {
+        RANDOMNESS_OK => Ok(()),
+        RANDOMNESS_FAILED => Err(
+            "CSPRNG self-test failed at worker init; refusing to generate secrets".to_string(),
+        ),
+        _ => run_os_randomness_self_test(),
+    }
+}
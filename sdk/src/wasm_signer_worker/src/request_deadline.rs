@@ -0,0 +1,71 @@
+//! Caller-supplied deadline for a whole signer request.
+//!
+//! The envelope may carry `deadlineMsEpoch` (milliseconds since the Unix epoch). A request that
+//! waited too long in the worker queue or on the WrapKeySeed handshake is abandoned instead of
+//! signing for a caller that has already given up. The deadline is checked before a handler
+//! starts and between items of a batch.
+
+use crate::error::RequestDeadlineError;
+
+/// Milliseconds since the epoch from the worker clock.
+pub(crate) fn now_ms() -> f64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as f64)
+            .unwrap_or(0.0)
+    }
+}
+
+/// Optional `deadlineMsEpoch` plus the clock it is checked against.
+#[derive(Debug, Clone, Copy)]
+pub struct RequestDeadline {
+    deadline_ms_epoch: Option<f64>,
+    clock: fn() -> f64,
+}
+
+impl Default for RequestDeadline {
+    fn default() -> Self {
+        RequestDeadline::new(None)
+    }
+}
+
+impl RequestDeadline {
+    /// `None` never expires.
+    pub fn new(deadline_ms_epoch: Option<f64>) -> Self {
+        RequestDeadline {
+            deadline_ms_epoch,
+            clock: now_ms,
+        }
+    }
+
+    /// Check against `clock` instead of the worker clock (tests).
+    pub fn with_clock(mut self, clock: fn() -> f64) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn deadline_ms_epoch(&self) -> Option<f64> {
+        self.deadline_ms_epoch
+    }
+
+    /// Fail once the clock has reached the deadline.
+    pub fn check(&self) -> Result<(), RequestDeadlineError> {
+        let Some(deadline_ms_epoch) = self.deadline_ms_epoch else {
+            return Ok(());
+        };
+        let now_ms = (self.clock)();
+        if now_ms >= deadline_ms_epoch {
+            return Err(RequestDeadlineError::Exceeded {
+                deadline_ms_epoch,
+                now_ms,
+            });
+        }
+        Ok(())
+    }
+}
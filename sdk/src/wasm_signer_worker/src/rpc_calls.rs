@@ -0,0 +1,673 @@
+// ******************************************************************************
+// *                                                                            *
+// *                     NEAR JSON-RPC CALLS FROM THE WORKER                    *
+// *                                                                            *
+// ******************************************************************************
+
+use crate::encoders::Encoding;
+use crate::error::NearRpcError;
+use crate::types::SignedTransaction;
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[cfg(target_arch = "wasm32")]
+use crate::config::{BROADCAST_STATUS_POLL_INTERVAL_MS, BROADCAST_STATUS_POLL_MAX_ATTEMPTS};
+
+// === BROADCAST OPTIONS / RESULTS ===
+
+/// How far `broadcast_signed_transaction` follows a transaction before returning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WaitUntil {
+    /// Return as soon as the node accepts the transaction.
+    #[default]
+    None,
+    /// Wait until the transaction is included in a block.
+    Included,
+    /// Wait until the transaction and its receipts have executed (optimistic finality).
+    Executed,
+}
+
+impl WaitUntil {
+    /// `wait_until` value for `send_tx` / `tx`.
+    pub fn rpc_finality(self) -> &'static str {
+        match self {
+            WaitUntil::None => "NONE",
+            WaitUntil::Included => "INCLUDED",
+            WaitUntil::Executed => "EXECUTED_OPTIMISTIC",
+        }
+    }
+}
+
+/// Opt-in `broadcast` field on `SignTransactionsWithActionsRequest`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastConfig {
+    pub rpc_url: String,
+    #[serde(default)]
+    pub wait_until: WaitUntil,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TxFinalStatus {
+    /// Accepted by the node (`waitUntil: "none"`).
+    Submitted,
+    /// Included in a block (`waitUntil: "included"`).
+    Included,
+    /// Executed successfully (`waitUntil: "executed"`).
+    Executed,
+    /// Reached the chain but the transaction or one of its actions failed.
+    Failed,
+    /// The RPC call itself failed; the transaction may not have reached the chain.
+    BroadcastError,
+    /// Signed only: an earlier transaction in the batch failed, so this one was never sent.
+    NotBroadcast,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastResult {
+    /// NEAR transaction hash (base58)
+    pub transaction_hash: String,
+    pub final_status: TxFinalStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_detail: Option<String>,
+}
+
+impl BroadcastResult {
+    pub fn new(
+        transaction_hash: String,
+        final_status: TxFinalStatus,
+        error_detail: Option<String>,
+    ) -> Self {
+        BroadcastResult {
+            transaction_hash,
+            final_status,
+            error_detail,
+        }
+    }
+}
+
+// === JSON-RPC ENVELOPE ===
+
+#[cfg(target_arch = "wasm32")]
+#[derive(Serialize)]
+struct JsonRpcRequest<'a, P> {
+    jsonrpc: &'static str,
+    id: &'a str,
+    method: &'a str,
+    params: P,
+}
+
+#[cfg(target_arch = "wasm32")]
+#[derive(Serialize)]
+struct SendTxParams<'a> {
+    signed_tx_base64: &'a str,
+    wait_until: &'static str,
+}
+
+#[cfg(target_arch = "wasm32")]
+#[derive(Serialize)]
+struct TxStatusParams<'a> {
+    tx_hash: &'a str,
+    sender_account_id: &'a str,
+    wait_until: &'static str,
+}
+
+#[cfg(any(test, target_arch = "wasm32"))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct JsonRpcResponse<T> {
+    pub result: Option<T>,
+    pub error: Option<RpcErrorView>,
+}
+
+#[cfg(any(test, target_arch = "wasm32"))]
+impl<T> JsonRpcResponse<T> {
+    pub fn into_result(self) -> Result<T, NearRpcError> {
+        if let Some(error) = self.error {
+            return Err(error.into());
+        }
+        self.result.ok_or_else(|| {
+            NearRpcError::InvalidResponse("response has neither result nor error".to_string())
+        })
+    }
+}
+
+/// JSON-RPC `error` object as returned by nearcore.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcErrorView {
+    pub name: Option<String>,
+    pub cause: Option<RpcErrorCauseView>,
+    pub message: Option<String>,
+    /// Legacy detail field: a string, or a structured error such as `{ TxExecutionError: .. }`.
+    pub data: Option<ErrorKindView>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcErrorCauseView {
+    pub name: String,
+}
+
+impl From<RpcErrorView> for NearRpcError {
+    fn from(error: RpcErrorView) -> Self {
+        let message = error
+            .data
+            .map(|d| d.to_string())
+            .filter(|d| !d.is_empty())
+            .or(error.message)
+            .unwrap_or_default();
+        NearRpcError::Rpc {
+            name: error.name.unwrap_or_else(|| "UNKNOWN_ERROR".to_string()),
+            cause: error.cause.map(|c| c.name),
+            message,
+        }
+    }
+}
+
+// === FinalExecutionOutcome SUBSET ===
+
+/// The parts of `FinalExecutionOutcomeView` needed to report a broadcast result.
+/// With `wait_until: NONE` nearcore returns only `final_execution_status`.
+#[cfg(any(test, target_arch = "wasm32"))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct FinalExecutionOutcomeView {
+    pub final_execution_status: Option<String>,
+    pub status: Option<FinalExecutionStatusView>,
+    pub transaction_outcome: Option<ExecutionOutcomeWithIdView>,
+}
+
+#[cfg(any(test, target_arch = "wasm32"))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExecutionOutcomeWithIdView {
+    pub id: String,
+}
+
+#[cfg(any(test, target_arch = "wasm32"))]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub enum FinalExecutionStatusView {
+    NotStarted,
+    Started,
+    Failure(TxExecutionErrorView),
+    SuccessValue(String),
+}
+
+#[cfg(any(test, target_arch = "wasm32"))]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub enum TxExecutionErrorView {
+    ActionError(ActionErrorView),
+    InvalidTxError(ErrorKindView),
+}
+
+#[cfg(any(test, target_arch = "wasm32"))]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ActionErrorView {
+    pub index: Option<u64>,
+    pub kind: ErrorKindView,
+}
+
+#[cfg(any(test, target_arch = "wasm32"))]
+impl fmt::Display for TxExecutionErrorView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TxExecutionErrorView::ActionError(err) => match err.index {
+                Some(index) => write!(f, "ActionError at action {}: {}", index, err.kind),
+                None => write!(f, "ActionError: {}", err.kind),
+            },
+            TxExecutionErrorView::InvalidTxError(kind) => write!(f, "InvalidTxError: {}", kind),
+        }
+    }
+}
+
+/// Nested nearcore error enum (`{ "FunctionCallError": { "ExecutionError": "..." } }`)
+/// kept as a readable path instead of mirroring every error variant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKindView {
+    Text(String),
+    Variant {
+        name: String,
+        inner: Box<ErrorKindView>,
+    },
+    Fields(Vec<(String, ErrorKindView)>),
+}
+
+impl fmt::Display for ErrorKindView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKindView::Text(text) => write!(f, "{}", text),
+            ErrorKindView::Variant { name, inner } => match inner.as_ref() {
+                ErrorKindView::Text(text) if text.is_empty() => write!(f, "{}", name),
+                ErrorKindView::Text(text) => write!(f, "{}: {}", name, text),
+                ErrorKindView::Variant { .. } => write!(f, "{}.{}", name, inner),
+                ErrorKindView::Fields(fields) if fields.is_empty() => write!(f, "{}", name),
+                ErrorKindView::Fields(_) => write!(f, "{} {{ {} }}", name, inner),
+            },
+            ErrorKindView::Fields(fields) => {
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", key, value)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ErrorKindView {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ErrorKindVisitor;
+
+        impl<'de> Visitor<'de> for ErrorKindVisitor {
+            type Value = ErrorKindView;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a NEAR error kind (string or nested object)")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(ErrorKindView::Text(v.to_string()))
+            }
+
+            fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+                Ok(ErrorKindView::Text(v.to_string()))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(ErrorKindView::Text(v.to_string()))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(ErrorKindView::Text(v.to_string()))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                Ok(ErrorKindView::Text(v.to_string()))
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(ErrorKindView::Text(String::new()))
+            }
+
+            fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(ErrorKindView::Text(String::new()))
+            }
+
+            fn visit_some<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+                ErrorKindView::deserialize(d)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut items = Vec::new();
+                while let Some(item) = seq.next_element::<ErrorKindView>()? {
+                    items.push(item.to_string());
+                }
+                Ok(ErrorKindView::Text(format!("[{}]", items.join(", "))))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut fields = Vec::new();
+                while let Some((key, value)) = map.next_entry::<String, ErrorKindView>()? {
+                    fields.push((key, value));
+                }
+                // Externally tagged enum variants are single-key objects with a CamelCase key;
+                // struct payloads (`{ "account_id": .. }`) use snake_case keys.
+                let is_variant =
+                    fields.len() == 1 && fields[0].0.starts_with(|c: char| c.is_ascii_uppercase());
+                if is_variant {
+                    let (name, inner) = fields.remove(0);
+                    return Ok(ErrorKindView::Variant {
+                        name,
+                        inner: Box::new(inner),
+                    });
+                }
+                Ok(ErrorKindView::Fields(fields))
+            }
+        }
+
+        deserializer.deserialize_any(ErrorKindVisitor)
+    }
+}
+
+/// Map an outcome to a final status for `wait_until`.
+/// Returns `None` while an `executed` broadcast is still pending.
+#[cfg(any(test, target_arch = "wasm32"))]
+pub fn classify_outcome(
+    outcome: &FinalExecutionOutcomeView,
+    wait_until: WaitUntil,
+) -> Option<(TxFinalStatus, Option<String>)> {
+    if let Some(FinalExecutionStatusView::Failure(err)) = &outcome.status {
+        return Some((TxFinalStatus::Failed, Some(err.to_string())));
+    }
+    match wait_until {
+        WaitUntil::None => Some((TxFinalStatus::Submitted, None)),
+        WaitUntil::Included => Some((TxFinalStatus::Included, None)),
+        WaitUntil::Executed => match outcome.status {
+            Some(FinalExecutionStatusView::SuccessValue(_)) => {
+                Some((TxFinalStatus::Executed, None))
+            }
+            _ => None,
+        },
+    }
+}
+
+// === BROADCAST ===
+
+/// Broadcast a standard-base64 borsh `SignedTransaction` via `send_tx`.
+///
+/// In `Executed` mode a node-side timeout is not a failure: the transaction is polled via
+/// `tx` up to `BROADCAST_STATUS_POLL_MAX_ATTEMPTS` times before giving up.
+pub async fn broadcast_signed_transaction(
+    rpc_url: &str,
+    signed_tx_borsh_b64: &str,
+    wait_until: WaitUntil,
+) -> Result<BroadcastResult, NearRpcError> {
+    let signed_tx_bytes = Encoding::B64Std
+        .decode(signed_tx_borsh_b64.trim())
+        .map_err(NearRpcError::InvalidTransaction)?;
+    let signed_tx = SignedTransaction::from_borsh_bytes(&signed_tx_bytes)
+        .map_err(NearRpcError::InvalidTransaction)?;
+    let (hash, _size) = signed_tx.transaction.get_hash_and_size();
+    let tx_hash = bs58::encode(hash.0).into_string();
+    let sender_account_id = signed_tx.transaction.signer_id.0;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let _ = rpc_url;
+        let _ = wait_until;
+        let _ = tx_hash;
+        let _ = sender_account_id;
+        Err(NearRpcError::Transport(
+            "NEAR RPC broadcast is only supported in wasm32 builds".to_string(),
+        ))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let finished = |outcome: &FinalExecutionOutcomeView| {
+            classify_outcome(outcome, wait_until)
+                .map(|(status, detail)| BroadcastResult::new(tx_hash.clone(), status, detail))
+        };
+
+        let sent = rpc_call::<_, FinalExecutionOutcomeView>(
+            rpc_url,
+            "send_tx",
+            SendTxParams {
+                signed_tx_base64: signed_tx_borsh_b64.trim(),
+                wait_until: wait_until.rpc_finality(),
+            },
+        )
+        .await;
+        match sent {
+            Ok(outcome) => {
+                if let Some(result) = finished(&outcome) {
+                    return Ok(result);
+                }
+            }
+            Err(e) if wait_until == WaitUntil::Executed && e.is_pending() => {}
+            Err(e) => return Err(e),
+        }
+
+        for _ in 0..BROADCAST_STATUS_POLL_MAX_ATTEMPTS {
+            sleep_ms(BROADCAST_STATUS_POLL_INTERVAL_MS).await;
+            let polled = rpc_call::<_, FinalExecutionOutcomeView>(
+                rpc_url,
+                "tx",
+                TxStatusParams {
+                    tx_hash: &tx_hash,
+                    sender_account_id: &sender_account_id,
+                    wait_until: wait_until.rpc_finality(),
+                },
+            )
+            .await;
+            match polled {
+                Ok(outcome) => {
+                    if let Some(result) = finished(&outcome) {
+                        return Ok(result);
+                    }
+                }
+                Err(e) if e.is_pending() => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(NearRpcError::PollTimeout {
+            tx_hash,
+            attempts: BROADCAST_STATUS_POLL_MAX_ATTEMPTS,
+        })
+    }
+}
+
+// === VIEW CALLS ===
+
+#[cfg(target_arch = "wasm32")]
+#[derive(Serialize)]
+struct CallFunctionParams<'a> {
+    request_type: &'static str,
+    finality: &'static str,
+    account_id: &'a str,
+    method_name: &'a str,
+    args_base64: String,
+}
+
+/// `query` result for `request_type: call_function`. Older nodes report contract panics
+/// here as `error` instead of a JSON-RPC error object.
+#[cfg(any(test, target_arch = "wasm32"))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct CallFunctionResultView {
+    #[serde(default)]
+    pub result: Option<Vec<u8>>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[cfg(any(test, target_arch = "wasm32"))]
+impl CallFunctionResultView {
+    /// The raw bytes returned by the view method.
+    pub fn into_bytes(self) -> Result<Vec<u8>, NearRpcError> {
+        if let Some(error) = self.error {
+            return Err(NearRpcError::Rpc {
+                name: "HANDLER_ERROR".to_string(),
+                cause: Some("CONTRACT_EXECUTION_ERROR".to_string()),
+                message: error,
+            });
+        }
+        self.result.ok_or_else(|| {
+            NearRpcError::InvalidResponse("call_function result has no result bytes".to_string())
+        })
+    }
+}
+
+/// Call a contract view method at final finality and decode its JSON return value.
+pub async fn view_function_call<A: Serialize, T: de::DeserializeOwned>(
+    rpc_url: &str,
+    contract_id: &str,
+    method_name: &str,
+    args: &A,
+) -> Result<T, NearRpcError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let _ = (rpc_url, contract_id, method_name, args);
+        Err(NearRpcError::Transport(
+            "NEAR RPC view calls are only supported in wasm32 builds".to_string(),
+        ))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let args_json = serde_wasm_bindgen::to_value(args)
+            .ok()
+            .and_then(|v| js_sys::JSON::stringify(&v).ok())
+            .and_then(|s| s.as_string())
+            .ok_or_else(|| {
+                NearRpcError::InvalidResponse(format!("failed to serialize {method_name} args"))
+            })?;
+        let view = rpc_call::<_, CallFunctionResultView>(
+            rpc_url,
+            "query",
+            CallFunctionParams {
+                request_type: "call_function",
+                finality: "final",
+                account_id: contract_id,
+                method_name,
+                args_base64: Encoding::B64Std.encode(args_json.as_bytes()),
+            },
+        )
+        .await?;
+        let bytes = view.into_bytes()?;
+        let text = String::from_utf8(bytes).map_err(|e| {
+            NearRpcError::InvalidResponse(format!("{method_name} returned non-UTF-8 data: {e}"))
+        })?;
+        let json_val = js_sys::JSON::parse(&text).map_err(|_| {
+            NearRpcError::InvalidResponse(format!("{method_name} returned invalid JSON"))
+        })?;
+        serde_wasm_bindgen::from_value(json_val)
+            .map_err(|e| NearRpcError::InvalidResponse(format!("{method_name}: {e}")))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+#[derive(Serialize)]
+struct ViewAccountParams<'a> {
+    request_type: &'static str,
+    finality: &'static str,
+    account_id: &'a str,
+}
+
+/// `query` result for `request_type: view_account`. Balances arrive as decimal strings.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AccountView {
+    /// Liquid balance, yoctoNEAR.
+    #[serde(with = "crate::types::near::serde_balance_as_dec_str")]
+    pub amount: u128,
+    /// Balance locked by staking, yoctoNEAR. It also covers storage staking first.
+    #[serde(with = "crate::types::near::serde_balance_as_dec_str")]
+    pub locked: u128,
+    /// Bytes of state the account pays storage staking for.
+    pub storage_usage: u64,
+    #[serde(default)]
+    pub code_hash: Option<String>,
+    #[serde(default)]
+    pub block_height: Option<u64>,
+}
+
+/// Fetch `account_id`'s balances at final finality.
+pub async fn view_account(rpc_url: &str, account_id: &str) -> Result<AccountView, NearRpcError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let _ = (rpc_url, account_id);
+        Err(NearRpcError::Transport(
+            "NEAR RPC view calls are only supported in wasm32 builds".to_string(),
+        ))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        rpc_call::<_, AccountView>(
+            rpc_url,
+            "query",
+            ViewAccountParams {
+                request_type: "view_account",
+                finality: "final",
+                account_id,
+            },
+        )
+        .await
+    }
+}
+
+/// `gas_price` result: yoctoNEAR per gas unit, as a decimal string.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct GasPriceView {
+    #[serde(with = "crate::types::near::serde_balance_as_dec_str")]
+    pub gas_price: u128,
+}
+
+/// Fetch the gas price at `block_height`, or at the latest block when `None`.
+pub async fn gas_price(
+    rpc_url: &str,
+    block_height: Option<u64>,
+) -> Result<GasPriceView, NearRpcError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let _ = (rpc_url, block_height);
+        Err(NearRpcError::Transport(
+            "NEAR RPC gas_price is only supported in wasm32 builds".to_string(),
+        ))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        rpc_call::<_, GasPriceView>(rpc_url, "gas_price", [block_height]).await
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn sleep_ms(ms: u32) {
+    let _ =
+        wasm_bindgen_futures::JsFuture::from(crate::wrap_key_handshake::timeout_promise(ms)).await;
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn rpc_call<P: Serialize, T: de::DeserializeOwned>(
+    rpc_url: &str,
+    method: &str,
+    params: P,
+) -> Result<T, NearRpcError> {
+    use crate::fetch::{
+        build_json_post_init, fetch_with_init, response_json, response_ok, response_status,
+        response_text,
+    };
+    use wasm_bindgen::JsValue;
+
+    let body_js = serde_wasm_bindgen::to_value(&JsonRpcRequest {
+        jsonrpc: "2.0",
+        id: "tatchi-signer",
+        method,
+        params,
+    })
+    .map_err(|e| NearRpcError::InvalidTransaction(format!("failed to serialize request: {e}")))?;
+    let body = js_sys::JSON::stringify(&body_js)
+        .ok()
+        .and_then(|s| s.as_string())
+        .ok_or_else(|| NearRpcError::InvalidTransaction("JSON.stringify failed".to_string()))?;
+
+    let init = build_json_post_init(&body).map_err(NearRpcError::Transport)?;
+    // Public RPC nodes answer with `Access-Control-Allow-Origin: *`, which browsers
+    // reject for credentialed requests.
+    js_sys::Reflect::set(
+        &init,
+        &JsValue::from_str("credentials"),
+        &JsValue::from_str("omit"),
+    )
+    .map_err(|_| NearRpcError::Transport("Failed to set fetch init.credentials".to_string()))?;
+
+    let resp = fetch_with_init(rpc_url, &init)
+        .await
+        .map_err(NearRpcError::Transport)?;
+
+    if !response_ok(&resp).map_err(NearRpcError::Transport)? {
+        let status = response_status(&resp).unwrap_or(0);
+        let body = response_text(&resp).await.unwrap_or_default();
+        // nearcore reports e.g. TIMEOUT_ERROR with HTTP 408 and a JSON-RPC error body.
+        if let Ok(json_val) = js_sys::JSON::parse(&body) {
+            if let Ok(parsed) =
+                serde_wasm_bindgen::from_value::<JsonRpcResponse<de::IgnoredAny>>(json_val)
+            {
+                if let Some(error) = parsed.error {
+                    return Err(error.into());
+                }
+            }
+        }
+        return Err(NearRpcError::Http { status, body });
+    }
+
+    let json_val = response_json(&resp)
+        .await
+        .map_err(NearRpcError::InvalidResponse)?;
+    serde_wasm_bindgen::from_value::<JsonRpcResponse<T>>(json_val)
+        .map_err(|e| NearRpcError::InvalidResponse(e.to_string()))?
+        .into_result()
+}
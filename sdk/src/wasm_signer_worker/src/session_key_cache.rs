@@ -0,0 +1,352 @@
+//! Opt-in reuse of one decrypted NEAR signing key across the handlers of a signing session.
+//!
+//! By default every local-signer handler decrypts its key envelope (HKDF + AEAD) on its own.
+//! When the confirmation that opened a session declares `allowKeyReuseWithinSession`, the VRF
+//! worker forwards that `keyReusePolicy` along with the session's WrapKeySeed. The first
+//! decryption is then kept here, keyed by session id, and later handlers of the same session
+//! reuse it until the operation budget or TTL runs out. Slots never cross session ids and are
+//! zeroized on `clear_signing_session`, `cancel_operation`, budget exhaustion and expiry.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::config::{SESSION_KEY_REUSE_MAX_OPERATIONS, SESSION_KEY_REUSE_MAX_TTL_MS};
+use crate::error::SessionKeyReuseError;
+use crate::state_changes::{record_state_change, StateChangeKind};
+
+/// What a session does once its cached key's budget or TTL is spent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyReuseExhaustion {
+    /// Decrypt the envelope again for every further operation (without caching it).
+    #[default]
+    Redecrypt,
+    /// Reject further operations in the session until it is confirmed again.
+    Fail,
+}
+
+/// `keyReusePolicy` as declared by the session's confirmation. Reuse stays off unless
+/// `allowKeyReuseWithinSession` is set together with a non-zero `maxOperations` and `ttlMs`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyReusePolicy {
+    #[serde(default)]
+    pub allow_key_reuse_within_session: bool,
+    /// Signing operations the cached key may serve, including the one that decrypted it.
+    #[serde(default)]
+    pub max_operations: u32,
+    /// Lifetime of the cached key, counted from the decryption that stored it.
+    #[serde(default)]
+    pub ttl_ms: u64,
+    #[serde(default)]
+    pub on_exhausted: KeyReuseExhaustion,
+}
+
+impl KeyReusePolicy {
+    pub fn enabled(&self) -> bool {
+        self.allow_key_reuse_within_session && self.max_operations > 0 && self.ttl_ms > 0
+    }
+
+    fn clamped(self) -> Self {
+        Self {
+            max_operations: self.max_operations.min(SESSION_KEY_REUSE_MAX_OPERATIONS),
+            ttl_ms: self.ttl_ms.min(SESSION_KEY_REUSE_MAX_TTL_MS),
+            ..self
+        }
+    }
+}
+
+/// The envelope a key was decrypted from; a cached key is only handed out for the same one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySource {
+    pub near_account_id: String,
+    pub credential_id: Option<String>,
+    pub encrypted_private_key_data: String,
+}
+
+struct CachedKey {
+    source: KeySource,
+    secret: Zeroizing<[u8; 32]>,
+}
+
+struct SessionKeySlot {
+    policy: KeyReusePolicy,
+    remaining_operations: u32,
+    /// Set when the first decryption is stored.
+    expires_at_ms: Option<u64>,
+    key: Option<CachedKey>,
+}
+
+impl SessionKeySlot {
+    fn is_expired(&self, now_ms: u64) -> bool {
+        self.expires_at_ms.is_some_and(|exp| now_ms >= exp)
+    }
+
+    /// Zeroize the cached key (if any) and return it.
+    fn take_key(&mut self) -> Option<Zeroizing<[u8; 32]>> {
+        self.key.take().map(|mut cached| {
+            cached.secret.zeroize();
+            cached.secret
+        })
+    }
+
+    fn expire(&mut self) {
+        self.take_key();
+        self.remaining_operations = 0;
+    }
+}
+
+/// How a signing operation should obtain its key.
+pub enum KeyReuseLookup {
+    /// Use the cached key; one operation has been charged to the session budget.
+    Reuse(Zeroizing<[u8; 32]>),
+    /// Decrypt, then `store` the key (first operation of a reuse-enabled session).
+    DecryptAndStore,
+    /// Decrypt without caching.
+    Decrypt,
+}
+
+/// Key reuse state of a session, as reported by `signing_session_status`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyReuseStatus {
+    pub enabled: bool,
+    pub key_cached: bool,
+    pub remaining_operations: u32,
+    pub expires_at_ms: Option<u64>,
+}
+
+/// Per-session key reuse slots keyed by session id.
+#[derive(Default)]
+pub struct SessionKeyCache {
+    slots: HashMap<String, SessionKeySlot>,
+}
+
+impl SessionKeyCache {
+    /// Install the policy a session was confirmed with, dropping any previous slot for that id.
+    /// A disabled policy leaves no slot, so every handler decrypts on its own.
+    pub fn set_policy(&mut self, session_id: &str, policy: KeyReusePolicy) {
+        self.clear_session(session_id);
+        let policy = policy.clamped();
+        if !policy.enabled() {
+            return;
+        }
+        self.slots.insert(
+            session_id.to_string(),
+            SessionKeySlot {
+                policy,
+                remaining_operations: policy.max_operations,
+                expires_at_ms: None,
+                key: None,
+            },
+        );
+    }
+
+    pub fn lookup(
+        &mut self,
+        session_id: &str,
+        source: &KeySource,
+        now_ms: u64,
+    ) -> Result<KeyReuseLookup, SessionKeyReuseError> {
+        let Some(slot) = self.slots.get_mut(session_id) else {
+            return Ok(KeyReuseLookup::Decrypt);
+        };
+        let fail = slot.policy.on_exhausted == KeyReuseExhaustion::Fail;
+        if slot.is_expired(now_ms) {
+            slot.expire();
+            if fail {
+                return Err(SessionKeyReuseError::Expired {
+                    session_id: session_id.to_string(),
+                    expired_at_ms: slot.expires_at_ms.unwrap_or_default(),
+                });
+            }
+            return Ok(KeyReuseLookup::Decrypt);
+        }
+        if slot.remaining_operations == 0 {
+            if fail {
+                return Err(SessionKeyReuseError::BudgetExhausted {
+                    session_id: session_id.to_string(),
+                });
+            }
+            return Ok(KeyReuseLookup::Decrypt);
+        }
+        let secret = match slot.key.as_ref() {
+            None => return Ok(KeyReuseLookup::DecryptAndStore),
+            // A different account or envelope in the same session is never served from the slot.
+            Some(cached) if cached.source != *source => return Ok(KeyReuseLookup::Decrypt),
+            Some(cached) => cached.secret.clone(),
+        };
+        slot.remaining_operations -= 1;
+        if slot.remaining_operations == 0 {
+            slot.take_key();
+        }
+        Ok(KeyReuseLookup::Reuse(secret))
+    }
+
+    /// Cache the key decrypted after a `DecryptAndStore` lookup, charging that operation.
+    pub fn store(
+        &mut self,
+        session_id: &str,
+        source: KeySource,
+        secret: Zeroizing<[u8; 32]>,
+        now_ms: u64,
+    ) {
+        let Some(slot) = self.slots.get_mut(session_id) else {
+            return;
+        };
+        if slot.key.is_some() || slot.remaining_operations == 0 {
+            return;
+        }
+        slot.remaining_operations -= 1;
+        slot.expires_at_ms = Some(now_ms.saturating_add(slot.policy.ttl_ms));
+        if slot.remaining_operations > 0 {
+            slot.key = Some(CachedKey { source, secret });
+        }
+    }
+
+    pub fn status(&mut self, session_id: &str, now_ms: u64) -> KeyReuseStatus {
+        let Some(slot) = self.slots.get_mut(session_id) else {
+            return KeyReuseStatus {
+                enabled: false,
+                key_cached: false,
+                remaining_operations: 0,
+                expires_at_ms: None,
+            };
+        };
+        if slot.is_expired(now_ms) {
+            slot.expire();
+        }
+        KeyReuseStatus {
+            enabled: true,
+            key_cached: slot.key.is_some(),
+            remaining_operations: slot.remaining_operations,
+            expires_at_ms: slot.expires_at_ms,
+        }
+    }
+
+    /// Zeroize the session's cached key if its TTL has passed, returning the account it belonged
+    /// to. `lookup` and `status` expire slots too; this only tells callers a key was dropped.
+    pub fn expire_if_due(&mut self, session_id: &str, now_ms: u64) -> Option<String> {
+        let slot = self.slots.get_mut(session_id)?;
+        if !slot.is_expired(now_ms) {
+            return None;
+        }
+        let account_id = slot.key.as_ref().map(|k| k.source.near_account_id.clone());
+        slot.expire();
+        account_id
+    }
+
+    /// Drop every slot, zeroizing cached keys. Returns how many keys were cached.
+    pub fn clear_all(&mut self) -> usize {
+        self.slots
+            .drain()
+            .filter_map(|(_, mut slot)| slot.take_key())
+            .count()
+    }
+
+    /// Drop the session's slot, returning its cached key already zeroized (if one was cached).
+    pub fn clear_session(&mut self, session_id: &str) -> Option<Zeroizing<[u8; 32]>> {
+        self.slots
+            .remove(session_id)
+            .and_then(|mut slot| slot.take_key())
+    }
+}
+
+/// Client clock for reuse TTLs. Native builds (tests) have no JS clock, so slots never expire
+/// there through the handler path; `SessionKeyCache` itself takes the time explicitly.
+pub(crate) fn now_ms() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now() as u64
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        0
+    }
+}
+
+thread_local! {
+    static SESSION_KEY_CACHE: RefCell<SessionKeyCache> = RefCell::new(SessionKeyCache::default());
+}
+
+/// Record the `keyReusePolicy` delivered with a session's WrapKeySeed.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub(crate) fn set_session_key_reuse_policy(session_id: &str, policy: KeyReusePolicy) {
+    SESSION_KEY_CACHE.with(|cache| cache.borrow_mut().set_policy(session_id, policy));
+}
+
+/// The session's cached key for `source` if its policy allows reuse, otherwise `decrypt()`
+/// (caching the result when this is the session's first decryption).
+pub(crate) fn session_key_or_decrypt(
+    session_id: &str,
+    source: KeySource,
+    now_ms: u64,
+    decrypt: impl FnOnce() -> Result<Zeroizing<[u8; 32]>, String>,
+) -> Result<Zeroizing<[u8; 32]>, String> {
+    record_auto_lock(session_id, now_ms);
+    let lookup =
+        SESSION_KEY_CACHE.with(|cache| cache.borrow_mut().lookup(session_id, &source, now_ms))?;
+    match lookup {
+        KeyReuseLookup::Reuse(secret) => {
+            record_state_change(
+                StateChangeKind::SessionConsumed,
+                Some(&source.near_account_id),
+            );
+            Ok(secret)
+        }
+        KeyReuseLookup::Decrypt => decrypt(),
+        KeyReuseLookup::DecryptAndStore => {
+            let secret = decrypt()?;
+            let account_id = source.near_account_id.clone();
+            SESSION_KEY_CACHE.with(|cache| {
+                cache
+                    .borrow_mut()
+                    .store(session_id, source, secret.clone(), now_ms)
+            });
+            record_state_change(StateChangeKind::SessionConsumed, Some(&account_id));
+            Ok(secret)
+        }
+    }
+}
+
+pub(crate) fn session_key_reuse_status(session_id: &str, now_ms: u64) -> KeyReuseStatus {
+    record_auto_lock(session_id, now_ms);
+    SESSION_KEY_CACHE.with(|cache| cache.borrow_mut().status(session_id, now_ms))
+}
+
+/// Record an `AutoLock` state change when the session's cached key has just timed out.
+fn record_auto_lock(session_id: &str, now_ms: u64) {
+    let expired =
+        SESSION_KEY_CACHE.with(|cache| cache.borrow_mut().expire_if_due(session_id, now_ms));
+    if let Some(account_id) = expired {
+        record_state_change(StateChangeKind::AutoLock, Some(&account_id));
+    }
+}
+
+/// Zeroize and drop the session's cached key and reuse policy (session teardown). Returns
+/// whether a decrypted key was cached.
+pub(crate) fn clear_session_key(session_id: &str) -> bool {
+    let cleared = SESSION_KEY_CACHE.with(|cache| cache.borrow_mut().clear_session(session_id));
+    if cleared.is_some() {
+        log::debug!(
+            "[rust wasm]: cleared cached signing key for session {}",
+            session_id
+        );
+    }
+    cleared.is_some()
+}
+
+/// Zeroize and drop every session's cached key and reuse policy (`LOGOUT_ALL`).
+pub(crate) fn clear_all_session_keys() -> usize {
+    SESSION_KEY_CACHE.with(|cache| cache.borrow_mut().clear_all())
+}
+
+/// `clear_all_session_keys` for the panic hook; false when the cache was mid-borrow.
+pub(crate) fn try_wipe_session_keys() -> bool {
+    crate::panic_guard::try_wipe(&SESSION_KEY_CACHE, |cache| {
+        cache.clear_all();
+    })
+}
@@ -0,0 +1,245 @@
+//! Authenticator signature counter (`signCount`) tracking for clone detection.
+//!
+//! Every WebAuthn assertion a signing request presents carries its authenticator's `signCount`.
+//! The last non-zero value seen per credential is kept here; an assertion whose counter is below
+//! the stored one fails the request with `AuthenticatorCounterRegression` and records a state
+//! change, since a cloned authenticator signing with a stale counter is the usual cause.
+//! Authenticators that always report 0 (most platform passkeys) are never flagged.
+//!
+//! Counters are not secret but must outlive the worker: the TS shell persists them as a
+//! snapshot sealed under the account's WrapKeySeed (`export_sign_counter_snapshot` /
+//! `import_sign_counter_snapshot`), and importing never lowers a counter. After a deliberate
+//! credential restore, `acknowledge_credential_restore` lets the next assertion re-seed it.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::config::{SIGN_COUNTER_SNAPSHOT_INFO, SIGN_COUNTER_SNAPSHOT_VERSION};
+use crate::crypto::{decrypt_data_chacha20_with_aad, encrypt_data_chacha20_with_aad, WrapKey};
+use crate::encoders::{base64_url_decode, base64_url_encode};
+use crate::error::SignCountError;
+use crate::state_changes::{record_state_change, StateChangeKind};
+use crate::types::WebAuthnAuthenticationCredential;
+
+/// Outcome of checking one assertion's counter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignCountCheck {
+    /// First counter seen for the credential, or it moved forward; now stored.
+    Advanced,
+    /// Same counter as stored (e.g. one assertion serving several requests of a session).
+    Unchanged,
+    /// The authenticator reports 0, so it keeps no counter to compare.
+    ZeroCounter,
+    /// The counter went backwards after `acknowledge_credential_restore`; it was re-seeded.
+    RestoreAccepted,
+}
+
+/// Counters worth persisting: the last non-zero `signCount` per credential id.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignCounterSnapshot {
+    pub counters: BTreeMap<String, u32>,
+}
+
+/// `SignCounterSnapshot` encrypted under a WrapKeySeed-derived key (ChaCha20-Poly1305).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SealedSignCounterSnapshot {
+    pub version: u8,
+    pub ciphertext_b64u: String,
+    pub nonce_b64u: String,
+}
+
+#[derive(Default)]
+pub struct SignCounterStore {
+    last_seen: BTreeMap<String, u32>,
+    restores_acknowledged: HashSet<String>,
+}
+
+impl SignCounterStore {
+    /// Check `presented` against the stored counter and remember it when it moved forward.
+    /// A pending restore acknowledgement is used up by the credential's next non-zero counter.
+    pub fn observe(
+        &mut self,
+        credential_id: &str,
+        presented: u32,
+    ) -> Result<SignCountCheck, SignCountError> {
+        if presented == 0 {
+            return Ok(SignCountCheck::ZeroCounter);
+        }
+        let restore_acknowledged = self.restores_acknowledged.remove(credential_id);
+        let stored = self.stored(credential_id).unwrap_or(0);
+        if presented < stored {
+            if !restore_acknowledged {
+                return Err(SignCountError::AuthenticatorCounterRegression {
+                    credential_id: credential_id.to_string(),
+                    stored,
+                    presented,
+                });
+            }
+            self.last_seen.insert(credential_id.to_string(), presented);
+            return Ok(SignCountCheck::RestoreAccepted);
+        }
+        if presented == stored {
+            return Ok(SignCountCheck::Unchanged);
+        }
+        self.last_seen.insert(credential_id.to_string(), presented);
+        Ok(SignCountCheck::Advanced)
+    }
+
+    /// Accept one lower counter for `credential_id` (the authenticator was deliberately restored).
+    pub fn acknowledge_restore(&mut self, credential_id: &str) {
+        self.restores_acknowledged.insert(credential_id.to_string());
+    }
+
+    pub fn stored(&self, credential_id: &str) -> Option<u32> {
+        self.last_seen.get(credential_id).copied()
+    }
+
+    pub fn snapshot(&self) -> SignCounterSnapshot {
+        SignCounterSnapshot {
+            counters: self.last_seen.clone(),
+        }
+    }
+
+    /// Fold a persisted snapshot in, keeping the higher counter per credential.
+    /// Returns how many counters were added or raised.
+    pub fn merge(&mut self, snapshot: SignCounterSnapshot) -> usize {
+        let mut changed = 0;
+        for (credential_id, counter) in snapshot.counters {
+            let stored = self.last_seen.entry(credential_id).or_insert(0);
+            if counter > *stored {
+                *stored = counter;
+                changed += 1;
+            }
+        }
+        changed
+    }
+}
+
+pub fn seal_sign_counter_snapshot(
+    snapshot: &SignCounterSnapshot,
+    wrap_key: &WrapKey,
+) -> Result<SealedSignCounterSnapshot, String> {
+    let key = wrap_key.derive_sign_counter_snapshot_key()?;
+    let bytes = borsh::to_vec(snapshot)
+        .map_err(|e| format!("Failed to serialize signCount snapshot: {}", e))?;
+    let sealed = encrypt_data_chacha20_with_aad(
+        &base64_url_encode(&bytes),
+        &key,
+        SIGN_COUNTER_SNAPSHOT_INFO,
+    )
+    .map_err(|e| format!("Failed to seal signCount snapshot: {}", e))?;
+    Ok(SealedSignCounterSnapshot {
+        version: SIGN_COUNTER_SNAPSHOT_VERSION,
+        ciphertext_b64u: sealed.encrypted_near_key_data_b64u,
+        nonce_b64u: sealed.chacha20_nonce_b64u,
+    })
+}
+
+/// Open a snapshot sealed by `seal_sign_counter_snapshot`; fails for another account's
+/// WrapKeySeed or a modified ciphertext.
+pub fn open_sign_counter_snapshot(
+    sealed: &SealedSignCounterSnapshot,
+    wrap_key: &WrapKey,
+) -> Result<SignCounterSnapshot, String> {
+    if sealed.version != SIGN_COUNTER_SNAPSHOT_VERSION {
+        return Err(format!(
+            "Unsupported signCount snapshot version {} (expected {})",
+            sealed.version, SIGN_COUNTER_SNAPSHOT_VERSION
+        ));
+    }
+    let key = wrap_key.derive_sign_counter_snapshot_key()?;
+    let bytes_b64u = decrypt_data_chacha20_with_aad(
+        &sealed.ciphertext_b64u,
+        &sealed.nonce_b64u,
+        &key,
+        SIGN_COUNTER_SNAPSHOT_INFO,
+    )
+    .map_err(|e| format!("Failed to open signCount snapshot: {}", e))?;
+    let bytes = base64_url_decode(&bytes_b64u)
+        .map_err(|e| format!("Invalid signCount snapshot encoding: {}", e))?;
+    borsh::from_slice(&bytes).map_err(|e| format!("Invalid signCount snapshot: {}", e))
+}
+
+thread_local! {
+    static SIGN_COUNTERS: RefCell<SignCounterStore> = RefCell::new(SignCounterStore::default());
+}
+
+/// Check the counter of an assertion presented for `near_account_id`. A regression is logged
+/// and recorded as an `AuthenticatorCounterRegression` state change before failing.
+pub(crate) fn check_assertion_sign_count(
+    credential: &WebAuthnAuthenticationCredential,
+    near_account_id: Option<&str>,
+) -> Result<SignCountCheck, String> {
+    let presented = credential.response.sign_count()?;
+    let check = SIGN_COUNTERS.with(|store| store.borrow_mut().observe(&credential.id, presented));
+    if let Err(err) = &check {
+        warn!("{}", err);
+        record_state_change(
+            StateChangeKind::AuthenticatorCounterRegression,
+            near_account_id,
+        );
+    }
+    check.map_err(String::from)
+}
+
+/// `check_assertion_sign_count` for the serialized credential a signing request carries.
+/// Requests without one (signing within an already confirmed session) are not checked.
+pub(crate) fn enforce_sign_count(
+    credential_json: Option<&str>,
+    near_account_id: &str,
+) -> Result<(), String> {
+    let Some(credential_json) = credential_json else {
+        return Ok(());
+    };
+    let cred_js = js_sys::JSON::parse(credential_json)
+        .map_err(|e| format!("Invalid credential JSON: {:?}", e))?;
+    let credential: WebAuthnAuthenticationCredential = serde_wasm_bindgen::from_value(cred_js)
+        .map_err(|e| format!("Invalid WebAuthn authentication credential: {}", e))?;
+    check_assertion_sign_count(&credential, Some(near_account_id)).map(|_| ())
+}
+
+/// Last non-zero counter seen for `credential_id` in this worker (tests).
+#[cfg(test)]
+pub(crate) fn stored_sign_count(credential_id: &str) -> Option<u32> {
+    SIGN_COUNTERS.with(|store| store.borrow().stored(credential_id))
+}
+
+/// Override for a deliberate credential restore: the next assertion of `credential_id` may
+/// present a lower `signCount` than stored, which then becomes the stored counter.
+#[wasm_bindgen]
+pub fn acknowledge_credential_restore(credential_id: String) {
+    SIGN_COUNTERS.with(|store| store.borrow_mut().acknowledge_restore(&credential_id));
+}
+
+/// Seal every stored counter under the WrapKeySeed of `session_id` for the TS shell to persist.
+/// Returns `{ version, ciphertextB64u, nonceB64u }`.
+#[wasm_bindgen]
+pub fn export_sign_counter_snapshot(session_id: String) -> Result<JsValue, JsValue> {
+    let wrap_key = crate::wrap_key_handshake::cached_wrap_key(&session_id).ok_or_else(|| {
+        JsValue::from_str(&format!("Missing WrapKeySeed for session {}", session_id))
+    })?;
+    let snapshot = SIGN_COUNTERS.with(|store| store.borrow().snapshot());
+    let sealed = seal_sign_counter_snapshot(&snapshot, &wrap_key)?;
+    serde_wasm_bindgen::to_value(&sealed)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize signCount snapshot: {}", e)))
+}
+
+/// Restore counters from a snapshot exported after an earlier worker start. Counters only
+/// move up, so replaying an old snapshot cannot hide a regression. Returns how many changed.
+#[wasm_bindgen]
+pub fn import_sign_counter_snapshot(session_id: String, sealed: JsValue) -> Result<u32, JsValue> {
+    let wrap_key = crate::wrap_key_handshake::cached_wrap_key(&session_id).ok_or_else(|| {
+        JsValue::from_str(&format!("Missing WrapKeySeed for session {}", session_id))
+    })?;
+    let sealed: SealedSignCounterSnapshot = serde_wasm_bindgen::from_value(sealed)
+        .map_err(|e| JsValue::from_str(&format!("Invalid signCount snapshot: {}", e)))?;
+    let snapshot = open_sign_counter_snapshot(&sealed, &wrap_key)?;
+    let changed = SIGN_COUNTERS.with(|store| store.borrow_mut().merge(snapshot));
+    Ok(changed as u32)
+}
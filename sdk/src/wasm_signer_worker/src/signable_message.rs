@@ -0,0 +1,170 @@
+//! Signable message construction (NEP-461 message discriminants).
+//!
+//! Every digest this worker signs is `sha256(prefix || borsh(payload))`, where `prefix` is
+//! the borsh-encoded `u32` discriminant for the message kind (transactions are currently
+//! unprefixed). All signing paths go through `compute_signable_digest`, so a protocol
+//! discriminant bump or a new message kind is a change to `SIGNABLE_MESSAGE_KINDS` only.
+
+use sha2::{Digest, Sha256};
+
+/// Lower bound for on-chain message discriminants (NEP-461): `2^30`.
+pub const MIN_ON_CHAIN_DISCRIMINANT: u32 = 1 << 30;
+
+/// Lower bound for off-chain message discriminants (NEP-461): `2^31`.
+pub const MIN_OFF_CHAIN_DISCRIMINANT: u32 = 1 << 31;
+
+/// NEP number for delegate actions (meta transactions).
+pub const NEP_366_DELEGATE_ACTION: u32 = 366;
+
+/// NEP number for off-chain message signing.
+pub const NEP_413_SIGN_MESSAGE: u32 = 413;
+
+/// Message type for contract-verification attestations. Kept above the NEP number range so
+/// it cannot collide with a future NEP off-chain discriminant.
+pub const VERIFICATION_ATTESTATION_MESSAGE_TYPE: u32 = 1 << 16;
+
+/// Message type for device-linking account cards, next to the attestation type.
+pub const ACCOUNT_CARD_MESSAGE_TYPE: u32 = VERIFICATION_ATTESTATION_MESSAGE_TYPE + 1;
+
+/// Delegate action discriminant (`2^30 + 366`).
+pub const DELEGATE_ACTION_DISCRIMINANT: u32 = MIN_ON_CHAIN_DISCRIMINANT + NEP_366_DELEGATE_ACTION;
+
+/// NEP-413 message discriminant (`2^31 + 413`).
+pub const NEP_413_DISCRIMINANT: u32 = MIN_OFF_CHAIN_DISCRIMINANT + NEP_413_SIGN_MESSAGE;
+
+/// Contract-verification attestation discriminant (`2^31 + 2^16`).
+pub const VERIFICATION_ATTESTATION_DISCRIMINANT: u32 =
+    MIN_OFF_CHAIN_DISCRIMINANT + VERIFICATION_ATTESTATION_MESSAGE_TYPE;
+
+/// Account card discriminant (`2^31 + 2^16 + 1`).
+pub const ACCOUNT_CARD_DISCRIMINANT: u32 = MIN_OFF_CHAIN_DISCRIMINANT + ACCOUNT_CARD_MESSAGE_TYPE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignableMessageKind {
+    /// `borsh(Transaction)`, unprefixed.
+    Transaction,
+    /// NEP-366 `DelegateAction` (meta transaction).
+    DelegateAction,
+    /// NEP-413 off-chain message.
+    Nep413,
+    /// Off-chain attestation that `verify_authentication_response` succeeded.
+    VerificationAttestation,
+    /// Device-linking account card shown by Device1 in its QR code.
+    AccountCard,
+}
+
+/// Per-kind signing metadata.
+pub struct SignableMessageSpec {
+    pub kind: SignableMessageKind,
+    /// Name used for the kind in JSON results (`signaturePreimages[].kind`).
+    pub name: &'static str,
+    /// Discriminant prepended to the payload; `None` for unprefixed kinds.
+    pub discriminant: Option<u32>,
+    /// `purpose` string used when authorizing a threshold signature with the relayer.
+    #[cfg_attr(not(feature = "threshold"), allow(dead_code))]
+    pub threshold_purpose: &'static str,
+}
+
+/// Known signable message kinds.
+pub const SIGNABLE_MESSAGE_KINDS: &[SignableMessageSpec] = &[
+    SignableMessageSpec {
+        kind: SignableMessageKind::Transaction,
+        name: "transaction",
+        discriminant: None,
+        threshold_purpose: "near_tx",
+    },
+    SignableMessageSpec {
+        kind: SignableMessageKind::DelegateAction,
+        name: "delegateAction",
+        discriminant: Some(DELEGATE_ACTION_DISCRIMINANT),
+        threshold_purpose: "nep461_delegate",
+    },
+    SignableMessageSpec {
+        kind: SignableMessageKind::Nep413,
+        name: "nep413",
+        discriminant: Some(NEP_413_DISCRIMINANT),
+        threshold_purpose: "nep413",
+    },
+    SignableMessageSpec {
+        kind: SignableMessageKind::VerificationAttestation,
+        name: "verificationAttestation",
+        discriminant: Some(VERIFICATION_ATTESTATION_DISCRIMINANT),
+        threshold_purpose: "verification_attestation",
+    },
+    SignableMessageSpec {
+        kind: SignableMessageKind::AccountCard,
+        name: "accountCard",
+        discriminant: Some(ACCOUNT_CARD_DISCRIMINANT),
+        threshold_purpose: "account_card",
+    },
+];
+
+impl SignableMessageKind {
+    pub fn spec(self) -> &'static SignableMessageSpec {
+        SIGNABLE_MESSAGE_KINDS
+            .iter()
+            .find(|s| s.kind == self)
+            .expect("every SignableMessageKind has an entry in SIGNABLE_MESSAGE_KINDS")
+    }
+
+    pub fn name(self) -> &'static str {
+        self.spec().name
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        SIGNABLE_MESSAGE_KINDS
+            .iter()
+            .find(|s| s.name == name.trim())
+            .map(|s| s.kind)
+    }
+
+    pub fn discriminant(self) -> Option<u32> {
+        self.spec().discriminant
+    }
+
+    #[cfg_attr(not(feature = "threshold"), allow(dead_code))]
+    pub fn threshold_purpose(self) -> &'static str {
+        self.spec().threshold_purpose
+    }
+
+    #[cfg_attr(not(feature = "threshold"), allow(dead_code))]
+    pub fn from_threshold_purpose(purpose: &str) -> Option<Self> {
+        let purpose = purpose.trim();
+        SIGNABLE_MESSAGE_KINDS
+            .iter()
+            .find(|s| s.threshold_purpose == purpose)
+            .map(|s| s.kind)
+    }
+}
+
+/// `prefix || payload_borsh`, with the discriminant borsh-encoded (u32 little-endian).
+pub fn signable_message_bytes(kind: SignableMessageKind, payload_borsh: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(4 + payload_borsh.len());
+    if let Some(discriminant) = kind.discriminant() {
+        out.extend_from_slice(&discriminant.to_le_bytes());
+    }
+    out.extend_from_slice(payload_borsh);
+    out
+}
+
+/// `sha256(prefix || payload_borsh)` — the 32-byte digest that gets ed25519-signed.
+pub fn compute_signable_digest(kind: SignableMessageKind, payload_borsh: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    if let Some(discriminant) = kind.discriminant() {
+        hasher.update(discriminant.to_le_bytes());
+    }
+    hasher.update(payload_borsh);
+    hasher.finalize().into()
+}
+
+/// Threshold signing only signs digests of a known message kind; an unknown `purpose`
+/// would let a caller obtain a relayer co-signature over an arbitrary 32-byte digest.
+#[cfg_attr(not(feature = "threshold"), allow(dead_code))]
+pub fn ensure_known_threshold_purpose(purpose: &str) -> Result<SignableMessageKind, String> {
+    SignableMessageKind::from_threshold_purpose(purpose).ok_or_else(|| {
+        format!(
+            "threshold-signer: refusing to sign digest of unknown kind '{}'",
+            purpose.trim()
+        )
+    })
+}
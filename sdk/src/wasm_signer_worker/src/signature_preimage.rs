@@ -0,0 +1,151 @@
+//! Opt-in signature preimages for external audit (`includeSignaturePreimage: true`).
+//!
+//! Auditors re-checking a signature need the exact bytes it covers. With the flag set, each
+//! result entry carries `signaturePreimageB64u` (the pre-hash bytes: borsh `Transaction`, or
+//! `prefix || borsh(payload)` for NEP-366 / NEP-413) and `signatureDigestB64u` (the sha256 the
+//! ed25519 signer was fed), built from the buffers that were signed rather than re-serialized.
+//!
+//! Threshold signatures are produced over the digest the relayer co-signs, so their preimage
+//! is that digest (`preimageFormat: "digest"`) tagged with the signable-message kind.
+//!
+//! Preimages can be large (DeployContract code), so they are only attached to results: they
+//! never enter `logs` or progress messages, and `scrub_error_message` redacts them.
+
+use ed25519_dalek::Verifier;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use wasm_bindgen::prelude::*;
+
+use crate::encoders::{base64_standard_decode, base64_url_decode_lenient, base64_url_encode};
+use crate::signable_message::{signable_message_bytes, SignableMessageKind};
+use crate::threshold::signer_backend::Ed25519SignerBackend;
+
+/// `kind` accepted by `verify_signature_over_preimage` for `preimageFormat: "digest"` entries.
+pub const DIGEST_PREIMAGE_KIND: &str = "digest";
+
+/// What `signaturePreimageB64u` holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SignaturePreimageFormat {
+    /// The full pre-hash message; the signature covers `sha256(preimage)`.
+    Message,
+    /// The 32-byte digest itself (threshold mode); the signature covers it directly.
+    Digest,
+}
+
+/// Bytes covered by one signature, attached per result entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignaturePreimage {
+    /// Signable message kind (`transaction`, `delegateAction`, `nep413`).
+    pub kind: String,
+    pub preimage_format: SignaturePreimageFormat,
+    pub signature_preimage_b64u: String,
+    /// Digest fed to the signer; absent when the preimage already is the digest.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature_digest_b64u: Option<String>,
+}
+
+impl SignaturePreimage {
+    /// Local signing: `payload_borsh` is the buffer `digest` was computed from.
+    pub fn message(kind: SignableMessageKind, payload_borsh: &[u8], digest: &[u8; 32]) -> Self {
+        SignaturePreimage {
+            kind: kind.name().to_string(),
+            preimage_format: SignaturePreimageFormat::Message,
+            signature_preimage_b64u: base64_url_encode(&signable_message_bytes(
+                kind,
+                payload_borsh,
+            )),
+            signature_digest_b64u: Some(base64_url_encode(digest)),
+        }
+    }
+
+    /// Threshold signing: the digest the relayer co-signed, tagged with its kind.
+    pub fn digest(kind: SignableMessageKind, digest: &[u8; 32]) -> Self {
+        SignaturePreimage {
+            kind: kind.name().to_string(),
+            preimage_format: SignaturePreimageFormat::Digest,
+            signature_preimage_b64u: base64_url_encode(digest),
+            signature_digest_b64u: None,
+        }
+    }
+
+    /// The preimage matching how `signer` produces its signatures.
+    pub fn for_signer(
+        signer: &Ed25519SignerBackend,
+        kind: SignableMessageKind,
+        payload_borsh: &[u8],
+        digest: &[u8; 32],
+    ) -> Self {
+        if signer.is_threshold() {
+            Self::digest(kind, digest)
+        } else {
+            Self::message(kind, payload_borsh, digest)
+        }
+    }
+}
+
+/// Recompute the signed digest from `preimage` and check `signature` under `public_key`.
+///
+/// `kind` is a signable-message kind name for `message` preimages (its discriminant prefix
+/// must match), or `"digest"` for threshold preimages. `Ok(false)` means the signature does
+/// not verify; malformed input is an error.
+pub fn verify_signature_over_preimage_inner(
+    public_key: &[u8; 32],
+    preimage: &[u8],
+    kind: &str,
+    signature: &[u8; 64],
+) -> Result<bool, String> {
+    let digest: [u8; 32] = if kind.trim() == DIGEST_PREIMAGE_KIND {
+        preimage
+            .try_into()
+            .map_err(|_| "digest preimage must be 32 bytes".to_string())?
+    } else {
+        let kind = SignableMessageKind::from_name(kind)
+            .ok_or_else(|| format!("Unknown signable message kind '{}'", kind.trim()))?;
+        if let Some(discriminant) = kind.discriminant() {
+            if !preimage.starts_with(&discriminant.to_le_bytes()) {
+                return Err(format!(
+                    "preimage does not start with the {} discriminant",
+                    kind.name()
+                ));
+            }
+        }
+        Sha256::digest(preimage).into()
+    };
+
+    let verifying_key = ed25519_dalek::VerifyingKey::from_bytes(public_key)
+        .map_err(|e| format!("Invalid publicKey: {}", e))?;
+    Ok(verifying_key
+        .verify(&digest, &ed25519_dalek::Signature::from_bytes(signature))
+        .is_ok())
+}
+
+/// Standalone check for `signaturePreimages` entries returned when `includeSignaturePreimage`
+/// is requested. `publicKey` is `ed25519:<base58>` (prefix optional), `preimageB64u` is
+/// `signaturePreimageB64u`, `kind` is the entry's `kind` (or `"digest"` when its
+/// `preimageFormat` is `digest`) and `signatureB64` is the ed25519 signature in base64.
+#[wasm_bindgen]
+pub fn verify_signature_over_preimage(
+    public_key: &str,
+    preimage_b64u: &str,
+    kind: &str,
+    signature_b64: &str,
+) -> Result<bool, JsValue> {
+    let public_key_b58 = public_key.strip_prefix("ed25519:").unwrap_or(public_key);
+    let public_key: [u8; 32] = bs58::decode(public_key_b58)
+        .into_vec()
+        .map_err(|e| JsValue::from_str(&format!("Invalid publicKey base58: {}", e)))?
+        .try_into()
+        .map_err(|_| JsValue::from_str("publicKey must be 32 bytes"))?;
+    let preimage = base64_url_decode_lenient(preimage_b64u)
+        .map_err(|e| JsValue::from_str(&format!("Invalid preimage base64url: {}", e)))?;
+    let signature: [u8; 64] = base64_standard_decode(signature_b64)
+        .or_else(|_| base64_url_decode_lenient(signature_b64))
+        .map_err(|e| JsValue::from_str(&format!("Invalid signature base64: {}", e)))?
+        .try_into()
+        .map_err(|_| JsValue::from_str("signature must be 64 bytes"))?;
+
+    verify_signature_over_preimage_inner(&public_key, &preimage, kind, &signature)
+        .map_err(|e| JsValue::from_str(&e))
+}
@@ -0,0 +1,172 @@
+//! Monotonic state version and a bounded ring of recent state changes for this worker.
+//!
+//! The TS shell polls `GET_STATE_VERSION` (or reads `stateVersion` off `signing_session_status`)
+//! and, when it moved, asks `GET_STATE_CHANGES_SINCE` for the events in between instead of
+//! re-reading every session. Mirrors the VRF worker's log; event kinds share numeric values.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+use serde::{Serialize, Serializer};
+use wasm_bindgen::prelude::*;
+
+use crate::config::STATE_CHANGE_LOG_CAPACITY;
+use crate::session_key_cache::now_ms;
+
+/// What changed. Values are stable and identical to the VRF worker's `StateChangeKind`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateChangeKind {
+    /// A VRF keypair became active (VRF worker)
+    Unlock = 1,
+    /// Sessions and caches were cleared (LOGOUT_ALL / CLEAR_ALL_SESSIONS; VRF worker: CLEAR_VRF)
+    Logout = 2,
+    /// WrapKeySeed material was delivered for a session
+    SessionCreated = 3,
+    /// A session was dropped because its TTL passed (VRF worker)
+    SessionExpired = 4,
+    /// A signing operation was charged to a session's key reuse budget
+    SessionConsumed = 5,
+    /// A session's cached material was cleared
+    SessionCleared = 6,
+    /// A session's expiry was extended (VRF worker)
+    SessionRenewed = 7,
+    /// A reused signing key was zeroized because its reuse TTL passed
+    AutoLock = 8,
+    /// A relayer threshold auth session was cached or dropped
+    ThresholdSessionChanged = 9,
+    /// An assertion's `signCount` went backwards for a credential (possible authenticator clone)
+    AuthenticatorCounterRegression = 10,
+}
+
+impl Serialize for StateChangeKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(*self as u32)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateChangeEvent {
+    /// State version right after this change.
+    pub version: u64,
+    pub kind: StateChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<String>,
+    pub at_ms: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateChangesSince {
+    /// Current state version.
+    pub version: u64,
+    /// Retained changes newer than the requested version, oldest first.
+    pub events: Vec<StateChangeEvent>,
+    /// Some changes after the requested version are no longer retained (or the version is
+    /// from another worker instance): re-read the full status instead of replaying `events`.
+    pub resync_required: bool,
+}
+
+pub struct StateChangeLog {
+    version: u64,
+    events: VecDeque<StateChangeEvent>,
+    capacity: usize,
+}
+
+impl Default for StateChangeLog {
+    fn default() -> Self {
+        Self::with_capacity(STATE_CHANGE_LOG_CAPACITY)
+    }
+}
+
+impl StateChangeLog {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            version: 0,
+            events: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Bump the version and remember the change, evicting the oldest one when full.
+    pub fn record(&mut self, kind: StateChangeKind, account_id: Option<&str>, at_ms: f64) -> u64 {
+        self.version += 1;
+        if self.capacity > 0 {
+            if self.events.len() == self.capacity {
+                self.events.pop_front();
+            }
+            self.events.push_back(StateChangeEvent {
+                version: self.version,
+                kind,
+                account_id: account_id.map(str::to_string),
+                at_ms,
+            });
+        }
+        self.version
+    }
+
+    pub fn since(&self, version: u64) -> StateChangesSince {
+        if version > self.version {
+            return StateChangesSince {
+                version: self.version,
+                events: Vec::new(),
+                resync_required: true,
+            };
+        }
+        let oldest_retained = self
+            .events
+            .front()
+            .map(|e| e.version)
+            .unwrap_or(self.version + 1);
+        StateChangesSince {
+            version: self.version,
+            events: self
+                .events
+                .iter()
+                .filter(|e| e.version > version)
+                .cloned()
+                .collect(),
+            resync_required: version < self.version && oldest_retained > version + 1,
+        }
+    }
+}
+
+thread_local! {
+    static STATE_CHANGES: RefCell<StateChangeLog> = RefCell::new(StateChangeLog::default());
+    static LOGOUT_EPOCH: Cell<u64> = const { Cell::new(0) };
+}
+
+pub(crate) fn record_state_change(kind: StateChangeKind, account_id: Option<&str>) {
+    STATE_CHANGES.with(|log| {
+        log.borrow_mut().record(kind, account_id, now_ms() as f64);
+    });
+}
+
+pub(crate) fn state_version() -> u64 {
+    STATE_CHANGES.with(|log| log.borrow().version())
+}
+
+pub(crate) fn state_changes_since(version: u64) -> StateChangesSince {
+    STATE_CHANGES.with(|log| log.borrow().since(version))
+}
+
+/// Epoch of the last completed `CLEAR_ALL_SESSIONS` (0 before any). Reported next to the VRF
+/// worker's `logoutEpoch` so the TS shell can confirm both sides finished the same logout.
+pub(crate) fn logout_epoch() -> u64 {
+    LOGOUT_EPOCH.with(Cell::get)
+}
+
+/// Move to the logout epoch the TS shell chose for this logout, or to the next one when it
+/// did not pick one.
+pub(crate) fn advance_logout_epoch(requested: Option<u64>) -> u64 {
+    LOGOUT_EPOCH.with(|epoch| {
+        let next = requested.unwrap_or_else(|| epoch.get() + 1);
+        epoch.set(next);
+        next
+    })
+}
@@ -0,0 +1,149 @@
+use ed25519_dalek::{Signer, SigningKey};
+
+use crate::account_card::{
+    account_card_digest, encode_account_card, verify_account_card_inner, AccountCard,
+};
+use crate::config::ACCOUNT_CARD_TTL_MS;
+use crate::encoders::{base64_url_decode, base64_url_encode};
+use crate::error::AccountCardError;
+
+const ISSUED_AT_MS: u64 = 1_700_000_000_000;
+
+fn signing_key() -> SigningKey {
+    SigningKey::from_bytes(&[0x24u8; 32])
+}
+
+fn card() -> AccountCard {
+    AccountCard::new(
+        "alice.testnet",
+        &signing_key().verifying_key().to_bytes(),
+        &[0x07u8; 32],
+        2,
+        ISSUED_AT_MS,
+    )
+    .unwrap()
+}
+
+fn sign(card: &AccountCard) -> String {
+    let signature = signing_key().sign(&account_card_digest(card).unwrap());
+    encode_account_card(card, &signature.to_bytes()).unwrap()
+}
+
+#[test]
+fn account_card_round_trips_and_verifies() {
+    let card = card();
+    assert_eq!(card.expires_at_ms, ISSUED_AT_MS + ACCOUNT_CARD_TTL_MS);
+
+    let blob = sign(&card);
+    assert!(blob
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+
+    let parsed = verify_account_card_inner(&blob, ISSUED_AT_MS + 1_000).unwrap();
+    assert_eq!(parsed, card);
+
+    let json = serde_json::to_value(&parsed).unwrap();
+    assert_eq!(json["nearAccountId"], "alice.testnet");
+    assert_eq!(
+        json["vrfPublicKeyRawB64u"],
+        base64_url_encode(&[0x07u8; 32])
+    );
+    assert_eq!(json["deviceNumber"], 2);
+    assert_eq!(json["expiresAtMs"], ISSUED_AT_MS + ACCOUNT_CARD_TTL_MS);
+}
+
+#[test]
+fn account_card_rejects_expired_and_overlong_cards() {
+    let blob = sign(&card());
+    let expires_at_ms = ISSUED_AT_MS + ACCOUNT_CARD_TTL_MS;
+    assert_eq!(
+        verify_account_card_inner(&blob, expires_at_ms).unwrap_err(),
+        AccountCardError::Expired {
+            expires_at_ms,
+            now_ms: expires_at_ms,
+        }
+    );
+    assert!(matches!(
+        verify_account_card_inner(&blob, 0).unwrap_err(),
+        AccountCardError::NotYetValid { .. }
+    ));
+
+    // A validly signed card cannot extend its own lifetime past the TTL.
+    let mut long_lived = card();
+    long_lived.expires_at_ms += 1;
+    assert_eq!(
+        verify_account_card_inner(&sign(&long_lived), ISSUED_AT_MS).unwrap_err(),
+        AccountCardError::LifetimeTooLong {
+            lifetime_ms: ACCOUNT_CARD_TTL_MS + 1,
+            max_ms: ACCOUNT_CARD_TTL_MS,
+        }
+    );
+}
+
+#[test]
+fn account_card_rejects_tampering() {
+    let card = card();
+    let blob = sign(&card);
+    let bytes = base64_url_decode(&blob).unwrap();
+
+    // Flip one bit in every byte position of the payload and of the signature.
+    for i in 1..bytes.len() {
+        let mut tampered = bytes.clone();
+        tampered[i] ^= 0x01;
+        let err = verify_account_card_inner(&base64_url_encode(&tampered), ISSUED_AT_MS)
+            .expect_err("tampered card must not verify");
+        assert!(
+            matches!(
+                err,
+                AccountCardError::BadSignature
+                    | AccountCardError::Malformed(_)
+                    | AccountCardError::InvalidField { .. }
+            ),
+            "byte {i}: {err}"
+        );
+    }
+
+    // A card re-signed by another key does not verify under the embedded key.
+    let other_key = SigningKey::from_bytes(&[0x25u8; 32]);
+    let forged = other_key.sign(&account_card_digest(&card).unwrap());
+    assert_eq!(
+        verify_account_card_inner(
+            &encode_account_card(&card, &forged.to_bytes()).unwrap(),
+            ISSUED_AT_MS
+        )
+        .unwrap_err(),
+        AccountCardError::BadSignature
+    );
+
+    let mut wrong_version = bytes.clone();
+    wrong_version[0] = 2;
+    assert_eq!(
+        verify_account_card_inner(&base64_url_encode(&wrong_version), ISSUED_AT_MS).unwrap_err(),
+        AccountCardError::UnsupportedVersion(2)
+    );
+    assert!(matches!(
+        verify_account_card_inner(&base64_url_encode(&bytes[..64]), ISSUED_AT_MS).unwrap_err(),
+        AccountCardError::Malformed(_)
+    ));
+}
+
+#[test]
+fn account_card_digest_is_domain_separated() {
+    use crate::signable_message::{compute_signable_digest, SignableMessageKind};
+
+    let card = card();
+    let digest = account_card_digest(&card).unwrap();
+    let bytes = base64_url_decode(&sign(&card)).unwrap();
+    let payload = &bytes[..bytes.len() - 64];
+    assert_eq!(
+        digest,
+        compute_signable_digest(SignableMessageKind::AccountCard, payload)
+    );
+    for kind in [
+        SignableMessageKind::Transaction,
+        SignableMessageKind::Nep413,
+        SignableMessageKind::VerificationAttestation,
+    ] {
+        assert_ne!(digest, compute_signable_digest(kind, payload));
+    }
+}
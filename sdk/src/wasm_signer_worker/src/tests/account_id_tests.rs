@@ -0,0 +1,56 @@
+use crate::error::AccountIdError;
+use crate::handlers::handle_derive_near_keypair_and_encrypt::implicit_account_id_from_public_key;
+use crate::types::near::is_implicit_account_id;
+use crate::types::AccountId;
+
+const IMPLICIT: &str = "f3a1000000000000000000000000000000000000000000000000000000009c2e";
+
+#[test]
+fn implicit_account_id_requires_64_lowercase_hex() {
+    assert!(is_implicit_account_id(IMPLICIT));
+    assert!(!is_implicit_account_id(&IMPLICIT[..63]));
+    assert!(!is_implicit_account_id(&IMPLICIT.to_uppercase()));
+    assert!(!is_implicit_account_id("alice.near"));
+
+    assert!(AccountId::new(IMPLICIT.to_string()).unwrap().is_implicit());
+    assert!(!AccountId::new("alice.near".to_string()).unwrap().is_implicit());
+    let err = AccountId::new(IMPLICIT.to_uppercase()).unwrap_err();
+    assert!(err.contains("lowercase"));
+}
+
+#[test]
+fn display_short_truncates_only_implicit_ids() {
+    let implicit: AccountId = IMPLICIT.parse().unwrap();
+    assert_eq!(implicit.display_short(), "f3a1…9c2e");
+    // The full value stays on the AccountId itself
+    assert_eq!(implicit.0, IMPLICIT);
+
+    let named: AccountId = "alice.near".parse().unwrap();
+    assert_eq!(named.display_short(), "alice.near");
+}
+
+#[test]
+fn implicit_account_id_is_hex_of_derived_public_key() {
+    let public_key_bytes = [0xabu8; 32];
+    let public_key = format!("ed25519:{}", bs58::encode(public_key_bytes).into_string());
+
+    let account_id = implicit_account_id_from_public_key(&public_key).unwrap();
+    assert_eq!(account_id, "ab".repeat(32));
+    assert_eq!(
+        account_id,
+        AccountId::implicit_from_public_key(&public_key_bytes).0
+    );
+
+    let bad = format!("ed25519:{}", bs58::encode([1u8; 16]).into_string());
+    assert!(implicit_account_id_from_public_key(&bad).is_err());
+}
+
+#[test]
+fn named_account_required_error_is_distinguishable() {
+    let msg: String = AccountIdError::NamedAccountRequired {
+        account_id: IMPLICIT.to_string(),
+    }
+    .into();
+    assert!(msg.starts_with("NamedAccountRequired:"));
+    assert!(msg.contains(IMPLICIT));
+}
@@ -160,6 +160,7 @@ fn test_get_action_handler_new_types() {
 
     let deploy_params = ActionParams::DeployContract {
         code: vec![0, 97, 115, 109],
+        code_attachment: None,
     }; // minimal wasm magic start
     assert!(deploy_params.to_action().is_ok());
 
@@ -186,6 +187,7 @@ fn test_get_action_handler_new_types() {
 fn test_deploy_contract_action_handler() {
     let params = ActionParams::DeployContract {
         code: vec![0, 97, 115, 109, 1, 0, 0, 0],
+        code_attachment: None,
     }; // "\0asm\1\0\0\0"
     assert!(params.validate().is_ok());
     let action = params.to_action().unwrap();
@@ -300,6 +302,73 @@ fn test_use_global_contract_validation_errors() {
     assert!(params.validate().is_err());
 }
 
+#[test]
+fn test_global_contract_validation_rejects_bad_hash_and_mode() {
+    // code_hash must decode to exactly 32 bytes
+    let short_hash = bs58::encode([1u8; 31]).into_string();
+    let params = ActionParams::UseGlobalContract {
+        account_id: None,
+        code_hash: Some(short_hash),
+    };
+    let err = params.validate().unwrap_err();
+    assert!(err.contains("code_hash must be 32 bytes"), "{}", err);
+
+    // deploy_mode must be one of the protocol enum variants
+    let params = ActionParams::DeployGlobalContract {
+        code: vec![0, 97, 115, 109],
+        deploy_mode: "Hash".to_string(),
+    };
+    let err = params.validate().unwrap_err();
+    assert!(err.contains("Invalid deploy_mode"), "{}", err);
+}
+
+#[test]
+fn test_global_contract_actions_borsh_known_encodings() {
+    // nearcore `Action` enum: DeployGlobalContract = 9, UseGlobalContract = 10.
+    // GlobalContractDeployMode / GlobalContractIdentifier: CodeHash = 0, AccountId = 1.
+    let deploy = ActionParams::DeployGlobalContract {
+        code: vec![0, 97, 115, 109],
+        deploy_mode: "AccountId".to_string(),
+    }
+    .to_action()
+    .unwrap();
+    let deploy_bytes = borsh::to_vec(&deploy).unwrap();
+    assert_eq!(deploy_bytes, vec![9, 4, 0, 0, 0, 0, 97, 115, 109, 1]);
+
+    let use_by_hash = ActionParams::UseGlobalContract {
+        account_id: None,
+        code_hash: Some(bs58::encode([7u8; 32]).into_string()),
+    }
+    .to_action()
+    .unwrap();
+    let mut expected = vec![10, 0];
+    expected.extend_from_slice(&[7u8; 32]);
+    let use_bytes = borsh::to_vec(&use_by_hash).unwrap();
+    assert_eq!(use_bytes, expected);
+
+    let use_by_account = ActionParams::UseGlobalContract {
+        account_id: Some("g.near".to_string()),
+        code_hash: None,
+    }
+    .to_action()
+    .unwrap();
+    let account_bytes = borsh::to_vec(&use_by_account).unwrap();
+    assert_eq!(
+        account_bytes,
+        vec![10, 1, 6, 0, 0, 0, b'g', b'.', b'n', b'e', b'a', b'r']
+    );
+
+    // Round-trip back through borsh
+    for (action, bytes) in [
+        (deploy, deploy_bytes),
+        (use_by_hash, use_bytes),
+        (use_by_account, account_bytes),
+    ] {
+        let decoded: NearAction = borsh::from_slice(&bytes).unwrap();
+        assert_eq!(decoded, action);
+    }
+}
+
 // ===== AMOUNT PARSING TESTS =====
 // These tests demonstrate the specific issue with amount parsing
 
@@ -436,3 +505,91 @@ fn test_amount_parsing_threshold_demonstration() {
 
     println!("\nCONCLUSION: The handler expects yoctoNEAR integers, not decimal NEAR amounts");
 }
+
+fn function_call(method_name: &str) -> ActionParams {
+    ActionParams::FunctionCall {
+        method_name: method_name.to_string(),
+        args: "{}".to_string(),
+        gas: "30000000000000".to_string(),
+        deposit: "1".to_string(),
+    }
+}
+
+#[test]
+fn test_allowed_methods_permits_listed_methods() {
+    let allowed = vec!["ft_transfer".to_string(), "storage_deposit".to_string()];
+    let actions = vec![
+        function_call("storage_deposit"),
+        ActionParams::Transfer {
+            deposit: "1".to_string(),
+        },
+        function_call("ft_transfer"),
+    ];
+    assert!(enforce_allowed_methods(&actions, Some(&allowed)).is_ok());
+}
+
+#[test]
+fn test_allowed_methods_rejects_unlisted_method() {
+    let allowed = vec!["ft_transfer".to_string()];
+    let actions = vec![function_call("ft_transfer"), function_call("add_full_access_key")];
+    assert_eq!(
+        enforce_allowed_methods(&actions, Some(&allowed)).unwrap_err(),
+        "method 'add_full_access_key' not permitted by policy"
+    );
+}
+
+#[test]
+fn test_allowed_methods_absent_or_empty_is_unrestricted() {
+    let actions = vec![function_call("anything_goes")];
+    assert!(enforce_allowed_methods(&actions, None).is_ok());
+    assert!(enforce_allowed_methods(&actions, Some(&[])).is_ok());
+}
+
+fn global_contract_actions() -> Vec<ActionParams> {
+    vec![
+        ActionParams::DeployGlobalContract {
+            code: vec![0, 97, 115, 109],
+            deploy_mode: "CodeHash".to_string(),
+        },
+        ActionParams::UseGlobalContract {
+            account_id: Some("g.near".to_string()),
+            code_hash: None,
+        },
+    ]
+}
+
+#[test]
+fn test_allowed_action_kinds_matches_global_contract_actions() {
+    let actions = global_contract_actions();
+    assert_eq!(actions[0].kind(), "DeployGlobalContract");
+    assert_eq!(actions[1].kind(), "UseGlobalContract");
+
+    let allowed = vec![
+        "DeployGlobalContract".to_string(),
+        "UseGlobalContract".to_string(),
+    ];
+    assert!(enforce_allowed_action_kinds(&actions, Some(&allowed)).is_ok());
+
+    let function_calls_only = vec!["FunctionCall".to_string()];
+    assert_eq!(
+        enforce_allowed_action_kinds(&actions, Some(&function_calls_only)).unwrap_err(),
+        "action 'DeployGlobalContract' not permitted by policy"
+    );
+    assert!(enforce_allowed_action_kinds(&actions, None).is_ok());
+    assert!(enforce_allowed_action_kinds(&actions, Some(&[])).is_ok());
+}
+
+#[test]
+fn test_action_risk_annotations() {
+    let actions = global_contract_actions();
+    assert_eq!(actions[0].risk(), ActionRisk::High);
+    assert_eq!(actions[1].risk(), ActionRisk::High);
+    assert_eq!(function_call("ft_transfer").risk(), ActionRisk::Medium);
+    assert_eq!(
+        ActionParams::Transfer {
+            deposit: "1".to_string()
+        }
+        .risk(),
+        ActionRisk::Low
+    );
+}
@@ -0,0 +1,165 @@
+use crate::actions::ActionParams;
+use crate::balance_check::{batch_cost, ensure_affordable};
+use crate::config::{BALANCE_CHECK_GAS_PER_ACTION, BALANCE_CHECK_GAS_PRICE_YOCTO};
+use crate::error::{BalanceCheckError, NearRpcError};
+use crate::rpc_calls::{AccountView, JsonRpcResponse};
+
+const ONE_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+
+fn parse_account(json: &str) -> Result<AccountView, NearRpcError> {
+    serde_json::from_str::<JsonRpcResponse<AccountView>>(json)
+        .expect("fixture should deserialize")
+        .into_result()
+}
+
+// 5 NEAR liquid, nothing staked, 100 kB of state (1 NEAR of storage staking).
+const VIEW_ACCOUNT_PLAIN: &str = r#"{
+  "jsonrpc": "2.0",
+  "id": "tatchi-signer",
+  "result": {
+    "amount": "5000000000000000000000000",
+    "locked": "0",
+    "code_hash": "11111111111111111111111111111111",
+    "storage_usage": 100000,
+    "storage_paid_at": 0,
+    "block_height": 17795474,
+    "block_hash": "9MjpcnwW3TSdzGweNfPbkx8M74q1XzUcT1PAN8G5bNDz"
+  }
+}"#;
+
+// A validator-style account: 2 NEAR liquid, 30 NEAR staked, which also covers its storage.
+const VIEW_ACCOUNT_LOCKED: &str = r#"{
+  "jsonrpc": "2.0",
+  "id": "tatchi-signer",
+  "result": {
+    "amount": "2000000000000000000000000",
+    "locked": "30000000000000000000000000",
+    "code_hash": "E8jZ1giWcVrps8PcV75ATauu6gFRkcwjNtKp7NKmipZG",
+    "storage_usage": 300000,
+    "storage_paid_at": 0,
+    "block_height": 17795474,
+    "block_hash": "9MjpcnwW3TSdzGweNfPbkx8M74q1XzUcT1PAN8G5bNDz"
+  }
+}"#;
+
+// Staked less than the 3 NEAR of storage it owes: the remainder comes out of `amount`.
+const VIEW_ACCOUNT_PARTIALLY_LOCKED: &str = r#"{
+  "jsonrpc": "2.0",
+  "id": "tatchi-signer",
+  "result": {
+    "amount": "4000000000000000000000000",
+    "locked": "1000000000000000000000000",
+    "code_hash": "11111111111111111111111111111111",
+    "storage_usage": 300000,
+    "block_height": 17795474
+  }
+}"#;
+
+const VIEW_ACCOUNT_UNKNOWN: &str = r#"{
+  "jsonrpc": "2.0",
+  "id": "tatchi-signer",
+  "error": {
+    "name": "HANDLER_ERROR",
+    "cause": { "name": "UNKNOWN_ACCOUNT", "info": { "requested_account_id": "ghost.testnet" } },
+    "message": "Server error"
+  }
+}"#;
+
+fn transfer(yocto: u128) -> ActionParams {
+    ActionParams::Transfer {
+        deposit: yocto.to_string(),
+    }
+}
+
+fn per_action_gas_cost() -> u128 {
+    u128::from(BALANCE_CHECK_GAS_PER_ACTION) * BALANCE_CHECK_GAS_PRICE_YOCTO
+}
+
+#[test]
+fn parses_view_account_balances_as_u128() {
+    let account = parse_account(VIEW_ACCOUNT_PLAIN).unwrap();
+    assert_eq!(account.amount, 5 * ONE_NEAR);
+    assert_eq!(account.locked, 0);
+    assert_eq!(account.storage_usage, 100_000);
+    assert_eq!(account.block_height, Some(17_795_474));
+    assert_eq!(account.available_balance(), 4 * ONE_NEAR);
+}
+
+#[test]
+fn staked_balance_covers_storage_first() {
+    let locked = parse_account(VIEW_ACCOUNT_LOCKED).unwrap();
+    assert_eq!(locked.available_balance(), 2 * ONE_NEAR);
+
+    let partial = parse_account(VIEW_ACCOUNT_PARTIALLY_LOCKED).unwrap();
+    assert_eq!(partial.available_balance(), 2 * ONE_NEAR);
+}
+
+#[test]
+fn unknown_account_surfaces_as_rpc_error() {
+    let err = parse_account(VIEW_ACCOUNT_UNKNOWN).unwrap_err();
+    assert!(matches!(
+        &err,
+        NearRpcError::Rpc { cause: Some(cause), .. } if cause == "UNKNOWN_ACCOUNT"
+    ));
+    assert!(BalanceCheckError::from(err).is_unavailable());
+}
+
+#[test]
+fn batch_cost_sums_deposits_transfers_and_gas() {
+    let call = ActionParams::FunctionCall {
+        method_name: "ft_transfer".to_string(),
+        args: "{}".to_string(),
+        gas: "30000000000000".to_string(),
+        deposit: "1".to_string(),
+    };
+    let txs = [vec![transfer(ONE_NEAR)], vec![call, transfer(2)]];
+    let cost = batch_cost(txs.iter().map(Vec::as_slice)).unwrap();
+    let attached_gas_cost = 30_000_000_000_000u128 * BALANCE_CHECK_GAS_PRICE_YOCTO;
+    assert_eq!(
+        cost,
+        ONE_NEAR + 1 + 2 + attached_gas_cost + 3 * per_action_gas_cost()
+    );
+}
+
+#[test]
+fn batch_cost_rejects_unbuildable_actions_and_overflow() {
+    let txs = [
+        vec![transfer(1)],
+        vec![ActionParams::DeployContract {
+            code: vec![],
+            code_attachment: None,
+        }],
+    ];
+    assert!(matches!(
+        batch_cost(txs.iter().map(Vec::as_slice)),
+        Err(BalanceCheckError::InvalidActions { index: 1, .. })
+    ));
+
+    let txs = [vec![transfer(u128::MAX), transfer(u128::MAX)]];
+    assert_eq!(
+        batch_cost(txs.iter().map(Vec::as_slice)),
+        Err(BalanceCheckError::AmountOverflow)
+    );
+}
+
+#[test]
+fn insufficient_balance_reports_required_and_available() {
+    let account = parse_account(VIEW_ACCOUNT_PLAIN).unwrap();
+    let txs = [vec![transfer(4 * ONE_NEAR)]];
+    let required = batch_cost(txs.iter().map(Vec::as_slice)).unwrap();
+
+    let err = ensure_affordable(&account, required).unwrap_err();
+    assert_eq!(
+        err,
+        BalanceCheckError::InsufficientBalance {
+            required,
+            available: 4 * ONE_NEAR,
+        }
+    );
+    assert!(!err.is_unavailable());
+    assert!(err.to_string().starts_with("InsufficientBalance:"));
+
+    let txs = [vec![transfer(3 * ONE_NEAR)]];
+    let required = batch_cost(txs.iter().map(Vec::as_slice)).unwrap();
+    assert_eq!(ensure_affordable(&account, required), Ok(4 * ONE_NEAR));
+}
@@ -0,0 +1,149 @@
+use futures::executor::block_on;
+use zeroize::Zeroizing;
+
+use crate::config::ERROR_LOGGED_OUT;
+use crate::handlers::{
+    handle_clear_all_sessions, handle_get_state_changes_since, ClearAllSessionsRequest,
+    GetStateChangesSinceRequest,
+};
+use crate::inflight_requests::{
+    signer_requests_in_flight, DuplicatePolicy, InFlight, InFlightKey, InFlightRegistry,
+};
+use crate::session_key_cache::{
+    clear_all_session_keys, session_key_or_decrypt, session_key_reuse_status,
+    set_session_key_reuse_policy, KeyReuseExhaustion, KeyReusePolicy, KeySource,
+};
+use crate::state_changes::{logout_epoch, state_version, StateChangeKind};
+use crate::tests::fixtures::session_wrap_key;
+use crate::types::worker_messages::WorkerRequestType;
+use crate::verification_attestation::ContractVerificationFacts;
+use crate::wrap_key_handshake::{
+    active_signing_session_ids, cache_session_extras, cache_wrap_key_seed, session_store_sizes,
+};
+
+const SIGN: u32 = WorkerRequestType::SignTransactionsWithActions as u32;
+
+type Outcome = Result<String, String>;
+
+fn facts() -> ContractVerificationFacts {
+    ContractVerificationFacts {
+        vrf_output: "vrf-output-b64u".to_string(),
+        credential_id: "cred-1".to_string(),
+        contract_id: "w3a-v1.testnet".to_string(),
+        verified_at_ms: 1_000.0,
+        block_height: Some(42),
+    }
+}
+
+fn cache_signing_key(session_id: &str) {
+    set_session_key_reuse_policy(
+        session_id,
+        KeyReusePolicy {
+            allow_key_reuse_within_session: true,
+            max_operations: 3,
+            ttl_ms: 60_000,
+            on_exhausted: KeyReuseExhaustion::Redecrypt,
+        },
+    );
+    let source = KeySource {
+        near_account_id: "alice.near".to_string(),
+        credential_id: None,
+        encrypted_private_key_data: "ciphertext".to_string(),
+    };
+    session_key_or_decrypt(session_id, source, 0, || Ok(Zeroizing::new([1u8; 32]))).unwrap();
+}
+
+#[test]
+fn clear_all_sessions_empties_every_store() {
+    cache_wrap_key_seed("session-a", session_wrap_key());
+    cache_session_extras("session-a", "prf-second-b64u", facts());
+    cache_session_extras("session-b", "prf-second-b64u", facts());
+    cache_signing_key("session-c");
+    let in_flight = signer_requests_in_flight();
+    let key = InFlightKey::new("session-a", SIGN, Some("digest-1"), "{}");
+    let _leader = in_flight
+        .begin(key.clone(), DuplicatePolicy::Share, 1_000)
+        .unwrap();
+    assert_eq!(session_store_sizes(), [1, 2, 2]);
+    assert!(session_key_reuse_status("session-c", 0).key_cached);
+    let before = state_version();
+
+    let result = handle_clear_all_sessions(ClearAllSessionsRequest::default());
+    assert_eq!(result.cleared_signing_sessions, 2);
+    assert_eq!(result.cleared_threshold_auth_sessions, 0);
+    assert_eq!(result.cleared_in_flight_requests, 1);
+    assert_eq!(result.rejected_waiters, 0);
+
+    assert_eq!(session_store_sizes(), [0, 0, 0]);
+    assert!(active_signing_session_ids().is_empty());
+    assert!(!session_key_reuse_status("session-c", 0).enabled);
+    assert_eq!(clear_all_session_keys(), 0);
+    #[cfg(feature = "threshold")]
+    assert_eq!(
+        crate::threshold::client_share_cache::clear_all_threshold_client_shares(),
+        0
+    );
+    assert!(!in_flight.is_in_flight(&key));
+    assert_eq!(in_flight.in_flight_count(), 0);
+
+    let changes = handle_get_state_changes_since(GetStateChangesSinceRequest { version: before });
+    assert_eq!(
+        changes.events.iter().map(|e| e.kind).collect::<Vec<_>>(),
+        vec![StateChangeKind::Logout]
+    );
+}
+
+#[test]
+fn logout_epoch_follows_the_requested_value_or_advances() {
+    assert_eq!(logout_epoch(), 0);
+    let first = handle_clear_all_sessions(ClearAllSessionsRequest::default());
+    assert_eq!(first.logout_epoch, 1);
+    let chosen = handle_clear_all_sessions(ClearAllSessionsRequest {
+        logout_epoch: Some(7),
+    });
+    assert_eq!(chosen.logout_epoch, 7);
+    assert_eq!(logout_epoch(), 7);
+    let next = handle_clear_all_sessions(ClearAllSessionsRequest::default());
+    assert_eq!(next.logout_epoch, 8);
+}
+
+#[test]
+fn cleared_duplicates_fail_with_logged_out() {
+    let registry = InFlightRegistry::<Outcome>::default();
+    let key = InFlightKey::new("session-a", SIGN, Some("digest-1"), "{}");
+    let leader = match registry.begin(key.clone(), DuplicatePolicy::Share, 1_000) {
+        Ok(InFlight::Leader(guard)) => guard,
+        _ => panic!("first request must lead"),
+    };
+    let follower = match registry.begin(key.clone(), DuplicatePolicy::Share, 1_001) {
+        Ok(InFlight::Follower(first)) => first,
+        _ => panic!("duplicate must follow"),
+    };
+
+    assert_eq!(
+        registry.clear_with(Some(Err(ERROR_LOGGED_OUT.to_string()))),
+        1
+    );
+    assert_eq!(block_on(follower), Some(Err(ERROR_LOGGED_OUT.to_string())));
+    assert!(ERROR_LOGGED_OUT.starts_with("LoggedOut"));
+
+    // A request started after the logout owns the key; the old leader finishing late must
+    // neither clear its marker nor leak its result to the new request's duplicates.
+    let new_leader = match registry.begin(key.clone(), DuplicatePolicy::Share, 2_000) {
+        Ok(InFlight::Leader(guard)) => guard,
+        _ => panic!("request after logout must lead"),
+    };
+    let new_follower = match registry.begin(key.clone(), DuplicatePolicy::Share, 2_001) {
+        Ok(InFlight::Follower(first)) => first,
+        _ => panic!("duplicate must follow"),
+    };
+    leader.complete(Ok("signed-before-logout".to_string()));
+    assert!(registry.is_in_flight(&key));
+
+    new_leader.complete(Ok("signed-after-logout".to_string()));
+    assert_eq!(
+        block_on(new_follower),
+        Some(Ok("signed-after-logout".to_string()))
+    );
+    assert!(!registry.is_in_flight(&key));
+}
@@ -0,0 +1,71 @@
+use crate::crypto::WrapKey;
+use crate::encoders::base64_url_encode;
+use crate::threshold::client_share_cache::{
+    clear_threshold_client_share_cache, client_signing_share, warm_client_signing_share,
+    ClientShareCache,
+};
+use crate::threshold::threshold_client_share::{
+    derive_threshold_client_signing_share_bytes_v1,
+    derive_threshold_client_verifying_share_bytes_v1, verifying_share_bytes_from_signing_share,
+};
+
+fn wrap_key(seed_byte: u8) -> WrapKey {
+    WrapKey {
+        wrap_key_seed: base64_url_encode(&[seed_byte; 32]),
+        wrap_key_salt: base64_url_encode(&[9u8; 32]),
+    }
+}
+
+#[test]
+fn warmed_share_matches_fresh_derivation() {
+    let wrap_key = wrap_key(7);
+    let mut cache = ClientShareCache::default();
+    let warmed = cache.warm("session-a", "alice.near", &wrap_key).unwrap();
+
+    let fresh = derive_threshold_client_signing_share_bytes_v1(&wrap_key, "alice.near").unwrap();
+    assert_eq!(*warmed, fresh);
+    assert_eq!(*cache.get("session-a", "alice.near").unwrap(), fresh);
+    assert!(cache.get("session-a", "bob.near").is_none());
+    assert!(cache.get("session-b", "alice.near").is_none());
+
+    assert_eq!(
+        verifying_share_bytes_from_signing_share(&warmed),
+        derive_threshold_client_verifying_share_bytes_v1(&wrap_key, "alice.near").unwrap()
+    );
+}
+
+#[test]
+fn teardown_zeroizes_only_that_sessions_shares() {
+    let wrap_key = wrap_key(7);
+    let mut cache = ClientShareCache::default();
+    cache.warm("session-a", "alice.near", &wrap_key).unwrap();
+    cache.warm("session-a", "bob.near", &wrap_key).unwrap();
+    cache.warm("session-b", "alice.near", &wrap_key).unwrap();
+
+    let drained = cache.drain_session("session-a");
+    assert_eq!(drained.len(), 2);
+    assert!(drained.iter().all(|share| share.iter().all(|b| *b == 0)));
+    assert!(cache.get("session-a", "alice.near").is_none());
+    assert!(cache.get("session-a", "bob.near").is_none());
+    assert!(cache.get("session-b", "alice.near").is_some());
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn signer_lookup_prefers_warmed_share_until_cleared() {
+    let warm_key = wrap_key(7);
+    let other_key = wrap_key(8);
+    let warmed = warm_client_signing_share("session-c", "alice.near", &warm_key).unwrap();
+
+    // While warmed, the cached share is used instead of deriving from the given WrapKey.
+    let looked_up = client_signing_share("session-c", "alice.near", &other_key).unwrap();
+    assert_eq!(*looked_up, *warmed);
+
+    clear_threshold_client_share_cache("session-c".to_string());
+    let derived = client_signing_share("session-c", "alice.near", &other_key).unwrap();
+    assert_eq!(
+        *derived,
+        derive_threshold_client_signing_share_bytes_v1(&other_key, "alice.near").unwrap()
+    );
+    assert_ne!(*derived, *warmed);
+}
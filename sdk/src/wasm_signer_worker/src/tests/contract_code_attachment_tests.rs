@@ -0,0 +1,190 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use crate::actions::{contract_code_size_warnings, resolve_code_attachments, ActionParams};
+use crate::config::CONTRACT_CODE_WARN_BYTES;
+use crate::error::BinaryAttachmentError;
+use crate::transaction::{
+    append_transaction_signature, build_actions_from_params, build_transaction_with_actions,
+    serialize_transaction_for_signing,
+};
+use crate::types::worker_messages::BinaryAttachments;
+use crate::types::{NearAction, Signature, SignedTransaction};
+
+/// Allocations at least this large are counted. Test contracts are megabytes, so only buffers
+/// holding (a copy of) the code show up.
+const LARGE_ALLOCATION_BYTES: usize = 256 * 1024;
+
+const CODE_BYTES: usize = 2 * 1024 * 1024;
+
+thread_local! {
+    /// (count, bytes) of large allocations made by this thread; per thread so tests running in
+    /// parallel do not see each other's buffers.
+    static LARGE_ALLOCATIONS: Cell<(usize, usize)> = const { Cell::new((0, 0)) };
+}
+
+fn record_allocation(size: usize) {
+    if size >= LARGE_ALLOCATION_BYTES {
+        let _ = LARGE_ALLOCATIONS.try_with(|counter| {
+            let (count, bytes) = counter.get();
+            counter.set((count + 1, bytes + size));
+        });
+    }
+}
+
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record_allocation(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record_allocation(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record_allocation(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Run `f`, returning its output and the (count, bytes) of large allocations it made.
+fn large_allocations<T>(f: impl FnOnce() -> T) -> (T, (usize, usize)) {
+    let (count_before, bytes_before) = LARGE_ALLOCATIONS.with(Cell::get);
+    let output = f();
+    let (count_after, bytes_after) = LARGE_ALLOCATIONS.with(Cell::get);
+    (
+        output,
+        (count_after - count_before, bytes_after - bytes_before),
+    )
+}
+
+fn contract_code() -> Vec<u8> {
+    let mut code = b"\0asm\x01\0\0\0".to_vec();
+    code.resize(CODE_BYTES, 0x2a);
+    code
+}
+
+fn attached_deploy(index: u32) -> ActionParams {
+    ActionParams::DeployContract {
+        code: vec![],
+        code_attachment: Some(index),
+    }
+}
+
+#[test]
+fn deploy_contract_references_an_attachment_from_json() {
+    let action: ActionParams =
+        serde_json::from_str(r#"{"action_type":"DeployContract","codeAttachment":0}"#).unwrap();
+    assert_eq!(action, attached_deploy(0));
+
+    // Unresolved references never reach a transaction.
+    let err = action.to_action().unwrap_err();
+    assert!(err.contains("attachment 0 was not resolved"), "{}", err);
+}
+
+#[test]
+fn attached_code_is_moved_into_the_action_without_copies() {
+    let code = contract_code();
+    let mut attachments = BinaryAttachments::new(vec![code.clone()]);
+    let mut actions = vec![
+        attached_deploy(0),
+        ActionParams::Transfer {
+            deposit: "1".to_string(),
+        },
+    ];
+
+    let (built, (count, _bytes)) = large_allocations(|| {
+        resolve_code_attachments(&mut actions, &mut attachments).unwrap();
+        build_actions_from_params(actions).unwrap()
+    });
+    assert_eq!(count, 0, "resolving and building must not copy the code");
+    assert!(matches!(&built[0], NearAction::DeployContract { code: built } if *built == code));
+    assert_eq!(attachments.ensure_all_taken(), Ok(()));
+}
+
+#[test]
+fn transaction_is_signed_in_one_presized_buffer() {
+    let transaction = build_transaction_with_actions(
+        "alice.testnet",
+        "alice.testnet",
+        7,
+        &[0x44u8; 32],
+        &[0x55u8; 32],
+        vec![NearAction::DeployContract {
+            code: contract_code(),
+        }],
+    )
+    .unwrap();
+    let expected_tx = transaction.clone();
+    let signature = [0x66u8; 64];
+
+    let ((hash, signed_bytes), (count, bytes)) = large_allocations(|| {
+        let (unsigned, hash) = serialize_transaction_for_signing(transaction).unwrap();
+        let signed = append_transaction_signature(unsigned, &signature).unwrap();
+        (hash, signed)
+    });
+    assert_eq!(count, 1, "one buffer for transaction and signature");
+    assert_eq!(bytes, signed_bytes.len());
+
+    assert_eq!(hash, expected_tx.get_hash_and_size().0);
+    let expected = SignedTransaction::new(Signature::from_ed25519_bytes(&signature), expected_tx);
+    assert_eq!(signed_bytes, expected.to_borsh_bytes().unwrap());
+}
+
+#[test]
+fn attachment_references_must_match_the_envelope() {
+    let mut attachments = BinaryAttachments::new(vec![vec![1], vec![2]]);
+    let mut actions = vec![attached_deploy(0), attached_deploy(0)];
+    assert_eq!(
+        resolve_code_attachments(&mut actions, &mut attachments),
+        Err(BinaryAttachmentError::AlreadyUsed { index: 0 })
+    );
+    assert_eq!(
+        attachments.ensure_all_taken(),
+        Err(BinaryAttachmentError::Unreferenced { indices: vec![1] })
+    );
+
+    let mut actions = vec![attached_deploy(5)];
+    assert_eq!(
+        resolve_code_attachments(&mut actions, &mut attachments),
+        Err(BinaryAttachmentError::OutOfRange { index: 5, count: 2 })
+    );
+
+    let mut actions = vec![ActionParams::DeployContract {
+        code: vec![1],
+        code_attachment: Some(1),
+    }];
+    assert_eq!(
+        resolve_code_attachments(&mut actions, &mut attachments),
+        Err(BinaryAttachmentError::InlineAndAttached { index: 1 })
+    );
+}
+
+#[test]
+fn oversized_contract_code_draws_a_warning() {
+    let actions = vec![
+        ActionParams::Transfer {
+            deposit: "1".to_string(),
+        },
+        ActionParams::DeployContract {
+            code: contract_code(),
+            code_attachment: None,
+        },
+    ];
+    assert!(contract_code_size_warnings(&actions, CONTRACT_CODE_WARN_BYTES).is_empty());
+
+    let warnings = contract_code_size_warnings(&actions, CODE_BYTES - 1);
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].starts_with("Action 1: contract code is 2097152 bytes"));
+}
@@ -1,4 +1,5 @@
 use crate::cose::*;
+use crate::error::{AttestationError, CoseKeyError};
 use base64ct::{Base64UrlUnpadded, Encoding};
 use ciborium::value::Value as CborValue;
 
@@ -185,3 +186,259 @@ fn test_extract_cose_public_key_invalid_base64() {
         .unwrap_err()
         .contains("Failed to decode attestation object"));
 }
+
+#[test]
+fn test_validate_attestation_object_reports_structure() {
+    let attestation_object_bytes = create_mock_attestation_object();
+    let summary = validate_attestation_object_bytes(&attestation_object_bytes).unwrap();
+    assert_eq!(
+        summary,
+        AttestationObjectSummary {
+            fmt: "none".to_string(),
+            has_auth_data: true,
+            has_att_stmt: true,
+            credential_id_length: 32,
+        }
+    );
+}
+
+#[test]
+fn test_validate_attestation_object_truncated_cbor() {
+    let attestation_object_bytes = create_mock_attestation_object();
+    let truncated = &attestation_object_bytes[..attestation_object_bytes.len() / 2];
+    let err = validate_attestation_object_bytes(truncated).unwrap_err();
+    assert_eq!(err, AttestationError::TruncatedCbor);
+    assert_eq!(err.code(), "TRUNCATED_CBOR");
+}
+
+#[test]
+fn test_validate_attestation_object_non_cbor_blob() {
+    let err = validate_attestation_object_bytes(b"{\"fmt\":\"none\"}").unwrap_err();
+    assert_eq!(err, AttestationError::NotCborMap { initial_byte: b'{' });
+    assert_eq!(err.code(), "NOT_CBOR_MAP");
+    assert!(err.to_string().contains("0x7b"), "{}", err);
+
+    assert_eq!(
+        validate_attestation_object_bytes(&[]).unwrap_err(),
+        AttestationError::Empty
+    );
+}
+
+#[test]
+fn test_extract_cose_public_keys_batch_isolates_errors() {
+    use crate::handlers::{handle_extract_cose_public_keys, ExtractCosePublicKeysRequest};
+
+    let attestation_object_bytes = create_mock_attestation_object();
+    let valid_b64u = Base64UrlUnpadded::encode_string(&attestation_object_bytes);
+    let truncated_b64u = Base64UrlUnpadded::encode_string(
+        &attestation_object_bytes[..attestation_object_bytes.len() / 2],
+    );
+    let expected_cose_b64u = Base64UrlUnpadded::encode_string(
+        &extract_cose_public_key_from_attestation(&valid_b64u).unwrap(),
+    );
+
+    let batch = handle_extract_cose_public_keys(ExtractCosePublicKeysRequest {
+        attestation_objects_b64u: vec![
+            valid_b64u.clone(),
+            "Invalid@Base64!".to_string(),
+            truncated_b64u,
+            valid_b64u,
+        ],
+    });
+
+    assert_eq!(batch.results.len(), 4);
+    for (i, item) in batch.results.iter().enumerate() {
+        assert_eq!(item.index, i as u32);
+    }
+
+    for item in [&batch.results[0], &batch.results[3]] {
+        assert!(item.ok);
+        assert_eq!(item.cose_public_key_b64u.as_deref(), Some(expected_cose_b64u.as_str()));
+        assert!(item.error.is_none());
+    }
+
+    for item in [&batch.results[1], &batch.results[2]] {
+        assert!(!item.ok);
+        assert!(item.cose_public_key_b64u.is_none());
+        let error = item.error.as_deref().unwrap();
+        assert!(error.starts_with("Failed to extract COSE public key:"), "{}", error);
+    }
+    assert!(batch.results[1]
+        .error
+        .as_deref()
+        .unwrap()
+        .contains("Failed to decode attestation object"));
+}
+
+#[test]
+fn test_extract_cose_public_keys_empty_batch() {
+    use crate::handlers::{handle_extract_cose_public_keys, ExtractCosePublicKeysRequest};
+
+    let batch = handle_extract_cose_public_keys(ExtractCosePublicKeysRequest {
+        attestation_objects_b64u: Vec::new(),
+    });
+    assert!(batch.results.is_empty());
+}
+
+#[test]
+fn test_extract_cose_public_keys_two_valid_attestations() {
+    use crate::handlers::{handle_extract_cose_public_keys, ExtractCosePublicKeysRequest};
+
+    let valid_b64u = Base64UrlUnpadded::encode_string(&create_mock_attestation_object());
+    let batch = handle_extract_cose_public_keys(ExtractCosePublicKeysRequest {
+        attestation_objects_b64u: vec![valid_b64u.clone(), valid_b64u],
+    });
+
+    assert_eq!(batch.results.len(), 2);
+    assert!(batch.results.iter().all(|item| item.ok && item.error.is_none()));
+    assert_eq!(batch.results[0].index, 0);
+    assert_eq!(batch.results[1].index, 1);
+}
+
+#[test]
+fn test_extract_cose_public_keys_reports_error_at_malformed_index() {
+    use crate::handlers::{handle_extract_cose_public_keys, ExtractCosePublicKeysRequest};
+
+    let valid_b64u = Base64UrlUnpadded::encode_string(&create_mock_attestation_object());
+    let malformed_b64u = Base64UrlUnpadded::encode_string(b"{\"fmt\":\"none\"}");
+    let batch = handle_extract_cose_public_keys(ExtractCosePublicKeysRequest {
+        attestation_objects_b64u: vec![valid_b64u, malformed_b64u],
+    });
+
+    // JS sees `{ index, ok, cosePublicKeyB64u }` or `{ index, ok, error }` per slot.
+    let json = serde_json::to_value(&batch).unwrap();
+    let results = json["results"].as_array().unwrap();
+    assert_eq!(results[0]["index"], 0);
+    assert_eq!(results[0]["ok"], true);
+    assert!(results[0]["cosePublicKeyB64u"].is_string());
+    assert!(results[0].get("error").is_none());
+
+    assert_eq!(results[1]["index"], 1);
+    assert_eq!(results[1]["ok"], false);
+    assert!(results[1].get("cosePublicKeyB64u").is_none());
+    assert!(results[1]["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("Failed to extract COSE public key:"));
+}
+
+/// Authenticator-encoded ES256 COSE_Key (kty EC2, alg -7, crv P-256) holding a valid P-256 point.
+const ES256_COSE_KEY_B64U: &str = "pQECAyYgASFYIJRbr7_km7_-XDPUnNCD2nXOgFqvHyqCz7AQ4JaBY1gqIlggQPf2kqMlBp4izA_gTA5oMi9EWeRRBtVDJe4juMZr0YQ";
+const ES256_X_B64U: &str = "lFuvv-Sbv_5cM9Sc0IPadc6AWq8fKoLPsBDgloFjWCo";
+const ES256_Y_B64U: &str = "QPf2kqMlBp4izA_gTA5oMi9EWeRRBtVDJe4juMZr0YQ";
+
+fn cose_key_bytes(params: Vec<(i64, CborValue)>) -> Vec<u8> {
+    let map = params
+        .into_iter()
+        .map(|(label, value)| (CborValue::Integer(label.into()), value))
+        .collect();
+    let mut bytes = Vec::new();
+    ciborium::into_writer(&CborValue::Map(map), &mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn test_extract_ec2_coordinates_from_captured_es256_key() {
+    let cose_key = Base64UrlUnpadded::decode_vec(ES256_COSE_KEY_B64U).unwrap();
+    let coordinates = extract_ec2_coordinates(&cose_key).unwrap();
+    assert_eq!(coordinates.crv, Ec2Curve::P256);
+    assert_eq!(coordinates.x_b64u, ES256_X_B64U);
+    assert_eq!(coordinates.y_b64u, ES256_Y_B64U);
+    assert_eq!(
+        Base64UrlUnpadded::decode_vec(&coordinates.x_b64u)
+            .unwrap()
+            .len(),
+        32
+    );
+    assert_eq!(
+        Base64UrlUnpadded::decode_vec(&coordinates.y_b64u)
+            .unwrap()
+            .len(),
+        32
+    );
+
+    // Authenticator extensions may follow the key in authData.
+    let mut with_extensions = cose_key.clone();
+    with_extensions.extend_from_slice(&[0xa0]);
+    assert_eq!(extract_ec2_coordinates(&with_extensions), Ok(coordinates));
+}
+
+#[test]
+fn test_extract_ec2_coordinates_from_attestation() {
+    let attestation_b64u = Base64UrlUnpadded::encode_string(&create_mock_attestation_object());
+    let coordinates = extract_ec2_coordinates_from_attestation(&attestation_b64u).unwrap();
+    assert_eq!(coordinates.crv, Ec2Curve::P256);
+    assert_eq!(
+        coordinates.x_b64u,
+        Base64UrlUnpadded::encode_string(&[0x42u8; 32])
+    );
+    assert_eq!(
+        coordinates.y_b64u,
+        Base64UrlUnpadded::encode_string(&[0x84u8; 32])
+    );
+}
+
+#[test]
+fn test_extract_ec2_coordinates_checks_lengths_against_curve() {
+    // A P-256-sized x on a P-384 key
+    let cose_key = cose_key_bytes(vec![
+        (1, CborValue::Integer(2.into())),
+        (-1, CborValue::Integer(2.into())),
+        (-2, CborValue::Bytes(vec![1u8; 32])),
+        (-3, CborValue::Bytes(vec![2u8; 48])),
+    ]);
+    assert_eq!(
+        extract_ec2_coordinates(&cose_key),
+        Err(CoseKeyError::InvalidCoordinateLength {
+            coordinate: "x",
+            curve: "P-384",
+            expected: 48,
+            actual: 32,
+        })
+    );
+
+    let cose_key = cose_key_bytes(vec![
+        (1, CborValue::Integer(2.into())),
+        (-1, CborValue::Integer(3.into())),
+        (-2, CborValue::Bytes(vec![1u8; 66])),
+        (-3, CborValue::Bytes(vec![2u8; 66])),
+    ]);
+    assert_eq!(
+        extract_ec2_coordinates(&cose_key).unwrap().crv,
+        Ec2Curve::P521
+    );
+}
+
+#[test]
+fn test_extract_ec2_coordinates_rejects_other_keys() {
+    // Ed25519 (OKP) credential
+    let okp = cose_key_bytes(vec![
+        (1, CborValue::Integer(1.into())),
+        (-1, CborValue::Integer(6.into())),
+        (-2, CborValue::Bytes(vec![1u8; 32])),
+    ]);
+    assert_eq!(
+        extract_ec2_coordinates(&okp),
+        Err(CoseKeyError::NotEc2 { kty: 1 })
+    );
+
+    // secp256k1 is not a WebAuthn EC2 curve
+    let k256 = cose_key_bytes(vec![
+        (1, CborValue::Integer(2.into())),
+        (-1, CborValue::Integer(8.into())),
+    ]);
+    assert_eq!(
+        extract_ec2_coordinates(&k256),
+        Err(CoseKeyError::UnsupportedCurve(8))
+    );
+
+    let missing_y = cose_key_bytes(vec![
+        (1, CborValue::Integer(2.into())),
+        (-1, CborValue::Integer(1.into())),
+        (-2, CborValue::Bytes(vec![1u8; 32])),
+    ]);
+    assert_eq!(
+        extract_ec2_coordinates(&missing_y),
+        Err(CoseKeyError::MissingParameter("y"))
+    );
+}
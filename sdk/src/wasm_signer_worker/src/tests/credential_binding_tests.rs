@@ -0,0 +1,163 @@
+use sha2::{Digest, Sha256};
+
+use crate::credential_binding::{validate_credential_binding, CredentialBinding};
+use crate::encoders::base64_url_encode;
+use crate::error::CredentialBindingError;
+use crate::handlers::handle_recover_keypair_from_passkey::{
+    check_recovery_credential_binding, RecoverKeypairRequest, RecoverKeypairResult,
+};
+
+const RP_ID: &str = "wallet.example.com";
+const LEGACY_APP_ID: &str = "https://example.com/u2f/app-id.json";
+
+/// Assertion authenticatorData: rpIdHash || flags (UP|UV) || signCount.
+fn authenticator_data_for(id: &str) -> Vec<u8> {
+    let mut data = Sha256::digest(id.as_bytes()).to_vec();
+    data.push(0x05);
+    data.extend_from_slice(&7u32.to_be_bytes());
+    data
+}
+
+fn recovery_request(
+    authenticator_data: &[u8],
+    rp_id: Option<&str>,
+    allowed_app_ids: &[&str],
+    appid_extension: Option<bool>,
+) -> RecoverKeypairRequest {
+    let mut extensions = serde_json::json!({ "prf": { "results": { "second": "AAAA" } } });
+    if let Some(appid) = appid_extension {
+        extensions["appid"] = serde_json::json!(appid);
+    }
+    serde_json::from_value(serde_json::json!({
+        "credential": {
+            "id": "cred",
+            "rawId": "cred",
+            "type": "public-key",
+            "authenticatorAttachment": null,
+            "response": {
+                "clientDataJSON": "e30",
+                "authenticatorData": base64_url_encode(authenticator_data),
+                "signature": "c2ln",
+                "userHandle": null
+            },
+            "clientExtensionResults": extensions
+        },
+        "accountIdHint": "alice.near",
+        "sessionId": "session-1",
+        "rpId": rp_id,
+        "allowedAppIds": allowed_app_ids
+    }))
+    .unwrap()
+}
+
+#[test]
+fn rp_id_match_is_not_legacy() {
+    let binding = validate_credential_binding(&authenticator_data_for(RP_ID), RP_ID, &[]).unwrap();
+    assert_eq!(
+        binding,
+        CredentialBinding {
+            used_legacy_app_id: false
+        }
+    );
+}
+
+#[test]
+fn allowlisted_appid_match_is_accepted_as_legacy() {
+    let allowed = vec![
+        "https://other.example".to_string(),
+        LEGACY_APP_ID.to_string(),
+    ];
+    let binding =
+        validate_credential_binding(&authenticator_data_for(LEGACY_APP_ID), RP_ID, &allowed)
+            .unwrap();
+    assert!(binding.used_legacy_app_id);
+}
+
+#[test]
+fn appid_match_is_rejected_without_an_allowlist() {
+    assert_eq!(
+        validate_credential_binding(&authenticator_data_for(LEGACY_APP_ID), RP_ID, &[]),
+        Err(CredentialBindingError::RpIdHashMismatch {
+            rp_id: RP_ID.to_string(),
+            allowed_app_ids: 0,
+        })
+    );
+}
+
+#[test]
+fn neither_rp_id_nor_appid_is_rejected() {
+    let allowed = vec![LEGACY_APP_ID.to_string()];
+    assert!(matches!(
+        validate_credential_binding(&authenticator_data_for("evil.example"), RP_ID, &allowed),
+        Err(CredentialBindingError::RpIdHashMismatch { .. })
+    ));
+    assert_eq!(
+        validate_credential_binding(&[0u8; 31], RP_ID, &allowed),
+        Err(CredentialBindingError::AuthenticatorDataTooShort)
+    );
+}
+
+#[test]
+fn recovery_surfaces_legacy_binding_and_appid_extension() {
+    let request = recovery_request(
+        &authenticator_data_for(LEGACY_APP_ID),
+        Some(RP_ID),
+        &[LEGACY_APP_ID],
+        Some(true),
+    );
+    assert_eq!(
+        request.credential.client_extension_results.appid,
+        Some(true)
+    );
+    let binding = check_recovery_credential_binding(&request).unwrap();
+
+    let result = RecoverKeypairResult::new(
+        "ed25519:pk".to_string(),
+        "data".to_string(),
+        "nonce".to_string(),
+        "salt".to_string(),
+        None,
+    )
+    .with_credential_binding(binding, request.credential.client_extension_results.appid);
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["usedLegacyAppId"], true);
+    assert_eq!(json["appidExtension"], true);
+}
+
+#[test]
+fn recovery_binding_is_opt_in_and_never_loosened() {
+    // No rpId: nothing to check, and nothing reported.
+    let request = recovery_request(&authenticator_data_for("anything"), None, &[], None);
+    assert_eq!(check_recovery_credential_binding(&request), Ok(None));
+    let json = serde_json::to_value(
+        RecoverKeypairResult::new(
+            "ed25519:pk".to_string(),
+            "data".to_string(),
+            "nonce".to_string(),
+            "salt".to_string(),
+            None,
+        )
+        .with_credential_binding(None, None),
+    )
+    .unwrap();
+    assert!(json.get("usedLegacyAppId").is_none());
+    assert!(json.get("appidExtension").is_none());
+
+    // An allowlist cannot stand in for the rp id.
+    let request = recovery_request(
+        &authenticator_data_for(LEGACY_APP_ID),
+        None,
+        &[LEGACY_APP_ID],
+        None,
+    );
+    assert!(check_recovery_credential_binding(&request).is_err());
+
+    // rpId set with an empty allowlist rejects an appid-bound assertion.
+    let request = recovery_request(
+        &authenticator_data_for(LEGACY_APP_ID),
+        Some(RP_ID),
+        &[],
+        None,
+    );
+    assert!(check_recovery_credential_binding(&request).is_err());
+}
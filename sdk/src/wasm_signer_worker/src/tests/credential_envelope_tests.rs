@@ -0,0 +1,177 @@
+use crate::config::NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL;
+use crate::crypto::{encrypt_data_chacha20, WrapKey};
+use crate::error::KeyEnvelopeError;
+use crate::tests::fixtures::{wrap_key, NEAR_PRIVATE_KEY, NEAR_PUBLIC_KEY};
+use crate::threshold::signer_backend::LocalEd25519Signer;
+use crate::types::{CredentialKeyEnvelope, DecryptionPayload};
+
+const ACCOUNT_ID: &str = "alice.testnet";
+const PLATFORM_CREDENTIAL: &str = "platform-passkey";
+const SECURITY_KEY_CREDENTIAL: &str = "security-key";
+
+fn credential_envelope(wrap_key: &WrapKey, credential_id: &str) -> CredentialKeyEnvelope {
+    let kek = wrap_key.derive_kek_for_credential(credential_id).unwrap();
+    let encrypted = encrypt_data_chacha20(NEAR_PRIVATE_KEY, &kek).unwrap();
+    CredentialKeyEnvelope {
+        credential_id: credential_id.to_string(),
+        encrypted_private_key_data: encrypted.encrypted_near_key_data_b64u,
+        encrypted_private_key_chacha20_nonce_b64u: encrypted.chacha20_nonce_b64u,
+        version: NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL,
+        aad_context: None,
+    }
+}
+
+fn two_credential_payload(wrap_key: &WrapKey) -> DecryptionPayload {
+    DecryptionPayload::new(String::new(), String::new()).with_credential_envelopes(vec![
+        credential_envelope(wrap_key, PLATFORM_CREDENTIAL),
+        credential_envelope(wrap_key, SECURITY_KEY_CREDENTIAL),
+    ])
+}
+
+fn public_key_string(signer: &LocalEd25519Signer) -> String {
+    format!(
+        "ed25519:{}",
+        bs58::encode(signer.public_key_bytes()).into_string()
+    )
+}
+
+#[test]
+fn credential_kek_is_domain_separated() {
+    let wrap_key = wrap_key();
+    let account_kek = wrap_key.derive_kek().unwrap();
+    let platform_kek = wrap_key
+        .derive_kek_for_credential(PLATFORM_CREDENTIAL)
+        .unwrap();
+    let security_key_kek = wrap_key
+        .derive_kek_for_credential(SECURITY_KEY_CREDENTIAL)
+        .unwrap();
+
+    assert_ne!(platform_kek, account_kek);
+    assert_ne!(platform_kek, security_key_kek);
+    assert!(wrap_key.derive_kek_for_credential("").is_err());
+}
+
+#[test]
+fn selects_envelope_by_requested_credential_id() {
+    let wrap_key = wrap_key();
+    let payload = two_credential_payload(&wrap_key);
+
+    let envelope = payload
+        .select_envelope(Some(SECURITY_KEY_CREDENTIAL), None)
+        .unwrap();
+    assert_eq!(envelope.credential_id, Some(SECURITY_KEY_CREDENTIAL));
+    assert_eq!(
+        envelope.encrypted_private_key_data,
+        payload.credential_envelopes[1].encrypted_private_key_data
+    );
+
+    // Both passkeys may wrap the same NEAR key; each opens only with its own KEK.
+    let signer =
+        LocalEd25519Signer::from_encrypted_near_private_key(&wrap_key, &envelope, ACCOUNT_ID)
+            .unwrap();
+    assert_eq!(public_key_string(&signer), NEAR_PUBLIC_KEY);
+    let mut wrong_credential = envelope;
+    wrong_credential.credential_id = Some(PLATFORM_CREDENTIAL);
+    assert!(LocalEd25519Signer::from_encrypted_near_private_key(
+        &wrap_key,
+        &wrong_credential,
+        ACCOUNT_ID
+    )
+    .is_err());
+}
+
+#[test]
+fn defaults_to_presented_assertion_credential() {
+    let wrap_key = wrap_key();
+    let payload = two_credential_payload(&wrap_key);
+
+    let envelope = payload
+        .select_envelope(None, Some(PLATFORM_CREDENTIAL))
+        .unwrap();
+    assert_eq!(envelope.credential_id, Some(PLATFORM_CREDENTIAL));
+
+    let signer =
+        LocalEd25519Signer::from_encrypted_near_private_key(&wrap_key, &envelope, ACCOUNT_ID)
+            .unwrap();
+    assert_eq!(public_key_string(&signer), NEAR_PUBLIC_KEY);
+}
+
+#[test]
+fn presented_credential_without_envelope_is_a_mismatch() {
+    let payload = two_credential_payload(&wrap_key());
+
+    let err = payload
+        .select_envelope(None, Some("unknown-passkey"))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        KeyEnvelopeError::NoEnvelopeForCredential {
+            credential_id: "unknown-passkey".to_string(),
+            available: vec![
+                PLATFORM_CREDENTIAL.to_string(),
+                SECURITY_KEY_CREDENTIAL.to_string()
+            ],
+        }
+    );
+    assert!(String::from(err).starts_with("CredentialEnvelopeMismatch:"));
+}
+
+#[test]
+fn requested_credential_must_match_presented_assertion() {
+    let payload = two_credential_payload(&wrap_key());
+
+    let err = payload
+        .select_envelope(Some(SECURITY_KEY_CREDENTIAL), Some(PLATFORM_CREDENTIAL))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        KeyEnvelopeError::CredentialMismatch {
+            requested: SECURITY_KEY_CREDENTIAL.to_string(),
+            presented: PLATFORM_CREDENTIAL.to_string(),
+        }
+    );
+}
+
+#[test]
+fn multiple_envelopes_need_a_credential_id() {
+    let payload = two_credential_payload(&wrap_key());
+    assert!(matches!(
+        payload.select_envelope(None, None),
+        Err(KeyEnvelopeError::AmbiguousEnvelope { .. })
+    ));
+}
+
+#[test]
+fn account_wide_envelope_is_the_fallback() {
+    let wrap_key = wrap_key();
+    let kek = wrap_key.derive_kek().unwrap();
+    let encrypted = encrypt_data_chacha20(NEAR_PRIVATE_KEY, &kek).unwrap();
+    let payload = DecryptionPayload::new(
+        encrypted.encrypted_near_key_data_b64u,
+        encrypted.chacha20_nonce_b64u,
+    )
+    .with_credential_envelopes(vec![credential_envelope(
+        &wrap_key,
+        SECURITY_KEY_CREDENTIAL,
+    )]);
+
+    // A passkey registered before credential scoping has no envelope of its own.
+    let envelope = payload
+        .select_envelope(None, Some(PLATFORM_CREDENTIAL))
+        .unwrap();
+    assert_eq!(envelope.credential_id, None);
+    let signer =
+        LocalEd25519Signer::from_encrypted_near_private_key(&wrap_key, &envelope, ACCOUNT_ID)
+            .unwrap();
+    assert_eq!(public_key_string(&signer), NEAR_PUBLIC_KEY);
+
+    let scoped = payload
+        .select_envelope(None, Some(SECURITY_KEY_CREDENTIAL))
+        .unwrap();
+    assert_eq!(scoped.credential_id, Some(SECURITY_KEY_CREDENTIAL));
+
+    assert_eq!(
+        DecryptionPayload::new(String::new(), String::new()).select_envelope(None, None),
+        Err(KeyEnvelopeError::MissingEnvelope)
+    );
+}
@@ -1,10 +1,15 @@
 use bs58;
 
-use crate::config::CHACHA20_KEY_SIZE;
+use crate::config::{
+    near_key_aad_for_account, CHACHA20_KEY_SIZE, NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL,
+    NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL_AAD,
+};
 use crate::crypto::{
-    decrypt_data_chacha20, derive_ed25519_key_from_prf_output, encrypt_data_chacha20,
+    decrypt_data_chacha20, decrypt_data_chacha20_with_aad, derive_ed25519_key_from_prf_output,
+    encrypt_data_chacha20, WrapKey,
 };
 use crate::encoders::base64_url_encode;
+use crate::types::{EncryptedDataChaCha20Response, SelectedKeyEnvelope};
 
 /// Core round-trip test for ChaCha20 encryption/decryption and wrap-key salt tagging.
 #[test]
@@ -59,3 +64,145 @@ fn derive_ed25519_key_from_prf_output_is_deterministic_and_prefixed() {
     assert_eq!(priv_bytes.len(), 64);
     assert_eq!(pub_bytes.len(), 32);
 }
+
+#[test]
+fn randomness_self_test_detects_all_zero_and_repeated_output() {
+    use crate::randomness::randomness_self_test_with;
+
+    let err = randomness_self_test_with(|buf| {
+        buf.fill(0);
+        Ok(())
+    })
+    .unwrap_err();
+    assert!(err.contains("all-zero"), "unexpected error: {err}");
+
+    let err = randomness_self_test_with(|buf| {
+        buf.fill(0xab);
+        Ok(())
+    })
+    .unwrap_err();
+    assert!(err.contains("identical"), "unexpected error: {err}");
+
+    let err = randomness_self_test_with(|_| Err("stubbed".to_string())).unwrap_err();
+    assert!(err.contains("stubbed"), "unexpected error: {err}");
+
+    let mut counter = 0u8;
+    assert!(randomness_self_test_with(|buf| {
+        counter += 1;
+        buf.fill(counter);
+        Ok(())
+    })
+    .is_ok());
+}
+
+fn test_wrap_key() -> WrapKey {
+    WrapKey {
+        wrap_key_seed: base64_url_encode(&[0x5au8; 32]),
+        wrap_key_salt: base64_url_encode(&[0xa5u8; 32]),
+    }
+}
+
+fn aad_envelope<'a>(
+    encrypted: &'a EncryptedDataChaCha20Response,
+    credential_id: &'a str,
+    version: u8,
+    aad_context: Option<&'a str>,
+) -> SelectedKeyEnvelope<'a> {
+    SelectedKeyEnvelope {
+        encrypted_private_key_data: &encrypted.encrypted_near_key_data_b64u,
+        encrypted_private_key_chacha20_nonce_b64u: &encrypted.chacha20_nonce_b64u,
+        credential_id: Some(credential_id),
+        version,
+        aad_context,
+    }
+}
+
+#[test]
+fn near_key_envelope_decrypts_with_matching_account_aad() {
+    let wrap_key = test_wrap_key();
+    let encrypted = wrap_key
+        .encrypt_near_key_for_credential("ed25519:secret", "cred-1", "alice.testnet", None)
+        .unwrap();
+
+    let envelope = aad_envelope(
+        &encrypted,
+        "cred-1",
+        NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL_AAD,
+        None,
+    );
+    assert_eq!(
+        wrap_key
+            .decrypt_near_key_envelope(&envelope, "alice.testnet")
+            .unwrap(),
+        "ed25519:secret"
+    );
+}
+
+#[test]
+fn near_key_envelope_for_another_account_fails_authentication() {
+    let wrap_key = test_wrap_key();
+    let encrypted = wrap_key
+        .encrypt_near_key_for_credential("ed25519:secret", "cred-1", "alice.testnet", Some("app-a"))
+        .unwrap();
+
+    let envelope = aad_envelope(
+        &encrypted,
+        "cred-1",
+        NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL_AAD,
+        Some("app-a"),
+    );
+    let err = wrap_key
+        .decrypt_near_key_envelope(&envelope, "mallory.testnet")
+        .unwrap_err();
+    assert!(err.starts_with("Decryption error"), "{err}");
+
+    let other_context = aad_envelope(
+        &encrypted,
+        "cred-1",
+        NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL_AAD,
+        Some("app-b"),
+    );
+    assert!(wrap_key
+        .decrypt_near_key_envelope(&other_context, "alice.testnet")
+        .is_err());
+
+    // Reading an AAD-bound blob as a pre-AAD version also fails authentication.
+    let downgraded = aad_envelope(
+        &encrypted,
+        "cred-1",
+        NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL,
+        None,
+    );
+    assert!(wrap_key
+        .decrypt_near_key_envelope(&downgraded, "alice.testnet")
+        .is_err());
+}
+
+#[test]
+fn pre_aad_envelopes_still_decrypt() {
+    let wrap_key = test_wrap_key();
+    let kek = wrap_key.derive_kek_for_credential("cred-1").unwrap();
+    let encrypted = encrypt_data_chacha20("ed25519:secret", &kek).unwrap();
+
+    let envelope = aad_envelope(
+        &encrypted,
+        "cred-1",
+        NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL,
+        None,
+    );
+    assert_eq!(
+        wrap_key
+            .decrypt_near_key_envelope(&envelope, "any-account.testnet")
+            .unwrap(),
+        "ed25519:secret"
+    );
+
+    let aad = near_key_aad_for_account("alice.testnet", None);
+    assert!(decrypt_data_chacha20_with_aad(
+        &encrypted.encrypted_near_key_data_b64u,
+        &encrypted.chacha20_nonce_b64u,
+        &kek,
+        &aad,
+    )
+    .is_err());
+}
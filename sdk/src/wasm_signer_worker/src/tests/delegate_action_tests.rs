@@ -0,0 +1,71 @@
+use crate::config::{
+    DELEGATE_DEFAULT_EXPIRY_BLOCKS, DELEGATE_DEFAULT_MAX_BLOCK_HEIGHT_WINDOW,
+    ERROR_MAX_BLOCK_HEIGHT_OUT_OF_RANGE,
+};
+use crate::handlers::handle_sign_delegate_action::resolve_delegate_max_block_height;
+
+const CURRENT: u64 = 180_000_000;
+const WINDOW: u64 = DELEGATE_DEFAULT_MAX_BLOCK_HEIGHT_WINDOW;
+
+fn out_of_range() -> Result<u64, String> {
+    Err(ERROR_MAX_BLOCK_HEIGHT_OUT_OF_RANGE.to_string())
+}
+
+#[test]
+fn zero_max_block_height_needs_a_current_height() {
+    assert_eq!(
+        resolve_delegate_max_block_height(0, None, WINDOW),
+        out_of_range()
+    );
+    assert_eq!(
+        resolve_delegate_max_block_height(0, Some(CURRENT), WINDOW),
+        Ok(CURRENT + DELEGATE_DEFAULT_EXPIRY_BLOCKS)
+    );
+}
+
+#[test]
+fn reasonable_max_block_height_is_kept() {
+    for height in [CURRENT + 1, CURRENT + 5_000, CURRENT + WINDOW] {
+        assert_eq!(
+            resolve_delegate_max_block_height(height, Some(CURRENT), WINDOW),
+            Ok(height)
+        );
+    }
+    // Without a current height only zero can be rejected.
+    assert_eq!(
+        resolve_delegate_max_block_height(CURRENT, None, WINDOW),
+        Ok(CURRENT)
+    );
+}
+
+#[test]
+fn far_future_or_past_max_block_height_is_rejected() {
+    assert_eq!(
+        resolve_delegate_max_block_height(CURRENT + WINDOW + 1, Some(CURRENT), WINDOW),
+        out_of_range()
+    );
+    assert_eq!(
+        resolve_delegate_max_block_height(u64::MAX, Some(CURRENT), WINDOW),
+        out_of_range()
+    );
+    assert_eq!(
+        resolve_delegate_max_block_height(CURRENT, Some(CURRENT), WINDOW),
+        out_of_range()
+    );
+    assert_eq!(
+        resolve_delegate_max_block_height(CURRENT - 1, Some(CURRENT), WINDOW),
+        out_of_range()
+    );
+}
+
+#[test]
+fn window_is_configurable() {
+    assert_eq!(
+        resolve_delegate_max_block_height(CURRENT + 200, Some(CURRENT), 100),
+        out_of_range()
+    );
+    assert_eq!(
+        resolve_delegate_max_block_height(0, Some(CURRENT), 100),
+        Ok(CURRENT + 100)
+    );
+}
@@ -0,0 +1,149 @@
+use crate::device_registry::{ContractAuthenticatorView, DeviceRecord, DeviceRegistry};
+use crate::error::DeviceRegistryError;
+use crate::rpc_calls::{CallFunctionResultView, JsonRpcResponse};
+
+const ACCOUNT: &str = "alice.testnet";
+
+fn record(device_number: u8, credential_id: &str) -> DeviceRecord {
+    DeviceRecord {
+        device_number,
+        public_key: format!("ed25519:key-{}", credential_id),
+        credential_id: credential_id.to_string(),
+        registered_at_ms: 1_700_000_000_000.0,
+    }
+}
+
+/// `query` response for `get_authenticators_by_user`, with the contract's JSON return value
+/// encoded as the byte array NEAR RPC uses for `result`.
+fn mocked_authenticators_view(contract_json: &str) -> String {
+    let bytes: Vec<String> = contract_json.bytes().map(|b| b.to_string()).collect();
+    format!(
+        r#"{{"jsonrpc":"2.0","id":"tatchi-signer","result":{{"block_height":1,"logs":[],"result":[{}]}}}}"#,
+        bytes.join(",")
+    )
+}
+
+fn parse_authenticators(rpc_json: &str) -> Vec<(String, ContractAuthenticatorView)> {
+    let view = serde_json::from_str::<JsonRpcResponse<CallFunctionResultView>>(rpc_json)
+        .expect("fixture should deserialize")
+        .into_result()
+        .expect("query should succeed");
+    let bytes = view.into_bytes().expect("view should return bytes");
+    serde_json::from_slice(&bytes).expect("authenticator list should deserialize")
+}
+
+#[test]
+fn test_device_numbers_auto_assign_in_order() {
+    let mut registry = DeviceRegistry::default();
+    let first = registry
+        .assign_device_number(ACCOUNT, None, "cred-a", false)
+        .unwrap();
+    assert_eq!(first, 1);
+    registry.insert(ACCOUNT, record(first, "cred-a"));
+
+    let second = registry
+        .assign_device_number(ACCOUNT, None, "cred-b", false)
+        .unwrap();
+    assert_eq!(second, 2);
+    registry.insert(ACCOUNT, record(second, "cred-b"));
+
+    let numbers: Vec<u8> = registry
+        .records(ACCOUNT)
+        .iter()
+        .map(|r| r.device_number)
+        .collect();
+    assert_eq!(numbers, vec![1, 2]);
+    // Accounts are tracked independently
+    assert_eq!(registry.next_free_device_number("bob.testnet").unwrap(), 1);
+}
+
+#[test]
+fn test_explicit_device_number_conflict_is_rejected_unless_forced() {
+    let mut registry = DeviceRegistry::default();
+    registry.insert(ACCOUNT, record(1, "cred-a"));
+
+    let err = registry
+        .assign_device_number(ACCOUNT, Some(1), "cred-b", false)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        DeviceRegistryError::DeviceNumberConflict {
+            near_account_id: ACCOUNT.to_string(),
+            device_number: 1,
+            existing_credential_id: "cred-a".to_string(),
+        }
+    );
+    assert!(String::from(err).contains("force: true"));
+
+    // Re-deriving for the same credential is not a conflict
+    assert_eq!(
+        registry.assign_device_number(ACCOUNT, Some(1), "cred-a", false),
+        Ok(1)
+    );
+    // force replaces the existing record
+    assert_eq!(
+        registry.assign_device_number(ACCOUNT, Some(1), "cred-b", true),
+        Ok(1)
+    );
+    registry.insert(ACCOUNT, record(1, "cred-b"));
+    assert_eq!(registry.records(ACCOUNT), vec![record(1, "cred-b")]);
+
+    assert_eq!(
+        registry.assign_device_number(ACCOUNT, Some(0), "cred-c", true),
+        Err(DeviceRegistryError::InvalidDeviceNumber(0))
+    );
+}
+
+#[test]
+fn test_reconcile_with_contract_authenticators() {
+    let mut registry = DeviceRegistry::default();
+    // Local view is stale: cred-b was recorded as device 2, but the contract registered it as 3
+    registry.insert(ACCOUNT, record(1, "cred-a"));
+    registry.insert(ACCOUNT, record(2, "cred-b"));
+    assert_eq!(registry.next_free_device_number(ACCOUNT).unwrap(), 3);
+
+    let rpc_json = mocked_authenticators_view(
+        r#"[
+          ["cred-a", {"credential_public_key": [1, 2], "device_number": 1, "registered": "1"}],
+          ["cred-x", {"credential_public_key": [3, 4], "device_number": 2, "registered": "2"}],
+          ["cred-b", {"credential_public_key": [5, 6], "device_number": 3, "registered": "3"}]
+        ]"#,
+    );
+    let authenticators = parse_authenticators(&rpc_json);
+    assert_eq!(authenticators.len(), 3);
+
+    let next = registry
+        .reconcile_with_contract(ACCOUNT, &authenticators)
+        .unwrap();
+    assert_eq!(next, 4);
+
+    let records: Vec<(u8, String)> = registry
+        .records(ACCOUNT)
+        .into_iter()
+        .map(|r| (r.device_number, r.credential_id))
+        .collect();
+    assert_eq!(
+        records,
+        vec![(1, "cred-a".to_string()), (3, "cred-b".to_string())]
+    );
+}
+
+#[test]
+fn test_reconcile_with_empty_contract_list_starts_at_one() {
+    let mut registry = DeviceRegistry::default();
+    let authenticators = parse_authenticators(&mocked_authenticators_view("[]"));
+    assert_eq!(
+        registry.reconcile_with_contract(ACCOUNT, &authenticators),
+        Ok(1)
+    );
+}
+
+#[test]
+fn test_view_call_contract_error_surfaces() {
+    let rpc_json = r#"{"jsonrpc":"2.0","id":"tatchi-signer","result":{"error":"wasm execution failed","logs":[]}}"#;
+    let view = serde_json::from_str::<JsonRpcResponse<CallFunctionResultView>>(rpc_json)
+        .unwrap()
+        .into_result()
+        .unwrap();
+    assert!(view.into_bytes().is_err());
+}
@@ -0,0 +1,51 @@
+use crate::encoders::{base64_url_decode, B64StdBytes, B64UrlBytes, Encoding};
+use serde::de::value::{Error as DeError, StrDeserializer};
+use serde::Deserialize;
+
+// 0xfb 0xff 0xfe encodes to "+//+" in standard base64 and "-__-" in base64url.
+const BYTES: [u8; 3] = [0xfb, 0xff, 0xfe];
+
+fn url_field(input: &str) -> Result<B64UrlBytes, DeError> {
+    B64UrlBytes::deserialize(StrDeserializer::<DeError>::new(input))
+}
+
+fn std_field(input: &str) -> Result<B64StdBytes, DeError> {
+    B64StdBytes::deserialize(StrDeserializer::<DeError>::new(input))
+}
+
+#[test]
+fn variants_round_trip_with_distinct_alphabets() {
+    assert_eq!(Encoding::B64Url.encode(&BYTES), "-__-");
+    assert_eq!(Encoding::B64Std.encode(&BYTES), "+//+");
+    assert_eq!(Encoding::B64Url.encode(&[1u8]), "AQ");
+
+    for encoding in [Encoding::B64Url, Encoding::B64Std] {
+        let encoded = encoding.encode(&BYTES);
+        assert_eq!(encoding.decode(&encoded).unwrap(), BYTES);
+    }
+}
+
+#[test]
+fn decode_errors_name_the_expected_variant() {
+    let err = base64_url_decode("+//+").unwrap_err();
+    assert!(err.starts_with("expected base64url:"), "{err}");
+
+    let err = Encoding::B64Std.decode("-__-").unwrap_err();
+    assert!(err.starts_with("expected standard base64:"), "{err}");
+}
+
+#[test]
+fn wire_newtypes_validate_on_deserialize() {
+    assert_eq!(url_field("-__-").unwrap().as_bytes(), BYTES);
+    // Browser APIs sometimes pad base64url; the canonical form is still emitted on output.
+    let padded = url_field("AQ==").unwrap();
+    assert_eq!(padded.as_bytes(), [1u8]);
+    assert_eq!(padded.to_encoded(), "AQ");
+    assert_eq!(std_field(" +//+ ").unwrap().as_bytes(), BYTES);
+
+    // Mixing the variants up is rejected with the expected variant in the message.
+    let err = url_field("+//+").unwrap_err().to_string();
+    assert!(err.contains("expected base64url"), "{err}");
+    let err = std_field("-__-").unwrap_err().to_string();
+    assert!(err.contains("expected standard base64"), "{err}");
+}
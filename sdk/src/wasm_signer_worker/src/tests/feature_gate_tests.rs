@@ -0,0 +1,101 @@
+use crate::crypto::WrapKey;
+use crate::encoders::base64_url_encode;
+use crate::error::FeatureError;
+use crate::threshold::signer_backend::Ed25519SignerBackend;
+use crate::types::{SelectedKeyEnvelope, SignerMode, ThresholdSignerConfig};
+
+fn wrap_key() -> WrapKey {
+    WrapKey {
+        wrap_key_seed: base64_url_encode(&[7u8; 32]),
+        wrap_key_salt: base64_url_encode(&[9u8; 32]),
+    }
+}
+
+fn unused_envelope() -> SelectedKeyEnvelope<'static> {
+    SelectedKeyEnvelope {
+        encrypted_private_key_data: "unused",
+        encrypted_private_key_chacha20_nonce_b64u: "unused",
+        credential_id: None,
+        version: 2,
+        aad_context: None,
+    }
+}
+
+fn threshold_config() -> ThresholdSignerConfig {
+    serde_json::from_value(serde_json::json!({
+        "relayerUrl": "https://relay.example.com",
+        "relayerKeyId": "relayer-key-1",
+    }))
+    .unwrap()
+}
+
+fn backend_error(result: Result<Ed25519SignerBackend, String>) -> String {
+    match result {
+        Ok(_) => panic!("expected signer backend selection to fail"),
+        Err(e) => e,
+    }
+}
+
+#[test]
+fn feature_not_compiled_error_names_feature_and_request() {
+    let err = FeatureError::not_compiled("threshold", "WARM_THRESHOLD_ED25519_CLIENT_SHARE");
+    assert_eq!(
+        String::from(err),
+        "FeatureNotCompiled: WARM_THRESHOLD_ED25519_CLIENT_SHARE requires the `threshold` feature, which is not compiled into this worker build"
+    );
+}
+
+#[cfg(not(feature = "threshold"))]
+#[test]
+fn slim_build_rejects_threshold_signer_mode() {
+    let err = backend_error(Ed25519SignerBackend::from_encrypted_near_private_key(
+        SignerMode::ThresholdSigner,
+        &wrap_key(),
+        &unused_envelope(),
+        "alice.near",
+    ));
+    assert!(err.starts_with("FeatureNotCompiled:"), "{err}");
+    assert!(err.contains("`threshold`"), "{err}");
+}
+
+#[cfg(not(feature = "threshold"))]
+#[test]
+fn slim_build_rejects_threshold_signer_config() {
+    let err = backend_error(Ed25519SignerBackend::from_threshold_signer_config(
+        &wrap_key(),
+        "session-a",
+        "alice.near",
+        "ed25519:11111111111111111111111111111111",
+        "near_tx",
+        None,
+        None,
+        Some("{}".to_string()),
+        &threshold_config(),
+    ));
+    assert!(err.starts_with("FeatureNotCompiled:"), "{err}");
+}
+
+#[cfg(feature = "threshold")]
+#[test]
+fn threshold_build_selects_threshold_backend() {
+    let backend = Ed25519SignerBackend::from_encrypted_near_private_key(
+        SignerMode::ThresholdSigner,
+        &wrap_key(),
+        &unused_envelope(),
+        "alice.near",
+    );
+    assert!(matches!(backend, Ok(Ed25519SignerBackend::Threshold(_))));
+
+    let err = backend_error(Ed25519SignerBackend::from_threshold_signer_config(
+        &wrap_key(),
+        "session-a",
+        "alice.near",
+        "ed25519:11111111111111111111111111111111",
+        "near_tx",
+        None,
+        None,
+        Some("{}".to_string()),
+        &threshold_config(),
+    ));
+    assert!(!err.starts_with("FeatureNotCompiled:"), "{err}");
+}
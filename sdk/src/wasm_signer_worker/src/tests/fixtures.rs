@@ -0,0 +1,25 @@
+//! Key material shared by the signer test modules.
+
+use crate::crypto::WrapKey;
+use crate::encoders::base64_url_encode;
+
+/// Ed25519 seed = [0x07; 32], as `ed25519:<bs58 seed || public key>`
+pub const NEAR_PRIVATE_KEY: &str = "ed25519:99eUso3aSbE9tqGSTXzo3TLfKb9RkMTURrHKQ1K7Zh3StnzFNUx8FKCPPPPpR479qsw5zv2WNBKmgiz7WqgAJfM";
+/// Public half of `NEAR_PRIVATE_KEY`.
+pub const NEAR_PUBLIC_KEY: &str = "ed25519:GmaDrppBC7P5ARKV8g3djiwP89vz1jLK23V2GBjuAEGB";
+
+/// A well-formed wrap key (seed [0x5a; 32], salt [0xa5; 32]) that derives real KEKs.
+pub fn wrap_key() -> WrapKey {
+    WrapKey {
+        wrap_key_seed: base64_url_encode(&[0x5au8; 32]),
+        wrap_key_salt: base64_url_encode(&[0xa5u8; 32]),
+    }
+}
+
+/// Placeholder wrap key for tests that only cache and clear session material.
+pub fn session_wrap_key() -> WrapKey {
+    WrapKey {
+        wrap_key_seed: "seed-b64u".to_string(),
+        wrap_key_salt: "salt-b64u".to_string(),
+    }
+}
@@ -0,0 +1,113 @@
+use crate::error::HostBridgeError;
+use crate::host_environment::{
+    evaluate_environment, EnvironmentReport, FETCH, SEND_PROGRESS_MESSAGE, SET_TIMEOUT,
+};
+
+#[test]
+fn complete_environment_reports_ok() {
+    assert_eq!(
+        evaluate_environment(|_| true),
+        EnvironmentReport {
+            ok: true,
+            missing: vec![],
+            warnings: vec![],
+        }
+    );
+}
+
+#[test]
+fn missing_required_bridge_is_listed_and_not_ok() {
+    let report = evaluate_environment(|name| name != SEND_PROGRESS_MESSAGE);
+    assert!(!report.ok);
+    assert_eq!(report.missing, vec![SEND_PROGRESS_MESSAGE.to_string()]);
+    assert!(report.warnings.is_empty());
+
+    let report = evaluate_environment(|_| false);
+    assert_eq!(
+        report.missing,
+        vec![SEND_PROGRESS_MESSAGE.to_string(), SET_TIMEOUT.to_string()]
+    );
+}
+
+#[test]
+fn missing_optional_global_only_warns() {
+    let report = evaluate_environment(|name| name != FETCH);
+    assert!(report.ok);
+    assert!(report.missing.is_empty());
+    assert_eq!(report.warnings.len(), 1);
+    assert!(report.warnings[0].starts_with("fetch is not available"));
+}
+
+#[test]
+fn missing_host_bridge_error_names_the_global() {
+    let err = HostBridgeError::MissingHostBridge(SEND_PROGRESS_MESSAGE).to_string();
+    assert!(err.starts_with("MissingHostBridge(\"sendProgressMessage\")"));
+}
+
+/// Browser checks: install/delete the bridges on globalThis and observe the worker's view.
+/// Run with `wasm-pack test --headless --chrome`.
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use super::*;
+    use crate::host_environment::{check_environment, require_host_bridge};
+    use js_sys::{Function, Reflect};
+    use wasm_bindgen::JsValue;
+    use wasm_bindgen_test::*;
+
+    fn install(name: &str) {
+        Reflect::set(
+            &js_sys::global(),
+            &JsValue::from_str(name),
+            &Function::new_no_args(""),
+        )
+        .unwrap();
+    }
+
+    fn remove(name: &str) {
+        Reflect::delete_property(&js_sys::global(), &JsValue::from_str(name)).unwrap();
+    }
+
+    fn report() -> EnvironmentReport {
+        let value = check_environment();
+        let get = |key: &str| Reflect::get(&value, &JsValue::from_str(key)).unwrap();
+        let strings = |key: &str| {
+            js_sys::Array::from(&get(key))
+                .iter()
+                .filter_map(|v| v.as_string())
+                .collect::<Vec<_>>()
+        };
+        EnvironmentReport {
+            ok: get("ok").as_bool().unwrap(),
+            missing: strings("missing"),
+            warnings: strings("warnings"),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn check_environment_reports_deleted_progress_bridge() {
+        install(SEND_PROGRESS_MESSAGE);
+        assert!(!report()
+            .missing
+            .contains(&SEND_PROGRESS_MESSAGE.to_string()));
+
+        remove(SEND_PROGRESS_MESSAGE);
+        let report = report();
+        assert!(!report.ok);
+        assert!(report.missing.contains(&SEND_PROGRESS_MESSAGE.to_string()));
+    }
+
+    #[wasm_bindgen_test]
+    fn handlers_reject_with_missing_host_bridge() {
+        remove(SEND_PROGRESS_MESSAGE);
+        assert_eq!(
+            require_host_bridge(SEND_PROGRESS_MESSAGE),
+            Err(HostBridgeError::MissingHostBridge(SEND_PROGRESS_MESSAGE))
+        );
+        // Best-effort progress is dropped instead of throwing an unresolved-import error.
+        crate::send_progress_message(0, 0, "no bridge", JsValue::UNDEFINED);
+
+        install(SEND_PROGRESS_MESSAGE);
+        assert!(require_host_bridge(SEND_PROGRESS_MESSAGE).is_ok());
+        remove(SEND_PROGRESS_MESSAGE);
+    }
+}
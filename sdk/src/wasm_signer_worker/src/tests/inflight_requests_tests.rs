@@ -0,0 +1,187 @@
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::executor::block_on;
+
+use crate::error::InFlightRequestError;
+use crate::inflight_requests::{
+    DuplicatePolicy, InFlight, InFlightGuard, InFlightKey, InFlightRegistry,
+};
+use crate::types::worker_messages::WorkerRequestType;
+
+const SIGN: u32 = WorkerRequestType::SignTransactionsWithActions as u32;
+const PAYLOAD: &str = r#"{"sessionId":"sess-1","txSigningRequests":[{"receiverId":"bob.near"}]}"#;
+
+type Outcome = Result<String, String>;
+
+fn key(session_id: &str, payload_json: &str) -> InFlightKey {
+    InFlightKey::new(session_id, SIGN, None, payload_json)
+}
+
+fn leader(registry: &InFlightRegistry<Outcome>, key: InFlightKey) -> InFlightGuard<Outcome> {
+    match registry.begin(key, DuplicatePolicy::Share, 1_000) {
+        Ok(InFlight::Leader(guard)) => guard,
+        _ => panic!("first request must lead"),
+    }
+}
+
+/// Pending once, then ready: lets the harness interleave two "concurrent" requests.
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+#[test]
+fn concurrent_duplicate_awaits_and_shares_the_first_result() {
+    let registry = InFlightRegistry::<Outcome>::default();
+    let signed = Cell::new(0u32);
+
+    // Stand-in for handle_signer_message: sign only when leading.
+    let run = |registry: InFlightRegistry<Outcome>| {
+        let signed = &signed;
+        async move {
+            match registry.begin(key("sess-1", PAYLOAD), DuplicatePolicy::Share, 1_000) {
+                Ok(InFlight::Leader(guard)) => {
+                    YieldOnce(false).await; // waiting on WrapKeySeed
+                    signed.set(signed.get() + 1);
+                    let result: Outcome = Ok("signed-tx-1".to_string());
+                    guard.complete(result.clone());
+                    result
+                }
+                Ok(InFlight::Follower(first)) => first.await.expect("leader completed"),
+                Err(err) => Err(err.to_string()),
+            }
+        }
+    };
+
+    let (first, second) =
+        block_on(async { futures::join!(run(registry.clone()), run(registry.clone())) });
+    assert_eq!(first, Ok("signed-tx-1".to_string()));
+    assert_eq!(second, first);
+    assert_eq!(signed.get(), 1, "the duplicate must not sign again");
+    assert!(!registry.is_in_flight(&key("sess-1", PAYLOAD)));
+}
+
+#[test]
+fn reject_policy_fails_fast_with_original_start_time() {
+    let registry = InFlightRegistry::<Outcome>::default();
+    let _first = leader(&registry, key("sess-1", PAYLOAD));
+
+    let err = match registry.begin(key("sess-1", PAYLOAD), DuplicatePolicy::Reject, 2_500) {
+        Err(err) => err,
+        Ok(_) => panic!("duplicate must be rejected"),
+    };
+    assert_eq!(
+        err,
+        InFlightRequestError::DuplicateRequestInFlight {
+            session_id: "sess-1".to_string(),
+            started_at_ms: 1_000,
+        }
+    );
+    assert!(err.to_string().starts_with("DuplicateRequestInFlight"));
+}
+
+#[test]
+fn distinct_payloads_in_the_same_session_both_run() {
+    let registry = InFlightRegistry::<Outcome>::default();
+    let other = r#"{"sessionId":"sess-1","txSigningRequests":[{"receiverId":"carol.near"}]}"#;
+    let _first = leader(&registry, key("sess-1", PAYLOAD));
+    let _second = leader(&registry, key("sess-1", other));
+    let _other_session = leader(&registry, key("sess-2", PAYLOAD));
+
+    // Same payload, different request type is a different operation too.
+    let nep413 = InFlightKey::new(
+        "sess-1",
+        WorkerRequestType::SignNep413Message as u32,
+        None,
+        PAYLOAD,
+    );
+    let _third = leader(&registry, nep413);
+}
+
+#[test]
+fn intent_digest_takes_precedence_over_payload_hash() {
+    let a = InFlightKey::new("sess-1", SIGN, Some("digest-1"), PAYLOAD);
+    let b = InFlightKey::new("sess-1", SIGN, Some("digest-1"), r#"{"createdAt":2}"#);
+    assert_eq!(a, b);
+    assert_ne!(a, InFlightKey::new("sess-1", SIGN, Some(" "), PAYLOAD));
+}
+
+#[test]
+fn marker_is_cleared_after_failure_and_shares_the_error() {
+    let registry = InFlightRegistry::<Outcome>::default();
+    let guard = match registry.begin(key("sess-1", PAYLOAD), DuplicatePolicy::Share, 1_000) {
+        Ok(InFlight::Leader(guard)) => guard,
+        _ => panic!("first request must lead"),
+    };
+    let follower = match registry.begin(key("sess-1", PAYLOAD), DuplicatePolicy::Share, 1_001) {
+        Ok(InFlight::Follower(first)) => first,
+        _ => panic!("duplicate must follow"),
+    };
+
+    guard.complete(Err("Timed out waiting for WrapKeySeed".to_string()));
+    assert_eq!(
+        block_on(follower),
+        Some(Err("Timed out waiting for WrapKeySeed".to_string()))
+    );
+    assert!(!registry.is_in_flight(&key("sess-1", PAYLOAD)));
+
+    // A retry after the failure runs normally.
+    let _retry = leader(&registry, key("sess-1", PAYLOAD));
+}
+
+#[test]
+fn session_is_in_flight_until_its_last_request_finishes() {
+    let registry = InFlightRegistry::<Outcome>::default();
+    assert!(!registry.has_session_in_flight("sess-1"));
+
+    let first = leader(&registry, key("sess-1", PAYLOAD));
+    let second = leader(&registry, key("sess-1", r#"{"sessionId":"sess-1"}"#));
+    assert!(registry.has_session_in_flight("sess-1"));
+    assert!(!registry.has_session_in_flight("sess-2"));
+
+    first.complete(Ok("signed".to_string()));
+    assert!(registry.has_session_in_flight("sess-1"));
+    drop(second);
+    assert!(!registry.has_session_in_flight("sess-1"));
+}
+
+#[test]
+fn abandoned_first_request_releases_duplicates_and_marker() {
+    let registry = InFlightRegistry::<Outcome>::default();
+    let guard = leader(&registry, key("sess-1", PAYLOAD));
+    let follower = match registry.begin(key("sess-1", PAYLOAD), DuplicatePolicy::Share, 1_001) {
+        Ok(InFlight::Follower(first)) => first,
+        _ => panic!("duplicate must follow"),
+    };
+
+    drop(guard);
+    assert_eq!(block_on(follower), None);
+    assert!(!registry.is_in_flight(&key("sess-1", PAYLOAD)));
+}
+
+#[test]
+fn envelope_policy_defaults_to_share() {
+    assert_eq!(DuplicatePolicy::from_envelope(None), DuplicatePolicy::Share);
+    assert_eq!(
+        DuplicatePolicy::from_envelope(Some("share")),
+        DuplicatePolicy::Share
+    );
+    assert_eq!(
+        DuplicatePolicy::from_envelope(Some("reject")),
+        DuplicatePolicy::Reject
+    );
+}
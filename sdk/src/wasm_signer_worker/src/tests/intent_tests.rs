@@ -0,0 +1,157 @@
+use crate::actions::ActionParams;
+use crate::error::IntentError;
+use crate::handlers::handle_sign_intent::{
+    compute_intent_digest, summarize_intent, validate_intent_constraints, Intent,
+    IntentConstraints, IntentStepSummary,
+};
+use crate::handlers::handle_sign_transactions_with_actions::TransactionPayload;
+
+const SIGNER: &str = "alice.testnet";
+const ONE_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+
+fn call(method_name: &str, deposit: u128) -> ActionParams {
+    ActionParams::FunctionCall {
+        method_name: method_name.to_string(),
+        args: "{}".to_string(),
+        gas: "30000000000000".to_string(),
+        deposit: deposit.to_string(),
+    }
+}
+
+fn step(receiver_id: &str, actions: Vec<ActionParams>) -> TransactionPayload {
+    TransactionPayload {
+        near_account_id: SIGNER.to_string(),
+        receiver_id: receiver_id.to_string(),
+        actions,
+    }
+}
+
+/// Approve on token A, swap on the DEX, withdraw the proceeds.
+fn swap_intent() -> Intent {
+    Intent {
+        intent_id: "swap-1".to_string(),
+        description: "Swap 2 NEAR of token A for token B".to_string(),
+        steps: vec![
+            step("token-a.testnet", vec![call("ft_approve", 1)]),
+            step(
+                "dex.testnet",
+                vec![
+                    call("storage_deposit", ONE_NEAR / 100),
+                    call("swap", 2 * ONE_NEAR),
+                ],
+            ),
+            step("dex.testnet", vec![call("withdraw", 1)]),
+        ],
+        constraints: IntentConstraints {
+            max_total_deposit_yocto: (3 * ONE_NEAR).to_string(),
+            deadline_block_height: "1000".to_string(),
+        },
+    }
+}
+
+#[test]
+fn intent_digest_is_stable_and_covers_every_field() {
+    let intent = swap_intent();
+    let digest = compute_intent_digest(&intent).unwrap();
+    assert_eq!(digest, compute_intent_digest(&intent.clone()).unwrap());
+    assert_eq!(digest.len(), 43, "base64url of 32 bytes");
+
+    let mut reordered = swap_intent();
+    reordered.steps.swap(0, 2);
+    let mut described = swap_intent();
+    described.description.push('!');
+    let mut later_deadline = swap_intent();
+    later_deadline.constraints.deadline_block_height = "1001".to_string();
+    let mut larger_swap = swap_intent();
+    larger_swap.steps[1].actions[1] = call("swap", 2 * ONE_NEAR + 1);
+
+    for changed in [reordered, described, later_deadline, larger_swap] {
+        assert_ne!(compute_intent_digest(&changed).unwrap(), digest);
+    }
+}
+
+#[test]
+fn intent_constraints_reject_excess_deposit_and_missed_deadline() {
+    let intent = swap_intent();
+    assert!(validate_intent_constraints(&intent, 1000).is_ok());
+
+    let mut over_budget = swap_intent();
+    over_budget.constraints.max_total_deposit_yocto = (2 * ONE_NEAR).to_string();
+    assert_eq!(
+        validate_intent_constraints(&over_budget, 900).unwrap_err(),
+        IntentError::DepositLimitExceeded {
+            total_yocto: 2 * ONE_NEAR + ONE_NEAR / 100 + 2,
+            max_yocto: 2 * ONE_NEAR,
+        }
+    );
+
+    assert_eq!(
+        validate_intent_constraints(&intent, 1001).unwrap_err(),
+        IntentError::DeadlinePassed {
+            current_block_height: 1001,
+            deadline_block_height: 1000,
+        }
+    );
+}
+
+#[test]
+fn intent_rejects_malformed_shapes() {
+    let mut empty = swap_intent();
+    empty.steps.clear();
+    assert_eq!(
+        compute_intent_digest(&empty).unwrap_err(),
+        IntentError::EmptyIntent
+    );
+
+    let mut mixed = swap_intent();
+    mixed.steps[2].near_account_id = "bob.testnet".to_string();
+    assert_eq!(
+        compute_intent_digest(&mixed).unwrap_err(),
+        IntentError::MixedSigners
+    );
+
+    let mut bad_limit = swap_intent();
+    bad_limit.constraints.max_total_deposit_yocto = "lots".to_string();
+    assert!(matches!(
+        compute_intent_digest(&bad_limit).unwrap_err(),
+        IntentError::InvalidNumber { .. }
+    ));
+}
+
+#[test]
+fn three_step_intent_summary_lists_each_step() {
+    let summary = summarize_intent(&swap_intent()).unwrap();
+    assert_eq!(summary.intent_id, "swap-1");
+    assert_eq!(summary.description, "Swap 2 NEAR of token A for token B");
+    assert_eq!(summary.signer_account_id, SIGNER);
+    assert_eq!(
+        summary.total_deposit_yocto,
+        (2 * ONE_NEAR + ONE_NEAR / 100 + 2).to_string()
+    );
+    assert_eq!(
+        summary.steps,
+        vec![
+            IntentStepSummary {
+                index: 0,
+                receiver_id: "token-a.testnet".to_string(),
+                action_count: 1,
+                method_names: vec!["ft_approve".to_string()],
+                deposit_yocto: "1".to_string(),
+            },
+            IntentStepSummary {
+                index: 1,
+                receiver_id: "dex.testnet".to_string(),
+                action_count: 2,
+                method_names: vec!["storage_deposit".to_string(), "swap".to_string()],
+                deposit_yocto: (2 * ONE_NEAR + ONE_NEAR / 100).to_string(),
+            },
+            IntentStepSummary {
+                index: 2,
+                receiver_id: "dex.testnet".to_string(),
+                action_count: 1,
+                method_names: vec!["withdraw".to_string()],
+                deposit_yocto: "1".to_string(),
+            },
+        ]
+    );
+}
@@ -0,0 +1,156 @@
+use crate::config::NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL_AAD;
+use crate::crypto::WrapKey;
+use crate::encoders::{base64_url_decode, base64_url_encode};
+use crate::handlers::handle_verify_encrypted_key_envelope::{
+    check_envelope_structure, verify_encrypted_key_envelope, EncryptedKeyEnvelope,
+    VerifyEncryptedKeyEnvelopeRequest, VerifyEncryptedKeyEnvelopeResult,
+};
+use crate::tests::fixtures::{wrap_key, NEAR_PRIVATE_KEY, NEAR_PUBLIC_KEY};
+
+const ACCOUNT_ID: &str = "alice.testnet";
+const CREDENTIAL_ID: &str = "platform-passkey";
+
+/// A version 4 envelope with the integrity metadata the derive handlers write.
+fn sealed_envelope(wrap_key: &WrapKey) -> EncryptedKeyEnvelope {
+    let encrypted = wrap_key
+        .encrypt_near_key_for_credential(NEAR_PRIVATE_KEY, CREDENTIAL_ID, ACCOUNT_ID, None)
+        .unwrap();
+    let integrity_mac_b64u = wrap_key
+        .near_key_envelope_integrity_mac_b64u(
+            &encrypted.encrypted_near_key_data_b64u,
+            &encrypted.chacha20_nonce_b64u,
+            ACCOUNT_ID,
+            NEAR_PUBLIC_KEY,
+        )
+        .unwrap();
+    EncryptedKeyEnvelope {
+        encrypted_private_key_data: encrypted.encrypted_near_key_data_b64u,
+        encrypted_private_key_chacha20_nonce_b64u: encrypted.chacha20_nonce_b64u,
+        version: Some(NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL_AAD),
+        credential_id: Some(CREDENTIAL_ID.to_string()),
+        public_key: Some(NEAR_PUBLIC_KEY.to_string()),
+        integrity_mac_b64u: Some(integrity_mac_b64u),
+    }
+}
+
+fn verify(
+    envelope: EncryptedKeyEnvelope,
+    near_account_id: &str,
+    wrap_key: &WrapKey,
+) -> VerifyEncryptedKeyEnvelopeResult {
+    verify_encrypted_key_envelope(
+        &VerifyEncryptedKeyEnvelopeRequest {
+            session_id: "integrity-session".to_string(),
+            near_account_id: near_account_id.to_string(),
+            envelope,
+        },
+        wrap_key,
+    )
+    .unwrap()
+}
+
+#[test]
+fn intact_envelope_verifies_and_reports_its_public_key() {
+    let wrap_key = wrap_key();
+    let result = verify(sealed_envelope(&wrap_key), ACCOUNT_ID, &wrap_key);
+    assert_eq!(
+        result,
+        VerifyEncryptedKeyEnvelopeResult {
+            intact: true,
+            public_key: Some(NEAR_PUBLIC_KEY.to_string()),
+            integrity_unavailable: false,
+        }
+    );
+}
+
+#[test]
+fn tampered_ciphertext_is_not_intact() {
+    let wrap_key = wrap_key();
+    let mut envelope = sealed_envelope(&wrap_key);
+    let mut ciphertext = base64_url_decode(&envelope.encrypted_private_key_data).unwrap();
+    ciphertext[0] ^= 0x01;
+    envelope.encrypted_private_key_data = base64_url_encode(&ciphertext);
+
+    let result = verify(envelope, ACCOUNT_ID, &wrap_key);
+    assert!(!result.intact);
+    assert!(!result.integrity_unavailable);
+}
+
+#[test]
+fn envelope_stored_under_another_account_is_not_intact() {
+    let wrap_key = wrap_key();
+    let result = verify(sealed_envelope(&wrap_key), "bob.testnet", &wrap_key);
+    assert!(!result.intact);
+    assert_eq!(result.public_key.as_deref(), Some(NEAR_PUBLIC_KEY));
+}
+
+#[test]
+fn swapped_public_key_or_other_session_is_not_intact() {
+    let wrap_key = wrap_key();
+    let mut envelope = sealed_envelope(&wrap_key);
+    envelope.public_key = Some("ed25519:11111111111111111111111111111111".to_string());
+    assert!(!verify(envelope, ACCOUNT_ID, &wrap_key).intact);
+
+    let other_session = WrapKey {
+        wrap_key_seed: base64_url_encode(&[0x6bu8; 32]),
+        wrap_key_salt: base64_url_encode(&[0xa5u8; 32]),
+    };
+    assert!(!verify(sealed_envelope(&wrap_key), ACCOUNT_ID, &other_session).intact);
+}
+
+#[test]
+fn legacy_envelope_reports_integrity_unavailable() {
+    let wrap_key = wrap_key();
+    let mut envelope = sealed_envelope(&wrap_key);
+    envelope.public_key = None;
+    envelope.integrity_mac_b64u = None;
+
+    assert_eq!(
+        verify(envelope.clone(), ACCOUNT_ID, &wrap_key),
+        VerifyEncryptedKeyEnvelopeResult {
+            intact: false,
+            public_key: None,
+            integrity_unavailable: true,
+        }
+    );
+    let report = check_envelope_structure(&envelope);
+    assert!(report.valid, "{:?}", report.errors);
+    assert!(report.integrity_unavailable);
+}
+
+#[test]
+fn structure_check_accepts_a_sealed_envelope() {
+    let report = check_envelope_structure(&sealed_envelope(&wrap_key()));
+    assert!(report.valid, "{:?}", report.errors);
+    assert!(!report.integrity_unavailable);
+}
+
+#[test]
+fn structure_check_reports_bad_lengths_and_unknown_versions() {
+    let mut envelope = sealed_envelope(&wrap_key());
+    envelope.encrypted_private_key_chacha20_nonce_b64u = base64_url_encode(&[0u8; 8]);
+    envelope.integrity_mac_b64u = Some(base64_url_encode(&[0u8; 16]));
+    envelope.version = Some(9);
+
+    let report = check_envelope_structure(&envelope);
+    assert!(!report.valid);
+    let fields: Vec<_> = report.errors.iter().map(|e| e.field.as_str()).collect();
+    assert_eq!(
+        fields,
+        vec![
+            "encryptedPrivateKeyChacha20NonceB64u",
+            "version",
+            "integrityMacB64u"
+        ]
+    );
+}
+
+#[test]
+fn structure_check_requires_a_credential_id_for_credential_envelopes() {
+    let mut envelope = sealed_envelope(&wrap_key());
+    envelope.credential_id = None;
+
+    let report = check_envelope_structure(&envelope);
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].field, "credentialId");
+}
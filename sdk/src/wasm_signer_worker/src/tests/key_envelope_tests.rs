@@ -0,0 +1,244 @@
+use std::collections::HashSet;
+
+use crate::config::PORTABLE_KEY_ENVELOPE_VERSION;
+use crate::crypto::derive_deterministic_chacha20_nonce;
+use crate::crypto::WrapKey;
+use crate::encoders::base64_url_encode;
+use crate::key_envelope::{
+    decrypt_private_key_from_envelope, encrypt_private_key_to_envelope,
+    encrypt_private_key_to_envelope_with_nonce_mode, EncryptedKeyEnvelope, EnvelopeNonceMode,
+};
+use crate::tests::fixtures::{wrap_key, NEAR_PRIVATE_KEY};
+
+const ACCOUNT_ID: &str = "alice.testnet";
+
+#[test]
+fn envelope_round_trips_through_json() {
+    let wrap_key = wrap_key();
+    let envelope =
+        encrypt_private_key_to_envelope(&wrap_key, ACCOUNT_ID, NEAR_PRIVATE_KEY).unwrap();
+    assert_eq!(envelope.version, PORTABLE_KEY_ENVELOPE_VERSION);
+    assert_eq!(envelope.aead, "chacha20-poly1305");
+    assert_eq!(envelope.account_id, ACCOUNT_ID);
+
+    let json = serde_json::to_string(&envelope).unwrap();
+    for field in [
+        "version",
+        "aead",
+        "kdf",
+        "ciphertextB64u",
+        "nonceB64u",
+        "accountId",
+    ] {
+        assert!(
+            json.contains(&format!("\"{}\"", field)),
+            "missing {} in {}",
+            field,
+            json
+        );
+    }
+    let parsed: EncryptedKeyEnvelope = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, envelope);
+
+    let private_key = decrypt_private_key_from_envelope(&wrap_key, &parsed).unwrap();
+    assert_eq!(private_key.as_str(), NEAR_PRIVATE_KEY);
+}
+
+#[test]
+fn envelope_moved_to_another_account_fails_to_decrypt() {
+    let wrap_key = wrap_key();
+    let mut envelope =
+        encrypt_private_key_to_envelope(&wrap_key, ACCOUNT_ID, NEAR_PRIVATE_KEY).unwrap();
+    envelope.account_id = "mallory.testnet".to_string();
+
+    let err = decrypt_private_key_from_envelope(&wrap_key, &envelope).unwrap_err();
+    assert!(err.contains("Failed to decrypt key envelope"), "{}", err);
+}
+
+#[test]
+fn envelope_under_another_wrap_key_fails_to_decrypt() {
+    let envelope =
+        encrypt_private_key_to_envelope(&wrap_key(), ACCOUNT_ID, NEAR_PRIVATE_KEY).unwrap();
+    let other = WrapKey {
+        wrap_key_seed: base64_url_encode(&[0x11u8; 32]),
+        wrap_key_salt: base64_url_encode(&[0xa5u8; 32]),
+    };
+
+    assert!(decrypt_private_key_from_envelope(&other, &envelope).is_err());
+}
+
+#[test]
+fn v2_envelope_is_rejected_by_v1_decoder() {
+    // A future layout may rename every other field; the version must be reported first.
+    let json = r#"{"version":2,"aead":"xchacha20-poly1305","ciphertext":"AAAA"}"#;
+    let err = serde_json::from_str::<EncryptedKeyEnvelope>(json)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.starts_with("Unsupported key envelope version 2: this worker only reads version 1"),
+        "{}",
+        err
+    );
+
+    let mut envelope =
+        encrypt_private_key_to_envelope(&wrap_key(), ACCOUNT_ID, NEAR_PRIVATE_KEY).unwrap();
+    envelope.version = 2;
+    let err = decrypt_private_key_from_envelope(&wrap_key(), &envelope).unwrap_err();
+    assert!(
+        err.starts_with("Unsupported key envelope version 2"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn v1_envelope_missing_a_field_names_it() {
+    let json = r#"{"version":1,"aead":"chacha20-poly1305","kdf":"hkdf-sha256:near-kek","nonceB64u":"AAAA","accountId":"alice.testnet"}"#;
+    let err = serde_json::from_str::<EncryptedKeyEnvelope>(json)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.starts_with("Key envelope is missing `ciphertextB64u`"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn envelope_with_unknown_algorithms_is_rejected() {
+    let mut envelope =
+        encrypt_private_key_to_envelope(&wrap_key(), ACCOUNT_ID, NEAR_PRIVATE_KEY).unwrap();
+    envelope.kdf = "argon2id".to_string();
+    let err = decrypt_private_key_from_envelope(&wrap_key(), &envelope).unwrap_err();
+    assert_eq!(err, "Unsupported key envelope kdf 'argon2id'");
+}
+
+// === DETERMINISTIC NONCES ===
+
+#[test]
+fn deterministic_nonces_never_repeat_for_incrementing_counters() {
+    let kek = wrap_key().derive_kek().unwrap();
+    let plain = NEAR_PRIVATE_KEY.as_bytes();
+    let mut seen = HashSet::new();
+    for counter in 0..10_000u64 {
+        let nonce = derive_deterministic_chacha20_nonce(&kek, ACCOUNT_ID, counter, plain).unwrap();
+        assert!(seen.insert(nonce), "nonce repeated at counter {}", counter);
+    }
+    // Same counter, other account: a different nonce.
+    assert!(!seen
+        .contains(&derive_deterministic_chacha20_nonce(&kek, "bob.testnet", 0, plain).unwrap()));
+    // Same inputs: the same nonce, so an auditor holding the key can recompute it.
+    assert_eq!(
+        derive_deterministic_chacha20_nonce(&kek, ACCOUNT_ID, 7, plain).unwrap(),
+        derive_deterministic_chacha20_nonce(&kek, ACCOUNT_ID, 7, plain).unwrap()
+    );
+}
+
+#[test]
+fn deterministic_nonces_differ_for_different_keys_at_the_same_counter() {
+    // A reset counter (e.g. the first deterministic re-encryption after a key rotation) must
+    // not reuse a (KEK, nonce) pair for a different private key.
+    const OTHER_PRIVATE_KEY: &str = "ed25519:other-private-key";
+    let kek = wrap_key().derive_kek().unwrap();
+    assert_ne!(
+        derive_deterministic_chacha20_nonce(&kek, ACCOUNT_ID, 0, NEAR_PRIVATE_KEY.as_bytes())
+            .unwrap(),
+        derive_deterministic_chacha20_nonce(&kek, ACCOUNT_ID, 0, OTHER_PRIVATE_KEY.as_bytes())
+            .unwrap()
+    );
+
+    let encrypt = |private_key: &str| {
+        encrypt_private_key_to_envelope_with_nonce_mode(
+            &wrap_key(),
+            ACCOUNT_ID,
+            private_key,
+            EnvelopeNonceMode::Deterministic { counter: 0 },
+        )
+        .unwrap()
+    };
+    let first = encrypt(NEAR_PRIVATE_KEY);
+    let rotated = encrypt(OTHER_PRIVATE_KEY);
+    assert_ne!(first.nonce_b64u, rotated.nonce_b64u);
+    assert_eq!(
+        decrypt_private_key_from_envelope(&wrap_key(), &rotated)
+            .unwrap()
+            .as_str(),
+        OTHER_PRIVATE_KEY
+    );
+
+    // Under another wrap key, the same key and counter also get a different nonce.
+    let other_wrap_key = WrapKey {
+        wrap_key_seed: base64_url_encode(&[0x11u8; 32]),
+        wrap_key_salt: base64_url_encode(&[0xa5u8; 32]),
+    };
+    let other_kek = other_wrap_key.derive_kek().unwrap();
+    assert_ne!(
+        derive_deterministic_chacha20_nonce(&kek, ACCOUNT_ID, 0, NEAR_PRIVATE_KEY.as_bytes())
+            .unwrap(),
+        derive_deterministic_chacha20_nonce(&other_kek, ACCOUNT_ID, 0, NEAR_PRIVATE_KEY.as_bytes())
+            .unwrap()
+    );
+}
+
+#[test]
+fn deterministic_envelope_stores_its_counter_and_decrypts() {
+    let wrap_key = wrap_key();
+    let envelope = encrypt_private_key_to_envelope_with_nonce_mode(
+        &wrap_key,
+        ACCOUNT_ID,
+        NEAR_PRIVATE_KEY,
+        EnvelopeNonceMode::Deterministic { counter: 41 },
+    )
+    .unwrap();
+    assert_eq!(envelope.nonce_counter, Some(41));
+    assert_eq!(envelope.next_nonce_counter(), Ok(42));
+
+    let json = serde_json::to_string(&envelope).unwrap();
+    assert!(json.contains("\"nonceCounter\":41"), "{}", json);
+    let parsed: EncryptedKeyEnvelope = serde_json::from_str(&json).unwrap();
+    let private_key = decrypt_private_key_from_envelope(&wrap_key, &parsed).unwrap();
+    assert_eq!(private_key.as_str(), NEAR_PRIVATE_KEY);
+
+    // Re-encrypting with the next counter yields a fresh nonce.
+    let next = encrypt_private_key_to_envelope_with_nonce_mode(
+        &wrap_key,
+        ACCOUNT_ID,
+        NEAR_PRIVATE_KEY,
+        EnvelopeNonceMode::Deterministic {
+            counter: envelope.next_nonce_counter().unwrap(),
+        },
+    )
+    .unwrap();
+    assert_ne!(next.nonce_b64u, envelope.nonce_b64u);
+    assert_eq!(
+        decrypt_private_key_from_envelope(&wrap_key, &next)
+            .unwrap()
+            .as_str(),
+        NEAR_PRIVATE_KEY
+    );
+}
+
+#[test]
+fn deterministic_envelope_with_a_mismatched_counter_is_rejected() {
+    let mut envelope = encrypt_private_key_to_envelope_with_nonce_mode(
+        &wrap_key(),
+        ACCOUNT_ID,
+        NEAR_PRIVATE_KEY,
+        EnvelopeNonceMode::Deterministic { counter: 3 },
+    )
+    .unwrap();
+    envelope.nonce_counter = Some(4);
+    let err = decrypt_private_key_from_envelope(&wrap_key(), &envelope).unwrap_err();
+    assert_eq!(err, "Key envelope nonce does not match nonceCounter 4");
+}
+
+#[test]
+fn random_mode_remains_the_default() {
+    let a = encrypt_private_key_to_envelope(&wrap_key(), ACCOUNT_ID, NEAR_PRIVATE_KEY).unwrap();
+    let b = encrypt_private_key_to_envelope(&wrap_key(), ACCOUNT_ID, NEAR_PRIVATE_KEY).unwrap();
+    assert_eq!(a.nonce_counter, None);
+    assert_ne!(a.nonce_b64u, b.nonce_b64u);
+    assert_eq!(a.next_nonce_counter(), Ok(0));
+    assert!(!serde_json::to_string(&a).unwrap().contains("nonceCounter"));
+    assert_eq!(EnvelopeNonceMode::default(), EnvelopeNonceMode::Random);
+}
@@ -0,0 +1,119 @@
+use crate::crypto::decrypt_data_chacha20;
+use crate::encoders::base64_url_encode;
+use crate::handlers::handle_migrate_legacy_encrypted_key::{
+    migrate_legacy_encrypted_key, MigrateLegacyEncryptedKeyRequest,
+};
+use crate::legacy::{
+    decrypt_private_key_with_prf, encrypt_private_key_with_prf, near_public_key_for_private_key,
+};
+use crate::tests::fixtures::{wrap_key, NEAR_PRIVATE_KEY, NEAR_PUBLIC_KEY};
+
+const LEGACY_ACCOUNT_ID: &str = "legacy-user.testnet";
+/// PRF.first = [0x11; 32]
+const LEGACY_PRF_FIRST_B64U: &str = "ERERERERERERERERERERERERERERERERERERERERERE";
+
+// Golden blob in the legacy `packages/passkey` format (fixed nonce [0x24; 12]).
+// Pins the legacy HKDF salt/info: if this stops decrypting, existing users are stranded.
+const LEGACY_BLOB_IV_B64U: &str = "JCQkJCQkJCQkJCQk";
+const LEGACY_BLOB_B64U: &str = "BmVHUjwptmLZ677WTi3PFzExYtgyJPYtN6lIDkCHxXI80B7Y0o8Ga8aYy630aZbHSTtR0Qf8lHwe_hQOf13yO2VsZgOzKGR_SUMIW051mAkU_dCQtPXXjqt7FQfYV7E8B-ZopGlJfQk1RRvnx_e_";
+
+fn migrate_request(encrypted_b64u: &str, iv_b64u: &str) -> MigrateLegacyEncryptedKeyRequest {
+    MigrateLegacyEncryptedKeyRequest {
+        session_id: "migrate-session".to_string(),
+        near_account_id: LEGACY_ACCOUNT_ID.to_string(),
+        legacy_encrypted_private_key_data: encrypted_b64u.to_string(),
+        legacy_encrypted_private_key_iv: iv_b64u.to_string(),
+        prf_first_output_b64u: LEGACY_PRF_FIRST_B64U.to_string(),
+    }
+}
+
+#[test]
+fn legacy_golden_blob_decrypts_to_known_key() {
+    let private_key = decrypt_private_key_with_prf(
+        LEGACY_ACCOUNT_ID,
+        LEGACY_BLOB_B64U,
+        LEGACY_BLOB_IV_B64U,
+        LEGACY_PRF_FIRST_B64U,
+    )
+    .expect("golden legacy blob should decrypt");
+    assert_eq!(private_key.as_str(), NEAR_PRIVATE_KEY);
+    assert_eq!(
+        near_public_key_for_private_key(&private_key).unwrap(),
+        NEAR_PUBLIC_KEY
+    );
+
+    // The legacy key is bound to the account ID via the HKDF salt.
+    assert!(decrypt_private_key_with_prf(
+        "someone-else.testnet",
+        LEGACY_BLOB_B64U,
+        LEGACY_BLOB_IV_B64U,
+        LEGACY_PRF_FIRST_B64U,
+    )
+    .is_err());
+}
+
+#[test]
+fn migration_reencrypts_legacy_blob_under_wrap_key_envelope() {
+    // Encrypt with the legacy path, then migrate with the new handler.
+    let legacy =
+        encrypt_private_key_with_prf(LEGACY_ACCOUNT_ID, NEAR_PRIVATE_KEY, LEGACY_PRF_FIRST_B64U)
+            .unwrap();
+    let wrap_key = wrap_key();
+    let result = migrate_legacy_encrypted_key(
+        &migrate_request(
+            &legacy.encrypted_near_key_data_b64u,
+            &legacy.chacha20_nonce_b64u,
+        ),
+        &wrap_key,
+    )
+    .expect("migration should succeed");
+
+    assert_eq!(result.near_account_id, LEGACY_ACCOUNT_ID);
+    assert_eq!(result.public_key, NEAR_PUBLIC_KEY);
+    assert_eq!(result.wrap_key_salt, wrap_key.salt_b64u());
+    assert_ne!(
+        result.encrypted_private_key_data,
+        legacy.encrypted_near_key_data_b64u
+    );
+
+    // The new envelope opens with the WrapKey-derived KEK, not the legacy key.
+    let kek = wrap_key.derive_kek().unwrap();
+    let reopened = decrypt_data_chacha20(
+        &result.encrypted_private_key_data,
+        &result.encrypted_private_key_chacha20_nonce_b64u,
+        &kek,
+    )
+    .unwrap();
+    assert_eq!(reopened, NEAR_PRIVATE_KEY);
+    assert!(decrypt_private_key_with_prf(
+        LEGACY_ACCOUNT_ID,
+        &result.encrypted_private_key_data,
+        &result.encrypted_private_key_chacha20_nonce_b64u,
+        LEGACY_PRF_FIRST_B64U,
+    )
+    .is_err());
+
+    // Golden blob migrates to the same public key.
+    let golden = migrate_legacy_encrypted_key(
+        &migrate_request(LEGACY_BLOB_B64U, LEGACY_BLOB_IV_B64U),
+        &wrap_key,
+    )
+    .unwrap();
+    assert_eq!(golden.public_key, NEAR_PUBLIC_KEY);
+}
+
+#[test]
+fn migration_rejects_wrong_prf_output_and_inconsistent_keys() {
+    let mut request = migrate_request(LEGACY_BLOB_B64U, LEGACY_BLOB_IV_B64U);
+    request.prf_first_output_b64u = base64_url_encode(&[0x12u8; 32]);
+    let err = migrate_legacy_encrypted_key(&request, &wrap_key()).unwrap_err();
+    assert!(err.contains("Legacy decryption failed"), "{}", err);
+
+    // A private key whose public half does not match its seed must not be migrated.
+    let mut bytes = bs58::decode(&NEAR_PRIVATE_KEY["ed25519:".len()..])
+        .into_vec()
+        .unwrap();
+    bytes[63] ^= 0x01;
+    let tampered = format!("ed25519:{}", bs58::encode(&bytes).into_string());
+    assert!(near_public_key_for_private_key(&tampered).is_err());
+}
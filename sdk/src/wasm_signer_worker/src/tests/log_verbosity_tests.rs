@@ -0,0 +1,57 @@
+use crate::types::{HandlerLogs, LogVerbosity};
+
+/// Mirrors the logging of a 3-transaction batch whose last transaction fails to sign.
+fn simulate_batch(verbosity: LogVerbosity) -> Vec<String> {
+    let mut logs = HandlerLogs::new(verbosity);
+    logs.push("Processing 3 transactions".to_string());
+    for index in 1..=2 {
+        logs.push(format!("Transaction {}: Actions built successfully", index));
+        logs.push(format!("Transaction {}: Signed successfully", index));
+    }
+    logs.push("Transaction 3: Actions built successfully".to_string());
+    logs.push_error("Transaction 3: Failed to sign transaction: relayer timeout".to_string());
+    logs.push_error("Broadcast halted at transaction 3 of 3".to_string());
+    logs.into_lines()
+}
+
+#[test]
+fn log_verbosity_all_keeps_every_line() {
+    let logs = simulate_batch(LogVerbosity::All);
+    assert_eq!(logs.len(), 8);
+    assert_eq!(logs[0], "Processing 3 transactions");
+    assert_eq!(logs[7], "Broadcast halted at transaction 3 of 3");
+}
+
+#[test]
+fn log_verbosity_errors_keeps_only_failure_lines() {
+    assert_eq!(
+        simulate_batch(LogVerbosity::Errors),
+        vec![
+            "Transaction 3: Failed to sign transaction: relayer timeout".to_string(),
+            "Broadcast halted at transaction 3 of 3".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn log_verbosity_none_returns_empty_logs() {
+    assert!(simulate_batch(LogVerbosity::None).is_empty());
+}
+
+#[test]
+fn log_verbosity_parses_request_field() {
+    assert_eq!(LogVerbosity::from_request(None).unwrap(), LogVerbosity::All);
+    assert_eq!(
+        LogVerbosity::from_request(Some("none")).unwrap(),
+        LogVerbosity::None
+    );
+    assert_eq!(
+        LogVerbosity::from_request(Some("errors")).unwrap(),
+        LogVerbosity::Errors
+    );
+    assert_eq!(
+        LogVerbosity::from_request(Some("all")).unwrap(),
+        LogVerbosity::All
+    );
+    assert!(LogVerbosity::from_request(Some("verbose")).is_err());
+}
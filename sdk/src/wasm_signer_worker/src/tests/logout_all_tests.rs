@@ -0,0 +1,56 @@
+use zeroize::Zeroizing;
+
+use crate::handlers::{
+    handle_get_state_changes_since, handle_logout_all, GetStateChangesSinceRequest,
+};
+use crate::session_key_cache::{
+    session_key_or_decrypt, session_key_reuse_status, set_session_key_reuse_policy,
+    KeyReuseExhaustion, KeyReusePolicy, KeySource,
+};
+use crate::state_changes::{state_version, StateChangeKind};
+use crate::tests::fixtures::session_wrap_key;
+use crate::wrap_key_handshake::{active_signing_session_ids, cache_wrap_key_seed, cached_wrap_key};
+
+#[test]
+fn logout_all_clears_every_signing_session() {
+    cache_wrap_key_seed("session-a", session_wrap_key());
+    cache_wrap_key_seed("session-b", session_wrap_key());
+    // A third session only has a cached signing key.
+    set_session_key_reuse_policy(
+        "session-c",
+        KeyReusePolicy {
+            allow_key_reuse_within_session: true,
+            max_operations: 3,
+            ttl_ms: 60_000,
+            on_exhausted: KeyReuseExhaustion::Redecrypt,
+        },
+    );
+    let source = KeySource {
+        near_account_id: "alice.near".to_string(),
+        credential_id: None,
+        encrypted_private_key_data: "ciphertext".to_string(),
+    };
+    session_key_or_decrypt("session-c", source, 0, || Ok(Zeroizing::new([1u8; 32]))).unwrap();
+    assert!(session_key_reuse_status("session-c", 0).key_cached);
+    let before = state_version();
+
+    let result = handle_logout_all();
+    assert_eq!(result.cleared_signing_sessions, 2);
+    assert_eq!(result.cleared_threshold_auth_sessions, 0);
+    assert!(active_signing_session_ids().is_empty());
+    assert!(cached_wrap_key("session-a").is_none());
+    assert!(!session_key_reuse_status("session-c", 0).enabled);
+
+    let changes = handle_get_state_changes_since(GetStateChangesSinceRequest { version: before });
+    assert_eq!(
+        changes.events.iter().map(|e| e.kind).collect::<Vec<_>>(),
+        vec![StateChangeKind::Logout]
+    );
+}
+
+#[test]
+fn logout_all_with_nothing_cached_is_a_no_op() {
+    let result = handle_logout_all();
+    assert_eq!(result.cleared_signing_sessions, 0);
+    assert_eq!(result.cleared_threshold_auth_sessions, 0);
+}
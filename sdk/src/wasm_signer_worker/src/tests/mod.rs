@@ -1,7 +1,58 @@
 // Test modules
+pub mod account_card_tests;
+pub mod account_id_tests;
 pub mod actions_tests;
+pub mod balance_check_tests;
+pub mod clear_all_sessions_tests;
+#[cfg(feature = "threshold")]
+pub mod client_share_cache_tests;
+pub mod contract_code_attachment_tests;
 pub mod cose_tests;
+pub mod credential_binding_tests;
+pub mod credential_envelope_tests;
 pub mod crypto_tests;
+pub mod delegate_action_tests;
+pub mod device_registry_tests;
+pub mod encoding_tests;
+pub mod feature_gate_tests;
+pub mod fixtures;
+pub mod host_environment_tests;
+pub mod inflight_requests_tests;
+pub mod intent_tests;
+pub mod key_envelope_integrity_tests;
+pub mod key_envelope_tests;
+#[cfg(feature = "threshold")]
 pub mod frost_compat_tests;
+pub mod legacy_tests;
+pub mod logout_all_tests;
+pub mod log_verbosity_tests;
+pub mod near_access_key_tests;
+pub mod nep413_tests;
+pub mod origin_binding_tests;
+pub mod panic_guard_tests;
 pub mod progress_tests;
+pub mod registration_transports_tests;
+pub mod relayer_concurrency_tests;
+pub mod request_deadline_tests;
+pub mod rpc_calls_tests;
+pub mod session_binding_tests;
+pub mod session_key_cache_tests;
+pub mod sign_borsh_transaction_tests;
+pub mod sign_counter_tests;
+pub mod signable_message_tests;
+pub mod signature_preimage_tests;
+pub mod state_changes_tests;
+pub mod threshold_config_tests;
+pub mod threshold_nonce_reservation_tests;
+#[cfg(feature = "threshold")]
+pub mod threshold_proof_tests;
+pub mod threshold_request_auth_tests;
+#[cfg(feature = "threshold")]
+pub mod threshold_session_clock_tests;
+pub mod transaction_payload_tests;
+pub mod transaction_summary_tests;
 pub mod transaction_tests;
+pub mod verification_attestation_tests;
+pub mod worker_message_types_tests;
+pub mod wrap_key_handshake_tests;
+pub mod wrap_key_ordering_tests;
@@ -0,0 +1,39 @@
+use crate::types::near::is_valid_near_access_key;
+
+fn encoded(prefix: &str, len: usize) -> String {
+    format!("{}{}", prefix, bs58::encode(vec![7u8; len]).into_string())
+}
+
+#[test]
+fn accepts_ed25519_keys_of_32_bytes() {
+    assert!(is_valid_near_access_key(encoded("ed25519:", 32)));
+    assert!(is_valid_near_access_key(
+        "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp".to_string()
+    ));
+}
+
+#[test]
+fn accepts_secp256k1_keys_of_64_bytes() {
+    assert!(is_valid_near_access_key(encoded("secp256k1:", 64)));
+    // The 0x04-tagged (65) and compressed-with-tag (33) encodings are not NEAR keys.
+    assert!(!is_valid_near_access_key(encoded("secp256k1:", 65)));
+    assert!(!is_valid_near_access_key(encoded("secp256k1:", 33)));
+}
+
+#[test]
+fn rejects_malformed_keys() {
+    for key in [
+        String::new(),
+        "ed25519:".to_string(),
+        encoded("", 32),
+        encoded("ED25519:", 32),
+        encoded("ed25519:", 31),
+        encoded("ed25519:", 33),
+        encoded("ed25519:", 64),
+        encoded("secp256k1:", 32),
+        "ed25519:0OIl".to_string(),
+        format!(" {}", encoded("ed25519:", 32)),
+    ] {
+        assert!(!is_valid_near_access_key(key.clone()), "{:?}", key);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::crypto::derive_ed25519_key_from_prf_output;
+use crate::encoders::base64_url_encode;
+use crate::config::{NEP413_DEFAULT_MAX_MESSAGE_BYTES, NEP413_MAX_RECIPIENT_LEN};
+use crate::error::Nep413Error;
+use crate::handlers::handle_sign_nep413_message::{
+    ensure_expected_public_key, nep413_message_summary, nep413_payload_bytes,
+    validate_nep413_message, validate_nep413_recipient,
+};
+use sha2::{Digest, Sha256};
+
+fn derived_public_key(account_id: &str) -> (String, [u8; 32]) {
+    let prf_b64u = base64_url_encode(b"deterministic-prf-output-for-tests");
+    let (_sk, pk) = derive_ed25519_key_from_prf_output(&prf_b64u, account_id).unwrap();
+    let bytes: [u8; 32] = bs58::decode(pk.strip_prefix("ed25519:").unwrap())
+        .into_vec()
+        .unwrap()
+        .try_into()
+        .unwrap();
+    (pk, bytes)
+}
+
+#[test]
+fn expected_public_key_match_is_accepted() {
+    let (pk, bytes) = derived_public_key("alice.near");
+    assert!(ensure_expected_public_key(&bytes, Some(&pk)).is_ok());
+    // Bare base58 (no `ed25519:` prefix) is accepted too
+    let bare = pk.strip_prefix("ed25519:").unwrap();
+    assert!(ensure_expected_public_key(&bytes, Some(bare)).is_ok());
+    // No expectation: unrestricted
+    assert!(ensure_expected_public_key(&bytes, None).is_ok());
+}
+
+#[test]
+fn expected_public_key_mismatch_is_rejected() {
+    let (_, bytes) = derived_public_key("alice.near");
+    let (other_pk, _) = derived_public_key("bob.near");
+    assert_eq!(
+        ensure_expected_public_key(&bytes, Some(&other_pk)).unwrap_err(),
+        "decrypted key does not match expected public key"
+    );
+    assert!(ensure_expected_public_key(&bytes, Some("ed25519:0OIl")).is_err());
+}
+
+#[test]
+fn message_length_boundaries() {
+    let max = NEP413_DEFAULT_MAX_MESSAGE_BYTES;
+    assert_eq!(max, 16 * 1024);
+    assert!(validate_nep413_message("", max).is_ok());
+    assert!(validate_nep413_message(&"a".repeat(max), max).is_ok());
+    assert_eq!(
+        validate_nep413_message(&"a".repeat(max + 1), max),
+        Err(Nep413Error::MessageTooLarge {
+            len: max + 1,
+            max
+        })
+    );
+    // The cap is in UTF-8 bytes, not characters: 3 x 2-byte chars exceed a 5-byte cap
+    assert!(validate_nep413_message("éé", 5).is_ok());
+    assert!(matches!(
+        validate_nep413_message("ééé", 5),
+        Err(Nep413Error::MessageTooLarge { len: 6, max: 5 })
+    ));
+    let err: String = validate_nep413_message("abc", 2).unwrap_err().into();
+    assert!(err.starts_with("MessageTooLarge"));
+}
+
+#[test]
+fn control_characters_are_rejected_except_newline_and_tab() {
+    let max = NEP413_DEFAULT_MAX_MESSAGE_BYTES;
+    assert!(validate_nep413_message("line one\n\tline two", max).is_ok());
+    assert_eq!(
+        validate_nep413_message("ok\rno", max),
+        Err(Nep413Error::ControlCharacter {
+            byte_offset: 2,
+            code_point: 0x0d
+        })
+    );
+    assert!(validate_nep413_message("nul\0", max).is_err());
+    assert!(validate_nep413_message("esc\u{1b}[2J", max).is_err());
+    assert!(validate_nep413_message("del\u{7f}", max).is_err());
+    // C1 controls (e.g. NEL) are rejected; ordinary non-ASCII text is not
+    assert!(validate_nep413_message("nel\u{85}", max).is_err());
+    assert!(validate_nep413_message("héllo wörld ✓", max).is_ok());
+}
+
+#[test]
+fn recipient_must_be_account_id_or_url_like() {
+    assert!(validate_nep413_recipient("alice.near").is_ok());
+    assert!(validate_nep413_recipient("app.example.com").is_ok());
+    assert!(validate_nep413_recipient("https://app.example.com/login?x=1").is_ok());
+    assert!(validate_nep413_recipient("web+near://Callback").is_ok());
+
+    assert!(validate_nep413_recipient("").is_err());
+    assert!(validate_nep413_recipient("Alice Near").is_err());
+    assert!(validate_nep413_recipient("not an account").is_err());
+    assert!(validate_nep413_recipient("://missing-scheme").is_err());
+    assert!(validate_nep413_recipient("https://").is_err());
+    assert!(validate_nep413_recipient("alice.near\n").is_err());
+
+    let at_limit = format!("https://{}", "a".repeat(NEP413_MAX_RECIPIENT_LEN - 8));
+    assert_eq!(at_limit.len(), NEP413_MAX_RECIPIENT_LEN);
+    assert!(validate_nep413_recipient(&at_limit).is_ok());
+    let over_limit = format!("{}a", at_limit);
+    assert!(matches!(
+        validate_nep413_recipient(&over_limit),
+        Err(Nep413Error::InvalidRecipient(_))
+    ));
+}
+
+#[test]
+fn summary_hash_matches_message_in_signed_payload() {
+    let message = "Sign in to example.com\nNonce: 42 ✓";
+    let summary = nep413_message_summary(message);
+    assert_eq!(summary.message_byte_length as usize, message.len());
+    assert_eq!(summary.message_sha256_hex.len(), 64);
+
+    let payload = nep413_payload_bytes(message, "example.com", [7u8; 32], Some("st")).unwrap();
+    // Borsh string: u32 little-endian length, then the UTF-8 bytes
+    let len = u32::from_le_bytes(payload[..4].try_into().unwrap());
+    let signed_message = &payload[4..4 + len as usize];
+    assert_eq!(len, summary.message_byte_length);
+    let signed_hash: String = Sha256::digest(signed_message)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    assert_eq!(signed_hash, summary.message_sha256_hex);
+
+    // Known vector: sha256("abc")
+    assert_eq!(
+        nep413_message_summary("abc").message_sha256_hex,
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+}
@@ -0,0 +1,61 @@
+//! clientDataJSON is parsed with `js_sys::JSON`; run with
+//! `wasm-pack test --headless --chrome`.
+#![cfg(target_arch = "wasm32")]
+
+use crate::encoders::base64_url_encode;
+use crate::types::verify_client_data_origin;
+use wasm_bindgen_test::*;
+
+const CLIENT_DATA_JSON: &str = r#"{"type":"webauthn.get","challenge":"dGVzdC1jaGFsbGVuZ2U","origin":"https://wallet.example.com","crossOrigin":false}"#;
+
+fn client_data_b64u(json: &str) -> String {
+    base64_url_encode(json.as_bytes())
+}
+
+#[wasm_bindgen_test]
+fn accepts_matching_origin() {
+    let b64u = client_data_b64u(CLIENT_DATA_JSON);
+    assert_eq!(
+        verify_client_data_origin("https://wallet.example.com", &b64u),
+        Ok(())
+    );
+    // Same normalization as the worker origin gate: case and trailing slash are ignored.
+    assert_eq!(
+        verify_client_data_origin("https://Wallet.example.com/", &b64u),
+        Ok(())
+    );
+}
+
+#[wasm_bindgen_test]
+fn reads_only_the_top_level_origin() {
+    let nested = r#"{"type":"webauthn.get","tokenBinding":{"origin":"https://nested.example"},"note":"say \"origin\"","origin":"https://wallet.example.com"}"#;
+    assert_eq!(
+        verify_client_data_origin("https://wallet.example.com", &client_data_b64u(nested)),
+        Ok(())
+    );
+}
+
+#[wasm_bindgen_test]
+fn rejects_mismatching_origin() {
+    let b64u = client_data_b64u(CLIENT_DATA_JSON);
+    assert_eq!(
+        verify_client_data_origin("https://dapp.example.com", &b64u),
+        Err(
+            "origin mismatch: expected https://dapp.example.com, got https://wallet.example.com"
+                .to_string()
+        )
+    );
+}
+
+#[wasm_bindgen_test]
+fn rejects_client_data_without_origin() {
+    let missing = client_data_b64u(r#"{"type":"webauthn.get"}"#);
+    assert_eq!(
+        verify_client_data_origin("https://wallet.example.com", &missing),
+        Err("clientDataJSON has no origin".to_string())
+    );
+    assert!(
+        verify_client_data_origin("https://wallet.example.com", &client_data_b64u("not json"))
+            .is_err()
+    );
+}
@@ -0,0 +1,41 @@
+use std::cell::RefCell;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use futures::executor::block_on;
+
+use crate::handlers::{handle_debug_panic, DebugPanicRequest};
+use crate::panic_guard::{install_panic_hook, try_wipe};
+use crate::tests::fixtures::session_wrap_key;
+use crate::wrap_key_handshake::{active_signing_session_ids, cache_wrap_key_seed, cached_wrap_key};
+
+#[test]
+fn panic_hook_wipes_signing_session_secrets() {
+    install_panic_hook();
+    cache_wrap_key_seed("session-a", session_wrap_key());
+    cache_wrap_key_seed("session-b", session_wrap_key());
+    assert_eq!(active_signing_session_ids().len(), 2);
+
+    let panicked = catch_unwind(AssertUnwindSafe(|| {
+        block_on(handle_debug_panic(DebugPanicRequest {
+            message: Some("cbor slice out of bounds".to_string()),
+        }))
+    }));
+
+    assert!(panicked.is_err());
+    assert!(active_signing_session_ids().is_empty());
+    assert!(cached_wrap_key("session-a").is_none());
+}
+
+thread_local! {
+    static SECRETS: RefCell<Vec<u8>> = RefCell::new(vec![1, 2, 3]);
+}
+
+#[test]
+fn hook_wipe_skips_a_store_that_is_mid_borrow() {
+    SECRETS.with(|secrets| {
+        let _held = secrets.borrow();
+        assert!(!try_wipe(&SECRETS, |s| s.clear()));
+    });
+    assert!(try_wipe(&SECRETS, |s| s.clear()));
+    assert!(SECRETS.with(|secrets| secrets.borrow().is_empty()));
+}
@@ -0,0 +1,87 @@
+use crate::types::{
+    normalize_transports, RegistrationResponse, WebAuthnRegistrationCredential,
+    WebAuthnRegistrationCredentialStruct, WebAuthnRegistrationResponse,
+};
+
+fn registration_response(transports_json: Option<&str>) -> RegistrationResponse {
+    let transports = transports_json
+        .map(|t| format!(r#","transports":{}"#, t))
+        .unwrap_or_default();
+    let json = format!(
+        r#"{{"clientDataJSON":"client-data","attestationObject":"attestation"{}}}"#,
+        transports
+    );
+    serde_json::from_str(&json).expect("registration response should deserialize")
+}
+
+fn credential_struct(transports: Option<Vec<String>>) -> WebAuthnRegistrationCredentialStruct {
+    WebAuthnRegistrationCredentialStruct::new(
+        "cred-id".to_string(),
+        "cred-raw-id".to_string(),
+        "public-key".to_string(),
+        None,
+        "client-data".to_string(),
+        "attestation".to_string(),
+        transports,
+        None,
+    )
+}
+
+fn hybrid_internal() -> Vec<String> {
+    vec!["hybrid".to_string(), "internal".to_string()]
+}
+
+#[test]
+fn absent_and_empty_transports_normalize_to_none() {
+    assert_eq!(registration_response(None).transports, None);
+    assert_eq!(registration_response(Some("null")).transports, None);
+    assert_eq!(registration_response(Some("[]")).transports, None);
+    assert_eq!(normalize_transports(Some(Vec::new())), None);
+    assert_eq!(normalize_transports(None), None);
+}
+
+#[test]
+fn populated_transports_are_preserved() {
+    assert_eq!(
+        registration_response(Some(r#"["hybrid","internal"]"#)).transports,
+        Some(hybrid_internal())
+    );
+    assert_eq!(
+        normalize_transports(Some(hybrid_internal())),
+        Some(hybrid_internal())
+    );
+}
+
+#[test]
+fn contract_credential_conversion_normalizes_transports() {
+    let absent = WebAuthnRegistrationCredential::from(&credential_struct(None));
+    assert_eq!(absent.response.transports, None);
+
+    let empty = WebAuthnRegistrationCredential::from(&credential_struct(Some(Vec::new())));
+    assert_eq!(empty.response.transports, None);
+
+    let populated =
+        WebAuthnRegistrationCredential::from(&credential_struct(Some(hybrid_internal())));
+    assert_eq!(populated.response.transports, Some(hybrid_internal()));
+}
+
+#[test]
+fn contract_response_tolerates_absent_or_empty_transports() {
+    let absent: WebAuthnRegistrationResponse =
+        serde_json::from_str(r#"{"clientDataJSON":"c","attestationObject":"a"}"#).unwrap();
+    assert_eq!(absent.transports, None);
+
+    let empty: WebAuthnRegistrationResponse =
+        serde_json::from_str(r#"{"clientDataJSON":"c","attestationObject":"a","transports":[]}"#)
+            .unwrap();
+    assert_eq!(empty.transports, None);
+}
+
+#[test]
+fn absent_transports_are_omitted_when_serialized() {
+    let json = serde_json::to_value(registration_response(Some("[]"))).unwrap();
+    assert!(json.get("transports").is_none());
+
+    let json = serde_json::to_value(registration_response(Some(r#"["usb"]"#))).unwrap();
+    assert_eq!(json["transports"], serde_json::json!(["usb"]));
+}
@@ -0,0 +1,36 @@
+//! Limiter queueing. The cap itself is exercised through the transport trait in
+//! `threshold::transport::tests`.
+
+use futures::executor::block_on;
+
+use crate::threshold::relayer_concurrency::RelayerRequestLimiter;
+
+#[test]
+fn permits_are_granted_in_arrival_order_up_to_the_limit() {
+    let limiter = RelayerRequestLimiter::default();
+    block_on(async {
+        let first = limiter.acquire(2).await;
+        let _second = limiter.acquire(2).await;
+        assert_eq!(limiter.in_flight(), 2);
+        let third = limiter.acquire(2);
+        drop(first);
+        let _third = third.await;
+        assert_eq!(limiter.in_flight(), 2);
+    });
+    assert_eq!(limiter.in_flight(), 0);
+}
+
+#[test]
+fn abandoned_waiter_does_not_hold_up_the_queue() {
+    let limiter = RelayerRequestLimiter::default();
+    block_on(async {
+        let first = limiter.acquire(1).await;
+        let abandoned = limiter.acquire(1);
+        let next = limiter.acquire(1);
+        drop(abandoned);
+        drop(first);
+        let _permit = next.await;
+        assert_eq!(limiter.in_flight(), 1);
+    });
+    assert_eq!(limiter.in_flight(), 0);
+}
@@ -0,0 +1,106 @@
+use futures::executor::block_on;
+
+use crate::actions::ActionParams;
+use crate::crypto::WrapKey;
+use crate::error::RequestDeadlineError;
+use crate::handlers::handle_sign_transactions_with_actions::{
+    handle_sign_transactions_with_actions, SignTransactionsWithActionsRequest, TransactionPayload,
+};
+use crate::request_deadline::RequestDeadline;
+use crate::types::handlers::RpcCallPayload;
+use crate::types::{DecryptionPayload, SignerMode};
+
+const NOW_MS: f64 = 1_700_000_000_000.0;
+
+fn fixed_clock() -> f64 {
+    NOW_MS
+}
+
+fn batch_request(deadline: RequestDeadline) -> SignTransactionsWithActionsRequest {
+    SignTransactionsWithActionsRequest {
+        signer_mode: SignerMode::LocalSigner,
+        rpc_call: RpcCallPayload {
+            contract_id: "w3a-v1.testnet".to_string(),
+            near_rpc_url: "https://rpc.testnet.near.org".to_string(),
+            near_account_id: "alice.testnet".to_string(),
+        },
+        session_id: "deadline-session".to_string(),
+        created_at: None,
+        decryption: DecryptionPayload::new(String::new(), String::new()),
+        threshold: None,
+        tx_signing_requests: vec![TransactionPayload {
+            near_account_id: "alice.testnet".to_string(),
+            receiver_id: "bob.testnet".to_string(),
+            actions: vec![ActionParams::Transfer {
+                deposit: "1".to_string(),
+            }],
+        }],
+        confirmation_config: None,
+        intent_digest: None,
+        transaction_context: None,
+        vrf_challenge: None,
+        credential: None,
+        credential_id: None,
+        allowed_methods: None,
+        allowed_action_kinds: None,
+        include_threshold_proof: false,
+        include_signature_preimage: false,
+        broadcast: None,
+        log_verbosity: None,
+        produce_verification_attestation: false,
+        check_balance: false,
+        contract_code_warn_bytes: None,
+        deadline,
+    }
+}
+
+fn wrap_key() -> WrapKey {
+    WrapKey {
+        wrap_key_seed: String::new(),
+        wrap_key_salt: String::new(),
+    }
+}
+
+#[test]
+fn passed_deadline_aborts_before_any_work() {
+    let deadline = RequestDeadline::new(Some(NOW_MS - 1.0)).with_clock(fixed_clock);
+    assert_eq!(
+        deadline.check(),
+        Err(RequestDeadlineError::Exceeded {
+            deadline_ms_epoch: NOW_MS - 1.0,
+            now_ms: NOW_MS,
+        })
+    );
+
+    // Rejected ahead of the intent digest / transaction context checks, so nothing is decrypted.
+    let err = block_on(handle_sign_transactions_with_actions(
+        batch_request(deadline),
+        wrap_key(),
+    ))
+    .expect_err("a passed deadline must abort");
+    assert!(err.starts_with("request deadline exceeded"), "{}", err);
+}
+
+#[test]
+fn deadline_is_exceeded_once_reached() {
+    let deadline = RequestDeadline::new(Some(NOW_MS)).with_clock(fixed_clock);
+    assert!(deadline.check().is_err());
+}
+
+#[test]
+fn future_or_absent_deadline_proceeds() {
+    let deadline = RequestDeadline::new(Some(NOW_MS + 60_000.0)).with_clock(fixed_clock);
+    assert_eq!(deadline.check(), Ok(()));
+    assert_eq!(
+        RequestDeadline::new(None).with_clock(fixed_clock).check(),
+        Ok(())
+    );
+
+    // Past the deadline check the handler goes on to its next validation step.
+    let err = block_on(handle_sign_transactions_with_actions(
+        batch_request(deadline),
+        wrap_key(),
+    ))
+    .expect_err("request has no intent digest");
+    assert_eq!(err, "Missing intent digest from pre-confirmed session");
+}
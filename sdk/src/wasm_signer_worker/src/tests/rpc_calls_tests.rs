@@ -0,0 +1,143 @@
+use crate::error::NearRpcError;
+use crate::rpc_calls::{
+    classify_outcome, FinalExecutionOutcomeView, FinalExecutionStatusView, JsonRpcResponse,
+    TxFinalStatus, WaitUntil,
+};
+
+fn parse_response(json: &str) -> Result<FinalExecutionOutcomeView, NearRpcError> {
+    serde_json::from_str::<JsonRpcResponse<FinalExecutionOutcomeView>>(json)
+        .expect("fixture should deserialize")
+        .into_result()
+}
+
+const SEND_TX_NONE: &str = r#"{
+  "jsonrpc": "2.0",
+  "id": "tatchi-signer",
+  "result": { "final_execution_status": "NONE" }
+}"#;
+
+const TX_EXECUTED_SUCCESS: &str = r#"{
+  "jsonrpc": "2.0",
+  "id": "tatchi-signer",
+  "result": {
+    "final_execution_status": "EXECUTED_OPTIMISTIC",
+    "status": { "SuccessValue": "" },
+    "transaction": { "signer_id": "alice.testnet", "nonce": 7 },
+    "transaction_outcome": {
+      "id": "9FtHUFBQsZ2MG77K3x3MJ9wjX3UT8zE1TczCrhZEcG8U",
+      "outcome": { "gas_burnt": 223182562500, "status": { "SuccessReceiptId": "2J2c" } }
+    },
+    "receipts_outcome": []
+  }
+}"#;
+
+const TX_ACTION_FAILURE: &str = r#"{
+  "jsonrpc": "2.0",
+  "id": "tatchi-signer",
+  "result": {
+    "final_execution_status": "EXECUTED_OPTIMISTIC",
+    "status": {
+      "Failure": {
+        "ActionError": {
+          "index": 0,
+          "kind": {
+            "FunctionCallError": { "ExecutionError": "Smart contract panicked: not allowed" }
+          }
+        }
+      }
+    },
+    "transaction_outcome": { "id": "9FtHUFBQsZ2MG77K3x3MJ9wjX3UT8zE1TczCrhZEcG8U" }
+  }
+}"#;
+
+const SEND_TX_INVALID_NONCE: &str = r#"{
+  "jsonrpc": "2.0",
+  "id": "tatchi-signer",
+  "error": {
+    "name": "HANDLER_ERROR",
+    "cause": { "name": "INVALID_TRANSACTION", "info": {} },
+    "code": -32000,
+    "message": "Server error",
+    "data": {
+      "TxExecutionError": {
+        "InvalidTxError": { "InvalidNonce": { "ak_nonce": 7, "tx_nonce": 5 } }
+      }
+    }
+  }
+}"#;
+
+const SEND_TX_TIMEOUT: &str = r#"{
+  "jsonrpc": "2.0",
+  "id": "tatchi-signer",
+  "error": {
+    "name": "HANDLER_ERROR",
+    "cause": { "name": "TIMEOUT_ERROR", "info": {} },
+    "code": -32000,
+    "message": "Server error",
+    "data": "Timeout"
+  }
+}"#;
+
+#[test]
+fn outcome_subset_deserializes_and_maps_to_final_status() {
+    let none = parse_response(SEND_TX_NONE).unwrap();
+    assert_eq!(none.final_execution_status.as_deref(), Some("NONE"));
+    assert!(none.status.is_none());
+    assert_eq!(
+        classify_outcome(&none, WaitUntil::None),
+        Some((TxFinalStatus::Submitted, None))
+    );
+    // A bare acceptance is not enough for `executed`: keep polling.
+    assert_eq!(classify_outcome(&none, WaitUntil::Executed), None);
+
+    let executed = parse_response(TX_EXECUTED_SUCCESS).unwrap();
+    assert_eq!(
+        executed.status,
+        Some(FinalExecutionStatusView::SuccessValue(String::new()))
+    );
+    assert_eq!(
+        executed.transaction_outcome.map(|o| o.id).as_deref(),
+        Some("9FtHUFBQsZ2MG77K3x3MJ9wjX3UT8zE1TczCrhZEcG8U")
+    );
+    let executed = parse_response(TX_EXECUTED_SUCCESS).unwrap();
+    assert_eq!(
+        classify_outcome(&executed, WaitUntil::Executed),
+        Some((TxFinalStatus::Executed, None))
+    );
+}
+
+#[test]
+fn action_failure_reports_readable_error_detail() {
+    let failed = parse_response(TX_ACTION_FAILURE).unwrap();
+    let (status, detail) = classify_outcome(&failed, WaitUntil::Executed).unwrap();
+    assert_eq!(status, TxFinalStatus::Failed);
+    assert_eq!(
+        detail.as_deref(),
+        Some(
+            "ActionError at action 0: FunctionCallError.ExecutionError: \
+             Smart contract panicked: not allowed"
+        )
+    );
+}
+
+#[test]
+fn rpc_errors_keep_cause_and_flag_pending_timeouts() {
+    let err = parse_response(SEND_TX_INVALID_NONCE).unwrap_err();
+    assert_eq!(
+        err,
+        NearRpcError::Rpc {
+            name: "HANDLER_ERROR".to_string(),
+            cause: Some("INVALID_TRANSACTION".to_string()),
+            message: "TxExecutionError.InvalidTxError.InvalidNonce { ak_nonce: 7, tx_nonce: 5 }"
+                .to_string(),
+        }
+    );
+    assert!(!err.is_pending());
+
+    let timeout = parse_response(SEND_TX_TIMEOUT).unwrap_err();
+    assert!(timeout.is_pending());
+    assert_eq!(
+        timeout.to_string(),
+        "NEAR RPC HANDLER_ERROR (TIMEOUT_ERROR): Timeout"
+    );
+}
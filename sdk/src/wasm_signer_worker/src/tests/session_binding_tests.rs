@@ -0,0 +1,84 @@
+//! Binding of WrapKeySeed port deliveries to the session that requested them.
+//!
+//! The VRF worker is played by `verify_session_binding`, which the port's onmessage runs on
+//! every delivery; the browser suite (`wrap_key_handshake_tests`) covers the port itself.
+
+use crate::error::SessionBindingError;
+use crate::wrap_key_handshake::{
+    check_session_intent_digest, clear_signing_session, session_binding_nonce,
+    verify_session_binding,
+};
+
+const INTENT_DIGEST: &str = "intent-digest-b64u";
+
+#[test]
+fn matched_binding_is_accepted() {
+    let sid = "session-binding-matched";
+    let nonce = session_binding_nonce(sid).unwrap();
+    assert_eq!(nonce.len(), 22, "16 bytes, base64url without padding");
+    assert_eq!(
+        session_binding_nonce(sid).unwrap(),
+        nonce,
+        "issued once per session"
+    );
+
+    assert_eq!(
+        verify_session_binding(sid, Some(&nonce), Some(INTENT_DIGEST)),
+        Ok(())
+    );
+    assert_eq!(check_session_intent_digest(sid, INTENT_DIGEST), Ok(()));
+    assert_eq!(
+        check_session_intent_digest(sid, "another-intent"),
+        Err(SessionBindingError::IntentDigestMismatch {
+            session_id: sid.to_string()
+        })
+    );
+    clear_signing_session(sid.to_string());
+}
+
+#[test]
+fn swapped_port_is_rejected() {
+    let (sid_a, sid_b) = ("session-binding-swap-a", "session-binding-swap-b");
+    let nonce_a = session_binding_nonce(sid_a).unwrap();
+    let nonce_b = session_binding_nonce(sid_b).unwrap();
+    assert_ne!(nonce_a, nonce_b);
+
+    // Session B's material arriving on session A's port.
+    let err = verify_session_binding(sid_a, Some(&nonce_b), Some(INTENT_DIGEST)).unwrap_err();
+    assert_eq!(
+        err,
+        SessionBindingError::NonceMismatch {
+            session_id: sid_a.to_string()
+        }
+    );
+    assert!(err.to_string().starts_with("SessionBindingMismatch"));
+    // A rejected delivery does not bind its intent digest.
+    assert_eq!(check_session_intent_digest(sid_a, "another-intent"), Ok(()));
+
+    // Once a nonce is issued, a delivery without one is no longer legacy.
+    assert_eq!(
+        verify_session_binding(sid_b, None, None),
+        Err(SessionBindingError::MissingNonce {
+            session_id: sid_b.to_string()
+        })
+    );
+    clear_signing_session(sid_a.to_string());
+    clear_signing_session(sid_b.to_string());
+}
+
+#[test]
+fn legacy_payload_is_accepted_only_without_an_issued_nonce() {
+    let sid = "session-binding-legacy";
+    assert_eq!(verify_session_binding(sid, None, None), Ok(()));
+    assert_eq!(
+        verify_session_binding(sid, Some("unissued-nonce"), None),
+        Err(SessionBindingError::UnexpectedNonce {
+            session_id: sid.to_string()
+        })
+    );
+
+    // Clearing the session forgets its nonce, so the id is legacy again.
+    session_binding_nonce(sid).unwrap();
+    clear_signing_session(sid.to_string());
+    assert_eq!(verify_session_binding(sid, None, None), Ok(()));
+}
@@ -0,0 +1,254 @@
+use std::cell::Cell;
+
+use zeroize::Zeroizing;
+
+use crate::error::SessionKeyReuseError;
+use crate::session_key_cache::{
+    clear_session_key, session_key_or_decrypt, session_key_reuse_status,
+    set_session_key_reuse_policy, KeyReuseExhaustion, KeyReuseLookup, KeyReusePolicy, KeySource,
+    SessionKeyCache,
+};
+
+fn source(account: &str) -> KeySource {
+    KeySource {
+        near_account_id: account.to_string(),
+        credential_id: Some("cred-1".to_string()),
+        encrypted_private_key_data: format!("ciphertext-{account}"),
+    }
+}
+
+fn policy(max_operations: u32, ttl_ms: u64, on_exhausted: KeyReuseExhaustion) -> KeyReusePolicy {
+    KeyReusePolicy {
+        allow_key_reuse_within_session: true,
+        max_operations,
+        ttl_ms,
+        on_exhausted,
+    }
+}
+
+fn secret(byte: u8) -> Zeroizing<[u8; 32]> {
+    Zeroizing::new([byte; 32])
+}
+
+fn reused(lookup: KeyReuseLookup) -> Option<[u8; 32]> {
+    match lookup {
+        KeyReuseLookup::Reuse(secret) => Some(*secret),
+        _ => None,
+    }
+}
+
+#[test]
+fn no_policy_decrypts_every_operation() {
+    let mut cache = SessionKeyCache::default();
+    for _ in 0..3 {
+        assert!(matches!(
+            cache.lookup("session-a", &source("alice.near"), 0).unwrap(),
+            KeyReuseLookup::Decrypt
+        ));
+    }
+
+    // A policy without the explicit opt-in is the same as none.
+    cache.set_policy(
+        "session-a",
+        KeyReusePolicy {
+            allow_key_reuse_within_session: false,
+            ..policy(8, 60_000, KeyReuseExhaustion::Redecrypt)
+        },
+    );
+    assert!(matches!(
+        cache.lookup("session-a", &source("alice.near"), 0).unwrap(),
+        KeyReuseLookup::Decrypt
+    ));
+    assert!(!cache.status("session-a", 0).enabled);
+}
+
+#[test]
+fn reuses_within_budget_then_redecrypts() {
+    let mut cache = SessionKeyCache::default();
+    cache.set_policy(
+        "session-a",
+        policy(3, 60_000, KeyReuseExhaustion::Redecrypt),
+    );
+
+    assert!(matches!(
+        cache
+            .lookup("session-a", &source("alice.near"), 1_000)
+            .unwrap(),
+        KeyReuseLookup::DecryptAndStore
+    ));
+    cache.store("session-a", source("alice.near"), secret(7), 1_000);
+    assert_eq!(cache.status("session-a", 1_000).remaining_operations, 2);
+
+    for remaining in [1, 0] {
+        let lookup = cache
+            .lookup("session-a", &source("alice.near"), 2_000)
+            .unwrap();
+        assert_eq!(reused(lookup), Some([7u8; 32]));
+        assert_eq!(
+            cache.status("session-a", 2_000).remaining_operations,
+            remaining
+        );
+    }
+    assert!(!cache.status("session-a", 2_000).key_cached);
+
+    assert!(matches!(
+        cache
+            .lookup("session-a", &source("alice.near"), 2_000)
+            .unwrap(),
+        KeyReuseLookup::Decrypt
+    ));
+}
+
+#[test]
+fn exhausted_budget_fails_under_fail_policy() {
+    let mut cache = SessionKeyCache::default();
+    cache.set_policy("session-a", policy(2, 60_000, KeyReuseExhaustion::Fail));
+    cache.store("session-a", source("alice.near"), secret(7), 0);
+    assert!(reused(cache.lookup("session-a", &source("alice.near"), 0).unwrap()).is_some());
+
+    let err = cache
+        .lookup("session-a", &source("alice.near"), 0)
+        .err()
+        .expect("budget exhausted");
+    assert_eq!(
+        err,
+        SessionKeyReuseError::BudgetExhausted {
+            session_id: "session-a".to_string()
+        }
+    );
+}
+
+#[test]
+fn ttl_expiry_drops_key() {
+    let mut cache = SessionKeyCache::default();
+    cache.set_policy("session-a", policy(8, 5_000, KeyReuseExhaustion::Redecrypt));
+    cache.store("session-a", source("alice.near"), secret(7), 10_000);
+    assert_eq!(
+        cache.status("session-a", 14_999).expires_at_ms,
+        Some(15_000)
+    );
+    assert!(reused(
+        cache
+            .lookup("session-a", &source("alice.near"), 14_999)
+            .unwrap()
+    )
+    .is_some());
+
+    assert!(matches!(
+        cache
+            .lookup("session-a", &source("alice.near"), 15_000)
+            .unwrap(),
+        KeyReuseLookup::Decrypt
+    ));
+    let status = cache.status("session-a", 15_000);
+    assert!(!status.key_cached);
+    assert_eq!(status.remaining_operations, 0);
+
+    cache.set_policy("session-b", policy(8, 5_000, KeyReuseExhaustion::Fail));
+    cache.store("session-b", source("alice.near"), secret(7), 10_000);
+    let err = cache
+        .lookup("session-b", &source("alice.near"), 20_000)
+        .err()
+        .expect("expired");
+    assert_eq!(
+        err,
+        SessionKeyReuseError::Expired {
+            session_id: "session-b".to_string(),
+            expired_at_ms: 15_000,
+        }
+    );
+}
+
+#[test]
+fn policy_is_clamped_to_config_limits() {
+    let mut cache = SessionKeyCache::default();
+    cache.set_policy(
+        "session-a",
+        policy(u32::MAX, u64::MAX, KeyReuseExhaustion::Redecrypt),
+    );
+    cache.store("session-a", source("alice.near"), secret(7), 0);
+    let status = cache.status("session-a", 0);
+    assert_eq!(
+        status.remaining_operations,
+        crate::config::SESSION_KEY_REUSE_MAX_OPERATIONS - 1
+    );
+    assert_eq!(
+        status.expires_at_ms,
+        Some(crate::config::SESSION_KEY_REUSE_MAX_TTL_MS)
+    );
+}
+
+#[test]
+fn never_reuses_across_sessions_or_envelopes() {
+    let mut cache = SessionKeyCache::default();
+    cache.set_policy(
+        "session-a",
+        policy(8, 60_000, KeyReuseExhaustion::Redecrypt),
+    );
+    cache.store("session-a", source("alice.near"), secret(7), 0);
+
+    assert!(matches!(
+        cache.lookup("session-b", &source("alice.near"), 0).unwrap(),
+        KeyReuseLookup::Decrypt
+    ));
+    assert!(matches!(
+        cache.lookup("session-a", &source("bob.near"), 0).unwrap(),
+        KeyReuseLookup::Decrypt
+    ));
+    assert_eq!(cache.status("session-a", 0).remaining_operations, 7);
+}
+
+#[test]
+fn clear_session_zeroizes_cached_key() {
+    let mut cache = SessionKeyCache::default();
+    cache.set_policy(
+        "session-a",
+        policy(8, 60_000, KeyReuseExhaustion::Redecrypt),
+    );
+    cache.store("session-a", source("alice.near"), secret(7), 0);
+
+    let cleared = cache.clear_session("session-a").expect("cached key");
+    assert_eq!(*cleared, [0u8; 32]);
+    assert!(!cache.status("session-a", 0).enabled);
+    assert!(cache.clear_session("session-a").is_none());
+
+    // Installing a new policy for the session also discards the old key.
+    cache.set_policy(
+        "session-b",
+        policy(8, 60_000, KeyReuseExhaustion::Redecrypt),
+    );
+    cache.store("session-b", source("alice.near"), secret(7), 0);
+    cache.set_policy(
+        "session-b",
+        policy(8, 60_000, KeyReuseExhaustion::Redecrypt),
+    );
+    assert!(!cache.status("session-b", 0).key_cached);
+}
+
+#[test]
+fn session_key_or_decrypt_decrypts_once_per_session() {
+    let decryptions = Cell::new(0);
+    let decrypt = || {
+        decryptions.set(decryptions.get() + 1);
+        Ok(secret(9))
+    };
+
+    set_session_key_reuse_policy(
+        "session-reuse",
+        policy(4, 60_000, KeyReuseExhaustion::Redecrypt),
+    );
+    for _ in 0..4 {
+        let key =
+            session_key_or_decrypt("session-reuse", source("alice.near"), 0, decrypt).unwrap();
+        assert_eq!(*key, [9u8; 32]);
+    }
+    assert_eq!(decryptions.get(), 1);
+
+    session_key_or_decrypt("session-reuse", source("alice.near"), 0, decrypt).unwrap();
+    assert_eq!(decryptions.get(), 2);
+
+    clear_session_key("session-reuse");
+    assert!(!session_key_reuse_status("session-reuse", 0).enabled);
+    session_key_or_decrypt("session-reuse", source("alice.near"), 0, decrypt).unwrap();
+    assert_eq!(decryptions.get(), 3);
+}
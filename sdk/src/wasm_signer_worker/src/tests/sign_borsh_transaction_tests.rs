@@ -0,0 +1,111 @@
+use ed25519_dalek::{Signature, SigningKey, Verifier};
+use futures::executor::block_on;
+
+use crate::config::NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL;
+use crate::crypto::{encrypt_data_chacha20, WrapKey};
+use crate::encoders::B64UrlBytes;
+use crate::error::BorshTransactionError;
+use crate::handlers::handle_sign_borsh_transaction::{
+    decode_borsh_transaction, sign_borsh_transaction, SignBorshTransactionRequest,
+};
+use crate::tests::fixtures::{wrap_key, NEAR_PRIVATE_KEY};
+use crate::transaction::{build_transaction_with_actions, calculate_transaction_hash};
+use crate::types::{CredentialKeyEnvelope, DecryptionPayload, NearAction, Transaction};
+
+const ACCOUNT_ID: &str = "alice.testnet";
+const CREDENTIAL_ID: &str = "platform-passkey";
+
+fn decryption(wrap_key: &WrapKey) -> DecryptionPayload {
+    let kek = wrap_key.derive_kek_for_credential(CREDENTIAL_ID).unwrap();
+    let encrypted = encrypt_data_chacha20(NEAR_PRIVATE_KEY, &kek).unwrap();
+    DecryptionPayload::new(String::new(), String::new()).with_credential_envelopes(vec![
+        CredentialKeyEnvelope {
+            credential_id: CREDENTIAL_ID.to_string(),
+            encrypted_private_key_data: encrypted.encrypted_near_key_data_b64u,
+            encrypted_private_key_chacha20_nonce_b64u: encrypted.chacha20_nonce_b64u,
+            version: NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL,
+            aad_context: None,
+        },
+    ])
+}
+
+fn unsigned_transfer(public_key_bytes: &[u8; 32]) -> Transaction {
+    build_transaction_with_actions(
+        ACCOUNT_ID,
+        "bob.testnet",
+        42,
+        &[0x33u8; 32],
+        public_key_bytes,
+        vec![NearAction::Transfer { deposit: 1 }],
+    )
+    .unwrap()
+}
+
+fn request(transaction: &Transaction, wrap_key: &WrapKey) -> SignBorshTransactionRequest {
+    SignBorshTransactionRequest {
+        session_id: "sign-borsh-session".to_string(),
+        transaction_borsh_b64u: B64UrlBytes(borsh::to_vec(transaction).unwrap()),
+        decryption: decryption(wrap_key),
+        credential: None,
+        credential_id: Some(CREDENTIAL_ID.to_string()),
+    }
+}
+
+#[test]
+fn signs_a_prebuilt_transaction_with_the_session_key() {
+    let wrap_key = wrap_key();
+    let verifying_key = SigningKey::from_bytes(&[0x07u8; 32]).verifying_key();
+    let transaction = unsigned_transfer(&verifying_key.to_bytes());
+
+    let (transaction_hash, signed) = block_on(sign_borsh_transaction(
+        &request(&transaction, &wrap_key),
+        &wrap_key,
+    ))
+    .expect("matching signer key signs");
+    assert_eq!(
+        transaction_hash,
+        calculate_transaction_hash(&signed.to_borsh_bytes().unwrap())
+    );
+    // The caller's bytes are signed as given.
+    assert_eq!(signed.transaction, transaction);
+    let (hash, _size) = transaction.get_hash_and_size();
+    let signature = Signature::from_bytes(&signed.signature.signature_data);
+    assert!(verifying_key.verify(&hash.0, &signature).is_ok());
+}
+
+#[test]
+fn rejects_a_transaction_for_another_signer_key() {
+    let wrap_key = wrap_key();
+    let other_key = SigningKey::from_bytes(&[0x08u8; 32])
+        .verifying_key()
+        .to_bytes();
+    let transaction = unsigned_transfer(&other_key);
+
+    let err = block_on(sign_borsh_transaction(
+        &request(&transaction, &wrap_key),
+        &wrap_key,
+    ))
+    .expect_err("mismatched signer key must be rejected");
+    assert!(err.starts_with("SignerPublicKeyMismatch"), "{}", err);
+    assert!(err.contains(&bs58::encode(other_key).into_string()));
+}
+
+#[test]
+fn rejects_malformed_or_padded_borsh() {
+    let err = serde_json::from_value::<SignBorshTransactionRequest>(serde_json::json!({
+        "sessionId": "sign-borsh-session",
+        "transactionBorshB64u": "not base64url!",
+        "decryption": {},
+    }))
+    .unwrap_err();
+    assert!(err.to_string().contains("expected base64url"), "{}", err);
+
+    let transaction = unsigned_transfer(&[0x11u8; 32]);
+    let mut bytes = borsh::to_vec(&transaction).unwrap();
+    assert_eq!(decode_borsh_transaction(&bytes), Ok(transaction));
+    bytes.push(0);
+    assert!(matches!(
+        decode_borsh_transaction(&bytes),
+        Err(BorshTransactionError::InvalidTransaction(_))
+    ));
+}
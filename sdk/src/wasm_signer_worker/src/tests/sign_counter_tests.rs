@@ -0,0 +1,181 @@
+use crate::cose::parse_authenticator_data_sign_count;
+use crate::crypto::WrapKey;
+use crate::encoders::base64_url_encode;
+use crate::error::SignCountError;
+use crate::handlers::{handle_get_state_changes_since, GetStateChangesSinceRequest};
+use crate::sign_counter::{
+    check_assertion_sign_count, open_sign_counter_snapshot, seal_sign_counter_snapshot,
+    stored_sign_count, SignCountCheck, SignCounterSnapshot, SignCounterStore,
+};
+use crate::state_changes::{state_version, StateChangeKind};
+use crate::types::{WebAuthnAuthenticationCredential, WebAuthnAuthenticationResponse};
+
+/// rpIdHash(32) + flags (UP | UV) + big-endian signCount, as in an assertion.
+fn authenticator_data(sign_count: u32) -> Vec<u8> {
+    let mut auth_data = vec![0x11u8; 32];
+    auth_data.push(0x05);
+    auth_data.extend_from_slice(&sign_count.to_be_bytes());
+    auth_data
+}
+
+fn assertion(credential_id: &str, sign_count: u32) -> WebAuthnAuthenticationCredential {
+    WebAuthnAuthenticationCredential {
+        id: credential_id.to_string(),
+        raw_id: credential_id.to_string(),
+        response: WebAuthnAuthenticationResponse {
+            client_data_json: "client-data".to_string(),
+            authenticator_data: base64_url_encode(&authenticator_data(sign_count)),
+            signature: "signature".to_string(),
+            user_handle: None,
+        },
+        authenticator_attachment: None,
+        auth_type: "public-key".to_string(),
+    }
+}
+
+fn wrap_key(seed: u8) -> WrapKey {
+    WrapKey {
+        wrap_key_seed: base64_url_encode(&[seed; 32]),
+        wrap_key_salt: base64_url_encode(&[9u8; 32]),
+    }
+}
+
+fn regression(stored: u32, presented: u32) -> SignCountError {
+    SignCountError::AuthenticatorCounterRegression {
+        credential_id: "cred-1".to_string(),
+        stored,
+        presented,
+    }
+}
+
+#[test]
+fn sign_count_is_read_from_authenticator_data() {
+    assert_eq!(
+        parse_authenticator_data_sign_count(&authenticator_data(0x0102_0304)),
+        Ok(0x0102_0304)
+    );
+    assert_eq!(assertion("cred-1", 42).response.sign_count(), Ok(42));
+    assert!(parse_authenticator_data_sign_count(&[0u8; 36]).is_err());
+}
+
+#[test]
+fn increasing_counters_are_accepted_and_stored() {
+    let mut store = SignCounterStore::default();
+    assert_eq!(store.observe("cred-1", 1), Ok(SignCountCheck::Advanced));
+    assert_eq!(store.observe("cred-1", 2), Ok(SignCountCheck::Advanced));
+    assert_eq!(store.observe("cred-1", 10), Ok(SignCountCheck::Advanced));
+    assert_eq!(store.stored("cred-1"), Some(10));
+}
+
+#[test]
+fn equal_counter_is_accepted() {
+    let mut store = SignCounterStore::default();
+    store.observe("cred-1", 5).unwrap();
+    assert_eq!(store.observe("cred-1", 5), Ok(SignCountCheck::Unchanged));
+    assert_eq!(store.stored("cred-1"), Some(5));
+}
+
+#[test]
+fn regressed_counter_is_rejected_and_not_stored() {
+    let mut store = SignCounterStore::default();
+    store.observe("cred-1", 7).unwrap();
+    assert_eq!(store.observe("cred-1", 3), Err(regression(7, 3)));
+    assert_eq!(store.stored("cred-1"), Some(7));
+    // Other credentials keep their own counters.
+    assert_eq!(store.observe("cred-2", 3), Ok(SignCountCheck::Advanced));
+    assert!(regression(7, 3)
+        .to_string()
+        .starts_with("AuthenticatorCounterRegression"));
+}
+
+#[test]
+fn always_zero_counters_never_trigger() {
+    let mut store = SignCounterStore::default();
+    for _ in 0..3 {
+        assert_eq!(store.observe("cred-1", 0), Ok(SignCountCheck::ZeroCounter));
+    }
+    assert_eq!(store.stored("cred-1"), None);
+
+    // A zero after a stored counter is not a regression either.
+    store.observe("cred-1", 4).unwrap();
+    assert_eq!(store.observe("cred-1", 0), Ok(SignCountCheck::ZeroCounter));
+    assert_eq!(store.stored("cred-1"), Some(4));
+}
+
+#[test]
+fn acknowledged_restore_accepts_one_lower_counter() {
+    let mut store = SignCounterStore::default();
+    store.observe("cred-1", 20).unwrap();
+    store.acknowledge_restore("cred-1");
+    assert_eq!(
+        store.observe("cred-1", 2),
+        Ok(SignCountCheck::RestoreAccepted)
+    );
+    assert_eq!(store.stored("cred-1"), Some(2));
+    assert_eq!(store.observe("cred-1", 1), Err(regression(2, 1)));
+
+    // An acknowledgement not needed by the next counter does not linger.
+    store.acknowledge_restore("cred-1");
+    assert_eq!(store.observe("cred-1", 3), Ok(SignCountCheck::Advanced));
+    assert_eq!(store.observe("cred-1", 1), Err(regression(3, 1)));
+}
+
+#[test]
+fn regression_fails_the_check_and_records_a_state_change() {
+    assert_eq!(
+        check_assertion_sign_count(&assertion("cred-1", 9), Some("alice.near")),
+        Ok(SignCountCheck::Advanced)
+    );
+    let before = state_version();
+    let err = check_assertion_sign_count(&assertion("cred-1", 8), Some("alice.near"))
+        .expect_err("regressed counter must fail");
+    assert_eq!(err, String::from(regression(9, 8)));
+    assert_eq!(stored_sign_count("cred-1"), Some(9));
+
+    let changes = handle_get_state_changes_since(GetStateChangesSinceRequest { version: before });
+    assert_eq!(changes.events.len(), 1);
+    assert_eq!(
+        changes.events[0].kind,
+        StateChangeKind::AuthenticatorCounterRegression
+    );
+    assert_eq!(changes.events[0].account_id.as_deref(), Some("alice.near"));
+}
+
+#[test]
+fn sealed_snapshot_round_trips_and_merges_upwards() {
+    let mut store = SignCounterStore::default();
+    store.observe("cred-1", 12).unwrap();
+    store.observe("cred-2", 3).unwrap();
+    let sealed = seal_sign_counter_snapshot(&store.snapshot(), &wrap_key(1)).unwrap();
+    assert!(!sealed.ciphertext_b64u.contains("cred-1"));
+
+    let opened = open_sign_counter_snapshot(&sealed, &wrap_key(1)).unwrap();
+    assert_eq!(opened, store.snapshot());
+
+    // A restarted worker picks the counters back up; regressions are caught again.
+    let mut restarted = SignCounterStore::default();
+    restarted.observe("cred-2", 5).unwrap();
+    assert_eq!(restarted.merge(opened), 1);
+    assert_eq!(restarted.stored("cred-1"), Some(12));
+    assert_eq!(restarted.stored("cred-2"), Some(5));
+    assert_eq!(restarted.observe("cred-1", 11), Err(regression(12, 11)));
+}
+
+#[test]
+fn sealed_snapshot_rejects_other_keys_and_tampering() {
+    let snapshot = SignCounterSnapshot {
+        counters: [("cred-1".to_string(), 4)].into_iter().collect(),
+    };
+    let sealed = seal_sign_counter_snapshot(&snapshot, &wrap_key(1)).unwrap();
+    assert!(open_sign_counter_snapshot(&sealed, &wrap_key(2)).is_err());
+
+    let mut tampered = sealed.clone();
+    tampered.nonce_b64u = base64_url_encode(&[0u8; 12]);
+    assert!(open_sign_counter_snapshot(&tampered, &wrap_key(1)).is_err());
+
+    let mut future = sealed;
+    future.version += 1;
+    assert!(open_sign_counter_snapshot(&future, &wrap_key(1))
+        .unwrap_err()
+        .contains("Unsupported signCount snapshot version"));
+}
@@ -0,0 +1,113 @@
+use sha2::{Digest, Sha256};
+
+use crate::signable_message::{
+    compute_signable_digest, ensure_known_threshold_purpose, signable_message_bytes,
+    SignableMessageKind, DELEGATE_ACTION_DISCRIMINANT, NEP_413_DISCRIMINANT,
+    SIGNABLE_MESSAGE_KINDS,
+};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// borsh({ message: "hello", recipient: "alice.near", nonce: [0; 32], state: None })
+fn nep413_fixture_payload() -> Vec<u8> {
+    #[derive(borsh::BorshSerialize)]
+    struct Nep413Payload {
+        message: String,
+        recipient: String,
+        nonce: [u8; 32],
+        state: Option<String>,
+    }
+    borsh::to_vec(&Nep413Payload {
+        message: "hello".to_string(),
+        recipient: "alice.near".to_string(),
+        nonce: [0u8; 32],
+        state: None,
+    })
+    .unwrap()
+}
+
+/// Prefix bytes match near-primitives `MessageDiscriminant` borsh encodings.
+#[test]
+fn discriminant_prefix_bytes_are_pinned() {
+    assert_eq!(DELEGATE_ACTION_DISCRIMINANT, 1_073_742_190);
+    assert_eq!(NEP_413_DISCRIMINANT, 2_147_484_061);
+
+    assert_eq!(
+        signable_message_bytes(SignableMessageKind::DelegateAction, &[]),
+        vec![0x6e, 0x01, 0x00, 0x40]
+    );
+    assert_eq!(
+        signable_message_bytes(SignableMessageKind::Nep413, &[]),
+        vec![0x9d, 0x01, 0x00, 0x80]
+    );
+    // Transactions are signed unprefixed
+    assert_eq!(
+        signable_message_bytes(SignableMessageKind::Transaction, &[0xaa]),
+        vec![0xaa]
+    );
+}
+
+#[test]
+fn signable_digests_match_fixtures() {
+    let payload = nep413_fixture_payload();
+    assert_eq!(
+        hex(&payload),
+        "0500000068656c6c6f0a000000616c6963652e6e656172\
+         000000000000000000000000000000000000000000000000000000000000000000"
+    );
+    assert_eq!(
+        hex(&compute_signable_digest(SignableMessageKind::Nep413, &payload)),
+        "b38f2ed3686defec7c645915af67fcfd48844ac11720bc7f51b517c5a60003f6"
+    );
+    assert_eq!(
+        hex(&compute_signable_digest(SignableMessageKind::DelegateAction, &payload)),
+        "1adb022080b056dcddb693c46c2236b5de5122d5524a7f0b21ccbd812671d65d"
+    );
+
+    let tx_digest: [u8; 32] = Sha256::digest(&payload).into();
+    assert_eq!(
+        compute_signable_digest(SignableMessageKind::Transaction, &payload),
+        tx_digest
+    );
+}
+
+#[test]
+fn every_kind_is_registered_once() {
+    for kind in [
+        SignableMessageKind::Transaction,
+        SignableMessageKind::DelegateAction,
+        SignableMessageKind::Nep413,
+        SignableMessageKind::VerificationAttestation,
+        SignableMessageKind::AccountCard,
+    ] {
+        let matches = SIGNABLE_MESSAGE_KINDS.iter().filter(|s| s.kind == kind).count();
+        assert_eq!(matches, 1, "{:?} must appear exactly once", kind);
+        assert_eq!(
+            SignableMessageKind::from_threshold_purpose(kind.threshold_purpose()),
+            Some(kind)
+        );
+    }
+}
+
+#[test]
+fn threshold_mode_rejects_unknown_digest_kinds() {
+    assert_eq!(
+        ensure_known_threshold_purpose("near_tx"),
+        Ok(SignableMessageKind::Transaction)
+    );
+    assert_eq!(
+        ensure_known_threshold_purpose(" nep461_delegate "),
+        Ok(SignableMessageKind::DelegateAction)
+    );
+    assert_eq!(
+        ensure_known_threshold_purpose("nep413"),
+        Ok(SignableMessageKind::Nep413)
+    );
+
+    for purpose in ["raw_digest", "", "NEAR_TX"] {
+        let err = ensure_known_threshold_purpose(purpose).unwrap_err();
+        assert!(err.contains("unknown kind"), "unexpected error: {err}");
+    }
+}
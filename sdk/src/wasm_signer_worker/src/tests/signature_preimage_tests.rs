@@ -0,0 +1,201 @@
+use ed25519_dalek::{Signer, SigningKey};
+use sha2::{Digest, Sha256};
+
+use crate::actions::ActionParams;
+use crate::encoders::{base64_url_decode, hash_delegate_action};
+use crate::handlers::handle_sign_nep413_message::nep413_payload_bytes;
+use crate::signable_message::{compute_signable_digest, SignableMessageKind};
+use crate::signature_preimage::{
+    verify_signature_over_preimage_inner, SignaturePreimage, SignaturePreimageFormat,
+    DIGEST_PREIMAGE_KIND,
+};
+use crate::transaction::{
+    append_transaction_signature, build_actions_from_params, build_transaction_with_actions,
+    serialize_transaction_for_signing, signed_transaction_rpc_payload,
+};
+use crate::types::{AccountId, DelegateAction, PublicKey};
+
+fn signing_key() -> SigningKey {
+    SigningKey::from_bytes(&[7u8; 32])
+}
+
+/// Decode the entry and check it the way an external auditor would.
+fn verify_entry(key: &SigningKey, entry: &SignaturePreimage, signature: &[u8; 64]) -> bool {
+    let preimage = base64_url_decode(&entry.signature_preimage_b64u).unwrap();
+    let kind = match entry.preimage_format {
+        SignaturePreimageFormat::Message => entry.kind.as_str(),
+        SignaturePreimageFormat::Digest => DIGEST_PREIMAGE_KIND,
+    };
+    verify_signature_over_preimage_inner(
+        &key.verifying_key().to_bytes(),
+        &preimage,
+        kind,
+        signature,
+    )
+    .unwrap()
+}
+
+#[test]
+fn transaction_preimage_verifies_and_hashes_to_the_transaction_hash() {
+    let key = signing_key();
+    let actions = build_actions_from_params(vec![ActionParams::Transfer {
+        deposit: "1".to_string(),
+    }])
+    .unwrap();
+    let transaction = build_transaction_with_actions(
+        "alice.near",
+        "bob.near",
+        1,
+        &[5u8; 32],
+        &key.verifying_key().to_bytes(),
+        actions,
+    )
+    .unwrap();
+
+    let (transaction_bytes, hash) = serialize_transaction_for_signing(transaction).unwrap();
+    let entry = SignaturePreimage::message(
+        SignableMessageKind::Transaction,
+        &transaction_bytes,
+        &hash.0,
+    );
+    let signature = key.sign(&hash.0).to_bytes();
+    assert!(verify_entry(&key, &entry, &signature));
+
+    // The preimage is the unsigned borsh Transaction, and its digest is the NEAR tx hash.
+    let preimage = base64_url_decode(&entry.signature_preimage_b64u).unwrap();
+    assert_eq!(preimage, transaction_bytes);
+    let digest = base64_url_decode(entry.signature_digest_b64u.as_deref().unwrap()).unwrap();
+    assert_eq!(digest, Sha256::digest(&preimage).to_vec());
+
+    let signed = append_transaction_signature(transaction_bytes, &signature).unwrap();
+    let rpc = signed_transaction_rpc_payload(&signed).unwrap();
+    assert_eq!(bs58::decode(&rpc.tx_hash).into_vec().unwrap(), digest);
+}
+
+#[test]
+fn nep413_preimage_carries_the_prefix_and_verifies() {
+    let key = signing_key();
+    let payload = nep413_payload_bytes("hello", "example.com", [9u8; 32], None).unwrap();
+    let digest = compute_signable_digest(SignableMessageKind::Nep413, &payload);
+    let signature = key.sign(&digest).to_bytes();
+
+    let entry = SignaturePreimage::message(SignableMessageKind::Nep413, &payload, &digest);
+    assert_eq!(entry.kind, "nep413");
+    assert!(verify_entry(&key, &entry, &signature));
+
+    let preimage = base64_url_decode(&entry.signature_preimage_b64u).unwrap();
+    assert_eq!(&preimage[4..], payload.as_slice());
+}
+
+#[test]
+fn delegate_preimage_matches_the_delegate_hash() {
+    let key = signing_key();
+    let delegate = DelegateAction {
+        sender_id: "alice.near".parse::<AccountId>().unwrap(),
+        receiver_id: "bob.near".parse::<AccountId>().unwrap(),
+        actions: build_actions_from_params(vec![ActionParams::Transfer {
+            deposit: "1".to_string(),
+        }])
+        .unwrap(),
+        nonce: 3,
+        max_block_height: 1_000,
+        public_key: PublicKey::from_ed25519_bytes(&key.verifying_key().to_bytes()),
+    };
+    let delegate_bytes = borsh::to_vec(&delegate).unwrap();
+    let digest = compute_signable_digest(SignableMessageKind::DelegateAction, &delegate_bytes);
+    assert_eq!(digest, hash_delegate_action(&delegate).unwrap());
+    let signature = key.sign(&digest).to_bytes();
+
+    let entry = SignaturePreimage::message(
+        SignableMessageKind::DelegateAction,
+        &delegate_bytes,
+        &digest,
+    );
+    assert!(verify_entry(&key, &entry, &signature));
+}
+
+#[test]
+fn threshold_preimage_is_the_digest_with_its_kind() {
+    let key = signing_key();
+    let digest = compute_signable_digest(SignableMessageKind::Transaction, b"tx bytes");
+    let signature = key.sign(&digest).to_bytes();
+
+    let entry = SignaturePreimage::digest(SignableMessageKind::Transaction, &digest);
+    assert_eq!(entry.kind, "transaction");
+    assert_eq!(entry.preimage_format, SignaturePreimageFormat::Digest);
+    assert!(entry.signature_digest_b64u.is_none());
+    assert!(verify_entry(&key, &entry, &signature));
+}
+
+#[test]
+fn tampered_or_mislabelled_preimages_do_not_verify() {
+    let key = signing_key();
+    let public_key = key.verifying_key().to_bytes();
+    let payload = nep413_payload_bytes("hello", "example.com", [9u8; 32], None).unwrap();
+    let digest = compute_signable_digest(SignableMessageKind::Nep413, &payload);
+    let signature = key.sign(&digest).to_bytes();
+    let mut preimage = base64_url_decode(
+        &SignaturePreimage::message(SignableMessageKind::Nep413, &payload, &digest)
+            .signature_preimage_b64u,
+    )
+    .unwrap();
+
+    // A NEP-413 preimage presented as a delegate action lacks the delegate prefix.
+    assert!(verify_signature_over_preimage_inner(
+        &public_key,
+        &preimage,
+        "delegateAction",
+        &signature
+    )
+    .is_err());
+    assert!(
+        verify_signature_over_preimage_inner(&public_key, &preimage, "unknown", &signature)
+            .is_err()
+    );
+    assert!(
+        verify_signature_over_preimage_inner(&public_key, &preimage, "digest", &signature).is_err()
+    );
+
+    let last = preimage.len() - 1;
+    preimage[last] ^= 1;
+    assert_eq!(
+        verify_signature_over_preimage_inner(&public_key, &preimage, "nep413", &signature),
+        Ok(false)
+    );
+}
+
+#[test]
+fn results_omit_preimages_unless_requested() {
+    use crate::handlers::handle_sign_nep413_message::SignNep413Result;
+
+    let result = SignNep413Result::new(
+        "alice.near".to_string(),
+        "ed25519:pk".to_string(),
+        "sig".to_string(),
+        None,
+        5,
+        String::new(),
+    );
+    let json = serde_json::to_value(&result).unwrap();
+    assert!(json.get("signaturePreimage").is_none());
+
+    let digest = [1u8; 32];
+    let json = serde_json::to_value(result.with_signature_preimage(SignaturePreimage::digest(
+        SignableMessageKind::Nep413,
+        &digest,
+    )))
+    .unwrap();
+    assert_eq!(json["signaturePreimage"]["kind"], "nep413");
+    assert_eq!(json["signaturePreimage"]["preimageFormat"], "digest");
+    assert!(json["signaturePreimage"]
+        .get("signatureDigestB64u")
+        .is_none());
+}
+
+#[test]
+fn error_messages_redact_preimages() {
+    let message = r#"failed: {"signaturePreimageB64u":"AAAA","kind":"transaction"}"#;
+    let scrubbed = crate::error::scrub_error_message(message);
+    assert!(!scrubbed.contains("AAAA"));
+    assert!(scrubbed.contains(r#""kind":"transaction""#));
+}
@@ -0,0 +1,139 @@
+use zeroize::Zeroizing;
+
+use crate::handlers::{
+    handle_get_state_changes_since, handle_get_state_version, GetStateChangesSinceRequest,
+};
+use crate::session_key_cache::{
+    session_key_or_decrypt, session_key_reuse_status, set_session_key_reuse_policy,
+    KeyReuseExhaustion, KeyReusePolicy, KeySource,
+};
+use crate::state_changes::{state_version, StateChangeKind, StateChangeLog};
+use crate::tests::fixtures::session_wrap_key;
+use crate::wrap_key_handshake::{cache_wrap_key_seed, clear_signing_session};
+
+fn kinds_since(version: u64) -> Vec<StateChangeKind> {
+    handle_get_state_changes_since(GetStateChangesSinceRequest { version })
+        .events
+        .into_iter()
+        .map(|e| e.kind)
+        .collect()
+}
+
+#[test]
+fn since_returns_only_newer_events() {
+    let mut log = StateChangeLog::with_capacity(8);
+    log.record(StateChangeKind::SessionCreated, None, 1.0);
+    log.record(StateChangeKind::SessionConsumed, Some("alice.near"), 2.0);
+    log.record(StateChangeKind::SessionCleared, None, 3.0);
+
+    let changes = log.since(1);
+    assert_eq!(changes.version, 3);
+    assert!(!changes.resync_required);
+    assert_eq!(
+        changes.events.iter().map(|e| e.version).collect::<Vec<_>>(),
+        vec![2, 3]
+    );
+    assert_eq!(changes.events[0].account_id.as_deref(), Some("alice.near"));
+
+    let current = log.since(3);
+    assert!(current.events.is_empty());
+    assert!(!current.resync_required);
+}
+
+#[test]
+fn evicted_or_unknown_versions_require_resync() {
+    let mut log = StateChangeLog::with_capacity(2);
+    for at in 0..5 {
+        log.record(StateChangeKind::SessionConsumed, None, at as f64);
+    }
+    // Versions 4 and 5 are retained; 2 and 3 were evicted.
+    assert!(log.since(1).resync_required);
+    assert!(!log.since(3).resync_required);
+    assert_eq!(log.since(3).events.len(), 2);
+    // A version from another worker instance (ahead of ours).
+    let ahead = log.since(9);
+    assert!(ahead.resync_required);
+    assert!(ahead.events.is_empty());
+}
+
+#[test]
+fn session_lifecycle_bumps_the_worker_version() {
+    let start = state_version();
+    cache_wrap_key_seed("session-state", session_wrap_key());
+    clear_signing_session("session-state".to_string());
+    // Clearing again finds nothing and records nothing.
+    clear_signing_session("session-state".to_string());
+
+    assert_eq!(handle_get_state_version().state_version, start + 2);
+    assert_eq!(
+        kinds_since(start),
+        vec![
+            StateChangeKind::SessionCreated,
+            StateChangeKind::SessionCleared
+        ]
+    );
+}
+
+#[test]
+fn cached_key_timeout_is_recorded_as_auto_lock() {
+    let session_id = "session-autolock";
+    set_session_key_reuse_policy(
+        session_id,
+        KeyReusePolicy {
+            allow_key_reuse_within_session: true,
+            max_operations: 5,
+            ttl_ms: 100,
+            on_exhausted: KeyReuseExhaustion::Redecrypt,
+        },
+    );
+    let source = KeySource {
+        near_account_id: "alice.near".to_string(),
+        credential_id: None,
+        encrypted_private_key_data: "ciphertext".to_string(),
+    };
+    let start = state_version();
+    session_key_or_decrypt(session_id, source.clone(), 0, || {
+        Ok(Zeroizing::new([7u8; 32]))
+    })
+    .unwrap();
+    session_key_or_decrypt(session_id, source, 10, || unreachable!("reused")).unwrap();
+    assert!(!session_key_reuse_status(session_id, 200).key_cached);
+
+    let changes = handle_get_state_changes_since(GetStateChangesSinceRequest { version: start });
+    let kinds: Vec<_> = changes.events.iter().map(|e| e.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            StateChangeKind::SessionConsumed,
+            StateChangeKind::SessionConsumed,
+            StateChangeKind::AutoLock,
+        ]
+    );
+    assert!(changes
+        .events
+        .iter()
+        .all(|e| e.account_id.as_deref() == Some("alice.near")));
+}
+
+#[test]
+fn events_serialize_with_numeric_kinds() {
+    let mut log = StateChangeLog::with_capacity(4);
+    log.record(
+        StateChangeKind::ThresholdSessionChanged,
+        Some("bob.near"),
+        5.0,
+    );
+    log.record(StateChangeKind::SessionCreated, None, 6.0);
+    let json = serde_json::to_value(log.since(0)).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "version": 2,
+            "events": [
+                { "version": 1, "kind": 9, "accountId": "bob.near", "atMs": 5.0 },
+                { "version": 2, "kind": 3, "atMs": 6.0 }
+            ],
+            "resyncRequired": false
+        })
+    );
+}
@@ -0,0 +1,159 @@
+use crate::error::ThresholdConfigError;
+use crate::threshold::signer_config::ThresholdSignerConfigBuilder;
+use crate::types::ThresholdSignerConfig;
+
+fn minimal() -> ThresholdSignerConfigBuilder {
+    ThresholdSignerConfig::builder()
+        .relayer_url("https://relay.example.com")
+        .relayer_key_id("relayer-key-1")
+}
+
+fn build_err(builder: ThresholdSignerConfigBuilder) -> ThresholdConfigError {
+    match builder.build() {
+        Ok(cfg) => panic!("expected an invalid config, got {cfg:?}"),
+        Err(err) => err,
+    }
+}
+
+#[test]
+fn minimal_config_gets_default_participants() {
+    let cfg = ThresholdSignerConfig::builder()
+        .relayer_url("  https://relay.example.com/ ")
+        .relayer_key_id(" relayer-key-1 ")
+        .mpc_session_id("   ")
+        .build()
+        .expect("minimal config");
+
+    assert_eq!(cfg.relayer_url, "https://relay.example.com");
+    assert_eq!(cfg.relayer_key_id, "relayer-key-1");
+    assert_eq!(cfg.client_participant_id, Some(1));
+    assert_eq!(cfg.relayer_participant_id, Some(2));
+    assert_eq!(cfg.participant_ids, Some(vec![1, 2]));
+    assert_eq!(cfg.mpc_session_id, None);
+    assert_eq!(cfg.threshold_session_kind, None);
+    assert!(!cfg.force_session_refresh);
+}
+
+#[test]
+fn session_fields_are_normalized() {
+    let cfg = minimal()
+        .participant_ids(vec![7, 3, 3, 0])
+        .session_kind(" JWT ")
+        .session_jwt(" token ")
+        .session_policy_json(r#"{"ttlMs":60000}"#)
+        .session_refresh_margin_ms(0.0)
+        .build()
+        .expect("valid config");
+
+    assert_eq!(cfg.client_participant_id, Some(3));
+    assert_eq!(cfg.relayer_participant_id, Some(7));
+    assert_eq!(cfg.participant_ids, Some(vec![3, 7]));
+    assert_eq!(cfg.threshold_session_kind.as_deref(), Some("jwt"));
+    assert_eq!(cfg.threshold_session_jwt.as_deref(), Some("token"));
+    assert_eq!(cfg.threshold_session_refresh_margin_ms, Some(0.0));
+}
+
+#[test]
+fn js_shaped_args_deserialize_into_the_builder() {
+    let builder: ThresholdSignerConfigBuilder = serde_json::from_value(serde_json::json!({
+        "relayerUrl": "http://localhost:3000",
+        "relayerKeyId": "ed25519:abc",
+        "thresholdSessionKind": "cookie",
+        "forceSessionRefresh": true,
+    }))
+    .unwrap();
+    let cfg = builder.build().expect("valid config");
+    assert_eq!(cfg.relayer_url, "http://localhost:3000");
+    assert_eq!(cfg.threshold_session_kind.as_deref(), Some("cookie"));
+    assert!(cfg.force_session_refresh);
+}
+
+#[test]
+fn missing_or_malformed_relayer_url_is_rejected() {
+    assert_eq!(
+        build_err(ThresholdSignerConfig::builder().relayer_key_id("k")),
+        ThresholdConfigError::MissingRelayerUrl
+    );
+    assert_eq!(
+        build_err(minimal().relayer_url("relay.example.com")),
+        ThresholdConfigError::InvalidRelayerUrl {
+            url: "relay.example.com".to_string()
+        }
+    );
+    assert_eq!(
+        build_err(minimal().relayer_url("https:///path")),
+        ThresholdConfigError::InvalidRelayerUrl {
+            url: "https:///path".to_string()
+        }
+    );
+}
+
+#[test]
+fn blank_relayer_key_id_is_rejected() {
+    let err = build_err(minimal().relayer_key_id("  "));
+    assert_eq!(err, ThresholdConfigError::MissingRelayerKeyId);
+    assert_eq!(err.to_string(), "threshold-signer: missing relayerKeyId");
+}
+
+#[test]
+fn inconsistent_participants_are_rejected() {
+    let err = build_err(minimal().participants(2, 2));
+    assert!(matches!(
+        err,
+        ThresholdConfigError::InvalidParticipantIds { .. }
+    ));
+    assert!(err.to_string().contains("must differ"), "{err}");
+
+    let err = build_err(minimal().participants(1, 2).participant_ids(vec![1, 3]));
+    assert!(err.to_string().contains("must include"), "{err}");
+
+    let err = build_err(minimal().participant_ids(vec![1, 2, 3]));
+    assert!(err.to_string().contains("more than 2 ids"), "{err}");
+}
+
+#[test]
+fn contradictory_session_settings_are_rejected() {
+    assert_eq!(
+        build_err(minimal().session_kind("oauth")),
+        ThresholdConfigError::InvalidSessionKind {
+            kind: "oauth".to_string()
+        }
+    );
+    assert_eq!(
+        build_err(minimal().session_kind("cookie").session_jwt("token")),
+        ThresholdConfigError::JwtWithCookieSession
+    );
+    assert!(matches!(
+        build_err(minimal().session_policy_json("[1,2]")),
+        ThresholdConfigError::InvalidSessionPolicyJson { .. }
+    ));
+    assert!(matches!(
+        build_err(minimal().session_policy_json("{not json")),
+        ThresholdConfigError::InvalidSessionPolicyJson { .. }
+    ));
+    assert_eq!(
+        build_err(minimal().session_max_clock_skew_ms(-1.0)),
+        ThresholdConfigError::InvalidDuration {
+            field: "thresholdSessionMaxClockSkewMs",
+            value: -1.0
+        }
+    );
+}
+
+#[test]
+fn relayer_concurrency_limit_defaults_to_four_and_rejects_zero() {
+    let cfg = minimal().build().unwrap();
+    assert_eq!(cfg.max_concurrent_relayer_requests, None);
+    assert_eq!(cfg.relayer_request_limit(), 4);
+
+    let cfg = minimal()
+        .max_concurrent_relayer_requests(2)
+        .build()
+        .unwrap();
+    assert_eq!(cfg.relayer_request_limit(), 2);
+
+    assert_eq!(
+        build_err(minimal().max_concurrent_relayer_requests(0)),
+        ThresholdConfigError::InvalidConcurrencyLimit { value: 0 }
+    );
+}
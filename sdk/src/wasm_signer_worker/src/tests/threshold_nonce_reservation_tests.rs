@@ -0,0 +1,103 @@
+use crate::threshold::nonce_reservation::{
+    negotiate_nonce_mode, BatchNonces, NonceReservation, ThresholdNonceMode,
+};
+use crate::types::ThresholdSignerConfig;
+
+const NOW_MS: u64 = 1_700_000_000_000;
+
+fn reservation(start_nonce: u64, count: u32, expires_at_ms: u64) -> NonceReservation {
+    NonceReservation::new("lease-1", start_nonce, count, expires_at_ms, count).unwrap()
+}
+
+#[test]
+fn reserved_range_is_consumed_in_order_across_a_batch() {
+    let mut nonces = BatchNonces::start(7, Some(reservation(500, 3, NOW_MS + 30_000)), NOW_MS);
+    assert_eq!(nonces.lease_id(), Some("lease-1"));
+
+    let stamped: Vec<u64> = (0..3).map(|_| nonces.next_nonce(NOW_MS).unwrap()).collect();
+    assert_eq!(stamped, vec![500, 501, 502]);
+
+    let err = nonces.next_nonce(NOW_MS).unwrap_err();
+    assert!(err.contains("lease-1 exhausted after 3"), "{}", err);
+}
+
+#[test]
+fn access_key_nonces_increment_without_a_reservation() {
+    let mut nonces = BatchNonces::start(7, None, NOW_MS);
+    assert_eq!(nonces.lease_id(), None);
+    assert_eq!(nonces.next_nonce(NOW_MS).unwrap(), 7);
+    assert_eq!(nonces.next_nonce(NOW_MS).unwrap(), 8);
+}
+
+#[test]
+fn lease_expired_before_signing_falls_back_to_access_key_nonces() {
+    let mut nonces = BatchNonces::start(7, Some(reservation(500, 2, NOW_MS)), NOW_MS);
+    assert_eq!(nonces, BatchNonces::AccessKey { next: 7 });
+    assert_eq!(nonces.next_nonce(NOW_MS).unwrap(), 7);
+}
+
+#[test]
+fn lease_expiring_mid_batch_fails_instead_of_mixing_nonce_sources() {
+    let mut nonces = BatchNonces::start(7, Some(reservation(500, 2, NOW_MS + 10)), NOW_MS);
+    assert_eq!(nonces.next_nonce(NOW_MS).unwrap(), 500);
+
+    let err = nonces.next_nonce(NOW_MS + 10).unwrap_err();
+    assert!(err.contains("lease-1 expired mid-batch"), "{}", err);
+}
+
+#[test]
+fn short_or_malformed_reservations_are_rejected() {
+    let err = NonceReservation::new("lease-1", 500, 2, NOW_MS, 3).unwrap_err();
+    assert_eq!(
+        err,
+        "threshold-signer: relayer reserved 2 nonces, batch needs 3"
+    );
+    assert!(NonceReservation::new("  ", 500, 3, NOW_MS, 3).is_err());
+    assert!(NonceReservation::new("lease-1", u64::MAX, 3, NOW_MS, 3).is_err());
+}
+
+#[test]
+fn relayer_reserved_mode_requires_the_relayer_capability() {
+    use ThresholdNonceMode::{AccessKey, RelayerReserved};
+
+    assert_eq!(negotiate_nonce_mode(RelayerReserved, None, None), AccessKey);
+    assert_eq!(
+        negotiate_nonce_mode(RelayerReserved, Some(true), None),
+        RelayerReserved
+    );
+    assert_eq!(
+        negotiate_nonce_mode(RelayerReserved, None, Some(true)),
+        RelayerReserved
+    );
+    // The session response is fresher than keygen.
+    assert_eq!(
+        negotiate_nonce_mode(RelayerReserved, Some(true), Some(false)),
+        AccessKey
+    );
+    assert_eq!(
+        negotiate_nonce_mode(AccessKey, Some(true), Some(true)),
+        AccessKey
+    );
+}
+
+#[test]
+fn nonce_mode_round_trips_through_the_config() {
+    let cfg: ThresholdSignerConfig = serde_json::from_value(serde_json::json!({
+        "relayerUrl": "https://relay.example.com",
+        "relayerKeyId": "relayer-key-1",
+        "nonceMode": "relayerReserved",
+        "relayerNonceReservation": true,
+    }))
+    .unwrap();
+    assert_eq!(cfg.nonce_mode, ThresholdNonceMode::RelayerReserved);
+    assert_eq!(cfg.relayer_nonce_reservation, Some(true));
+
+    let default_cfg: ThresholdSignerConfig = serde_json::from_value(serde_json::json!({
+        "relayerUrl": "https://relay.example.com",
+        "relayerKeyId": "relayer-key-1",
+    }))
+    .unwrap();
+    assert_eq!(default_cfg.nonce_mode, ThresholdNonceMode::AccessKey);
+    let json = serde_json::to_value(&default_cfg).unwrap();
+    assert!(json.get("nonceMode").is_none());
+}
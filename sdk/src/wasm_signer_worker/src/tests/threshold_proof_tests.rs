@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+
+use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+use curve25519_dalek::scalar::Scalar as CurveScalar;
+use frost_ed25519 as frost;
+
+use crate::encoders::{base64_url_decode, base64_url_encode};
+use crate::threshold::threshold_proof::{
+    build_threshold_signature_proof, verify_threshold_signature_proof_inner,
+    ThresholdProofShareInput, ThresholdSignatureProof,
+};
+
+/// Run a full 2-of-2 FROST signing round (ids 1 = client, 2 = relayer) and bundle the proof.
+fn valid_proof() -> ThresholdSignatureProof {
+    let mut rng = frost::rand_core::OsRng;
+    let (shares, pubkey_package) =
+        frost::keys::generate_with_dealer(2, 2, frost::keys::IdentifierList::Default, rng).unwrap();
+    let mut key_packages = BTreeMap::new();
+    for (identifier, secret_share) in shares {
+        key_packages.insert(
+            identifier,
+            frost::keys::KeyPackage::try_from(secret_share).unwrap(),
+        );
+    }
+    let client_id: frost::Identifier = 1u16.try_into().unwrap();
+    let relayer_id: frost::Identifier = 2u16.try_into().unwrap();
+
+    let digest = [0x5au8; 32];
+    let mut nonces = BTreeMap::new();
+    let mut commitments = BTreeMap::new();
+    for (id, kp) in &key_packages {
+        let (n, c) = frost::round1::commit(kp.signing_share(), &mut rng);
+        nonces.insert(*id, n);
+        commitments.insert(*id, c);
+    }
+    let signing_package = frost::SigningPackage::new(commitments.clone(), &digest);
+    let mut sig_shares = BTreeMap::new();
+    for (id, kp) in &key_packages {
+        sig_shares.insert(
+            *id,
+            frost::round2::sign(&signing_package, &nonces[id], kp).unwrap(),
+        );
+    }
+    let signature: [u8; 64] = frost::aggregate(&signing_package, &sig_shares, &pubkey_package)
+        .unwrap()
+        .serialize()
+        .unwrap()
+        .as_slice()
+        .try_into()
+        .unwrap();
+
+    let group_pk = pubkey_package.verifying_key().serialize().unwrap();
+    let relayer_key_id = format!("ed25519:{}", bs58::encode(&group_pk).into_string());
+    build_threshold_signature_proof(
+        &digest,
+        pubkey_package.verifying_key(),
+        &relayer_key_id,
+        "mpc-session-1",
+        ThresholdProofShareInput {
+            identifier: client_id,
+            commitments: &commitments[&client_id],
+            signature_share: &sig_shares[&client_id],
+            verifying_share: &pubkey_package.verifying_shares()[&client_id],
+        },
+        ThresholdProofShareInput {
+            identifier: relayer_id,
+            commitments: &commitments[&relayer_id],
+            signature_share: &sig_shares[&relayer_id],
+            verifying_share: &pubkey_package.verifying_shares()[&relayer_id],
+        },
+        &signature,
+    )
+    .unwrap()
+}
+
+fn other_point_b64u() -> String {
+    base64_url_encode(
+        (ED25519_BASEPOINT_POINT * CurveScalar::from(7u64))
+            .compress()
+            .as_bytes(),
+    )
+}
+
+fn bump_scalar_b64u(b64u: &str) -> String {
+    let bytes: [u8; 32] = base64_url_decode(b64u).unwrap().try_into().unwrap();
+    let s = CurveScalar::from_canonical_bytes(bytes).unwrap() + CurveScalar::ONE;
+    base64_url_encode(s.as_bytes())
+}
+
+fn assert_rejected(proof: &ThresholdSignatureProof, expected: &str) {
+    let err = verify_threshold_signature_proof_inner(proof).expect_err("tampered proof must fail");
+    assert!(
+        err.contains(expected),
+        "expected error containing {expected:?}, got {err:?}"
+    );
+}
+
+#[test]
+fn valid_threshold_proof_verifies() {
+    let proof = valid_proof();
+    assert_eq!(proof.client.participant_id, 1);
+    assert_eq!(proof.relayer.participant_id, 2);
+    verify_threshold_signature_proof_inner(&proof).expect("valid proof should verify");
+}
+
+#[test]
+fn threshold_proof_tampered_fields_fail_with_specific_reason() {
+    let proof = valid_proof();
+
+    let mut p = proof.clone();
+    p.scheme = "threshold_ed25519_2p_v0".to_string();
+    assert_rejected(&p, "unsupported proof scheme");
+
+    let mut p = proof.clone();
+    p.mpc_session_id = String::new();
+    assert_rejected(&p, "missing mpcSessionId");
+
+    let mut p = proof.clone();
+    p.relayer_key_id = format!(
+        "ed25519:{}",
+        bs58::encode(base64_url_decode(&other_point_b64u()).unwrap()).into_string()
+    );
+    assert_rejected(&p, "relayerKeyId does not match groupPublicKey");
+
+    let mut p = proof.clone();
+    p.group_public_key = format!(
+        "ed25519:{}",
+        bs58::encode(base64_url_decode(&other_point_b64u()).unwrap()).into_string()
+    );
+    assert_rejected(&p, "verifying shares do not interpolate to groupPublicKey");
+
+    let mut p = proof.clone();
+    p.relayer.participant_id = 3;
+    assert_rejected(&p, "verifying shares do not interpolate to groupPublicKey");
+
+    let mut p = proof.clone();
+    p.relayer.verifying_share_b64u = other_point_b64u();
+    assert_rejected(&p, "verifying shares do not interpolate to groupPublicKey");
+
+    let mut p = proof.clone();
+    p.signing_digest_b64u = base64_url_encode(&[0xa5u8; 32]);
+    assert_rejected(&p, "client signature share does not verify");
+
+    let mut p = proof.clone();
+    p.client.commitments.hiding = other_point_b64u();
+    assert_rejected(&p, "client signature share does not verify");
+
+    let mut p = proof.clone();
+    p.relayer.commitments.binding = other_point_b64u();
+    assert_rejected(&p, "client signature share does not verify");
+
+    let mut p = proof.clone();
+    p.relayer.signature_share_b64u = bump_scalar_b64u(&p.relayer.signature_share_b64u);
+    assert_rejected(&p, "relayer signature share does not verify");
+
+    let mut p = proof.clone();
+    p.client.signature_share_b64u = bump_scalar_b64u(&p.client.signature_share_b64u);
+    assert_rejected(&p, "client signature share does not verify");
+
+    let mut p = proof.clone();
+    let mut sig = base64_url_decode(&p.signature_b64u).unwrap();
+    sig[0] ^= 0x01;
+    p.signature_b64u = base64_url_encode(&sig);
+    assert_rejected(&p, "aggregated signature shares do not match signature");
+}
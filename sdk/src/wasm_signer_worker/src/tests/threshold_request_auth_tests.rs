@@ -0,0 +1,286 @@
+use crate::error::{RequestAuthError, ThresholdConfigError};
+use crate::threshold::request_auth::{
+    canonical_request_string, provision_request_auth_secret, request_path_for_url,
+    verify_relayer_request_signature, HmacRequestAuth, RelayerRequestAuth, RequestAuthSecret,
+    SignedRelayerRequest,
+};
+use crate::types::ThresholdSignerConfig;
+
+/// 32 bytes of 0x11.
+const SECRET_B64U: &str = "ERERERERERERERERERERERERERERERERERERERERERE";
+const KEY_ID: &str = "relayer-hmac-1";
+const TIMESTAMP_MS: f64 = 1_700_000_000_000.0;
+const URL: &str = "https://relay.example.com/api/threshold-ed25519/sign/init?trace=1";
+const BODY: &str = r#"{"mpcSessionId":"mpc-1","relayerKeyId":"relayer-key-1"}"#;
+
+/// Config referring to `KEY_ID`, with the fixture secret provisioned on this test's thread.
+fn hmac_auth() -> RelayerRequestAuth {
+    provision_request_auth_secret(KEY_ID, RequestAuthSecret::new(SECRET_B64U)).unwrap();
+    RelayerRequestAuth::HmacSha256(HmacRequestAuth {
+        key_id: KEY_ID.to_string(),
+        secret_b64u: None,
+        header_name: None,
+        timestamp_header_name: None,
+    })
+}
+
+fn signed_fixture_request() -> SignedRelayerRequest {
+    let headers = hmac_auth()
+        .sign_request(
+            "POST",
+            request_path_for_url(URL),
+            BODY.as_bytes(),
+            TIMESTAMP_MS,
+        )
+        .unwrap();
+    SignedRelayerRequest {
+        method: "POST".to_string(),
+        path: "/api/threshold-ed25519/sign/init".to_string(),
+        body: BODY.to_string(),
+        timestamp_header: headers[0].1.clone(),
+        signature_header: headers[1].1.clone(),
+    }
+}
+
+fn verify(request: &SignedRelayerRequest, now_ms: f64) -> Result<(), RequestAuthError> {
+    verify_relayer_request_signature(
+        &RequestAuthSecret::new(SECRET_B64U),
+        KEY_ID,
+        request,
+        now_ms,
+        300_000.0,
+    )
+}
+
+#[test]
+fn canonical_string_to_sign_is_pinned() {
+    assert_eq!(
+        canonical_request_string(
+            TIMESTAMP_MS as u64,
+            "post",
+            request_path_for_url(URL),
+            BODY.as_bytes()
+        ),
+        "1700000000000\nPOST\n/api/threshold-ed25519/sign/init\nM1VD-y0UvfIJAf-BKYnjz8dStpdC6hlSQMlAxZLO51U"
+    );
+}
+
+#[test]
+fn fixture_request_gets_pinned_default_headers() {
+    let headers = hmac_auth()
+        .sign_request(
+            "POST",
+            request_path_for_url(URL),
+            BODY.as_bytes(),
+            TIMESTAMP_MS,
+        )
+        .unwrap();
+    assert_eq!(
+        headers,
+        vec![
+            (
+                "X-Relayer-Timestamp".to_string(),
+                "1700000000000".to_string()
+            ),
+            (
+                "X-Relayer-Signature".to_string(),
+                "keyId=relayer-hmac-1,signature=zQuHIjWqhAMrXv2W9mMUVzL11MqvjRubXlTiFsH5EAU"
+                    .to_string()
+            ),
+        ]
+    );
+    assert!(RelayerRequestAuth::None
+        .sign_request("POST", "/", BODY.as_bytes(), TIMESTAMP_MS)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn relayer_verifies_the_signed_fixture() {
+    let request = signed_fixture_request();
+    assert_eq!(verify(&request, TIMESTAMP_MS + 1_000.0), Ok(()));
+}
+
+#[test]
+fn any_change_to_the_signed_bytes_fails_verification() {
+    let mut reserialized = signed_fixture_request();
+    reserialized.body = r#"{"relayerKeyId":"relayer-key-1","mpcSessionId":"mpc-1"}"#.to_string();
+    assert_eq!(
+        verify(&reserialized, TIMESTAMP_MS),
+        Err(RequestAuthError::SignatureMismatch)
+    );
+
+    let mut other_path = signed_fixture_request();
+    other_path.path = "/api/threshold-ed25519/sign/finalize".to_string();
+    assert_eq!(
+        verify(&other_path, TIMESTAMP_MS),
+        Err(RequestAuthError::SignatureMismatch)
+    );
+
+    let mut wrong_key = signed_fixture_request();
+    wrong_key.signature_header = wrong_key.signature_header.replace(KEY_ID, "other-key");
+    assert_eq!(
+        verify(&wrong_key, TIMESTAMP_MS),
+        Err(RequestAuthError::UnknownKeyId {
+            key_id: "other-key".to_string()
+        })
+    );
+}
+
+#[test]
+fn stale_or_unparseable_timestamps_are_rejected() {
+    let request = signed_fixture_request();
+    assert!(matches!(
+        verify(&request, TIMESTAMP_MS + 600_000.0),
+        Err(RequestAuthError::TimestampOutOfRange { .. })
+    ));
+    assert!(matches!(
+        verify(&request, f64::NAN),
+        Err(RequestAuthError::TimestampOutOfRange { .. })
+    ));
+
+    let mut garbled = signed_fixture_request();
+    garbled.timestamp_header = "yesterday".to_string();
+    assert_eq!(
+        verify(&garbled, TIMESTAMP_MS),
+        Err(RequestAuthError::MalformedTimestamp)
+    );
+}
+
+#[test]
+fn implausible_client_clock_is_not_signed_with() {
+    for clock in [0.0, f64::NAN, f64::INFINITY] {
+        assert!(matches!(
+            hmac_auth().sign_request("POST", "/", b"{}", clock),
+            Err(RequestAuthError::ClockUnavailable { .. })
+        ));
+    }
+}
+
+#[test]
+fn secret_never_appears_in_debug_or_errors() {
+    let cfg: ThresholdSignerConfig = serde_json::from_value(serde_json::json!({
+        "relayerUrl": "https://relay.example.com",
+        "relayerKeyId": "relayer-key-1",
+        "requestAuth": { "kind": "hmac-sha256", "keyId": KEY_ID, "secretB64u": SECRET_B64U },
+    }))
+    .unwrap();
+    assert!(!format!("{cfg:?}").contains(SECRET_B64U));
+    assert!(format!("{cfg:?}").contains("[REDACTED]"));
+
+    let malformed = "not base64url! hunter2";
+    let err = provision_request_auth_secret(KEY_ID, RequestAuthSecret::new(malformed)).unwrap_err();
+    assert_eq!(err, RequestAuthError::InvalidSecret { decoded_len: None });
+    assert!(!String::from(err).contains("hunter2"));
+
+    let short = "c2hvcnQ";
+    let err = provision_request_auth_secret(KEY_ID, RequestAuthSecret::new(short)).unwrap_err();
+    assert_eq!(
+        err,
+        RequestAuthError::InvalidSecret {
+            decoded_len: Some(5)
+        }
+    );
+    assert!(!err.to_string().contains(short));
+
+    let mut tampered = signed_fixture_request();
+    tampered.body.push(' ');
+    let err = verify(&tampered, TIMESTAMP_MS).unwrap_err();
+    assert!(!err.to_string().contains(SECRET_B64U));
+}
+
+#[test]
+fn secret_is_only_taken_from_the_worker() {
+    let err = ThresholdSignerConfig::builder()
+        .relayer_url("https://relay.example.com")
+        .relayer_key_id("relayer-key-1")
+        .request_auth(RelayerRequestAuth::HmacSha256(HmacRequestAuth {
+            key_id: KEY_ID.to_string(),
+            secret_b64u: Some(RequestAuthSecret::new(SECRET_B64U)),
+            header_name: None,
+            timestamp_header_name: None,
+        }))
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ThresholdConfigError::InvalidRequestAuth(RequestAuthError::InlineSecret)
+    );
+
+    let cfg = ThresholdSignerConfig::builder()
+        .relayer_url("https://relay.example.com")
+        .relayer_key_id("relayer-key-1")
+        .request_auth(hmac_auth())
+        .build()
+        .unwrap();
+    let echoed = serde_json::to_string(&cfg).unwrap();
+    assert!(echoed.contains(KEY_ID));
+    assert!(!echoed.contains("secretB64u"));
+    assert!(!echoed.contains(SECRET_B64U));
+
+    let unprovisioned = RelayerRequestAuth::HmacSha256(HmacRequestAuth {
+        key_id: "never-provisioned".to_string(),
+        secret_b64u: None,
+        header_name: None,
+        timestamp_header_name: None,
+    });
+    assert_eq!(
+        unprovisioned.sign_request("POST", "/", b"{}", TIMESTAMP_MS),
+        Err(RequestAuthError::SecretNotProvisioned {
+            key_id: "never-provisioned".to_string()
+        })
+    );
+}
+
+#[test]
+fn builder_normalizes_request_auth() {
+    let cfg = ThresholdSignerConfig::builder()
+        .relayer_url("https://relay.example.com")
+        .relayer_key_id("relayer-key-1")
+        .request_auth(RelayerRequestAuth::HmacSha256(HmacRequestAuth {
+            key_id: format!("  {KEY_ID} "),
+            secret_b64u: None,
+            header_name: Some(" X-Sig ".to_string()),
+            timestamp_header_name: None,
+        }))
+        .build()
+        .expect("valid request auth");
+    assert_eq!(
+        cfg.request_auth,
+        RelayerRequestAuth::HmacSha256(HmacRequestAuth {
+            key_id: KEY_ID.to_string(),
+            secret_b64u: None,
+            header_name: Some("X-Sig".to_string()),
+            timestamp_header_name: Some("X-Relayer-Timestamp".to_string()),
+        })
+    );
+
+    let err = ThresholdSignerConfig::builder()
+        .relayer_url("https://relay.example.com")
+        .relayer_key_id("relayer-key-1")
+        .request_auth(RelayerRequestAuth::HmacSha256(HmacRequestAuth {
+            key_id: KEY_ID.to_string(),
+            secret_b64u: None,
+            header_name: Some("x-relayer-timestamp".to_string()),
+            timestamp_header_name: None,
+        }))
+        .build()
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ThresholdConfigError::InvalidRequestAuth(RequestAuthError::InvalidHeaderName { .. })
+    ));
+}
+
+#[test]
+fn request_path_drops_origin_and_query() {
+    assert_eq!(
+        request_path_for_url(URL),
+        "/api/threshold-ed25519/sign/init"
+    );
+    assert_eq!(request_path_for_url("https://relay.example.com"), "/");
+    assert_eq!(
+        request_path_for_url("http://localhost:3000/threshold-ed25519/session#x"),
+        "/threshold-ed25519/session"
+    );
+}
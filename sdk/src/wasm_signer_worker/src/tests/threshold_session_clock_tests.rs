@@ -0,0 +1,157 @@
+use crate::threshold::session_clock::{
+    is_session_fresh, is_unauthorized_error, SessionClockEstimate, SessionClockTracker,
+};
+
+const RELAYER_NOW_MS: f64 = 1_700_000_000_000.0;
+const TEN_MINUTES_MS: f64 = 600_000.0;
+const SESSION_TTL_MS: f64 = 300_000.0;
+const MARGIN_MS: f64 = 30_000.0;
+const ROUND_TRIP_MS: f64 = 100.0;
+
+/// Mint a session whose client clock is `client_skew_ms` ahead of the relayer.
+fn mint(tracker: &mut SessionClockTracker, client_skew_ms: f64) -> (SessionClockEstimate, f64) {
+    let sent = RELAYER_NOW_MS + client_skew_ms - ROUND_TRIP_MS / 2.0;
+    let received = sent + ROUND_TRIP_MS;
+    let clock = tracker.record_mint(Some(RELAYER_NOW_MS), sent, received);
+    (clock, received)
+}
+
+#[test]
+fn fast_client_keeps_valid_session() {
+    let mut tracker = SessionClockTracker::default();
+    let (clock, client_now) = mint(&mut tracker, TEN_MINUTES_MS);
+    assert_eq!(clock.offset_ms, -TEN_MINUTES_MS);
+    assert_eq!(clock.uncertainty_ms, ROUND_TRIP_MS / 2.0);
+
+    let expires_at = Some(RELAYER_NOW_MS + SESSION_TTL_MS);
+    // A naive client-clock comparison would already consider this session expired.
+    assert!(!is_session_fresh(
+        expires_at,
+        client_now,
+        &SessionClockEstimate::default(),
+        MARGIN_MS
+    ));
+    assert!(is_session_fresh(expires_at, client_now, &clock, MARGIN_MS));
+    assert_eq!(tracker.snapshot().clock_offset_ms, Some(-TEN_MINUTES_MS));
+}
+
+#[test]
+fn slow_client_stops_using_expired_session() {
+    let mut tracker = SessionClockTracker::default();
+    let (clock, client_now) = mint(&mut tracker, -TEN_MINUTES_MS);
+    let expires_at = Some(RELAYER_NOW_MS + SESSION_TTL_MS);
+    assert!(is_session_fresh(expires_at, client_now, &clock, MARGIN_MS));
+
+    // Six minutes later the relayer has expired the session; the slow client clock has not.
+    let later = client_now + 360_000.0;
+    assert!(is_session_fresh(
+        expires_at,
+        later,
+        &SessionClockEstimate::default(),
+        MARGIN_MS
+    ));
+    assert!(!is_session_fresh(expires_at, later, &clock, MARGIN_MS));
+}
+
+#[test]
+fn unexpected_unauthorized_widens_skew_estimate() {
+    let mut tracker = SessionClockTracker::default();
+    // No server time at mint: the client falls back to its own clock.
+    let clock = tracker.record_mint(None, RELAYER_NOW_MS, RELAYER_NOW_MS + ROUND_TRIP_MS);
+    assert_eq!(clock, SessionClockEstimate::default());
+
+    let expires_at = RELAYER_NOW_MS + SESSION_TTL_MS;
+    let client_now = RELAYER_NOW_MS + 60_000.0;
+    assert!(is_session_fresh(
+        Some(expires_at),
+        client_now,
+        &clock,
+        MARGIN_MS
+    ));
+
+    let widened = tracker.record_unexpected_unauthorized(&clock, Some(expires_at), client_now);
+    assert_eq!(widened.uncertainty_ms, expires_at - client_now);
+    assert!(!is_session_fresh(
+        Some(expires_at),
+        client_now,
+        &widened,
+        MARGIN_MS
+    ));
+
+    let snapshot = tracker.snapshot();
+    assert_eq!(snapshot.unexpected_unauthorized_count, 1);
+    assert_eq!(snapshot.clock_uncertainty_ms, Some(expires_at - client_now));
+
+    // The widened bound survives a re-mint.
+    let (reminted, _) = mint(&mut tracker, 0.0);
+    assert_eq!(reminted.uncertainty_ms, expires_at - client_now);
+
+    assert!(is_unauthorized_error(
+        "unauthorized: threshold session expired"
+    ));
+    assert!(is_unauthorized_error(
+        "threshold-signer: /authorize HTTP 401 Unauthorized: {}"
+    ));
+    assert!(!is_unauthorized_error(
+        "threshold-signer: /authorize HTTP 500 Internal: {}"
+    ));
+}
+
+#[test]
+fn refresh_margin_expires_sessions_early() {
+    let clock = SessionClockEstimate::default();
+    let expires_at = Some(RELAYER_NOW_MS + SESSION_TTL_MS);
+    let near_expiry = RELAYER_NOW_MS + SESSION_TTL_MS - 20_000.0;
+
+    assert!(is_session_fresh(expires_at, near_expiry, &clock, 0.0));
+    assert!(!is_session_fresh(
+        expires_at,
+        near_expiry,
+        &clock,
+        MARGIN_MS
+    ));
+    assert!(is_session_fresh(expires_at, near_expiry, &clock, 10_000.0));
+    // Sessions without an expiry never expire client-side; a NaN clock always does.
+    assert!(is_session_fresh(None, near_expiry, &clock, MARGIN_MS));
+    assert!(!is_session_fresh(expires_at, f64::NAN, &clock, MARGIN_MS));
+}
+
+#[test]
+fn max_clock_skew_tolerance_refreshes_near_expiry_sessions() {
+    use crate::config::THRESHOLD_SESSION_MAX_CLOCK_SKEW_MS;
+
+    // Refresh margin disabled to isolate the skew tolerance. A freshly minted session with a
+    // tight round trip still gets the default tolerance subtracted from its expiry.
+    let mut tracker = SessionClockTracker::default();
+    let (clock, _) = mint(&mut tracker, 0.0);
+    let clock = clock.with_max_clock_skew(THRESHOLD_SESSION_MAX_CLOCK_SKEW_MS);
+    assert_eq!(clock.uncertainty_ms, THRESHOLD_SESSION_MAX_CLOCK_SKEW_MS);
+
+    let expiry = RELAYER_NOW_MS + SESSION_TTL_MS;
+    assert!(!is_session_fresh(
+        Some(expiry),
+        expiry - 3_000.0,
+        &clock,
+        0.0
+    ));
+    assert!(is_session_fresh(
+        Some(expiry),
+        expiry - 30_000.0,
+        &clock,
+        0.0
+    ));
+
+    // A larger measured bound is kept; the tolerance is only a floor.
+    let widened = SessionClockEstimate {
+        offset_ms: 0.0,
+        uncertainty_ms: 40_000.0,
+    }
+    .with_max_clock_skew(THRESHOLD_SESSION_MAX_CLOCK_SKEW_MS);
+    assert_eq!(widened.uncertainty_ms, 40_000.0);
+    assert!(!is_session_fresh(
+        Some(expiry),
+        expiry - 30_000.0,
+        &widened,
+        0.0
+    ));
+}
@@ -0,0 +1,139 @@
+use serde_json::json;
+
+use crate::actions::ActionParams;
+use crate::handlers::handle_sign_transaction_with_keypair::SignTransactionWithKeyPairRequest;
+use crate::handlers::handle_sign_transactions_with_actions::{
+    PayloadFieldError, TransactionPayload,
+};
+
+fn transfer() -> ActionParams {
+    ActionParams::Transfer {
+        deposit: "1".to_string(),
+    }
+}
+
+fn function_call() -> ActionParams {
+    ActionParams::FunctionCall {
+        method_name: "set_greeting".to_string(),
+        args: r#"{"greeting":"hi"}"#.to_string(),
+        gas: "30000000000000".to_string(),
+        deposit: "0".to_string(),
+    }
+}
+
+fn payload_json(actions: serde_json::Value) -> serde_json::Value {
+    json!({
+        "nearAccountId": "alice.testnet",
+        "receiverId": "bob.testnet",
+        "actions": actions,
+    })
+}
+
+#[test]
+fn actions_are_accepted_as_an_array_or_a_json_string() {
+    let actions = json!([
+        {"action_type": "Transfer", "deposit": "1"},
+        {
+            "action_type": "FunctionCall",
+            "method_name": "set_greeting",
+            "args": "{\"greeting\":\"hi\"}",
+            "gas": "30000000000000",
+            "deposit": "0"
+        }
+    ]);
+    let from_array: TransactionPayload =
+        serde_json::from_value(payload_json(actions.clone())).unwrap();
+    let from_string: TransactionPayload =
+        serde_json::from_value(payload_json(json!(actions.to_string()))).unwrap();
+    assert_eq!(from_array.actions, vec![transfer(), function_call()]);
+    assert_eq!(from_string.actions, from_array.actions);
+
+    // Results carry the typed form, whichever form came in.
+    let serialized = serde_json::to_value(&from_string).unwrap();
+    assert_eq!(serialized["actions"], actions);
+}
+
+#[test]
+fn malformed_action_strings_fail_to_parse() {
+    let err = serde_json::from_value::<TransactionPayload>(payload_json(json!(
+        r#"[{"action_type":"Transfer""#
+    )))
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("Failed to parse actions JSON string"),
+        "{}",
+        err
+    );
+
+    let err = serde_json::from_value::<TransactionPayload>(payload_json(json!(42))).unwrap_err();
+    assert!(err.to_string().contains("a list of actions"), "{}", err);
+}
+
+#[test]
+fn keypair_requests_accept_both_action_forms() {
+    let request = |actions: serde_json::Value| {
+        serde_json::from_value::<SignTransactionWithKeyPairRequest>(json!({
+            "nearPrivateKey": "ed25519:unused",
+            "signerAccountId": "alice.testnet",
+            "receiverId": "bob.testnet",
+            "nonce": "1",
+            "blockHash": "11111111111111111111111111111111",
+            "actions": actions,
+        }))
+        .unwrap()
+        .actions
+    };
+    let actions = json!([{"action_type": "Transfer", "deposit": "1"}]);
+    assert_eq!(request(actions.clone()), vec![transfer()]);
+    assert_eq!(request(json!(actions.to_string())), vec![transfer()]);
+}
+
+#[test]
+fn field_errors_name_each_invalid_field() {
+    let valid = TransactionPayload {
+        near_account_id: "alice.testnet".to_string(),
+        receiver_id: "bob.testnet".to_string(),
+        actions: vec![transfer(), function_call()],
+    };
+    assert_eq!(valid.field_errors(), vec![]);
+
+    let invalid = TransactionPayload {
+        near_account_id: "alice.testnet".to_string(),
+        receiver_id: "Bob..testnet".to_string(),
+        actions: vec![
+            transfer(),
+            ActionParams::Transfer {
+                deposit: "one".to_string(),
+            },
+        ],
+    };
+    let errors = invalid.field_errors();
+    let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+    assert_eq!(fields, vec!["receiverId", "actions[1]"]);
+
+    let empty = TransactionPayload {
+        actions: vec![],
+        ..valid
+    };
+    assert_eq!(
+        empty.field_errors(),
+        vec![PayloadFieldError {
+            field: "actions".to_string(),
+            message: "at least one action is required".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn attached_contract_code_is_not_required_before_confirmation() {
+    let payload = TransactionPayload {
+        near_account_id: "alice.testnet".to_string(),
+        receiver_id: "alice.testnet".to_string(),
+        actions: vec![ActionParams::DeployContract {
+            code: vec![],
+            code_attachment: Some(0),
+        }],
+    };
+    assert_eq!(payload.field_errors(), vec![]);
+}
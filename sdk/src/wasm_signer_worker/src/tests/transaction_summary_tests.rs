@@ -0,0 +1,223 @@
+use futures::executor::block_on;
+
+use crate::actions::ActionParams;
+use crate::config::GAS_PRICE_STALE_AFTER_BLOCKS;
+use crate::error::{NearRpcError, TransactionSummaryError};
+use crate::handlers::handle_sign_transactions_with_actions::TransactionPayload;
+use crate::rpc_calls::{GasPriceView, JsonRpcResponse};
+use crate::transaction_summary::{
+    create_transaction_summary, create_transaction_summary_with_rpc, estimated_max_cost,
+    format_yocto_as_near, GasPriceQuote, TransactionSummaryRequest,
+};
+
+const ONE_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+const TGAS: u64 = 1_000_000_000_000;
+const MIN_GAS_PRICE_YOCTO: u128 = 100_000_000;
+
+fn parse_gas_price(json: &str) -> Result<GasPriceView, NearRpcError> {
+    serde_json::from_str::<JsonRpcResponse<GasPriceView>>(json)
+        .expect("fixture should deserialize")
+        .into_result()
+}
+
+const GAS_PRICE_MIN: &str = r#"{
+  "jsonrpc": "2.0",
+  "id": "tatchi-signer",
+  "result": {
+    "gas_price": "100000000"
+  }
+}"#;
+
+const GAS_PRICE_UNKNOWN_BLOCK: &str = r#"{
+  "jsonrpc": "2.0",
+  "id": "tatchi-signer",
+  "error": {
+    "name": "HANDLER_ERROR",
+    "cause": {
+      "name": "UNKNOWN_BLOCK",
+      "info": {}
+    },
+    "code": -32000,
+    "message": "Server error",
+    "data": "DB Not Found Error: BLOCK HEIGHT: 1 \n Cause: Unknown"
+  }
+}"#;
+
+fn function_call(gas: u64, deposit: u128) -> ActionParams {
+    ActionParams::FunctionCall {
+        method_name: "ft_transfer".to_string(),
+        args: "{}".to_string(),
+        gas: gas.to_string(),
+        deposit: deposit.to_string(),
+    }
+}
+
+fn transfer(deposit: u128) -> ActionParams {
+    ActionParams::Transfer {
+        deposit: deposit.to_string(),
+    }
+}
+
+fn tx(receiver_id: &str, actions: Vec<ActionParams>) -> TransactionPayload {
+    TransactionPayload {
+        near_account_id: "alice.testnet".to_string(),
+        receiver_id: receiver_id.to_string(),
+        actions,
+    }
+}
+
+fn quote(gas_price_yocto: u128, block_height: u64) -> GasPriceQuote {
+    GasPriceQuote {
+        gas_price_yocto,
+        block_height,
+    }
+}
+
+#[test]
+fn gas_price_fixtures_deserialize() {
+    assert_eq!(
+        parse_gas_price(GAS_PRICE_MIN),
+        Ok(GasPriceView {
+            gas_price: MIN_GAS_PRICE_YOCTO
+        })
+    );
+    assert!(parse_gas_price(GAS_PRICE_UNKNOWN_BLOCK).is_err());
+}
+
+#[test]
+fn estimate_is_gas_times_price_plus_deposits_per_transaction() {
+    let transactions = vec![
+        tx(
+            "token.testnet",
+            vec![function_call(300 * TGAS, 1), transfer(ONE_NEAR)],
+        ),
+        tx("bob.testnet", vec![transfer(ONE_NEAR / 2)]),
+    ];
+    let summary = create_transaction_summary(
+        &transactions,
+        Some(&quote(MIN_GAS_PRICE_YOCTO, 1_000)),
+        Some(1_010),
+    )
+    .unwrap();
+    assert_eq!(summary.signer_account_id, "alice.testnet");
+
+    // 300 Tgas at 0.1 Ggas price = 0.03 NEAR, plus 1 NEAR + 1 yocto of deposits.
+    let first = &summary.transactions[0];
+    assert_eq!(first.total_gas, (300 * TGAS).to_string());
+    assert_eq!(first.total_deposit_yocto, (ONE_NEAR + 1).to_string());
+    let estimate = first.estimate.as_ref().unwrap();
+    assert_eq!(
+        estimate.estimated_max_cost_yocto,
+        (ONE_NEAR + 30_000_000_000_000_000_000_001).to_string()
+    );
+    assert_eq!(
+        estimate.estimated_max_cost_near,
+        "1.030000000000000000000001"
+    );
+    assert_eq!(estimate.gas_price_yocto, "100000000");
+    assert!(!estimate.gas_price_stale);
+
+    // No gas attached: the estimate is just the deposit.
+    let second = summary.transactions[1].estimate.as_ref().unwrap();
+    assert_eq!(second.estimated_max_cost_near, "0.5");
+}
+
+#[test]
+fn old_quotes_are_flagged_stale() {
+    let transactions = vec![tx("bob.testnet", vec![transfer(1)])];
+    let at_limit = create_transaction_summary(
+        &transactions,
+        Some(&quote(MIN_GAS_PRICE_YOCTO, 1_000)),
+        Some(1_000 + GAS_PRICE_STALE_AFTER_BLOCKS),
+    )
+    .unwrap();
+    assert!(
+        !at_limit.transactions[0]
+            .estimate
+            .as_ref()
+            .unwrap()
+            .gas_price_stale
+    );
+
+    let stale = create_transaction_summary(
+        &transactions,
+        Some(&quote(MIN_GAS_PRICE_YOCTO, 1_000)),
+        Some(1_001 + GAS_PRICE_STALE_AFTER_BLOCKS),
+    )
+    .unwrap();
+    assert!(
+        stale.transactions[0]
+            .estimate
+            .as_ref()
+            .unwrap()
+            .gas_price_stale
+    );
+}
+
+#[test]
+fn estimate_is_omitted_without_a_gas_price() {
+    let transactions = vec![tx("token.testnet", vec![function_call(30 * TGAS, 0)])];
+    let summary = create_transaction_summary(&transactions, None, Some(1_000)).unwrap();
+    assert_eq!(summary.transactions[0].estimate, None);
+    assert_eq!(summary.transactions[0].total_deposit_near, "0");
+
+    // Native builds cannot reach an RPC; the summary still comes back, without an estimate.
+    let request = TransactionSummaryRequest {
+        transactions,
+        gas_price: None,
+        near_rpc_url: Some("https://rpc.testnet.near.org".to_string()),
+        current_block_height: Some(1_000),
+    };
+    let summary = block_on(create_transaction_summary_with_rpc(&request)).unwrap();
+    assert_eq!(summary.transactions[0].estimate, None);
+}
+
+#[test]
+fn max_gas_at_max_plausible_price_is_overflow_checked() {
+    // nearcore's mainnet `max_gas_price` (10^22 yocto/gas).
+    let max_plausible_price: u128 = 10_000_000_000_000_000_000_000;
+    // 1 Pgas, the most a single transaction can attach, fits with room for any deposit.
+    let max_tx_gas = u128::from(1_000 * TGAS);
+    assert_eq!(
+        estimated_max_cost(max_tx_gas, max_plausible_price, 1_000_000 * ONE_NEAR),
+        Some(max_tx_gas * max_plausible_price + 1_000_000 * ONE_NEAR)
+    );
+    // Type-level maxima overflow u128 and are reported, never wrapped.
+    assert_eq!(
+        estimated_max_cost(u128::from(u64::MAX), max_plausible_price, 0),
+        None
+    );
+    assert_eq!(
+        estimated_max_cost(max_tx_gas, max_plausible_price, u128::MAX),
+        None
+    );
+
+    let transactions = vec![
+        tx("bob.testnet", vec![transfer(1)]),
+        tx(
+            "token.testnet",
+            vec![function_call(u64::MAX, 0), function_call(u64::MAX, 0)],
+        ),
+    ];
+    assert_eq!(
+        create_transaction_summary(&transactions, Some(&quote(max_plausible_price, 1)), None),
+        Err(TransactionSummaryError::AmountOverflow { index: 1 })
+    );
+    // Without a price the u64::MAX gas is still summed exactly.
+    let summary = create_transaction_summary(&transactions, None, None).unwrap();
+    assert_eq!(
+        summary.transactions[1].total_gas,
+        (u128::from(u64::MAX) * 2).to_string()
+    );
+}
+
+#[test]
+fn summaries_format_yocto_as_near() {
+    assert_eq!(format_yocto_as_near(0), "0");
+    assert_eq!(format_yocto_as_near(1), "0.000000000000000000000001");
+    assert_eq!(format_yocto_as_near(ONE_NEAR * 12 + ONE_NEAR / 4), "12.25");
+    assert_eq!(
+        create_transaction_summary(&[], None, None),
+        Err(TransactionSummaryError::EmptyBatch)
+    );
+}
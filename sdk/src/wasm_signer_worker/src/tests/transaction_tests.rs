@@ -2,11 +2,12 @@ use borsh;
 use ed25519_dalek::SigningKey;
 
 use crate::actions::ActionParams;
+use crate::config::MAX_TX_SIZE_BYTES;
 use crate::transaction::{
     build_actions_from_params, build_transaction_with_actions, calculate_transaction_hash,
-    sign_transaction,
+    check_transaction_size, parse_nonce, sign_transaction, signed_transaction_rpc_payload,
 };
-use crate::types::{NearAction, Transaction};
+use crate::types::{FunctionCallAction, NearAction, Transaction};
 
 /// Build a simple transaction with a single transfer action and sign it end-to-end.
 #[test]
@@ -76,3 +77,333 @@ fn build_transaction_rejects_invalid_block_hash_size() {
 
     assert!(err.contains("Block hash must be 32 bytes"));
 }
+
+/// Build and sign a transaction carrying a NEP-0591 UseGlobalContract action.
+#[test]
+fn build_and_sign_use_global_contract_transaction() {
+    let seed = [9u8; 32];
+    let signing_key = SigningKey::from_bytes(&seed);
+    let public_key_bytes = signing_key.verifying_key().to_bytes();
+
+    let params = vec![ActionParams::UseGlobalContract {
+        account_id: None,
+        code_hash: Some(bs58::encode([3u8; 32]).into_string()),
+    }];
+    let actions = build_actions_from_params(params).expect("actions should build");
+
+    let tx = build_transaction_with_actions(
+        "alice.near",
+        "alice.near",
+        42,
+        &[5u8; 32],
+        &public_key_bytes,
+        actions,
+    )
+    .expect("transaction should build");
+
+    use ed25519_dalek::{Signer, Verifier};
+    let (tx_hash_to_sign, _size) = tx.get_hash_and_size();
+    let signature = signing_key.sign(&tx_hash_to_sign.0);
+    let signed_bytes =
+        sign_transaction(tx, &signature.to_bytes()).expect("signing should succeed");
+
+    let signed: crate::types::SignedTransaction =
+        borsh::from_slice(&signed_bytes).expect("signed tx should be valid Borsh");
+    match &signed.transaction.actions[..] {
+        [NearAction::UseGlobalContract {
+            contract_identifier: crate::types::GlobalContractIdentifier::CodeHash(hash),
+        }] => assert_eq!(hash.0, [3u8; 32]),
+        other => panic!("Expected a single UseGlobalContract action, got {:?}", other),
+    }
+    let (hash_after, _) = signed.transaction.get_hash_and_size();
+    assert!(signing_key
+        .verifying_key()
+        .verify(&hash_after.0, &signature)
+        .is_ok());
+}
+
+/// Implicit accounts (64 lowercase hex) are valid signer and receiver IDs.
+#[test]
+fn build_transaction_accepts_implicit_signer_and_receiver() {
+    let signing_key = SigningKey::from_bytes(&[3u8; 32]);
+    let public_key_bytes = signing_key.verifying_key().to_bytes();
+    let implicit = crate::types::AccountId::implicit_from_public_key(&public_key_bytes);
+
+    let actions = build_actions_from_params(vec![ActionParams::Transfer {
+        deposit: "1".to_string(),
+    }])
+    .expect("actions should build");
+
+    let tx = build_transaction_with_actions(
+        &implicit.0,
+        &implicit.0,
+        1,
+        &[5u8; 32],
+        &public_key_bytes,
+        actions,
+    )
+    .expect("implicit accounts should be accepted");
+    assert!(tx.signer_id.is_implicit());
+    assert!(tx.receiver_id.is_implicit());
+}
+
+/// Receiver IDs are syntax-checked before the transaction is built.
+#[test]
+fn build_transaction_validates_receiver_id() {
+    let signing_key = SigningKey::from_bytes(&[4u8; 32]);
+    let public_key_bytes = signing_key.verifying_key().to_bytes();
+    let build = |receiver: &str| {
+        let actions = build_actions_from_params(vec![ActionParams::Transfer {
+            deposit: "1".to_string(),
+        }])
+        .expect("actions should build");
+        build_transaction_with_actions(
+            "alice.near",
+            receiver,
+            1,
+            &[5u8; 32],
+            &public_key_bytes,
+            actions,
+        )
+    };
+
+    for valid in ["bob.near", "a1", "my_app-v2.alice.testnet", "near"] {
+        let tx = build(valid).unwrap_or_else(|e| panic!("{} should be valid: {}", valid, e));
+        assert_eq!(tx.receiver_id.0, valid);
+    }
+
+    for malformed in [
+        "",
+        "b",
+        "Bob.near",
+        "bob..near",
+        ".bob.near",
+        "bob.near.",
+        "bob-.near",
+        "bob__x.near",
+        "bob near",
+        "bob@near",
+        &"a".repeat(65),
+    ] {
+        let err = build(malformed).unwrap_err();
+        assert!(
+            err.starts_with("invalid receiver_id"),
+            "{:?} should be rejected, got: {}",
+            malformed,
+            err
+        );
+    }
+}
+
+/// alice.near -> bob.near, nonce 1, block hash [5; 32], 1 yoctoNEAR transfer, signed with
+/// the ed25519 seed [7; 32]. Generated independently of this crate.
+const SIGNED_TX_FIXTURE_B64U: &str = "CgAAAGFsaWNlLm5lYXIA6kpsY-KcUgq-9VB7Ey7F-ZVHdq6-vnuSQh7qaRRG0iwBAAAAAAAAAAgAAABib2IubmVhcgUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFAQAAAAMBAAAAAAAAAAAAAAAAAAAAADIdoKbnjlN0sjYLozMFi2A1ZKLgxFQ1yMu30T3BaGkpub65xN5mmvjKOMQonJ7ctqrB2cA8ZZD_sSD68pkM_wo";
+const SIGNED_TX_FIXTURE_BASE64: &str = "CgAAAGFsaWNlLm5lYXIA6kpsY+KcUgq+9VB7Ey7F+ZVHdq6+vnuSQh7qaRRG0iwBAAAAAAAAAAgAAABib2IubmVhcgUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFAQAAAAMBAAAAAAAAAAAAAAAAAAAAADIdoKbnjlN0sjYLozMFi2A1ZKLgxFQ1yMu30T3BaGkpub65xN5mmvjKOMQonJ7ctqrB2cA8ZZD/sSD68pkM/wo=";
+const SIGNED_TX_FIXTURE_HASH: &str = "BhCzfDjfyWvNwEJtfWR2qNACPKV6YVapgwXriYEvrGTH";
+
+#[test]
+fn rpc_payload_matches_signed_transaction_fixture() {
+    let signed_bytes = crate::encoders::base64_url_decode(SIGNED_TX_FIXTURE_B64U).unwrap();
+    let payload = signed_transaction_rpc_payload(&signed_bytes).expect("fixture should decode");
+    assert_eq!(payload.signed_tx_base64, SIGNED_TX_FIXTURE_BASE64);
+    assert_eq!(payload.tx_hash, SIGNED_TX_FIXTURE_HASH);
+
+    // The fixture is exactly what this crate produces for the same inputs.
+    let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+    let actions = build_actions_from_params(vec![ActionParams::Transfer {
+        deposit: "1".to_string(),
+    }])
+    .unwrap();
+    let tx = build_transaction_with_actions(
+        "alice.near",
+        "bob.near",
+        1,
+        &[5u8; 32],
+        &signing_key.verifying_key().to_bytes(),
+        actions,
+    )
+    .unwrap();
+    use ed25519_dalek::Signer;
+    let (hash, _size) = tx.get_hash_and_size();
+    assert_eq!(bs58::encode(hash.0).into_string(), SIGNED_TX_FIXTURE_HASH);
+    let signature = signing_key.sign(&hash.0).to_bytes();
+    assert_eq!(sign_transaction(tx, &signature).unwrap(), signed_bytes);
+}
+
+#[test]
+fn rpc_payload_rejects_non_transaction_bytes() {
+    assert!(signed_transaction_rpc_payload(&[]).is_err());
+    assert!(signed_transaction_rpc_payload(&[1, 2, 3]).is_err());
+
+    // Trailing bytes after a valid SignedTransaction are not silently dropped.
+    let mut signed_bytes = crate::encoders::base64_url_decode(SIGNED_TX_FIXTURE_B64U).unwrap();
+    signed_bytes.push(0);
+    assert!(signed_transaction_rpc_payload(&signed_bytes).is_err());
+}
+
+#[test]
+fn transaction_sign_result_serializes_intent_digest_only_when_set() {
+    use crate::handlers::handle_sign_transactions_with_actions::TransactionSignResult;
+
+    let result = TransactionSignResult::new(true, Some(vec![]), None, vec![], None);
+    let json = serde_json::to_value(&result).unwrap();
+    assert!(json.get("intentDigest").is_none());
+
+    let json = serde_json::to_value(result.with_intent_digest("digest-b64u".to_string())).unwrap();
+    assert_eq!(json["intentDigest"], "digest-b64u");
+}
+
+#[test]
+fn parse_nonce_accepts_the_full_u64_range() {
+    assert_eq!(parse_nonce("0"), Ok(0));
+    assert_eq!(parse_nonce(" 42\n"), Ok(42));
+    assert_eq!(parse_nonce("18446744073709551615"), Ok(u64::MAX));
+}
+
+#[test]
+fn parse_nonce_reports_overflow_and_garbage_the_same_way() {
+    assert_eq!(
+        parse_nonce("18446744073709551616"),
+        Err("nonce must be a u64, got '18446744073709551616'".to_string())
+    );
+    assert_eq!(
+        parse_nonce("12abc"),
+        Err("nonce must be a u64, got '12abc'".to_string())
+    );
+    assert_eq!(
+        parse_nonce("-1"),
+        Err("nonce must be a u64, got '-1'".to_string())
+    );
+    assert_eq!(
+        parse_nonce(""),
+        Err("nonce must be a u64, got ''".to_string())
+    );
+}
+
+fn function_call_with_args(args_len: usize) -> NearAction {
+    NearAction::FunctionCall(Box::new(FunctionCallAction {
+        method_name: "store".to_string(),
+        args: vec![b'x'; args_len],
+        gas: 30_000_000_000_000,
+        deposit: 0,
+    }))
+}
+
+/// Tiny FunctionCalls whose args add up to exactly `total` borsh bytes.
+fn function_calls_totalling(count: usize, total: usize) -> Vec<NearAction> {
+    let overhead = borsh::object_length(&function_call_with_args(0)).unwrap();
+    let args_total = total - count * overhead;
+    (0..count)
+        .map(|i| {
+            let extra = usize::from(i < args_total % count);
+            function_call_with_args(args_total / count + extra)
+        })
+        .collect()
+}
+
+fn build_with(actions: Vec<NearAction>) -> Result<Transaction, String> {
+    let public_key_bytes = SigningKey::from_bytes(&[3u8; 32])
+        .verifying_key()
+        .to_bytes();
+    build_transaction_with_actions(
+        "alice.near",
+        "bob.near",
+        1,
+        &[5u8; 32],
+        &public_key_bytes,
+        actions,
+    )
+}
+
+#[test]
+fn transaction_at_the_size_limit_builds() {
+    let actions = function_calls_totalling(90, MAX_TX_SIZE_BYTES);
+    assert_eq!(check_transaction_size(&actions), Ok(MAX_TX_SIZE_BYTES));
+    assert!(build_with(actions).is_ok());
+}
+
+#[test]
+fn transaction_just_over_the_size_limit_is_rejected_before_signing() {
+    let actions = function_calls_totalling(90, MAX_TX_SIZE_BYTES + 1);
+    let expected = format!("transaction too large: {} bytes", MAX_TX_SIZE_BYTES + 1);
+    assert_eq!(check_transaction_size(&actions), Err(expected.clone()));
+    assert_eq!(build_with(actions).unwrap_err(), expected);
+}
+
+/// Signing builds `WasmSignedTransaction`, which needs js_sys; run with
+/// `wasm-pack test --headless --chrome`.
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    /// The confirmed intent digest rides along with the signatures it authorized.
+    #[wasm_bindgen_test]
+    async fn sign_transactions_with_actions_returns_intent_digest() {
+        use crate::crypto::encrypt_data_chacha20;
+        use crate::handlers::handle_sign_transactions_with_actions;
+        use crate::handlers::handle_sign_transactions_with_actions::{
+            SignTransactionsWithActionsRequest, TransactionPayload,
+        };
+        use crate::tests::fixtures::{wrap_key, NEAR_PRIVATE_KEY, NEAR_PUBLIC_KEY};
+        use crate::types::handlers::{RpcCallPayload, TransactionContext};
+        use crate::types::{DecryptionPayload, SignerMode};
+
+        const INTENT_DIGEST: &str = "b9QvQm4Vh0n5p4lXQ2dS0F3m9lJwWg5eZ2r1yQk7xYc";
+
+        let wrap_key = wrap_key();
+        let encrypted =
+            encrypt_data_chacha20(NEAR_PRIVATE_KEY, &wrap_key.derive_kek().unwrap()).unwrap();
+
+        let request = SignTransactionsWithActionsRequest {
+            signer_mode: SignerMode::LocalSigner,
+            rpc_call: RpcCallPayload {
+                contract_id: "w3a-v1.testnet".to_string(),
+                near_rpc_url: "https://rpc.testnet.near.org".to_string(),
+                near_account_id: "alice.testnet".to_string(),
+            },
+            session_id: "intent-digest-session".to_string(),
+            created_at: None,
+            decryption: DecryptionPayload::new(
+                encrypted.encrypted_near_key_data_b64u,
+                encrypted.chacha20_nonce_b64u,
+            ),
+            threshold: None,
+            tx_signing_requests: vec![TransactionPayload {
+                near_account_id: "alice.testnet".to_string(),
+                receiver_id: "bob.testnet".to_string(),
+                actions: vec![ActionParams::Transfer {
+                    deposit: "1".to_string(),
+                }],
+            }],
+            confirmation_config: None,
+            intent_digest: Some(INTENT_DIGEST.to_string()),
+            transaction_context: Some(TransactionContext {
+                near_public_key_str: NEAR_PUBLIC_KEY.to_string(),
+                next_nonce: "42".to_string(),
+                tx_block_height: "1000".to_string(),
+                tx_block_hash: bs58::encode([5u8; 32]).into_string(),
+            }),
+            vrf_challenge: None,
+            credential: None,
+            credential_id: None,
+            allowed_methods: None,
+            allowed_action_kinds: None,
+            include_threshold_proof: false,
+            include_signature_preimage: false,
+            broadcast: None,
+            log_verbosity: None,
+            produce_verification_attestation: false,
+            check_balance: false,
+            contract_code_warn_bytes: None,
+            deadline: Default::default(),
+        };
+
+        let result = handle_sign_transactions_with_actions(request, wrap_key)
+            .await
+            .expect("handler should sign");
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.intent_digest.as_deref(), Some(INTENT_DIGEST));
+        assert_eq!(result.signed_transactions.map(|txs| txs.len()), Some(1));
+    }
+}
@@ -0,0 +1,145 @@
+use ed25519_dalek::{Signer, SigningKey, Verifier};
+use sha2::{Digest, Sha256};
+
+use crate::signable_message::{compute_signable_digest, SignableMessageKind};
+use crate::verification_attestation::{
+    verification_attestation_digest, verify_verification_attestation_inner,
+    ContractVerificationFacts, VerificationAttestation, VERIFICATION_ATTESTATION_VERSION,
+};
+
+const VERIFIED_AT_MS: f64 = 1_700_000_000_123.0;
+
+fn facts() -> ContractVerificationFacts {
+    ContractVerificationFacts {
+        vrf_output: "dnJmLW91dHB1dA".to_string(),
+        credential_id: "Y3JlZGVudGlhbC1pZA".to_string(),
+        contract_id: "w3a-v1.testnet".to_string(),
+        verified_at_ms: VERIFIED_AT_MS,
+        block_height: Some(187_654_321),
+    }
+}
+
+fn signing_key() -> SigningKey {
+    SigningKey::from_bytes(&[0x42u8; 32])
+}
+
+fn sign_attestation(facts: &ContractVerificationFacts) -> VerificationAttestation {
+    let key = signing_key();
+    let digest = verification_attestation_digest(VERIFICATION_ATTESTATION_VERSION, facts).unwrap();
+    let signature = key.sign(&digest).to_bytes();
+    VerificationAttestation::new(facts, &key.verifying_key().to_bytes(), &signature)
+}
+
+#[test]
+fn attestation_round_trips_through_json_and_verifies() {
+    let attestation = sign_attestation(&facts());
+    assert_eq!(attestation.version, VERIFICATION_ATTESTATION_VERSION);
+
+    let json = serde_json::to_value(&attestation).unwrap();
+    assert_eq!(json["contractId"], "w3a-v1.testnet");
+    assert_eq!(json["blockHeight"], 187_654_321u64);
+    let parsed: VerificationAttestation = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed, attestation);
+    verify_verification_attestation_inner(&parsed, VERIFIED_AT_MS, None).unwrap();
+
+    // Any signed field change breaks the signature.
+    let mut tampered = parsed.clone();
+    tampered.contract_id = "evil.testnet".to_string();
+    assert!(verify_verification_attestation_inner(&tampered, VERIFIED_AT_MS, None).is_err());
+    let mut tampered = parsed.clone();
+    tampered.block_height = None;
+    assert!(verify_verification_attestation_inner(&tampered, VERIFIED_AT_MS, None).is_err());
+    let mut tampered = parsed;
+    tampered.version = VERIFICATION_ATTESTATION_VERSION + 1;
+    let err = verify_verification_attestation_inner(&tampered, VERIFIED_AT_MS, None).unwrap_err();
+    assert!(
+        err.contains("Unsupported verification attestation version"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn attestation_signature_is_domain_separated_from_transactions() {
+    let key = signing_key();
+    let verifying_key = key.verifying_key();
+    let facts = facts();
+    let attestation_digest =
+        verification_attestation_digest(VERIFICATION_ATTESTATION_VERSION, &facts).unwrap();
+    let signature = key.sign(&attestation_digest);
+
+    // Same borsh payload, hashed as an (unprefixed) transaction: the attestation signature
+    // must not verify there, and a transaction signature must not verify as an attestation.
+    let attestation = sign_attestation(&facts);
+    let payload_borsh = {
+        let mut bytes = vec![VERIFICATION_ATTESTATION_VERSION];
+        for s in [&facts.vrf_output, &facts.credential_id, &facts.contract_id] {
+            bytes.extend_from_slice(&(s.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(s.as_bytes());
+        }
+        bytes.extend_from_slice(&(VERIFIED_AT_MS as u64).to_le_bytes());
+        bytes.push(1);
+        bytes.extend_from_slice(&187_654_321u64.to_le_bytes());
+        bytes
+    };
+    let tx_digest = compute_signable_digest(SignableMessageKind::Transaction, &payload_borsh);
+    assert_eq!(tx_digest, <[u8; 32]>::from(Sha256::digest(&payload_borsh)));
+    assert_eq!(
+        compute_signable_digest(SignableMessageKind::VerificationAttestation, &payload_borsh),
+        attestation_digest
+    );
+    assert_ne!(tx_digest, attestation_digest);
+    assert!(verifying_key.verify(&tx_digest, &signature).is_err());
+
+    let tx_signature = key.sign(&tx_digest).to_bytes();
+    let mut forged = attestation.clone();
+    forged.signature = crate::encoders::base64_standard_encode(&tx_signature);
+    let err = verify_verification_attestation_inner(&forged, VERIFIED_AT_MS, None).unwrap_err();
+    assert!(err.contains("signature does not verify"), "{}", err);
+
+    // Nor does it collide with the other prefixed kinds.
+    for kind in [
+        SignableMessageKind::DelegateAction,
+        SignableMessageKind::Nep413,
+    ] {
+        assert_ne!(
+            compute_signable_digest(kind, &payload_borsh),
+            attestation_digest
+        );
+    }
+}
+
+#[test]
+fn attestation_freshness_uses_verified_at_from_vrf_worker() {
+    let attestation = sign_attestation(&ContractVerificationFacts {
+        verified_at_ms: VERIFIED_AT_MS + 0.9,
+        ..facts()
+    });
+    // Signed with whole milliseconds; the returned field matches what was signed.
+    assert_eq!(attestation.verified_at_ms, VERIFIED_AT_MS);
+    assert_eq!(attestation.block_height, Some(187_654_321));
+    assert_eq!(attestation.age_ms(VERIFIED_AT_MS + 5_000.0), 5_000.0);
+
+    let window = Some(10_000.0);
+    verify_verification_attestation_inner(&attestation, VERIFIED_AT_MS + 10_000.0, window).unwrap();
+    let err =
+        verify_verification_attestation_inner(&attestation, VERIFIED_AT_MS + 10_001.0, window)
+            .unwrap_err();
+    assert!(err.contains("stale"), "{}", err);
+    // A verifiedAtMs far ahead of the verifier's clock is not fresh either.
+    assert!(
+        verify_verification_attestation_inner(&attestation, VERIFIED_AT_MS - 10_001.0, window)
+            .is_err()
+    );
+    assert!(
+        verify_verification_attestation_inner(&attestation, VERIFIED_AT_MS, Some(f64::NAN))
+            .is_err()
+    );
+
+    // Negative/non-finite timestamps cannot be signed.
+    let bad = ContractVerificationFacts {
+        verified_at_ms: -1.0,
+        ..facts()
+    };
+    assert!(verification_attestation_digest(VERIFICATION_ATTESTATION_VERSION, &bad).is_err());
+}
@@ -0,0 +1,211 @@
+use std::collections::HashSet;
+
+use crate::error::RequestTypeError;
+use crate::types::worker_messages::{
+    worker_request_type_name, worker_response_type_name, WorkerRequestType, WorkerResponseType,
+    REQUEST_TYPE_RANGES, WORKER_REQUEST_TYPE_REGISTRY, WORKER_RESPONSE_TYPE_REGISTRY,
+};
+
+/// Wire values as shipped. The match is exhaustive, so a new variant does not compile until
+/// it is pinned here (and, by the registry tests below, listed in the registry).
+fn pinned_request_value(request_type: WorkerRequestType) -> u32 {
+    match request_type {
+        WorkerRequestType::DeriveNearKeypairAndEncrypt => 0,
+        WorkerRequestType::RecoverKeypairFromPasskey => 1,
+        WorkerRequestType::DecryptPrivateKeyWithPrf => 2,
+        WorkerRequestType::SignTransactionsWithActions => 3,
+        WorkerRequestType::ExtractCosePublicKey => 4,
+        WorkerRequestType::SignTransactionWithKeyPair => 5,
+        WorkerRequestType::SignNep413Message => 6,
+        WorkerRequestType::RegisterDevice2WithDerivedKey => 7,
+        WorkerRequestType::SignDelegateAction => 8,
+        WorkerRequestType::DeriveThresholdEd25519ClientVerifyingShare => 9,
+        WorkerRequestType::SignAddKeyThresholdPublicKeyNoPrompt => 10,
+        WorkerRequestType::MigrateLegacyEncryptedKey => 11,
+        WorkerRequestType::WarmThresholdEd25519ClientShare => 12,
+        WorkerRequestType::ExtractCosePublicKeys => 13,
+        WorkerRequestType::GetDeviceRecords => 14,
+        WorkerRequestType::SignIntent => 15,
+        WorkerRequestType::CreateAccountCard => 16,
+        WorkerRequestType::VerifyAccountCard => 17,
+        WorkerRequestType::GetStateVersion => 18,
+        WorkerRequestType::GetStateChangesSince => 19,
+        WorkerRequestType::LogoutAll => 20,
+        WorkerRequestType::ClearAllSessions => 21,
+        WorkerRequestType::SignBorshTransaction => 22,
+        WorkerRequestType::VerifyEncryptedKeyEnvelope => 23,
+        WorkerRequestType::DebugPanic => 24,
+        WorkerRequestType::ProvisionRelayerRequestAuthSecret => 100,
+    }
+}
+
+fn pinned_response_value(response_type: WorkerResponseType) -> u32 {
+    match response_type {
+        WorkerResponseType::DeriveNearKeypairAndEncryptSuccess => 0,
+        WorkerResponseType::RecoverKeypairFromPasskeySuccess => 1,
+        WorkerResponseType::DecryptPrivateKeyWithPrfSuccess => 2,
+        WorkerResponseType::SignTransactionsWithActionsSuccess => 3,
+        WorkerResponseType::ExtractCosePublicKeySuccess => 4,
+        WorkerResponseType::SignTransactionWithKeyPairSuccess => 5,
+        WorkerResponseType::SignNep413MessageSuccess => 6,
+        WorkerResponseType::RegisterDevice2WithDerivedKeySuccess => 7,
+        WorkerResponseType::SignDelegateActionSuccess => 8,
+        WorkerResponseType::DeriveNearKeypairAndEncryptFailure => 9,
+        WorkerResponseType::RecoverKeypairFromPasskeyFailure => 10,
+        WorkerResponseType::DecryptPrivateKeyWithPrfFailure => 11,
+        WorkerResponseType::SignTransactionsWithActionsFailure => 12,
+        WorkerResponseType::ExtractCosePublicKeyFailure => 13,
+        WorkerResponseType::SignTransactionWithKeyPairFailure => 14,
+        WorkerResponseType::SignNep413MessageFailure => 15,
+        WorkerResponseType::RegisterDevice2WithDerivedKeyFailure => 16,
+        WorkerResponseType::SignDelegateActionFailure => 17,
+        WorkerResponseType::RegistrationProgress => 18,
+        WorkerResponseType::RegistrationComplete => 19,
+        WorkerResponseType::ExecuteActionsProgress => 20,
+        WorkerResponseType::ExecuteActionsComplete => 21,
+        WorkerResponseType::DeriveThresholdEd25519ClientVerifyingShareSuccess => 22,
+        WorkerResponseType::DeriveThresholdEd25519ClientVerifyingShareFailure => 23,
+        WorkerResponseType::SignAddKeyThresholdPublicKeyNoPromptSuccess => 24,
+        WorkerResponseType::SignAddKeyThresholdPublicKeyNoPromptFailure => 25,
+        WorkerResponseType::MigrateLegacyEncryptedKeySuccess => 26,
+        WorkerResponseType::MigrateLegacyEncryptedKeyFailure => 27,
+        WorkerResponseType::WarmThresholdEd25519ClientShareSuccess => 28,
+        WorkerResponseType::WarmThresholdEd25519ClientShareFailure => 29,
+        WorkerResponseType::ExtractCosePublicKeysSuccess => 30,
+        WorkerResponseType::ExtractCosePublicKeysFailure => 31,
+        WorkerResponseType::GetDeviceRecordsSuccess => 32,
+        WorkerResponseType::GetDeviceRecordsFailure => 33,
+        WorkerResponseType::SignIntentSuccess => 34,
+        WorkerResponseType::SignIntentFailure => 35,
+        WorkerResponseType::CreateAccountCardSuccess => 36,
+        WorkerResponseType::CreateAccountCardFailure => 37,
+        WorkerResponseType::VerifyAccountCardSuccess => 38,
+        WorkerResponseType::VerifyAccountCardFailure => 39,
+        WorkerResponseType::GetStateVersionSuccess => 40,
+        WorkerResponseType::GetStateVersionFailure => 41,
+        WorkerResponseType::GetStateChangesSinceSuccess => 42,
+        WorkerResponseType::GetStateChangesSinceFailure => 43,
+        WorkerResponseType::LogoutAllSuccess => 44,
+        WorkerResponseType::LogoutAllFailure => 45,
+        WorkerResponseType::ClearAllSessionsSuccess => 46,
+        WorkerResponseType::ClearAllSessionsFailure => 47,
+        WorkerResponseType::SignBorshTransactionSuccess => 48,
+        WorkerResponseType::SignBorshTransactionFailure => 49,
+        WorkerResponseType::VerifyEncryptedKeyEnvelopeSuccess => 50,
+        WorkerResponseType::VerifyEncryptedKeyEnvelopeFailure => 51,
+        WorkerResponseType::DebugPanicSuccess => 52,
+        WorkerResponseType::DebugPanicFailure => 53,
+        WorkerResponseType::ProvisionRelayerRequestAuthSecretSuccess => 54,
+        WorkerResponseType::ProvisionRelayerRequestAuthSecretFailure => 55,
+    }
+}
+
+#[test]
+fn request_registry_matches_pinned_values_and_names() {
+    for (value, name) in WORKER_REQUEST_TYPE_REGISTRY {
+        let request_type = WorkerRequestType::try_from_u32(value)
+            .unwrap_or_else(|| panic!("registry value {} does not decode", value));
+        assert_eq!(request_type as u32, value);
+        assert_eq!(pinned_request_value(request_type), value);
+        assert_eq!(request_type.name(), name);
+        assert_eq!(worker_request_type_name(request_type), name);
+    }
+}
+
+#[test]
+fn every_decodable_request_value_is_registered() {
+    let registered: HashSet<u32> = WORKER_REQUEST_TYPE_REGISTRY
+        .iter()
+        .map(|(v, _)| *v)
+        .collect();
+    let decodable: HashSet<u32> = (0..1_000)
+        .filter(|v| WorkerRequestType::try_from_u32(*v).is_some())
+        .collect();
+    assert_eq!(decodable, registered);
+}
+
+#[test]
+fn response_registry_matches_pinned_values_and_names() {
+    for (value, name) in WORKER_RESPONSE_TYPE_REGISTRY {
+        let response_type = WorkerResponseType::from(value);
+        assert_eq!(u32::from(response_type), value);
+        assert_eq!(pinned_response_value(response_type), value);
+        assert_eq!(worker_response_type_name(response_type), name);
+    }
+}
+
+#[test]
+fn registry_values_and_names_are_unique() {
+    for registry in [
+        &WORKER_REQUEST_TYPE_REGISTRY[..],
+        &WORKER_RESPONSE_TYPE_REGISTRY[..],
+    ] {
+        let values: HashSet<u32> = registry.iter().map(|(v, _)| *v).collect();
+        let names: HashSet<&str> = registry.iter().map(|(_, n)| *n).collect();
+        assert_eq!(values.len(), registry.len());
+        assert_eq!(names.len(), registry.len());
+    }
+}
+
+#[test]
+fn request_values_sit_in_disjoint_reserved_ranges() {
+    for (i, (range, _)) in REQUEST_TYPE_RANGES.iter().enumerate() {
+        for (other, _) in &REQUEST_TYPE_RANGES[i + 1..] {
+            assert!(range.end <= other.start || other.end <= range.start);
+        }
+    }
+    for (value, name) in WORKER_REQUEST_TYPE_REGISTRY {
+        assert!(
+            REQUEST_TYPE_RANGES
+                .iter()
+                .any(|(range, _)| range.contains(&value)),
+            "{} ({}) is outside every reserved range",
+            name,
+            value
+        );
+    }
+}
+
+#[test]
+fn unassigned_values_tell_newer_clients_from_garbage() {
+    assert_eq!(
+        WorkerRequestType::from_wire(3),
+        Ok(WorkerRequestType::SignTransactionsWithActions)
+    );
+    assert_eq!(
+        WorkerRequestType::from_wire(25),
+        Err(RequestTypeError::FromNewerClient {
+            value: 25,
+            category: "core"
+        })
+    );
+    assert_eq!(
+        WorkerRequestType::from_wire(150),
+        Err(RequestTypeError::FromNewerClient {
+            value: 150,
+            category: "threshold"
+        })
+    );
+    assert_eq!(
+        WorkerRequestType::from_wire(200),
+        Err(RequestTypeError::FromNewerClient {
+            value: 200,
+            category: "session"
+        })
+    );
+    for value in [300, 4_096, u32::MAX] {
+        assert_eq!(
+            WorkerRequestType::from_wire(value),
+            Err(RequestTypeError::Unknown { value })
+        );
+    }
+    let newer = RequestTypeError::FromNewerClient {
+        value: 150,
+        category: "threshold",
+    }
+    .to_string();
+    assert!(newer.contains("newer"));
+    assert!(!RequestTypeError::Unknown { value: 300 }
+        .to_string()
+        .contains("newer"));
+}
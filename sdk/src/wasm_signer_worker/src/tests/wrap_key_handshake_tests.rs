@@ -0,0 +1,529 @@
+//! Browser smoke tests for the WrapKeySeed MessagePort handshake.
+//!
+//! Run with `wasm-pack test --headless --chrome` from `sdk/src/wasm_signer_worker`.
+//! Each test attaches one end of a real `MessageChannel` to the signer registry and plays
+//! the VRF worker on the other end. Session ids are unique per test because the registry
+//! is thread-local and shared across the whole suite.
+#![cfg(target_arch = "wasm32")]
+
+use crate::config::ERROR_OPERATION_CANCELLED;
+use crate::types::worker_messages::WorkerRequestType;
+use crate::wrap_key_handshake::{
+    active_signing_sessions, attach_wrap_key_seed_port, cancel_operation, clear_signing_session,
+    get_prf_second_b64u, get_session_binding_nonce, get_wrap_key_shards, session_material_error,
+};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+use web_sys::{MessageChannel, MessagePort};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const REQUEST_TYPE: WorkerRequestType = WorkerRequestType::SignTransactionsWithActions;
+const SHORT_TIMEOUT_MS: u32 = 50;
+const DELIVERY_TIMEOUT_MS: u32 = 1000;
+
+/// Attach port1 to the signer registry and return port2 for the test to drive.
+fn attach_channel(session_id: &str) -> MessagePort {
+    let channel = MessageChannel::new().expect("MessageChannel");
+    attach_wrap_key_seed_port(session_id.to_string(), channel.port1().into());
+    channel.port2()
+}
+
+fn message(fields: &[(&str, JsValue)]) -> JsValue {
+    let obj = js_sys::Object::new();
+    for (key, value) in fields {
+        js_sys::Reflect::set(&obj, &JsValue::from_str(key), value).unwrap();
+    }
+    obj.into()
+}
+
+fn seed_message(prf_second: Option<&str>) -> JsValue {
+    let mut fields = vec![
+        ("ok", JsValue::TRUE),
+        ("wrap_key_seed", JsValue::from_str("seed-b64u")),
+        ("wrapKeySalt", JsValue::from_str("salt-b64u")),
+    ];
+    if let Some(prf) = prf_second {
+        fields.push(("prfSecond", JsValue::from_str(prf)));
+    }
+    message(&fields)
+}
+
+async fn sleep_ms(ms: i32) {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        let global = js_sys::global();
+        let set_timeout: js_sys::Function = js_sys::Reflect::get(&global, &"setTimeout".into())
+            .unwrap()
+            .dyn_into()
+            .unwrap();
+        set_timeout
+            .call2(&global, &resolve, &JsValue::from(ms))
+            .unwrap();
+    });
+    JsFuture::from(promise).await.unwrap();
+}
+
+fn error_string(err: JsValue) -> String {
+    err.as_string().unwrap_or_else(|| format!("{:?}", err))
+}
+
+#[wasm_bindgen_test]
+async fn delivers_seed_posted_before_the_request() {
+    let sid = "wrap-key-handshake-before";
+    let port = attach_channel(sid);
+    port.post_message(&seed_message(None)).unwrap();
+    // Let the message land in the registry before anyone asks for it.
+    sleep_ms(20).await;
+
+    let wrap_key = get_wrap_key_shards(sid, REQUEST_TYPE, SHORT_TIMEOUT_MS)
+        .await
+        .expect("seed delivered before the request");
+    assert_eq!(wrap_key.wrap_key_seed, "seed-b64u");
+    assert_eq!(wrap_key.wrap_key_salt, "salt-b64u");
+}
+
+#[wasm_bindgen_test]
+async fn resolves_waiter_when_seed_arrives_after_the_request() {
+    let sid = "wrap-key-handshake-after";
+    let port = attach_channel(sid);
+
+    let pending = get_wrap_key_shards(sid, REQUEST_TYPE, DELIVERY_TIMEOUT_MS);
+    let deliver = async {
+        sleep_ms(20).await;
+        port.post_message(&seed_message(None)).unwrap();
+    };
+    let (result, ()) = futures::join!(pending, deliver);
+
+    let wrap_key = result.expect("waiter resolved by later delivery");
+    assert_eq!(wrap_key.wrap_key_seed, "seed-b64u");
+}
+
+#[wasm_bindgen_test]
+async fn seed_landing_just_after_the_timeout_still_resolves() {
+    let sid = "wrap-key-handshake-late";
+    let port = attach_channel(sid);
+
+    // Delivered after SHORT_TIMEOUT_MS but within the bounded re-check window.
+    let pending = get_wrap_key_shards(sid, REQUEST_TYPE, SHORT_TIMEOUT_MS);
+    let deliver = async {
+        sleep_ms(SHORT_TIMEOUT_MS as i32 + 30).await;
+        port.post_message(&seed_message(None)).unwrap();
+    };
+    let (result, ()) = futures::join!(pending, deliver);
+
+    let wrap_key = result.expect("late delivery picked up by the re-check");
+    assert_eq!(wrap_key.wrap_key_seed, "seed-b64u");
+}
+
+#[wasm_bindgen_test]
+async fn propagates_vrf_error_to_waiters() {
+    let sid = "wrap-key-handshake-error";
+    let port = attach_channel(sid);
+
+    let pending = get_wrap_key_shards(sid, REQUEST_TYPE, DELIVERY_TIMEOUT_MS);
+    let deliver = async {
+        sleep_ms(20).await;
+        port.post_message(&message(&[
+            ("ok", JsValue::FALSE),
+            ("error", JsValue::from_str("VRF session expired")),
+        ]))
+        .unwrap();
+    };
+    let (result, ()) = futures::join!(pending, deliver);
+
+    let err = result.err().expect("ok: false must fail the waiter");
+    assert_eq!(error_string(err), "VRF session expired");
+
+    // Later requests for the same session fail fast with the same error.
+    let err = get_wrap_key_shards(sid, REQUEST_TYPE, SHORT_TIMEOUT_MS)
+        .await
+        .err()
+        .expect("stored error");
+    assert_eq!(error_string(err), "VRF session expired");
+}
+
+#[wasm_bindgen_test]
+async fn malformed_message_rejects_waiter_without_waiting_for_the_timeout() {
+    let sid = "wrap-key-handshake-malformed";
+    let port = attach_channel(sid);
+    let expected = format!(
+        "Malformed WrapKeySeed port message for session {}: missing both ok and wrap_key_seed",
+        sid
+    );
+
+    let started_ms = js_sys::Date::now();
+    let pending = get_wrap_key_shards(sid, REQUEST_TYPE, DELIVERY_TIMEOUT_MS);
+    let deliver = async {
+        sleep_ms(20).await;
+        port.post_message(&message(&[("wrapKeySalt", JsValue::from_str("salt-b64u"))]))
+            .unwrap();
+    };
+    let (result, ()) = futures::join!(pending, deliver);
+    let elapsed_ms = js_sys::Date::now() - started_ms;
+
+    let err = result
+        .err()
+        .expect("malformed message must fail the waiter");
+    assert_eq!(error_string(err), expected);
+    assert!(
+        elapsed_ms < f64::from(DELIVERY_TIMEOUT_MS) / 2.0,
+        "waiter rejected after {elapsed_ms}ms, expected well before the timeout"
+    );
+    assert_eq!(session_material_error(sid), Some(expected));
+}
+
+#[wasm_bindgen_test]
+async fn incomplete_material_rejects_waiter_without_waiting_for_the_timeout() {
+    let cases = [
+        (
+            "wrap-key-handshake-ok-without-seed",
+            message(&[("ok", JsValue::TRUE)]),
+            "wrap_key_seed",
+        ),
+        (
+            "wrap-key-handshake-seed-without-salt",
+            message(&[
+                ("ok", JsValue::TRUE),
+                ("wrap_key_seed", JsValue::from_str("seed-b64u")),
+            ]),
+            "wrapKeySalt",
+        ),
+        (
+            "wrap-key-handshake-non-boolean-ok",
+            message(&[
+                ("ok", JsValue::from_str("true")),
+                ("wrap_key_seed", JsValue::from_str("seed-b64u")),
+            ]),
+            "wrapKeySalt",
+        ),
+    ];
+    for (sid, payload, field) in cases {
+        let port = attach_channel(sid);
+        let expected = format!(
+            "Malformed WrapKeySeed port message for session {}: missing {}",
+            sid, field
+        );
+
+        let started_ms = js_sys::Date::now();
+        let pending = get_wrap_key_shards(sid, REQUEST_TYPE, DELIVERY_TIMEOUT_MS);
+        let deliver = async {
+            sleep_ms(20).await;
+            port.post_message(&payload).unwrap();
+        };
+        let (result, ()) = futures::join!(pending, deliver);
+        let elapsed_ms = js_sys::Date::now() - started_ms;
+
+        let err = result
+            .err()
+            .expect("incomplete material must fail the waiter");
+        assert_eq!(error_string(err), expected);
+        assert!(
+            elapsed_ms < f64::from(DELIVERY_TIMEOUT_MS) / 2.0,
+            "{sid}: waiter rejected after {elapsed_ms}ms, expected well before the timeout"
+        );
+        assert_eq!(session_material_error(sid), Some(expected));
+    }
+}
+
+#[wasm_bindgen_test]
+async fn rejects_seed_bound_to_another_session() {
+    let sid = "wrap-key-handshake-binding";
+    let nonce = get_session_binding_nonce(sid.to_string()).unwrap();
+    let other_nonce =
+        get_session_binding_nonce("wrap-key-handshake-binding-other".to_string()).unwrap();
+    let bound_seed_message = |nonce: &str| {
+        message(&[
+            ("ok", JsValue::TRUE),
+            ("wrap_key_seed", JsValue::from_str("seed-b64u")),
+            ("wrapKeySalt", JsValue::from_str("salt-b64u")),
+            ("sessionBindingNonce", JsValue::from_str(nonce)),
+        ])
+    };
+
+    // The port for `sid` carries the other session's material.
+    let port = attach_channel(sid);
+    port.post_message(&bound_seed_message(&other_nonce))
+        .unwrap();
+    sleep_ms(20).await;
+
+    let err = get_wrap_key_shards(sid, REQUEST_TYPE, SHORT_TIMEOUT_MS)
+        .await
+        .err()
+        .expect("misdirected seed must be refused");
+    assert!(error_string(err).starts_with("SessionBindingMismatch"));
+
+    // The session's own nonce is accepted on a fresh port.
+    clear_signing_session(sid.to_string());
+    let nonce_after_clear = get_session_binding_nonce(sid.to_string()).unwrap();
+    assert_ne!(nonce_after_clear, nonce);
+    let port = attach_channel(sid);
+    port.post_message(&bound_seed_message(&nonce_after_clear))
+        .unwrap();
+    let wrap_key = get_wrap_key_shards(sid, REQUEST_TYPE, DELIVERY_TIMEOUT_MS)
+        .await
+        .expect("matching binding delivers the seed");
+    assert_eq!(wrap_key.wrap_key_seed, "seed-b64u");
+}
+
+#[wasm_bindgen_test]
+async fn times_out_when_nothing_is_delivered() {
+    let sid = "wrap-key-handshake-timeout";
+    let _port = attach_channel(sid);
+
+    let err = get_wrap_key_shards(sid, REQUEST_TYPE, SHORT_TIMEOUT_MS)
+        .await
+        .err()
+        .expect("no delivery must time out");
+    assert!(
+        error_string(err).contains("Timed out waiting for WrapKeySeed"),
+        "unexpected timeout error"
+    );
+}
+
+// === CHANNEL CLOSED EARLY ===
+
+/// Fire the `close` handler the signer installed on its end, as a browser does when the VRF
+/// end is closed (not every browser dispatches `close` on MessagePort, so tests call it).
+fn fire_close(port: &MessagePort) {
+    let on_close: js_sys::Function = js_sys::Reflect::get(port, &"onclose".into())
+        .unwrap()
+        .dyn_into()
+        .expect("onclose handler installed");
+    on_close.call0(port).unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn channel_closed_before_delivery_fails_waiters_with_specific_error() {
+    let sid = "wrap-key-handshake-closed-early";
+    let channel = MessageChannel::new().expect("MessageChannel");
+    attach_wrap_key_seed_port(sid.to_string(), channel.port1().into());
+
+    let pending = get_wrap_key_shards(sid, REQUEST_TYPE, DELIVERY_TIMEOUT_MS);
+    let close = async {
+        sleep_ms(20).await;
+        channel.port2().close();
+        fire_close(&channel.port1());
+    };
+    let (result, ()) = futures::join!(pending, close);
+
+    let err = error_string(result.err().expect("closed channel must fail the waiter"));
+    assert!(
+        err.contains("VRF closed the channel before delivering WrapKeySeed"),
+        "unexpected error: {}",
+        err
+    );
+
+    // Later requests fail fast with the same error instead of waiting out the timeout.
+    let err = get_wrap_key_shards(sid, REQUEST_TYPE, SHORT_TIMEOUT_MS)
+        .await
+        .err()
+        .expect("stored error");
+    assert!(error_string(err).contains("VRF closed the channel"));
+}
+
+#[wasm_bindgen_test]
+async fn message_error_fails_waiters_as_channel_closed() {
+    let sid = "wrap-key-handshake-message-error";
+    let channel = MessageChannel::new().expect("MessageChannel");
+    attach_wrap_key_seed_port(sid.to_string(), channel.port1().into());
+
+    let pending = get_wrap_key_shards(sid, REQUEST_TYPE, DELIVERY_TIMEOUT_MS);
+    let fail = async {
+        sleep_ms(20).await;
+        let event = web_sys::MessageEvent::new("messageerror").unwrap();
+        channel.port1().dispatch_event(&event).unwrap();
+    };
+    let (result, ()) = futures::join!(pending, fail);
+
+    let err = error_string(result.err().expect("messageerror must fail the waiter"));
+    assert!(err.contains("VRF closed the channel before delivering WrapKeySeed"));
+}
+
+#[wasm_bindgen_test]
+async fn seed_delivered_before_close_is_kept() {
+    let sid = "wrap-key-handshake-close-after-seed";
+    let channel = MessageChannel::new().expect("MessageChannel");
+    attach_wrap_key_seed_port(sid.to_string(), channel.port1().into());
+
+    channel.port2().post_message(&seed_message(None)).unwrap();
+    channel.port2().close();
+    fire_close(&channel.port1());
+    sleep_ms(40).await;
+
+    let wrap_key = get_wrap_key_shards(sid, REQUEST_TYPE, SHORT_TIMEOUT_MS)
+        .await
+        .expect("the one-shot close after delivery is not an error");
+    assert_eq!(wrap_key.wrap_key_seed, "seed-b64u");
+}
+
+#[wasm_bindgen_test]
+async fn delivers_prf_second_for_device2_registration() {
+    let sid = "wrap-key-handshake-prf-second";
+    let port = attach_channel(sid);
+    let request_type = WorkerRequestType::RegisterDevice2WithDerivedKey;
+
+    let pending = async {
+        let wrap_key = get_wrap_key_shards(sid, request_type, DELIVERY_TIMEOUT_MS).await?;
+        let prf_second = get_prf_second_b64u(sid, request_type, DELIVERY_TIMEOUT_MS).await?;
+        Ok::<_, JsValue>((wrap_key, prf_second))
+    };
+    let deliver = async {
+        sleep_ms(20).await;
+        port.post_message(&seed_message(Some("prf-second-b64u")))
+            .unwrap();
+    };
+    let (result, ()) = futures::join!(pending, deliver);
+
+    let (wrap_key, prf_second) = result.expect("seed and PRF.second delivered");
+    assert_eq!(wrap_key.wrap_key_seed, "seed-b64u");
+    assert_eq!(prf_second, "prf-second-b64u");
+
+    // A seed-only delivery never satisfies a PRF.second wait.
+    let sid = "wrap-key-handshake-prf-second-missing";
+    let port = attach_channel(sid);
+    port.post_message(&seed_message(None)).unwrap();
+    sleep_ms(20).await;
+    let err = get_prf_second_b64u(sid, request_type, SHORT_TIMEOUT_MS)
+        .await
+        .err()
+        .expect("missing PRF.second must time out");
+    assert!(error_string(err).contains("Timed out waiting for PRF.second"));
+}
+
+// === CANCELLATION ===
+
+#[wasm_bindgen_test]
+async fn cancel_interrupts_waiter_with_distinct_error() {
+    let sid = "wrap-key-handshake-cancel";
+    let _port = attach_channel(sid);
+
+    let pending = get_wrap_key_shards(sid, REQUEST_TYPE, DELIVERY_TIMEOUT_MS);
+    let cancel = async {
+        sleep_ms(20).await;
+        cancel_operation(sid.to_string());
+    };
+    let (result, ()) = futures::join!(pending, cancel);
+
+    let err = error_string(result.err().expect("cancel must fail the waiter"));
+    assert_eq!(err, ERROR_OPERATION_CANCELLED);
+    assert!(!err.contains("Timed out"));
+}
+
+#[wasm_bindgen_test]
+async fn finished_wait_leaves_concurrent_wait_cancellable() {
+    let sid = "wrap-key-handshake-cancel-concurrent";
+    let port = attach_channel(sid);
+
+    let started_ms = js_sys::Date::now();
+    let seed_wait = get_wrap_key_shards(sid, REQUEST_TYPE, DELIVERY_TIMEOUT_MS);
+    let prf_wait = get_prf_second_b64u(sid, REQUEST_TYPE, DELIVERY_TIMEOUT_MS);
+    let drive = async {
+        // The seed wait finishes (no PRF.second delivered) while the PRF.second wait runs on.
+        sleep_ms(20).await;
+        port.post_message(&seed_message(None)).unwrap();
+        sleep_ms(20).await;
+        cancel_operation(sid.to_string());
+    };
+    let (seed, prf, ()) = futures::join!(seed_wait, prf_wait, drive);
+    let elapsed_ms = js_sys::Date::now() - started_ms;
+
+    assert_eq!(seed.expect("seed delivered").wrap_key_seed, "seed-b64u");
+    let err = error_string(prf.err().expect("cancel must fail the PRF.second wait"));
+    assert_eq!(err, ERROR_OPERATION_CANCELLED);
+    assert!(
+        elapsed_ms < f64::from(DELIVERY_TIMEOUT_MS) / 2.0,
+        "PRF.second wait cancelled after {elapsed_ms}ms, expected well before the timeout"
+    );
+}
+
+#[wasm_bindgen_test]
+async fn cancel_before_wait_fails_next_wait_only() {
+    let sid = "wrap-key-handshake-cancel-early";
+    let port = attach_channel(sid);
+    cancel_operation(sid.to_string());
+
+    let err = get_prf_second_b64u(sid, REQUEST_TYPE, DELIVERY_TIMEOUT_MS)
+        .await
+        .err()
+        .expect("pending cancel must fail the next wait");
+    assert_eq!(error_string(err), ERROR_OPERATION_CANCELLED);
+
+    // The cancel is consumed: a later wait still receives delivered material.
+    port.post_message(&seed_message(None)).unwrap();
+    let wrap_key = get_wrap_key_shards(sid, REQUEST_TYPE, DELIVERY_TIMEOUT_MS)
+        .await
+        .expect("seed delivered after the cancelled wait");
+    assert_eq!(wrap_key.wrap_key_seed, "seed-b64u");
+}
+
+#[wasm_bindgen_test]
+async fn material_and_timeout_outcomes_are_unaffected_by_other_sessions_cancel() {
+    let sid = "wrap-key-handshake-cancel-other";
+    let port = attach_channel(sid);
+
+    let pending = get_wrap_key_shards(sid, REQUEST_TYPE, DELIVERY_TIMEOUT_MS);
+    let deliver = async {
+        sleep_ms(10).await;
+        cancel_operation("wrap-key-handshake-cancel-unrelated".to_string());
+        sleep_ms(10).await;
+        port.post_message(&seed_message(None)).unwrap();
+    };
+    let (result, ()) = futures::join!(pending, deliver);
+    assert_eq!(result.expect("material arrives").wrap_key_seed, "seed-b64u");
+
+    let sid = "wrap-key-handshake-cancel-timeout";
+    let _port = attach_channel(sid);
+    let err = error_string(
+        get_wrap_key_shards(sid, REQUEST_TYPE, SHORT_TIMEOUT_MS)
+            .await
+            .err()
+            .expect("no delivery must time out"),
+    );
+    assert!(err.contains("Timed out waiting for WrapKeySeed"));
+    assert_ne!(err, ERROR_OPERATION_CANCELLED);
+}
+
+fn active_session_ids() -> Vec<String> {
+    js_sys::Array::from(&active_signing_sessions())
+        .iter()
+        .map(|v| v.as_string().expect("session ids are strings"))
+        .collect()
+}
+
+#[wasm_bindgen_test]
+async fn lists_active_sessions_and_clears_one() {
+    let (sid_a, sid_b) = ("wrap-key-handshake-active-a", "wrap-key-handshake-active-b");
+    attach_channel(sid_a)
+        .post_message(&seed_message(Some("prf-second-b64u")))
+        .unwrap();
+    attach_channel(sid_b)
+        .post_message(&seed_message(None))
+        .unwrap();
+    sleep_ms(20).await;
+
+    let ids = active_session_ids();
+    assert!(ids.iter().any(|id| id == sid_a), "{ids:?}");
+    assert!(ids.iter().any(|id| id == sid_b), "{ids:?}");
+
+    clear_signing_session(sid_a.to_string());
+
+    let ids = active_session_ids();
+    assert!(!ids.iter().any(|id| id == sid_a), "{ids:?}");
+    assert!(ids.iter().any(|id| id == sid_b), "{ids:?}");
+    let err = error_string(
+        get_prf_second_b64u(sid_a, REQUEST_TYPE, SHORT_TIMEOUT_MS)
+            .await
+            .err()
+            .expect("cleared session has no PRF.second"),
+    );
+    assert!(err.contains("Timed out"), "{err}");
+    assert_eq!(
+        get_wrap_key_shards(sid_b, REQUEST_TYPE, SHORT_TIMEOUT_MS)
+            .await
+            .expect("other session untouched")
+            .wrap_key_seed,
+        "seed-b64u"
+    );
+}
@@ -0,0 +1,98 @@
+//! Both orderings of WrapKeySeed delivery vs. the signing request, on the session maps.
+//!
+//! The browser suite (`wrap_key_handshake_tests`) drives a real MessagePort; here the VRF
+//! worker is played by `cache_wrap_key_seed` and the event loop by the `sleep` hook of
+//! `recheck_session_material`, so both orderings run under `cargo test`.
+
+use std::cell::Cell;
+
+use futures::executor::block_on;
+
+use crate::config::{WRAP_KEY_SEED_RECHECK_ATTEMPTS, WRAP_KEY_SEED_RECHECK_INTERVAL_MS};
+use crate::crypto::WrapKey;
+use crate::tests::fixtures::session_wrap_key;
+use crate::types::worker_messages::WorkerRequestType;
+use crate::wrap_key_handshake::{
+    cache_wrap_key_seed, cached_wrap_key, clear_signing_session, get_wrap_key_shards,
+    recheck_session_material,
+};
+
+const REQUEST_TYPE: WorkerRequestType = WorkerRequestType::SignTransactionsWithActions;
+
+fn poll(session_id: &str) -> Option<Result<WrapKey, String>> {
+    cached_wrap_key(session_id).map(Ok)
+}
+
+#[test]
+fn seed_cached_before_the_request_is_served_directly() {
+    let sid = "wrap-key-ordering-seed-first";
+    cache_wrap_key_seed(sid, session_wrap_key());
+
+    let wrap_key = block_on(get_wrap_key_shards(sid, REQUEST_TYPE, 0)).expect("cached seed");
+    assert_eq!(wrap_key.wrap_key_seed, "seed-b64u");
+    assert_eq!(wrap_key.wrap_key_salt, "salt-b64u");
+    clear_signing_session(sid.to_string());
+}
+
+#[test]
+fn seed_arriving_after_the_timeout_is_picked_up_by_the_recheck() {
+    let sid = "wrap-key-ordering-request-first";
+    assert!(poll(sid).is_none(), "nothing delivered yet");
+
+    // The port message lands during the second poll interval.
+    let sleeps = Cell::new(0u32);
+    let outcome = block_on(recheck_session_material(
+        WRAP_KEY_SEED_RECHECK_ATTEMPTS,
+        WRAP_KEY_SEED_RECHECK_INTERVAL_MS,
+        || poll(sid),
+        |ms| {
+            assert_eq!(ms, WRAP_KEY_SEED_RECHECK_INTERVAL_MS);
+            sleeps.set(sleeps.get() + 1);
+            if sleeps.get() == 2 {
+                cache_wrap_key_seed(sid, session_wrap_key());
+            }
+            async {}
+        },
+    ));
+
+    let wrap_key = outcome.expect("material found").expect("no error");
+    assert_eq!(wrap_key.wrap_key_seed, "seed-b64u");
+    assert_eq!(sleeps.get(), 2, "stops polling once material is found");
+    clear_signing_session(sid.to_string());
+}
+
+#[test]
+fn recheck_is_bounded_when_nothing_arrives() {
+    let sid = "wrap-key-ordering-never";
+    let sleeps = Cell::new(0u32);
+    let outcome = block_on(recheck_session_material(
+        WRAP_KEY_SEED_RECHECK_ATTEMPTS,
+        WRAP_KEY_SEED_RECHECK_INTERVAL_MS,
+        || poll(sid),
+        |_| {
+            sleeps.set(sleeps.get() + 1);
+            async {}
+        },
+    ));
+    assert!(outcome.is_none());
+    assert_eq!(sleeps.get(), WRAP_KEY_SEED_RECHECK_ATTEMPTS);
+}
+
+#[test]
+fn recheck_surfaces_an_error_delivered_late() {
+    let delivered = Cell::new(false);
+    let outcome: Option<Result<WrapKey, String>> = block_on(recheck_session_material(
+        WRAP_KEY_SEED_RECHECK_ATTEMPTS,
+        WRAP_KEY_SEED_RECHECK_INTERVAL_MS,
+        || {
+            delivered
+                .get()
+                .then(|| Err("VRF session expired".to_string()))
+        },
+        |_| {
+            delivered.set(true);
+            async {}
+        },
+    ));
+    assert!(matches!(outcome, Some(Err(ref e)) if e == "VRF session expired"));
+}
@@ -0,0 +1,135 @@
+//! Opt-in per-session cache of the client threshold signing share.
+//!
+//! Without warming, every threshold signature re-derives the share from WrapKeySeed via HKDF.
+//! `WarmThresholdEd25519ClientShare` derives it once and stores it here, keyed by
+//! `(session_id, near_account_id)`; `ThresholdEd25519RelayerSigner` consults the cache before
+//! deriving. Entries are zeroized when the session is released.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::threshold::threshold_client_share::derive_threshold_client_signing_share_bytes_v1;
+use crate::WrapKey;
+
+/// Cached client signing shares keyed by `(session_id, near_account_id)`.
+#[derive(Default)]
+pub struct ClientShareCache {
+    shares: HashMap<(String, String), Zeroizing<[u8; 32]>>,
+}
+
+impl ClientShareCache {
+    /// Derive and store the signing share for this session/account, replacing any previous entry.
+    pub fn warm(
+        &mut self,
+        session_id: &str,
+        near_account_id: &str,
+        wrap_key: &WrapKey,
+    ) -> Result<Zeroizing<[u8; 32]>, String> {
+        let share = Zeroizing::new(derive_threshold_client_signing_share_bytes_v1(
+            wrap_key,
+            near_account_id,
+        )?);
+        self.shares.insert(
+            (session_id.to_string(), near_account_id.to_string()),
+            share.clone(),
+        );
+        Ok(share)
+    }
+
+    pub fn get(&self, session_id: &str, near_account_id: &str) -> Option<Zeroizing<[u8; 32]>> {
+        self.shares
+            .get(&(session_id.to_string(), near_account_id.to_string()))
+            .cloned()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.shares.len()
+    }
+
+    /// Remove every entry for `session_id`, zeroizing each share before it is returned.
+    pub fn drain_session(&mut self, session_id: &str) -> Vec<Zeroizing<[u8; 32]>> {
+        let keys: Vec<(String, String)> = self
+            .shares
+            .keys()
+            .filter(|(sid, _)| sid == session_id)
+            .cloned()
+            .collect();
+        keys.into_iter()
+            .filter_map(|key| self.shares.remove(&key))
+            .map(|mut share| {
+                share.zeroize();
+                share
+            })
+            .collect()
+    }
+
+    /// Zeroize and drop every entry, returning how many were cached.
+    pub fn clear(&mut self) -> usize {
+        let cleared = self.shares.len();
+        for (_, mut share) in self.shares.drain() {
+            share.zeroize();
+        }
+        cleared
+    }
+}
+
+thread_local! {
+    static CLIENT_SHARE_CACHE: RefCell<ClientShareCache> = RefCell::new(ClientShareCache::default());
+}
+
+/// Derive the session's client signing share once and cache it for subsequent threshold signs.
+pub(crate) fn warm_client_signing_share(
+    session_id: &str,
+    near_account_id: &str,
+    wrap_key: &WrapKey,
+) -> Result<Zeroizing<[u8; 32]>, String> {
+    CLIENT_SHARE_CACHE.with(|cache| {
+        cache
+            .borrow_mut()
+            .warm(session_id, near_account_id, wrap_key)
+    })
+}
+
+/// Cached share for this session/account if it was warmed, otherwise a fresh derivation
+/// (which is not cached).
+pub(crate) fn client_signing_share(
+    session_id: &str,
+    near_account_id: &str,
+    wrap_key: &WrapKey,
+) -> Result<Zeroizing<[u8; 32]>, String> {
+    if let Some(share) =
+        CLIENT_SHARE_CACHE.with(|cache| cache.borrow().get(session_id, near_account_id))
+    {
+        return Ok(share);
+    }
+    derive_threshold_client_signing_share_bytes_v1(wrap_key, near_account_id).map(Zeroizing::new)
+}
+
+/// Zeroize and drop every cached client signing share for `session_id` (session teardown).
+#[wasm_bindgen]
+pub fn clear_threshold_client_share_cache(session_id: String) {
+    let cleared = CLIENT_SHARE_CACHE.with(|cache| cache.borrow_mut().drain_session(&session_id));
+    if !cleared.is_empty() {
+        log::debug!(
+            "[rust wasm]: cleared {} cached threshold client share(s) for session {}",
+            cleared.len(),
+            session_id
+        );
+    }
+}
+
+/// Zeroize and drop every cached client signing share, across all sessions (`LOGOUT_ALL`).
+pub(crate) fn clear_all_threshold_client_shares() -> usize {
+    CLIENT_SHARE_CACHE.with(|cache| cache.borrow_mut().clear())
+}
+
+/// `clear_all_threshold_client_shares` for the panic hook; false when the cache was mid-borrow.
+pub(crate) fn try_wipe_threshold_client_shares() -> bool {
+    crate::panic_guard::try_wipe(&CLIENT_SHARE_CACHE, |cache| {
+        cache.clear();
+    })
+}
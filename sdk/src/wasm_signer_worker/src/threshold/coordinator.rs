@@ -2,8 +2,16 @@ use crate::types::ThresholdSignerConfig;
 use std::collections::BTreeMap;
 
 use super::protocol;
+use super::threshold_proof::{
+    build_threshold_signature_proof, ThresholdProofShareInput, ThresholdSignatureProof,
+};
 use super::transport::ThresholdEd25519Transport;
 
+pub(super) struct ThresholdSignOutput {
+    pub(super) signature: [u8; 64],
+    pub(super) proof: ThresholdSignatureProof,
+}
+
 pub(super) async fn sign_ed25519_2p_v1<T: ThresholdEd25519Transport>(
     transport: &T,
     cfg: &ThresholdSignerConfig,
@@ -13,7 +21,8 @@ pub(super) async fn sign_ed25519_2p_v1<T: ThresholdEd25519Transport>(
     client_key_package: &frost_ed25519::keys::KeyPackage,
     client_identifier: frost_ed25519::Identifier,
     relayer_identifier: frost_ed25519::Identifier,
-) -> Result<[u8; 64], String> {
+    nonce_lease_id: Option<&str>,
+) -> Result<ThresholdSignOutput, String> {
     let round1 = protocol::client_round1_commit(client_key_package)?;
     let client_commitments_wire = round1.commitments_wire;
 
@@ -26,6 +35,7 @@ pub(super) async fn sign_ed25519_2p_v1<T: ThresholdEd25519Transport>(
             near_account_id,
             &signing_digest_b64u,
             client_commitments_wire,
+            nonce_lease_id,
         )
         .await?;
 
@@ -48,13 +58,18 @@ pub(super) async fn sign_ed25519_2p_v1<T: ThresholdEd25519Transport>(
     let client_sig_share_b64u = protocol::signature_share_to_b64u(&client_sig_share)?;
 
     let relayer_sig_share_b64u = transport
-        .sign_finalize(cfg, &signing_session_id, &client_sig_share_b64u)
+        .sign_finalize(
+            cfg,
+            &signing_session_id,
+            &client_sig_share_b64u,
+            nonce_lease_id,
+        )
         .await?;
 
     let relayer_sig_share = protocol::signature_share_from_b64u(&relayer_sig_share_b64u)?;
 
-    let verifying_key = client_key_package.verifying_key().clone();
-    let client_verifying_share = client_key_package.verifying_share().clone();
+    let verifying_key = *client_key_package.verifying_key();
+    let client_verifying_share = *client_key_package.verifying_share();
     let relayer_verifying_share =
         protocol::verifying_share_from_b64u(&relayer_verifying_share_b64u)?;
 
@@ -66,10 +81,33 @@ pub(super) async fn sign_ed25519_2p_v1<T: ThresholdEd25519Transport>(
     signature_shares_by_id.insert(client_identifier, client_sig_share);
     signature_shares_by_id.insert(relayer_identifier, relayer_sig_share);
 
-    protocol::aggregate_signature(
+    let signature = protocol::aggregate_signature(
         &signing_package,
         verifying_key,
         verifying_shares_by_id,
         signature_shares_by_id,
-    )
+    )?;
+
+    // Public transcript of this signature, for off-chain audit of relayer participation.
+    let proof = build_threshold_signature_proof(
+        signing_digest_32,
+        &verifying_key,
+        &cfg.relayer_key_id,
+        mpc_session_id,
+        ThresholdProofShareInput {
+            identifier: client_identifier,
+            commitments: &round1.commitments,
+            signature_share: &client_sig_share,
+            verifying_share: &client_verifying_share,
+        },
+        ThresholdProofShareInput {
+            identifier: relayer_identifier,
+            commitments: &relayer_commitments,
+            signature_share: &relayer_sig_share,
+            verifying_share: &relayer_verifying_share,
+        },
+        &signature,
+    )?;
+
+    Ok(ThresholdSignOutput { signature, proof })
 }
@@ -1,14 +1,36 @@
-#[cfg(target_arch = "wasm32")]
+//! Threshold signing support. Everything except the local/threshold signer dispatch in
+//! `signer_backend`, config validation (`signer_config`, `participant_ids`, `request_auth`),
+//! nonce reservation bookkeeping (`nonce_reservation`), the relayer request limiter
+//! (`relayer_concurrency`) and the proof bundle types in `threshold_proof` is behind the
+//! `threshold` feature, so slim builds drop frost-ed25519 and curve25519-dalek entirely.
+
+#[cfg(feature = "threshold")]
+pub mod client_share_cache;
+#[cfg(all(feature = "threshold", any(test, target_arch = "wasm32")))]
 pub mod coordinator;
+pub mod nonce_reservation;
 pub mod participant_ids;
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(feature = "threshold", any(test, target_arch = "wasm32")))]
 pub mod protocol;
+pub mod relayer_concurrency;
+#[cfg(feature = "threshold")]
+pub mod relayer_signer;
+pub mod request_auth;
+#[cfg(feature = "threshold")]
+pub mod rp_id_normalization;
+#[cfg(feature = "threshold")]
+pub mod session_clock;
 pub mod signer_backend;
+pub mod signer_config;
+#[cfg(feature = "threshold")]
 pub mod threshold_client_share;
+#[cfg(feature = "threshold")]
 pub mod threshold_digests;
+#[cfg(feature = "threshold")]
 pub mod threshold_frost;
-#[cfg(target_arch = "wasm32")]
+pub mod threshold_proof;
+#[cfg(all(feature = "threshold", any(test, target_arch = "wasm32")))]
 pub mod transport;
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(feature = "threshold", target_arch = "wasm32"))]
 mod relayer_http;
@@ -0,0 +1,171 @@
+//! Relayer-reserved nonces for threshold accounts (`nonceMode: "relayerReserved"`).
+//!
+//! When the relayer broadcasts for a threshold key it may have other transactions in flight,
+//! so `accessKey.nonce + 1` from the client's view can already be taken and the whole FROST
+//! round is wasted on an `InvalidNonce`. With reservation the signer asks the relayer for a
+//! contiguous range up front (`POST /threshold-ed25519/nonce/reserve`), stamps the batch from
+//! it and sends the lease id with `/sign/init` and `/sign/finalize` so the relayer can
+//! correlate the signatures and release what was not used.
+//!
+//! Relayers advertise the capability with `nonceReservation: true` on their keygen or
+//! session response; without it (or once a lease has expired) signing keeps using the
+//! access-key nonce from the transaction context.
+
+use serde::{Deserialize, Serialize};
+
+/// How threshold transactions pick their nonces.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ThresholdNonceMode {
+    /// `nextNonce` from the transaction context, incremented per transaction.
+    #[default]
+    AccessKey,
+    /// A range reserved from the relayer for each batch.
+    RelayerReserved,
+}
+
+impl ThresholdNonceMode {
+    pub fn is_access_key(&self) -> bool {
+        matches!(self, ThresholdNonceMode::AccessKey)
+    }
+}
+
+/// The mode actually used for a batch: `RelayerReserved` only when requested and the relayer
+/// advertised the capability. The session flag is the fresher one, so it wins when present.
+#[cfg_attr(
+    not(all(feature = "threshold", target_arch = "wasm32")),
+    allow(dead_code)
+)]
+pub fn negotiate_nonce_mode(
+    requested: ThresholdNonceMode,
+    keygen_capability: Option<bool>,
+    session_capability: Option<bool>,
+) -> ThresholdNonceMode {
+    match requested {
+        ThresholdNonceMode::AccessKey => ThresholdNonceMode::AccessKey,
+        ThresholdNonceMode::RelayerReserved => {
+            if session_capability.or(keygen_capability).unwrap_or(false) {
+                ThresholdNonceMode::RelayerReserved
+            } else {
+                ThresholdNonceMode::AccessKey
+            }
+        }
+    }
+}
+
+/// A contiguous nonce range leased from the relayer: `start_nonce .. start_nonce + count`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonceReservation {
+    pub lease_id: String,
+    pub start_nonce: u64,
+    pub count: u32,
+    /// Lease expiry on the client clock (ms since epoch).
+    pub expires_at_ms: u64,
+}
+
+impl NonceReservation {
+    /// Check a relayer response against the `requested` batch size.
+    #[cfg_attr(
+        not(all(feature = "threshold", target_arch = "wasm32")),
+        allow(dead_code)
+    )]
+    pub fn new(
+        lease_id: &str,
+        start_nonce: u64,
+        count: u32,
+        expires_at_ms: u64,
+        requested: u32,
+    ) -> Result<Self, String> {
+        let lease_id = lease_id.trim();
+        if lease_id.is_empty() {
+            return Err("threshold-signer: nonce reservation missing leaseId".to_string());
+        }
+        if count < requested {
+            return Err(format!(
+                "threshold-signer: relayer reserved {} nonces, batch needs {}",
+                count, requested
+            ));
+        }
+        if start_nonce.checked_add(u64::from(count)).is_none() {
+            return Err(format!(
+                "threshold-signer: reserved nonce range starting at {} overflows",
+                start_nonce
+            ));
+        }
+        Ok(Self {
+            lease_id: lease_id.to_string(),
+            start_nonce,
+            count,
+            expires_at_ms,
+        })
+    }
+
+    pub fn is_expired(&self, now_ms: u64) -> bool {
+        now_ms >= self.expires_at_ms
+    }
+}
+
+/// Nonce source for one signing batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchNonces {
+    AccessKey {
+        next: u64,
+    },
+    Reserved {
+        reservation: NonceReservation,
+        used: u32,
+    },
+}
+
+impl BatchNonces {
+    /// Use `reservation` if it is still live, else fall back to the access-key nonce. A lease
+    /// that expired before the first transaction is never partially used.
+    pub fn start(access_key_next: u64, reservation: Option<NonceReservation>, now_ms: u64) -> Self {
+        match reservation {
+            Some(reservation) if !reservation.is_expired(now_ms) => BatchNonces::Reserved {
+                reservation,
+                used: 0,
+            },
+            _ => BatchNonces::AccessKey {
+                next: access_key_next,
+            },
+        }
+    }
+
+    /// Lease id to send with `/sign/init` and `/sign/finalize`, when nonces are reserved.
+    pub fn lease_id(&self) -> Option<&str> {
+        match self {
+            BatchNonces::AccessKey { .. } => None,
+            BatchNonces::Reserved { reservation, .. } => Some(&reservation.lease_id),
+        }
+    }
+
+    /// Nonce for the next transaction. A reserved range that runs out or expires mid-batch is
+    /// an error: mixing in access-key nonces could collide with the relayer's own.
+    pub fn next_nonce(&mut self, now_ms: u64) -> Result<u64, String> {
+        match self {
+            BatchNonces::AccessKey { next } => {
+                let nonce = *next;
+                *next = next.saturating_add(1);
+                Ok(nonce)
+            }
+            BatchNonces::Reserved { reservation, used } => {
+                if *used >= reservation.count {
+                    return Err(format!(
+                        "threshold-signer: nonce lease {} exhausted after {} transactions",
+                        reservation.lease_id, reservation.count
+                    ));
+                }
+                if reservation.is_expired(now_ms) {
+                    return Err(format!(
+                        "threshold-signer: nonce lease {} expired mid-batch; retry the remaining transactions",
+                        reservation.lease_id
+                    ));
+                }
+                let nonce = reservation.start_nonce + u64::from(*used);
+                *used += 1;
+                Ok(nonce)
+            }
+        }
+    }
+}
@@ -1,12 +1,12 @@
-use crate::encoders::{base64_url_decode, base64_url_encode};
+use crate::encoders::{base64_url_decode, base64_url_encode, B64UrlBytes};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(super) struct CommitmentsWire {
-    pub(super) hiding: String,
-    pub(super) binding: String,
+    pub(super) hiding: B64UrlBytes,
+    pub(super) binding: B64UrlBytes,
 }
 
 pub(super) struct ClientRound1State {
@@ -18,6 +18,7 @@ pub(super) struct ClientRound1State {
 pub(super) fn client_round1_commit(
     key_package: &frost_ed25519::keys::KeyPackage,
 ) -> Result<ClientRound1State, String> {
+    crate::randomness::ensure_randomness_ok()?;
     let mut rng = frost_ed25519::rand_core::OsRng;
     let (nonces, commitments) =
         frost_ed25519::round1::commit(key_package.signing_share(), &mut rng);
@@ -41,22 +42,17 @@ pub(super) fn commitments_to_wire(
         .serialize()
         .map_err(|e| format!("threshold-signer: serialize binding commitment: {e}"))?;
     Ok(CommitmentsWire {
-        hiding: base64_url_encode(&hiding_bytes),
-        binding: base64_url_encode(&binding_bytes),
+        hiding: B64UrlBytes(hiding_bytes),
+        binding: B64UrlBytes(binding_bytes),
     })
 }
 
 pub(super) fn commitments_from_wire(
     wire: &CommitmentsWire,
 ) -> Result<frost_ed25519::round1::SigningCommitments, String> {
-    let hiding_bytes = base64_url_decode(wire.hiding.trim())
-        .map_err(|e| format!("threshold-signer: invalid commitments.hiding: {e}"))?;
-    let binding_bytes = base64_url_decode(wire.binding.trim())
-        .map_err(|e| format!("threshold-signer: invalid commitments.binding: {e}"))?;
-
-    let hiding = frost_ed25519::round1::NonceCommitment::deserialize(&hiding_bytes)
+    let hiding = frost_ed25519::round1::NonceCommitment::deserialize(wire.hiding.as_bytes())
         .map_err(|e| format!("threshold-signer: invalid hiding commitment: {e}"))?;
-    let binding = frost_ed25519::round1::NonceCommitment::deserialize(&binding_bytes)
+    let binding = frost_ed25519::round1::NonceCommitment::deserialize(wire.binding.as_bytes())
         .map_err(|e| format!("threshold-signer: invalid binding commitment: {e}"))?;
     Ok(frost_ed25519::round1::SigningCommitments::new(
         hiding, binding,
@@ -0,0 +1,127 @@
+//! Cap on relayer HTTP requests in flight at once.
+//!
+//! Each threshold signature takes several relayer round trips, and a batch (or several signing
+//! requests handled concurrently) can otherwise put all of them on the wire together. Every
+//! relayer call first takes a permit from a worker-wide limiter; at most
+//! `maxConcurrentRelayerRequests` (default `THRESHOLD_MAX_CONCURRENT_RELAYER_REQUESTS`) hold
+//! one, and the rest wait in arrival order until a permit is dropped.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+thread_local! {
+    static RELAYER_REQUESTS: RelayerRequestLimiter = RelayerRequestLimiter::default();
+}
+
+/// Permit for one relayer request against the worker-wide limiter.
+#[cfg_attr(
+    not(all(feature = "threshold", target_arch = "wasm32")),
+    allow(dead_code)
+)]
+pub(crate) fn acquire_relayer_request_permit(limit: usize) -> AcquireRelayerRequest {
+    RELAYER_REQUESTS.with(|limiter| limiter.acquire(limit))
+}
+
+#[derive(Default)]
+struct LimiterState {
+    in_flight: usize,
+    next_ticket: u64,
+    /// Tickets of pending acquisitions, oldest first, with the waker to call when one may run.
+    waiting: VecDeque<(u64, Option<Waker>)>,
+}
+
+/// Counting semaphore for the single-threaded worker. Clones share the same permits.
+#[derive(Clone, Default)]
+pub struct RelayerRequestLimiter {
+    state: Rc<RefCell<LimiterState>>,
+}
+
+impl RelayerRequestLimiter {
+    /// Wait for a permit; `limit` is clamped to at least 1.
+    pub fn acquire(&self, limit: usize) -> AcquireRelayerRequest {
+        let mut state = self.state.borrow_mut();
+        let ticket = state.next_ticket;
+        state.next_ticket += 1;
+        state.waiting.push_back((ticket, None));
+        AcquireRelayerRequest {
+            limiter: self.clone(),
+            ticket,
+            limit: limit.max(1),
+            acquired: false,
+        }
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn in_flight(&self) -> usize {
+        self.state.borrow().in_flight
+    }
+
+    fn wake_front(state: &mut LimiterState) {
+        if let Some((_, Some(waker))) = state.waiting.front_mut() {
+            waker.wake_by_ref();
+        }
+    }
+}
+
+/// Future returned by `RelayerRequestLimiter::acquire`. Dropping it before it resolves gives up
+/// its place in the queue.
+pub struct AcquireRelayerRequest {
+    limiter: RelayerRequestLimiter,
+    ticket: u64,
+    limit: usize,
+    acquired: bool,
+}
+
+impl Future for AcquireRelayerRequest {
+    type Output = RelayerRequestPermit;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let limiter = self.limiter.clone();
+        let mut state = limiter.state.borrow_mut();
+        let at_front = state.waiting.front().map(|(t, _)| *t) == Some(self.ticket);
+        if at_front && state.in_flight < self.limit {
+            state.waiting.pop_front();
+            state.in_flight += 1;
+            // The next waiter may fit too when the limit is above the number in flight.
+            RelayerRequestLimiter::wake_front(&mut state);
+            self.acquired = true;
+            return Poll::Ready(RelayerRequestPermit {
+                limiter: limiter.clone(),
+            });
+        }
+        let ticket = self.ticket;
+        if let Some((_, waker)) = state.waiting.iter_mut().find(|(t, _)| *t == ticket) {
+            *waker = Some(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for AcquireRelayerRequest {
+    fn drop(&mut self) {
+        if self.acquired {
+            return;
+        }
+        let mut state = self.limiter.state.borrow_mut();
+        let ticket = self.ticket;
+        state.waiting.retain(|(t, _)| *t != ticket);
+        RelayerRequestLimiter::wake_front(&mut state);
+    }
+}
+
+/// Held for the duration of one relayer request; dropping it lets the next one start.
+pub struct RelayerRequestPermit {
+    limiter: RelayerRequestLimiter,
+}
+
+impl Drop for RelayerRequestPermit {
+    fn drop(&mut self) {
+        let mut state = self.limiter.state.borrow_mut();
+        state.in_flight -= 1;
+        RelayerRequestLimiter::wake_front(&mut state);
+    }
+}
@@ -2,6 +2,7 @@ use super::participant_ids::{
     normalize_participant_ids, validate_threshold_ed25519_participant_ids_2p,
 };
 use super::protocol::CommitmentsWire;
+use super::request_auth::request_path_for_url;
 use crate::fetch::{
     build_json_post_init, fetch_with_init, response_json, response_ok, response_status,
     response_status_text, response_text,
@@ -40,6 +41,32 @@ pub(super) struct ThresholdSessionResponse {
     pub(super) expires_at: Option<String>,
     pub(super) remaining_uses: Option<u32>,
     pub(super) jwt: Option<String>,
+    /// Relayer `Date.now()` when the session was minted (matches the JWT `iat`).
+    pub(super) server_time_ms: Option<f64>,
+    /// The relayer serves `/threshold-ed25519/nonce/reserve` for this key.
+    pub(super) nonce_reservation: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NonceReserveRequest<'a> {
+    relayer_key_id: &'a str,
+    group_public_key: &'a str,
+    count: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct NonceReserveResponse {
+    pub(super) ok: bool,
+    pub(super) code: Option<String>,
+    pub(super) message: Option<String>,
+    pub(super) lease_id: Option<String>,
+    /// Decimal string: nonces exceed the JS safe integer range.
+    pub(super) start_nonce: Option<String>,
+    pub(super) count: Option<u32>,
+    /// Relayer `Date.now()` at which unused nonces return to the pool.
+    pub(super) lease_expires_at_ms: Option<f64>,
 }
 
 fn format_threshold_response_error(
@@ -74,6 +101,8 @@ struct SignInitRequest<'a> {
     signing_digest_b64u: &'a str,
     #[serde(rename = "clientCommitments")]
     client_commitments: CommitmentsWire,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nonce_lease_id: Option<&'a str>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -93,6 +122,8 @@ struct SignFinalizeRequest<'a> {
     signing_session_id: &'a str,
     #[serde(rename = "clientSignatureShareB64u")]
     client_signature_share_b64u: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nonce_lease_id: Option<&'a str>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -121,7 +152,7 @@ fn to_json_string<T: Serialize>(val: &T) -> Result<String, String> {
         .ok_or_else(|| "threshold-signer: JSON.stringify did not return a string".to_string())
 }
 
-fn set_authorization_header(init: &JsValue, token: &str) -> Result<(), String> {
+fn set_header(init: &JsValue, name: &str, value: &str) -> Result<(), String> {
     let headers_val = Reflect::get(init, &JsValue::from_str("headers"))
         .map_err(|_| "threshold-signer: failed to read fetch init.headers".to_string())?;
     if !headers_val.is_object() {
@@ -129,10 +160,38 @@ fn set_authorization_header(init: &JsValue, token: &str) -> Result<(), String> {
     }
     Reflect::set(
         &headers_val,
-        &JsValue::from_str("Authorization"),
-        &JsValue::from_str(&format!("Bearer {}", token.trim())),
+        &JsValue::from_str(name),
+        &JsValue::from_str(value),
     )
-    .map_err(|_| "threshold-signer: failed to set Authorization header".to_string())?;
+    .map_err(|_| format!("threshold-signer: failed to set {name} header"))?;
+    Ok(())
+}
+
+fn set_authorization_header(init: &JsValue, token: &str) -> Result<(), String> {
+    set_header(init, "Authorization", &format!("Bearer {}", token.trim()))
+}
+
+/// Sign `body` exactly as it will be sent when `cfg.request_auth` asks for it. The timestamp
+/// is client time shifted by the relayer clock offset measured at the last session mint.
+fn set_request_auth_headers(
+    cfg: &ThresholdSignerConfig,
+    init: &JsValue,
+    url: &str,
+    body: &str,
+) -> Result<(), String> {
+    if cfg.request_auth.is_none() {
+        return Ok(());
+    }
+    let timestamp_ms = js_sys::Date::now() + super::relayer_signer::relayer_clock_offset_ms();
+    let headers = cfg.request_auth.sign_request(
+        "POST",
+        request_path_for_url(url),
+        body.as_bytes(),
+        timestamp_ms,
+    )?;
+    for (name, value) in headers {
+        set_header(init, &name, &value)?;
+    }
     Ok(())
 }
 
@@ -152,6 +211,7 @@ async fn post_json(
     if let Some(token) = bearer_token {
         set_authorization_header(&init, token)?;
     }
+    set_request_auth_headers(cfg, &init, &url, body)?;
     let resp = fetch_with_init(&url, &init).await?;
 
     if !response_ok(&resp)? {
@@ -569,12 +629,47 @@ pub(super) async fn mint_threshold_session(
     Ok(resp)
 }
 
+/// Reserve `count` consecutive nonces for `group_public_key` (`ed25519:...`). Authenticated
+/// like `/authorize`: the threshold session bearer token, or its cookie.
+pub(super) async fn reserve_nonce(
+    cfg: &ThresholdSignerConfig,
+    group_public_key: &str,
+    count: u32,
+    bearer_token: Option<&str>,
+) -> Result<NonceReserveResponse, String> {
+    let body = to_json_string(&NonceReserveRequest {
+        relayer_key_id: cfg.relayer_key_id.trim(),
+        group_public_key,
+        count,
+    })?;
+    let resp_json = post_json(
+        cfg,
+        "/threshold-ed25519/nonce/reserve",
+        &body,
+        "/nonce/reserve",
+        bearer_token,
+    )
+    .await?;
+    let resp: NonceReserveResponse = serde_wasm_bindgen::from_value(resp_json)
+        .map_err(|e| format!("threshold-signer: failed to parse /nonce/reserve response: {e}"))?;
+
+    if !resp.ok {
+        return Err(format_threshold_response_error(
+            "/nonce/reserve",
+            resp.code.as_deref(),
+            resp.message.as_deref(),
+        ));
+    }
+    Ok(resp)
+}
+
 pub(super) async fn sign_init(
     cfg: &ThresholdSignerConfig,
     mpc_session_id: &str,
     near_account_id: &str,
     signing_digest_b64u: &str,
     client_commitments: CommitmentsWire,
+    nonce_lease_id: Option<&str>,
 ) -> Result<SignInitOk, String> {
     let init_req = SignInitRequest {
         mpc_session_id,
@@ -582,6 +677,7 @@ pub(super) async fn sign_init(
         near_account_id,
         signing_digest_b64u,
         client_commitments,
+        nonce_lease_id,
     };
 
     let init_body = to_json_string(&init_req)?;
@@ -641,10 +737,12 @@ pub(super) async fn sign_finalize(
     cfg: &ThresholdSignerConfig,
     signing_session_id: &str,
     client_signature_share_b64u: &str,
+    nonce_lease_id: Option<&str>,
 ) -> Result<String, String> {
     let finalize_req = SignFinalizeRequest {
         signing_session_id,
         client_signature_share_b64u,
+        nonce_lease_id,
     };
     let finalize_body = to_json_string(&finalize_req)?;
 
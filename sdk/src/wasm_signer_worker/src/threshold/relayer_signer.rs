@@ -0,0 +1,1159 @@
+//! Relayer-assisted 2-of-2 threshold Ed25519 signer (`SignerMode::ThresholdSigner`).
+//!
+//! Holds the client FROST key package and drives the relayer signing protocol, authorizing
+//! each signature with an explicit `mpcSessionId`, a cached relayer threshold session, or
+//! WebAuthn+VRF. Only compiled with the `threshold` feature.
+
+#[cfg(target_arch = "wasm32")]
+use crate::state_changes::{record_state_change, StateChangeKind};
+use crate::threshold::nonce_reservation::NonceReservation;
+use crate::threshold::participant_ids::{
+    normalize_participant_ids, validate_threshold_ed25519_participant_ids_2p,
+};
+#[cfg(target_arch = "wasm32")]
+use crate::threshold::session_clock::SessionClockEstimate;
+use crate::threshold::session_clock::{SessionClockMetrics, SessionClockTracker};
+use crate::threshold::threshold_proof::ThresholdSignatureProof;
+use crate::types::ThresholdSignerConfig;
+use crate::WrapKey;
+#[cfg(target_arch = "wasm32")]
+use js_sys::Date;
+use serde::Serialize;
+use std::cell::RefCell;
+#[cfg(target_arch = "wasm32")]
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use zeroize::Zeroize;
+
+fn threshold_signer_not_implemented_error() -> String {
+    "threshold-signer requires relayer FROST endpoints and threshold key material (client share + relayer share). See docs/threshold-ed25519.md."
+        .to_string()
+}
+
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ThresholdAuthSessionKind {
+    Jwt,
+    Cookie,
+}
+
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone, Debug)]
+struct CachedThresholdAuthSession {
+    kind: ThresholdAuthSessionKind,
+    jwt: Option<String>,
+    /// Relayer-clock expiry.
+    expires_at_ms: Option<f64>,
+    /// Relayer clock offset estimated when this session was minted.
+    clock: SessionClockEstimate,
+    /// `nonceReservation` flag from the session response.
+    nonce_reservation: Option<bool>,
+}
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    static THRESHOLD_AUTH_SESSIONS: RefCell<BTreeMap<String, CachedThresholdAuthSession>> =
+        RefCell::new(BTreeMap::new());
+}
+
+thread_local! {
+    static THRESHOLD_SESSION_CLOCK: RefCell<SessionClockTracker> =
+        RefCell::new(SessionClockTracker::default());
+}
+
+/// Threshold session cache state exposed for diagnostics.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThresholdSessionMetricsSnapshot {
+    pub cached_sessions: usize,
+    #[serde(flatten)]
+    pub clock: SessionClockMetrics,
+}
+
+/// Estimated `relayer_time - client_time` from the last session mint (0 before any mint).
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn relayer_clock_offset_ms() -> f64 {
+    THRESHOLD_SESSION_CLOCK.with(|t| t.borrow().estimate().map_or(0.0, |e| e.offset_ms))
+}
+
+pub fn threshold_session_metrics_snapshot() -> ThresholdSessionMetricsSnapshot {
+    #[cfg(target_arch = "wasm32")]
+    let cached_sessions = THRESHOLD_AUTH_SESSIONS.with(|m| m.borrow().len());
+    #[cfg(not(target_arch = "wasm32"))]
+    let cached_sessions = 0;
+    ThresholdSessionMetricsSnapshot {
+        cached_sessions,
+        clock: THRESHOLD_SESSION_CLOCK.with(|t| t.borrow().snapshot()),
+    }
+}
+
+/// Metrics snapshot for cached relayer threshold sessions, including the current
+/// relayer clock-skew estimate (`clockOffsetMs`, `clockUncertaintyMs`).
+#[wasm_bindgen]
+pub fn get_threshold_session_metrics() -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(&threshold_session_metrics_snapshot())
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize metrics: {e}")))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn threshold_auth_cache_key(cfg: &ThresholdSignerConfig, near_account_id: &str) -> String {
+    let mut out = format!(
+        "{}|{}|{}",
+        cfg.relayer_url.trim_end_matches('/'),
+        cfg.relayer_key_id.trim(),
+        near_account_id.trim()
+    );
+
+    if let Some(ids) = cfg.participant_ids.as_ref() {
+        let mut ids_norm: Vec<u16> = ids.iter().copied().filter(|n| *n > 0).collect();
+        ids_norm.sort_unstable();
+        ids_norm.dedup();
+        if !ids_norm.is_empty() {
+            out.push('|');
+            out.push_str(
+                &ids_norm
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            );
+        }
+    }
+
+    out
+}
+
+#[cfg(target_arch = "wasm32")]
+fn normalize_threshold_session_kind(input: Option<&str>) -> ThresholdAuthSessionKind {
+    match input.map(|s| s.trim()) {
+        Some("cookie") => ThresholdAuthSessionKind::Cookie,
+        _ => ThresholdAuthSessionKind::Jwt,
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn trim_nonempty(input: Option<&str>) -> Option<&str> {
+    input.map(str::trim).filter(|s| !s.is_empty())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn is_cached_session_valid(cfg: &ThresholdSignerConfig, sess: &CachedThresholdAuthSession) -> bool {
+    let refresh_margin_ms = cfg
+        .threshold_session_refresh_margin_ms
+        .unwrap_or(crate::config::THRESHOLD_SESSION_REFRESH_MARGIN_MS);
+    let max_clock_skew_ms = cfg
+        .threshold_session_max_clock_skew_ms
+        .unwrap_or(crate::config::THRESHOLD_SESSION_MAX_CLOCK_SKEW_MS);
+    crate::threshold::session_clock::is_session_fresh(
+        sess.expires_at_ms,
+        Date::now(),
+        &sess.clock.with_max_clock_skew(max_clock_skew_ms),
+        refresh_margin_ms,
+    )
+}
+
+/// Drop a session the relayer rejected. A 401 on a session we believed valid means our
+/// skew estimate was too optimistic, so it is recorded and widened before clearing.
+#[cfg(target_arch = "wasm32")]
+fn clear_rejected_threshold_auth_session(
+    cfg: &ThresholdSignerConfig,
+    near_account_id: &str,
+    sess: &CachedThresholdAuthSession,
+    err: &str,
+) {
+    if crate::threshold::session_clock::is_unauthorized_error(err) {
+        let widened = THRESHOLD_SESSION_CLOCK.with(|t| {
+            t.borrow_mut().record_unexpected_unauthorized(
+                &sess.clock,
+                sess.expires_at_ms,
+                Date::now(),
+            )
+        });
+        log::warn!(
+            "threshold-signer: relayer rejected a cached session that looked valid ({}); clock offset {}ms, uncertainty now {}ms",
+            err,
+            widened.offset_ms,
+            widened.uncertainty_ms
+        );
+    }
+    clear_cached_threshold_auth_session(cfg, near_account_id);
+}
+
+#[cfg(target_arch = "wasm32")]
+fn get_cached_threshold_auth_session(
+    cfg: &ThresholdSignerConfig,
+    near_account_id: &str,
+) -> Option<CachedThresholdAuthSession> {
+    let key = threshold_auth_cache_key(cfg, near_account_id);
+    THRESHOLD_AUTH_SESSIONS.with(|m| m.borrow().get(&key).cloned())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn put_cached_threshold_auth_session(
+    cfg: &ThresholdSignerConfig,
+    near_account_id: &str,
+    session: CachedThresholdAuthSession,
+) {
+    let key = threshold_auth_cache_key(cfg, near_account_id);
+    THRESHOLD_AUTH_SESSIONS.with(|m| {
+        m.borrow_mut().insert(key, session);
+    });
+    record_state_change(
+        StateChangeKind::ThresholdSessionChanged,
+        Some(near_account_id),
+    );
+}
+
+#[cfg(target_arch = "wasm32")]
+fn clear_cached_threshold_auth_session(cfg: &ThresholdSignerConfig, near_account_id: &str) {
+    let key = threshold_auth_cache_key(cfg, near_account_id);
+    let removed = THRESHOLD_AUTH_SESSIONS.with(|m| m.borrow_mut().remove(&key).is_some());
+    if removed {
+        record_state_change(
+            StateChangeKind::ThresholdSessionChanged,
+            Some(near_account_id),
+        );
+    }
+}
+
+/// Drop every cached relayer threshold auth session, zeroizing JWTs (`LOGOUT_ALL`). Returns how
+/// many were cached.
+pub(crate) fn clear_all_threshold_auth_sessions() -> usize {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let cleared = THRESHOLD_AUTH_SESSIONS.with(|m| std::mem::take(&mut *m.borrow_mut()));
+        let count = cleared.len();
+        for (_, mut session) in cleared {
+            if let Some(jwt) = session.jwt.as_mut() {
+                jwt.zeroize();
+            }
+        }
+        count
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        0
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn authorize_mpc_session_id_with_cached_threshold_auth_session_strict(
+    transport: &impl super::transport::ThresholdEd25519Transport,
+    cfg: &ThresholdSignerConfig,
+    client_verifying_share_b64u: &str,
+    near_account_id: &str,
+    purpose: &str,
+    signing_digest_32: &[u8],
+    signing_payload_json: Option<&str>,
+    sess: CachedThresholdAuthSession,
+) -> Result<String, String> {
+    if !is_cached_session_valid(cfg, &sess) {
+        clear_cached_threshold_auth_session(cfg, near_account_id);
+        return Err(
+            "threshold-signer: relayer threshold session expired; re-authenticate".to_string(),
+        );
+    }
+
+    let bearer = match sess.kind {
+        ThresholdAuthSessionKind::Jwt => sess.jwt.as_deref(),
+        ThresholdAuthSessionKind::Cookie => None,
+    };
+
+    match transport
+        .authorize_mpc_session_id_with_threshold_session(
+            cfg,
+            client_verifying_share_b64u,
+            purpose,
+            signing_digest_32,
+            signing_payload_json,
+            bearer,
+        )
+        .await
+    {
+        Ok(id) => Ok(id),
+        Err(e) => {
+            clear_rejected_threshold_auth_session(cfg, near_account_id, &sess, &e);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn try_authorize_mpc_session_id_with_cached_threshold_auth_session(
+    transport: &impl super::transport::ThresholdEd25519Transport,
+    cfg: &ThresholdSignerConfig,
+    client_verifying_share_b64u: &str,
+    near_account_id: &str,
+    purpose: &str,
+    signing_digest_32: &[u8],
+    signing_payload_json: Option<&str>,
+) -> Option<String> {
+    let sess = get_cached_threshold_auth_session(cfg, near_account_id)?;
+    if !is_cached_session_valid(cfg, &sess) {
+        clear_cached_threshold_auth_session(cfg, near_account_id);
+        return None;
+    }
+
+    let bearer = match sess.kind {
+        ThresholdAuthSessionKind::Jwt => sess.jwt.as_deref(),
+        ThresholdAuthSessionKind::Cookie => None,
+    };
+
+    match transport
+        .authorize_mpc_session_id_with_threshold_session(
+            cfg,
+            client_verifying_share_b64u,
+            purpose,
+            signing_digest_32,
+            signing_payload_json,
+            bearer,
+        )
+        .await
+    {
+        Ok(id) => Some(id),
+        Err(e) => {
+            clear_rejected_threshold_auth_session(cfg, near_account_id, &sess, &e);
+            None
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn resolve_mpc_session_id(
+    transport: &impl super::transport::ThresholdEd25519Transport,
+    cfg: &ThresholdSignerConfig,
+    client_verifying_share_b64u: &str,
+    near_account_id: &str,
+    purpose: &str,
+    signing_digest_32: &[u8],
+    signing_payload_json: Option<&str>,
+    vrf_challenge_opt: Option<&crate::types::VrfChallenge>,
+    credential_json_opt: Option<&str>,
+) -> Result<String, String> {
+    if let Some(id) = trim_nonempty(cfg.mpc_session_id.as_deref()) {
+        return Ok(id.to_string());
+    }
+
+    // Forced refresh: forget the cached session so we fall through to mint/authorize.
+    if cfg.force_session_refresh {
+        clear_cached_threshold_auth_session(cfg, near_account_id);
+    }
+
+    // If the caller provided a threshold session JWT (persisted outside this worker), prefer it
+    // over any in-worker cache so session-style authorization works across one-shot signer worker
+    // instances.
+    if let Some(jwt) =
+        trim_nonempty(cfg.threshold_session_jwt.as_deref()).filter(|_| !cfg.force_session_refresh)
+    {
+        return transport
+            .authorize_mpc_session_id_with_threshold_session(
+                cfg,
+                client_verifying_share_b64u,
+                purpose,
+                signing_digest_32,
+                signing_payload_json,
+                Some(jwt),
+            )
+            .await;
+    }
+
+    // Prefer a cached relayer session token/cookie when available.
+    if let Some(sess) = get_cached_threshold_auth_session(cfg, near_account_id) {
+        return authorize_mpc_session_id_with_cached_threshold_auth_session_strict(
+            transport,
+            cfg,
+            client_verifying_share_b64u,
+            near_account_id,
+            purpose,
+            signing_digest_32,
+            signing_payload_json,
+            sess,
+        )
+        .await;
+    }
+
+    // No cached session: require WebAuthn+VRF to mint one (if configured), then authorize per
+    // signature.
+    let vrf_challenge = vrf_challenge_opt.ok_or_else(|| {
+        "threshold-signer: missing vrfChallenge and no cached threshold session token".to_string()
+    })?;
+    let credential_json = credential_json_opt.ok_or_else(|| {
+        "threshold-signer: missing credential and no cached threshold session token".to_string()
+    })?;
+
+    // Best-effort session mint when policy JSON is configured.
+    if let Some(policy_json) = trim_nonempty(cfg.threshold_session_policy_json.as_deref()) {
+        let kind = normalize_threshold_session_kind(cfg.threshold_session_kind.as_deref());
+        let kind_str = match kind {
+            ThresholdAuthSessionKind::Cookie => "cookie",
+            ThresholdAuthSessionKind::Jwt => "jwt",
+        };
+
+        let mint_sent_at_ms = Date::now();
+        if let Ok(sess) = transport
+            .mint_threshold_session(
+                cfg,
+                client_verifying_share_b64u,
+                near_account_id,
+                vrf_challenge,
+                credential_json,
+                policy_json,
+                kind_str,
+            )
+            .await
+        {
+            let clock = THRESHOLD_SESSION_CLOCK.with(|t| {
+                t.borrow_mut()
+                    .record_mint(sess.server_time_ms, mint_sent_at_ms, Date::now())
+            });
+            let expires_at_ms = sess
+                .expires_at
+                .as_deref()
+                .map(Date::parse)
+                .filter(|ms| !ms.is_nan());
+            let cached = CachedThresholdAuthSession {
+                kind,
+                jwt: sess
+                    .jwt
+                    .as_ref()
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty()),
+                expires_at_ms,
+                clock,
+                nonce_reservation: sess.nonce_reservation,
+            };
+            put_cached_threshold_auth_session(cfg, near_account_id, cached);
+        }
+    }
+
+    // After session-mint attempt, prefer session authorization if token/cookie is present.
+    if let Some(id) = try_authorize_mpc_session_id_with_cached_threshold_auth_session(
+        transport,
+        cfg,
+        client_verifying_share_b64u,
+        near_account_id,
+        purpose,
+        signing_digest_32,
+        signing_payload_json,
+    )
+    .await
+    {
+        return Ok(id);
+    }
+
+    // Fallback: authorize per signature with WebAuthn+VRF.
+    transport
+        .authorize_mpc_session_id(
+            cfg,
+            client_verifying_share_b64u,
+            near_account_id,
+            purpose,
+            signing_digest_32,
+            vrf_challenge,
+            credential_json,
+            signing_payload_json,
+        )
+        .await
+}
+
+/// Reserve `count` nonces for the batch when `nonceMode` is `relayerReserved` and the relayer
+/// advertised the capability (session flag first, then the keygen flag in the config).
+/// `Ok(None)` means: use the access-key nonce.
+#[cfg(target_arch = "wasm32")]
+async fn reserve_relayer_nonces(
+    transport: &impl super::transport::ThresholdEd25519Transport,
+    cfg: &ThresholdSignerConfig,
+    near_account_id: &str,
+    group_public_key: &str,
+    count: u32,
+) -> Result<Option<NonceReservation>, String> {
+    use crate::threshold::nonce_reservation::{negotiate_nonce_mode, ThresholdNonceMode};
+
+    let sess = get_cached_threshold_auth_session(cfg, near_account_id)
+        .filter(|sess| is_cached_session_valid(cfg, sess));
+    let mode = negotiate_nonce_mode(
+        cfg.nonce_mode,
+        cfg.relayer_nonce_reservation,
+        sess.as_ref().and_then(|sess| sess.nonce_reservation),
+    );
+    if mode != ThresholdNonceMode::RelayerReserved {
+        return Ok(None);
+    }
+
+    let bearer = match sess.as_ref() {
+        Some(sess) if sess.kind == ThresholdAuthSessionKind::Jwt => sess.jwt.as_deref(),
+        Some(_) => None,
+        None => trim_nonempty(cfg.threshold_session_jwt.as_deref()),
+    };
+    let out = transport
+        .reserve_nonce(cfg, group_public_key, count, bearer)
+        .await?;
+    // The relayer reports expiry on its own clock; without one the lease is good for now only.
+    let now_ms = Date::now();
+    let expires_at_ms = out
+        .lease_expires_at_ms
+        .map(|relayer_ms| relayer_ms - relayer_clock_offset_ms())
+        .unwrap_or(now_ms)
+        .max(0.0) as u64;
+    NonceReservation::new(
+        &out.lease_id,
+        out.start_nonce,
+        out.count,
+        expires_at_ms,
+        count,
+    )
+    .map(Some)
+}
+
+pub enum ThresholdEd25519RelayerSigner {
+    Unconfigured,
+    Configured(ThresholdEd25519RelayerSignerConfigured),
+}
+
+pub struct ThresholdEd25519RelayerSignerConfigured {
+    cfg: ThresholdSignerConfig,
+    near_account_id: String,
+    near_public_key_bytes: [u8; 32],
+    client_verifying_share_b64u: String,
+    client_key_package: frost_ed25519::keys::KeyPackage,
+    client_identifier: frost_ed25519::Identifier,
+    relayer_identifier: frost_ed25519::Identifier,
+    purpose: String,
+    vrf_challenge: Option<crate::types::VrfChallenge>,
+    webauthn_authentication_json: Option<String>,
+    authorize_signing_payload_json: Option<String>,
+    /// Lease of the batch's reserved nonces, sent with every `/sign/init` and `/sign/finalize`.
+    nonce_lease_id: RefCell<Option<String>>,
+}
+
+impl ThresholdEd25519RelayerSigner {
+    pub fn unconfigured() -> Self {
+        Self::Unconfigured
+    }
+
+    pub fn public_key_bytes(&self) -> Result<[u8; 32], String> {
+        match self {
+            Self::Unconfigured => Err(threshold_signer_not_implemented_error()),
+            Self::Configured(cfg) => Ok(cfg.near_public_key_bytes),
+        }
+    }
+
+    /// `session_id` selects a client signing share warmed via `WarmThresholdEd25519ClientShare`;
+    /// without one the share is derived from `wrap_key`.
+    pub fn new(
+        wrap_key: &WrapKey,
+        session_id: &str,
+        near_account_id: &str,
+        near_public_key_str: &str,
+        purpose: &str,
+        vrf_challenge: Option<crate::types::VrfChallenge>,
+        webauthn_authentication_json: Option<String>,
+        authorize_signing_payload_json: Option<String>,
+        cfg: &ThresholdSignerConfig,
+    ) -> Result<Self, String> {
+        let relayer_url = cfg.relayer_url.trim();
+        let relayer_key_id = cfg.relayer_key_id.trim();
+        if relayer_url.is_empty() {
+            return Err("threshold-signer: missing relayerUrl".to_string());
+        }
+        if relayer_key_id.is_empty() {
+            return Err("threshold-signer: missing relayerKeyId".to_string());
+        }
+        let purpose = purpose.trim();
+        if purpose.is_empty() {
+            return Err("threshold-signer: missing purpose".to_string());
+        }
+        crate::signable_message::ensure_known_threshold_purpose(purpose)?;
+
+        let participant_ids_norm = normalize_participant_ids(cfg.participant_ids.as_ref());
+
+        let normalized_mpc_session_id = cfg
+            .mpc_session_id
+            .as_ref()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+
+        // If we don't have an externally provided mpcSessionId, we must have enough context to
+        // authorize per signature. This may be either:
+        // - a WebAuthn+VRF payload (for per-signature or session-mint), or
+        // - a cached threshold auth session token/cookie (session-style).
+        //
+        // signingPayload is always required so the relayer can recompute digests server-side.
+        if normalized_mpc_session_id.is_none()
+            && authorize_signing_payload_json
+                .as_ref()
+                .map(|s| s.trim().is_empty())
+                .unwrap_or(true)
+        {
+            return Err(
+                "threshold-signer: missing signingPayload (required to authorize)".to_string(),
+            );
+        }
+
+        let near_public_key_bytes = parse_near_public_key_to_bytes(near_public_key_str)?;
+
+        let client_id_opt = cfg.client_participant_id.filter(|n| *n > 0);
+        let relayer_id_opt = cfg.relayer_participant_id.filter(|n| *n > 0);
+        let (client_id, relayer_id) = validate_threshold_ed25519_participant_ids_2p(
+            client_id_opt,
+            relayer_id_opt,
+            &participant_ids_norm,
+        )?;
+
+        let client_identifier: frost_ed25519::Identifier = client_id
+            .try_into()
+            .map_err(|_| "threshold-signer: invalid client identifier".to_string())?;
+        let relayer_identifier: frost_ed25519::Identifier = relayer_id
+            .try_into()
+            .map_err(|_| "threshold-signer: invalid relayer identifier".to_string())?;
+
+        let signing_share_bytes = crate::threshold::client_share_cache::client_signing_share(
+            session_id,
+            near_account_id,
+            wrap_key,
+        )?;
+        let verifying_share_bytes =
+            crate::threshold::threshold_client_share::verifying_share_bytes_from_signing_share(
+                &signing_share_bytes,
+            );
+        let key_package = client_key_package_from_signing_share(
+            &signing_share_bytes,
+            &verifying_share_bytes,
+            &near_public_key_bytes,
+            client_identifier,
+        )?;
+        let client_verifying_share_b64u =
+            crate::encoders::base64_url_encode(&verifying_share_bytes);
+
+        let mut cfg_norm = cfg.clone();
+        cfg_norm.mpc_session_id = normalized_mpc_session_id.clone();
+
+        Ok(Self::Configured(ThresholdEd25519RelayerSignerConfigured {
+            cfg: cfg_norm,
+            near_account_id: near_account_id.to_string(),
+            near_public_key_bytes,
+            client_verifying_share_b64u,
+            client_key_package: key_package,
+            client_identifier,
+            relayer_identifier,
+            purpose: purpose.to_string(),
+            vrf_challenge,
+            webauthn_authentication_json,
+            authorize_signing_payload_json,
+            nonce_lease_id: RefCell::new(None),
+        }))
+    }
+
+    /// Reserve nonces for a batch of `count` transactions from the relayer (see
+    /// `nonce_reservation`). Later signatures carry the lease id until `release_nonce_lease`.
+    pub async fn reserve_nonces(&self, count: u32) -> Result<Option<NonceReservation>, String> {
+        let configured = match self {
+            Self::Unconfigured => return Err(threshold_signer_not_implemented_error()),
+            Self::Configured(cfg) => cfg,
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let _ = (configured, count);
+            Ok(None)
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            use super::transport::{HttpThresholdEd25519Transport, RelayerLimitedTransport};
+
+            let group_public_key = format!(
+                "ed25519:{}",
+                bs58::encode(configured.near_public_key_bytes).into_string()
+            );
+            let reservation = reserve_relayer_nonces(
+                &RelayerLimitedTransport(HttpThresholdEd25519Transport),
+                &configured.cfg,
+                &configured.near_account_id,
+                &group_public_key,
+                count,
+            )
+            .await?;
+            *configured.nonce_lease_id.borrow_mut() =
+                reservation.as_ref().map(|r| r.lease_id.clone());
+            Ok(reservation)
+        }
+    }
+
+    /// Stop sending the lease id, e.g. when the batch fell back to access-key nonces.
+    pub fn release_nonce_lease(&self) {
+        if let Self::Configured(cfg) = self {
+            cfg.nonce_lease_id.borrow_mut().take();
+        }
+    }
+
+    pub async fn sign(&self, message: &[u8]) -> Result<[u8; 64], String> {
+        self.sign_with_proof(message)
+            .await
+            .map(|(signature, _proof)| signature)
+    }
+
+    pub async fn sign_with_proof(
+        &self,
+        message: &[u8],
+    ) -> Result<([u8; 64], ThresholdSignatureProof), String> {
+        let configured = match self {
+            Self::Unconfigured => return Err(threshold_signer_not_implemented_error()),
+            Self::Configured(cfg) => cfg,
+        };
+
+        let cfg = &configured.cfg;
+        let near_account_id = configured.near_account_id.as_str();
+        let purpose = configured.purpose.as_str();
+        let client_key_package = &configured.client_key_package;
+        let client_identifier = configured.client_identifier;
+        let relayer_identifier = configured.relayer_identifier;
+        let vrf_challenge_opt = &configured.vrf_challenge;
+        let webauthn_authentication_json_opt = &configured.webauthn_authentication_json;
+        let authorize_signing_payload_json_opt = &configured.authorize_signing_payload_json;
+
+        if message.len() != 32 {
+            return Err(format!(
+                "threshold-signer: signing digest must be 32 bytes, got {}",
+                message.len()
+            ));
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let _ = cfg;
+            let _ = near_account_id;
+            let _ = configured.client_verifying_share_b64u.as_str();
+            let _ = purpose;
+            let _ = client_key_package;
+            let _ = client_identifier;
+            let _ = relayer_identifier;
+            let _ = vrf_challenge_opt;
+            let _ = webauthn_authentication_json_opt;
+            let _ = authorize_signing_payload_json_opt;
+            let _ = message;
+            Err("threshold-signer is only supported in wasm32 builds".to_string())
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            use super::coordinator;
+            use super::transport::{HttpThresholdEd25519Transport, RelayerLimitedTransport};
+
+            let client_verifying_share_b64u = configured.client_verifying_share_b64u.as_str();
+            let transport = RelayerLimitedTransport(HttpThresholdEd25519Transport);
+
+            // Prefer a provided mpcSessionId; otherwise authorize via session/cached WebAuthn.
+            let signing_payload_json = authorize_signing_payload_json_opt.as_deref();
+            let mpc_session_id = resolve_mpc_session_id(
+                &transport,
+                cfg,
+                client_verifying_share_b64u,
+                near_account_id,
+                purpose,
+                message,
+                signing_payload_json,
+                vrf_challenge_opt.as_ref(),
+                webauthn_authentication_json_opt.as_deref(),
+            )
+            .await?;
+
+            let nonce_lease_id = configured.nonce_lease_id.borrow().clone();
+            let output = coordinator::sign_ed25519_2p_v1(
+                &transport,
+                cfg,
+                &mpc_session_id,
+                near_account_id,
+                message,
+                client_key_package,
+                client_identifier,
+                relayer_identifier,
+                nonce_lease_id.as_deref(),
+            )
+            .await?;
+            Ok((output.signature, output.proof))
+        }
+    }
+}
+
+fn parse_near_public_key_to_bytes(public_key: &str) -> Result<[u8; 32], String> {
+    let decoded = bs58::decode(public_key.strip_prefix("ed25519:").unwrap_or(public_key))
+        .into_vec()
+        .map_err(|e| format!("Invalid public key base58: {}", e))?;
+    if decoded.len() != 32 {
+        return Err(format!(
+            "Invalid public key length: expected 32 bytes, got {}",
+            decoded.len()
+        ));
+    }
+    Ok(decoded.as_slice().try_into().expect("checked length above"))
+}
+
+fn client_key_package_from_signing_share(
+    signing_share_bytes: &[u8; 32],
+    verifying_share_bytes: &[u8; 32],
+    near_public_key_bytes: &[u8; 32],
+    client_identifier: frost_ed25519::Identifier,
+) -> Result<frost_ed25519::keys::KeyPackage, String> {
+    let signing_share = frost_ed25519::keys::SigningShare::deserialize(signing_share_bytes)
+        .map_err(|e| format!("threshold-signer: invalid derived signing share: {e}"))?;
+
+    let verifying_share = frost_ed25519::keys::VerifyingShare::deserialize(verifying_share_bytes)
+        .map_err(|e| format!("threshold-signer: invalid verifying share: {e}"))?;
+
+    let verifying_key = frost_ed25519::VerifyingKey::deserialize(near_public_key_bytes)
+        .map_err(|e| format!("threshold-signer: invalid group public key: {e}"))?;
+
+    Ok(frost_ed25519::keys::KeyPackage::new(
+        client_identifier,
+        signing_share,
+        verifying_share,
+        verifying_key,
+        2, // min_signers (2-of-2)
+    ))
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    //! Session resolution against a scripted transport. Run with `wasm-pack test` (the cache
+    //! and clock use `js_sys::Date`); each test uses its own account so the thread-local
+    //! cache does not leak between them.
+    use super::*;
+    use crate::threshold::nonce_reservation::{BatchNonces, ThresholdNonceMode};
+    use crate::threshold::protocol::CommitmentsWire;
+    use crate::threshold::transport::{
+        ThresholdEd25519NonceReservationOk, ThresholdEd25519SessionMintOk,
+        ThresholdEd25519SignInitOk, ThresholdEd25519Transport,
+    };
+    use crate::types::VrfChallenge;
+    use std::cell::{Cell, RefCell};
+    use wasm_bindgen_test::*;
+
+    const CREDENTIAL_JSON: &str = r#"{"id":"cred-1"}"#;
+    const DIGEST: [u8; 32] = [3u8; 32];
+
+    #[derive(Default)]
+    struct ScriptedTransport {
+        mints: Cell<u32>,
+        webauthn_authorizations: Cell<u32>,
+        bearer_tokens: RefCell<Vec<Option<String>>>,
+        /// `nonceReservation` flag returned by `mint_threshold_session`.
+        mint_nonce_reservation: Option<bool>,
+        /// `count` of every `reserve_nonce` call.
+        reservations: RefCell<Vec<u32>>,
+        /// Relayer-clock lease expiry returned by `reserve_nonce`.
+        lease_expires_at_ms: Option<f64>,
+    }
+
+    impl ThresholdEd25519Transport for ScriptedTransport {
+        async fn authorize_mpc_session_id(
+            &self,
+            _cfg: &ThresholdSignerConfig,
+            _client_verifying_share_b64u: &str,
+            _near_account_id: &str,
+            _purpose: &str,
+            _signing_digest_32: &[u8],
+            _vrf_challenge: &VrfChallenge,
+            _credential_json: &str,
+            _signing_payload_json: Option<&str>,
+        ) -> Result<String, String> {
+            self.webauthn_authorizations
+                .set(self.webauthn_authorizations.get() + 1);
+            Ok("mpc-from-webauthn".to_string())
+        }
+
+        async fn authorize_mpc_session_id_with_threshold_session(
+            &self,
+            _cfg: &ThresholdSignerConfig,
+            _client_verifying_share_b64u: &str,
+            _purpose: &str,
+            _signing_digest_32: &[u8],
+            _signing_payload_json: Option<&str>,
+            bearer_token: Option<&str>,
+        ) -> Result<String, String> {
+            self.bearer_tokens
+                .borrow_mut()
+                .push(bearer_token.map(str::to_string));
+            Ok(format!("mpc-from-{}", bearer_token.unwrap_or("cookie")))
+        }
+
+        async fn mint_threshold_session(
+            &self,
+            _cfg: &ThresholdSignerConfig,
+            _client_verifying_share_b64u: &str,
+            _near_account_id: &str,
+            _vrf_challenge: &VrfChallenge,
+            _credential_json: &str,
+            _session_policy_json: &str,
+            _session_kind: &str,
+        ) -> Result<ThresholdEd25519SessionMintOk, String> {
+            self.mints.set(self.mints.get() + 1);
+            Ok(ThresholdEd25519SessionMintOk {
+                expires_at: None,
+                jwt: Some(format!("fresh-jwt-{}", self.mints.get())),
+                server_time_ms: None,
+                nonce_reservation: self.mint_nonce_reservation,
+            })
+        }
+
+        async fn sign_init(
+            &self,
+            _cfg: &ThresholdSignerConfig,
+            _mpc_session_id: &str,
+            _near_account_id: &str,
+            _signing_digest_b64u: &str,
+            _client_commitments: CommitmentsWire,
+            _nonce_lease_id: Option<&str>,
+        ) -> Result<ThresholdEd25519SignInitOk, String> {
+            unreachable!("session resolution does not sign")
+        }
+
+        async fn sign_finalize(
+            &self,
+            _cfg: &ThresholdSignerConfig,
+            _signing_session_id: &str,
+            _client_signature_share_b64u: &str,
+            _nonce_lease_id: Option<&str>,
+        ) -> Result<String, String> {
+            unreachable!("session resolution does not sign")
+        }
+
+        async fn reserve_nonce(
+            &self,
+            _cfg: &ThresholdSignerConfig,
+            _group_public_key: &str,
+            count: u32,
+            bearer_token: Option<&str>,
+        ) -> Result<ThresholdEd25519NonceReservationOk, String> {
+            self.reservations.borrow_mut().push(count);
+            self.bearer_tokens
+                .borrow_mut()
+                .push(bearer_token.map(str::to_string));
+            Ok(ThresholdEd25519NonceReservationOk {
+                lease_id: format!("lease-{}", self.reservations.borrow().len()),
+                start_nonce: 500,
+                count,
+                lease_expires_at_ms: self.lease_expires_at_ms,
+            })
+        }
+    }
+
+    fn config(force_session_refresh: bool) -> ThresholdSignerConfig {
+        serde_wasm_bindgen::from_value(
+            js_sys::JSON::parse(&format!(
+                r#"{{"relayerUrl":"https://relay.example.com","relayerKeyId":"relayer-key-1",
+                    "thresholdSessionPolicyJson":"{{}}","forceSessionRefresh":{}}}"#,
+                force_session_refresh
+            ))
+            .unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn vrf_challenge() -> VrfChallenge {
+        serde_wasm_bindgen::from_value(
+            js_sys::JSON::parse(
+                r#"{"vrfInput":"","vrfOutput":"","vrfProof":"","vrfPublicKey":"","userId":"",
+                    "rpId":"example.com","blockHeight":"1","blockHash":""}"#,
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn seed_cached_session(cfg: &ThresholdSignerConfig, near_account_id: &str) {
+        put_cached_threshold_auth_session(
+            cfg,
+            near_account_id,
+            CachedThresholdAuthSession {
+                kind: ThresholdAuthSessionKind::Jwt,
+                jwt: Some("cached-jwt".to_string()),
+                expires_at_ms: None,
+                clock: SessionClockEstimate::default(),
+                nonce_reservation: None,
+            },
+        );
+    }
+
+    async fn resolve(
+        transport: &ScriptedTransport,
+        cfg: &ThresholdSignerConfig,
+        near_account_id: &str,
+    ) -> Result<String, String> {
+        let vrf_challenge = vrf_challenge();
+        resolve_mpc_session_id(
+            transport,
+            cfg,
+            "client-verifying-share",
+            near_account_id,
+            "near_tx",
+            &DIGEST,
+            None,
+            Some(&vrf_challenge),
+            Some(CREDENTIAL_JSON),
+        )
+        .await
+    }
+
+    #[wasm_bindgen_test]
+    async fn cached_session_is_used_without_force_refresh() {
+        let cfg = config(false);
+        seed_cached_session(&cfg, "cache-hit.near");
+        let transport = ScriptedTransport::default();
+
+        let id = resolve(&transport, &cfg, "cache-hit.near").await.unwrap();
+
+        assert_eq!(id, "mpc-from-cached-jwt");
+        assert_eq!(transport.mints.get(), 0);
+        assert_eq!(
+            *transport.bearer_tokens.borrow(),
+            vec![Some("cached-jwt".to_string())]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn force_refresh_clears_cache_and_mints_fresh_session() {
+        let cfg = config(true);
+        seed_cached_session(&cfg, "force-refresh.near");
+        let transport = ScriptedTransport::default();
+
+        let id = resolve(&transport, &cfg, "force-refresh.near")
+            .await
+            .unwrap();
+
+        assert_eq!(transport.mints.get(), 1);
+        assert_eq!(id, "mpc-from-fresh-jwt-1");
+        assert_eq!(
+            *transport.bearer_tokens.borrow(),
+            vec![Some("fresh-jwt-1".to_string())]
+        );
+        let cached = get_cached_threshold_auth_session(&cfg, "force-refresh.near").unwrap();
+        assert_eq!(cached.jwt.as_deref(), Some("fresh-jwt-1"));
+    }
+
+    #[wasm_bindgen_test]
+    async fn force_refresh_ignores_caller_supplied_session_jwt() {
+        let mut cfg = config(true);
+        cfg.threshold_session_jwt = Some("persisted-jwt".to_string());
+        let transport = ScriptedTransport::default();
+
+        let id = resolve(&transport, &cfg, "force-refresh-jwt.near")
+            .await
+            .unwrap();
+
+        assert_eq!(transport.mints.get(), 1);
+        assert_eq!(id, "mpc-from-fresh-jwt-1");
+    }
+
+    fn reserved_config(keygen_capability: Option<bool>) -> ThresholdSignerConfig {
+        let mut cfg = config(false);
+        cfg.nonce_mode = ThresholdNonceMode::RelayerReserved;
+        cfg.relayer_nonce_reservation = keygen_capability;
+        cfg
+    }
+
+    async fn reserve(
+        transport: &ScriptedTransport,
+        cfg: &ThresholdSignerConfig,
+        near_account_id: &str,
+        count: u32,
+    ) -> Option<NonceReservation> {
+        reserve_relayer_nonces(transport, cfg, near_account_id, "ed25519:group", count)
+            .await
+            .unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    async fn relayer_without_capability_keeps_access_key_nonces() {
+        let cfg = reserved_config(None);
+        let transport = ScriptedTransport::default();
+
+        assert_eq!(
+            reserve(&transport, &cfg, "no-capability.near", 3).await,
+            None
+        );
+        assert!(transport.reservations.borrow().is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    async fn access_key_mode_never_reserves() {
+        let mut cfg = reserved_config(Some(true));
+        cfg.nonce_mode = ThresholdNonceMode::AccessKey;
+        let transport = ScriptedTransport::default();
+
+        assert_eq!(
+            reserve(&transport, &cfg, "access-key-mode.near", 3).await,
+            None
+        );
+        assert!(transport.reservations.borrow().is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    async fn session_capability_overrides_keygen_flag() {
+        let cfg = reserved_config(Some(true));
+        let transport = ScriptedTransport {
+            mint_nonce_reservation: Some(false),
+            ..Default::default()
+        };
+        resolve(&transport, &cfg, "session-says-no.near")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            reserve(&transport, &cfg, "session-says-no.near", 2).await,
+            None
+        );
+        assert!(transport.reservations.borrow().is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    async fn reservation_is_consumed_across_a_batch() {
+        let cfg = reserved_config(None);
+        let transport = ScriptedTransport {
+            mint_nonce_reservation: Some(true),
+            lease_expires_at_ms: Some(Date::now() + 60_000.0),
+            ..Default::default()
+        };
+        resolve(&transport, &cfg, "reserved-batch.near")
+            .await
+            .unwrap();
+
+        let reservation = reserve(&transport, &cfg, "reserved-batch.near", 3).await;
+        assert_eq!(*transport.reservations.borrow(), vec![3]);
+        assert_eq!(
+            transport
+                .bearer_tokens
+                .borrow()
+                .last()
+                .cloned()
+                .flatten()
+                .as_deref(),
+            Some("fresh-jwt-1")
+        );
+
+        let now_ms = Date::now() as u64;
+        let mut nonces = BatchNonces::start(7, reservation, now_ms);
+        assert_eq!(nonces.lease_id(), Some("lease-1"));
+        let stamped: Vec<u64> = (0..3).map(|_| nonces.next_nonce(now_ms).unwrap()).collect();
+        assert_eq!(stamped, vec![500, 501, 502]);
+    }
+
+    #[wasm_bindgen_test]
+    async fn expired_lease_falls_back_to_access_key_nonces() {
+        let cfg = reserved_config(Some(true));
+        let transport = ScriptedTransport {
+            lease_expires_at_ms: Some(Date::now() - 1.0),
+            ..Default::default()
+        };
+
+        let reservation = reserve(&transport, &cfg, "expired-lease.near", 2).await;
+        assert!(reservation.is_some());
+
+        let now_ms = Date::now() as u64;
+        let mut nonces = BatchNonces::start(7, reservation, now_ms);
+        assert_eq!(nonces.lease_id(), None);
+        assert_eq!(nonces.next_nonce(now_ms).unwrap(), 7);
+        assert_eq!(nonces.next_nonce(now_ms).unwrap(), 8);
+    }
+}
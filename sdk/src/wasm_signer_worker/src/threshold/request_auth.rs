@@ -0,0 +1,399 @@
+//! Per-request HMAC authentication for relayer threshold endpoints (`requestAuth`).
+//!
+//! Some relayer operators front their FROST endpoints with signed requests instead of (or in
+//! addition to) threshold sessions. With `kind: "hmac-sha256"` every relayer request carries
+//!
+//! - `<timestampHeaderName>: <unix ms>`
+//! - `<headerName>: keyId=<keyId>,signature=<b64u HMAC-SHA256(secret, string-to-sign)>`
+//!
+//! where the string-to-sign is `timestamp \n METHOD \n path \n b64u(sha256(body))` over the
+//! exact body bytes sent. `verify_relayer_request_signature` applies the same canonicalization
+//! on the relayer side. The shared secret is never included in errors or `Debug` output.
+//!
+//! The secret itself is not part of the signer config: it is provisioned into the worker once
+//! per `keyId` with `PROVISION_RELAYER_REQUEST_AUTH_SECRET` and looked up when signing, so it
+//! never travels in signing payloads and `RequestAuthSecret` cannot be serialized back out.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+
+use hkdf::hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
+
+use crate::config::{
+    THRESHOLD_REQUEST_AUTH_DEFAULT_HEADER, THRESHOLD_REQUEST_AUTH_DEFAULT_TIMESTAMP_HEADER,
+    THRESHOLD_REQUEST_AUTH_MAX_SKEW_MS, THRESHOLD_REQUEST_AUTH_MIN_SECRET_BYTES,
+    THRESHOLD_REQUEST_AUTH_MIN_TIMESTAMP_MS,
+};
+use crate::encoders::{base64_url_decode, base64_url_encode};
+use crate::error::RequestAuthError;
+
+/// Shared HMAC secret (base64url). `Debug` is redacted, the string is zeroized on drop, and
+/// there is deliberately no `Serialize`, so it can never be echoed back to JS.
+#[derive(Clone, PartialEq, Eq, Default, Deserialize)]
+#[serde(transparent)]
+pub struct RequestAuthSecret(String);
+
+impl RequestAuthSecret {
+    pub fn new(secret_b64u: impl Into<String>) -> Self {
+        Self(secret_b64u.into())
+    }
+
+    /// Decoded key bytes. Decode failures are reported without the input.
+    fn decode(&self) -> Result<Vec<u8>, RequestAuthError> {
+        let bytes = base64_url_decode(self.0.trim())
+            .map_err(|_| RequestAuthError::InvalidSecret { decoded_len: None })?;
+        if bytes.len() < THRESHOLD_REQUEST_AUTH_MIN_SECRET_BYTES {
+            return Err(RequestAuthError::InvalidSecret {
+                decoded_len: Some(bytes.len()),
+            });
+        }
+        Ok(bytes)
+    }
+}
+
+impl fmt::Debug for RequestAuthSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+impl Drop for RequestAuthSecret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HmacRequestAuth {
+    /// Identifies the shared secret to the relayer (sent in the signature header) and to the
+    /// worker's provisioned secrets.
+    pub key_id: String,
+    /// Inline secrets are rejected; provision them with `PROVISION_RELAYER_REQUEST_AUTH_SECRET`.
+    #[serde(default, skip_serializing)]
+    pub secret_b64u: Option<RequestAuthSecret>,
+    /// Signature header; defaults to `THRESHOLD_REQUEST_AUTH_DEFAULT_HEADER`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header_name: Option<String>,
+    /// Timestamp header; defaults to `THRESHOLD_REQUEST_AUTH_DEFAULT_TIMESTAMP_HEADER`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_header_name: Option<String>,
+}
+
+/// How relayer requests authenticate themselves, in addition to any threshold session.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum RelayerRequestAuth {
+    #[default]
+    None,
+    HmacSha256(HmacRequestAuth),
+}
+
+impl RelayerRequestAuth {
+    pub fn is_none(&self) -> bool {
+        matches!(self, RelayerRequestAuth::None)
+    }
+
+    /// Trimmed key id and header names with defaults filled in. A config that still carries
+    /// `secretB64u` is rejected, so the secret is not passed around with every request.
+    pub fn normalized(self) -> Result<Self, RequestAuthError> {
+        let RelayerRequestAuth::HmacSha256(auth) = self else {
+            return Ok(RelayerRequestAuth::None);
+        };
+        if auth.secret_b64u.is_some() {
+            return Err(RequestAuthError::InlineSecret);
+        }
+        let key_id = auth.key_id.trim().to_string();
+        validate_key_id(&key_id)?;
+        let header_name = header_name_or_default(
+            auth.header_name.as_deref(),
+            THRESHOLD_REQUEST_AUTH_DEFAULT_HEADER,
+        )?;
+        let timestamp_header_name = header_name_or_default(
+            auth.timestamp_header_name.as_deref(),
+            THRESHOLD_REQUEST_AUTH_DEFAULT_TIMESTAMP_HEADER,
+        )?;
+        if header_name.eq_ignore_ascii_case(&timestamp_header_name) {
+            return Err(RequestAuthError::InvalidHeaderName {
+                name: timestamp_header_name,
+            });
+        }
+        Ok(RelayerRequestAuth::HmacSha256(HmacRequestAuth {
+            key_id,
+            secret_b64u: None,
+            header_name: Some(header_name),
+            timestamp_header_name: Some(timestamp_header_name),
+        }))
+    }
+
+    /// Headers to attach to a relayer request, or none when request auth is off.
+    /// `timestamp_ms` should already be corrected for the estimated relayer clock offset.
+    pub fn sign_request(
+        &self,
+        method: &str,
+        path: &str,
+        body: &[u8],
+        timestamp_ms: f64,
+    ) -> Result<Vec<(String, String)>, RequestAuthError> {
+        let RelayerRequestAuth::HmacSha256(auth) = self.clone().normalized()? else {
+            return Ok(Vec::new());
+        };
+        let timestamp = request_timestamp(timestamp_ms)?;
+        let secret = provisioned_request_auth_secret(&auth.key_id)?;
+        let signature = relayer_request_signature_b64u(
+            &secret.decode()?,
+            &canonical_request_string(timestamp, method, path, body),
+        );
+        Ok(vec![
+            (
+                auth.timestamp_header_name.unwrap_or_default(),
+                timestamp.to_string(),
+            ),
+            (
+                auth.header_name.unwrap_or_default(),
+                format!("keyId={},signature={}", auth.key_id, signature),
+            ),
+        ])
+    }
+}
+
+thread_local! {
+    /// Shared secrets provisioned into this worker, by `keyId`.
+    static REQUEST_AUTH_SECRETS: RefCell<HashMap<String, RequestAuthSecret>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Store the shared secret for `key_id` in this worker, replacing any earlier one. The secret
+/// is checked to decode here, so a bad secret is reported when provisioned rather than on the
+/// first signed request.
+pub fn provision_request_auth_secret(
+    key_id: &str,
+    secret_b64u: RequestAuthSecret,
+) -> Result<String, RequestAuthError> {
+    let key_id = key_id.trim().to_string();
+    validate_key_id(&key_id)?;
+    let secret = RequestAuthSecret::new(secret_b64u.0.trim());
+    secret.decode()?;
+    REQUEST_AUTH_SECRETS.with(|secrets| secrets.borrow_mut().insert(key_id.clone(), secret));
+    Ok(key_id)
+}
+
+fn provisioned_request_auth_secret(key_id: &str) -> Result<RequestAuthSecret, RequestAuthError> {
+    REQUEST_AUTH_SECRETS
+        .with(|secrets| secrets.borrow().get(key_id).cloned())
+        .ok_or_else(|| RequestAuthError::SecretNotProvisioned {
+            key_id: key_id.to_string(),
+        })
+}
+
+/// The string-to-sign: `timestamp \n METHOD \n path \n b64u(sha256(body))`. The method is
+/// upper-cased; the path is the URL path exactly as requested (no query string).
+pub fn canonical_request_string(
+    timestamp_ms: u64,
+    method: &str,
+    path: &str,
+    body: &[u8],
+) -> String {
+    format!(
+        "{}\n{}\n{}\n{}",
+        timestamp_ms,
+        method.trim().to_ascii_uppercase(),
+        path,
+        base64_url_encode(&Sha256::digest(body))
+    )
+}
+
+fn relayer_request_hmac(secret: &[u8], string_to_sign: &str) -> Hmac<Sha256> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(secret)
+        .expect("HMAC-SHA256 accepts keys of any length");
+    mac.update(string_to_sign.as_bytes());
+    mac
+}
+
+pub fn relayer_request_signature_b64u(secret: &[u8], string_to_sign: &str) -> String {
+    base64_url_encode(
+        &relayer_request_hmac(secret, string_to_sign)
+            .finalize()
+            .into_bytes(),
+    )
+}
+
+/// Path component of an absolute relayer URL (`/` when empty), without query or fragment.
+#[cfg(any(test, target_arch = "wasm32"))]
+pub fn request_path_for_url(url: &str) -> &str {
+    let after_scheme = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let path = after_scheme
+        .find('/')
+        .map(|i| &after_scheme[i..])
+        .unwrap_or("/");
+    path.split(['?', '#']).next().unwrap_or("/")
+}
+
+/// Reject client clocks that are obviously wrong (unset, NaN, pre-2020) before signing.
+fn request_timestamp(timestamp_ms: f64) -> Result<u64, RequestAuthError> {
+    if !timestamp_ms.is_finite() || timestamp_ms < THRESHOLD_REQUEST_AUTH_MIN_TIMESTAMP_MS as f64 {
+        return Err(RequestAuthError::ClockUnavailable {
+            timestamp_ms: timestamp_ms.to_string(),
+        });
+    }
+    Ok(timestamp_ms.floor() as u64)
+}
+
+fn validate_key_id(key_id: &str) -> Result<(), RequestAuthError> {
+    if key_id.is_empty() {
+        return Err(RequestAuthError::MissingKeyId);
+    }
+    // Must survive the `keyId=...,signature=...` header grammar unquoted.
+    if !key_id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+    {
+        return Err(RequestAuthError::InvalidKeyId {
+            key_id: key_id.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn header_name_or_default(input: Option<&str>, default: &str) -> Result<String, RequestAuthError> {
+    let name = input
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or(default);
+    // RFC 9110 token characters.
+    let is_token = name.chars().all(|c| {
+        c.is_ascii_alphanumeric()
+            || matches!(
+                c,
+                '!' | '#'
+                    | '$'
+                    | '%'
+                    | '&'
+                    | '\''
+                    | '*'
+                    | '+'
+                    | '-'
+                    | '.'
+                    | '^'
+                    | '_'
+                    | '`'
+                    | '|'
+                    | '~'
+            )
+    });
+    if !is_token {
+        return Err(RequestAuthError::InvalidHeaderName {
+            name: name.to_string(),
+        });
+    }
+    Ok(name.to_string())
+}
+
+/// A signed relayer request as received, for `verify_relayer_request_signature`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedRelayerRequest {
+    pub method: String,
+    pub path: String,
+    /// Raw request body exactly as received.
+    pub body: String,
+    /// Value of the signature header.
+    pub signature_header: String,
+    /// Value of the timestamp header.
+    pub timestamp_header: String,
+}
+
+/// Relayer-side check of a signed request: known key id, timestamp within `max_skew_ms` of
+/// `now_ms`, and a matching signature (compared in constant time).
+pub fn verify_relayer_request_signature(
+    secret: &RequestAuthSecret,
+    expected_key_id: &str,
+    request: &SignedRelayerRequest,
+    now_ms: f64,
+    max_skew_ms: f64,
+) -> Result<(), RequestAuthError> {
+    let (key_id, signature_b64u) = parse_signature_header(&request.signature_header)?;
+    if key_id != expected_key_id.trim() {
+        return Err(RequestAuthError::UnknownKeyId {
+            key_id: key_id.to_string(),
+        });
+    }
+
+    let timestamp_ms: u64 = request
+        .timestamp_header
+        .trim()
+        .parse()
+        .map_err(|_| RequestAuthError::MalformedTimestamp)?;
+    let skew_ms = (timestamp_ms as f64 - now_ms).abs();
+    if skew_ms.is_nan() || skew_ms > max_skew_ms.max(0.0) {
+        return Err(RequestAuthError::TimestampOutOfRange {
+            timestamp_ms,
+            now_ms,
+            max_skew_ms,
+        });
+    }
+
+    let signature = base64_url_decode(signature_b64u)
+        .map_err(|_| RequestAuthError::MalformedSignatureHeader)?;
+    let string_to_sign = canonical_request_string(
+        timestamp_ms,
+        &request.method,
+        &request.path,
+        request.body.as_bytes(),
+    );
+    relayer_request_hmac(&secret.decode()?, &string_to_sign)
+        .verify_slice(&signature)
+        .map_err(|_| RequestAuthError::SignatureMismatch)
+}
+
+/// Split `keyId=<id>,signature=<b64u>` (either order, optional spaces).
+fn parse_signature_header(value: &str) -> Result<(&str, &str), RequestAuthError> {
+    let mut key_id = None;
+    let mut signature = None;
+    for part in value.split(',') {
+        match part.trim().split_once('=') {
+            Some(("keyId", v)) if key_id.is_none() => key_id = Some(v.trim()),
+            Some(("signature", v)) if signature.is_none() => signature = Some(v.trim()),
+            _ => return Err(RequestAuthError::MalformedSignatureHeader),
+        }
+    }
+    match (key_id, signature) {
+        (Some(k), Some(s)) if !k.is_empty() && !s.is_empty() => Ok((k, s)),
+        _ => Err(RequestAuthError::MalformedSignatureHeader),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VerifyRelayerRequestArgs {
+    key_id: String,
+    secret_b64u: RequestAuthSecret,
+    #[serde(flatten)]
+    request: SignedRelayerRequest,
+    now_ms: f64,
+    #[serde(default)]
+    max_skew_ms: Option<f64>,
+}
+
+/// Relayer-side (Node) verification of a `requestAuth: "hmac-sha256"` request. Takes
+/// `{ keyId, secretB64u, method, path, body, signatureHeader, timestampHeader, nowMs,
+/// maxSkewMs? }`; resolves when the request is authentic and rejects with the reason otherwise.
+#[wasm_bindgen]
+pub fn verify_threshold_relayer_request_signature(args: JsValue) -> Result<(), JsValue> {
+    let args: VerifyRelayerRequestArgs = serde_wasm_bindgen::from_value(args)
+        .map_err(|_| JsValue::from_str("Invalid relayer request verification args"))?;
+    verify_relayer_request_signature(
+        &args.secret_b64u,
+        &args.key_id,
+        &args.request,
+        args.now_ms,
+        args.max_skew_ms
+            .unwrap_or(THRESHOLD_REQUEST_AUTH_MAX_SKEW_MS),
+    )
+    .map_err(JsValue::from)
+}
@@ -0,0 +1,132 @@
+//! RP-ID normalization policy for relayer share derivation.
+//!
+//! The normalized rpId is bound into the HKDF `info` of `derive_threshold_relayer_share_scalar_v1`,
+//! so the policy is part of the derivation: switching a deployment to a different policy changes
+//! the derived relayer share (and therefore the group public key) of every account whose rpId
+//! normalizes differently under the two policies. Pick one per deployment and keep it.
+//!
+//! The VRF worker applies the same policies to the rpId it hashes into `vrf_input_data`.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpIdNormalization {
+    /// Lowercase ASCII letters and leave every other character untouched (legacy behavior).
+    #[default]
+    LowercaseAscii,
+    /// Lowercase ASCII letters and reject any non-ASCII rpId.
+    RejectNonAscii,
+    /// Lowercase each label and Punycode-encode non-ASCII labels as `xn--` A-labels.
+    /// This does not apply full UTS #46 mapping (no NFC or compatibility mapping).
+    PunycodeEncode,
+}
+
+impl RpIdNormalization {
+    pub fn normalize(self, rp_id: &str) -> Result<String, String> {
+        match self {
+            RpIdNormalization::LowercaseAscii => Ok(rp_id.to_ascii_lowercase()),
+            RpIdNormalization::RejectNonAscii => {
+                if !rp_id.is_ascii() {
+                    return Err(format!("rpId must be ASCII, got {rp_id:?}"));
+                }
+                Ok(rp_id.to_ascii_lowercase())
+            }
+            RpIdNormalization::PunycodeEncode => {
+                let labels = rp_id
+                    .split('.')
+                    .map(|label| {
+                        if label.is_ascii() {
+                            return Ok(label.to_ascii_lowercase());
+                        }
+                        let lowered: Vec<char> =
+                            label.chars().flat_map(char::to_lowercase).collect();
+                        punycode_encode(&lowered)
+                            .map(|encoded| format!("xn--{encoded}"))
+                            .ok_or_else(|| format!("rpId label too long to encode: {label:?}"))
+                    })
+                    .collect::<Result<Vec<_>, String>>()?;
+                Ok(labels.join("."))
+            }
+        }
+    }
+}
+
+const PUNYCODE_BASE: u32 = 36;
+const PUNYCODE_TMIN: u32 = 1;
+const PUNYCODE_TMAX: u32 = 26;
+const PUNYCODE_SKEW: u32 = 38;
+const PUNYCODE_DAMP: u32 = 700;
+const PUNYCODE_INITIAL_BIAS: u32 = 72;
+const PUNYCODE_INITIAL_N: u32 = 0x80;
+
+fn punycode_adapt(mut delta: u32, num_points: u32, first_time: bool) -> u32 {
+    delta /= if first_time { PUNYCODE_DAMP } else { 2 };
+    delta += delta / num_points;
+    let mut k = 0;
+    while delta > ((PUNYCODE_BASE - PUNYCODE_TMIN) * PUNYCODE_TMAX) / 2 {
+        delta /= PUNYCODE_BASE - PUNYCODE_TMIN;
+        k += PUNYCODE_BASE;
+    }
+    k + (PUNYCODE_BASE - PUNYCODE_TMIN + 1) * delta / (delta + PUNYCODE_SKEW)
+}
+
+fn punycode_digit(d: u32) -> char {
+    match d {
+        0..=25 => (b'a' + d as u8) as char,
+        _ => (b'0' + (d - 26) as u8) as char,
+    }
+}
+
+/// RFC 3492 encoder; returns `None` on arithmetic overflow.
+fn punycode_encode(input: &[char]) -> Option<String> {
+    let mut output: String = input.iter().filter(|c| c.is_ascii()).collect();
+    let basic_len = output.len() as u32;
+    if basic_len > 0 {
+        output.push('-');
+    }
+
+    let mut n = PUNYCODE_INITIAL_N;
+    let mut delta: u32 = 0;
+    let mut bias = PUNYCODE_INITIAL_BIAS;
+    let mut handled = basic_len;
+    let total = input.len() as u32;
+
+    while handled < total {
+        let m = input.iter().map(|&c| c as u32).filter(|&c| c >= n).min()?;
+        delta = delta.checked_add((m - n).checked_mul(handled + 1)?)?;
+        n = m;
+        for &c in input {
+            let c = c as u32;
+            if c < n {
+                delta = delta.checked_add(1)?;
+            }
+            if c == n {
+                let mut q = delta;
+                let mut k = PUNYCODE_BASE;
+                loop {
+                    let t = if k <= bias {
+                        PUNYCODE_TMIN
+                    } else if k >= bias + PUNYCODE_TMAX {
+                        PUNYCODE_TMAX
+                    } else {
+                        k - bias
+                    };
+                    if q < t {
+                        break;
+                    }
+                    output.push(punycode_digit(t + (q - t) % (PUNYCODE_BASE - t)));
+                    q = (q - t) / (PUNYCODE_BASE - t);
+                    k += PUNYCODE_BASE;
+                }
+                output.push(punycode_digit(q));
+                bias = punycode_adapt(delta, handled + 1, handled == basic_len);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta = delta.checked_add(1)?;
+        n = n.checked_add(1)?;
+    }
+    Some(output)
+}
@@ -0,0 +1,166 @@
+//! Clock-skew handling for cached relayer threshold sessions.
+//!
+//! A session's `expiresAt` is relayer time. Comparing it against the client clock directly
+//! discards valid sessions on fast clocks and keeps rejected ones on slow clocks, so each
+//! session carries an offset estimate taken from the relayer's `serverTimeMs` at mint time.
+//! All functions take the client time explicitly so they can be tested with an injected clock.
+
+use serde::Serialize;
+
+/// Estimated relayer clock relative to the client clock.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SessionClockEstimate {
+    /// `relayer_time - client_time` (ms).
+    pub offset_ms: f64,
+    /// Error bound on `offset_ms` (ms): half the mint round trip, widened by unexpected 401s.
+    pub uncertainty_ms: f64,
+}
+
+impl SessionClockEstimate {
+    /// Estimate from a relayer timestamp, assuming it was taken halfway through the round trip.
+    /// Missing or non-finite inputs yield a zero offset (plain client-clock comparison).
+    #[cfg(any(test, target_arch = "wasm32"))]
+    pub fn from_server_time(
+        server_time_ms: Option<f64>,
+        request_sent_ms: f64,
+        response_received_ms: f64,
+    ) -> Self {
+        let Some(server_time_ms) = server_time_ms else {
+            return Self::default();
+        };
+        if !server_time_ms.is_finite()
+            || !request_sent_ms.is_finite()
+            || !response_received_ms.is_finite()
+        {
+            return Self::default();
+        }
+        let round_trip_ms = (response_received_ms - request_sent_ms).max(0.0);
+        Self {
+            offset_ms: server_time_ms - (request_sent_ms + round_trip_ms / 2.0),
+            uncertainty_ms: round_trip_ms / 2.0,
+        }
+    }
+
+    #[cfg(any(test, target_arch = "wasm32"))]
+    pub fn server_now_ms(&self, client_now_ms: f64) -> f64 {
+        client_now_ms + self.offset_ms
+    }
+
+    /// Apply a skew tolerance: never assume the offset is known better than `max_clock_skew_ms`.
+    #[cfg(any(test, target_arch = "wasm32"))]
+    pub fn with_max_clock_skew(mut self, max_clock_skew_ms: f64) -> Self {
+        if max_clock_skew_ms.is_finite() {
+            self.uncertainty_ms = self.uncertainty_ms.max(max_clock_skew_ms);
+        }
+        self
+    }
+
+    /// The relayer rejected a session we believed valid, so its clock had already reached
+    /// `expires_at_ms`. Widen the uncertainty until this session would have counted as expired.
+    #[cfg(any(test, target_arch = "wasm32"))]
+    pub fn widen_for_rejected_session(&mut self, expires_at_ms: f64, client_now_ms: f64) {
+        let gap_ms = expires_at_ms - self.server_now_ms(client_now_ms);
+        if gap_ms.is_finite() && gap_ms > self.uncertainty_ms {
+            self.uncertainty_ms = gap_ms;
+        }
+    }
+}
+
+/// Whether a cached session should still be used: the latest plausible relayer time plus
+/// the early-refresh margin must fall before `expires_at_ms`. Sessions without an expiry
+/// never expire client-side.
+#[cfg(any(test, target_arch = "wasm32"))]
+pub fn is_session_fresh(
+    expires_at_ms: Option<f64>,
+    client_now_ms: f64,
+    clock: &SessionClockEstimate,
+    refresh_margin_ms: f64,
+) -> bool {
+    let Some(expires_at_ms) = expires_at_ms else {
+        return true;
+    };
+    if client_now_ms.is_nan() {
+        return false;
+    }
+    clock.server_now_ms(client_now_ms) + clock.uncertainty_ms + refresh_margin_ms.max(0.0)
+        < expires_at_ms
+}
+
+/// True for relayer errors that mean the session credential itself was rejected.
+#[cfg(any(test, target_arch = "wasm32"))]
+pub fn is_unauthorized_error(err: &str) -> bool {
+    err.trim_start().starts_with("unauthorized") || err.contains(" HTTP 401 ")
+}
+
+/// Worker-wide skew state: the latest estimate (carried across re-mints so a widened bound
+/// is not forgotten) and how often the relayer contradicted it.
+#[derive(Clone, Debug, Default)]
+pub struct SessionClockTracker {
+    estimate: Option<SessionClockEstimate>,
+    unexpected_unauthorized_count: u32,
+}
+
+impl SessionClockTracker {
+    /// Estimate for a freshly minted session. Keeps any uncertainty learned from earlier 401s.
+    #[cfg(any(test, target_arch = "wasm32"))]
+    pub fn record_mint(
+        &mut self,
+        server_time_ms: Option<f64>,
+        request_sent_ms: f64,
+        response_received_ms: f64,
+    ) -> SessionClockEstimate {
+        let mut estimate = SessionClockEstimate::from_server_time(
+            server_time_ms,
+            request_sent_ms,
+            response_received_ms,
+        );
+        if let Some(prior) = self.estimate {
+            estimate.uncertainty_ms = estimate.uncertainty_ms.max(prior.uncertainty_ms);
+        }
+        self.estimate = Some(estimate);
+        estimate
+    }
+
+    /// The relayer returned 401 for a session the client believed valid.
+    #[cfg(any(test, target_arch = "wasm32"))]
+    pub fn record_unexpected_unauthorized(
+        &mut self,
+        session_clock: &SessionClockEstimate,
+        expires_at_ms: Option<f64>,
+        client_now_ms: f64,
+    ) -> SessionClockEstimate {
+        self.unexpected_unauthorized_count = self.unexpected_unauthorized_count.saturating_add(1);
+        let mut widened = *session_clock;
+        if let Some(expires_at_ms) = expires_at_ms {
+            widened.widen_for_rejected_session(expires_at_ms, client_now_ms);
+        }
+        if let Some(prior) = self.estimate {
+            widened.uncertainty_ms = widened.uncertainty_ms.max(prior.uncertainty_ms);
+        }
+        self.estimate = Some(widened);
+        widened
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn estimate(&self) -> Option<SessionClockEstimate> {
+        self.estimate
+    }
+
+    pub fn snapshot(&self) -> SessionClockMetrics {
+        SessionClockMetrics {
+            clock_offset_ms: self.estimate.map(|e| e.offset_ms),
+            clock_uncertainty_ms: self.estimate.map(|e| e.uncertainty_ms),
+            unexpected_unauthorized_count: self.unexpected_unauthorized_count,
+        }
+    }
+}
+
+/// Clock-skew section of the threshold session metrics snapshot.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionClockMetrics {
+    /// Estimated `relayer_time - client_time` (ms); `None` before the first mint.
+    pub clock_offset_ms: Option<f64>,
+    pub clock_uncertainty_ms: Option<f64>,
+    pub unexpected_unauthorized_count: u32,
+}
@@ -0,0 +1,277 @@
+//! Validated construction of `ThresholdSignerConfig`.
+//!
+//! Hosts used to assemble the config field-by-field and only learned about a bad relayer URL or
+//! a contradictory session setup when the first signature failed. The builder checks everything
+//! that does not need the network up front and returns a normalized config: trimmed strings,
+//! empty optionals dropped, and the 2-party signer set filled in.
+
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use crate::config::THRESHOLD_MAX_CONCURRENT_RELAYER_REQUESTS;
+use crate::error::ThresholdConfigError;
+use crate::threshold::nonce_reservation::ThresholdNonceMode;
+use crate::threshold::participant_ids::{
+    normalize_participant_ids, validate_threshold_ed25519_participant_ids_2p,
+};
+use crate::threshold::request_auth::RelayerRequestAuth;
+use crate::types::ThresholdSignerConfig;
+
+/// Field-by-field input for `ThresholdSignerConfig`; also the shape accepted from JS by
+/// `build_threshold_signer_config`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThresholdSignerConfigBuilder {
+    #[serde(default)]
+    relayer_url: Option<String>,
+    #[serde(default)]
+    relayer_key_id: Option<String>,
+    #[serde(default)]
+    client_participant_id: Option<u16>,
+    #[serde(default)]
+    relayer_participant_id: Option<u16>,
+    #[serde(default)]
+    participant_ids: Option<Vec<u16>>,
+    #[serde(default)]
+    mpc_session_id: Option<String>,
+    #[serde(default)]
+    threshold_session_policy_json: Option<String>,
+    #[serde(default)]
+    threshold_session_kind: Option<String>,
+    #[serde(default)]
+    threshold_session_jwt: Option<String>,
+    #[serde(default)]
+    threshold_session_refresh_margin_ms: Option<f64>,
+    #[serde(default)]
+    threshold_session_max_clock_skew_ms: Option<f64>,
+    #[serde(default)]
+    force_session_refresh: bool,
+    #[serde(default)]
+    request_auth: RelayerRequestAuth,
+    #[serde(default)]
+    nonce_mode: ThresholdNonceMode,
+    #[serde(default)]
+    relayer_nonce_reservation: Option<bool>,
+    #[serde(default)]
+    max_concurrent_relayer_requests: Option<u32>,
+}
+
+impl ThresholdSignerConfig {
+    pub fn builder() -> ThresholdSignerConfigBuilder {
+        ThresholdSignerConfigBuilder::default()
+    }
+
+    /// `maxConcurrentRelayerRequests`, or the default; never below 1.
+    #[cfg_attr(
+        not(all(feature = "threshold", target_arch = "wasm32")),
+        allow(dead_code)
+    )]
+    pub fn relayer_request_limit(&self) -> usize {
+        self.max_concurrent_relayer_requests
+            .unwrap_or(THRESHOLD_MAX_CONCURRENT_RELAYER_REQUESTS)
+            .max(1) as usize
+    }
+}
+
+impl ThresholdSignerConfigBuilder {
+    pub fn relayer_url(mut self, url: impl Into<String>) -> Self {
+        self.relayer_url = Some(url.into());
+        self
+    }
+
+    pub fn relayer_key_id(mut self, key_id: impl Into<String>) -> Self {
+        self.relayer_key_id = Some(key_id.into());
+        self
+    }
+
+    /// Client and relayer FROST identifiers; set both or neither.
+    pub fn participants(mut self, client_id: u16, relayer_id: u16) -> Self {
+        self.client_participant_id = Some(client_id);
+        self.relayer_participant_id = Some(relayer_id);
+        self
+    }
+
+    pub fn participant_ids(mut self, ids: Vec<u16>) -> Self {
+        self.participant_ids = Some(ids);
+        self
+    }
+
+    pub fn mpc_session_id(mut self, id: impl Into<String>) -> Self {
+        self.mpc_session_id = Some(id.into());
+        self
+    }
+
+    pub fn session_policy_json(mut self, json: impl Into<String>) -> Self {
+        self.threshold_session_policy_json = Some(json.into());
+        self
+    }
+
+    /// `"jwt"` or `"cookie"`.
+    pub fn session_kind(mut self, kind: impl Into<String>) -> Self {
+        self.threshold_session_kind = Some(kind.into());
+        self
+    }
+
+    pub fn session_jwt(mut self, jwt: impl Into<String>) -> Self {
+        self.threshold_session_jwt = Some(jwt.into());
+        self
+    }
+
+    pub fn session_refresh_margin_ms(mut self, ms: f64) -> Self {
+        self.threshold_session_refresh_margin_ms = Some(ms);
+        self
+    }
+
+    pub fn session_max_clock_skew_ms(mut self, ms: f64) -> Self {
+        self.threshold_session_max_clock_skew_ms = Some(ms);
+        self
+    }
+
+    pub fn force_session_refresh(mut self, force: bool) -> Self {
+        self.force_session_refresh = force;
+        self
+    }
+
+    pub fn request_auth(mut self, auth: RelayerRequestAuth) -> Self {
+        self.request_auth = auth;
+        self
+    }
+
+    pub fn nonce_mode(mut self, mode: ThresholdNonceMode) -> Self {
+        self.nonce_mode = mode;
+        self
+    }
+
+    /// The relayer's `nonceReservation` flag from keygen.
+    pub fn relayer_nonce_reservation(mut self, supported: bool) -> Self {
+        self.relayer_nonce_reservation = Some(supported);
+        self
+    }
+
+    /// Most relayer HTTP requests in flight at once; at least 1.
+    pub fn max_concurrent_relayer_requests(mut self, limit: u32) -> Self {
+        self.max_concurrent_relayer_requests = Some(limit);
+        self
+    }
+
+    pub fn build(self) -> Result<ThresholdSignerConfig, ThresholdConfigError> {
+        let relayer_url = normalize_relayer_url(self.relayer_url.as_deref())?;
+        let relayer_key_id = trim_nonempty(self.relayer_key_id.as_deref())
+            .ok_or(ThresholdConfigError::MissingRelayerKeyId)?;
+
+        let mut participant_ids = normalize_participant_ids(self.participant_ids.as_ref());
+        let (client_id, relayer_id) = validate_threshold_ed25519_participant_ids_2p(
+            self.client_participant_id,
+            self.relayer_participant_id,
+            &participant_ids,
+        )
+        .map_err(|reason| ThresholdConfigError::InvalidParticipantIds { reason })?;
+        if participant_ids.is_empty() {
+            participant_ids = vec![client_id, relayer_id];
+            participant_ids.sort_unstable();
+        }
+
+        let threshold_session_kind = match trim_nonempty(self.threshold_session_kind.as_deref()) {
+            None => None,
+            Some(kind) => match kind.to_ascii_lowercase().as_str() {
+                "jwt" => Some("jwt".to_string()),
+                "cookie" => Some("cookie".to_string()),
+                _ => return Err(ThresholdConfigError::InvalidSessionKind { kind }),
+            },
+        };
+        let threshold_session_jwt = trim_nonempty(self.threshold_session_jwt.as_deref());
+        if threshold_session_jwt.is_some() && threshold_session_kind.as_deref() == Some("cookie") {
+            return Err(ThresholdConfigError::JwtWithCookieSession);
+        }
+
+        let threshold_session_policy_json =
+            trim_nonempty(self.threshold_session_policy_json.as_deref());
+        // Shape check only: the worker has no JSON parser outside JS, and the relayer parses
+        // (and digests) the policy itself.
+        if let Some(json) = threshold_session_policy_json.as_deref() {
+            if !(json.starts_with('{') && json.ends_with('}')) {
+                return Err(ThresholdConfigError::InvalidSessionPolicyJson {
+                    reason: "expected an object literal".to_string(),
+                });
+            }
+        }
+
+        if self.max_concurrent_relayer_requests == Some(0) {
+            return Err(ThresholdConfigError::InvalidConcurrencyLimit { value: 0 });
+        }
+        let max_concurrent_relayer_requests = self.max_concurrent_relayer_requests;
+
+        Ok(ThresholdSignerConfig {
+            relayer_url,
+            relayer_key_id,
+            client_participant_id: Some(client_id),
+            relayer_participant_id: Some(relayer_id),
+            participant_ids: Some(participant_ids),
+            mpc_session_id: trim_nonempty(self.mpc_session_id.as_deref()),
+            threshold_session_policy_json,
+            threshold_session_kind,
+            threshold_session_jwt,
+            threshold_session_refresh_margin_ms: non_negative_ms(
+                "thresholdSessionRefreshMarginMs",
+                self.threshold_session_refresh_margin_ms,
+            )?,
+            threshold_session_max_clock_skew_ms: non_negative_ms(
+                "thresholdSessionMaxClockSkewMs",
+                self.threshold_session_max_clock_skew_ms,
+            )?,
+            force_session_refresh: self.force_session_refresh,
+            request_auth: self
+                .request_auth
+                .normalized()
+                .map_err(ThresholdConfigError::InvalidRequestAuth)?,
+            nonce_mode: self.nonce_mode,
+            relayer_nonce_reservation: self.relayer_nonce_reservation,
+            max_concurrent_relayer_requests,
+        })
+    }
+}
+
+fn trim_nonempty(input: Option<&str>) -> Option<String> {
+    input
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Trimmed, without trailing slashes (request paths are appended with a leading `/`).
+fn normalize_relayer_url(input: Option<&str>) -> Result<String, ThresholdConfigError> {
+    let url = trim_nonempty(input).ok_or(ThresholdConfigError::MissingRelayerUrl)?;
+    let host = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"));
+    match host {
+        Some(rest) if !rest.is_empty() && !rest.starts_with('/') && !rest.contains(' ') => {
+            Ok(url.trim_end_matches('/').to_string())
+        }
+        _ => Err(ThresholdConfigError::InvalidRelayerUrl { url }),
+    }
+}
+
+fn non_negative_ms(
+    field: &'static str,
+    value: Option<f64>,
+) -> Result<Option<f64>, ThresholdConfigError> {
+    match value {
+        Some(ms) if !ms.is_finite() || ms < 0.0 => {
+            Err(ThresholdConfigError::InvalidDuration { field, value: ms })
+        }
+        _ => Ok(value),
+    }
+}
+
+/// Validate and normalize a threshold signer config assembled in JS. Rejects with the first
+/// problem found; resolves to the config to pass as `threshold` on signing requests.
+#[wasm_bindgen]
+pub fn build_threshold_signer_config(args: JsValue) -> Result<JsValue, JsValue> {
+    let builder: ThresholdSignerConfigBuilder = serde_wasm_bindgen::from_value(args)
+        .map_err(|e| JsValue::from_str(&format!("Invalid threshold signer config: {e}")))?;
+    let config = builder.build()?;
+    serde_wasm_bindgen::to_value(&config).map_err(|e| {
+        JsValue::from_str(&format!("Failed to serialize threshold signer config: {e}"))
+    })
+}
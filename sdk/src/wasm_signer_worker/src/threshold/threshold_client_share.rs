@@ -47,6 +47,12 @@ pub(crate) fn derive_threshold_client_verifying_share_bytes_v1(
     Ok((ED25519_BASEPOINT_POINT * scalar).compress().to_bytes())
 }
 
+/// Verifying share (compressed Edwards point) for an already-derived signing share.
+pub(crate) fn verifying_share_bytes_from_signing_share(signing_share: &[u8; 32]) -> [u8; 32] {
+    let scalar = CurveScalar::from_bytes_mod_order(*signing_share);
+    (ED25519_BASEPOINT_POINT * scalar).compress().to_bytes()
+}
+
 pub(crate) fn derive_threshold_client_verifying_share_b64u_v1(
     wrap_key: &WrapKey,
     near_account_id: &str,
@@ -1,6 +1,7 @@
 use crate::actions::ActionParams;
-use crate::encoders::{base64_standard_decode, hash_delegate_action};
-use crate::transaction::{build_actions_from_params, build_transaction_with_actions};
+use crate::encoders::{hash_delegate_action, B64StdBytes};
+use crate::signable_message::{compute_signable_digest, SignableMessageKind};
+use crate::transaction::{build_actions_from_params, build_transaction_with_actions, parse_nonce};
 use crate::types::{AccountId, DelegateAction, PublicKey};
 use serde::Deserialize;
 use wasm_bindgen::prelude::*;
@@ -43,6 +44,7 @@ struct NearTxSigningPayload {
 struct NearTxRequest {
     near_account_id: String,
     receiver_id: String,
+    #[serde(deserialize_with = "crate::actions::deserialize_actions_flexible")]
     actions: Vec<ActionParams>,
 }
 
@@ -73,12 +75,8 @@ pub fn threshold_ed25519_compute_near_tx_signing_digests(
     let block_hash_bytes =
         parse_near_block_hash_to_bytes(&payload.transaction_context.tx_block_hash)?;
 
-    let base_nonce: u64 = payload
-        .transaction_context
-        .next_nonce
-        .trim()
-        .parse()
-        .map_err(|e| JsValue::from_str(&format!("Invalid transactionContext.nextNonce: {e}")))?;
+    let base_nonce =
+        parse_nonce(&payload.transaction_context.next_nonce).map_err(|e| JsValue::from_str(&e))?;
 
     // Ensure all txs share the same signer account id (mirrors worker behavior).
     let signer_account_id = payload.tx_signing_requests[0]
@@ -130,6 +128,7 @@ struct DelegateSigningPayload {
 struct DelegatePayload {
     sender_id: String,
     receiver_id: String,
+    #[serde(deserialize_with = "crate::actions::deserialize_actions_flexible")]
     actions: Vec<ActionParams>,
     nonce: String,
     max_block_height: String,
@@ -196,7 +195,8 @@ pub fn threshold_ed25519_compute_delegate_signing_digest(
 struct Nep413SigningPayload {
     message: String,
     recipient: String,
-    nonce: String,
+    /// 32-byte nonce, standard base64 per NEP-413
+    nonce: B64StdBytes,
     #[serde(default)]
     state: Option<String>,
 }
@@ -210,18 +210,14 @@ pub fn threshold_ed25519_compute_nep413_signing_digest(
     let payload: Nep413SigningPayload = serde_wasm_bindgen::from_value(payload)
         .map_err(|e| JsValue::from_str(&format!("Invalid nep413 signingPayload: {e}")))?;
 
-    let nonce_bytes = base64_standard_decode(payload.nonce.trim())
-        .map_err(|e| JsValue::from_str(&format!("Invalid nonce (base64): {e}")))?;
+    let nonce_bytes = payload.nonce.as_bytes();
     if nonce_bytes.len() != 32 {
         return Err(JsValue::from_str(&format!(
             "Invalid nonce length: expected 32 bytes, got {}",
             nonce_bytes.len()
         )));
     }
-    let nonce_array: [u8; 32] = nonce_bytes
-        .as_slice()
-        .try_into()
-        .expect("checked length above");
+    let nonce_array: [u8; 32] = nonce_bytes.try_into().expect("checked length above");
 
     #[derive(borsh::BorshSerialize)]
     struct Nep413PayloadBorsh {
@@ -240,13 +236,6 @@ pub fn threshold_ed25519_compute_nep413_signing_digest(
 
     let serialized = borsh::to_vec(&payload_borsh)
         .map_err(|e| JsValue::from_str(&format!("Borsh serialization failed: {e}")))?;
-    let prefix: u32 = 2147484061;
-    let mut prefixed = prefix.to_le_bytes().to_vec();
-    prefixed.extend_from_slice(&serialized);
-
-    use sha2::{Digest, Sha256};
-    let mut hasher = Sha256::new();
-    hasher.update(&prefixed);
-    let digest = hasher.finalize();
+    let digest = compute_signable_digest(SignableMessageKind::Nep413, &serialized);
     Ok(digest.to_vec())
 }
@@ -1,4 +1,6 @@
 use crate::encoders::{base64_url_decode, base64_url_encode};
+use crate::threshold::rp_id_normalization::RpIdNormalization;
+use crate::threshold::threshold_proof::signing_package_commitments_and_challenge;
 use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
 use curve25519_dalek::edwards::CompressedEdwardsY;
 use curve25519_dalek::edwards::EdwardsPoint;
@@ -8,7 +10,8 @@ use frost_ed25519::Group;
 use hkdf::Hkdf;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::collections::BTreeMap;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
 use wasm_bindgen::prelude::*;
 
 fn parse_near_public_key_to_bytes(public_key: &str) -> Result<[u8; 32], JsValue> {
@@ -60,6 +63,66 @@ pub(crate) fn compute_threshold_ed25519_group_public_key_2p_from_verifying_share
     Ok(group_point.compress().to_bytes())
 }
 
+/// Default number of memoized `(client_id, relayer_id)` Lagrange coefficients.
+const LAGRANGE_CACHE_DEFAULT_MAX_ENTRIES: usize = 64;
+
+/// Memoized 2-party relayer Lagrange coefficients. A relayer typically signs for the
+/// same `{client, relayer}` id pair thousands of times, so the field inversion is cached.
+struct LagrangeCache {
+    max_entries: usize,
+    entries: HashMap<(u16, u16), CurveScalar>,
+}
+
+thread_local! {
+    static RELAYER_LAGRANGE_CACHE: RefCell<LagrangeCache> = RefCell::new(LagrangeCache {
+        max_entries: LAGRANGE_CACHE_DEFAULT_MAX_ENTRIES,
+        entries: HashMap::new(),
+    });
+}
+
+/// Lagrange coefficient for the relayer at x=0 in a 2-party signer set:
+///   lambda_relayer = x_client / (x_client - x_relayer)
+fn compute_lambda_relayer_2p(client_id: u16, relayer_id: u16) -> Result<CurveScalar, String> {
+    let xc = CurveScalar::from(client_id as u64);
+    let xr = CurveScalar::from(relayer_id as u64);
+    let denom = xc - xr;
+    if denom == CurveScalar::ZERO {
+        return Err("Invalid participant identifiers".to_string());
+    }
+    Ok(xc * denom.invert())
+}
+
+/// Cached `compute_lambda_relayer_2p`; identical inputs always yield the identical scalar.
+pub(crate) fn lambda_relayer_2p(client_id: u16, relayer_id: u16) -> Result<CurveScalar, String> {
+    let key = (client_id, relayer_id);
+    if let Some(lambda) = RELAYER_LAGRANGE_CACHE.with(|c| c.borrow().entries.get(&key).copied()) {
+        return Ok(lambda);
+    }
+    let lambda = compute_lambda_relayer_2p(client_id, relayer_id)?;
+    RELAYER_LAGRANGE_CACHE.with(|c| {
+        let mut cache = c.borrow_mut();
+        if cache.max_entries == 0 {
+            return;
+        }
+        if cache.entries.len() >= cache.max_entries {
+            cache.entries.clear();
+        }
+        cache.entries.insert(key, lambda);
+    });
+    Ok(lambda)
+}
+
+/// Set how many `(client_id, relayer_id)` Lagrange coefficients the worker memoizes.
+/// `0` disables the cache. Existing entries are dropped.
+#[wasm_bindgen]
+pub fn threshold_ed25519_configure_lagrange_cache(max_entries: u32) {
+    RELAYER_LAGRANGE_CACHE.with(|c| {
+        let mut cache = c.borrow_mut();
+        cache.max_entries = max_entries as usize;
+        cache.entries.clear();
+    });
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct CommitmentsWire {
@@ -69,9 +132,7 @@ struct CommitmentsWire {
 
 const THRESHOLD_RELAYER_SHARE_INFO_PREFIX_V1: &[u8] = b"w3a/threshold/relayer_share_v1";
 
-fn normalize_rp_id(rp_id: &str) -> String {
-    rp_id.trim().to_ascii_lowercase()
-}
+const THRESHOLD_RELAYER_SHARE_SERVICE_ID_TAG_V1: &[u8] = b"w3a/threshold/relayer_service_id_v1";
 
 const THRESHOLD_DERIVE_NONZERO_SCALAR_MAX_TRIES_V1: u32 = 1024;
 
@@ -97,11 +158,32 @@ where
     Err(exhausted_error.to_string())
 }
 
+// salt := SHA256(client_verifying_share) when no service id is set (the original v1 salt),
+// otherwise SHA256(tag || 0 || service_id || client_verifying_share).
+fn relayer_share_hkdf_salt_v1(
+    client_verifying_share_bytes: &[u8; 32],
+    service_id: Option<&str>,
+) -> [u8; 32] {
+    match service_id.map(str::trim).filter(|id| !id.is_empty()) {
+        None => Sha256::digest(client_verifying_share_bytes).into(),
+        Some(id) => {
+            let mut hasher = Sha256::new();
+            hasher.update(THRESHOLD_RELAYER_SHARE_SERVICE_ID_TAG_V1);
+            hasher.update([0u8]);
+            hasher.update(id.as_bytes());
+            hasher.update(client_verifying_share_bytes);
+            hasher.finalize().into()
+        }
+    }
+}
+
 fn derive_threshold_relayer_share_scalar_v1(
     master_secret_bytes: &[u8],
     near_account_id: &str,
     rp_id: &str,
+    rp_id_normalization: RpIdNormalization,
     client_verifying_share_bytes: &[u8; 32],
+    service_id: Option<&str>,
 ) -> Result<CurveScalar, String> {
     if master_secret_bytes.len() != 32 {
         return Err(format!(
@@ -110,15 +192,18 @@ fn derive_threshold_relayer_share_scalar_v1(
         ));
     }
 
-    let rp_id = normalize_rp_id(rp_id);
+    // The normalized rpId feeds the HKDF info below: changing the policy changes the share.
+    let rp_id = rp_id_normalization.normalize(rp_id.trim())?;
 
     // Deterministically derive the relayer signing share from the relayer master secret + public inputs.
     //
-    // - HKDF salt binds the derivation to the client verifying share (public key-share).
+    // - HKDF salt binds the derivation to the client verifying share (public key-share) and,
+    //   when set, the relayer `service_id` (domain separation between relayer services that
+    //   share one master secret; unset keeps the original derivation).
     // - HKDF `info` binds it to the NEAR account + rpId.
     // - `ctr` enables deterministic "rejection sampling": if the reduced scalar is 0 mod ℓ,
     //   increment ctr and retry.
-    let salt = Sha256::digest(client_verifying_share_bytes);
+    let salt = relayer_share_hkdf_salt_v1(client_verifying_share_bytes, service_id);
     let hk = Hkdf::<Sha256>::new(Some(salt.as_slice()), master_secret_bytes);
 
     // info := prefix || 0 || near_account_id || 0 || rp_id || 0 || epoch || ctr
@@ -202,6 +287,7 @@ pub fn threshold_ed25519_keygen_from_client_verifying_share(
         .ok_or_else(|| JsValue::from_str("Invalid client verifying share point"))?;
 
     // Generate a random non-zero signing share for relayer id=2.
+    crate::randomness::ensure_randomness_ok().map_err(|e| JsValue::from_str(&e))?;
     let mut rng = frost_ed25519::rand_core::OsRng;
     let relayer_scalar: CurveScalar = loop {
         let mut wide = [0u8; 64];
@@ -270,7 +356,12 @@ pub fn threshold_ed25519_keygen_from_master_secret_and_client_verifying_share(
         master_secret_b64u: String,
         near_account_id: String,
         rp_id: String,
+        #[serde(default)]
+        rp_id_normalization: RpIdNormalization,
         client_verifying_share_b64u: String,
+        /// Relayer service id folded into the HKDF salt; unset keeps existing derivations.
+        #[serde(default)]
+        service_id: Option<String>,
     }
 
     let args: ThresholdEd25519KeygenFromMasterSecretArgs = serde_wasm_bindgen::from_value(args)
@@ -308,7 +399,9 @@ pub fn threshold_ed25519_keygen_from_master_secret_and_client_verifying_share(
         master_secret_bytes.as_slice(),
         &args.near_account_id,
         &args.rp_id,
+        args.rp_id_normalization,
         &client_bytes,
+        args.service_id.as_deref(),
     )
     .map_err(|e| JsValue::from_str(&e))?;
 
@@ -348,6 +441,239 @@ pub fn threshold_ed25519_keygen_from_master_secret_and_client_verifying_share(
     .map_err(|e| JsValue::from_str(&format!("Failed to serialize keygen output: {e}")))
 }
 
+/// Shamir-split `secret` over the Ed25519 scalar field for a relayer cosigner fleet:
+/// `f(x) = secret + a_1 x + ... + a_{t-1} x^{t-1}` and cosigner `id` receives `f(id)`.
+///
+/// `random_scalar` draws the coefficients `a_1..a_{t-1}`. A zero leading coefficient or a zero
+/// share is redrawn, so every `t` (and no fewer) of the shares reconstruct `secret`.
+pub(crate) fn split_relayer_signing_share_scalar(
+    secret: CurveScalar,
+    n: u16,
+    t: u16,
+    cosigner_ids: &[u16],
+    mut random_scalar: impl FnMut() -> CurveScalar,
+) -> Result<Vec<(u16, CurveScalar)>, String> {
+    if t < 2 || t > n {
+        return Err(format!("t must satisfy 1 < t <= n (got t={t}, n={n})"));
+    }
+    if cosigner_ids.len() != n as usize {
+        return Err(format!(
+            "cosignerIds must list exactly n={n} ids, got {}",
+            cosigner_ids.len()
+        ));
+    }
+    let mut seen = std::collections::BTreeSet::new();
+    for &id in cosigner_ids {
+        if id == 0 {
+            return Err("cosignerIds must be integers in [1,65535]".to_string());
+        }
+        if !seen.insert(id) {
+            return Err(format!("cosignerIds must be distinct (duplicate id {id})"));
+        }
+    }
+    if secret == CurveScalar::ZERO {
+        return Err("Relayer signing share must be non-zero".to_string());
+    }
+
+    loop {
+        let coefficients: Vec<CurveScalar> = (1..t).map(|_| random_scalar()).collect();
+        if coefficients.last() == Some(&CurveScalar::ZERO) {
+            continue;
+        }
+        // Horner: f(x) = secret + x * (a_1 + x * (a_2 + ...))
+        let shares: Vec<(u16, CurveScalar)> = cosigner_ids
+            .iter()
+            .map(|&id| {
+                let x = CurveScalar::from(id as u64);
+                let tail = coefficients
+                    .iter()
+                    .rev()
+                    .fold(CurveScalar::ZERO, |acc, a| acc * x + a);
+                (id, secret + tail * x)
+            })
+            .collect();
+        if shares.iter().all(|(_, y)| *y != CurveScalar::ZERO) {
+            return Ok(shares);
+        }
+    }
+}
+
+/// Server-side helper: split a relayer signing share into `n` Shamir shares with threshold `t`
+/// for a relayer cosigner fleet. Any `t` shares, weighted by their Lagrange coefficients at
+/// x=0, sum to the relayer signing share.
+///
+/// Returns `{ shares: [{ id, shareB64u }] }` in `cosignerIds` order. Share values are secret;
+/// deliver each one only to its cosigner.
+#[wasm_bindgen]
+pub fn threshold_ed25519_split_relayer_share(
+    relayer_signing_share_b64u: String,
+    n: u16,
+    t: u16,
+    cosigner_ids: Vec<u16>,
+) -> Result<JsValue, JsValue> {
+    let share_bytes = base64_url_decode(relayer_signing_share_b64u.trim())
+        .map_err(|e| JsValue::from_str(&format!("Invalid relayerSigningShareB64u: {e}")))?;
+    let share_bytes: [u8; 32] = share_bytes.as_slice().try_into().map_err(|_| {
+        JsValue::from_str(&format!(
+            "relayerSigningShareB64u must be 32 bytes, got {}",
+            share_bytes.len()
+        ))
+    })?;
+    let secret = Option::<CurveScalar>::from(CurveScalar::from_canonical_bytes(share_bytes))
+        .ok_or_else(|| JsValue::from_str("relayerSigningShareB64u is not a canonical scalar"))?;
+
+    crate::randomness::ensure_randomness_ok().map_err(|e| JsValue::from_str(&e))?;
+    let mut rng = frost_ed25519::rand_core::OsRng;
+    let shares = split_relayer_signing_share_scalar(secret, n, t, &cosigner_ids, || {
+        use frost_ed25519::rand_core::RngCore;
+        let mut wide = [0u8; 64];
+        rng.fill_bytes(&mut wide);
+        CurveScalar::from_bytes_mod_order_wide(&wide)
+    })
+    .map_err(|e| JsValue::from_str(&e))?;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ShareOut {
+        id: u16,
+        share_b64u: String,
+    }
+
+    #[derive(Serialize)]
+    struct Out {
+        shares: Vec<ShareOut>,
+    }
+
+    serde_wasm_bindgen::to_value(&Out {
+        shares: shares
+            .into_iter()
+            .map(|(id, y)| ShareOut {
+                id,
+                share_b64u: base64_url_encode(&y.to_bytes()),
+            })
+            .collect(),
+    })
+    .map_err(|e| JsValue::from_str(&format!("Failed to serialize split output: {e}")))
+}
+
+/// Lagrange-interpolate the polynomial through `shares` (distinct, non-zero ids) at `x`:
+///   f(x) = sum_i y_i * prod_{j != i} (x - x_j) / (x_i - x_j)
+fn interpolate_relayer_shares_at(
+    shares: &[(u16, CurveScalar)],
+    x: CurveScalar,
+) -> Result<CurveScalar, String> {
+    let mut acc = CurveScalar::ZERO;
+    for &(id, y) in shares {
+        let x_i = CurveScalar::from(id as u64);
+        let mut num = CurveScalar::ONE;
+        let mut den = CurveScalar::ONE;
+        for &(j, _) in shares.iter().filter(|(j, _)| *j != id) {
+            let x_j = CurveScalar::from(j as u64);
+            num *= x - x_j;
+            den *= x_i - x_j;
+        }
+        if den == CurveScalar::ZERO {
+            return Err("Invalid participant identifiers".to_string());
+        }
+        acc += y * num * den.invert();
+    }
+    Ok(acc)
+}
+
+/// Check that `shares` are a consistent degree-`t-1` Shamir sharing of the relayer signing
+/// share behind `relayer_verifying_share`: the first `t` shares Lagrange-combine to a scalar
+/// `s` with `s*B == P`, and every further share lies on the polynomial they define, so any
+/// `t` of the shares reconstruct the same scalar.
+///
+/// Malformed input (bad `t`, zero or duplicate ids) is an error; shares that merely fail to
+/// reconstruct `P` yield `Ok(false)`.
+pub(crate) fn verify_relayer_shares_reconstruct(
+    shares: &[(u16, CurveScalar)],
+    relayer_verifying_share: &EdwardsPoint,
+    t: u16,
+) -> Result<bool, String> {
+    if t < 2 || t as usize > shares.len() {
+        return Err(format!(
+            "t must satisfy 1 < t <= shares.len() (got t={t}, {} shares)",
+            shares.len()
+        ));
+    }
+    let mut seen = std::collections::BTreeSet::new();
+    for &(id, _) in shares {
+        if id == 0 {
+            return Err("share ids must be integers in [1,65535]".to_string());
+        }
+        if !seen.insert(id) {
+            return Err(format!("share ids must be distinct (duplicate id {id})"));
+        }
+    }
+
+    let (basis, rest) = shares.split_at(t as usize);
+    let secret = interpolate_relayer_shares_at(basis, CurveScalar::ZERO)?;
+    if ED25519_BASEPOINT_POINT * secret != *relayer_verifying_share {
+        return Ok(false);
+    }
+    for &(id, y) in rest {
+        if interpolate_relayer_shares_at(basis, CurveScalar::from(id as u64))? != y {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Server-side helper: confirm a cosigner share set (as produced by
+/// `threshold_ed25519_split_relayer_share`) reconstructs the relayer verifying share before
+/// the fleet is put into service.
+///
+/// `shares` is `[{ id, shareB64u }]`. Resolves to `true` only if every `t` of the shares
+/// reconstruct the relayer signing share; rejects on malformed input.
+#[wasm_bindgen]
+pub fn threshold_ed25519_verify_relayer_shares(
+    shares: JsValue,
+    relayer_verifying_share_b64u: String,
+    t: u16,
+) -> Result<bool, JsValue> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ShareIn {
+        id: u16,
+        #[serde(alias = "share_b64u")]
+        share_b64u: String,
+    }
+
+    let shares_in: Vec<ShareIn> = serde_wasm_bindgen::from_value(shares)
+        .map_err(|e| JsValue::from_str(&format!("Invalid shares: {e}")))?;
+    let mut shares = Vec::with_capacity(shares_in.len());
+    for ShareIn { id, share_b64u } in shares_in {
+        let bytes = base64_url_decode(share_b64u.trim())
+            .map_err(|e| JsValue::from_str(&format!("Invalid shareB64u for id {id}: {e}")))?;
+        let bytes: [u8; 32] = bytes.as_slice().try_into().map_err(|_| {
+            JsValue::from_str(&format!(
+                "shareB64u for id {id} must be 32 bytes, got {}",
+                bytes.len()
+            ))
+        })?;
+        let y = Option::<CurveScalar>::from(CurveScalar::from_canonical_bytes(bytes)).ok_or_else(
+            || JsValue::from_str(&format!("shareB64u for id {id} is not a canonical scalar")),
+        )?;
+        shares.push((id, y));
+    }
+
+    let point_bytes = base64_url_decode(relayer_verifying_share_b64u.trim())
+        .map_err(|e| JsValue::from_str(&format!("Invalid relayerVerifyingShareB64u: {e}")))?;
+    let point_bytes: [u8; 32] = point_bytes.as_slice().try_into().map_err(|_| {
+        JsValue::from_str(&format!(
+            "relayerVerifyingShareB64u must be 32 bytes, got {}",
+            point_bytes.len()
+        ))
+    })?;
+    let relayer_point = CompressedEdwardsY(point_bytes)
+        .decompress()
+        .ok_or_else(|| JsValue::from_str("Invalid relayer verifying share point"))?;
+
+    verify_relayer_shares_reconstruct(&shares, &relayer_point, t).map_err(|e| JsValue::from_str(&e))
+}
+
 /// Server-side helper: Round 1 FROST commit for the relayer share.
 /// Returns relayer nonces (opaque, serialized) and relayer commitments (public).
 #[wasm_bindgen]
@@ -365,6 +691,7 @@ pub fn threshold_ed25519_round1_commit(
     let signing_share = frost_ed25519::keys::SigningShare::deserialize(&share_bytes)
         .map_err(|e| JsValue::from_str(&format!("Invalid relayer signing share: {e}")))?;
 
+    crate::randomness::ensure_randomness_ok().map_err(|e| JsValue::from_str(&e))?;
     let mut rng = frost_ed25519::rand_core::OsRng;
     let (nonces, commitments) = frost_ed25519::round1::commit(&signing_share, &mut rng);
 
@@ -411,6 +738,66 @@ struct Round2SignArgs {
     signing_digest_b64u: String,
     client_commitments: CommitmentsWire,
     relayer_commitments: CommitmentsWire,
+    /// Client verifying share; when present the group public key is re-derived and checked.
+    #[serde(default)]
+    client_verifying_share_b64u: Option<String>,
+    /// Relayer verifying share the caller expects this signing share to produce.
+    #[serde(default)]
+    expected_relayer_verifying_share_b64u: Option<String>,
+}
+
+/// Reject round2 inputs whose `groupPublicKey` isn't the interpolation of the client and
+/// relayer verifying shares, so a client can't steer the relayer into signing under a
+/// different key. Checks are skipped when the corresponding optional arg is absent.
+fn ensure_group_public_key_matches_verifying_shares(
+    client_verifying_share_b64u: Option<&str>,
+    expected_relayer_verifying_share_b64u: Option<&str>,
+    relayer_point: EdwardsPoint,
+    group_public_key_bytes: &[u8; 32],
+    client_id: u16,
+    relayer_id: u16,
+) -> Result<(), String> {
+    if let Some(expected) = expected_relayer_verifying_share_b64u.map(str::trim) {
+        let expected_bytes = base64_url_decode(expected)
+            .map_err(|e| format!("Invalid expectedRelayerVerifyingShareB64u: {e}"))?;
+        if expected_bytes.as_slice() != relayer_point.compress().as_bytes() {
+            return Err(
+                "relayer signing share does not match expectedRelayerVerifyingShareB64u"
+                    .to_string(),
+            );
+        }
+    }
+
+    let Some(client_verifying_share_b64u) = client_verifying_share_b64u.map(str::trim) else {
+        if expected_relayer_verifying_share_b64u.is_some() {
+            return Err(
+                "clientVerifyingShareB64u is required to re-derive the group public key"
+                    .to_string(),
+            );
+        }
+        return Ok(());
+    };
+    let client_bytes: [u8; 32] = base64_url_decode(client_verifying_share_b64u)
+        .map_err(|e| format!("Invalid clientVerifyingShareB64u: {e}"))?
+        .as_slice()
+        .try_into()
+        .map_err(|_| "clientVerifyingShareB64u must be 32 bytes".to_string())?;
+    let client_point = CompressedEdwardsY(client_bytes)
+        .decompress()
+        .ok_or_else(|| "Invalid clientVerifyingShareB64u: not a curve point".to_string())?;
+
+    let derived = compute_threshold_ed25519_group_public_key_2p_from_verifying_shares(
+        client_point,
+        relayer_point,
+        client_id,
+        relayer_id,
+    )?;
+    if &derived != group_public_key_bytes {
+        return Err(
+            "groupPublicKey does not match the client and relayer verifying shares".to_string(),
+        );
+    }
+    Ok(())
 }
 
 /// Server-side helper: Round 2 FROST sign for the relayer share.
@@ -463,6 +850,16 @@ pub fn threshold_ed25519_round2_sign(args: JsValue) -> Result<JsValue, JsValue>
         ));
     }
 
+    ensure_group_public_key_matches_verifying_shares(
+        args.client_verifying_share_b64u.as_deref(),
+        args.expected_relayer_verifying_share_b64u.as_deref(),
+        relayer_point,
+        &group_pk_bytes,
+        client_id,
+        relayer_id,
+    )
+    .map_err(|e| JsValue::from_str(&e))?;
+
     let relayer_identifier: frost_ed25519::Identifier = relayer_id
         .try_into()
         .map_err(|_| JsValue::from_str("Invalid relayer identifier"))?;
@@ -520,58 +917,34 @@ pub fn threshold_ed25519_round2_sign(args: JsValue) -> Result<JsValue, JsValue>
     .map_err(|e| JsValue::from_str(&format!("Failed to serialize round2 output: {e}")))
 }
 
-fn threshold_ed25519_round2_sign_cosigner_bytes(args: Round2SignArgs) -> Result<[u8; 32], String> {
-    let share_bytes = base64_url_decode(args.relayer_signing_share_b64u.trim())
-        .map_err(|e| format!("Invalid relayerSigningShareB64u: {e}"))?;
-    if share_bytes.len() != 32 {
-        return Err(format!(
-            "relayerSigningShareB64u must be 32 bytes, got {}",
-            share_bytes.len()
-        ));
-    }
-    let share_scalar = Option::<CurveScalar>::from(CurveScalar::from_canonical_bytes(
-        share_bytes
-            .as_slice()
-            .try_into()
-            .expect("checked length above"),
-    ))
-    .ok_or_else(|| "Invalid relayer signing share scalar".to_string())?;
-
-    let nonces_bytes = base64_url_decode(args.relayer_nonces_b64u.trim())
-        .map_err(|e| format!("Invalid relayerNoncesB64u: {e}"))?;
-    let nonces = frost_ed25519::round1::SigningNonces::deserialize(&nonces_bytes)
-        .map_err(|e| format!("Invalid relayer signing nonces: {e}"))?;
-
-    // Extract nonce scalars via serialization (internals feature not required).
-    let hiding_bytes = nonces.hiding().serialize();
-    if hiding_bytes.len() != 32 {
-        return Err("Invalid hiding nonce encoding".to_string());
-    }
-    let binding_bytes = nonces.binding().serialize();
-    if binding_bytes.len() != 32 {
-        return Err("Invalid binding nonce encoding".to_string());
-    }
-    let hiding_scalar = Option::<CurveScalar>::from(CurveScalar::from_canonical_bytes(
-        hiding_bytes
-            .as_slice()
-            .try_into()
-            .expect("checked length above"),
-    ))
-    .ok_or_else(|| "Invalid hiding nonce scalar".to_string())?;
-    let binding_scalar = Option::<CurveScalar>::from(CurveScalar::from_canonical_bytes(
-        binding_bytes
-            .as_slice()
-            .try_into()
-            .expect("checked length above"),
-    ))
-    .ok_or_else(|| "Invalid binding nonce scalar".to_string())?;
+/// Public values of a 2-party signing package: the group commitment `R`, the challenge
+/// `c = H2(R || Y || msg)` and the relayer's binding factor `rho`. Derived from commitments,
+/// the group public key and the message only; no shares or nonces are involved.
+struct SigningPackageValues {
+    group_commitment: [u8; 32],
+    challenge: CurveScalar,
+    rho_relayer: CurveScalar,
+}
 
-    let message = base64_url_decode(args.signing_digest_b64u.trim())
+fn compute_signing_package_values(
+    client_id: u16,
+    relayer_id: u16,
+    group_public_key: &str,
+    signing_digest_b64u: &str,
+    client_commitments: &CommitmentsWire,
+    relayer_commitments: &CommitmentsWire,
+) -> Result<SigningPackageValues, String> {
+    let message = base64_url_decode(signing_digest_b64u.trim())
         .map_err(|e| format!("Invalid signingDigestB64u: {e}"))?;
 
-    let group_pk_bytes = bs58::decode(args.group_public_key.trim().strip_prefix("ed25519:").unwrap_or(args.group_public_key.trim()))
-        .into_vec()
-        .map_err(|e| format!("Invalid group public key base58: {e}"))?;
+    let group_pk_bytes = bs58::decode(
+        group_public_key
+            .trim()
+            .strip_prefix("ed25519:")
+            .unwrap_or(group_public_key.trim()),
+    )
+    .into_vec()
+    .map_err(|e| format!("Invalid group public key base58: {e}"))?;
     if group_pk_bytes.len() != 32 {
         return Err(format!(
             "Invalid group public key length: expected 32 bytes, got {}",
@@ -586,8 +959,6 @@ fn threshold_ed25519_round2_sign_cosigner_bytes(args: Round2SignArgs) -> Result<
     let verifying_key = frost_ed25519::VerifyingKey::deserialize(&group_pk_bytes)
         .map_err(|e| format!("Invalid group public key: {e}"))?;
 
-    let client_id = args.client_participant_id.unwrap_or(1u16);
-    let relayer_id = args.relayer_participant_id.unwrap_or(2u16);
     if client_id == relayer_id {
         return Err(
             "Invalid participant identifiers: clientParticipantId must differ from relayerParticipantId"
@@ -603,31 +974,31 @@ fn threshold_ed25519_round2_sign_cosigner_bytes(args: Round2SignArgs) -> Result<
         .map_err(|_| "Invalid client identifier".to_string())?;
 
     // Parse commitments (client + *aggregated* relayer commitments).
-    let client_hiding = base64_url_decode(args.client_commitments.hiding.trim())
+    let client_hiding = base64_url_decode(client_commitments.hiding.trim())
         .map_err(|e| format!("Invalid client commitments.hiding: {e}"))?;
-    let client_binding = base64_url_decode(args.client_commitments.binding.trim())
+    let client_binding = base64_url_decode(client_commitments.binding.trim())
         .map_err(|e| format!("Invalid client commitments.binding: {e}"))?;
     let client_hiding = frost_ed25519::round1::NonceCommitment::deserialize(&client_hiding)
         .map_err(|e| format!("Invalid client hiding commitment: {e}"))?;
     let client_binding = frost_ed25519::round1::NonceCommitment::deserialize(&client_binding)
         .map_err(|e| format!("Invalid client binding commitment: {e}"))?;
-    let client_commitments =
+    let client_commitments_parsed =
         frost_ed25519::round1::SigningCommitments::new(client_hiding, client_binding);
 
-    let relayer_hiding = base64_url_decode(args.relayer_commitments.hiding.trim())
+    let relayer_hiding = base64_url_decode(relayer_commitments.hiding.trim())
         .map_err(|e| format!("Invalid relayer commitments.hiding: {e}"))?;
-    let relayer_binding = base64_url_decode(args.relayer_commitments.binding.trim())
+    let relayer_binding = base64_url_decode(relayer_commitments.binding.trim())
         .map_err(|e| format!("Invalid relayer commitments.binding: {e}"))?;
     let relayer_hiding = frost_ed25519::round1::NonceCommitment::deserialize(&relayer_hiding)
         .map_err(|e| format!("Invalid relayer hiding commitment: {e}"))?;
     let relayer_binding = frost_ed25519::round1::NonceCommitment::deserialize(&relayer_binding)
         .map_err(|e| format!("Invalid relayer binding commitment: {e}"))?;
-    let relayer_commitments =
+    let relayer_commitments_parsed =
         frost_ed25519::round1::SigningCommitments::new(relayer_hiding, relayer_binding);
 
     let mut commitments_map = BTreeMap::new();
-    commitments_map.insert(client_identifier, client_commitments);
-    commitments_map.insert(relayer_identifier, relayer_commitments);
+    commitments_map.insert(client_identifier, client_commitments_parsed);
+    commitments_map.insert(relayer_identifier, relayer_commitments_parsed);
     let signing_package = frost_ed25519::SigningPackage::new(commitments_map, &message);
 
     // Compute binding factors (rho) using the same preimages as frost-core.
@@ -641,7 +1012,7 @@ fn threshold_ed25519_round2_sign_cosigner_bytes(args: Round2SignArgs) -> Result<
         );
         rho_by_id.insert(id, rho);
     }
-    let rho_relayer = rho_by_id
+    let rho_relayer = *rho_by_id
         .get(&relayer_identifier)
         .ok_or_else(|| "Missing relayer binding factor".to_string())?;
 
@@ -694,61 +1065,368 @@ fn threshold_ed25519_round2_sign_cosigner_bytes(args: Round2SignArgs) -> Result<
     challenge_preimage.extend_from_slice(group_commitment_bytes.as_ref());
     challenge_preimage.extend_from_slice(vk_bytes.as_ref());
     challenge_preimage.extend_from_slice(message.as_slice());
-    let challenge =
-        <frost_ed25519::Ed25519Sha512 as frost_ed25519::Ciphersuite>::H2(
-            challenge_preimage.as_slice(),
+    let challenge = <frost_ed25519::Ed25519Sha512 as frost_ed25519::Ciphersuite>::H2(
+        challenge_preimage.as_slice(),
+    );
+
+    Ok(SigningPackageValues {
+        group_commitment: group_commitment_bytes,
+        challenge,
+        rho_relayer,
+    })
+}
+
+fn threshold_ed25519_round2_sign_cosigner_bytes(args: Round2SignArgs) -> Result<[u8; 32], String> {
+    let share_bytes = base64_url_decode(args.relayer_signing_share_b64u.trim())
+        .map_err(|e| format!("Invalid relayerSigningShareB64u: {e}"))?;
+    if share_bytes.len() != 32 {
+        return Err(format!(
+            "relayerSigningShareB64u must be 32 bytes, got {}",
+            share_bytes.len()
+        ));
+    }
+    let share_scalar = Option::<CurveScalar>::from(CurveScalar::from_canonical_bytes(
+        share_bytes
+            .as_slice()
+            .try_into()
+            .expect("checked length above"),
+    ))
+    .ok_or_else(|| "Invalid relayer signing share scalar".to_string())?;
+
+    let nonces_bytes = base64_url_decode(args.relayer_nonces_b64u.trim())
+        .map_err(|e| format!("Invalid relayerNoncesB64u: {e}"))?;
+    let nonces = frost_ed25519::round1::SigningNonces::deserialize(&nonces_bytes)
+        .map_err(|e| format!("Invalid relayer signing nonces: {e}"))?;
+
+    // Extract nonce scalars via serialization (internals feature not required).
+    let hiding_bytes = nonces.hiding().serialize();
+    if hiding_bytes.len() != 32 {
+        return Err("Invalid hiding nonce encoding".to_string());
+    }
+    let binding_bytes = nonces.binding().serialize();
+    if binding_bytes.len() != 32 {
+        return Err("Invalid binding nonce encoding".to_string());
+    }
+    let hiding_scalar = Option::<CurveScalar>::from(CurveScalar::from_canonical_bytes(
+        hiding_bytes
+            .as_slice()
+            .try_into()
+            .expect("checked length above"),
+    ))
+    .ok_or_else(|| "Invalid hiding nonce scalar".to_string())?;
+    let binding_scalar = Option::<CurveScalar>::from(CurveScalar::from_canonical_bytes(
+        binding_bytes
+            .as_slice()
+            .try_into()
+            .expect("checked length above"),
+    ))
+    .ok_or_else(|| "Invalid binding nonce scalar".to_string())?;
+
+    let client_id = args.client_participant_id.unwrap_or(1u16);
+    let relayer_id = args.relayer_participant_id.unwrap_or(2u16);
+    let SigningPackageValues {
+        challenge,
+        rho_relayer,
+        ..
+    } = compute_signing_package_values(
+        client_id,
+        relayer_id,
+        &args.group_public_key,
+        &args.signing_digest_b64u,
+        &args.client_commitments,
+        &args.relayer_commitments,
+    )?;
+
+    // For 2-of-2, the Lagrange coefficient for the relayer signer at x=0 (memoized per id pair).
+    let lambda_relayer = lambda_relayer_2p(client_id, relayer_id)?;
+
+    // Signature share scalar:
+    //   z_i = r_i^hiding + rho_relayer * r_i^binding + lambda_relayer * share_i * challenge
+    let z = hiding_scalar
+        + (binding_scalar * rho_relayer)
+        + (lambda_relayer * share_scalar * challenge);
+    Ok(z.to_bytes())
+}
+
+/// Internal helper for the relayer-fleet "cosigner" mode.
+///
+/// In this mode, the relayer participant's (id=2 in the 2-party scheme) secret share is split
+/// across multiple relayer servers (cosigners). Each cosigner holds:
+/// - a Shamir share of the *relayer signing share* (not the group secret), and
+/// - its own per-signature nonces.
+///
+/// The coordinator aggregates:
+/// - relayer commitments (hiding/binding) by point addition, and
+/// - relayer signature shares by scalar addition.
+///
+/// We cannot call `frost_ed25519::round2::sign` on each cosigner using the *aggregated* relayer
+/// commitments because `sign` checks that the commitments match the signer's own nonces.
+/// Instead, we compute the signature-share scalar directly using the same binding factors,
+/// group commitment, Lagrange coefficient (2-party signer set), and challenge as the normal flow.
+#[wasm_bindgen]
+pub fn threshold_ed25519_round2_sign_cosigner(args: JsValue) -> Result<JsValue, JsValue> {
+    let args: Round2SignArgs = serde_wasm_bindgen::from_value(args)
+        .map_err(|e| JsValue::from_str(&format!("Invalid round2 args: {e}")))?;
+    let z_bytes = threshold_ed25519_round2_sign_cosigner_bytes(args)
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Out {
+        relayer_signature_share_b64u: String,
+    }
+
+    serde_wasm_bindgen::to_value(&Out {
+        relayer_signature_share_b64u: base64_url_encode(z_bytes.as_ref()),
+    })
+    .map_err(|e| JsValue::from_str(&format!("Failed to serialize round2 output: {e}")))
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DebugSigningPackageArgs {
+    #[serde(default)]
+    client_participant_id: Option<u16>,
+    #[serde(default)]
+    relayer_participant_id: Option<u16>,
+    group_public_key: String,
+    signing_digest_b64u: String,
+    client_commitments: CommitmentsWire,
+    relayer_commitments: CommitmentsWire,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DebugSigningPackageOutput {
+    group_commitment_b64u: String,
+    challenge_b64u: String,
+    rho_relayer_b64u: String,
+}
+
+fn threshold_ed25519_debug_signing_package_values(
+    args: DebugSigningPackageArgs,
+) -> Result<DebugSigningPackageOutput, String> {
+    let values = compute_signing_package_values(
+        args.client_participant_id.unwrap_or(1u16),
+        args.relayer_participant_id.unwrap_or(2u16),
+        &args.group_public_key,
+        &args.signing_digest_b64u,
+        &args.client_commitments,
+        &args.relayer_commitments,
+    )?;
+    Ok(DebugSigningPackageOutput {
+        group_commitment_b64u: base64_url_encode(values.group_commitment.as_ref()),
+        challenge_b64u: base64_url_encode(values.challenge.as_bytes()),
+        rho_relayer_b64u: base64_url_encode(values.rho_relayer.as_bytes()),
+    })
+}
+
+/// Diagnostics for a 2-of-2 signature that failed verification: reports the group commitment,
+/// challenge and relayer binding factor the round2 signers derive for this signing package.
+/// Takes only public inputs (commitments, group public key, digest), so the output carries no
+/// secret material and can be logged alongside the failing signature.
+#[wasm_bindgen]
+pub fn threshold_ed25519_debug_signing_package(args: JsValue) -> Result<JsValue, JsValue> {
+    let args: DebugSigningPackageArgs = serde_wasm_bindgen::from_value(args)
+        .map_err(|e| JsValue::from_str(&format!("Invalid signing package args: {e}")))?;
+    let out =
+        threshold_ed25519_debug_signing_package_values(args).map_err(|e| JsValue::from_str(&e))?;
+    serde_wasm_bindgen::to_value(&out)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize signing package: {e}")))
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AggregateArgs {
+    #[serde(default)]
+    client_participant_id: Option<u16>,
+    #[serde(default)]
+    relayer_participant_id: Option<u16>,
+    client_verifying_share_b64u: String,
+    relayer_verifying_share_b64u: String,
+    group_public_key: String,
+    signing_digest_b64u: String,
+    client_commitments: CommitmentsWire,
+    relayer_commitments: CommitmentsWire,
+    client_signature_share_b64u: String,
+    relayer_signature_share_b64u: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AggregateOutput {
+    signature_b64u: String,
+}
+
+fn parse_commitments_wire(
+    party: &str,
+    wire: &CommitmentsWire,
+) -> Result<frost_ed25519::round1::SigningCommitments, String> {
+    let hiding = base64_url_decode(wire.hiding.trim())
+        .map_err(|e| format!("Invalid {party} commitments.hiding: {e}"))?;
+    let binding = base64_url_decode(wire.binding.trim())
+        .map_err(|e| format!("Invalid {party} commitments.binding: {e}"))?;
+    let hiding = frost_ed25519::round1::NonceCommitment::deserialize(&hiding)
+        .map_err(|e| format!("Invalid {party} hiding commitment: {e}"))?;
+    let binding = frost_ed25519::round1::NonceCommitment::deserialize(&binding)
+        .map_err(|e| format!("Invalid {party} binding commitment: {e}"))?;
+    Ok(frost_ed25519::round1::SigningCommitments::new(
+        hiding, binding,
+    ))
+}
+
+fn parse_verifying_share(
+    party: &str,
+    verifying_share_b64u: &str,
+) -> Result<(EdwardsPoint, frost_ed25519::keys::VerifyingShare), String> {
+    let bytes: [u8; 32] = base64_url_decode(verifying_share_b64u.trim())
+        .map_err(|e| format!("Invalid {party}VerifyingShareB64u: {e}"))?
+        .as_slice()
+        .try_into()
+        .map_err(|_| format!("{party}VerifyingShareB64u must be 32 bytes"))?;
+    let point = CompressedEdwardsY(bytes)
+        .decompress()
+        .ok_or_else(|| format!("Invalid {party}VerifyingShareB64u: not a curve point"))?;
+    let share = frost_ed25519::keys::VerifyingShare::deserialize(&bytes)
+        .map_err(|e| format!("Invalid {party} verifying share: {e}"))?;
+    Ok((point, share))
+}
+
+fn threshold_ed25519_aggregate_signature(args: AggregateArgs) -> Result<[u8; 64], String> {
+    let client_id = args.client_participant_id.unwrap_or(1u16);
+    let relayer_id = args.relayer_participant_id.unwrap_or(2u16);
+    if client_id == relayer_id {
+        return Err(
+            "Invalid participant identifiers: clientParticipantId must differ from relayerParticipantId"
+                .to_string(),
         );
+    }
+    let client_identifier: frost_ed25519::Identifier = client_id
+        .try_into()
+        .map_err(|_| "Invalid client identifier".to_string())?;
+    let relayer_identifier: frost_ed25519::Identifier = relayer_id
+        .try_into()
+        .map_err(|_| "Invalid relayer identifier".to_string())?;
+
+    let group_pk_bytes =
+        parse_near_public_key_to_bytes(args.group_public_key.trim()).map_err(|e| {
+            e.as_string()
+                .unwrap_or_else(|| "Invalid group public key".to_string())
+        })?;
+    let verifying_key = frost_ed25519::VerifyingKey::deserialize(&group_pk_bytes)
+        .map_err(|e| format!("Invalid group public key: {e}"))?;
+
+    // The verifying shares must interpolate to the group key the signature is checked against.
+    let (client_point, client_verifying_share) =
+        parse_verifying_share("client", &args.client_verifying_share_b64u)?;
+    let (relayer_point, relayer_verifying_share) =
+        parse_verifying_share("relayer", &args.relayer_verifying_share_b64u)?;
+    ensure_group_public_key_matches_verifying_shares(
+        Some(&args.client_verifying_share_b64u),
+        None,
+        relayer_point,
+        &group_pk_bytes,
+        client_id,
+        relayer_id,
+    )?;
+
+    let message = base64_url_decode(args.signing_digest_b64u.trim())
+        .map_err(|e| format!("Invalid signingDigestB64u: {e}"))?;
+    let mut commitments_map = BTreeMap::new();
+    commitments_map.insert(
+        client_identifier,
+        parse_commitments_wire("client", &args.client_commitments)?,
+    );
+    commitments_map.insert(
+        relayer_identifier,
+        parse_commitments_wire("relayer", &args.relayer_commitments)?,
+    );
+    let signing_package = frost_ed25519::SigningPackage::new(commitments_map, &message);
 
-    // For 2-of-2, compute Lagrange coefficient for the relayer signer at x=0:
-    //   lambda_relayer = x_client / (x_client - x_relayer)
+    // Check each share on its own first, so a bad share is attributed to its signer:
+    // z_i·B == R_i + c·λ_i·Y_i.
+    let (commitment_shares, _, challenge) = signing_package_commitments_and_challenge(
+        &signing_package,
+        &verifying_key,
+        &group_pk_bytes,
+    )?;
     let xc = CurveScalar::from(client_id as u64);
     let xr = CurveScalar::from(relayer_id as u64);
-    let denom = xc - xr;
-    if denom == CurveScalar::ZERO {
-        return Err("Invalid participant identifiers".to_string());
+    let lambda_client = xr * (xr - xc).invert();
+    let lambda_relayer = xc * (xc - xr).invert();
+
+    let mut signature_shares = BTreeMap::new();
+    for (party, identifier, lambda, share_point, share_b64u) in [
+        (
+            "client",
+            client_identifier,
+            lambda_client,
+            client_point,
+            &args.client_signature_share_b64u,
+        ),
+        (
+            "relayer",
+            relayer_identifier,
+            lambda_relayer,
+            relayer_point,
+            &args.relayer_signature_share_b64u,
+        ),
+    ] {
+        let share_bytes = base64_url_decode(share_b64u.trim())
+            .map_err(|e| format!("Invalid {party}SignatureShareB64u: {e}"))?;
+        let share = frost_ed25519::round2::SignatureShare::deserialize(&share_bytes)
+            .map_err(|e| format!("Invalid {party} signature share: {e}"))?;
+        let z_bytes: [u8; 32] = share_bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| format!("{party}SignatureShareB64u must be 32 bytes"))?;
+        let z = Option::<CurveScalar>::from(CurveScalar::from_canonical_bytes(z_bytes))
+            .ok_or_else(|| format!("Invalid {party} signature share: not a canonical scalar"))?;
+        let r_i = commitment_shares
+            .get(&identifier)
+            .ok_or_else(|| format!("Missing {party} commitment"))?;
+        if ED25519_BASEPOINT_POINT * z != r_i + share_point * (challenge * lambda) {
+            return Err(format!(
+                "Invalid {party} signature share: does not verify against its verifying share"
+            ));
+        }
+        signature_shares.insert(identifier, share);
     }
-    let lambda_relayer = xc * denom.invert();
 
-    // Signature share scalar:
-    //   z_i = r_i^hiding + rho_relayer * r_i^binding + lambda_relayer * share_i * challenge
-    let z = hiding_scalar
-        + (binding_scalar * (*rho_relayer))
-        + (lambda_relayer * share_scalar * challenge);
-    Ok(z.to_bytes())
+    let mut verifying_shares = BTreeMap::new();
+    verifying_shares.insert(client_identifier, client_verifying_share);
+    verifying_shares.insert(relayer_identifier, relayer_verifying_share);
+    let pubkey_package =
+        frost_ed25519::keys::PublicKeyPackage::new(verifying_shares, verifying_key);
+    let signature = frost_ed25519::aggregate(&signing_package, &signature_shares, &pubkey_package)
+        .map_err(|e| format!("Aggregate failed: {e}"))?;
+    verifying_key
+        .verify(&message, &signature)
+        .map_err(|e| format!("Aggregated signature does not verify under groupPublicKey: {e}"))?;
+
+    let signature_bytes = signature
+        .serialize()
+        .map_err(|e| format!("Failed to serialize signature: {e}"))?;
+    signature_bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| "Aggregated signature must be 64 bytes".to_string())
 }
 
-/// Internal helper for the relayer-fleet "cosigner" mode.
-///
-/// In this mode, the relayer participant's (id=2 in the 2-party scheme) secret share is split
-/// across multiple relayer servers (cosigners). Each cosigner holds:
-/// - a Shamir share of the *relayer signing share* (not the group secret), and
-/// - its own per-signature nonces.
-///
-/// The coordinator aggregates:
-/// - relayer commitments (hiding/binding) by point addition, and
-/// - relayer signature shares by scalar addition.
-///
-/// We cannot call `frost_ed25519::round2::sign` on each cosigner using the *aggregated* relayer
-/// commitments because `sign` checks that the commitments match the signer's own nonces.
-/// Instead, we compute the signature-share scalar directly using the same binding factors,
-/// group commitment, Lagrange coefficient (2-party signer set), and challenge as the normal flow.
+/// Coordinator helper: aggregate the client and relayer signature shares of a 2-of-2 signing
+/// round into the final Ed25519 signature. Each share is verified against its signer's
+/// verifying share, the verifying shares against `groupPublicKey`, and the result against
+/// `groupPublicKey` before `{ signatureB64u }` is returned.
 #[wasm_bindgen]
-pub fn threshold_ed25519_round2_sign_cosigner(args: JsValue) -> Result<JsValue, JsValue> {
-    let args: Round2SignArgs = serde_wasm_bindgen::from_value(args)
-        .map_err(|e| JsValue::from_str(&format!("Invalid round2 args: {e}")))?;
-    let z_bytes = threshold_ed25519_round2_sign_cosigner_bytes(args)
-        .map_err(|e| JsValue::from_str(&e))?;
-
-    #[derive(Serialize)]
-    #[serde(rename_all = "camelCase")]
-    struct Out {
-        relayer_signature_share_b64u: String,
-    }
-
-    serde_wasm_bindgen::to_value(&Out {
-        relayer_signature_share_b64u: base64_url_encode(z_bytes.as_ref()),
+pub fn threshold_ed25519_aggregate(args: JsValue) -> Result<JsValue, JsValue> {
+    let args: AggregateArgs = serde_wasm_bindgen::from_value(args)
+        .map_err(|e| JsValue::from_str(&format!("Invalid aggregate args: {e}")))?;
+    let signature =
+        threshold_ed25519_aggregate_signature(args).map_err(|e| JsValue::from_str(&e))?;
+    serde_wasm_bindgen::to_value(&AggregateOutput {
+        signature_b64u: base64_url_encode(&signature),
     })
-    .map_err(|e| JsValue::from_str(&format!("Failed to serialize round2 output: {e}")))
+    .map_err(|e| JsValue::from_str(&format!("Failed to serialize aggregate output: {e}")))
 }
 
 #[cfg(test)]
@@ -772,14 +1450,18 @@ mod tests {
             &master_secret,
             near_account_id,
             rp_id_mixed_case,
+            RpIdNormalization::default(),
             &client_bytes,
+            None,
         )
         .expect("should derive scalar");
         let s2 = derive_threshold_relayer_share_scalar_v1(
             &master_secret,
             near_account_id,
             rp_id_mixed_case,
+            RpIdNormalization::default(),
             &client_bytes,
+            None,
         )
         .expect("should derive scalar");
         assert_eq!(s1.to_bytes(), s2.to_bytes());
@@ -789,12 +1471,296 @@ mod tests {
             &master_secret,
             near_account_id,
             "example.com",
+            RpIdNormalization::default(),
             &client_bytes,
+            None,
         )
         .expect("should derive scalar");
         assert_eq!(s1.to_bytes(), s3.to_bytes());
     }
 
+    #[test]
+    fn rp_id_normalization_policies() {
+        let ascii = "example.com";
+        let uppercase = "Example.COM";
+        let unicode = "Bücher.Example";
+
+        let lowercase = RpIdNormalization::LowercaseAscii;
+        assert_eq!(lowercase.normalize(ascii).unwrap(), "example.com");
+        assert_eq!(lowercase.normalize(uppercase).unwrap(), "example.com");
+        assert_eq!(lowercase.normalize(unicode).unwrap(), "bücher.example");
+
+        let reject = RpIdNormalization::RejectNonAscii;
+        assert_eq!(reject.normalize(ascii).unwrap(), "example.com");
+        assert_eq!(reject.normalize(uppercase).unwrap(), "example.com");
+        assert!(reject.normalize(unicode).is_err());
+
+        let punycode = RpIdNormalization::PunycodeEncode;
+        assert_eq!(punycode.normalize(ascii).unwrap(), "example.com");
+        assert_eq!(punycode.normalize(uppercase).unwrap(), "example.com");
+        assert_eq!(
+            punycode.normalize(unicode).unwrap(),
+            "xn--bcher-kva.example"
+        );
+        assert_eq!(
+            punycode.normalize("例え.テスト").unwrap(),
+            "xn--r8jz45g.xn--zckzah"
+        );
+    }
+
+    #[test]
+    fn relayer_share_depends_on_rp_id_normalization_policy() {
+        let master_secret = [11u8; 32];
+        let client_bytes = (ED25519_BASEPOINT_POINT * CurveScalar::from(3u64))
+            .compress()
+            .to_bytes();
+        let derive = |rp_id: &str, policy: RpIdNormalization| {
+            derive_threshold_relayer_share_scalar_v1(
+                &master_secret,
+                "alice.near",
+                rp_id,
+                policy,
+                &client_bytes,
+                None,
+            )
+            .map(|s| s.to_bytes())
+        };
+
+        // ASCII rpIds derive the same share under every policy.
+        let ascii = derive("Example.COM", RpIdNormalization::LowercaseAscii).unwrap();
+        assert_eq!(
+            derive("example.com", RpIdNormalization::RejectNonAscii).unwrap(),
+            ascii
+        );
+        assert_eq!(
+            derive("example.com", RpIdNormalization::PunycodeEncode).unwrap(),
+            ascii
+        );
+
+        // Unicode rpIds do not: switching policy changes the derived share.
+        let unicode = "bücher.example";
+        let legacy = derive(unicode, RpIdNormalization::LowercaseAscii).unwrap();
+        let punycode = derive(unicode, RpIdNormalization::PunycodeEncode).unwrap();
+        assert_ne!(legacy, punycode);
+        assert_eq!(
+            punycode,
+            derive("xn--bcher-kva.example", RpIdNormalization::LowercaseAscii).unwrap()
+        );
+        assert!(derive(unicode, RpIdNormalization::RejectNonAscii).is_err());
+    }
+
+    fn random_scalar() -> CurveScalar {
+        use frost_ed25519::rand_core::RngCore;
+        let mut wide = [0u8; 64];
+        frost_ed25519::rand_core::OsRng.fill_bytes(&mut wide);
+        CurveScalar::from_bytes_mod_order_wide(&wide)
+    }
+
+    fn combine(shares: &[(u16, CurveScalar)]) -> CurveScalar {
+        interpolate_relayer_shares_at(shares, CurveScalar::ZERO).unwrap()
+    }
+
+    #[test]
+    fn split_relayer_share_any_t_shares_reconstruct() {
+        let relayer_scalar = random_scalar();
+        let cosigner_ids = [3u16, 7, 11, 19, 42];
+        let shares =
+            split_relayer_signing_share_scalar(relayer_scalar, 5, 3, &cosigner_ids, random_scalar)
+                .unwrap();
+        assert_eq!(
+            shares.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            cosigner_ids
+        );
+
+        for a in 0..shares.len() {
+            for b in a + 1..shares.len() {
+                // Fewer than t shares do not reveal the relayer scalar.
+                assert_ne!(combine(&[shares[a], shares[b]]), relayer_scalar);
+                for c in b + 1..shares.len() {
+                    assert_eq!(combine(&[shares[a], shares[b], shares[c]]), relayer_scalar);
+                }
+            }
+        }
+        // More than t shares interpolate the same polynomial.
+        assert_eq!(combine(&shares), relayer_scalar);
+    }
+
+    #[test]
+    fn split_relayer_share_validates_parameters() {
+        let secret = CurveScalar::from(9u64);
+        let split = |n: u16, t: u16, ids: &[u16]| {
+            split_relayer_signing_share_scalar(secret, n, t, ids, random_scalar)
+        };
+        assert!(split(3, 1, &[1, 2, 3]).is_err());
+        assert!(split(3, 4, &[1, 2, 3]).is_err());
+        assert!(split(3, 2, &[1, 2]).is_err());
+        assert!(split(3, 2, &[1, 0, 3]).is_err());
+        assert!(split(3, 2, &[1, 2, 2])
+            .unwrap_err()
+            .contains("duplicate id 2"));
+        assert!(split_relayer_signing_share_scalar(
+            CurveScalar::ZERO,
+            2,
+            2,
+            &[1, 2],
+            random_scalar
+        )
+        .is_err());
+        assert_eq!(split(2, 2, &[1, 2]).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn verify_relayer_shares_accepts_consistent_set() {
+        let relayer_scalar = random_scalar();
+        let relayer_point = ED25519_BASEPOINT_POINT * relayer_scalar;
+        let shares = split_relayer_signing_share_scalar(
+            relayer_scalar,
+            5,
+            3,
+            &[1, 2, 3, 4, 5],
+            random_scalar,
+        )
+        .unwrap();
+
+        assert_eq!(
+            verify_relayer_shares_reconstruct(&shares, &relayer_point, 3),
+            Ok(true)
+        );
+        assert_eq!(
+            verify_relayer_shares_reconstruct(&shares[2..], &relayer_point, 3),
+            Ok(true)
+        );
+        let other_point = ED25519_BASEPOINT_POINT * random_scalar();
+        assert_eq!(
+            verify_relayer_shares_reconstruct(&shares, &other_point, 3),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn verify_relayer_shares_rejects_one_corrupted_share() {
+        let relayer_scalar = random_scalar();
+        let relayer_point = ED25519_BASEPOINT_POINT * relayer_scalar;
+        let shares = split_relayer_signing_share_scalar(
+            relayer_scalar,
+            5,
+            3,
+            &[1, 2, 3, 4, 5],
+            random_scalar,
+        )
+        .unwrap();
+
+        // Corrupting a share inside the first t breaks reconstruction; corrupting a later one
+        // breaks consistency with the polynomial the first t define.
+        for corrupt in 0..shares.len() {
+            let mut tampered = shares.clone();
+            tampered[corrupt].1 += CurveScalar::ONE;
+            assert_eq!(
+                verify_relayer_shares_reconstruct(&tampered, &relayer_point, 3),
+                Ok(false),
+                "corrupted share at index {corrupt} must be detected"
+            );
+        }
+
+        assert!(verify_relayer_shares_reconstruct(&shares, &relayer_point, 1).is_err());
+        assert!(verify_relayer_shares_reconstruct(&shares[..2], &relayer_point, 3).is_err());
+        let mut duplicate = shares.clone();
+        duplicate[4].0 = duplicate[0].0;
+        assert!(
+            verify_relayer_shares_reconstruct(&duplicate, &relayer_point, 3)
+                .unwrap_err()
+                .contains("duplicate id 1")
+        );
+    }
+
+    #[test]
+    fn cached_lagrange_coefficient_matches_uncached() {
+        let pairs = [(1u16, 2u16), (2, 1), (1, 3), (7, 65535), (65535, 1), (1, 2)];
+        for (client_id, relayer_id) in pairs {
+            let uncached = compute_lambda_relayer_2p(client_id, relayer_id).unwrap();
+            // First call populates the cache, second is served from it.
+            assert_eq!(lambda_relayer_2p(client_id, relayer_id).unwrap(), uncached);
+            assert_eq!(lambda_relayer_2p(client_id, relayer_id).unwrap(), uncached);
+            // lambda_client + lambda_relayer = 1 for a 2-party set
+            let lambda_client = compute_lambda_relayer_2p(relayer_id, client_id).unwrap();
+            assert_eq!(lambda_client + uncached, CurveScalar::ONE);
+        }
+        assert!(lambda_relayer_2p(4, 4).is_err());
+
+        threshold_ed25519_configure_lagrange_cache(0);
+        assert_eq!(
+            lambda_relayer_2p(1, 2).unwrap(),
+            compute_lambda_relayer_2p(1, 2).unwrap()
+        );
+        threshold_ed25519_configure_lagrange_cache(LAGRANGE_CACHE_DEFAULT_MAX_ENTRIES as u32);
+    }
+
+    #[test]
+    fn round2_group_public_key_check_accepts_consistent_triple() {
+        let client_point = ED25519_BASEPOINT_POINT * CurveScalar::from(11u64);
+        let relayer_point = ED25519_BASEPOINT_POINT * CurveScalar::from(23u64);
+        let group_pk = compute_threshold_ed25519_group_public_key_2p_from_verifying_shares(
+            client_point,
+            relayer_point,
+            1,
+            2,
+        )
+        .unwrap();
+        let client_b64u = base64_url_encode(client_point.compress().as_bytes());
+        let relayer_b64u = base64_url_encode(relayer_point.compress().as_bytes());
+
+        ensure_group_public_key_matches_verifying_shares(
+            Some(&client_b64u),
+            Some(&relayer_b64u),
+            relayer_point,
+            &group_pk,
+            1,
+            2,
+        )
+        .expect("consistent triple should pass");
+        // Both args absent: legacy callers are unaffected
+        ensure_group_public_key_matches_verifying_shares(None, None, relayer_point, &group_pk, 1, 2)
+            .expect("check is opt-in");
+    }
+
+    #[test]
+    fn round2_group_public_key_check_rejects_spoofed_group_pk() {
+        let client_point = ED25519_BASEPOINT_POINT * CurveScalar::from(11u64);
+        let relayer_point = ED25519_BASEPOINT_POINT * CurveScalar::from(23u64);
+        let spoofed_pk = (ED25519_BASEPOINT_POINT * CurveScalar::from(99u64))
+            .compress()
+            .to_bytes();
+        let client_b64u = base64_url_encode(client_point.compress().as_bytes());
+
+        let err = ensure_group_public_key_matches_verifying_shares(
+            Some(&client_b64u),
+            None,
+            relayer_point,
+            &spoofed_pk,
+            1,
+            2,
+        )
+        .unwrap_err();
+        assert!(err.contains("groupPublicKey does not match"), "unexpected error: {err}");
+
+        // A relayer share that doesn't match the expected verifying share is rejected too
+        let other_relayer_b64u = base64_url_encode(
+            (ED25519_BASEPOINT_POINT * CurveScalar::from(24u64))
+                .compress()
+                .as_bytes(),
+        );
+        let err = ensure_group_public_key_matches_verifying_shares(
+            Some(&client_b64u),
+            Some(&other_relayer_b64u),
+            relayer_point,
+            &spoofed_pk,
+            1,
+            2,
+        )
+        .unwrap_err();
+        assert!(err.contains("expectedRelayerVerifyingShareB64u"), "unexpected error: {err}");
+    }
+
     #[test]
     fn deterministic_relayer_share_changes_with_inputs() {
         let master_secret = [42u8; 32];
@@ -807,7 +1773,9 @@ mod tests {
             &master_secret,
             "alice.near",
             "example.com",
+            RpIdNormalization::default(),
             &client_bytes,
+            None,
         )
         .expect("should derive scalar");
 
@@ -815,7 +1783,9 @@ mod tests {
             &master_secret,
             "bob.near",
             "example.com",
+            RpIdNormalization::default(),
             &client_bytes,
+            None,
         )
         .expect("should derive scalar");
         assert_ne!(base.to_bytes(), different_near.to_bytes());
@@ -824,7 +1794,9 @@ mod tests {
             &master_secret,
             "alice.near",
             "other.example.com",
+            RpIdNormalization::default(),
             &client_bytes,
+            None,
         )
         .expect("should derive scalar");
         assert_ne!(base.to_bytes(), different_rp.to_bytes());
@@ -836,12 +1808,78 @@ mod tests {
             &master_secret,
             "alice.near",
             "example.com",
+            RpIdNormalization::default(),
             &client_bytes2,
+            None,
         )
         .expect("should derive scalar");
         assert_ne!(base.to_bytes(), different_client.to_bytes());
     }
 
+    #[test]
+    fn relayer_share_service_id_separates_relayer_services() {
+        let master_secret = [42u8; 32];
+        let client_bytes = (ED25519_BASEPOINT_POINT * CurveScalar::from(7u64))
+            .compress()
+            .to_bytes();
+        let derive = |service_id: Option<&str>| {
+            derive_threshold_relayer_share_scalar_v1(
+                &master_secret,
+                "alice.near",
+                "example.com",
+                RpIdNormalization::default(),
+                &client_bytes,
+                service_id,
+            )
+            .expect("should derive scalar")
+            .to_bytes()
+        };
+
+        let unscoped = derive(None);
+        let payments = derive(Some("payments"));
+        let wallet = derive(Some("wallet"));
+        assert_ne!(payments, wallet);
+        assert_ne!(payments, unscoped);
+        assert_ne!(wallet, unscoped);
+        assert_eq!(derive(Some("payments")), payments);
+        // An empty service id is the same as none.
+        assert_eq!(derive(Some("")), unscoped);
+    }
+
+    #[test]
+    fn relayer_share_without_service_id_keeps_the_original_derivation() {
+        let master_secret = [42u8; 32];
+        let client_bytes = (ED25519_BASEPOINT_POINT * CurveScalar::from(7u64))
+            .compress()
+            .to_bytes();
+
+        // The pre-service-id derivation: salt = SHA256(client verifying share), ctr = 0.
+        let salt = Sha256::digest(client_bytes);
+        let hk = Hkdf::<Sha256>::new(Some(salt.as_slice()), &master_secret);
+        let mut info = THRESHOLD_RELAYER_SHARE_INFO_PREFIX_V1.to_vec();
+        info.push(0);
+        info.extend_from_slice(b"alice.near");
+        info.push(0);
+        info.extend_from_slice(b"example.com");
+        info.push(0);
+        info.extend_from_slice(&0u64.to_le_bytes());
+        info.extend_from_slice(&0u32.to_le_bytes());
+        let mut okm = [0u8; 64];
+        hk.expand(&info, &mut okm).expect("HKDF expand");
+        let expected = CurveScalar::from_bytes_mod_order_wide(&okm);
+
+        let derived = derive_threshold_relayer_share_scalar_v1(
+            &master_secret,
+            "alice.near",
+            "example.com",
+            RpIdNormalization::default(),
+            &client_bytes,
+            None,
+        )
+        .expect("should derive scalar");
+        assert_eq!(derived.to_bytes(), expected.to_bytes());
+    }
+
     #[test]
     fn two_of_two_signature_from_derived_shares_verifies() {
         // NOTE: This is a pure in-process cryptography test (no HTTP).
@@ -898,7 +1936,9 @@ mod tests {
             &master_secret,
             near_account_id,
             rp_id,
+            RpIdNormalization::default(),
             &client_verifying_share_bytes,
+            None,
         )
         .expect("relayer share should derive");
         let relayer_signing_share_bytes = relayer_scalar.to_bytes();
@@ -1117,7 +2157,9 @@ mod tests {
             &master_secret,
             near_account_id,
             rp_id,
+            RpIdNormalization::default(),
             &client_verifying_share_bytes,
+            None,
         )
         .expect("relayer share should derive");
         let relayer_verifying_share_bytes = (ED25519_BASEPOINT_POINT * relayer_scalar)
@@ -1330,6 +2372,8 @@ mod tests {
             signing_digest_b64u: msg_digest_b64u.clone(),
             client_commitments: client_commitments_wire.clone(),
             relayer_commitments: relayer_commitments_wire.clone(),
+            client_verifying_share_b64u: None,
+            expected_relayer_verifying_share_b64u: None,
         })
         .expect("cosigner1 round2 must succeed");
 
@@ -1342,6 +2386,8 @@ mod tests {
             signing_digest_b64u: msg_digest_b64u,
             client_commitments: client_commitments_wire,
             relayer_commitments: relayer_commitments_wire,
+            client_verifying_share_b64u: None,
+            expected_relayer_verifying_share_b64u: None,
         })
         .expect("cosigner2 round2 must succeed");
 
@@ -1377,4 +2423,208 @@ mod tests {
         vk.verify(msg_digest.as_slice(), &sig)
             .expect("ed25519-dalek should verify group signature");
     }
+
+    #[test]
+    fn debug_signing_package_reports_public_values_of_the_aggregated_signature() {
+        let mut rng = frost_ed25519::rand_core::OsRng;
+        let (shares, pubkey_package) = frost_ed25519::keys::generate_with_dealer(
+            2,
+            2,
+            frost_ed25519::keys::IdentifierList::Default,
+            rng,
+        )
+        .expect("dealer keygen should succeed");
+        let client_identifier: frost_ed25519::Identifier = 1u16.try_into().unwrap();
+        let relayer_identifier: frost_ed25519::Identifier = 2u16.try_into().unwrap();
+        let key_package = |id: &frost_ed25519::Identifier| {
+            frost_ed25519::keys::KeyPackage::try_from(shares[id].clone())
+                .expect("key package should build")
+        };
+        let client_key_package = key_package(&client_identifier);
+        let relayer_key_package = key_package(&relayer_identifier);
+
+        let (client_nonces, client_commitments) =
+            frost_ed25519::round1::commit(client_key_package.signing_share(), &mut rng);
+        let (relayer_nonces, relayer_commitments) =
+            frost_ed25519::round1::commit(relayer_key_package.signing_share(), &mut rng);
+
+        let msg_digest = Sha256::digest(b"debug-signing-package-test");
+        let mut commitments_map = BTreeMap::new();
+        commitments_map.insert(client_identifier, client_commitments);
+        commitments_map.insert(relayer_identifier, relayer_commitments);
+        let signing_package =
+            frost_ed25519::SigningPackage::new(commitments_map, msg_digest.as_slice());
+
+        let mut signature_shares = BTreeMap::new();
+        signature_shares.insert(
+            client_identifier,
+            frost_ed25519::round2::sign(&signing_package, &client_nonces, &client_key_package)
+                .expect("client round2 sign should succeed"),
+        );
+        signature_shares.insert(
+            relayer_identifier,
+            frost_ed25519::round2::sign(&signing_package, &relayer_nonces, &relayer_key_package)
+                .expect("relayer round2 sign should succeed"),
+        );
+        let signature =
+            frost_ed25519::aggregate(&signing_package, &signature_shares, &pubkey_package)
+                .expect("aggregate should succeed")
+                .serialize()
+                .expect("signature serialization should succeed");
+
+        let wire = |c: &frost_ed25519::round1::SigningCommitments| CommitmentsWire {
+            hiding: base64_url_encode(&c.hiding().serialize().expect("hiding must serialize")),
+            binding: base64_url_encode(&c.binding().serialize().expect("binding must serialize")),
+        };
+        let group_pk_bytes = pubkey_package
+            .verifying_key()
+            .serialize()
+            .expect("group public key must serialize");
+        let out = threshold_ed25519_debug_signing_package_values(DebugSigningPackageArgs {
+            client_participant_id: None,
+            relayer_participant_id: None,
+            group_public_key: format!("ed25519:{}", bs58::encode(&group_pk_bytes).into_string()),
+            signing_digest_b64u: base64_url_encode(msg_digest.as_slice()),
+            client_commitments: wire(&client_commitments),
+            relayer_commitments: wire(&relayer_commitments),
+        })
+        .expect("debug signing package should compute");
+
+        // The group commitment is the `R` half of the aggregated Ed25519 signature.
+        assert_eq!(
+            out.group_commitment_b64u,
+            base64_url_encode(&signature[..32])
+        );
+
+        // Ed25519 challenge, computed without frost: SHA-512(R || A || M) mod l.
+        let mut hasher = sha2::Sha512::new();
+        hasher.update(&signature[..32]);
+        hasher.update(group_pk_bytes.as_slice());
+        hasher.update(msg_digest.as_slice());
+        let mut wide = [0u8; 64];
+        wide.copy_from_slice(hasher.finalize().as_slice());
+        let challenge = CurveScalar::from_bytes_mod_order_wide(&wide);
+        assert_eq!(out.challenge_b64u, base64_url_encode(challenge.as_bytes()));
+
+        // No secret material: only the three public values are reported.
+        let json = serde_json::to_value(out).expect("output must serialize");
+        let mut keys: Vec<_> = json.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec!["challengeB64u", "groupCommitmentB64u", "rhoRelayerB64u"]
+        );
+    }
+
+    fn aggregate_args_from_2p_round(message: &[u8], corrupt_relayer_share: bool) -> AggregateArgs {
+        let mut rng = frost_ed25519::rand_core::OsRng;
+        let (shares, pubkey_package) = frost_ed25519::keys::generate_with_dealer(
+            2,
+            2,
+            frost_ed25519::keys::IdentifierList::Default,
+            rng,
+        )
+        .expect("dealer keygen should succeed");
+        let client_identifier: frost_ed25519::Identifier = 1u16.try_into().unwrap();
+        let relayer_identifier: frost_ed25519::Identifier = 2u16.try_into().unwrap();
+        let key_package = |id: &frost_ed25519::Identifier| {
+            frost_ed25519::keys::KeyPackage::try_from(shares[id].clone())
+                .expect("key package should build")
+        };
+        let client_key_package = key_package(&client_identifier);
+        let relayer_key_package = key_package(&relayer_identifier);
+
+        let (client_nonces, client_commitments) =
+            frost_ed25519::round1::commit(client_key_package.signing_share(), &mut rng);
+        let (relayer_nonces, relayer_commitments) =
+            frost_ed25519::round1::commit(relayer_key_package.signing_share(), &mut rng);
+
+        let mut commitments_map = BTreeMap::new();
+        commitments_map.insert(client_identifier, client_commitments);
+        commitments_map.insert(relayer_identifier, relayer_commitments);
+        let signing_package = frost_ed25519::SigningPackage::new(commitments_map, message);
+
+        let client_share =
+            frost_ed25519::round2::sign(&signing_package, &client_nonces, &client_key_package)
+                .expect("client round2 sign should succeed");
+        let relayer_share = if corrupt_relayer_share {
+            // A share over a different message is well-formed but must not verify.
+            let other_package = frost_ed25519::SigningPackage::new(
+                signing_package.signing_commitments().clone(),
+                b"some other message",
+            );
+            frost_ed25519::round2::sign(&other_package, &relayer_nonces, &relayer_key_package)
+                .expect("relayer round2 sign should succeed")
+        } else {
+            frost_ed25519::round2::sign(&signing_package, &relayer_nonces, &relayer_key_package)
+                .expect("relayer round2 sign should succeed")
+        };
+
+        let wire = |c: &frost_ed25519::round1::SigningCommitments| CommitmentsWire {
+            hiding: base64_url_encode(&c.hiding().serialize().expect("hiding must serialize")),
+            binding: base64_url_encode(&c.binding().serialize().expect("binding must serialize")),
+        };
+        let verifying_share = |id: &frost_ed25519::Identifier| {
+            base64_url_encode(
+                &pubkey_package.verifying_shares()[id]
+                    .serialize()
+                    .expect("verifying share must serialize"),
+            )
+        };
+        let group_pk_bytes = pubkey_package
+            .verifying_key()
+            .serialize()
+            .expect("group public key must serialize");
+        AggregateArgs {
+            client_participant_id: None,
+            relayer_participant_id: None,
+            client_verifying_share_b64u: verifying_share(&client_identifier),
+            relayer_verifying_share_b64u: verifying_share(&relayer_identifier),
+            group_public_key: format!("ed25519:{}", bs58::encode(&group_pk_bytes).into_string()),
+            signing_digest_b64u: base64_url_encode(message),
+            client_commitments: wire(&client_commitments),
+            relayer_commitments: wire(&relayer_commitments),
+            client_signature_share_b64u: base64_url_encode(&client_share.serialize()),
+            relayer_signature_share_b64u: base64_url_encode(&relayer_share.serialize()),
+        }
+    }
+
+    #[test]
+    fn aggregate_combines_2p_signature_shares_into_a_group_signature() {
+        let msg_digest = Sha256::digest(b"aggregate-test");
+        let args = aggregate_args_from_2p_round(msg_digest.as_slice(), false);
+        let group_public_key = args.group_public_key.clone();
+
+        let signature =
+            threshold_ed25519_aggregate_signature(args).expect("aggregate should succeed");
+
+        let group_pk_bytes = parse_near_public_key_to_bytes(&group_public_key).unwrap();
+        let vk = ed25519_dalek::VerifyingKey::from_bytes(&group_pk_bytes)
+            .expect("ed25519 group pk must be valid");
+        vk.verify(
+            msg_digest.as_slice(),
+            &ed25519_dalek::Signature::from_bytes(&signature),
+        )
+        .expect("ed25519-dalek should verify aggregated signature");
+    }
+
+    #[test]
+    fn aggregate_rejects_a_bad_signature_share() {
+        let msg_digest = Sha256::digest(b"aggregate-test");
+        let args = aggregate_args_from_2p_round(msg_digest.as_slice(), true);
+
+        let err = threshold_ed25519_aggregate_signature(args)
+            .expect_err("bad relayer share must be rejected");
+        assert!(err.contains("relayer signature share"), "{err}");
+    }
+
+    #[test]
+    fn aggregate_rejects_verifying_shares_for_another_group_key() {
+        let msg_digest = Sha256::digest(b"aggregate-test");
+        let mut args = aggregate_args_from_2p_round(msg_digest.as_slice(), false);
+        args.group_public_key =
+            aggregate_args_from_2p_round(msg_digest.as_slice(), false).group_public_key;
+
+        assert!(threshold_ed25519_aggregate_signature(args).is_err());
+    }
 }
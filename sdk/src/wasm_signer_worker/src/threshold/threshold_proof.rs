@@ -0,0 +1,397 @@
+//! Per-signature proof bundle for 2-of-2 threshold Ed25519 signatures.
+//!
+//! Everything in the bundle is already public within the FROST protocol (commitments,
+//! signature shares, verifying shares, group key). Re-running the verifier lets an auditor
+//! confirm after the fact that the relayer's share contributed to a NEAR signature, rather
+//! than the signature coming from a unilaterally reconstructed key.
+
+#[cfg(feature = "threshold")]
+use crate::encoders::base64_url_decode;
+#[cfg(all(feature = "threshold", any(test, target_arch = "wasm32")))]
+use crate::encoders::base64_url_encode;
+#[cfg(feature = "threshold")]
+use crate::threshold::threshold_frost::compute_threshold_ed25519_group_public_key_2p_from_verifying_shares;
+#[cfg(feature = "threshold")]
+use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+#[cfg(feature = "threshold")]
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+#[cfg(feature = "threshold")]
+use curve25519_dalek::scalar::Scalar as CurveScalar;
+#[cfg(feature = "threshold")]
+use curve25519_dalek::traits::Identity;
+#[cfg(feature = "threshold")]
+use ed25519_dalek::Verifier;
+#[cfg(feature = "threshold")]
+use frost_ed25519::Group;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "threshold")]
+use std::collections::BTreeMap;
+#[cfg(feature = "threshold")]
+use wasm_bindgen::prelude::*;
+
+#[cfg(feature = "threshold")]
+pub const THRESHOLD_SIGNATURE_PROOF_SCHEME: &str = "threshold_ed25519_2p_v1";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThresholdProofCommitments {
+    pub hiding: String,
+    pub binding: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThresholdProofParticipant {
+    pub participant_id: u16,
+    pub commitments: ThresholdProofCommitments,
+    pub signature_share_b64u: String,
+    pub verifying_share_b64u: String,
+}
+
+/// Public transcript of one 2-of-2 FROST signature, sufficient to re-verify offline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThresholdSignatureProof {
+    pub scheme: String,
+    pub signing_digest_b64u: String,
+    /// Group public key (`ed25519:<base58>`)
+    pub group_public_key: String,
+    pub relayer_key_id: String,
+    /// Relayer-issued session id; informational (correlate with relayer logs).
+    pub mpc_session_id: String,
+    pub client: ThresholdProofParticipant,
+    pub relayer: ThresholdProofParticipant,
+    /// Final aggregated Ed25519 signature (R || z)
+    pub signature_b64u: String,
+}
+
+/// One participant's public contribution, as held by the coordinator.
+#[cfg(all(feature = "threshold", any(test, target_arch = "wasm32")))]
+pub(crate) struct ThresholdProofShareInput<'a> {
+    pub identifier: frost_ed25519::Identifier,
+    pub commitments: &'a frost_ed25519::round1::SigningCommitments,
+    pub signature_share: &'a frost_ed25519::round2::SignatureShare,
+    pub verifying_share: &'a frost_ed25519::keys::VerifyingShare,
+}
+
+#[cfg(all(feature = "threshold", any(test, target_arch = "wasm32")))]
+fn identifier_to_u16(identifier: &frost_ed25519::Identifier) -> Result<u16, String> {
+    let bytes = identifier.serialize();
+    if bytes.len() != 32 || bytes[2..].iter().any(|b| *b != 0) {
+        return Err("threshold-proof: participant identifier out of u16 range".to_string());
+    }
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+#[cfg(all(feature = "threshold", any(test, target_arch = "wasm32")))]
+fn proof_participant(share: &ThresholdProofShareInput<'_>) -> Result<ThresholdProofParticipant, String> {
+    let hiding = share
+        .commitments
+        .hiding()
+        .serialize()
+        .map_err(|e| format!("threshold-proof: serialize hiding commitment: {e}"))?;
+    let binding = share
+        .commitments
+        .binding()
+        .serialize()
+        .map_err(|e| format!("threshold-proof: serialize binding commitment: {e}"))?;
+    let verifying_share = share
+        .verifying_share
+        .serialize()
+        .map_err(|e| format!("threshold-proof: serialize verifying share: {e}"))?;
+    Ok(ThresholdProofParticipant {
+        participant_id: identifier_to_u16(&share.identifier)?,
+        commitments: ThresholdProofCommitments {
+            hiding: base64_url_encode(&hiding),
+            binding: base64_url_encode(&binding),
+        },
+        signature_share_b64u: base64_url_encode(&share.signature_share.serialize()),
+        verifying_share_b64u: base64_url_encode(&verifying_share),
+    })
+}
+
+/// Assemble the proof bundle from the coordinator's view of a completed signing round.
+#[cfg(all(feature = "threshold", any(test, target_arch = "wasm32")))]
+pub(crate) fn build_threshold_signature_proof(
+    signing_digest_32: &[u8],
+    verifying_key: &frost_ed25519::VerifyingKey,
+    relayer_key_id: &str,
+    mpc_session_id: &str,
+    client: ThresholdProofShareInput<'_>,
+    relayer: ThresholdProofShareInput<'_>,
+    signature: &[u8; 64],
+) -> Result<ThresholdSignatureProof, String> {
+    let group_pk_bytes = verifying_key
+        .serialize()
+        .map_err(|e| format!("threshold-proof: serialize group public key: {e}"))?;
+    Ok(ThresholdSignatureProof {
+        scheme: THRESHOLD_SIGNATURE_PROOF_SCHEME.to_string(),
+        signing_digest_b64u: base64_url_encode(signing_digest_32),
+        group_public_key: format!("ed25519:{}", bs58::encode(&group_pk_bytes).into_string()),
+        relayer_key_id: relayer_key_id.to_string(),
+        mpc_session_id: mpc_session_id.to_string(),
+        client: proof_participant(&client)?,
+        relayer: proof_participant(&relayer)?,
+        signature_b64u: base64_url_encode(signature),
+    })
+}
+
+#[cfg(feature = "threshold")]
+fn decode_32(b64u: &str, field: &str) -> Result<[u8; 32], String> {
+    let bytes = base64_url_decode(b64u.trim()).map_err(|e| format!("invalid {field}: {e}"))?;
+    bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| format!("invalid {field}: expected 32 bytes, got {}", bytes.len()))
+}
+
+#[cfg(feature = "threshold")]
+fn decode_point(b64u: &str, field: &str) -> Result<EdwardsPoint, String> {
+    CompressedEdwardsY(decode_32(b64u, field)?)
+        .decompress()
+        .ok_or_else(|| format!("invalid {field}: not a curve point"))
+}
+
+#[cfg(feature = "threshold")]
+fn decode_scalar(b64u: &str, field: &str) -> Result<CurveScalar, String> {
+    Option::<CurveScalar>::from(CurveScalar::from_canonical_bytes(decode_32(b64u, field)?))
+        .ok_or_else(|| format!("invalid {field}: non-canonical scalar"))
+}
+
+#[cfg(feature = "threshold")]
+fn parse_group_public_key(public_key: &str) -> Result<[u8; 32], String> {
+    let trimmed = public_key.trim();
+    let decoded = bs58::decode(trimmed.strip_prefix("ed25519:").unwrap_or(trimmed))
+        .into_vec()
+        .map_err(|e| format!("invalid groupPublicKey: {e}"))?;
+    decoded
+        .as_slice()
+        .try_into()
+        .map_err(|_| format!("invalid groupPublicKey: expected 32 bytes, got {}", decoded.len()))
+}
+
+#[cfg(feature = "threshold")]
+fn decode_commitments(
+    c: &ThresholdProofCommitments,
+    role: &str,
+) -> Result<frost_ed25519::round1::SigningCommitments, String> {
+    let hiding = decode_32(&c.hiding, &format!("{role} commitments.hiding"))?;
+    let binding = decode_32(&c.binding, &format!("{role} commitments.binding"))?;
+    let hiding = frost_ed25519::round1::NonceCommitment::deserialize(&hiding)
+        .map_err(|e| format!("invalid {role} hiding commitment: {e}"))?;
+    let binding = frost_ed25519::round1::NonceCommitment::deserialize(&binding)
+        .map_err(|e| format!("invalid {role} binding commitment: {e}"))?;
+    Ok(frost_ed25519::round1::SigningCommitments::new(hiding, binding))
+}
+
+/// Commitment share `R_i` per participant.
+#[cfg(feature = "threshold")]
+pub(crate) type CommitmentShares = BTreeMap<frost_ed25519::Identifier, EdwardsPoint>;
+
+/// Per-participant commitment shares `R_i`, the encoded group commitment `R` and the challenge
+/// `c` of a signing package, recomputed the same way frost-core does during aggregation.
+#[cfg(feature = "threshold")]
+pub(crate) fn signing_package_commitments_and_challenge(
+    signing_package: &frost_ed25519::SigningPackage,
+    verifying_key: &frost_ed25519::VerifyingKey,
+    group_pk_bytes: &[u8; 32],
+) -> Result<(CommitmentShares, [u8; 32], CurveScalar), String> {
+    let preimages = signing_package
+        .binding_factor_preimages(verifying_key, &[])
+        .map_err(|e| format!("failed to compute binding factor preimages: {e}"))?;
+    let mut rho_by_id: BTreeMap<frost_ed25519::Identifier, CurveScalar> = BTreeMap::new();
+    for (id, preimage) in preimages {
+        let rho = <frost_ed25519::Ed25519Sha512 as frost_ed25519::Ciphersuite>::H1(
+            preimage.as_slice(),
+        );
+        rho_by_id.insert(id, rho);
+    }
+
+    let mut group_commitment = EdwardsPoint::identity();
+    let mut commitment_shares = CommitmentShares::new();
+    for (id, c) in signing_package.signing_commitments() {
+        let hiding_bytes: [u8; 32] = c
+            .hiding()
+            .serialize()
+            .map_err(|e| format!("invalid hiding commitment: {e}"))?
+            .as_slice()
+            .try_into()
+            .map_err(|_| "invalid hiding commitment encoding".to_string())?;
+        let binding_bytes: [u8; 32] = c
+            .binding()
+            .serialize()
+            .map_err(|e| format!("invalid binding commitment: {e}"))?
+            .as_slice()
+            .try_into()
+            .map_err(|_| "invalid binding commitment encoding".to_string())?;
+        let hiding = frost_ed25519::Ed25519Group::deserialize(&hiding_bytes)
+            .map_err(|e| format!("invalid hiding commitment: {e}"))?;
+        let binding = frost_ed25519::Ed25519Group::deserialize(&binding_bytes)
+            .map_err(|e| format!("invalid binding commitment: {e}"))?;
+        let rho = rho_by_id
+            .get(id)
+            .ok_or_else(|| "missing binding factor for commitment".to_string())?;
+        let share_commitment = hiding + (binding * (*rho));
+        commitment_shares.insert(*id, share_commitment);
+        group_commitment += share_commitment;
+    }
+    let group_commitment_bytes = group_commitment.compress().to_bytes();
+
+    let mut challenge_preimage = Vec::with_capacity(96);
+    challenge_preimage.extend_from_slice(&group_commitment_bytes);
+    challenge_preimage.extend_from_slice(group_pk_bytes);
+    challenge_preimage.extend_from_slice(signing_package.message());
+    let challenge = <frost_ed25519::Ed25519Sha512 as frost_ed25519::Ciphersuite>::H2(
+        challenge_preimage.as_slice(),
+    );
+
+    Ok((commitment_shares, group_commitment_bytes, challenge))
+}
+
+/// Re-verify a proof bundle. Each failure names the specific check that did not hold.
+#[cfg(feature = "threshold")]
+pub(crate) fn verify_threshold_signature_proof_inner(
+    proof: &ThresholdSignatureProof,
+) -> Result<(), String> {
+    if proof.scheme != THRESHOLD_SIGNATURE_PROOF_SCHEME {
+        return Err(format!("unsupported proof scheme: {}", proof.scheme));
+    }
+    if proof.mpc_session_id.trim().is_empty() {
+        return Err("missing mpcSessionId".to_string());
+    }
+    if proof.relayer_key_id.trim().is_empty() {
+        return Err("missing relayerKeyId".to_string());
+    }
+    let client_id = proof.client.participant_id;
+    let relayer_id = proof.relayer.participant_id;
+    if client_id == 0 || relayer_id == 0 || client_id == relayer_id {
+        return Err("invalid participant ids: must be distinct and non-zero".to_string());
+    }
+
+    let message = base64_url_decode(proof.signing_digest_b64u.trim())
+        .map_err(|e| format!("invalid signingDigest: {e}"))?;
+    if message.len() != 32 {
+        return Err(format!(
+            "invalid signingDigest: expected 32 bytes, got {}",
+            message.len()
+        ));
+    }
+    let group_pk_bytes = parse_group_public_key(&proof.group_public_key)?;
+    let verifying_key = frost_ed25519::VerifyingKey::deserialize(&group_pk_bytes)
+        .map_err(|e| format!("invalid groupPublicKey: {e}"))?;
+
+    // 1) Verifying shares must interpolate to the group key for these participant ids.
+    let client_share_point =
+        decode_point(&proof.client.verifying_share_b64u, "client verifyingShare")?;
+    let relayer_share_point =
+        decode_point(&proof.relayer.verifying_share_b64u, "relayer verifyingShare")?;
+    let interpolated = compute_threshold_ed25519_group_public_key_2p_from_verifying_shares(
+        client_share_point,
+        relayer_share_point,
+        client_id,
+        relayer_id,
+    )?;
+    if interpolated != group_pk_bytes {
+        return Err("verifying shares do not interpolate to groupPublicKey".to_string());
+    }
+
+    // 2) relayerKeyId defaults to the group public key; when it is one, it must match.
+    if proof.relayer_key_id.trim().starts_with("ed25519:")
+        && parse_group_public_key(&proof.relayer_key_id).ok() != Some(group_pk_bytes)
+    {
+        return Err("relayerKeyId does not match groupPublicKey".to_string());
+    }
+
+    // 3) Recompute binding factors, group commitment and challenge (same as frost-core).
+    let client_identifier: frost_ed25519::Identifier = client_id
+        .try_into()
+        .map_err(|_| "invalid client participant id".to_string())?;
+    let relayer_identifier: frost_ed25519::Identifier = relayer_id
+        .try_into()
+        .map_err(|_| "invalid relayer participant id".to_string())?;
+    let mut commitments_map = BTreeMap::new();
+    commitments_map.insert(
+        client_identifier,
+        decode_commitments(&proof.client.commitments, "client")?,
+    );
+    commitments_map.insert(
+        relayer_identifier,
+        decode_commitments(&proof.relayer.commitments, "relayer")?,
+    );
+    let signing_package = frost_ed25519::SigningPackage::new(commitments_map, &message);
+    let (commitment_shares, group_commitment_bytes, challenge) =
+        signing_package_commitments_and_challenge(
+            &signing_package,
+            &verifying_key,
+            &group_pk_bytes,
+        )?;
+
+    // 4) Each share must satisfy z_i·B == R_i + c·λ_i·Y_i.
+    let xc = CurveScalar::from(client_id as u64);
+    let xr = CurveScalar::from(relayer_id as u64);
+    let lambda_client = xr * (xr - xc).invert();
+    let lambda_relayer = xc * (xc - xr).invert();
+
+    let z_client = decode_scalar(&proof.client.signature_share_b64u, "client signatureShare")?;
+    let z_relayer = decode_scalar(&proof.relayer.signature_share_b64u, "relayer signatureShare")?;
+    for (role, id, z, lambda, share_point) in [
+        ("client", client_identifier, z_client, lambda_client, client_share_point),
+        ("relayer", relayer_identifier, z_relayer, lambda_relayer, relayer_share_point),
+    ] {
+        let r_i = commitment_shares
+            .get(&id)
+            .ok_or_else(|| format!("missing {role} commitment"))?;
+        if ED25519_BASEPOINT_POINT * z != r_i + share_point * (challenge * lambda) {
+            return Err(format!(
+                "{role} signature share does not verify against its verifying share"
+            ));
+        }
+    }
+
+    // 5) Aggregation must reproduce the final signature, which must verify under the group key.
+    let mut aggregated = [0u8; 64];
+    aggregated[..32].copy_from_slice(&group_commitment_bytes);
+    aggregated[32..].copy_from_slice((z_client + z_relayer).as_bytes());
+    let signature = base64_url_decode(proof.signature_b64u.trim())
+        .map_err(|e| format!("invalid signature: {e}"))?;
+    if signature.as_slice() != aggregated.as_slice() {
+        return Err("aggregated signature shares do not match signature".to_string());
+    }
+    let dalek_vk = ed25519_dalek::VerifyingKey::from_bytes(&group_pk_bytes)
+        .map_err(|e| format!("invalid groupPublicKey: {e}"))?;
+    dalek_vk
+        .verify(&message, &ed25519_dalek::Signature::from_bytes(&aggregated))
+        .map_err(|_| "signature does not verify under groupPublicKey".to_string())?;
+
+    Ok(())
+}
+
+/// Standalone verifier for `ThresholdSignatureProof` bundles returned alongside signed
+/// transactions when `includeThresholdProof: true` is requested.
+///
+/// Resolves to `{ valid: true, groupPublicKey, relayerKeyId, mpcSessionId }`, or rejects
+/// with the specific check that failed.
+#[cfg(feature = "threshold")]
+#[wasm_bindgen]
+pub fn verify_threshold_signature_proof(proof: JsValue) -> Result<JsValue, JsValue> {
+    let proof: ThresholdSignatureProof = serde_wasm_bindgen::from_value(proof)
+        .map_err(|e| JsValue::from_str(&format!("Invalid threshold signature proof: {e}")))?;
+    verify_threshold_signature_proof_inner(&proof).map_err(|e| JsValue::from_str(&e))?;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Out<'a> {
+        valid: bool,
+        group_public_key: &'a str,
+        relayer_key_id: &'a str,
+        mpc_session_id: &'a str,
+    }
+
+    serde_wasm_bindgen::to_value(&Out {
+        valid: true,
+        group_public_key: &proof.group_public_key,
+        relayer_key_id: &proof.relayer_key_id,
+        mpc_session_id: &proof.mpc_session_id,
+    })
+    .map_err(|e| JsValue::from_str(&format!("Failed to serialize verification result: {e}")))
+}
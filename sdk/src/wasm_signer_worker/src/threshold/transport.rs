@@ -1,3 +1,4 @@
+use crate::threshold::relayer_concurrency::acquire_relayer_request_permit;
 use crate::types::ThresholdSignerConfig;
 
 use super::protocol::CommitmentsWire;
@@ -8,9 +9,24 @@ pub(super) struct ThresholdEd25519SignInitOk {
     pub(super) relayer_verifying_share_b64u: String,
 }
 
+// Only the wasm32 session resolution reads these back.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
 pub(super) struct ThresholdEd25519SessionMintOk {
     pub(super) expires_at: Option<String>,
     pub(super) jwt: Option<String>,
+    /// Relayer clock at mint time (ms since epoch), used to estimate client clock skew.
+    pub(super) server_time_ms: Option<f64>,
+    /// `nonceReservation` capability flag, when the relayer reports one.
+    pub(super) nonce_reservation: Option<bool>,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub(super) struct ThresholdEd25519NonceReservationOk {
+    pub(super) lease_id: String,
+    pub(super) start_nonce: u64,
+    pub(super) count: u32,
+    /// Relayer clock (ms since epoch).
+    pub(super) lease_expires_at_ms: Option<f64>,
 }
 
 pub(super) trait ThresholdEd25519Transport {
@@ -54,6 +70,7 @@ pub(super) trait ThresholdEd25519Transport {
         near_account_id: &str,
         signing_digest_b64u: &str,
         client_commitments: CommitmentsWire,
+        nonce_lease_id: Option<&str>,
     ) -> Result<ThresholdEd25519SignInitOk, String>;
 
     async fn sign_finalize(
@@ -61,11 +78,154 @@ pub(super) trait ThresholdEd25519Transport {
         cfg: &ThresholdSignerConfig,
         signing_session_id: &str,
         client_signature_share_b64u: &str,
+        nonce_lease_id: Option<&str>,
     ) -> Result<String, String>;
+
+    async fn reserve_nonce(
+        &self,
+        cfg: &ThresholdSignerConfig,
+        group_public_key: &str,
+        count: u32,
+        bearer_token: Option<&str>,
+    ) -> Result<ThresholdEd25519NonceReservationOk, String>;
 }
 
+/// Takes a worker-wide relayer request permit (see `relayer_concurrency`) around every call to
+/// the wrapped transport, so at most `maxConcurrentRelayerRequests` calls are on the wire.
+pub(super) struct RelayerLimitedTransport<T>(pub(super) T);
+
+impl<T: ThresholdEd25519Transport> ThresholdEd25519Transport for RelayerLimitedTransport<T> {
+    async fn authorize_mpc_session_id(
+        &self,
+        cfg: &ThresholdSignerConfig,
+        client_verifying_share_b64u: &str,
+        near_account_id: &str,
+        purpose: &str,
+        signing_digest_32: &[u8],
+        vrf_challenge: &crate::types::VrfChallenge,
+        credential_json: &str,
+        signing_payload_json: Option<&str>,
+    ) -> Result<String, String> {
+        let _permit = acquire_relayer_request_permit(cfg.relayer_request_limit()).await;
+        self.0
+            .authorize_mpc_session_id(
+                cfg,
+                client_verifying_share_b64u,
+                near_account_id,
+                purpose,
+                signing_digest_32,
+                vrf_challenge,
+                credential_json,
+                signing_payload_json,
+            )
+            .await
+    }
+
+    async fn authorize_mpc_session_id_with_threshold_session(
+        &self,
+        cfg: &ThresholdSignerConfig,
+        client_verifying_share_b64u: &str,
+        purpose: &str,
+        signing_digest_32: &[u8],
+        signing_payload_json: Option<&str>,
+        bearer_token: Option<&str>,
+    ) -> Result<String, String> {
+        let _permit = acquire_relayer_request_permit(cfg.relayer_request_limit()).await;
+        self.0
+            .authorize_mpc_session_id_with_threshold_session(
+                cfg,
+                client_verifying_share_b64u,
+                purpose,
+                signing_digest_32,
+                signing_payload_json,
+                bearer_token,
+            )
+            .await
+    }
+
+    async fn mint_threshold_session(
+        &self,
+        cfg: &ThresholdSignerConfig,
+        client_verifying_share_b64u: &str,
+        near_account_id: &str,
+        vrf_challenge: &crate::types::VrfChallenge,
+        credential_json: &str,
+        session_policy_json: &str,
+        session_kind: &str,
+    ) -> Result<ThresholdEd25519SessionMintOk, String> {
+        let _permit = acquire_relayer_request_permit(cfg.relayer_request_limit()).await;
+        self.0
+            .mint_threshold_session(
+                cfg,
+                client_verifying_share_b64u,
+                near_account_id,
+                vrf_challenge,
+                credential_json,
+                session_policy_json,
+                session_kind,
+            )
+            .await
+    }
+
+    async fn sign_init(
+        &self,
+        cfg: &ThresholdSignerConfig,
+        mpc_session_id: &str,
+        near_account_id: &str,
+        signing_digest_b64u: &str,
+        client_commitments: CommitmentsWire,
+        nonce_lease_id: Option<&str>,
+    ) -> Result<ThresholdEd25519SignInitOk, String> {
+        let _permit = acquire_relayer_request_permit(cfg.relayer_request_limit()).await;
+        self.0
+            .sign_init(
+                cfg,
+                mpc_session_id,
+                near_account_id,
+                signing_digest_b64u,
+                client_commitments,
+                nonce_lease_id,
+            )
+            .await
+    }
+
+    async fn sign_finalize(
+        &self,
+        cfg: &ThresholdSignerConfig,
+        signing_session_id: &str,
+        client_signature_share_b64u: &str,
+        nonce_lease_id: Option<&str>,
+    ) -> Result<String, String> {
+        let _permit = acquire_relayer_request_permit(cfg.relayer_request_limit()).await;
+        self.0
+            .sign_finalize(
+                cfg,
+                signing_session_id,
+                client_signature_share_b64u,
+                nonce_lease_id,
+            )
+            .await
+    }
+
+    async fn reserve_nonce(
+        &self,
+        cfg: &ThresholdSignerConfig,
+        group_public_key: &str,
+        count: u32,
+        bearer_token: Option<&str>,
+    ) -> Result<ThresholdEd25519NonceReservationOk, String> {
+        let _permit = acquire_relayer_request_permit(cfg.relayer_request_limit()).await;
+        self.0
+            .reserve_nonce(cfg, group_public_key, count, bearer_token)
+            .await
+    }
+}
+
+/// Relayer HTTP endpoints. Production wraps it in `RelayerLimitedTransport`.
+#[cfg(target_arch = "wasm32")]
 pub(super) struct HttpThresholdEd25519Transport;
 
+#[cfg(target_arch = "wasm32")]
 impl ThresholdEd25519Transport for HttpThresholdEd25519Transport {
     async fn authorize_mpc_session_id(
         &self,
@@ -135,6 +295,8 @@ impl ThresholdEd25519Transport for HttpThresholdEd25519Transport {
         Ok(ThresholdEd25519SessionMintOk {
             expires_at: out.expires_at,
             jwt: out.jwt,
+            server_time_ms: out.server_time_ms,
+            nonce_reservation: out.nonce_reservation,
         })
     }
 
@@ -145,6 +307,7 @@ impl ThresholdEd25519Transport for HttpThresholdEd25519Transport {
         near_account_id: &str,
         signing_digest_b64u: &str,
         client_commitments: CommitmentsWire,
+        nonce_lease_id: Option<&str>,
     ) -> Result<ThresholdEd25519SignInitOk, String> {
         let out = super::relayer_http::sign_init(
             cfg,
@@ -152,6 +315,7 @@ impl ThresholdEd25519Transport for HttpThresholdEd25519Transport {
             near_account_id,
             signing_digest_b64u,
             client_commitments,
+            nonce_lease_id,
         )
         .await?;
 
@@ -167,8 +331,377 @@ impl ThresholdEd25519Transport for HttpThresholdEd25519Transport {
         cfg: &ThresholdSignerConfig,
         signing_session_id: &str,
         client_signature_share_b64u: &str,
+        nonce_lease_id: Option<&str>,
     ) -> Result<String, String> {
-        super::relayer_http::sign_finalize(cfg, signing_session_id, client_signature_share_b64u)
-            .await
+        super::relayer_http::sign_finalize(
+            cfg,
+            signing_session_id,
+            client_signature_share_b64u,
+            nonce_lease_id,
+        )
+        .await
+    }
+
+    async fn reserve_nonce(
+        &self,
+        cfg: &ThresholdSignerConfig,
+        group_public_key: &str,
+        count: u32,
+        bearer_token: Option<&str>,
+    ) -> Result<ThresholdEd25519NonceReservationOk, String> {
+        let out =
+            super::relayer_http::reserve_nonce(cfg, group_public_key, count, bearer_token).await?;
+        let lease_id = out
+            .lease_id
+            .ok_or_else(|| "threshold-signer: /nonce/reserve missing leaseId".to_string())?;
+        let start_nonce = out
+            .start_nonce
+            .ok_or_else(|| "threshold-signer: /nonce/reserve missing startNonce".to_string())?;
+        let start_nonce = crate::transaction::parse_nonce(&start_nonce)
+            .map_err(|e| format!("threshold-signer: /nonce/reserve {e}"))?;
+
+        Ok(ThresholdEd25519NonceReservationOk {
+            lease_id,
+            start_nonce,
+            count: out.count.unwrap_or(0),
+            lease_expires_at_ms: out.lease_expires_at_ms,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Relayer request limiting exercised through the transport trait: a fake relayer that
+    //! really plays FROST round 1/2 sits behind `RelayerLimitedTransport`, and concurrent
+    //! signing rounds fan out through the coordinator the way a batch does.
+    use super::*;
+    use crate::encoders::{base64_url_decode, base64_url_encode};
+    use crate::threshold::coordinator::sign_ed25519_2p_v1;
+    use crate::threshold::protocol;
+    use crate::threshold::threshold_proof::verify_threshold_signature_proof_inner;
+    use crate::types::VrfChallenge;
+    use futures::executor::block_on;
+    use futures::future::join_all;
+    use std::cell::{Cell, RefCell};
+    use std::collections::{BTreeMap, HashMap};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    /// Pending once, so other requests get polled while this one is "on the wire".
+    struct YieldNow(bool);
+
+    impl Future for YieldNow {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                return Poll::Ready(());
+            }
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    type Round = (
+        frost_ed25519::round1::SigningNonces,
+        frost_ed25519::SigningPackage,
+    );
+
+    struct FakeRelayer {
+        key_package: frost_ed25519::keys::KeyPackage,
+        client_identifier: frost_ed25519::Identifier,
+        /// Relayer nonces and signing package per open signing session.
+        rounds: RefCell<HashMap<String, Round>>,
+        in_flight: Cell<usize>,
+        max_in_flight: Cell<usize>,
+        /// Digest of every `sign_init`, in the order the calls went on the wire.
+        started: RefCell<Vec<String>>,
+    }
+
+    impl FakeRelayer {
+        /// Count the call as in flight across a few scheduler turns.
+        async fn on_the_wire(&self) {
+            self.in_flight.set(self.in_flight.get() + 1);
+            self.max_in_flight
+                .set(self.max_in_flight.get().max(self.in_flight.get()));
+            for _ in 0..3 {
+                YieldNow(false).await;
+            }
+            self.in_flight.set(self.in_flight.get() - 1);
+        }
+    }
+
+    impl ThresholdEd25519Transport for FakeRelayer {
+        async fn authorize_mpc_session_id(
+            &self,
+            _cfg: &ThresholdSignerConfig,
+            _client_verifying_share_b64u: &str,
+            _near_account_id: &str,
+            _purpose: &str,
+            _signing_digest_32: &[u8],
+            _vrf_challenge: &VrfChallenge,
+            _credential_json: &str,
+            _signing_payload_json: Option<&str>,
+        ) -> Result<String, String> {
+            self.on_the_wire().await;
+            Ok("mpc-session".to_string())
+        }
+
+        async fn authorize_mpc_session_id_with_threshold_session(
+            &self,
+            _cfg: &ThresholdSignerConfig,
+            _client_verifying_share_b64u: &str,
+            _purpose: &str,
+            _signing_digest_32: &[u8],
+            _signing_payload_json: Option<&str>,
+            _bearer_token: Option<&str>,
+        ) -> Result<String, String> {
+            self.on_the_wire().await;
+            Ok("mpc-session".to_string())
+        }
+
+        async fn mint_threshold_session(
+            &self,
+            _cfg: &ThresholdSignerConfig,
+            _client_verifying_share_b64u: &str,
+            _near_account_id: &str,
+            _vrf_challenge: &VrfChallenge,
+            _credential_json: &str,
+            _session_policy_json: &str,
+            _session_kind: &str,
+        ) -> Result<ThresholdEd25519SessionMintOk, String> {
+            self.on_the_wire().await;
+            Ok(ThresholdEd25519SessionMintOk {
+                expires_at: None,
+                jwt: None,
+                server_time_ms: None,
+                nonce_reservation: None,
+            })
+        }
+
+        async fn sign_init(
+            &self,
+            _cfg: &ThresholdSignerConfig,
+            _mpc_session_id: &str,
+            _near_account_id: &str,
+            signing_digest_b64u: &str,
+            client_commitments: CommitmentsWire,
+            _nonce_lease_id: Option<&str>,
+        ) -> Result<ThresholdEd25519SignInitOk, String> {
+            self.started
+                .borrow_mut()
+                .push(signing_digest_b64u.to_string());
+            self.on_the_wire().await;
+
+            let digest = base64_url_decode(signing_digest_b64u)?;
+            let client_commitments = protocol::commitments_from_wire(&client_commitments)?;
+            let mut rng = frost_ed25519::rand_core::OsRng;
+            let (nonces, relayer_commitments) =
+                frost_ed25519::round1::commit(self.key_package.signing_share(), &mut rng);
+            let mut commitments_by_id = BTreeMap::new();
+            commitments_by_id.insert(self.client_identifier, client_commitments);
+            commitments_by_id.insert(*self.key_package.identifier(), relayer_commitments);
+            let signing_package = protocol::build_signing_package(&digest, commitments_by_id);
+
+            let signing_session_id = format!("signing-{signing_digest_b64u}");
+            self.rounds
+                .borrow_mut()
+                .insert(signing_session_id.clone(), (nonces, signing_package));
+            let verifying_share = self
+                .key_package
+                .verifying_share()
+                .serialize()
+                .map_err(|e| e.to_string())?;
+            Ok(ThresholdEd25519SignInitOk {
+                signing_session_id,
+                relayer_commitments: protocol::commitments_to_wire(&relayer_commitments)?,
+                relayer_verifying_share_b64u: base64_url_encode(&verifying_share),
+            })
+        }
+
+        async fn sign_finalize(
+            &self,
+            _cfg: &ThresholdSignerConfig,
+            signing_session_id: &str,
+            _client_signature_share_b64u: &str,
+            _nonce_lease_id: Option<&str>,
+        ) -> Result<String, String> {
+            self.on_the_wire().await;
+            let (nonces, signing_package) = self
+                .rounds
+                .borrow_mut()
+                .remove(signing_session_id)
+                .ok_or_else(|| format!("unknown signing session {signing_session_id}"))?;
+            let share = frost_ed25519::round2::sign(&signing_package, &nonces, &self.key_package)
+                .map_err(|e| e.to_string())?;
+            protocol::signature_share_to_b64u(&share)
+        }
+
+        async fn reserve_nonce(
+            &self,
+            _cfg: &ThresholdSignerConfig,
+            _group_public_key: &str,
+            count: u32,
+            _bearer_token: Option<&str>,
+        ) -> Result<ThresholdEd25519NonceReservationOk, String> {
+            self.on_the_wire().await;
+            Ok(ThresholdEd25519NonceReservationOk {
+                lease_id: "lease".to_string(),
+                start_nonce: 1,
+                count,
+                lease_expires_at_ms: None,
+            })
+        }
+    }
+
+    /// Client key package and a fake relayer holding the other share of a 2-of-2 key.
+    fn client_and_relayer() -> (frost_ed25519::keys::KeyPackage, FakeRelayer) {
+        let rng = frost_ed25519::rand_core::OsRng;
+        let (shares, _pubkey_package) = frost_ed25519::keys::generate_with_dealer(
+            2,
+            2,
+            frost_ed25519::keys::IdentifierList::Default,
+            rng,
+        )
+        .expect("dealer keygen should succeed");
+        let client_identifier: frost_ed25519::Identifier = 1u16.try_into().unwrap();
+        let relayer_identifier: frost_ed25519::Identifier = 2u16.try_into().unwrap();
+        let key_package = |id| {
+            frost_ed25519::keys::KeyPackage::try_from(shares[&id].clone())
+                .expect("key package from dealer share")
+        };
+        (
+            key_package(client_identifier),
+            FakeRelayer {
+                key_package: key_package(relayer_identifier),
+                client_identifier,
+                rounds: RefCell::default(),
+                in_flight: Cell::new(0),
+                max_in_flight: Cell::new(0),
+                started: RefCell::default(),
+            },
+        )
+    }
+
+    fn config(max_concurrent: Option<u32>) -> ThresholdSignerConfig {
+        let mut json = serde_json::json!({
+            "relayerUrl": "https://relay.example.com",
+            "relayerKeyId": "relayer-key-1",
+        });
+        if let Some(limit) = max_concurrent {
+            json["maxConcurrentRelayerRequests"] = serde_json::json!(limit);
+        }
+        serde_json::from_value(json).unwrap()
+    }
+
+    /// Sign `count` distinct digests concurrently through the coordinator.
+    fn fan_out(
+        transport: &RelayerLimitedTransport<FakeRelayer>,
+        client_key_package: &frost_ed25519::keys::KeyPackage,
+        cfg: &ThresholdSignerConfig,
+        count: u8,
+    ) -> Vec<String> {
+        let digests: Vec<[u8; 32]> = (0..count).map(|i| [i; 32]).collect();
+        let outputs = block_on(join_all(digests.iter().map(|digest| {
+            sign_ed25519_2p_v1(
+                transport,
+                cfg,
+                "mpc-session",
+                "alice.near",
+                digest,
+                client_key_package,
+                transport.0.client_identifier,
+                *transport.0.key_package.identifier(),
+                None,
+            )
+        })));
+        for output in outputs {
+            let output = output.expect("threshold signing round");
+            verify_threshold_signature_proof_inner(&output.proof).expect("valid signature");
+            assert_eq!(
+                base64_url_encode(&output.signature),
+                output.proof.signature_b64u
+            );
+        }
+        digests.iter().map(|d| base64_url_encode(d)).collect()
+    }
+
+    #[test]
+    fn batch_fan_out_never_exceeds_the_default_cap() {
+        let (client_key_package, relayer) = client_and_relayer();
+        let transport = RelayerLimitedTransport(relayer);
+
+        fan_out(&transport, &client_key_package, &config(None), 12);
+
+        assert_eq!(
+            transport.0.max_in_flight.get(),
+            crate::config::THRESHOLD_MAX_CONCURRENT_RELAYER_REQUESTS as usize
+        );
+        assert_eq!(transport.0.in_flight.get(), 0);
+        assert!(transport.0.rounds.borrow().is_empty());
+    }
+
+    #[test]
+    fn configured_cap_is_respected_and_requests_start_in_order() {
+        for limit in [1, 2, 5] {
+            let (client_key_package, relayer) = client_and_relayer();
+            let transport = RelayerLimitedTransport(relayer);
+
+            let digests = fan_out(&transport, &client_key_package, &config(Some(limit)), 9);
+
+            assert_eq!(transport.0.max_in_flight.get(), limit as usize);
+            assert_eq!(*transport.0.started.borrow(), digests);
+        }
+    }
+
+    #[test]
+    fn every_transport_call_holds_a_permit() {
+        let (_, relayer) = client_and_relayer();
+        let transport = RelayerLimitedTransport(relayer);
+        let cfg = config(Some(1));
+        let vrf_challenge: VrfChallenge = serde_json::from_value(serde_json::json!({
+            "vrfInput": "", "vrfOutput": "", "vrfProof": "", "vrfPublicKey": "", "userId": "",
+            "rpId": "example.com", "blockHeight": "1", "blockHash": "",
+        }))
+        .unwrap();
+
+        // `sign_init` only succeeds inside a round; the fan-out tests above cover it.
+        fn twice<F: Future>(call: impl Fn() -> F) {
+            block_on(futures::future::join(call(), call()));
+        }
+        twice(|| {
+            transport.authorize_mpc_session_id(
+                &cfg,
+                "share",
+                "alice.near",
+                "purpose",
+                &[0u8; 32],
+                &vrf_challenge,
+                "{}",
+                None,
+            )
+        });
+        twice(|| {
+            transport.authorize_mpc_session_id_with_threshold_session(
+                &cfg, "share", "purpose", &[0u8; 32], None, None,
+            )
+        });
+        twice(|| {
+            transport.mint_threshold_session(
+                &cfg,
+                "share",
+                "alice.near",
+                &vrf_challenge,
+                "{}",
+                "{}",
+                "jwt",
+            )
+        });
+        twice(|| transport.sign_finalize(&cfg, "signing-unknown", "share", None));
+        twice(|| transport.reserve_nonce(&cfg, "ed25519:group", 2, None));
+
+        assert_eq!(transport.0.max_in_flight.get(), 1);
     }
 }
@@ -1,9 +1,21 @@
 use borsh;
+use serde::Serialize;
 use sha2::{Digest, Sha256};
+use wasm_bindgen::prelude::*;
 
 use crate::actions::ActionParams;
+use crate::config::MAX_TX_SIZE_BYTES;
+use crate::encoders::{base64_standard_encode, base64_url_decode_lenient};
 use crate::types::*;
 
+/// Parse a decimal nonce string (surrounding whitespace allowed). Every handler reports a bad
+/// nonce, including one past `u64::MAX`, with the same message.
+pub fn parse_nonce(s: &str) -> Result<u64, String> {
+    s.trim()
+        .parse()
+        .map_err(|_| format!("nonce must be a u64, got '{}'", s))
+}
+
 /// Build a transaction with multiple actions
 pub fn build_transaction_with_actions(
     signer_account_id: &str,
@@ -17,10 +29,15 @@ pub fn build_transaction_with_actions(
     let signer_id: AccountId = signer_account_id
         .parse()
         .map_err(|e| format!("Invalid signer account: {}", e))?;
+    // Reject receiver typos here rather than after the RPC refuses the signed transaction.
+    validate_account_id_syntax(receiver_account_id)
+        .map_err(|e| format!("invalid receiver_id '{}': {}", receiver_account_id, e))?;
     let receiver_id: AccountId = receiver_account_id
         .parse()
         .map_err(|e| format!("Invalid receiver account: {}", e))?;
 
+    check_transaction_size(&actions)?;
+
     // Parse block hash
     if block_hash_bytes.len() != 32 {
         return Err("Block hash must be 32 bytes".to_string());
@@ -43,6 +60,21 @@ pub fn build_transaction_with_actions(
     })
 }
 
+/// Sum the borsh-serialized size of `actions` and reject the transaction when it exceeds
+/// `MAX_TX_SIZE_BYTES`. Many individually small actions can add up past the network limit,
+/// which would otherwise only surface when the signed transaction is broadcast.
+pub fn check_transaction_size(actions: &[NearAction]) -> Result<usize, String> {
+    let mut total = 0usize;
+    for action in actions {
+        total += borsh::object_length(action)
+            .map_err(|e| format!("Action serialization failed: {}", e))?;
+    }
+    if total > MAX_TX_SIZE_BYTES {
+        return Err(format!("transaction too large: {} bytes", total));
+    }
+    Ok(total)
+}
+
 /// Build actions from action parameters
 pub fn build_actions_from_params(
     action_params: Vec<ActionParams>,
@@ -50,7 +82,7 @@ pub fn build_actions_from_params(
     let mut actions = Vec::new();
     for (i, params) in action_params.into_iter().enumerate() {
         let action = params
-            .to_action()
+            .into_action()
             .map_err(|e| format!("Action {} build failed: {}", i, e))?;
         actions.push(action);
     }
@@ -73,6 +105,42 @@ pub fn sign_transaction(
         .map_err(|e| format!("Signed transaction serialization failed: {}", e))
 }
 
+/// Borsh size of a `SignedTransaction`'s trailing signature: key type byte + 64 signature bytes.
+const SIGNATURE_BORSH_LEN: usize = 1 + 64;
+
+/// Serialize `transaction` once, into a buffer pre-sized for the signature as well, and return
+/// it with the digest to sign. The transaction (with any contract code it owns) is dropped as
+/// soon as it is written, so a large DeployContract is never held twice.
+pub fn serialize_transaction_for_signing(
+    transaction: Transaction,
+) -> Result<(Vec<u8>, CryptoHash), String> {
+    let len = borsh::object_length(&transaction)
+        .map_err(|e| format!("Transaction serialization failed: {}", e))?;
+    let mut bytes = Vec::with_capacity(len + SIGNATURE_BORSH_LEN);
+    borsh::to_writer(&mut bytes, &transaction)
+        .map_err(|e| format!("Transaction serialization failed: {}", e))?;
+    drop(transaction);
+    let hash = crate::signable_message::compute_signable_digest(
+        crate::signable_message::SignableMessageKind::Transaction,
+        &bytes,
+    );
+    Ok((bytes, CryptoHash::from_bytes(hash)))
+}
+
+/// Append the signature to bytes from `serialize_transaction_for_signing`, which yields the
+/// borsh encoding of the `SignedTransaction` without serializing the transaction again.
+pub fn append_transaction_signature(
+    mut transaction_bytes: Vec<u8>,
+    signature_bytes: &[u8; 64],
+) -> Result<Vec<u8>, String> {
+    borsh::to_writer(
+        &mut transaction_bytes,
+        &Signature::from_ed25519_bytes(signature_bytes),
+    )
+    .map_err(|e| format!("Signed transaction serialization failed: {}", e))?;
+    Ok(transaction_bytes)
+}
+
 /// Calculate a proper transaction hash from signed transaction bytes using SHA256
 pub fn calculate_transaction_hash(signed_tx_bytes: &[u8]) -> String {
     let mut hasher = Sha256::new();
@@ -82,3 +150,36 @@ pub fn calculate_transaction_hash(signed_tx_bytes: &[u8]) -> String {
     // Convert to hex string for readability and consistency
     format!("{:x}", result)
 }
+
+/// Params for the NEAR JSON-RPC `send_tx` / `broadcast_tx_commit` methods.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SignedTransactionRpcPayload {
+    /// Standard (padded) base64 of the borsh-serialized SignedTransaction
+    pub signed_tx_base64: String,
+    /// NEAR transaction hash: base58(sha256(borsh(Transaction)))
+    pub tx_hash: String,
+}
+
+/// Decode borsh SignedTransaction bytes and derive the broadcast payload.
+pub fn signed_transaction_rpc_payload(
+    signed_tx_bytes: &[u8],
+) -> Result<SignedTransactionRpcPayload, String> {
+    let signed_tx = SignedTransaction::from_borsh_bytes(signed_tx_bytes)?;
+    let (tx_hash, _size) = signed_tx.transaction.get_hash_and_size();
+    Ok(SignedTransactionRpcPayload {
+        signed_tx_base64: base64_standard_encode(signed_tx_bytes),
+        tx_hash: bs58::encode(tx_hash.0).into_string(),
+    })
+}
+
+/// Convert a base64url borsh SignedTransaction (`WasmSignedTransaction.borshBytes`, encoded)
+/// into `{ signed_tx_base64, tx_hash }` for `send_tx` / `broadcast_tx_commit`.
+#[wasm_bindgen]
+pub fn signed_transaction_to_rpc_payload(signed_tx_borsh_b64u: String) -> Result<JsValue, JsValue> {
+    let signed_tx_bytes = base64_url_decode_lenient(&signed_tx_borsh_b64u)
+        .map_err(|e| JsValue::from_str(&format!("Invalid signed transaction base64url: {}", e)))?;
+    let payload = signed_transaction_rpc_payload(&signed_tx_bytes)
+        .map_err(|e| JsValue::from_str(&format!("Invalid signed transaction: {}", e)))?;
+    serde_wasm_bindgen::to_value(&payload)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize RPC payload: {:?}", e)))
+}
@@ -0,0 +1,209 @@
+//! Confirmation summary for a transaction batch, with an optional cost estimate.
+//!
+//! Attached gas alone does not tell a user what a transaction may cost; that depends on the
+//! gas price in the block header. When a gas price is available (passed in, or fetched with
+//! `gas_price` from `nearRpcUrl`), each transaction carries
+//! `estimatedMaxCostYocto = gas * gasPrice + deposits`. It is a maximum: unused gas is refunded.
+//! Without a price the estimate is omitted and the summary is otherwise unchanged.
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::config::GAS_PRICE_STALE_AFTER_BLOCKS;
+use crate::error::TransactionSummaryError;
+use crate::handlers::handle_sign_transactions_with_actions::TransactionPayload;
+use crate::rpc_calls::gas_price;
+use crate::transaction::build_actions_from_params;
+use crate::types::near::NearAction;
+
+const YOCTO_NEAR_DECIMALS: usize = 24;
+
+/// Gas price and the block it was read at (e.g. cached from an earlier `gas_price` call).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasPriceQuote {
+    #[serde(with = "crate::types::near::serde_balance_as_dec_str")]
+    pub gas_price_yocto: u128,
+    pub block_height: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionSummaryRequest {
+    pub transactions: Vec<TransactionPayload>,
+    /// Price to estimate with; when absent and `near_rpc_url` is set, the latest price is fetched.
+    #[serde(default)]
+    pub gas_price: Option<GasPriceQuote>,
+    #[serde(default)]
+    pub near_rpc_url: Option<String>,
+    /// Block the transactions will reference; `gas_price` quotes older than
+    /// `GAS_PRICE_STALE_AFTER_BLOCKS` before it are flagged stale.
+    #[serde(default)]
+    pub current_block_height: Option<u64>,
+}
+
+/// Upper bound on what one transaction can cost the signer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionCostEstimate {
+    pub gas_price_yocto: String,
+    /// `gas * gasPrice + deposits`. A maximum: unused gas is refunded.
+    pub estimated_max_cost_yocto: String,
+    pub estimated_max_cost_near: String,
+    pub gas_price_stale: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionSummaryItem {
+    pub index: u32,
+    pub receiver_id: String,
+    pub action_count: u32,
+    /// Gas attached to FunctionCall actions.
+    pub total_gas: String,
+    /// FunctionCall and Transfer deposits, yoctoNEAR.
+    pub total_deposit_yocto: String,
+    pub total_deposit_near: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<TransactionCostEstimate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionSummary {
+    pub signer_account_id: String,
+    pub transactions: Vec<TransactionSummaryItem>,
+}
+
+/// Render yoctoNEAR as a decimal NEAR string (`1.5`), keeping full precision.
+pub fn format_yocto_as_near(yocto: u128) -> String {
+    let padded = format!("{:0>width$}", yocto, width = YOCTO_NEAR_DECIMALS + 1);
+    let (whole, frac) = padded.split_at(padded.len() - YOCTO_NEAR_DECIMALS);
+    let frac = frac.trim_end_matches('0');
+    if frac.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, frac)
+    }
+}
+
+/// `(attached gas, deposits)` of one transaction's actions.
+fn gas_and_deposit(
+    actions: &[NearAction],
+    index: usize,
+) -> Result<(u128, u128), TransactionSummaryError> {
+    actions
+        .iter()
+        .try_fold((0u128, 0u128), |(gas, deposit), action| {
+            let (action_gas, action_deposit) = match action {
+                NearAction::FunctionCall(call) => (call.gas, call.deposit),
+                NearAction::Transfer { deposit } => (0, *deposit),
+                _ => (0, 0),
+            };
+            Some((
+                gas.checked_add(u128::from(action_gas))?,
+                deposit.checked_add(action_deposit)?,
+            ))
+        })
+        .ok_or(TransactionSummaryError::AmountOverflow { index })
+}
+
+/// `gas * gas_price + deposit`, failing instead of wrapping.
+pub fn estimated_max_cost(gas: u128, gas_price_yocto: u128, deposit: u128) -> Option<u128> {
+    gas.checked_mul(gas_price_yocto)?.checked_add(deposit)
+}
+
+/// Summarize `transactions` for confirmation. With `gas_price`, each item carries a maximum
+/// cost estimate, flagged stale when the quote is too far behind `current_block_height`.
+pub fn create_transaction_summary(
+    transactions: &[TransactionPayload],
+    gas_price: Option<&GasPriceQuote>,
+    current_block_height: Option<u64>,
+) -> Result<TransactionSummary, TransactionSummaryError> {
+    let first = transactions
+        .first()
+        .ok_or(TransactionSummaryError::EmptyBatch)?;
+    let gas_price_stale = match (gas_price, current_block_height) {
+        (Some(quote), Some(current)) => {
+            current.saturating_sub(quote.block_height) > GAS_PRICE_STALE_AFTER_BLOCKS
+        }
+        _ => false,
+    };
+
+    let mut items = Vec::with_capacity(transactions.len());
+    for (index, tx) in transactions.iter().enumerate() {
+        let actions = build_actions_from_params(tx.actions.clone())
+            .map_err(|reason| TransactionSummaryError::InvalidActions { index, reason })?;
+        let (gas, deposit) = gas_and_deposit(&actions, index)?;
+        let estimate = gas_price
+            .map(|quote| {
+                let cost = estimated_max_cost(gas, quote.gas_price_yocto, deposit)
+                    .ok_or(TransactionSummaryError::AmountOverflow { index })?;
+                Ok(TransactionCostEstimate {
+                    gas_price_yocto: quote.gas_price_yocto.to_string(),
+                    estimated_max_cost_yocto: cost.to_string(),
+                    estimated_max_cost_near: format_yocto_as_near(cost),
+                    gas_price_stale,
+                })
+            })
+            .transpose()?;
+        items.push(TransactionSummaryItem {
+            index: index as u32,
+            receiver_id: tx.receiver_id.clone(),
+            action_count: actions.len() as u32,
+            total_gas: gas.to_string(),
+            total_deposit_yocto: deposit.to_string(),
+            total_deposit_near: format_yocto_as_near(deposit),
+            estimate,
+        });
+    }
+    Ok(TransactionSummary {
+        signer_account_id: first.near_account_id.clone(),
+        transactions: items,
+    })
+}
+
+/// `create_transaction_summary` with the request's gas price, or the latest one from
+/// `nearRpcUrl`. An unreachable RPC only omits the estimate.
+pub async fn create_transaction_summary_with_rpc(
+    request: &TransactionSummaryRequest,
+) -> Result<TransactionSummary, TransactionSummaryError> {
+    let mut quote = request.gas_price;
+    if quote.is_none() {
+        if let Some(rpc_url) = request
+            .near_rpc_url
+            .as_deref()
+            .filter(|u| !u.trim().is_empty())
+        {
+            match gas_price(rpc_url, None).await {
+                // The latest price is as fresh as the reference block.
+                Ok(view) => {
+                    quote = Some(GasPriceQuote {
+                        gas_price_yocto: view.gas_price,
+                        block_height: request.current_block_height.unwrap_or_default(),
+                    })
+                }
+                Err(e) => log::warn!(
+                    "[rust wasm]: gas_price unavailable, omitting cost estimate: {}",
+                    e
+                ),
+            }
+        }
+    }
+    create_transaction_summary(
+        &request.transactions,
+        quote.as_ref(),
+        request.current_block_height,
+    )
+}
+
+/// Confirmation summary for a transaction batch (see `TransactionSummaryRequest`). Resolves to
+/// `{ signerAccountId, transactions: [{ ..., estimate? }] }`.
+#[wasm_bindgen]
+pub async fn summarize_transactions(request: JsValue) -> Result<JsValue, JsValue> {
+    let request: TransactionSummaryRequest = serde_wasm_bindgen::from_value(request)
+        .map_err(|e| JsValue::from_str(&format!("Invalid transaction summary request: {}", e)))?;
+    let summary = create_transaction_summary_with_rpc(&request).await?;
+    serde_wasm_bindgen::to_value(&summary)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize summary: {:?}", e)))
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
+use crate::error::KeyEnvelopeError;
+
 // ******************************************************************************
 // *                                                                            *
 // *                    SHARED AUTHENTICATOR OPTIONS TYPES                      *
@@ -155,6 +157,23 @@ pub struct ConfirmationConfig {
     /// UI theme preference (dark/light)
     #[wasm_bindgen(getter_with_clone)]
     pub theme: Option<String>,
+
+    /// Optional origin the signing session is bound to. When set, the WebAuthn credential's
+    /// clientDataJSON must carry this origin or signing is rejected.
+    #[wasm_bindgen(getter_with_clone, js_name = "expectedOrigin")]
+    #[serde(default)]
+    pub expected_origin: Option<String>,
+
+    /// Locale of `localized_strings` (e.g. "fr"); display-only.
+    #[wasm_bindgen(getter_with_clone)]
+    #[serde(default)]
+    pub locale: Option<String>,
+
+    /// Pre-rendered summary strings keyed by summary field id (`receiver`, `amount`).
+    /// Each must contain the field's raw value or the VRF worker falls back to English.
+    #[wasm_bindgen(skip)]
+    #[serde(default)]
+    pub localized_strings: Option<std::collections::BTreeMap<String, String>>,
 }
 
 impl Default for ConfirmationConfig {
@@ -164,10 +183,37 @@ impl Default for ConfirmationConfig {
             behavior: ConfirmationBehavior::RequireClick,
             auto_proceed_delay: Some(2000),
             theme: Some("dark".to_string()),
+            expected_origin: None,
+            locale: None,
+            localized_strings: None,
         }
     }
 }
 
+impl ConfirmationConfig {
+    /// Enforce `expected_origin` (if set) against the credential JSON forwarded with the request.
+    pub fn enforce_expected_origin(&self, credential_json: Option<&str>) -> Result<(), String> {
+        let Some(expected_origin) = self.expected_origin.as_deref() else {
+            return Ok(());
+        };
+        let credential_json = credential_json.ok_or_else(|| {
+            format!(
+                "origin mismatch: expected {}, got no credential",
+                expected_origin
+            )
+        })?;
+        let cred_js = js_sys::JSON::parse(credential_json)
+            .map_err(|e| format!("Invalid credential JSON: {:?}", e))?;
+        let credential: crate::types::WebAuthnAuthenticationCredential =
+            serde_wasm_bindgen::from_value(cred_js)
+                .map_err(|e| format!("Invalid WebAuthn authentication credential: {}", e))?;
+        crate::types::verify_client_data_origin(
+            expected_origin,
+            &credential.response.client_data_json,
+        )
+    }
+}
+
 // === DECRYPTION TYPES ===
 
 /// Decryption payload (consolidated for deserialization and WASM binding)
@@ -175,12 +221,18 @@ impl Default for ConfirmationConfig {
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DecryptionPayload {
-    /// Encrypted NEAR private key
+    /// Encrypted NEAR private key (version 2 envelope, account-wide KEK)
     #[wasm_bindgen(getter_with_clone, js_name = "encryptedPrivateKeyData")]
+    #[serde(default)]
     pub encrypted_private_key_data: String,
     /// ChaCha20-Poly1305 nonce (base64url) for `encryptedPrivateKeyData`.
     #[wasm_bindgen(getter_with_clone, js_name = "encryptedPrivateKeyChacha20NonceB64u")]
+    #[serde(default)]
     pub encrypted_private_key_chacha20_nonce_b64u: String,
+    /// Version 3 envelopes, one per passkey registered for the account on this device.
+    #[wasm_bindgen(skip)]
+    #[serde(default)]
+    pub credential_envelopes: Vec<CredentialKeyEnvelope>,
 }
 
 #[wasm_bindgen]
@@ -193,10 +245,145 @@ impl DecryptionPayload {
         DecryptionPayload {
             encrypted_private_key_data,
             encrypted_private_key_chacha20_nonce_b64u,
+            credential_envelopes: Vec::new(),
         }
     }
 }
 
+impl DecryptionPayload {
+    pub fn with_credential_envelopes(mut self, envelopes: Vec<CredentialKeyEnvelope>) -> Self {
+        self.credential_envelopes = envelopes;
+        self
+    }
+
+    /// Pick the envelope to decrypt for this signing request.
+    ///
+    /// `requested` is the request's `credentialId`; `presented` is the credential id of the
+    /// WebAuthn assertion that unlocked the session. The account-wide (version 2) envelope
+    /// is the fallback for credentials registered before envelopes were credential-scoped.
+    pub fn select_envelope(
+        &self,
+        requested: Option<&str>,
+        presented: Option<&str>,
+    ) -> Result<SelectedKeyEnvelope<'_>, KeyEnvelopeError> {
+        if let (Some(requested), Some(presented)) = (requested, presented) {
+            if requested != presented {
+                return Err(KeyEnvelopeError::CredentialMismatch {
+                    requested: requested.to_string(),
+                    presented: presented.to_string(),
+                });
+            }
+        }
+
+        let account_envelope =
+            (!self.encrypted_private_key_data.is_empty()).then(|| SelectedKeyEnvelope {
+                encrypted_private_key_data: &self.encrypted_private_key_data,
+                encrypted_private_key_chacha20_nonce_b64u: &self
+                    .encrypted_private_key_chacha20_nonce_b64u,
+                credential_id: None,
+                version: crate::config::NEAR_KEY_ENVELOPE_VERSION_ACCOUNT,
+                aad_context: None,
+            });
+
+        let Some(credential_id) = requested.or(presented) else {
+            return match (account_envelope, self.credential_envelopes.as_slice()) {
+                (Some(envelope), _) => Ok(envelope),
+                (None, [only]) => Ok(only.as_selected()),
+                (None, []) => Err(KeyEnvelopeError::MissingEnvelope),
+                (None, _) => Err(KeyEnvelopeError::AmbiguousEnvelope {
+                    available: self.stored_credential_ids(),
+                }),
+            };
+        };
+
+        if let Some(envelope) = self
+            .credential_envelopes
+            .iter()
+            .find(|envelope| envelope.credential_id == credential_id)
+        {
+            return Ok(envelope.as_selected());
+        }
+        match account_envelope {
+            Some(envelope) => Ok(envelope),
+            None if self.credential_envelopes.is_empty() => Err(KeyEnvelopeError::MissingEnvelope),
+            None => Err(KeyEnvelopeError::NoEnvelopeForCredential {
+                credential_id: credential_id.to_string(),
+                available: self.stored_credential_ids(),
+            }),
+        }
+    }
+
+    fn stored_credential_ids(&self) -> Vec<String> {
+        self.credential_envelopes
+            .iter()
+            .map(|envelope| envelope.credential_id.clone())
+            .collect()
+    }
+}
+
+/// NEAR private key encrypted under a KEK bound to one WebAuthn credential (version 3 or 4).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialKeyEnvelope {
+    /// WebAuthn credential id (base64url) the envelope belongs to.
+    pub credential_id: String,
+    pub encrypted_private_key_data: String,
+    pub encrypted_private_key_chacha20_nonce_b64u: String,
+    /// Envelope format; version 4 authenticates the account id (and `aadContext`) as AAD.
+    #[serde(default = "credential_envelope_version_without_aad")]
+    pub version: u8,
+    /// App context bound into the AAD at encryption time (version 4 only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aad_context: Option<String>,
+}
+
+fn credential_envelope_version_without_aad() -> u8 {
+    crate::config::NEAR_KEY_ENVELOPE_VERSION_CREDENTIAL
+}
+
+impl CredentialKeyEnvelope {
+    fn as_selected(&self) -> SelectedKeyEnvelope<'_> {
+        SelectedKeyEnvelope {
+            encrypted_private_key_data: &self.encrypted_private_key_data,
+            encrypted_private_key_chacha20_nonce_b64u: &self
+                .encrypted_private_key_chacha20_nonce_b64u,
+            credential_id: Some(&self.credential_id),
+            version: self.version,
+            aad_context: self.aad_context.as_deref(),
+        }
+    }
+}
+
+/// Envelope chosen by `DecryptionPayload::select_envelope`; `credential_id` is set for
+/// credential-scoped envelopes and selects the matching KEK, `version` says whether the
+/// ciphertext carries AAD (see `WrapKey::decrypt_near_key_envelope`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelectedKeyEnvelope<'a> {
+    pub encrypted_private_key_data: &'a str,
+    pub encrypted_private_key_chacha20_nonce_b64u: &'a str,
+    pub credential_id: Option<&'a str>,
+    pub version: u8,
+    pub aad_context: Option<&'a str>,
+}
+
+/// Credential id of the WebAuthn assertion behind this signing request: the serialized
+/// credential when the request carries one, otherwise the credential the VRF worker
+/// verified when it minted the session.
+pub(crate) fn presented_credential_id(
+    credential_json: Option<&str>,
+    session_id: &str,
+) -> Option<String> {
+    let from_credential = credential_json
+        .and_then(|json| js_sys::JSON::parse(json).ok())
+        .and_then(|cred| js_sys::Reflect::get(&cred, &JsValue::from_str("id")).ok())
+        .and_then(|id| id.as_string())
+        .filter(|id| !id.is_empty());
+    from_credential.or_else(|| {
+        crate::wrap_key_handshake::session_contract_verification(session_id)
+            .map(|facts| facts.credential_id)
+    })
+}
+
 // === REGISTRATION TYPES ===
 
 #[wasm_bindgen]
@@ -0,0 +1,76 @@
+//! Handler log collection with a caller-selected verbosity.
+//!
+//! Handlers return `logs: Vec<String>` to JS. `HandlerLogs` filters lines as they are
+//! pushed, so `"none"` and `"errors"` never retain the verbose per-step context.
+
+/// Which handler log lines are kept in the result (`logVerbosity` on the request).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogVerbosity {
+    /// Return no log lines.
+    None,
+    /// Keep only lines pushed from failure branches.
+    Errors,
+    /// Keep every line (default).
+    #[default]
+    All,
+}
+
+impl LogVerbosity {
+    /// Resolve the optional request field; `None` means `"all"`.
+    pub fn from_request(value: Option<&str>) -> Result<Self, String> {
+        match value {
+            None => Ok(Self::All),
+            Some(v) => v.parse(),
+        }
+    }
+}
+
+impl std::str::FromStr for LogVerbosity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "errors" => Ok(Self::Errors),
+            "all" => Ok(Self::All),
+            other => Err(format!(
+                "Invalid logVerbosity '{}': expected \"none\", \"errors\" or \"all\"",
+                other
+            )),
+        }
+    }
+}
+
+/// Log lines accumulated by a handler, filtered by `LogVerbosity` on push.
+#[derive(Debug, Clone, Default)]
+pub struct HandlerLogs {
+    verbosity: LogVerbosity,
+    lines: Vec<String>,
+}
+
+impl HandlerLogs {
+    pub fn new(verbosity: LogVerbosity) -> Self {
+        Self {
+            verbosity,
+            lines: Vec::new(),
+        }
+    }
+
+    /// Informational line; kept only at `LogVerbosity::All`.
+    pub fn push(&mut self, line: String) {
+        if self.verbosity == LogVerbosity::All {
+            self.lines.push(line);
+        }
+    }
+
+    /// Line from a failure branch; dropped only at `LogVerbosity::None`.
+    pub fn push_error(&mut self, line: String) {
+        if self.verbosity != LogVerbosity::None {
+            self.lines.push(line);
+        }
+    }
+
+    pub fn into_lines(self) -> Vec<String> {
+        self.lines
+    }
+}
@@ -3,6 +3,7 @@
 pub mod crypto;
 pub mod deserializers;
 pub mod handlers;
+pub mod logs;
 pub mod near;
 pub mod participants;
 pub mod progress;
@@ -14,6 +15,7 @@ pub mod worker_messages;
 // Re-export commonly used types
 pub use crypto::*;
 pub use handlers::*;
+pub use logs::*;
 pub use near::*;
 pub use progress::*;
 pub use signing::*;
@@ -1,7 +1,7 @@
 use super::deserializers::{serde_array_32, serde_array_64};
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
+use wasm_bindgen::prelude::*;
 
 // === CORE NEAR TYPES ===
 
@@ -9,13 +9,84 @@ use sha2::{Digest, Sha256};
 #[serde(rename_all = "camelCase")]
 pub struct AccountId(pub String);
 
+/// Length of a NEAR implicit account ID (hex of a 32-byte ed25519 public key).
+pub const IMPLICIT_ACCOUNT_ID_LEN: usize = 64;
+
 impl AccountId {
     pub fn new(account_id: String) -> Result<Self, String> {
         if account_id.is_empty() {
             return Err("Account ID cannot be empty".to_string());
         }
+        // 64 hex characters can only be an implicit account; NEAR requires those lowercase.
+        if account_id.len() == IMPLICIT_ACCOUNT_ID_LEN
+            && account_id.chars().all(|c| c.is_ascii_hexdigit())
+            && !is_implicit_account_id(&account_id)
+        {
+            return Err("Implicit account ID must be lowercase hex".to_string());
+        }
         Ok(AccountId(account_id))
     }
+
+    /// Implicit account ID for an ed25519 public key (lowercase hex of the key bytes).
+    pub fn implicit_from_public_key(public_key_bytes: &[u8; 32]) -> Self {
+        AccountId(public_key_bytes.iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    pub fn is_implicit(&self) -> bool {
+        is_implicit_account_id(&self.0)
+    }
+
+    /// Short form for confirmation UIs: implicit IDs render as `f3a1…9c2e`,
+    /// named accounts are returned unchanged.
+    pub fn display_short(&self) -> String {
+        if self.is_implicit() {
+            format!("{}…{}", &self.0[..4], &self.0[IMPLICIT_ACCOUNT_ID_LEN - 4..])
+        } else {
+            self.0.clone()
+        }
+    }
+}
+
+/// NEAR account ID length bounds (protocol `MIN_ACCOUNT_ID_LEN` / `MAX_ACCOUNT_ID_LEN`).
+pub const MIN_ACCOUNT_ID_LEN: usize = 2;
+pub const MAX_ACCOUNT_ID_LEN: usize = 64;
+
+/// Full NEAR account ID syntax check: 2–64 chars of `[a-z0-9]` in `.`-separated parts,
+/// with `-`/`_` only between alphanumerics (no leading, trailing, or doubled separators).
+pub fn validate_account_id_syntax(account_id: &str) -> Result<(), String> {
+    if account_id.len() < MIN_ACCOUNT_ID_LEN || account_id.len() > MAX_ACCOUNT_ID_LEN {
+        return Err(format!(
+            "length {} is outside {}..={}",
+            account_id.len(),
+            MIN_ACCOUNT_ID_LEN,
+            MAX_ACCOUNT_ID_LEN
+        ));
+    }
+    let mut last_was_separator = true; // start of id behaves like a separator
+    for c in account_id.chars() {
+        match c {
+            'a'..='z' | '0'..='9' => last_was_separator = false,
+            '-' | '_' | '.' => {
+                if last_was_separator {
+                    return Err(format!("unexpected '{}'", c));
+                }
+                last_was_separator = true;
+            }
+            _ => return Err(format!("invalid character '{}'", c)),
+        }
+    }
+    if last_was_separator {
+        return Err("cannot end with a separator".to_string());
+    }
+    Ok(())
+}
+
+/// True when `account_id` is exactly 64 lowercase hex characters.
+pub fn is_implicit_account_id(account_id: &str) -> bool {
+    account_id.len() == IMPLICIT_ACCOUNT_ID_LEN
+        && account_id
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
 }
 
 impl std::str::FromStr for AccountId {
@@ -48,6 +119,29 @@ impl PublicKey {
     }
 }
 
+/// Decoded key lengths NEAR accepts for access keys: Ed25519 points are 32 bytes and
+/// secp256k1 keys are the 64-byte uncompressed point without its `0x04` tag.
+pub const ED25519_PUBLIC_KEY_LEN: usize = 32;
+pub const SECP256K1_PUBLIC_KEY_LEN: usize = 64;
+
+/// True when `public_key` has the shape of a NEAR access key: an `ed25519:` or `secp256k1:`
+/// prefix followed by base58 of the right length. Check derived keys with this before
+/// submitting an AddKey.
+#[wasm_bindgen]
+pub fn is_valid_near_access_key(public_key: String) -> bool {
+    let (encoded, expected_len) = if let Some(rest) = public_key.strip_prefix("ed25519:") {
+        (rest, ED25519_PUBLIC_KEY_LEN)
+    } else if let Some(rest) = public_key.strip_prefix("secp256k1:") {
+        (rest, SECP256K1_PUBLIC_KEY_LEN)
+    } else {
+        return false;
+    };
+    bs58::decode(encoded)
+        .into_vec()
+        .map(|bytes| bytes.len() == expected_len)
+        .unwrap_or(false)
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Signature {
@@ -94,7 +188,7 @@ pub type Balance = u128;
 // working (especially for delegate actions that serialize inner `Action`s),
 // we encode Balance as a decimal string and accept either a string or a
 // non-negative number when deserializing.
-mod serde_balance_as_dec_str {
+pub(crate) mod serde_balance_as_dec_str {
     use super::Balance;
     use serde::{Deserializer, Serializer};
 
@@ -353,13 +447,10 @@ impl Transaction {
     /// This mirrors the logic from near-primitives Transaction::get_hash_and_size()
     pub fn get_hash_and_size(&self) -> (CryptoHash, u64) {
         let bytes = borsh::to_vec(&self).expect("Failed to serialize transaction");
-        let hash_bytes = {
-            let mut hasher = Sha256::new();
-            hasher.update(&bytes);
-            hasher.finalize()
-        };
-        let mut hash_array = [0u8; 32];
-        hash_array.copy_from_slice(&hash_bytes);
+        let hash_array = crate::signable_message::compute_signable_digest(
+            crate::signable_message::SignableMessageKind::Transaction,
+            &bytes,
+        );
         (CryptoHash::from_bytes(hash_array), bytes.len() as u64)
     }
 
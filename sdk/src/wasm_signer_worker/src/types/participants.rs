@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::encoders::B64UrlBytes;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ThresholdParticipantRole {
     #[serde(rename = "client")]
@@ -32,9 +34,9 @@ pub struct ThresholdEd25519ParticipantV1 {
     /// Key/share identifier understood by this participant (e.g. relayerKeyId).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub relayer_key_id: Option<String>,
-    /// Base64url-encoded 32-byte verifying share (compressed EdwardsY).
+    /// 32-byte verifying share (compressed EdwardsY), `Encoding::B64Url` on the wire.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub verifying_share_b64u: Option<String>,
+    pub verifying_share_b64u: Option<B64UrlBytes>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub share_derivation: Option<ThresholdEd25519ShareDerivationV1>,
 }
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+use crate::threshold::nonce_reservation::ThresholdNonceMode;
+use crate::threshold::request_auth::RelayerRequestAuth;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SignerMode {
     #[serde(rename = "local-signer")]
@@ -51,4 +54,34 @@ pub struct ThresholdSignerConfig {
     /// When present, the signer worker uses it to authenticate `/threshold-ed25519/authorize` requests.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub threshold_session_jwt: Option<String>,
+    /// Treat cached relayer sessions as expired this many ms before their (skew-corrected)
+    /// expiry. Defaults to `THRESHOLD_SESSION_REFRESH_MARGIN_MS`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold_session_refresh_margin_ms: Option<f64>,
+    /// Clock-skew tolerance subtracted from a cached session's expiry on top of the measured
+    /// skew bound. Defaults to `THRESHOLD_SESSION_MAX_CLOCK_SKEW_MS`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold_session_max_clock_skew_ms: Option<f64>,
+    /// Drop any cached relayer session for the account and mint/authorize afresh, e.g. after
+    /// a relayer key rotation or session policy change. A caller-supplied
+    /// `threshold_session_jwt` is ignored as well; an explicit `mpc_session_id` still wins.
+    #[serde(default)]
+    pub force_session_refresh: bool,
+    /// Per-request authentication for relayers that require signed requests
+    /// (`{ kind: "hmac-sha256", keyId, ... }`, secret provisioned in the worker by `keyId`).
+    /// Defaults to `{ kind: "none" }`.
+    #[serde(default, skip_serializing_if = "RelayerRequestAuth::is_none")]
+    pub request_auth: RelayerRequestAuth,
+    /// `"relayerReserved"` reserves a nonce range from the relayer per batch instead of using
+    /// the access-key nonce, when the relayer supports it. Defaults to `"accessKey"`.
+    #[serde(default, skip_serializing_if = "ThresholdNonceMode::is_access_key")]
+    pub nonce_mode: ThresholdNonceMode,
+    /// `nonceReservation` capability flag from the relayer's keygen response. A flag on the
+    /// threshold session response, when one was minted, takes precedence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relayer_nonce_reservation: Option<bool>,
+    /// Most relayer HTTP requests in flight at once. Defaults to
+    /// `THRESHOLD_MAX_CONCURRENT_RELAYER_REQUESTS`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_relayer_requests: Option<u32>,
 }
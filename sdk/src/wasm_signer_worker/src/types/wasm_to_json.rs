@@ -150,6 +150,19 @@ impl WasmSignedTransaction {
     }
 }
 
+impl WasmSignedTransaction {
+    /// Build from borsh `SignedTransaction` bytes, keeping those bytes as `borshBytes`
+    /// instead of serializing the transaction again.
+    pub fn from_signed_borsh_bytes(borsh_bytes: Vec<u8>) -> Result<Self, String> {
+        let signed_tx = crate::types::SignedTransaction::from_borsh_bytes(&borsh_bytes)?;
+        Ok(WasmSignedTransaction {
+            transaction: WasmTransaction::from(&signed_tx.transaction),
+            signature: WasmSignature::from(&signed_tx.signature),
+            borsh_bytes,
+        })
+    }
+}
+
 impl From<&crate::types::SignedTransaction> for WasmSignedTransaction {
     fn from(signed_tx: &crate::types::SignedTransaction) -> Self {
         let borsh_bytes = signed_tx.to_borsh_bytes().unwrap_or_default();
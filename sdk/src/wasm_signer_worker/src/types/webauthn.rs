@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use wasm_bindgen::prelude::*;
 
 // === WEBAUTHN CREDENTIAL TYPES ===
@@ -127,7 +127,7 @@ impl From<&WebAuthnRegistrationCredentialStruct> for WebAuthnRegistrationCredent
             response: WebAuthnRegistrationResponse {
                 client_data_json: credential.client_data_json.clone(),
                 attestation_object: credential.attestation_object.clone(),
-                transports: credential.transports.clone(),
+                transports: normalize_transports(credential.transports.clone()),
             },
             authenticator_attachment: credential.authenticator_attachment.clone(),
             reg_type: credential.credential_type.clone(),
@@ -150,15 +150,28 @@ pub struct WebAuthnAuthenticationCredential {
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WebAuthnAuthenticationResponse {
+    /// base64url (`Encoding::B64Url`), forwarded to the contract verbatim
     #[serde(rename = "clientDataJSON")]
     pub client_data_json: String,
+    /// base64url (`Encoding::B64Url`)
     #[serde(rename = "authenticatorData")]
     pub authenticator_data: String,
+    /// base64url (`Encoding::B64Url`)
     pub signature: String,
+    /// base64url (`Encoding::B64Url`)
     #[serde(rename = "userHandle")]
     pub user_handle: Option<String>,
 }
 
+impl WebAuthnAuthenticationResponse {
+    /// Authenticator signature counter (`signCount`) carried in `authenticatorData`.
+    pub fn sign_count(&self) -> Result<u32, String> {
+        let auth_data = crate::encoders::base64_url_decode(&self.authenticator_data)
+            .map_err(|e| format!("Invalid authenticatorData: {}", e))?;
+        crate::cose::parse_authenticator_data_sign_count(&auth_data)
+    }
+}
+
 // ============================================================================
 // CONTRACT INTERFACE: WebAuthn Registration Credential
 // ============================================================================
@@ -223,10 +236,24 @@ pub struct WebAuthnRegistrationResponse {
 
     /// Transport types supported by this authenticator
     /// CONTRACT EXPECTS: Optional array of strings
-    /// Example: ["hybrid", "internal"]
+    /// Example: ["hybrid", "internal"]; `None` when the authenticator reported none
+    #[serde(default, deserialize_with = "deserialize_transports")]
     pub transports: Option<Vec<String>>,
 }
 
+/// Authenticators may omit `transports` or report an empty list; both mean "unknown" and are
+/// normalized to `None`, so a populated list is the only thing ever forwarded.
+pub fn normalize_transports(transports: Option<Vec<String>>) -> Option<Vec<String>> {
+    transports.filter(|list| !list.is_empty())
+}
+
+fn deserialize_transports<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<Vec<String>>::deserialize(deserializer).map(normalize_transports)
+}
+
 // === SHARED CREDENTIAL TYPES ===
 
 #[wasm_bindgen]
@@ -295,7 +322,12 @@ pub struct RegistrationResponse {
     #[serde(alias = "attestationObject")]
     pub attestation_object: String,
     #[wasm_bindgen(getter_with_clone, js_name = "transports")]
-    pub transports: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_transports",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub transports: Option<Vec<String>>,
 }
 
 #[wasm_bindgen]
@@ -303,6 +335,10 @@ pub struct RegistrationResponse {
 pub struct ClientExtensionResults {
     #[wasm_bindgen(getter_with_clone, js_name = "prf")]
     pub prf: PrfResults,
+    /// Legacy U2F `appid` extension output: `true` when the authenticator used the appid.
+    #[wasm_bindgen(js_name = "appid")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub appid: Option<bool>,
 }
 
 #[wasm_bindgen]
@@ -354,3 +390,31 @@ pub struct VrfChallenge {
     )]
     pub session_policy_digest_32: Option<String>,
 }
+
+// === CLIENT DATA ORIGIN ===
+
+/// Check that a base64url clientDataJSON was produced for `expected_origin`.
+pub fn verify_client_data_origin(
+    expected_origin: &str,
+    client_data_json_b64u: &str,
+) -> Result<(), String> {
+    let bytes = crate::encoders::base64_url_decode(client_data_json_b64u)
+        .map_err(|e| format!("Failed to decode clientDataJSON: {}", e))?;
+    let client_data_json =
+        String::from_utf8(bytes).map_err(|_| "clientDataJSON is not valid UTF-8".to_string())?;
+    let client_data = js_sys::JSON::parse(&client_data_json)
+        .map_err(|e| format!("Invalid clientDataJSON: {:?}", e))?;
+    let origin = js_sys::Reflect::get(&client_data, &JsValue::from_str("origin"))
+        .ok()
+        .and_then(|value| value.as_string())
+        .ok_or_else(|| "clientDataJSON has no origin".to_string())?;
+    if crate::worker_security::normalize_origin(&origin)
+        != crate::worker_security::normalize_origin(expected_origin)
+    {
+        return Err(format!(
+            "origin mismatch: expected {}, got {}",
+            expected_origin, origin
+        ));
+    }
+    Ok(())
+}
@@ -1,53 +1,125 @@
 // === WORKER MESSAGES: REQUEST & RESPONSE TYPES ===
 // Enums and message structures for worker communication
 
-use crate::error::ParsePayloadError;
+use crate::error::{BinaryAttachmentError, ParsePayloadError, RequestTypeError};
+use crate::inflight_requests::DuplicatePolicy;
+use crate::request_deadline::RequestDeadline;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::ops::Range;
 use wasm_bindgen::prelude::*;
 
 // === CLEAN RUST ENUMS WITH NUMERIC CONVERSION ===
 // These export to TypeScript as numeric enums and we convert directly from numbers
+//
+// Wire values are explicit and must never change once shipped: TS and wasm builds of
+// different versions talk to each other, so new variants take the next free value in
+// their category's range (see `REQUEST_TYPE_RANGES`) and are added to
+// `WORKER_REQUEST_TYPE_REGISTRY`.
 #[wasm_bindgen]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
 pub enum WorkerRequestType {
-    DeriveNearKeypairAndEncrypt,
-    RecoverKeypairFromPasskey,
-    DecryptPrivateKeyWithPrf,
-    SignTransactionsWithActions,
-    ExtractCosePublicKey,
-    SignTransactionWithKeyPair,
-    SignNep413Message,
+    DeriveNearKeypairAndEncrypt = 0,
+    RecoverKeypairFromPasskey = 1,
+    DecryptPrivateKeyWithPrf = 2,
+    SignTransactionsWithActions = 3,
+    ExtractCosePublicKey = 4,
+    SignTransactionWithKeyPair = 5,
+    SignNep413Message = 6,
     // Combined Device2 registration: derive + sign in one step
-    RegisterDevice2WithDerivedKey,
+    RegisterDevice2WithDerivedKey = 7,
     // Delegate action signing (NEP-461)
-    SignDelegateAction,
+    SignDelegateAction = 8,
     // Public, deterministic key enrollment helper for threshold mode
-    DeriveThresholdEd25519ClientVerifyingShare,
+    DeriveThresholdEd25519ClientVerifyingShare = 9,
     /// Single-purpose internal signing path for post-registration activation:
     /// Sign AddKey(thresholdPublicKey) for receiverId == nearAccountId without VRF/confirmTxFlow.
-    SignAddKeyThresholdPublicKeyNoPrompt,
+    SignAddKeyThresholdPublicKeyNoPrompt = 10,
+    /// Re-encrypt a legacy `packages/passkey` (dual-PRF) key blob under the WrapKey envelope.
+    MigrateLegacyEncryptedKey = 11,
+    /// Derive and cache the client threshold signing share for a session (opt-in warm path).
+    WarmThresholdEd25519ClientShare = 12,
+    /// Batched COSE public key extraction with per-item error isolation.
+    ExtractCosePublicKeys = 13,
+    /// List the device records (device number, public key, credential) kept for an account.
+    GetDeviceRecords = 14,
+    /// Sign every step of a batched multi-contract `Intent` after a single confirmation.
+    SignIntent = 15,
+    /// Sign a device-linking account card for Device1's QR code.
+    CreateAccountCard = 16,
+    /// Check a scanned account card's signature and expiry (no key material needed).
+    VerifyAccountCard = 17,
+    /// Current state version (cheap poll; no payload needed).
+    GetStateVersion = 18,
+    /// State-change events after a given version, for UI sync between polls.
+    GetStateChangesSince = 19,
+    LogoutAll = 20,
+    /// Logout everywhere: clear every session store, fail pending waits, advance the logout epoch.
+    ClearAllSessions = 21,
+    /// Sign a caller-built, borsh-serialized unsigned `Transaction` with the session key.
+    SignBorshTransaction = 22,
+    /// Check a stored key envelope's integrity tag with the session, without decrypting it.
+    VerifyEncryptedKeyEnvelope = 23,
+    /// Panic inside a handler on purpose (test builds with the `debug-panic` feature only).
+    DebugPanic = 24,
+    /// Store a relayer `requestAuth` HMAC secret in this worker under its `keyId`.
+    ProvisionRelayerRequestAuthSecret = 100,
 }
 
 impl From<u32> for WorkerRequestType {
     fn from(value: u32) -> Self {
-        match value {
-            0 => WorkerRequestType::DeriveNearKeypairAndEncrypt,
-            1 => WorkerRequestType::RecoverKeypairFromPasskey,
-            2 => WorkerRequestType::DecryptPrivateKeyWithPrf,
-            3 => WorkerRequestType::SignTransactionsWithActions,
-            4 => WorkerRequestType::ExtractCosePublicKey,
-            5 => WorkerRequestType::SignTransactionWithKeyPair,
-            6 => WorkerRequestType::SignNep413Message,
-            7 => WorkerRequestType::RegisterDevice2WithDerivedKey,
-            8 => WorkerRequestType::SignDelegateAction,
-            9 => WorkerRequestType::DeriveThresholdEd25519ClientVerifyingShare,
-            10 => WorkerRequestType::SignAddKeyThresholdPublicKeyNoPrompt,
-            _ => panic!("Invalid WorkerRequestType value: {}", value),
-        }
+        WorkerRequestType::try_from_u32(value)
+            .unwrap_or_else(|| panic!("Invalid WorkerRequestType value: {}", value))
     }
 }
 impl WorkerRequestType {
+    pub fn try_from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(WorkerRequestType::DeriveNearKeypairAndEncrypt),
+            1 => Some(WorkerRequestType::RecoverKeypairFromPasskey),
+            2 => Some(WorkerRequestType::DecryptPrivateKeyWithPrf),
+            3 => Some(WorkerRequestType::SignTransactionsWithActions),
+            4 => Some(WorkerRequestType::ExtractCosePublicKey),
+            5 => Some(WorkerRequestType::SignTransactionWithKeyPair),
+            6 => Some(WorkerRequestType::SignNep413Message),
+            7 => Some(WorkerRequestType::RegisterDevice2WithDerivedKey),
+            8 => Some(WorkerRequestType::SignDelegateAction),
+            9 => Some(WorkerRequestType::DeriveThresholdEd25519ClientVerifyingShare),
+            10 => Some(WorkerRequestType::SignAddKeyThresholdPublicKeyNoPrompt),
+            11 => Some(WorkerRequestType::MigrateLegacyEncryptedKey),
+            12 => Some(WorkerRequestType::WarmThresholdEd25519ClientShare),
+            13 => Some(WorkerRequestType::ExtractCosePublicKeys),
+            14 => Some(WorkerRequestType::GetDeviceRecords),
+            15 => Some(WorkerRequestType::SignIntent),
+            16 => Some(WorkerRequestType::CreateAccountCard),
+            17 => Some(WorkerRequestType::VerifyAccountCard),
+            18 => Some(WorkerRequestType::GetStateVersion),
+            19 => Some(WorkerRequestType::GetStateChangesSince),
+            20 => Some(WorkerRequestType::LogoutAll),
+            21 => Some(WorkerRequestType::ClearAllSessions),
+            22 => Some(WorkerRequestType::SignBorshTransaction),
+            23 => Some(WorkerRequestType::VerifyEncryptedKeyEnvelope),
+            24 => Some(WorkerRequestType::DebugPanic),
+            100 => Some(WorkerRequestType::ProvisionRelayerRequestAuthSecret),
+            _ => None,
+        }
+    }
+
+    /// Decode a wire value, telling a newer client (value in a reserved range this build
+    /// does not assign) apart from garbage.
+    pub fn from_wire(value: u32) -> Result<Self, RequestTypeError> {
+        WorkerRequestType::try_from_u32(value).ok_or_else(|| {
+            match REQUEST_TYPE_RANGES
+                .iter()
+                .find(|(range, _)| range.contains(&value))
+            {
+                Some((_, category)) => RequestTypeError::FromNewerClient { value, category },
+                None => RequestTypeError::Unknown { value },
+            }
+        })
+    }
+
     pub fn name(&self) -> &'static str {
         match self {
             WorkerRequestType::DeriveNearKeypairAndEncrypt => "DERIVE_NEAR_KEYPAIR_AND_ENCRYPT",
@@ -65,10 +137,68 @@ impl WorkerRequestType {
             WorkerRequestType::SignAddKeyThresholdPublicKeyNoPrompt => {
                 "SIGN_ADD_KEY_THRESHOLD_PUBLIC_KEY_NO_PROMPT"
             }
+            WorkerRequestType::MigrateLegacyEncryptedKey => "MIGRATE_LEGACY_ENCRYPTED_KEY",
+            WorkerRequestType::WarmThresholdEd25519ClientShare => {
+                "WARM_THRESHOLD_ED25519_CLIENT_SHARE"
+            }
+            WorkerRequestType::ExtractCosePublicKeys => "EXTRACT_COSE_PUBLIC_KEYS",
+            WorkerRequestType::GetDeviceRecords => "GET_DEVICE_RECORDS",
+            WorkerRequestType::SignIntent => "SIGN_INTENT",
+            WorkerRequestType::CreateAccountCard => "CREATE_ACCOUNT_CARD",
+            WorkerRequestType::VerifyAccountCard => "VERIFY_ACCOUNT_CARD",
+            WorkerRequestType::GetStateVersion => "GET_STATE_VERSION",
+            WorkerRequestType::GetStateChangesSince => "GET_STATE_CHANGES_SINCE",
+            WorkerRequestType::LogoutAll => "LOGOUT_ALL",
+            WorkerRequestType::ClearAllSessions => "CLEAR_ALL_SESSIONS",
+            WorkerRequestType::SignBorshTransaction => "SIGN_BORSH_TRANSACTION",
+            WorkerRequestType::VerifyEncryptedKeyEnvelope => "VERIFY_ENCRYPTED_KEY_ENVELOPE",
+            WorkerRequestType::DebugPanic => "DEBUG_PANIC",
+            WorkerRequestType::ProvisionRelayerRequestAuthSecret => {
+                "PROVISION_RELAYER_REQUEST_AUTH_SECRET"
+            }
         }
     }
 }
 
+/// Wire-value ranges reserved per request category. Core types use 0..100; threshold and
+/// session request types added from now on take values from their own range.
+pub const REQUEST_TYPE_RANGES: [(Range<u32>, &str); 3] = [
+    (0..100, "core"),
+    (100..200, "threshold"),
+    (200..300, "session"),
+];
+
+/// Every request type this build accepts, as `(wire value, name)`.
+#[cfg_attr(not(test), allow(dead_code))]
+pub const WORKER_REQUEST_TYPE_REGISTRY: [(u32, &str); 26] = [
+    (0, "DERIVE_NEAR_KEYPAIR_AND_ENCRYPT"),
+    (1, "RECOVER_KEYPAIR_FROM_PASSKEY"),
+    (2, "DECRYPT_PRIVATE_KEY_WITH_PRF"),
+    (3, "SIGN_TRANSACTIONS_WITH_ACTIONS"),
+    (4, "EXTRACT_COSE_PUBLIC_KEY"),
+    (5, "SIGN_TRANSACTION_WITH_KEYPAIR"),
+    (6, "SIGN_NEP413_MESSAGE"),
+    (7, "REGISTER_DEVICE2_WITH_DERIVED_KEY"),
+    (8, "SIGN_DELEGATE_ACTION"),
+    (9, "DERIVE_THRESHOLD_ED25519_CLIENT_VERIFYING_SHARE"),
+    (10, "SIGN_ADD_KEY_THRESHOLD_PUBLIC_KEY_NO_PROMPT"),
+    (11, "MIGRATE_LEGACY_ENCRYPTED_KEY"),
+    (12, "WARM_THRESHOLD_ED25519_CLIENT_SHARE"),
+    (13, "EXTRACT_COSE_PUBLIC_KEYS"),
+    (14, "GET_DEVICE_RECORDS"),
+    (15, "SIGN_INTENT"),
+    (16, "CREATE_ACCOUNT_CARD"),
+    (17, "VERIFY_ACCOUNT_CARD"),
+    (18, "GET_STATE_VERSION"),
+    (19, "GET_STATE_CHANGES_SINCE"),
+    (20, "LOGOUT_ALL"),
+    (21, "CLEAR_ALL_SESSIONS"),
+    (22, "SIGN_BORSH_TRANSACTION"),
+    (23, "VERIFY_ENCRYPTED_KEY_ENVELOPE"),
+    (24, "DEBUG_PANIC"),
+    (100, "PROVISION_RELAYER_REQUEST_AUTH_SECRET"),
+];
+
 /// Convert WorkerRequestType enum to readable string for debugging.
 /// Used in logs to make numeric enum values human-friendly.
 pub fn worker_request_type_name(request_type: WorkerRequestType) -> &'static str {
@@ -88,6 +218,25 @@ pub fn worker_request_type_name(request_type: WorkerRequestType) -> &'static str
         WorkerRequestType::SignAddKeyThresholdPublicKeyNoPrompt => {
             "SIGN_ADD_KEY_THRESHOLD_PUBLIC_KEY_NO_PROMPT"
         }
+        WorkerRequestType::MigrateLegacyEncryptedKey => "MIGRATE_LEGACY_ENCRYPTED_KEY",
+        WorkerRequestType::WarmThresholdEd25519ClientShare => {
+            "WARM_THRESHOLD_ED25519_CLIENT_SHARE"
+        }
+        WorkerRequestType::ExtractCosePublicKeys => "EXTRACT_COSE_PUBLIC_KEYS",
+        WorkerRequestType::GetDeviceRecords => "GET_DEVICE_RECORDS",
+        WorkerRequestType::SignIntent => "SIGN_INTENT",
+        WorkerRequestType::CreateAccountCard => "CREATE_ACCOUNT_CARD",
+        WorkerRequestType::VerifyAccountCard => "VERIFY_ACCOUNT_CARD",
+        WorkerRequestType::GetStateVersion => "GET_STATE_VERSION",
+        WorkerRequestType::GetStateChangesSince => "GET_STATE_CHANGES_SINCE",
+        WorkerRequestType::LogoutAll => "LOGOUT_ALL",
+        WorkerRequestType::ClearAllSessions => "CLEAR_ALL_SESSIONS",
+        WorkerRequestType::SignBorshTransaction => "SIGN_BORSH_TRANSACTION",
+        WorkerRequestType::VerifyEncryptedKeyEnvelope => "VERIFY_ENCRYPTED_KEY_ENVELOPE",
+        WorkerRequestType::DebugPanic => "DEBUG_PANIC",
+        WorkerRequestType::ProvisionRelayerRequestAuthSecret => {
+            "PROVISION_RELAYER_REQUEST_AUTH_SECRET"
+        }
     }
 }
 
@@ -103,6 +252,7 @@ pub fn parse_typed_payload<T: DeserializeOwned>(
 }
 
 /// Worker response types enum - corresponds to TypeScript WorkerResponseType
+/// Values are pinned like `WorkerRequestType`'s; add new ones at the end.
 #[wasm_bindgen]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u32)]
@@ -142,7 +292,119 @@ pub enum WorkerResponseType {
     // Internal post-registration activation helper
     SignAddKeyThresholdPublicKeyNoPromptSuccess = 24,
     SignAddKeyThresholdPublicKeyNoPromptFailure = 25,
+
+    // Legacy key migration
+    MigrateLegacyEncryptedKeySuccess = 26,
+    MigrateLegacyEncryptedKeyFailure = 27,
+
+    // Threshold client share warm-up
+    WarmThresholdEd25519ClientShareSuccess = 28,
+    WarmThresholdEd25519ClientShareFailure = 29,
+
+    // Batched COSE public key extraction
+    ExtractCosePublicKeysSuccess = 30,
+    ExtractCosePublicKeysFailure = 31,
+
+    // Device registry lookup
+    GetDeviceRecordsSuccess = 32,
+    GetDeviceRecordsFailure = 33,
+
+    // Batched intent signing
+    SignIntentSuccess = 34,
+    SignIntentFailure = 35,
+
+    // Device-linking account cards
+    CreateAccountCardSuccess = 36,
+    CreateAccountCardFailure = 37,
+    VerifyAccountCardSuccess = 38,
+    VerifyAccountCardFailure = 39,
+
+    // State change polling
+    GetStateVersionSuccess = 40,
+    GetStateVersionFailure = 41,
+    GetStateChangesSinceSuccess = 42,
+    GetStateChangesSinceFailure = 43,
+    LogoutAllSuccess = 44,
+    LogoutAllFailure = 45,
+    ClearAllSessionsSuccess = 46,
+    ClearAllSessionsFailure = 47,
+    SignBorshTransactionSuccess = 48,
+    SignBorshTransactionFailure = 49,
+    VerifyEncryptedKeyEnvelopeSuccess = 50,
+    VerifyEncryptedKeyEnvelopeFailure = 51,
+    DebugPanicSuccess = 52,
+    DebugPanicFailure = 53,
+    ProvisionRelayerRequestAuthSecretSuccess = 54,
+    ProvisionRelayerRequestAuthSecretFailure = 55,
 }
+
+/// Every response type this build emits, as `(wire value, name)`.
+#[cfg_attr(not(test), allow(dead_code))]
+pub const WORKER_RESPONSE_TYPE_REGISTRY: [(u32, &str); 56] = [
+    (0, "DERIVE_NEAR_KEYPAIR_AND_ENCRYPT_SUCCESS"),
+    (1, "RECOVER_KEYPAIR_FROM_PASSKEY_SUCCESS"),
+    (2, "DECRYPT_PRIVATE_KEY_WITH_PRF_SUCCESS"),
+    (3, "SIGN_TRANSACTIONS_WITH_ACTIONS_SUCCESS"),
+    (4, "EXTRACT_COSE_PUBLIC_KEY_SUCCESS"),
+    (5, "SIGN_TRANSACTION_WITH_KEYPAIR_SUCCESS"),
+    (6, "SIGN_NEP413_MESSAGE_SUCCESS"),
+    (7, "REGISTER_DEVICE2_WITH_DERIVED_KEY_SUCCESS"),
+    (8, "SIGN_DELEGATE_ACTION_SUCCESS"),
+    (9, "DERIVE_NEAR_KEYPAIR_AND_ENCRYPT_FAILURE"),
+    (10, "RECOVER_KEYPAIR_FROM_PASSKEY_FAILURE"),
+    (11, "DECRYPT_PRIVATE_KEY_WITH_PRF_FAILURE"),
+    (12, "SIGN_TRANSACTIONS_WITH_ACTIONS_FAILURE"),
+    (13, "EXTRACT_COSE_PUBLIC_KEY_FAILURE"),
+    (14, "SIGN_TRANSACTION_WITH_KEYPAIR_FAILURE"),
+    (15, "SIGN_NEP413_MESSAGE_FAILURE"),
+    (16, "REGISTER_DEVICE2_WITH_DERIVED_KEY_FAILURE"),
+    (17, "SIGN_DELEGATE_ACTION_FAILURE"),
+    (18, "REGISTRATION_PROGRESS"),
+    (19, "REGISTRATION_COMPLETE"),
+    (20, "EXECUTE_ACTIONS_PROGRESS"),
+    (21, "EXECUTE_ACTIONS_COMPLETE"),
+    (
+        22,
+        "DERIVE_THRESHOLD_ED25519_CLIENT_VERIFYING_SHARE_SUCCESS",
+    ),
+    (
+        23,
+        "DERIVE_THRESHOLD_ED25519_CLIENT_VERIFYING_SHARE_FAILURE",
+    ),
+    (24, "SIGN_ADD_KEY_THRESHOLD_PUBLIC_KEY_NO_PROMPT_SUCCESS"),
+    (25, "SIGN_ADD_KEY_THRESHOLD_PUBLIC_KEY_NO_PROMPT_FAILURE"),
+    (26, "MIGRATE_LEGACY_ENCRYPTED_KEY_SUCCESS"),
+    (27, "MIGRATE_LEGACY_ENCRYPTED_KEY_FAILURE"),
+    (28, "WARM_THRESHOLD_ED25519_CLIENT_SHARE_SUCCESS"),
+    (29, "WARM_THRESHOLD_ED25519_CLIENT_SHARE_FAILURE"),
+    (30, "EXTRACT_COSE_PUBLIC_KEYS_SUCCESS"),
+    (31, "EXTRACT_COSE_PUBLIC_KEYS_FAILURE"),
+    (32, "GET_DEVICE_RECORDS_SUCCESS"),
+    (33, "GET_DEVICE_RECORDS_FAILURE"),
+    (34, "SIGN_INTENT_SUCCESS"),
+    (35, "SIGN_INTENT_FAILURE"),
+    (36, "CREATE_ACCOUNT_CARD_SUCCESS"),
+    (37, "CREATE_ACCOUNT_CARD_FAILURE"),
+    (38, "VERIFY_ACCOUNT_CARD_SUCCESS"),
+    (39, "VERIFY_ACCOUNT_CARD_FAILURE"),
+    (40, "GET_STATE_VERSION_SUCCESS"),
+    (41, "GET_STATE_VERSION_FAILURE"),
+    (42, "GET_STATE_CHANGES_SINCE_SUCCESS"),
+    (43, "GET_STATE_CHANGES_SINCE_FAILURE"),
+    (44, "LOGOUT_ALL_SUCCESS"),
+    (45, "LOGOUT_ALL_FAILURE"),
+    (46, "CLEAR_ALL_SESSIONS_SUCCESS"),
+    (47, "CLEAR_ALL_SESSIONS_FAILURE"),
+    (48, "SIGN_BORSH_TRANSACTION_SUCCESS"),
+    (49, "SIGN_BORSH_TRANSACTION_FAILURE"),
+    (50, "VERIFY_ENCRYPTED_KEY_ENVELOPE_SUCCESS"),
+    (51, "VERIFY_ENCRYPTED_KEY_ENVELOPE_FAILURE"),
+    (52, "DEBUG_PANIC_SUCCESS"),
+    (53, "DEBUG_PANIC_FAILURE"),
+    (54, "PROVISION_RELAYER_REQUEST_AUTH_SECRET_SUCCESS"),
+    (55, "PROVISION_RELAYER_REQUEST_AUTH_SECRET_FAILURE"),
+];
+
 impl From<WorkerResponseType> for u32 {
     fn from(value: WorkerResponseType) -> Self {
         value as u32
@@ -182,6 +444,36 @@ impl From<u32> for WorkerResponseType {
             23 => WorkerResponseType::DeriveThresholdEd25519ClientVerifyingShareFailure,
             24 => WorkerResponseType::SignAddKeyThresholdPublicKeyNoPromptSuccess,
             25 => WorkerResponseType::SignAddKeyThresholdPublicKeyNoPromptFailure,
+            26 => WorkerResponseType::MigrateLegacyEncryptedKeySuccess,
+            27 => WorkerResponseType::MigrateLegacyEncryptedKeyFailure,
+            28 => WorkerResponseType::WarmThresholdEd25519ClientShareSuccess,
+            29 => WorkerResponseType::WarmThresholdEd25519ClientShareFailure,
+            30 => WorkerResponseType::ExtractCosePublicKeysSuccess,
+            31 => WorkerResponseType::ExtractCosePublicKeysFailure,
+            32 => WorkerResponseType::GetDeviceRecordsSuccess,
+            33 => WorkerResponseType::GetDeviceRecordsFailure,
+            34 => WorkerResponseType::SignIntentSuccess,
+            35 => WorkerResponseType::SignIntentFailure,
+            36 => WorkerResponseType::CreateAccountCardSuccess,
+            37 => WorkerResponseType::CreateAccountCardFailure,
+            38 => WorkerResponseType::VerifyAccountCardSuccess,
+            39 => WorkerResponseType::VerifyAccountCardFailure,
+            40 => WorkerResponseType::GetStateVersionSuccess,
+            41 => WorkerResponseType::GetStateVersionFailure,
+            42 => WorkerResponseType::GetStateChangesSinceSuccess,
+            43 => WorkerResponseType::GetStateChangesSinceFailure,
+            44 => WorkerResponseType::LogoutAllSuccess,
+            45 => WorkerResponseType::LogoutAllFailure,
+            46 => WorkerResponseType::ClearAllSessionsSuccess,
+            47 => WorkerResponseType::ClearAllSessionsFailure,
+            48 => WorkerResponseType::SignBorshTransactionSuccess,
+            49 => WorkerResponseType::SignBorshTransactionFailure,
+            50 => WorkerResponseType::VerifyEncryptedKeyEnvelopeSuccess,
+            51 => WorkerResponseType::VerifyEncryptedKeyEnvelopeFailure,
+            52 => WorkerResponseType::DebugPanicSuccess,
+            53 => WorkerResponseType::DebugPanicFailure,
+            54 => WorkerResponseType::ProvisionRelayerRequestAuthSecretSuccess,
+            55 => WorkerResponseType::ProvisionRelayerRequestAuthSecretFailure,
             _ => panic!("Invalid WorkerResponseType value: {}", value),
         }
     }
@@ -254,6 +546,52 @@ pub fn worker_response_type_name(response_type: WorkerResponseType) -> &'static
         WorkerResponseType::SignAddKeyThresholdPublicKeyNoPromptFailure => {
             "SIGN_ADD_KEY_THRESHOLD_PUBLIC_KEY_NO_PROMPT_FAILURE"
         }
+        WorkerResponseType::MigrateLegacyEncryptedKeySuccess => {
+            "MIGRATE_LEGACY_ENCRYPTED_KEY_SUCCESS"
+        }
+        WorkerResponseType::MigrateLegacyEncryptedKeyFailure => {
+            "MIGRATE_LEGACY_ENCRYPTED_KEY_FAILURE"
+        }
+        WorkerResponseType::WarmThresholdEd25519ClientShareSuccess => {
+            "WARM_THRESHOLD_ED25519_CLIENT_SHARE_SUCCESS"
+        }
+        WorkerResponseType::WarmThresholdEd25519ClientShareFailure => {
+            "WARM_THRESHOLD_ED25519_CLIENT_SHARE_FAILURE"
+        }
+        WorkerResponseType::ExtractCosePublicKeysSuccess => "EXTRACT_COSE_PUBLIC_KEYS_SUCCESS",
+        WorkerResponseType::ExtractCosePublicKeysFailure => "EXTRACT_COSE_PUBLIC_KEYS_FAILURE",
+        WorkerResponseType::GetDeviceRecordsSuccess => "GET_DEVICE_RECORDS_SUCCESS",
+        WorkerResponseType::GetDeviceRecordsFailure => "GET_DEVICE_RECORDS_FAILURE",
+        WorkerResponseType::SignIntentSuccess => "SIGN_INTENT_SUCCESS",
+        WorkerResponseType::SignIntentFailure => "SIGN_INTENT_FAILURE",
+        WorkerResponseType::CreateAccountCardSuccess => "CREATE_ACCOUNT_CARD_SUCCESS",
+        WorkerResponseType::CreateAccountCardFailure => "CREATE_ACCOUNT_CARD_FAILURE",
+        WorkerResponseType::VerifyAccountCardSuccess => "VERIFY_ACCOUNT_CARD_SUCCESS",
+        WorkerResponseType::VerifyAccountCardFailure => "VERIFY_ACCOUNT_CARD_FAILURE",
+        WorkerResponseType::GetStateVersionSuccess => "GET_STATE_VERSION_SUCCESS",
+        WorkerResponseType::GetStateVersionFailure => "GET_STATE_VERSION_FAILURE",
+        WorkerResponseType::GetStateChangesSinceSuccess => "GET_STATE_CHANGES_SINCE_SUCCESS",
+        WorkerResponseType::GetStateChangesSinceFailure => "GET_STATE_CHANGES_SINCE_FAILURE",
+        WorkerResponseType::LogoutAllSuccess => "LOGOUT_ALL_SUCCESS",
+        WorkerResponseType::LogoutAllFailure => "LOGOUT_ALL_FAILURE",
+        WorkerResponseType::ClearAllSessionsSuccess => "CLEAR_ALL_SESSIONS_SUCCESS",
+        WorkerResponseType::ClearAllSessionsFailure => "CLEAR_ALL_SESSIONS_FAILURE",
+        WorkerResponseType::SignBorshTransactionSuccess => "SIGN_BORSH_TRANSACTION_SUCCESS",
+        WorkerResponseType::SignBorshTransactionFailure => "SIGN_BORSH_TRANSACTION_FAILURE",
+        WorkerResponseType::VerifyEncryptedKeyEnvelopeSuccess => {
+            "VERIFY_ENCRYPTED_KEY_ENVELOPE_SUCCESS"
+        }
+        WorkerResponseType::VerifyEncryptedKeyEnvelopeFailure => {
+            "VERIFY_ENCRYPTED_KEY_ENVELOPE_FAILURE"
+        }
+        WorkerResponseType::DebugPanicSuccess => "DEBUG_PANIC_SUCCESS",
+        WorkerResponseType::DebugPanicFailure => "DEBUG_PANIC_FAILURE",
+        WorkerResponseType::ProvisionRelayerRequestAuthSecretSuccess => {
+            "PROVISION_RELAYER_REQUEST_AUTH_SECRET_SUCCESS"
+        }
+        WorkerResponseType::ProvisionRelayerRequestAuthSecretFailure => {
+            "PROVISION_RELAYER_REQUEST_AUTH_SECRET_FAILURE"
+        }
     }
 }
 
@@ -271,7 +609,92 @@ pub fn worker_response_type_name(response_type: WorkerResponseType) -> &'static
 pub struct SignerWorkerMessage {
     pub request_type: WorkerRequestType,
     pub request_type_raw: u32,
+    /// Origin of the embedding page, set by `SignerWorkerManager` on the main thread (the worker
+    /// cannot observe it). Only consulted once `initialize_worker_security` has pinned an origin;
+    /// a missing value then fails the gate.
+    pub caller_origin: Option<String>,
+    /// `onDuplicate`: what an identical request does while this one is in flight.
+    pub on_duplicate: DuplicatePolicy,
     pub payload: JsValue,
+    /// `binaryAttachments`: large byte payloads sent beside `payload` as transferred
+    /// `Uint8Array`s instead of JSON number arrays.
+    pub binary_attachments: BinaryAttachments,
+    /// `deadlineMsEpoch`: the request is abandoned once the worker clock reaches it.
+    pub deadline: RequestDeadline,
+}
+
+/// Byte buffers from the envelope's `binaryAttachments`, referenced by index from the payload
+/// (e.g. `{"codeAttachment": 0}` on a DeployContract). Each attachment is taken exactly once,
+/// so its bytes are moved to where they are used rather than copied.
+#[derive(Default)]
+pub struct BinaryAttachments(Vec<Option<Vec<u8>>>);
+
+impl BinaryAttachments {
+    pub fn new(attachments: Vec<Vec<u8>>) -> Self {
+        BinaryAttachments(attachments.into_iter().map(Some).collect())
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Move attachment `index` out; a second take of the same index fails.
+    pub fn take(&mut self, index: u32) -> Result<Vec<u8>, BinaryAttachmentError> {
+        let count = self.0.len();
+        let slot = self
+            .0
+            .get_mut(index as usize)
+            .ok_or(BinaryAttachmentError::OutOfRange { index, count })?;
+        slot.take()
+            .ok_or(BinaryAttachmentError::AlreadyUsed { index })
+    }
+
+    /// Fail when an attachment was sent but never taken.
+    pub fn ensure_all_taken(&self) -> Result<(), BinaryAttachmentError> {
+        let indices: Vec<u32> = (0..self.0.len() as u32)
+            .filter(|index| self.0[*index as usize].is_some())
+            .collect();
+        if indices.is_empty() {
+            return Ok(());
+        }
+        Err(BinaryAttachmentError::Unreferenced { indices })
+    }
+}
+
+impl std::fmt::Debug for BinaryAttachments {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lens: Vec<Option<usize>> = self.0.iter().map(|a| a.as_ref().map(Vec::len)).collect();
+        f.debug_tuple("BinaryAttachments").field(&lens).finish()
+    }
+}
+
+/// Read `message.binaryAttachments` (an array of `Uint8Array`); absent means none.
+fn read_binary_attachments(message_obj: &JsValue) -> Result<BinaryAttachments, JsValue> {
+    let value = js_sys::Reflect::get(message_obj, &JsValue::from_str("binaryAttachments"))
+        .unwrap_or(JsValue::UNDEFINED);
+    if value.is_undefined() || value.is_null() {
+        return Ok(BinaryAttachments::default());
+    }
+    if !js_sys::Array::is_array(&value) {
+        return Err(JsValue::from_str(
+            "message.binaryAttachments must be an array of Uint8Array",
+        ));
+    }
+    let mut attachments = Vec::new();
+    for (index, item) in js_sys::Array::from(&value).iter().enumerate() {
+        let bytes = item.dyn_into::<js_sys::Uint8Array>().map_err(|_| {
+            JsValue::from_str(&format!(
+                "message.binaryAttachments[{}] must be a Uint8Array",
+                index
+            ))
+        })?;
+        attachments.push(bytes.to_vec());
+    }
+    Ok(BinaryAttachments::new(attachments))
 }
 
 pub fn parse_worker_request_envelope(message_val: JsValue) -> Result<SignerWorkerMessage, JsValue> {
@@ -293,15 +716,33 @@ pub fn parse_worker_request_envelope(message_val: JsValue) -> Result<SignerWorke
         .as_f64()
         .ok_or_else(|| JsValue::from_str("message.type must be a number"))?
         as u32;
-    let request_type = WorkerRequestType::from(msg_type_num);
+    let request_type = WorkerRequestType::from_wire(msg_type_num)?;
+
+    let caller_origin = js_sys::Reflect::get(&message_obj, &JsValue::from_str("callerOrigin"))
+        .ok()
+        .and_then(|v| v.as_string());
+
+    let on_duplicate = js_sys::Reflect::get(&message_obj, &JsValue::from_str("onDuplicate"))
+        .ok()
+        .and_then(|v| v.as_string());
+    let on_duplicate = DuplicatePolicy::from_envelope(on_duplicate.as_deref());
 
     let payload_js = js_sys::Reflect::get(&message_obj, &JsValue::from_str("payload"))
         .map_err(|e| JsValue::from_str(&format!("Failed to read message.payload: {:?}", e)))?;
+    let binary_attachments = read_binary_attachments(&message_obj)?;
+    let deadline_ms_epoch =
+        js_sys::Reflect::get(&message_obj, &JsValue::from_str("deadlineMsEpoch"))
+            .ok()
+            .and_then(|v| v.as_f64());
 
     Ok(SignerWorkerMessage {
         request_type,
         request_type_raw: msg_type_num,
+        caller_origin,
+        on_duplicate,
         payload: payload_js,
+        binary_attachments,
+        deadline: RequestDeadline::new(deadline_ms_epoch),
     })
 }
 
@@ -0,0 +1,226 @@
+//! Signed attestations of a successful `verify_authentication_response` call.
+//!
+//! The VRF worker verifies the WebAuthn credential against the contract before releasing
+//! WrapKeySeed, and forwards the verification facts to this worker alongside the seed.
+//! When a signing request sets `produceVerificationAttestation`, those facts are signed with
+//! the user's NEAR key so a relayer can check the signature instead of repeating the RPC
+//! call, within a freshness window of its own choosing.
+//!
+//! The signed digest is `sha256(VERIFICATION_ATTESTATION_DISCRIMINANT || borsh(payload))`,
+//! so an attestation signature can never be replayed as a transaction, delegate action or
+//! NEP-413 signature (and vice versa).
+
+use borsh::BorshSerialize;
+use ed25519_dalek::Verifier;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::encoders::{base64_standard_decode, base64_standard_encode};
+use crate::signable_message::{compute_signable_digest, SignableMessageKind};
+
+/// Current attestation format version; bumped whenever the signed payload layout changes.
+pub const VERIFICATION_ATTESTATION_VERSION: u8 = 1;
+
+/// Contract verification facts recorded by the VRF worker for a signing session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractVerificationFacts {
+    /// VRF output (base64url) bound into the verified challenge.
+    pub vrf_output: String,
+    /// WebAuthn credential id (base64url) that was verified.
+    pub credential_id: String,
+    /// Contract that answered `verify_authentication_response`.
+    pub contract_id: String,
+    /// VRF-worker clock when the verification succeeded (ms since epoch).
+    pub verified_at_ms: f64,
+    /// Block height of the RPC view call, when the node reported one.
+    #[serde(default)]
+    pub block_height: Option<u64>,
+}
+
+/// Borsh layout of the signed payload. Field order is part of the format.
+#[derive(BorshSerialize)]
+struct VerificationAttestationPayloadV1<'a> {
+    version: u8,
+    vrf_output: &'a str,
+    credential_id: &'a str,
+    contract_id: &'a str,
+    verified_at_ms: u64,
+    block_height: Option<u64>,
+}
+
+/// Attestation returned to JS and forwarded to the relayer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationAttestation {
+    pub version: u8,
+    pub vrf_output: String,
+    pub credential_id: String,
+    pub contract_id: String,
+    pub verified_at_ms: f64,
+    #[serde(default)]
+    pub block_height: Option<u64>,
+    /// Signer public key (`ed25519:<base58>`)
+    pub public_key: String,
+    /// Standard base64 Ed25519 signature over `verification_attestation_digest`
+    pub signature: String,
+}
+
+fn verified_at_ms_u64(verified_at_ms: f64) -> Result<u64, String> {
+    if !verified_at_ms.is_finite() || verified_at_ms < 0.0 {
+        return Err(format!("Invalid verifiedAtMs: {}", verified_at_ms));
+    }
+    Ok(verified_at_ms.trunc() as u64)
+}
+
+/// `sha256(VERIFICATION_ATTESTATION_DISCRIMINANT || borsh(payload))` for a given version.
+pub fn verification_attestation_digest(
+    version: u8,
+    facts: &ContractVerificationFacts,
+) -> Result<[u8; 32], String> {
+    if version != VERIFICATION_ATTESTATION_VERSION {
+        return Err(format!(
+            "Unsupported verification attestation version: {}",
+            version
+        ));
+    }
+    let payload = VerificationAttestationPayloadV1 {
+        version,
+        vrf_output: &facts.vrf_output,
+        credential_id: &facts.credential_id,
+        contract_id: &facts.contract_id,
+        verified_at_ms: verified_at_ms_u64(facts.verified_at_ms)?,
+        block_height: facts.block_height,
+    };
+    let payload_borsh =
+        borsh::to_vec(&payload).map_err(|e| format!("Borsh serialization failed: {}", e))?;
+    Ok(compute_signable_digest(
+        SignableMessageKind::VerificationAttestation,
+        &payload_borsh,
+    ))
+}
+
+impl VerificationAttestation {
+    /// Assemble an attestation from facts and a signature over their digest.
+    pub fn new(
+        facts: &ContractVerificationFacts,
+        public_key_bytes: &[u8; 32],
+        signature_bytes: &[u8; 64],
+    ) -> Self {
+        Self {
+            version: VERIFICATION_ATTESTATION_VERSION,
+            vrf_output: facts.vrf_output.clone(),
+            credential_id: facts.credential_id.clone(),
+            contract_id: facts.contract_id.clone(),
+            // Truncated to whole milliseconds, exactly as signed.
+            verified_at_ms: facts.verified_at_ms.trunc(),
+            block_height: facts.block_height,
+            public_key: format!("ed25519:{}", bs58::encode(public_key_bytes).into_string()),
+            signature: base64_standard_encode(signature_bytes),
+        }
+    }
+
+    pub fn facts(&self) -> ContractVerificationFacts {
+        ContractVerificationFacts {
+            vrf_output: self.vrf_output.clone(),
+            credential_id: self.credential_id.clone(),
+            contract_id: self.contract_id.clone(),
+            verified_at_ms: self.verified_at_ms,
+            block_height: self.block_height,
+        }
+    }
+
+    /// Milliseconds between verification and `now_ms` (negative if `verifiedAtMs` is ahead).
+    pub fn age_ms(&self, now_ms: f64) -> f64 {
+        now_ms - self.verified_at_ms
+    }
+}
+
+/// Check the signature, and when `max_age_ms` is set, that the verification happened within
+/// that window of `now_ms` (in either direction, so a future `verifiedAtMs` does not stay
+/// fresh indefinitely).
+pub fn verify_verification_attestation_inner(
+    attestation: &VerificationAttestation,
+    now_ms: f64,
+    max_age_ms: Option<f64>,
+) -> Result<(), String> {
+    let digest = verification_attestation_digest(attestation.version, &attestation.facts())?;
+
+    let public_key_b58 = attestation
+        .public_key
+        .strip_prefix("ed25519:")
+        .ok_or_else(|| "publicKey must be an ed25519:<base58> key".to_string())?;
+    let public_key_bytes: [u8; 32] = bs58::decode(public_key_b58)
+        .into_vec()
+        .map_err(|e| format!("Invalid publicKey base58: {}", e))?
+        .try_into()
+        .map_err(|_| "publicKey must be 32 bytes".to_string())?;
+    let verifying_key = ed25519_dalek::VerifyingKey::from_bytes(&public_key_bytes)
+        .map_err(|e| format!("Invalid publicKey: {}", e))?;
+
+    let signature_bytes: [u8; 64] = base64_standard_decode(&attestation.signature)?
+        .try_into()
+        .map_err(|_| "signature must be 64 bytes".to_string())?;
+    verifying_key
+        .verify(
+            &digest,
+            &ed25519_dalek::Signature::from_bytes(&signature_bytes),
+        )
+        .map_err(|_| "signature does not verify under publicKey".to_string())?;
+
+    if let Some(max_age_ms) = max_age_ms {
+        let age_ms = attestation.age_ms(now_ms);
+        // Written so that NaN ages/windows count as stale.
+        let fresh = age_ms.abs() <= max_age_ms;
+        if !fresh {
+            return Err(format!(
+                "attestation is stale: verified {}ms from now, allowed {}ms",
+                age_ms, max_age_ms
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Standalone verifier for `VerificationAttestation`s returned when
+/// `produceVerificationAttestation: true` is requested.
+///
+/// Resolves to `{ valid: true, publicKey, contractId, verifiedAtMs, ageMs }`, or rejects with
+/// the check that failed. `maxAgeMs` is the caller's freshness window; omit it to check the
+/// signature only. Callers must still confirm `publicKey` is an access key of the account.
+#[wasm_bindgen]
+pub fn verify_verification_attestation(
+    attestation: JsValue,
+    max_age_ms: Option<f64>,
+) -> Result<JsValue, JsValue> {
+    let attestation: VerificationAttestation = serde_wasm_bindgen::from_value(attestation)
+        .map_err(|e| JsValue::from_str(&format!("Invalid verification attestation: {e}")))?;
+
+    #[cfg(target_arch = "wasm32")]
+    let now_ms = js_sys::Date::now();
+    #[cfg(not(target_arch = "wasm32"))]
+    let now_ms = 0.0;
+
+    verify_verification_attestation_inner(&attestation, now_ms, max_age_ms)
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Out<'a> {
+        valid: bool,
+        public_key: &'a str,
+        contract_id: &'a str,
+        verified_at_ms: f64,
+        age_ms: f64,
+    }
+
+    serde_wasm_bindgen::to_value(&Out {
+        valid: true,
+        public_key: &attestation.public_key,
+        contract_id: &attestation.contract_id,
+        verified_at_ms: attestation.verified_at_ms,
+        age_ms: attestation.age_ms(now_ms),
+    })
+    .map_err(|e| JsValue::from_str(&format!("Failed to serialize verification result: {e}")))
+}
@@ -0,0 +1,258 @@
+//! Optional origin/capability gate for `handle_signer_message`.
+//!
+//! In the wallet-iframe architecture only the wallet origin can reach the worker, so the
+//! gate is off by default. Integrators that embed the worker directly in a dApp page call
+//! `initialize_worker_security` once at startup; from then on every envelope must carry a
+//! `callerOrigin` matching the pinned origin and a request type from the allowlist.
+//! `callerOrigin` is written by the main-thread `SignerWorkerManager`; an envelope without one
+//! (or with the opaque `"null"` origin) is rejected once a policy is installed.
+//! The policy cannot be replaced or loosened for the rest of the worker's lifetime.
+
+use std::cell::RefCell;
+
+use crate::error::WorkerSecurityError;
+use crate::types::worker_messages::{worker_request_type_name, WorkerRequestType};
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+thread_local! {
+    static WORKER_SECURITY_POLICY: RefCell<Option<WorkerSecurityPolicy>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerSecurityPolicy {
+    pub pinned_origin: String,
+    /// `None` pins the origin only; `Some` additionally restricts request types.
+    pub allowed_request_types: Option<Vec<WorkerRequestType>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct WorkerSecurityConfig {
+    /// Defaults to `self.location.origin`.
+    #[serde(default)]
+    expected_origin: Option<String>,
+    /// Numeric `WorkerRequestType` values permitted from the pinned origin.
+    #[serde(default)]
+    allowed_request_types: Option<Vec<u32>>,
+}
+
+pub(crate) fn normalize_origin(origin: &str) -> String {
+    origin.trim().trim_end_matches('/').to_ascii_lowercase()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn own_location_origin() -> Option<String> {
+    let location = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("location")).ok()?;
+    js_sys::Reflect::get(&location, &JsValue::from_str("origin"))
+        .ok()?
+        .as_string()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn own_location_origin() -> Option<String> {
+    None
+}
+
+impl WorkerSecurityPolicy {
+    fn from_config(
+        expected_origin: Option<String>,
+        allowed_request_types: Option<Vec<u32>>,
+    ) -> Result<Self, WorkerSecurityError> {
+        let pinned_origin = expected_origin
+            .or_else(own_location_origin)
+            .map(|o| normalize_origin(&o))
+            .filter(|o| !o.is_empty() && o != "null")
+            .ok_or_else(|| {
+                WorkerSecurityError::InvalidConfig("expectedOrigin is required".to_string())
+            })?;
+        let allowed_request_types = allowed_request_types
+            .map(|types| {
+                types
+                    .into_iter()
+                    .map(|raw| {
+                        WorkerRequestType::try_from_u32(raw).ok_or_else(|| {
+                            WorkerSecurityError::InvalidConfig(format!(
+                                "unknown request type {} in allowedRequestTypes",
+                                raw
+                            ))
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+        Ok(Self {
+            pinned_origin,
+            allowed_request_types,
+        })
+    }
+}
+
+/// Install the policy; fails if one is already installed.
+pub(crate) fn install_worker_security_policy(
+    policy: WorkerSecurityPolicy,
+) -> Result<(), WorkerSecurityError> {
+    WORKER_SECURITY_POLICY.with(|slot| {
+        let mut slot = slot.borrow_mut();
+        if slot.is_some() {
+            return Err(WorkerSecurityError::AlreadyInitialized);
+        }
+        *slot = Some(policy);
+        Ok(())
+    })
+}
+
+/// Gate a request before its payload is parsed. Unrestricted until a policy is installed.
+pub(crate) fn ensure_request_allowed(
+    caller_origin: Option<&str>,
+    request_type: WorkerRequestType,
+) -> Result<(), WorkerSecurityError> {
+    WORKER_SECURITY_POLICY.with(|slot| {
+        let slot = slot.borrow();
+        let Some(policy) = slot.as_ref() else {
+            return Ok(());
+        };
+        let origin_ok = caller_origin
+            .map(normalize_origin)
+            .filter(|origin| !origin.is_empty() && origin != "null")
+            .is_some_and(|origin| origin == policy.pinned_origin);
+        let type_ok = policy
+            .allowed_request_types
+            .as_ref()
+            .is_none_or(|types| types.contains(&request_type));
+        if origin_ok && type_ok {
+            Ok(())
+        } else {
+            Err(WorkerSecurityError::OriginNotAllowed {
+                caller_origin: caller_origin.map(str::to_string),
+                request_type: worker_request_type_name(request_type),
+            })
+        }
+    })
+}
+
+/// Pin the embedder origin and the request types it may send. Callable once per worker.
+///
+/// `config`: `{ expectedOrigin?: string, allowedRequestTypes?: number[] }`. Without
+/// `expectedOrigin` the worker's own `self.location.origin` is pinned; without
+/// `allowedRequestTypes` every request type is allowed from the pinned origin.
+#[wasm_bindgen]
+pub fn initialize_worker_security(config: JsValue) -> Result<(), JsValue> {
+    let config: WorkerSecurityConfig = if config.is_undefined() || config.is_null() {
+        WorkerSecurityConfig::default()
+    } else {
+        serde_wasm_bindgen::from_value(config)
+            .map_err(|e| WorkerSecurityError::InvalidConfig(e.to_string()))?
+    };
+    let policy =
+        WorkerSecurityPolicy::from_config(config.expected_origin, config.allowed_request_types)?;
+    install_worker_security_policy(policy)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The policy is thread-local and each #[test] runs on its own thread, so every test
+    // starts from the uninitialized (unrestricted) state.
+
+    fn pin(origin: &str, types: Option<Vec<u32>>) -> Result<(), WorkerSecurityError> {
+        install_worker_security_policy(WorkerSecurityPolicy::from_config(
+            Some(origin.to_string()),
+            types,
+        )?)
+    }
+
+    #[test]
+    fn uninitialized_gate_allows_everything() {
+        assert_eq!(
+            ensure_request_allowed(None, WorkerRequestType::SignTransactionsWithActions),
+            Ok(())
+        );
+        assert_eq!(
+            ensure_request_allowed(
+                Some("https://evil.example"),
+                WorkerRequestType::DecryptPrivateKeyWithPrf
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn policy_can_only_be_set_once() {
+        pin("https://wallet.example", Some(vec![3])).unwrap();
+        assert_eq!(
+            pin("https://evil.example", None),
+            Err(WorkerSecurityError::AlreadyInitialized)
+        );
+        // The original, stricter policy still applies.
+        assert!(ensure_request_allowed(
+            Some("https://evil.example"),
+            WorkerRequestType::SignTransactionsWithActions
+        )
+        .is_err());
+        assert!(ensure_request_allowed(
+            Some("https://wallet.example"),
+            WorkerRequestType::DecryptPrivateKeyWithPrf
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn enforces_pinned_origin_and_allowlist() {
+        pin(
+            "https://Wallet.example/",
+            Some(vec![WorkerRequestType::SignTransactionsWithActions as u32]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            ensure_request_allowed(
+                Some("https://wallet.example"),
+                WorkerRequestType::SignTransactionsWithActions
+            ),
+            Ok(())
+        );
+
+        let err = ensure_request_allowed(
+            Some("https://wallet.example"),
+            WorkerRequestType::DecryptPrivateKeyWithPrf,
+        )
+        .unwrap_err();
+        assert!(err.to_string().starts_with("OriginNotAllowed:"));
+
+        assert!(matches!(
+            ensure_request_allowed(
+                Some("https://dapp.example"),
+                WorkerRequestType::SignTransactionsWithActions
+            ),
+            Err(WorkerSecurityError::OriginNotAllowed { .. })
+        ));
+        assert!(matches!(
+            ensure_request_allowed(None, WorkerRequestType::SignTransactionsWithActions),
+            Err(WorkerSecurityError::OriginNotAllowed { .. })
+        ));
+    }
+
+    #[test]
+    fn missing_or_opaque_caller_origin_fails_closed() {
+        pin("https://wallet.example", None).unwrap();
+        for caller_origin in [None, Some(""), Some("  "), Some("null")] {
+            assert!(matches!(
+                ensure_request_allowed(caller_origin, WorkerRequestType::SignNep413Message),
+                Err(WorkerSecurityError::OriginNotAllowed { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_request_types_in_config() {
+        assert!(matches!(
+            pin("https://wallet.example", Some(vec![999])),
+            Err(WorkerSecurityError::InvalidConfig(_))
+        ));
+        // A rejected config does not consume the one-shot initialization.
+        pin("https://wallet.example", None).unwrap();
+    }
+}
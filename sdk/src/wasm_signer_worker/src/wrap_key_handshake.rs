@@ -1,8 +1,26 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::future::Future;
 
+#[cfg(target_arch = "wasm32")]
+use crate::config::{
+    ERROR_OPERATION_CANCELLED, WRAP_KEY_SEED_CLOSE_GRACE_MS, WRAP_KEY_SEED_RECHECK_ATTEMPTS,
+    WRAP_KEY_SEED_RECHECK_INTERVAL_MS,
+};
 use crate::crypto::WrapKey;
+use crate::encoders::base64_url_encode;
+use crate::error::SessionBindingError;
+#[cfg(target_arch = "wasm32")]
+use crate::error::WrapKeySeedPortError;
+use crate::panic_guard::try_wipe;
+use crate::session_key_cache::{
+    clear_all_session_keys, clear_session_key, now_ms, session_key_reuse_status, KeyReuseStatus,
+};
+#[cfg(target_arch = "wasm32")]
+use crate::session_key_cache::{set_session_key_reuse_policy, KeyReusePolicy};
+use crate::state_changes::{logout_epoch, record_state_change, state_version, StateChangeKind};
 use crate::types::worker_messages::WorkerRequestType;
+use crate::verification_attestation::ContractVerificationFacts;
 use wasm_bindgen::prelude::*;
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::JsCast;
@@ -10,10 +28,23 @@ use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::JsFuture;
 #[cfg(target_arch = "wasm32")]
 use web_sys::{MessageEvent, MessagePort};
+use zeroize::Zeroize;
 
 thread_local! {
     static WRAP_KEY_SEED_SESSIONS: RefCell<HashMap<String, WrapKey>> = RefCell::new(HashMap::new());
     static SESSION_PRF_OUTPUTS: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+    static SESSION_CONTRACT_VERIFICATIONS: RefCell<HashMap<String, ContractVerificationFacts>> =
+        RefCell::new(HashMap::new());
+    static SESSION_BINDINGS: RefCell<HashMap<String, SessionBinding>> = RefCell::new(HashMap::new());
+}
+
+/// What ties a session's port delivery to the session in this worker.
+#[derive(Default)]
+struct SessionBinding {
+    /// Issued by `get_session_binding_nonce`; the VRF worker echoes it with the WrapKeySeed.
+    nonce: Option<String>,
+    /// Intent digest the VRF worker confirmed, delivered with the WrapKeySeed.
+    intent_digest: Option<String>,
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -21,6 +52,13 @@ thread_local! {
     static WRAP_KEY_SEED_WAITERS: RefCell<HashMap<String, Vec<js_sys::Function>>> = RefCell::new(HashMap::new());
     static PRF_SECOND_WAITERS: RefCell<HashMap<String, Vec<js_sys::Function>>> = RefCell::new(HashMap::new());
     static SESSION_MATERIAL_ERRORS: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+    // Cancel resolvers per session, tagged with the ticket of the wait that registered them.
+    static CANCEL_WAITERS: RefCell<HashMap<String, Vec<(u64, js_sys::Function)>>> = RefCell::new(HashMap::new());
+    static NEXT_CANCEL_TICKET: std::cell::Cell<u64> = const { std::cell::Cell::new(0) };
+    // Cancels that arrived while a request for the session was running but not yet waiting;
+    // consumed by its next wait, or dropped once no request for the session is in flight.
+    static PENDING_CANCELS: RefCell<std::collections::HashSet<String>> =
+        RefCell::new(std::collections::HashSet::new());
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -49,6 +87,62 @@ fn resolve_prf_second_waiters(session_id: &str, value: &JsValue) {
     });
 }
 
+/// Settle every pending WrapKeySeed / PRF.second wait with `error` and drop cancel resolvers.
+/// Returns the number of waits settled.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn reject_all_material_waiters(error: &str) -> usize {
+    let error_js = JsValue::from_str(error);
+    let mut settled = 0;
+    for waiters in [&WRAP_KEY_SEED_WAITERS, &PRF_SECOND_WAITERS] {
+        let drained: Vec<_> = waiters.with(|waiters| waiters.borrow_mut().drain().collect());
+        for (_, list) in drained {
+            for resolve in list {
+                let _ = resolve.call1(&JsValue::UNDEFINED, &error_js);
+                settled += 1;
+            }
+        }
+    }
+    CANCEL_WAITERS.with(|waiters| waiters.borrow_mut().clear());
+    settled
+}
+
+/// Interrupt any in-flight WrapKeySeed / PRF.second wait for `session_id` (`CANCEL_OPERATION`).
+/// Waiters fail with "operation cancelled while awaiting key material" instead of timing out.
+/// A cancel that arrives while a request for the session is running but not waiting yet is held
+/// for that request's next wait; with no request in flight it only clears key material, so it
+/// cannot fail a later request. Any signing key cached for the session under its
+/// `keyReusePolicy` is zeroized.
+#[wasm_bindgen]
+pub fn cancel_operation(session_id: String) {
+    // A cancelled session must not keep serving a decrypted signing key.
+    clear_session_key(&session_id);
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let waiters = CANCEL_WAITERS.with(|waiters| waiters.borrow_mut().remove(&session_id));
+        match waiters {
+            Some(list) if !list.is_empty() => {
+                for (_, resolve) in list {
+                    let _ = resolve.call1(&JsValue::UNDEFINED, &JsValue::NULL);
+                }
+            }
+            _ if crate::inflight_requests::signer_requests_in_flight()
+                .has_session_in_flight(&session_id) =>
+            {
+                PENDING_CANCELS.with(|set| {
+                    set.borrow_mut().insert(session_id);
+                });
+            }
+            _ => {}
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let _ = session_id;
+    }
+}
+
 /// Attach a MessagePort for a signing session and store WrapKeySeed material in Rust.
 /// JS shim should transfer the port; all parsing/caching lives here.
 #[wasm_bindgen]
@@ -62,13 +156,18 @@ pub fn attach_wrap_key_seed_port(session_id: String, port_val: JsValue) {
 
         let sid = session_id.clone();
         let port_for_close = port.clone();
+        // Set once the port delivered anything; a later close is then the normal one-shot close.
+        let message_seen = std::rc::Rc::new(std::cell::Cell::new(false));
+        let message_seen_for_message = message_seen.clone();
         let on_message = move |event: MessageEvent| {
+            message_seen_for_message.set(true);
             let Ok(data) = js_sys::Reflect::get(&event, &JsValue::from_str("data")) else {
                 return;
             };
 
             // New contract: payload is result-like:
-            // - success: { ok: true, wrap_key_seed, wrapKeySalt, prfSecond? }
+            // - success: { ok: true, wrap_key_seed, wrapKeySalt, prfSecond?, contractVerification?,
+            //              keyReusePolicy?, sessionBindingNonce?, intentDigest? }
             // - error:   { ok: false, error }
             let ok = js_sys::Reflect::get(&data, &JsValue::from_str("ok"))
                 .ok()
@@ -99,21 +198,85 @@ pub fn attach_wrap_key_seed_port(session_id: String, port_val: JsValue) {
             let prf_second = js_sys::Reflect::get(&data, &JsValue::from_str("prfSecond"))
                 .ok()
                 .and_then(|v| v.as_string());
+            let contract_verification =
+                js_sys::Reflect::get(&data, &JsValue::from_str("contractVerification"))
+                    .ok()
+                    .filter(|v| !v.is_undefined() && !v.is_null())
+                    .and_then(|v| {
+                        serde_wasm_bindgen::from_value::<ContractVerificationFacts>(v).ok()
+                    });
+            // Absent or malformed policies mean no key reuse for this session.
+            let key_reuse_policy =
+                js_sys::Reflect::get(&data, &JsValue::from_str("keyReusePolicy"))
+                    .ok()
+                    .filter(|v| !v.is_undefined() && !v.is_null())
+                    .and_then(|v| serde_wasm_bindgen::from_value::<KeyReusePolicy>(v).ok())
+                    .unwrap_or_default();
+
+            let session_binding_nonce =
+                js_sys::Reflect::get(&data, &JsValue::from_str("sessionBindingNonce"))
+                    .ok()
+                    .and_then(|v| v.as_string());
+            let intent_digest = js_sys::Reflect::get(&data, &JsValue::from_str("intentDigest"))
+                .ok()
+                .and_then(|v| v.as_string());
+
+            // Not an error envelope and missing seed material: fail waiters fast instead of
+            // letting them run into the timeout. Material minted for another session (a
+            // misdirected port) is refused the same way.
+            let missing_field = match (&wrap_key_seed, &wrap_key_salt) {
+                (None, _) => Some("wrap_key_seed"),
+                (Some(_), None) => Some("wrapKeySalt"),
+                (Some(_), Some(_)) => None,
+            };
+            let rejection = if ok.is_none() && wrap_key_seed.is_none() {
+                Some(
+                    WrapKeySeedPortError::MissingOkAndWrapKeySeed {
+                        session_id: sid.clone(),
+                    }
+                    .to_string(),
+                )
+            } else if let Some(field) = missing_field {
+                Some(
+                    WrapKeySeedPortError::MissingWrapKeyMaterial {
+                        session_id: sid.clone(),
+                        field,
+                    }
+                    .to_string(),
+                )
+            } else {
+                verify_session_binding(
+                    &sid,
+                    session_binding_nonce.as_deref(),
+                    intent_digest.as_deref(),
+                )
+                .err()
+                .map(|err| err.to_string())
+            };
+            if let Some(err) = rejection {
+                SESSION_MATERIAL_ERRORS.with(|map| {
+                    map.borrow_mut().insert(sid.clone(), err.clone());
+                });
+
+                let err_js = JsValue::from_str(&err);
+                resolve_wrap_key_seed_waiters(&sid, &err_js);
+                resolve_prf_second_waiters(&sid, &err_js);
+                port_for_close.close();
+                return;
+            }
 
             if let (Some(seed), Some(salt)) = (wrap_key_seed, wrap_key_salt) {
                 SESSION_MATERIAL_ERRORS.with(|map| {
                     map.borrow_mut().remove(&sid);
                 });
 
-                WRAP_KEY_SEED_SESSIONS.with(|map| {
-                    map.borrow_mut().insert(
-                        sid.clone(),
-                        WrapKey {
-                            wrap_key_seed: seed,
-                            wrap_key_salt: salt,
-                        },
-                    );
-                });
+                cache_wrap_key_seed(
+                    &sid,
+                    WrapKey {
+                        wrap_key_seed: seed,
+                        wrap_key_salt: salt,
+                    },
+                );
                 resolve_wrap_key_seed_waiters(&sid, &JsValue::TRUE);
 
                 // Store PRF.second if present (used in Device2 registration flow)
@@ -125,6 +288,21 @@ pub fn attach_wrap_key_seed_port(session_id: String, port_val: JsValue) {
                         resolve_prf_second_waiters(&sid, &JsValue::TRUE);
                     }
                 }
+
+                set_session_key_reuse_policy(&sid, key_reuse_policy);
+
+                // Facts from the VRF worker's contract verification (used for attestations).
+                SESSION_CONTRACT_VERIFICATIONS.with(|map| {
+                    let mut map = map.borrow_mut();
+                    match contract_verification {
+                        Some(facts) => {
+                            map.insert(sid.clone(), facts);
+                        }
+                        None => {
+                            map.remove(&sid);
+                        }
+                    }
+                });
             }
             // One-shot semantics: VRF sends one message and closes its end; close ours too.
             port_for_close.close();
@@ -132,9 +310,50 @@ pub fn attach_wrap_key_seed_port(session_id: String, port_val: JsValue) {
 
         let closure = Closure::<dyn FnMut(MessageEvent)>::wrap(Box::new(on_message));
         port.set_onmessage(Some(closure.as_ref().unchecked_ref()));
+
+        // A payload that fails to deserialize never reaches onmessage; fail waiters now
+        // rather than letting them run into the generic timeout.
+        let sid_for_error = session_id.clone();
+        let seen_for_error = message_seen.clone();
+        let port_for_error = port.clone();
+        let on_message_error = move |_event: MessageEvent| {
+            if !seen_for_error.replace(true) {
+                fail_waiters_channel_closed(&sid_for_error);
+            }
+            port_for_error.close();
+        };
+        let error_closure = Closure::<dyn FnMut(MessageEvent)>::wrap(Box::new(on_message_error));
+        port.set_onmessageerror(Some(error_closure.as_ref().unchecked_ref()));
+
+        // `close` fires when the VRF end is closed or collected (where the browser supports
+        // it). Wait a short grace period so a message posted right before the close still
+        // lands first.
+        let sid_for_close = session_id;
+        let on_close = move || {
+            if message_seen.get() {
+                return;
+            }
+            let sid = sid_for_close.clone();
+            let seen = message_seen.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let _ = JsFuture::from(timeout_promise(WRAP_KEY_SEED_CLOSE_GRACE_MS)).await;
+                if !seen.get() {
+                    fail_waiters_channel_closed(&sid);
+                }
+            });
+        };
+        let close_closure = Closure::<dyn FnMut()>::wrap(Box::new(on_close));
+        let _ = js_sys::Reflect::set(
+            port,
+            &JsValue::from_str("onclose"),
+            close_closure.as_ref().unchecked_ref(),
+        );
+
         port.start();
-        // Keep the closure alive for the lifetime of the port
+        // Keep the closures alive for the lifetime of the port
         closure.forget();
+        error_closure.forget();
+        close_closure.forget();
     }
 
     #[cfg(not(target_arch = "wasm32"))]
@@ -144,12 +363,324 @@ pub fn attach_wrap_key_seed_port(session_id: String, port_val: JsValue) {
     }
 }
 
+/// Latch `ChannelClosed` for a session whose port went away without delivering anything, and
+/// fail its waiters with it. Material or an error that already arrived is left alone.
+#[cfg(target_arch = "wasm32")]
+fn fail_waiters_channel_closed(session_id: &str) {
+    if cached_wrap_key(session_id).is_some() {
+        return;
+    }
+    let err = WrapKeySeedPortError::ChannelClosed {
+        session_id: session_id.to_string(),
+    }
+    .to_string();
+    let latched = SESSION_MATERIAL_ERRORS.with(|map| {
+        let mut map = map.borrow_mut();
+        if map.contains_key(session_id) {
+            return false;
+        }
+        map.insert(session_id.to_string(), err.clone());
+        true
+    });
+    if latched {
+        let err_js = JsValue::from_str(&err);
+        resolve_wrap_key_seed_waiters(session_id, &err_js);
+        resolve_prf_second_waiters(session_id, &err_js);
+    }
+}
+
+/// Binding nonce for `session_id`, issued on first use. The JS shell hands it to the VRF worker,
+/// which echoes it in the port payload; material arriving with any other nonce is refused.
+pub(crate) fn session_binding_nonce(session_id: &str) -> Result<String, String> {
+    if let Some(nonce) = SESSION_BINDINGS.with(|map| {
+        map.borrow()
+            .get(session_id)
+            .and_then(|binding| binding.nonce.clone())
+    }) {
+        return Ok(nonce);
+    }
+    crate::randomness::ensure_randomness_ok()?;
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| format!("Failed to generate session binding nonce: {}", e))?;
+    let nonce = base64_url_encode(&bytes);
+    SESSION_BINDINGS.with(|map| {
+        map.borrow_mut()
+            .entry(session_id.to_string())
+            .or_default()
+            .nonce = Some(nonce.clone());
+    });
+    Ok(nonce)
+}
+
+/// Issue (or return) the `sessionBindingNonce` the VRF worker must include when it delivers
+/// WrapKeySeed for `session_id`.
+#[wasm_bindgen]
+pub fn get_session_binding_nonce(session_id: String) -> Result<String, JsValue> {
+    session_binding_nonce(&session_id).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Check a port delivery against the session's binding and remember its intent digest.
+///
+/// Payloads without a nonce are accepted only for sessions this worker never issued one for
+/// (VRF workers that predate binding).
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub(crate) fn verify_session_binding(
+    session_id: &str,
+    delivered_nonce: Option<&str>,
+    delivered_intent_digest: Option<&str>,
+) -> Result<(), SessionBindingError> {
+    SESSION_BINDINGS.with(|map| {
+        let mut map = map.borrow_mut();
+        let issued = map
+            .get(session_id)
+            .and_then(|binding| binding.nonce.as_deref());
+        let session_id_owned = || session_id.to_string();
+        match (issued, delivered_nonce) {
+            (Some(issued), Some(delivered)) if issued == delivered => {}
+            (Some(_), Some(_)) => {
+                return Err(SessionBindingError::NonceMismatch {
+                    session_id: session_id_owned(),
+                })
+            }
+            (Some(_), None) => {
+                return Err(SessionBindingError::MissingNonce {
+                    session_id: session_id_owned(),
+                })
+            }
+            (None, Some(_)) => {
+                return Err(SessionBindingError::UnexpectedNonce {
+                    session_id: session_id_owned(),
+                })
+            }
+            (None, None) => {}
+        }
+        if let Some(digest) = delivered_intent_digest.filter(|d| !d.is_empty()) {
+            map.entry(session_id.to_string()).or_default().intent_digest = Some(digest.to_string());
+        }
+        Ok(())
+    })
+}
+
+/// Reject a signing request whose intent digest differs from the one the VRF worker delivered
+/// with this session's WrapKeySeed. Sessions delivered without a digest are not checked.
+pub(crate) fn check_session_intent_digest(
+    session_id: &str,
+    intent_digest: &str,
+) -> Result<(), SessionBindingError> {
+    let delivered = SESSION_BINDINGS.with(|map| {
+        map.borrow()
+            .get(session_id)
+            .and_then(|binding| binding.intent_digest.clone())
+    });
+    match delivered {
+        Some(delivered) if delivered != intent_digest => {
+            Err(SessionBindingError::IntentDigestMismatch {
+                session_id: session_id.to_string(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Cache WrapKeySeed material delivered for `session_id` (the port's onmessage; tests use it
+/// to stand in for the VRF worker).
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub(crate) fn cache_wrap_key_seed(session_id: &str, wrap_key: WrapKey) {
+    WRAP_KEY_SEED_SESSIONS.with(|map| {
+        map.borrow_mut().insert(session_id.to_string(), wrap_key);
+    });
+    record_state_change(StateChangeKind::SessionCreated, None);
+}
+
+/// Store PRF.second and contract verification facts the way the port's onmessage does (tests).
+#[cfg(test)]
+pub(crate) fn cache_session_extras(
+    session_id: &str,
+    prf_second_b64u: &str,
+    contract_verification: ContractVerificationFacts,
+) {
+    SESSION_PRF_OUTPUTS.with(|map| {
+        map.borrow_mut()
+            .insert(session_id.to_string(), prf_second_b64u.to_string());
+    });
+    SESSION_CONTRACT_VERIFICATIONS.with(|map| {
+        map.borrow_mut()
+            .insert(session_id.to_string(), contract_verification);
+    });
+}
+
+/// Entries in the WrapKeySeed, PRF.second and contract verification stores (tests).
+#[cfg(test)]
+pub(crate) fn session_store_sizes() -> [usize; 3] {
+    [
+        WRAP_KEY_SEED_SESSIONS.with(|map| map.borrow().len()),
+        SESSION_PRF_OUTPUTS.with(|map| map.borrow().len()),
+        SESSION_CONTRACT_VERIFICATIONS.with(|map| map.borrow().len()),
+    ]
+}
+
+/// Error latched for `session_id` by its port, if any (tests).
+#[cfg(all(test, target_arch = "wasm32"))]
+pub(crate) fn session_material_error(session_id: &str) -> Option<String> {
+    SESSION_MATERIAL_ERRORS.with(|map| map.borrow().get(session_id).cloned())
+}
+
+/// Session ids with WrapKeySeed material currently cached in this worker, sorted.
+pub(crate) fn active_signing_session_ids() -> Vec<String> {
+    let mut ids: Vec<String> =
+        WRAP_KEY_SEED_SESSIONS.with(|map| map.borrow().keys().cloned().collect());
+    ids.sort_unstable();
+    ids
+}
+
+/// Ids of the signing sessions whose WrapKeySeed is cached in this worker (ids only, never
+/// key material). Pair with `clear_signing_session` to find and drop leaked sessions.
+#[wasm_bindgen]
+pub fn active_signing_sessions() -> JsValue {
+    active_signing_session_ids()
+        .into_iter()
+        .map(JsValue::from)
+        .collect::<js_sys::Array>()
+        .into()
+}
+
+/// Drop everything cached for `session_id`: WrapKeySeed, PRF.second, contract verification
+/// facts, the session binding, a latched VRF error, a pending cancel, a reused signing key, and
+/// any warmed threshold client share. Secrets are zeroized before they are freed. Unknown ids are a no-op.
+#[wasm_bindgen]
+pub fn clear_signing_session(session_id: String) {
+    let mut cleared = clear_session_key(&session_id);
+    if let Some(mut wrap_key) =
+        WRAP_KEY_SEED_SESSIONS.with(|map| map.borrow_mut().remove(&session_id))
+    {
+        wrap_key.wrap_key_seed.zeroize();
+        cleared = true;
+    }
+    if let Some(mut prf_second) =
+        SESSION_PRF_OUTPUTS.with(|map| map.borrow_mut().remove(&session_id))
+    {
+        prf_second.zeroize();
+        cleared = true;
+    }
+    SESSION_CONTRACT_VERIFICATIONS.with(|map| {
+        map.borrow_mut().remove(&session_id);
+    });
+    SESSION_BINDINGS.with(|map| {
+        map.borrow_mut().remove(&session_id);
+    });
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        SESSION_MATERIAL_ERRORS.with(|map| {
+            map.borrow_mut().remove(&session_id);
+        });
+        PENDING_CANCELS.with(|set| {
+            set.borrow_mut().remove(&session_id);
+        });
+    }
+
+    if cleared {
+        record_state_change(StateChangeKind::SessionCleared, None);
+    }
+
+    #[cfg(feature = "threshold")]
+    crate::threshold::client_share_cache::clear_threshold_client_share_cache(session_id);
+}
+
+/// `clear_signing_session` for every session this worker holds material for (`LOGOUT_ALL`).
+/// Returns how many sessions had WrapKeySeed or PRF.second cached.
+pub(crate) fn clear_all_signing_sessions() -> usize {
+    let mut session_ids: std::collections::HashSet<String> = WRAP_KEY_SEED_SESSIONS.with(|map| {
+        map.borrow_mut()
+            .drain()
+            .map(|(session_id, mut wrap_key)| {
+                wrap_key.wrap_key_seed.zeroize();
+                session_id
+            })
+            .collect()
+    });
+    SESSION_PRF_OUTPUTS.with(|map| {
+        for (session_id, mut prf_second) in map.borrow_mut().drain() {
+            prf_second.zeroize();
+            session_ids.insert(session_id);
+        }
+    });
+    SESSION_CONTRACT_VERIFICATIONS.with(|map| map.borrow_mut().clear());
+    SESSION_BINDINGS.with(|map| map.borrow_mut().clear());
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        SESSION_MATERIAL_ERRORS.with(|map| map.borrow_mut().clear());
+        PENDING_CANCELS.with(|set| set.borrow_mut().clear());
+    }
+
+    clear_all_session_keys();
+    #[cfg(feature = "threshold")]
+    crate::threshold::client_share_cache::clear_all_threshold_client_shares();
+
+    session_ids.len()
+}
+
+/// Panic-hook variant of `clear_all_signing_sessions`: zeroizes WrapKeySeeds, PRF.second,
+/// cached signing keys and warmed threshold shares without ever panicking. Returns false when
+/// a store was mid-borrow and kept its contents.
+pub(crate) fn try_wipe_signing_secrets() -> bool {
+    let seeds = try_wipe(&WRAP_KEY_SEED_SESSIONS, |map| {
+        for (_, mut wrap_key) in map.drain() {
+            wrap_key.wrap_key_seed.zeroize();
+        }
+    });
+    let prf_outputs = try_wipe(&SESSION_PRF_OUTPUTS, |map| {
+        for (_, mut prf_second) in map.drain() {
+            prf_second.zeroize();
+        }
+    });
+    let session_keys = crate::session_key_cache::try_wipe_session_keys();
+    #[cfg(feature = "threshold")]
+    let client_shares = crate::threshold::client_share_cache::try_wipe_threshold_client_shares();
+    #[cfg(not(feature = "threshold"))]
+    let client_shares = true;
+    seeds && prf_outputs && session_keys && client_shares
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SigningSessionStatus<'a> {
+    session_id: &'a str,
+    has_wrap_key_seed: bool,
+    key_reuse: KeyReuseStatus,
+    state_version: u64,
+    logout_epoch: u64,
+}
+
+/// Status of a signing session in this worker: whether WrapKeySeed is cached and how much of the
+/// session's signing key reuse budget is left (`keyReuse.enabled` is false when reuse is off).
+/// `stateVersion` is the worker's current state version (see `GET_STATE_CHANGES_SINCE`).
+/// `logoutEpoch` is the epoch of the last `CLEAR_ALL_SESSIONS`.
+#[wasm_bindgen]
+pub fn signing_session_status(session_id: String) -> JsValue {
+    let status = SigningSessionStatus {
+        session_id: &session_id,
+        has_wrap_key_seed: WRAP_KEY_SEED_SESSIONS
+            .with(|map| map.borrow().contains_key(&session_id)),
+        key_reuse: session_key_reuse_status(&session_id, now_ms()),
+        state_version: state_version(),
+        logout_epoch: logout_epoch(),
+    };
+    serde_wasm_bindgen::to_value(&status).unwrap_or(JsValue::UNDEFINED)
+}
+
+/// WrapKeySeed material cached for `session_id`, if it has been delivered.
+pub(crate) fn cached_wrap_key(session_id: &str) -> Option<WrapKey> {
+    WRAP_KEY_SEED_SESSIONS.with(|map| map.borrow().get(session_id).cloned())
+}
+
 fn lookup_wrap_key_shards(
     session_id: &str,
     _request_type: WorkerRequestType,
 ) -> Result<WrapKey, JsValue> {
-    let material = WRAP_KEY_SEED_SESSIONS.with(|map| map.borrow().get(session_id).cloned());
-    let Some(mat) = material else {
+    let Some(mat) = cached_wrap_key(session_id) else {
         return Err(JsValue::from_str(&format!(
             "Missing WrapKeySeed for session {}",
             session_id
@@ -159,12 +690,21 @@ fn lookup_wrap_key_shards(
     Ok(mat)
 }
 
+/// Contract verification the VRF worker performed before releasing this session's WrapKeySeed.
+/// `None` when the session was minted without `contractId`/`nearRpcUrl` gating.
+pub(crate) fn session_contract_verification(session_id: &str) -> Option<ContractVerificationFacts> {
+    SESSION_CONTRACT_VERIFICATIONS.with(|map| map.borrow().get(session_id).cloned())
+}
+
+fn cached_prf_second(session_id: &str) -> Option<String> {
+    SESSION_PRF_OUTPUTS.with(|map| map.borrow().get(session_id).cloned())
+}
+
 fn lookup_prf_second(
     session_id: &str,
     _request_type: WorkerRequestType,
 ) -> Result<String, JsValue> {
-    let prf_second = SESSION_PRF_OUTPUTS.with(|map| map.borrow().get(session_id).cloned());
-    let Some(prf) = prf_second else {
+    let Some(prf) = cached_prf_second(session_id) else {
         return Err(JsValue::from_str(&format!(
             "Missing PRF.second for session {}",
             session_id
@@ -175,7 +715,7 @@ fn lookup_prf_second(
 }
 
 #[cfg(target_arch = "wasm32")]
-fn timeout_promise(ms: u32) -> js_sys::Promise {
+pub(crate) fn timeout_promise(ms: u32) -> js_sys::Promise {
     js_sys::Promise::new(&mut |resolve, _reject| {
         let global = js_sys::global();
         let set_timeout = js_sys::Reflect::get(&global, &JsValue::from_str("setTimeout"))
@@ -216,6 +756,141 @@ fn wrap_key_seed_waiter_promise(session_id: &str) -> js_sys::Promise {
     })
 }
 
+/// Resolves with `null` when `cancel_operation` is called for the session. The ticket lets the
+/// wait drop exactly its own resolver afterwards.
+#[cfg(target_arch = "wasm32")]
+fn cancel_waiter_promise(session_id: &str) -> (u64, js_sys::Promise) {
+    let sid = session_id.to_string();
+    let ticket = NEXT_CANCEL_TICKET.with(|next| {
+        let ticket = next.get();
+        next.set(ticket.wrapping_add(1));
+        ticket
+    });
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        CANCEL_WAITERS.with(|waiters| {
+            waiters
+                .borrow_mut()
+                .entry(sid.clone())
+                .or_default()
+                .push((ticket, resolve));
+        });
+    });
+    (ticket, promise)
+}
+
+/// Drop the cancel resolver registered under `ticket`, leaving other waits for the session armed.
+#[cfg(target_arch = "wasm32")]
+fn remove_cancel_waiter(session_id: &str, ticket: u64) {
+    CANCEL_WAITERS.with(|waiters| {
+        let mut waiters = waiters.borrow_mut();
+        if let Some(list) = waiters.get_mut(session_id) {
+            list.retain(|(t, _)| *t != ticket);
+            if list.is_empty() {
+                waiters.remove(session_id);
+            }
+        }
+    });
+}
+
+/// Drop a cancel held for `session_id` once no request for it is in flight any more, so it
+/// cannot fail the session's next request.
+pub(crate) fn discard_pending_cancel_if_idle(session_id: &str) {
+    #[cfg(target_arch = "wasm32")]
+    if !crate::inflight_requests::signer_requests_in_flight().has_session_in_flight(session_id) {
+        PENDING_CANCELS.with(|set| {
+            set.borrow_mut().remove(session_id);
+        });
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = session_id;
+}
+
+#[cfg(target_arch = "wasm32")]
+fn take_pending_cancel(session_id: &str) -> bool {
+    PENDING_CANCELS.with(|set| set.borrow_mut().remove(session_id))
+}
+
+/// Race the material waiter against the timeout and an explicit cancel.
+/// Resolution values: `true` (material), `false` (timeout), `null` (cancel), string (VRF error).
+#[cfg(target_arch = "wasm32")]
+async fn race_material_waiter(
+    session_id: &str,
+    waiter: js_sys::Promise,
+    timeout_ms: u32,
+) -> Result<JsValue, JsValue> {
+    // Without setTimeout the timeout leg resolves immediately and every wait looks timed out.
+    crate::host_environment::require_host_bridge(crate::host_environment::SET_TIMEOUT)?;
+    let race_inputs = js_sys::Array::new();
+    race_inputs.push(&waiter);
+    race_inputs.push(&timeout_promise(timeout_ms));
+    let (cancel_ticket, cancel_waiter) = cancel_waiter_promise(session_id);
+    race_inputs.push(&cancel_waiter);
+    let raced = js_sys::Promise::race(&race_inputs);
+    let result = JsFuture::from(raced).await;
+    // Drop only this wait's cancel resolver: a later cancel must not be swallowed by a finished
+    // wait, and concurrent waits for the session must stay cancellable.
+    remove_cancel_waiter(session_id, cancel_ticket);
+    result
+}
+
+/// Poll `lookup` up to `attempts` times, `interval_ms` apart, after a material wait timed out.
+///
+/// Covers the ordering where the signing request raced ahead of the port's onmessage: material
+/// (or a VRF error) that lands just after the timeout still settles the request instead of
+/// failing it. `lookup` returns `None` while nothing has arrived; `None` overall means timeout.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub(crate) async fn recheck_session_material<T, L, S, F>(
+    attempts: u32,
+    interval_ms: u32,
+    mut lookup: L,
+    mut sleep: S,
+) -> Option<Result<T, String>>
+where
+    L: FnMut() -> Option<Result<T, String>>,
+    S: FnMut(u32) -> F,
+    F: Future<Output = ()>,
+{
+    for _ in 0..attempts {
+        sleep(interval_ms).await;
+        if let Some(outcome) = lookup() {
+            return Some(outcome);
+        }
+    }
+    None
+}
+
+/// One poll of the session maps: a cancel or latched VRF error wins over material.
+#[cfg(target_arch = "wasm32")]
+fn poll_session_material<T>(
+    session_id: &str,
+    lookup: impl Fn(&str) -> Option<T>,
+) -> Option<Result<T, String>> {
+    if take_pending_cancel(session_id) {
+        return Some(Err(ERROR_OPERATION_CANCELLED.to_string()));
+    }
+    if let Some(err) = SESSION_MATERIAL_ERRORS.with(|map| map.borrow().get(session_id).cloned()) {
+        return Some(Err(err));
+    }
+    lookup(session_id).map(Ok)
+}
+
+/// `recheck_session_material` on the real session maps, sleeping with `setTimeout`.
+#[cfg(target_arch = "wasm32")]
+async fn recheck_after_timeout<T>(
+    session_id: &str,
+    lookup: impl Fn(&str) -> Option<T>,
+) -> Option<Result<T, String>> {
+    recheck_session_material(
+        WRAP_KEY_SEED_RECHECK_ATTEMPTS,
+        WRAP_KEY_SEED_RECHECK_INTERVAL_MS,
+        || poll_session_material(session_id, &lookup),
+        |ms| async move {
+            let _ = JsFuture::from(timeout_promise(ms)).await;
+        },
+    )
+    .await
+}
+
 #[cfg(target_arch = "wasm32")]
 fn prf_second_waiter_promise(session_id: &str) -> js_sys::Promise {
     let sid = session_id.to_string();
@@ -241,6 +916,10 @@ async fn await_wrap_key_shards(
     request_type: WorkerRequestType,
     timeout_ms: u32,
 ) -> Result<WrapKey, JsValue> {
+    if take_pending_cancel(session_id) {
+        return Err(JsValue::from_str(ERROR_OPERATION_CANCELLED));
+    }
+
     let error = SESSION_MATERIAL_ERRORS.with(|map| map.borrow().get(session_id).cloned());
     if let Some(err) = error {
         return Err(JsValue::from_str(&err));
@@ -251,18 +930,21 @@ async fn await_wrap_key_shards(
     }
 
     let seed_promise = wrap_key_seed_waiter_promise(session_id);
-    let race_inputs = js_sys::Array::new();
-    race_inputs.push(&seed_promise);
-    race_inputs.push(&timeout_promise(timeout_ms));
-    let raced = js_sys::Promise::race(&race_inputs);
-    let result = JsFuture::from(raced).await?;
+    let result = race_material_waiter(session_id, seed_promise, timeout_ms).await?;
 
+    if result.is_null() {
+        return Err(JsValue::from_str(ERROR_OPERATION_CANCELLED));
+    }
     // Timeout promise resolves with `false`.
     if result.as_bool() == Some(false) {
-        return Err(JsValue::from_str(&format!(
-            "Timed out waiting for WrapKeySeed for session {}",
-            session_id
-        )));
+        let rechecked = recheck_after_timeout(session_id, cached_wrap_key).await;
+        return match rechecked {
+            Some(outcome) => outcome.map_err(|err| JsValue::from_str(&err)),
+            None => Err(JsValue::from_str(&format!(
+                "Timed out waiting for WrapKeySeed for session {}",
+                session_id
+            ))),
+        };
     }
     if let Some(err) = result.as_string() {
         return Err(JsValue::from_str(&err));
@@ -282,6 +964,10 @@ async fn await_prf_second(
     request_type: WorkerRequestType,
     timeout_ms: u32,
 ) -> Result<String, JsValue> {
+    if take_pending_cancel(session_id) {
+        return Err(JsValue::from_str(ERROR_OPERATION_CANCELLED));
+    }
+
     let error = SESSION_MATERIAL_ERRORS.with(|map| map.borrow().get(session_id).cloned());
     if let Some(err) = error {
         return Err(JsValue::from_str(&err));
@@ -292,17 +978,20 @@ async fn await_prf_second(
     }
 
     let prf_promise = prf_second_waiter_promise(session_id);
-    let race_inputs = js_sys::Array::new();
-    race_inputs.push(&prf_promise);
-    race_inputs.push(&timeout_promise(timeout_ms));
-    let raced = js_sys::Promise::race(&race_inputs);
-    let result = JsFuture::from(raced).await?;
+    let result = race_material_waiter(session_id, prf_promise, timeout_ms).await?;
 
+    if result.is_null() {
+        return Err(JsValue::from_str(ERROR_OPERATION_CANCELLED));
+    }
     if result.as_bool() == Some(false) {
-        return Err(JsValue::from_str(&format!(
-            "Timed out waiting for PRF.second for session {}",
-            session_id
-        )));
+        let rechecked = recheck_after_timeout(session_id, cached_prf_second).await;
+        return match rechecked {
+            Some(outcome) => outcome.map_err(|err| JsValue::from_str(&err)),
+            None => Err(JsValue::from_str(&format!(
+                "Timed out waiting for PRF.second for session {}",
+                session_id
+            ))),
+        };
     }
     if let Some(err) = result.as_string() {
         return Err(JsValue::from_str(&err));
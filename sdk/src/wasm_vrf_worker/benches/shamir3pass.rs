@@ -0,0 +1,50 @@
+//! Native Shamir3Pass latency benchmarks (256-bit default prime vs a 2048-bit prime).
+//!
+//! Run with `cargo bench --bench shamir3pass`. The `modpow_baseline` entries measure
+//! the previous `BigUint::modpow` path for comparison with the cached Montgomery context.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use num_bigint::BigUint;
+use wasm_vrf_worker::{Shamir3Pass, DEFAULT_SHAMIR_P_B64U};
+
+const PRIME_2048_B64U: &str = "x45jhDh_sOg6l0pGqW1B_ObSY45I9jnIFcHDXvYcPWHMJH-tiFwqpULNHDKP5NcRWq9aqZ6wtmZsSfxBvSTpp9MEJjHVyJWQSfLCAdSvWgkuRZGo1guSZFq8_h2mjFT8GnMOeIQHU-dmEBn1wtrh5Z3EcP8GHZLaE_lOmKR06dknVRohLSt8pQDNqmz9qtmcz6zkb8h-ixnvDabtrQN7AAtsYYENGCrno00RnivYLhMO4vAgpOQNc38rzK5R693rAnfmNDskRIqsSYOX6ZZf0xrH0LpVLqgX4mRtvhbmrXxvbZvITzEVbcZb5FIpLqC9P8HN_AAHjHv3sGGmgTq8hw";
+
+fn primes() -> [(&'static str, Shamir3Pass); 2] {
+    [
+        ("256", Shamir3Pass::new(DEFAULT_SHAMIR_P_B64U).unwrap()),
+        ("2048", Shamir3Pass::new(PRIME_2048_B64U).unwrap()),
+    ]
+}
+
+fn bench_lock_ops(c: &mut Criterion) {
+    let mut group = c.benchmark_group("shamir3pass_lock");
+    for (bits, shamir) in primes() {
+        let keys = shamir.generate_lock_keys().unwrap();
+        let (_, kek) = shamir.encrypt_with_random_kek_key(b"bench").unwrap();
+
+        group.bench_with_input(BenchmarkId::new("add_lock", bits), &kek, |b, kek| {
+            b.iter(|| shamir.add_lock(black_box(kek), black_box(&keys.e)))
+        });
+        group.bench_with_input(BenchmarkId::new("modpow_baseline", bits), &kek, |b, kek| {
+            b.iter(|| black_box(kek).modpow(black_box(&keys.e), shamir.p()))
+        });
+    }
+    group.finish();
+}
+
+fn bench_modinv(c: &mut Criterion) {
+    let mut group = c.benchmark_group("shamir3pass_modinv");
+    for (bits, shamir) in primes() {
+        let k: BigUint = shamir.random_k().unwrap();
+        group.bench_with_input(BenchmarkId::new("modinv", bits), &k, |b, k| {
+            b.iter(|| shamir.modinv(black_box(k)))
+        });
+        group.bench_function(BenchmarkId::new("generate_lock_keys", bits), |b| {
+            b.iter(|| shamir.generate_lock_keys().unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_lock_ops, bench_modinv);
+criterion_main!(benches);
@@ -0,0 +1,12 @@
+// Build script for the VRF worker.
+//
+// Emits `confirmation_bypass_release_opt_in` when the caller explicitly opts in to
+// building the `test_confirmation_bypass` feature with optimizations enabled.
+// Without this cfg, combining the feature with `--release` is a compile error.
+fn main() {
+    println!("cargo:rustc-check-cfg=cfg(confirmation_bypass_release_opt_in)");
+    println!("cargo:rerun-if-env-changed=TATCHI_ALLOW_RELEASE_CONFIRMATION_BYPASS");
+    if std::env::var("TATCHI_ALLOW_RELEASE_CONFIRMATION_BYPASS").as_deref() == Ok("1") {
+        println!("cargo:rustc-cfg=confirmation_bypass_release_opt_in");
+    }
+}
@@ -1,7 +1,10 @@
 // SecureConfirm response type reused from types module
+use crate::errors::{VrfResult, VrfWorkerError};
 use crate::types::WorkerConfirmationResponse;
 use js_sys::Promise;
-use serde::Serialize;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
 
@@ -14,23 +17,190 @@ extern "C" {
 }
 
 /// Helper: call awaitSecureConfirmationV2 from Rust and deserialize the response.
+/// Shape and invariant failures surface as `CONFIRMATION_MALFORMED` /
+/// `CONFIRMATION_MISSING_CREDENTIAL`; the response's keys (never its values) are logged.
 pub async fn vrf_await_secure_confirmation(
     request: JsValue,
 ) -> Result<WorkerConfirmationResponse, String> {
+    if let Some(decision) = test_auto_confirm(&request) {
+        return Ok(decision);
+    }
+
+    // Without the bridge the import call throws an opaque unresolved-import error.
+    crate::host_environment::require_host_bridge(
+        crate::host_environment::AWAIT_SECURE_CONFIRMATION_V2,
+    )
+    .map_err(|e| e.to_string())?;
+
+    let request_type = js_sys::Reflect::get(&request, &JsValue::from_str("type"))
+        .ok()
+        .and_then(|v| v.as_string())
+        .unwrap_or_default();
+
     let promise = await_secure_confirmation_v2(request);
 
     let js_val = JsFuture::from(promise)
         .await
         .map_err(|e| format!("awaitSecureConfirmationV2 rejected: {:?}", e))?;
 
-    serde_wasm_bindgen::from_value(js_val)
-        .map_err(|e| format!("Failed to deserialize confirmation response: {}", e))
+    let raw: BTreeMap<String, ConfirmationFieldValue> =
+        serde_wasm_bindgen::from_value(js_val.clone()).map_err(|_| {
+            VrfWorkerError::ConfirmationMalformed {
+                field: "response".to_string(),
+            }
+            .to_string()
+        })?;
+    debug!(
+        "[VRF] {} confirmation response keys: {:?}",
+        request_type,
+        raw.keys().collect::<Vec<_>>()
+    );
+    let fields = parse_confirmation_fields(&raw).map_err(|e| e.to_string())?;
+    validate_confirmation_fields(&request_type, &fields).map_err(|e| e.to_string())?;
+
+    // The checks above cover every field the derive can reject.
+    serde_wasm_bindgen::from_value(js_val).map_err(|_| {
+        VrfWorkerError::ConfirmationMalformed {
+            field: "response".to_string(),
+        }
+        .to_string()
+    })
+}
+
+/// Shape of one top-level value in a confirmation response, without copying nested objects.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum ConfirmationFieldValue {
+    /// `null` or `undefined`
+    Absent,
+    Bool(bool),
+    String(String),
+    Other(serde::de::IgnoredAny),
+}
+
+/// Scalar fields of a confirmation response plus whether a credential came back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfirmationFields {
+    pub request_id: String,
+    pub confirmed: bool,
+    pub intent_digest: Option<String>,
+    pub error: Option<String>,
+    pub has_credential: bool,
+}
+
+/// The value stored under `snake` or, failing that, its camelCase spelling `camel`.
+fn confirmation_field<'a>(
+    raw: &'a BTreeMap<String, ConfirmationFieldValue>,
+    snake: &str,
+    camel: &str,
+) -> Option<&'a ConfirmationFieldValue> {
+    raw.get(snake)
+        .or_else(|| raw.get(camel))
+        .filter(|v| !matches!(v, ConfirmationFieldValue::Absent))
+}
+
+fn optional_string_field(
+    raw: &BTreeMap<String, ConfirmationFieldValue>,
+    snake: &str,
+    camel: &str,
+) -> VrfResult<Option<String>> {
+    match confirmation_field(raw, snake, camel) {
+        None => Ok(None),
+        Some(ConfirmationFieldValue::String(s)) => Ok(Some(s.clone())),
+        Some(_) => Err(VrfWorkerError::ConfirmationMalformed {
+            field: snake.to_string(),
+        }),
+    }
+}
+
+/// Read the scalar fields from either casing. `request_id` and `confirmed` are required;
+/// every other field may be absent, `null` or `undefined`.
+pub fn parse_confirmation_fields(
+    raw: &BTreeMap<String, ConfirmationFieldValue>,
+) -> VrfResult<ConfirmationFields> {
+    let malformed = |field: &str| VrfWorkerError::ConfirmationMalformed {
+        field: field.to_string(),
+    };
+    let request_id = optional_string_field(raw, "request_id", "requestId")?
+        .ok_or_else(|| malformed("request_id"))?;
+    let confirmed = match confirmation_field(raw, "confirmed", "confirmed") {
+        Some(ConfirmationFieldValue::Bool(b)) => *b,
+        _ => return Err(malformed("confirmed")),
+    };
+    Ok(ConfirmationFields {
+        request_id,
+        confirmed,
+        intent_digest: optional_string_field(raw, "intent_digest", "intentDigest")?,
+        error: optional_string_field(raw, "error", "error")?,
+        has_credential: confirmation_field(raw, "credential", "credential").is_some(),
+    })
+}
+
+/// Semantic checks for an approved confirmation: request types that collect a WebAuthn
+/// credential must return one, and signing confirmations must echo the intent digest.
+/// Signing may run on a warm session, so it does not require a credential.
+pub fn validate_confirmation_fields(
+    request_type: &str,
+    fields: &ConfirmationFields,
+) -> VrfResult<()> {
+    if !fields.confirmed {
+        return Ok(());
+    }
+    let needs_credential = matches!(
+        request_type,
+        "registerAccount" | "linkDevice" | "decryptPrivateKeyWithPrf"
+    );
+    if needs_credential && !fields.has_credential {
+        return Err(VrfWorkerError::ConfirmationMissingCredential);
+    }
+    let is_signing = matches!(request_type, "signTransaction" | "signNep413Message");
+    if is_signing && fields.intent_digest.as_deref().unwrap_or("").is_empty() {
+        return Err(VrfWorkerError::ConfirmationMalformed {
+            field: "intent_digest".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Test builds only (`test_confirmation_bypass`): short-circuit requests that carry
+/// `__testAutoConfirm: true` with an approved response echoing `requestId`/`intentDigest`.
+#[cfg(feature = "test_confirmation_bypass")]
+pub(crate) fn test_auto_confirm(request: &JsValue) -> Option<WorkerConfirmationResponse> {
+    use js_sys::Reflect;
+
+    let get = |key: &str| Reflect::get(request, &JsValue::from_str(key)).ok();
+    if get("__testAutoConfirm").and_then(|v| v.as_bool()) != Some(true) {
+        return None;
+    }
+
+    log::warn!("[VRF] test_confirmation_bypass: auto-confirming SecureConfirm request");
+    Some(WorkerConfirmationResponse {
+        request_id: get("requestId")
+            .and_then(|v| v.as_string())
+            .unwrap_or_default(),
+        intent_digest: get("intentDigest").and_then(|v| v.as_string()),
+        confirmed: true,
+        credential: JsValue::UNDEFINED,
+        vrf_challenge: JsValue::UNDEFINED,
+        transaction_context: JsValue::UNDEFINED,
+        error: None,
+    })
+}
+
+/// Default builds: the bypass is compiled out and `__testAutoConfirm` is ignored.
+#[cfg(not(feature = "test_confirmation_bypass"))]
+#[inline(always)]
+pub(crate) fn test_auto_confirm(_request: &JsValue) -> Option<WorkerConfirmationResponse> {
+    None
 }
 
 #[derive(Serialize)]
 #[allow(non_snake_case)]
 pub struct Summary<'a> {
+    /// Full account ID; `nearAccountIdDisplay` carries the short form for implicit accounts.
     pub nearAccountId: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nearAccountIdDisplay: Option<String>,
     pub deviceNumber: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub contractId: Option<&'a str>,
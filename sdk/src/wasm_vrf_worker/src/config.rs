@@ -22,6 +22,10 @@ pub const LOG_INCLUDE_LEVEL: bool = true;
 /// Used to ensure VRF challenges are domain-specific and cannot be replayed across different contexts
 pub const VRF_DOMAIN_SEPARATOR: &[u8] = b"web3_authn_challenge_v4";
 
+/// Domain separator prefixed to the optional `extraContextDigest32` when it is folded into the
+/// VRF input, so an app-supplied context can never be read as a session policy digest
+pub const VRF_EXTRA_CONTEXT_DOMAIN_SEPARATOR: &[u8] = b"web3_authn_extra_context_v1";
+
 /// HKDF info string for ChaCha20 key derivation from PRF output
 /// Used for both VRF keypair encryption and general ChaCha20 operations
 pub const HKDF_CHACHA20_KEY_INFO: &[u8] = b"vrf-chacha20-key";
@@ -30,12 +34,27 @@ pub const HKDF_CHACHA20_KEY_INFO: &[u8] = b"vrf-chacha20-key";
 /// Spec-aligned with `docs/vrf_webauthn_hybrid_feature_spec.md`.
 pub const HKDF_VRF_KEYPAIR_INFO: &[u8] = b"tatchi:v1:vrf-sk";
 
+/// HKDF info string for expanding a dev-only `deterministicSeedB64u` into the
+/// 32-byte stand-in for PRF.second (`dev_deterministic_vrf` feature only).
+pub const HKDF_DEV_DETERMINISTIC_VRF_INFO: &[u8] = b"tatchi:dev:deterministic-vrf-seed";
+
+/// Minimum decoded length of `deterministicSeedB64u`
+pub const DEV_DETERMINISTIC_SEED_MIN_LEN: usize = 16;
+
 /// Constant used for HKDF info when deriving K_pass_auth
 pub const VRF_WRAP_PASS_INFO: &[u8] = b"vrf-wrap-pass";
 
 /// Constant used for HKDF info when deriving WrapKeySeed
 pub const NEAR_WRAP_SEED_INFO: &[u8] = b"near-wrap-seed";
 
+/// Domain prefix hashed ahead of the canonical policy JSON for `sessionPolicyDigest32`.
+/// The Node relayer computes the same digest through this worker's wasm export.
+pub const SESSION_POLICY_DIGEST_DOMAIN: &[u8] = b"tatchi:v1:session-policy-digest";
+
+/// Largest integer accepted in a session policy (JS `Number.MAX_SAFE_INTEGER`), so the
+/// canonical form is identical wherever the policy was built.
+pub const SESSION_POLICY_MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
 // === ENCRYPTION PARAMETERS ===
 
 /// ChaCha20Poly1305 key size in bytes (256 bits)
@@ -47,6 +66,10 @@ pub const CHACHA20_NONCE_SIZE: usize = 12;
 /// VRF seed size in bytes for deterministic generation (256 bits)
 pub const VRF_SEED_SIZE: usize = 32;
 
+/// Version byte prefixed to serialized `VRFKeypairData` (bincode 1.x, default options).
+/// Bump when the bincode config or the `VRFKeypairData`/`ECVRFKeyPair` layout changes.
+pub const VRF_KEYPAIR_DATA_VERSION: u8 = 1;
+
 /// HKDF info string for deriving AEAD key from Shamir3Pass KEK (K)
 /// Longer, namespaced context string to avoid collisions across schemes/usages
 pub const SHAMIR_AEAD_HKDF_INFO: &[u8] = b"web3authn-shamir3pass-kek-to-aead-key-v1";
@@ -68,6 +91,13 @@ pub const SHAMIR_REJECTION_SAMPLING_MAX_ATTEMPTS: u32 = 10;
 /// This helps reduce bias when the range doesn't align with byte boundaries
 pub const SHAMIR_RANDOM_BYTES_OVERHEAD: usize = 64;
 
+/// Default Miller-Rabin rounds when validating a configured Shamir P
+/// (false-positive probability at most 4^-40)
+pub const SHAMIR_PRIMALITY_ROUNDS: u32 = 40;
+
+/// Floor for `set_shamir_primality_rounds`; lower requests are raised to this
+pub const SHAMIR_PRIMALITY_MIN_ROUNDS: u32 = 16;
+
 // Default Shamir P
 pub const DEFAULT_SHAMIR_P_B64U: &str = "3N5w46AIGjGT2v5Vua_TMD5Ywfa9U2F7-WzW8SNDsIM";
 
@@ -138,3 +168,33 @@ pub const VRF_SESSION_DEFAULT_TTL_MS: u64 = 5 * 60 * 1000; // 5 minutes
 /// A "use" is intentionally defined at the VRF boundary (per dispense),
 /// not per signer worker internal loop.
 pub const VRF_SESSION_DEFAULT_MAX_USES: u32 = 5;
+
+/// Ceiling (ms) on a VRF session's total lifetime across RENEW_SESSION calls.
+/// Once `created_at_ms + max lifetime` passes, a fresh WebAuthn prompt is required.
+pub const VRF_SESSION_MAX_LIFETIME_MS: u64 = 8 * 60 * 60 * 1000; // 8 hours
+
+/// Default lifetime (ms) of a cached contract verification result.
+/// Lets back-to-back signing requests presenting the same credential + VRF challenge
+/// skip a duplicate `verify_authentication_response` RPC call.
+pub const VERIFICATION_CACHE_DEFAULT_TTL_MS: u64 = 30 * 1000; // 30 seconds
+
+/// Upper bound for a configured verification cache TTL (ms).
+pub const VERIFICATION_CACHE_MAX_TTL_MS: u64 = 120 * 1000; // 2 minutes
+
+/// Default maximum age (ms) of a cached block context used for VRF challenges when the
+/// caller omits `blockHeight`/`blockHash`.
+pub const BLOCK_CONTEXT_DEFAULT_MAX_STALENESS_MS: u64 = 10 * 1000; // 10 seconds
+
+/// Upper bound for a configured block context staleness (ms); the contract rejects
+/// VRF challenges bound to blocks that are too old.
+pub const BLOCK_CONTEXT_MAX_STALENESS_MS: u64 = 60 * 1000; // 1 minute
+
+/// Most challenges one GENERATE_VRF_CHALLENGES request may mint; a request's `maxCount`
+/// can lower but not raise it.
+pub const VRF_CHALLENGE_BATCH_MAX_COUNT: usize = 20;
+
+// === STATE CHANGE LOG ===
+
+/// State-change events kept for GET_STATE_CHANGES_SINCE. Older changes are evicted and a
+/// caller that missed them is told to resync from the full status.
+pub const STATE_CHANGE_LOG_CAPACITY: usize = 64;
@@ -20,6 +20,9 @@ pub enum VrfWorkerError {
     /// PRF output is empty or invalid
     InvalidPrfOutput(String),
 
+    /// PRF.first and PRF.second are byte-identical (both PRF salts evaluated the same)
+    IdenticalPrfOutputs,
+
     /// HKDF key derivation failed
     HkdfDerivationFailed(HkdfError),
 
@@ -56,8 +59,52 @@ pub enum VrfWorkerError {
     /// VRF session usage budget exhausted
     SessionExhausted,
 
+    /// VRF session can no longer be renewed (max lifetime reached or not renewable)
+    SessionRenewalWindowClosed,
+
+    /// VRF session was minted under a different session policy than the one presented
+    SessionPolicyMismatch,
+
+    /// Session policy JSON could not be canonicalized for hashing
+    InvalidSessionPolicy(PolicyError),
+
     /// No MessagePort is attached for this session id
     SessionPortNotAttached(String),
+
+    /// Host CSPRNG failed the startup self-test
+    RandomnessUnavailable(String),
+
+    /// No fresh block height/hash could be obtained for a VRF challenge
+    BlockContextUnavailable(String),
+
+    /// Request needs a cargo feature that was left out of this (slim) worker build
+    FeatureNotCompiled { feature: String, requested: String },
+
+    /// A globalThis function the worker host must install is absent
+    MissingHostBridge(String),
+
+    /// Shamir relay answered, but with a value that cannot be a lock operation on our input
+    /// (unchanged, out of range, or not decodable)
+    ShamirServerMisbehavior(String),
+
+    /// KEK recovered through the Shamir round trip does not open the VRF ciphertext
+    ShamirDecryptFailed(String),
+
+    /// Shamir relay could not be reached or returned a non-2xx status
+    ShamirServerUnreachable(String),
+
+    /// A Device1 account card does not describe the registration it was presented with
+    AccountCardMismatch(String),
+
+    /// A Device1 account card's validity window has passed (`expiresAtMs`)
+    AccountCardExpired(u64),
+
+    /// The confirmation response from the main thread is missing a required field or has one
+    /// of the wrong type
+    ConfirmationMalformed { field: String },
+
+    /// The user confirmed, but no WebAuthn credential came back for a request that needs one
+    ConfirmationMissingCredential,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,10 +137,24 @@ pub enum SerializationError {
     KeypairDataDeserialization(String),
     /// Failed to deserialize VRF keypair
     VrfKeypairDeserialization(String),
+    /// Serialized keypair data carries an unknown version byte
+    UnsupportedKeypairVersion(u8),
     /// Base64 encoding/decoding errors
     Base64Error(String),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PolicyError {
+    /// Policy is not valid JSON
+    InvalidJson(String),
+    /// Top-level policy value is not a JSON object
+    NotAnObject,
+    /// Non-integer number at the given path (`$.field`)
+    FloatNotAllowed(String),
+    /// Integer outside the JS safe-integer range at the given path
+    IntegerOutOfRange(String),
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MessageError {
     JsonParsingFailed(String),
@@ -115,6 +176,12 @@ impl fmt::Display for VrfWorkerError {
             VrfWorkerError::InvalidPrfOutput(msg) => {
                 write!(f, "Invalid PRF output: {}", msg)
             }
+            VrfWorkerError::IdenticalPrfOutputs => {
+                write!(
+                    f,
+                    "PRF.first and PRF.second are identical; dual-PRF misconfiguration"
+                )
+            }
             VrfWorkerError::HkdfDerivationFailed(err) => {
                 write!(f, "HKDF derivation failed: {}", err)
             }
@@ -160,9 +227,59 @@ impl fmt::Display for VrfWorkerError {
             VrfWorkerError::SessionExhausted => {
                 write!(f, "SESSION_EXHAUSTED")
             }
+            VrfWorkerError::SessionRenewalWindowClosed => {
+                write!(f, "SESSION_RENEWAL_WINDOW_CLOSED")
+            }
+            VrfWorkerError::SessionPolicyMismatch => {
+                write!(f, "SESSION_POLICY_MISMATCH")
+            }
+            VrfWorkerError::InvalidSessionPolicy(err) => {
+                write!(f, "{}", err)
+            }
             VrfWorkerError::SessionPortNotAttached(session_id) => {
                 write!(f, "SESSION_PORT_NOT_ATTACHED: {}", session_id)
             }
+            VrfWorkerError::RandomnessUnavailable(msg) => {
+                write!(f, "Randomness unavailable: {}", msg)
+            }
+            VrfWorkerError::BlockContextUnavailable(msg) => {
+                write!(f, "BLOCK_CONTEXT_UNAVAILABLE: {}", msg)
+            }
+            VrfWorkerError::FeatureNotCompiled { feature, requested } => {
+                write!(
+                    f,
+                    "FeatureNotCompiled: {} requires the `{}` feature, which is not compiled into this worker build",
+                    requested, feature
+                )
+            }
+            VrfWorkerError::MissingHostBridge(name) => {
+                write!(
+                    f,
+                    "MissingHostBridge(\"{}\"): the worker host did not install globalThis.{}",
+                    name, name
+                )
+            }
+            VrfWorkerError::ShamirServerMisbehavior(msg) => {
+                write!(f, "SHAMIR_SERVER_MISBEHAVIOR: {}", msg)
+            }
+            VrfWorkerError::ShamirDecryptFailed(msg) => {
+                write!(f, "SHAMIR_DECRYPT_FAILED: {}", msg)
+            }
+            VrfWorkerError::ShamirServerUnreachable(msg) => {
+                write!(f, "SHAMIR_SERVER_UNREACHABLE: {}", msg)
+            }
+            VrfWorkerError::AccountCardMismatch(msg) => {
+                write!(f, "ACCOUNT_CARD_MISMATCH: {}", msg)
+            }
+            VrfWorkerError::AccountCardExpired(expires_at_ms) => {
+                write!(f, "ACCOUNT_CARD_EXPIRED: card expired at {}", expires_at_ms)
+            }
+            VrfWorkerError::ConfirmationMalformed { field } => {
+                write!(f, "CONFIRMATION_MALFORMED: field `{}`", field)
+            }
+            VrfWorkerError::ConfirmationMissingCredential => {
+                write!(f, "CONFIRMATION_MISSING_CREDENTIAL")
+            }
         }
     }
 }
@@ -205,6 +322,9 @@ impl fmt::Display for SerializationError {
             SerializationError::VrfKeypairDeserialization(msg) => {
                 write!(f, "Failed to deserialize VRF keypair: {}", msg)
             }
+            SerializationError::UnsupportedKeypairVersion(version) => {
+                write!(f, "unsupported VRF keypair serialization version {}", version)
+            }
             SerializationError::Base64Error(msg) => {
                 write!(f, "Base64 encoding/decoding error: {}", msg)
             }
@@ -212,6 +332,29 @@ impl fmt::Display for SerializationError {
     }
 }
 
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyError::InvalidJson(msg) => {
+                write!(f, "INVALID_SESSION_POLICY: not valid JSON: {}", msg)
+            }
+            PolicyError::NotAnObject => {
+                write!(f, "INVALID_SESSION_POLICY: policy must be a JSON object")
+            }
+            PolicyError::FloatNotAllowed(path) => {
+                write!(f, "INVALID_SESSION_POLICY: {} must be an integer", path)
+            }
+            PolicyError::IntegerOutOfRange(path) => {
+                write!(
+                    f,
+                    "INVALID_SESSION_POLICY: {} is outside the safe integer range",
+                    path
+                )
+            }
+        }
+    }
+}
+
 impl fmt::Display for MessageError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -227,6 +370,7 @@ impl std::error::Error for VrfWorkerError {}
 impl std::error::Error for HkdfError {}
 impl std::error::Error for AesError {}
 impl std::error::Error for SerializationError {}
+impl std::error::Error for PolicyError {}
 // impl std::error::Error for VrfCryptoError {}
 impl std::error::Error for MessageError {}
 
@@ -253,6 +397,18 @@ impl From<String> for VrfWorkerError {
     }
 }
 
+impl From<PolicyError> for VrfWorkerError {
+    fn from(err: PolicyError) -> Self {
+        VrfWorkerError::InvalidSessionPolicy(err)
+    }
+}
+
+impl From<PolicyError> for JsValue {
+    fn from(err: PolicyError) -> Self {
+        JsValue::from_str(&err.to_string())
+    }
+}
+
 impl From<VrfWorkerError> for JsValue {
     fn from(err: VrfWorkerError) -> Self {
         JsValue::from_str(&format!("{}", err))
@@ -272,6 +428,22 @@ impl VrfWorkerError {
         VrfWorkerError::MissingRequiredData(field.to_string())
     }
 
+    /// Machine-readable `errorCode` for responses the UI branches on. `VRF_LOCKED` means no
+    /// VRF keypair is unlocked (prompt an unlock); input problems are `INVALID_INPUT`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            VrfWorkerError::NoVrfKeypair | VrfWorkerError::VrfNotUnlocked => "VRF_LOCKED",
+            VrfWorkerError::InvalidMessageFormat(_)
+            | VrfWorkerError::MissingRequiredData(_)
+            | VrfWorkerError::BlockHeightParsingError(_)
+            | VrfWorkerError::MessageParsingError(_)
+            | VrfWorkerError::InvalidSessionPolicy(_) => "INVALID_INPUT",
+            VrfWorkerError::BlockContextUnavailable(_) => "BLOCK_CONTEXT_UNAVAILABLE",
+            VrfWorkerError::SessionExhausted => "SESSION_EXHAUSTED",
+            _ => "VRF_ERROR",
+        }
+    }
+
     pub fn invalid_format(msg: &str) -> Self {
         VrfWorkerError::InvalidMessageFormat(msg.to_string())
     }
@@ -4,6 +4,15 @@ use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::JsFuture;
 
 pub fn build_json_post_init(body: &str) -> Result<JsValue, String> {
+    let headers = [("Content-Type".to_string(), "application/json".to_string())];
+    build_json_post_init_with_headers(body, &headers)
+}
+
+/// Build a POST `RequestInit` with an explicit header list (must include Content-Type).
+pub fn build_json_post_init_with_headers(
+    body: &str,
+    header_list: &[(String, String)],
+) -> Result<JsValue, String> {
     let init = Object::new();
     Reflect::set(
         &init,
@@ -13,12 +22,14 @@ pub fn build_json_post_init(body: &str) -> Result<JsValue, String> {
     .map_err(|_| "Failed to set fetch init.method".to_string())?;
 
     let headers = Object::new();
-    Reflect::set(
-        &headers,
-        &JsValue::from_str("Content-Type"),
-        &JsValue::from_str("application/json"),
-    )
-    .map_err(|_| "Failed to set fetch init.headers".to_string())?;
+    for (name, value) in header_list {
+        Reflect::set(
+            &headers,
+            &JsValue::from_str(name),
+            &JsValue::from_str(value),
+        )
+        .map_err(|_| format!("Failed to set fetch init.headers[{}]", name))?;
+    }
     let headers_val: JsValue = headers.into();
     Reflect::set(&init, &JsValue::from_str("headers"), &headers_val)
         .map_err(|_| "Failed to set fetch init.headers".to_string())?;
@@ -98,10 +109,3 @@ pub async fn response_text(resp: &JsValue) -> Result<String, String> {
     v.as_string()
         .ok_or_else(|| "Response text is not a string".to_string())
 }
-
-pub async fn response_json(resp: &JsValue) -> Result<JsValue, String> {
-    let promise = call_method0(resp, "json")?;
-    JsFuture::from(promise)
-        .await
-        .map_err(|e| format!("Failed to parse JSON: {:?}", e))
-}
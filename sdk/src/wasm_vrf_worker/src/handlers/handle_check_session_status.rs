@@ -39,28 +39,50 @@ pub fn handle_check_session_status(
         expires_at_ms: Option<f64>,
         #[serde(rename = "createdAtMs")]
         created_at_ms: Option<f64>,
+        #[serde(rename = "renewableUntilMs")]
+        renewable_until_ms: Option<f64>,
+        /// Whether RENEW_SESSION can currently extend the session without a WebAuthn prompt.
+        renewable: bool,
+        #[serde(rename = "stateVersion")]
+        state_version: u64,
+        #[serde(rename = "logoutEpoch")]
+        logout_epoch: u64,
     }
 
-    let (status, remaining_uses, expires_at_ms, created_at_ms) = {
+    let (status, remaining_uses, expires_at_ms, created_at_ms, renewable_until_ms, renewable) = {
         let mut mgr = manager.borrow_mut();
         match mgr.sessions.get(&request.session_id) {
-            None => ("not_found", None, None, None),
+            None => ("not_found", None, None, None, None, false),
             Some(session) => {
                 let remaining_uses = session.remaining_uses;
                 let expires_at_ms = session.expires_at_ms;
                 let created_at_ms = Some(session.created_at_ms);
+                let renewable_until_ms = session.renewable_until_ms;
+                let renewable = session.is_renewable(now_ms);
 
-                if session.is_expired(now_ms) {
-                    mgr.sessions.remove(&request.session_id);
-                    ("expired", remaining_uses, expires_at_ms, created_at_ms)
+                let status = if session.is_expired(now_ms) {
+                    mgr.expire_session(&request.session_id, now_ms);
+                    "expired"
                 } else if remaining_uses == Some(0) {
-                    ("exhausted", remaining_uses, expires_at_ms, created_at_ms)
+                    "exhausted"
                 } else {
-                    ("active", remaining_uses, expires_at_ms, created_at_ms)
-                }
+                    "active"
+                };
+                (
+                    status,
+                    remaining_uses,
+                    expires_at_ms,
+                    created_at_ms,
+                    renewable_until_ms,
+                    renewable,
+                )
             }
         }
     };
+    let (state_version, logout_epoch) = {
+        let mgr = manager.borrow();
+        (mgr.state_changes.version(), mgr.logout_epoch)
+    };
 
     VrfWorkerResponse::success_from(
         message_id,
@@ -70,6 +92,10 @@ pub fn handle_check_session_status(
             remaining_uses,
             expires_at_ms,
             created_at_ms,
+            renewable_until_ms,
+            renewable,
+            state_version,
+            logout_epoch,
         }),
     )
 }
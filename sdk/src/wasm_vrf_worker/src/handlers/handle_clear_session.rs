@@ -24,8 +24,11 @@ pub fn handle_clear_session(
 
     let (cleared_session, cleared_challenge) = {
         let mut mgr = manager.borrow_mut();
-        let cleared_session = mgr.sessions.remove(&request.session_id).is_some();
+        let cleared_session = mgr.clear_session(&request.session_id);
         let cleared_challenge = mgr.vrf_challenges.remove(&request.session_id).is_some();
+        // Verification results aren't keyed by session; drop them all so a cleared
+        // session can't be revived through a cached verification.
+        mgr.verification_cache.clear();
         (cleared_session, cleared_challenge)
     };
 
@@ -0,0 +1,72 @@
+use crate::config::{BLOCK_CONTEXT_DEFAULT_MAX_STALENESS_MS, BLOCK_CONTEXT_MAX_STALENESS_MS};
+use crate::manager::{BlockContextSource, VRFKeyManager};
+use crate::types::VrfWorkerResponse;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+/// Request payload for letting the worker fetch block height/hash itself.
+/// Once configured, GENERATE_VRF_CHALLENGE may omit `blockHeight`/`blockHash`.
+/// An empty `nearRpcUrl` removes the source and drops any cached block context.
+#[wasm_bindgen]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigureBlockContextSourceRequest {
+    #[wasm_bindgen(getter_with_clone, js_name = "nearRpcUrl")]
+    #[serde(rename = "nearRpcUrl")]
+    pub near_rpc_url: String,
+    /// Maximum age (ms) of a cached block context before it is refetched.
+    /// Defaults to `BLOCK_CONTEXT_DEFAULT_MAX_STALENESS_MS`; clamped to
+    /// `BLOCK_CONTEXT_MAX_STALENESS_MS`.
+    #[wasm_bindgen(js_name = "maxStalenessMs")]
+    #[serde(rename = "maxStalenessMs", default)]
+    pub max_staleness_ms: Option<u32>,
+}
+
+pub fn handle_configure_block_context_source(
+    manager: Rc<RefCell<VRFKeyManager>>,
+    message_id: Option<String>,
+    request: ConfigureBlockContextSourceRequest,
+) -> VrfWorkerResponse {
+    let near_rpc_url = request.near_rpc_url.trim().to_string();
+    let max_staleness_ms = request
+        .max_staleness_ms
+        .map(|ms| (ms as u64).min(BLOCK_CONTEXT_MAX_STALENESS_MS))
+        .unwrap_or(BLOCK_CONTEXT_DEFAULT_MAX_STALENESS_MS);
+
+    {
+        let mut mgr = manager.borrow_mut();
+        // Any cached block came from the previous source.
+        mgr.block_context = None;
+        mgr.block_context_source = if near_rpc_url.is_empty() {
+            None
+        } else {
+            Some(BlockContextSource {
+                near_rpc_url: near_rpc_url.clone(),
+                max_staleness_ms,
+            })
+        };
+    }
+    debug!(
+        "[VRF] configure_block_context_source: enabled={}, maxStalenessMs={}",
+        !near_rpc_url.is_empty(),
+        max_staleness_ms
+    );
+
+    #[derive(Serialize)]
+    struct Resp {
+        status: &'static str,
+        enabled: bool,
+        #[serde(rename = "maxStalenessMs")]
+        max_staleness_ms: u64,
+    }
+    VrfWorkerResponse::success_from(
+        message_id,
+        Some(Resp {
+            status: "ok",
+            enabled: !near_rpc_url.is_empty(),
+            max_staleness_ms,
+        }),
+    )
+}
@@ -0,0 +1,66 @@
+use crate::manager::VRFKeyManager;
+use crate::rpc_headers::{describe_rpc_headers, validate_rpc_headers, RpcHeaders};
+use crate::types::VrfWorkerResponse;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+/// Request payload for setting worker-wide NEAR RPC defaults.
+/// `rpcHeaders` replaces any previously configured defaults; per-request
+/// `rpcHeaders` still override individual names.
+#[wasm_bindgen]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigureRpcRequest {
+    #[wasm_bindgen(skip)]
+    #[serde(rename = "rpcHeaders", default)]
+    pub rpc_headers: RpcHeaders,
+    /// Optional lifetime (ms) for cached contract verification results.
+    /// Clamped to `VERIFICATION_CACHE_MAX_TTL_MS`; 0 disables reuse.
+    #[wasm_bindgen(js_name = "verificationCacheTtlMs")]
+    #[serde(rename = "verificationCacheTtlMs", default)]
+    pub verification_cache_ttl_ms: Option<u32>,
+}
+
+pub fn handle_configure_rpc(
+    manager: Rc<RefCell<VRFKeyManager>>,
+    message_id: Option<String>,
+    request: ConfigureRpcRequest,
+) -> VrfWorkerResponse {
+    if let Err(e) = validate_rpc_headers(&request.rpc_headers) {
+        return VrfWorkerResponse::fail(message_id, e);
+    }
+    debug!(
+        "[VRF] configure_rpc: default headers [{}]",
+        describe_rpc_headers(&request.rpc_headers)
+    );
+
+    let mut header_names: Vec<String> = request.rpc_headers.keys().cloned().collect();
+    header_names.sort();
+    let verification_cache_ttl_ms = {
+        let mut mgr = manager.borrow_mut();
+        mgr.default_rpc_headers = request.rpc_headers;
+        if let Some(ttl_ms) = request.verification_cache_ttl_ms {
+            mgr.verification_cache.set_ttl_ms(ttl_ms as u64);
+        }
+        mgr.verification_cache.ttl_ms()
+    };
+
+    #[derive(Serialize)]
+    struct Resp {
+        status: &'static str,
+        #[serde(rename = "headerNames")]
+        header_names: Vec<String>,
+        #[serde(rename = "verificationCacheTtlMs")]
+        verification_cache_ttl_ms: u64,
+    }
+    VrfWorkerResponse::success_from(
+        message_id,
+        Some(Resp {
+            status: "ok",
+            header_names,
+            verification_cache_ttl_ms,
+        }),
+    )
+}
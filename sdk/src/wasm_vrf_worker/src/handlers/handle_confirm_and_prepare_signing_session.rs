@@ -1,7 +1,8 @@
 use crate::await_secure_confirmation::vrf_await_secure_confirmation;
 use crate::manager::VRFKeyManager;
+use crate::summary_localization::{canonical_summary_fields, localize_summary, LocalizedStrings};
 use crate::types::{VrfWorkerResponse, WorkerConfirmationResponse};
-use js_sys::{Array, Date, Reflect};
+use js_sys::{Array, Date, Object, Reflect};
 use log::debug;
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
@@ -15,6 +16,9 @@ use wasm_bindgen::JsValue;
 /// This handler will auto-set `payload.signingAuthMode` for signing requests when absent:
 /// - `warmSession` if a valid VRF session exists for `requestId` with enough remaining uses
 /// - otherwise `webauthn`
+///
+/// The summary is rewritten as canonical `summary.fields`; `confirmationConfig.localizedStrings`
+/// replace field text only if each still contains the raw value, else `localizationRejected` is set.
 #[wasm_bindgen]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ConfirmAndPrepareSigningSessionRequest {
@@ -48,6 +52,10 @@ pub async fn handle_confirm_and_prepare_signing_session(
         return VrfWorkerResponse::fail(message_id, e);
     };
 
+    if let Err(e) = apply_summary_localization(&request_val) {
+        return VrfWorkerResponse::fail(message_id, e);
+    }
+
     let decision: WorkerConfirmationResponse =
         match vrf_await_secure_confirmation(request_val).await {
             Ok(v) => v,
@@ -83,7 +91,7 @@ fn warm_session_available(
     let expired = session.is_expired(now_ms);
     let can_consume = session.can_consume(uses_needed.max(1));
     if expired {
-        manager.sessions.remove(session_id);
+        manager.expire_session(session_id, now_ms);
         return false;
     }
     can_consume
@@ -127,6 +135,82 @@ fn inject_signing_auth_mode_if_missing(
     Ok(())
 }
 
+fn apply_summary_localization(request: &JsValue) -> Result<(), String> {
+    let req_type = get_string(request, "type")?;
+    if req_type != "signTransaction" && req_type != "signNep413Message" {
+        return Ok(());
+    }
+    let summary = get_object(request, "summary")?;
+
+    let config = Reflect::get(request, &JsValue::from_str("confirmationConfig"))
+        .map_err(|e| format!("Failed to read confirmationConfig: {:?}", e))?;
+    let (locale, localized) = if config.is_object() {
+        (
+            get_optional_string(&config, "locale"),
+            get_localized_strings(&config)?,
+        )
+    } else {
+        (None, None)
+    };
+
+    let canonical = canonical_summary_fields(
+        get_optional_string(&summary, "receiverId").as_deref(),
+        get_optional_string(&summary, "totalAmount").as_deref(),
+    );
+    let localized = localize_summary(canonical, locale.as_deref(), localized.as_ref());
+
+    let fields = serde_wasm_bindgen::to_value(&localized.fields)
+        .map_err(|e| format!("Failed to serialize summary fields: {}", e))?;
+    set_value(&summary, "fields", &fields)?;
+    set_value(
+        &summary,
+        "fieldsDigest",
+        &JsValue::from_str(&localized.canonical_digest_b64u()),
+    )?;
+    if let Some(locale) = localized.locale.as_deref() {
+        set_value(&summary, "locale", &JsValue::from_str(locale))?;
+    }
+    set_value(
+        request,
+        "localizationRejected",
+        &JsValue::from_bool(localized.localization_rejected),
+    )
+}
+
+fn get_localized_strings(config: &JsValue) -> Result<Option<LocalizedStrings>, String> {
+    let v = Reflect::get(config, &JsValue::from_str("localizedStrings"))
+        .map_err(|e| format!("Failed to read localizedStrings: {:?}", e))?;
+    if v.is_undefined() || v.is_null() {
+        return Ok(None);
+    }
+    if !v.is_object() {
+        return Err("confirmationConfig.localizedStrings must be an object".to_string());
+    }
+    let mut out = LocalizedStrings::new();
+    for entry in Object::entries(&Object::from(v)).iter() {
+        let entry = Array::from(&entry);
+        let key = entry.get(0).as_string().unwrap_or_default();
+        let text = entry
+            .get(1)
+            .as_string()
+            .ok_or_else(|| format!("localizedStrings.{} must be a string", key))?;
+        out.insert(key, text);
+    }
+    Ok(Some(out))
+}
+
+fn get_optional_string(obj: &JsValue, key: &str) -> Option<String> {
+    Reflect::get(obj, &JsValue::from_str(key))
+        .ok()
+        .and_then(|v| v.as_string())
+}
+
+fn set_value(obj: &JsValue, key: &str, value: &JsValue) -> Result<(), String> {
+    Reflect::set(obj, &JsValue::from_str(key), value)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to set {}: {:?}", key, e))
+}
+
 fn has_signing_auth_mode(payload: &JsValue) -> bool {
     Reflect::get(payload, &JsValue::from_str("signingAuthMode"))
         .ok()
@@ -36,12 +36,54 @@ pub struct DecryptSessionRequest {
     #[wasm_bindgen(getter_with_clone, js_name = "expectedVrfPublicKey")]
     #[serde(rename = "expectedVrfPublicKey")]
     pub expected_vrf_public_key: Option<String>,
+    /// Optional session policy digest (base64url) to mint the decrypt session under.
+    #[wasm_bindgen(getter_with_clone, js_name = "sessionPolicyDigest")]
+    #[serde(rename = "sessionPolicyDigest", default)]
+    pub session_policy_digest: Option<String>,
+    /// Optional session policy JSON; hashed canonically and checked against `sessionPolicyDigest`.
+    #[wasm_bindgen(getter_with_clone, js_name = "sessionPolicyJson")]
+    #[serde(rename = "sessionPolicyJson", default)]
+    pub session_policy_json: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct DecryptSessionResult {
     #[serde(rename = "sessionId")]
     pub session_id: String,
+    /// Absolute expiry of the minted session (ms since epoch), if it has a TTL.
+    #[serde(rename = "expiresAtMs", skip_serializing_if = "Option::is_none")]
+    pub expires_at_ms: Option<f64>,
+    /// Milliseconds until expiry, so UIs can warn before the session lapses.
+    #[serde(rename = "remainingMs", skip_serializing_if = "Option::is_none")]
+    pub remaining_ms: Option<f64>,
+    /// Latest expiry RENEW_SESSION can extend this session to without a new prompt.
+    #[serde(rename = "renewableUntilMs", skip_serializing_if = "Option::is_none")]
+    pub renewable_until_ms: Option<f64>,
+}
+
+/// Report a minted session's lifetime at `now_ms`; shared by the handler and native tests.
+pub fn decrypt_session_result_at(
+    manager: &VRFKeyManager,
+    session_id: String,
+    now_ms: f64,
+) -> DecryptSessionResult {
+    let (expires_at_ms, remaining_ms, renewable_until_ms) = manager
+        .sessions
+        .get(&session_id)
+        .map(|s| {
+            (
+                s.expires_at_ms,
+                s.remaining_ms(now_ms),
+                s.renewable_until_ms,
+            )
+        })
+        .unwrap_or((None, None, None));
+    DecryptSessionResult {
+        session_id,
+        expires_at_ms,
+        remaining_ms,
+        renewable_until_ms,
+    }
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -80,6 +122,15 @@ pub async fn handle_decrypt_session(
     let near_account_id = request.near_account_id.clone();
     let wrap_key_salt_b64u = request.wrap_key_salt_b64u.clone();
 
+    // Check a reused session id before prompting: once minted, WrapKeySeed is dispatched to
+    // the signer immediately, so an expired or exhausted session must be refused up front.
+    if let Err(e) = manager
+        .borrow_mut()
+        .ensure_session_usable(&session_id, js_sys::Date::now())
+    {
+        return VrfWorkerResponse::fail(message_id, e.to_string());
+    }
+
     // Build a SecureConfirmRequest object and hand it to awaitSecureConfirmationV2.
     let req = SecureConfirmRequest {
         requestId: &session_id,
@@ -257,7 +308,7 @@ pub async fn handle_decrypt_session(
     }
 
     let response = crate::handlers::handle_mint_session_keys_and_send_to_signer(
-        manager,
+        manager.clone(),
         message_id.clone(),
         crate::handlers::handle_mint_session_keys_and_send_to_signer::MintSessionKeysAndSendToSignerRequest {
             session_id: session_id.clone(),
@@ -265,8 +316,15 @@ pub async fn handle_decrypt_session(
             wrap_key_salt_b64u,
             contract_id: None,
             near_rpc_url: None,
+            rpc_headers: None,
             ttl_ms: None,
             remaining_uses: None,
+            max_lifetime_ms: None,
+            session_policy_digest: request.session_policy_digest.clone(),
+            session_policy_json: request.session_policy_json.clone(),
+            key_reuse_policy: None,
+            session_binding_nonce: None,
+            intent_digest: None,
             credential: decision.credential,
         },
     )
@@ -276,11 +334,11 @@ pub async fn handle_decrypt_session(
         return response;
     }
 
+    // Report the freshly minted session's lifetime; the key has already been dispatched.
+    let result = decrypt_session_result_at(&manager.borrow(), session_id, js_sys::Date::now());
+
     VrfWorkerResponse::success(
         message_id,
-        Some(
-            serde_wasm_bindgen::to_value(&DecryptSessionResult { session_id })
-                .unwrap_or(wasm_bindgen::JsValue::UNDEFINED),
-        ),
+        Some(serde_wasm_bindgen::to_value(&result).unwrap_or(wasm_bindgen::JsValue::UNDEFINED)),
     )
 }
@@ -10,8 +10,9 @@ use crate::handlers::handle_shamir3pass_client::{
     perform_shamir3pass_client_encrypt_current_vrf_keypair, Shamir3PassEncryptVrfKeypairResult,
 };
 use crate::manager::VRFKeyManager;
+use crate::types::progress::{VrfProgress, VrfProgressPhase};
 use crate::types::{EncryptedVRFKeypair, VRFChallengeData, VRFInputData, VrfWorkerResponse};
-use crate::utils::base64_url_decode;
+use crate::utils::{base64_url_decode, parse_deterministic_seed_b64u};
 
 #[wasm_bindgen]
 #[derive(Serialize, Deserialize, Clone)]
@@ -32,6 +33,11 @@ pub struct DeriveVrfKeypairFromPrfRequest {
     #[wasm_bindgen(getter_with_clone, js_name = "vrfInputData")]
     #[serde(default, rename = "vrfInputData")]
     pub vrf_input_data: Option<VRFInputData>,
+    /// Dev-only seed (base64url) that replaces PRF.second for reproducible VRF keypairs.
+    /// Rejected unless built with the `dev_deterministic_vrf` feature.
+    #[wasm_bindgen(getter_with_clone, js_name = "deterministicSeedB64u")]
+    #[serde(default, rename = "deterministicSeedB64u")]
+    pub deterministic_seed_b64u: Option<String>,
 }
 
 fn js_undefined() -> JsValue {
@@ -58,20 +64,15 @@ pub struct DeterministicVrfKeypairResponse {
     #[serde(rename = "serverEncryptedVrfKeypair")]
     pub server_encrypted_vrf_keypair: Option<Shamir3PassEncryptVrfKeypairResult>,
     pub success: bool,
+    /// True when derived from a dev-only `deterministicSeedB64u` (never production material)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deterministic: bool,
 }
 
-/// Handle DERIVE_VRF_KEYPAIR_FROM_PRF message
-///
-/// Derives a VRF keypair deterministically from PRF.second embedded in a WebAuthn credential,
-/// optionally storing it in memory
-/// and performing Shamir 3-pass encryption for server storage.
-pub async fn handle_derive_vrf_keypair_from_prf(
-    manager: Rc<RefCell<VRFKeyManager>>,
-    message_id: Option<String>,
-    payload: DeriveVrfKeypairFromPrfRequest,
-) -> VrfWorkerResponse {
+/// Extract and validate the 32-byte PRF.second output embedded in the request credential.
+fn extract_prf_output(payload: &DeriveVrfKeypairFromPrfRequest) -> Result<Vec<u8>, String> {
     if payload.credential.is_null() || payload.credential.is_undefined() {
-        return VrfWorkerResponse::fail(message_id, "Missing credential");
+        return Err("Missing credential".to_string());
     }
 
     let prf_second_b64u: Option<String> = {
@@ -88,31 +89,59 @@ pub async fn handle_derive_vrf_keypair_from_prf(
     let prf_output = match prf_second_b64u.as_deref() {
         Some(b64u) => match base64_url_decode(b64u) {
             Ok(bytes) if !bytes.is_empty() => bytes,
-            Ok(_) => {
-                return VrfWorkerResponse::fail(message_id, "Missing PRF.second in credential")
-            }
-            Err(_) => {
-                return VrfWorkerResponse::fail(
-                    message_id,
-                    "Missing or invalid PRF.second in credential",
-                )
-            }
+            Ok(_) => return Err("Missing PRF.second in credential".to_string()),
+            Err(_) => return Err("Missing or invalid PRF.second in credential".to_string()),
         },
-        None => return VrfWorkerResponse::fail(message_id, "Missing PRF.second in credential"),
+        None => return Err("Missing PRF.second in credential".to_string()),
     };
     if prf_output.len() != CHACHA20_KEY_SIZE {
-        return VrfWorkerResponse::fail(message_id, "Invalid PRF output length: expected 32 bytes");
+        return Err("Invalid PRF output length: expected 32 bytes".to_string());
     }
+    Ok(prf_output)
+}
+
+/// Handle DERIVE_VRF_KEYPAIR_FROM_PRF message
+///
+/// Derives a VRF keypair deterministically from PRF.second embedded in a WebAuthn credential,
+/// optionally storing it in memory
+/// and performing Shamir 3-pass encryption for server storage.
+pub async fn handle_derive_vrf_keypair_from_prf(
+    manager: Rc<RefCell<VRFKeyManager>>,
+    message_id: Option<String>,
+    payload: DeriveVrfKeypairFromPrfRequest,
+) -> VrfWorkerResponse {
+    let deterministic_seed =
+        match parse_deterministic_seed_b64u(payload.deterministic_seed_b64u.as_deref()) {
+            Ok(seed) => seed,
+            Err(e) => return VrfWorkerResponse::fail(message_id, e),
+        };
+    let deterministic = deterministic_seed.is_some();
+
+    let prf_output = match deterministic_seed {
+        Some(seed) => {
+            warn!("Deriving DETERMINISTIC dev VRF keypair; never use outside localnet");
+            match VRFKeyManager::derive_dev_deterministic_prf_output(&seed) {
+                Ok(prf_output) => prf_output,
+                Err(e) => return VrfWorkerResponse::fail(message_id, e.to_string()),
+            }
+        }
+        None => match extract_prf_output(&payload) {
+            Ok(prf_output) => prf_output,
+            Err(e) => return VrfWorkerResponse::fail(message_id, e),
+        },
+    };
     if payload.near_account_id.is_empty() {
         return VrfWorkerResponse::fail(message_id, "Missing NEAR account ID");
     }
 
+    let progress = VrfProgress::start();
     let (mut derivation_result, vrf_keypair) = {
         let manager_ref = manager.borrow();
-        match manager_ref.derive_vrf_keypair_from_prf(
+        match manager_ref.derive_vrf_keypair_from_prf_with_progress(
             prf_output,
             payload.near_account_id.clone(),
             payload.vrf_input_data.clone(),
+            &progress,
         ) {
             Ok((result, keypair)) => (result, keypair),
             Err(e) => {
@@ -132,6 +161,7 @@ pub async fn handle_derive_vrf_keypair_from_prf(
 
     match (relay_url, apply_server_lock_route) {
         (Some(relay_url), Some(apply_server_lock_route)) => {
+            progress.emit(VrfProgressPhase::ServerEncryption, Some(relay_url.as_str()));
             match perform_shamir3pass_client_encrypt_current_vrf_keypair(
                 manager.clone(),
                 relay_url,
@@ -159,6 +189,7 @@ pub async fn handle_derive_vrf_keypair_from_prf(
         encrypted_vrf_keypair: derivation_result.encrypted_vrf_keypair,
         server_encrypted_vrf_keypair: derivation_result.server_encrypted_vrf_keypair,
         success: derivation_result.success,
+        deterministic,
     };
 
     VrfWorkerResponse::success_from(message_id, Some(response_data))
@@ -0,0 +1,221 @@
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsValue;
+
+use crate::config::CHACHA20_KEY_SIZE;
+use crate::handlers::handle_mint_session_keys_and_send_to_signer::extract_prf_first_bytes_from_credential;
+use crate::handlers::handle_shamir3pass_client::{
+    perform_shamir3pass_client_encrypt_current_vrf_keypair, Shamir3PassEncryptVrfKeypairResult,
+};
+use crate::manager::VRFKeyManager;
+use crate::types::{EncryptedVRFKeypair, VRFChallengeData, VRFInputData, VrfWorkerResponse};
+use crate::utils::{base64_url_decode, base64_url_encode, generate_wrap_key_salt_b64u};
+
+/// Request payload for DEVICE2_COMPLETE_REGISTRATION.
+///
+/// Composes DERIVE_VRF_KEYPAIR_FROM_PRF, the Shamir 3-pass encryption and
+/// MINT_SESSION_KEYS_AND_SEND_TO_SIGNER for a freshly collected Device2 credential.
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Device2CompleteRegistrationRequest {
+    /// Session ID of the MessagePort the signer worker is waiting on
+    #[wasm_bindgen(getter_with_clone, js_name = "sessionId")]
+    #[serde(rename = "sessionId")]
+    pub session_id: String,
+    #[wasm_bindgen(getter_with_clone, js_name = "nearAccountId")]
+    #[serde(rename = "nearAccountId")]
+    pub near_account_id: String,
+    /// Registration credential carrying both PRF.first and PRF.second
+    #[wasm_bindgen(skip)]
+    #[serde(
+        rename = "credential",
+        default = "js_undefined",
+        with = "serde_wasm_bindgen::preserve"
+    )]
+    pub credential: JsValue,
+    /// Optional VRF inputs; when present a challenge is generated with the derived keypair
+    #[wasm_bindgen(getter_with_clone, js_name = "vrfInputData")]
+    #[serde(default, rename = "vrfInputData")]
+    pub vrf_input_data: Option<VRFInputData>,
+    /// Optional wrapKeySalt. If empty/null, VRF will generate a fresh one.
+    #[wasm_bindgen(getter_with_clone, js_name = "wrapKeySalt")]
+    #[serde(default, rename = "wrapKeySalt")]
+    pub wrap_key_salt_b64u: Option<String>,
+}
+
+fn js_undefined() -> JsValue {
+    JsValue::UNDEFINED
+}
+
+/// Result of DEVICE2_COMPLETE_REGISTRATION.
+/// WrapKeySeed and PRF.second are delivered to the signer via MessagePort, never returned.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Device2CompleteRegistrationResult {
+    pub session_id: String,
+    pub vrf_public_key: String,
+    pub vrf_challenge_data: Option<VRFChallengeData>,
+    pub encrypted_vrf_keypair: Option<EncryptedVRFKeypair>,
+    pub server_encrypted_vrf_keypair: Option<Shamir3PassEncryptVrfKeypairResult>,
+    pub wrap_key_salt: String,
+}
+
+/// Secret material bound for the signer worker's MessagePort.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub(crate) struct Device2SignerDelivery {
+    pub wrap_key_seed_b64u: String,
+    pub wrap_key_salt_b64u: String,
+    pub prf_second_b64u: String,
+}
+
+/// Derive, store and encrypt the deterministic VRF keypair, then derive the WrapKeySeed from it.
+///
+/// Produces the same values as DERIVE_VRF_KEYPAIR_FROM_PRF followed by
+/// MINT_SESSION_KEYS_AND_SEND_TO_SIGNER with the same credential.
+pub(crate) fn derive_device2_registration_material(
+    manager: &RefCell<VRFKeyManager>,
+    near_account_id: &str,
+    prf_first: &[u8],
+    prf_second: Vec<u8>,
+    vrf_input_data: Option<VRFInputData>,
+    wrap_key_salt_b64u: Option<String>,
+    now_ms: f64,
+) -> Result<(Device2CompleteRegistrationResult, Device2SignerDelivery), String> {
+    if near_account_id.is_empty() {
+        return Err("Missing NEAR account ID".to_string());
+    }
+    if prf_second.len() != CHACHA20_KEY_SIZE {
+        return Err("Invalid PRF output length: expected 32 bytes".to_string());
+    }
+    crate::webauthn::ensure_distinct_prf_outputs(prf_first, &prf_second)
+        .map_err(|e| e.to_string())?;
+    let prf_second_b64u = base64_url_encode(&prf_second);
+
+    let (derivation, vrf_keypair) = manager
+        .borrow()
+        .derive_vrf_keypair_from_prf(prf_second, near_account_id.to_string(), vrf_input_data)
+        .map_err(|e| e.to_string())?;
+    manager.borrow_mut().store_vrf_keypair_in_memory_at(
+        vrf_keypair,
+        near_account_id.to_string(),
+        now_ms,
+    );
+
+    let wrap_key_seed = manager
+        .borrow()
+        .derive_wrap_key_seed(prf_first)
+        .map_err(|e| e.to_string())?;
+    let wrap_key_salt_b64u = match wrap_key_salt_b64u.filter(|s| !s.trim().is_empty()) {
+        Some(salt) => salt,
+        None => generate_wrap_key_salt_b64u()?,
+    };
+
+    let delivery = Device2SignerDelivery {
+        wrap_key_seed_b64u: base64_url_encode(&wrap_key_seed),
+        wrap_key_salt_b64u: wrap_key_salt_b64u.clone(),
+        prf_second_b64u,
+    };
+    let result = Device2CompleteRegistrationResult {
+        session_id: String::new(),
+        vrf_public_key: derivation.vrf_public_key,
+        vrf_challenge_data: derivation.vrf_challenge_data,
+        encrypted_vrf_keypair: derivation.encrypted_vrf_keypair,
+        server_encrypted_vrf_keypair: None,
+        wrap_key_salt: wrap_key_salt_b64u,
+    };
+    Ok((result, delivery))
+}
+
+fn decode_prf_output(label: &str, b64u: Option<String>) -> Result<Vec<u8>, String> {
+    match b64u.as_deref().map(str::trim) {
+        Some(value) if !value.is_empty() => {
+            base64_url_decode(value).map_err(|e| format!("Failed to decode PRF.{}: {}", label, e))
+        }
+        _ => Err(format!("Missing PRF.{} in credential", label)),
+    }
+}
+
+/// Handle DEVICE2_COMPLETE_REGISTRATION message
+///
+/// Single-request replacement for the VRF half of Device2 linking:
+/// 1. Derive the deterministic VRF keypair from PRF.second and load it in memory
+/// 2. Encrypt it for IndexedDB and, when a relay is configured, via Shamir 3-pass
+/// 3. Derive WrapKeySeed from PRF.first + the new VRF secret key
+/// 4. Deliver WrapKeySeed + wrapKeySalt + PRF.second to the signer via MessagePort
+///
+/// The signer then completes with a single REGISTER_DEVICE2_WITH_DERIVED_KEY request.
+pub async fn handle_device2_complete_registration(
+    manager: Rc<RefCell<VRFKeyManager>>,
+    message_id: Option<String>,
+    request: Device2CompleteRegistrationRequest,
+) -> VrfWorkerResponse {
+    debug!(
+        "[VRF] Device2 complete registration for account {} session {}",
+        request.near_account_id, request.session_id
+    );
+
+    let fail = |msg: String| -> VrfWorkerResponse {
+        #[cfg(target_arch = "wasm32")]
+        crate::wrap_key_seed_port::send_wrap_key_seed_error_to_signer(&request.session_id, &msg);
+        VrfWorkerResponse::fail(message_id.clone(), msg)
+    };
+
+    let prf_first = match extract_prf_first_bytes_from_credential(&request.credential) {
+        Ok(bytes) => bytes,
+        Err(e) => return fail(format!("Device2 registration: {}", e)),
+    };
+    let prf_second = match decode_prf_output(
+        "second",
+        crate::webauthn::extract_prf_second_from_credential(&request.credential),
+    ) {
+        Ok(bytes) => bytes,
+        Err(e) => return fail(format!("Device2 registration: {}", e)),
+    };
+
+    let (mut result, delivery) = match derive_device2_registration_material(
+        &manager,
+        &request.near_account_id,
+        &prf_first,
+        prf_second,
+        request.vrf_input_data.clone(),
+        request.wrap_key_salt_b64u.clone(),
+        js_sys::Date::now(),
+    ) {
+        Ok(output) => output,
+        Err(e) => return fail(e),
+    };
+    result.session_id = request.session_id.clone();
+
+    let relay_url = manager.borrow().relay_server_url.clone();
+    let apply_server_lock_route = manager.borrow().apply_lock_route.clone();
+    if let (Some(relay_url), Some(apply_server_lock_route)) = (relay_url, apply_server_lock_route) {
+        match perform_shamir3pass_client_encrypt_current_vrf_keypair(
+            manager.clone(),
+            relay_url,
+            apply_server_lock_route,
+        )
+        .await
+        {
+            Ok(server_blob) => result.server_encrypted_vrf_keypair = Some(server_blob),
+            Err(e) => warn!("VRF keypair server encryption failed: {} (proceeding)", e),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    crate::wrap_key_seed_port::send_wrap_key_seed_to_signer(
+        &request.session_id,
+        &delivery.wrap_key_seed_b64u,
+        &delivery.wrap_key_salt_b64u,
+        Some(&delivery.prf_second_b64u),
+        None,
+        None,
+        crate::wrap_key_seed_port::SessionBinding::default(),
+    );
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = delivery;
+
+    VrfWorkerResponse::success_from(message_id, Some(result))
+}
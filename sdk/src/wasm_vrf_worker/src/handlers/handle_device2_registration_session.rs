@@ -1,8 +1,6 @@
-use hkdf::Hkdf;
 use log::debug;
 use serde::{Deserialize, Serialize};
 use serde_wasm_bindgen;
-use sha2::Sha256;
 use std::cell::RefCell;
 use std::rc::Rc;
 use wasm_bindgen::prelude::*;
@@ -11,10 +9,10 @@ use wasm_bindgen::JsValue;
 use crate::await_secure_confirmation::{
     vrf_await_secure_confirmation, Payload, RpcCall, SecureConfirmRequest, Summary,
 };
-use crate::errors::HkdfError;
+use crate::errors::VrfWorkerError;
 use crate::manager::VRFKeyManager;
 use crate::types::{VrfWorkerResponse, WorkerConfirmationResponse};
-use crate::utils::{base64_url_decode, generate_wrap_key_salt_b64u};
+use crate::utils::{base64_url_decode, generate_wrap_key_salt_b64u, B64UrlBytes};
 
 /// Request payload for combined Device2 registration session.
 /// This combines registration credential collection + WrapKeySeed derivation in a single flow.
@@ -59,6 +57,53 @@ pub struct Device2RegistrationSessionRequest {
     #[wasm_bindgen(getter_with_clone, js_name = "wrapKeySalt")]
     #[serde(rename = "wrapKeySalt")]
     pub wrap_key_salt_b64u: Option<String>,
+
+    /// Optional Device1 account card, as returned by the signer worker's VERIFY_ACCOUNT_CARD.
+    /// When present, registration fails unless it describes this account and device number.
+    #[wasm_bindgen(skip)]
+    #[serde(rename = "accountCard", default)]
+    pub account_card: Option<VerifiedAccountCard>,
+}
+
+/// Fields of a signature-checked account card scanned from Device1's linking QR code.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifiedAccountCard {
+    pub near_account_id: String,
+    pub near_public_key: String,
+    /// `Encoding::B64Url` on the wire, as the signer worker's `AccountCard` emits it.
+    pub vrf_public_key_raw_b64u: B64UrlBytes,
+    pub device_number: u32,
+    pub issued_at_ms: u64,
+    pub expires_at_ms: u64,
+}
+
+/// Cross-check a verified account card against the registration parameters, and re-check its
+/// expiry in case it was verified well before the user confirmed.
+pub fn check_account_card_for_registration(
+    card: &VerifiedAccountCard,
+    near_account_id: &str,
+    device_number: u32,
+    now_ms: f64,
+) -> Result<(), VrfWorkerError> {
+    if card.near_account_id != near_account_id {
+        return Err(VrfWorkerError::AccountCardMismatch(format!(
+            "card is for account {}, registering {}",
+            card.near_account_id, near_account_id
+        )));
+    }
+    if card.device_number != device_number {
+        return Err(VrfWorkerError::AccountCardMismatch(format!(
+            "card assigns device number {}, registering device {}",
+            card.device_number, device_number
+        )));
+    }
+    // Written so that a NaN clock counts as expired.
+    let live = now_ms < card.expires_at_ms as f64;
+    if !live {
+        return Err(VrfWorkerError::AccountCardExpired(card.expires_at_ms));
+    }
+    Ok(())
 }
 
 /// Result for combined Device2 registration session.
@@ -130,6 +175,18 @@ pub async fn handle_device2_registration_session(
         near_account_id, device_number, session_id
     );
 
+    if let Some(card) = request.account_card.as_ref() {
+        if let Err(e) = check_account_card_for_registration(
+            card,
+            &near_account_id,
+            device_number,
+            js_sys::Date::now(),
+        ) {
+            debug!("[VRF] Rejecting Device2 registration: {}", e);
+            return VrfWorkerResponse::fail(message_id, e.to_string());
+        }
+    }
+
     // === STEP 1: Build SecureConfirmRequest for Device2 registration ===
 
     let request_id = message_id
@@ -142,6 +199,7 @@ pub async fn handle_device2_registration_session(
         request_type: "registerAccount",
         summary: Summary {
             nearAccountId: &near_account_id,
+            nearAccountIdDisplay: crate::utils::implicit_account_display(&near_account_id),
             deviceNumber: device_number,
             contractId: Some(&request.contract_id),
         },
@@ -271,6 +329,13 @@ pub async fn handle_device2_registration_session(
         }
     };
 
+    if let Err(e) =
+        crate::webauthn::ensure_distinct_prf_outputs(&prf_first_bytes, &prf_second_bytes)
+    {
+        debug!("[VRF] Rejecting Device2 credential: {}", e);
+        return VrfWorkerResponse::fail(message_id, format!("Device2 registration: {}", e));
+    }
+
     // === STEP 4: Derive deterministic VRF keypair from PRF.second ===
     // This is the deterministic VRF public key that should be registered with the contract
     let deterministic_vrf_keypair = match manager
@@ -335,38 +400,17 @@ pub async fn handle_device2_registration_session(
         session_id
     );
 
-    // Derive K_pass_auth = HKDF(PRF.first, "vrf-wrap-pass")
-    let hk = Hkdf::<Sha256>::new(None, &prf_first_bytes);
-    let mut k_pass_auth = vec![0u8; 32];
-    if let Err(_e) = hk.expand(crate::config::VRF_WRAP_PASS_INFO, &mut k_pass_auth) {
-        return VrfWorkerResponse::fail(message_id, HkdfError::KeyDerivationFailed.to_string());
-    }
-
-    // Get VRF secret key bytes from current in-memory keypair
-    let vrf_secret = match manager.borrow().get_vrf_secret_key_bytes() {
-        Ok(sk) => sk,
+    // Derive WrapKeySeed from PRF.first and the deterministic VRF keypair just stored
+    let wrap_key_seed = match manager.borrow().derive_wrap_key_seed(&prf_first_bytes) {
+        Ok(seed) => seed,
         Err(e) => {
             return VrfWorkerResponse::fail(
                 message_id,
-                format!(
-                    "Failed to get VRF secret key for Device2 WrapKeySeed derivation: {}",
-                    e
-                ),
+                format!("Failed to derive Device2 WrapKeySeed: {}", e),
             )
         }
     };
 
-    // Derive WrapKeySeed = HKDF(K_pass_auth || vrf_sk, "near-wrap-seed")
-    let mut seed = Vec::with_capacity(k_pass_auth.len() + vrf_secret.len());
-    seed.extend_from_slice(&k_pass_auth);
-    seed.extend_from_slice(&vrf_secret);
-
-    let hk2 = Hkdf::<Sha256>::new(None, &seed);
-    let mut wrap_key_seed = vec![0u8; 32];
-    if let Err(_e) = hk2.expand(crate::config::NEAR_WRAP_SEED_INFO, &mut wrap_key_seed) {
-        return VrfWorkerResponse::fail(message_id, HkdfError::KeyDerivationFailed.to_string());
-    }
-
     // Determine wrapKeySalt: use provided or generate fresh
     let wrap_key_salt_b64u = if let Some(salt) = request.wrap_key_salt_b64u {
         if salt.trim().is_empty() {
@@ -399,8 +443,13 @@ pub async fn handle_device2_registration_session(
             &wrap_key_seed_b64u,
             &wrap_key_salt_b64u,
             Some(&prf_second_b64u),
+            None,
+            None,
+            crate::wrap_key_seed_port::SessionBinding::default(),
         );
     }
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = wrap_key_seed;
 
     // === STEP 5: Return credential + session metadata to JS ===
     // Note: credential still contains PRF.second for signer worker to use for NEAR key derivation
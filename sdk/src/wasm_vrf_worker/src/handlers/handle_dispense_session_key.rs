@@ -23,6 +23,10 @@ pub struct DispenseSessionKeyRequest {
     #[wasm_bindgen(getter_with_clone, js_name = "uses")]
     #[serde(rename = "uses")]
     pub uses: Option<u32>,
+    /// Optional nonce the signer worker issued for this session; echoed with the WrapKeySeed.
+    #[wasm_bindgen(getter_with_clone, js_name = "sessionBindingNonce")]
+    #[serde(rename = "sessionBindingNonce", default)]
+    pub session_binding_nonce: Option<String>,
 }
 
 pub async fn handle_dispense_session_key(
@@ -51,7 +55,14 @@ pub async fn handle_dispense_session_key(
     let now_ms = js_sys::Date::now();
     let uses = request.uses.unwrap_or(1);
 
-    let (_wrap_key_seed_b64u, _wrap_key_salt_b64u, remaining_uses, expires_at_ms) = {
+    let (
+        _wrap_key_seed_b64u,
+        _wrap_key_salt_b64u,
+        _contract_verification,
+        _key_reuse_policy,
+        remaining_uses,
+        expires_at_ms,
+    ) = {
         let mut mgr = manager.borrow_mut();
         let (seed_b64u, salt_b64u) =
             match mgr.dispense_session_key(&request.session_id, uses, now_ms) {
@@ -66,12 +77,26 @@ pub async fn handle_dispense_session_key(
                     return VrfWorkerResponse::fail(message_id, e.to_string());
                 }
             };
-        let (remaining_uses, expires_at_ms) = mgr
+        let (contract_verification, key_reuse_policy, remaining_uses, expires_at_ms) = mgr
             .sessions
             .get(&request.session_id)
-            .map(|s| (s.remaining_uses, s.expires_at_ms))
-            .unwrap_or((None, None));
-        (seed_b64u, salt_b64u, remaining_uses, expires_at_ms)
+            .map(|s| {
+                (
+                    s.contract_verification.clone(),
+                    s.key_reuse_policy,
+                    s.remaining_uses,
+                    s.expires_at_ms,
+                )
+            })
+            .unwrap_or((None, None, None, None));
+        (
+            seed_b64u,
+            salt_b64u,
+            contract_verification,
+            key_reuse_policy,
+            remaining_uses,
+            expires_at_ms,
+        )
     };
 
     // Deliver WrapKeySeed + wrapKeySalt to the signer worker via the attached MessagePort.
@@ -82,6 +107,12 @@ pub async fn handle_dispense_session_key(
             &_wrap_key_seed_b64u,
             &_wrap_key_salt_b64u,
             None,
+            _contract_verification.as_ref(),
+            _key_reuse_policy.as_ref(),
+            crate::wrap_key_seed_port::SessionBinding {
+                session_binding_nonce: request.session_binding_nonce.as_deref(),
+                intent_digest: None,
+            },
         );
         port.close();
     }
@@ -1,4 +1,6 @@
+use crate::errors::{VrfResult, VrfWorkerError};
 use crate::manager::VRFKeyManager;
+use crate::rpc_calls::{refresh_block_context, BlockFetcher, RpcBlockFetcher};
 use crate::types::VRFInputData;
 use crate::types::VrfWorkerResponse;
 use log::{debug, error};
@@ -22,14 +24,34 @@ pub struct GenerateVrfChallengeRequest {
 }
 
 /// Handle GENERATE_VRF_CHALLENGE message
-pub fn handle_generate_vrf_challenge(
+pub async fn handle_generate_vrf_challenge(
     manager: Rc<RefCell<VRFKeyManager>>,
     message_id: Option<String>,
     payload: GenerateVrfChallengeRequest,
 ) -> VrfWorkerResponse {
+    // Locked is reported before any block context fetch so the UI can prompt an unlock.
+    if !manager.borrow().session_active {
+        return VrfWorkerResponse::fail_with_code(message_id, &VrfWorkerError::VrfNotUnlocked);
+    }
+
+    let vrf_input_data = match resolve_block_context(
+        &manager,
+        &RpcBlockFetcher,
+        payload.vrf_input_data,
+        js_sys::Date::now(),
+    )
+    .await
+    {
+        Ok(input) => input,
+        Err(e) => {
+            error!("VRF challenge generation failed: {}", e);
+            return VrfWorkerResponse::fail_with_code(message_id, &e);
+        }
+    };
+
     let mut manager_ref = manager.borrow_mut();
 
-    match manager_ref.generate_vrf_challenge(payload.vrf_input_data) {
+    match manager_ref.generate_vrf_challenge(vrf_input_data) {
         Ok(challenge_data) => {
             debug!("VRF challenge generated successfully");
             // Cache VRF challenge for this session if a sessionId was provided so later
@@ -43,7 +65,118 @@ pub fn handle_generate_vrf_challenge(
         }
         Err(e) => {
             error!("VRF challenge generation failed: {}", e);
-            VrfWorkerResponse::fail(message_id, e.to_string())
+            VrfWorkerResponse::fail_with_code(message_id, &e)
         }
     }
 }
+
+/// GENERATE_VRF_CHALLENGES payload: several challenges on one block context, one per digest.
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateVrfChallengesRequest {
+    /// Session whose use budget the minted challenges are charged to.
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Shared VRF input; `intentDigest` must be omitted.
+    pub base: VRFInputData,
+    pub intent_digests: Vec<String>,
+    /// Lowers the batch cap (`VRF_CHALLENGE_BATCH_MAX_COUNT`).
+    #[serde(default)]
+    pub max_count: Option<usize>,
+}
+
+/// Handle GENERATE_VRF_CHALLENGES message
+///
+/// The block context is resolved once, so every challenge in the batch binds the same block.
+/// Challenges are not cached per session: each is self-contained for contract verification.
+pub async fn handle_generate_vrf_challenges(
+    manager: Rc<RefCell<VRFKeyManager>>,
+    message_id: Option<String>,
+    payload: GenerateVrfChallengesRequest,
+) -> VrfWorkerResponse {
+    if !manager.borrow().session_active {
+        return VrfWorkerResponse::fail_with_code(message_id, &VrfWorkerError::VrfNotUnlocked);
+    }
+
+    let now_ms = js_sys::Date::now();
+    let base = match resolve_block_context(&manager, &RpcBlockFetcher, payload.base, now_ms).await {
+        Ok(input) => input,
+        Err(e) => {
+            error!("VRF challenge batch failed: {}", e);
+            return VrfWorkerResponse::fail_with_code(message_id, &e);
+        }
+    };
+
+    let result = manager.borrow_mut().generate_vrf_challenges(
+        base,
+        &payload.intent_digests,
+        payload.max_count,
+        payload.session_id.as_deref(),
+        now_ms,
+    );
+    match result {
+        Ok(batch) => {
+            debug!(
+                "VRF challenge batch: minted {} of {}",
+                batch.minted,
+                batch.challenges.len()
+            );
+            VrfWorkerResponse::success_from(message_id, Some(batch))
+        }
+        Err(e) => {
+            error!("VRF challenge batch failed: {}", e);
+            VrfWorkerResponse::fail_with_code(message_id, &e)
+        }
+    }
+}
+
+/// Fill in `blockHeight`/`blockHash` when the caller omitted both.
+///
+/// Explicit values always win. Otherwise the cached block context is used while it is
+/// within `max_staleness_ms`, and refreshed from the configured source when it is not.
+/// A failed refresh is an error: the challenge is never bound to stale block data.
+pub(crate) async fn resolve_block_context<F: BlockFetcher>(
+    manager: &Rc<RefCell<VRFKeyManager>>,
+    fetcher: &F,
+    mut input: VRFInputData,
+    now_ms: f64,
+) -> VrfResult<VRFInputData> {
+    let has_height = !input.block_height.trim().is_empty();
+    let has_hash = !input.block_hash.trim().is_empty();
+    match (has_height, has_hash) {
+        (true, true) => return Ok(input),
+        (true, false) | (false, true) => {
+            return Err(VrfWorkerError::invalid_format(
+                "blockHeight and blockHash must be provided together or both omitted",
+            ));
+        }
+        (false, false) => {}
+    }
+
+    // Copy what we need out of the manager: no borrow may be held across the fetch.
+    let (source, cached) = {
+        let mgr = manager.borrow();
+        (mgr.block_context_source.clone(), mgr.block_context.clone())
+    };
+    let source = source.ok_or_else(|| {
+        VrfWorkerError::BlockContextUnavailable(
+            "blockHeight/blockHash omitted and no block context source is configured".to_string(),
+        )
+    })?;
+
+    let context = match cached.filter(|ctx| ctx.is_fresh(now_ms, source.max_staleness_ms)) {
+        Some(ctx) => ctx,
+        None => {
+            let headers = manager.borrow().default_rpc_headers.clone();
+            let ctx = refresh_block_context(fetcher, &source.near_rpc_url, &headers, now_ms)
+                .await
+                .map_err(VrfWorkerError::BlockContextUnavailable)?;
+            manager.borrow_mut().block_context = Some(ctx.clone());
+            ctx
+        }
+    };
+
+    input.block_height = context.height.to_string();
+    input.block_hash = context.hash;
+    Ok(input)
+}
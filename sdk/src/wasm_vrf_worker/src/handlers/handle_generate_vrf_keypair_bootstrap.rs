@@ -1,6 +1,7 @@
 use crate::manager::VRFKeyManager;
 use crate::types::VRFInputData;
 use crate::types::VrfWorkerResponse;
+use crate::utils::parse_deterministic_seed_b64u;
 use log::{debug, error};
 use serde::{Deserialize, Serialize};
 use serde_wasm_bindgen;
@@ -20,6 +21,11 @@ pub struct GenerateVrfKeypairBootstrapRequest {
     #[wasm_bindgen(getter_with_clone, js_name = "vrfInputData")]
     #[serde(rename = "vrfInputData")]
     pub vrf_input_data: Option<VRFInputData>,
+    /// Dev-only seed (base64url) for reproducible VRF keypairs on localnet.
+    /// Rejected unless built with the `dev_deterministic_vrf` feature.
+    #[wasm_bindgen(getter_with_clone, js_name = "deterministicSeedB64u")]
+    #[serde(default, rename = "deterministicSeedB64u")]
+    pub deterministic_seed_b64u: Option<String>,
 }
 
 /// Handle GENERATE_VRF_KEYPAIR_BOOTSTRAP message
@@ -28,10 +34,18 @@ pub fn handle_generate_vrf_keypair_bootstrap(
     message_id: Option<String>,
     payload: GenerateVrfKeypairBootstrapRequest,
 ) -> VrfWorkerResponse {
+    let deterministic_seed =
+        match parse_deterministic_seed_b64u(payload.deterministic_seed_b64u.as_deref()) {
+            Ok(seed) => seed,
+            Err(e) => return VrfWorkerResponse::fail(message_id, e),
+        };
+
     let mut manager_mut = manager.borrow_mut();
     debug!("Generating bootstrap VRF keypair");
 
-    match manager_mut.generate_vrf_keypair_bootstrap(payload.vrf_input_data) {
+    match manager_mut
+        .generate_vrf_keypair_bootstrap(payload.vrf_input_data, deterministic_seed.as_deref())
+    {
         Ok(bootstrap_data) => {
             debug!("VRF keypair bootstrap completed successfully");
             // Cache VRF challenge for this session if present so future contract
@@ -48,11 +62,14 @@ pub fn handle_generate_vrf_keypair_bootstrap(
                 vrf_public_key: &'a str,
                 #[serde(skip_serializing_if = "Option::is_none")]
                 vrf_challenge_data: Option<&'a crate::types::VRFChallengeData>,
+                #[serde(skip_serializing_if = "std::ops::Not::not")]
+                deterministic: bool,
             }
 
             let response = BootstrapResponse {
                 vrf_public_key: &bootstrap_data.vrf_public_key,
                 vrf_challenge_data: bootstrap_data.vrf_challenge_data.as_ref(),
+                deterministic: bootstrap_data.deterministic,
             };
 
             let response_js =
@@ -1,14 +1,14 @@
-use hkdf::Hkdf;
 use log::debug;
-use sha2::Sha256;
 use wasm_bindgen::prelude::*;
 
-use crate::errors::HkdfError;
-use crate::manager::{VRFKeyManager, VrfSessionData};
+use crate::manager::{KeyReusePolicy, VRFKeyManager, VrfSessionData};
 use crate::rpc_calls::{
     verify_authentication_response_rpc_call, VrfData, WebAuthnAuthenticationCredential,
 };
+use crate::rpc_headers::{merge_rpc_headers, RpcHeaders};
+use crate::session_policy::resolve_session_policy_digest;
 use crate::types::VrfWorkerResponse;
+use crate::verification_cache::{ContractVerificationFacts, VerificationCacheKey};
 #[cfg(target_arch = "wasm32")]
 use crate::utils::base64_url_decode;
 use crate::utils::generate_wrap_key_salt_b64u;
@@ -40,7 +40,7 @@ fn extract_prf_second_bytes_from_credential(
         })
 }
 
-fn extract_prf_first_bytes_from_credential(credential: &JsValue) -> Result<Vec<u8>, String> {
+pub(crate) fn extract_prf_first_bytes_from_credential(credential: &JsValue) -> Result<Vec<u8>, String> {
     #[cfg(target_arch = "wasm32")]
     {
         if credential.is_null() || credential.is_undefined() {
@@ -86,16 +86,17 @@ pub(crate) async fn verify_authentication_if_needed(
     message_id: &Option<String>,
     contract_id: &Option<String>,
     rpc_url: &Option<String>,
+    rpc_headers: Option<&RpcHeaders>,
     session_id: &str,
     credential: &JsValue,
-) -> Result<(), VrfWorkerResponse> {
+) -> Result<Option<ContractVerificationFacts>, VrfWorkerResponse> {
     let (Some(contract_id), Some(rpc_url)) = (contract_id.as_ref(), rpc_url.as_ref()) else {
-        return Ok(());
+        return Ok(None);
     };
 
     if credential.is_null() || credential.is_undefined() {
         // Preserve existing behavior: skip verification when credential is absent.
-        return Ok(());
+        return Ok(None);
     }
 
     let Some(auth_credential) = as_authentication_credential(credential).map_err(|e| {
@@ -106,7 +107,7 @@ pub(crate) async fn verify_authentication_if_needed(
     })?
     else {
         debug!("[VRF] Skipping contract verification for non-authentication credential");
-        return Ok(());
+        return Ok(None);
     };
 
     // Look up the VRF challenge for this session from the worker-owned cache.
@@ -132,6 +133,29 @@ pub(crate) async fn verify_authentication_if_needed(
         ));
     }
 
+    // Reuse a recent verification of the same credential + VRF output + contract, but only
+    // when that verified challenge already bound this intent digest.
+    let cache_key = VerificationCacheKey {
+        credential_id: auth_credential.id.clone(),
+        vrf_output: challenge.vrf_output.clone(),
+        contract_id: contract_id.clone(),
+    };
+    let intent_digest = challenge.intent_digest.clone().unwrap_or_default();
+    let now_ms = js_sys::Date::now();
+    let cached =
+        manager
+            .borrow_mut()
+            .verification_cache
+            .verified_facts(&cache_key, &intent_digest, now_ms);
+    if let Some(facts) = cached {
+        debug!(
+            "[VRF] Reusing cached contract verification for session {}",
+            session_id
+        );
+        manager.borrow_mut().clear_challenge(session_id);
+        return Ok(Some(facts));
+    }
+
     let vrf_data = match VrfData::try_from(&challenge) {
         Ok(data) => {
             let vrf_pk_b64u = crate::utils::base64_url_encode(&data.public_key);
@@ -153,8 +177,16 @@ pub(crate) async fn verify_authentication_if_needed(
         }
     };
 
-    match verify_authentication_response_rpc_call(contract_id, rpc_url, vrf_data, auth_credential)
-        .await
+    let rpc_headers = merge_rpc_headers(&manager.borrow().default_rpc_headers, rpc_headers);
+
+    let block_height = match verify_authentication_response_rpc_call(
+        contract_id,
+        rpc_url,
+        &rpc_headers,
+        vrf_data,
+        auth_credential,
+    )
+    .await
     {
         Ok(result) => {
             if !result.success || !result.verified {
@@ -163,6 +195,7 @@ pub(crate) async fn verify_authentication_if_needed(
                     .unwrap_or_else(|| "Contract verification failed".to_string());
                 return Err(VrfWorkerResponse::fail(message_id.clone(), err_msg));
             }
+            result.block_height
         }
         Err(e) => {
             return Err(VrfWorkerResponse::fail(
@@ -170,13 +203,26 @@ pub(crate) async fn verify_authentication_if_needed(
                 format!("verify_authentication_response RPC failed: {}", e),
             ))
         }
-    }
+    };
 
-    // This VRF challenge is one-time-use. Clear it after a successful verification so
-    // stale challenges can't linger and break later session refreshes.
-    manager.borrow_mut().clear_challenge(session_id);
+    let verified_at_ms = js_sys::Date::now();
+    let facts = ContractVerificationFacts {
+        vrf_output: cache_key.vrf_output.clone(),
+        credential_id: cache_key.credential_id.clone(),
+        contract_id: cache_key.contract_id.clone(),
+        verified_at_ms,
+        block_height,
+    };
+    {
+        let mut mgr = manager.borrow_mut();
+        mgr.verification_cache
+            .insert(cache_key, vec![intent_digest], block_height, verified_at_ms);
+        // This VRF challenge is one-time-use. Clear it after a successful verification so
+        // stale challenges can't linger and break later session refreshes.
+        mgr.clear_challenge(session_id);
+    }
 
-    Ok(())
+    Ok(Some(facts))
 }
 
 #[wasm_bindgen]
@@ -196,6 +242,11 @@ pub struct MintSessionKeysAndSendToSignerRequest {
     #[wasm_bindgen(getter_with_clone, js_name = "nearRpcUrl")]
     #[serde(rename = "nearRpcUrl")]
     pub near_rpc_url: Option<String>,
+    /// Optional extra NEAR RPC headers (e.g. provider API keys). Values are redacted in
+    /// logs/errors and override CONFIGURE_RPC defaults with the same name.
+    #[wasm_bindgen(skip)]
+    #[serde(rename = "rpcHeaders", default)]
+    pub rpc_headers: Option<RpcHeaders>,
     /// Optional signing-session TTL in milliseconds.
     /// When omitted, VRF_SESSION_DEFAULT_TTL_MS is used.
     #[wasm_bindgen(getter_with_clone, js_name = "ttlMs")]
@@ -207,6 +258,34 @@ pub struct MintSessionKeysAndSendToSignerRequest {
     #[wasm_bindgen(getter_with_clone, js_name = "remainingUses")]
     #[serde(rename = "remainingUses")]
     pub remaining_uses: Option<u32>,
+    /// Optional ceiling on the session's total lifetime across RENEW_SESSION calls.
+    /// Clamped to VRF_SESSION_MAX_LIFETIME_MS, which is also the default.
+    #[wasm_bindgen(getter_with_clone, js_name = "maxLifetimeMs")]
+    #[serde(rename = "maxLifetimeMs", default)]
+    pub max_lifetime_ms: Option<u32>,
+    /// Optional session policy digest (base64url); RENEW_SESSION must present the same value.
+    #[wasm_bindgen(getter_with_clone, js_name = "sessionPolicyDigest")]
+    #[serde(rename = "sessionPolicyDigest", default)]
+    pub session_policy_digest: Option<String>,
+    /// Optional session policy JSON; the canonical digest is computed from it and must match
+    /// `sessionPolicyDigest` when both are sent.
+    #[wasm_bindgen(getter_with_clone, js_name = "sessionPolicyJson")]
+    #[serde(rename = "sessionPolicyJson", default)]
+    pub session_policy_json: Option<String>,
+    /// Optional policy for reusing one decrypted signing key across the session's signing
+    /// operations. Forwarded to the signer worker with the WrapKeySeed; absent means no reuse.
+    #[wasm_bindgen(skip)]
+    #[serde(rename = "keyReusePolicy", default)]
+    pub key_reuse_policy: Option<KeyReusePolicy>,
+    /// Optional nonce the signer worker issued for this session (`get_session_binding_nonce`).
+    /// Echoed with the WrapKeySeed so the signer can refuse material sent down the wrong port.
+    #[wasm_bindgen(getter_with_clone, js_name = "sessionBindingNonce")]
+    #[serde(rename = "sessionBindingNonce", default)]
+    pub session_binding_nonce: Option<String>,
+    /// Optional intent digest the session was confirmed for; echoed alongside the nonce.
+    #[wasm_bindgen(getter_with_clone, js_name = "intentDigest")]
+    #[serde(rename = "intentDigest", default)]
+    pub intent_digest: Option<String>,
     /// Optional WebAuthn credential (registration or authentication) for PRF.second extraction.
     /// PRF extension results are intentionally omitted when forwarding to RPC, so
     /// any PRF outputs present in the JS object are not sent over the network.
@@ -239,32 +318,44 @@ pub async fn handle_mint_session_keys_and_send_to_signer(
         VrfWorkerResponse::fail(message_id.clone(), msg)
     };
 
+    let policy_digest = match resolve_session_policy_digest(
+        request.session_policy_json.as_deref(),
+        request.session_policy_digest.as_deref(),
+    ) {
+        Ok(digest) => digest,
+        Err(e) => return fail(e.to_string()),
+    };
+
     // If contract verification context is provided, perform verify_authentication_response
     // before deriving WrapKeySeed. This ensures that only contract-verified sessions
     // receive WrapKeySeed material.
-    if let Err(resp) = verify_authentication_if_needed(
+    let contract_verification = match verify_authentication_if_needed(
         manager.clone(),
         &message_id,
         &request.contract_id,
         &request.near_rpc_url,
+        request.rpc_headers.as_ref(),
         &request.session_id,
         &request.credential,
     )
     .await
     {
-        #[cfg(target_arch = "wasm32")]
-        {
-            let err = resp
-                .error
-                .clone()
-                .unwrap_or_else(|| "VRF mint session keys failed".to_string());
-            crate::wrap_key_seed_port::send_wrap_key_seed_error_to_signer(
-                &request.session_id,
-                &err,
-            );
+        Ok(facts) => facts,
+        Err(resp) => {
+            #[cfg(target_arch = "wasm32")]
+            {
+                let err = resp
+                    .error
+                    .clone()
+                    .unwrap_or_else(|| "VRF mint session keys failed".to_string());
+                crate::wrap_key_seed_port::send_wrap_key_seed_error_to_signer(
+                    &request.session_id,
+                    &err,
+                );
+            }
+            return resp;
         }
-        return resp;
-    }
+    };
 
     // Determine which wrapKeySalt to use:
     // - If caller provided a non-empty wrapKeySalt (e.g., existing vault entry), honor it.
@@ -284,30 +375,41 @@ pub async fn handle_mint_session_keys_and_send_to_signer(
         Err(e) => return fail(e),
     };
 
-    // Derive K_pass_auth = HKDF(PRF.first_auth, "vrf-wrap-pass")
-    let hk = Hkdf::<Sha256>::new(None, &prf_first_bytes);
-    let mut k_pass_auth = vec![0u8; 32];
-    if let Err(_e) = hk.expand(crate::config::VRF_WRAP_PASS_INFO, &mut k_pass_auth) {
-        return fail(HkdfError::KeyDerivationFailed.to_string());
-    }
+    // === STEP: Extract PRF.second from credential if present ===
+    // If credential is provided, extract PRF.second for NEAR key derivation in signer worker.
+    // Done before any session material is cached so a misconfigured credential leaves no state.
+    #[cfg(target_arch = "wasm32")]
+    let prf_second_b64u = if !request.credential.is_null() && !request.credential.is_undefined() {
+        match extract_prf_second_bytes_from_credential(&request.credential) {
+            Ok(Some(prf_second_bytes)) => {
+                if let Err(e) = crate::webauthn::ensure_distinct_prf_outputs(
+                    &prf_first_bytes,
+                    &prf_second_bytes,
+                ) {
+                    return fail(e.to_string());
+                }
+                debug!(
+                    "[VRF] Extracted PRF.second ({} bytes) from credential",
+                    prf_second_bytes.len()
+                );
+                Some(crate::utils::base64_url_encode(&prf_second_bytes))
+            }
+            Ok(None) => {
+                debug!("[VRF] PRF.second not present in credential");
+                None
+            }
+            Err(e) => return fail(e),
+        }
+    } else {
+        None
+    };
 
-    // Get VRF secret key bytes from the current in-memory keypair
-    let vrf_secret = match manager.borrow().get_vrf_secret_key_bytes() {
-        Ok(sk) => sk,
+    // Derive WrapKeySeed from PRF.first_auth and the current in-memory VRF keypair
+    let wrap_key_seed = match manager.borrow().derive_wrap_key_seed(&prf_first_bytes) {
+        Ok(seed) => seed,
         Err(e) => return fail(e.to_string()),
     };
 
-    // Derive WrapKeySeed = HKDF(K_pass_auth || vrf_sk, "near-wrap-seed")
-    let mut seed = Vec::with_capacity(k_pass_auth.len() + vrf_secret.len());
-    seed.extend_from_slice(&k_pass_auth);
-    seed.extend_from_slice(&vrf_secret);
-
-    let hk2 = Hkdf::<Sha256>::new(None, &seed);
-    let mut wrap_key_seed = vec![0u8; 32];
-    if let Err(_e) = hk2.expand(crate::config::NEAR_WRAP_SEED_INFO, &mut wrap_key_seed) {
-        return fail(HkdfError::KeyDerivationFailed.to_string());
-    }
-
     // Cache VRF-owned session material for reuse (TTL/uses enforced on dispense).
     // This does not expose WrapKeySeed to the main thread; it remains in VRF worker memory.
     {
@@ -322,6 +424,11 @@ pub async fn handle_mint_session_keys_and_send_to_signer(
                 .remaining_uses
                 .unwrap_or(crate::config::VRF_SESSION_DEFAULT_MAX_USES),
         );
+        let max_lifetime_ms = request
+            .max_lifetime_ms
+            .map(|v| v as u64)
+            .unwrap_or(crate::config::VRF_SESSION_MAX_LIFETIME_MS)
+            .min(crate::config::VRF_SESSION_MAX_LIFETIME_MS);
         manager.borrow_mut().upsert_session(
             &request.session_id,
             VrfSessionData {
@@ -330,32 +437,14 @@ pub async fn handle_mint_session_keys_and_send_to_signer(
                 created_at_ms: now_ms,
                 expires_at_ms,
                 remaining_uses,
+                renewable_until_ms: Some(now_ms + (max_lifetime_ms as f64)),
+                policy_digest,
+                contract_verification: contract_verification.clone(),
+                key_reuse_policy: request.key_reuse_policy,
             },
         );
     }
 
-    // === STEP: Extract PRF.second from credential if present ===
-    // If credential is provided, extract PRF.second for NEAR key derivation in signer worker
-    #[cfg(target_arch = "wasm32")]
-    let prf_second_b64u = if !request.credential.is_null() && !request.credential.is_undefined() {
-        match extract_prf_second_bytes_from_credential(&request.credential) {
-            Ok(Some(prf_second_bytes)) => {
-                debug!(
-                    "[VRF] Extracted PRF.second ({} bytes) from credential",
-                    prf_second_bytes.len()
-                );
-                Some(crate::utils::base64_url_encode(&prf_second_bytes))
-            }
-            Ok(None) => {
-                debug!("[VRF] PRF.second not present in credential");
-                None
-            }
-            Err(e) => return fail(e),
-        }
-    } else {
-        None
-    };
-
     // Deliver WrapKeySeed + wrapKeySalt + PRF.second to the signer worker via the attached MessagePort
     #[cfg(target_arch = "wasm32")]
     {
@@ -365,6 +454,12 @@ pub async fn handle_mint_session_keys_and_send_to_signer(
             &wrap_key_seed_b64u,
             &wrap_key_salt_b64u,
             prf_second_b64u.as_deref(),
+            contract_verification.as_ref(),
+            request.key_reuse_policy.as_ref(),
+            crate::wrap_key_seed_port::SessionBinding {
+                session_binding_nonce: request.session_binding_nonce.as_deref(),
+                intent_digest: request.intent_digest.as_deref(),
+            },
         );
     }
 
@@ -97,6 +97,7 @@ pub async fn handle_registration_credential_confirmation(
         request_type: "registerAccount",
         summary: Summary {
             nearAccountId: &near_account_id,
+            nearAccountIdDisplay: crate::utils::implicit_account_display(&near_account_id),
             deviceNumber: device_number,
             contractId: if request.contract_id.is_empty() {
                 None
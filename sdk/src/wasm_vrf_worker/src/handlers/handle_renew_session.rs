@@ -0,0 +1,93 @@
+use crate::config::VRF_SESSION_DEFAULT_TTL_MS;
+use crate::manager::VRFKeyManager;
+use crate::session_policy::resolve_session_policy_digest;
+use crate::types::VrfWorkerResponse;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+/// Request payload for extending a live VRF-owned session without a WebAuthn prompt.
+///
+/// The cached WrapKeySeed and remaining uses are kept; only the expiry moves, and never
+/// past the session's `renewableUntilMs`.
+#[wasm_bindgen]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RenewSessionRequest {
+    #[wasm_bindgen(getter_with_clone, js_name = "sessionId")]
+    #[serde(rename = "sessionId")]
+    pub session_id: String,
+    /// Optional extension (ms from now). When omitted, VRF_SESSION_DEFAULT_TTL_MS is used.
+    #[wasm_bindgen(getter_with_clone, js_name = "ttlMs")]
+    #[serde(rename = "ttlMs", default)]
+    pub ttl_ms: Option<u32>,
+    /// Currently configured session policy digest (base64url); must match the minted one.
+    #[wasm_bindgen(getter_with_clone, js_name = "sessionPolicyDigest")]
+    #[serde(rename = "sessionPolicyDigest", default)]
+    pub session_policy_digest: Option<String>,
+    /// Currently configured session policy JSON; hashed canonically in place of (or checked
+    /// against) `sessionPolicyDigest`.
+    #[wasm_bindgen(getter_with_clone, js_name = "sessionPolicyJson")]
+    #[serde(rename = "sessionPolicyJson", default)]
+    pub session_policy_json: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenewSessionResult {
+    pub session_id: String,
+    pub expires_at_ms: f64,
+    pub remaining_ms: f64,
+    pub renewable_until_ms: Option<f64>,
+}
+
+/// Renew a session at `now_ms`; shared by the handler and native tests.
+pub fn renew_session_at(
+    manager: &mut VRFKeyManager,
+    request: &RenewSessionRequest,
+    now_ms: f64,
+) -> Result<RenewSessionResult, String> {
+    let ttl_ms = request
+        .ttl_ms
+        .map(|v| v as u64)
+        .unwrap_or(VRF_SESSION_DEFAULT_TTL_MS);
+    let policy_digest = resolve_session_policy_digest(
+        request.session_policy_json.as_deref(),
+        request.session_policy_digest.as_deref(),
+    )
+    .map_err(|e| e.to_string())?;
+    let expires_at_ms = manager
+        .renew_session(
+            &request.session_id,
+            ttl_ms,
+            policy_digest.as_deref(),
+            now_ms,
+        )
+        .map_err(|e| e.to_string())?;
+    let renewable_until_ms = manager
+        .sessions
+        .get(&request.session_id)
+        .and_then(|s| s.renewable_until_ms);
+    Ok(RenewSessionResult {
+        session_id: request.session_id.clone(),
+        expires_at_ms,
+        remaining_ms: (expires_at_ms - now_ms).max(0.0),
+        renewable_until_ms,
+    })
+}
+
+/// Handle RENEW_SESSION message
+pub fn handle_renew_session(
+    manager: Rc<RefCell<VRFKeyManager>>,
+    message_id: Option<String>,
+    request: RenewSessionRequest,
+) -> VrfWorkerResponse {
+    debug!("[VRF] renew_session for session {}", request.session_id);
+
+    let now_ms = js_sys::Date::now();
+    match renew_session_at(&mut manager.borrow_mut(), &request, now_ms) {
+        Ok(result) => VrfWorkerResponse::success_from(message_id, Some(result)),
+        Err(e) => VrfWorkerResponse::fail(message_id, e),
+    }
+}
@@ -1,8 +1,13 @@
-use crate::http::{post_apply_server_lock, post_remove_server_lock};
+use crate::errors::{VrfResult, VrfWorkerError};
+use crate::http::{FetchShamirRelay, ShamirRelayTransport};
 use crate::manager::VRFKeyManager;
-use crate::shamir3pass::{decode_biguint_b64u, encode_biguint_b64u};
+use crate::shamir3pass::{decode_biguint_b64u, encode_biguint_b64u, Shamir3Pass};
+use crate::types::progress::{VrfProgress, VrfProgressPhase};
 use crate::types::VrfWorkerResponse;
-use log::error;
+use base64ct::{Base64UrlUnpadded, Encoding};
+use log::{debug, error};
+use num_bigint::BigUint;
+use num_traits::Zero;
 use serde::{Deserialize, Serialize};
 use serde_wasm_bindgen;
 use std::cell::RefCell;
@@ -19,6 +24,112 @@ fn normalize_relay_url(base: &str, route: &str) -> String {
     }
 }
 
+/// Check a value the relay returned for one Shamir lock operation on `submitted`.
+///
+/// A well-behaved relay returns `submitted^k mod p` for its secret exponent, which is a
+/// group element (0 < x < p) different from the input. An unchanged, zero, out-of-range or
+/// undecodable value means the relay misbehaved; it is rejected before anything is stored.
+pub fn check_shamir_server_response(
+    shamir: &Shamir3Pass,
+    step: &str,
+    submitted: &BigUint,
+    returned_b64u: &str,
+) -> VrfResult<BigUint> {
+    let bytes = Base64UrlUnpadded::decode_vec(returned_b64u.trim()).map_err(|_| {
+        VrfWorkerError::ShamirServerMisbehavior(format!("{}: response is not base64url", step))
+    })?;
+    let returned = BigUint::from_bytes_be(&bytes);
+    debug!(
+        "Shamir3Pass {}: submitted {} bits, relay returned {} bits (p is {} bits)",
+        step,
+        submitted.bits(),
+        returned.bits(),
+        shamir.p().bits()
+    );
+    if returned.is_zero() || &returned >= shamir.p() {
+        return Err(VrfWorkerError::ShamirServerMisbehavior(format!(
+            "{}: returned value is out of range (0 < x < p)",
+            step
+        )));
+    }
+    if &returned == submitted {
+        return Err(VrfWorkerError::ShamirServerMisbehavior(format!(
+            "{}: returned value is unchanged",
+            step
+        )));
+    }
+    Ok(returned)
+}
+
+/// Registration half of the protocol: lock `kek` with a one-time client lock, have the
+/// relay add its lock, and strip the client lock again. Returns `(KEK_s, relay key id)`.
+pub async fn shamir_apply_server_lock_with<T: ShamirRelayTransport>(
+    transport: &T,
+    shamir: &Shamir3Pass,
+    url: &str,
+    kek: &BigUint,
+) -> VrfResult<(BigUint, Option<String>)> {
+    let client_lock = shamir.generate_lock_keys().map_err(|e| {
+        VrfWorkerError::invalid_format(&format!("generate_lock_keys failed: {:?}", e))
+    })?;
+
+    // Client locks the KEK as kek_c with a temp key
+    let kek_c = shamir.add_lock(kek, &client_lock.e);
+    let kek_c_b64u = encode_biguint_b64u(&kek_c);
+
+    // POST to server to lock (double locked)
+    let apply_resp = transport.apply_server_lock(url, &kek_c_b64u).await?;
+    let kek_cs =
+        check_shamir_server_response(shamir, "apply-server-lock", &kek_c, &apply_resp.kek_cs_b64u)?;
+
+    // Client removes onetime client lock to get KEK_s
+    let kek_s = shamir.remove_lock(&kek_cs, &client_lock.d);
+    Ok((kek_s, apply_resp.key_id))
+}
+
+/// Login half of the protocol: re-lock `kek_s`, have the relay remove its lock, strip the
+/// client lock and open `ciphertext_vrf` with the recovered KEK. The round trip only counts
+/// as successful once the AEAD decryption succeeds. Each step is reported on `progress`.
+pub async fn shamir_remove_server_lock_and_decrypt_with<T: ShamirRelayTransport>(
+    transport: &T,
+    shamir: &Shamir3Pass,
+    url: &str,
+    kek_s: &BigUint,
+    key_id: String,
+    ciphertext_vrf: &[u8],
+    progress: &VrfProgress,
+) -> VrfResult<Vec<u8>> {
+    // Choose fresh one-time client lock keys (e_c', d_c')
+    let client_lock = shamir.generate_lock_keys().map_err(|e| {
+        VrfWorkerError::invalid_format(&format!("generate_lock_keys failed: {:?}", e))
+    })?;
+
+    // Client locks the server locked KEK_s as kek_cs
+    let kek_cs = shamir.add_lock(kek_s, &client_lock.e);
+    let kek_cs_b64u = encode_biguint_b64u(&kek_cs);
+    progress.emit(VrfProgressPhase::ClientLockApplied, None);
+
+    // POST KEK_cs to server /remove-server-lock and receive KEK_c back
+    progress.emit(VrfProgressPhase::ServerRoundtripStart, Some(url));
+    let remove_resp = transport
+        .remove_server_lock(url, &kek_cs_b64u, key_id)
+        .await?;
+    let kek_c = check_shamir_server_response(
+        shamir,
+        "remove-server-lock",
+        &kek_cs,
+        &remove_resp.kek_c_b64u,
+    )?;
+    progress.emit(VrfProgressPhase::ServerRoundtripComplete, None);
+
+    // Remove the one-time lock to get the real KEK, then prove it by decrypting
+    let kek = shamir.remove_lock(&kek_c, &client_lock.d);
+    progress.emit(VrfProgressPhase::AeadDecryptStart, None);
+    shamir
+        .decrypt_with_key(ciphertext_vrf, &kek)
+        .map_err(|e| VrfWorkerError::ShamirDecryptFailed(format!("{:?}", e)))
+}
+
 #[wasm_bindgen]
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Shamir3PassClientEncryptCurrentVrfKeypairRequest {
@@ -136,7 +247,7 @@ pub async fn perform_shamir3pass_client_encrypt_current_vrf_keypair(
         keypair_bytes: vrf_keypair_bytes,
         public_key_base64: vrf_pub_b64.clone(),
     };
-    let vrf_keypair_bytes = match bincode::serialize(&vrf_keypair) {
+    let vrf_keypair_bytes = match vrf_keypair.to_versioned_bytes() {
         Ok(b) => b,
         Err(e) => return Err(format!("Serialize VRFKeypairData failed: {}", e)),
     };
@@ -153,30 +264,12 @@ pub async fn perform_shamir3pass_client_encrypt_current_vrf_keypair(
         Err(e) => return Err(format!("encrypt_with_random_kek_key failed: {:?}", e)),
     };
 
-    // Generate client one-time lock keys (e_c, d_c)
-    let client_lock = match shamir3pass.generate_lock_keys() {
-        Ok(k) => k,
-        Err(e) => return Err(format!("generate_lock_keys failed: {:?}", e)),
-    };
-
-    // Client locks vrf keypair as kek_c with temp key
-    let kek_c = shamir3pass.add_lock(&kek, &client_lock.e);
-    let kek_c_b64u = encode_biguint_b64u(&kek_c);
-
-    // POST to server to lock (double locked)
+    // Lock the KEK with the relay's server lock (client lock added and removed around it)
     let url = normalize_relay_url(&relay_url, &apply_lock_route);
-    let apply_resp = match post_apply_server_lock(&url, &kek_c_b64u).await {
-        Ok(v) => v,
-        Err(e) => return Err(e),
-    };
-    let server_key_id = apply_resp.key_id.clone();
-    let kek_cs_b64u = apply_resp.kek_cs_b64u;
-    // Client receives double locked KEK back and base64url decodes it
-    let kek_cs =
-        decode_biguint_b64u(&kek_cs_b64u).map_err(|_| "invalid kek_cs_b64u".to_string())?;
-
-    // Client removes onetime client lock to get KEK_s
-    let kek_s = shamir3pass.remove_lock(&kek_cs, &client_lock.d);
+    let (kek_s, server_key_id) =
+        shamir_apply_server_lock_with(&FetchShamirRelay, &shamir3pass, &url, &kek)
+            .await
+            .map_err(|e| e.to_string())?;
     let kek_s_b64u = encode_biguint_b64u(&kek_s);
 
     // Return ciphertext_vrf (base64url) and KEK_s to save to indexedDB
@@ -193,6 +286,7 @@ pub async fn handle_shamir3pass_client_decrypt_vrf_keypair(
     message_id: Option<String>,
     payload: Shamir3PassClientDecryptVrfKeypairRequest,
 ) -> VrfWorkerResponse {
+    let progress = VrfProgress::start();
     let relay_url = match manager.borrow().relay_server_url.clone() {
         Some(url) => url,
         None => return VrfWorkerResponse::fail(message_id, "VRFManager.relayServerUrl is empty"),
@@ -232,46 +326,29 @@ pub async fn handle_shamir3pass_client_decrypt_vrf_keypair(
         mgr.shamir3pass().clone()
     };
 
-    // Choose fresh one-time client lock keys (e_c', d_c')
-    let client_lock = match shamir3pass.generate_lock_keys() {
-        Ok(k) => k,
-        Err(e) => {
-            return VrfWorkerResponse::fail(
-                message_id,
-                format!("generate_lock_keys failed: {:?}", e),
-            )
-        }
-    };
-
-    // Client locks the server locked KEK_s as kek_cs
-    let kek_cs = shamir3pass.add_lock(&kek_s, &client_lock.e);
-    let kek_cs_b64u = encode_biguint_b64u(&kek_cs);
-
-    // POST KEK_cs to server /remove-server-lock and receive KEK_c back
+    // Round trip through /remove-server-lock; only a KEK that opens the ciphertext counts
     let url = normalize_relay_url(&relay_url, &remove_route);
-    let kek_c_b64u = match post_remove_server_lock(&url, &kek_cs_b64u, payload.key_id.clone()).await
+    let vrf_keypair_bytes = match shamir_remove_server_lock_and_decrypt_with(
+        &FetchShamirRelay,
+        &shamir3pass,
+        &url,
+        &kek_s,
+        payload.key_id.clone(),
+        &ciphertext_vrf,
+        &progress,
+    )
+    .await
     {
-        Ok(v) => v.kek_c_b64u,
-        Err(e) => return VrfWorkerResponse::fail(message_id, e),
-    };
-    let kek_c = match decode_biguint_b64u(&kek_c_b64u) {
-        Ok(v) => v,
-        Err(_) => return VrfWorkerResponse::fail(message_id.clone(), "invalid kek_c_b64u"),
-    };
-    // remove the one-time lock to get the real KEK
-    let kek = shamir3pass.remove_lock(&kek_c, &client_lock.d);
-
-    // Decrypt VRF with AEAD(KEK)
-    let vrf_keypair_bytes = match shamir3pass.decrypt_with_key(&ciphertext_vrf, &kek) {
         Ok(v) => v,
         Err(e) => {
-            return VrfWorkerResponse::fail(message_id, format!("decrypt VRF failed: {:?}", e))
+            error!("Shamir3Pass unlock failed: {}", e);
+            return VrfWorkerResponse::fail(message_id, e.to_string());
         }
     };
 
     // Parse VRFKeypairData and load into manager
-    let keypair_payload: crate::types::VRFKeypairData =
-        match bincode::deserialize(&vrf_keypair_bytes) {
+    let keypair_payload =
+        match crate::types::VRFKeypairData::from_versioned_bytes(&vrf_keypair_bytes) {
             Ok(v) => v,
             Err(e) => {
                 return VrfWorkerResponse::fail(
@@ -283,10 +360,14 @@ pub async fn handle_shamir3pass_client_decrypt_vrf_keypair(
 
     if let Err(e) = manager
         .borrow_mut()
-        .load_plaintext_vrf_keypair(payload.near_account_id, keypair_payload)
+        .load_plaintext_vrf_keypair(payload.near_account_id.clone(), keypair_payload)
     {
         return VrfWorkerResponse::fail(message_id, e.to_string());
     }
+    progress.emit(
+        VrfProgressPhase::KeypairLoaded,
+        Some(payload.near_account_id.as_str()),
+    );
 
     VrfWorkerResponse::success_from(message_id, Some(ShamirUnlockStatus { status: "unlocked" }))
 }
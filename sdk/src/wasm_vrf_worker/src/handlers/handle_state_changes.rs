@@ -0,0 +1,37 @@
+use crate::manager::VRFKeyManager;
+use crate::types::VrfWorkerResponse;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Request payload for GET_STATE_CHANGES_SINCE: the last `stateVersion` the caller saw.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetStateChangesSinceRequest {
+    #[serde(rename = "version")]
+    pub version: u64,
+}
+
+/// Handle GET_STATE_VERSION message: the cheap poll, `{ stateVersion }` only.
+pub fn handle_get_state_version(
+    manager: Rc<RefCell<VRFKeyManager>>,
+    message_id: Option<String>,
+) -> VrfWorkerResponse {
+    #[derive(Serialize)]
+    struct Resp {
+        #[serde(rename = "stateVersion")]
+        state_version: u64,
+    }
+
+    let state_version = manager.borrow().state_changes.version();
+    VrfWorkerResponse::success_from(message_id, Some(Resp { state_version }))
+}
+
+/// Handle GET_STATE_CHANGES_SINCE message
+pub fn handle_get_state_changes_since(
+    manager: Rc<RefCell<VRFKeyManager>>,
+    message_id: Option<String>,
+    request: GetStateChangesSinceRequest,
+) -> VrfWorkerResponse {
+    let changes = manager.borrow().state_changes.since(request.version);
+    VrfWorkerResponse::success_from(message_id, Some(changes))
+}
@@ -1,4 +1,5 @@
 use crate::manager::VRFKeyManager;
+use crate::types::progress::{VrfProgress, VrfProgressPhase};
 use crate::types::EncryptedVRFKeypair;
 use crate::types::VrfWorkerResponse;
 use log::error;
@@ -73,13 +74,21 @@ pub fn handle_unlock_vrf_keypair(
         return VrfWorkerResponse::fail(message_id, "Missing nearAccountId");
     }
 
+    let progress = VrfProgress::start();
+    progress.emit(VrfProgressPhase::AeadDecryptStart, None);
     let mut manager_mut = manager.borrow_mut();
     match manager_mut.unlock_vrf_keypair(
-        payload.near_account_id,
+        payload.near_account_id.clone(),
         payload.encrypted_vrf_keypair,
         prf_key,
     ) {
-        Ok(_) => VrfWorkerResponse::success(message_id, None),
+        Ok(_) => {
+            progress.emit(
+                VrfProgressPhase::KeypairLoaded,
+                Some(payload.near_account_id.as_str()),
+            );
+            VrfWorkerResponse::success(message_id, None)
+        }
         Err(e) => {
             error!("VRF keypair unlock failed: {}", e);
             VrfWorkerResponse::fail(message_id, e.to_string())
@@ -0,0 +1,80 @@
+use crate::manager::VRFKeyManager;
+use crate::types::EncryptedVRFKeypair;
+use crate::types::VrfWorkerResponse;
+use crate::utils::base64_url_decode;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+/// One account to unlock in an UNLOCK_VRF_KEYPAIRS batch.
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UnlockVrfKeypairsEntry {
+    #[wasm_bindgen(getter_with_clone, js_name = "nearAccountId")]
+    #[serde(rename = "nearAccountId")]
+    pub near_account_id: String,
+    #[wasm_bindgen(getter_with_clone, js_name = "encryptedVrfKeypair")]
+    #[serde(rename = "encryptedVrfKeypair")]
+    pub encrypted_vrf_keypair: EncryptedVRFKeypair,
+    /// Base64url-encoded PRF output used to decrypt this account's keypair.
+    #[wasm_bindgen(getter_with_clone, js_name = "prfKey")]
+    #[serde(rename = "prfKey")]
+    pub prf_key: String,
+}
+
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UnlockVrfKeypairsRequest {
+    #[wasm_bindgen(getter_with_clone)]
+    pub entries: Vec<UnlockVrfKeypairsEntry>,
+}
+
+/// Handle UNLOCK_VRF_KEYPAIRS message.
+///
+/// Succeeds whenever the request is well-formed; per-account failures are reported in
+/// `results` so callers can retry only the accounts that did not unlock.
+pub fn handle_unlock_vrf_keypairs(
+    manager: Rc<RefCell<VRFKeyManager>>,
+    message_id: Option<String>,
+    payload: UnlockVrfKeypairsRequest,
+) -> VrfWorkerResponse {
+    if payload.entries.is_empty() {
+        return VrfWorkerResponse::fail(message_id, "Missing entries");
+    }
+
+    let entries = payload
+        .entries
+        .into_iter()
+        .map(|entry| {
+            // An undecodable key is reported for that account by the manager (empty PRF).
+            let prf_key = base64_url_decode(&entry.prf_key).unwrap_or_default();
+            (entry.near_account_id, entry.encrypted_vrf_keypair, prf_key)
+        })
+        .collect();
+
+    let results = manager
+        .borrow_mut()
+        .unlock_vrf_keypairs(entries, js_sys::Date::now());
+    let unlocked_count = results.iter().filter(|r| r.success).count();
+    debug!(
+        "[VRF] unlock_vrf_keypairs: {}/{} unlocked",
+        unlocked_count,
+        results.len()
+    );
+
+    #[derive(Serialize)]
+    struct Resp {
+        #[serde(rename = "unlockedCount")]
+        unlocked_count: usize,
+        results: Vec<crate::manager::VrfKeypairUnlockOutcome>,
+    }
+    VrfWorkerResponse::success_from(
+        message_id,
+        Some(Resp {
+            unlocked_count,
+            results,
+        }),
+    )
+}
@@ -1,39 +1,53 @@
 pub mod handle_check_session_status;
 pub mod handle_clear_session;
+pub mod handle_configure_block_context_source;
+pub mod handle_configure_rpc;
 pub mod handle_confirm_and_prepare_signing_session;
 pub mod handle_decrypt_session;
 pub mod handle_derive_vrf_keypair_from_prf;
+pub mod handle_device2_complete_registration;
 pub mod handle_device2_registration_session;
 pub mod handle_dispense_session_key;
 pub mod handle_generate_vrf_challenge;
 pub mod handle_generate_vrf_keypair_bootstrap;
 pub mod handle_mint_session_keys_and_send_to_signer;
 pub mod handle_registration_credential_confirmation;
+pub mod handle_renew_session;
 pub mod handle_shamir3pass_client;
 pub mod handle_shamir3pass_config;
+#[cfg(feature = "shamir-server")]
 pub mod handle_shamir3pass_server;
+pub mod handle_state_changes;
 pub mod handle_unlock_vrf_keypair;
+pub mod handle_unlock_vrf_keypairs;
 
 pub use handle_check_session_status::*;
 pub use handle_clear_session::*;
+pub use handle_configure_block_context_source::*;
+pub use handle_configure_rpc::*;
 pub use handle_confirm_and_prepare_signing_session::*;
 pub use handle_decrypt_session::*;
 pub use handle_derive_vrf_keypair_from_prf::*;
+pub use handle_device2_complete_registration::*;
 pub use handle_device2_registration_session::*;
 pub use handle_dispense_session_key::*;
 pub use handle_generate_vrf_challenge::*;
 pub use handle_generate_vrf_keypair_bootstrap::*;
 pub use handle_mint_session_keys_and_send_to_signer::*;
 pub use handle_registration_credential_confirmation::*;
+pub use handle_renew_session::*;
 pub use handle_shamir3pass_client::*;
 pub use handle_shamir3pass_config::*;
+#[cfg(feature = "shamir-server")]
 pub use handle_shamir3pass_server::*;
+pub use handle_state_changes::*;
 pub use handle_unlock_vrf_keypair::*;
+pub use handle_unlock_vrf_keypairs::*;
 
 use crate::manager::VRFKeyManager;
 use crate::types::VrfWorkerResponse;
 use js_sys::Date;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -64,14 +78,55 @@ pub fn handle_check_vrf_status(
     VrfWorkerResponse::success_from(message_id, Some(status))
 }
 
-/// Handle CLEAR_VRF message
+/// Handle VRF_DIAGNOSTICS message
+pub fn handle_vrf_diagnostics(
+    manager: Rc<RefCell<VRFKeyManager>>,
+    message_id: Option<String>,
+) -> VrfWorkerResponse {
+    let diagnostics = manager.borrow().diagnostics();
+    VrfWorkerResponse::success_from(message_id, Some(diagnostics))
+}
+
+/// Optional CLEAR_VRF payload.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClearVrfRequest {
+    /// Epoch of a coordinated logout; the same value goes to the signer worker's
+    /// CLEAR_ALL_SESSIONS. Defaults to the current epoch + 1.
+    #[serde(default)]
+    pub logout_epoch: Option<u64>,
+}
+
+/// Handle CLEAR_VRF message: keypair, sessions (dispensed session keys), challenges,
+/// verifications and block context are dropped; responds with the completed `logoutEpoch`.
 pub fn handle_logout(
     manager: Rc<RefCell<VRFKeyManager>>,
     message_id: Option<String>,
+    request: ClearVrfRequest,
+) -> VrfWorkerResponse {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Resp {
+        logout_epoch: u64,
+    }
+
+    let mut manager_mut = manager.borrow_mut();
+    match manager_mut.logout_at_epoch(request.logout_epoch) {
+        Ok(logout_epoch) => {
+            VrfWorkerResponse::success_from(message_id, Some(Resp { logout_epoch }))
+        }
+        Err(e) => VrfWorkerResponse::fail(message_id, e.to_string()),
+    }
+}
+
+/// Handle LOGOUT_ALL message: CLEAR_VRF plus counts of what was wiped
+pub fn handle_logout_all(
+    manager: Rc<RefCell<VRFKeyManager>>,
+    message_id: Option<String>,
 ) -> VrfWorkerResponse {
     let mut manager_mut = manager.borrow_mut();
-    match manager_mut.logout() {
-        Ok(_) => VrfWorkerResponse::success(message_id, None),
+    match manager_mut.logout_all() {
+        Ok(summary) => VrfWorkerResponse::success_from(message_id, Some(summary)),
         Err(e) => VrfWorkerResponse::fail(message_id, e.to_string()),
     }
 }
@@ -0,0 +1,111 @@
+//! Probe for the JS globals the worker host (web3authn-vrf.worker.ts) must install.
+//!
+//! `awaitSecureConfirmationV2` and `setTimeout` are `extern "C"` imports resolved on
+//! globalThis at call time, so a bundler that tree-shakes or renames one only fails in the
+//! middle of a confirmation flow. `check_environment` reports what is missing up front;
+//! call sites that need a bridge use `require_host_bridge` and fail with `MissingHostBridge`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use log::warn;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::errors::{VrfResult, VrfWorkerError};
+
+/// SecureConfirm bridge used by `vrf_await_secure_confirmation`.
+pub const AWAIT_SECURE_CONFIRMATION_V2: &str = "awaitSecureConfirmationV2";
+/// Used for NEAR RPC retry back-off (`rpc_calls::sleep`).
+pub const SET_TIMEOUT: &str = "setTimeout";
+/// Used for contract verification and Shamir relay calls.
+pub const FETCH: &str = "fetch";
+/// Progress bridge for keypair unlock/derivation (`send_vrf_progress_message`); optional.
+#[cfg(target_arch = "wasm32")]
+pub const SEND_VRF_PROGRESS_MESSAGE: &str = "sendVrfProgressMessage";
+
+/// Globals without which some request types cannot complete.
+pub const REQUIRED_GLOBALS: &[&str] = &[AWAIT_SECURE_CONFIRMATION_V2, SET_TIMEOUT];
+/// Globals whose absence only disables optional features.
+pub const OPTIONAL_GLOBALS: &[(&str, &str)] = &[(
+    FETCH,
+    "fetch is not available; contract verification and relay calls will fail",
+)];
+
+static ENVIRONMENT_CHECKED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentReport {
+    pub ok: bool,
+    pub missing: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Build the report from a predicate telling whether a global function is installed.
+pub fn evaluate_environment<F>(has_global: F) -> EnvironmentReport
+where
+    F: Fn(&str) -> bool,
+{
+    let missing: Vec<String> = REQUIRED_GLOBALS
+        .iter()
+        .filter(|name| !has_global(name))
+        .map(|name| name.to_string())
+        .collect();
+    let warnings = OPTIONAL_GLOBALS
+        .iter()
+        .filter(|(name, _)| !has_global(name))
+        .map(|(_, warning)| warning.to_string())
+        .collect();
+    EnvironmentReport {
+        ok: missing.is_empty(),
+        missing,
+        warnings,
+    }
+}
+
+/// Whether `globalThis[name]` is a function. Native builds have no JS host, so everything
+/// counts as installed.
+pub fn has_global_function(name: &str) -> bool {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str(name))
+            .map(|value| value.is_function())
+            .unwrap_or(false)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let _ = name;
+        true
+    }
+}
+
+/// Fail with `MissingHostBridge` if the named bridge is not installed.
+pub fn require_host_bridge(name: &str) -> VrfResult<()> {
+    if has_global_function(name) {
+        Ok(())
+    } else {
+        Err(VrfWorkerError::MissingHostBridge(name.to_string()))
+    }
+}
+
+/// Probe every global the VRF worker relies on. Returns `{ ok, missing, warnings }`.
+/// Idempotent; safe to call at any time.
+#[wasm_bindgen]
+pub fn check_environment() -> JsValue {
+    let report = evaluate_environment(has_global_function);
+    serde_wasm_bindgen::to_value(&report).unwrap_or(JsValue::NULL)
+}
+
+/// Log a structured warning for missing globals, once per worker. Never fails startup.
+pub(crate) fn warn_if_environment_incomplete() {
+    if ENVIRONMENT_CHECKED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let report = evaluate_environment(has_global_function);
+    if !report.ok || !report.warnings.is_empty() {
+        warn!(
+            "[VRF] host environment incomplete: missing={:?} warnings={:?}",
+            report.missing, report.warnings
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::errors::{VrfResult, VrfWorkerError};
 use crate::fetch::{
     fetch_json_post, response_ok, response_status, response_status_text, response_text,
 };
@@ -7,13 +8,74 @@ use crate::types::http::{
 };
 use log::debug;
 
+/// Talks to the relay's Shamir 3-pass routes. `FetchShamirRelay` is the runtime
+/// implementation; tests substitute a mock.
+///
+/// Network and HTTP failures surface as `ShamirServerUnreachable`; a 2xx body that does not
+/// parse surfaces as `ShamirServerMisbehavior`.
+#[allow(async_fn_in_trait)]
+pub trait ShamirRelayTransport {
+    async fn apply_server_lock(
+        &self,
+        endpoint_url: &str,
+        kek_c_b64u: &str,
+    ) -> VrfResult<ShamirApplyServerLockHTTPResponse>;
+
+    async fn remove_server_lock(
+        &self,
+        endpoint_url: &str,
+        kek_cs_b64u: &str,
+        key_id: String,
+    ) -> VrfResult<ShamirRemoveServerLockHTTPResponse>;
+}
+
+/// `fetch()`-backed relay transport used by the worker at runtime.
+pub struct FetchShamirRelay;
+
+impl ShamirRelayTransport for FetchShamirRelay {
+    async fn apply_server_lock(
+        &self,
+        endpoint_url: &str,
+        kek_c_b64u: &str,
+    ) -> VrfResult<ShamirApplyServerLockHTTPResponse> {
+        post_apply_server_lock(endpoint_url, kek_c_b64u).await
+    }
+
+    async fn remove_server_lock(
+        &self,
+        endpoint_url: &str,
+        kek_cs_b64u: &str,
+        key_id: String,
+    ) -> VrfResult<ShamirRemoveServerLockHTTPResponse> {
+        post_remove_server_lock(endpoint_url, kek_cs_b64u, key_id).await
+    }
+}
+
+/// POST a JSON body and return the 2xx response text; any failure means the relay was not reached.
+async fn post_shamir_route(endpoint_url: &str, body_str: &str) -> VrfResult<String> {
+    let unreachable = VrfWorkerError::ShamirServerUnreachable;
+    let resp = fetch_json_post(endpoint_url, body_str)
+        .await
+        .map_err(unreachable)?;
+
+    if !response_ok(&resp).map_err(unreachable)? {
+        return Err(unreachable(format!(
+            "HTTP error: {} {}",
+            response_status(&resp).map_err(unreachable)?,
+            response_status_text(&resp).map_err(unreachable)?
+        )));
+    }
+
+    response_text(&resp).await.map_err(unreachable)
+}
+
 /// POST Shamir 3-pass apply-server-exponent
 /// Request: { kek_c_b64u }
 /// Response: { kek_cs_b64u }
-pub(crate) async fn post_apply_server_lock(
+async fn post_apply_server_lock(
     endpoint_url: &str,
     kek_c_b64u: &str,
-) -> Result<ShamirApplyServerLockHTTPResponse, String> {
+) -> VrfResult<ShamirApplyServerLockHTTPResponse> {
     debug!("POST endpoint: {}", endpoint_url);
 
     // Use strongly typed request structure and serialize to JSON string
@@ -24,31 +86,24 @@ pub(crate) async fn post_apply_server_lock(
     let body_str = js_sys::JSON::stringify(&body_js)
         .map_err(|e| format!("Failed to stringify apply-server-lock body: {:?}", e))?
         .as_string()
-        .ok_or_else(|| "Failed to stringify apply-server-lock body".to_string())?;
+        .ok_or_else(|| {
+            VrfWorkerError::invalid_format("Failed to stringify apply-server-lock body")
+        })?;
 
-    let resp = fetch_json_post(endpoint_url, &body_str).await?;
-
-    if !response_ok(&resp)? {
-        return Err(format!(
-            "HTTP error: {} {}",
-            response_status(&resp)?,
-            response_status_text(&resp)?
-        ));
-    }
-
-    let response_text = response_text(&resp).await?;
+    let response_text = post_shamir_route(endpoint_url, &body_str).await?;
 
     ShamirApplyServerLockHTTPResponse::from_str(&response_text)
+        .map_err(VrfWorkerError::ShamirServerMisbehavior)
 }
 
 /// POST Shamir 3-pass remove-server-exponent
 /// Request: { kek_cs_b64u }
 /// Response: { kek_c_b64u }
-pub(crate) async fn post_remove_server_lock(
+async fn post_remove_server_lock(
     endpoint_url: &str,
     kek_cs_b64u: &str,
     key_id: String,
-) -> Result<ShamirRemoveServerLockHTTPResponse, String> {
+) -> VrfResult<ShamirRemoveServerLockHTTPResponse> {
     debug!("Shamir3Pass remove-server-lock: {}", endpoint_url);
 
     // Use strongly typed request structure and serialize to JSON string
@@ -60,19 +115,12 @@ pub(crate) async fn post_remove_server_lock(
     let body_str = js_sys::JSON::stringify(&body_js)
         .map_err(|e| format!("Failed to stringify remove-server-lock body: {:?}", e))?
         .as_string()
-        .ok_or_else(|| "Failed to stringify remove-server-lock body".to_string())?;
-
-    let resp = fetch_json_post(endpoint_url, &body_str).await?;
-
-    if !response_ok(&resp)? {
-        return Err(format!(
-            "HTTP error: {} {}",
-            response_status(&resp)?,
-            response_status_text(&resp)?
-        ));
-    }
+        .ok_or_else(|| {
+            VrfWorkerError::invalid_format("Failed to stringify remove-server-lock body")
+        })?;
 
-    let response_text = response_text(&resp).await?;
+    let response_text = post_shamir_route(endpoint_url, &body_str).await?;
 
     ShamirRemoveServerLockHTTPResponse::from_str(&response_text)
+        .map_err(VrfWorkerError::ShamirServerMisbehavior)
 }
@@ -9,18 +9,38 @@ use wasm_bindgen::JsValue;
 #[cfg(target_arch = "wasm32")]
 use web_sys::MessagePort;
 
+// The confirmation bypass must never ship in optimized builds without an explicit opt-in.
+#[cfg(all(
+    feature = "test_confirmation_bypass",
+    not(debug_assertions),
+    not(confirmation_bypass_release_opt_in)
+))]
+compile_error!(
+    "feature `test_confirmation_bypass` is test-only and cannot be built with --release \
+     (set TATCHI_ALLOW_RELEASE_CONFIRMATION_BYPASS=1 to override for E2E builds)"
+);
+
 mod await_secure_confirmation;
 mod config;
 mod errors;
 mod fetch;
 mod handlers;
+mod host_environment;
 mod http;
 mod logger;
 mod manager;
+mod panic_guard;
+mod randomness;
 mod rpc_calls;
+mod rpc_headers;
+mod rp_id_normalization;
+mod session_policy;
 mod shamir3pass;
+mod state_changes;
+mod summary_localization;
 mod types;
 mod utils;
+mod verification_cache;
 mod webauthn;
 #[cfg(target_arch = "wasm32")]
 pub mod wrap_key_seed_port;
@@ -31,7 +51,10 @@ mod tests;
 // Re-export important types and functions
 pub use config::*;
 pub use errors::*;
+pub use host_environment::check_environment;
 pub use manager::*;
+pub use randomness::randomness_self_test;
+pub use session_policy::{canonicalize_session_policy_json, compute_session_policy_digest};
 pub use shamir3pass::*;
 pub use utils::*;
 
@@ -48,37 +71,51 @@ pub use types::{VrfWorkerMessage, VrfWorkerResponse, WorkerRequestType};
 // Import request types from their respective handler files
 pub use handlers::handle_check_session_status::CheckSessionStatusRequest;
 pub use handlers::handle_clear_session::ClearSessionRequest;
+pub use handlers::handle_configure_block_context_source::ConfigureBlockContextSourceRequest;
+pub use handlers::handle_configure_rpc::ConfigureRpcRequest;
 pub use handlers::handle_confirm_and_prepare_signing_session::ConfirmAndPrepareSigningSessionRequest;
 pub use handlers::handle_decrypt_session::DecryptSessionRequest;
 pub use handlers::handle_derive_vrf_keypair_from_prf::DeriveVrfKeypairFromPrfRequest;
+pub use handlers::handle_device2_complete_registration::Device2CompleteRegistrationRequest;
 pub use handlers::handle_device2_registration_session::Device2RegistrationSessionRequest;
 pub use handlers::handle_dispense_session_key::DispenseSessionKeyRequest;
-pub use handlers::handle_generate_vrf_challenge::GenerateVrfChallengeRequest;
+pub use handlers::handle_generate_vrf_challenge::{
+    GenerateVrfChallengeRequest, GenerateVrfChallengesRequest,
+};
 pub use handlers::handle_generate_vrf_keypair_bootstrap::GenerateVrfKeypairBootstrapRequest;
 pub use handlers::handle_mint_session_keys_and_send_to_signer::MintSessionKeysAndSendToSignerRequest;
 pub use handlers::handle_registration_credential_confirmation::RegistrationCredentialConfirmationRequest;
+pub use handlers::handle_renew_session::RenewSessionRequest;
 pub use handlers::handle_shamir3pass_client::{
     Shamir3PassClientDecryptVrfKeypairRequest, Shamir3PassClientEncryptCurrentVrfKeypairRequest,
 };
 pub use handlers::handle_shamir3pass_config::{
     Shamir3PassConfigPRequest, Shamir3PassConfigServerUrlsRequest,
 };
+#[cfg(feature = "shamir-server")]
 pub use handlers::handle_shamir3pass_server::{
     Shamir3PassApplyServerLockRequest, Shamir3PassGenerateServerKeypairRequest,
     Shamir3PassRemoveServerLockRequest,
 };
+pub use handlers::handle_state_changes::GetStateChangesSinceRequest;
 pub use handlers::handle_unlock_vrf_keypair::UnlockVrfKeypairRequest;
+pub use handlers::handle_unlock_vrf_keypairs::{UnlockVrfKeypairsEntry, UnlockVrfKeypairsRequest};
 
 // Set up panic hook for better error messages
 #[wasm_bindgen(start)]
 pub fn main() {
     // Initialize logger with the configured log level
     logger::init(config::CURRENT_LOG_LEVEL);
+    panic_guard::install_panic_hook();
     debug!("VRF WASM Worker starting up...");
     debug!(
         "Logging system initialized with level: {:?}",
         config::CURRENT_LOG_LEVEL
     );
+    // Failure is logged and latched; secret-generating paths refuse to run afterwards.
+    let _ = randomness::randomness_self_test();
+    // Missing host bridges are only logged here; handlers that need one reject lazily.
+    host_environment::warn_if_environment_incomplete();
 }
 
 // === GLOBAL STATE ===
@@ -120,6 +157,26 @@ pub fn configure_shamir_server_urls(
     })
 }
 
+/// Canonical `sessionPolicyDigest32` for a session policy JSON string (32 bytes).
+///
+/// Exported for the client shell and the Node relayer so both hash the same canonical form.
+#[wasm_bindgen]
+pub fn compute_session_policy_digest_32(policy_json: String) -> Result<Vec<u8>, JsValue> {
+    Ok(session_policy::compute_session_policy_digest(&policy_json)?.to_vec())
+}
+
+/// Base64url `vrfInput` for a `VRFInputData`: the exact bytes the worker feeds to the VRF.
+///
+/// Lets integration tests and independent verifiers reproduce a challenge's `vrfInput`
+/// without a VRF keypair.
+#[wasm_bindgen]
+pub fn compute_vrf_input_b64u(input: JsValue) -> Result<String, JsValue> {
+    let input: types::VRFInputData = serde_wasm_bindgen::from_value(input)
+        .map_err(|e| JsValue::from_str(&format!("Invalid VRFInputData: {}", e)))?;
+    let resolved = manager::compute_vrf_input(&input)?;
+    Ok(utils::base64_url_encode(&resolved.vrf_input))
+}
+
 /// Attach a MessagePort for a signing session so VRF Rust can send WrapKeySeed directly
 /// to the signer worker over the dedicated channel.
 #[wasm_bindgen]
@@ -138,6 +195,50 @@ pub fn attach_wrap_key_seed_port(session_id: String, port_val: JsValue) {
     }
 }
 
+// === PROGRESS MESSAGING ===
+
+/// Forward a VRF unlock/derivation phase to the main thread via the TypeScript
+/// `sendVrfProgressMessage` global. Sends both the numeric phase and its name.
+pub fn send_vrf_progress_message(phase: u32, elapsed_ms: f64, detail: Option<&str>) {
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_name = sendVrfProgressMessage)]
+        fn send_vrf_progress_message_js(
+            phase: u32,
+            phase_name: &str,
+            elapsed_ms: f64,
+            detail: Option<String>,
+        );
+    }
+
+    let phase_name = match types::progress::VrfProgressPhase::try_from(phase) {
+        Ok(phase_enum) => types::progress::vrf_progress_phase_name(phase_enum),
+        Err(_) => "unknown-phase",
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        // Calling an uninstalled import throws; progress is best-effort, so drop it instead.
+        if !host_environment::has_global_function(host_environment::SEND_VRF_PROGRESS_MESSAGE) {
+            debug!("Dropping progress message: sendVrfProgressMessage is not installed");
+            return;
+        }
+        send_vrf_progress_message_js(phase, phase_name, elapsed_ms, detail.map(str::to_string));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        // In non-WASM context (like tests), just log the progress
+        println!(
+            "VRF progress: {} ({}) at {}ms - {}",
+            phase_name,
+            phase,
+            elapsed_ms,
+            detail.unwrap_or("")
+        );
+    }
+}
+
 // === WASM EXPORTS ===
 
 #[wasm_bindgen]
@@ -184,6 +285,11 @@ pub async fn handle_message(message: JsValue) -> Result<JsValue, JsValue> {
                 parse_typed_payload(payload.clone(), request_type)?;
             handlers::handle_unlock_vrf_keypair(manager_rc.clone(), id.clone(), request)
         }
+        WorkerRequestType::UnlockVrfKeypairs => {
+            let request: UnlockVrfKeypairsRequest =
+                parse_typed_payload(payload.clone(), request_type)?;
+            handlers::handle_unlock_vrf_keypairs(manager_rc.clone(), id.clone(), request)
+        }
         WorkerRequestType::CheckVrfStatus => {
             handlers::handle_check_vrf_status(manager_rc.clone(), id.clone())
         }
@@ -193,12 +299,26 @@ pub async fn handle_message(message: JsValue) -> Result<JsValue, JsValue> {
                 // Cleanup all attached ports on logout.
                 wrap_key_seed_port::close_all_ports();
             }
-            handlers::handle_logout(manager_rc.clone(), id.clone())
+            let request: handlers::ClearVrfRequest = match &payload {
+                Some(_) => parse_typed_payload(payload.clone(), request_type)?,
+                None => handlers::ClearVrfRequest::default(),
+            };
+            handlers::handle_logout(manager_rc.clone(), id.clone(), request)
+        }
+        WorkerRequestType::LogoutAll => handlers::handle_logout_all(manager_rc.clone(), id.clone()),
+        WorkerRequestType::VrfDiagnostics => {
+            handlers::handle_vrf_diagnostics(manager_rc.clone(), id.clone())
         }
         WorkerRequestType::GenerateVrfChallenge => {
             let request: GenerateVrfChallengeRequest =
                 parse_typed_payload(payload.clone(), request_type)?;
             handlers::handle_generate_vrf_challenge(manager_rc.clone(), id.clone(), request)
+                .await
+        }
+        WorkerRequestType::GenerateVrfChallenges => {
+            let request: GenerateVrfChallengesRequest =
+                parse_typed_payload(payload.clone(), request_type)?;
+            handlers::handle_generate_vrf_challenges(manager_rc.clone(), id.clone(), request).await
         }
         WorkerRequestType::DeriveVrfKeypairFromPrf => {
             let request: DeriveVrfKeypairFromPrfRequest =
@@ -228,6 +348,7 @@ pub async fn handle_message(message: JsValue) -> Result<JsValue, JsValue> {
             .await
         }
         // Server-side helpers used by Node relay-server, they lock and unlock the KEK (key encryption key)
+        #[cfg(feature = "shamir-server")]
         WorkerRequestType::Shamir3PassGenerateServerKeypair => {
             handlers::handle_shamir3pass_generate_server_keypair(
                 manager_rc.clone(),
@@ -235,6 +356,7 @@ pub async fn handle_message(message: JsValue) -> Result<JsValue, JsValue> {
                 Shamir3PassGenerateServerKeypairRequest {},
             )
         }
+        #[cfg(feature = "shamir-server")]
         WorkerRequestType::Shamir3PassApplyServerLock => {
             let request: Shamir3PassApplyServerLockRequest =
                 parse_typed_payload(payload.clone(), request_type)?;
@@ -244,6 +366,7 @@ pub async fn handle_message(message: JsValue) -> Result<JsValue, JsValue> {
                 request,
             )
         }
+        #[cfg(feature = "shamir-server")]
         WorkerRequestType::Shamir3PassRemoveServerLock => {
             let request: Shamir3PassRemoveServerLockRequest =
                 parse_typed_payload(payload.clone(), request_type)?;
@@ -253,6 +376,17 @@ pub async fn handle_message(message: JsValue) -> Result<JsValue, JsValue> {
                 request,
             )
         }
+        #[cfg(not(feature = "shamir-server"))]
+        WorkerRequestType::Shamir3PassGenerateServerKeypair
+        | WorkerRequestType::Shamir3PassApplyServerLock
+        | WorkerRequestType::Shamir3PassRemoveServerLock => VrfWorkerResponse::fail(
+            id.clone(),
+            VrfWorkerError::FeatureNotCompiled {
+                feature: "shamir-server".to_string(),
+                requested: request_type.name().to_string(),
+            }
+            .to_string(),
+        ),
         // Configure Shamir p (global) and server URLs
         WorkerRequestType::Shamir3PassConfigP => {
             let request: Shamir3PassConfigPRequest =
@@ -295,6 +429,12 @@ pub async fn handle_message(message: JsValue) -> Result<JsValue, JsValue> {
             handlers::handle_device2_registration_session(manager_rc.clone(), id.clone(), request)
                 .await
         }
+        WorkerRequestType::Device2CompleteRegistration => {
+            let request: Device2CompleteRegistrationRequest =
+                parse_typed_payload(payload.clone(), request_type)?;
+            handlers::handle_device2_complete_registration(manager_rc.clone(), id.clone(), request)
+                .await
+        }
         WorkerRequestType::DispenseSessionKey => {
             let request: DispenseSessionKeyRequest =
                 parse_typed_payload(payload.clone(), request_type)?;
@@ -305,10 +445,31 @@ pub async fn handle_message(message: JsValue) -> Result<JsValue, JsValue> {
                 parse_typed_payload(payload.clone(), request_type)?;
             handlers::handle_check_session_status(manager_rc.clone(), id.clone(), request)
         }
+        WorkerRequestType::RenewSession => {
+            let request: RenewSessionRequest = parse_typed_payload(payload.clone(), request_type)?;
+            handlers::handle_renew_session(manager_rc.clone(), id.clone(), request)
+        }
+        WorkerRequestType::GetStateVersion => {
+            handlers::handle_get_state_version(manager_rc.clone(), id.clone())
+        }
+        WorkerRequestType::GetStateChangesSince => {
+            let request: GetStateChangesSinceRequest =
+                parse_typed_payload(payload.clone(), request_type)?;
+            handlers::handle_get_state_changes_since(manager_rc.clone(), id.clone(), request)
+        }
         WorkerRequestType::ClearSession => {
             let request: ClearSessionRequest = parse_typed_payload(payload.clone(), request_type)?;
             handlers::handle_clear_session(manager_rc.clone(), id.clone(), request)
         }
+        WorkerRequestType::ConfigureRpc => {
+            let request: ConfigureRpcRequest = parse_typed_payload(payload.clone(), request_type)?;
+            handlers::handle_configure_rpc(manager_rc.clone(), id.clone(), request)
+        }
+        WorkerRequestType::ConfigureBlockContextSource => {
+            let request: ConfigureBlockContextSourceRequest =
+                parse_typed_payload(payload.clone(), request_type)?;
+            handlers::handle_configure_block_context_source(manager_rc.clone(), id.clone(), request)
+        }
         WorkerRequestType::ConfirmAndPrepareSigningSession => {
             let request: ConfirmAndPrepareSigningSessionRequest =
                 parse_typed_payload(payload.clone(), request_type)?;
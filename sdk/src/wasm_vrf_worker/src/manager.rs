@@ -10,16 +10,23 @@ use sha2::{Digest, Sha256};
 use vrf_wasm::ecvrf::ECVRFKeyPair;
 use vrf_wasm::traits::WasmRngFromSeed;
 use vrf_wasm::vrf::{VRFKeyPair, VRFProof};
-use zeroize::ZeroizeOnDrop;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::config::*;
 use crate::errors::{AesError, HkdfError, SerializationError, VrfResult, VrfWorkerError};
 use crate::handlers::DeterministicVrfKeypairResponse;
+use crate::randomness::ensure_randomness_ok;
+use crate::rpc_calls::BlockContext;
+use crate::rpc_headers::RpcHeaders;
+use crate::session_policy::resolve_session_policy_digest;
 use crate::shamir3pass::Shamir3Pass;
+use crate::state_changes::{StateChangeKind, StateChangeLog};
+use crate::types::progress::{self, VrfProgress, VrfProgressPhase};
 use crate::types::*;
 use crate::types::{EncryptedVrfKeypairResponse, GenerateVrfKeypairBootstrapResponse};
 use crate::utils::{base64_url_decode, base64_url_encode, parse_block_height};
-use serde::Serialize;
+use crate::verification_cache::{ContractVerificationFacts, VerificationCache};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 // === SECURE VRF KEYPAIR WRAPPER ===
@@ -60,6 +67,66 @@ pub struct VRFKeyManager {
     pub relay_server_url: Option<String>,
     pub apply_lock_route: Option<String>,
     pub remove_lock_route: Option<String>,
+    /// Default NEAR RPC headers (e.g. provider API keys), set via CONFIGURE_RPC.
+    pub default_rpc_headers: RpcHeaders,
+    /// Recent successful contract verifications, reused within a short TTL.
+    pub verification_cache: VerificationCache,
+    /// Where to fetch block height/hash when a VRF challenge request omits them,
+    /// set via CONFIGURE_BLOCK_CONTEXT_SOURCE.
+    pub block_context_source: Option<BlockContextSource>,
+    /// Last fetched block context (reused while fresh).
+    pub block_context: Option<BlockContext>,
+    /// Keypairs unlocked via UNLOCK_VRF_KEYPAIRS, by NEAR account id. One of them is also
+    /// the active `vrf_keypair`; the others can be activated without another PRF prompt.
+    pub unlocked_vrf_keypairs: HashMap<String, SecureVRFKeyPair>,
+    /// Version counter and recent-change ring polled by the TS shell.
+    pub state_changes: StateChangeLog,
+    /// Epoch of the last completed logout (CLEAR_VRF / LOGOUT_ALL); 0 before any. The TS shell
+    /// compares it with the signer worker's `logoutEpoch` after CLEAR_ALL_SESSIONS.
+    pub logout_epoch: u64,
+    /// Account the active `vrf_keypair` was unlocked for; `None` while locked and for
+    /// bootstrap keypairs not yet bound to an account.
+    pub vrf_account_id: Option<String>,
+}
+
+/// Per-account outcome of a batch unlock.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VrfKeypairUnlockOutcome {
+    pub near_account_id: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// RPC endpoint and freshness bound for worker-fetched block context.
+#[derive(Debug, Clone)]
+pub struct BlockContextSource {
+    pub near_rpc_url: String,
+    pub max_staleness_ms: u64,
+}
+
+/// `keyReusePolicy` from the session's confirmation. The VRF worker only forwards it with the
+/// WrapKeySeed; the signer worker decides (and clamps) how a decrypted key may be reused.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyReusePolicy {
+    #[serde(default)]
+    pub allow_key_reuse_within_session: bool,
+    #[serde(default)]
+    pub max_operations: u32,
+    #[serde(default)]
+    pub ttl_ms: u64,
+    #[serde(default)]
+    pub on_exhausted: KeyReuseExhaustion,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyReuseExhaustion {
+    #[default]
+    Redecrypt,
+    Fail,
 }
 
 /// VRF-owned session state for reusing WrapKeySeed without re-prompting WebAuthn.
@@ -73,6 +140,16 @@ pub struct VrfSessionData {
     pub created_at_ms: f64,
     pub expires_at_ms: Option<f64>,
     pub remaining_uses: Option<u32>,
+    /// Latest instant RENEW_SESSION may extend `expires_at_ms` to; `None` disables renewal.
+    pub renewable_until_ms: Option<f64>,
+    /// Session policy digest (base64url) the session was minted under, if any.
+    pub policy_digest: Option<String>,
+    /// Contract verification performed at mint time; forwarded to signers on each dispense.
+    #[zeroize(skip)]
+    pub contract_verification: Option<ContractVerificationFacts>,
+    /// Key reuse policy forwarded to signers with the WrapKeySeed on each dispense.
+    #[zeroize(skip)]
+    pub key_reuse_policy: Option<KeyReusePolicy>,
 }
 
 impl VrfSessionData {
@@ -80,6 +157,16 @@ impl VrfSessionData {
         self.expires_at_ms.is_some_and(|exp| now_ms >= exp)
     }
 
+    /// Whether RENEW_SESSION would currently succeed (ignoring the policy check).
+    pub fn is_renewable(&self, now_ms: f64) -> bool {
+        !self.is_expired(now_ms) && self.renewable_until_ms.is_some_and(|until| now_ms < until)
+    }
+
+    /// Milliseconds left before `expires_at_ms` (clamped at 0); `None` for sessions without a TTL.
+    pub fn remaining_ms(&self, now_ms: f64) -> Option<f64> {
+        self.expires_at_ms.map(|exp| (exp - now_ms).max(0.0))
+    }
+
     pub fn can_consume(&self, uses: u32) -> bool {
         match self.remaining_uses {
             None => true,
@@ -100,6 +187,59 @@ pub struct VrfStatus {
     pub active: bool,
     pub session_duration: f64,
     pub vrf_public_key: Option<String>,
+    pub state_version: u64,
+    pub logout_epoch: u64,
+}
+
+/// Non-secret manager state for VRF_DIAGNOSTICS ("why is my VRF locked?"). Never carries key
+/// bytes, session seeds or configured URLs.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct VrfDiagnostics {
+    pub session_active: bool,
+    pub has_keypair: bool,
+    /// Account the active keypair belongs to (`None` when locked or not yet bound).
+    pub account_id: Option<String>,
+    /// A Shamir prime other than the built-in default was configured.
+    pub shamir_configured: bool,
+    /// Relay server URL and both server-lock routes are set.
+    pub relay_urls_set: bool,
+    pub prime_bits: u64,
+}
+
+/// One GENERATE_VRF_CHALLENGES entry, in request order: a challenge, or why this digest
+/// could not be used. A failed entry does not affect the others.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VrfChallengeBatchItem {
+    pub intent_digest: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub challenge: Option<VRFChallengeData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VrfChallengeBatch {
+    pub challenges: Vec<VrfChallengeBatchItem>,
+    /// Challenges minted (and, with a `sessionId`, charged against its use budget).
+    pub minted: u32,
+    /// The session's uses left after this batch; `None` without a session or use limit.
+    pub remaining_uses: Option<u32>,
+}
+
+/// What LOGOUT_ALL wiped, for sign-out telemetry.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LogoutAllSummary {
+    pub cleared_sessions: usize,
+    pub cleared_challenges: usize,
+    pub cleared_active_keypair: bool,
+    /// Batch-unlocked keypairs (UNLOCK_VRF_KEYPAIRS), including a copy of the active one.
+    pub cleared_unlocked_keypairs: usize,
 }
 
 impl VRFKeyManager {
@@ -133,7 +273,32 @@ impl VRFKeyManager {
             relay_server_url,
             apply_lock_route,
             remove_lock_route,
+            default_rpc_headers: RpcHeaders::new(),
+            verification_cache: VerificationCache::new(),
+            block_context_source: None,
+            block_context: None,
+            unlocked_vrf_keypairs: HashMap::new(),
+            state_changes: StateChangeLog::default(),
+            logout_epoch: 0,
+            vrf_account_id: None,
+        }
+    }
+
+    /// Bump the state version for a mutation and remember it for GET_STATE_CHANGES_SINCE.
+    pub fn record_state_change(
+        &mut self,
+        kind: StateChangeKind,
+        account_id: Option<&str>,
+        now_ms: f64,
+    ) {
+        // Every keypair activation and logout is recorded here, so this is also where the
+        // active account is tracked.
+        match kind {
+            StateChangeKind::Unlock => self.vrf_account_id = account_id.map(str::to_string),
+            StateChangeKind::Logout => self.vrf_account_id = None,
+            _ => {}
         }
+        self.state_changes.record(kind, account_id, now_ms);
     }
 
     /// Get a reference to the Shamir3Pass instance
@@ -165,12 +330,25 @@ impl VRFKeyManager {
 
     /// Store/replace a VRF-owned session.
     pub fn upsert_session(&mut self, session_id: &str, session: VrfSessionData) {
+        let created_at_ms = session.created_at_ms;
         self.sessions.insert(session_id.to_string(), session);
+        self.record_state_change(StateChangeKind::SessionCreated, None, created_at_ms);
     }
 
-    /// Clear session material for a given session id.
-    pub fn clear_session(&mut self, session_id: &str) {
-        self.sessions.remove(session_id);
+    /// Clear session material for a given session id. Returns whether a session was cached.
+    pub fn clear_session(&mut self, session_id: &str) -> bool {
+        let cleared = self.sessions.remove(session_id).is_some();
+        if cleared {
+            self.record_state_change(StateChangeKind::SessionCleared, None, progress::now_ms());
+        }
+        cleared
+    }
+
+    /// Drop a session whose TTL has passed.
+    pub fn expire_session(&mut self, session_id: &str, now_ms: f64) {
+        if self.sessions.remove(session_id).is_some() {
+            self.record_state_change(StateChangeKind::SessionExpired, None, now_ms);
+        }
     }
 
     /// Dispense WrapKeySeed + wrapKeySalt for a session, enforcing TTL and usage budget.
@@ -189,12 +367,13 @@ impl VRFKeyManager {
             .ok_or_else(|| VrfWorkerError::SessionNotFound(session_id.to_string()))?;
 
         if session.is_expired(now_ms) {
-            self.sessions.remove(session_id);
+            self.expire_session(session_id, now_ms);
             return Err(VrfWorkerError::SessionExpired);
         }
 
         if !session.can_consume(uses) {
             self.sessions.remove(session_id);
+            self.record_state_change(StateChangeKind::SessionCleared, None, now_ms);
             return Err(VrfWorkerError::SessionExhausted);
         }
 
@@ -204,9 +383,70 @@ impl VRFKeyManager {
 
         let wrap_key_seed_b64u = base64_url_encode(&session.wrap_key_seed);
         let wrap_key_salt_b64u = session.wrap_key_salt_b64u.clone();
+        self.record_state_change(StateChangeKind::SessionConsumed, None, now_ms);
         Ok((wrap_key_seed_b64u, wrap_key_salt_b64u))
     }
 
+    /// Refuse to re-mint over a cached session that has expired or used up its budget.
+    ///
+    /// Unknown session ids are fine (a fresh session will be minted). Expired or exhausted
+    /// sessions are cleared and reported as `SessionExpired` / `SessionExhausted`.
+    pub fn ensure_session_usable(&mut self, session_id: &str, now_ms: f64) -> VrfResult<()> {
+        let Some(session) = self.sessions.get(session_id) else {
+            return Ok(());
+        };
+
+        if session.is_expired(now_ms) {
+            self.expire_session(session_id, now_ms);
+            return Err(VrfWorkerError::SessionExpired);
+        }
+
+        if !session.can_consume(1) {
+            self.sessions.remove(session_id);
+            self.record_state_change(StateChangeKind::SessionCleared, None, now_ms);
+            return Err(VrfWorkerError::SessionExhausted);
+        }
+
+        Ok(())
+    }
+
+    /// Extend a live session's expiry by `ttl_ms` without a new WebAuthn prompt.
+    ///
+    /// The new expiry is capped at `renewable_until_ms`. Expired sessions and sessions minted
+    /// under a different policy digest are cleared. Returns the new `expires_at_ms`.
+    pub fn renew_session(
+        &mut self,
+        session_id: &str,
+        ttl_ms: u64,
+        policy_digest: Option<&str>,
+        now_ms: f64,
+    ) -> VrfResult<f64> {
+        let session = self
+            .sessions
+            .get_mut(session_id)
+            .ok_or_else(|| VrfWorkerError::SessionNotFound(session_id.to_string()))?;
+
+        if session.is_expired(now_ms) {
+            self.expire_session(session_id, now_ms);
+            return Err(VrfWorkerError::SessionExpired);
+        }
+
+        if session.policy_digest.as_deref() != policy_digest {
+            self.sessions.remove(session_id);
+            self.record_state_change(StateChangeKind::SessionCleared, None, now_ms);
+            return Err(VrfWorkerError::SessionPolicyMismatch);
+        }
+
+        let Some(renewable_until_ms) = session.renewable_until_ms.filter(|u| now_ms < *u) else {
+            return Err(VrfWorkerError::SessionRenewalWindowClosed);
+        };
+
+        let expires_at_ms = (now_ms + ttl_ms as f64).min(renewable_until_ms);
+        session.expires_at_ms = Some(expires_at_ms);
+        self.record_state_change(StateChangeKind::SessionRenewed, None, now_ms);
+        Ok(expires_at_ms)
+    }
+
     /// Get secret key bytes for the current VRF keypair (error if not unlocked)
     pub fn get_vrf_secret_key_bytes(&self) -> VrfResult<Vec<u8>> {
         if !self.session_active {
@@ -219,9 +459,34 @@ impl VRFKeyManager {
         Ok(sk.secret_key_bytes())
     }
 
+    /// Derive WrapKeySeed = HKDF(HKDF(PRF.first, "vrf-wrap-pass") || vrf_sk, "near-wrap-seed")
+    /// from the current in-memory VRF keypair.
+    pub fn derive_wrap_key_seed(&self, prf_first: &[u8]) -> VrfResult<Vec<u8>> {
+        let mut k_pass_auth = vec![0u8; 32];
+        Hkdf::<Sha256>::new(None, prf_first)
+            .expand(VRF_WRAP_PASS_INFO, &mut k_pass_auth)
+            .map_err(|_| VrfWorkerError::HkdfDerivationFailed(HkdfError::KeyDerivationFailed))?;
+
+        let mut vrf_secret = self.get_vrf_secret_key_bytes()?;
+        let mut ikm = Vec::with_capacity(k_pass_auth.len() + vrf_secret.len());
+        ikm.extend_from_slice(&k_pass_auth);
+        ikm.extend_from_slice(&vrf_secret);
+
+        let mut wrap_key_seed = vec![0u8; 32];
+        let expanded =
+            Hkdf::<Sha256>::new(None, &ikm).expand(NEAR_WRAP_SEED_INFO, &mut wrap_key_seed);
+        k_pass_auth.zeroize();
+        vrf_secret.zeroize();
+        ikm.zeroize();
+        expanded
+            .map_err(|_| VrfWorkerError::HkdfDerivationFailed(HkdfError::KeyDerivationFailed))?;
+        Ok(wrap_key_seed)
+    }
+
     pub fn generate_vrf_keypair_bootstrap(
         &mut self,
         vrf_input_data: Option<VRFInputData>,
+        deterministic_seed: Option<&[u8]>,
     ) -> VrfResult<GenerateVrfKeypairBootstrapResponse> {
         debug!("Generating VRF keypair for bootstrapping");
         debug!("VRF keypair will be stored in memory unencrypted until PRF encryption");
@@ -236,8 +501,16 @@ impl VRFKeyManager {
             self.vrf_challenges.clear();
         }
 
-        // Generate VRF keypair with cryptographically secure randomness
-        let vrf_keypair = self.generate_vrf_keypair()?;
+        // Generate VRF keypair with cryptographically secure randomness, unless a
+        // dev-only deterministic seed was supplied (dev_deterministic_vrf builds only)
+        let vrf_keypair = match deterministic_seed {
+            Some(seed) => {
+                warn!("Generating DETERMINISTIC dev VRF keypair; never use outside localnet");
+                let prf_output = Self::derive_dev_deterministic_prf_output(seed)?;
+                self.generate_vrf_keypair_from_seed(&prf_output, "dev-deterministic")?
+            }
+            None => self.generate_vrf_keypair()?,
+        };
 
         // Get public key bytes for response
         let vrf_public_key_bytes = bincode::serialize(&vrf_keypair.pk).map_err(|e| {
@@ -251,10 +524,12 @@ impl VRFKeyManager {
         self.vrf_keypair = Some(SecureVRFKeyPair::new(vrf_keypair));
         self.session_active = true;
         self.session_start_time = Date::now();
+        self.record_state_change(StateChangeKind::Unlock, None, self.session_start_time);
 
         let mut result = GenerateVrfKeypairBootstrapResponse {
             vrf_public_key: vrf_public_key_b64,
             vrf_challenge_data: None,
+            deterministic: deterministic_seed.is_some(),
         };
 
         // Generate VRF challenge if input parameters provided
@@ -337,11 +612,104 @@ impl VRFKeyManager {
         self.vrf_keypair = Some(SecureVRFKeyPair::new(decrypted_keypair));
         self.session_active = true;
         self.session_start_time = Date::now();
+        self.record_state_change(
+            StateChangeKind::Unlock,
+            Some(near_account_id.as_str()),
+            self.session_start_time,
+        );
 
         debug!("VRF keypair unlocked successfully");
         Ok(())
     }
 
+    /// Unlock several encrypted VRF keypairs at once (multi-account restore).
+    ///
+    /// Each entry is decrypted independently; failures are reported per account and do
+    /// not affect the others. The first successfully unlocked keypair becomes the active
+    /// one. If nothing unlocks, the current keypair and sessions are left untouched.
+    pub fn unlock_vrf_keypairs(
+        &mut self,
+        entries: Vec<(String, EncryptedVRFKeypair, Vec<u8>)>,
+        now_ms: f64,
+    ) -> Vec<VrfKeypairUnlockOutcome> {
+        let mut unlocked: Vec<(String, ECVRFKeyPair)> = Vec::new();
+        let mut outcomes = Vec::with_capacity(entries.len());
+
+        for (near_account_id, encrypted_vrf_keypair, prf_key) in entries {
+            let result = if near_account_id.is_empty() {
+                Err(VrfWorkerError::missing_field("nearAccountId"))
+            } else if prf_key.is_empty() {
+                Err(VrfWorkerError::empty_prf_output())
+            } else {
+                self.decrypt_vrf_keypair(encrypted_vrf_keypair, prf_key)
+            };
+            match result {
+                Ok(keypair) => {
+                    unlocked.push((near_account_id.clone(), keypair));
+                    outcomes.push(VrfKeypairUnlockOutcome {
+                        near_account_id,
+                        success: true,
+                        error: None,
+                    });
+                }
+                Err(e) => {
+                    warn!("VRF keypair unlock failed for {}: {}", near_account_id, e);
+                    outcomes.push(VrfKeypairUnlockOutcome {
+                        near_account_id,
+                        success: false,
+                        error: Some(e.to_string()),
+                    });
+                }
+            }
+        }
+
+        let Some(active_account_id) = unlocked.first().map(|(id, _)| id.clone()) else {
+            return outcomes;
+        };
+        for (near_account_id, keypair) in unlocked {
+            self.unlocked_vrf_keypairs
+                .insert(near_account_id, SecureVRFKeyPair::new(keypair));
+        }
+        if let Err(e) = self.activate_unlocked_vrf_keypair(&active_account_id, now_ms) {
+            warn!("Failed to activate unlocked VRF keypair: {}", e);
+        }
+        outcomes
+    }
+
+    /// Make a keypair from `unlocked_vrf_keypairs` the active one.
+    pub fn activate_unlocked_vrf_keypair(
+        &mut self,
+        near_account_id: &str,
+        now_ms: f64,
+    ) -> VrfResult<()> {
+        let stored = self
+            .unlocked_vrf_keypairs
+            .get(near_account_id)
+            .ok_or(VrfWorkerError::VrfNotUnlocked)?;
+        // Copy via bincode (the stored bytes format) so the map keeps its own instance.
+        let mut keypair_bytes = bincode::serialize(stored.inner()).map_err(|e| {
+            VrfWorkerError::SerializationError(SerializationError::VrfKeypairSerialization(
+                e.to_string(),
+            ))
+        })?;
+        let keypair: Result<ECVRFKeyPair, _> = bincode::deserialize(&keypair_bytes);
+        keypair_bytes.zeroize();
+        let keypair = keypair.map_err(|e| {
+            VrfWorkerError::SerializationError(SerializationError::VrfKeypairDeserialization(
+                e.to_string(),
+            ))
+        })?;
+        // Per-session state is bound to the previously active keypair.
+        self.sessions.clear();
+        self.vrf_challenges.clear();
+        self.vrf_keypair = Some(SecureVRFKeyPair::new(keypair));
+        self.session_active = true;
+        self.session_start_time = now_ms;
+        self.record_state_change(StateChangeKind::Unlock, Some(near_account_id), now_ms);
+        debug!("Activated VRF keypair for {}", near_account_id);
+        Ok(())
+    }
+
     /// Load a plaintext VRF keypair from VRFKeypairData (used after Shamir 3‑pass unlock)
     pub fn load_plaintext_vrf_keypair(
         &mut self,
@@ -359,10 +727,20 @@ impl VRFKeyManager {
             self.vrf_challenges.clear();
         }
         // Reconstruct ECVRFKeyPair from stored bytes
-        let keypair: ECVRFKeyPair = bincode::deserialize(&keypair_data.keypair_bytes)?;
+        let keypair: ECVRFKeyPair =
+            bincode::deserialize(&keypair_data.keypair_bytes).map_err(|e| {
+                VrfWorkerError::SerializationError(SerializationError::VrfKeypairDeserialization(
+                    e.to_string(),
+                ))
+            })?;
         self.vrf_keypair = Some(SecureVRFKeyPair::new(keypair));
         self.session_active = true;
         self.session_start_time = Date::now();
+        self.record_state_change(
+            StateChangeKind::Unlock,
+            Some(near_account_id.as_str()),
+            self.session_start_time,
+        );
         Ok(())
     }
 
@@ -377,98 +755,122 @@ impl VRFKeyManager {
         self.generate_vrf_challenge_with_keypair(vrf_keypair, input_data)
     }
 
-    /// Generate VRF challenge using a specific keypair (can be in-memory or provided)
-    pub fn generate_vrf_challenge_with_keypair(
-        &self,
-        vrf_keypair: &ECVRFKeyPair,
-        input_data: VRFInputData,
-    ) -> VrfResult<VRFChallengeData> {
-        debug!("Generating VRF challenge using provided keypair");
+    /// Mint one challenge per intent digest from a single unlock, all bound to `base`'s block
+    /// context. Each digest changes the VRF input, so the outputs are unlinkable. Malformed or
+    /// repeated digests fail only their own entry. With `session_id`, every minted challenge
+    /// costs one session use; entries past the remaining budget fail with SESSION_EXHAUSTED.
+    pub fn generate_vrf_challenges(
+        &mut self,
+        base: VRFInputData,
+        intent_digests: &[String],
+        max_count: Option<usize>,
+        session_id: Option<&str>,
+        now_ms: f64,
+    ) -> VrfResult<VrfChallengeBatch> {
+        if !self.session_active || self.vrf_keypair.is_none() {
+            return Err(VrfWorkerError::VrfNotUnlocked);
+        }
+        if base.intent_digest.is_some() {
+            return Err(VrfWorkerError::invalid_format(
+                "base.intentDigest must be omitted; pass each digest in intentDigests",
+            ));
+        }
+        let cap = max_count
+            .unwrap_or(VRF_CHALLENGE_BATCH_MAX_COUNT)
+            .min(VRF_CHALLENGE_BATCH_MAX_COUNT);
+        if intent_digests.is_empty() || intent_digests.len() > cap {
+            return Err(VrfWorkerError::invalid_format(&format!(
+                "intentDigests must contain 1..={} entries, got {}",
+                cap,
+                intent_digests.len()
+            )));
+        }
 
-        // Construct VRF input according to specification from the contract test
-        let domain_separator = VRF_DOMAIN_SEPARATOR;
-        let user_id_bytes = input_data.user_id.as_bytes();
-        // Match on-chain derivation: the contract lowercases rp_id before hashing into vrf_input_data.
-        // Normalize here so vrf_input_data remains consistent even if callers pass mixed-case domains.
-        let rp_id_normalized = input_data.rp_id.to_ascii_lowercase();
-        let rp_id_bytes = rp_id_normalized.as_bytes();
-        let block_height_num = parse_block_height(&input_data.block_height)?;
-        let block_height_bytes = block_height_num.to_le_bytes();
-
-        // Decode block_hash from base58 string to bytes
-        let block_hash_bytes = bs58::decode(&input_data.block_hash)
-            .into_vec()
-            .map_err(|e| VrfWorkerError::invalid_format(&format!("invalid blockHash: {}", e)))?;
-
-        // Optional 32-byte intent digest (base64url) to bind into the VRF input hash.
-        // When present, it must decode to exactly 32 bytes and will be appended to the input.
-        let intent_digest_b64u = input_data
-            .intent_digest
-            .clone()
-            .and_then(|s| {
-                let trimmed = s.trim().to_string();
-                if trimmed.is_empty() { None } else { Some(trimmed) }
-            });
-        let intent_digest_bytes = match intent_digest_b64u.as_deref() {
-            Some(b64u) => {
-                let bytes = base64_url_decode(b64u).map_err(|e| {
-                    VrfWorkerError::invalid_format(&format!("invalid intentDigest (base64url): {}", e))
-                })?;
-                if bytes.len() != 32 {
-                    return Err(VrfWorkerError::invalid_format(&format!(
-                        "invalid intentDigest length: expected 32 bytes, got {}",
-                        bytes.len()
-                    )));
+        let mut budget = match session_id {
+            Some(session_id) => {
+                let session = self
+                    .sessions
+                    .get(session_id)
+                    .ok_or_else(|| VrfWorkerError::SessionNotFound(session_id.to_string()))?;
+                if session.is_expired(now_ms) {
+                    self.expire_session(session_id, now_ms);
+                    return Err(VrfWorkerError::SessionExpired);
                 }
-                Some(bytes)
+                session.remaining_uses
             }
             None => None,
         };
 
-        // Optional 32-byte session policy digest (base64url) to bind into the VRF input hash.
-        // When present, it must decode to exactly 32 bytes and will be appended to the input.
-        let session_policy_digest_b64u = input_data
-            .session_policy_digest_32
-            .clone()
-            .and_then(|s| {
-                let trimmed = s.trim().to_string();
-                if trimmed.is_empty() { None } else { Some(trimmed) }
+        let vrf_keypair = self.vrf_keypair.as_ref().unwrap().inner();
+        let mut seen: Vec<&str> = Vec::with_capacity(intent_digests.len());
+        let mut minted = 0u32;
+        let mut challenges = Vec::with_capacity(intent_digests.len());
+        for digest in intent_digests {
+            let digest_trimmed = digest.trim();
+            let result = if seen.contains(&digest_trimmed) {
+                Err(VrfWorkerError::invalid_format(
+                    "duplicate intentDigest; each challenge needs its own digest",
+                ))
+            } else if budget == Some(0) {
+                Err(VrfWorkerError::SessionExhausted)
+            } else {
+                seen.push(digest_trimmed);
+                let mut input = base.clone();
+                input.intent_digest = Some(digest_trimmed.to_string());
+                self.generate_vrf_challenge_with_keypair(vrf_keypair, input)
+            };
+            challenges.push(match result {
+                Ok(challenge) => {
+                    minted += 1;
+                    budget = budget.map(|n| n - 1);
+                    VrfChallengeBatchItem {
+                        intent_digest: digest.clone(),
+                        challenge: Some(challenge),
+                        error_code: None,
+                        error: None,
+                    }
+                }
+                Err(e) => VrfChallengeBatchItem {
+                    intent_digest: digest.clone(),
+                    challenge: None,
+                    error_code: Some(e.code()),
+                    error: Some(e.to_string()),
+                },
             });
-        let session_policy_digest_bytes = match session_policy_digest_b64u.as_deref() {
-            Some(b64u) => {
-                let bytes = base64_url_decode(b64u).map_err(|e| {
-                    VrfWorkerError::invalid_format(&format!(
-                        "invalid sessionPolicyDigest32 (base64url): {}",
-                        e
-                    ))
-                })?;
-                if bytes.len() != 32 {
-                    return Err(VrfWorkerError::invalid_format(&format!(
-                        "invalid sessionPolicyDigest32 length: expected 32 bytes, got {}",
-                        bytes.len()
-                    )));
+        }
+
+        if let Some(session_id) = session_id {
+            if minted > 0 {
+                if let Some(session) = self.sessions.get_mut(session_id) {
+                    session.decrement_uses(minted);
                 }
-                Some(bytes)
+                self.record_state_change(StateChangeKind::SessionConsumed, None, now_ms);
             }
-            None => None,
-        };
-
-        // Concatenate all input components following the test pattern
-        let mut vrf_input_data = Vec::new();
-        vrf_input_data.extend_from_slice(domain_separator);
-        vrf_input_data.extend_from_slice(user_id_bytes);
-        vrf_input_data.extend_from_slice(rp_id_bytes);
-        vrf_input_data.extend_from_slice(&block_height_bytes);
-        vrf_input_data.extend_from_slice(&block_hash_bytes);
-        if let Some(bytes) = intent_digest_bytes.as_deref() {
-            vrf_input_data.extend_from_slice(bytes);
-        }
-        if let Some(bytes) = session_policy_digest_bytes.as_deref() {
-            vrf_input_data.extend_from_slice(bytes);
         }
 
-        // Hash the input data (VRF input should be hashed)
-        let vrf_input = Sha256::digest(&vrf_input_data).to_vec();
+        Ok(VrfChallengeBatch {
+            challenges,
+            minted,
+            remaining_uses: budget,
+        })
+    }
+
+    /// Generate VRF challenge using a specific keypair (can be in-memory or provided)
+    pub fn generate_vrf_challenge_with_keypair(
+        &self,
+        vrf_keypair: &ECVRFKeyPair,
+        input_data: VRFInputData,
+    ) -> VrfResult<VRFChallengeData> {
+        debug!("Generating VRF challenge using provided keypair");
+
+        let ResolvedVrfInput {
+            vrf_input,
+            rp_id: rp_id_normalized,
+            block_hash_bytes,
+            intent_digest_b64u,
+            session_policy_digest_b64u,
+            extra_context_digest_b64u,
+        } = compute_vrf_input(&input_data)?;
 
         // Generate VRF proof and output using the proper vrf-wasm API
         let proof = vrf_keypair.prove(&vrf_input);
@@ -495,6 +897,7 @@ impl VRFKeyManager {
             block_hash: base64_url_encode(&block_hash_bytes),
             intent_digest: intent_digest_b64u,
             session_policy_digest_32: session_policy_digest_b64u,
+            extra_context_digest_32: extra_context_digest_b64u,
         };
 
         Ok(result)
@@ -518,10 +921,18 @@ impl VRFKeyManager {
             active: self.session_active,
             session_duration,
             vrf_public_key,
+            state_version: self.state_changes.version(),
+            logout_epoch: self.logout_epoch,
         }
     }
 
     pub fn logout(&mut self) -> VrfResult<()> {
+        self.logout_at_epoch(None).map(|_| ())
+    }
+
+    /// `logout` that completes `requested` (the epoch the TS shell also sent the signer worker's
+    /// CLEAR_ALL_SESSIONS), or the next epoch when none is given. Returns the epoch now current.
+    pub fn logout_at_epoch(&mut self, requested: Option<u64>) -> VrfResult<u64> {
         // Clear VRF keypair (automatic zeroization via ZeroizeOnDrop)
         if self.vrf_keypair.take().is_some() {
             debug!("VRF keypair cleared with zeroization");
@@ -536,6 +947,17 @@ impl VRFKeyManager {
             self.sessions.clear();
             debug!("Cleared cached VRF sessions on logout");
         }
+        if !self.verification_cache.is_empty() {
+            self.verification_cache.clear();
+            debug!("Cleared cached contract verifications on logout");
+        }
+        if !self.unlocked_vrf_keypairs.is_empty() {
+            self.unlocked_vrf_keypairs.clear();
+            debug!("Cleared batch-unlocked VRF keypairs on logout");
+        }
+        // Block context is public chain data, but it is tied to the logged-out user's last
+        // challenge; the next user fetches their own.
+        self.block_context = None;
 
         // Best-effort: close and drop any attached MessagePorts for WrapKeySeed delivery.
         // These ports are one-shot, but if a signing flow is interrupted (e.g., user logs out
@@ -549,7 +971,50 @@ impl VRFKeyManager {
         // Clear session data
         self.session_active = false;
         self.session_start_time = 0.0;
-        Ok(())
+        self.logout_epoch = requested.unwrap_or(self.logout_epoch + 1);
+        self.record_state_change(StateChangeKind::Logout, None, progress::now_ms());
+        Ok(self.logout_epoch)
+    }
+
+    pub fn diagnostics(&self) -> VrfDiagnostics {
+        let is_set =
+            |value: &Option<String>| value.as_deref().is_some_and(|s| !s.trim().is_empty());
+        VrfDiagnostics {
+            session_active: self.session_active,
+            has_keypair: self.vrf_keypair.is_some(),
+            account_id: self.vrf_account_id.clone(),
+            shamir_configured: self.shamir3pass.p() != Shamir3Pass::new_default().p(),
+            relay_urls_set: is_set(&self.relay_server_url)
+                && is_set(&self.apply_lock_route)
+                && is_set(&self.remove_lock_route),
+            prime_bits: self.shamir3pass.p().bits(),
+        }
+    }
+
+    /// Drop every secret the manager holds (active and batch-unlocked keypairs, sessions,
+    /// cached challenges) without touching ports or the state log. Called from the
+    /// `panic_guard` hook, so it must not panic.
+    pub fn wipe_secrets_after_panic(&mut self) {
+        self.vrf_keypair = None;
+        self.unlocked_vrf_keypairs.clear();
+        self.sessions.clear();
+        self.vrf_challenges.clear();
+        self.verification_cache.clear();
+        self.session_active = false;
+        self.vrf_account_id = None;
+    }
+
+    /// Full sign-out: every session, challenge, verification and unlocked keypair (all
+    /// zeroized on drop), not just the active one. Returns how much was cleared.
+    pub fn logout_all(&mut self) -> VrfResult<LogoutAllSummary> {
+        let summary = LogoutAllSummary {
+            cleared_sessions: self.sessions.len(),
+            cleared_challenges: self.vrf_challenges.len(),
+            cleared_active_keypair: self.vrf_keypair.is_some(),
+            cleared_unlocked_keypairs: self.unlocked_vrf_keypairs.len(),
+        };
+        self.logout()?;
+        Ok(summary)
     }
 
     /// Derive deterministic VRF keypair from PRF output for recovery
@@ -560,12 +1025,29 @@ impl VRFKeyManager {
         prf_output: Vec<u8>,
         near_account_id: String,
         vrf_input_params: Option<VRFInputData>,
+    ) -> VrfResult<(DeterministicVrfKeypairResponse, ECVRFKeyPair)> {
+        self.derive_vrf_keypair_from_prf_with_progress(
+            prf_output,
+            near_account_id,
+            vrf_input_params,
+            &VrfProgress::silent(),
+        )
+    }
+
+    /// `derive_vrf_keypair_from_prf`, reporting the derivation and encryption phases.
+    pub fn derive_vrf_keypair_from_prf_with_progress(
+        &self,
+        prf_output: Vec<u8>,
+        near_account_id: String,
+        vrf_input_params: Option<VRFInputData>,
+        progress: &VrfProgress,
     ) -> VrfResult<(DeterministicVrfKeypairResponse, ECVRFKeyPair)> {
         if prf_output.is_empty() {
             return Err(VrfWorkerError::empty_prf_output());
         }
 
         // Generate deterministic VRF keypair from PRF output
+        progress.emit(VrfProgressPhase::Derivation, None);
         let vrf_keypair = self.generate_vrf_keypair_from_seed(&prf_output, &near_account_id)?;
 
         // Get public key bytes for response
@@ -577,6 +1059,7 @@ impl VRFKeyManager {
         let vrf_public_key_b64 = base64_url_encode(&vrf_public_key_bytes);
 
         // Encrypt the VRF keypair with the same PRF output used for derivation (for local storage)
+        progress.emit(VrfProgressPhase::Encryption, None);
         let (_public_key, encrypted_vrf_keypair) =
             self.encrypt_vrf_keypair_data(&vrf_keypair, &prf_output)?;
 
@@ -594,6 +1077,7 @@ impl VRFKeyManager {
             vrf_challenge_data,
             encrypted_vrf_keypair: Some(encrypted_vrf_keypair),
             success: true,
+            deterministic: false,
             server_encrypted_vrf_keypair: None,
             // added next in handler.rs: perform_shamir3pass_client_encrypt_current_vrf_keypair
         };
@@ -606,6 +1090,16 @@ impl VRFKeyManager {
         &mut self,
         vrf_keypair: ECVRFKeyPair,
         near_account_id: String,
+    ) {
+        self.store_vrf_keypair_in_memory_at(vrf_keypair, near_account_id, js_sys::Date::now());
+    }
+
+    /// `store_vrf_keypair_in_memory` with an explicit session start time.
+    pub fn store_vrf_keypair_in_memory_at(
+        &mut self,
+        vrf_keypair: ECVRFKeyPair,
+        near_account_id: String,
+        now_ms: f64,
     ) {
         debug!(
             "Storing VRF keypair in worker memory for account: {}",
@@ -622,7 +1116,8 @@ impl VRFKeyManager {
         }
         self.vrf_keypair = Some(SecureVRFKeyPair::new(vrf_keypair));
         self.session_active = true;
-        self.session_start_time = js_sys::Date::now();
+        self.session_start_time = now_ms;
+        self.record_state_change(StateChangeKind::Unlock, Some(near_account_id.as_str()), now_ms);
         debug!("VRF keypair stored in memory for future operations");
     }
 
@@ -663,12 +1158,9 @@ impl VRFKeyManager {
             .decrypt(nonce, encrypted_data.as_ref())
             .map_err(|e| VrfWorkerError::AesGcmError(AesError::DecryptionFailed(e.to_string())))?;
 
-        // Parse decrypted keypair data using bincode (not JSON)
-        let keypair_data: VRFKeypairData = bincode::deserialize(&decrypted_data).map_err(|e| {
-            VrfWorkerError::SerializationError(SerializationError::KeypairDataDeserialization(
-                e.to_string(),
-            ))
-        })?;
+        // Parse decrypted keypair data: version byte + bincode (not JSON)
+        let keypair_data = VRFKeypairData::from_versioned_bytes(&decrypted_data)
+            .map_err(VrfWorkerError::SerializationError)?;
 
         // Reconstruct ECVRFKeyPair from the stored bincode bytes
         // This preserves the exact original keypair without regeneration
@@ -686,6 +1178,7 @@ impl VRFKeyManager {
     /// Generate a new VRF keypair with cryptographically secure randomness
     fn generate_vrf_keypair(&self) -> VrfResult<ECVRFKeyPair> {
         debug!("Generating VRF keypair with secure randomness");
+        ensure_randomness_ok().map_err(VrfWorkerError::RandomnessUnavailable)?;
 
         // Generate VRF keypair with cryptographically secure randomness
         let mut rng = WasmRngFromSeed::from_entropy();
@@ -726,6 +1219,18 @@ impl VRFKeyManager {
         Ok(vrf_keypair)
     }
 
+    /// Dev-only: expand a `deterministicSeedB64u` seed into 32 bytes that stand in
+    /// for PRF.second, so bootstrap and PRF derivation yield the same keypair per seed.
+    pub fn derive_dev_deterministic_prf_output(seed: &[u8]) -> VrfResult<Vec<u8>> {
+        let hk = Hkdf::<Sha256>::new(None, seed);
+        let mut prf_output = vec![0u8; VRF_SEED_SIZE];
+        hk.expand(HKDF_DEV_DETERMINISTIC_VRF_INFO, &mut prf_output)
+            .map_err(|_| {
+                VrfWorkerError::HkdfDerivationFailed(HkdfError::VrfSeedDerivationFailed)
+            })?;
+        Ok(prf_output)
+    }
+
     /// Encrypt VRF keypair data using PRF-derived AES key
     pub fn encrypt_vrf_keypair_data(
         &self,
@@ -746,12 +1251,10 @@ impl VRFKeyManager {
             public_key_base64: base64_url_encode(&vrf_public_key_bytes),
         };
 
-        // Serialize the VRF keypair data using bincode
-        let keypair_data_bytes = bincode::serialize(&keypair_data).map_err(|e| {
-            VrfWorkerError::SerializationError(SerializationError::KeypairDataSerialization(
-                format!("{:?}", e),
-            ))
-        })?;
+        // Serialize the VRF keypair data (version byte + bincode)
+        let keypair_data_bytes = keypair_data
+            .to_versioned_bytes()
+            .map_err(VrfWorkerError::SerializationError)?;
 
         // Encrypt the VRF keypair data using AES-GCM
         let encrypted_keypair = self.encrypt_vrf_keypair(&keypair_data_bytes, prf_key)?;
@@ -775,6 +1278,7 @@ impl VRFKeyManager {
         let cipher = ChaCha20Poly1305::new(key_slice);
 
         // Generate cryptographically secure random IV/nonce
+        ensure_randomness_ok().map_err(VrfWorkerError::RandomnessUnavailable)?;
         let mut iv_nonce_bytes = [0u8; CHACHA20_NONCE_SIZE];
         getrandom(&mut iv_nonce_bytes).map_err(|e| {
             VrfWorkerError::AesGcmError(AesError::IvGenerationFailed(e.to_string()))
@@ -791,3 +1295,144 @@ impl VRFKeyManager {
         })
     }
 }
+
+/// Everything `generate_vrf_challenge_with_keypair` derives from `VRFInputData` before proving.
+pub struct ResolvedVrfInput {
+    /// SHA-256 of the concatenated input components: the exact bytes fed to the VRF.
+    pub vrf_input: Vec<u8>,
+    pub rp_id: String,
+    pub block_hash_bytes: Vec<u8>,
+    pub intent_digest_b64u: Option<String>,
+    pub session_policy_digest_b64u: Option<String>,
+    pub extra_context_digest_b64u: Option<String>,
+}
+
+/// Derive the VRF input from `VRFInputData` without a keypair (see `compute_vrf_input_b64u`).
+pub fn compute_vrf_input(input_data: &VRFInputData) -> VrfResult<ResolvedVrfInput> {
+    // Construct VRF input according to specification from the contract test
+    let domain_separator = VRF_DOMAIN_SEPARATOR;
+    let user_id_bytes = input_data.user_id.as_bytes();
+    // Match on-chain derivation: the contract lowercases rp_id before hashing into vrf_input_data.
+    // Normalize here so vrf_input_data remains consistent even if callers pass mixed-case domains.
+    let rp_id_normalized = input_data
+        .rp_id_normalization
+        .normalize(&input_data.rp_id)
+        .map_err(|e| VrfWorkerError::invalid_format(&e))?;
+    let rp_id_bytes = rp_id_normalized.as_bytes();
+    let block_height_num = parse_block_height(&input_data.block_height)?;
+    let block_height_bytes = block_height_num.to_le_bytes();
+
+    // Decode block_hash from base58 string to bytes
+    let block_hash_bytes = bs58::decode(&input_data.block_hash)
+        .into_vec()
+        .map_err(|e| VrfWorkerError::invalid_format(&format!("invalid blockHash: {}", e)))?;
+
+    // Optional 32-byte intent digest (base64url) to bind into the VRF input hash.
+    // When present, it must decode to exactly 32 bytes and will be appended to the input.
+    let intent_digest_b64u = input_data
+        .intent_digest
+        .clone()
+        .and_then(|s| {
+            let trimmed = s.trim().to_string();
+            if trimmed.is_empty() { None } else { Some(trimmed) }
+        });
+    let intent_digest_bytes = match intent_digest_b64u.as_deref() {
+        Some(b64u) => {
+            let bytes = base64_url_decode(b64u).map_err(|e| {
+                VrfWorkerError::invalid_format(&format!("invalid intentDigest (base64url): {}", e))
+            })?;
+            if bytes.len() != 32 {
+                return Err(VrfWorkerError::invalid_format(&format!(
+                    "invalid intentDigest length: expected 32 bytes, got {}",
+                    bytes.len()
+                )));
+            }
+            Some(bytes)
+        }
+        None => None,
+    };
+
+    // Optional 32-byte session policy digest (base64url) to bind into the VRF input hash.
+    // Computed canonically from `sessionPolicyJson` when provided; when present, it must
+    // decode to exactly 32 bytes and will be appended to the input.
+    let session_policy_digest_b64u = resolve_session_policy_digest(
+        input_data.session_policy_json.as_deref(),
+        input_data.session_policy_digest_32.as_deref(),
+    )?;
+    let session_policy_digest_bytes = match session_policy_digest_b64u.as_deref() {
+        Some(b64u) => {
+            let bytes = base64_url_decode(b64u).map_err(|e| {
+                VrfWorkerError::invalid_format(&format!(
+                    "invalid sessionPolicyDigest32 (base64url): {}",
+                    e
+                ))
+            })?;
+            if bytes.len() != 32 {
+                return Err(VrfWorkerError::invalid_format(&format!(
+                    "invalid sessionPolicyDigest32 length: expected 32 bytes, got {}",
+                    bytes.len()
+                )));
+            }
+            Some(bytes)
+        }
+        None => None,
+    };
+
+    // Optional 32-byte app context (base64url). When present, it must decode to exactly
+    // 32 bytes and is appended after its own domain separator.
+    let extra_context_digest_b64u = input_data
+        .extra_context_digest_32
+        .clone()
+        .and_then(|s| {
+            let trimmed = s.trim().to_string();
+            if trimmed.is_empty() { None } else { Some(trimmed) }
+        });
+    let extra_context_digest_bytes = match extra_context_digest_b64u.as_deref() {
+        Some(b64u) => {
+            let bytes = base64_url_decode(b64u).map_err(|e| {
+                VrfWorkerError::invalid_format(&format!(
+                    "invalid extraContextDigest32 (base64url): {}",
+                    e
+                ))
+            })?;
+            if bytes.len() != 32 {
+                return Err(VrfWorkerError::invalid_format(&format!(
+                    "invalid extraContextDigest32 length: expected 32 bytes, got {}",
+                    bytes.len()
+                )));
+            }
+            Some(bytes)
+        }
+        None => None,
+    };
+
+    // Concatenate all input components following the test pattern
+    let mut vrf_input_data = Vec::new();
+    vrf_input_data.extend_from_slice(domain_separator);
+    vrf_input_data.extend_from_slice(user_id_bytes);
+    vrf_input_data.extend_from_slice(rp_id_bytes);
+    vrf_input_data.extend_from_slice(&block_height_bytes);
+    vrf_input_data.extend_from_slice(&block_hash_bytes);
+    if let Some(bytes) = intent_digest_bytes.as_deref() {
+        vrf_input_data.extend_from_slice(bytes);
+    }
+    if let Some(bytes) = session_policy_digest_bytes.as_deref() {
+        vrf_input_data.extend_from_slice(bytes);
+    }
+    if let Some(bytes) = extra_context_digest_bytes.as_deref() {
+        vrf_input_data.extend_from_slice(VRF_EXTRA_CONTEXT_DOMAIN_SEPARATOR);
+        vrf_input_data.extend_from_slice(bytes);
+    }
+
+    // Hash the input data (VRF input should be hashed)
+    let vrf_input = Sha256::digest(&vrf_input_data).to_vec();
+
+    Ok(ResolvedVrfInput {
+        vrf_input,
+        rp_id: rp_id_normalized,
+        block_hash_bytes,
+        intent_digest_b64u,
+        session_policy_digest_b64u,
+        extra_context_digest_b64u,
+    })
+}
@@ -0,0 +1,30 @@
+//! Secret wipe on handler panic.
+//!
+//! Same contract as the signer worker's `panic_guard`: release builds use `panic = "abort"`,
+//! so a handler panic traps the instance and the worker must be recycled. The hook runs
+//! before the trap and wipes the VRF manager's keypairs and sessions if it can borrow them.
+
+use std::panic;
+use std::sync::Once;
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// Install the wiping panic hook (idempotent). Chains to the previously installed hook.
+pub(crate) fn install_panic_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            wipe_manager_secrets();
+            previous(info);
+        }));
+    });
+}
+
+/// Runs inside the panic hook: must not panic itself, so the manager is only `try_borrow`ed.
+fn wipe_manager_secrets() {
+    let _ = crate::VRF_MANAGER.try_with(|manager| {
+        if let Ok(mut manager) = manager.try_borrow_mut() {
+            manager.wipe_secrets_after_panic();
+        }
+    });
+}
@@ -5,9 +5,10 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 
 use crate::fetch::{
-    build_json_post_init, fetch_with_init, response_json, response_ok, response_status,
+    build_json_post_init_with_headers, fetch_with_init, response_ok, response_status,
     response_status_text, response_text,
 };
+use crate::rpc_headers::{redact_rpc_headers_in, validate_rpc_headers, RpcHeaders};
 
 use crate::types::VRFChallengeData;
 use crate::utils::{base64_url_decode, base64_url_encode};
@@ -21,6 +22,9 @@ pub struct ContractVerificationResult {
     pub verified: bool,
     pub error: Option<String>,
     pub logs: Vec<String>,
+    /// Block height the view call was answered at, when the node reported one.
+    #[serde(default)]
+    pub block_height: Option<u64>,
 }
 
 /// VRF challenge data for contract verification
@@ -52,6 +56,14 @@ pub struct VrfData {
         alias = "session_policy_digest_32"
     )]
     pub session_policy_digest_32: Option<Vec<u8>>,
+    /// Optional 32-byte app context bound into VRF input derivation (after its domain separator).
+    #[serde(
+        rename = "extra_context_digest_32",
+        default,
+        skip_serializing_if = "Option::is_none",
+        alias = "extraContextDigest32"
+    )]
+    pub extra_context_digest_32: Option<Vec<u8>>,
 }
 
 impl TryFrom<&VRFChallengeData> for VrfData {
@@ -115,6 +127,24 @@ impl TryFrom<&VRFChallengeData> for VrfData {
                 }
                 _ => None,
             },
+            extra_context_digest_32: match vrf_challenge.extra_context_digest_32.as_deref() {
+                Some(b64u) if !b64u.trim().is_empty() => {
+                    let bytes = base64_url_decode(b64u).map_err(|e| {
+                        wasm_bindgen::JsValue::from_str(&format!(
+                            "Failed to decode extraContextDigest32 (base64url): {}",
+                            e
+                        ))
+                    })?;
+                    if bytes.len() != 32 {
+                        return Err(wasm_bindgen::JsValue::from_str(&format!(
+                            "Invalid extraContextDigest32 length: expected 32 bytes, got {}",
+                            bytes.len()
+                        )));
+                    }
+                    Some(bytes)
+                }
+                _ => None,
+            },
         })
     }
 }
@@ -267,6 +297,7 @@ fn parse_verification_rpc_response(result: &JsValue) -> Result<ContractVerificat
             verified: false,
             error: Some(error_msg),
             logs: vec![],
+            block_height: None,
         });
     }
 
@@ -282,10 +313,16 @@ fn parse_verification_rpc_response(result: &JsValue) -> Result<ContractVerificat
             verified: false,
             error: Some(error_msg),
             logs: vec![],
+            block_height: None,
         });
     }
 
     let logs = extract_string_array(&contract_result, "logs");
+    let block_height = Reflect::get(&contract_result, &JsValue::from_str("block_height"))
+        .ok()
+        .and_then(|v| v.as_f64())
+        .filter(|h| h.is_finite() && *h >= 0.0)
+        .map(|h| h as u64);
     let result_u8 = extract_u8_array(&contract_result, "result")?;
 
     let result_string = String::from_utf8(result_u8)
@@ -312,12 +349,14 @@ fn parse_verification_rpc_response(result: &JsValue) -> Result<ContractVerificat
         verified,
         error,
         logs,
+        block_height,
     })
 }
 
 async fn verify_with_finality(
     contract_id: &str,
     rpc_url: &str,
+    rpc_headers: &RpcHeaders,
     args_base64: &str,
     finality: &'static str,
 ) -> Result<ContractVerificationResult, String> {
@@ -332,7 +371,7 @@ async fn verify_with_finality(
 
         let rpc_body = build_verify_rpc_body(contract_id, args_base64, finality)?;
 
-        match execute_rpc_request(rpc_url, &rpc_body).await {
+        match execute_rpc_request(rpc_url, &rpc_body, rpc_headers).await {
             Ok(raw) => match parse_verification_rpc_response(&raw) {
                 Ok(result) => {
                     if result.verified {
@@ -382,9 +421,12 @@ async fn verify_with_finality(
 pub async fn verify_authentication_response_rpc_call(
     contract_id: &str,
     rpc_url: &str,
+    rpc_headers: &RpcHeaders,
     vrf_data: VrfData,
     webauthn_authentication_credential: WebAuthnAuthenticationCredential,
 ) -> Result<ContractVerificationResult, String> {
+    validate_rpc_headers(rpc_headers)?;
+
     let contract_args_bytes = ContractArgs {
         vrf_data: &vrf_data,
         webauthn_authentication: &webauthn_authentication_credential,
@@ -395,7 +437,8 @@ pub async fn verify_authentication_response_rpc_call(
 
     // Prefer finalized state first, then fall back to optimistic to avoid false-negatives
     // right after authenticator registration (finalized head can lag behind).
-    let final_result = verify_with_finality(contract_id, rpc_url, &args_base64, "final").await;
+    let final_result =
+        verify_with_finality(contract_id, rpc_url, rpc_headers, &args_base64, "final").await;
     if matches!(final_result, Ok(ref r) if r.verified) {
         return final_result;
     }
@@ -403,9 +446,11 @@ pub async fn verify_authentication_response_rpc_call(
     let optimistic_result = verify_with_finality(
         contract_id,
         rpc_url,
+        rpc_headers,
         &args_base64,
-        "optimistic"
-    ).await;
+        "optimistic",
+    )
+    .await;
 
     if matches!(optimistic_result, Ok(ref r) if r.verified) {
         return optimistic_result;
@@ -432,6 +477,8 @@ extern "C" {
 }
 
 async fn sleep(ms: i32) -> Result<(), String> {
+    crate::host_environment::require_host_bridge(crate::host_environment::SET_TIMEOUT)
+        .map_err(|e| e.to_string())?;
     let promise = js_sys::Promise::new(&mut |resolve, _| {
         set_timeout(&resolve, ms);
     });
@@ -441,8 +488,81 @@ async fn sleep(ms: i32) -> Result<(), String> {
     Ok(())
 }
 
-/// Shared HTTP request execution logic for VRF worker
-async fn execute_rpc_request(rpc_url: &str, rpc_body: &JsValue) -> Result<JsValue, String> {
+/// A single NEAR RPC HTTP request, built independently of the transport so header
+/// injection can be tested natively.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcHttpRequest {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcHttpResponse {
+    pub ok: bool,
+    pub status: u16,
+    pub status_text: String,
+    pub body: String,
+}
+
+/// Sends built RPC requests. `FetchRpcTransport` is the browser/worker implementation.
+#[allow(async_fn_in_trait)]
+pub trait RpcTransport {
+    async fn send(&self, request: &RpcHttpRequest) -> Result<RpcHttpResponse, String>;
+}
+
+/// `fetch()`-backed transport used by the worker at runtime.
+pub struct FetchRpcTransport;
+
+impl RpcTransport for FetchRpcTransport {
+    async fn send(&self, request: &RpcHttpRequest) -> Result<RpcHttpResponse, String> {
+        let init = build_json_post_init_with_headers(&request.body, &request.headers)?;
+        let resp = fetch_with_init(&request.url, &init).await?;
+        let ok = response_ok(&resp)?;
+        let status = response_status(&resp).unwrap_or(0);
+        let status_text = response_status_text(&resp).unwrap_or_default();
+        let body = if ok {
+            response_text(&resp).await?
+        } else {
+            response_text(&resp)
+                .await
+                .unwrap_or_else(|_| "Failed to read error response".to_string())
+        };
+        Ok(RpcHttpResponse {
+            ok,
+            status,
+            status_text,
+            body,
+        })
+    }
+}
+
+/// Build the POST request for one endpoint: JSON content type plus caller headers
+/// (sorted by name for deterministic output).
+pub fn build_rpc_http_request(endpoint: &str, body: &str, headers: &RpcHeaders) -> RpcHttpRequest {
+    let mut extra: Vec<(String, String)> = headers
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    extra.sort();
+    let mut all = vec![("Content-Type".to_string(), "application/json".to_string())];
+    all.extend(extra);
+    RpcHttpRequest {
+        url: endpoint.to_string(),
+        headers: all,
+        body: body.to_string(),
+    }
+}
+
+/// Shared HTTP request execution logic: tries each comma/space-separated endpoint in
+/// order and returns the first successful response body. Errors never contain raw
+/// header values.
+pub async fn execute_rpc_request_with<T: RpcTransport>(
+    transport: &T,
+    rpc_url: &str,
+    body: &str,
+    rpc_headers: &RpcHeaders,
+) -> Result<String, String> {
     let endpoints: Vec<String> = rpc_url
         .split(|c: char| c == ',' || c.is_whitespace())
         .map(|s| s.trim())
@@ -454,24 +574,11 @@ async fn execute_rpc_request(rpc_url: &str, rpc_body: &JsValue) -> Result<JsValu
         return Err("NEAR RPC URL cannot be empty".to_string());
     }
 
-    let body_str = js_sys::JSON::stringify(rpc_body)
-        .map_err(|e| format!("Failed to stringify RPC body: {:?}", e))?
-        .as_string()
-        .ok_or_else(|| "Failed to stringify RPC body".to_string())?;
-    let fetch_init = build_json_post_init(&body_str)?;
-
     let mut last_error: Option<String> = None;
 
     for (index, endpoint) in endpoints.iter().enumerate() {
-        let resp = match fetch_with_init(endpoint, &fetch_init).await {
-            Ok(v) => v,
-            Err(e) => {
-                last_error = Some(e);
-                continue;
-            }
-        };
-
-        let ok = match response_ok(&resp) {
+        let request = build_rpc_http_request(endpoint, body, rpc_headers);
+        let resp = match transport.send(&request).await {
             Ok(v) => v,
             Err(e) => {
                 last_error = Some(e);
@@ -479,29 +586,14 @@ async fn execute_rpc_request(rpc_url: &str, rpc_body: &JsValue) -> Result<JsValu
             }
         };
 
-        if !ok {
-            let error_text = response_text(&resp)
-                .await
-                .unwrap_or_else(|_| "Failed to read error response".to_string());
-            let status = response_status(&resp)
-                .map(|s| s.to_string())
-                .unwrap_or_else(|_| "?".to_string());
-            let status_text = response_status_text(&resp).unwrap_or_default();
+        if !resp.ok {
             last_error = Some(format!(
                 "HTTP error from {}: {} {} - Response: {}",
-                endpoint, status, status_text, error_text
+                endpoint, resp.status, resp.status_text, resp.body
             ));
             continue;
         }
 
-        let result = match response_json(&resp).await {
-            Ok(v) => v,
-            Err(e) => {
-                last_error = Some(e);
-                continue;
-            }
-        };
-
         if index > 0 {
             warn!(
                 "[vrf wasm] RPC call succeeded using fallback endpoint: {}",
@@ -509,10 +601,114 @@ async fn execute_rpc_request(rpc_url: &str, rpc_body: &JsValue) -> Result<JsValu
             );
         }
 
-        return Ok(result);
+        return Ok(resp.body);
+    }
+
+    let err = last_error.unwrap_or_else(|| "RPC request failed".to_string());
+    Err(redact_rpc_headers_in(&err, rpc_headers))
+}
+
+async fn execute_rpc_request(
+    rpc_url: &str,
+    rpc_body: &JsValue,
+    rpc_headers: &RpcHeaders,
+) -> Result<JsValue, String> {
+    let body_str = js_sys::JSON::stringify(rpc_body)
+        .map_err(|e| format!("Failed to stringify RPC body: {:?}", e))?
+        .as_string()
+        .ok_or_else(|| "Failed to stringify RPC body".to_string())?;
+
+    let text =
+        execute_rpc_request_with(&FetchRpcTransport, rpc_url, &body_str, rpc_headers).await?;
+    js_sys::JSON::parse(&text).map_err(|e| format!("Failed to parse JSON: {:?}", e))
+}
+
+// === BLOCK CONTEXT ===
+
+const BLOCK_CONTEXT_RPC_ID: &str = "block_context_from_vrf_worker";
+
+/// Latest final block as last fetched by the worker.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockContext {
+    pub height: u64,
+    /// base58-encoded block hash (as returned by NEAR RPC).
+    pub hash: String,
+    pub fetched_at_ms: f64,
+}
+
+impl BlockContext {
+    pub fn is_fresh(&self, now_ms: f64, max_staleness_ms: u64) -> bool {
+        now_ms >= self.fetched_at_ms && now_ms - self.fetched_at_ms <= max_staleness_ms as f64
+    }
+}
+
+/// Fetches the latest final block `(height, base58 hash)`. `RpcBlockFetcher` is the
+/// runtime implementation; tests substitute a mock.
+#[allow(async_fn_in_trait)]
+pub trait BlockFetcher {
+    async fn fetch_latest_block(
+        &self,
+        rpc_url: &str,
+        rpc_headers: &RpcHeaders,
+    ) -> Result<(u64, String), String>;
+}
+
+/// `block` RPC over `fetch()`.
+pub struct RpcBlockFetcher;
+
+impl BlockFetcher for RpcBlockFetcher {
+    async fn fetch_latest_block(
+        &self,
+        rpc_url: &str,
+        rpc_headers: &RpcHeaders,
+    ) -> Result<(u64, String), String> {
+        let body = format!(
+            r#"{{"jsonrpc":"2.0","id":"{}","method":"block","params":{{"finality":"final"}}}}"#,
+            BLOCK_CONTEXT_RPC_ID
+        );
+        let text =
+            execute_rpc_request_with(&FetchRpcTransport, rpc_url, &body, rpc_headers).await?;
+        let parsed =
+            js_sys::JSON::parse(&text).map_err(|e| format!("Failed to parse JSON: {:?}", e))?;
+        if let Some(error_msg) = extract_error_message(&parsed, "error") {
+            return Err(format!("block RPC error: {}", error_msg));
+        }
+        let header = Reflect::get(&parsed, &JsValue::from_str("result"))
+            .and_then(|result| Reflect::get(&result, &JsValue::from_str("header")))
+            .map_err(|e| format!("Missing result.header in block response: {:?}", e))?;
+        let height = Reflect::get(&header, &JsValue::from_str("height"))
+            .ok()
+            .and_then(|v| v.as_f64())
+            .ok_or_else(|| "Missing result.header.height in block response".to_string())?;
+        let hash = Reflect::get(&header, &JsValue::from_str("hash"))
+            .ok()
+            .and_then(|v| v.as_string())
+            .ok_or_else(|| "Missing result.header.hash in block response".to_string())?;
+        Ok((height as u64, hash))
     }
+}
 
-    Err(last_error.unwrap_or_else(|| "RPC request failed".to_string()))
+/// Fetch the latest final block once and stamp it with `now_ms`.
+pub async fn refresh_block_context<F: BlockFetcher>(
+    fetcher: &F,
+    rpc_url: &str,
+    rpc_headers: &RpcHeaders,
+    now_ms: f64,
+) -> Result<BlockContext, String> {
+    validate_rpc_headers(rpc_headers)?;
+    let (height, hash) = fetcher
+        .fetch_latest_block(rpc_url, rpc_headers)
+        .await
+        .map_err(|e| redact_rpc_headers_in(&e, rpc_headers))?;
+    if hash.trim().is_empty() {
+        return Err("block RPC returned an empty block hash".to_string());
+    }
+    debug!("[vrf wasm] Refreshed block context at height {}", height);
+    Ok(BlockContext {
+        height,
+        hash,
+        fetched_at_ms: now_ms,
+    })
 }
 
 fn extract_error_message(obj: &JsValue, field: &str) -> Option<String> {
@@ -0,0 +1,125 @@
+//! Custom NEAR RPC headers (API keys for FastNEAR, Lava, QuickNode, ...).
+//!
+//! Header values are treated as secrets: they never appear verbatim in logs or
+//! error strings. Only the header name and the last 4 characters are shown.
+
+use log::{debug, warn};
+use std::collections::HashMap;
+
+/// Extra HTTP headers attached to every NEAR RPC fetch.
+pub type RpcHeaders = HashMap<String, String>;
+
+/// Headers callers may never set (browser-forbidden or owned by the worker).
+const FORBIDDEN_RPC_HEADERS: [&str; 10] = [
+    "cookie",
+    "cookie2",
+    "set-cookie",
+    "host",
+    "connection",
+    "content-length",
+    "content-type",
+    "origin",
+    "referer",
+    "transfer-encoding",
+];
+
+/// Forbidden header name prefixes (per the Fetch spec).
+const FORBIDDEN_RPC_HEADER_PREFIXES: [&str; 2] = ["proxy-", "sec-"];
+
+/// Allowed, but flagged as sensitive whenever headers are logged.
+const SENSITIVE_RPC_HEADERS: [&str; 2] = ["authorization", "proxy-authorization"];
+
+const REDACTED_VISIBLE_SUFFIX_CHARS: usize = 4;
+
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+/// Validate header names/values before they are attached to any fetch.
+pub fn validate_rpc_headers(headers: &RpcHeaders) -> Result<(), String> {
+    for (name, value) in headers {
+        if name.is_empty() || !name.chars().all(is_token_char) {
+            return Err(format!("Invalid RPC header name: {:?}", name));
+        }
+        let lower = name.to_ascii_lowercase();
+        if FORBIDDEN_RPC_HEADERS.contains(&lower.as_str())
+            || FORBIDDEN_RPC_HEADER_PREFIXES
+                .iter()
+                .any(|p| lower.starts_with(p))
+        {
+            return Err(format!("Forbidden RPC header: {}", name));
+        }
+        if value.contains(['\r', '\n']) {
+            return Err(format!(
+                "Invalid RPC header value for {}: {}",
+                name,
+                redact_header_value(value)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Redact a header value down to its last 4 characters (e.g. `****abcd`).
+pub fn redact_header_value(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() <= REDACTED_VISIBLE_SUFFIX_CHARS {
+        return "****".to_string();
+    }
+    let suffix: String = chars[chars.len() - REDACTED_VISIBLE_SUFFIX_CHARS..]
+        .iter()
+        .collect();
+    format!("****{}", suffix)
+}
+
+/// Replace every occurrence of a header value in `message` with its redacted form.
+pub fn redact_rpc_headers_in(message: &str, headers: &RpcHeaders) -> String {
+    let mut values: Vec<&String> = headers.values().filter(|v| !v.is_empty()).collect();
+    // Longest first so a value containing another value is fully redacted.
+    values.sort_by_key(|v| std::cmp::Reverse(v.len()));
+    let mut out = message.to_string();
+    for value in values {
+        out = out.replace(value.as_str(), &redact_header_value(value));
+    }
+    out
+}
+
+/// Log-safe summary: `X-Api-Key=****abcd, Authorization(sensitive)=****wxyz`.
+pub fn describe_rpc_headers(headers: &RpcHeaders) -> String {
+    let mut names: Vec<&String> = headers.keys().collect();
+    names.sort();
+    names
+        .into_iter()
+        .map(|name| {
+            let flag = if SENSITIVE_RPC_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+                "(sensitive)"
+            } else {
+                ""
+            };
+            format!("{}{}={}", name, flag, redact_header_value(&headers[name]))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Merge the globally configured defaults with per-request headers.
+/// Per-request values win on (case-insensitive) name collisions.
+pub fn merge_rpc_headers(defaults: &RpcHeaders, per_request: Option<&RpcHeaders>) -> RpcHeaders {
+    let mut merged = defaults.clone();
+    if let Some(extra) = per_request {
+        for (name, value) in extra {
+            merged.retain(|k, _| !k.eq_ignore_ascii_case(name));
+            merged.insert(name.clone(), value.clone());
+        }
+    }
+    if !merged.is_empty() {
+        debug!("[vrf wasm] RPC headers: {}", describe_rpc_headers(&merged));
+        if merged
+            .keys()
+            .any(|k| SENSITIVE_RPC_HEADERS.contains(&k.to_ascii_lowercase().as_str()))
+        {
+            warn!("[vrf wasm] RPC requests carry a sensitive Authorization header");
+        }
+    }
+    merged
+}
@@ -0,0 +1,105 @@
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::config::{SESSION_POLICY_DIGEST_DOMAIN, SESSION_POLICY_MAX_SAFE_INTEGER};
+use crate::errors::{PolicyError, VrfResult, VrfWorkerError};
+use crate::utils::base64_url_encode;
+
+/// Canonical JSON form of a session policy: object keys sorted (UTF-16 code unit order, as
+/// JS `Array.prototype.sort` does), no insignificant whitespace, integers only.
+///
+/// Floats and integers outside the JS safe range are rejected rather than normalized, since
+/// they would not round-trip identically through the client shell and the relayer.
+pub fn canonicalize_session_policy_json(policy_json: &str) -> Result<String, PolicyError> {
+    let value: Value =
+        serde_json::from_str(policy_json).map_err(|e| PolicyError::InvalidJson(e.to_string()))?;
+    if !value.is_object() {
+        return Err(PolicyError::NotAnObject);
+    }
+    let mut out = String::with_capacity(policy_json.len());
+    write_canonical(&value, "$", &mut out)?;
+    Ok(out)
+}
+
+/// `SHA-256(SESSION_POLICY_DIGEST_DOMAIN || canonical_json)`, the `sessionPolicyDigest32`
+/// bound into VRF challenges and checked by the relayer at threshold session mint.
+pub fn compute_session_policy_digest(policy_json: &str) -> Result<[u8; 32], PolicyError> {
+    let canonical = canonicalize_session_policy_json(policy_json)?;
+    let mut hasher = Sha256::new();
+    hasher.update(SESSION_POLICY_DIGEST_DOMAIN);
+    hasher.update(canonical.as_bytes());
+    Ok(hasher.finalize().into())
+}
+
+/// Resolve the policy digest (base64url) a request is bound to.
+///
+/// Callers may pass the policy JSON, a precomputed digest, or both. When the JSON is present
+/// the digest is always recomputed here; a precomputed digest that disagrees with it is a
+/// `SessionPolicyMismatch`. A digest-only request is passed through unchanged.
+pub fn resolve_session_policy_digest(
+    policy_json: Option<&str>,
+    digest_b64u: Option<&str>,
+) -> VrfResult<Option<String>> {
+    let digest_b64u = digest_b64u.map(str::trim).filter(|s| !s.is_empty());
+    let Some(policy_json) = policy_json.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(digest_b64u.map(str::to_string));
+    };
+    let computed = base64_url_encode(&compute_session_policy_digest(policy_json)?);
+    match digest_b64u {
+        Some(presented) if presented != computed => Err(VrfWorkerError::SessionPolicyMismatch),
+        _ => Ok(Some(computed)),
+    }
+}
+
+fn write_canonical(value: &Value, path: &str, out: &mut String) -> Result<(), PolicyError> {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => {
+            if let Some(u) = n.as_u64() {
+                if u > SESSION_POLICY_MAX_SAFE_INTEGER {
+                    return Err(PolicyError::IntegerOutOfRange(path.to_string()));
+                }
+                out.push_str(&u.to_string());
+            } else if let Some(i) = n.as_i64() {
+                if i.unsigned_abs() > SESSION_POLICY_MAX_SAFE_INTEGER {
+                    return Err(PolicyError::IntegerOutOfRange(path.to_string()));
+                }
+                out.push_str(&i.to_string());
+            } else {
+                return Err(PolicyError::FloatNotAllowed(path.to_string()));
+            }
+        }
+        Value::String(s) => write_json_string(s, out),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, &format!("{}[{}]", path, i), out)?;
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort_by(|a, b| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_json_string(key, out);
+                out.push(':');
+                write_canonical(&map[key], &format!("{}.{}", path, key), out)?;
+            }
+            out.push('}');
+        }
+    }
+    Ok(())
+}
+
+fn write_json_string(s: &str, out: &mut String) {
+    // serde_json escapes exactly what JSON.stringify does for well-formed strings.
+    out.push_str(&serde_json::to_string(s).unwrap_or_default());
+}
@@ -24,8 +24,8 @@ mod tests_integration;
 mod tests_unit;
 
 use crate::config::{
-    DEFAULT_SHAMIR_P_B64U, SHAMIR_MIN_PRIME_BITS, SHAMIR_RANDOM_BYTES_OVERHEAD,
-    SHAMIR_REJECTION_SAMPLING_MAX_ATTEMPTS,
+    DEFAULT_SHAMIR_P_B64U, SHAMIR_MIN_PRIME_BITS, SHAMIR_PRIMALITY_MIN_ROUNDS,
+    SHAMIR_PRIMALITY_ROUNDS, SHAMIR_RANDOM_BYTES_OVERHEAD, SHAMIR_REJECTION_SAMPLING_MAX_ATTEMPTS,
 };
 use base64ct::{Base64UrlUnpadded, Encoding};
 use chacha20poly1305::{
@@ -34,10 +34,11 @@ use chacha20poly1305::{
 };
 use getrandom::getrandom;
 use hkdf::Hkdf;
-use num_bigint::{BigInt, BigUint, Sign};
+use num_bigint::BigUint;
 use num_integer::Integer;
 use num_traits::{One, Zero};
-use sha2::Sha256;
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicU32, Ordering};
 use wasm_bindgen::prelude::*;
 
 // Error types for better error handling
@@ -58,6 +59,91 @@ impl From<Shamir3PassError> for JsValue {
     }
 }
 
+// === PRIMALITY TESTING ===
+
+static PRIMALITY_ROUNDS: AtomicU32 = AtomicU32::new(SHAMIR_PRIMALITY_ROUNDS);
+
+/// Set the Miller-Rabin round count used when a Shamir P is configured.
+/// Requests below `SHAMIR_PRIMALITY_MIN_ROUNDS` are raised to the floor; returns the applied count.
+#[wasm_bindgen]
+pub fn set_shamir_primality_rounds(rounds: u32) -> u32 {
+    let applied = rounds.max(SHAMIR_PRIMALITY_MIN_ROUNDS);
+    PRIMALITY_ROUNDS.store(applied, Ordering::SeqCst);
+    applied
+}
+
+/// Miller-Rabin round count currently applied to configured primes
+pub fn shamir_primality_rounds() -> u32 {
+    PRIMALITY_ROUNDS.load(Ordering::SeqCst)
+}
+
+const SMALL_PRIMES: [u32; 25] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+/// Miller-Rabin probable-prime test (after trial division by small primes).
+///
+/// Witnesses are derived from SHA-256 over `n` and the round index rather than `getrandom`,
+/// so validating P works in server runtimes where the WASM RNG is unavailable.
+pub fn is_probable_prime(n: &BigUint, rounds: u32) -> bool {
+    let one = BigUint::one();
+    let two = BigUint::from(2u32);
+    if n < &two {
+        return false;
+    }
+    for small in SMALL_PRIMES {
+        let small = BigUint::from(small);
+        if n == &small {
+            return true;
+        }
+        if (n % &small).is_zero() {
+            return false;
+        }
+    }
+
+    // n - 1 = d * 2^s with d odd
+    let n_minus_1 = n - &one;
+    let s = n_minus_1.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_1 >> s;
+    let n_minus_3 = n - BigUint::from(3u32);
+
+    'witness: for round in 0..rounds {
+        // a in [2, n-2]
+        let a = primality_witness_seed(n, round) % &n_minus_3 + &two;
+        let mut x = a.modpow(&d, n);
+        if x == one || x == n_minus_1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = (&x * &x) % n;
+            if x == n_minus_1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// Expand SHA-256(domain || n || round || counter) to cover n's width plus sampling overhead.
+fn primality_witness_seed(n: &BigUint, round: u32) -> BigUint {
+    let n_bytes = n.to_bytes_be();
+    let bytes_needed = n_bytes.len() + SHAMIR_RANDOM_BYTES_OVERHEAD;
+    let mut out = Vec::with_capacity(bytes_needed + 32);
+    let mut counter: u32 = 0;
+    while out.len() < bytes_needed {
+        let mut hasher = Sha256::new();
+        hasher.update(b"shamir3pass-miller-rabin-witness");
+        hasher.update(&n_bytes);
+        hasher.update(round.to_be_bytes());
+        hasher.update(counter.to_be_bytes());
+        out.extend_from_slice(&hasher.finalize());
+        counter += 1;
+    }
+    out.truncate(bytes_needed);
+    BigUint::from_bytes_be(&out)
+}
+
 /// Client lock keys for adding/removing locks
 #[derive(Clone, Debug)]
 pub struct ClientLockKeys {
@@ -72,6 +158,7 @@ pub struct Shamir3Pass {
     p_minus_1: BigUint, // Cached for efficiency
     min_k: BigUint,     // Minimum value for k (security bound)
     max_k: BigUint,     // Maximum value for k (p-2)
+    monty: Option<MontgomeryContext>, // Cached Montgomery context (None if p is even)
 }
 
 impl Shamir3Pass {
@@ -101,14 +188,20 @@ impl Shamir3Pass {
             });
         }
 
+        let rounds = shamir_primality_rounds();
+        if !is_probable_prime(&p, rounds) {
+            return Err(Shamir3PassError::InvalidPrime(format!(
+                "p is composite (Miller-Rabin, {} rounds)",
+                rounds
+            )));
+        }
+
         Ok(Self::new_with_biguint_unchecked(p))
     }
 
-    /// Create instance with a BigUint prime without size validation (for default prime)
+    /// Create instance with a BigUint prime without size or primality validation
+    /// (for the trusted default prime)
     fn new_with_biguint_unchecked(p: BigUint) -> Self {
-        // TODO: Add primality test for production use
-        // For now, we trust that provided primes are actually prime
-
         let one = BigUint::one();
         let two = &one + &one;
         let p_minus_1 = &p - &one;
@@ -121,11 +214,14 @@ impl Shamir3Pass {
             BigUint::from(1u64 << 32) // Reduced bound for smaller primes (like our 256-bit default)
         };
 
+        let monty = MontgomeryContext::new(&p);
+
         Shamir3Pass {
             p,
             p_minus_1,
             min_k,
             max_k: p_minus_2,
+            monty,
         }
     }
 
@@ -144,27 +240,15 @@ impl Shamir3Pass {
         if self.p.is_zero() {
             return BigUint::zero();
         }
-        base.modpow(exp, &self.p)
+        match &self.monty {
+            Some(monty) => monty.modpow(base, exp),
+            None => base.modpow(exp, &self.p),
+        }
     }
 
-    /// Compute modular inverse using extended Euclidean algorithm
+    /// Compute modular inverse of `a` modulo p-1 (iterative binary extended GCD)
     pub fn modinv(&self, a: &BigUint) -> Option<BigUint> {
-        let a_bigint = BigInt::from_biguint(Sign::Plus, a.clone());
-        let m_bigint = BigInt::from_biguint(Sign::Plus, self.p_minus_1.clone());
-
-        let (gcd, x, _) = extended_gcd(a_bigint, m_bigint.clone());
-
-        if gcd != BigInt::one() {
-            return None;
-        }
-
-        // Ensure positive result
-        let mut x_mod = x % &m_bigint;
-        if x_mod.sign() == Sign::Minus {
-            x_mod += &m_bigint;
-        }
-
-        Some(x_mod.to_biguint().unwrap())
+        modinv_biguint(a, &self.p_minus_1)
     }
 
     /// Generate random k in range [min_k, p-2] using rejection sampling
@@ -173,6 +257,8 @@ impl Shamir3Pass {
         let range = &self.max_k - &self.min_k;
         let bytes_needed = ((range.bits() + 7) / 8 + SHAMIR_RANDOM_BYTES_OVERHEAD as u64) as usize;
 
+        crate::randomness::ensure_randomness_ok()
+            .map_err(|_| Shamir3PassError::RandomGenerationFailed)?;
         for _ in 0..SHAMIR_REJECTION_SAMPLING_MAX_ATTEMPTS {
             let mut buf = vec![0u8; bytes_needed];
             getrandom(&mut buf).map_err(|_| Shamir3PassError::RandomGenerationFailed)?;
@@ -251,6 +337,8 @@ impl Shamir3Pass {
 
         let cipher = ChaCha20Poly1305::new(Key::<ChaCha20Poly1305>::from_slice(&key_bytes));
 
+        crate::randomness::ensure_randomness_ok()
+            .map_err(|_| Shamir3PassError::RandomGenerationFailed)?;
         let mut nonce = [0u8; 12];
         getrandom(&mut nonce).map_err(|_| Shamir3PassError::RandomGenerationFailed)?;
         let nonce_ga = GenericArray::from_slice(&nonce);
@@ -293,17 +381,180 @@ impl Shamir3Pass {
 
 // Utility functions
 
-/// Extended Euclidean algorithm
-fn extended_gcd(a: BigInt, b: BigInt) -> (BigInt, BigInt, BigInt) {
-    if b.is_zero() {
-        return (a, BigInt::one(), BigInt::zero());
+/// Modular inverse of `a` modulo `m` using only unsigned arithmetic.
+///
+/// For an odd modulus this is a direct binary inverse. For an even modulus (p-1),
+/// `a` must be odd, so we invert `m` modulo `a` instead and map back via
+/// `a^-1 = (m * (a - m^-1 mod a) + 1) / a  (mod m)`.
+fn modinv_biguint(a: &BigUint, m: &BigUint) -> Option<BigUint> {
+    if m.is_zero() {
+        return None;
+    }
+    if m.is_one() {
+        return Some(BigUint::zero());
+    }
+    let a = a % m;
+    if a.is_zero() {
+        return None;
+    }
+    if m.is_odd() {
+        return binary_modinv_odd(&a, m);
+    }
+    if a.is_even() {
+        return None;
+    }
+    if a.is_one() {
+        return Some(BigUint::one());
+    }
+    let y = binary_modinv_odd(m, &a)?;
+    Some(((m * (&a - &y) + BigUint::one()) / &a) % m)
+}
+
+/// Binary extended GCD inverse of `b` modulo an odd `n` (n > 1).
+/// Coefficients are kept reduced in [0, n) so no signed arithmetic is needed.
+fn binary_modinv_odd(b: &BigUint, n: &BigUint) -> Option<BigUint> {
+    let halve = |x: &mut BigUint| {
+        if x.is_odd() {
+            *x += n;
+        }
+        *x >>= 1u32;
+    };
+    let sub_mod = |x: &BigUint, y: &BigUint| -> BigUint {
+        if x >= y {
+            x - y
+        } else {
+            x + n - y
+        }
+    };
+
+    let mut u = b % n;
+    let mut v = n.clone();
+    let mut x1 = BigUint::one();
+    let mut x2 = BigUint::zero();
+
+    while !u.is_one() && !v.is_one() {
+        // gcd(b, n) > 1
+        if u.is_zero() || v.is_zero() {
+            return None;
+        }
+        while u.is_even() {
+            u >>= 1u32;
+            halve(&mut x1);
+        }
+        while v.is_even() {
+            v >>= 1u32;
+            halve(&mut x2);
+        }
+        if u >= v {
+            u -= &v;
+            x1 = sub_mod(&x1, &x2);
+        } else {
+            v -= &u;
+            x2 = sub_mod(&x2, &x1);
+        }
+    }
+
+    Some(if u.is_one() { x1 % n } else { x2 % n })
+}
+
+/// Window width (bits) for fixed-window Montgomery exponentiation.
+const MONTY_WINDOW_BITS: u64 = 4;
+
+/// Montgomery multiplication context for an odd modulus, cached per `Shamir3Pass`
+/// so `R^2 mod n` and `-n^-1 mod R` are computed once rather than per `modexp`.
+#[derive(Clone, Debug)]
+struct MontgomeryContext {
+    n: BigUint,
+    /// R = 2^r_bits, with r_bits a multiple of 64 and R > n
+    r_bits: u64,
+    r_mask: BigUint,
+    /// -n^-1 mod R
+    n_prime: BigUint,
+    /// R^2 mod n (converts into Montgomery form)
+    r2: BigUint,
+    /// R mod n (Montgomery form of 1)
+    one: BigUint,
+}
+
+impl MontgomeryContext {
+    /// Returns None for even moduli, where Montgomery reduction is undefined.
+    fn new(n: &BigUint) -> Option<Self> {
+        if n.is_even() || n.is_one() {
+            return None;
+        }
+        let r_bits = n.bits().div_ceil(64) * 64;
+        let r = BigUint::one() << r_bits;
+        let r_mask = &r - BigUint::one();
+
+        // Newton iteration for n^-1 mod 2^r_bits (each step doubles correct bits)
+        let two = BigUint::from(2u32);
+        let mut inv = BigUint::one();
+        let mut correct_bits = 1u64;
+        while correct_bits < r_bits {
+            let t = (n * &inv) & &r_mask;
+            inv = (&inv * ((&two + &r - t) & &r_mask)) & &r_mask;
+            correct_bits *= 2;
+        }
+        let n_prime = (&r - inv) & &r_mask;
+
+        let one = &r % n;
+        let r2 = (&one * &one) % n;
+
+        Some(MontgomeryContext {
+            n: n.clone(),
+            r_bits,
+            r_mask,
+            n_prime,
+            r2,
+            one,
+        })
     }
 
-    let (gcd, x1, y1) = extended_gcd(b.clone(), &a % &b);
-    let x = y1.clone();
-    let y = x1 - (&a / &b) * y1;
+    /// REDC: returns t * R^-1 mod n for t < n * R
+    fn reduce(&self, t: BigUint) -> BigUint {
+        let m = ((&t & &self.r_mask) * &self.n_prime) & &self.r_mask;
+        let u = (t + m * &self.n) >> self.r_bits;
+        if u >= self.n {
+            u - &self.n
+        } else {
+            u
+        }
+    }
 
-    (gcd, x, y)
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        self.reduce(a * b)
+    }
+
+    /// base^exp mod n using fixed-window exponentiation in Montgomery form
+    fn modpow(&self, base: &BigUint, exp: &BigUint) -> BigUint {
+        let base_m = self.mul(&(base % &self.n), &self.r2);
+
+        let table_len = 1usize << MONTY_WINDOW_BITS;
+        let mut table = Vec::with_capacity(table_len);
+        table.push(self.one.clone());
+        table.push(base_m.clone());
+        for i in 2..table_len {
+            let next = self.mul(&table[i - 1], &base_m);
+            table.push(next);
+        }
+
+        let mut acc = self.one.clone();
+        let windows = exp.bits().div_ceil(MONTY_WINDOW_BITS);
+        for w in (0..windows).rev() {
+            for _ in 0..MONTY_WINDOW_BITS {
+                acc = self.mul(&acc, &acc);
+            }
+            let mut digit = 0usize;
+            for b in (0..MONTY_WINDOW_BITS).rev() {
+                digit = (digit << 1) | exp.bit(w * MONTY_WINDOW_BITS + b) as usize;
+            }
+            if digit != 0 {
+                acc = self.mul(&acc, &table[digit]);
+            }
+        }
+
+        self.reduce(acc)
+    }
 }
 
 /// Encode BigUint as base64url
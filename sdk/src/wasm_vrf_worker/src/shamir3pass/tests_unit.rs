@@ -175,4 +175,162 @@ mod tests {
 
         assert_eq!(path1, path2);
     }
+
+    // Reference implementation: the original recursive extended Euclidean modinv
+    // over BigInt, kept here to property-test the iterative BigUint version.
+    fn legacy_modinv(a: &BigUint, m: &BigUint) -> Option<BigUint> {
+        use num_bigint::{BigInt, Sign};
+
+        fn extended_gcd(a: BigInt, b: BigInt) -> (BigInt, BigInt, BigInt) {
+            if b.is_zero() {
+                return (a, BigInt::one(), BigInt::zero());
+            }
+            let (gcd, x1, y1) = extended_gcd(b.clone(), &a % &b);
+            let x = y1.clone();
+            let y = x1 - (&a / &b) * y1;
+            (gcd, x, y)
+        }
+
+        let a_bigint = BigInt::from_biguint(Sign::Plus, a.clone());
+        let m_bigint = BigInt::from_biguint(Sign::Plus, m.clone());
+        let (gcd, x, _) = extended_gcd(a_bigint, m_bigint.clone());
+        if gcd != BigInt::one() {
+            return None;
+        }
+        let mut x_mod = x % &m_bigint;
+        if x_mod.sign() == Sign::Minus {
+            x_mod += &m_bigint;
+        }
+        Some(x_mod.to_biguint().unwrap())
+    }
+
+    fn random_biguint(max_bytes: usize) -> BigUint {
+        let mut len = [0u8; 2];
+        getrandom::getrandom(&mut len).unwrap();
+        let len = 1 + (u16::from_le_bytes(len) as usize % max_bytes);
+        let mut buf = vec![0u8; len];
+        getrandom::getrandom(&mut buf).unwrap();
+        BigUint::from_bytes_be(&buf)
+    }
+
+    const LARGE_TEST_PRIME_B64U: &str = "x45jhDh_sOg6l0pGqW1B_ObSY45I9jnIFcHDXvYcPWHMJH-tiFwqpULNHDKP5NcRWq9aqZ6wtmZsSfxBvSTpp9MEJjHVyJWQSfLCAdSvWgkuRZGo1guSZFq8_h2mjFT8GnMOeIQHU-dmEBn1wtrh5Z3EcP8GHZLaE_lOmKR06dknVRohLSt8pQDNqmz9qtmcz6zkb8h-ixnvDabtrQN7AAtsYYENGCrno00RnivYLhMO4vAgpOQNc38rzK5R693rAnfmNDskRIqsSYOX6ZZf0xrH0LpVLqgX4mRtvhbmrXxvbZvITzEVbcZb5FIpLqC9P8HN_AAHjHv3sGGmgTq8hw";
+
+    #[test]
+    fn test_modinv_matches_legacy_on_random_inputs() {
+        for _ in 0..500 {
+            let m = random_biguint(64);
+            if m.is_zero() {
+                continue;
+            }
+            let a = random_biguint(80);
+            assert_eq!(
+                modinv_biguint(&a, &m),
+                legacy_modinv(&(&a % &m), &m).map(|x| x % &m),
+                "modinv mismatch for a={}, m={}",
+                a,
+                m
+            );
+        }
+    }
+
+    #[test]
+    fn test_modinv_matches_legacy_for_lock_keys() {
+        for p_b64u in [DEFAULT_SHAMIR_P_B64U, LARGE_TEST_PRIME_B64U] {
+            let shamir = Shamir3Pass::new(p_b64u).unwrap();
+            for _ in 0..20 {
+                // p-1 of the large test prime is divisible by 2 and 3, so `random_k` can run
+                // out of rejection-sampling attempts; only the drawn keys matter here.
+                let Ok(k) = shamir.random_k() else {
+                    continue;
+                };
+                assert_eq!(shamir.modinv(&k), legacy_modinv(&k, &shamir.p_minus_1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_montgomery_modexp_matches_modpow() {
+        for p_b64u in [DEFAULT_SHAMIR_P_B64U, LARGE_TEST_PRIME_B64U] {
+            let shamir = Shamir3Pass::new(p_b64u).unwrap();
+            assert!(shamir.monty.is_some());
+            for _ in 0..50 {
+                let base = random_biguint(300);
+                let exp = random_biguint(260);
+                assert_eq!(
+                    shamir.modexp(&base, &exp),
+                    base.modpow(&exp, &shamir.p),
+                    "modexp mismatch for base={}, exp={}",
+                    base,
+                    exp
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_montgomery_context_random_odd_moduli() {
+        for _ in 0..200 {
+            let n = random_biguint(48) | BigUint::one();
+            let Some(ctx) = MontgomeryContext::new(&n) else {
+                assert!(n.is_one());
+                continue;
+            };
+            let base = random_biguint(64);
+            let exp = random_biguint(40);
+            assert_eq!(ctx.modpow(&base, &exp), base.modpow(&exp, &n));
+        }
+    }
+
+    #[test]
+    fn test_lock_unlock_roundtrip_large_prime() {
+        let shamir = Shamir3Pass::new(LARGE_TEST_PRIME_B64U).unwrap();
+        // `random_k` can exhaust its attempts on this prime (see above); draw until it succeeds.
+        let keys = (0..8)
+            .find_map(|_| shamir.generate_lock_keys().ok())
+            .expect("lock keys");
+        let value = random_biguint(200) % &shamir.p;
+
+        let locked = shamir.add_lock(&value, &keys.e);
+        assert_eq!(shamir.remove_lock(&locked, &keys.d), value);
+    }
+
+    #[test]
+    fn test_primality_rounds_floor_is_enforced() {
+        use crate::config::{SHAMIR_PRIMALITY_MIN_ROUNDS, SHAMIR_PRIMALITY_ROUNDS};
+
+        assert_eq!(set_shamir_primality_rounds(0), SHAMIR_PRIMALITY_MIN_ROUNDS);
+        assert_eq!(shamir_primality_rounds(), SHAMIR_PRIMALITY_MIN_ROUNDS);
+        assert_eq!(
+            set_shamir_primality_rounds(SHAMIR_PRIMALITY_MIN_ROUNDS - 1),
+            SHAMIR_PRIMALITY_MIN_ROUNDS
+        );
+        assert_eq!(set_shamir_primality_rounds(64), 64);
+        assert_eq!(
+            set_shamir_primality_rounds(SHAMIR_PRIMALITY_ROUNDS),
+            SHAMIR_PRIMALITY_ROUNDS
+        );
+    }
+
+    #[test]
+    fn test_composite_rejected_at_min_and_default_rounds() {
+        use crate::config::{SHAMIR_PRIMALITY_MIN_ROUNDS, SHAMIR_PRIMALITY_ROUNDS};
+
+        let default_p = decode_biguint_b64u(DEFAULT_SHAMIR_P_B64U).unwrap();
+        let mersenne_127 = (BigUint::one() << 127u32) - BigUint::one();
+        // Strong pseudoprime to bases 2, 3, 5 and 7 with no factor below 100
+        let strong_pseudoprime = BigUint::from(3_215_031_751u64);
+        let composite = &default_p * &mersenne_127;
+
+        for rounds in [SHAMIR_PRIMALITY_MIN_ROUNDS, SHAMIR_PRIMALITY_ROUNDS] {
+            assert!(is_probable_prime(&default_p, rounds));
+            assert!(is_probable_prime(&mersenne_127, rounds));
+            assert!(!is_probable_prime(&strong_pseudoprime, rounds));
+            assert!(!is_probable_prime(&composite, rounds));
+        }
+
+        match Shamir3Pass::new(&encode_biguint_b64u(&composite)) {
+            Err(Shamir3PassError::InvalidPrime(msg)) => assert!(msg.contains("composite")),
+            other => panic!("Expected InvalidPrime error, got {:?}", other.map(|_| ())),
+        }
+    }
 }
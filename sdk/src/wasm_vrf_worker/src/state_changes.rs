@@ -0,0 +1,133 @@
+use std::collections::VecDeque;
+
+use serde::{Serialize, Serializer};
+use wasm_bindgen::prelude::*;
+
+use crate::config::STATE_CHANGE_LOG_CAPACITY;
+
+/// What changed in a worker's state. Values are stable across releases and shared with the
+/// signer worker so the TS shell can use a single enum for both logs.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateChangeKind {
+    /// A VRF keypair became the active one (unlock, derive, bootstrap, account switch)
+    Unlock = 1,
+    /// Keypair, sessions and caches were cleared (CLEAR_VRF)
+    Logout = 2,
+    /// Session material was minted / delivered
+    SessionCreated = 3,
+    /// A session was dropped because its TTL passed
+    SessionExpired = 4,
+    /// A session use was dispensed
+    SessionConsumed = 5,
+    /// A session was cleared explicitly, exhausted, or invalidated
+    SessionCleared = 6,
+    /// A session's expiry was extended (RENEW_SESSION)
+    SessionRenewed = 7,
+    /// Cached key material was dropped on an idle / reuse timeout (signer worker)
+    AutoLock = 8,
+    /// The relayer threshold auth session cache changed (signer worker)
+    ThresholdSessionChanged = 9,
+    /// An assertion's `signCount` went backwards for a credential (signer worker)
+    AuthenticatorCounterRegression = 10,
+}
+
+impl Serialize for StateChangeKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(*self as u32)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateChangeEvent {
+    /// State version right after this change.
+    pub version: u64,
+    pub kind: StateChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<String>,
+    pub at_ms: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateChangesSince {
+    /// Current state version.
+    pub version: u64,
+    /// Retained changes newer than the requested version, oldest first.
+    pub events: Vec<StateChangeEvent>,
+    /// Some changes after the requested version are no longer retained (or the version is
+    /// from another worker instance): re-read the full status instead of replaying `events`.
+    pub resync_required: bool,
+}
+
+/// Monotonic state version plus a bounded ring of the most recent changes, so the TS shell
+/// can poll `GET_STATE_VERSION` cheaply and fetch only what it missed.
+#[derive(Debug)]
+pub struct StateChangeLog {
+    version: u64,
+    events: VecDeque<StateChangeEvent>,
+    capacity: usize,
+}
+
+impl Default for StateChangeLog {
+    fn default() -> Self {
+        Self::with_capacity(STATE_CHANGE_LOG_CAPACITY)
+    }
+}
+
+impl StateChangeLog {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            version: 0,
+            events: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Bump the version and remember the change, evicting the oldest one when full.
+    pub fn record(&mut self, kind: StateChangeKind, account_id: Option<&str>, at_ms: f64) -> u64 {
+        self.version += 1;
+        if self.capacity > 0 {
+            if self.events.len() == self.capacity {
+                self.events.pop_front();
+            }
+            self.events.push_back(StateChangeEvent {
+                version: self.version,
+                kind,
+                account_id: account_id.map(str::to_string),
+                at_ms,
+            });
+        }
+        self.version
+    }
+
+    pub fn since(&self, version: u64) -> StateChangesSince {
+        if version > self.version {
+            return StateChangesSince {
+                version: self.version,
+                events: Vec::new(),
+                resync_required: true,
+            };
+        }
+        let oldest_retained = self
+            .events
+            .front()
+            .map(|e| e.version)
+            .unwrap_or(self.version + 1);
+        StateChangesSince {
+            version: self.version,
+            events: self
+                .events
+                .iter()
+                .filter(|e| e.version > version)
+                .cloned()
+                .collect(),
+            resync_required: version < self.version && oldest_retained > version + 1,
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! Localized SecureConfirm summaries with integrity binding.
+//!
+//! The summary shown in the confirmation UI is generated here as canonical field ids
+//! plus raw values (receiver, formatted amount). Callers may pass pre-rendered
+//! localized strings per field id via `confirmationConfig.localizedStrings`; each one
+//! must still contain its field's raw value verbatim, otherwise the whole set is
+//! rejected and the canonical English rendering is shown instead.
+//!
+//! Localized text is display-only: digests are always computed over canonical values.
+
+use crate::utils::base64_url_encode;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+pub const SUMMARY_FIELD_RECEIVER: &str = "receiver";
+pub const SUMMARY_FIELD_AMOUNT: &str = "amount";
+
+const YOCTO_NEAR_DECIMALS: usize = 24;
+
+/// Caller-supplied localized strings, keyed by summary field id.
+pub type LocalizedStrings = BTreeMap<String, String>;
+
+/// One summary line: canonical id, raw critical value, and the text to display.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SummaryField {
+    pub id: String,
+    pub value: String,
+    pub text: String,
+}
+
+/// Display-ready summary after localization was accepted or rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalizedSummary {
+    /// Locale of `fields[*].text`; `None` for the canonical English rendering.
+    pub locale: Option<String>,
+    pub fields: Vec<SummaryField>,
+    pub localization_rejected: bool,
+}
+
+impl LocalizedSummary {
+    /// SHA-256 (base64url) over the canonical `id=value` pairs. Display text is excluded,
+    /// so the digest is identical for every locale.
+    pub fn canonical_digest_b64u(&self) -> String {
+        let mut hasher = Sha256::new();
+        for field in &self.fields {
+            hasher.update(field.id.as_bytes());
+            hasher.update(b"=");
+            hasher.update(field.value.as_bytes());
+            hasher.update(b"\n");
+        }
+        base64_url_encode(&hasher.finalize())
+    }
+}
+
+/// Render a yoctoNEAR amount as a plain decimal NEAR string (`1.5`), keeping full precision.
+/// Non-numeric input is returned unchanged.
+pub fn format_near_amount(yocto: &str) -> String {
+    let yocto = yocto.trim();
+    if yocto.is_empty() || !yocto.bytes().all(|b| b.is_ascii_digit()) {
+        return yocto.to_string();
+    }
+    let digits = yocto.trim_start_matches('0');
+    let padded = format!("{:0>width$}", digits, width = YOCTO_NEAR_DECIMALS + 1);
+    let (whole, frac) = padded.split_at(padded.len() - YOCTO_NEAR_DECIMALS);
+    let frac = frac.trim_end_matches('0');
+    if frac.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, frac)
+    }
+}
+
+/// Canonical (English) summary fields for a signing request.
+pub fn canonical_summary_fields(
+    receiver_id: Option<&str>,
+    total_amount_yocto: Option<&str>,
+) -> Vec<SummaryField> {
+    let mut fields = Vec::new();
+    if let Some(receiver_id) = receiver_id.filter(|r| !r.is_empty()) {
+        fields.push(SummaryField {
+            id: SUMMARY_FIELD_RECEIVER.to_string(),
+            value: receiver_id.to_string(),
+            text: format!("Receiver: {}", receiver_id),
+        });
+    }
+    if let Some(amount) = total_amount_yocto.filter(|a| !a.is_empty()) {
+        let formatted = format_near_amount(amount);
+        fields.push(SummaryField {
+            id: SUMMARY_FIELD_AMOUNT.to_string(),
+            text: format!("Amount: {} NEAR", formatted),
+            value: formatted,
+        });
+    }
+    fields
+}
+
+/// Check caller-supplied strings against the canonical fields. Every localized string
+/// must target a known field id and contain that field's raw value as a substring.
+pub fn validate_localized_strings(
+    canonical: &[SummaryField],
+    localized: &LocalizedStrings,
+) -> Result<(), String> {
+    for (id, text) in localized {
+        let field = canonical
+            .iter()
+            .find(|f| &f.id == id)
+            .ok_or_else(|| format!("unknown summary field id: {}", id))?;
+        if !text.contains(field.value.as_str()) {
+            return Err(format!(
+                "localized '{}' does not contain the raw value {:?}",
+                id, field.value
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Apply caller-supplied localized strings to the canonical fields, falling back to
+/// the canonical rendering (and flagging `localization_rejected`) if any fails validation.
+pub fn localize_summary(
+    canonical: Vec<SummaryField>,
+    locale: Option<&str>,
+    localized: Option<&LocalizedStrings>,
+) -> LocalizedSummary {
+    let Some(localized) = localized.filter(|l| !l.is_empty()) else {
+        return LocalizedSummary {
+            locale: None,
+            fields: canonical,
+            localization_rejected: false,
+        };
+    };
+
+    if let Err(e) = validate_localized_strings(&canonical, localized) {
+        log::warn!("[VRF] rejecting localized summary ({:?}): {}", locale, e);
+        return LocalizedSummary {
+            locale: None,
+            fields: canonical,
+            localization_rejected: true,
+        };
+    }
+
+    let fields = canonical
+        .into_iter()
+        .map(|field| match localized.get(&field.id) {
+            Some(text) => SummaryField {
+                text: text.clone(),
+                ..field
+            },
+            None => field,
+        })
+        .collect();
+    LocalizedSummary {
+        locale: locale.map(str::to_string),
+        fields,
+        localization_rejected: false,
+    }
+}
@@ -3,15 +3,24 @@
 
 use crate::config::{
     CHACHA20_KEY_SIZE, CHACHA20_NONCE_SIZE, HKDF_CHACHA20_KEY_INFO, HKDF_VRF_KEYPAIR_INFO,
-    VRF_DOMAIN_SEPARATOR, VRF_SEED_SIZE,
+    VERIFICATION_CACHE_DEFAULT_TTL_MS, VERIFICATION_CACHE_MAX_TTL_MS, VRF_DOMAIN_SEPARATOR,
+    VRF_KEYPAIR_DATA_VERSION, VRF_SEED_SIZE,
 };
 use crate::errors::VrfWorkerError;
+use crate::handlers::handle_decrypt_session::decrypt_session_result_at;
 #[cfg(target_arch = "wasm32")]
 use crate::handlers::handle_mint_session_keys_and_send_to_signer::verify_authentication_if_needed;
 use crate::manager::{VRFKeyManager, VrfSessionData};
+use crate::rp_id_normalization::RpIdNormalization;
 use crate::shamir3pass::{decode_biguint_b64u, encode_biguint_b64u};
+use crate::types::worker_messages::{
+    WorkerRequestType, WorkerResponseType, WORKER_REQUEST_TYPE_REGISTRY,
+    WORKER_RESPONSE_TYPE_REGISTRY,
+};
 use crate::types::VRFInputData;
-use crate::utils::{base64_url_decode, base64_url_encode};
+use crate::utils::{
+    base64_url_decode, base64_url_encode, implicit_account_display, parse_deterministic_seed_b64u,
+};
 use num_bigint::BigUint;
 
 // Test helper functions
@@ -36,6 +45,10 @@ fn session_ttl_is_enforced_on_dispense() {
             created_at_ms: 0.0,
             expires_at_ms: Some(100.0),
             remaining_uses: Some(5),
+            renewable_until_ms: None,
+            policy_digest: None,
+            contract_verification: None,
+            key_reuse_policy: None,
         },
     );
 
@@ -44,6 +57,65 @@ fn session_ttl_is_enforced_on_dispense() {
     assert!(mgr.sessions.get(session_id).is_none());
 }
 
+fn session_with_expiry(expires_at_ms: f64) -> VrfSessionData {
+    VrfSessionData {
+        wrap_key_seed: vec![3u8; 32],
+        wrap_key_salt_b64u: "salt".to_string(),
+        created_at_ms: 0.0,
+        expires_at_ms: Some(expires_at_ms),
+        remaining_uses: Some(5),
+        renewable_until_ms: None,
+        policy_digest: None,
+        contract_verification: None,
+        key_reuse_policy: None,
+    }
+}
+
+#[test]
+fn decrypt_session_result_reports_fresh_session_lifetime() {
+    let mut mgr = VRFKeyManager::new(None, None, None, None);
+    mgr.upsert_session("sess-fresh", session_with_expiry(60_000.0));
+
+    assert!(mgr.ensure_session_usable("sess-fresh", 0.0).is_ok());
+    let result = decrypt_session_result_at(&mgr, "sess-fresh".to_string(), 0.0);
+    assert_eq!(result.session_id, "sess-fresh");
+    assert_eq!(result.expires_at_ms, Some(60_000.0));
+    assert_eq!(result.remaining_ms, Some(60_000.0));
+    // Neither the check nor the report consumes a use
+    assert_eq!(mgr.sessions.get("sess-fresh").unwrap().remaining_uses, Some(5));
+}
+
+#[test]
+fn decrypt_session_result_near_expiry() {
+    let mut mgr = VRFKeyManager::new(None, None, None, None);
+    mgr.upsert_session("sess-near", session_with_expiry(60_000.0));
+
+    assert!(mgr.ensure_session_usable("sess-near", 59_999.0).is_ok());
+    let result = decrypt_session_result_at(&mgr, "sess-near".to_string(), 59_999.0);
+    assert_eq!(result.remaining_ms, Some(1.0));
+}
+
+#[test]
+fn ensure_session_usable_refuses_expired_or_exhausted_sessions() {
+    let mut mgr = VRFKeyManager::new(None, None, None, None);
+    assert!(mgr.ensure_session_usable("sess-unknown", 0.0).is_ok());
+
+    mgr.upsert_session("sess-live", session_with_expiry(60_000.0));
+    assert!(mgr.ensure_session_usable("sess-live", 59_999.0).is_ok());
+
+    mgr.upsert_session("sess-expired", session_with_expiry(60_000.0));
+    let res = mgr.ensure_session_usable("sess-expired", 60_000.0);
+    assert!(matches!(res, Err(VrfWorkerError::SessionExpired)));
+    assert!(!mgr.sessions.contains_key("sess-expired"));
+
+    let mut spent = session_with_expiry(60_000.0);
+    spent.remaining_uses = Some(0);
+    mgr.upsert_session("sess-spent", spent);
+    let res = mgr.ensure_session_usable("sess-spent", 0.0);
+    assert!(matches!(res, Err(VrfWorkerError::SessionExhausted)));
+    assert!(!mgr.sessions.contains_key("sess-spent"));
+}
+
 #[test]
 fn session_remaining_uses_are_enforced_on_dispense() {
     let mut mgr = VRFKeyManager::new(None, None, None, None);
@@ -57,6 +129,10 @@ fn session_remaining_uses_are_enforced_on_dispense() {
             created_at_ms: 0.0,
             expires_at_ms: Some(1_000_000.0),
             remaining_uses: Some(1),
+            renewable_until_ms: None,
+            policy_digest: None,
+            contract_verification: None,
+            key_reuse_policy: None,
         },
     );
 
@@ -94,6 +170,7 @@ fn logout_clears_cached_sessions_and_challenges() {
             block_hash: "h".to_string(),
             intent_digest: None,
             session_policy_digest_32: None,
+            extra_context_digest_32: None,
         },
     );
     mgr.upsert_session(
@@ -104,6 +181,10 @@ fn logout_clears_cached_sessions_and_challenges() {
             created_at_ms: 0.0,
             expires_at_ms: Some(1_000_000.0),
             remaining_uses: Some(5),
+            renewable_until_ms: None,
+            policy_digest: None,
+            contract_verification: None,
+            key_reuse_policy: None,
         },
     );
 
@@ -149,6 +230,7 @@ fn verify_authentication_fails_when_challenge_missing() {
         &message_id,
         &Some("contract.testnet".to_string()),
         &Some("https://rpc.testnet.near.org".to_string()),
+        None,
         session_id,
         &cred_js,
     ));
@@ -253,6 +335,9 @@ fn vrf_input_hash_matches_regression_vector_with_intent_digest() {
         block_hash: "11111111111111111111111111111111".to_string(),
         intent_digest: Some("AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8".to_string()),
         session_policy_digest_32: None,
+        session_policy_json: None,
+        extra_context_digest_32: None,
+        rp_id_normalization: RpIdNormalization::default(),
     };
 
     let challenge = mgr
@@ -275,6 +360,184 @@ fn vrf_input_hash_matches_regression_vector_with_intent_digest() {
     println!("[Passed] VRF input hash regression vector test passed");
 }
 
+fn extra_context_input(extra_context_digest_32: Option<String>) -> VRFInputData {
+    VRFInputData {
+        user_id: "alice.near".to_string(),
+        rp_id: "example.com".to_string(),
+        block_height: "12345".to_string(),
+        block_hash: "11111111111111111111111111111111".to_string(),
+        intent_digest: Some("AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8".to_string()),
+        session_policy_digest_32: None,
+        session_policy_json: None,
+        extra_context_digest_32,
+        rp_id_normalization: RpIdNormalization::default(),
+    }
+}
+
+#[test]
+fn extra_context_digest_changes_vrf_output() {
+    let mgr = VRFKeyManager::new(None, None, None, None);
+    let vrf_keypair = mgr
+        .generate_vrf_keypair_from_seed(&create_test_prf_output(), &create_test_account_id())
+        .expect("deterministic VRF keypair");
+
+    let without = mgr
+        .generate_vrf_challenge_with_keypair(&vrf_keypair, extra_context_input(None))
+        .expect("VRF challenge without extra context");
+    let extra_b64u = base64_url_encode(&[0x42u8; 32]);
+    let with = mgr
+        .generate_vrf_challenge_with_keypair(
+            &vrf_keypair,
+            extra_context_input(Some(extra_b64u.clone())),
+        )
+        .expect("VRF challenge with extra context");
+
+    // Absent extra context must keep the regression vector input unchanged.
+    assert_eq!(without.vrf_input, "-N4GgUAlGrK6ZO5mSzcQdJ0InpsqRxWmuMlJ7rCXR04");
+    assert_eq!(without.extra_context_digest_32, None);
+    assert_ne!(with.vrf_input, without.vrf_input);
+    assert_ne!(with.vrf_output, without.vrf_output);
+    assert_eq!(with.extra_context_digest_32.as_deref(), Some(extra_b64u.as_str()));
+}
+
+#[test]
+fn rp_id_normalization_policy_applies_to_vrf_input() {
+    let mgr = VRFKeyManager::new(None, None, None, None);
+    let vrf_keypair = mgr
+        .generate_vrf_keypair_from_seed(&create_test_prf_output(), &create_test_account_id())
+        .expect("deterministic VRF keypair");
+    let challenge = |rp_id: &str, policy: RpIdNormalization| {
+        let mut input = extra_context_input(None);
+        input.rp_id = rp_id.to_string();
+        input.rp_id_normalization = policy;
+        mgr.generate_vrf_challenge_with_keypair(&vrf_keypair, input)
+    };
+    let regression_vrf_input = "-N4GgUAlGrK6ZO5mSzcQdJ0InpsqRxWmuMlJ7rCXR04";
+
+    for policy in [
+        RpIdNormalization::LowercaseAscii,
+        RpIdNormalization::RejectNonAscii,
+        RpIdNormalization::PunycodeEncode,
+    ] {
+        for rp_id in ["example.com", "Example.COM"] {
+            let out = challenge(rp_id, policy).expect("ASCII rpId");
+            assert_eq!(out.rp_id, "example.com");
+            assert_eq!(out.vrf_input, regression_vrf_input);
+        }
+    }
+
+    let unicode = "Bücher.Example";
+    let legacy = challenge(unicode, RpIdNormalization::LowercaseAscii).expect("legacy policy");
+    assert_eq!(legacy.rp_id, "bücher.example");
+    assert!(challenge(unicode, RpIdNormalization::RejectNonAscii).is_err());
+    let punycode = challenge(unicode, RpIdNormalization::PunycodeEncode).expect("punycode policy");
+    assert_eq!(punycode.rp_id, "xn--bcher-kva.example");
+    assert_ne!(punycode.vrf_input, legacy.vrf_input);
+    let a_label = challenge("xn--bcher-kva.example", RpIdNormalization::LowercaseAscii)
+        .expect("A-label rpId");
+    assert_eq!(punycode.vrf_input, a_label.vrf_input);
+}
+
+#[test]
+fn compute_vrf_input_matches_generated_challenge() {
+    let mgr = VRFKeyManager::new(None, None, None, None);
+    let vrf_keypair = mgr
+        .generate_vrf_keypair_from_seed(&create_test_prf_output(), &create_test_account_id())
+        .expect("deterministic VRF keypair");
+
+    let mut with_policy = extra_context_input(Some(base64_url_encode(&[0x42u8; 32])));
+    with_policy.session_policy_digest_32 = Some(base64_url_encode(&[0x24u8; 32]));
+    let mut without_intent = extra_context_input(None);
+    without_intent.intent_digest = None;
+    without_intent.rp_id = "Example.COM".to_string();
+
+    for input in [extra_context_input(None), with_policy, without_intent] {
+        let expected = base64_url_encode(
+            &crate::manager::compute_vrf_input(&input)
+                .expect("vrf input")
+                .vrf_input,
+        );
+        let challenge = mgr
+            .generate_vrf_challenge_with_keypair(&vrf_keypair, input)
+            .expect("challenge");
+        assert_eq!(challenge.vrf_input, expected);
+    }
+
+    // Regression vector shared with `rp_id_normalization_policy_applies_to_vrf_input`.
+    assert_eq!(
+        base64_url_encode(
+            &crate::manager::compute_vrf_input(&extra_context_input(None))
+                .unwrap()
+                .vrf_input
+        ),
+        "-N4GgUAlGrK6ZO5mSzcQdJ0InpsqRxWmuMlJ7rCXR04"
+    );
+}
+
+#[test]
+fn extra_context_digest_rejects_wrong_length() {
+    let mgr = VRFKeyManager::new(None, None, None, None);
+    let vrf_keypair = mgr
+        .generate_vrf_keypair_from_seed(&create_test_prf_output(), &create_test_account_id())
+        .expect("deterministic VRF keypair");
+
+    let short = base64_url_encode(&[0x42u8; 31]);
+    let err = mgr
+        .generate_vrf_challenge_with_keypair(&vrf_keypair, extra_context_input(Some(short)))
+        .expect_err("31-byte extra context must be rejected");
+    assert!(
+        err.to_string().contains("expected 32 bytes, got 31"),
+        "unexpected error: {}",
+        err
+    );
+}
+
+#[test]
+fn generate_vrf_challenge_reports_locked_separately_from_bad_input() {
+    let mut mgr = VRFKeyManager::new(None, None, None, None);
+    let locked = mgr
+        .generate_vrf_challenge(extra_context_input(None))
+        .expect_err("no keypair unlocked");
+    assert_eq!(locked.code(), "VRF_LOCKED");
+
+    let vrf_keypair = mgr
+        .generate_vrf_keypair_from_seed(&create_test_prf_output(), &create_test_account_id())
+        .expect("deterministic VRF keypair");
+    mgr.vrf_keypair = Some(crate::manager::SecureVRFKeyPair::new(vrf_keypair));
+    mgr.session_active = true;
+    mgr.generate_vrf_challenge(extra_context_input(None))
+        .expect("unlocked challenge");
+
+    let mut bad_input = extra_context_input(None);
+    bad_input.block_height = "not-a-height".to_string();
+    let err = mgr
+        .generate_vrf_challenge(bad_input)
+        .expect_err("invalid blockHeight");
+    assert_eq!(err.code(), "INVALID_INPUT");
+}
+
+#[test]
+fn generate_vrf_challenge_handler_returns_vrf_locked_code() {
+    use crate::handlers::handle_generate_vrf_challenge::{
+        handle_generate_vrf_challenge, GenerateVrfChallengeRequest,
+    };
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let manager = Rc::new(RefCell::new(VRFKeyManager::new(None, None, None, None)));
+    let response = futures::executor::block_on(handle_generate_vrf_challenge(
+        manager,
+        Some("msg-locked".to_string()),
+        GenerateVrfChallengeRequest {
+            session_id: None,
+            vrf_input_data: extra_context_input(None),
+        },
+    ));
+    assert!(!response.success);
+    assert_eq!(response.error_code.as_deref(), Some("VRF_LOCKED"));
+    assert_eq!(response.id.as_deref(), Some("msg-locked"));
+}
+
 #[test]
 #[cfg(target_arch = "wasm32")]
 fn test_vrf_data_structures_serialization() {
@@ -286,6 +549,9 @@ fn test_vrf_data_structures_serialization() {
         block_hash: String::from_utf8(vec![0u8; 32]).unwrap(),
         intent_digest: None,
         session_policy_digest_32: None,
+        session_policy_json: None,
+        extra_context_digest_32: None,
+        rp_id_normalization: RpIdNormalization::default(),
     };
 
     let js_val = serde_wasm_bindgen::to_value(&vrf_input).expect("Should serialize VRFInputData");
@@ -415,6 +681,21 @@ fn test_base64_encoding_consistency() {
     println!("[Passed] Base64 encoding consistency test passed");
 }
 
+#[test]
+fn test_base64_decode_errors_name_expected_variant() {
+    use crate::utils::{B64UrlBytes, Encoding};
+
+    let err = base64_url_decode("+//+").unwrap_err();
+    assert!(err.starts_with("expected base64url:"), "{}", err);
+    let err = Encoding::B64Std.decode("-__-").unwrap_err();
+    assert!(err.starts_with("expected standard base64:"), "{}", err);
+
+    // Padded input from browser APIs is accepted; standard-alphabet input is not.
+    assert_eq!(B64UrlBytes::decode("AQ==").unwrap().as_bytes(), [1u8]);
+    let err = B64UrlBytes::decode("+//+").unwrap_err();
+    assert!(err.contains("expected base64url"), "{}", err);
+}
+
 #[test]
 fn test_configuration_constants() {
     // Test that configuration constants are properly defined
@@ -832,8 +1113,15 @@ fn mint_session_keys_and_send_to_signer_uses_caller_wrap_key_salt_when_provided(
         wrap_key_salt_b64u: "explicit-salt".to_string(),
         contract_id: None,
         near_rpc_url: None,
+        rpc_headers: None,
         ttl_ms: None,
         remaining_uses: None,
+        max_lifetime_ms: None,
+        session_policy_digest: None,
+        session_policy_json: None,
+        key_reuse_policy: None,
+        session_binding_nonce: None,
+        intent_digest: None,
         credential: JsValue::UNDEFINED,
     };
     let json = serde_wasm_bindgen::to_value(&req).expect("serialize");
@@ -853,8 +1141,15 @@ fn mint_session_keys_and_send_to_signer_generates_salt_when_empty() {
         wrap_key_salt_b64u: "  ".to_string(),
         contract_id: None,
         near_rpc_url: None,
+        rpc_headers: None,
         ttl_ms: None,
         remaining_uses: None,
+        max_lifetime_ms: None,
+        session_policy_digest: None,
+        session_policy_json: None,
+        key_reuse_policy: None,
+        session_binding_nonce: None,
+        intent_digest: None,
         credential: JsValue::UNDEFINED,
     };
     // The handler itself runs under wasm32, but the request shape must be JSON-compatible.
@@ -879,7 +1174,11 @@ fn generate_vrf_keypair_bootstrap_request_allows_optional_input() {
             block_hash: "hash".to_string(),
             intent_digest: None,
             session_policy_digest_32: None,
+            session_policy_json: None,
+            extra_context_digest_32: None,
+            rp_id_normalization: RpIdNormalization::default(),
         }),
+        deterministic_seed_b64u: None,
     };
     let json = serde_wasm_bindgen::to_value(&req).expect("serialize");
     let parsed: GenerateVrfKeypairBootstrapRequest =
@@ -902,6 +1201,9 @@ fn generate_vrf_challenge_request_requires_input() {
             block_hash: "hash2".to_string(),
             intent_digest: None,
             session_policy_digest_32: None,
+            session_policy_json: None,
+            extra_context_digest_32: None,
+            rp_id_normalization: RpIdNormalization::default(),
         },
     };
     let json = serde_wasm_bindgen::to_value(&req).expect("serialize");
@@ -947,6 +1249,7 @@ fn derive_vrf_keypair_from_prf_request_uses_defaults() {
         near_account_id: create_test_account_id(),
         save_in_memory: true,
         vrf_input_data: None,
+        deterministic_seed_b64u: None,
     };
     let json = serde_wasm_bindgen::to_value(&req).expect("serialize");
     let parsed: DeriveVrfKeypairFromPrfRequest =
@@ -957,3 +1260,2482 @@ fn derive_vrf_keypair_from_prf_request_uses_defaults() {
         "vrfInputData can be omitted"
     );
 }
+
+#[test]
+#[cfg(all(target_arch = "wasm32", not(feature = "test_confirmation_bypass")))]
+fn test_auto_confirm_field_is_ignored_in_default_build() {
+    use crate::await_secure_confirmation::test_auto_confirm;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    #[allow(non_snake_case)]
+    struct Req<'a> {
+        requestId: &'a str,
+        intentDigest: &'a str,
+        __testAutoConfirm: bool,
+    }
+    let request = serde_wasm_bindgen::to_value(&Req {
+        requestId: "req-1",
+        intentDigest: "register:alice.testnet:1",
+        __testAutoConfirm: true,
+    })
+    .expect("serialize request");
+
+    assert!(
+        test_auto_confirm(&request).is_none(),
+        "__testAutoConfirm must be ignored unless test_confirmation_bypass is enabled"
+    );
+}
+
+#[test]
+fn randomness_self_test_detects_all_zero_and_repeated_output() {
+    use crate::randomness::randomness_self_test_with;
+
+    let err = randomness_self_test_with(|buf| {
+        buf.fill(0);
+        Ok(())
+    })
+    .unwrap_err();
+    assert!(err.contains("all-zero"), "unexpected error: {err}");
+
+    let err = randomness_self_test_with(|buf| {
+        buf.fill(0x42);
+        Ok(())
+    })
+    .unwrap_err();
+    assert!(err.contains("identical"), "unexpected error: {err}");
+
+    let mut calls = 0u8;
+    assert!(randomness_self_test_with(|buf| {
+        calls += 1;
+        buf.fill(calls);
+        Ok(())
+    })
+    .is_ok());
+}
+
+// === DEV DETERMINISTIC VRF ===
+
+/// Golden vector seed: bytes 0x00..=0x1f
+const DEV_DETERMINISTIC_SEED_B64U: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8";
+/// HKDF-SHA256(seed, "tatchi:dev:deterministic-vrf-seed") — the PRF.second stand-in
+const DEV_DETERMINISTIC_PRF_OUTPUT_B64U: &str = "gWErB-T2z4HieVgZlH1E8OFyOr6zhKHue7N3ryYDNwM";
+/// HKDF-SHA256(prf_output, "tatchi:v1:vrf-sk") — the seed fed to the VRF keypair RNG
+const DEV_DETERMINISTIC_VRF_RNG_SEED_HEX: &str =
+    "f0dc24f389a252e9d1beeb41e494aa5af2f27a2a36bd7055e211db94aad5c858";
+/// VRF public key generated from the golden seed
+const DEV_DETERMINISTIC_VRF_PUBLIC_KEY_B64U: &str = "LCK28tX2jIczATF7-eeOI3Y6vGESLO8vtwx5b9oLg2s";
+/// VRF output for the golden seed over `dev_golden_vrf_input()`
+const DEV_DETERMINISTIC_VRF_OUTPUT_B64U: &str =
+    "zH6vi6Xny54x5cMxQjEXk-5LWR5Mmbyb3nCxZzsStVQ5Nd3UHExr3Qt0J6MPCUt_bmc_PNIXsphp8pUSAynFdg";
+
+fn dev_golden_vrf_input() -> VRFInputData {
+    VRFInputData {
+        user_id: "dev-deterministic.test.near".to_string(),
+        rp_id: "localhost".to_string(),
+        block_height: "1".to_string(),
+        block_hash: bs58::encode([7u8; 32]).into_string(),
+        intent_digest: None,
+        session_policy_digest_32: None,
+        session_policy_json: None,
+        extra_context_digest_32: None,
+        rp_id_normalization: RpIdNormalization::default(),
+    }
+}
+
+#[test]
+#[cfg(not(feature = "dev_deterministic_vrf"))]
+fn deterministic_seed_is_rejected_without_dev_feature() {
+    assert_eq!(parse_deterministic_seed_b64u(None), Ok(None));
+    let err = parse_deterministic_seed_b64u(Some(DEV_DETERMINISTIC_SEED_B64U))
+        .expect_err("seed must be rejected, not ignored");
+    assert!(err.contains("dev_deterministic_vrf"), "unexpected error: {err}");
+}
+
+#[test]
+#[cfg(feature = "dev_deterministic_vrf")]
+fn deterministic_seed_rejects_short_or_malformed_input() {
+    assert!(parse_deterministic_seed_b64u(Some("AAEC")).is_err());
+    assert!(parse_deterministic_seed_b64u(Some("not base64!")).is_err());
+}
+
+#[test]
+fn dev_deterministic_seed_golden_vectors() {
+    use hkdf::Hkdf;
+    use sha2::Sha256;
+
+    let seed = base64_url_decode(DEV_DETERMINISTIC_SEED_B64U).unwrap();
+    assert_eq!(seed, (0u8..32).collect::<Vec<u8>>());
+
+    let prf_output = VRFKeyManager::derive_dev_deterministic_prf_output(&seed).unwrap();
+    assert_eq!(base64_url_encode(&prf_output), DEV_DETERMINISTIC_PRF_OUTPUT_B64U);
+
+    let mut rng_seed = [0u8; VRF_SEED_SIZE];
+    Hkdf::<Sha256>::new(None, &prf_output)
+        .expand(HKDF_VRF_KEYPAIR_INFO, &mut rng_seed)
+        .unwrap();
+    let rng_seed_hex: String = rng_seed.iter().map(|b| format!("{:02x}", b)).collect();
+    assert_eq!(rng_seed_hex, DEV_DETERMINISTIC_VRF_RNG_SEED_HEX);
+}
+
+#[test]
+fn dev_deterministic_seed_yields_reproducible_public_key_and_challenge() {
+    let mgr = VRFKeyManager::new(None, None, None, None);
+    let seed = base64_url_decode(DEV_DETERMINISTIC_SEED_B64U).unwrap();
+    let prf_output = VRFKeyManager::derive_dev_deterministic_prf_output(&seed).unwrap();
+
+    let (first, _) = mgr
+        .derive_vrf_keypair_from_prf(
+            prf_output.clone(),
+            "dev-deterministic.test.near".to_string(),
+            Some(dev_golden_vrf_input()),
+        )
+        .expect("derive should succeed");
+    let (second, _) = mgr
+        .derive_vrf_keypair_from_prf(
+            prf_output,
+            "other-account.test.near".to_string(),
+            Some(dev_golden_vrf_input()),
+        )
+        .expect("derive should succeed");
+
+    // Keypair depends only on the seed; the challenge only on keypair + input
+    assert_eq!(first.vrf_public_key, DEV_DETERMINISTIC_VRF_PUBLIC_KEY_B64U);
+    assert_eq!(first.vrf_public_key, second.vrf_public_key);
+    let (c1, c2) = (
+        first.vrf_challenge_data.unwrap(),
+        second.vrf_challenge_data.unwrap(),
+    );
+    assert_eq!(c1.vrf_output, DEV_DETERMINISTIC_VRF_OUTPUT_B64U);
+    assert_eq!(c1.vrf_output, c2.vrf_output);
+    assert_eq!(c1.vrf_proof, c2.vrf_proof);
+    assert_eq!(c1.vrf_public_key, first.vrf_public_key);
+}
+
+// === VERIFICATION CACHE ===
+
+fn verification_key() -> crate::verification_cache::VerificationCacheKey {
+    crate::verification_cache::VerificationCacheKey {
+        credential_id: "cred-id".to_string(),
+        vrf_output: "vrf-output".to_string(),
+        contract_id: "w3a-v1.testnet".to_string(),
+    }
+}
+
+#[test]
+fn verification_cache_reuses_within_ttl_for_matching_digest() {
+    let mut cache = crate::verification_cache::VerificationCache::new();
+    cache.insert(verification_key(), vec!["digest-a".to_string()], None, 1_000.0);
+
+    assert!(cache.is_verified(&verification_key(), "digest-a", 1_000.0 + 29_999.0));
+
+    let other_contract = crate::verification_cache::VerificationCacheKey {
+        contract_id: "other.testnet".to_string(),
+        ..verification_key()
+    };
+    assert!(!cache.is_verified(&other_contract, "digest-a", 1_000.0));
+}
+
+#[test]
+fn verification_cache_requires_fresh_verification_on_digest_mismatch() {
+    let mut cache = crate::verification_cache::VerificationCache::new();
+    cache.insert(verification_key(), vec!["digest-a".to_string()], None, 0.0);
+
+    assert!(!cache.is_verified(&verification_key(), "digest-b", 1.0));
+    assert!(!cache.is_verified(&verification_key(), "", 1.0));
+}
+
+#[test]
+fn verification_cache_entries_expire_and_ttl_is_clamped() {
+    let mut cache = crate::verification_cache::VerificationCache::new();
+    assert_eq!(cache.ttl_ms(), VERIFICATION_CACHE_DEFAULT_TTL_MS);
+    cache.insert(verification_key(), vec!["digest-a".to_string()], None, 0.0);
+
+    let expiry = VERIFICATION_CACHE_DEFAULT_TTL_MS as f64;
+    assert!(!cache.is_verified(&verification_key(), "digest-a", expiry));
+    assert!(cache.is_empty(), "expired entry should be dropped on lookup");
+
+    cache.set_ttl_ms(10 * 60 * 1000);
+    assert_eq!(cache.ttl_ms(), VERIFICATION_CACHE_MAX_TTL_MS);
+
+    cache.set_ttl_ms(0);
+    cache.insert(verification_key(), vec!["digest-a".to_string()], None, 0.0);
+    assert!(!cache.is_verified(&verification_key(), "digest-a", 0.0));
+}
+
+#[test]
+fn verification_cache_is_cleared_on_logout() {
+    let mut mgr = VRFKeyManager::new(None, None, None, None);
+    mgr.verification_cache
+        .insert(verification_key(), vec!["digest-a".to_string()], None, 0.0);
+    assert_eq!(mgr.verification_cache.len(), 1);
+
+    mgr.logout().expect("logout should succeed");
+    assert!(mgr.verification_cache.is_empty());
+    assert!(!mgr
+        .verification_cache
+        .is_verified(&verification_key(), "digest-a", 0.0));
+}
+
+#[test]
+fn verification_cache_hit_reports_original_verification_facts() {
+    let mut cache = crate::verification_cache::VerificationCache::new();
+    cache.insert(
+        verification_key(),
+        vec!["digest-a".to_string()],
+        Some(187_654_321),
+        1_000.0,
+    );
+
+    // A reuse 5s later must still report when (and at which block) the RPC verification ran,
+    // so relayers judge attestation freshness against the real verification.
+    let facts = cache
+        .verified_facts(&verification_key(), "digest-a", 6_000.0)
+        .expect("cache hit");
+    assert_eq!(facts.verified_at_ms, 1_000.0);
+    assert_eq!(facts.block_height, Some(187_654_321));
+    assert_eq!(facts.contract_id, "w3a-v1.testnet");
+    assert_eq!(facts.vrf_output, "vrf-output");
+    assert_eq!(facts.credential_id, "cred-id");
+
+    assert!(cache
+        .verified_facts(&verification_key(), "digest-b", 6_000.0)
+        .is_none());
+}
+
+// === ACCOUNT DISPLAY ===
+
+#[test]
+fn implicit_account_ids_render_truncated_in_summaries() {
+    let implicit = "f3a1000000000000000000000000000000000000000000000000000000009c2e";
+    assert_eq!(implicit_account_display(implicit).as_deref(), Some("f3a1…9c2e"));
+    assert_eq!(implicit_account_display("alice.testnet"), None);
+    assert_eq!(implicit_account_display(&implicit.to_uppercase()), None);
+    assert_eq!(implicit_account_display(&implicit[..63]), None);
+}
+
+// === KEYPAIR SERIALIZATION ===
+
+fn test_keypair_data() -> crate::types::VRFKeypairData {
+    let mgr = VRFKeyManager::new(None, None, None, None);
+    let keypair = mgr
+        .generate_vrf_keypair_from_seed(&create_test_prf_output(), &create_test_account_id())
+        .expect("deterministic VRF keypair");
+    crate::types::VRFKeypairData {
+        keypair_bytes: bincode::serialize(&keypair).unwrap(),
+        public_key_base64: base64_url_encode(&bincode::serialize(&keypair.pk).unwrap()),
+    }
+}
+
+#[test]
+fn vrf_keypair_data_v1_round_trips() {
+    let data = test_keypair_data();
+    let bytes = data.to_versioned_bytes().unwrap();
+    assert_eq!(bytes[0], VRF_KEYPAIR_DATA_VERSION);
+
+    let decoded = crate::types::VRFKeypairData::from_versioned_bytes(&bytes).unwrap();
+    assert_eq!(decoded.keypair_bytes, data.keypair_bytes);
+    assert_eq!(decoded.public_key_base64, data.public_key_base64);
+    let _: vrf_wasm::ecvrf::ECVRFKeyPair = bincode::deserialize(&decoded.keypair_bytes).unwrap();
+}
+
+#[test]
+fn vrf_keypair_data_rejects_unknown_version() {
+    let mut bytes = test_keypair_data().to_versioned_bytes().unwrap();
+    bytes[0] = VRF_KEYPAIR_DATA_VERSION + 1;
+
+    let err = crate::types::VRFKeypairData::from_versioned_bytes(&bytes)
+        .err()
+        .expect("bumped version must be rejected");
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "unsupported VRF keypair serialization version {}",
+            VRF_KEYPAIR_DATA_VERSION + 1
+        )
+    );
+}
+
+#[test]
+fn vrf_keypair_data_still_reads_unversioned_blobs() {
+    let data = test_keypair_data();
+    let legacy = bincode::serialize(&data).unwrap();
+    assert_ne!(legacy[0], VRF_KEYPAIR_DATA_VERSION);
+
+    let decoded = crate::types::VRFKeypairData::from_versioned_bytes(&legacy).unwrap();
+    assert_eq!(decoded.keypair_bytes, data.keypair_bytes);
+}
+
+// === SESSION RENEWAL ===
+
+const RENEW_TTL_MS: u32 = 60_000;
+const RENEW_MAX_LIFETIME_MS: f64 = 150_000.0;
+
+fn renewable_session(policy_digest: Option<&str>) -> VrfSessionData {
+    VrfSessionData {
+        wrap_key_seed: vec![4u8; 32],
+        wrap_key_salt_b64u: "salt".to_string(),
+        created_at_ms: 0.0,
+        expires_at_ms: Some(RENEW_TTL_MS as f64),
+        remaining_uses: Some(5),
+        renewable_until_ms: Some(RENEW_MAX_LIFETIME_MS),
+        policy_digest: policy_digest.map(str::to_string),
+        contract_verification: None,
+        key_reuse_policy: None,
+    }
+}
+
+fn renew_request(
+    session_id: &str,
+    policy_digest: Option<&str>,
+) -> crate::handlers::RenewSessionRequest {
+    crate::handlers::RenewSessionRequest {
+        session_id: session_id.to_string(),
+        ttl_ms: Some(RENEW_TTL_MS),
+        session_policy_digest: policy_digest.map(str::to_string),
+        session_policy_json: None,
+    }
+}
+
+#[test]
+fn renew_session_extends_expiry_inside_window() {
+    use crate::handlers::renew_session_at;
+    let mut mgr = VRFKeyManager::new(None, None, None, None);
+    mgr.upsert_session("sess-renew", renewable_session(Some("policy-a")));
+
+    let result = renew_session_at(
+        &mut mgr,
+        &renew_request("sess-renew", Some("policy-a")),
+        50_000.0,
+    )
+    .expect("renewal inside the window");
+    assert_eq!(result.expires_at_ms, 110_000.0);
+    assert_eq!(result.remaining_ms, 60_000.0);
+    assert_eq!(result.renewable_until_ms, Some(RENEW_MAX_LIFETIME_MS));
+
+    let session = mgr.sessions.get("sess-renew").unwrap();
+    assert_eq!(
+        session.remaining_uses,
+        Some(5),
+        "renewal keeps the uses budget"
+    );
+    assert!(session.is_renewable(100_000.0));
+}
+
+#[test]
+fn renew_session_is_capped_at_max_lifetime() {
+    use crate::handlers::renew_session_at;
+    let mut mgr = VRFKeyManager::new(None, None, None, None);
+    mgr.upsert_session("sess-cap", renewable_session(None));
+
+    renew_session_at(&mut mgr, &renew_request("sess-cap", None), 50_000.0).unwrap();
+    let capped = renew_session_at(&mut mgr, &renew_request("sess-cap", None), 100_000.0).unwrap();
+    assert_eq!(capped.expires_at_ms, RENEW_MAX_LIFETIME_MS);
+    assert!(!mgr
+        .sessions
+        .get("sess-cap")
+        .unwrap()
+        .is_renewable(RENEW_MAX_LIFETIME_MS));
+
+    // A session without a renewal ceiling cannot be renewed at all.
+    let mut legacy = renewable_session(None);
+    legacy.renewable_until_ms = None;
+    mgr.upsert_session("sess-legacy", legacy);
+    let err = mgr.renew_session("sess-legacy", 60_000, None, 10_000.0);
+    assert!(matches!(
+        err,
+        Err(VrfWorkerError::SessionRenewalWindowClosed)
+    ));
+    assert!(mgr.sessions.contains_key("sess-legacy"));
+}
+
+#[test]
+fn renew_session_rejects_and_clears_expired_session() {
+    use crate::handlers::renew_session_at;
+    let mut mgr = VRFKeyManager::new(None, None, None, None);
+    mgr.upsert_session("sess-late", renewable_session(None));
+
+    let err = renew_session_at(&mut mgr, &renew_request("sess-late", None), 60_000.0).unwrap_err();
+    assert_eq!(err, "SESSION_EXPIRED");
+    assert!(!mgr.sessions.contains_key("sess-late"));
+}
+
+#[test]
+fn renew_session_rejects_policy_change() {
+    use crate::handlers::renew_session_at;
+    let mut mgr = VRFKeyManager::new(None, None, None, None);
+    mgr.upsert_session("sess-policy", renewable_session(Some("policy-a")));
+
+    let err = renew_session_at(
+        &mut mgr,
+        &renew_request("sess-policy", Some("policy-b")),
+        10_000.0,
+    )
+    .unwrap_err();
+    assert_eq!(err, "SESSION_POLICY_MISMATCH");
+    assert!(!mgr.sessions.contains_key("sess-policy"));
+}
+
+// === SESSION POLICY DIGEST ===
+
+/// Shared with the relayer tests (`__tests__/relayer/sessionPolicyDigest.test.ts`).
+const PINNED_POLICY_JSON: &str = r#"{"version":"threshold_session_v1","nearAccountId":"alice.testnet","rpId":"example.localhost","relayerKeyId":"relayer-key-1","sessionId":"tsess-fixed","participantIds":[1,2],"ttlMs":300000,"remainingUses":5}"#;
+const PINNED_POLICY_DIGEST_B64U: &str = "pzR1TlQUUoWeSUM38w1ulPPHi_neDTP0C4U2n7G4I_c";
+
+#[test]
+fn session_policy_digest_matches_pinned_vector() {
+    use crate::session_policy::{canonicalize_session_policy_json, compute_session_policy_digest};
+    assert_eq!(
+        canonicalize_session_policy_json(PINNED_POLICY_JSON).unwrap(),
+        r#"{"nearAccountId":"alice.testnet","participantIds":[1,2],"relayerKeyId":"relayer-key-1","remainingUses":5,"rpId":"example.localhost","sessionId":"tsess-fixed","ttlMs":300000,"version":"threshold_session_v1"}"#
+    );
+    let digest = compute_session_policy_digest(PINNED_POLICY_JSON).unwrap();
+    assert_eq!(base64_url_encode(&digest), PINNED_POLICY_DIGEST_B64U);
+}
+
+#[test]
+fn session_policy_digest_ignores_key_order_and_whitespace() {
+    use crate::session_policy::compute_session_policy_digest;
+    let reordered = r#"{"ttlMs":300000,"remainingUses":5,"participantIds":[1,2],"sessionId":"tsess-fixed","relayerKeyId":"relayer-key-1","rpId":"example.localhost","nearAccountId":"alice.testnet","version":"threshold_session_v1"}"#;
+    let spaced = "{\n  \"version\": \"threshold_session_v1\",\n  \"nearAccountId\": \"alice.testnet\",\n  \"rpId\": \"example.localhost\",\n  \"relayerKeyId\": \"relayer-key-1\",\n  \"sessionId\": \"tsess-fixed\",\n  \"participantIds\": [ 1, 2 ],\n  \"ttlMs\": 300000,\n  \"remainingUses\": 5\n}\n";
+    let expected = compute_session_policy_digest(PINNED_POLICY_JSON).unwrap();
+    assert_eq!(compute_session_policy_digest(reordered).unwrap(), expected);
+    assert_eq!(compute_session_policy_digest(spaced).unwrap(), expected);
+
+    // Array order is significant.
+    let swapped = PINNED_POLICY_JSON.replace("[1,2]", "[2,1]");
+    assert_ne!(compute_session_policy_digest(&swapped).unwrap(), expected);
+}
+
+#[test]
+fn session_policy_digest_rejects_floats_and_unsafe_integers() {
+    use crate::errors::PolicyError;
+    use crate::session_policy::compute_session_policy_digest;
+    for ttl in ["300000.0", "3e5", "300000.5"] {
+        let json = format!(r#"{{"ttlMs":{},"remainingUses":5}}"#, ttl);
+        assert_eq!(
+            compute_session_policy_digest(&json),
+            Err(PolicyError::FloatNotAllowed("$.ttlMs".to_string())),
+            "{ttl}"
+        );
+    }
+    assert_eq!(
+        compute_session_policy_digest(r#"{"limits":[{"maxYocto":9007199254740992}]}"#),
+        Err(PolicyError::IntegerOutOfRange("$.limits[0].maxYocto".to_string()))
+    );
+    assert_eq!(
+        compute_session_policy_digest("[1,2]"),
+        Err(PolicyError::NotAnObject)
+    );
+    assert!(matches!(
+        compute_session_policy_digest("{\"ttlMs\":"),
+        Err(PolicyError::InvalidJson(_))
+    ));
+}
+
+#[test]
+fn session_policy_json_must_agree_with_presented_digest() {
+    use crate::session_policy::resolve_session_policy_digest;
+    assert_eq!(
+        resolve_session_policy_digest(Some(PINNED_POLICY_JSON), None).unwrap(),
+        Some(PINNED_POLICY_DIGEST_B64U.to_string())
+    );
+    assert_eq!(
+        resolve_session_policy_digest(
+            Some(PINNED_POLICY_JSON),
+            Some(PINNED_POLICY_DIGEST_B64U)
+        )
+        .unwrap(),
+        Some(PINNED_POLICY_DIGEST_B64U.to_string())
+    );
+    assert_eq!(
+        resolve_session_policy_digest(None, Some(" policy-a ")).unwrap(),
+        Some("policy-a".to_string())
+    );
+    assert!(matches!(
+        resolve_session_policy_digest(Some(PINNED_POLICY_JSON), Some("policy-a")),
+        Err(VrfWorkerError::SessionPolicyMismatch)
+    ));
+}
+
+#[test]
+fn renew_session_accepts_policy_json_for_digest_minted_session() {
+    use crate::handlers::renew_session_at;
+    let mut mgr = VRFKeyManager::new(None, None, None, None);
+    mgr.upsert_session(
+        "sess-policy-json",
+        renewable_session(Some(PINNED_POLICY_DIGEST_B64U)),
+    );
+
+    let mut request = renew_request("sess-policy-json", None);
+    request.session_policy_json = Some(
+        PINNED_POLICY_JSON
+            .replace(",", ", ")
+            .replace(r#""ttlMs":300000,"#, ""),
+    );
+    let err = renew_session_at(&mut mgr, &request, 10_000.0).unwrap_err();
+    assert_eq!(err, "SESSION_POLICY_MISMATCH");
+
+    mgr.upsert_session(
+        "sess-policy-json",
+        renewable_session(Some(PINNED_POLICY_DIGEST_B64U)),
+    );
+    request.session_policy_json = Some(PINNED_POLICY_JSON.replace(",", ", "));
+    renew_session_at(&mut mgr, &request, 10_000.0).expect("same policy, reformatted");
+}
+
+// === BATCH UNLOCK ===
+
+fn encrypted_keypair_for(
+    mgr: &VRFKeyManager,
+    account_id: &str,
+    prf_key: &[u8],
+) -> (String, crate::types::EncryptedVRFKeypair) {
+    let keypair = mgr
+        .generate_vrf_keypair_from_seed(prf_key, account_id)
+        .expect("deterministic VRF keypair");
+    mgr.encrypt_vrf_keypair_data(&keypair, prf_key)
+        .expect("encrypt VRF keypair")
+}
+
+#[test]
+fn batch_unlock_reports_partial_success() {
+    let mut mgr = VRFKeyManager::new(None, None, None, None);
+    let prf_alice = vec![1u8; 32];
+    let prf_bob = vec![2u8; 32];
+    let (alice_pk, alice_enc) = encrypted_keypair_for(&mgr, "alice.testnet", &prf_alice);
+    let (_, bob_enc) = encrypted_keypair_for(&mgr, "bob.testnet", &prf_bob);
+    let (_, carol_enc) = encrypted_keypair_for(&mgr, "carol.testnet", &[3u8; 32]);
+
+    let results = mgr.unlock_vrf_keypairs(
+        vec![
+            ("alice.testnet".to_string(), alice_enc, prf_alice),
+            ("bob.testnet".to_string(), bob_enc, prf_bob),
+            // Wrong PRF key: AEAD decryption must fail for this account only.
+            ("carol.testnet".to_string(), carol_enc, vec![9u8; 32]),
+        ],
+        1_000.0,
+    );
+
+    let summary: Vec<(&str, bool)> = results
+        .iter()
+        .map(|r| (r.near_account_id.as_str(), r.success))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("alice.testnet", true),
+            ("bob.testnet", true),
+            ("carol.testnet", false)
+        ]
+    );
+    assert!(results[2].error.as_deref().unwrap_or("").contains("decrypt"));
+
+    assert_eq!(mgr.unlocked_vrf_keypairs.len(), 2);
+    assert!(!mgr.unlocked_vrf_keypairs.contains_key("carol.testnet"));
+    // The first unlocked account is active.
+    assert!(mgr.session_active);
+    let active_pk = base64_url_encode(
+        &bincode::serialize(&mgr.vrf_keypair.as_ref().unwrap().inner().pk).unwrap(),
+    );
+    assert_eq!(active_pk, alice_pk);
+
+    mgr.activate_unlocked_vrf_keypair("bob.testnet", 2_000.0)
+        .expect("bob was unlocked");
+    assert!(mgr
+        .activate_unlocked_vrf_keypair("carol.testnet", 2_000.0)
+        .is_err());
+
+    mgr.logout().expect("logout should succeed");
+    assert!(mgr.unlocked_vrf_keypairs.is_empty());
+}
+
+#[test]
+fn batch_unlock_with_no_successes_keeps_manager_locked() {
+    let mut mgr = VRFKeyManager::new(None, None, None, None);
+    let (_, enc) = encrypted_keypair_for(&mgr, "alice.testnet", &[1u8; 32]);
+
+    let results = mgr.unlock_vrf_keypairs(
+        vec![
+            ("alice.testnet".to_string(), enc.clone(), vec![]),
+            (String::new(), enc, vec![1u8; 32]),
+        ],
+        0.0,
+    );
+
+    assert!(results.iter().all(|r| !r.success));
+    assert!(!mgr.session_active);
+    assert!(mgr.vrf_keypair.is_none());
+}
+
+// === DEVICE2 COMPLETE REGISTRATION ===
+
+mod device2_complete_registration_tests {
+    use super::*;
+    use crate::handlers::handle_device2_complete_registration::{
+        derive_device2_registration_material, Device2CompleteRegistrationResult,
+        Device2SignerDelivery,
+    };
+    use hkdf::Hkdf;
+    use sha2::Sha256;
+    use std::cell::RefCell;
+
+    const ACCOUNT: &str = "device2.testnet";
+    const SALT: &str = "device2-wrap-key-salt";
+
+    /// Stands in for the TS layer: fixed PRF outputs, driven through either the composed
+    /// request or the fine-grained DERIVE_VRF_KEYPAIR_FROM_PRF + MINT_SESSION_KEYS steps.
+    struct Device2Harness {
+        prf_first: Vec<u8>,
+        prf_second: Vec<u8>,
+    }
+
+    impl Device2Harness {
+        fn new() -> Self {
+            Self {
+                prf_first: vec![0x11; 32],
+                prf_second: create_test_prf_output(),
+            }
+        }
+
+        fn composed(
+            &self,
+            manager: &RefCell<VRFKeyManager>,
+        ) -> Result<(Device2CompleteRegistrationResult, Device2SignerDelivery), String> {
+            derive_device2_registration_material(
+                manager,
+                ACCOUNT,
+                &self.prf_first,
+                self.prf_second.clone(),
+                Some(dev_golden_vrf_input()),
+                Some(SALT.to_string()),
+                1_000.0,
+            )
+        }
+
+        /// Returns the VRF public key, challenge output and WrapKeySeed the fine-grained
+        /// requests produce, re-deriving the seed the way the mint handler does.
+        fn fine_grained(&self) -> (String, String, Vec<u8>) {
+            let mut mgr = VRFKeyManager::new(None, None, None, None);
+            let (derivation, keypair) = mgr
+                .derive_vrf_keypair_from_prf(
+                    self.prf_second.clone(),
+                    ACCOUNT.to_string(),
+                    Some(dev_golden_vrf_input()),
+                )
+                .expect("derive should succeed");
+            mgr.store_vrf_keypair_in_memory_at(keypair, ACCOUNT.to_string(), 1_000.0);
+
+            let mut k_pass_auth = [0u8; 32];
+            Hkdf::<Sha256>::new(None, &self.prf_first)
+                .expand(crate::config::VRF_WRAP_PASS_INFO, &mut k_pass_auth)
+                .unwrap();
+            let mut ikm = k_pass_auth.to_vec();
+            ikm.extend_from_slice(&mgr.get_vrf_secret_key_bytes().unwrap());
+            let mut wrap_key_seed = vec![0u8; 32];
+            Hkdf::<Sha256>::new(None, &ikm)
+                .expand(crate::config::NEAR_WRAP_SEED_INFO, &mut wrap_key_seed)
+                .unwrap();
+
+            let challenge = derivation.vrf_challenge_data.expect("challenge requested");
+            (derivation.vrf_public_key, challenge.vrf_output, wrap_key_seed)
+        }
+    }
+
+    #[test]
+    fn composed_request_matches_fine_grained_flow() {
+        let harness = Device2Harness::new();
+        let manager = RefCell::new(VRFKeyManager::new(None, None, None, None));
+
+        let (result, delivery) = harness.composed(&manager).expect("composed flow");
+        let (vrf_public_key, vrf_output, wrap_key_seed) = harness.fine_grained();
+
+        assert_eq!(result.vrf_public_key, vrf_public_key);
+        assert_eq!(result.vrf_challenge_data.unwrap().vrf_output, vrf_output);
+        assert_eq!(delivery.wrap_key_seed_b64u, base64_url_encode(&wrap_key_seed));
+        assert_eq!(delivery.prf_second_b64u, base64_url_encode(&harness.prf_second));
+        assert_eq!(delivery.wrap_key_salt_b64u, SALT);
+        assert_eq!(result.wrap_key_salt, SALT);
+        assert!(result.server_encrypted_vrf_keypair.is_none());
+
+        // The derived keypair is active, and the stored blob unlocks with PRF.second.
+        assert!(manager.borrow().session_active);
+        let mut fresh = VRFKeyManager::new(None, None, None, None);
+        let outcomes = fresh.unlock_vrf_keypairs(
+            vec![(
+                ACCOUNT.to_string(),
+                result.encrypted_vrf_keypair.expect("encrypted keypair"),
+                harness.prf_second.clone(),
+            )],
+            0.0,
+        );
+        assert!(outcomes[0].success);
+    }
+
+    #[test]
+    fn composed_request_rejects_bad_input_without_loading_a_keypair() {
+        let manager = RefCell::new(VRFKeyManager::new(None, None, None, None));
+        let mut harness = Device2Harness::new();
+        harness.prf_second.truncate(16);
+        assert!(harness
+            .composed(&manager)
+            .err()
+            .unwrap()
+            .contains("expected 32 bytes"));
+
+        let err = derive_device2_registration_material(
+            &manager,
+            "",
+            &[0x11; 32],
+            create_test_prf_output(),
+            None,
+            Some(SALT.to_string()),
+            0.0,
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err, "Missing NEAR account ID");
+        assert!(manager.borrow().vrf_keypair.is_none());
+    }
+
+    #[test]
+    fn composed_request_rejects_identical_prf_outputs() {
+        let manager = RefCell::new(VRFKeyManager::new(None, None, None, None));
+        let mut harness = Device2Harness::new();
+        harness.prf_first = harness.prf_second.clone();
+
+        let err = harness.composed(&manager).err().unwrap();
+        assert_eq!(
+            err,
+            "PRF.first and PRF.second are identical; dual-PRF misconfiguration"
+        );
+        assert!(manager.borrow().vrf_keypair.is_none());
+
+        // Distinct outputs of the same length go through.
+        harness.prf_first = vec![0x11; 32];
+        assert!(harness.composed(&manager).is_ok());
+        assert!(manager.borrow().vrf_keypair.is_some());
+    }
+
+    #[test]
+    fn ensure_distinct_prf_outputs_compares_full_contents() {
+        use crate::webauthn::ensure_distinct_prf_outputs;
+
+        let prf = create_test_prf_output();
+        assert!(matches!(
+            ensure_distinct_prf_outputs(&prf, &prf.clone()),
+            Err(VrfWorkerError::IdenticalPrfOutputs)
+        ));
+
+        let mut last_byte_differs = prf.clone();
+        *last_byte_differs.last_mut().unwrap() ^= 0x01;
+        assert!(ensure_distinct_prf_outputs(&prf, &last_byte_differs).is_ok());
+        assert!(ensure_distinct_prf_outputs(&prf, &prf[..16]).is_ok());
+    }
+}
+
+// === BLOCK CONTEXT ===
+
+mod block_context_tests {
+    use crate::errors::VrfWorkerError;
+    use crate::handlers::handle_generate_vrf_challenge::resolve_block_context;
+    use crate::manager::{BlockContextSource, VRFKeyManager};
+    use crate::rp_id_normalization::RpIdNormalization;
+    use crate::rpc_calls::{BlockContext, BlockFetcher};
+    use crate::rpc_headers::RpcHeaders;
+    use crate::types::VRFInputData;
+    use futures::executor::block_on;
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    const BLOCK_HASH: &str = "11111111111111111111111111111111";
+
+    struct MockBlockFetcher {
+        result: Result<(u64, String), String>,
+        calls: Cell<u32>,
+    }
+
+    impl MockBlockFetcher {
+        fn ok(height: u64) -> Self {
+            Self {
+                result: Ok((height, BLOCK_HASH.to_string())),
+                calls: Cell::new(0),
+            }
+        }
+
+        fn failing() -> Self {
+            Self {
+                result: Err("connection refused".to_string()),
+                calls: Cell::new(0),
+            }
+        }
+    }
+
+    impl BlockFetcher for MockBlockFetcher {
+        async fn fetch_latest_block(
+            &self,
+            _rpc_url: &str,
+            _rpc_headers: &RpcHeaders,
+        ) -> Result<(u64, String), String> {
+            self.calls.set(self.calls.get() + 1);
+            self.result.clone()
+        }
+    }
+
+    fn manager_with_source(max_staleness_ms: u64) -> Rc<RefCell<VRFKeyManager>> {
+        let mut mgr = VRFKeyManager::new(None, None, None, None);
+        mgr.block_context_source = Some(BlockContextSource {
+            near_rpc_url: "https://rpc.testnet.near.org".to_string(),
+            max_staleness_ms,
+        });
+        Rc::new(RefCell::new(mgr))
+    }
+
+    fn input(block_height: &str, block_hash: &str) -> VRFInputData {
+        VRFInputData {
+            user_id: "alice.testnet".to_string(),
+            rp_id: "example.com".to_string(),
+            block_height: block_height.to_string(),
+            block_hash: block_hash.to_string(),
+            intent_digest: None,
+            session_policy_digest_32: None,
+            session_policy_json: None,
+            extra_context_digest_32: None,
+            rp_id_normalization: RpIdNormalization::default(),
+        }
+    }
+
+    #[test]
+    fn fresh_cached_block_context_is_reused() {
+        let manager = manager_with_source(10_000);
+        manager.borrow_mut().block_context = Some(BlockContext {
+            height: 100,
+            hash: BLOCK_HASH.to_string(),
+            fetched_at_ms: 1_000.0,
+        });
+        let fetcher = MockBlockFetcher::ok(200);
+
+        let resolved =
+            block_on(resolve_block_context(&manager, &fetcher, input("", ""), 5_000.0)).unwrap();
+
+        assert_eq!(resolved.block_height, "100");
+        assert_eq!(resolved.block_hash, BLOCK_HASH);
+        assert_eq!(fetcher.calls.get(), 0);
+    }
+
+    #[test]
+    fn stale_block_context_is_refreshed_and_cached() {
+        let manager = manager_with_source(10_000);
+        manager.borrow_mut().block_context = Some(BlockContext {
+            height: 100,
+            hash: BLOCK_HASH.to_string(),
+            fetched_at_ms: 1_000.0,
+        });
+        let fetcher = MockBlockFetcher::ok(200);
+
+        let resolved =
+            block_on(resolve_block_context(&manager, &fetcher, input("", ""), 20_000.0)).unwrap();
+
+        assert_eq!(resolved.block_height, "200");
+        assert_eq!(fetcher.calls.get(), 1);
+        let cached = manager.borrow().block_context.clone().unwrap();
+        assert_eq!(cached.height, 200);
+        assert_eq!(cached.fetched_at_ms, 20_000.0);
+
+        // Subsequent requests within the staleness window hit the new cache entry.
+        block_on(resolve_block_context(&manager, &fetcher, input("", ""), 21_000.0)).unwrap();
+        assert_eq!(fetcher.calls.get(), 1);
+    }
+
+    #[test]
+    fn explicit_block_values_override_the_cache() {
+        let manager = manager_with_source(10_000);
+        let fetcher = MockBlockFetcher::ok(200);
+        let explicit_hash = "22222222222222222222222222222222";
+
+        let resolved = block_on(resolve_block_context(
+            &manager,
+            &fetcher,
+            input("42", explicit_hash),
+            0.0,
+        ))
+        .unwrap();
+
+        assert_eq!(resolved.block_height, "42");
+        assert_eq!(resolved.block_hash, explicit_hash);
+        assert_eq!(fetcher.calls.get(), 0);
+        assert!(manager.borrow().block_context.is_none());
+
+        let err = block_on(resolve_block_context(&manager, &fetcher, input("42", ""), 0.0))
+            .unwrap_err();
+        assert!(matches!(err, VrfWorkerError::InvalidMessageFormat(_)));
+    }
+
+    #[test]
+    fn fetch_failure_is_block_context_unavailable_not_stale_data() {
+        let manager = manager_with_source(10_000);
+        manager.borrow_mut().block_context = Some(BlockContext {
+            height: 100,
+            hash: BLOCK_HASH.to_string(),
+            fetched_at_ms: 0.0,
+        });
+        let fetcher = MockBlockFetcher::failing();
+
+        let err = block_on(resolve_block_context(&manager, &fetcher, input("", ""), 60_000.0))
+            .unwrap_err();
+
+        assert!(matches!(err, VrfWorkerError::BlockContextUnavailable(_)));
+        assert!(err.to_string().contains("connection refused"));
+
+        let unconfigured = Rc::new(RefCell::new(VRFKeyManager::new(None, None, None, None)));
+        let err = block_on(resolve_block_context(
+            &unconfigured,
+            &MockBlockFetcher::ok(1),
+            input("", ""),
+            0.0,
+        ))
+        .unwrap_err();
+        assert!(matches!(err, VrfWorkerError::BlockContextUnavailable(_)));
+    }
+}
+
+// === RPC HEADERS ===
+
+mod rpc_header_tests {
+    use crate::rpc_calls::{
+        execute_rpc_request_with, RpcHttpRequest, RpcHttpResponse, RpcTransport,
+    };
+    use crate::rpc_headers::{
+        describe_rpc_headers, merge_rpc_headers, redact_header_value, validate_rpc_headers,
+        RpcHeaders,
+    };
+    use futures::executor::block_on;
+    use std::cell::RefCell;
+
+    /// Records every request and replies with a fixed response.
+    struct MockTransport {
+        sent: RefCell<Vec<RpcHttpRequest>>,
+        response: RpcHttpResponse,
+    }
+
+    impl RpcTransport for MockTransport {
+        async fn send(&self, request: &RpcHttpRequest) -> Result<RpcHttpResponse, String> {
+            self.sent.borrow_mut().push(request.clone());
+            Ok(self.response.clone())
+        }
+    }
+
+    fn api_key_headers() -> RpcHeaders {
+        RpcHeaders::from([("x-api-key".to_string(), "secret-api-key-1234".to_string())])
+    }
+
+    #[test]
+    fn rpc_headers_are_injected_into_built_request() {
+        let transport = MockTransport {
+            sent: RefCell::new(Vec::new()),
+            response: RpcHttpResponse {
+                ok: true,
+                status: 200,
+                status_text: "OK".to_string(),
+                body: "{}".to_string(),
+            },
+        };
+        let body = block_on(execute_rpc_request_with(
+            &transport,
+            "https://rpc.example",
+            "{\"jsonrpc\":\"2.0\"}",
+            &api_key_headers(),
+        ))
+        .expect("request should succeed");
+        assert_eq!(body, "{}");
+
+        let sent = transport.sent.borrow();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].url, "https://rpc.example");
+        assert!(sent[0]
+            .headers
+            .contains(&("Content-Type".to_string(), "application/json".to_string())));
+        assert!(sent[0]
+            .headers
+            .contains(&("x-api-key".to_string(), "secret-api-key-1234".to_string())));
+    }
+
+    #[test]
+    fn rpc_error_paths_redact_header_values() {
+        // Provider echoes the key back in its error body.
+        let transport = MockTransport {
+            sent: RefCell::new(Vec::new()),
+            response: RpcHttpResponse {
+                ok: false,
+                status: 401,
+                status_text: "Unauthorized".to_string(),
+                body: "invalid api key secret-api-key-1234".to_string(),
+            },
+        };
+        let err = block_on(execute_rpc_request_with(
+            &transport,
+            "https://a.example, https://b.example",
+            "{}",
+            &api_key_headers(),
+        ))
+        .unwrap_err();
+        assert_eq!(transport.sent.borrow().len(), 2, "fallback endpoint should be tried");
+        assert!(!err.contains("secret-api-key-1234"), "{}", err);
+        assert!(err.contains("****1234"), "{}", err);
+
+        let described = describe_rpc_headers(&api_key_headers());
+        assert_eq!(described, "x-api-key=****1234");
+        assert_eq!(redact_header_value("abc"), "****");
+    }
+
+    #[test]
+    fn forbidden_rpc_headers_are_rejected() {
+        for name in ["Cookie", "host", "Content-Type", "Sec-Fetch-Mode", "Proxy-Foo", "bad header"] {
+            let headers = RpcHeaders::from([(name.to_string(), "v".to_string())]);
+            assert!(
+                validate_rpc_headers(&headers).is_err(),
+                "{} should be rejected",
+                name
+            );
+        }
+
+        let crlf = RpcHeaders::from([("x-api-key".to_string(), "a\r\nInjected: 1".to_string())]);
+        assert!(validate_rpc_headers(&crlf).is_err());
+
+        // Authorization is allowed (flagged as sensitive in logs only).
+        let auth = RpcHeaders::from([("Authorization".to_string(), "Bearer tok-9876".to_string())]);
+        assert!(validate_rpc_headers(&auth).is_ok());
+        assert_eq!(
+            describe_rpc_headers(&auth),
+            "Authorization(sensitive)=****9876"
+        );
+    }
+
+    #[test]
+    fn per_request_rpc_headers_override_defaults_case_insensitively() {
+        let defaults = RpcHeaders::from([
+            ("X-Api-Key".to_string(), "default".to_string()),
+            ("x-client".to_string(), "tatchi".to_string()),
+        ]);
+        let per_request = api_key_headers();
+        let merged = merge_rpc_headers(&defaults, Some(&per_request));
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged["x-api-key"], "secret-api-key-1234");
+        assert_eq!(merged["x-client"], "tatchi");
+    }
+}
+
+// === SUMMARY LOCALIZATION ===
+
+mod summary_localization_tests {
+    use crate::summary_localization::{
+        canonical_summary_fields, format_near_amount, localize_summary, LocalizedStrings,
+        SUMMARY_FIELD_AMOUNT, SUMMARY_FIELD_RECEIVER,
+    };
+
+    const RECEIVER: &str = "bob.testnet";
+    const AMOUNT_YOCTO: &str = "1500000000000000000000000";
+
+    fn localized(receiver: &str, amount: &str) -> LocalizedStrings {
+        LocalizedStrings::from([
+            (SUMMARY_FIELD_RECEIVER.to_string(), receiver.to_string()),
+            (SUMMARY_FIELD_AMOUNT.to_string(), amount.to_string()),
+        ])
+    }
+
+    #[test]
+    fn localized_strings_containing_raw_values_are_accepted() {
+        assert_eq!(format_near_amount(AMOUNT_YOCTO), "1.5");
+        assert_eq!(format_near_amount("2000000000000000000000000"), "2");
+        assert_eq!(format_near_amount("1"), "0.000000000000000000000001");
+
+        let strings = localized("Destinataire : bob.testnet", "Montant : 1.5 NEAR");
+        let summary = localize_summary(
+            canonical_summary_fields(Some(RECEIVER), Some(AMOUNT_YOCTO)),
+            Some("fr"),
+            Some(&strings),
+        );
+        assert!(!summary.localization_rejected);
+        assert_eq!(summary.locale.as_deref(), Some("fr"));
+        assert_eq!(summary.fields[0].value, RECEIVER);
+        assert_eq!(summary.fields[0].text, "Destinataire : bob.testnet");
+        assert_eq!(summary.fields[1].value, "1.5");
+        assert_eq!(summary.fields[1].text, "Montant : 1.5 NEAR");
+    }
+
+    #[test]
+    fn localized_strings_missing_the_amount_fall_back_to_english() {
+        // Localized decimal separator drops the raw "1.5" value.
+        let strings = localized("Destinataire : bob.testnet", "Montant : 1,5 NEAR");
+        let summary = localize_summary(
+            canonical_summary_fields(Some(RECEIVER), Some(AMOUNT_YOCTO)),
+            Some("fr"),
+            Some(&strings),
+        );
+        assert!(summary.localization_rejected);
+        assert_eq!(summary.locale, None);
+        assert_eq!(
+            summary.fields,
+            canonical_summary_fields(Some(RECEIVER), Some(AMOUNT_YOCTO))
+        );
+        assert_eq!(summary.fields[1].text, "Amount: 1.5 NEAR");
+
+        let unknown_field =
+            LocalizedStrings::from([("memo".to_string(), "bob.testnet".to_string())]);
+        let summary = localize_summary(
+            canonical_summary_fields(Some(RECEIVER), Some(AMOUNT_YOCTO)),
+            Some("fr"),
+            Some(&unknown_field),
+        );
+        assert!(summary.localization_rejected);
+    }
+
+    #[test]
+    fn canonical_digest_is_invariant_across_locales() {
+        let canonical = canonical_summary_fields(Some(RECEIVER), Some(AMOUNT_YOCTO));
+        let english = localize_summary(canonical.clone(), None, None);
+        let french = localize_summary(
+            canonical.clone(),
+            Some("fr"),
+            Some(&localized(
+                "Destinataire : bob.testnet",
+                "Montant : 1.5 NEAR",
+            )),
+        );
+        let japanese = localize_summary(
+            canonical.clone(),
+            Some("ja"),
+            Some(&localized("受取人: bob.testnet", "金額: 1.5 NEAR")),
+        );
+        assert!(!french.localization_rejected && !japanese.localization_rejected);
+        assert_ne!(english.fields[0].text, japanese.fields[0].text);
+
+        let digest = english.canonical_digest_b64u();
+        assert_eq!(french.canonical_digest_b64u(), digest);
+        assert_eq!(japanese.canonical_digest_b64u(), digest);
+
+        let other = localize_summary(
+            canonical_summary_fields(Some("mallory.testnet"), Some(AMOUNT_YOCTO)),
+            None,
+            None,
+        );
+        assert_ne!(other.canonical_digest_b64u(), digest);
+    }
+}
+
+mod host_environment_tests {
+    use crate::host_environment::{
+        evaluate_environment, AWAIT_SECURE_CONFIRMATION_V2, FETCH, SET_TIMEOUT,
+    };
+
+    #[test]
+    fn complete_environment_reports_ok() {
+        let report = evaluate_environment(|_| true);
+        assert!(report.ok);
+        assert!(report.missing.is_empty());
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn missing_required_bridges_are_listed() {
+        let report = evaluate_environment(|name| name == FETCH);
+        assert!(!report.ok);
+        assert_eq!(
+            report.missing,
+            vec![
+                AWAIT_SECURE_CONFIRMATION_V2.to_string(),
+                SET_TIMEOUT.to_string()
+            ]
+        );
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn missing_fetch_only_warns() {
+        let report = evaluate_environment(|name| name != FETCH);
+        assert!(report.ok);
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].starts_with("fetch is not available"));
+    }
+
+    /// Browser checks: delete the bridge from globalThis and observe the structured error.
+    /// Run with `wasm-pack test --headless --chrome`.
+    #[cfg(target_arch = "wasm32")]
+    mod browser {
+        use super::*;
+        use crate::await_secure_confirmation::vrf_await_secure_confirmation;
+        use crate::host_environment::check_environment;
+        use js_sys::Reflect;
+        use wasm_bindgen::JsValue;
+        use wasm_bindgen_test::*;
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        fn remove(name: &str) {
+            Reflect::delete_property(&js_sys::global(), &JsValue::from_str(name)).unwrap();
+        }
+
+        #[wasm_bindgen_test]
+        fn check_environment_reports_deleted_confirmation_bridge() {
+            remove(AWAIT_SECURE_CONFIRMATION_V2);
+            let report = check_environment();
+            let ok = Reflect::get(&report, &JsValue::from_str("ok")).unwrap();
+            let missing: Vec<String> =
+                js_sys::Array::from(&Reflect::get(&report, &JsValue::from_str("missing")).unwrap())
+                    .iter()
+                    .filter_map(|v| v.as_string())
+                    .collect();
+            assert_eq!(ok.as_bool(), Some(false));
+            assert!(missing.contains(&AWAIT_SECURE_CONFIRMATION_V2.to_string()));
+        }
+
+        #[wasm_bindgen_test]
+        async fn confirmation_without_bridge_fails_with_missing_host_bridge() {
+            remove(AWAIT_SECURE_CONFIRMATION_V2);
+            let err = vrf_await_secure_confirmation(js_sys::Object::new().into())
+                .await
+                .err()
+                .expect("confirmation must fail without the bridge");
+            assert!(
+                err.starts_with("MissingHostBridge(\"awaitSecureConfirmationV2\")"),
+                "unexpected error: {}",
+                err
+            );
+        }
+    }
+}
+
+mod shamir3pass_client_tests {
+    use crate::errors::{VrfResult, VrfWorkerError};
+    use crate::handlers::handle_shamir3pass_client::{
+        check_shamir_server_response, shamir_apply_server_lock_with,
+        shamir_remove_server_lock_and_decrypt_with,
+    };
+    use crate::http::ShamirRelayTransport;
+    use crate::shamir3pass::{
+        decode_biguint_b64u, encode_biguint_b64u, ClientLockKeys, Shamir3Pass,
+    };
+    use crate::types::http::{
+        ShamirApplyServerLockHTTPResponse, ShamirRemoveServerLockHTTPResponse,
+    };
+    use crate::types::progress::{VrfProgress, VrfProgressPhase};
+    use futures::executor::block_on;
+    use num_bigint::BigUint;
+
+    const URL: &str = "https://relay.example/vrf/shamir";
+    const PLAINTEXT: &[u8] = b"vrf keypair bytes";
+
+    enum Reply {
+        /// Apply/remove the server lock as a real relay would
+        Honest,
+        /// Echo the submitted value
+        Identity,
+        /// Return p itself (not a group element)
+        OutOfRange,
+        /// Honest value with the trailing base64url characters cut to `keep` of them
+        Truncated { keep: fn(usize) -> usize },
+        /// Transport-level failure
+        Unreachable,
+    }
+
+    struct MockRelay {
+        shamir: Shamir3Pass,
+        server_lock: ClientLockKeys,
+        reply: Reply,
+    }
+
+    impl MockRelay {
+        fn new(shamir: &Shamir3Pass, reply: Reply) -> Self {
+            Self {
+                shamir: shamir.clone(),
+                server_lock: shamir.generate_lock_keys().unwrap(),
+                reply,
+            }
+        }
+
+        fn answer(&self, submitted_b64u: &str, exponent: &BigUint) -> VrfResult<String> {
+            let submitted = decode_biguint_b64u(submitted_b64u).unwrap();
+            let honest = encode_biguint_b64u(&self.shamir.modexp(&submitted, exponent));
+            match &self.reply {
+                Reply::Honest => Ok(honest),
+                Reply::Identity => Ok(submitted_b64u.to_string()),
+                Reply::OutOfRange => Ok(encode_biguint_b64u(self.shamir.p())),
+                Reply::Truncated { keep } => Ok(honest[..keep(honest.len())].to_string()),
+                Reply::Unreachable => Err(VrfWorkerError::ShamirServerUnreachable(
+                    "HTTP error: 503 Service Unavailable".to_string(),
+                )),
+            }
+        }
+    }
+
+    impl ShamirRelayTransport for MockRelay {
+        async fn apply_server_lock(
+            &self,
+            _endpoint_url: &str,
+            kek_c_b64u: &str,
+        ) -> VrfResult<ShamirApplyServerLockHTTPResponse> {
+            Ok(ShamirApplyServerLockHTTPResponse {
+                kek_cs_b64u: self.answer(kek_c_b64u, &self.server_lock.e)?,
+                key_id: Some("server-key-1".to_string()),
+            })
+        }
+
+        async fn remove_server_lock(
+            &self,
+            _endpoint_url: &str,
+            kek_cs_b64u: &str,
+            _key_id: String,
+        ) -> VrfResult<ShamirRemoveServerLockHTTPResponse> {
+            Ok(ShamirRemoveServerLockHTTPResponse {
+                kek_c_b64u: self.answer(kek_cs_b64u, &self.server_lock.d)?,
+            })
+        }
+    }
+
+    /// Register against an honest relay, then unlock against `relay` (same server lock).
+    fn unlock_with(reply: Reply) -> VrfResult<Vec<u8>> {
+        let shamir = Shamir3Pass::new_default();
+        let (ciphertext, kek) = shamir.encrypt_with_random_kek_key(PLAINTEXT).unwrap();
+        let mut relay = MockRelay::new(&shamir, Reply::Honest);
+        let (kek_s, _) =
+            block_on(shamir_apply_server_lock_with(&relay, &shamir, URL, &kek)).unwrap();
+        relay.reply = reply;
+        block_on(shamir_remove_server_lock_and_decrypt_with(
+            &relay,
+            &shamir,
+            URL,
+            &kek_s,
+            "server-key-1".to_string(),
+            &ciphertext,
+            &VrfProgress::silent(),
+        ))
+    }
+
+    fn apply_with(reply: Reply) -> VrfResult<(BigUint, Option<String>)> {
+        let shamir = Shamir3Pass::new_default();
+        let (_, kek) = shamir.encrypt_with_random_kek_key(PLAINTEXT).unwrap();
+        let relay = MockRelay::new(&shamir, reply);
+        block_on(shamir_apply_server_lock_with(&relay, &shamir, URL, &kek))
+    }
+
+    #[test]
+    fn honest_relay_round_trip_decrypts() {
+        let (_, key_id) = apply_with(Reply::Honest).unwrap();
+        assert_eq!(key_id.as_deref(), Some("server-key-1"));
+        assert_eq!(unlock_with(Reply::Honest).unwrap(), PLAINTEXT);
+    }
+
+    #[test]
+    fn identity_response_is_server_misbehavior() {
+        let err = apply_with(Reply::Identity).unwrap_err();
+        assert!(
+            matches!(err, VrfWorkerError::ShamirServerMisbehavior(ref msg) if msg.contains("unchanged")),
+            "{}",
+            err
+        );
+        let err = unlock_with(Reply::Identity).unwrap_err();
+        assert!(
+            err.to_string().starts_with("SHAMIR_SERVER_MISBEHAVIOR"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn out_of_range_response_is_server_misbehavior() {
+        let err = apply_with(Reply::OutOfRange).unwrap_err();
+        assert!(
+            matches!(err, VrfWorkerError::ShamirServerMisbehavior(ref msg) if msg.contains("out of range")),
+            "{}",
+            err
+        );
+
+        let shamir = Shamir3Pass::new_default();
+        let submitted = BigUint::from(7u32);
+        let zero = encode_biguint_b64u(&BigUint::from(0u32));
+        assert!(matches!(
+            check_shamir_server_response(&shamir, "apply-server-lock", &submitted, &zero),
+            Err(VrfWorkerError::ShamirServerMisbehavior(_))
+        ));
+        assert!(matches!(
+            check_shamir_server_response(&shamir, "apply-server-lock", &submitted, "not base64!"),
+            Err(VrfWorkerError::ShamirServerMisbehavior(_))
+        ));
+    }
+
+    #[test]
+    fn truncated_response_is_detected() {
+        // A cut that leaves an invalid base64url length never decodes.
+        let err = unlock_with(Reply::Truncated {
+            keep: |len| (len - 1) / 4 * 4 + 1,
+        })
+        .unwrap_err();
+        assert!(
+            matches!(err, VrfWorkerError::ShamirServerMisbehavior(_)),
+            "{}",
+            err
+        );
+
+        // A cut on a clean boundary decodes to a smaller in-range value; only the AEAD
+        // check after remove-lock can tell, and it must fail before success is reported.
+        let err = unlock_with(Reply::Truncated {
+            keep: |len| (len - 1) / 4 * 4,
+        })
+        .unwrap_err();
+        assert!(
+            matches!(err, VrfWorkerError::ShamirDecryptFailed(_)),
+            "{}",
+            err
+        );
+        assert!(err.to_string().starts_with("SHAMIR_DECRYPT_FAILED"));
+    }
+
+    #[test]
+    fn unlock_reports_network_and_local_phases_in_order() {
+        let shamir = Shamir3Pass::new_default();
+        let (ciphertext, kek) = shamir.encrypt_with_random_kek_key(PLAINTEXT).unwrap();
+        let relay = MockRelay::new(&shamir, Reply::Honest);
+        let (kek_s, _) =
+            block_on(shamir_apply_server_lock_with(&relay, &shamir, URL, &kek)).unwrap();
+
+        super::vrf_progress_tests::reset_recorded();
+        let progress = VrfProgress::with_sink(super::vrf_progress_tests::record);
+        let plaintext = block_on(shamir_remove_server_lock_and_decrypt_with(
+            &relay,
+            &shamir,
+            URL,
+            &kek_s,
+            "server-key-1".to_string(),
+            &ciphertext,
+            &progress,
+        ))
+        .unwrap();
+        assert_eq!(plaintext, PLAINTEXT);
+
+        let recorded = super::vrf_progress_tests::take_recorded();
+        let phases: Vec<VrfProgressPhase> = recorded.iter().map(|(p, _)| *p).collect();
+        assert_eq!(
+            phases,
+            vec![
+                VrfProgressPhase::ClientLockApplied,
+                VrfProgressPhase::ServerRoundtripStart,
+                VrfProgressPhase::ServerRoundtripComplete,
+                VrfProgressPhase::AeadDecryptStart,
+            ]
+        );
+        assert_eq!(recorded[1].1.as_deref(), Some(URL));
+    }
+
+    #[test]
+    fn unreachable_relay_stops_progress_at_roundtrip_start() {
+        let shamir = Shamir3Pass::new_default();
+        let (ciphertext, kek) = shamir.encrypt_with_random_kek_key(PLAINTEXT).unwrap();
+        let mut relay = MockRelay::new(&shamir, Reply::Honest);
+        let (kek_s, _) =
+            block_on(shamir_apply_server_lock_with(&relay, &shamir, URL, &kek)).unwrap();
+        relay.reply = Reply::Unreachable;
+
+        super::vrf_progress_tests::reset_recorded();
+        let progress = VrfProgress::with_sink(super::vrf_progress_tests::record);
+        assert!(block_on(shamir_remove_server_lock_and_decrypt_with(
+            &relay,
+            &shamir,
+            URL,
+            &kek_s,
+            "server-key-1".to_string(),
+            &ciphertext,
+            &progress,
+        ))
+        .is_err());
+
+        let phases: Vec<VrfProgressPhase> = super::vrf_progress_tests::take_recorded()
+            .into_iter()
+            .map(|(p, _)| p)
+            .collect();
+        assert_eq!(
+            phases.last(),
+            Some(&VrfProgressPhase::ServerRoundtripStart),
+            "UI must be left waiting on the relay, not on local decryption"
+        );
+    }
+
+    #[test]
+    fn transport_failure_is_server_unreachable() {
+        let err = apply_with(Reply::Unreachable).unwrap_err();
+        assert!(
+            err.to_string().starts_with("SHAMIR_SERVER_UNREACHABLE"),
+            "{}",
+            err
+        );
+        assert!(matches!(
+            unlock_with(Reply::Unreachable).unwrap_err(),
+            VrfWorkerError::ShamirServerUnreachable(_)
+        ));
+    }
+}
+
+// === VRF PROGRESS PHASES ===
+
+mod vrf_progress_tests {
+    use crate::manager::VRFKeyManager;
+    use crate::types::progress::{vrf_progress_phase_name, VrfProgress, VrfProgressPhase};
+    use std::cell::RefCell;
+
+    thread_local! {
+        static RECORDED: RefCell<Vec<(VrfProgressPhase, Option<String>)>> =
+            const { RefCell::new(Vec::new()) };
+    }
+
+    /// Recording sink: stands in for the JS `sendVrfProgressMessage` bridge.
+    pub(super) fn record(phase: VrfProgressPhase, _elapsed_ms: f64, detail: Option<&str>) {
+        RECORDED.with(|r| r.borrow_mut().push((phase, detail.map(str::to_string))));
+    }
+
+    pub(super) fn reset_recorded() {
+        RECORDED.with(|r| r.borrow_mut().clear());
+    }
+
+    pub(super) fn take_recorded() -> Vec<(VrfProgressPhase, Option<String>)> {
+        RECORDED.with(|r| std::mem::take(&mut *r.borrow_mut()))
+    }
+
+    #[test]
+    fn phase_values_round_trip_with_names() {
+        let phases = [
+            (VrfProgressPhase::ClientLockApplied, "client-lock-applied"),
+            (
+                VrfProgressPhase::ServerRoundtripStart,
+                "server-roundtrip-start",
+            ),
+            (
+                VrfProgressPhase::ServerRoundtripComplete,
+                "server-roundtrip-complete",
+            ),
+            (VrfProgressPhase::AeadDecryptStart, "aead-decrypt-start"),
+            (VrfProgressPhase::KeypairLoaded, "keypair-loaded"),
+            (VrfProgressPhase::Derivation, "derivation"),
+            (VrfProgressPhase::Encryption, "encryption"),
+            (VrfProgressPhase::ServerEncryption, "server-encryption"),
+        ];
+        for (i, (phase, name)) in phases.iter().enumerate() {
+            assert_eq!(*phase as u32, 200 + i as u32);
+            assert_eq!(VrfProgressPhase::try_from(*phase as u32), Ok(*phase));
+            assert_eq!(vrf_progress_phase_name(*phase), *name);
+        }
+        assert!(VrfProgressPhase::try_from(208).is_err());
+        assert!(VrfProgressPhase::try_from(106).is_err());
+    }
+
+    #[test]
+    fn derivation_reports_derivation_then_encryption() {
+        let mgr = VRFKeyManager::new(None, None, None, None);
+        reset_recorded();
+        mgr.derive_vrf_keypair_from_prf_with_progress(
+            super::create_test_prf_output(),
+            super::create_test_account_id(),
+            None,
+            &VrfProgress::with_sink(record),
+        )
+        .expect("derive should succeed");
+        let phases: Vec<VrfProgressPhase> = take_recorded().into_iter().map(|(p, _)| p).collect();
+        assert_eq!(
+            phases,
+            vec![VrfProgressPhase::Derivation, VrfProgressPhase::Encryption]
+        );
+    }
+
+    #[test]
+    fn elapsed_time_is_never_negative() {
+        let progress = VrfProgress::silent();
+        assert!(progress.elapsed_ms() >= 0.0);
+    }
+
+    /// Browser check: the real bridge forwards numeric phase + name to the JS global.
+    /// Run with `wasm-pack test --headless --chrome`.
+    #[cfg(target_arch = "wasm32")]
+    mod browser {
+        use super::*;
+        use crate::host_environment::SEND_VRF_PROGRESS_MESSAGE;
+        use js_sys::{Array, Function, Reflect};
+        use wasm_bindgen::JsValue;
+        use wasm_bindgen_test::*;
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        /// Install a `sendVrfProgressMessage` that appends `[phase, phaseName, elapsedMs, detail]`.
+        fn install_recording_bridge() -> Array {
+            let calls = Array::new();
+            let recorder = Function::new_with_args(
+                "phase, phaseName, elapsedMs, detail",
+                "this.push([phase, phaseName, elapsedMs, detail]);",
+            )
+            .bind(&calls);
+            Reflect::set(
+                &js_sys::global(),
+                &JsValue::from_str(SEND_VRF_PROGRESS_MESSAGE),
+                &recorder,
+            )
+            .unwrap();
+            calls
+        }
+
+        #[wasm_bindgen_test]
+        fn derivation_phases_reach_the_js_bridge() {
+            let calls = install_recording_bridge();
+            VRFKeyManager::new(None, None, None, None)
+                .derive_vrf_keypair_from_prf_with_progress(
+                    super::super::create_test_prf_output(),
+                    super::super::create_test_account_id(),
+                    None,
+                    &VrfProgress::start(),
+                )
+                .expect("derive should succeed");
+
+            let recorded: Vec<(u32, String, f64)> = calls
+                .iter()
+                .map(|call| {
+                    let call = Array::from(&call);
+                    (
+                        call.get(0).as_f64().unwrap() as u32,
+                        call.get(1).as_string().unwrap(),
+                        call.get(2).as_f64().unwrap(),
+                    )
+                })
+                .collect();
+            assert_eq!(
+                recorded
+                    .iter()
+                    .map(|(phase, name, _)| (*phase, name.as_str()))
+                    .collect::<Vec<_>>(),
+                vec![(205, "derivation"), (206, "encryption")]
+            );
+            assert!(recorded.iter().all(|(_, _, elapsed)| *elapsed >= 0.0));
+        }
+    }
+}
+
+// === DEVICE2 ACCOUNT CARD CROSS-CHECK ===
+
+mod device2_account_card_tests {
+    use crate::errors::VrfWorkerError;
+    use crate::handlers::handle_device2_registration_session::{
+        check_account_card_for_registration, VerifiedAccountCard,
+    };
+
+    const EXPIRES_AT_MS: u64 = 1_700_000_600_000;
+
+    /// Shape returned by the signer worker's VERIFY_ACCOUNT_CARD.
+    fn card() -> VerifiedAccountCard {
+        serde_json::from_value(serde_json::json!({
+            "nearAccountId": "alice.testnet",
+            "nearPublicKey": "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp",
+            "vrfPublicKeyRawB64u": "BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc",
+            "deviceNumber": 2,
+            "issuedAtMs": 1_700_000_000_000u64,
+            "expiresAtMs": EXPIRES_AT_MS,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn matching_card_is_accepted() {
+        assert!(check_account_card_for_registration(
+            &card(),
+            "alice.testnet",
+            2,
+            (EXPIRES_AT_MS - 1) as f64
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn card_for_another_account_or_device_is_rejected() {
+        let now_ms = (EXPIRES_AT_MS - 1) as f64;
+        for (account, device_number) in [("mallory.testnet", 2), ("alice.testnet", 3)] {
+            let err = check_account_card_for_registration(&card(), account, device_number, now_ms)
+                .unwrap_err();
+            assert!(
+                matches!(err, VrfWorkerError::AccountCardMismatch(_)),
+                "{}",
+                err
+            );
+            assert!(err.to_string().starts_with("ACCOUNT_CARD_MISMATCH"));
+        }
+    }
+
+    #[test]
+    fn expired_card_is_rejected_at_registration() {
+        for now_ms in [EXPIRES_AT_MS as f64, f64::NAN] {
+            let err = check_account_card_for_registration(&card(), "alice.testnet", 2, now_ms)
+                .unwrap_err();
+            assert!(
+                matches!(err, VrfWorkerError::AccountCardExpired(EXPIRES_AT_MS)),
+                "{}",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn card_with_a_non_base64url_vrf_key_does_not_parse() {
+        let mut json = serde_json::to_value(card()).unwrap();
+        json["vrfPublicKeyRawB64u"] = "+//+".into();
+        let err = serde_json::from_value::<VerifiedAccountCard>(json).unwrap_err();
+        assert!(err.to_string().contains("expected base64url"), "{}", err);
+    }
+}
+
+// === CONFIRMATION RESPONSE PARSING ===
+
+mod confirmation_response_tests {
+    use crate::await_secure_confirmation::{
+        parse_confirmation_fields, validate_confirmation_fields, ConfirmationFieldValue,
+        ConfirmationFields,
+    };
+    use crate::errors::{VrfResult, VrfWorkerError};
+    use std::collections::BTreeMap;
+
+    fn parse(fixture: serde_json::Value) -> VrfResult<ConfirmationFields> {
+        let raw: BTreeMap<String, ConfirmationFieldValue> =
+            serde_json::from_value(fixture).expect("fixture is an object");
+        parse_confirmation_fields(&raw)
+    }
+
+    fn malformed(field: &str) -> VrfWorkerError {
+        VrfWorkerError::ConfirmationMalformed {
+            field: field.to_string(),
+        }
+    }
+
+    fn assert_err(result: VrfResult<impl std::fmt::Debug>, expected: VrfWorkerError) {
+        let err = result.expect_err("expected an error");
+        assert_eq!(err.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn snake_and_camel_case_responses_parse_identically() {
+        let snake = parse(serde_json::json!({
+            "request_id": "req-1",
+            "intent_digest": "digest-1",
+            "confirmed": true,
+            "credential": { "id": "cred", "response": {} },
+            "vrf_challenge": { "vrfOutput": "out" },
+            "transaction_context": { "nextNonce": "5" },
+        }))
+        .unwrap();
+        let camel = parse(serde_json::json!({
+            "requestId": "req-1",
+            "intentDigest": "digest-1",
+            "confirmed": true,
+            "credential": { "id": "cred", "response": {} },
+            "vrfChallenge": { "vrfOutput": "out" },
+            "transactionContext": { "nextNonce": "5" },
+        }))
+        .unwrap();
+
+        assert_eq!(snake, camel);
+        assert_eq!(
+            snake,
+            ConfirmationFields {
+                request_id: "req-1".to_string(),
+                confirmed: true,
+                intent_digest: Some("digest-1".to_string()),
+                error: None,
+                has_credential: true,
+            }
+        );
+        assert!(validate_confirmation_fields("signTransaction", &snake).is_ok());
+        assert!(validate_confirmation_fields("registerAccount", &camel).is_ok());
+    }
+
+    #[test]
+    fn absent_optional_fields_are_none() {
+        for fixture in [
+            serde_json::json!({ "requestId": "req-2", "confirmed": false }),
+            serde_json::json!({
+                "request_id": "req-2",
+                "confirmed": false,
+                "intent_digest": null,
+                "credential": null,
+                "error": null,
+            }),
+        ] {
+            let fields = parse(fixture).unwrap();
+            assert_eq!(fields.intent_digest, None);
+            assert_eq!(fields.error, None);
+            assert!(!fields.has_credential);
+            // A cancelled confirmation carries nothing else, whatever its type.
+            assert!(validate_confirmation_fields("registerAccount", &fields).is_ok());
+        }
+    }
+
+    #[test]
+    fn missing_or_mistyped_required_fields_are_malformed() {
+        assert_err(
+            parse(serde_json::json!({ "confirmed": true })),
+            malformed("request_id"),
+        );
+        assert_err(
+            parse(serde_json::json!({ "requestId": 7, "confirmed": true })),
+            malformed("request_id"),
+        );
+        assert_err(
+            parse(serde_json::json!({ "requestId": "req-3" })),
+            malformed("confirmed"),
+        );
+        assert_err(
+            parse(serde_json::json!({ "requestId": "req-3", "confirmed": "yes" })),
+            malformed("confirmed"),
+        );
+        assert_err(
+            parse(serde_json::json!({
+                "requestId": "req-3",
+                "confirmed": true,
+                "intentDigest": { "digest": "d" },
+            })),
+            malformed("intent_digest"),
+        );
+    }
+
+    #[test]
+    fn confirmed_registration_without_credential_is_rejected() {
+        let fields = parse(serde_json::json!({ "requestId": "req-4", "confirmed": true })).unwrap();
+        for request_type in ["registerAccount", "linkDevice", "decryptPrivateKeyWithPrf"] {
+            let err = validate_confirmation_fields(request_type, &fields).unwrap_err();
+            assert!(
+                matches!(err, VrfWorkerError::ConfirmationMissingCredential),
+                "{request_type}: {err}"
+            );
+            assert_eq!(err.to_string(), "CONFIRMATION_MISSING_CREDENTIAL");
+        }
+        assert!(validate_confirmation_fields("showSecurePrivateKeyUi", &fields).is_ok());
+    }
+
+    #[test]
+    fn confirmed_signing_requires_intent_digest_but_not_credential() {
+        let warm = parse(serde_json::json!({
+            "request_id": "req-5",
+            "confirmed": true,
+            "intent_digest": "digest-5",
+        }))
+        .unwrap();
+        assert!(validate_confirmation_fields("signTransaction", &warm).is_ok());
+
+        for fixture in [
+            serde_json::json!({ "request_id": "req-5", "confirmed": true }),
+            serde_json::json!({ "request_id": "req-5", "confirmed": true, "intent_digest": "" }),
+        ] {
+            let fields = parse(fixture).unwrap();
+            for request_type in ["signTransaction", "signNep413Message"] {
+                assert_err(
+                    validate_confirmation_fields(request_type, &fields),
+                    malformed("intent_digest"),
+                );
+            }
+        }
+    }
+}
+
+mod state_change_tests {
+    use super::{create_test_account_id, create_test_prf_output, session_with_expiry};
+    use crate::manager::{VRFKeyManager, VrfSessionData};
+    use crate::state_changes::{StateChangeKind, StateChangeLog};
+
+    fn kinds(mgr: &VRFKeyManager, since: u64) -> Vec<StateChangeKind> {
+        mgr.state_changes
+            .since(since)
+            .events
+            .iter()
+            .map(|e| e.kind)
+            .collect()
+    }
+
+    fn renewable(expires_at_ms: f64) -> VrfSessionData {
+        let mut session = session_with_expiry(expires_at_ms);
+        session.renewable_until_ms = Some(1_000_000.0);
+        session
+    }
+
+    #[test]
+    fn every_session_mutation_bumps_the_version() {
+        let mut mgr = VRFKeyManager::new(None, None, None, None);
+        assert_eq!(mgr.state_changes.version(), 0);
+
+        mgr.upsert_session("sess-a", renewable(60_000.0));
+        assert_eq!(mgr.state_changes.version(), 1);
+        mgr.dispense_session_key("sess-a", 1, 1_000.0).unwrap();
+        assert_eq!(mgr.state_changes.version(), 2);
+        mgr.renew_session("sess-a", 60_000, None, 2_000.0).unwrap();
+        assert_eq!(mgr.state_changes.version(), 3);
+        assert!(mgr.clear_session("sess-a"));
+        assert_eq!(mgr.state_changes.version(), 4);
+        assert!(!mgr.clear_session("sess-a"), "nothing left to clear");
+        assert_eq!(mgr.state_changes.version(), 4);
+
+        mgr.upsert_session("sess-b", session_with_expiry(10_000.0));
+        assert!(mgr.dispense_session_key("sess-b", 1, 10_000.0).is_err());
+        assert_eq!(
+            kinds(&mgr, 0),
+            vec![
+                StateChangeKind::SessionCreated,
+                StateChangeKind::SessionConsumed,
+                StateChangeKind::SessionRenewed,
+                StateChangeKind::SessionCleared,
+                StateChangeKind::SessionCreated,
+                StateChangeKind::SessionExpired,
+            ]
+        );
+
+        // Checking a missing session changes nothing.
+        assert!(mgr.ensure_session_usable("sess-b", 0.0).is_ok());
+        assert_eq!(mgr.state_changes.version(), 6);
+    }
+
+    #[test]
+    fn unlock_and_logout_are_recorded_with_account() {
+        let mut mgr = VRFKeyManager::new(None, None, None, None);
+        let keypair = mgr
+            .generate_vrf_keypair_from_seed(&create_test_prf_output(), &create_test_account_id())
+            .unwrap();
+        mgr.store_vrf_keypair_in_memory_at(keypair, create_test_account_id(), 5_000.0);
+        mgr.logout().unwrap();
+
+        let changes = mgr.state_changes.since(0);
+        assert_eq!(changes.version, 2);
+        assert_eq!(changes.events[0].kind, StateChangeKind::Unlock);
+        assert_eq!(
+            changes.events[0].account_id.as_deref(),
+            Some("test-account.testnet")
+        );
+        assert_eq!(changes.events[0].at_ms, 5_000.0);
+        assert_eq!(changes.events[1].kind, StateChangeKind::Logout);
+        assert_eq!(changes.events[1].account_id, None);
+    }
+
+    #[test]
+    fn since_returns_exactly_the_missed_events() {
+        let mut log = StateChangeLog::with_capacity(8);
+        for at_ms in [1.0, 2.0, 3.0, 4.0] {
+            log.record(StateChangeKind::SessionConsumed, None, at_ms);
+        }
+
+        let missed = log.since(2);
+        assert_eq!(missed.version, 4);
+        assert!(!missed.resync_required);
+        assert_eq!(
+            missed.events.iter().map(|e| e.version).collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+
+        let up_to_date = log.since(4);
+        assert!(up_to_date.events.is_empty());
+        assert!(!up_to_date.resync_required);
+    }
+
+    #[test]
+    fn ring_overflow_requires_resync() {
+        let mut log = StateChangeLog::with_capacity(3);
+        for _ in 0..5 {
+            log.record(StateChangeKind::SessionCreated, None, 0.0);
+        }
+
+        // Versions 1 and 2 were evicted; a caller at 0 or 1 cannot replay.
+        let stale = log.since(1);
+        assert!(stale.resync_required);
+        assert_eq!(
+            stale.events.iter().map(|e| e.version).collect::<Vec<_>>(),
+            vec![3, 4, 5]
+        );
+        // A caller at 2 only missed retained events.
+        assert!(!log.since(2).resync_required);
+        // A version from another worker instance (e.g. after a reload) is never replayable.
+        let foreign = log.since(9);
+        assert!(foreign.resync_required);
+        assert!(foreign.events.is_empty());
+    }
+
+    #[test]
+    fn event_kinds_serialize_as_stable_numbers() {
+        let mut log = StateChangeLog::default();
+        log.record(StateChangeKind::SessionRenewed, Some("alice.near"), 7.0);
+        let json = serde_json::to_value(log.since(0)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "version": 1,
+                "events": [{ "version": 1, "kind": 7, "accountId": "alice.near", "atMs": 7.0 }],
+                "resyncRequired": false,
+            })
+        );
+        assert_eq!(StateChangeKind::Unlock as u32, 1);
+        assert_eq!(StateChangeKind::ThresholdSessionChanged as u32, 9);
+        assert_eq!(StateChangeKind::AuthenticatorCounterRegression as u32, 10);
+    }
+}
+
+mod logout_all_tests {
+    use super::{create_test_account_id, create_test_prf_output, session_with_expiry};
+    use crate::manager::{LogoutAllSummary, VRFKeyManager};
+    use crate::state_changes::StateChangeKind;
+
+    #[test]
+    fn logout_all_clears_every_session_and_the_keypair() {
+        let mut mgr = VRFKeyManager::new(None, None, None, None);
+        let keypair = mgr
+            .generate_vrf_keypair_from_seed(&create_test_prf_output(), &create_test_account_id())
+            .unwrap();
+        mgr.store_vrf_keypair_in_memory_at(keypair, create_test_account_id(), 1_000.0);
+        mgr.upsert_session("sess-a", session_with_expiry(60_000.0));
+        mgr.upsert_session("sess-b", session_with_expiry(60_000.0));
+
+        let summary = mgr.logout_all().unwrap();
+        assert_eq!(
+            summary,
+            LogoutAllSummary {
+                cleared_sessions: 2,
+                cleared_challenges: 0,
+                cleared_active_keypair: true,
+                cleared_unlocked_keypairs: 0,
+            }
+        );
+        assert!(mgr.sessions.is_empty());
+        assert!(mgr.vrf_keypair.is_none());
+        assert!(!mgr.session_active);
+        assert!(mgr.dispense_session_key("sess-a", 1, 2_000.0).is_err());
+        assert_eq!(
+            mgr.state_changes.since(0).events.last().map(|e| e.kind),
+            Some(StateChangeKind::Logout)
+        );
+
+        // Nothing left for a second call.
+        let again = mgr.logout_all().unwrap();
+        assert_eq!(again.cleared_sessions, 0);
+        assert!(!again.cleared_active_keypair);
+    }
+}
+
+mod logout_epoch_tests {
+    use super::{
+        create_test_account_id, create_test_prf_output, session_with_expiry, verification_key,
+    };
+    use crate::manager::VRFKeyManager;
+    use crate::rpc_calls::BlockContext;
+
+    fn logged_in_manager() -> VRFKeyManager {
+        let mut mgr = VRFKeyManager::new(None, None, None, None);
+        let keypair = mgr
+            .generate_vrf_keypair_from_seed(&create_test_prf_output(), &create_test_account_id())
+            .unwrap();
+        mgr.store_vrf_keypair_in_memory_at(keypair, create_test_account_id(), 1_000.0);
+        mgr.upsert_session("sess-a", session_with_expiry(60_000.0));
+        mgr.verification_cache
+            .insert(verification_key(), vec!["digest-a".to_string()], None, 0.0);
+        mgr.block_context = Some(BlockContext {
+            height: 100,
+            hash: "block-hash".to_string(),
+            fetched_at_ms: 1_000.0,
+        });
+        mgr
+    }
+
+    #[test]
+    fn logout_clears_every_store_and_completes_the_requested_epoch() {
+        let mut mgr = logged_in_manager();
+        assert_eq!(mgr.logout_epoch, 0);
+
+        assert_eq!(mgr.logout_at_epoch(Some(5)).unwrap(), 5);
+        assert!(mgr.vrf_keypair.is_none());
+        assert!(mgr.unlocked_vrf_keypairs.is_empty());
+        assert!(mgr.sessions.is_empty());
+        assert!(mgr.vrf_challenges.is_empty());
+        assert!(mgr.verification_cache.is_empty());
+        assert!(mgr.block_context.is_none());
+        assert!(!mgr.session_active);
+        assert!(mgr.dispense_session_key("sess-a", 1, 2_000.0).is_err());
+
+        let status = mgr.get_vrf_status();
+        assert!(!status.active);
+        assert_eq!(status.logout_epoch, 5);
+    }
+
+    #[test]
+    fn logout_without_an_epoch_advances_it() {
+        let mut mgr = logged_in_manager();
+        mgr.logout().unwrap();
+        assert_eq!(mgr.logout_epoch, 1);
+        assert_eq!(mgr.logout_at_epoch(None).unwrap(), 2);
+        // LOGOUT_ALL is a logout too.
+        mgr.logout_all().unwrap();
+        assert_eq!(mgr.get_vrf_status().logout_epoch, 3);
+    }
+}
+
+mod vrf_diagnostics_tests {
+    use super::{create_test_account_id, create_test_prf_output};
+    use crate::manager::{VRFKeyManager, VrfDiagnostics};
+    use crate::shamir3pass::Shamir3Pass;
+
+    /// 256-bit prime (2^255 + 95), distinct from the built-in default.
+    const TEST_PRIME_B64U: &str = "gAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAF8";
+
+    #[test]
+    fn unconfigured_locked_manager_reports_defaults() {
+        let mgr = VRFKeyManager::new(None, None, None, None);
+        assert_eq!(
+            mgr.diagnostics(),
+            VrfDiagnostics {
+                session_active: false,
+                has_keypair: false,
+                account_id: None,
+                shamir_configured: false,
+                relay_urls_set: false,
+                prime_bits: Shamir3Pass::new_default().p().bits(),
+            }
+        );
+    }
+
+    #[test]
+    fn configured_unlocked_manager_reports_account_and_config() {
+        let mut mgr = VRFKeyManager::new(
+            Some(TEST_PRIME_B64U),
+            Some("https://relay.example.com".to_string()),
+            Some("/vrf/apply-server-lock".to_string()),
+            Some("/vrf/remove-server-lock".to_string()),
+        );
+        let keypair = mgr
+            .generate_vrf_keypair_from_seed(&create_test_prf_output(), &create_test_account_id())
+            .unwrap();
+        mgr.store_vrf_keypair_in_memory_at(keypair, create_test_account_id(), 1_000.0);
+
+        let diagnostics = mgr.diagnostics();
+        assert_eq!(
+            diagnostics,
+            VrfDiagnostics {
+                session_active: true,
+                has_keypair: true,
+                account_id: Some(create_test_account_id()),
+                shamir_configured: true,
+                relay_urls_set: true,
+                prime_bits: 256,
+            }
+        );
+        let json = serde_json::to_string(&diagnostics).unwrap();
+        assert!(!json.contains("relay.example.com"));
+
+        mgr.logout().unwrap();
+        let locked = mgr.diagnostics();
+        assert!(!locked.session_active && !locked.has_keypair);
+        assert_eq!(locked.account_id, None);
+        assert!(locked.shamir_configured && locked.relay_urls_set);
+    }
+
+    #[test]
+    fn relay_url_without_lock_routes_is_not_set() {
+        let mgr = VRFKeyManager::new(
+            None,
+            Some("https://relay.example.com".to_string()),
+            None,
+            Some("  ".to_string()),
+        );
+        assert!(!mgr.diagnostics().relay_urls_set);
+    }
+}
+
+mod vrf_challenge_batch_tests {
+    use super::{create_test_account_id, create_test_prf_output, session_with_expiry};
+    use crate::config::VRF_CHALLENGE_BATCH_MAX_COUNT;
+    use crate::errors::VrfWorkerError;
+    use crate::manager::VRFKeyManager;
+    use crate::rp_id_normalization::RpIdNormalization;
+    use crate::types::VRFInputData;
+    use crate::utils::base64_url_encode;
+
+    fn unlocked_manager() -> VRFKeyManager {
+        let mut mgr = VRFKeyManager::new(None, None, None, None);
+        let keypair = mgr
+            .generate_vrf_keypair_from_seed(&create_test_prf_output(), &create_test_account_id())
+            .unwrap();
+        mgr.store_vrf_keypair_in_memory_at(keypair, create_test_account_id(), 1_000.0);
+        mgr
+    }
+
+    fn base_input() -> VRFInputData {
+        VRFInputData {
+            user_id: create_test_account_id(),
+            rp_id: "example.com".to_string(),
+            block_height: "12345".to_string(),
+            block_hash: "11111111111111111111111111111111".to_string(),
+            intent_digest: None,
+            session_policy_digest_32: None,
+            session_policy_json: None,
+            extra_context_digest_32: None,
+            rp_id_normalization: RpIdNormalization::default(),
+        }
+    }
+
+    fn digests(n: u8) -> Vec<String> {
+        (0..n).map(|i| base64_url_encode(&[i; 32])).collect()
+    }
+
+    #[test]
+    fn each_digest_gets_a_distinct_challenge_in_request_order() {
+        let mut mgr = unlocked_manager();
+        let intent_digests = digests(3);
+        let batch = mgr
+            .generate_vrf_challenges(base_input(), &intent_digests, None, None, 2_000.0)
+            .unwrap();
+
+        assert_eq!(batch.minted, 3);
+        assert_eq!(batch.remaining_uses, None);
+        let outputs: Vec<_> = batch
+            .challenges
+            .iter()
+            .zip(&intent_digests)
+            .map(|(item, digest)| {
+                assert_eq!(&item.intent_digest, digest);
+                let challenge = item.challenge.as_ref().expect("minted");
+                assert_eq!(challenge.intent_digest.as_deref(), Some(digest.as_str()));
+                assert_eq!(challenge.block_height, "12345");
+                challenge.vrf_output.clone()
+            })
+            .collect();
+        assert_ne!(outputs[0], outputs[1]);
+        assert_ne!(outputs[1], outputs[2]);
+        assert_ne!(outputs[0], outputs[2]);
+
+        // Same as minting each challenge on its own.
+        let mut single = base_input();
+        single.intent_digest = Some(intent_digests[1].clone());
+        assert_eq!(
+            mgr.generate_vrf_challenge(single).unwrap().vrf_output,
+            outputs[1]
+        );
+    }
+
+    #[test]
+    fn malformed_or_repeated_digests_fail_only_their_entry() {
+        let mut mgr = unlocked_manager();
+        let good = digests(2);
+        let intent_digests = vec![
+            good[0].clone(),
+            base64_url_encode(&[9u8; 16]),
+            good[0].clone(),
+            good[1].clone(),
+        ];
+        let batch = mgr
+            .generate_vrf_challenges(base_input(), &intent_digests, None, None, 2_000.0)
+            .unwrap();
+
+        assert_eq!(batch.minted, 2);
+        let minted: Vec<bool> = batch
+            .challenges
+            .iter()
+            .map(|item| item.challenge.is_some())
+            .collect();
+        assert_eq!(minted, vec![true, false, false, true]);
+        assert!(batch.challenges[1]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("expected 32 bytes"));
+        assert!(batch.challenges[2]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("duplicate"));
+    }
+
+    #[test]
+    fn minted_challenges_are_charged_to_the_session_budget() {
+        let mut mgr = unlocked_manager();
+        // 5 uses.
+        mgr.upsert_session("sess-batch", session_with_expiry(60_000.0));
+
+        let batch = mgr
+            .generate_vrf_challenges(base_input(), &digests(3), None, Some("sess-batch"), 2_000.0)
+            .unwrap();
+        assert_eq!(batch.minted, 3);
+        assert_eq!(batch.remaining_uses, Some(2));
+        assert_eq!(mgr.sessions["sess-batch"].remaining_uses, Some(2));
+
+        // Only two uses left: the rest of the batch reports SESSION_EXHAUSTED.
+        let batch = mgr
+            .generate_vrf_challenges(base_input(), &digests(4), None, Some("sess-batch"), 3_000.0)
+            .unwrap();
+        assert_eq!(batch.minted, 2);
+        assert_eq!(batch.remaining_uses, Some(0));
+        assert_eq!(batch.challenges[2].error_code, Some("SESSION_EXHAUSTED"));
+        assert_eq!(batch.challenges[3].error_code, Some("SESSION_EXHAUSTED"));
+        assert_eq!(mgr.sessions["sess-batch"].remaining_uses, Some(0));
+
+        assert!(matches!(
+            mgr.generate_vrf_challenges(
+                base_input(),
+                &digests(1),
+                None,
+                Some("sess-batch"),
+                61_000.0
+            ),
+            Err(VrfWorkerError::SessionExpired)
+        ));
+    }
+
+    #[test]
+    fn batch_size_is_capped() {
+        let mut mgr = unlocked_manager();
+        let too_many = digests(VRF_CHALLENGE_BATCH_MAX_COUNT as u8 + 1);
+        assert!(matches!(
+            mgr.generate_vrf_challenges(base_input(), &too_many, Some(100), None, 2_000.0),
+            Err(VrfWorkerError::InvalidMessageFormat(_))
+        ));
+        assert!(matches!(
+            mgr.generate_vrf_challenges(base_input(), &digests(3), Some(2), None, 2_000.0),
+            Err(VrfWorkerError::InvalidMessageFormat(_))
+        ));
+        assert!(matches!(
+            mgr.generate_vrf_challenges(base_input(), &[], None, None, 2_000.0),
+            Err(VrfWorkerError::InvalidMessageFormat(_))
+        ));
+
+        let mut with_digest = base_input();
+        with_digest.intent_digest = Some(digests(1)[0].clone());
+        assert!(mgr
+            .generate_vrf_challenges(with_digest, &digests(1), None, None, 2_000.0)
+            .is_err());
+    }
+
+    #[test]
+    fn locked_manager_rejects_the_batch() {
+        let mut mgr = VRFKeyManager::new(None, None, None, None);
+        assert!(matches!(
+            mgr.generate_vrf_challenges(base_input(), &digests(1), None, None, 2_000.0),
+            Err(VrfWorkerError::VrfNotUnlocked)
+        ));
+    }
+}
+
+mod panic_guard_tests {
+    use super::{create_test_account_id, create_test_prf_output};
+    use crate::panic_guard::install_panic_hook;
+    use std::panic::catch_unwind;
+
+    #[test]
+    fn panic_hook_wipes_the_manager() {
+        install_panic_hook();
+        let manager = crate::VRF_MANAGER.with(|m| m.clone());
+        {
+            let mut mgr = manager.borrow_mut();
+            let keypair = mgr
+                .generate_vrf_keypair_from_seed(
+                    &create_test_prf_output(),
+                    &create_test_account_id(),
+                )
+                .unwrap();
+            mgr.store_vrf_keypair_in_memory_at(keypair, create_test_account_id(), 1_000.0);
+        }
+        let before = manager.borrow().state_changes.version();
+
+        let panicked = catch_unwind(|| {
+            let bytes: Vec<u8> = Vec::new();
+            bytes[3]
+        });
+
+        assert!(panicked.is_err());
+        let mgr = manager.borrow();
+        assert!(mgr.vrf_keypair.is_none());
+        assert!(!mgr.session_active);
+        // The hook only wipes; it does not record a state change.
+        assert_eq!(mgr.state_changes.version(), before);
+    }
+}
+
+/// Wire values as shipped. The match is exhaustive, so a new variant does not compile until
+/// it is pinned here and listed in the registry.
+fn pinned_request_value(request_type: WorkerRequestType) -> u32 {
+    match request_type {
+        WorkerRequestType::Ping => 0,
+        WorkerRequestType::GenerateVrfChallenge => 1,
+        WorkerRequestType::GenerateVrfKeypairBootstrap => 2,
+        WorkerRequestType::UnlockVrfKeypair => 3,
+        WorkerRequestType::CheckVrfStatus => 4,
+        WorkerRequestType::ClearVrf => 5,
+        WorkerRequestType::DeriveVrfKeypairFromPrf => 6,
+        WorkerRequestType::Shamir3PassClientEncryptCurrentVrfKeypair => 7,
+        WorkerRequestType::Shamir3PassClientDecryptVrfKeypair => 8,
+        WorkerRequestType::Shamir3PassGenerateServerKeypair => 9,
+        WorkerRequestType::Shamir3PassApplyServerLock => 10,
+        WorkerRequestType::Shamir3PassRemoveServerLock => 11,
+        WorkerRequestType::Shamir3PassConfigP => 12,
+        WorkerRequestType::Shamir3PassConfigServerUrls => 13,
+        WorkerRequestType::MintSessionKeysAndSendToSigner => 14,
+        WorkerRequestType::DecryptSession => 15,
+        WorkerRequestType::RegistrationCredentialConfirmation => 16,
+        WorkerRequestType::Device2RegistrationSession => 17,
+        WorkerRequestType::DispenseSessionKey => 18,
+        WorkerRequestType::CheckSessionStatus => 19,
+        WorkerRequestType::ClearSession => 20,
+        WorkerRequestType::ConfirmAndPrepareSigningSession => 21,
+        WorkerRequestType::ConfigureRpc => 22,
+        WorkerRequestType::ConfigureBlockContextSource => 23,
+        WorkerRequestType::UnlockVrfKeypairs => 24,
+        WorkerRequestType::Device2CompleteRegistration => 25,
+        WorkerRequestType::RenewSession => 26,
+        WorkerRequestType::GetStateVersion => 27,
+        WorkerRequestType::GetStateChangesSince => 28,
+        WorkerRequestType::LogoutAll => 29,
+        WorkerRequestType::VrfDiagnostics => 30,
+        WorkerRequestType::GenerateVrfChallenges => 31,
+    }
+}
+
+fn pinned_response_value(response_type: WorkerResponseType) -> u32 {
+    match response_type {
+        WorkerResponseType::PingSuccess => 0,
+        WorkerResponseType::GenerateVrfChallengeSuccess => 1,
+        WorkerResponseType::GenerateVrfKeypairBootstrapSuccess => 2,
+        WorkerResponseType::UnlockVrfKeypairSuccess => 3,
+        WorkerResponseType::CheckVrfStatusSuccess => 4,
+        WorkerResponseType::ClearVrfSuccess => 5,
+        WorkerResponseType::DeriveVrfKeypairFromPrfSuccess => 6,
+        WorkerResponseType::Shamir3PassClientEncryptCurrentVrfKeypairSuccess => 7,
+        WorkerResponseType::Shamir3PassClientDecryptVrfKeypairSuccess => 8,
+        WorkerResponseType::Shamir3PassGenerateServerKeypairSuccess => 9,
+        WorkerResponseType::Shamir3PassApplyServerLockSuccess => 10,
+        WorkerResponseType::Shamir3PassRemoveServerLockSuccess => 11,
+        WorkerResponseType::Shamir3PassConfigPSuccess => 12,
+        WorkerResponseType::Shamir3PassConfigServerUrlsSuccess => 13,
+        WorkerResponseType::MintSessionKeysAndSendToSignerSuccess => 14,
+        WorkerResponseType::DecryptSessionSuccess => 15,
+    }
+}
+
+#[test]
+fn request_registry_matches_pinned_values_and_names() {
+    let mut values = std::collections::HashSet::new();
+    for (value, name) in WORKER_REQUEST_TYPE_REGISTRY {
+        assert!(values.insert(value), "duplicate request value {}", value);
+        let request_type = WorkerRequestType::try_from_str(name)
+            .unwrap_or_else(|| panic!("registry name {} does not parse", name));
+        assert_eq!(request_type as u32, value);
+        assert_eq!(pinned_request_value(request_type), value);
+        assert_eq!(WorkerRequestType::from(value), request_type);
+        assert_eq!(request_type.name(), name);
+    }
+}
+
+#[test]
+fn response_registry_matches_pinned_values() {
+    let mut values = std::collections::HashSet::new();
+    for (value, name) in WORKER_RESPONSE_TYPE_REGISTRY {
+        assert!(
+            values.insert(value),
+            "duplicate response value {} ({})",
+            value,
+            name
+        );
+        let response_type = WorkerResponseType::from(value);
+        assert_eq!(response_type as u32, value);
+        assert_eq!(u32::from(response_type), value);
+        assert_eq!(pinned_response_value(response_type), value);
+    }
+}
@@ -1,9 +1,13 @@
+use crate::config::VRF_KEYPAIR_DATA_VERSION;
+use crate::errors::SerializationError;
+use crate::rp_id_normalization::RpIdNormalization;
 use serde::{Deserialize, Serialize};
 use serde_wasm_bindgen;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsValue;
 
 pub mod http;
+pub mod progress;
 pub mod worker_messages;
 
 // Re-export worker_messages types
@@ -19,6 +23,37 @@ pub struct VRFKeypairData {
     pub public_key_base64: String,
 }
 
+impl VRFKeypairData {
+    /// `VRF_KEYPAIR_DATA_VERSION || bincode(self)`.
+    pub fn to_versioned_bytes(&self) -> Result<Vec<u8>, SerializationError> {
+        let body = bincode::serialize(self)
+            .map_err(|e| SerializationError::KeypairDataSerialization(e.to_string()))?;
+        let mut out = Vec::with_capacity(1 + body.len());
+        out.push(VRF_KEYPAIR_DATA_VERSION);
+        out.extend_from_slice(&body);
+        Ok(out)
+    }
+
+    /// Inverse of `to_versioned_bytes`.
+    ///
+    /// Blobs written before versioning have no prefix; their first byte is the low byte of
+    /// bincode's u64 `keypair_bytes` length (64 for `ECVRFKeyPair`), so it cannot collide
+    /// with `VRF_KEYPAIR_DATA_VERSION`.
+    /// Those are decoded as-is, and anything that is neither v1 nor a valid legacy blob is
+    /// reported as an unsupported version.
+    pub fn from_versioned_bytes(bytes: &[u8]) -> Result<Self, SerializationError> {
+        let (&version, body) = bytes.split_first().ok_or_else(|| {
+            SerializationError::KeypairDataDeserialization("empty keypair data".to_string())
+        })?;
+        if version == VRF_KEYPAIR_DATA_VERSION {
+            return bincode::deserialize(body)
+                .map_err(|e| SerializationError::KeypairDataDeserialization(e.to_string()));
+        }
+        bincode::deserialize::<VRFKeypairData>(bytes)
+            .map_err(|_| SerializationError::UnsupportedKeypairVersion(version))
+    }
+}
+
 #[wasm_bindgen]
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct EncryptedVRFKeypair {
@@ -39,11 +74,13 @@ pub struct VRFInputData {
     #[wasm_bindgen(getter_with_clone, js_name = "rpId")]
     #[serde(rename = "rpId")]
     pub rp_id: String,
+    /// May be omitted (together with `blockHash`) for GENERATE_VRF_CHALLENGE when a block
+    /// context source is configured; the worker then fills in the block it used.
     #[wasm_bindgen(getter_with_clone, js_name = "blockHeight")]
-    #[serde(rename = "blockHeight")]
+    #[serde(rename = "blockHeight", default)]
     pub block_height: String,
     #[wasm_bindgen(getter_with_clone, js_name = "blockHash")]
-    #[serde(rename = "blockHash")]
+    #[serde(rename = "blockHash", default)]
     pub block_hash: String,
     /// Optional base64url-encoded 32-byte digest to bind into the VRF input hash.
     /// When present, must decode to exactly 32 bytes.
@@ -55,6 +92,21 @@ pub struct VRFInputData {
     #[wasm_bindgen(getter_with_clone, js_name = "sessionPolicyDigest32")]
     #[serde(rename = "sessionPolicyDigest32")]
     pub session_policy_digest_32: Option<String>,
+    /// Optional session policy JSON. When present the worker computes the canonical
+    /// `sessionPolicyDigest32` itself; a `sessionPolicyDigest32` sent alongside must match.
+    #[wasm_bindgen(getter_with_clone, js_name = "sessionPolicyJson")]
+    #[serde(rename = "sessionPolicyJson", default)]
+    pub session_policy_json: Option<String>,
+    /// Optional base64url-encoded 32-byte app context (e.g. a device attestation hash) bound
+    /// into the VRF input hash under its own domain separator.
+    /// When present, must decode to exactly 32 bytes.
+    #[wasm_bindgen(getter_with_clone, js_name = "extraContextDigest32")]
+    #[serde(rename = "extraContextDigest32", default)]
+    pub extra_context_digest_32: Option<String>,
+    /// How `rpId` is normalized before hashing; defaults to ASCII lowercasing.
+    #[wasm_bindgen(skip)]
+    #[serde(rename = "rpIdNormalization", default)]
+    pub rp_id_normalization: RpIdNormalization,
 }
 
 #[wasm_bindgen]
@@ -93,6 +145,10 @@ pub struct VRFChallengeData {
     #[wasm_bindgen(getter_with_clone, js_name = "sessionPolicyDigest32")]
     #[serde(rename = "sessionPolicyDigest32")]
     pub session_policy_digest_32: Option<String>,
+    /// Optional base64url-encoded 32-byte app context that was included in VRF input derivation.
+    #[wasm_bindgen(getter_with_clone, js_name = "extraContextDigest32")]
+    #[serde(rename = "extraContextDigest32", default)]
+    pub extra_context_digest_32: Option<String>,
 }
 impl VRFChallengeData {
     pub fn to_js_value(&self) -> JsValue {
@@ -108,6 +164,9 @@ fn js_undefined() -> JsValue {
 pub struct GenerateVrfKeypairBootstrapResponse {
     pub vrf_public_key: String,
     pub vrf_challenge_data: Option<VRFChallengeData>,
+    /// True when derived from a dev-only `deterministicSeedB64u` (never production material)
+    #[serde(default)]
+    pub deterministic: bool,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -116,18 +175,31 @@ pub struct EncryptedVrfKeypairResponse {
     pub encrypted_vrf_keypair: EncryptedVRFKeypair,
 }
 
-/// Mirror of JS WorkerConfirmationResponse (confirmTxFlow/types.ts)
+/// Mirror of JS WorkerConfirmationResponse (confirmTxFlow/types.ts).
+/// Every field also accepts its camelCase spelling; see `parse_confirmation_fields` for the
+/// checks run before this is deserialized.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct WorkerConfirmationResponse {
+    #[serde(alias = "requestId")]
     pub request_id: String,
+    #[serde(default, alias = "intentDigest")]
     pub intent_digest: Option<String>,
     pub confirmed: bool,
     #[serde(default = "js_undefined", with = "serde_wasm_bindgen::preserve")]
     pub credential: JsValue,
-    #[serde(default = "js_undefined", with = "serde_wasm_bindgen::preserve")]
+    #[serde(
+        default = "js_undefined",
+        alias = "vrfChallenge",
+        with = "serde_wasm_bindgen::preserve"
+    )]
     pub vrf_challenge: JsValue,
-    #[serde(default = "js_undefined", with = "serde_wasm_bindgen::preserve")]
+    #[serde(
+        default = "js_undefined",
+        alias = "transactionContext",
+        with = "serde_wasm_bindgen::preserve"
+    )]
     pub transaction_context: JsValue,
+    #[serde(default)]
     pub error: Option<String>,
 }
@@ -0,0 +1,122 @@
+//! Progress messages for VRF keypair unlock and derivation.
+//!
+//! Mirrors the signer worker's progress channel:
+//!    Rust WASM → VrfProgress::emit() → send_vrf_progress_message() → TypeScript
+//!    sendVrfProgressMessage() → postMessage() → Main Thread
+//!
+//! Progress is best-effort: it never changes a handler's result, and the final
+//! `VrfWorkerResponse` is still the only message the caller awaits.
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+/// Phases reported while unlocking or deriving a VRF keypair.
+/// Values start at 200 so they never collide with the signer's ProgressStep (100..)
+/// when both streams are shown in the same UI.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VrfProgressPhase {
+    /// One-time client lock applied to KEK_s; nothing has left the worker yet
+    ClientLockApplied = 200,
+    /// Waiting on the relay's /remove-server-lock
+    ServerRoundtripStart = 201,
+    /// Relay answered and its response passed validation
+    ServerRoundtripComplete = 202,
+    /// Local AEAD decryption of the VRF keypair
+    AeadDecryptStart = 203,
+    /// Keypair is in worker memory; the unlock is done
+    KeypairLoaded = 204,
+    /// Deterministic keypair derivation from PRF output
+    Derivation = 205,
+    /// PRF-keyed encryption of the derived keypair for local storage
+    Encryption = 206,
+    /// Shamir 3-pass encryption with the relay for server-assisted unlock
+    ServerEncryption = 207,
+}
+
+impl TryFrom<u32> for VrfProgressPhase {
+    type Error = String;
+
+    fn try_from(value: u32) -> Result<Self, <Self as TryFrom<u32>>::Error> {
+        match value {
+            200 => Ok(VrfProgressPhase::ClientLockApplied),
+            201 => Ok(VrfProgressPhase::ServerRoundtripStart),
+            202 => Ok(VrfProgressPhase::ServerRoundtripComplete),
+            203 => Ok(VrfProgressPhase::AeadDecryptStart),
+            204 => Ok(VrfProgressPhase::KeypairLoaded),
+            205 => Ok(VrfProgressPhase::Derivation),
+            206 => Ok(VrfProgressPhase::Encryption),
+            207 => Ok(VrfProgressPhase::ServerEncryption),
+            _ => Err(format!("Invalid VrfProgressPhase value: {}", value)),
+        }
+    }
+}
+
+/// Convert VrfProgressPhase enum to its wire name
+pub fn vrf_progress_phase_name(phase: VrfProgressPhase) -> &'static str {
+    match phase {
+        VrfProgressPhase::ClientLockApplied => "client-lock-applied",
+        VrfProgressPhase::ServerRoundtripStart => "server-roundtrip-start",
+        VrfProgressPhase::ServerRoundtripComplete => "server-roundtrip-complete",
+        VrfProgressPhase::AeadDecryptStart => "aead-decrypt-start",
+        VrfProgressPhase::KeypairLoaded => "keypair-loaded",
+        VrfProgressPhase::Derivation => "derivation",
+        VrfProgressPhase::Encryption => "encryption",
+        VrfProgressPhase::ServerEncryption => "server-encryption",
+    }
+}
+
+/// Receives `(phase, elapsed_ms, detail)` for each emitted phase.
+pub type VrfProgressSink = fn(VrfProgressPhase, f64, Option<&str>);
+
+fn host_sink(phase: VrfProgressPhase, elapsed_ms: f64, detail: Option<&str>) {
+    crate::send_vrf_progress_message(phase as u32, elapsed_ms, detail);
+}
+
+fn silent_sink(_phase: VrfProgressPhase, _elapsed_ms: f64, _detail: Option<&str>) {}
+
+/// Wall clock in ms. Native builds have no JS clock and report 0.
+pub fn now_ms() -> f64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        0.0
+    }
+}
+
+/// Progress reporter for one handler invocation; elapsed time is measured from `start()`.
+#[derive(Clone, Copy)]
+pub struct VrfProgress {
+    started_at_ms: f64,
+    sink: VrfProgressSink,
+}
+
+impl VrfProgress {
+    /// Report to the JS host via `sendVrfProgressMessage`.
+    pub fn start() -> Self {
+        Self::with_sink(host_sink)
+    }
+
+    /// Report nowhere (callers that have no UI to update).
+    pub fn silent() -> Self {
+        Self::with_sink(silent_sink)
+    }
+
+    pub fn with_sink(sink: VrfProgressSink) -> Self {
+        Self {
+            started_at_ms: now_ms(),
+            sink,
+        }
+    }
+
+    pub fn elapsed_ms(&self) -> f64 {
+        (now_ms() - self.started_at_ms).max(0.0)
+    }
+
+    pub fn emit(&self, phase: VrfProgressPhase, detail: Option<&str>) {
+        (self.sink)(phase, self.elapsed_ms(), detail);
+    }
+}
@@ -5,6 +5,8 @@ use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
+use crate::errors::VrfWorkerError;
+
 // === PAYLOAD & ENVELOPE HELPERS ===
 
 /// Deserialize a typed Rust payload from a raw `JsValue`.
@@ -81,34 +83,84 @@ pub fn parse_worker_request_envelope(message_val: JsValue) -> Result<VrfWorkerMe
 
 // === WORKER REQUEST TYPE ENUM ===
 
-// These export to TypeScript as numeric enums and we convert directly from numbers
+// These export to TypeScript as numeric enums and we convert directly from numbers.
+// Wire values are explicit and must never change once shipped; add new variants at the
+// end and list them in `WORKER_REQUEST_TYPE_REGISTRY`.
 #[wasm_bindgen]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
 pub enum WorkerRequestType {
-    Ping,
-    GenerateVrfChallenge,
-    GenerateVrfKeypairBootstrap,
-    UnlockVrfKeypair,
-    CheckVrfStatus,
-    ClearVrf,
-    DeriveVrfKeypairFromPrf,
-    Shamir3PassClientEncryptCurrentVrfKeypair,
-    Shamir3PassClientDecryptVrfKeypair,
-    Shamir3PassGenerateServerKeypair,
-    Shamir3PassApplyServerLock,
-    Shamir3PassRemoveServerLock,
-    Shamir3PassConfigP,
-    Shamir3PassConfigServerUrls,
-    MintSessionKeysAndSendToSigner,
-    DecryptSession,
-    RegistrationCredentialConfirmation,
-    Device2RegistrationSession,
-    DispenseSessionKey,
-    CheckSessionStatus,
-    ClearSession,
-    ConfirmAndPrepareSigningSession,
+    Ping = 0,
+    GenerateVrfChallenge = 1,
+    GenerateVrfKeypairBootstrap = 2,
+    UnlockVrfKeypair = 3,
+    CheckVrfStatus = 4,
+    ClearVrf = 5,
+    DeriveVrfKeypairFromPrf = 6,
+    Shamir3PassClientEncryptCurrentVrfKeypair = 7,
+    Shamir3PassClientDecryptVrfKeypair = 8,
+    Shamir3PassGenerateServerKeypair = 9,
+    Shamir3PassApplyServerLock = 10,
+    Shamir3PassRemoveServerLock = 11,
+    Shamir3PassConfigP = 12,
+    Shamir3PassConfigServerUrls = 13,
+    MintSessionKeysAndSendToSigner = 14,
+    DecryptSession = 15,
+    RegistrationCredentialConfirmation = 16,
+    Device2RegistrationSession = 17,
+    DispenseSessionKey = 18,
+    CheckSessionStatus = 19,
+    ClearSession = 20,
+    ConfirmAndPrepareSigningSession = 21,
+    ConfigureRpc = 22,
+    ConfigureBlockContextSource = 23,
+    UnlockVrfKeypairs = 24,
+    Device2CompleteRegistration = 25,
+    RenewSession = 26,
+    GetStateVersion = 27,
+    GetStateChangesSince = 28,
+    LogoutAll = 29,
+    VrfDiagnostics = 30,
+    GenerateVrfChallenges = 31,
 }
 
+/// Every request type this build accepts, as `(wire value, name)`.
+#[cfg_attr(not(test), allow(dead_code))]
+pub const WORKER_REQUEST_TYPE_REGISTRY: [(u32, &str); 32] = [
+    (0, "PING"),
+    (1, "GENERATE_VRF_CHALLENGE"),
+    (2, "GENERATE_VRF_KEYPAIR_BOOTSTRAP"),
+    (3, "UNLOCK_VRF_KEYPAIR"),
+    (4, "CHECK_VRF_STATUS"),
+    (5, "CLEAR_VRF"),
+    (6, "DERIVE_VRF_KEYPAIR_FROM_PRF"),
+    (7, "SHAMIR3PASS_CLIENT_ENCRYPT_CURRENT_VRF_KEYPAIR"),
+    (8, "SHAMIR3PASS_CLIENT_DECRYPT_VRF_KEYPAIR"),
+    (9, "SHAMIR3PASS_GENERATE_SERVER_KEYPAIR"),
+    (10, "SHAMIR3PASS_APPLY_SERVER_LOCK_KEK"),
+    (11, "SHAMIR3PASS_REMOVE_SERVER_LOCK_KEK"),
+    (12, "SHAMIR3PASS_CONFIG_P"),
+    (13, "SHAMIR3PASS_CONFIG_SERVER_URLS"),
+    (14, "MINT_SESSION_KEYS_AND_SEND_TO_SIGNER"),
+    (15, "DECRYPT_SESSION"),
+    (16, "REGISTRATION_CREDENTIAL_CONFIRMATION"),
+    (17, "DEVICE2_REGISTRATION_SESSION"),
+    (18, "DISPENSE_SESSION_KEY"),
+    (19, "CHECK_SESSION_STATUS"),
+    (20, "CLEAR_SESSION"),
+    (21, "CONFIRM_AND_PREPARE_SIGNING_SESSION"),
+    (22, "CONFIGURE_RPC"),
+    (23, "CONFIGURE_BLOCK_CONTEXT_SOURCE"),
+    (24, "UNLOCK_VRF_KEYPAIRS"),
+    (25, "DEVICE2_COMPLETE_REGISTRATION"),
+    (26, "RENEW_SESSION"),
+    (27, "GET_STATE_VERSION"),
+    (28, "GET_STATE_CHANGES_SINCE"),
+    (29, "LOGOUT_ALL"),
+    (30, "VRF_DIAGNOSTICS"),
+    (31, "GENERATE_VRF_CHALLENGES"),
+];
+
 impl WorkerRequestType {
     pub fn try_from_str(value: &str) -> Option<Self> {
         match value {
@@ -154,6 +206,20 @@ impl WorkerRequestType {
             "CONFIRM_AND_PREPARE_SIGNING_SESSION" => {
                 Some(WorkerRequestType::ConfirmAndPrepareSigningSession)
             }
+            "CONFIGURE_RPC" => Some(WorkerRequestType::ConfigureRpc),
+            "CONFIGURE_BLOCK_CONTEXT_SOURCE" => {
+                Some(WorkerRequestType::ConfigureBlockContextSource)
+            }
+            "UNLOCK_VRF_KEYPAIRS" => Some(WorkerRequestType::UnlockVrfKeypairs),
+            "DEVICE2_COMPLETE_REGISTRATION" => {
+                Some(WorkerRequestType::Device2CompleteRegistration)
+            }
+            "RENEW_SESSION" => Some(WorkerRequestType::RenewSession),
+            "GET_STATE_VERSION" => Some(WorkerRequestType::GetStateVersion),
+            "GET_STATE_CHANGES_SINCE" => Some(WorkerRequestType::GetStateChangesSince),
+            "LOGOUT_ALL" => Some(WorkerRequestType::LogoutAll),
+            "VRF_DIAGNOSTICS" => Some(WorkerRequestType::VrfDiagnostics),
+            "GENERATE_VRF_CHALLENGES" => Some(WorkerRequestType::GenerateVrfChallenges),
             _ => None,
         }
     }
@@ -194,6 +260,16 @@ impl WorkerRequestType {
             WorkerRequestType::ConfirmAndPrepareSigningSession => {
                 "CONFIRM_AND_PREPARE_SIGNING_SESSION"
             }
+            WorkerRequestType::ConfigureRpc => "CONFIGURE_RPC",
+            WorkerRequestType::ConfigureBlockContextSource => "CONFIGURE_BLOCK_CONTEXT_SOURCE",
+            WorkerRequestType::UnlockVrfKeypairs => "UNLOCK_VRF_KEYPAIRS",
+            WorkerRequestType::Device2CompleteRegistration => "DEVICE2_COMPLETE_REGISTRATION",
+            WorkerRequestType::RenewSession => "RENEW_SESSION",
+            WorkerRequestType::GetStateVersion => "GET_STATE_VERSION",
+            WorkerRequestType::GetStateChangesSince => "GET_STATE_CHANGES_SINCE",
+            WorkerRequestType::LogoutAll => "LOGOUT_ALL",
+            WorkerRequestType::VrfDiagnostics => "VRF_DIAGNOSTICS",
+            WorkerRequestType::GenerateVrfChallenges => "GENERATE_VRF_CHALLENGES",
         }
     }
 }
@@ -223,6 +299,16 @@ impl From<u32> for WorkerRequestType {
             19 => WorkerRequestType::CheckSessionStatus,
             20 => WorkerRequestType::ClearSession,
             21 => WorkerRequestType::ConfirmAndPrepareSigningSession,
+            22 => WorkerRequestType::ConfigureRpc,
+            23 => WorkerRequestType::ConfigureBlockContextSource,
+            24 => WorkerRequestType::UnlockVrfKeypairs,
+            25 => WorkerRequestType::Device2CompleteRegistration,
+            26 => WorkerRequestType::RenewSession,
+            27 => WorkerRequestType::GetStateVersion,
+            28 => WorkerRequestType::GetStateChangesSince,
+            29 => WorkerRequestType::LogoutAll,
+            30 => WorkerRequestType::VrfDiagnostics,
+            31 => WorkerRequestType::GenerateVrfChallenges,
             _ => panic!("Invalid WorkerRequestType value: {}", value),
         }
     }
@@ -236,28 +322,51 @@ impl From<&str> for WorkerRequestType {
 }
 
 /// Worker response types enum - corresponds to TypeScript WorkerResponseType
+/// Values are pinned like `WorkerRequestType`'s; add new ones at the end.
 #[wasm_bindgen]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
 pub enum WorkerResponseType {
     // Success responses - one for each request type
-    PingSuccess,
-    GenerateVrfChallengeSuccess,
-    GenerateVrfKeypairBootstrapSuccess,
-    UnlockVrfKeypairSuccess,
-    CheckVrfStatusSuccess,
-    ClearVrfSuccess,
-    DeriveVrfKeypairFromPrfSuccess,
-    Shamir3PassClientEncryptCurrentVrfKeypairSuccess,
-    Shamir3PassClientDecryptVrfKeypairSuccess,
-    Shamir3PassGenerateServerKeypairSuccess,
-    Shamir3PassApplyServerLockSuccess,
-    Shamir3PassRemoveServerLockSuccess,
-    Shamir3PassConfigPSuccess,
-    Shamir3PassConfigServerUrlsSuccess,
-    MintSessionKeysAndSendToSignerSuccess,
-    DecryptSessionSuccess,
+    PingSuccess = 0,
+    GenerateVrfChallengeSuccess = 1,
+    GenerateVrfKeypairBootstrapSuccess = 2,
+    UnlockVrfKeypairSuccess = 3,
+    CheckVrfStatusSuccess = 4,
+    ClearVrfSuccess = 5,
+    DeriveVrfKeypairFromPrfSuccess = 6,
+    Shamir3PassClientEncryptCurrentVrfKeypairSuccess = 7,
+    Shamir3PassClientDecryptVrfKeypairSuccess = 8,
+    Shamir3PassGenerateServerKeypairSuccess = 9,
+    Shamir3PassApplyServerLockSuccess = 10,
+    Shamir3PassRemoveServerLockSuccess = 11,
+    Shamir3PassConfigPSuccess = 12,
+    Shamir3PassConfigServerUrlsSuccess = 13,
+    MintSessionKeysAndSendToSignerSuccess = 14,
+    DecryptSessionSuccess = 15,
 }
 
+/// Every response type this build emits, as `(wire value, name)`.
+#[cfg_attr(not(test), allow(dead_code))]
+pub const WORKER_RESPONSE_TYPE_REGISTRY: [(u32, &str); 16] = [
+    (0, "PING_SUCCESS"),
+    (1, "GENERATE_VRF_CHALLENGE_SUCCESS"),
+    (2, "GENERATE_VRF_KEYPAIR_BOOTSTRAP_SUCCESS"),
+    (3, "UNLOCK_VRF_KEYPAIR_SUCCESS"),
+    (4, "CHECK_VRF_STATUS_SUCCESS"),
+    (5, "CLEAR_VRF_SUCCESS"),
+    (6, "DERIVE_VRF_KEYPAIR_FROM_PRF_SUCCESS"),
+    (7, "SHAMIR3PASS_CLIENT_ENCRYPT_CURRENT_VRF_KEYPAIR_SUCCESS"),
+    (8, "SHAMIR3PASS_CLIENT_DECRYPT_VRF_KEYPAIR_SUCCESS"),
+    (9, "SHAMIR3PASS_GENERATE_SERVER_KEYPAIR_SUCCESS"),
+    (10, "SHAMIR3PASS_APPLY_SERVER_LOCK_SUCCESS"),
+    (11, "SHAMIR3PASS_REMOVE_SERVER_LOCK_SUCCESS"),
+    (12, "SHAMIR3PASS_CONFIG_P_SUCCESS"),
+    (13, "SHAMIR3PASS_CONFIG_SERVER_URLS_SUCCESS"),
+    (14, "MINT_SESSION_KEYS_AND_SEND_TO_SIGNER_SUCCESS"),
+    (15, "DECRYPT_SESSION_SUCCESS"),
+];
+
 impl From<WorkerResponseType> for u32 {
     fn from(value: WorkerResponseType) -> Self {
         match value {
@@ -313,6 +422,10 @@ pub struct VrfWorkerResponse {
     #[serde(with = "serde_wasm_bindgen::preserve")]
     pub data: JsValue,
     pub error: Option<String>,
+    /// Machine-readable failure class (`VrfWorkerError::code`), set by handlers whose callers
+    /// branch on it, e.g. `VRF_LOCKED` to trigger an unlock instead of a generic failure.
+    #[serde(rename = "errorCode", default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
 }
 
 fn serialize_data<T: Serialize>(value: T) -> JsValue {
@@ -327,6 +440,7 @@ impl VrfWorkerResponse {
             success,
             data,
             error,
+            error_code: None,
         }
     }
 
@@ -345,6 +459,14 @@ impl VrfWorkerResponse {
         Self::new(id, false, JsValue::UNDEFINED, Some(message.into()))
     }
 
+    /// Failure response carrying the error's `errorCode` alongside its message.
+    pub fn fail_with_code(id: Option<String>, error: &VrfWorkerError) -> Self {
+        Self {
+            error_code: Some(error.code().to_string()),
+            ..Self::fail(id, error.to_string())
+        }
+    }
+
     pub fn error(id: Option<String>, error: String) -> Self {
         Self::new(id, false, JsValue::UNDEFINED, Some(error))
     }
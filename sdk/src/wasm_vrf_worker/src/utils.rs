@@ -1,22 +1,98 @@
 use crate::errors::VrfWorkerError;
-use base64ct::{Base64UrlUnpadded, Encoding};
+use base64ct::{Base64, Base64Url, Base64UrlUnpadded, Encoding as _};
 use getrandom::getrandom;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 // === BASE64 UTILITIES ===
 
-/// Base64 URL encode bytes
+/// Base64 variants seen at the worker boundary. Everything the VRF worker produces is
+/// `B64Url`; the other variants only describe inputs from external sources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// URL-safe alphabet, no padding (canonical)
+    B64Url,
+    /// URL-safe alphabet with `=` padding
+    B64UrlPadded,
+    /// Standard alphabet with `=` padding
+    B64Std,
+}
+
+impl Encoding {
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::B64Url => "base64url",
+            Encoding::B64UrlPadded => "padded base64url",
+            Encoding::B64Std => "standard base64",
+        }
+    }
+
+    pub fn encode(self, bytes: &[u8]) -> String {
+        match self {
+            Encoding::B64Url => Base64UrlUnpadded::encode_string(bytes),
+            Encoding::B64UrlPadded => Base64Url::encode_string(bytes),
+            Encoding::B64Std => Base64::encode_string(bytes),
+        }
+    }
+
+    /// Decode `s`; the error names the variant that was expected.
+    pub fn decode(self, s: &str) -> Result<Vec<u8>, String> {
+        let decoded = match self {
+            Encoding::B64Url => Base64UrlUnpadded::decode_vec(s),
+            Encoding::B64UrlPadded => Base64Url::decode_vec(s),
+            Encoding::B64Std => Base64::decode_vec(s),
+        };
+        decoded.map_err(|e| format!("expected {}: {}", self.name(), e))
+    }
+}
+
+/// Base64 URL encode bytes (`Encoding::B64Url`)
 pub fn base64_url_encode(bytes: &[u8]) -> String {
-    Base64UrlUnpadded::encode_string(bytes)
+    Encoding::B64Url.encode(bytes)
 }
 
-/// Base64 URL decode string
+/// Base64 URL decode string (`Encoding::B64Url`)
 pub fn base64_url_decode(s: &str) -> Result<Vec<u8>, String> {
-    Base64UrlUnpadded::decode_vec(s).map_err(|e| format!("Base64 decode error: {}", e))
+    Encoding::B64Url.decode(s)
+}
+
+/// Bytes carried as base64url on the wire. Deserializing validates the encoding (padding
+/// from browser APIs is tolerated); serializing always emits unpadded base64url.
+///
+/// Nearly every VRF request struct is `#[wasm_bindgen]`-exported, and those fields stay
+/// `String` (their JS getters need a wasm-bindgen type); Shamir exponents and ciphertexts
+/// are big integers decoded by `decode_biguint_b64u`. This type covers the serde-only rest.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct B64UrlBytes(pub Vec<u8>);
+
+impl B64UrlBytes {
+    pub fn decode(s: &str) -> Result<Self, String> {
+        Encoding::B64Url
+            .decode(s.trim().trim_end_matches('='))
+            .map(Self)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Serialize for B64UrlBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64_url_encode(&self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for B64UrlBytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        Self::decode(&encoded).map_err(serde::de::Error::custom)
+    }
 }
 
 /// Generate a random 32-byte salt and return it as base64url-encoded string.
 /// Used as wrap_key_salt for WrapKeySeed → KEK derivation when no caller-provided salt is available.
 pub fn generate_wrap_key_salt_b64u() -> Result<String, String> {
+    crate::randomness::ensure_randomness_ok()?;
     let mut bytes = [0u8; 32];
     if let Err(e) = getrandom(&mut bytes) {
         return Err(format!("Failed to generate wrapKeySalt: {}", e));
@@ -24,6 +100,52 @@ pub fn generate_wrap_key_salt_b64u() -> Result<String, String> {
     Ok(base64_url_encode(&bytes))
 }
 
+/// Decode the dev-only `deterministicSeedB64u` request field.
+/// Builds without the `dev_deterministic_vrf` feature reject the field outright
+/// rather than silently falling back to OS randomness.
+#[cfg(not(feature = "dev_deterministic_vrf"))]
+pub fn parse_deterministic_seed_b64u(seed_b64u: Option<&str>) -> Result<Option<Vec<u8>>, String> {
+    match seed_b64u {
+        None => Ok(None),
+        Some(_) => Err(
+            "deterministicSeedB64u is not supported: VRF worker was built without the dev_deterministic_vrf feature"
+                .to_string(),
+        ),
+    }
+}
+
+/// Decode the dev-only `deterministicSeedB64u` request field (at least
+/// `DEV_DETERMINISTIC_SEED_MIN_LEN` bytes).
+#[cfg(feature = "dev_deterministic_vrf")]
+pub fn parse_deterministic_seed_b64u(seed_b64u: Option<&str>) -> Result<Option<Vec<u8>>, String> {
+    let Some(seed_b64u) = seed_b64u else {
+        return Ok(None);
+    };
+    let seed = base64_url_decode(seed_b64u.trim())
+        .map_err(|e| format!("Invalid deterministicSeedB64u: {}", e))?;
+    if seed.len() < crate::config::DEV_DETERMINISTIC_SEED_MIN_LEN {
+        return Err(format!(
+            "Invalid deterministicSeedB64u: expected at least {} bytes, got {}",
+            crate::config::DEV_DETERMINISTIC_SEED_MIN_LEN,
+            seed.len()
+        ));
+    }
+    Ok(Some(seed))
+}
+
+/// Truncated form (`f3a1…9c2e`) of an implicit account ID (64 lowercase hex) for
+/// confirmation summaries. Named accounts return `None` and render as-is.
+pub fn implicit_account_display(account_id: &str) -> Option<String> {
+    let is_implicit = account_id.len() == 64
+        && account_id
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b));
+    if !is_implicit {
+        return None;
+    }
+    Some(format!("{}…{}", &account_id[..4], &account_id[60..]))
+}
+
 pub fn parse_block_height(block_height: &str) -> Result<u64, VrfWorkerError> {
     block_height.parse().map_err(|_| {
         VrfWorkerError::BlockHeightParsingError(format!("Invalid block height: {}", block_height))
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::config::{VERIFICATION_CACHE_DEFAULT_TTL_MS, VERIFICATION_CACHE_MAX_TTL_MS};
+
+/// Identifies a contract verification: the same credential presenting the same VRF
+/// output to the same contract.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VerificationCacheKey {
+    pub credential_id: String,
+    pub vrf_output: String,
+    pub contract_id: String,
+}
+
+/// What the signer worker is told about a successful contract verification, so it can sign
+/// a `VerificationAttestation` on request. Sent alongside WrapKeySeed as `contractVerification`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractVerificationFacts {
+    pub vrf_output: String,
+    pub credential_id: String,
+    pub contract_id: String,
+    /// When the RPC verification succeeded (not when a cached result was reused).
+    pub verified_at_ms: f64,
+    pub block_height: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+struct VerificationCacheEntry {
+    expires_at_ms: f64,
+    /// Intent digests bound into the verified VRF challenge.
+    intent_digests: Vec<String>,
+    verified_at_ms: f64,
+    block_height: Option<u64>,
+}
+
+/// Short-lived, worker-memory-only cache of successful contract verifications.
+///
+/// A hit requires the same (credential id, VRF output, contract id) tuple, an unexpired
+/// entry, and an intent digest that the verified VRF challenge already covered. Anything
+/// else must go through a fresh `verify_authentication_response` call.
+/// Intentionally not `Serialize`: entries never leave the worker.
+#[derive(Debug)]
+pub struct VerificationCache {
+    ttl_ms: u64,
+    entries: HashMap<VerificationCacheKey, VerificationCacheEntry>,
+}
+
+impl Default for VerificationCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VerificationCache {
+    pub fn new() -> Self {
+        Self {
+            ttl_ms: VERIFICATION_CACHE_DEFAULT_TTL_MS,
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn ttl_ms(&self) -> u64 {
+        self.ttl_ms
+    }
+
+    /// Set the entry lifetime, clamped to `VERIFICATION_CACHE_MAX_TTL_MS`.
+    /// A TTL of 0 disables caching.
+    pub fn set_ttl_ms(&mut self, ttl_ms: u64) {
+        self.ttl_ms = ttl_ms.min(VERIFICATION_CACHE_MAX_TTL_MS);
+        if self.ttl_ms == 0 {
+            self.entries.clear();
+        }
+    }
+
+    /// Record a successful verification covering `intent_digests`, answered at `block_height`.
+    pub fn insert(
+        &mut self,
+        key: VerificationCacheKey,
+        intent_digests: Vec<String>,
+        block_height: Option<u64>,
+        now_ms: f64,
+    ) {
+        if self.ttl_ms == 0 || intent_digests.is_empty() {
+            return;
+        }
+        self.entries.retain(|_, e| now_ms < e.expires_at_ms);
+        self.entries.insert(
+            key,
+            VerificationCacheEntry {
+                expires_at_ms: now_ms + self.ttl_ms as f64,
+                intent_digests,
+                verified_at_ms: now_ms,
+                block_height,
+            },
+        );
+    }
+
+    /// True when a live entry exists for `key` and it covers `intent_digest`.
+    /// Expired entries are dropped on lookup.
+    pub fn is_verified(
+        &mut self,
+        key: &VerificationCacheKey,
+        intent_digest: &str,
+        now_ms: f64,
+    ) -> bool {
+        self.verified_facts(key, intent_digest, now_ms).is_some()
+    }
+
+    /// Like `is_verified`, returning the original verification's facts on a hit.
+    pub fn verified_facts(
+        &mut self,
+        key: &VerificationCacheKey,
+        intent_digest: &str,
+        now_ms: f64,
+    ) -> Option<ContractVerificationFacts> {
+        let entry = self.entries.get(key)?;
+        if now_ms >= entry.expires_at_ms {
+            self.entries.remove(key);
+            return None;
+        }
+        if !entry.intent_digests.iter().any(|d| d == intent_digest) {
+            return None;
+        }
+        Some(ContractVerificationFacts {
+            vrf_output: key.vrf_output.clone(),
+            credential_id: key.credential_id.clone(),
+            contract_id: key.contract_id.clone(),
+            verified_at_ms: entry.verified_at_ms,
+            block_height: entry.block_height,
+        })
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -1,5 +1,7 @@
 use wasm_bindgen::JsValue;
 
+use crate::errors::{VrfResult, VrfWorkerError};
+
 #[cfg(target_arch = "wasm32")]
 use js_sys::Reflect;
 
@@ -85,3 +87,21 @@ pub(crate) fn extract_prf_second_from_credential(credential: &JsValue) -> Option
 pub(crate) fn extract_prf_second_from_credential(_credential: &JsValue) -> Option<String> {
     None
 }
+
+/// Reject PRF.first and PRF.second outputs that are byte-identical.
+///
+/// The two PRF salts are meant to yield independent secrets (PRF.first feeds the
+/// WrapKeySeed, PRF.second the VRF and NEAR keys). Equal outputs mean both salts were
+/// evaluated the same way, so the keys would no longer be separated.
+pub(crate) fn ensure_distinct_prf_outputs(prf_first: &[u8], prf_second: &[u8]) -> VrfResult<()> {
+    let identical = prf_first.len() == prf_second.len()
+        && prf_first
+            .iter()
+            .zip(prf_second)
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0;
+    if identical {
+        return Err(VrfWorkerError::IdenticalPrfOutputs);
+    }
+    Ok(())
+}
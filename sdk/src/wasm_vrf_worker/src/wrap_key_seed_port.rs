@@ -1,6 +1,9 @@
 use wasm_bindgen::JsValue;
 use web_sys::MessagePort;
 
+use crate::manager::KeyReusePolicy;
+use crate::verification_cache::ContractVerificationFacts;
+
 /// WrapKeySeed/PRF.second delivery utilities for VRF → Signer secret transfer.
 ///
 /// This module is only compiled for `wasm32` targets. It manages the `MessagePort`
@@ -28,11 +31,23 @@ pub fn close_all_ports() {
     });
 }
 
+/// Values the signer worker checks before accepting a delivery, so material minted for one
+/// session is refused if it arrives on another session's port. Both are optional; a delivery
+/// without a nonce is only accepted by signers that never issued one.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SessionBinding<'a> {
+    pub session_binding_nonce: Option<&'a str>,
+    pub intent_digest: Option<&'a str>,
+}
+
 pub fn send_wrap_key_seed_on_port(
     port: &MessagePort,
     wrap_key_seed_b64u: &str,
     wrap_key_salt_b64u: &str,
     prf_second_b64u: Option<&str>,
+    contract_verification: Option<&ContractVerificationFacts>,
+    key_reuse_policy: Option<&KeyReusePolicy>,
+    binding: SessionBinding,
 ) {
     let obj = js_sys::Object::new();
     let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("ok"), &JsValue::TRUE);
@@ -53,6 +68,31 @@ pub fn send_wrap_key_seed_on_port(
             &JsValue::from_str(prf_second),
         );
     }
+    if let Some(facts) = contract_verification {
+        if let Ok(facts_js) = serde_wasm_bindgen::to_value(facts) {
+            let _ =
+                js_sys::Reflect::set(&obj, &JsValue::from_str("contractVerification"), &facts_js);
+        }
+    }
+    if let Some(policy) = key_reuse_policy {
+        if let Ok(policy_js) = serde_wasm_bindgen::to_value(policy) {
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str("keyReusePolicy"), &policy_js);
+        }
+    }
+    if let Some(nonce) = binding.session_binding_nonce {
+        let _ = js_sys::Reflect::set(
+            &obj,
+            &JsValue::from_str("sessionBindingNonce"),
+            &JsValue::from_str(nonce),
+        );
+    }
+    if let Some(intent_digest) = binding.intent_digest {
+        let _ = js_sys::Reflect::set(
+            &obj,
+            &JsValue::from_str("intentDigest"),
+            &JsValue::from_str(intent_digest),
+        );
+    }
     let _ = port.post_message(&obj);
 }
 
@@ -68,6 +108,9 @@ pub fn send_wrap_key_seed_to_signer(
     wrap_key_seed_b64u: &str,
     wrap_key_salt_b64u: &str,
     prf_second_b64u: Option<&str>,
+    contract_verification: Option<&ContractVerificationFacts>,
+    key_reuse_policy: Option<&KeyReusePolicy>,
+    binding: SessionBinding,
 ) {
     if let Some(port) = take_port(session_id) {
         send_wrap_key_seed_on_port(
@@ -75,6 +118,9 @@ pub fn send_wrap_key_seed_to_signer(
             wrap_key_seed_b64u,
             wrap_key_salt_b64u,
             prf_second_b64u,
+            contract_verification,
+            key_reuse_policy,
+            binding,
         );
         port.close();
     }